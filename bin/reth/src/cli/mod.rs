@@ -8,7 +8,7 @@ use crate::{
     commands::{
         config_cmd, db, debug_cmd, dump_genesis, import, init_cmd, init_state,
         node::{self, NoArgs},
-        p2p, recover, stage, test_vectors,
+        p2p, recover, stage, test_vectors, trie,
     },
     version::{LONG_VERSION, SHORT_VERSION},
 };
@@ -162,6 +162,7 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
             Commands::Config(command) => runner.run_until_ctrl_c(command.execute()),
             Commands::Debug(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Recover(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
+            Commands::Trie(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
         }
     }
 
@@ -221,6 +222,9 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// Scripts for node recovery
     #[command(name = "recover")]
     Recover(recover::Command),
+    /// State trie debugging utilities
+    #[command(name = "trie")]
+    Trie(trie::Command),
 }
 
 #[cfg(test)]
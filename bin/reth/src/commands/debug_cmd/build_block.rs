@@ -34,7 +34,7 @@ use reth_provider::{
     BundleStateWithReceipts, ChainSpecProvider, ProviderFactory, StageCheckpointReader,
     StateProviderFactory,
 };
-use reth_revm::database::StateProviderDatabase;
+use reth_revm::{database::StateProviderDatabase, precompile_cache::PrecompileCache};
 use reth_rpc_types::engine::{BlobsBundleV1, PayloadAttributes};
 use reth_transaction_pool::{
     blobstore::InMemoryBlobStore, BlobStore, EthPooledTransaction, PoolConfig, TransactionOrigin,
@@ -240,9 +240,11 @@ impl Command {
             blockchain_db.clone(),
             transaction_pool,
             CachedReads::default(),
+            PrecompileCache::default(),
             payload_config,
             Cancelled::default(),
             None,
+            Box::new(ctx.task_executor.clone()),
         );
 
         #[cfg(feature = "optimism")]
@@ -16,5 +16,6 @@ pub mod p2p;
 pub mod recover;
 pub mod stage;
 pub mod test_vectors;
+pub mod trie;
 
 pub mod common;
@@ -2,8 +2,8 @@
 
 use crate::args::{
     utils::{chain_help, genesis_value_parser, parse_socket_address, SUPPORTED_CHAINS},
-    DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs, PruningArgs,
-    RpcServerArgs, TxPoolArgs,
+    DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, EngineArgs, ExecutionArgs, NetworkArgs,
+    PayloadBuilderArgs, PruningArgs, RpcServerArgs, TrieArgs, TxPoolArgs,
 };
 use clap::{value_parser, Args, Parser};
 use reth_cli_runner::CliContext;
@@ -99,6 +99,18 @@ pub struct NodeCommand<Ext: clap::Args + fmt::Debug = NoArgs> {
     #[command(flatten)]
     pub pruning: PruningArgs,
 
+    /// All trie related arguments with --trie prefix
+    #[command(flatten)]
+    pub trie: TrieArgs,
+
+    /// All block execution related arguments with --execution prefix
+    #[command(flatten)]
+    pub execution: ExecutionArgs,
+
+    /// All engine API related arguments with --engine prefix
+    #[command(flatten)]
+    pub engine: EngineArgs,
+
     /// Additional cli arguments
     #[command(flatten, next_help_heading = "Extension")]
     pub ext: Ext,
@@ -147,6 +159,9 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            trie,
+            execution,
+            engine,
             ext,
         } = self;
 
@@ -165,6 +180,9 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            trie,
+            execution,
+            engine,
         };
 
         // Register the prometheus recorder before creating the database,
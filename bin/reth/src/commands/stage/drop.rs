@@ -162,6 +162,13 @@ impl Command {
                 )?;
                 insert_genesis_history(&provider_rw, self.env.chain.genesis.alloc.iter())?;
             }
+            StageEnum::AddressTransactions => {
+                tx.clear::<tables::AddressTransactionIndex>()?;
+                tx.delete::<tables::StageCheckpoints>(
+                    StageId::IndexAddressTransactions.to_string(),
+                    None,
+                )?;
+            }
             StageEnum::TxLookup => {
                 tx.clear::<tables::TransactionHashNumbers>()?;
                 tx.put::<tables::StageCheckpoints>(
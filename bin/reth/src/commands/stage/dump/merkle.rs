@@ -150,10 +150,8 @@ async fn dry_run<DB: Database>(
     info!(target: "reth::cli", "Executing stage.");
     let provider = output_provider_factory.provider_rw()?;
 
-    let mut stage = MerkleStage::Execution {
-        // Forces updating the root instead of calculating from scratch
-        clean_threshold: u64::MAX,
-    };
+    // Forces updating the root instead of calculating from scratch
+    let mut stage = MerkleStage::new_execution(u64::MAX);
 
     loop {
         let input = reth_stages::ExecInput {
@@ -0,0 +1,29 @@
+//! `reth trie` command.
+
+use clap::{Parser, Subcommand};
+use reth_cli_runner::CliContext;
+
+mod verify_proof;
+
+/// `reth trie` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[command(subcommand)]
+    command: Subcommands,
+}
+
+/// `reth trie` subcommands
+#[derive(Subcommand, Debug)]
+pub enum Subcommands {
+    /// Verify the `eth_getProof` response against the local state trie.
+    VerifyProof(verify_proof::Command),
+}
+
+impl Command {
+    /// Execute `trie` command
+    pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
+        match self.command {
+            Subcommands::VerifyProof(command) => command.execute(ctx).await,
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::commands::common::{AccessRights, Environment, EnvironmentArgs};
+use clap::Parser;
+use reth_cli_runner::CliContext;
+use reth_primitives::{keccak256, Address, BlockNumber, Bytes, B256};
+use reth_provider::{BlockNumReader, HeaderProvider, ProviderError};
+use reth_rpc_types::EIP1186AccountProofResponse;
+use reth_rpc_types_compat::proof::verify_account_proof;
+use reth_trie::HashedPostState;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+use tracing::info;
+
+/// `reth trie verify-proof` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    /// The address of the proven account.
+    #[arg(long, value_name = "ADDRESS")]
+    account: Address,
+
+    /// The proven storage slot of the account.
+    #[arg(long, value_name = "KEY")]
+    slot: Option<B256>,
+
+    /// The path to the JSON file with the `eth_getProof` response.
+    ///
+    /// Both the full JSON-RPC response and its bare `result` are accepted.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    proof: PathBuf,
+
+    /// The block number to verify the proof at. Defaults to the latest block.
+    #[arg(long, value_name = "BLOCK_NUMBER")]
+    block: Option<BlockNumber>,
+}
+
+impl Command {
+    /// Execute `trie verify-proof` command
+    pub async fn execute(self, _ctx: CliContext) -> eyre::Result<()> {
+        let mut json: serde_json::Value =
+            serde_json::from_str(&reth_fs_util::read_to_string(&self.proof)?)?;
+        if let Some(result) = json.get_mut("result") {
+            json = result.take();
+        }
+        let mut response: EIP1186AccountProofResponse = serde_json::from_value(json)?;
+
+        if response.address != self.account {
+            eyre::bail!(
+                "Proof is for account {}, but {} was requested",
+                response.address,
+                self.account
+            );
+        }
+        if let Some(slot) = self.slot {
+            response.storage_proof.retain(|proof| proof.key.0 == slot);
+            if response.storage_proof.is_empty() {
+                eyre::bail!("Proof doesn't contain the storage slot {slot}");
+            }
+        }
+
+        let Environment { provider_factory, .. } = self.env.init(AccessRights::RO)?;
+
+        let block = match self.block {
+            Some(block) => block,
+            None => provider_factory.best_block_number()?,
+        };
+        let header = provider_factory
+            .header_by_number(block)?
+            .ok_or(ProviderError::HeaderNotFound(block.into()))?;
+        let state = provider_factory.history_by_block_number(block)?;
+
+        let hashed_address = keccak256(self.account);
+        let hashed_slots =
+            response.storage_proof.iter().map(|proof| keccak256(proof.key.0)).collect::<Vec<_>>();
+        let multiproof = state.multiproof(
+            HashedPostState::default(),
+            HashMap::from([(hashed_address, hashed_slots.iter().copied().collect::<HashSet<_>>())]),
+        )?;
+
+        info!(target: "reth::cli", block, state_root = %header.state_root, "Verifying proof against the local state");
+
+        match verify_account_proof(&response, header.state_root) {
+            Ok(()) => println!(
+                "Proof matches the local state root {} at block {block}",
+                header.state_root
+            ),
+            Err(err) => println!(
+                "Proof doesn't match the local state root {} at block {block}: {err}",
+                header.state_root
+            ),
+        }
+
+        let local_account_proof = multiproof.account_proof_nodes(hashed_address);
+        match first_divergence(&local_account_proof, &response.account_proof) {
+            Some(depth) => println!("Account proof diverges at trie depth {depth}"),
+            None => println!("Account proof matches the local trie"),
+        }
+
+        let local_storage = multiproof.storages.get(&hashed_address);
+        for (proof, hashed_slot) in response.storage_proof.iter().zip(hashed_slots) {
+            let local_storage_proof = local_storage
+                .map(|storage| storage.storage_proof_nodes(hashed_slot))
+                .unwrap_or_default();
+            match first_divergence(&local_storage_proof, &proof.proof) {
+                Some(depth) => {
+                    println!("Storage proof of slot {} diverges at trie depth {depth}", proof.key.0)
+                }
+                None => println!("Storage proof of slot {} matches the local trie", proof.key.0),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the depth of the first node that differs between the local and the provided proofs,
+/// or `None` if the proofs are identical.
+fn first_divergence(local: &[Bytes], provided: &[Bytes]) -> Option<usize> {
+    local
+        .iter()
+        .zip(provided)
+        .position(|(local, provided)| local != provided)
+        .or_else(|| (local.len() != provided.len()).then_some(local.len().min(provided.len())))
+}
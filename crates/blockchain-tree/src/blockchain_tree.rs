@@ -275,7 +275,7 @@ where
             // get block state
             let Some(chain) = self.state.chains.get(&chain_id) else {
                 debug!(target: "blockchain_tree", ?chain_id, "Chain with ID not present");
-                return None;
+                return None
             };
             let block_number = chain.block_number(block_hash)?;
             let state = chain.state_at_block(block_number)?;
@@ -593,13 +593,13 @@ where
             // Get chain of dependent block.
             let Some(chain_id) = self.block_indices().get_block_chain_id(&block) else {
                 debug!(target: "blockchain_tree", ?block, "Block not in tree");
-                return Default::default();
+                return Default::default()
             };
 
             // Find all blocks that fork from this chain.
             let Some(chain) = self.state.chains.get(&chain_id) else {
                 debug!(target: "blockchain_tree", ?chain_id, "Chain not in tree");
-                return Default::default();
+                return Default::default()
             };
             for chain_block in chain.blocks().values() {
                 if let Some(forks) = self.block_indices().fork_to_child().get(&chain_block.hash()) {
@@ -636,7 +636,7 @@ where
                 for chain_id in chains_to_bump {
                     let Some(chain) = self.state.chains.get_mut(&chain_id) else {
                         debug!(target: "blockchain_tree", ?chain_id, "Chain not in tree");
-                        return None;
+                        return None
                     };
 
                     debug!(target: "blockchain_tree",
@@ -730,7 +730,7 @@ where
             // find the canonical fork of this chain
             let Some(canonical_fork) = self.canonical_fork(chain_id) else {
                 debug!(target: "blockchain_tree", chain_id=?chain_id, block=?block.hash, "Chain id not valid");
-                return None;
+                return None
             };
             // if the block's chain extends canonical chain
             return if canonical_fork == self.block_indices().canonical_tip() {
@@ -841,6 +841,10 @@ where
     /// Update all block hashes. iterate over present and new list of canonical hashes and compare
     /// them. Remove all mismatches, disconnect them and removes all chains.
     pub fn update_block_hashes(&mut self) -> ProviderResult<BTreeMap<BlockNumber, B256>> {
+        // The canonical chain might have been changed outside of the tree, the cached trie nodes
+        // can't be trusted anymore.
+        self.externals.clear_trie_node_cache();
+
         let last_canonical_hashes = self
             .externals
             .fetch_latest_canonical_hashes(self.config.num_of_canonical_hashes() as usize)?;
@@ -1202,6 +1206,8 @@ where
 
         durations_recorder.record_relative(MakeCanonicalAction::ClearTrieUpdatesForOtherChilds);
 
+        self.externals.on_canonical_tip(chain_notification.tip().number);
+
         // Send notification about new canonical chain and return outcome of canonicalization.
         let outcome = CanonicalOutcome::Committed { head: chain_notification.tip().header.clone() };
         let _ = self.canon_state_notification_sender.send(chain_notification);
@@ -1253,6 +1259,8 @@ where
         };
         recorder.record_relative(MakeCanonicalAction::RetrieveStateTrieUpdates);
 
+        let cached_trie_updates =
+            self.externals.trie_node_cache.as_ref().map(|cache| (cache, trie_updates.clone()));
         let provider_rw = self.externals.provider_factory.provider_rw()?;
         provider_rw
             .append_blocks_with_state(
@@ -1267,6 +1275,10 @@ where
         provider_rw.commit()?;
         recorder.record_relative(MakeCanonicalAction::CommitCanonicalChainToDatabase);
 
+        if let Some((cache, trie_updates)) = cached_trie_updates {
+            cache.apply_updates(&trie_updates);
+        }
+
         Ok(())
     }
 
@@ -1331,6 +1343,8 @@ where
             .map_err(|e| CanonicalError::CanonicalRevert(e.to_string()))?;
 
         provider_rw.commit()?;
+        self.externals.clear_trie_node_cache();
+        self.externals.clear_payload_reads_cache();
 
         if blocks_and_execution.is_empty() {
             Ok(None)
@@ -205,11 +205,25 @@ impl AppendableChain {
         let provider = BundleStateProvider::new(state_provider, bundle_state_data_provider);
 
         let db = StateProviderDatabase::new(&provider);
-        let executor = externals.executor_factory.executor(db);
         let block_hash = block.hash();
         let block = block.unseal();
 
-        let state = executor.execute((&block, U256::MAX).into())?;
+        // Warm up the execution with the state read by the payload builder, if any
+        let state = match externals.take_payload_reads(&block.parent_hash) {
+            Some(mut cached_reads) => {
+                tracing::trace!(
+                    target: "blockchain_tree::chain",
+                    number = block.number,
+                    hash = %block_hash,
+                    "Executing block with cached payload reads"
+                );
+                externals
+                    .executor_factory
+                    .executor(cached_reads.as_db_mut(db))
+                    .execute((&block, U256::MAX).into())?
+            }
+            None => externals.executor_factory.executor(db).execute((&block, U256::MAX).into())?,
+        };
         let BlockExecutionOutput { state, receipts, requests, .. } = state;
         externals
             .consensus
@@ -230,7 +244,13 @@ impl AppendableChain {
                 let mut state = provider.bundle_state_data_provider.state().clone();
                 state.extend(bundle_state.clone());
                 let hashed_state = state.hash_state_slow();
-                ParallelStateRoot::new(consistent_view, hashed_state)
+                let mut state_root = ParallelStateRoot::new(consistent_view, hashed_state)
+                    .with_config(externals.parallel_state_root_config)
+                    .with_cancellation(externals.state_root_cancellation.clone());
+                if let Some(cache) = &externals.trie_node_cache {
+                    state_root = state_root.with_trie_node_cache(cache.clone());
+                }
+                state_root
                     .incremental_root_with_updates()
                     .map(|(root, updates)| (root, Some(updates)))
                     .map_err(ProviderError::from)?
@@ -8,7 +8,10 @@ use reth_provider::{
     FinalizedBlockReader, FinalizedBlockWriter, ProviderFactory, StaticFileProviderFactory,
     StatsReader,
 };
+use reth_revm::cached::{CachedReads, PayloadReadsCache};
 use reth_storage_errors::provider::ProviderResult;
+use reth_trie::{trie_cursor::TrieNodeCache, CancellationToken};
+use reth_trie_parallel::ParallelStateRootConfig;
 use std::{collections::BTreeMap, sync::Arc};
 
 /// A container for external components.
@@ -20,6 +23,10 @@ use std::{collections::BTreeMap, sync::Arc};
 /// - A handle to the consensus engine
 /// - The executor factory to execute blocks with
 /// - The chain spec
+/// - The parallel state root configuration
+/// - The cancellation token of the state root computation
+/// - The cache of the database trie nodes
+/// - The cache of the reads of the payloads built by the node
 #[derive(Debug)]
 pub struct TreeExternals<DB, E> {
     /// The provider factory, used to commit the canonical chain, or unwind it.
@@ -28,6 +35,15 @@ pub struct TreeExternals<DB, E> {
     pub(crate) consensus: Arc<dyn Consensus>,
     /// The executor factory to execute blocks with.
     pub(crate) executor_factory: E,
+    /// The configuration of the parallel state root computation.
+    pub(crate) parallel_state_root_config: ParallelStateRootConfig,
+    /// The token that cancels in-progress state root computations, e.g. on shutdown.
+    pub(crate) state_root_cancellation: CancellationToken,
+    /// The cache of the database trie nodes used to validate the state root of the blocks that
+    /// extend the canonical chain.
+    pub(crate) trie_node_cache: Option<TrieNodeCache>,
+    /// The reads of the payloads built by the node, used to warm up the execution of the blocks.
+    pub(crate) payload_reads_cache: Option<PayloadReadsCache>,
 }
 
 impl<DB, E> TreeExternals<DB, E> {
@@ -37,7 +53,74 @@ impl<DB, E> TreeExternals<DB, E> {
         consensus: Arc<dyn Consensus>,
         executor_factory: E,
     ) -> Self {
-        Self { provider_factory, consensus, executor_factory }
+        Self {
+            provider_factory,
+            consensus,
+            executor_factory,
+            parallel_state_root_config: ParallelStateRootConfig::default(),
+            state_root_cancellation: CancellationToken::default(),
+            trie_node_cache: None,
+            payload_reads_cache: None,
+        }
+    }
+
+    /// Set the configuration of the parallel state root computation.
+    pub const fn with_parallel_state_root_config(
+        mut self,
+        config: ParallelStateRootConfig,
+    ) -> Self {
+        self.parallel_state_root_config = config;
+        self
+    }
+
+    /// Set the token that cancels in-progress state root computations.
+    pub fn with_state_root_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.state_root_cancellation = cancellation;
+        self
+    }
+
+    /// Set the cache of the database trie nodes.
+    ///
+    /// The tree keeps the cache in sync with the trie updates it commits and clears it whenever
+    /// the database is changed outside of the tree, e.g. by the pipeline.
+    pub fn with_trie_node_cache(mut self, cache: TrieNodeCache) -> Self {
+        self.trie_node_cache = Some(cache);
+        self
+    }
+
+    /// Clears the cache of the database trie nodes, if any.
+    pub(crate) fn clear_trie_node_cache(&self) {
+        if let Some(cache) = &self.trie_node_cache {
+            cache.clear();
+        }
+    }
+
+    /// Set the cache of the reads of the payloads built by the node.
+    ///
+    /// The blocks built on top of the cached parent blocks are executed on top of the cached
+    /// reads instead of reading the same state from the database again.
+    pub fn with_payload_reads_cache(mut self, cache: PayloadReadsCache) -> Self {
+        self.payload_reads_cache = Some(cache);
+        self
+    }
+
+    /// Takes the cached reads of the state of the given parent block, if any.
+    pub(crate) fn take_payload_reads(&self, parent_hash: &BlockHash) -> Option<CachedReads> {
+        self.payload_reads_cache.as_ref().and_then(|cache| cache.take(parent_hash))
+    }
+
+    /// Evicts the cached payload reads that are too old relative to the new canonical tip.
+    pub(crate) fn on_canonical_tip(&self, tip_number: BlockNumber) {
+        if let Some(cache) = &self.payload_reads_cache {
+            cache.on_canonical_tip(tip_number);
+        }
+    }
+
+    /// Clears the cache of the payload reads, if any.
+    pub(crate) fn clear_payload_reads_cache(&self) {
+        if let Some(cache) = &self.payload_reads_cache {
+            cache.clear();
+        }
     }
 }
 
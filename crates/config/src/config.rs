@@ -82,6 +82,8 @@ pub struct StageConfig {
     pub index_account_history: IndexHistoryConfig,
     /// Index Storage History stage configuration.
     pub index_storage_history: IndexHistoryConfig,
+    /// Index Address Transactions stage configuration.
+    pub index_address_transactions: IndexAddressTransactionsConfig,
     /// Common ETL related configuration.
     pub etl: EtlConfig,
 }
@@ -310,6 +312,24 @@ impl Default for IndexHistoryConfig {
     }
 }
 
+/// Address transaction index stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct IndexAddressTransactionsConfig {
+    /// Whether the index of the blocks with transactions of an address is built.
+    ///
+    /// If disabled, an existing index is dropped on startup, since it isn't kept up to date.
+    pub enabled: bool,
+    /// The maximum number of blocks to process before committing progress to the database.
+    pub commit_threshold: u64,
+}
+
+impl Default for IndexAddressTransactionsConfig {
+    fn default() -> Self {
+        Self { enabled: false, commit_threshold: 100_000 }
+    }
+}
+
 /// Pruning configuration.
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
@@ -405,6 +425,18 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_load_index_address_transactions_stage() {
+        with_tempdir("config-load-test", |config_path| {
+            let mut config = Config::default();
+            config.stages.index_address_transactions.enabled = true;
+            confy::store_path(config_path, &config).unwrap();
+
+            let loaded_config: Config = confy::load_path(config_path).unwrap();
+            assert_eq!(config, loaded_config);
+        })
+    }
+
     // ensures config deserialization is backwards compatible
     #[test]
     fn test_backwards_compatibility() {
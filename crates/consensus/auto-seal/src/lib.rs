@@ -21,7 +21,6 @@ use reth_engine_primitives::EngineTypes;
 use reth_execution_errors::{BlockExecutionError, BlockValidationError};
 use reth_primitives::{
     constants::{EMPTY_TRANSACTIONS, ETHEREUM_BLOCK_GAS_LIMIT},
-    eip4844::calculate_excess_blob_gas,
     proofs, Block, BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders,
     ChainSpec, Header, Receipts, Requests, SealedBlock, SealedHeader, TransactionSigned,
     Withdrawals, B256, U256,
@@ -325,8 +324,11 @@ impl StorageInner {
                 }
                 _ => (0, 0),
             };
-            header.excess_blob_gas =
-                Some(calculate_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used))
+            header.excess_blob_gas = Some(
+                chain_spec
+                    .blob_params_at_timestamp(timestamp)
+                    .next_block_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used),
+            )
         }
 
         header.transactions_root = if transactions.is_empty() {
@@ -0,0 +1,181 @@
+//! Decides when the engine falls back from live sync to backfill sync with the pipeline.
+
+use reth_primitives::{stage::PipelineTarget, BlockNumber};
+use std::task::{Context, Poll};
+
+/// The reason the engine backfills blocks with the pipeline instead of live syncing them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackfillReason {
+    /// The sync target was configured on startup, or the previous pipeline run was interrupted.
+    Startup,
+    /// The gap between the canonical tip and the sync target exceeds the backfill trigger
+    /// distance.
+    Distance {
+        /// The block number of the canonical tip.
+        local_tip: BlockNumber,
+        /// The block number of the sync target.
+        target: BlockNumber,
+    },
+    /// The head is missing and the backfill trigger distance is zero, so blocks are never live
+    /// synced.
+    LiveSyncDisabled,
+    /// The finalized block is still missing after the previous pipeline run.
+    FinalizedMissing,
+    /// The canonical chain has to be unwound below a block that was synced optimistically.
+    OptimisticRevert,
+    /// The pipeline is run continuously.
+    Continuous,
+}
+
+/// A request to start backfill sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillRequest {
+    /// The target of the pipeline.
+    ///
+    /// This is `None` if the pipeline is run continuously without a specific target.
+    pub target: Option<PipelineTarget>,
+    /// Why the blocks are backfilled.
+    pub reason: BackfillReason,
+}
+
+/// What a [`BackfillHook`] decided about a [`BackfillRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackfillAction {
+    /// Backfill sync may start.
+    Proceed,
+    /// Backfill sync must not start, the request is dropped.
+    ///
+    /// The engine requests backfill again if it still falls behind.
+    Veto,
+}
+
+/// Hook that is consulted by the engine before it starts backfill sync.
+///
+/// This allows components that track the canonical chain, e.g. an `ExEx`, to finish their own work
+/// before the pipeline takes over the database.
+pub trait BackfillHook: Send + Sync + 'static {
+    /// Returns a human-readable name for the hook.
+    fn name(&self) -> &'static str;
+
+    /// Decides whether backfill sync for the given request may start.
+    ///
+    /// Returning [`Poll::Pending`] delays backfill sync, the hook is polled again once it woke up
+    /// the task. The engine keeps live syncing in the meantime and drops the request if the gap
+    /// closes.
+    fn poll_backfill(
+        &mut self,
+        cx: &mut Context<'_>,
+        request: &BackfillRequest,
+    ) -> Poll<BackfillAction>;
+}
+
+/// Decides when the engine backfills blocks instead of live syncing them, based on the distance
+/// between the canonical tip and the sync target.
+///
+/// Backfill is triggered if the gap is larger than the trigger distance. A pending backfill
+/// request is only dropped once the gap closed to at most half the trigger distance, so a sync
+/// target that keeps moving around the trigger distance doesn't switch between live sync and
+/// backfill sync on every update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BackfillTrigger {
+    /// The largest gap that is live synced.
+    distance: u64,
+}
+
+impl BackfillTrigger {
+    /// Creates a new [`BackfillTrigger`] with the given trigger distance.
+    pub(crate) const fn new(distance: u64) -> Self {
+        Self { distance }
+    }
+
+    /// Returns the trigger distance.
+    pub(crate) const fn distance(&self) -> u64 {
+        self.distance
+    }
+
+    /// Returns `true` if the distance from the local tip to the block is greater than the trigger
+    /// distance.
+    ///
+    /// If the `local_tip` is greater than the `block`, then this will return false.
+    pub(crate) const fn exceeds(&self, local_tip: BlockNumber, block: BlockNumber) -> bool {
+        block > local_tip && block - local_tip > self.distance
+    }
+
+    /// Returns `true` if a pending backfill to the target is no longer needed because the local
+    /// tip caught up with it.
+    pub(crate) const fn has_gap_closed(&self, local_tip: BlockNumber, target: BlockNumber) -> bool {
+        target <= local_tip || target - local_tip <= self.distance / 2
+    }
+
+    /// Returns `true` if the blocks up to the target should be backfilled, depending on whether a
+    /// backfill is already pending.
+    pub(crate) const fn should_backfill(
+        &self,
+        pending: bool,
+        local_tip: BlockNumber,
+        target: BlockNumber,
+    ) -> bool {
+        if pending {
+            !self.has_gap_closed(local_tip, target)
+        } else {
+            self.exceeds(local_tip, target)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exceeds_at_threshold() {
+        let trigger = BackfillTrigger::new(32);
+
+        assert!(!trigger.exceeds(100, 132));
+        assert!(trigger.exceeds(100, 133));
+
+        // the local tip is ahead of the block
+        assert!(!trigger.exceeds(200, 100));
+        assert!(!trigger.exceeds(100, 100));
+
+        // a trigger distance of zero backfills any gap
+        let trigger = BackfillTrigger::new(0);
+        assert!(trigger.exceeds(100, 101));
+        assert!(!trigger.exceeds(100, 100));
+    }
+
+    #[test]
+    fn gap_closed() {
+        let trigger = BackfillTrigger::new(32);
+
+        assert!(!trigger.has_gap_closed(100, 133));
+        // still above half the trigger distance
+        assert!(!trigger.has_gap_closed(100, 117));
+        assert!(trigger.has_gap_closed(100, 116));
+        assert!(trigger.has_gap_closed(133, 133));
+        assert!(trigger.has_gap_closed(140, 133));
+    }
+
+    #[test]
+    fn small_jumps_dont_thrash() {
+        let trigger = BackfillTrigger::new(32);
+
+        // the sync target moves back and forth around the trigger distance while the local tip
+        // stays put, the pending backfill is entered once and kept
+        let mut pending = false;
+        let mut switches = 0;
+        for target in [130, 134, 131, 135, 129, 133, 120, 136] {
+            let backfill = trigger.should_backfill(pending, 100, target);
+            if backfill != pending {
+                switches += 1;
+            }
+            pending = backfill;
+        }
+        assert_eq!(switches, 1);
+        assert!(pending);
+
+        // the pending backfill is dropped once the local tip caught up
+        assert!(trigger.should_backfill(true, 119, 136));
+        assert!(!trigger.should_backfill(true, 120, 136));
+    }
+}
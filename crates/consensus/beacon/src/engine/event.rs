@@ -1,5 +1,5 @@
-use crate::engine::forkchoice::ForkchoiceStatus;
-use reth_primitives::{SealedBlock, SealedHeader, B256};
+use crate::engine::{backfill::BackfillReason, forkchoice::ForkchoiceStatus};
+use reth_primitives::{stage::PipelineTarget, BlockNumber, SealedBlock, SealedHeader, B256};
 use reth_rpc_types::engine::ForkchoiceState;
 use std::{sync::Arc, time::Duration};
 
@@ -16,6 +16,22 @@ pub enum BeaconConsensusEngineEvent {
     LiveSyncProgress(ConsensusEngineLiveSyncProgress),
     /// A block was added to the fork chain.
     ForkBlockAdded(Arc<SealedBlock>),
+    /// The consensus engine started to backfill blocks with the pipeline.
+    BackfillSyncStarted {
+        /// The target of the pipeline, `None` if the pipeline is run continuously.
+        target: Option<PipelineTarget>,
+        /// Why the blocks are backfilled.
+        reason: BackfillReason,
+    },
+    /// The consensus engine finished to backfill blocks with the pipeline.
+    BackfillSyncFinished {
+        /// The target of the pipeline, `None` if the pipeline is run continuously.
+        target: Option<PipelineTarget>,
+        /// Why the blocks were backfilled.
+        reason: BackfillReason,
+        /// The block number the pipeline reached, `None` if the pipeline failed.
+        block_number: Option<BlockNumber>,
+    },
 }
 
 /// Progress of the consensus engine during live sync.
@@ -48,6 +48,19 @@ impl InvalidHeaderCache {
         None
     }
 
+    /// Walks up the known invalid ancestry of the given block and returns the hash of the first
+    /// ancestor that is not known to be invalid.
+    ///
+    /// Returns the given hash if the block itself is not known to be invalid. Unlike
+    /// [`Self::get`], this doesn't count as a hit for any of the visited entries.
+    pub(crate) fn first_ancestor_not_known_invalid(&self, hash: B256) -> B256 {
+        let mut current = hash;
+        while let Some(entry) = self.headers.peek(&current) {
+            current = entry.header.parent_hash;
+        }
+        current
+    }
+
     /// Inserts an invalid block into the cache, with a given invalid ancestor.
     pub(crate) fn insert_with_invalid_ancestor(
         &mut self,
@@ -118,4 +131,34 @@ mod tests {
 
         assert!(cache.get(&header.hash()).is_none());
     }
+
+    #[test]
+    fn test_first_ancestor_not_known_invalid() {
+        let mut cache = InvalidHeaderCache::new(10);
+        let valid = Header::default().seal_slow();
+        let invalid = Header { parent_hash: valid.hash(), number: 1, ..Default::default() };
+        let invalid = invalid.seal_slow();
+        cache.insert(invalid.clone());
+
+        // a chain of three descendants of the invalid block
+        let mut parent = invalid.hash();
+        let mut descendants = Vec::new();
+        for number in 2..5 {
+            let descendant =
+                Header { parent_hash: parent, number, ..Default::default() }.seal_slow();
+            cache.insert_with_invalid_ancestor(
+                descendant.hash(),
+                Arc::new(invalid.header().clone()),
+            );
+            parent = descendant.hash();
+            descendants.push(descendant.hash());
+        }
+
+        for hash in descendants.iter().chain(std::iter::once(&invalid.hash())) {
+            assert_eq!(cache.first_ancestor_not_known_invalid(*hash), valid.hash());
+            // walking the ancestry doesn't count as a hit
+            assert_eq!(cache.headers.peek(hash).unwrap().hit_count, 0);
+        }
+        assert_eq!(cache.first_ancestor_not_known_invalid(valid.hash()), valid.hash());
+    }
 }
@@ -59,6 +59,9 @@ use invalid_headers::InvalidHeaderCache;
 mod event;
 pub use event::{BeaconConsensusEngineEvent, ConsensusEngineLiveSyncProgress};
 
+mod backfill;
+pub use backfill::{BackfillAction, BackfillHook, BackfillReason, BackfillRequest};
+
 mod handle;
 pub use handle::BeaconConsensusEngineHandle;
 
@@ -83,8 +86,8 @@ pub mod test_utils;
 /// The maximum number of invalid headers that can be tracked by the engine.
 const MAX_INVALID_HEADERS: u32 = 512u32;
 
-/// The largest gap for which the tree will be used for sync. See docs for `pipeline_run_threshold`
-/// for more information.
+/// The largest gap for which the tree will be used for sync. See docs for
+/// [`BeaconConsensusEngine::new`] for more information.
 ///
 /// This is the default threshold, the distance to the head that the tree will be used for sync.
 /// If the distance exceeds this threshold, the pipeline will be used for sync.
@@ -200,18 +203,6 @@ where
     /// Tracks the header of invalid payloads that were rejected by the engine because they're
     /// invalid.
     invalid_headers: InvalidHeaderCache,
-    /// After downloading a block corresponding to a recent forkchoice update, the engine will
-    /// check whether or not we can connect the block to the current canonical chain. If we can't,
-    /// we need to download and execute the missing parents of that block.
-    ///
-    /// When the block can't be connected, its block number will be compared to the canonical head,
-    /// resulting in a heuristic for the number of missing blocks, or the size of the gap between
-    /// the new block and the canonical head.
-    ///
-    /// If the gap is larger than this threshold, the engine will download and execute the missing
-    /// blocks using the pipeline. Otherwise, the engine, sync controller, and blockchain tree will
-    /// be used to download and execute the missing blocks.
-    pipeline_run_threshold: u64,
     hooks: EngineHooksController,
     /// Sender for engine events.
     event_sender: EventSender<BeaconConsensusEngineEvent>,
@@ -233,6 +224,18 @@ where
     EngineT: EngineTypes + Unpin + 'static,
 {
     /// Create a new instance of the [`BeaconConsensusEngine`].
+    ///
+    /// After downloading a block corresponding to a recent forkchoice update, the engine will
+    /// check whether or not we can connect the block to the current canonical chain. If we can't,
+    /// we need to download and execute the missing parents of that block.
+    ///
+    /// When the block can't be connected, its block number will be compared to the canonical head,
+    /// resulting in a heuristic for the number of missing blocks, or the size of the gap between
+    /// the new block and the canonical head.
+    ///
+    /// If the gap is larger than the `pipeline_run_threshold`, the engine will download and
+    /// execute the missing blocks using the pipeline (backfill sync). Otherwise, the engine, sync
+    /// controller, and blockchain tree will be used to download and execute the missing blocks.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         client: Client,
@@ -304,6 +307,7 @@ where
             max_block,
             blockchain.chain_spec(),
             event_sender.clone(),
+            pipeline_run_threshold,
         );
         let mut this = Self {
             sync,
@@ -317,7 +321,6 @@ where
             invalid_headers: InvalidHeaderCache::new(MAX_INVALID_HEADERS),
             blockchain_tree_action: None,
             pending_forkchoice_update: None,
-            pipeline_run_threshold,
             hooks: EngineHooksController::new(hooks),
             event_sender,
             metrics: EngineMetrics::default(),
@@ -330,7 +333,7 @@ where
         };
 
         if let Some(target) = maybe_pipeline_target {
-            this.sync.set_pipeline_sync_target(target.into());
+            this.sync.set_pipeline_sync_target(target.into(), BackfillReason::Startup);
         }
 
         Ok((this, handle))
@@ -414,6 +417,7 @@ where
                         Box::new(head.clone()),
                         elapsed,
                     ));
+                    self.sync.on_canonical_tip(head.number);
                 }
 
                 // Validate that the forkchoice state is consistent.
@@ -624,18 +628,32 @@ where
         self.handle.clone()
     }
 
-    /// Returns true if the distance from the local tip to the block is greater than the configured
+    /// Adds a [`BackfillHook`] that is consulted before the engine backfills blocks with the
+    /// pipeline.
+    pub fn add_backfill_hook<H: BackfillHook>(&mut self, hook: H) {
+        self.sync.add_backfill_hook(Box::new(hook));
+    }
+
+    /// Returns true if the blocks from the local tip to the block should be backfilled with the
+    /// pipeline, because the distance is greater than the configured threshold.
+    ///
+    /// If a pipeline run is already pending, it is kept until the gap closed, so that the engine
+    /// doesn't switch between live sync and the pipeline if the distance moves around the
     /// threshold.
     ///
     /// If the `local_tip` is greater than the `block`, then this will return false.
     #[inline]
     const fn exceeds_pipeline_run_threshold(&self, local_tip: u64, block: u64) -> bool {
-        block > local_tip && block - local_tip > self.pipeline_run_threshold
+        self.sync.backfill_trigger().should_backfill(
+            self.sync.is_pipeline_sync_pending(),
+            local_tip,
+            block,
+        )
     }
 
     /// Returns the finalized hash to sync to if the distance from the local tip to the block is
     /// greater than the configured threshold and we're not synced to the finalized block yet
-    /// yet (if we've seen that block already).
+    /// yet (if we've seen that block already), with the reason for the pipeline run.
     ///
     /// If this is invoked after a new block has been downloaded, the downloaded block could be the
     /// (missing) finalized block.
@@ -644,12 +662,11 @@ where
         canonical_tip_num: u64,
         target_block_number: u64,
         downloaded_block: Option<BlockNumHash>,
-    ) -> Option<B256> {
+    ) -> Option<(B256, BackfillReason)> {
         let sync_target_state = self.forkchoice_state_tracker.sync_target_state();
 
-        // check if the distance exceeds the threshold for pipeline sync
-        let mut exceeds_pipeline_run_threshold =
-            self.exceeds_pipeline_run_threshold(canonical_tip_num, target_block_number);
+        // the block number the distance is checked against
+        let mut target_block_number = target_block_number;
 
         // check if the downloaded block is the tracked finalized block
         if let Some(ref buffered_finalized) = sync_target_state
@@ -658,8 +675,7 @@ where
         {
             // if we have buffered the finalized block, we should check how far
             // we're off
-            exceeds_pipeline_run_threshold =
-                self.exceeds_pipeline_run_threshold(canonical_tip_num, buffered_finalized.number);
+            target_block_number = buffered_finalized.number;
         }
 
         // If this is invoked after we downloaded a block we can check if this block is the
@@ -667,11 +683,16 @@ where
         if let (Some(downloaded_block), Some(ref state)) = (downloaded_block, sync_target_state) {
             if downloaded_block.hash == state.finalized_block_hash {
                 // we downloaded the finalized block
-                exceeds_pipeline_run_threshold =
-                    self.exceeds_pipeline_run_threshold(canonical_tip_num, downloaded_block.number);
+                target_block_number = downloaded_block.number;
             }
         }
 
+        // check if the distance exceeds the threshold for pipeline sync
+        let exceeds_pipeline_run_threshold =
+            self.exceeds_pipeline_run_threshold(canonical_tip_num, target_block_number);
+        let reason =
+            BackfillReason::Distance { local_tip: canonical_tip_num, target: target_block_number };
+
         // if the number of missing blocks is greater than the max, run the
         // pipeline
         if exceeds_pipeline_run_threshold {
@@ -687,7 +708,7 @@ where
                         if !state.finalized_block_hash.is_zero() {
                             // we don't have the block yet and the distance exceeds the allowed
                             // threshold
-                            return Some(state.finalized_block_hash)
+                            return Some((state.finalized_block_hash, reason))
                         }
 
                         // OPTIMISTIC SYNCING
@@ -703,7 +724,7 @@ where
                         // However, optimism chains will do this. The risk of a reorg is however
                         // low.
                         debug!(target: "consensus::engine", hash=?state.head_block_hash, "Setting head hash as an optimistic pipeline target.");
-                        return Some(state.head_block_hash)
+                        return Some((state.head_block_hash, reason))
                     }
                     Ok(Some(_)) => {
                         // we're fully synced to the finalized block
@@ -741,47 +762,37 @@ where
         &mut self,
         parent_hash: B256,
     ) -> ProviderResult<Option<B256>> {
-        // Check if parent exists in side chain or in canonical chain.
-        if self.blockchain.find_block_by_hash(parent_hash, BlockSource::Any)?.is_some() {
-            return Ok(Some(parent_hash))
-        }
+        // skip over the ancestors that are known to be invalid, without counting these lookups as
+        // hits in the cache
+        let ancestor_hash = self.invalid_headers.first_ancestor_not_known_invalid(parent_hash);
+
+        // the ancestor is only known to be valid if it exists in a side chain or the canonical
+        // chain
+        let Some(ancestor) = self.blockchain.find_block_by_hash(ancestor_hash, BlockSource::Any)?
+        else {
+            return Ok(None)
+        };
 
-        // iterate over ancestors in the invalid cache
-        // until we encounter the first valid ancestor
-        let mut current_hash = parent_hash;
-        let mut current_header = self.invalid_headers.get(&current_hash);
-        while let Some(header) = current_header {
-            current_hash = header.parent_hash;
-            current_header = self.invalid_headers.get(&current_hash);
-
-            // If current_header is None, then the current_hash does not have an invalid
-            // ancestor in the cache, check its presence in blockchain tree
-            if current_header.is_none() &&
-                self.blockchain.find_block_by_hash(current_hash, BlockSource::Any)?.is_some()
-            {
-                return Ok(Some(current_hash))
-            }
+        // Edge case: the `latestValid` field is the zero hash if the latest valid ancestor is a PoW
+        // block, which we need to identify by looking at its block difficulty
+        if !ancestor.header.is_zero_difficulty() {
+            return Ok(Some(B256::ZERO))
         }
-        Ok(None)
+
+        Ok(Some(ancestor_hash))
     }
 
     /// Prepares the invalid payload response for the given hash, checking the
     /// database for the parent hash and populating the payload status with the latest valid hash
     /// according to the engine api spec.
-    fn prepare_invalid_response(&mut self, mut parent_hash: B256) -> ProviderResult<PayloadStatus> {
-        // Edge case: the `latestValid` field is the zero hash if the parent block is the terminal
-        // PoW block, which we need to identify by looking at the parent's block difficulty
-        if let Ok(Some(parent)) = self.blockchain.header_by_hash_or_number(parent_hash.into()) {
-            if !parent.is_zero_difficulty() {
-                parent_hash = B256::ZERO;
-            }
-        }
-
+    fn prepare_invalid_response(&mut self, parent_hash: B256) -> ProviderResult<PayloadStatus> {
         let valid_parent_hash = self.latest_valid_hash_for_invalid_payload(parent_hash)?;
-        Ok(PayloadStatus::from_status(PayloadStatusEnum::Invalid {
-            validation_error: PayloadValidationError::LinksToRejectedPayload.to_string(),
-        })
-        .with_latest_valid_hash(valid_parent_hash.unwrap_or_default()))
+        Ok(PayloadStatus::new(
+            PayloadStatusEnum::Invalid {
+                validation_error: PayloadValidationError::LinksToRejectedPayload.to_string(),
+            },
+            valid_parent_hash,
+        ))
     }
 
     /// Checks if the given `check` hash points to an invalid header, inserting the given `head`
@@ -1005,7 +1016,10 @@ where
                 // to a new target and is considered normal operation during sync
             }
             CanonicalError::OptimisticTargetRevert(block_number) => {
-                self.sync.set_pipeline_sync_target(PipelineTarget::Unwind(*block_number));
+                self.sync.set_pipeline_sync_target(
+                    PipelineTarget::Unwind(*block_number),
+                    BackfillReason::OptimisticRevert,
+                );
                 return Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing))
             }
             _ => {
@@ -1035,10 +1049,10 @@ where
 
         // if the threshold is zero, we should not download the block first, and just use the
         // pipeline. Otherwise we use the tree to insert the block first
-        if self.pipeline_run_threshold == 0 {
+        if self.sync.backfill_trigger().distance() == 0 {
             // use the pipeline to sync to the target
             trace!(target: "consensus::engine", %target, "Triggering pipeline run to sync missing ancestors of the new head");
-            self.sync.set_pipeline_sync_target(target.into());
+            self.sync.set_pipeline_sync_target(target.into(), BackfillReason::LiveSyncDisabled);
         } else {
             // trigger a full block download for missing hash, or the parent of its lowest buffered
             // ancestor
@@ -1134,6 +1148,13 @@ where
             }
         };
 
+        // check if the block itself was already marked as invalid, so it isn't executed again
+        if let Some(status) =
+            self.check_invalid_ancestor(block.hash()).map_err(BeaconOnNewPayloadError::internal)?
+        {
+            return Ok(Either::Left(status))
+        }
+
         let mut lowest_buffered_ancestor = self.lowest_buffered_ancestor_or(block.hash());
         if lowest_buffered_ancestor == block.hash() {
             lowest_buffered_ancestor = block.parent_hash;
@@ -1289,14 +1310,14 @@ where
         head: BlockNumHash,
     ) {
         // compare the missing parent with the canonical tip
-        if let Some(target) = self.can_pipeline_sync_to_finalized(
+        if let Some((target, reason)) = self.can_pipeline_sync_to_finalized(
             head.number,
             missing_parent.number,
             Some(downloaded_block),
         ) {
             // we don't have the block yet and the distance exceeds the allowed
             // threshold
-            self.sync.set_pipeline_sync_target(target.into());
+            self.sync.set_pipeline_sync_target(target.into(), reason);
             // we can exit early here because the pipeline will take care of syncing
             return
         }
@@ -1346,6 +1367,7 @@ where
                         Box::new(head.clone()),
                         elapsed,
                     ));
+                    self.sync.on_canonical_tip(head.number);
                 }
 
                 let new_head = outcome.into_header();
@@ -1379,7 +1401,10 @@ where
                         let _ = self.blockchain.make_canonical(*target_hash.as_ref());
                     }
                 } else if let Some(block_number) = err.optimistic_revert_block_number() {
-                    self.sync.set_pipeline_sync_target(PipelineTarget::Unwind(block_number));
+                    self.sync.set_pipeline_sync_target(
+                        PipelineTarget::Unwind(block_number),
+                        BackfillReason::OptimisticRevert,
+                    );
                 }
 
                 Err((target.head_block_hash, err))
@@ -1525,9 +1550,9 @@ where
 
         // If the distance is large enough, we should run the pipeline again to prevent
         // the tree update from executing too many blocks and blocking.
-        if let Some(target) = pipeline_target {
+        if let Some((target, reason)) = pipeline_target {
             // run the pipeline to the target since the distance is sufficient
-            self.sync.set_pipeline_sync_target(target.into());
+            self.sync.set_pipeline_sync_target(target.into(), reason);
         } else if let Some(number) =
             self.blockchain.block_number(sync_target_state.finalized_block_hash)?
         {
@@ -1539,7 +1564,10 @@ where
         } else {
             // We don't have the finalized block in the database, so we need to
             // trigger another pipeline run.
-            self.sync.set_pipeline_sync_target(sync_target_state.finalized_block_hash.into());
+            self.sync.set_pipeline_sync_target(
+                sync_target_state.finalized_block_hash.into(),
+                BackfillReason::FinalizedMissing,
+            );
         }
 
         Ok(())
@@ -2503,6 +2531,7 @@ mod tests {
         use reth_primitives::{genesis::Genesis, Hardfork, U256};
         use reth_provider::{
             providers::StaticFileProvider, test_utils::blocks::BlockchainTestData,
+            BundleStateWithReceipts,
         };
         use reth_testing_utils::{generators::random_block, GenesisAllocator};
         #[tokio::test]
@@ -2725,6 +2754,88 @@ mod tests {
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
         }
 
+        #[tokio::test]
+        async fn payload_links_to_invalid_ancestor() {
+            let mut rng = generators::rng();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            // only the invalid block is executed, its descendants must not be
+            let (consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                    checkpoint: StageCheckpoint::new(0),
+                    done: true,
+                })]))
+                .with_executor_results(Vec::from([BundleStateWithReceipts::default()]))
+                .build();
+
+            let genesis = random_block(&mut rng, 0, None, Some(0), Some(0));
+
+            let (_static_dir, static_dir_path) = create_test_static_files_dir();
+            insert_blocks(
+                ProviderFactory::new(
+                    env.db.as_ref(),
+                    chain_spec.clone(),
+                    StaticFileProvider::read_write(static_dir_path).unwrap(),
+                ),
+                std::iter::once(&genesis),
+            );
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            // Send forkchoice
+            let res = env
+                .send_forkchoice_updated(ForkchoiceState {
+                    head_block_hash: genesis.hash(),
+                    finalized_block_hash: genesis.hash(),
+                    ..Default::default()
+                })
+                .await;
+            let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Valid)
+                .with_latest_valid_hash(genesis.hash());
+            assert_matches!(res, Ok(ForkchoiceUpdated { payload_status, .. }) => assert_eq!(payload_status, expected_result));
+
+            // Send a payload with a state root mismatch
+            let mut invalid =
+                random_block(&mut rng, 1, Some(genesis.hash()), Some(0), Some(0)).unseal();
+            invalid.header.state_root = rng.gen();
+            let invalid = invalid.seal_slow();
+            let result = env.send_new_payload(block_to_payload_v1(invalid.clone()), None).await;
+            assert_matches!(result, Ok(PayloadStatus { status: PayloadStatusEnum::Invalid { .. }, latest_valid_hash: Some(hash) }) => assert_eq!(hash, genesis.hash()));
+
+            // Send descendants of the invalid payload, these all point to the genesis block as the
+            // latest valid ancestor
+            let expected_result = PayloadStatus::new(
+                PayloadStatusEnum::Invalid {
+                    validation_error: PayloadValidationError::LinksToRejectedPayload.to_string(),
+                },
+                Some(genesis.hash()),
+            );
+            let mut parent = invalid.hash();
+            for number in 2..5 {
+                let block = random_block(&mut rng, number, Some(parent), Some(0), Some(0));
+                parent = block.hash();
+                let result = env.send_new_payload(block_to_payload_v1(block), None).await;
+                assert_matches!(result, Ok(status) => assert_eq!(status, expected_result));
+            }
+
+            // Forkchoice to the last descendant is rejected the same way
+            let res = env
+                .send_forkchoice_updated(ForkchoiceState {
+                    head_block_hash: parent,
+                    ..Default::default()
+                })
+                .await;
+            assert_matches!(res, Ok(ForkchoiceUpdated { payload_status, .. }) => assert_eq!(payload_status, expected_result));
+
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
         #[tokio::test]
         async fn payload_pre_merge() {
             let data = BlockchainTestData::default();
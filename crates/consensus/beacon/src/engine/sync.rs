@@ -1,7 +1,8 @@
 //! Sync management for the engine implementation.
 
 use crate::{
-    engine::metrics::EngineSyncMetrics, BeaconConsensusEngineEvent,
+    engine::{backfill::BackfillTrigger, metrics::EngineSyncMetrics},
+    BackfillAction, BackfillHook, BackfillReason, BackfillRequest, BeaconConsensusEngineEvent,
     ConsensusEngineLiveSyncProgress, EthBeaconConsensus,
 };
 use futures::FutureExt;
@@ -22,7 +23,7 @@ use std::{
     task::{ready, Context, Poll},
 };
 use tokio::sync::oneshot;
-use tracing::trace;
+use tracing::{debug, trace};
 
 /// Manages syncing under the control of the engine.
 ///
@@ -43,8 +44,14 @@ where
     /// The current state of the pipeline.
     /// The pipeline is used for large ranges.
     pipeline_state: PipelineState<DB>,
-    /// Pending target block for the pipeline to sync
-    pending_pipeline_target: Option<PipelineTarget>,
+    /// Pending request to sync the pipeline to a target block
+    pending_pipeline_target: Option<BackfillRequest>,
+    /// The request the running pipeline was started for.
+    active_backfill: Option<BackfillRequest>,
+    /// Decides when blocks are backfilled with the pipeline instead of downloaded.
+    backfill_trigger: BackfillTrigger,
+    /// Hooks that are consulted before the pipeline is started.
+    backfill_hooks: Vec<Box<dyn BackfillHook>>,
     /// In-flight full block requests in progress.
     inflight_full_block_requests: Vec<FetchFullBlockFuture<Client>>,
     /// In-flight full block _range_ requests in progress.
@@ -77,6 +84,7 @@ where
         max_block: Option<BlockNumber>,
         chain_spec: Arc<ChainSpec>,
         event_sender: EventSender<BeaconConsensusEngineEvent>,
+        backfill_trigger_distance: u64,
     ) -> Self {
         Self {
             full_block_client: FullBlockClient::new(
@@ -86,6 +94,9 @@ where
             pipeline_task_spawner,
             pipeline_state: PipelineState::Idle(Some(pipeline)),
             pending_pipeline_target: None,
+            active_backfill: None,
+            backfill_trigger: BackfillTrigger::new(backfill_trigger_distance),
+            backfill_hooks: Vec::new(),
             inflight_full_block_requests: Vec::new(),
             inflight_block_range_requests: Vec::new(),
             range_buffered_blocks: BinaryHeap::new(),
@@ -127,8 +138,18 @@ where
         self.run_pipeline_continuously
     }
 
-    /// Returns `true` if a pipeline target is queued and will be triggered on the next `poll`.
-    #[allow(dead_code)]
+    /// Returns the trigger that decides when blocks are backfilled with the pipeline.
+    pub(crate) const fn backfill_trigger(&self) -> &BackfillTrigger {
+        &self.backfill_trigger
+    }
+
+    /// Adds a hook that is consulted before the pipeline is started.
+    pub(crate) fn add_backfill_hook(&mut self, hook: Box<dyn BackfillHook>) {
+        self.backfill_hooks.push(hook);
+    }
+
+    /// Returns `true` if a pipeline target is queued and will be triggered on the next `poll`,
+    /// unless a [`BackfillHook`] delays it.
     pub(crate) const fn is_pipeline_sync_pending(&self) -> bool {
         self.pending_pipeline_target.is_some() && self.pipeline_state.is_idle()
     }
@@ -211,7 +232,11 @@ where
     /// Sets a new target to sync the pipeline to.
     ///
     /// But ensures the target is not the zero hash.
-    pub(crate) fn set_pipeline_sync_target(&mut self, target: PipelineTarget) {
+    pub(crate) fn set_pipeline_sync_target(
+        &mut self,
+        target: PipelineTarget,
+        reason: BackfillReason,
+    ) {
         if target.sync_target().is_some_and(|target| target.is_zero()) {
             trace!(
                 target: "consensus::engine::sync",
@@ -220,7 +245,50 @@ where
             // precaution to never sync to the zero hash
             return
         }
-        self.pending_pipeline_target = Some(target);
+        self.pending_pipeline_target = Some(BackfillRequest { target: Some(target), reason });
+    }
+
+    /// Invoked when the canonical chain advanced to the given block number.
+    ///
+    /// Drops a pending pipeline target that was set because of the distance to the canonical tip,
+    /// if the canonical tip caught up with it while the pipeline run was delayed.
+    pub(crate) fn on_canonical_tip(&mut self, tip: BlockNumber) {
+        let Some(request) = self.pending_pipeline_target else { return };
+        if let BackfillReason::Distance { target, .. } = request.reason {
+            if !self.backfill_trigger.should_backfill(true, tip, target) {
+                debug!(
+                    target: "consensus::engine::sync",
+                    tip,
+                    target,
+                    "Canonical tip caught up with the pending pipeline target, dropping it"
+                );
+                self.pending_pipeline_target = None;
+            }
+        }
+    }
+
+    /// Polls the backfill hooks for the given request.
+    ///
+    /// Returns [`Poll::Pending`] if any hook delays the pipeline run.
+    fn poll_backfill_hooks(
+        &mut self,
+        cx: &mut Context<'_>,
+        request: &BackfillRequest,
+    ) -> Poll<BackfillAction> {
+        for hook in &mut self.backfill_hooks {
+            match hook.poll_backfill(cx, request) {
+                Poll::Ready(BackfillAction::Proceed) => {}
+                Poll::Ready(BackfillAction::Veto) => {
+                    debug!(target: "consensus::engine::sync", hook = hook.name(), ?request, "Backfill vetoed");
+                    return Poll::Ready(BackfillAction::Veto)
+                }
+                Poll::Pending => {
+                    trace!(target: "consensus::engine::sync", hook = hook.name(), ?request, "Backfill delayed");
+                    return Poll::Pending
+                }
+            }
+        }
+        Poll::Ready(BackfillAction::Proceed)
     }
 
     /// Check if the engine reached max block as specified by `max_block` parameter.
@@ -250,6 +318,18 @@ where
                 ready!(fut.poll_unpin(cx))
             }
         };
+        if let Some(BackfillRequest { target, reason }) = self.active_backfill.take() {
+            let block_number = res
+                .as_ref()
+                .ok()
+                .and_then(|(_, result)| result.as_ref().ok())
+                .and_then(|ctrl| ctrl.block_number());
+            self.event_sender.notify(BeaconConsensusEngineEvent::BackfillSyncFinished {
+                target,
+                reason,
+                block_number,
+            });
+        }
         let ev = match res {
             Ok((pipeline, result)) => {
                 let minimum_block_number = pipeline.minimum_block_number();
@@ -268,42 +348,60 @@ where
 
     /// This will spawn the pipeline if it is idle and a target is set or if the pipeline is set to
     /// run continuously.
-    fn try_spawn_pipeline(&mut self) -> Option<EngineSyncEvent> {
-        match &mut self.pipeline_state {
-            PipelineState::Idle(pipeline) => {
-                let target = self.pending_pipeline_target.take();
-
-                if target.is_none() && !self.run_pipeline_continuously {
-                    // nothing to sync
-                    return None
-                }
-
-                let (tx, rx) = oneshot::channel();
-
-                let pipeline = pipeline.take().expect("exists");
-                self.pipeline_task_spawner.spawn_critical_blocking(
-                    "pipeline task",
-                    Box::pin(async move {
-                        let result = pipeline.run_as_fut(target).await;
-                        let _ = tx.send(result);
-                    }),
-                );
-                self.pipeline_state = PipelineState::Running(rx);
+    ///
+    /// The pipeline is only spawned if none of the [`BackfillHook`]s delays or vetoes it.
+    fn try_spawn_pipeline(&mut self, cx: &mut Context<'_>) -> Option<EngineSyncEvent> {
+        if !self.pipeline_state.is_idle() {
+            return None
+        }
 
-                // we also clear any pending full block requests because we expect them to be
-                // outdated (included in the range the pipeline is syncing anyway)
-                self.clear_block_download_requests();
+        let request = match self.pending_pipeline_target {
+            Some(request) => request,
+            None if self.run_pipeline_continuously => {
+                BackfillRequest { target: None, reason: BackfillReason::Continuous }
+            }
+            // nothing to sync
+            None => return None,
+        };
 
-                Some(EngineSyncEvent::PipelineStarted(target))
+        match self.poll_backfill_hooks(cx, &request) {
+            Poll::Ready(BackfillAction::Proceed) => {}
+            Poll::Ready(BackfillAction::Veto) => {
+                self.pending_pipeline_target = None;
+                return None
             }
-            PipelineState::Running(_) => None,
+            // keep the target until the hooks are ready
+            Poll::Pending => return None,
         }
+        self.pending_pipeline_target = None;
+
+        let PipelineState::Idle(pipeline) = &mut self.pipeline_state else { return None };
+        let pipeline = pipeline.take().expect("exists");
+        let BackfillRequest { target, reason } = request;
+        let (tx, rx) = oneshot::channel();
+        self.pipeline_task_spawner.spawn_critical_blocking(
+            "pipeline task",
+            Box::pin(async move {
+                let result = pipeline.run_as_fut(target).await;
+                let _ = tx.send(result);
+            }),
+        );
+        self.pipeline_state = PipelineState::Running(rx);
+        self.active_backfill = Some(request);
+        self.event_sender
+            .notify(BeaconConsensusEngineEvent::BackfillSyncStarted { target, reason });
+
+        // we also clear any pending full block requests because we expect them to be
+        // outdated (included in the range the pipeline is syncing anyway)
+        self.clear_block_download_requests();
+
+        Some(EngineSyncEvent::PipelineStarted(target))
     }
 
     /// Advances the sync process.
     pub(crate) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<EngineSyncEvent> {
         // try to spawn a pipeline if a target is set
-        if let Some(event) = self.try_spawn_pipeline() {
+        if let Some(event) = self.try_spawn_pipeline(cx) {
             return Poll::Ready(event)
         }
 
@@ -426,8 +524,9 @@ impl<DB: Database> PipelineState<DB> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::MIN_BLOCKS_FOR_PIPELINE_RUN;
     use assert_matches::assert_matches;
-    use futures::poll;
+    use futures::{poll, StreamExt};
     use reth_db::{mdbx::DatabaseEnv, test_utils::TempDatabase};
     use reth_network_p2p::{either::Either, test_utils::TestFullBlockClient};
     use reth_primitives::{
@@ -441,7 +540,7 @@ mod tests {
     use reth_stages::{test_utils::TestStages, ExecOutput, StageError};
     use reth_static_file::StaticFileProducer;
     use reth_tasks::TokioTaskExecutor;
-    use std::{collections::VecDeque, future::poll_fn, ops::Range};
+    use std::{collections::VecDeque, future::poll_fn, ops::Range, sync::Mutex};
     use tokio::sync::watch;
 
     struct TestPipelineBuilder {
@@ -509,12 +608,34 @@ mod tests {
     struct TestSyncControllerBuilder<Client> {
         max_block: Option<BlockNumber>,
         client: Option<Client>,
+        event_sender: EventSender<BeaconConsensusEngineEvent>,
+        backfill_trigger_distance: u64,
     }
 
     impl<Client> TestSyncControllerBuilder<Client> {
         /// Create a new [`TestSyncControllerBuilder`].
-        const fn new() -> Self {
-            Self { max_block: None, client: None }
+        fn new() -> Self {
+            Self {
+                max_block: None,
+                client: None,
+                event_sender: Default::default(),
+                backfill_trigger_distance: MIN_BLOCKS_FOR_PIPELINE_RUN,
+            }
+        }
+
+        /// Sets the sender for the engine events.
+        fn with_event_sender(
+            mut self,
+            event_sender: EventSender<BeaconConsensusEngineEvent>,
+        ) -> Self {
+            self.event_sender = event_sender;
+            self
+        }
+
+        /// Sets the backfill trigger distance.
+        const fn with_backfill_trigger_distance(mut self, distance: u64) -> Self {
+            self.backfill_trigger_distance = distance;
+            self
         }
 
         /// Sets the max block for the pipeline to run.
@@ -553,7 +674,8 @@ mod tests {
                 false,
                 self.max_block,
                 chain_spec,
-                Default::default(),
+                self.event_sender,
+                self.backfill_trigger_distance,
             )
         }
     }
@@ -583,7 +705,7 @@ mod tests {
             .build(pipeline, chain_spec);
 
         let tip = client.highest_block().expect("there should be blocks here");
-        sync_controller.set_pipeline_sync_target(tip.hash().into());
+        sync_controller.set_pipeline_sync_target(tip.hash().into(), BackfillReason::Startup);
 
         let sync_future = poll_fn(|cx| sync_controller.poll(cx));
         let next_event = poll!(sync_future);
@@ -604,6 +726,101 @@ mod tests {
         });
     }
 
+    /// A backfill hook that returns the configured action.
+    struct TestBackfillHook {
+        action: Arc<Mutex<Poll<BackfillAction>>>,
+    }
+
+    impl BackfillHook for TestBackfillHook {
+        fn name(&self) -> &'static str {
+            "Test"
+        }
+
+        fn poll_backfill(
+            &mut self,
+            _cx: &mut Context<'_>,
+            _request: &BackfillRequest,
+        ) -> Poll<BackfillAction> {
+            *self.action.lock().unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn pipeline_delayed_by_backfill_hook() {
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(MAINNET.genesis.clone())
+                .paris_activated()
+                .build(),
+        );
+
+        let client = TestFullBlockClient::default();
+        insert_headers_into_client(&client, SealedHeader::default(), 0..10);
+        // force the pipeline to be "done" after 5 blocks
+        let pipeline = TestPipelineBuilder::new()
+            .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                checkpoint: StageCheckpoint::new(5),
+                done: true,
+            })]))
+            .build(chain_spec.clone());
+
+        let event_sender = EventSender::default();
+        let mut events = event_sender.new_listener();
+        let mut sync_controller = TestSyncControllerBuilder::new()
+            .with_client(client.clone())
+            .with_event_sender(event_sender)
+            .with_backfill_trigger_distance(4)
+            .build(pipeline, chain_spec);
+        let action = Arc::new(Mutex::new(Poll::Pending));
+        sync_controller.add_backfill_hook(Box::new(TestBackfillHook { action: action.clone() }));
+
+        let tip = client.highest_block().expect("there should be blocks here");
+        let reason = BackfillReason::Distance { local_tip: 0, target: tip.number };
+        sync_controller.set_pipeline_sync_target(tip.hash().into(), reason);
+
+        // the hook delays the pipeline
+        assert!(poll!(poll_fn(|cx| sync_controller.poll(cx))).is_pending());
+        assert!(sync_controller.is_pipeline_sync_pending());
+
+        // the gap is still larger than half the trigger distance
+        sync_controller.on_canonical_tip(tip.number - 3);
+        assert!(sync_controller.is_pipeline_sync_pending());
+
+        // the canonical tip caught up while the pipeline was delayed
+        sync_controller.on_canonical_tip(tip.number - 2);
+        assert!(!sync_controller.is_pipeline_sync_pending());
+
+        // a vetoed target is dropped
+        sync_controller.set_pipeline_sync_target(tip.hash().into(), reason);
+        *action.lock().unwrap() = Poll::Ready(BackfillAction::Veto);
+        assert!(poll!(poll_fn(|cx| sync_controller.poll(cx))).is_pending());
+        assert!(!sync_controller.is_pipeline_sync_pending());
+
+        sync_controller.set_pipeline_sync_target(tip.hash().into(), reason);
+        *action.lock().unwrap() = Poll::Ready(BackfillAction::Proceed);
+        assert_matches!(
+            poll!(poll_fn(|cx| sync_controller.poll(cx))),
+            Poll::Ready(EngineSyncEvent::PipelineStarted(Some(_)))
+        );
+        assert_matches!(
+            events.next().await,
+            Some(BeaconConsensusEngineEvent::BackfillSyncStarted { target: Some(target), reason: started_reason }) => {
+                assert_eq!(target.sync_target(), Some(tip.hash()));
+                assert_eq!(started_reason, reason);
+            }
+        );
+
+        assert_matches!(
+            poll_fn(|cx| sync_controller.poll(cx)).await,
+            EngineSyncEvent::PipelineFinished { .. }
+        );
+        assert_matches!(
+            events.next().await,
+            Some(BeaconConsensusEngineEvent::BackfillSyncFinished { block_number: Some(5), .. })
+        );
+    }
+
     fn insert_headers_into_client(
         client: &TestFullBlockClient,
         genesis_header: SealedHeader,
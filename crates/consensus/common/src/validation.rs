@@ -2,10 +2,8 @@
 
 use reth_consensus::ConsensusError;
 use reth_primitives::{
-    constants::{
-        eip4844::{DATA_GAS_PER_BLOB, MAX_DATA_GAS_PER_BLOCK},
-        MAXIMUM_EXTRA_DATA_SIZE,
-    },
+    constants::{eip4844::DATA_GAS_PER_BLOB, MAXIMUM_EXTRA_DATA_SIZE},
+    eip4844::BlobParams,
     ChainSpec, GotExpected, Hardfork, Header, SealedBlock, SealedHeader,
 };
 
@@ -42,7 +40,10 @@ pub fn validate_header_standalone(
 
     // Ensures that EIP-4844 fields are valid once cancun is active.
     if chain_spec.is_cancun_active_at_timestamp(header.timestamp) {
-        validate_4844_header_standalone(header)?;
+        validate_4844_header_standalone(
+            header,
+            chain_spec.blob_params_at_timestamp(header.timestamp),
+        )?;
     } else if header.blob_gas_used.is_some() {
         return Err(ConsensusError::BlobGasUsedUnexpected)
     } else if header.excess_blob_gas.is_some() {
@@ -134,10 +135,14 @@ pub fn validate_block_pre_execution(
 ///  * `blob_gas_used` exists as a header field
 ///  * `excess_blob_gas` exists as a header field
 ///  * `parent_beacon_block_root` exists as a header field
-///  * `blob_gas_used` is less than or equal to `MAX_DATA_GAS_PER_BLOCK`
+///  * `blob_gas_used` is less than or equal to the max blob gas per block of the [`BlobParams`]
+///    active at the timestamp of the header
 ///  * `blob_gas_used` is a multiple of `DATA_GAS_PER_BLOB`
 ///  * `excess_blob_gas` is a multiple of `DATA_GAS_PER_BLOB`
-pub fn validate_4844_header_standalone(header: &SealedHeader) -> Result<(), ConsensusError> {
+pub fn validate_4844_header_standalone(
+    header: &SealedHeader,
+    blob_params: BlobParams,
+) -> Result<(), ConsensusError> {
     let blob_gas_used = header.blob_gas_used.ok_or(ConsensusError::BlobGasUsedMissing)?;
     let excess_blob_gas = header.excess_blob_gas.ok_or(ConsensusError::ExcessBlobGasMissing)?;
 
@@ -145,10 +150,10 @@ pub fn validate_4844_header_standalone(header: &SealedHeader) -> Result<(), Cons
         return Err(ConsensusError::ParentBeaconBlockRootMissing)
     }
 
-    if blob_gas_used > MAX_DATA_GAS_PER_BLOCK {
+    if blob_gas_used > blob_params.max_blob_gas_per_block {
         return Err(ConsensusError::BlobGasUsedExceedsMaxBlobGasPerBlock {
             blob_gas_used,
-            max_blob_gas_per_block: MAX_DATA_GAS_PER_BLOCK,
+            max_blob_gas_per_block: blob_params.max_blob_gas_per_block,
         })
     }
 
@@ -160,7 +165,7 @@ pub fn validate_4844_header_standalone(header: &SealedHeader) -> Result<(), Cons
     }
 
     // `excess_blob_gas` must also be a multiple of `DATA_GAS_PER_BLOB`. This will be checked later
-    // (via `calculate_excess_blob_gas`), but it doesn't hurt to catch the problem sooner.
+    // (via `next_block_excess_blob_gas`), but it doesn't hurt to catch the problem sooner.
     if excess_blob_gas % DATA_GAS_PER_BLOB != 0 {
         return Err(ConsensusError::ExcessBlobGasNotMultipleOfBlobGasPerBlob {
             excess_blob_gas,
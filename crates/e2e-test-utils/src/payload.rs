@@ -59,7 +59,7 @@ impl<E: EngineTypes + 'static> PayloadTestContext<E> {
     /// Expects the next event to be a built payload event or panics
     pub async fn expect_built_payload(&mut self) -> eyre::Result<E::BuiltPayload> {
         let second_event = self.payload_event_stream.next().await.unwrap()?;
-        if let reth::payload::Events::BuiltPayload(payload) = second_event {
+        if let reth::payload::Events::BuiltPayload { payload, .. } = second_event {
             Ok(payload)
         } else {
             panic!("Expect a built payload event.");
@@ -3,9 +3,10 @@ use alloy_consensus::{
 };
 use alloy_network::{eip2718::Encodable2718, EthereumSigner, TransactionBuilder};
 use alloy_rpc_types::{TransactionInput, TransactionRequest};
+use alloy_signer::Signer;
 use alloy_signer_wallet::LocalWallet;
 use eyre::Ok;
-use reth_primitives::{hex, Address, Bytes, U256};
+use reth_primitives::{hex, Address, Bytes, TxKind, U256};
 
 use reth_primitives::{constants::eip4844::MAINNET_KZG_TRUSTED_SETUP, B256};
 
@@ -24,6 +25,35 @@ impl TransactionTestContext {
         signed.encoded_2718().into()
     }
 
+    /// Creates a deployment of a contract with the given init code and signs it, returning the
+    /// address of the contract and the bytes of the transaction
+    pub async fn deploy_tx_bytes(
+        chain_id: u64,
+        wallet: LocalWallet,
+        nonce: u64,
+        init_code: Bytes,
+    ) -> (Address, Bytes) {
+        let contract = wallet.address().create(nonce);
+        let mut tx = tx(chain_id, Some(init_code), nonce);
+        tx.to = Some(TxKind::Create);
+        let signed = Self::sign_tx(wallet, tx).await;
+        (contract, signed.encoded_2718().into())
+    }
+
+    /// Creates a call to the given contract and signs it, returning bytes
+    pub async fn call_tx_bytes(
+        chain_id: u64,
+        wallet: LocalWallet,
+        nonce: u64,
+        to: Address,
+        input: Bytes,
+    ) -> Bytes {
+        let mut tx = tx(chain_id, Some(input), nonce);
+        tx.to = Some(TxKind::Call(to));
+        let signed = Self::sign_tx(wallet, tx).await;
+        signed.encoded_2718().into()
+    }
+
     /// Creates a tx with blob sidecar and sign it
     pub async fn tx_with_blobs(chain_id: u64, wallet: LocalWallet) -> eyre::Result<TxEnvelope> {
         let mut tx = tx(chain_id, None, 0);
@@ -45,6 +75,12 @@ impl TransactionTestContext {
         tx.build(&signer).await.unwrap()
     }
 
+    /// Signs an arbitrary TransactionRequest using the provided wallet, returning bytes
+    pub async fn sign_tx_bytes(wallet: LocalWallet, tx: TransactionRequest) -> Bytes {
+        let signed = Self::sign_tx(wallet, tx).await;
+        signed.encoded_2718().into()
+    }
+
     /// Creates a tx with blob sidecar and sign it, returning bytes
     pub async fn tx_with_blobs_bytes(chain_id: u64, wallet: LocalWallet) -> eyre::Result<Bytes> {
         let signed = Self::tx_with_blobs(chain_id, wallet).await?;
@@ -17,7 +17,9 @@ pub use error::{RethError, RethResult};
 
 pub use reth_blockchain_tree_api::error::{BlockchainTreeError, CanonicalError};
 pub use reth_consensus::ConsensusError;
-pub use reth_execution_errors::{BlockExecutionError, BlockValidationError};
+pub use reth_execution_errors::{
+    BlockExecutionError, BlockValidationError, TransactionReplayError,
+};
 pub use reth_storage_errors::{
     db::DatabaseError,
     provider::{ProviderError, ProviderResult},
@@ -3,8 +3,9 @@
 use alloy_rlp::Encodable;
 use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
 use reth_primitives::{
-    constants::EIP1559_INITIAL_BASE_FEE, revm::config::revm_spec_by_timestamp_after_merge, Address,
-    BlobTransactionSidecar, ChainSpec, Hardfork, Header, SealedBlock, Withdrawals, B256, U256,
+    calculate_next_block_gas_limit, constants::EIP1559_INITIAL_BASE_FEE,
+    revm::config::revm_spec_by_timestamp_after_merge, Address, BlobTransactionSidecar, ChainSpec,
+    Hardfork, Header, SealedBlock, Withdrawals, B256, U256,
 };
 use reth_rpc_types::engine::{
     ExecutionPayloadEnvelopeV2, ExecutionPayloadEnvelopeV3, ExecutionPayloadEnvelopeV4,
@@ -14,7 +15,8 @@ use reth_rpc_types_compat::engine::payload::{
     block_to_payload_v1, block_to_payload_v3, block_to_payload_v4,
     convert_block_to_payload_field_v2,
 };
-use revm_primitives::{BlobExcessGasAndPrice, BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, SpecId};
+use revm_primitives::{BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, SpecId};
+use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
 
 /// Contains the built payload.
@@ -22,7 +24,7 @@ use std::convert::Infallible;
 /// According to the [engine API specification](https://github.com/ethereum/execution-apis/blob/main/src/engine/README.md) the execution layer should build the initial version of the payload with an empty transaction set and then keep update it in order to maximize the revenue.
 /// Therefore, the empty-block here is always available and full-block will be set/updated
 /// afterward.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EthBuiltPayload {
     /// Identifier of the payload
     pub(crate) id: PayloadId,
@@ -148,7 +150,7 @@ impl From<EthBuiltPayload> for ExecutionPayloadEnvelopeV4 {
 }
 
 /// Container type for all components required to build a payload.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EthPayloadBuilderAttributes {
     /// Id of the payload
     pub id: PayloadId,
@@ -166,6 +168,10 @@ pub struct EthPayloadBuilderAttributes {
     pub withdrawals: Withdrawals,
     /// Root of the parent beacon block
     pub parent_beacon_block_root: Option<B256>,
+    /// The gas limit the generated payload should move towards, instead of keeping the gas limit
+    /// of the parent block
+    #[serde(default)]
+    pub gas_limit: Option<u64>,
 }
 
 // === impl EthPayloadBuilderAttributes ===
@@ -190,8 +196,19 @@ impl EthPayloadBuilderAttributes {
             prev_randao: attributes.prev_randao,
             withdrawals: attributes.withdrawals.unwrap_or_default().into(),
             parent_beacon_block_root: attributes.parent_beacon_block_root,
+            gas_limit: None,
         }
     }
+
+    /// Sets the gas limit the generated payload should move towards.
+    ///
+    /// The gas limit of the payload can only move from the gas limit of the parent by less than
+    /// `parent_gas_limit / 1024`, so reaching the target can take several blocks. This doesn't
+    /// change the payload id.
+    pub const fn with_gas_limit(mut self, gas_limit: Option<u64>) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
 }
 
 impl PayloadBuilderAttributes for EthPayloadBuilderAttributes {
@@ -233,6 +250,10 @@ impl PayloadBuilderAttributes for EthPayloadBuilderAttributes {
         &self.withdrawals
     }
 
+    fn gas_limit(&self) -> Option<u64> {
+        self.gas_limit
+    }
+
     fn cfg_and_block_env(
         &self,
         chain_spec: &ChainSpec,
@@ -246,8 +267,9 @@ impl PayloadBuilderAttributes for EthPayloadBuilderAttributes {
 
         // if the parent block did not have excess blob gas (i.e. it was pre-cancun), but it is
         // cancun now, we need to set the excess blob gas to the default value
+        let blob_params = chain_spec.blob_params_at_timestamp(self.timestamp());
         let blob_excess_gas_and_price = parent
-            .next_block_excess_blob_gas()
+            .next_block_excess_blob_gas(blob_params)
             .or_else(|| {
                 if spec_id == SpecId::CANCUN {
                     // default excess blob gas is zero
@@ -256,7 +278,7 @@ impl PayloadBuilderAttributes for EthPayloadBuilderAttributes {
                     None
                 }
             })
-            .map(BlobExcessGasAndPrice::new);
+            .map(|excess_blob_gas| blob_params.blob_excess_gas_and_price(excess_blob_gas));
 
         let mut basefee =
             parent.next_block_base_fee(chain_spec.base_fee_params_at_timestamp(self.timestamp()));
@@ -276,6 +298,14 @@ impl PayloadBuilderAttributes for EthPayloadBuilderAttributes {
             basefee = Some(EIP1559_INITIAL_BASE_FEE)
         }
 
+        // move towards the requested gas limit, as far as the consensus rules allow
+        if let Some(desired_gas_limit) = self.gas_limit {
+            gas_limit = U256::from(calculate_next_block_gas_limit(
+                gas_limit.saturating_to(),
+                desired_gas_limit,
+            ));
+        }
+
         let block_env = BlockEnv {
             number: U256::from(parent.number + 1),
             coinbase: self.suggested_fee_recipient(),
@@ -320,7 +350,50 @@ pub(crate) fn payload_id(parent: &B256, attributes: &PayloadAttributes) -> Paylo
 #[cfg(test)]
 mod tests {
     use super::*;
-    use reth_primitives::Genesis;
+    use reth_primitives::{Genesis, MAINNET};
+
+    #[test]
+    fn gas_limit_override_is_bounded_by_parent() {
+        let parent = Header {
+            number: 20_000_000,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(EIP1559_INITIAL_BASE_FEE),
+            ..Default::default()
+        };
+        let max_change = parent.gas_limit / 1024;
+        let attributes = EthPayloadBuilderAttributes::new(
+            B256::ZERO,
+            PayloadAttributes {
+                timestamp: 1_700_000_000,
+                prev_randao: B256::ZERO,
+                suggested_fee_recipient: Address::ZERO,
+                withdrawals: Some(Vec::new()),
+                parent_beacon_block_root: None,
+            },
+        );
+
+        // without an override the gas limit of the parent is kept
+        let (_, block_env) = attributes.cfg_and_block_env(&MAINNET, &parent);
+        assert_eq!(block_env.gas_limit, U256::from(parent.gas_limit));
+
+        // the gas limit moves towards a higher target by less than 1/1024 of the parent's
+        let raised = attributes.clone().with_gas_limit(Some(60_000_000));
+        let (_, block_env) = raised.cfg_and_block_env(&MAINNET, &parent);
+        assert_eq!(block_env.gas_limit, U256::from(parent.gas_limit + max_change - 1));
+
+        // the gas limit moves towards a lower target by less than 1/1024 of the parent's
+        let lowered = attributes.clone().with_gas_limit(Some(15_000_000));
+        let (_, block_env) = lowered.cfg_and_block_env(&MAINNET, &parent);
+        assert_eq!(block_env.gas_limit, U256::from(parent.gas_limit - max_change + 1));
+
+        // a target within the bound is reached right away
+        let close = attributes.clone().with_gas_limit(Some(parent.gas_limit + 100));
+        let (_, block_env) = close.cfg_and_block_env(&MAINNET, &parent);
+        assert_eq!(block_env.gas_limit, U256::from(parent.gas_limit + 100));
+
+        // the override doesn't change the payload id
+        assert_eq!(raised.payload_id(), attributes.payload_id());
+    }
 
     #[test]
     fn ensure_first_london_block_base_fee_is_set() {
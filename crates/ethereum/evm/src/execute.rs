@@ -8,22 +8,23 @@ use reth_ethereum_consensus::validate_block_post_execution;
 use reth_evm::{
     execute::{
         BatchBlockExecutionOutput, BatchExecutor, BlockExecutionError, BlockExecutionInput,
-        BlockExecutionOutput, BlockExecutorProvider, BlockValidationError, Executor, ProviderError,
+        BlockExecutionOutput, BlockExecutorProvider, BlockInspectorFactory, BlockValidationError,
+        Executor, FailedTransaction, NoopBlockInspectorFactory, ProviderError,
     },
+    parallel::{ParallelExecutionConfig, ParallelExecutor},
+    receipt::{EthReceiptBuilder, ReceiptBuilder, ReceiptBuilderCtx},
+    system_calls::SystemCaller,
     ConfigureEvm,
 };
 use reth_primitives::{
-    BlockNumber, BlockWithSenders, ChainSpec, Hardfork, Header, Receipt, Request, Withdrawals,
-    MAINNET, U256,
+    BlockNumHash, BlockNumber, BlockWithSenders, ChainSpec, Hardfork, Header, Receipt, Request,
+    Withdrawals, MAINNET, U256,
 };
 use reth_prune_types::PruneModes;
 use reth_revm::{
     batch::{BlockBatchRecord, BlockExecutorStats},
     db::states::bundle_state::BundleRetention,
-    state_change::{
-        apply_beacon_root_contract_call, apply_blockhashes_update,
-        apply_withdrawal_requests_contract_call, post_block_balance_increments,
-    },
+    state_change::post_block_balance_increments,
     Evm, State,
 };
 use revm_primitives::{
@@ -34,9 +35,16 @@ use std::sync::Arc;
 
 /// Provides executors to execute regular ethereum blocks
 #[derive(Debug, Clone)]
-pub struct EthExecutorProvider<EvmConfig = EthEvmConfig> {
+pub struct EthExecutorProvider<
+    EvmConfig = EthEvmConfig,
+    F = NoopBlockInspectorFactory,
+    R = EthReceiptBuilder,
+> {
     chain_spec: Arc<ChainSpec>,
     evm_config: EvmConfig,
+    inspector_factory: F,
+    receipt_builder: R,
+    parallel_execution: Option<ParallelExecutionConfig>,
 }
 
 impl EthExecutorProvider {
@@ -54,15 +62,66 @@ impl EthExecutorProvider {
 impl<EvmConfig> EthExecutorProvider<EvmConfig> {
     /// Creates a new executor provider.
     pub fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig) -> Self {
-        Self { chain_spec, evm_config }
+        Self {
+            chain_spec,
+            evm_config,
+            inspector_factory: NoopBlockInspectorFactory,
+            receipt_builder: EthReceiptBuilder,
+            parallel_execution: None,
+        }
+    }
+}
+
+impl<EvmConfig, F, R> EthExecutorProvider<EvmConfig, F, R> {
+    /// Configures the factory of the inspectors that are attached to the EVM for every executed
+    /// block, e.g. to collect custom statistics during the historical sync.
+    pub fn with_inspector_factory<I>(
+        self,
+        inspector_factory: I,
+    ) -> EthExecutorProvider<EvmConfig, I, R>
+    where
+        I: BlockInspectorFactory,
+    {
+        EthExecutorProvider {
+            chain_spec: self.chain_spec,
+            evm_config: self.evm_config,
+            inspector_factory,
+            receipt_builder: self.receipt_builder,
+            parallel_execution: self.parallel_execution,
+        }
+    }
+
+    /// Configures how the receipts of the executed transactions are built.
+    pub fn with_receipt_builder<B>(self, receipt_builder: B) -> EthExecutorProvider<EvmConfig, F, B>
+    where
+        B: ReceiptBuilder,
+    {
+        EthExecutorProvider {
+            chain_spec: self.chain_spec,
+            evm_config: self.evm_config,
+            inspector_factory: self.inspector_factory,
+            receipt_builder,
+            parallel_execution: self.parallel_execution,
+        }
+    }
+
+    /// Enables the optimistic parallel execution of the transactions of a block with the given
+    /// configuration.
+    ///
+    /// Blocks are still executed sequentially if an inspector is attached.
+    pub const fn with_parallel_execution(mut self, config: ParallelExecutionConfig) -> Self {
+        self.parallel_execution = Some(config);
+        self
     }
 }
 
-impl<EvmConfig> EthExecutorProvider<EvmConfig>
+impl<EvmConfig, F, R> EthExecutorProvider<EvmConfig, F, R>
 where
     EvmConfig: ConfigureEvm,
+    F: BlockInspectorFactory,
+    R: ReceiptBuilder,
 {
-    fn eth_executor<DB>(&self, db: DB) -> EthBlockExecutor<EvmConfig, DB>
+    fn eth_executor<DB>(&self, db: DB) -> EthBlockExecutor<EvmConfig, DB, F, R>
     where
         DB: Database<Error = ProviderError>,
     {
@@ -71,16 +130,21 @@ where
             self.evm_config.clone(),
             State::builder().with_database(db).with_bundle_update().without_state_clear().build(),
         )
+        .with_inspector_factory(self.inspector_factory.clone())
+        .with_receipt_builder(self.receipt_builder.clone())
+        .with_parallel_execution(self.parallel_execution)
     }
 }
 
-impl<EvmConfig> BlockExecutorProvider for EthExecutorProvider<EvmConfig>
+impl<EvmConfig, F, R> BlockExecutorProvider for EthExecutorProvider<EvmConfig, F, R>
 where
     EvmConfig: ConfigureEvm,
+    F: BlockInspectorFactory,
+    R: ReceiptBuilder<Receipt = Receipt>,
 {
-    type Executor<DB: Database<Error = ProviderError>> = EthBlockExecutor<EvmConfig, DB>;
+    type Executor<DB: Database<Error = ProviderError>> = EthBlockExecutor<EvmConfig, DB, F, R>;
 
-    type BatchExecutor<DB: Database<Error = ProviderError>> = EthBatchExecutor<EvmConfig, DB>;
+    type BatchExecutor<DB: Database<Error = ProviderError>> = EthBatchExecutor<EvmConfig, DB, F, R>;
 
     fn executor<DB>(&self, db: DB) -> Self::Executor<DB>
     where
@@ -104,24 +168,31 @@ where
 
 /// Helper type for the output of executing a block.
 #[derive(Debug, Clone)]
-struct EthExecuteOutput {
-    receipts: Vec<Receipt>,
+struct EthExecuteOutput<T = Receipt> {
+    receipts: Vec<T>,
     requests: Vec<Request>,
     gas_used: u64,
 }
 
 /// Helper container type for EVM with chain spec.
 #[derive(Debug, Clone)]
-struct EthEvmExecutor<EvmConfig> {
+struct EthEvmExecutor<EvmConfig, F, R> {
     /// The chainspec
     chain_spec: Arc<ChainSpec>,
     /// How to create an EVM.
     evm_config: EvmConfig,
+    /// How to create the inspector attached to the EVM.
+    inspector_factory: F,
+    /// How to build the receipts of the transactions.
+    receipt_builder: R,
+    /// The configuration of the parallel execution, if enabled.
+    parallel_execution: Option<ParallelExecutionConfig>,
 }
 
-impl<EvmConfig> EthEvmExecutor<EvmConfig>
+impl<EvmConfig, F, R> EthEvmExecutor<EvmConfig, F, R>
 where
     EvmConfig: ConfigureEvm,
+    R: ReceiptBuilder,
 {
     /// Executes the transactions in the block and returns the receipts of the transactions in the
     /// block, the total gas used and the list of EIP-7685 [requests](Request).
@@ -129,6 +200,8 @@ where
     /// This applies the pre-execution and post-execution changes that require an [EVM](Evm), and
     /// executes the transactions.
     ///
+    /// The transactions are executed in parallel if a [`ParallelExecutionConfig`] is given.
+    ///
     /// # Note
     ///
     /// It does __not__ apply post-execution changes that do not require an [EVM](Evm), for that see
@@ -137,79 +210,90 @@ where
         &self,
         block: &BlockWithSenders,
         mut evm: Evm<'_, Ext, &mut State<DB>>,
-    ) -> Result<EthExecuteOutput, BlockExecutionError>
+        parallel_execution: Option<ParallelExecutionConfig>,
+    ) -> Result<EthExecuteOutput<R::Receipt>, BlockExecutionError>
     where
         DB: Database<Error = ProviderError>,
     {
         // apply pre execution changes
-        apply_beacon_root_contract_call(
-            &self.chain_spec,
-            block.timestamp,
-            block.number,
-            block.parent_beacon_block_root,
-            &mut evm,
-        )?;
-        apply_blockhashes_update(
-            evm.db_mut(),
-            &self.chain_spec,
-            block.timestamp,
-            block.number,
-            block.parent_hash,
-        )?;
+        let system_caller = SystemCaller::new(&self.chain_spec);
+        system_caller.apply_pre_execution_changes(&block.header, &mut evm)?;
 
         // execute transactions
         let mut cumulative_gas_used = 0;
         let mut receipts = Vec::with_capacity(block.body.len());
-        for (sender, transaction) in block.transactions_with_sender() {
-            // The sum of the transaction’s gas limit, Tg, and the gas utilized in this block prior,
-            // must be no greater than the block’s gasLimit.
-            let block_available_gas = block.header.gas_limit - cumulative_gas_used;
-            if transaction.gas_limit() > block_available_gas {
-                return Err(BlockValidationError::TransactionGasLimitMoreThanAvailableBlockGas {
-                    transaction_gas_limit: transaction.gas_limit(),
-                    block_available_gas,
-                }
-                .into())
+        if let Some(config) = parallel_execution {
+            let env =
+                EnvWithHandlerCfg::new_with_spec_id(evm.context.evm.env.clone(), evm.spec_id());
+            let results = ParallelExecutor::new(&self.evm_config, config).execute_transactions(
+                evm.db_mut(),
+                &env,
+                block,
+            )?;
+            for (tx, result) in block.body.iter().zip(results) {
+                cumulative_gas_used += result.gas_used();
+                receipts.push(self.receipt_builder.build_receipt(ReceiptBuilderCtx {
+                    tx,
+                    result,
+                    cumulative_gas_used,
+                }));
             }
+        } else {
+            for (index, (sender, transaction)) in block.transactions_with_sender().enumerate() {
+                let failed_transaction = || {
+                    Box::new(FailedTransaction {
+                        block: BlockNumHash::new(block.number, block.header.hash_slow()),
+                        index,
+                        // Ensure hash is calculated for error log, if not already done
+                        hash: transaction.recalculate_hash(),
+                        cumulative_gas_used,
+                    })
+                };
+
+                // The sum of the transaction’s gas limit, Tg, and the gas utilized in this block
+                // prior, must be no greater than the block’s gasLimit.
+                let block_available_gas = block.header.gas_limit - cumulative_gas_used;
+                if transaction.gas_limit() > block_available_gas {
+                    return Err(BlockValidationError::TransactionGasLimitMoreThanAvailableBlockGas {
+                        transaction: failed_transaction(),
+                        transaction_gas_limit: transaction.gas_limit(),
+                        block_available_gas,
+                    }
+                    .into())
+                }
 
-            EvmConfig::fill_tx_env(evm.tx_mut(), transaction, *sender);
+                EvmConfig::fill_tx_env(evm.tx_mut(), transaction, *sender);
 
-            // Execute transaction.
-            let ResultAndState { result, state } = evm.transact().map_err(move |err| {
-                // Ensure hash is calculated for error log, if not already done
-                BlockValidationError::EVM {
-                    hash: transaction.recalculate_hash(),
-                    error: err.into(),
-                }
-            })?;
-            evm.db_mut().commit(state);
-
-            // append gas used
-            cumulative_gas_used += result.gas_used();
-
-            // Push transaction changeset and calculate header bloom filter for receipt.
-            receipts.push(
-                #[allow(clippy::needless_update)] // side-effect of optimism fields
-                Receipt {
-                    tx_type: transaction.tx_type(),
-                    // Success flag was added in `EIP-658: Embedding transaction status code in
-                    // receipts`.
-                    success: result.is_success(),
+                // Execute transaction.
+                let ResultAndState { result, state } =
+                    evm.transact().map_err(move |err| BlockValidationError::EVM {
+                        transaction: failed_transaction(),
+                        error: err.into(),
+                    })?;
+                evm.db_mut().commit(state);
+
+                // append gas used
+                cumulative_gas_used += result.gas_used();
+
+                // Push transaction changeset and calculate header bloom filter for receipt.
+                receipts.push(self.receipt_builder.build_receipt(ReceiptBuilderCtx {
+                    tx: transaction,
+                    result,
                     cumulative_gas_used,
-                    // convert to reth log
-                    logs: result.into_logs(),
-                    ..Default::default()
-                },
-            );
+                }));
+            }
         }
 
         let requests = if self.chain_spec.is_prague_active_at_timestamp(block.timestamp) {
             // Collect all EIP-6110 deposits
-            let deposit_requests =
-                crate::eip6110::parse_deposits_from_receipts(&self.chain_spec, &receipts)?;
+            let deposit_requests = crate::eip6110::parse_deposits_from_receipts(
+                &self.chain_spec,
+                receipts.iter().map(AsRef::as_ref),
+            )?;
 
             // Collect all EIP-7685 requests
-            let withdrawal_requests = apply_withdrawal_requests_contract_call(&mut evm)?;
+            let withdrawal_requests =
+                system_caller.apply_post_execution_changes(&block.header, &mut evm)?;
 
             [deposit_requests, withdrawal_requests].concat()
         } else {
@@ -226,9 +310,9 @@ where
 /// - Create a new instance of the executor.
 /// - Execute the block.
 #[derive(Debug)]
-pub struct EthBlockExecutor<EvmConfig, DB> {
+pub struct EthBlockExecutor<EvmConfig, DB, F = NoopBlockInspectorFactory, R = EthReceiptBuilder> {
     /// Chain specific evm config that's used to execute a block.
-    executor: EthEvmExecutor<EvmConfig>,
+    executor: EthEvmExecutor<EvmConfig, F, R>,
     /// The state to use for execution
     state: State<DB>,
 }
@@ -236,7 +320,73 @@ pub struct EthBlockExecutor<EvmConfig, DB> {
 impl<EvmConfig, DB> EthBlockExecutor<EvmConfig, DB> {
     /// Creates a new Ethereum block executor.
     pub fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig, state: State<DB>) -> Self {
-        Self { executor: EthEvmExecutor { chain_spec, evm_config }, state }
+        Self {
+            executor: EthEvmExecutor {
+                chain_spec,
+                evm_config,
+                inspector_factory: NoopBlockInspectorFactory,
+                receipt_builder: EthReceiptBuilder,
+                parallel_execution: None,
+            },
+            state,
+        }
+    }
+}
+
+impl<EvmConfig, DB, F, R> EthBlockExecutor<EvmConfig, DB, F, R> {
+    /// Configures the factory of the inspectors that are attached to the EVM for every executed
+    /// block.
+    pub fn with_inspector_factory<I>(
+        self,
+        inspector_factory: I,
+    ) -> EthBlockExecutor<EvmConfig, DB, I, R>
+    where
+        I: BlockInspectorFactory,
+    {
+        let EthEvmExecutor { chain_spec, evm_config, receipt_builder, parallel_execution, .. } =
+            self.executor;
+        EthBlockExecutor {
+            executor: EthEvmExecutor {
+                chain_spec,
+                evm_config,
+                inspector_factory,
+                receipt_builder,
+                parallel_execution,
+            },
+            state: self.state,
+        }
+    }
+
+    /// Configures how the receipts of the executed transactions are built.
+    pub fn with_receipt_builder<B>(
+        self,
+        receipt_builder: B,
+    ) -> EthBlockExecutor<EvmConfig, DB, F, B>
+    where
+        B: ReceiptBuilder,
+    {
+        let EthEvmExecutor {
+            chain_spec, evm_config, inspector_factory, parallel_execution, ..
+        } = self.executor;
+        EthBlockExecutor {
+            executor: EthEvmExecutor {
+                chain_spec,
+                evm_config,
+                inspector_factory,
+                receipt_builder,
+                parallel_execution,
+            },
+            state: self.state,
+        }
+    }
+
+    /// Configures the parallel execution of the transactions, or disables it if `None`.
+    pub const fn with_parallel_execution(
+        mut self,
+        config: Option<ParallelExecutionConfig>,
+    ) -> Self {
+        self.executor.parallel_execution = config;
+        self
     }
 
     #[inline]
@@ -251,10 +401,12 @@ impl<EvmConfig, DB> EthBlockExecutor<EvmConfig, DB> {
     }
 }
 
-impl<EvmConfig, DB> EthBlockExecutor<EvmConfig, DB>
+impl<EvmConfig, DB, F, R> EthBlockExecutor<EvmConfig, DB, F, R>
 where
     EvmConfig: ConfigureEvm,
     DB: Database<Error = ProviderError>,
+    F: BlockInspectorFactory,
+    R: ReceiptBuilder,
 {
     /// Configures a new evm configuration and block environment for the given block.
     ///
@@ -285,15 +437,30 @@ where
         &mut self,
         block: &BlockWithSenders,
         total_difficulty: U256,
-    ) -> Result<EthExecuteOutput, BlockExecutionError> {
+    ) -> Result<EthExecuteOutput<R::Receipt>, BlockExecutionError> {
         // 1. prepare state on new block
         self.on_new_block(&block.header);
 
         // 2. configure the evm and execute
         let env = self.evm_env_for_block(&block.header, total_difficulty);
-        let output = {
-            let evm = self.executor.evm_config.evm_with_env(&mut self.state, env);
-            self.executor.execute_state_transitions(block, evm)
+        let output = match self.executor.inspector_factory.inspector::<&mut State<DB>>(&env.block) {
+            Some(inspector) => {
+                let evm = self.executor.evm_config.evm_with_env_and_inspector(
+                    &mut self.state,
+                    env,
+                    inspector,
+                );
+                // The inspector has to observe the sequential execution
+                self.executor.execute_state_transitions(block, evm, None)
+            }
+            None => {
+                let evm = self.executor.evm_config.evm_with_env(&mut self.state, env);
+                self.executor.execute_state_transitions(
+                    block,
+                    evm,
+                    self.executor.parallel_execution,
+                )
+            }
         }?;
 
         // 3. apply post execution changes
@@ -349,13 +516,15 @@ where
     }
 }
 
-impl<EvmConfig, DB> Executor<DB> for EthBlockExecutor<EvmConfig, DB>
+impl<EvmConfig, DB, F, R> Executor<DB> for EthBlockExecutor<EvmConfig, DB, F, R>
 where
     EvmConfig: ConfigureEvm,
     DB: Database<Error = ProviderError>,
+    F: BlockInspectorFactory,
+    R: ReceiptBuilder,
 {
     type Input<'a> = BlockExecutionInput<'a, BlockWithSenders>;
-    type Output = BlockExecutionOutput<Receipt>;
+    type Output = BlockExecutionOutput<R::Receipt>;
     type Error = BlockExecutionError;
 
     /// Executes the block and commits the state changes.
@@ -381,17 +550,17 @@ where
 ///
 /// State changes are tracked until the executor is finalized.
 #[derive(Debug)]
-pub struct EthBatchExecutor<EvmConfig, DB> {
+pub struct EthBatchExecutor<EvmConfig, DB, F = NoopBlockInspectorFactory, R = EthReceiptBuilder> {
     /// The executor used to execute single blocks
     ///
     /// All state changes are committed to the [State].
-    executor: EthBlockExecutor<EvmConfig, DB>,
+    executor: EthBlockExecutor<EvmConfig, DB, F, R>,
     /// Keeps track of the batch and records receipts based on the configured prune mode
     batch_record: BlockBatchRecord,
     stats: BlockExecutorStats,
 }
 
-impl<EvmConfig, DB> EthBatchExecutor<EvmConfig, DB> {
+impl<EvmConfig, DB, F, R> EthBatchExecutor<EvmConfig, DB, F, R> {
     /// Returns mutable reference to the state that wraps the underlying database.
     #[allow(unused)]
     fn state_mut(&mut self) -> &mut State<DB> {
@@ -399,10 +568,12 @@ impl<EvmConfig, DB> EthBatchExecutor<EvmConfig, DB> {
     }
 }
 
-impl<EvmConfig, DB> BatchExecutor<DB> for EthBatchExecutor<EvmConfig, DB>
+impl<EvmConfig, DB, F, R> BatchExecutor<DB> for EthBatchExecutor<EvmConfig, DB, F, R>
 where
     EvmConfig: ConfigureEvm,
     DB: Database<Error = ProviderError>,
+    F: BlockInspectorFactory,
+    R: ReceiptBuilder<Receipt = Receipt>,
 {
     type Input<'a> = BlockExecutionInput<'a, BlockWithSenders>;
     type Output = BatchBlockExecutionOutput;
@@ -462,17 +633,27 @@ mod tests {
     };
     use reth_primitives::{
         constants::{EMPTY_ROOT_HASH, ETH_TO_WEI},
-        keccak256, public_key_to_address, Account, Block, ChainSpecBuilder, ForkCondition,
-        Transaction, TxKind, TxLegacy, B256,
+        hex, keccak256,
+        proofs::calculate_receipt_root_no_memo,
+        public_key_to_address, Account, Address, Block, ChainSpecBuilder, ForkCondition,
+        Transaction, TransactionSigned, TxKind, TxLegacy, B256,
     };
     use reth_revm::{
-        database::StateProviderDatabase, state_change::HISTORY_SERVE_WINDOW,
-        test_utils::StateProviderTest, TransitionState,
+        database::StateProviderDatabase,
+        interpreter::{opcode, Interpreter},
+        state_change::HISTORY_SERVE_WINDOW,
+        test_utils::StateProviderTest,
+        EvmContext, Inspector, TransitionState,
     };
     use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
     use revm_primitives::{b256, fixed_bytes, Bytes};
     use secp256k1::{Keypair, Secp256k1};
-    use std::collections::HashMap;
+    use serde::Deserialize;
+    use std::{
+        collections::{hash_map::Entry, HashMap},
+        path::Path,
+        sync::Mutex,
+    };
 
     fn create_state_provider_with_beacon_root_contract() -> StateProviderTest {
         let mut db = StateProviderTest::default();
@@ -513,7 +694,7 @@ mod tests {
     }
 
     fn executor_provider(chain_spec: Arc<ChainSpec>) -> EthExecutorProvider<EthEvmConfig> {
-        EthExecutorProvider { chain_spec, evm_config: Default::default() }
+        EthExecutorProvider::new(chain_spec, Default::default())
     }
 
     #[test]
@@ -1314,4 +1495,381 @@ mod tests {
         assert_eq!(withdrawal_request.validator_public_key, validator_public_key);
         assert_eq!(withdrawal_request.amount, u64::from_be_bytes(withdrawal_amount.into()));
     }
+
+    /// Executes the block sequentially and in parallel and asserts that the outputs are equal.
+    fn assert_parallel_execution_matches(
+        chain_spec: Arc<ChainSpec>,
+        db: &StateProviderTest,
+        block: &BlockWithSenders,
+        total_difficulty: U256,
+    ) {
+        let provider = executor_provider(chain_spec);
+        let parallel_provider = provider.clone().with_parallel_execution(
+            ParallelExecutionConfig::default().with_workers(4).with_min_transactions(0),
+        );
+
+        let mut sequential = provider
+            .executor(StateProviderDatabase::new(db))
+            .execute((block, total_difficulty).into())
+            .unwrap();
+        let mut parallel = parallel_provider
+            .executor(StateProviderDatabase::new(db))
+            .execute((block, total_difficulty).into())
+            .unwrap();
+
+        assert_eq!(parallel.receipts.len(), sequential.receipts.len());
+        for (index, (parallel, sequential)) in
+            parallel.receipts.iter().zip(&sequential.receipts).enumerate()
+        {
+            assert_eq!(parallel, sequential, "receipt of transaction {index} differs");
+        }
+        assert_eq!(parallel.gas_used, sequential.gas_used);
+        // The accounts of a revert are not ordered
+        sequential.state.reverts.sort();
+        parallel.state.reverts.sort();
+        assert_eq!(parallel.state, sequential.state);
+    }
+
+    /// Funds a new account and returns its key pair.
+    fn funded_sender(db: &mut StateProviderTest) -> Keypair {
+        let key_pair = Keypair::new(&Secp256k1::new(), &mut generators::rng());
+        db.insert_account(
+            public_key_to_address(key_pair.public_key()),
+            Account { nonce: 0, balance: U256::from(ETH_TO_WEI), bytecode_hash: None },
+            None,
+            HashMap::new(),
+        );
+        key_pair
+    }
+
+    /// Creates a transaction paying a priority fee of 1 wei, so the coinbase balance changes
+    /// with every transaction.
+    fn call_tx(
+        chain_spec: &ChainSpec,
+        header: &Header,
+        key_pair: Keypair,
+        nonce: u64,
+        to: Address,
+    ) -> TransactionSigned {
+        sign_tx_with_key_pair(
+            key_pair,
+            Transaction::Legacy(TxLegacy {
+                chain_id: Some(chain_spec.chain.id()),
+                nonce,
+                gas_price: header.base_fee_per_gas.unwrap() as u128 + 1,
+                gas_limit: 100_000,
+                to: TxKind::Call(to),
+                value: U256::from(1),
+                input: Bytes::new(),
+            }),
+        )
+    }
+
+    fn test_block_setup() -> (Arc<ChainSpec>, Header) {
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build());
+
+        let mut header = chain_spec.genesis_header();
+        header.number = 1;
+        header.gas_limit = 30_000_000;
+        header.beneficiary = Address::with_last_byte(0xfe);
+
+        (chain_spec, header)
+    }
+
+    #[test]
+    fn parallel_execution_independent_transfers() {
+        let (chain_spec, header) = test_block_setup();
+        let mut db = StateProviderTest::default();
+
+        let body = (0..32u8)
+            .map(|i| {
+                let sender = funded_sender(&mut db);
+                call_tx(&chain_spec, &header, sender, 0, Address::with_last_byte(i))
+            })
+            .collect();
+
+        let block = Block { header, body, ommers: vec![], withdrawals: None, requests: None }
+            .with_recovered_senders()
+            .unwrap();
+        assert_parallel_execution_matches(chain_spec, &db, &block, U256::ZERO);
+    }
+
+    #[test]
+    fn parallel_execution_conflicting_transactions() {
+        let (chain_spec, header) = test_block_setup();
+        let mut db = StateProviderTest::default();
+
+        // SSTORE(0, SLOAD(0) + 1)
+        let counter = Address::with_last_byte(0xc0);
+        let counter_code = Bytes::from_static(&hex!("60005460010160005500"));
+        // SSTORE(0, BALANCE(COINBASE))
+        let coinbase_reader = Address::with_last_byte(0xc1);
+        let coinbase_reader_code = Bytes::from_static(&hex!("413160005500"));
+        for (address, code) in [(counter, counter_code), (coinbase_reader, coinbase_reader_code)] {
+            db.insert_account(
+                address,
+                Account { nonce: 1, balance: U256::ZERO, bytecode_hash: None },
+                Some(code),
+                HashMap::new(),
+            );
+        }
+
+        let shared_recipient = Address::with_last_byte(0xaa);
+        let nonce_chain_sender = funded_sender(&mut db);
+        let mut body = Vec::new();
+        for i in 0..8 {
+            // Transactions of the same sender depend on each other through the nonce
+            body.push(call_tx(&chain_spec, &header, nonce_chain_sender, i, shared_recipient));
+
+            let sender = funded_sender(&mut db);
+            let to = match i % 3 {
+                0 => shared_recipient,
+                1 => counter,
+                _ => coinbase_reader,
+            };
+            body.push(call_tx(&chain_spec, &header, sender, 0, to));
+
+            // Pays the transaction that sent to it
+            let sender = funded_sender(&mut db);
+            body.push(call_tx(
+                &chain_spec,
+                &header,
+                sender,
+                0,
+                public_key_to_address(nonce_chain_sender.public_key()),
+            ));
+        }
+
+        let block = Block { header, body, ommers: vec![], withdrawals: None, requests: None }
+            .with_recovered_senders()
+            .unwrap();
+        assert_parallel_execution_matches(chain_spec, &db, &block, U256::ZERO);
+    }
+
+    /// A block and the state it is executed on, as returned by `eth_getBlockByNumber` with full
+    /// transactions and `debug_traceBlockByNumber` with the `prestateTracer`.
+    #[derive(Debug, Deserialize)]
+    struct BlockFixture {
+        block: alloy_rpc_types::Block,
+        prestate: Vec<PrestateTrace>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct PrestateTrace {
+        result: HashMap<Address, PrestateAccount>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct PrestateAccount {
+        #[serde(default)]
+        balance: U256,
+        #[serde(default)]
+        nonce: u64,
+        code: Option<Bytes>,
+        #[serde(default)]
+        storage: HashMap<B256, U256>,
+    }
+
+    /// Loads a fixture from the `testdata` directory and returns the state before the block, the
+    /// block and its total difficulty.
+    fn load_block_fixture(name: &str) -> (StateProviderTest, BlockWithSenders, U256) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join(name);
+        let fixture: BlockFixture =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+
+        // The prestate of a transaction includes the changes of the transactions before it, so
+        // the first occurrence of an account or a slot holds its value before the block.
+        let mut accounts = HashMap::<Address, PrestateAccount>::new();
+        for PrestateTrace { result } in fixture.prestate {
+            for (address, account) in result {
+                match accounts.entry(address) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(account);
+                    }
+                    Entry::Occupied(mut entry) => {
+                        for (slot, value) in account.storage {
+                            entry.get_mut().storage.entry(slot).or_insert(value);
+                        }
+                    }
+                }
+            }
+        }
+        let mut db = StateProviderTest::default();
+        for (address, account) in accounts {
+            db.insert_account(
+                address,
+                Account { nonce: account.nonce, balance: account.balance, bytecode_hash: None },
+                account.code.filter(|code| !code.is_empty()),
+                account.storage,
+            );
+        }
+
+        let senders = match &fixture.block.transactions {
+            alloy_rpc_types::BlockTransactions::Full(transactions) => {
+                transactions.iter().map(|transaction| transaction.from).collect()
+            }
+            _ => Vec::new(),
+        };
+        let total_difficulty = fixture.block.header.total_difficulty.unwrap_or_default();
+        let block = Block::try_from(fixture.block).unwrap().with_senders_unchecked(senders);
+
+        (db, block, total_difficulty)
+    }
+
+    /// Executes the fixture blocks sequentially and in parallel and compares the outputs.
+    ///
+    /// Fixtures of mainnet blocks can be captured from an archive node with
+    /// `eth_getBlockByNumber(number, true)` and
+    /// `debug_traceBlockByNumber(number, {"tracer": "prestateTracer"})`.
+    ///
+    /// `contention.json` is a synthetic post-Shanghai mainnet block modelled after blocks built by
+    /// MEV builders: every transaction pays the coinbase, bundles transfer bribes to the coinbase
+    /// from a contract, transactions read the coinbase balance, swaps share the reserves of a
+    /// pool, a searcher sends a chain of transactions and the block ends with the builder paying
+    /// the proposer from the coinbase.
+    #[test]
+    fn parallel_execution_block_fixtures() {
+        for name in ["contention.json"] {
+            let (db, block, total_difficulty) = load_block_fixture(name);
+            assert_parallel_execution_matches(MAINNET.clone(), &db, &block, total_difficulty);
+        }
+    }
+
+    /// A receipt that additionally holds the gas used by the transaction alone.
+    #[derive(Debug, Clone)]
+    struct ReceiptWithGasUsed {
+        receipt: Receipt,
+        gas_used: u64,
+    }
+
+    impl AsRef<Receipt> for ReceiptWithGasUsed {
+        fn as_ref(&self) -> &Receipt {
+            &self.receipt
+        }
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    struct ReceiptWithGasUsedBuilder;
+
+    impl ReceiptBuilder for ReceiptWithGasUsedBuilder {
+        type Receipt = ReceiptWithGasUsed;
+
+        fn build_receipt(&self, ctx: ReceiptBuilderCtx<'_>) -> Self::Receipt {
+            let gas_used = ctx.result.gas_used();
+            ReceiptWithGasUsed { receipt: EthReceiptBuilder.build_receipt(ctx), gas_used }
+        }
+    }
+
+    #[test]
+    fn custom_receipt_builder() {
+        let (chain_spec, header) = test_block_setup();
+        let mut db = StateProviderTest::default();
+
+        let body = (0..2u8)
+            .map(|i| {
+                let sender = funded_sender(&mut db);
+                call_tx(&chain_spec, &header, sender, 0, Address::with_last_byte(i))
+            })
+            .collect();
+        let block = Block { header, body, ommers: vec![], withdrawals: None, requests: None }
+            .with_recovered_senders()
+            .unwrap();
+
+        let provider =
+            executor_provider(chain_spec).with_receipt_builder(ReceiptWithGasUsedBuilder);
+        let BlockExecutionOutput { receipts, gas_used, .. } = provider
+            .eth_executor(StateProviderDatabase::new(&db))
+            .execute((&block, U256::ZERO).into())
+            .unwrap();
+
+        assert_eq!(gas_used, 42_000);
+        assert_eq!(
+            receipts.iter().map(|receipt| receipt.gas_used).collect::<Vec<_>>(),
+            vec![21_000, 21_000]
+        );
+        assert_eq!(
+            receipts.iter().map(|receipt| receipt.receipt.cumulative_gas_used).collect::<Vec<_>>(),
+            vec![21_000, 42_000]
+        );
+
+        // The root is calculated from the consensus fields only
+        let consensus_receipts =
+            receipts.iter().map(|receipt| &receipt.receipt).collect::<Vec<_>>();
+        assert_eq!(
+            ReceiptWithGasUsedBuilder.receipts_root(&receipts),
+            calculate_receipt_root_no_memo(&consensus_receipts)
+        );
+    }
+
+    /// Counts the `SLOAD` instructions of the blocks with an odd number.
+    #[derive(Debug, Clone, Default)]
+    struct OddBlockSloadCounterFactory {
+        counts: Arc<Mutex<Vec<(U256, u64)>>>,
+    }
+
+    impl BlockInspectorFactory for OddBlockSloadCounterFactory {
+        type Inspector<DB: Database> = SloadCounter;
+
+        fn inspector<DB: Database>(&self, block_env: &BlockEnv) -> Option<Self::Inspector<DB>> {
+            if block_env.number % U256::from(2) == U256::ZERO {
+                return None
+            }
+            self.counts.lock().unwrap().push((block_env.number, 0));
+            Some(SloadCounter { counts: self.counts.clone() })
+        }
+    }
+
+    struct SloadCounter {
+        counts: Arc<Mutex<Vec<(U256, u64)>>>,
+    }
+
+    impl<DB: Database> Inspector<DB> for SloadCounter {
+        fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+            if interp.current_opcode() == opcode::SLOAD {
+                self.counts.lock().unwrap().last_mut().unwrap().1 += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn inspector_attached_per_block() {
+        let (chain_spec, header) = test_block_setup();
+        let mut db = StateProviderTest::default();
+
+        // SSTORE(0, SLOAD(0) + 1)
+        let counter = Address::with_last_byte(0xc0);
+        db.insert_account(
+            counter,
+            Account { nonce: 1, balance: U256::ZERO, bytecode_hash: None },
+            Some(Bytes::from_static(&hex!("60005460010160005500"))),
+            HashMap::new(),
+        );
+
+        let factory = OddBlockSloadCounterFactory::default();
+        // The inspector has to observe the transactions even if parallel execution is enabled
+        let provider = executor_provider(chain_spec.clone())
+            .with_parallel_execution(
+                ParallelExecutionConfig::default().with_workers(4).with_min_transactions(0),
+            )
+            .with_inspector_factory(factory.clone());
+
+        for (number, transactions) in [(1, 3), (2, 2), (3, 1)] {
+            let header = Header { number, ..header.clone() };
+            let body = (0..transactions)
+                .map(|_| {
+                    let sender = funded_sender(&mut db);
+                    call_tx(&chain_spec, &header, sender, 0, counter)
+                })
+                .collect();
+            let block = Block { header, body, ommers: vec![], withdrawals: None, requests: None }
+                .with_recovered_senders()
+                .unwrap();
+            provider
+                .executor(StateProviderDatabase::new(&db))
+                .execute((&block, U256::ZERO).into())
+                .unwrap();
+        }
+
+        assert_eq!(*factory.counts.lock().unwrap(), vec![(U256::from(1), 3), (U256::from(3), 1)]);
+    }
 }
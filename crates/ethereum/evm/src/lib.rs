@@ -8,15 +8,19 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
-use reth_evm::{ConfigureEvm, ConfigureEvmEnv};
+use reth_evm::{
+    precompile::{precompile_provider_handle_register, PrecompileProvider},
+    ConfigureEvm, ConfigureEvmEnv,
+};
 use reth_primitives::{
     revm::{config::revm_spec, env::fill_tx_env},
     revm_primitives::{AnalysisKind, CfgEnvWithHandlerCfg, TxEnv},
     Address, ChainSpec, Head, Header, TransactionSigned, U256,
 };
-use reth_revm::{Database, EvmBuilder};
+use reth_revm::{inspector_handle_register, Database, Evm, EvmBuilder, GetInspector};
 
 pub mod execute;
+pub mod simulate;
 
 /// Ethereum DAO hardfork state change data.
 pub mod dao_fork;
@@ -26,8 +30,21 @@ pub mod eip6110;
 
 /// Ethereum-related EVM configuration.
 #[derive(Debug, Clone, Copy, Default)]
-#[non_exhaustive]
-pub struct EthEvmConfig;
+pub struct EthEvmConfig {
+    /// The provider of the custom precompiles, if any.
+    precompiles: Option<&'static dyn PrecompileProvider>,
+}
+
+impl EthEvmConfig {
+    /// Configures the provider of the precompiles that are used instead of the standard ones.
+    ///
+    /// The provider is shared by all EVMs of the node for its whole lifetime, so it's taken by
+    /// static reference, e.g. of a `static` item, which keeps the config `Copy`.
+    pub const fn with_precompiles(mut self, precompiles: &'static dyn PrecompileProvider) -> Self {
+        self.precompiles = Some(precompiles);
+        self
+    }
+}
 
 impl ConfigureEvmEnv for EthEvmConfig {
     fn fill_tx_env(tx_env: &mut TxEnv, transaction: &TransactionSigned, sender: Address) {
@@ -61,18 +78,88 @@ impl ConfigureEvmEnv for EthEvmConfig {
 impl ConfigureEvm for EthEvmConfig {
     type DefaultExternalContext<'a> = ();
 
-    fn evm<'a, DB: Database + 'a>(
-        &self,
-        db: DB,
-    ) -> reth_revm::Evm<'a, Self::DefaultExternalContext<'a>, DB> {
-        EvmBuilder::default().with_db(db).build()
+    fn evm<'a, DB: Database + 'a>(&self, db: DB) -> Evm<'a, Self::DefaultExternalContext<'a>, DB> {
+        let builder = EvmBuilder::default().with_db(db);
+        match self.precompiles {
+            Some(precompiles) => builder
+                .append_handler_register_box(precompile_provider_handle_register(precompiles))
+                .build(),
+            None => builder.build(),
+        }
+    }
+
+    fn evm_with_inspector<'a, DB, I>(&self, db: DB, inspector: I) -> Evm<'a, I, DB>
+    where
+        DB: Database + 'a,
+        I: GetInspector<DB>,
+    {
+        let mut builder = EvmBuilder::default()
+            .with_db(db)
+            .with_external_context(inspector)
+            .append_handler_register(inspector_handle_register);
+        if let Some(precompiles) = self.precompiles {
+            builder = builder
+                .append_handler_register_box(precompile_provider_handle_register(precompiles));
+        }
+        builder.build()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use reth_primitives::revm_primitives::{BlockEnv, CfgEnv, SpecId};
+    use reth_primitives::{
+        address,
+        revm_primitives::{BlockEnv, CfgEnv, Env, EnvWithHandlerCfg, SpecId},
+        Bytes, TxKind,
+    };
+    use reth_revm::{
+        db::{CacheDB, EmptyDB},
+        precompile::{Precompile, PrecompileError, PrecompileResult, Precompiles},
+    };
+
+    /// The address of the identity precompile.
+    const IDENTITY: Address = address!("0000000000000000000000000000000000000004");
+
+    /// Replaces the identity precompile with one that charges a flat fee.
+    #[derive(Debug)]
+    struct ExpensiveIdentity;
+
+    impl PrecompileProvider for ExpensiveIdentity {
+        fn precompiles(&self, _spec_id: SpecId, precompiles: &mut Precompiles) {
+            precompiles.inner.insert(IDENTITY, Precompile::Standard(expensive_identity));
+        }
+    }
+
+    fn expensive_identity(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+        if gas_limit < 10_000 {
+            return Err(PrecompileError::OutOfGas)
+        }
+        Ok((10_000, input.clone()))
+    }
+
+    /// Calls the identity precompile with an empty input and returns the gas used.
+    fn identity_call_gas_used(evm_config: EthEvmConfig, spec_id: SpecId) -> u64 {
+        let mut env = EnvWithHandlerCfg::new_with_spec_id(Box::<Env>::default(), spec_id);
+        env.tx.transact_to = TxKind::Call(IDENTITY);
+        env.tx.gas_limit = 100_000;
+        let mut evm = evm_config.evm_with_env(CacheDB::new(EmptyDB::default()), env);
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        result.gas_used()
+    }
+
+    #[test]
+    fn precompile_gas_override() {
+        let evm_config = EthEvmConfig::default().with_precompiles(&ExpensiveIdentity);
+        for spec_id in [SpecId::BERLIN, SpecId::CANCUN] {
+            // the standard identity precompile costs 15 gas for an empty input
+            assert_eq!(
+                identity_call_gas_used(evm_config, spec_id),
+                identity_call_gas_used(EthEvmConfig::default(), spec_id) - 15 + 10_000
+            );
+        }
+    }
 
     #[test]
     #[ignore]
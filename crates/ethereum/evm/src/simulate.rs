@@ -0,0 +1,209 @@
+//! Block simulation on top of an overridden state.
+
+use crate::{execute::EthBlockExecutor, EthEvmConfig};
+use reth_evm::{
+    execute::{
+        BlockExecutionError, BlockExecutionInput, BlockExecutionOutput, Executor, ProviderError,
+    },
+    precompile::MovedPrecompilesEvmConfig,
+    ConfigureEvm,
+};
+use reth_primitives::{Address, BlockWithSenders, ChainSpec, Receipt};
+use reth_revm::{
+    db::CacheDB,
+    primitives::db::DatabaseRef,
+    state_override::{apply_state_overrides, StateOverride, StateOverrideError},
+    State,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// Executes blocks on top of an overridden state, e.g. with the code of an oracle replaced by a
+/// mock.
+///
+/// The overrides are the ones of `eth_call`: code, balance, nonce and storage of accounts, and
+/// precompiles moved to other addresses.
+///
+/// The simulator is deliberately not a
+/// [`BlockExecutorProvider`](reth_evm::execute::BlockExecutorProvider), so it can't be used to
+/// execute the blocks of the chain.
+#[derive(Debug, Clone)]
+pub struct EthBlockSimulator<EvmConfig = EthEvmConfig> {
+    /// The chain spec.
+    chain_spec: Arc<ChainSpec>,
+    /// How to create an EVM.
+    evm_config: EvmConfig,
+    /// The overrides applied to the state before the block is executed.
+    state_overrides: StateOverride,
+    /// The new address of the precompile at every moved address.
+    moved_precompiles: HashMap<Address, Address>,
+}
+
+impl<EvmConfig> EthBlockSimulator<EvmConfig> {
+    /// Creates a new simulator without any overrides.
+    pub fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig) -> Self {
+        Self {
+            chain_spec,
+            evm_config,
+            state_overrides: StateOverride::default(),
+            moved_precompiles: HashMap::new(),
+        }
+    }
+
+    /// Sets the overrides applied to the state before the block is executed.
+    pub fn with_state_overrides(mut self, state_overrides: StateOverride) -> Self {
+        self.state_overrides = state_overrides;
+        self
+    }
+
+    /// Moves the precompile at `from` to `to`, so the code of `from` can be overridden.
+    pub fn with_moved_precompile(mut self, from: Address, to: Address) -> Self {
+        self.moved_precompiles.insert(from, to);
+        self
+    }
+}
+
+impl<EvmConfig> EthBlockSimulator<EvmConfig>
+where
+    EvmConfig: ConfigureEvm,
+{
+    /// Executes the block on top of the overridden state of the given database.
+    ///
+    /// The overrides are part of the state the block is executed on, so the returned state only
+    /// contains the changes of the block.
+    pub fn simulate<DB>(
+        &self,
+        db: DB,
+        input: BlockExecutionInput<'_, BlockWithSenders>,
+    ) -> Result<BlockExecutionOutput<Receipt>, BlockExecutionError>
+    where
+        DB: DatabaseRef<Error = ProviderError>,
+    {
+        let mut db = CacheDB::new(db);
+        apply_state_overrides(self.state_overrides.clone(), &mut db).map_err(|err| match err {
+            StateOverrideError::Database(err) => err.into(),
+            err => BlockExecutionError::other(err),
+        })?;
+
+        let state =
+            State::builder().with_database(db).with_bundle_update().without_state_clear().build();
+        if self.moved_precompiles.is_empty() {
+            return EthBlockExecutor::new(self.chain_spec.clone(), self.evm_config.clone(), state)
+                .execute(input)
+        }
+        let evm_config =
+            MovedPrecompilesEvmConfig::new(self.evm_config.clone(), self.moved_precompiles.clone());
+        EthBlockExecutor::new(self.chain_spec.clone(), evm_config, state).execute(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        address, constants::ETH_TO_WEI, hex, public_key_to_address, Account, Block, Bytes,
+        ChainSpecBuilder, Transaction, TxKind, TxLegacy, MAINNET, U256,
+    };
+    use reth_revm::{
+        database::StateProviderDatabase, state_override::AccountOverride,
+        test_utils::StateProviderTest,
+    };
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use secp256k1::{Keypair, Secp256k1};
+    use std::collections::HashMap;
+
+    const ORACLE: Address = address!("00000000000000000000000000000000000000aa");
+    const IDENTITY_PRECOMPILE: Address = address!("0000000000000000000000000000000000000004");
+
+    /// `REVERT(0, 0)`
+    const REVERTING_CODE: [u8; 5] = hex!("60006000fd");
+    /// `LOG0(0, 0)`
+    const LOGGING_CODE: [u8; 6] = hex!("60006000a000");
+
+    #[test]
+    fn simulate_with_overridden_code() {
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build());
+        let mut db = StateProviderTest::default();
+
+        let key_pair = Keypair::new(&Secp256k1::new(), &mut generators::rng());
+        db.insert_account(
+            public_key_to_address(key_pair.public_key()),
+            Account { nonce: 0, balance: U256::from(ETH_TO_WEI), bytecode_hash: None },
+            None,
+            HashMap::new(),
+        );
+        db.insert_account(
+            ORACLE,
+            Account { nonce: 1, balance: U256::ZERO, bytecode_hash: None },
+            Some(Bytes::from_static(&REVERTING_CODE)),
+            HashMap::new(),
+        );
+
+        let mut header = chain_spec.genesis_header();
+        header.number = 1;
+        header.gas_limit = 30_000_000;
+        let body = [ORACLE, IDENTITY_PRECOMPILE]
+            .into_iter()
+            .enumerate()
+            .map(|(nonce, to)| {
+                sign_tx_with_key_pair(
+                    key_pair,
+                    Transaction::Legacy(TxLegacy {
+                        chain_id: Some(chain_spec.chain.id()),
+                        nonce: nonce as u64,
+                        gas_price: header.base_fee_per_gas.unwrap().into(),
+                        gas_limit: 100_000,
+                        to: TxKind::Call(to),
+                        value: U256::ZERO,
+                        input: Bytes::new(),
+                    }),
+                )
+            })
+            .collect();
+        let block = Block { header, body, ommers: vec![], withdrawals: None, requests: None }
+            .with_recovered_senders()
+            .unwrap();
+
+        let simulator = EthBlockSimulator::new(chain_spec, EthEvmConfig::default());
+        let simulate = |simulator: EthBlockSimulator<EthEvmConfig>| {
+            let output = simulator
+                .simulate(StateProviderDatabase::new(&db), (&block, U256::ZERO).into())
+                .unwrap();
+            assert_eq!(output.gas_used, output.receipts.last().unwrap().cumulative_gas_used);
+            assert!(
+                output.receipts[0].cumulative_gas_used < output.receipts[1].cumulative_gas_used
+            );
+            output
+        };
+        let overrides = [IDENTITY_PRECOMPILE, ORACLE]
+            .into_iter()
+            .map(|address| {
+                let code = Some(Bytes::from_static(&LOGGING_CODE));
+                (address, AccountOverride { code, ..Default::default() })
+            })
+            .collect::<StateOverride>();
+
+        // Without overrides the oracle reverts
+        let original = simulate(simulator.clone());
+        assert!(!original.receipts[0].success);
+        assert!(original.receipts.iter().all(|receipt| receipt.logs.is_empty()));
+
+        // The code of a precompile can't be overridden without moving the precompile
+        let overridden = simulate(simulator.clone().with_state_overrides(overrides.clone()));
+        assert!(overridden.receipts[0].success);
+        assert_eq!(overridden.receipts[0].logs.len(), 1);
+        assert_eq!(overridden.receipts[0].logs[0].address, ORACLE);
+        assert!(overridden.receipts[1].logs.is_empty());
+
+        let moved = simulate(
+            simulator
+                .with_state_overrides(overrides)
+                .with_moved_precompile(IDENTITY_PRECOMPILE, Address::with_last_byte(0xff)),
+        );
+        assert_eq!(moved.receipts[0], overridden.receipts[0]);
+        assert_eq!(moved.receipts[1].logs.len(), 1);
+        assert_eq!(moved.receipts[1].logs[0].address, IDENTITY_PRECOMPILE);
+
+        // The overridden code is charged like any other code
+        assert!(moved.gas_used > overridden.gas_used);
+    }
+}
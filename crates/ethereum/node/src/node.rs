@@ -1,7 +1,13 @@
 //! Ethereum Node types config.
 
 use crate::{EthEngineTypes, EthEvmConfig};
-use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
+use reth_basic_payload_builder::{
+    BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig, PayloadBuilder,
+};
+use reth_ethereum_payload_builder::{EthereumBuilderConfig, LocalTxPriority};
+#[cfg(feature = "execution-metrics")]
+use reth_evm::metrics::ExecutionMetricsInspectorFactory;
+use reth_evm::precompile::PrecompileProvider;
 use reth_evm_ethereum::execute::EthExecutorProvider;
 use reth_network::NetworkHandle;
 use reth_node_builder::{
@@ -11,12 +17,19 @@ use reth_node_builder::{
     node::{FullNodeTypes, NodeTypes},
     BuilderContext, Node, PayloadBuilderConfig,
 };
-use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
+use reth_payload_builder::{
+    DiskPayloadPersistence, EthBuiltPayload, EthPayloadBuilderAttributes, PayloadBuilderHandle,
+    PayloadBuilderService,
+};
 use reth_provider::CanonStateSubscriptions;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::{
-    blobstore::DiskFileBlobStore, EthTransactionPool, TransactionPool,
-    TransactionValidationTaskExecutor,
+    blobstore::DiskFileBlobStore,
+    journal::{
+        local_transactions_journal_task, LocalTransactionJournal, LocalTransactionJournalConfig,
+    },
+    CoinbaseTipOrdering, EthPooledTransaction, EthTransactionValidator, Pool, TransactionOrdering,
+    TransactionPool, TransactionValidationTaskExecutor,
 };
 
 /// Type configuration for a regular Ethereum node.
@@ -43,6 +56,25 @@ impl EthereumNode {
             .network(EthereumNetworkBuilder::default())
             .executor(EthereumExecutorBuilder::default())
     }
+
+    /// Returns a [`ComponentsBuilder`] configured for a regular Ethereum node that uses the given
+    /// precompiles for block execution, payload building and RPC calls.
+    pub fn components_with_precompiles<Node>(
+        precompiles: &'static dyn PrecompileProvider,
+    ) -> ComponentsBuilder<
+        Node,
+        EthereumPoolBuilder,
+        EthereumPayloadBuilder,
+        EthereumNetworkBuilder,
+        EthereumExecutorBuilder,
+    >
+    where
+        Node: FullNodeTypes<Engine = EthEngineTypes>,
+    {
+        Self::components()
+            .payload(EthereumPayloadBuilder::default().with_precompiles(precompiles))
+            .executor(EthereumExecutorBuilder::default().with_precompiles(precompiles))
+    }
 }
 
 impl NodeTypes for EthereumNode {
@@ -69,14 +101,30 @@ where
 
 /// A regular ethereum evm and executor builder.
 #[derive(Debug, Default, Clone, Copy)]
-#[non_exhaustive]
-pub struct EthereumExecutorBuilder;
+pub struct EthereumExecutorBuilder {
+    /// The EVM configuration used by the executor and the rest of the node.
+    evm_config: EthEvmConfig,
+}
+
+impl EthereumExecutorBuilder {
+    /// Configures the provider of the precompiles that are used instead of the standard ones.
+    ///
+    /// The payload builder must be configured with the same precompiles, see
+    /// [`EthereumNode::components_with_precompiles`].
+    pub fn with_precompiles(mut self, precompiles: &'static dyn PrecompileProvider) -> Self {
+        self.evm_config = self.evm_config.with_precompiles(precompiles);
+        self
+    }
+}
 
 impl<Node> ExecutorBuilder<Node> for EthereumExecutorBuilder
 where
     Node: FullNodeTypes,
 {
     type EVM = EthEvmConfig;
+    #[cfg(feature = "execution-metrics")]
+    type Executor = EthExecutorProvider<Self::EVM, ExecutionMetricsInspectorFactory>;
+    #[cfg(not(feature = "execution-metrics"))]
     type Executor = EthExecutorProvider<Self::EVM>;
 
     async fn build_evm(
@@ -84,8 +132,23 @@ where
         ctx: &BuilderContext<Node>,
     ) -> eyre::Result<(Self::EVM, Self::Executor)> {
         let chain_spec = ctx.chain_spec();
-        let evm_config = EthEvmConfig::default();
-        let executor = EthExecutorProvider::new(chain_spec, evm_config);
+        let evm_config = self.evm_config;
+        let mut executor = EthExecutorProvider::new(chain_spec, evm_config);
+        if let Some(config) = ctx.config().execution.parallel_execution_config() {
+            executor = executor.with_parallel_execution(config);
+        }
+
+        #[cfg(feature = "execution-metrics")]
+        let executor = executor.with_inspector_factory(ExecutionMetricsInspectorFactory::new(
+            ctx.config().debug.execution_metrics,
+        ));
+        #[cfg(not(feature = "execution-metrics"))]
+        if ctx.config().debug.execution_metrics {
+            reth_tracing::tracing::warn!(
+                target: "reth::cli",
+                "Execution metrics require the `execution-metrics` feature, ignoring"
+            );
+        }
 
         Ok((evm_config, executor))
     }
@@ -95,22 +158,55 @@ where
 ///
 /// This contains various settings that can be configured and take precedence over the node's
 /// config.
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Clone)]
 #[non_exhaustive]
-pub struct EthereumPoolBuilder {
-    // TODO add options for txpool args
+pub struct EthereumPoolBuilder<Ordering = CoinbaseTipOrdering<EthPooledTransaction>> {
+    /// The ordering of the transactions in the pool.
+    ordering: Ordering,
+}
+
+impl Default for EthereumPoolBuilder {
+    fn default() -> Self {
+        Self { ordering: Default::default() }
+    }
 }
 
-impl<Node> PoolBuilder<Node> for EthereumPoolBuilder
+impl<Ordering> EthereumPoolBuilder<Ordering> {
+    /// Configures the ordering of the transactions in the pool.
+    ///
+    /// The ordering determines the order of the pending transactions and therefore the order in
+    /// which the payload builder includes them, see [`TransactionOrdering`] for the invariants an
+    /// ordering must uphold.
+    ///
+    /// ```ignore
+    /// EthereumNode::components().map_pool(|builder| builder.with_ordering(MyOrdering::default()))
+    /// ```
+    pub fn with_ordering<O>(self, ordering: O) -> EthereumPoolBuilder<O>
+    where
+        O: TransactionOrdering<Transaction = EthPooledTransaction>,
+    {
+        EthereumPoolBuilder { ordering }
+    }
+}
+
+impl<Node, Ordering> PoolBuilder<Node> for EthereumPoolBuilder<Ordering>
 where
     Node: FullNodeTypes,
+    Ordering: TransactionOrdering<Transaction = EthPooledTransaction>,
 {
-    type Pool = EthTransactionPool<Node::Provider, DiskFileBlobStore>;
+    type Pool = Pool<
+        TransactionValidationTaskExecutor<
+            EthTransactionValidator<Node::Provider, EthPooledTransaction>,
+        >,
+        Ordering,
+        DiskFileBlobStore,
+    >;
 
     async fn build_pool(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::Pool> {
         let data_dir = ctx.config().datadir();
         let pool_config = ctx.pool_config();
-        let blob_store = DiskFileBlobStore::open(data_dir.blobstore(), Default::default())?;
+        let blob_store =
+            DiskFileBlobStore::open(data_dir.blobstore(), ctx.config().txpool.blob_store_config())?;
         let validator = TransactionValidationTaskExecutor::eth_builder(ctx.chain_spec())
             .with_head_timestamp(ctx.head().timestamp)
             .kzg_settings(ctx.kzg_settings()?)
@@ -122,29 +218,38 @@ where
                 blob_store.clone(),
             );
 
-        let transaction_pool =
-            reth_transaction_pool::Pool::eth_pool(validator, blob_store, pool_config);
+        let transaction_pool = Pool::new(validator, self.ordering, blob_store, pool_config);
         info!(target: "reth::cli", "Transaction pool initialized");
-        let transactions_path = data_dir.txpool_transactions();
 
         // spawn txpool maintenance task
         {
             let pool = transaction_pool.clone();
             let chain_events = ctx.provider().canonical_state_stream();
             let client = ctx.provider().clone();
-            let transactions_backup_config =
-                reth_transaction_pool::maintain::LocalTransactionBackupConfig::with_local_txs_backup(transactions_path);
-
-            ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
-                "local transactions backup task",
-                |shutdown| {
-                    reth_transaction_pool::maintain::backup_local_transactions_task(
-                        shutdown,
-                        pool.clone(),
-                        transactions_backup_config,
-                    )
-                },
-            );
+
+            if !ctx.config().txpool.no_locals_journal {
+                // the journaled transactions are reinserted before the pool is handed to the RPC
+                // server, the backup of previous versions is imported into the journal
+                let journal = LocalTransactionJournal::load(
+                    &pool,
+                    LocalTransactionJournalConfig::new(data_dir.txpool_journal())
+                        .with_legacy_backup(data_dir.txpool_transactions()),
+                )
+                .await?;
+                let journal_events = ctx.provider().canonical_state_stream();
+
+                ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                    "local transactions journal task",
+                    |shutdown| {
+                        local_transactions_journal_task(
+                            shutdown,
+                            pool.clone(),
+                            journal,
+                            journal_events,
+                        )
+                    },
+                );
+            }
 
             // spawn the maintenance task
             ctx.task_executor().spawn_critical(
@@ -165,39 +270,123 @@ where
 }
 
 /// A basic ethereum payload service.
-#[derive(Debug, Default, Clone)]
-#[non_exhaustive]
-pub struct EthereumPayloadBuilder;
+///
+/// The payloads can be raced against the payloads of an additional [`PayloadBuilder`], see
+/// [`EthereumPayloadBuilder::with_additional_payload_builder`].
+#[derive(Debug, Clone, Copy)]
+pub struct EthereumPayloadBuilder<
+    Additional = reth_ethereum_payload_builder::EthereumPayloadBuilder,
+> {
+    /// The EVM configuration used to build the payloads.
+    evm_config: EthEvmConfig,
+    /// The builder whose payloads race the payloads of the ethereum payload builder.
+    additional_builder: Option<Additional>,
+}
+
+impl Default for EthereumPayloadBuilder {
+    fn default() -> Self {
+        Self { evm_config: Default::default(), additional_builder: None }
+    }
+}
+
+impl<Additional> EthereumPayloadBuilder<Additional> {
+    /// Configures the provider of the precompiles that are used instead of the standard ones.
+    ///
+    /// The executor must be configured with the same precompiles, see
+    /// [`EthereumNode::components_with_precompiles`].
+    pub fn with_precompiles(mut self, precompiles: &'static dyn PrecompileProvider) -> Self {
+        self.evm_config = self.evm_config.with_precompiles(precompiles);
+        self
+    }
 
-impl<Node, Pool> PayloadServiceBuilder<Node, Pool> for EthereumPayloadBuilder
+    /// Builds every payload with the given builder as well and returns the payload with the
+    /// highest fees when the payload is requested.
+    ///
+    /// The jobs of the additional builder use the same settings as the jobs of the ethereum
+    /// payload builder, see also [`PayloadBuilderService::with_additional_generator`].
+    pub fn with_additional_payload_builder<B>(self, builder: B) -> EthereumPayloadBuilder<B> {
+        EthereumPayloadBuilder { evm_config: self.evm_config, additional_builder: Some(builder) }
+    }
+}
+
+impl<Node, Pool, Additional> PayloadServiceBuilder<Node, Pool>
+    for EthereumPayloadBuilder<Additional>
 where
     Node: FullNodeTypes<Engine = EthEngineTypes>,
     Pool: TransactionPool + Unpin + 'static,
+    Additional: PayloadBuilder<
+            Pool,
+            Node::Provider,
+            Attributes = EthPayloadBuilderAttributes,
+            BuiltPayload = EthBuiltPayload,
+        > + Unpin
+        + 'static,
 {
     async fn spawn_payload_service(
         self,
         ctx: &BuilderContext<Node>,
         pool: Pool,
     ) -> eyre::Result<PayloadBuilderHandle<Node::Engine>> {
-        let payload_builder = reth_ethereum_payload_builder::EthereumPayloadBuilder::default();
         let conf = ctx.payload_builder_config();
 
+        let mut builder_config = EthereumBuilderConfig::default()
+            .drop_reverting_txs(conf.drop_reverting_txs())
+            .gas_limit(Some(conf.max_gas_limit()));
+        if let Some(path) = conf.revert_allowlist() {
+            let allowlist =
+                EthereumBuilderConfig::parse_revert_allowlist(&reth_fs_util::read_to_string(path)?)
+                    .map_err(|err| {
+                        eyre::eyre!("invalid revert allowlist {}: {err}", path.display())
+                    })?;
+            info!(target: "reth::cli", senders = allowlist.len(), "Loaded revert allowlist");
+            builder_config = builder_config.revert_allowlist(allowlist);
+        }
+        if conf.local_tx_priority() {
+            builder_config = builder_config.local_tx_priority(match conf.local_tx_min_tip() {
+                Some(min_tip) => LocalTxPriority::AboveThreshold(min_tip),
+                None => LocalTxPriority::Always,
+            });
+        }
+
+        let payload_builder =
+            reth_ethereum_payload_builder::EthereumPayloadBuilder::new(self.evm_config)
+                .with_builder_config(builder_config);
+
         let payload_job_config = BasicPayloadJobGeneratorConfig::default()
             .interval(conf.interval())
             .deadline(conf.deadline())
             .max_payload_tasks(conf.max_payload_tasks())
+            .min_fee_improvement(conf.min_fee_improvement())
             .extradata(conf.extradata_bytes());
 
         let payload_generator = BasicPayloadJobGenerator::with_builder(
             ctx.provider().clone(),
-            pool,
+            pool.clone(),
             ctx.task_executor().clone(),
-            payload_job_config,
+            payload_job_config.clone(),
             ctx.chain_spec(),
             payload_builder,
-        );
-        let (payload_service, payload_builder) =
+        )
+        .with_payload_reads_cache(ctx.payload_reads_cache().clone());
+        let (mut payload_service, payload_builder) =
             PayloadBuilderService::new(payload_generator, ctx.provider().canonical_state_stream());
+        if let Some(additional_builder) = self.additional_builder {
+            let additional_generator = BasicPayloadJobGenerator::with_builder(
+                ctx.provider().clone(),
+                pool,
+                ctx.task_executor().clone(),
+                payload_job_config,
+                ctx.chain_spec(),
+                additional_builder,
+            )
+            .with_payload_reads_cache(ctx.payload_reads_cache().clone());
+            payload_service = payload_service.with_additional_generator(additional_generator);
+        }
+        if conf.persist_payloads() {
+            let persistence = DiskPayloadPersistence::new(ctx.config().datadir().payloads())
+                .with_max_payloads(conf.max_persisted_payloads());
+            payload_service = payload_service.with_persistence(persistence, conf.interval());
+        }
 
         ctx.task_executor().spawn_critical("payload builder service", Box::pin(payload_service));
 
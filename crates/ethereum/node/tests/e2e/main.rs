@@ -1,7 +1,11 @@
 mod blobs;
 mod dev;
 mod eth;
+mod memory_limit;
+mod ordering;
 mod p2p;
+mod precompiles;
+mod rpc;
 mod utils;
 
 const fn main() {}
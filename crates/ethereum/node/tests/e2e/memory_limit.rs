@@ -0,0 +1,113 @@
+use crate::utils::eth_payload_attributes;
+use reth::{
+    args::RpcServerArgs,
+    builder::{NodeBuilder, NodeConfig, NodeHandle},
+    rpc::types::{
+        DebugTrace, LimitedDefaultFrame, TraceCallOptions, TransactionInput, TransactionRequest,
+    },
+    tasks::TaskManager,
+};
+use reth_e2e_test_utils::{
+    node::NodeTestContext, transaction::TransactionTestContext, wallet::Wallet,
+};
+use reth_node_ethereum::EthereumNode;
+use reth_primitives::{hex, BlockNumberOrTag, Bytes, ChainSpecBuilder, Genesis, TxKind, MAINNET};
+use reth_rpc_server_types::constants::{DEFAULT_RPC_MEMORY_LIMIT, MIN_RPC_MEMORY_LIMIT};
+use std::sync::Arc;
+
+/// Creation code that expands the memory to 2 MiB: `MSTORE(0x200000, 1)`
+const MEMORY_HEAVY_CODE: [u8; 7] = hex!("6001622000005200");
+
+#[tokio::test]
+async fn rpc_calls_honor_memory_limit() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+
+    assert_memory_heavy_call(MIN_RPC_MEMORY_LIMIT, false).await?;
+    assert_memory_heavy_call(DEFAULT_RPC_MEMORY_LIMIT, true).await?;
+
+    Ok(())
+}
+
+/// Launches a node with the given RPC memory limit and asserts whether the memory heavy call
+/// succeeds with `eth_call`, `eth_estimateGas` and `debug_traceCall`.
+///
+/// The same code is then mined and traced with `debug_traceTransaction`, which must always
+/// succeed because replays of mined transactions keep the protocol default.
+async fn assert_memory_heavy_call(memory_limit: u64, succeeds: bool) -> eyre::Result<()> {
+    let exec = TaskManager::current();
+    let exec = exec.executor();
+
+    // Chain spec with test allocs
+    let genesis: Genesis = serde_json::from_str(include_str!("../assets/genesis.json")).unwrap();
+    let chain_spec = Arc::new(
+        ChainSpecBuilder::default()
+            .chain(MAINNET.chain)
+            .genesis(genesis)
+            .cancun_activated()
+            .build(),
+    );
+
+    // Node setup
+    let rpc_args = RpcServerArgs { rpc_memory_limit: memory_limit, ..Default::default() };
+    let node_config = NodeConfig::test()
+        .with_chain(chain_spec)
+        .with_rpc(rpc_args.with_unused_ports().with_http());
+
+    let NodeHandle { node, node_exit_future: _ } = NodeBuilder::new(node_config)
+        .testing_node(exec)
+        .node(EthereumNode::default())
+        .launch()
+        .await?;
+    let mut node = NodeTestContext::new(node).await?;
+
+    // expanding the memory to 2 MiB costs ~8.6M gas
+    let request = TransactionRequest {
+        to: Some(TxKind::Create),
+        input: TransactionInput::new(Bytes::from_static(&MEMORY_HEAVY_CODE)),
+        gas: Some(10_000_000),
+        ..Default::default()
+    };
+    let at = BlockNumberOrTag::Latest.into();
+
+    let eth_api = node.rpc.inner.eth_api();
+    let call = eth_api.call(request.clone(), Some(at), Default::default()).await;
+    assert_eq!(call.is_ok(), succeeds, "eth_call: {call:?}");
+    let estimate = eth_api.estimate_gas_at(request.clone(), at, Default::default()).await;
+    assert_eq!(estimate.is_ok(), succeeds, "eth_estimateGas: {estimate:?}");
+
+    let trace = node
+        .rpc
+        .inner
+        .debug_api()
+        .debug_trace_call(request, Some(at), TraceCallOptions::default())
+        .await?;
+    let DebugTrace::StructLogs(LimitedDefaultFrame { frame, .. }) = trace else {
+        panic!("unexpected trace: {trace:?}")
+    };
+    assert_eq!(frame.failed, !succeeds);
+
+    let wallet = Wallet::default();
+    let raw_tx = TransactionTestContext::sign_tx_bytes(
+        wallet.inner,
+        TransactionRequest {
+            nonce: Some(0),
+            chain_id: Some(1),
+            max_fee_per_gas: Some(20e9 as u128),
+            max_priority_fee_per_gas: Some(20e9 as u128),
+            ..request
+        },
+    )
+    .await;
+    let tx_hash = node.rpc.inject_tx(raw_tx).await?;
+    let (payload, _) = node.advance_block(vec![], eth_payload_attributes).await?;
+    node.assert_new_block(tx_hash, payload.block().hash(), payload.block().number).await?;
+
+    let trace =
+        node.rpc.inner.debug_api().debug_trace_transaction(tx_hash, Default::default()).await?;
+    let DebugTrace::StructLogs(LimitedDefaultFrame { frame, .. }) = trace else {
+        panic!("unexpected trace: {trace:?}")
+    };
+    assert!(!frame.failed);
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use crate::utils::eth_payload_attributes;
+use reth::{
+    args::RpcServerArgs,
+    builder::{NodeBuilder, NodeConfig, NodeHandle},
+    tasks::TaskManager,
+};
+use reth_e2e_test_utils::{
+    node::NodeTestContext, transaction::TransactionTestContext, wallet::Wallet,
+};
+use reth_node_ethereum::EthereumNode;
+use reth_primitives::{address, Address, ChainSpecBuilder, Genesis, MAINNET, U256};
+use reth_transaction_pool::{EthPooledTransaction, PoolTransaction, Priority, TransactionOrdering};
+use std::{collections::HashSet, sync::Arc};
+
+/// Address of the second genesis account of the test mnemonic.
+const PREFERRED_SENDER: Address = address!("70997970c51812dc3a010c7d01b50e0d17dc79c8");
+
+/// Orders transactions of the configured senders before all other transactions, and by coinbase
+/// tip otherwise.
+///
+/// The priority of all transactions of a sender has the same preference, so the ordering is
+/// monotonic along the sender's nonce chain.
+#[derive(Debug, Clone)]
+struct PreferredSendersOrdering {
+    preferred: Arc<HashSet<Address>>,
+}
+
+impl PreferredSendersOrdering {
+    fn new(preferred: impl IntoIterator<Item = Address>) -> Self {
+        Self { preferred: Arc::new(preferred.into_iter().collect()) }
+    }
+}
+
+impl TransactionOrdering for PreferredSendersOrdering {
+    type PriorityValue = (bool, U256);
+    type Transaction = EthPooledTransaction;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        let is_preferred = self.preferred.contains(&transaction.sender());
+        transaction
+            .effective_tip_per_gas(base_fee)
+            .map(|tip| (is_preferred, U256::from(tip)))
+            .into()
+    }
+}
+
+#[tokio::test]
+async fn can_run_eth_node_with_custom_ordering() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let exec = TaskManager::current();
+    let exec = exec.executor();
+
+    // Chain spec with test allocs
+    let genesis: Genesis = serde_json::from_str(include_str!("../assets/genesis.json")).unwrap();
+    let chain_spec = Arc::new(
+        ChainSpecBuilder::default()
+            .chain(MAINNET.chain)
+            .genesis(genesis)
+            .cancun_activated()
+            .build(),
+    );
+
+    // Node setup
+    let node_config = NodeConfig::test()
+        .with_chain(chain_spec)
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+    let NodeHandle { node, node_exit_future: _ } = NodeBuilder::new(node_config)
+        .testing_node(exec)
+        .with_types::<EthereumNode>()
+        .with_components(EthereumNode::components().map_pool(|builder| {
+            builder.with_ordering(PreferredSendersOrdering::new([PREFERRED_SENDER]))
+        }))
+        .launch()
+        .await?;
+    let mut node = NodeTestContext::new(node).await?;
+    let mut wallets = Wallet::new(2).gen();
+    let preferred = wallets.pop().unwrap();
+    let other = wallets.pop().unwrap();
+
+    // Both transactions pay the same tip, the transaction of the preferred sender is submitted last
+    let raw_tx = TransactionTestContext::transfer_tx_bytes(1, other).await;
+    let other_hash = node.rpc.inject_tx(raw_tx).await?;
+    let raw_tx = TransactionTestContext::transfer_tx_bytes(1, preferred).await;
+    let preferred_hash = node.rpc.inject_tx(raw_tx).await?;
+
+    let (payload, _) = node.advance_block(vec![], eth_payload_attributes).await?;
+    let block = payload.block();
+    let included = block.body.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+    assert_eq!(included, vec![preferred_hash, other_hash]);
+    assert_eq!(block.body[0].recover_signer(), Some(PREFERRED_SENDER));
+
+    node.assert_new_block(other_hash, block.hash(), block.number).await?;
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use crate::utils::eth_payload_attributes;
+use reth::{
+    args::RpcServerArgs,
+    builder::{NodeBuilder, NodeConfig, NodeHandle},
+    providers::{StateProvider, StateProviderFactory},
+    revm::precompile::{Precompile, PrecompileResult, Precompiles},
+    rpc::types::{DebugTrace, LimitedDefaultFrame, TraceCallOptions, TransactionRequest},
+    tasks::TaskManager,
+};
+use reth_e2e_test_utils::{
+    node::NodeTestContext, transaction::TransactionTestContext, wallet::Wallet,
+};
+use reth_evm::precompile::PrecompileProvider;
+use reth_node_ethereum::EthereumNode;
+use reth_primitives::{
+    address, hex, revm_primitives::SpecId, Address, BlockNumberOrTag, Bytes, ChainSpecBuilder,
+    Genesis, TxKind, B256, MAINNET, U256,
+};
+use std::sync::Arc;
+
+/// Address of the custom precompile.
+const ANSWER_PRECOMPILE: Address = address!("0000000000000000000000000000000000000999");
+
+/// Adds the precompile that always returns 42.
+#[derive(Debug, Clone, Copy)]
+struct AnswerPrecompile;
+
+impl PrecompileProvider for AnswerPrecompile {
+    fn precompiles(&self, _spec_id: SpecId, precompiles: &mut Precompiles) {
+        precompiles.inner.insert(ANSWER_PRECOMPILE, Precompile::Standard(answer));
+    }
+}
+
+fn answer(_input: &Bytes, _gas_limit: u64) -> PrecompileResult {
+    Ok((100, Bytes::copy_from_slice(B256::with_last_byte(42).as_slice())))
+}
+
+#[tokio::test]
+async fn can_run_eth_node_with_custom_precompiles() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let exec = TaskManager::current();
+    let exec = exec.executor();
+
+    // Chain spec with test allocs
+    let genesis: Genesis = serde_json::from_str(include_str!("../assets/genesis.json")).unwrap();
+    let chain_spec = Arc::new(
+        ChainSpecBuilder::default()
+            .chain(MAINNET.chain)
+            .genesis(genesis)
+            .cancun_activated()
+            .build(),
+    );
+
+    // Node setup
+    let node_config = NodeConfig::test()
+        .with_chain(chain_spec)
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+    let NodeHandle { node, node_exit_future: _ } = NodeBuilder::new(node_config)
+        .testing_node(exec)
+        .with_types::<EthereumNode>()
+        .with_components(EthereumNode::components_with_precompiles(&AnswerPrecompile))
+        .launch()
+        .await?;
+    let mut node = NodeTestContext::new(node).await?;
+    let wallet = Wallet::default();
+
+    // The contract calls the precompile, stores the result in slot 0 and returns it:
+    //
+    // STATICCALL(GAS, 0x0999, 0, 0, 0, 32)
+    // SSTORE(0, MLOAD(0))
+    // RETURN(0, 32)
+    let runtime_code = hex!("60206000600060006109995afa506000518060005560005260206000f3");
+    let init_code = [&hex!("601d600c600039601d6000f3")[..], &runtime_code[..]].concat();
+
+    let (contract, raw_tx) =
+        TransactionTestContext::deploy_tx_bytes(1, wallet.inner.clone(), 0, init_code.into()).await;
+    let tx_hash = node.rpc.inject_tx(raw_tx).await?;
+    let (payload, _) = node.advance_block(vec![], eth_payload_attributes).await?;
+    node.assert_new_block(tx_hash, payload.block().hash(), payload.block().number).await?;
+
+    // The payload is built and executed with the custom precompile
+    let raw_tx =
+        TransactionTestContext::call_tx_bytes(1, wallet.inner, 1, contract, Bytes::new()).await;
+    let tx_hash = node.rpc.inject_tx(raw_tx).await?;
+    let (payload, _) = node.advance_block(vec![], eth_payload_attributes).await?;
+    node.assert_new_block(tx_hash, payload.block().hash(), payload.block().number).await?;
+
+    let stored = node.inner.provider.latest()?.storage(contract, B256::ZERO)?;
+    assert_eq!(stored, Some(U256::from(42)));
+
+    // Tracing uses the custom precompile as well
+    let trace = node
+        .rpc
+        .inner
+        .debug_api()
+        .debug_trace_call(
+            TransactionRequest { to: Some(TxKind::Call(contract)), ..Default::default() },
+            Some(BlockNumberOrTag::Latest.into()),
+            TraceCallOptions::default(),
+        )
+        .await?;
+    let DebugTrace::StructLogs(LimitedDefaultFrame { frame, .. }) = trace else {
+        panic!("unexpected trace: {trace:?}")
+    };
+    assert!(!frame.failed);
+    assert_eq!(frame.return_value, Bytes::copy_from_slice(B256::with_last_byte(42).as_slice()));
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use crate::utils::eth_payload_attributes;
+use jsonrpsee::{
+    core::{client::ClientT, params::ArrayParams},
+    types::error::ErrorCode,
+};
+use reth_e2e_test_utils::{setup, transaction::TransactionTestContext, wallet::Wallet};
+use reth_node_ethereum::EthereumNode;
+use reth_primitives::{ChainSpecBuilder, Genesis, MAINNET};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// The expected response of a request.
+#[derive(Debug, Clone, Copy)]
+enum Response {
+    /// `null` result.
+    Null,
+    /// Any result other than `null`.
+    NonNull,
+    /// Invalid params error.
+    InvalidParams,
+}
+
+/// Requests for blocks that don't exist, out of range indices of existing blocks and malformed
+/// block ids, with the responses of geth.
+///
+/// Every getter is also requested for the existing block, so that a `null` response of the getter
+/// is known to come from the missing block or index.
+fn conformance_matrix(block_hash: Value) -> Vec<(&'static str, Vec<Value>, Response)> {
+    use Response::*;
+
+    let block = json!("0x1");
+    let future_block = json!("0x1b4");
+    let unknown_hash = json!("0xc6ef2fc5426d6ad6fd9e2a26abeab0aa2411b7ab17f30a99d3cb96aed1d1055b");
+    let out_of_range = json!("0xffff");
+    let address = json!("0x407d73d8a49eeb85d32cf465507dd71d507100c1");
+
+    vec![
+        ("eth_getBlockByNumber", vec![block.clone(), json!(false)], NonNull),
+        ("eth_getBlockByNumber", vec![future_block.clone(), json!(false)], Null),
+        ("eth_getBlockByHash", vec![block_hash.clone(), json!(true)], NonNull),
+        ("eth_getBlockByHash", vec![unknown_hash.clone(), json!(true)], Null),
+        ("eth_getHeaderByNumber", vec![block.clone()], NonNull),
+        ("eth_getHeaderByNumber", vec![future_block.clone()], Null),
+        ("eth_getHeaderByHash", vec![block_hash.clone()], NonNull),
+        ("eth_getHeaderByHash", vec![unknown_hash.clone()], Null),
+        ("eth_getBlockTransactionCountByNumber", vec![block.clone()], NonNull),
+        ("eth_getBlockTransactionCountByNumber", vec![future_block.clone()], Null),
+        ("eth_getBlockTransactionCountByHash", vec![block_hash.clone()], NonNull),
+        ("eth_getBlockTransactionCountByHash", vec![unknown_hash.clone()], Null),
+        ("eth_getUncleCountByBlockNumber", vec![block.clone()], NonNull),
+        ("eth_getUncleCountByBlockNumber", vec![future_block.clone()], Null),
+        ("eth_getUncleCountByBlockHash", vec![block_hash.clone()], NonNull),
+        ("eth_getUncleCountByBlockHash", vec![unknown_hash.clone()], Null),
+        ("eth_getBlockReceipts", vec![block.clone()], NonNull),
+        ("eth_getBlockReceipts", vec![future_block.clone()], Null),
+        ("eth_getBlockReceipts", vec![unknown_hash.clone()], Null),
+        ("eth_getUncleByBlockNumberAndIndex", vec![future_block.clone(), json!("0x0")], Null),
+        ("eth_getUncleByBlockNumberAndIndex", vec![block.clone(), json!("0x0")], Null),
+        ("eth_getUncleByBlockHashAndIndex", vec![block_hash.clone(), out_of_range.clone()], Null),
+        ("eth_getUncleByBlockHashAndIndex", vec![unknown_hash.clone(), json!("0x0")], Null),
+        ("eth_getTransactionByBlockNumberAndIndex", vec![block.clone(), json!("0x0")], NonNull),
+        ("eth_getTransactionByBlockNumberAndIndex", vec![future_block, json!("0x0")], Null),
+        ("eth_getTransactionByBlockNumberAndIndex", vec![block, out_of_range.clone()], Null),
+        ("eth_getTransactionByBlockHashAndIndex", vec![block_hash.clone(), json!("0x0")], NonNull),
+        ("eth_getTransactionByBlockHashAndIndex", vec![block_hash, out_of_range.clone()], Null),
+        ("eth_getTransactionByBlockHashAndIndex", vec![unknown_hash.clone(), out_of_range], Null),
+        ("eth_getTransactionByHash", vec![unknown_hash.clone()], Null),
+        ("eth_getTransactionReceipt", vec![unknown_hash], Null),
+        ("eth_getBlockByNumber", vec![json!("0xzz"), json!(false)], InvalidParams),
+        ("eth_getBlockByNumber", vec![json!("latests"), json!(false)], InvalidParams),
+        ("eth_getBlockByHash", vec![json!("0x1b4"), json!(false)], InvalidParams),
+        ("eth_getBlockTransactionCountByNumber", vec![json!("0x")], InvalidParams),
+        ("eth_getBlockReceipts", vec![json!("0xzz")], InvalidParams),
+        ("eth_getBalance", vec![address.clone(), json!("0xzz")], InvalidParams),
+        ("eth_getTransactionCount", vec![address, json!({"blockHash": "0x1b4"})], InvalidParams),
+    ]
+}
+
+#[tokio::test]
+async fn eth_missing_block_conformance() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+
+    let (mut nodes, _tasks, _wallet) = setup::<EthereumNode>(
+        1,
+        Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(serde_json::from_str::<Genesis>(include_str!("../assets/genesis.json"))?)
+                .cancun_activated()
+                .build(),
+        ),
+        false,
+    )
+    .await?;
+    let mut node = nodes.pop().unwrap();
+
+    // block 1 with a single transaction and no uncles
+    let wallet = Wallet::default();
+    let raw_tx = TransactionTestContext::transfer_tx_bytes(1, wallet.inner).await;
+    let tx_hash = node.rpc.inject_tx(raw_tx).await?;
+    let (payload, _) = node.advance_block(vec![], eth_payload_attributes).await?;
+    let block_hash = payload.block().hash();
+    node.assert_new_block(tx_hash, block_hash, payload.block().number).await?;
+
+    let client = node.inner.rpc_server_handle().http_client().unwrap();
+    for (method, params, expected) in conformance_matrix(json!(block_hash)) {
+        let mut array_params = ArrayParams::new();
+        for param in &params {
+            array_params.insert(param)?;
+        }
+        let resp = client.request::<Value, _>(method, array_params).await;
+        match (expected, resp) {
+            (Response::Null, Ok(Value::Null)) => {}
+            (Response::NonNull, Ok(value)) if !value.is_null() => {}
+            (Response::InvalidParams, Err(jsonrpsee::core::client::Error::Call(err)))
+                if err.code() == ErrorCode::InvalidParams.code() => {}
+            (expected, resp) => {
+                panic!("{method} {params:?}: expected {expected:?}, got {resp:?}")
+            }
+        }
+    }
+
+    Ok(())
+}
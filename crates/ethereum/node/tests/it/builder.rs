@@ -1,9 +1,13 @@
 //! Node builder setup tests.
 
 use reth_db::test_utils::create_test_rw_db;
+use reth_ethereum_payload_builder::EthereumPayloadBuilder as EthereumBlockBuilder;
 use reth_node_api::FullNodeComponents;
 use reth_node_builder::{NodeBuilder, NodeConfig};
-use reth_node_ethereum::node::EthereumNode;
+use reth_node_ethereum::{
+    node::{EthereumNode, EthereumPayloadBuilder},
+    EthEvmConfig,
+};
 
 #[test]
 fn test_basic_setup() {
@@ -34,6 +38,19 @@ fn test_basic_setup() {
         .check_launch();
 }
 
+#[test]
+fn test_additional_payload_builder_setup() {
+    let config = NodeConfig::test();
+    let db = create_test_rw_db();
+    let payload_builder = EthereumPayloadBuilder::default()
+        .with_additional_payload_builder(EthereumBlockBuilder::new(EthEvmConfig::default()));
+    let _builder = NodeBuilder::new(config)
+        .with_database(db)
+        .with_types::<EthereumNode>()
+        .with_components(EthereumNode::components().payload(payload_builder))
+        .check_launch();
+}
+
 #[test]
 fn test_node_setup() {
     let config = NodeConfig::test();
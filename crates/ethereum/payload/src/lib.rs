@@ -9,10 +9,11 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![allow(clippy::useless_let_if_seq)]
 
+use crate::metrics::EthereumPayloadBuilderMetrics;
 use reth_basic_payload_builder::{
     commit_withdrawals, is_better_payload, post_block_withdrawal_requests_contract_call,
     pre_block_beacon_root_contract_call, BuildArguments, BuildOutcome, PayloadBuilder,
-    PayloadConfig, WithdrawalsOutcome,
+    PayloadConfig, StateRootTask, WithdrawalsOutcome,
 };
 use reth_errors::RethError;
 use reth_evm::ConfigureEvm;
@@ -20,36 +21,55 @@ use reth_evm_ethereum::{eip6110::parse_deposits_from_receipts, EthEvmConfig};
 use reth_payload_builder::{
     error::PayloadBuilderError, EthBuiltPayload, EthPayloadBuilderAttributes,
 };
+use reth_payload_primitives::PayloadBuilderAttributes;
 use reth_primitives::{
-    constants::{
-        eip4844::MAX_DATA_GAS_PER_BLOCK, BEACON_NONCE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS,
-    },
-    eip4844::calculate_excess_blob_gas,
+    constants::{BEACON_NONCE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS},
     proofs::{self, calculate_requests_root},
     revm::env::tx_env_with_recovered,
-    Block, Header, IntoRecoveredTransaction, Receipt, Receipts, EMPTY_OMMER_ROOT_HASH, U256,
+    Address, Block, Header, IntoRecoveredTransaction, KnownAccount, Receipt, Receipts,
+    TransactionConditional, B256, EMPTY_OMMER_ROOT_HASH, U256,
+};
+use reth_provider::{BundleStateWithReceipts, StateProvider, StateProviderFactory};
+use reth_revm::{
+    database::StateProviderDatabase, precompile_cache::cached_precompiles_handle_register,
+    state_change::apply_blockhashes_update,
 };
-use reth_provider::{BundleStateWithReceipts, StateProviderFactory};
-use reth_revm::{database::StateProviderDatabase, state_change::apply_blockhashes_update};
 use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
+use reth_trie::HashedPostState;
 use revm::{
     db::states::bundle_state::BundleRetention,
-    primitives::{EVMError, EnvWithHandlerCfg, InvalidTransaction, ResultAndState},
-    DatabaseCommit, State,
+    primitives::{
+        EVMError, EnvWithHandlerCfg, ExecutionResult, InvalidTransaction, ResultAndState,
+    },
+    Database, DatabaseCommit, State,
 };
+use std::{collections::HashSet, str::FromStr};
 use tracing::{debug, trace, warn};
 
+mod local;
+pub use local::{LocalFirstTransactions, LocalTxPriority};
+
+mod metrics;
+
 /// Ethereum payload builder
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EthereumPayloadBuilder<EvmConfig = EthEvmConfig> {
     /// The type responsible for creating the evm.
     evm_config: EvmConfig,
+    /// Settings for the transactions that are included in the payloads.
+    builder_config: EthereumBuilderConfig,
 }
 
 impl<EvmConfig> EthereumPayloadBuilder<EvmConfig> {
     /// `EthereumPayloadBuilder` constructor.
-    pub const fn new(evm_config: EvmConfig) -> Self {
-        Self { evm_config }
+    pub fn new(evm_config: EvmConfig) -> Self {
+        Self { evm_config, builder_config: Default::default() }
+    }
+
+    /// Sets the settings for the transactions that are included in the payloads.
+    pub fn with_builder_config(mut self, builder_config: EthereumBuilderConfig) -> Self {
+        self.builder_config = builder_config;
+        self
     }
 }
 
@@ -73,14 +93,15 @@ where
         &self,
         args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
     ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError> {
-        default_ethereum_payload_builder(self.evm_config.clone(), args)
+        default_ethereum_payload_builder(self.evm_config.clone(), &self.builder_config, args)
     }
 
     fn build_empty_payload(
         &self,
         client: &Client,
-        config: PayloadConfig<Self::Attributes>,
+        mut config: PayloadConfig<Self::Attributes>,
     ) -> Result<EthBuiltPayload, PayloadBuilderError> {
+        self.builder_config.configure_gas_limit(&mut config);
         let extra_data = config.extra_data();
         let PayloadConfig {
             initialized_block_env,
@@ -174,14 +195,18 @@ where
         let mut blob_gas_used = None;
 
         if chain_spec.is_cancun_active_at_timestamp(attributes.timestamp) {
+            let blob_params = chain_spec.blob_params_at_timestamp(attributes.timestamp);
             excess_blob_gas = if chain_spec.is_cancun_active_at_timestamp(parent_block.timestamp) {
                 let parent_excess_blob_gas = parent_block.excess_blob_gas.unwrap_or_default();
                 let parent_blob_gas_used = parent_block.blob_gas_used.unwrap_or_default();
-                Some(calculate_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used))
+                Some(
+                    blob_params
+                        .next_block_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used),
+                )
             } else {
                 // for the first post-fork block, both parent.blob_gas_used and
                 // parent.excess_blob_gas are evaluated as 0
-                Some(calculate_excess_blob_gas(0, 0))
+                Some(blob_params.next_block_excess_blob_gas(0, 0))
             };
 
             blob_gas_used = Some(0);
@@ -236,6 +261,89 @@ where
     }
 }
 
+/// Settings of the [`EthereumPayloadBuilder`] for the blocks it builds and the pool transactions
+/// it includes.
+///
+/// These only affect locally built payloads, never the validation of payloads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EthereumBuilderConfig {
+    /// Whether to drop pool transactions whose execution reverts or halts.
+    drop_reverting_txs: bool,
+    /// The senders whose reverting transactions are included nonetheless.
+    revert_allowlist: HashSet<Address>,
+    /// Which local transactions are included ahead of the tip ordering.
+    local_tx_priority: LocalTxPriority,
+    /// The gas limit the built blocks move towards, unless the attributes carry their own.
+    gas_limit: Option<u64>,
+}
+
+impl EthereumBuilderConfig {
+    /// Sets whether to drop pool transactions whose execution reverts or halts.
+    ///
+    /// A dropped transaction is treated like an invalid one: neither it nor any later transaction
+    /// of the same sender is included in the payload, because that would leave a nonce gap.
+    pub const fn drop_reverting_txs(mut self, drop_reverting_txs: bool) -> Self {
+        self.drop_reverting_txs = drop_reverting_txs;
+        self
+    }
+
+    /// Sets the senders whose reverting transactions are included nonetheless.
+    pub fn revert_allowlist(mut self, revert_allowlist: HashSet<Address>) -> Self {
+        self.revert_allowlist = revert_allowlist;
+        self
+    }
+
+    /// Sets which local transactions are included ahead of the tip ordering.
+    ///
+    /// Prioritized local transactions are still subject to the gas and blob gas limits of the
+    /// block and are included in nonce order. A local transaction whose sender has pending
+    /// non-local transactions with a lower nonce isn't prioritized.
+    pub const fn local_tx_priority(mut self, local_tx_priority: LocalTxPriority) -> Self {
+        self.local_tx_priority = local_tx_priority;
+        self
+    }
+
+    /// Sets the gas limit the built blocks move towards.
+    ///
+    /// The gas limit of a block can only differ from the gas limit of its parent by less than
+    /// `parent_gas_limit / 1024`, so reaching the target can take several blocks. Payload
+    /// attributes that carry their own gas limit take precedence. If not set, the gas limit of the
+    /// parent is kept.
+    pub const fn gas_limit(mut self, gas_limit: Option<u64>) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    /// Applies the configured gas limit to the payload config, unless its attributes carry their
+    /// own.
+    fn configure_gas_limit(&self, config: &mut PayloadConfig<EthPayloadBuilderAttributes>) {
+        let Some(gas_limit) = self.gas_limit else { return };
+        if config.attributes.gas_limit.is_some() {
+            return
+        }
+        config.attributes.gas_limit = Some(gas_limit);
+        (config.initialized_cfg, config.initialized_block_env) =
+            config.attributes.cfg_and_block_env(&config.chain_spec, &config.parent_block);
+    }
+
+    /// Parses an allowlist of senders with one address per line.
+    ///
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn parse_revert_allowlist(s: &str) -> Result<HashSet<Address>, <Address as FromStr>::Err> {
+        s.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(Address::from_str)
+            .collect()
+    }
+
+    /// Returns true if the transaction of the sender with this execution result must not be
+    /// included.
+    pub fn should_drop(&self, sender: &Address, result: &ExecutionResult) -> bool {
+        self.drop_reverting_txs && !result.is_success() && !self.revert_allowlist.contains(sender)
+    }
+}
+
 /// Constructs an Ethereum transaction payload using the best transactions from the pool.
 ///
 /// Given build arguments including an Ethereum client, transaction pool,
@@ -244,6 +352,7 @@ where
 #[inline]
 pub fn default_ethereum_payload_builder<EvmConfig, Pool, Client>(
     evm_config: EvmConfig,
+    builder_config: &EthereumBuilderConfig,
     args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
 ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
 where
@@ -251,12 +360,28 @@ where
     Client: StateProviderFactory,
     Pool: TransactionPool,
 {
-    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload } = args;
+    let BuildArguments {
+        client,
+        pool,
+        mut cached_reads,
+        precompile_cache,
+        mut config,
+        cancel,
+        best_payload,
+        executor,
+    } = args;
+    builder_config.configure_gas_limit(&mut config);
 
     let state_provider = client.state_by_block_hash(config.parent_block.hash())?;
     let state = StateProviderDatabase::new(state_provider);
     let mut db =
         State::builder().with_database_ref(cached_reads.as_db(state)).with_bundle_update().build();
+    // compute the state root in the background while the transactions are being executed
+    let state_root_task = StateRootTask::spawn(
+        &*executor,
+        client.state_by_block_hash(config.parent_block.hash())?,
+        config.parent_block.state_root,
+    );
     let extra_data = config.extra_data();
     let PayloadConfig {
         initialized_block_env,
@@ -270,15 +395,26 @@ where
     debug!(target: "payload_builder", id=%attributes.id, parent_hash = ?parent_block.hash(), parent_number = parent_block.number, "building new payload");
     let mut cumulative_gas_used = 0;
     let mut sum_blob_gas_used = 0;
+    let blob_params = chain_spec.blob_params_at_timestamp(attributes.timestamp);
     let block_gas_limit: u64 = initialized_block_env.gas_limit.try_into().unwrap_or(u64::MAX);
     let base_fee = initialized_block_env.basefee.to::<u64>();
 
     let mut executed_txs = Vec::new();
+    let mut dropped_reverting_txs = 0;
 
-    let mut best_txs = pool.best_transactions_with_attributes(BestTransactionsAttributes::new(
+    let best_txs_attributes = BestTransactionsAttributes::new(
         base_fee,
         initialized_block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
-    ));
+    );
+    let mut best_txs = pool.best_transactions_with_attributes(best_txs_attributes);
+    if builder_config.local_tx_priority != LocalTxPriority::None {
+        best_txs = Box::new(LocalFirstTransactions::new(
+            best_txs,
+            pool.best_transactions_with_attributes(best_txs_attributes),
+            builder_config.local_tx_priority,
+            base_fee,
+        ));
+    }
 
     let mut total_fees = U256::ZERO;
 
@@ -304,6 +440,9 @@ where
     )
     .map_err(|err| PayloadBuilderError::Internal(err.into()))?;
 
+    // the parent state is only opened if a conditional expects a storage root
+    let mut parent_state = None;
+
     let mut receipts = Vec::new();
     while let Some(pool_tx) = best_txs.next() {
         // ensure we still have capacity for this transaction
@@ -320,6 +459,28 @@ where
             return Ok(BuildOutcome::Cancelled)
         }
 
+        // skip the transaction if the conditional it was submitted with doesn't hold for this block
+        if let Some(conditional) = pool_tx.conditional() {
+            let holds = conditional_holds(
+                conditional,
+                block_number,
+                attributes.timestamp,
+                &mut db,
+                |address| {
+                    if parent_state.is_none() {
+                        parent_state = Some(client.state_by_block_hash(parent_block.hash())?);
+                    }
+                    let parent_state = parent_state.as_ref().expect("parent state is opened");
+                    Ok(parent_state.proof(address, &[])?.storage_root)
+                },
+            )?;
+            if !holds {
+                trace!(target: "payload_builder", tx=?pool_tx.hash(), "skipping transaction with violated conditional and its descendants");
+                best_txs.mark_invalid(&pool_tx);
+                continue
+            }
+        }
+
         // convert tx to a signed transaction
         let tx = pool_tx.to_recovered_transaction();
 
@@ -327,7 +488,7 @@ where
         // the EIP-4844 can still fit in the block
         if let Some(blob_tx) = tx.transaction.as_eip4844() {
             let tx_blob_gas = blob_tx.blob_gas();
-            if sum_blob_gas_used + tx_blob_gas > MAX_DATA_GAS_PER_BLOCK {
+            if sum_blob_gas_used + tx_blob_gas > blob_params.max_blob_gas_per_block {
                 // we can't fit this _blob_ transaction into the block, so we mark it as
                 // invalid, which removes its dependent transactions from
                 // the iterator. This is similar to the gas limit condition
@@ -345,7 +506,13 @@ where
         );
 
         // Configure the environment for the block.
-        let mut evm = evm_config.evm_with_env(&mut db, env);
+        let mut evm = evm_config
+            .evm_with_env(&mut db, env)
+            .modify()
+            .append_handler_register_box(cached_precompiles_handle_register(
+                precompile_cache.clone(),
+            ))
+            .build();
 
         let ResultAndState { result, state } = match evm.transact() {
             Ok(res) => res,
@@ -373,7 +540,17 @@ where
         };
         // drop evm so db is released.
         drop(evm);
-        // commit changes
+
+        if builder_config.should_drop(&tx.signer(), &result) {
+            // the transaction is not committed, so its later nonces are not executable either
+            trace!(target: "payload_builder", ?tx, "skipping reverting transaction and its descendants");
+            best_txs.mark_invalid(&pool_tx);
+            dropped_reverting_txs += 1;
+            continue
+        }
+
+        // send the changes to the state root task and commit them
+        state_root_task.update(HashedPostState::from_evm_state(&state));
         db.commit(state);
 
         // add to the total blob gas used if the transaction successfully executed
@@ -382,7 +559,7 @@ where
             sum_blob_gas_used += tx_blob_gas;
 
             // if we've reached the max data gas per block, we can skip blob txs entirely
-            if sum_blob_gas_used == MAX_DATA_GAS_PER_BLOCK {
+            if sum_blob_gas_used == blob_params.max_blob_gas_per_block {
                 best_txs.skip_blobs();
             }
         }
@@ -412,6 +589,11 @@ where
         executed_txs.push(tx.into_signed());
     }
 
+    if builder_config.drop_reverting_txs {
+        EthereumPayloadBuilderMetrics::default()
+            .record_dropped_reverting_txs(dropped_reverting_txs);
+    }
+
     // check if we have a better block
     if !is_better_payload(best_payload.as_ref(), total_fees) {
         // can skip building the block
@@ -452,10 +634,27 @@ where
     let receipts_root = bundle.receipts_root_slow(block_number).expect("Number is in range");
     let logs_bloom = bundle.block_logs_bloom(block_number).expect("Number is in range");
 
-    // calculate the state root
-    let state_root = {
-        let state_provider = db.database.0.inner.borrow_mut();
-        state_provider.db.state_root(bundle.state())?
+    // calculate the state root, only the changes since the last transaction are left to process
+    let state_root_result =
+        state_root_task.finish(HashedPostState::from_bundle_state(&bundle.state().state));
+    let state_root = match state_root_result {
+        Ok(state_root) => {
+            #[cfg(debug_assertions)]
+            {
+                let state_provider = db.database.0.inner.borrow_mut();
+                let expected_state_root = state_provider.db.state_root(bundle.state())?;
+                assert_eq!(
+                    state_root, expected_state_root,
+                    "state root computed by the state root task doesn't match the state root computed from scratch"
+                );
+            }
+            state_root
+        }
+        Err(err) => {
+            warn!(target: "payload_builder", %err, parent_hash=%parent_block.hash(), "state root task failed, computing the state root from scratch");
+            let state_provider = db.database.0.inner.borrow_mut();
+            state_provider.db.state_root(bundle.state())?
+        }
     };
 
     // create the block header
@@ -476,11 +675,14 @@ where
         excess_blob_gas = if chain_spec.is_cancun_active_at_timestamp(parent_block.timestamp) {
             let parent_excess_blob_gas = parent_block.excess_blob_gas.unwrap_or_default();
             let parent_blob_gas_used = parent_block.blob_gas_used.unwrap_or_default();
-            Some(calculate_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used))
+            Some(
+                blob_params
+                    .next_block_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used),
+            )
         } else {
             // for the first post-fork block, both parent.blob_gas_used and
             // parent.excess_blob_gas are evaluated as 0
-            Some(calculate_excess_blob_gas(0, 0))
+            Some(blob_params.next_block_excess_blob_gas(0, 0))
         };
 
         blob_gas_used = Some(sum_blob_gas_used);
@@ -523,3 +725,242 @@ where
 
     Ok(BuildOutcome::Better { payload, cached_reads })
 }
+
+/// Returns `true` if the given [`TransactionConditional`] holds for a block with the given number
+/// and timestamp that is built on top of the given state.
+///
+/// Storage slots are checked against the building state. Storage roots are looked up in the parent
+/// state, so an expected storage root is violated once an earlier transaction of the block changed
+/// the storage of the account.
+fn conditional_holds<DB: Database>(
+    conditional: &TransactionConditional,
+    block_number: u64,
+    timestamp: u64,
+    db: &mut State<DB>,
+    mut parent_storage_root: impl FnMut(Address) -> Result<B256, DB::Error>,
+) -> Result<bool, DB::Error> {
+    if !conditional.matches_block(block_number, timestamp) {
+        return Ok(false)
+    }
+
+    let storage_changed = conditional.known_accounts.iter().any(|(address, account)| {
+        matches!(account, KnownAccount::StorageRoot(_)) &&
+            db.transition_state
+                .as_ref()
+                .and_then(|transitions| transitions.transitions.get(address))
+                .is_some_and(|account| {
+                    account.storage_was_destroyed ||
+                        account.storage.values().any(|slot| slot.is_changed())
+                })
+    });
+    if storage_changed {
+        return Ok(false)
+    }
+
+    conditional.matches_known_accounts(parent_storage_root, |address, slot| {
+        db.storage(address, U256::from_be_bytes(slot.0))
+            .map(|value| B256::from(value.to_be_bytes::<32>()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Bytes, U64};
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        BestTransactions, TransactionOrigin, ValidPoolTransaction,
+    };
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        primitives::{Account, EvmStorageSlot},
+    };
+    use std::{
+        collections::{BTreeMap, HashMap},
+        sync::Arc,
+    };
+
+    fn revert() -> ExecutionResult {
+        ExecutionResult::Revert { gas_used: 21_000, output: Bytes::new() }
+    }
+
+    #[test]
+    fn parse_revert_allowlist() {
+        let allowlist = EthereumBuilderConfig::parse_revert_allowlist(
+            "# searchers\n0x0000000000000000000000000000000000000001\n\n  0x0000000000000000000000000000000000000002  \n",
+        )
+        .unwrap();
+        assert_eq!(
+            allowlist,
+            HashSet::from([Address::with_last_byte(1), Address::with_last_byte(2)])
+        );
+        assert!(EthereumBuilderConfig::parse_revert_allowlist("0x01").is_err());
+    }
+
+    #[test]
+    fn should_drop_reverting_tx_unless_allow_listed() {
+        let sender = Address::with_last_byte(1);
+        let config = EthereumBuilderConfig::default();
+        assert!(!config.should_drop(&sender, &revert()));
+
+        let config = config.drop_reverting_txs(true);
+        assert!(config.should_drop(&sender, &revert()));
+
+        let config = config.revert_allowlist(HashSet::from([sender]));
+        assert!(!config.should_drop(&sender, &revert()));
+        assert!(config.should_drop(&Address::with_last_byte(2), &revert()));
+    }
+
+    #[tokio::test]
+    async fn dropped_reverting_tx_excludes_later_nonces() {
+        let pool = testing_pool();
+        let reverting = MockTransaction::eip1559();
+        let valid = reverting.next();
+        for tx in [reverting.clone(), valid] {
+            pool.add_external_transaction(tx).await.unwrap();
+        }
+
+        let config = EthereumBuilderConfig::default().drop_reverting_txs(true);
+        let mut best_txs = pool.best_transactions();
+
+        // the builder drops the first transaction, which leaves a nonce gap for the second one
+        let pool_tx = best_txs.next().unwrap();
+        assert_eq!(*pool_tx.hash(), reverting.get_hash());
+        assert!(config.should_drop(&pool_tx.sender(), &revert()));
+        best_txs.mark_invalid(&pool_tx);
+
+        assert!(best_txs.next().is_none());
+    }
+
+    fn tx_with_tip(tip: u128) -> MockTransaction {
+        MockTransaction::eip1559().with_max_fee(1_000).with_priority_fee(tip)
+    }
+
+    #[tokio::test]
+    async fn low_tip_local_tx_is_included_first() {
+        let pool = testing_pool();
+        let external = tx_with_tip(100);
+        let local = tx_with_tip(1);
+        let equivalent_external = tx_with_tip(1);
+        pool.add_external_transaction(external.clone()).await.unwrap();
+        pool.add_transaction(TransactionOrigin::Local, local.clone()).await.unwrap();
+        pool.add_external_transaction(equivalent_external.clone()).await.unwrap();
+
+        // a block that only has room for two transactions
+        let build = |priority| {
+            let best_txs: Box<
+                dyn BestTransactions<Item = Arc<ValidPoolTransaction<MockTransaction>>>,
+            > = match priority {
+                LocalTxPriority::None => pool.best_transactions(),
+                priority => Box::new(LocalFirstTransactions::new(
+                    pool.best_transactions(),
+                    pool.best_transactions(),
+                    priority,
+                    0,
+                )),
+            };
+            best_txs.take(2).map(|tx| *tx.hash()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(build(LocalTxPriority::Always), vec![local.get_hash(), external.get_hash()]);
+        assert_eq!(
+            build(LocalTxPriority::AboveThreshold(1)),
+            vec![local.get_hash(), external.get_hash()]
+        );
+        // below the threshold the local transaction competes on its tip like the external one
+        let block = build(LocalTxPriority::AboveThreshold(2));
+        assert_eq!(block[0], external.get_hash());
+        let block = build(LocalTxPriority::None);
+        assert_eq!(block[0], external.get_hash());
+        assert!(
+            !build(LocalTxPriority::Always).contains(&equivalent_external.get_hash()),
+            "the external transaction with the same tip doesn't make it into the block"
+        );
+    }
+
+    #[tokio::test]
+    async fn local_tx_behind_external_nonce_is_not_prioritized() {
+        let pool = testing_pool();
+        let external = tx_with_tip(1);
+        let local = external.next();
+        let other = tx_with_tip(100);
+        pool.add_external_transaction(external.clone()).await.unwrap();
+        pool.add_transaction(TransactionOrigin::Local, local.clone()).await.unwrap();
+        pool.add_external_transaction(other.clone()).await.unwrap();
+
+        let best_txs = LocalFirstTransactions::new(
+            pool.best_transactions(),
+            pool.best_transactions(),
+            LocalTxPriority::Always,
+            0,
+        );
+        // the local transaction is only included after its external ancestor
+        assert_eq!(
+            best_txs.map(|tx| *tx.hash()).collect::<Vec<_>>(),
+            vec![other.get_hash(), external.get_hash(), local.get_hash()]
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_local_tx_is_not_yielded_again() {
+        let pool = testing_pool();
+        let local = tx_with_tip(1);
+        let descendant = local.next();
+        let external = tx_with_tip(100);
+        pool.add_transaction(TransactionOrigin::Local, local.clone()).await.unwrap();
+        pool.add_transaction(TransactionOrigin::Local, descendant).await.unwrap();
+        pool.add_external_transaction(external.clone()).await.unwrap();
+
+        let mut best_txs = LocalFirstTransactions::new(
+            pool.best_transactions(),
+            pool.best_transactions(),
+            LocalTxPriority::Always,
+            0,
+        );
+        // e.g. the local transaction doesn't fit into the block
+        let tx = best_txs.next().unwrap();
+        assert_eq!(*tx.hash(), local.get_hash());
+        best_txs.mark_invalid(&tx);
+
+        assert_eq!(best_txs.map(|tx| *tx.hash()).collect::<Vec<_>>(), vec![external.get_hash()]);
+    }
+
+    #[test]
+    fn conditional_violated_by_storage_change() {
+        let address = Address::with_last_byte(1);
+        let slot = U256::from(1);
+        let root = B256::with_last_byte(0xaa);
+        let mut cache = CacheDB::new(EmptyDB::default());
+        cache.insert_account_storage(address, slot, U256::from(1)).unwrap();
+        let mut db = State::builder().with_database(cache).with_bundle_update().build();
+
+        let slots = TransactionConditional {
+            known_accounts: BTreeMap::from([(
+                address,
+                KnownAccount::Slots(BTreeMap::from([(
+                    B256::from(slot),
+                    B256::from(U256::from(1)),
+                )])),
+            )]),
+            block_number_max: Some(U64::from(10)),
+            ..Default::default()
+        };
+        let storage_root = TransactionConditional {
+            known_accounts: BTreeMap::from([(address, KnownAccount::StorageRoot(root))]),
+            ..Default::default()
+        };
+
+        assert!(conditional_holds(&slots, 10, 0, &mut db, |_| unreachable!()).unwrap());
+        assert!(!conditional_holds(&slots, 11, 0, &mut db, |_| unreachable!()).unwrap());
+        assert!(conditional_holds(&storage_root, 10, 0, &mut db, |_| Ok(root)).unwrap());
+
+        // an earlier transaction of the block changes the slot
+        let mut account: Account = db.basic(address).unwrap().unwrap_or_default().into();
+        account.storage.insert(slot, EvmStorageSlot::new_changed(U256::from(1), U256::from(2)));
+        account.mark_touch();
+        db.commit(HashMap::from([(address, account)]));
+
+        assert!(!conditional_holds(&slots, 10, 0, &mut db, |_| unreachable!()).unwrap());
+        assert!(!conditional_holds(&storage_root, 10, 0, &mut db, |_| Ok(root)).unwrap());
+    }
+}
@@ -0,0 +1,154 @@
+//! Prioritization of local transactions in built payloads.
+
+use reth_primitives::TxHash;
+use reth_transaction_pool::{BestTransactions, PoolTransaction, ValidPoolTransaction};
+use std::{collections::HashSet, fmt, sync::Arc};
+
+/// Which local transactions are included ahead of the pool's tip ordering.
+///
+/// Local transactions are the ones submitted with [`TransactionOrigin::Local`], e.g. via the
+/// node's own RPC.
+///
+/// [`TransactionOrigin::Local`]: reth_transaction_pool::TransactionOrigin::Local
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LocalTxPriority {
+    /// Local transactions are ordered like any other transaction.
+    #[default]
+    None,
+    /// Local transactions that pay at least the given effective tip per gas are included first.
+    AboveThreshold(u128),
+    /// All local transactions are included first, regardless of their tip.
+    Always,
+}
+
+impl LocalTxPriority {
+    /// Returns true if the transaction is included ahead of the tip ordering, given the base fee
+    /// of the payload.
+    pub fn is_prioritized<T: PoolTransaction>(
+        &self,
+        tx: &ValidPoolTransaction<T>,
+        base_fee: u64,
+    ) -> bool {
+        if !tx.origin.is_local() {
+            return false
+        }
+        match *self {
+            Self::None => false,
+            Self::AboveThreshold(min_tip) => {
+                tx.effective_tip_per_gas(base_fee).is_some_and(|tip| tip >= min_tip)
+            }
+            Self::Always => true,
+        }
+    }
+}
+
+/// A [`BestTransactions`] iterator that yields the prioritized local transactions before all
+/// other transactions.
+///
+/// This drains two iterators over the same pending transactions: the first one only yields the
+/// prioritized local transactions, every other transaction is marked as invalid in it. That also
+/// drops prioritized transactions whose sender has pending non-prioritized transactions with a
+/// lower nonce, these can't be included without their ancestors and are ordered by their tip
+/// instead. Once the first iterator is exhausted, the second one yields the remaining
+/// transactions in the regular order.
+pub struct LocalFirstTransactions<T: PoolTransaction> {
+    /// Yields the prioritized local transactions.
+    local: Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<T>>>>,
+    /// Yields the remaining transactions once the local ones are exhausted.
+    rest: Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<T>>>>,
+    /// Decides which local transactions are prioritized.
+    priority: LocalTxPriority,
+    /// The base fee of the payload.
+    base_fee: u64,
+    /// Whether all prioritized local transactions have been yielded.
+    local_done: bool,
+    /// The local transactions that were yielded and not marked as invalid.
+    included: HashSet<TxHash>,
+    /// The local transactions that were marked as invalid.
+    invalid: HashSet<TxHash>,
+}
+
+impl<T: PoolTransaction> LocalFirstTransactions<T> {
+    /// Creates a new iterator from two independent iterators over the pending transactions.
+    pub fn new(
+        local: Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<T>>>>,
+        rest: Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<T>>>>,
+        priority: LocalTxPriority,
+        base_fee: u64,
+    ) -> Self {
+        Self {
+            local,
+            rest,
+            priority,
+            base_fee,
+            local_done: false,
+            included: HashSet::new(),
+            invalid: HashSet::new(),
+        }
+    }
+}
+
+impl<T: PoolTransaction> Iterator for LocalFirstTransactions<T> {
+    type Item = Arc<ValidPoolTransaction<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.local_done {
+            let Some(tx) = self.local.next() else {
+                self.local_done = true;
+                break
+            };
+            if self.priority.is_prioritized(&tx, self.base_fee) {
+                self.included.insert(*tx.hash());
+                return Some(tx)
+            }
+            // this also removes all descendants of the transaction from the local iterator
+            self.local.mark_invalid(&tx);
+        }
+
+        loop {
+            let tx = self.rest.next()?;
+            if self.invalid.contains(tx.hash()) {
+                // the transaction was already rejected, so are its descendants
+                self.rest.mark_invalid(&tx);
+            } else if !self.included.contains(tx.hash()) {
+                return Some(tx)
+            }
+        }
+    }
+}
+
+impl<T: PoolTransaction> BestTransactions for LocalFirstTransactions<T> {
+    fn mark_invalid(&mut self, tx: &Self::Item) {
+        if self.local_done {
+            self.rest.mark_invalid(tx)
+        } else {
+            self.included.remove(tx.hash());
+            self.invalid.insert(*tx.hash());
+            self.local.mark_invalid(tx)
+        }
+    }
+
+    fn no_updates(&mut self) {
+        self.local.no_updates();
+        self.rest.no_updates();
+    }
+
+    fn skip_blobs(&mut self) {
+        self.set_skip_blobs(true)
+    }
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        self.local.set_skip_blobs(skip_blobs);
+        self.rest.set_skip_blobs(skip_blobs);
+    }
+}
+
+impl<T: PoolTransaction> fmt::Debug for LocalFirstTransactions<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalFirstTransactions")
+            .field("priority", &self.priority)
+            .field("base_fee", &self.base_fee)
+            .field("local_done", &self.local_done)
+            .finish_non_exhaustive()
+    }
+}
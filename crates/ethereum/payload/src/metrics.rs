@@ -0,0 +1,24 @@
+//! Metrics for the ethereum payload builder
+
+use reth_metrics::{
+    metrics::{Counter, Histogram},
+    Metrics,
+};
+
+/// Ethereum payload builder metrics
+#[derive(Metrics)]
+#[metrics(scope = "payloads.ethereum")]
+pub(crate) struct EthereumPayloadBuilderMetrics {
+    /// Total number of pool transactions that were dropped because they reverted or halted
+    pub(crate) dropped_reverting_txs: Counter,
+    /// The number of pool transactions that were dropped per built payload because they reverted
+    /// or halted
+    pub(crate) dropped_reverting_txs_per_payload: Histogram,
+}
+
+impl EthereumPayloadBuilderMetrics {
+    pub(crate) fn record_dropped_reverting_txs(&self, dropped: u64) {
+        self.dropped_reverting_txs.increment(dropped);
+        self.dropped_reverting_txs_per_payload.record(dropped as f64);
+    }
+}
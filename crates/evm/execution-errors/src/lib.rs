@@ -9,7 +9,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use reth_consensus::ConsensusError;
-use reth_primitives::{revm_primitives::EVMError, BlockNumHash, B256};
+use reth_primitives::{revm_primitives::EVMError, BlockNumHash, BlockNumber, B256};
 use reth_prune_types::PruneSegmentError;
 use reth_storage_errors::provider::ProviderError;
 use std::fmt::Display;
@@ -21,11 +21,11 @@ pub use trie::{StateRootError, StorageRootError};
 /// Transaction validation errors
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum BlockValidationError {
-    /// EVM error with transaction hash and message
-    #[error("EVM reported invalid transaction ({hash}): {error}")]
+    /// EVM error with the failed transaction and message
+    #[error("EVM reported invalid transaction {transaction}: {error}")]
     EVM {
-        /// The hash of the transaction
-        hash: B256,
+        /// The transaction that failed to execute
+        transaction: Box<FailedTransaction>,
         /// The EVM error.
         #[source]
         error: Box<EVMError<ProviderError>>,
@@ -40,8 +40,10 @@ pub enum BlockValidationError {
     #[error(transparent)]
     StateRoot(#[from] StateRootError),
     /// Error when transaction gas limit exceeds available block gas
-    #[error("transaction gas limit {transaction_gas_limit} is more than blocks available gas {block_available_gas}")]
+    #[error("transaction gas limit {transaction_gas_limit} is more than blocks available gas {block_available_gas}, transaction {transaction}")]
     TransactionGasLimitMoreThanAvailableBlockGas {
+        /// The transaction that exceeds the available block gas
+        transaction: Box<FailedTransaction>,
         /// The transaction's gas limit
         transaction_gas_limit: u64,
         /// The available block gas
@@ -71,8 +73,10 @@ pub enum BlockValidationError {
     /// EVM error during [EIP-4788] beacon root contract call.
     ///
     /// [EIP-4788]: https://eips.ethereum.org/EIPS/eip-4788
-    #[error("failed to apply beacon root contract call at {parent_beacon_block_root}: {message}")]
+    #[error("failed to apply beacon root contract call at {parent_beacon_block_root} in block #{block_number}: {message}")]
     BeaconRootContractCall {
+        /// The number of the block
+        block_number: u64,
         /// The beacon block root
         parent_beacon_block_root: Box<B256>,
         /// The error message.
@@ -86,8 +90,12 @@ pub enum BlockValidationError {
     /// EVM error during withdrawal requests contract call [EIP-7002]
     ///
     /// [EIP-7002]: https://eips.ethereum.org/EIPS/eip-7002
-    #[error("failed to apply withdrawal requests contract call: {message}")]
+    #[error(
+        "failed to apply withdrawal requests contract call in block #{block_number}: {message}"
+    )]
     WithdrawalRequestsContractCall {
+        /// The number of the block
+        block_number: u64,
         /// The error message.
         message: String,
     },
@@ -98,6 +106,29 @@ pub enum BlockValidationError {
     DepositRequestDecode(String),
 }
 
+/// The transaction of a block that failed to execute, see [`BlockValidationError::EVM`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailedTransaction {
+    /// The number and hash of the block
+    pub block: BlockNumHash,
+    /// The index of the transaction in the block
+    pub index: usize,
+    /// The hash of the transaction
+    pub hash: B256,
+    /// The gas used by the block before the transaction
+    pub cumulative_gas_used: u64,
+}
+
+impl Display for FailedTransaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at index {} in block #{} ({}), cumulative gas used {}",
+            self.hash, self.index, self.block.number, self.block.hash, self.cumulative_gas_used
+        )
+    }
+}
+
 /// `BlockExecutor` Errors
 #[derive(Error, Debug)]
 pub enum BlockExecutionError {
@@ -174,3 +205,54 @@ impl BlockExecutionError {
         matches!(self, Self::Validation(BlockValidationError::StateRoot(_)))
     }
 }
+
+/// Errors when replaying a historical transaction on top of the state it was executed on.
+#[derive(Error, Debug)]
+pub enum TransactionReplayError {
+    /// The transaction is not included in the block.
+    #[error("transaction {tx_hash} is not included in block {block_hash}")]
+    TransactionNotInBlock {
+        /// The hash of the block
+        block_hash: B256,
+        /// The hash of the transaction
+        tx_hash: B256,
+    },
+    /// The state the block was executed on is pruned.
+    #[error("state at block #{0} is pruned")]
+    StateAtBlockPruned(BlockNumber),
+    /// Error when executing the preceding transactions of the block, or the transaction itself.
+    #[error(transparent)]
+    Execution(BlockExecutionError),
+    /// Error when reading the block or the state.
+    #[error(transparent)]
+    Provider(ProviderError),
+}
+
+impl From<ProviderError> for TransactionReplayError {
+    fn from(error: ProviderError) -> Self {
+        match error {
+            ProviderError::StateAtBlockPruned(number) => Self::StateAtBlockPruned(number),
+            error => Self::Provider(error),
+        }
+    }
+}
+
+impl From<BlockExecutionError> for TransactionReplayError {
+    fn from(error: BlockExecutionError) -> Self {
+        match error {
+            BlockExecutionError::LatestBlock(error) |
+            BlockExecutionError::Validation(
+                BlockValidationError::BlockHashAccountLoadingFailed(error),
+            ) => error.into(),
+            BlockExecutionError::Validation(BlockValidationError::EVM { transaction, error }) => {
+                match *error {
+                    EVMError::Database(error) => error.into(),
+                    error => Self::Execution(
+                        BlockValidationError::EVM { transaction, error: Box::new(error) }.into(),
+                    ),
+                }
+            }
+            error => Self::Execution(error),
+        }
+    }
+}
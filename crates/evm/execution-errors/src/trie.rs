@@ -12,6 +12,9 @@ pub enum StateRootError {
     /// Storage root error.
     #[error(transparent)]
     StorageRootError(#[from] StorageRootError),
+    /// The computation was cancelled before completion.
+    #[error("state root computation was cancelled")]
+    Cancelled,
 }
 
 impl From<StateRootError> for DatabaseError {
@@ -19,6 +22,7 @@ impl From<StateRootError> for DatabaseError {
         match err {
             StateRootError::DB(err) |
             StateRootError::StorageRootError(StorageRootError::DB(err)) => err,
+            StateRootError::Cancelled => Self::Other(StateRootError::Cancelled.to_string()),
         }
     }
 }
@@ -30,3 +34,11 @@ pub enum StorageRootError {
     #[error(transparent)]
     DB(#[from] DatabaseError),
 }
+
+impl From<StorageRootError> for DatabaseError {
+    fn from(err: StorageRootError) -> Self {
+        match err {
+            StorageRootError::DB(err) => err,
+        }
+    }
+}
@@ -2,10 +2,10 @@
 
 use reth_primitives::{BlockNumber, BlockWithSenders, Receipt, Receipts, Request, Requests, U256};
 use reth_prune_types::PruneModes;
-use revm::db::BundleState;
-use revm_primitives::db::Database;
+use revm::{db::BundleState, inspectors::NoOpInspector, Inspector};
+use revm_primitives::{db::Database, BlockEnv};
 
-pub use reth_execution_errors::{BlockExecutionError, BlockValidationError};
+pub use reth_execution_errors::{BlockExecutionError, BlockValidationError, FailedTransaction};
 pub use reth_storage_errors::provider::ProviderError;
 
 /// A general purpose executor trait that executes an input (e.g. block) and produces an output
@@ -207,6 +207,33 @@ pub trait BlockExecutorProvider: Send + Sync + Clone + Unpin + 'static {
         DB: Database<Error = ProviderError>;
 }
 
+/// A factory for the [`Inspector`]s that are attached to the EVM executing the blocks.
+///
+/// A new inspector is created for every block, so the inspectors can collect per-block data.
+pub trait BlockInspectorFactory: Send + Sync + Clone + Unpin + 'static {
+    /// The inspector attached to the EVM executing the block.
+    type Inspector<DB: Database>: Inspector<DB>;
+
+    /// Creates the inspector for the block with the given environment.
+    ///
+    /// Returns `None` if the block should be executed without an inspector.
+    fn inspector<DB: Database>(&self, block_env: &BlockEnv) -> Option<Self::Inspector<DB>>;
+}
+
+/// A [`BlockInspectorFactory`] that never attaches an inspector, so the blocks are executed
+/// without the inspector overhead.
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
+pub struct NoopBlockInspectorFactory;
+
+impl BlockInspectorFactory for NoopBlockInspectorFactory {
+    type Inspector<DB: Database> = NoOpInspector;
+
+    fn inspector<DB: Database>(&self, _block_env: &BlockEnv) -> Option<Self::Inspector<DB>> {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
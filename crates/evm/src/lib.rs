@@ -16,8 +16,14 @@ use revm_primitives::{BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, SpecId,
 
 pub mod either;
 pub mod execute;
+#[cfg(feature = "execution-metrics")]
+pub mod metrics;
 pub mod noop;
+pub mod parallel;
+pub mod precompile;
 pub mod provider;
+pub mod receipt;
+pub mod system_calls;
 
 #[cfg(any(test, feature = "test-utils"))]
 /// test helpers for mocking executor
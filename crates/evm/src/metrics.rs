@@ -0,0 +1,279 @@
+//! Instrumented block execution.
+//!
+//! The [`ExecutionMetricsInspector`] measures the time spent executing every class of opcodes and
+//! every precompile, and records the totals of the block into histograms once the block is
+//! executed.
+//!
+//! Every instruction is timed, which makes the execution noticeably slower, so the inspector is
+//! only available with the `execution-metrics` feature and has to be enabled at runtime as well.
+
+use crate::execute::BlockInspectorFactory;
+use reth_metrics::{metrics::Histogram, Metrics};
+use reth_primitives::Address;
+use revm::{
+    interpreter::{opcode, CallInputs, CallOutcome, Interpreter},
+    Database, EvmContext, Inspector,
+};
+use revm_primitives::BlockEnv;
+use std::{
+    collections::HashMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Creates an [`ExecutionMetricsInspector`] for every executed block if enabled.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExecutionMetricsInspectorFactory {
+    /// Whether the blocks are executed with the inspector attached.
+    enabled: bool,
+}
+
+impl ExecutionMetricsInspectorFactory {
+    /// Creates a new factory. If disabled, the blocks are executed without an inspector.
+    pub const fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    /// Returns `true` if the blocks are executed with the inspector attached.
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+impl BlockInspectorFactory for ExecutionMetricsInspectorFactory {
+    type Inspector<DB: Database> = ExecutionMetricsInspector;
+
+    fn inspector<DB: Database>(&self, _block_env: &BlockEnv) -> Option<Self::Inspector<DB>> {
+        self.enabled.then(ExecutionMetricsInspector::default)
+    }
+}
+
+/// The classes the opcodes are grouped into for the execution metrics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpcodeClass {
+    /// `SLOAD`.
+    StorageRead,
+    /// `SSTORE`.
+    StorageWrite,
+    /// Opcodes that read another account: `BALANCE`, `EXTCODESIZE`, `EXTCODECOPY` and
+    /// `EXTCODEHASH`.
+    AccountAccess,
+    /// `CALL`, `CALLCODE`, `DELEGATECALL` and `STATICCALL`, excluding the execution of the
+    /// called code.
+    Call,
+    /// `CREATE` and `CREATE2`, excluding the execution of the init code.
+    Create,
+    /// `KECCAK256`.
+    Keccak,
+    /// `LOG0` to `LOG4`.
+    Log,
+    /// All other opcodes.
+    Other,
+}
+
+impl OpcodeClass {
+    /// All opcode classes.
+    pub const ALL: [Self; 8] = [
+        Self::StorageRead,
+        Self::StorageWrite,
+        Self::AccountAccess,
+        Self::Call,
+        Self::Create,
+        Self::Keccak,
+        Self::Log,
+        Self::Other,
+    ];
+
+    /// Returns the class of the given opcode.
+    pub const fn of(opcode: u8) -> Self {
+        match opcode {
+            opcode::SLOAD => Self::StorageRead,
+            opcode::SSTORE => Self::StorageWrite,
+            opcode::BALANCE | opcode::EXTCODESIZE | opcode::EXTCODECOPY | opcode::EXTCODEHASH => {
+                Self::AccountAccess
+            }
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+                Self::Call
+            }
+            opcode::CREATE | opcode::CREATE2 => Self::Create,
+            opcode::KECCAK256 => Self::Keccak,
+            opcode::LOG0..=opcode::LOG4 => Self::Log,
+            _ => Self::Other,
+        }
+    }
+
+    /// Returns the name of the class, used as the metrics label.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::StorageRead => "storage_read",
+            Self::StorageWrite => "storage_write",
+            Self::AccountAccess => "account_access",
+            Self::Call => "call",
+            Self::Create => "create",
+            Self::Keccak => "keccak",
+            Self::Log => "log",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for OpcodeClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The total time spent and the number of executions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionStats {
+    /// The total time spent.
+    pub duration: Duration,
+    /// The number of executions.
+    pub count: u64,
+}
+
+impl ExecutionStats {
+    fn record(&mut self, duration: Duration) {
+        self.duration += duration;
+        self.count += 1;
+    }
+}
+
+/// Measures the execution of a single block per opcode class and per precompile.
+///
+/// The measurements are recorded once the block is executed and the inspector is dropped.
+#[derive(Debug, Default)]
+pub struct ExecutionMetricsInspector {
+    /// The stats of every [`OpcodeClass`], in the order of [`OpcodeClass::ALL`].
+    opcodes: [ExecutionStats; OpcodeClass::ALL.len()],
+    /// The stats of every called precompile.
+    precompiles: HashMap<Address, ExecutionStats>,
+    /// The class and start of the instruction being executed.
+    current_step: Option<(OpcodeClass, Instant)>,
+    /// The called precompile and the start of the call for every call being executed, or `None`
+    /// if the called account is not a precompile.
+    calls: Vec<Option<(Address, Instant)>>,
+}
+
+impl ExecutionMetricsInspector {
+    /// Returns the stats of the given opcode class.
+    pub const fn opcode_stats(&self, class: OpcodeClass) -> ExecutionStats {
+        self.opcodes[class as usize]
+    }
+
+    /// Returns the stats of the called precompiles.
+    pub const fn precompile_stats(&self) -> &HashMap<Address, ExecutionStats> {
+        &self.precompiles
+    }
+}
+
+impl<DB: Database> Inspector<DB> for ExecutionMetricsInspector {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        self.current_step = Some((OpcodeClass::of(interp.current_opcode()), Instant::now()));
+    }
+
+    fn step_end(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if let Some((class, started_at)) = self.current_step.take() {
+            self.opcodes[class as usize].record(started_at.elapsed());
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        let precompile = context
+            .precompiles
+            .contains_key(&inputs.bytecode_address)
+            .then(|| (inputs.bytecode_address, Instant::now()));
+        self.calls.push(precompile);
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        if let Some(Some((address, started_at))) = self.calls.pop() {
+            self.precompiles.entry(address).or_default().record(started_at.elapsed());
+        }
+        outcome
+    }
+}
+
+impl Drop for ExecutionMetricsInspector {
+    fn drop(&mut self) {
+        for class in OpcodeClass::ALL {
+            let stats = self.opcode_stats(class);
+            OpcodeClassMetrics::new_with_labels(&[("class", class.as_str())]).record(stats);
+        }
+        for (address, stats) in &self.precompiles {
+            PrecompileMetrics::new_with_labels(&[("address", address.to_string())]).record(*stats);
+        }
+    }
+}
+
+/// Execution metrics of an [`OpcodeClass`].
+#[derive(Metrics)]
+#[metrics(scope = "sync.execution.opcodes")]
+struct OpcodeClassMetrics {
+    /// The time spent per block executing the opcodes of the class.
+    duration: Histogram,
+    /// The number of executed opcodes of the class per block.
+    count: Histogram,
+}
+
+impl OpcodeClassMetrics {
+    fn record(&self, stats: ExecutionStats) {
+        self.duration.record(stats.duration);
+        self.count.record(stats.count as f64);
+    }
+}
+
+/// Execution metrics of a precompile.
+#[derive(Metrics)]
+#[metrics(scope = "sync.execution.precompiles")]
+struct PrecompileMetrics {
+    /// The time spent per block executing the precompile.
+    duration: Histogram,
+    /// The number of calls to the precompile per block.
+    count: Histogram,
+}
+
+impl PrecompileMetrics {
+    fn record(&self, stats: ExecutionStats) {
+        self.duration.record(stats.duration);
+        self.count.record(stats.count as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opcode_classes() {
+        for (index, class) in OpcodeClass::ALL.into_iter().enumerate() {
+            assert_eq!(class as usize, index);
+        }
+
+        assert_eq!(OpcodeClass::of(opcode::SLOAD), OpcodeClass::StorageRead);
+        assert_eq!(OpcodeClass::of(opcode::EXTCODEHASH), OpcodeClass::AccountAccess);
+        assert_eq!(OpcodeClass::of(opcode::STATICCALL), OpcodeClass::Call);
+        assert_eq!(OpcodeClass::of(opcode::LOG2), OpcodeClass::Log);
+        assert_eq!(OpcodeClass::of(opcode::ADD), OpcodeClass::Other);
+    }
+
+    #[test]
+    fn inspector_only_if_enabled() {
+        assert!(ExecutionMetricsInspectorFactory::new(false)
+            .inspector::<revm::db::EmptyDB>(&BlockEnv::default())
+            .is_none());
+        assert!(ExecutionMetricsInspectorFactory::new(true)
+            .inspector::<revm::db::EmptyDB>(&BlockEnv::default())
+            .is_some());
+    }
+}
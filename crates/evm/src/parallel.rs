@@ -0,0 +1,611 @@
+//! Optimistic parallel execution of the transactions of a block.
+//!
+//! All transactions of the block are first executed speculatively and concurrently on top of the
+//! state at the start of the block, recording the accounts and storage slots that they read. The
+//! results are then committed in the order of the block: a speculative result is only used if
+//! none of the preceding transactions changed anything the transaction read, otherwise the
+//! transaction is executed again on top of the committed state. The committed state changes, and
+//! thus the execution outcome, are the same as if the transactions were executed one after the
+//! other.
+//!
+//! Every transaction pays its fee to the beneficiary of the block, which would make every
+//! transaction conflict with all preceding ones. Transactions that don't observe the beneficiary
+//! account apart from the fee payment therefore have their fee applied to the committed balance
+//! of the beneficiary instead.
+
+use crate::ConfigureEvm;
+use reth_execution_errors::{BlockExecutionError, BlockValidationError, FailedTransaction};
+use reth_primitives::{Address, BlockNumHash, BlockWithSenders, TransactionSigned, B256, U256};
+use reth_storage_errors::provider::ProviderError;
+use revm::{
+    interpreter::{opcode, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    Database, DatabaseCommit, EvmContext, Inspector, State,
+};
+use revm_primitives::{
+    AccountInfo, Bytecode, EVMError, EnvWithHandlerCfg, EvmState, ExecutionResult, ResultAndState,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+/// The default number of transactions in a block below which the transactions are executed
+/// sequentially.
+pub const DEFAULT_MIN_TRANSACTIONS: usize = 16;
+
+/// Configuration of the parallel execution.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ParallelExecutionConfig {
+    /// Maximum number of workers executing transactions speculatively.
+    pub workers: usize,
+    /// Number of transactions in a block below which the transactions are executed sequentially
+    /// on the calling thread.
+    pub min_transactions: usize,
+}
+
+impl Default for ParallelExecutionConfig {
+    fn default() -> Self {
+        Self {
+            workers: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            min_transactions: DEFAULT_MIN_TRANSACTIONS,
+        }
+    }
+}
+
+impl ParallelExecutionConfig {
+    /// Sets the maximum number of workers.
+    pub const fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Sets the number of transactions below which the transactions are executed sequentially.
+    pub const fn with_min_transactions(mut self, min_transactions: usize) -> Self {
+        self.min_transactions = min_transactions;
+        self
+    }
+
+    /// Returns `true` if the given number of transactions should be executed sequentially.
+    pub const fn should_fallback(&self, transactions: usize) -> bool {
+        transactions < self.min_transactions || transactions < 2 || self.workers <= 1
+    }
+}
+
+/// Executes the transactions of a block optimistically in parallel, see the
+/// [module documentation](self).
+#[derive(Debug)]
+pub struct ParallelExecutor<'a, EvmConfig> {
+    /// How to create an EVM.
+    evm_config: &'a EvmConfig,
+    /// The configuration of the parallel execution.
+    config: ParallelExecutionConfig,
+}
+
+impl<'a, EvmConfig> ParallelExecutor<'a, EvmConfig>
+where
+    EvmConfig: ConfigureEvm,
+{
+    /// Creates a new parallel executor.
+    pub const fn new(evm_config: &'a EvmConfig, config: ParallelExecutionConfig) -> Self {
+        Self { evm_config, config }
+    }
+
+    /// Executes the transactions of the block on top of the given state, commits their state
+    /// changes and returns their results in the order of the block.
+    ///
+    /// The pre-execution changes of the block must already be committed to the state.
+    pub fn execute_transactions<DB>(
+        &self,
+        state: &mut State<DB>,
+        env: &EnvWithHandlerCfg,
+        block: &BlockWithSenders,
+    ) -> Result<Vec<ExecutionResult>, BlockExecutionError>
+    where
+        DB: Database<Error = ProviderError>,
+    {
+        let transactions = block.transactions_with_sender().collect::<Vec<_>>();
+        let speculative_results = if self.config.should_fallback(transactions.len()) {
+            std::iter::repeat_with(|| None).take(transactions.len()).collect()
+        } else {
+            self.execute_speculatively(state, env, &transactions)
+        };
+
+        let block_num_hash = BlockNumHash::new(block.number, block.header.hash_slow());
+        let coinbase = env.block.coinbase;
+        let mut evm = self.evm_config.evm_with_env(state, env.clone());
+
+        let mut writes = WriteSet::default();
+        let mut cumulative_gas_used = 0;
+        let mut results = Vec::with_capacity(transactions.len());
+        for (index, ((sender, transaction), speculative)) in
+            transactions.into_iter().zip(speculative_results).enumerate()
+        {
+            let failed_transaction = || {
+                Box::new(FailedTransaction {
+                    block: block_num_hash,
+                    index,
+                    hash: transaction.recalculate_hash(),
+                    cumulative_gas_used,
+                })
+            };
+
+            // The sum of the transaction’s gas limit, Tg, and the gas utilized in this block prior,
+            // must be no greater than the block’s gasLimit.
+            let block_available_gas = block.header.gas_limit - cumulative_gas_used;
+            if transaction.gas_limit() > block_available_gas {
+                return Err(BlockValidationError::TransactionGasLimitMoreThanAvailableBlockGas {
+                    transaction: failed_transaction(),
+                    transaction_gas_limit: transaction.gas_limit(),
+                    block_available_gas,
+                }
+                .into())
+            }
+
+            let ResultAndState { result, state } = match speculative
+                .and_then(|speculative| speculative.into_valid(&writes, coinbase))
+            {
+                Some((result_and_state, None)) => result_and_state,
+                Some((mut result_and_state, Some(fee))) => {
+                    // Pay the fee on top of the committed balance of the beneficiary
+                    let mut info = evm.db_mut().basic(coinbase)?.unwrap_or_default();
+                    info.balance = info.balance.saturating_add(fee);
+                    if let Some(account) = result_and_state.state.get_mut(&coinbase) {
+                        account.info = info;
+                    }
+                    result_and_state
+                }
+                None => {
+                    EvmConfig::fill_tx_env(evm.tx_mut(), transaction, *sender);
+                    evm.transact().map_err(move |err| BlockValidationError::EVM {
+                        transaction: failed_transaction(),
+                        error: err.into(),
+                    })?
+                }
+            };
+
+            writes.record(evm.db_mut(), &state)?;
+            evm.db_mut().commit(state);
+
+            cumulative_gas_used += result.gas_used();
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Executes all transactions concurrently on top of the given state and returns their
+    /// speculative results in the order of the block.
+    ///
+    /// The workers read the state through the calling thread, which serves their reads until all
+    /// transactions are executed. The state is not modified, apart from loading the read accounts
+    /// and storage slots into its cache.
+    fn execute_speculatively<DB>(
+        &self,
+        state: &mut State<DB>,
+        env: &EnvWithHandlerCfg,
+        transactions: &[(&Address, &TransactionSigned)],
+    ) -> Vec<Option<SpeculativeResult>>
+    where
+        DB: Database<Error = ProviderError>,
+    {
+        let workers = self.config.workers.min(transactions.len());
+        let next_transaction = AtomicUsize::new(0);
+        let (request_tx, request_rx) = mpsc::channel();
+
+        let mut speculative_results =
+            std::iter::repeat_with(|| None).take(transactions.len()).collect::<Vec<_>>();
+        thread::scope(|scope| {
+            let mut handles = Vec::with_capacity(workers);
+            let mut response_txs = Vec::with_capacity(workers);
+            for worker in 0..workers {
+                let (response_tx, response_rx) = mpsc::channel();
+                response_txs.push(response_tx);
+                let db = SpeculativeDatabase::new(worker, request_tx.clone(), response_rx);
+                let next_transaction = &next_transaction;
+                handles.push(scope.spawn(move || {
+                    self.speculative_worker(db, env, transactions, next_transaction)
+                }));
+            }
+
+            // The reads are served until all workers are done and dropped their senders
+            drop(request_tx);
+            for (worker, request) in request_rx {
+                let _ = response_txs[worker].send(request.serve(state));
+            }
+
+            for handle in handles {
+                let results = handle.join().unwrap_or_else(|err| std::panic::resume_unwind(err));
+                for (index, result) in results {
+                    speculative_results[index] = Some(result);
+                }
+            }
+        });
+
+        speculative_results
+    }
+
+    /// Speculatively executes the next transaction that is not executed yet, until all
+    /// transactions are executed.
+    fn speculative_worker(
+        &self,
+        db: SpeculativeDatabase,
+        env: &EnvWithHandlerCfg,
+        transactions: &[(&Address, &TransactionSigned)],
+        next_transaction: &AtomicUsize,
+    ) -> Vec<(usize, SpeculativeResult)> {
+        let coinbase = env.block.coinbase;
+        let mut evm = self.evm_config.evm_with_env_and_inspector(
+            db,
+            env.clone(),
+            CoinbaseObserver { coinbase, observed: false },
+        );
+
+        let mut results = Vec::new();
+        loop {
+            let index = next_transaction.fetch_add(1, Ordering::Relaxed);
+            let Some((sender, transaction)) = transactions.get(index) else { break };
+
+            evm.context.external.observed = false;
+            evm.db_mut().reads.clear();
+            EvmConfig::fill_tx_env(evm.tx_mut(), transaction, **sender);
+            let outcome = evm.transact();
+
+            // The fee paid to the beneficiary, if the transaction didn't observe it otherwise
+            let coinbase_fee = match &outcome {
+                Ok(ResultAndState { state, .. }) if !evm.context.external.observed => {
+                    let pre_balance = evm
+                        .db_mut()
+                        .accounts
+                        .get(&coinbase)
+                        .and_then(|info| info.as_ref().map(|info| info.balance))
+                        .unwrap_or_default();
+                    state
+                        .get(&coinbase)
+                        .and_then(|account| account.info.balance.checked_sub(pre_balance))
+                }
+                _ => None,
+            };
+
+            let reads = std::mem::take(&mut evm.db_mut().reads);
+            results.push((index, SpeculativeResult { outcome, reads, coinbase_fee }));
+        }
+
+        results
+    }
+}
+
+/// The result of the speculative execution of a transaction.
+#[derive(Debug)]
+struct SpeculativeResult {
+    /// The result of the execution.
+    outcome: Result<ResultAndState, EVMError<ProviderError>>,
+    /// The accounts and storage slots read by the transaction.
+    reads: ReadSet,
+    /// The fee paid to the beneficiary of the block, if the transaction didn't observe the
+    /// beneficiary account otherwise.
+    coinbase_fee: Option<U256>,
+}
+
+impl SpeculativeResult {
+    /// Returns the result of the execution and the fee paid to the beneficiary, if any, unless
+    /// the execution failed or the transaction read any of the given writes.
+    ///
+    /// Only the fee paid to the beneficiary has to be applied to the committed state if the
+    /// transaction didn't observe the beneficiary otherwise.
+    fn into_valid(
+        self,
+        writes: &WriteSet,
+        coinbase: Address,
+    ) -> Option<(ResultAndState, Option<U256>)> {
+        let ignored = self.coinbase_fee.is_some().then_some(coinbase);
+        if writes.conflicts_with(&self.reads, ignored) {
+            return None
+        }
+        Some((self.outcome.ok()?, self.coinbase_fee))
+    }
+}
+
+/// The accounts and storage slots read by a transaction.
+#[derive(Debug, Default)]
+struct ReadSet {
+    /// The read accounts.
+    accounts: HashSet<Address>,
+    /// The read storage slots.
+    storage: HashSet<(Address, U256)>,
+}
+
+impl ReadSet {
+    fn clear(&mut self) {
+        self.accounts.clear();
+        self.storage.clear();
+    }
+}
+
+/// The accounts and storage slots changed by the committed transactions.
+#[derive(Debug, Default)]
+struct WriteSet {
+    /// The accounts with a changed balance, nonce, code or existence.
+    accounts: HashSet<Address>,
+    /// The changed storage slots.
+    storage: HashSet<(Address, U256)>,
+    /// The accounts that were created or destroyed, which changes all of their storage.
+    wiped: HashSet<Address>,
+}
+
+impl WriteSet {
+    /// Returns `true` if any of the given reads, apart from the ignored account, were changed.
+    fn conflicts_with(&self, reads: &ReadSet, ignored: Option<Address>) -> bool {
+        reads
+            .accounts
+            .iter()
+            .any(|address| Some(*address) != ignored && self.accounts.contains(address)) ||
+            reads
+                .storage
+                .iter()
+                .any(|slot| self.wiped.contains(&slot.0) || self.storage.contains(slot))
+    }
+
+    /// Records the given changes of a transaction, before they are committed to the state.
+    fn record<DB: Database>(
+        &mut self,
+        state: &mut DB,
+        changes: &EvmState,
+    ) -> Result<(), DB::Error> {
+        for (address, account) in changes {
+            if !account.is_touched() {
+                continue
+            }
+
+            if account.is_created() || account.is_selfdestructed() {
+                self.wiped.insert(*address);
+                self.accounts.insert(*address);
+            } else {
+                // Touched empty accounts may be removed from the state
+                let current = state.basic(*address)?;
+                let changed = account.is_empty() ||
+                    current.map(|info| (info.balance, info.nonce, info.code_hash)) !=
+                        Some((
+                            account.info.balance,
+                            account.info.nonce,
+                            account.info.code_hash,
+                        ));
+                if changed {
+                    self.accounts.insert(*address);
+                }
+            }
+
+            self.storage.extend(
+                account
+                    .storage
+                    .iter()
+                    .filter(|(_, slot)| slot.is_changed())
+                    .map(|(index, _)| (*address, *index)),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A read of the state requested by a worker.
+#[derive(Debug)]
+enum ReadRequest {
+    Account(Address),
+    Code(B256),
+    Storage(Address, U256),
+    BlockHash(U256),
+}
+
+impl ReadRequest {
+    /// Reads the requested value from the state.
+    fn serve<DB: Database>(self, state: &mut DB) -> Result<ReadResponse, DB::Error> {
+        Ok(match self {
+            Self::Account(address) => ReadResponse::Account(state.basic(address)?),
+            Self::Code(code_hash) => ReadResponse::Code(state.code_by_hash(code_hash)?),
+            Self::Storage(address, index) => {
+                // The account is always loaded before its storage
+                state.basic(address)?;
+                ReadResponse::Storage(state.storage(address, index)?)
+            }
+            Self::BlockHash(number) => ReadResponse::BlockHash(state.block_hash(number)?),
+        })
+    }
+}
+
+/// The value read from the state for a [`ReadRequest`].
+#[derive(Debug)]
+enum ReadResponse {
+    Account(Option<AccountInfo>),
+    Code(Bytecode),
+    Storage(U256),
+    BlockHash(B256),
+}
+
+/// The database of a worker that reads the state through the thread that owns it and records
+/// the read accounts and storage slots.
+#[derive(Debug)]
+struct SpeculativeDatabase {
+    /// The index of the worker.
+    worker: usize,
+    /// Sends the reads to the thread that owns the state.
+    requests: mpsc::Sender<(usize, ReadRequest)>,
+    /// Receives the values read from the state.
+    responses: mpsc::Receiver<Result<ReadResponse, ProviderError>>,
+    /// The accounts read so far, which are the same for all transactions.
+    accounts: HashMap<Address, Option<AccountInfo>>,
+    /// The storage slots read so far, which are the same for all transactions.
+    storage: HashMap<(Address, U256), U256>,
+    /// The reads of the current transaction.
+    reads: ReadSet,
+}
+
+impl SpeculativeDatabase {
+    fn new(
+        worker: usize,
+        requests: mpsc::Sender<(usize, ReadRequest)>,
+        responses: mpsc::Receiver<Result<ReadResponse, ProviderError>>,
+    ) -> Self {
+        Self {
+            worker,
+            requests,
+            responses,
+            accounts: HashMap::default(),
+            storage: HashMap::default(),
+            reads: ReadSet::default(),
+        }
+    }
+
+    /// Reads a value from the state.
+    fn request(&self, request: ReadRequest) -> Result<ReadResponse, ProviderError> {
+        // The reads are served until all workers are done, so the channels can't be closed
+        self.requests.send((self.worker, request)).expect("reads are served");
+        self.responses.recv().expect("reads are served")
+    }
+}
+
+impl Database for SpeculativeDatabase {
+    type Error = ProviderError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.reads.accounts.insert(address);
+        if let Some(account) = self.accounts.get(&address) {
+            return Ok(account.clone())
+        }
+
+        let ReadResponse::Account(account) = self.request(ReadRequest::Account(address))? else {
+            unreachable!("account read")
+        };
+        self.accounts.insert(address, account.clone());
+        Ok(account)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let ReadResponse::Code(code) = self.request(ReadRequest::Code(code_hash))? else {
+            unreachable!("code read")
+        };
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.reads.storage.insert((address, index));
+        if let Some(value) = self.storage.get(&(address, index)) {
+            return Ok(*value)
+        }
+
+        let ReadResponse::Storage(value) = self.request(ReadRequest::Storage(address, index))?
+        else {
+            unreachable!("storage read")
+        };
+        self.storage.insert((address, index), value);
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        let ReadResponse::BlockHash(hash) = self.request(ReadRequest::BlockHash(number))? else {
+            unreachable!("block hash read")
+        };
+        Ok(hash)
+    }
+}
+
+/// Detects whether a transaction observes the beneficiary of the block other than by paying the
+/// fee, i.e. reads its balance or code, calls it or is sent by it.
+#[derive(Debug)]
+struct CoinbaseObserver {
+    /// The beneficiary of the block.
+    coinbase: Address,
+    /// Whether the beneficiary was observed by the current transaction.
+    observed: bool,
+}
+
+impl<DB: Database> Inspector<DB> for CoinbaseObserver {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        let observed = match interp.current_opcode() {
+            opcode::BALANCE | opcode::EXTCODESIZE | opcode::EXTCODECOPY | opcode::EXTCODEHASH => {
+                interp.stack.peek(0).is_ok_and(|address| {
+                    Address::from_word(address.to_be_bytes::<32>().into()) == self.coinbase
+                })
+            }
+            opcode::SELFBALANCE => interp.contract.target_address == self.coinbase,
+            _ => false,
+        };
+        self.observed |= observed;
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.observed |= [inputs.target_address, inputs.bytecode_address, inputs.caller]
+            .contains(&self.coinbase);
+        None
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.observed |= inputs.caller == self.coinbase;
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.observed |= outcome.address == Some(self.coinbase);
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, _value: U256) {
+        self.observed |= contract == self.coinbase || target == self.coinbase;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_fallback() {
+        let config = ParallelExecutionConfig::default().with_workers(4).with_min_transactions(8);
+        assert!(config.should_fallback(7));
+        assert!(!config.should_fallback(8));
+        assert!(config.with_workers(1).should_fallback(100));
+        assert!(config.with_min_transactions(0).should_fallback(1));
+    }
+
+    #[test]
+    fn write_conflicts() {
+        let coinbase = Address::with_last_byte(1);
+        let account = Address::with_last_byte(2);
+        let slot = (Address::with_last_byte(3), U256::from(1));
+
+        let mut writes = WriteSet::default();
+        writes.accounts.insert(coinbase);
+        writes.storage.insert(slot);
+
+        let mut reads = ReadSet::default();
+        reads.accounts.insert(coinbase);
+        reads.accounts.insert(account);
+        assert!(writes.conflicts_with(&reads, None));
+        assert!(!writes.conflicts_with(&reads, Some(coinbase)));
+
+        reads.storage.insert((slot.0, U256::from(2)));
+        assert!(!writes.conflicts_with(&reads, Some(coinbase)));
+        writes.wiped.insert(slot.0);
+        assert!(writes.conflicts_with(&reads, Some(coinbase)));
+
+        reads.clear();
+        reads.storage.insert(slot);
+        assert!(writes.conflicts_with(&reads, Some(coinbase)));
+    }
+}
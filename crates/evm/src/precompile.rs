@@ -0,0 +1,135 @@
+//! Custom precompiles support.
+
+use crate::{ConfigureEvm, ConfigureEvmEnv};
+use reth_primitives::{Address, ChainSpec, Header, TransactionSigned, U256};
+use revm::{
+    handler::register::{EvmHandler, HandleRegisterBox},
+    precompile::{PrecompileSpecId, Precompiles},
+    Evm, GetInspector,
+};
+use revm_primitives::{db::Database, BlockEnv, CfgEnvWithHandlerCfg, SpecId, TxEnv};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
+
+/// Provides the precompiles that are available to the EVM, which allows adding new precompiles
+/// or replacing the standard ones, e.g. to change their gas cost.
+///
+/// The same provider must be used for all EVMs of the node, so the results of block execution,
+/// payload building and RPC calls are consistent.
+pub trait PrecompileProvider: Send + Sync + Debug + 'static {
+    /// Modifies the set of precompiles that is active for the given spec.
+    ///
+    /// The given set is initialized with the standard precompiles of the spec.
+    fn precompiles(&self, spec_id: SpecId, precompiles: &mut Precompiles);
+}
+
+/// Returns the handler register that loads the precompiles of the given [`PrecompileProvider`].
+///
+/// The spec id is read from the handler every time the register is applied, so the precompiles
+/// are updated accordingly when the spec id of the EVM is modified. The precompiles of the spec
+/// are assembled once when the register is applied, not every time they are loaded.
+pub fn precompile_provider_handle_register<EXT, DB: Database>(
+    provider: &'static dyn PrecompileProvider,
+) -> HandleRegisterBox<EXT, DB> {
+    Box::new(move |handler: &mut EvmHandler<'_, EXT, DB>| {
+        let spec_id = handler.cfg.spec_id;
+        let mut precompiles = Precompiles::new(PrecompileSpecId::from_spec_id(spec_id)).clone();
+        provider.precompiles(spec_id, &mut precompiles);
+        handler.pre_execution.load_precompiles = Arc::new(move || precompiles.clone().into());
+    })
+}
+
+/// Returns the handler register that moves the precompiles of the EVM to other addresses, keyed
+/// by their original address.
+///
+/// This implements the `movePrecompileTo` semantics of state overrides: once moved, the original
+/// address is a regular account whose code can be overridden. The register wraps the precompiles
+/// loaded by the handlers registered before, so it can be appended to an EVM without knowing its
+/// [`PrecompileProvider`].
+pub fn moved_precompiles_handle_register<EXT, DB: Database>(
+    moves: Arc<HashMap<Address, Address>>,
+) -> HandleRegisterBox<EXT, DB> {
+    Box::new(move |handler: &mut EvmHandler<'_, EXT, DB>| {
+        let load_precompiles = handler.pre_execution.load_precompiles.clone();
+        let moves = moves.clone();
+        handler.pre_execution.load_precompiles = Arc::new(move || {
+            let mut precompiles = load_precompiles();
+            // all precompiles are removed first, so precompiles can swap addresses
+            let moved = moves
+                .iter()
+                .filter_map(|(from, to)| Some((*to, precompiles.remove(from)?)))
+                .collect::<Vec<_>>();
+            precompiles.extend(moved);
+            precompiles
+        });
+    })
+}
+
+/// A [`ConfigureEvm`] that moves the precompiles of the EVMs created by the inner config to other
+/// addresses, see [`moved_precompiles_handle_register`].
+#[derive(Debug, Clone)]
+pub struct MovedPrecompilesEvmConfig<EvmConfig> {
+    /// The config creating the EVMs.
+    inner: EvmConfig,
+    /// The new address of the precompile at every moved address.
+    moves: Arc<HashMap<Address, Address>>,
+}
+
+impl<EvmConfig> MovedPrecompilesEvmConfig<EvmConfig> {
+    /// Creates a new config that moves the precompiles of the inner config, keyed by their
+    /// original address.
+    pub fn new(inner: EvmConfig, moves: HashMap<Address, Address>) -> Self {
+        Self { inner, moves: Arc::new(moves) }
+    }
+}
+
+impl<EvmConfig: ConfigureEvmEnv> ConfigureEvmEnv for MovedPrecompilesEvmConfig<EvmConfig> {
+    fn fill_tx_env(tx_env: &mut TxEnv, transaction: &TransactionSigned, sender: Address) {
+        EvmConfig::fill_tx_env(tx_env, transaction, sender)
+    }
+
+    fn fill_cfg_env(
+        cfg_env: &mut CfgEnvWithHandlerCfg,
+        chain_spec: &ChainSpec,
+        header: &Header,
+        total_difficulty: U256,
+    ) {
+        EvmConfig::fill_cfg_env(cfg_env, chain_spec, header, total_difficulty)
+    }
+
+    fn fill_cfg_and_block_env(
+        cfg: &mut CfgEnvWithHandlerCfg,
+        block_env: &mut BlockEnv,
+        chain_spec: &ChainSpec,
+        header: &Header,
+        total_difficulty: U256,
+    ) {
+        EvmConfig::fill_cfg_and_block_env(cfg, block_env, chain_spec, header, total_difficulty)
+    }
+}
+
+impl<EvmConfig: ConfigureEvm> ConfigureEvm for MovedPrecompilesEvmConfig<EvmConfig> {
+    type DefaultExternalContext<'a> = EvmConfig::DefaultExternalContext<'a>;
+
+    fn evm<'a, DB: Database + 'a>(
+        &'a self,
+        db: DB,
+    ) -> Evm<'a, Self::DefaultExternalContext<'a>, DB> {
+        self.inner
+            .evm(db)
+            .modify()
+            .append_handler_register_box(moved_precompiles_handle_register(self.moves.clone()))
+            .build()
+    }
+
+    fn evm_with_inspector<'a, DB, I>(&'a self, db: DB, inspector: I) -> Evm<'a, I, DB>
+    where
+        DB: Database + 'a,
+        I: GetInspector<DB>,
+    {
+        self.inner
+            .evm_with_inspector(db, inspector)
+            .modify()
+            .append_handler_register_box(moved_precompiles_handle_register(self.moves.clone()))
+            .build()
+    }
+}
@@ -0,0 +1,60 @@
+//! Construction of the receipts of executed transactions.
+
+use reth_primitives::{proofs::calculate_receipt_root_no_memo, Receipt, TransactionSigned, B256};
+use revm_primitives::ExecutionResult;
+use std::fmt::Debug;
+
+/// The executed transaction a receipt is built for.
+#[derive(Debug)]
+pub struct ReceiptBuilderCtx<'a> {
+    /// The executed transaction.
+    pub tx: &'a TransactionSigned,
+    /// The result of the execution of the transaction.
+    pub result: ExecutionResult,
+    /// The gas used by the transaction and all preceding transactions of the block.
+    pub cumulative_gas_used: u64,
+}
+
+/// Builds the receipts of the transactions executed by the block executor.
+///
+/// Chains with additional receipt fields only have to provide their own builder instead of a
+/// block executor.
+pub trait ReceiptBuilder: Debug + Clone + Send + Sync + Unpin + 'static {
+    /// The receipt type of the node.
+    ///
+    /// The consensus fields of the receipt are used to parse the requests of the block.
+    type Receipt: Debug + Clone + Send + Sync + Unpin + AsRef<Receipt> + 'static;
+
+    /// Builds the receipt of an executed transaction.
+    fn build_receipt(&self, ctx: ReceiptBuilderCtx<'_>) -> Self::Receipt;
+
+    /// Calculates the receipts root of the given receipts of a block.
+    ///
+    /// By default, the root is calculated from the consensus fields of the receipts.
+    fn receipts_root(&self, receipts: &[Self::Receipt]) -> B256 {
+        let receipts = receipts.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        calculate_receipt_root_no_memo(&receipts)
+    }
+}
+
+/// Builds the receipts of the ethereum transactions.
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
+pub struct EthReceiptBuilder;
+
+impl ReceiptBuilder for EthReceiptBuilder {
+    type Receipt = Receipt;
+
+    #[allow(clippy::needless_update)] // side-effect of optimism fields
+    fn build_receipt(&self, ctx: ReceiptBuilderCtx<'_>) -> Self::Receipt {
+        let ReceiptBuilderCtx { tx, result, cumulative_gas_used } = ctx;
+        Receipt {
+            tx_type: tx.tx_type(),
+            // Success flag was added in `EIP-658: Embedding transaction status code in receipts`.
+            success: result.is_success(),
+            cumulative_gas_used,
+            logs: result.into_logs(),
+            ..Default::default()
+        }
+    }
+}
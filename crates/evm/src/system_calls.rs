@@ -0,0 +1,599 @@
+//! System contract calls of the pre- and post-block execution changes.
+
+use alloy_eips::{
+    eip2935::{HISTORY_STORAGE_ADDRESS, HISTORY_STORAGE_CODE},
+    eip7002::WithdrawalRequest,
+};
+use alloy_rlp::Buf;
+use reth_execution_errors::{BlockExecutionError, BlockValidationError};
+use reth_primitives::{
+    revm::env::{
+        fill_tx_env_with_beacon_root_contract_call,
+        fill_tx_env_with_withdrawal_requests_contract_call,
+    },
+    Address, ChainSpec, Header, Request, B256, U256,
+};
+use reth_storage_errors::provider::ProviderError;
+use revm::{
+    interpreter::Host,
+    primitives::{
+        Account, AccountInfo, Bytecode, EvmStorageSlot, ExecutionResult, FixedBytes, ResultAndState,
+    },
+    Database, DatabaseCommit, Evm,
+};
+use std::collections::HashMap;
+
+/// Applies the system contract calls of a block, which are required by the active hardforks:
+///
+/// - [EIP-4788] beacon root contract call, before the transactions are executed.
+/// - [EIP-2935] block hashes history update, before the transactions are executed.
+/// - [EIP-7002] withdrawal requests contract call, after the transactions are executed.
+///
+/// The activation of each call is checked against the [`ChainSpec`], and every call can be
+/// disabled for chains that don't support it, e.g. the block hashes update is skipped by
+/// optimism.
+///
+/// [EIP-4788]: https://eips.ethereum.org/EIPS/eip-4788
+/// [EIP-2935]: https://eips.ethereum.org/EIPS/eip-2935
+/// [EIP-7002]: https://eips.ethereum.org/EIPS/eip-7002
+#[derive(Debug, Clone, Copy)]
+pub struct SystemCaller<'a> {
+    chain_spec: &'a ChainSpec,
+    /// Whether the [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) call is applied.
+    beacon_root_contract_call: bool,
+    /// Whether the [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935) update is applied.
+    blockhashes_update: bool,
+    /// Whether the [EIP-7002](https://eips.ethereum.org/EIPS/eip-7002) call is applied.
+    withdrawal_requests_contract_call: bool,
+}
+
+impl<'a> SystemCaller<'a> {
+    /// Creates a new system caller that applies all system calls activated by the chain spec.
+    pub const fn new(chain_spec: &'a ChainSpec) -> Self {
+        Self {
+            chain_spec,
+            beacon_root_contract_call: true,
+            blockhashes_update: true,
+            withdrawal_requests_contract_call: true,
+        }
+    }
+
+    /// Disables the [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) beacon root contract call.
+    pub const fn without_beacon_root_contract_call(mut self) -> Self {
+        self.beacon_root_contract_call = false;
+        self
+    }
+
+    /// Disables the [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935) block hashes update.
+    pub const fn without_blockhashes_update(mut self) -> Self {
+        self.blockhashes_update = false;
+        self
+    }
+
+    /// Disables the [EIP-7002](https://eips.ethereum.org/EIPS/eip-7002) withdrawal requests
+    /// contract call.
+    pub const fn without_withdrawal_requests_contract_call(mut self) -> Self {
+        self.withdrawal_requests_contract_call = false;
+        self
+    }
+
+    /// Applies the system calls that precede the execution of the transactions of the block.
+    ///
+    /// The block environment of the EVM is expected to be configured for the given block. The
+    /// transaction environment is restored after every call.
+    pub fn apply_pre_execution_changes<EXT, DB>(
+        &self,
+        header: &Header,
+        evm: &mut Evm<'_, EXT, DB>,
+    ) -> Result<(), BlockExecutionError>
+    where
+        DB: Database<Error = ProviderError> + DatabaseCommit,
+    {
+        if self.beacon_root_contract_call {
+            apply_beacon_root_contract_call(
+                self.chain_spec,
+                header.timestamp,
+                header.number,
+                header.parent_beacon_block_root,
+                evm,
+            )?;
+        }
+        if self.blockhashes_update {
+            apply_blockhashes_update(
+                evm.db_mut(),
+                self.chain_spec,
+                header.timestamp,
+                header.number,
+                header.parent_hash,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Applies the system calls that follow the execution of the transactions of the block and
+    /// returns the requests emitted by the system contracts.
+    ///
+    /// The deposit requests are not included, since they are collected from the receipts of the
+    /// block rather than from a system call.
+    pub fn apply_post_execution_changes<EXT, DB>(
+        &self,
+        header: &Header,
+        evm: &mut Evm<'_, EXT, DB>,
+    ) -> Result<Vec<Request>, BlockExecutionError>
+    where
+        DB: Database + DatabaseCommit,
+        DB::Error: std::fmt::Display,
+    {
+        if self.withdrawal_requests_contract_call &&
+            self.chain_spec.is_prague_active_at_timestamp(header.timestamp)
+        {
+            apply_withdrawal_requests_contract_call(evm)
+        } else {
+            Ok(vec![])
+        }
+    }
+}
+
+/// The number of block hashes served by the [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935)
+/// history storage contract, which is the length of its ring buffer.
+pub const HISTORY_SERVE_WINDOW: u64 = 8192;
+
+/// Applies the pre-block state change outlined in [EIP-2935] to store historical blockhashes in a
+/// system contract.
+///
+/// If Prague is not activated, or the block is the genesis block, then this is a no-op, and no
+/// state changes are made.
+///
+/// If the provided block is after Prague has been activated, the parent hash will be inserted.
+///
+/// [EIP-2935]: https://eips.ethereum.org/EIPS/eip-2935
+#[inline]
+pub fn apply_blockhashes_update<DB: Database<Error = ProviderError> + DatabaseCommit>(
+    db: &mut DB,
+    chain_spec: &ChainSpec,
+    block_timestamp: u64,
+    block_number: u64,
+    parent_block_hash: B256,
+) -> Result<(), BlockExecutionError>
+where
+    DB::Error: std::fmt::Display,
+{
+    // If Prague is not activated or this is the genesis block, no hashes are added.
+    if !chain_spec.is_prague_active_at_timestamp(block_timestamp) || block_number == 0 {
+        return Ok(())
+    }
+    assert!(block_number > 0);
+
+    // Account is expected to exist either in genesis (for tests) or deployed on mainnet or
+    // testnets.
+    // If the account for any reason does not exist, we create it with the EIP-2935 bytecode and a
+    // nonce of 1, so it does not get deleted.
+    let mut account: Account = db
+        .basic(HISTORY_STORAGE_ADDRESS)
+        .map_err(BlockValidationError::BlockHashAccountLoadingFailed)?
+        .unwrap_or_else(|| AccountInfo {
+            nonce: 1,
+            code: Some(Bytecode::new_raw(HISTORY_STORAGE_CODE.clone())),
+            ..Default::default()
+        })
+        .into();
+
+    // Insert the state change for the slot
+    let (slot, value) = eip2935_block_hash_slot(db, block_number - 1, parent_block_hash)?;
+    account.storage.insert(slot, value);
+
+    // Mark the account as touched and commit the state change
+    account.mark_touch();
+    db.commit(HashMap::from([(HISTORY_STORAGE_ADDRESS, account)]));
+
+    Ok(())
+}
+
+/// Helper function to create a [`EvmStorageSlot`] for [EIP-2935] state transitions for a given
+/// block number.
+///
+/// This calculates the correct storage slot in the `BLOCKHASH` history storage address, fetches the
+/// blockhash and creates a [`EvmStorageSlot`] with appropriate previous and new values.
+fn eip2935_block_hash_slot<DB: Database<Error = ProviderError>>(
+    db: &mut DB,
+    block_number: u64,
+    block_hash: B256,
+) -> Result<(U256, EvmStorageSlot), BlockValidationError> {
+    let slot = U256::from(block_number % HISTORY_SERVE_WINDOW);
+    let current_hash = db
+        .storage(HISTORY_STORAGE_ADDRESS, slot)
+        .map_err(BlockValidationError::BlockHashAccountLoadingFailed)?;
+
+    Ok((slot, EvmStorageSlot::new_changed(current_hash, block_hash.into())))
+}
+
+/// Applies the pre-block call to the [EIP-4788] beacon block root contract, using the given block,
+/// [`ChainSpec`], EVM.
+///
+/// If Cancun is not activated or the block is the genesis block, then this is a no-op, and no
+/// state changes are made.
+///
+/// [EIP-4788]: https://eips.ethereum.org/EIPS/eip-4788
+#[inline]
+pub fn apply_beacon_root_contract_call<EXT, DB: Database + DatabaseCommit>(
+    chain_spec: &ChainSpec,
+    block_timestamp: u64,
+    block_number: u64,
+    parent_beacon_block_root: Option<B256>,
+    evm: &mut Evm<'_, EXT, DB>,
+) -> Result<(), BlockExecutionError>
+where
+    DB::Error: std::fmt::Display,
+{
+    if !chain_spec.is_cancun_active_at_timestamp(block_timestamp) {
+        return Ok(())
+    }
+
+    let parent_beacon_block_root =
+        parent_beacon_block_root.ok_or(BlockValidationError::MissingParentBeaconBlockRoot)?;
+
+    // if the block number is zero (genesis block) then the parent beacon block root must
+    // be 0x0 and no system transaction may occur as per EIP-4788
+    if block_number == 0 {
+        if parent_beacon_block_root != B256::ZERO {
+            return Err(BlockValidationError::CancunGenesisParentBeaconBlockRootNotZero {
+                parent_beacon_block_root,
+            }
+            .into())
+        }
+        return Ok(())
+    }
+
+    // get previous env
+    let previous_env = Box::new(evm.context.env().clone());
+
+    // modify env for pre block call
+    fill_tx_env_with_beacon_root_contract_call(&mut evm.context.evm.env, parent_beacon_block_root);
+
+    let mut state = match evm.transact() {
+        Ok(res) => res.state,
+        Err(e) => {
+            evm.context.evm.env = previous_env;
+            return Err(BlockValidationError::BeaconRootContractCall {
+                block_number,
+                parent_beacon_block_root: Box::new(parent_beacon_block_root),
+                message: e.to_string(),
+            }
+            .into())
+        }
+    };
+
+    state.remove(&alloy_eips::eip4788::SYSTEM_ADDRESS);
+    state.remove(&evm.block().coinbase);
+
+    evm.context.evm.db.commit(state);
+
+    // re-set the previous env
+    evm.context.evm.env = previous_env;
+
+    Ok(())
+}
+
+/// Applies the post-block call to the EIP-7002 withdrawal requests contract.
+///
+/// If Prague is not active at the given timestamp, then this is a no-op, and an empty vector is
+/// returned. Otherwise, the withdrawal requests are returned.
+#[inline]
+pub fn apply_withdrawal_requests_contract_call<EXT, DB: Database + DatabaseCommit>(
+    evm: &mut Evm<'_, EXT, DB>,
+) -> Result<Vec<Request>, BlockExecutionError>
+where
+    DB::Error: std::fmt::Display,
+{
+    let block_number = evm.block().number.saturating_to();
+
+    // get previous env
+    let previous_env = Box::new(evm.context.env().clone());
+
+    // modify env for pre block call
+    fill_tx_env_with_withdrawal_requests_contract_call(&mut evm.context.evm.env);
+
+    let ResultAndState { result, mut state } = match evm.transact() {
+        Ok(res) => res,
+        Err(e) => {
+            evm.context.evm.env = previous_env;
+            return Err(BlockValidationError::WithdrawalRequestsContractCall {
+                block_number,
+                message: format!("execution failed: {e}"),
+            }
+            .into())
+        }
+    };
+
+    // cleanup the state
+    state.remove(&alloy_eips::eip7002::SYSTEM_ADDRESS);
+    state.remove(&evm.block().coinbase);
+    evm.context.evm.db.commit(state);
+
+    // re-set the previous env
+    evm.context.evm.env = previous_env;
+
+    let mut data = match result {
+        ExecutionResult::Success { output, .. } => Ok(output.into_data()),
+        ExecutionResult::Revert { output, .. } => {
+            Err(BlockValidationError::WithdrawalRequestsContractCall {
+                block_number,
+                message: format!("execution reverted: {output}"),
+            })
+        }
+        ExecutionResult::Halt { reason, .. } => {
+            Err(BlockValidationError::WithdrawalRequestsContractCall {
+                block_number,
+                message: format!("execution halted: {reason:?}"),
+            })
+        }
+    }?;
+
+    // Withdrawals are encoded as a series of withdrawal requests, each with the following
+    // format:
+    //
+    // +------+--------+--------+
+    // | addr | pubkey | amount |
+    // +------+--------+--------+
+    //    20      48        8
+
+    const WITHDRAWAL_REQUEST_SIZE: usize = 20 + 48 + 8;
+    let mut withdrawal_requests = Vec::with_capacity(data.len() / WITHDRAWAL_REQUEST_SIZE);
+    while data.has_remaining() {
+        if data.remaining() < WITHDRAWAL_REQUEST_SIZE {
+            return Err(BlockValidationError::WithdrawalRequestsContractCall {
+                block_number,
+                message: "invalid withdrawal request length".to_string(),
+            }
+            .into())
+        }
+
+        let mut source_address = Address::ZERO;
+        data.copy_to_slice(source_address.as_mut_slice());
+
+        let mut validator_public_key = FixedBytes::<48>::ZERO;
+        data.copy_to_slice(validator_public_key.as_mut_slice());
+
+        let amount = data.get_u64();
+
+        withdrawal_requests.push(Request::WithdrawalRequest(WithdrawalRequest {
+            source_address,
+            validator_public_key,
+            amount,
+        }));
+    }
+
+    Ok(withdrawal_requests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::{
+        eip4788::{BEACON_ROOTS_ADDRESS, BEACON_ROOTS_CODE},
+        eip7002::{WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS, WITHDRAWAL_REQUEST_PREDEPLOY_CODE},
+    };
+    use reth_primitives::{
+        constants::ETH_TO_WEI, hex, Bytes, ChainSpecBuilder, ForkCondition, Hardfork, TxKind,
+        MAINNET,
+    };
+    use revm::{
+        db::{CacheDB, EmptyDBTyped},
+        primitives::{fixed_bytes, SpecId},
+    };
+
+    type TestDb = CacheDB<EmptyDBTyped<ProviderError>>;
+
+    fn prague_chain_spec() -> ChainSpec {
+        ChainSpecBuilder::from(&*MAINNET)
+            .cancun_activated()
+            .with_fork(Hardfork::Prague, ForkCondition::Timestamp(0))
+            .build()
+    }
+
+    /// Returns an EVM configured for the given block with the contract deployed at the address.
+    fn evm_with_contract(
+        header: &Header,
+        address: Address,
+        code: Bytes,
+    ) -> Evm<'static, (), TestDb> {
+        let mut db = TestDb::default();
+        db.insert_account_info(
+            address,
+            AccountInfo { nonce: 1, code: Some(Bytecode::new_raw(code)), ..Default::default() },
+        );
+
+        Evm::builder()
+            .with_db(db)
+            .with_spec_id(SpecId::PRAGUE)
+            .modify_block_env(|block| {
+                block.number = U256::from(header.number);
+                block.timestamp = U256::from(header.timestamp);
+            })
+            .build()
+    }
+
+    fn header() -> Header {
+        Header {
+            number: 1,
+            timestamp: 12,
+            parent_hash: B256::with_last_byte(1),
+            parent_beacon_block_root: Some(B256::with_last_byte(2)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pre_execution_changes() {
+        let chain_spec = prague_chain_spec();
+        let header = header();
+        let mut evm = evm_with_contract(&header, BEACON_ROOTS_ADDRESS, BEACON_ROOTS_CODE.clone());
+
+        SystemCaller::new(&chain_spec).apply_pre_execution_changes(&header, &mut evm).unwrap();
+
+        // The timestamp and the beacon root are stored in the ring buffers of the contract
+        let db = evm.db_mut();
+        let history_buffer_length = 8191;
+        assert_eq!(db.storage(BEACON_ROOTS_ADDRESS, U256::from(12)).unwrap(), U256::from(12));
+        assert_eq!(
+            db.storage(BEACON_ROOTS_ADDRESS, U256::from(12 + history_buffer_length)).unwrap(),
+            U256::from(2)
+        );
+
+        // The parent hash is stored in the slot of the parent block
+        assert_eq!(db.storage(HISTORY_STORAGE_ADDRESS, U256::ZERO).unwrap(), U256::from(1));
+    }
+
+    #[test]
+    fn pre_execution_changes_inactive() {
+        let header = header();
+
+        // Prague is not active, so only the beacon root is stored
+        let chain_spec = ChainSpecBuilder::from(&*MAINNET).cancun_activated().build();
+        let mut evm = evm_with_contract(&header, BEACON_ROOTS_ADDRESS, BEACON_ROOTS_CODE.clone());
+        SystemCaller::new(&chain_spec).apply_pre_execution_changes(&header, &mut evm).unwrap();
+        let db = evm.db_mut();
+        assert_eq!(db.storage(BEACON_ROOTS_ADDRESS, U256::from(12)).unwrap(), U256::from(12));
+        assert_eq!(db.storage(HISTORY_STORAGE_ADDRESS, U256::ZERO).unwrap(), U256::ZERO);
+
+        // Both calls are disabled
+        let chain_spec = prague_chain_spec();
+        let mut evm = evm_with_contract(&header, BEACON_ROOTS_ADDRESS, BEACON_ROOTS_CODE.clone());
+        SystemCaller::new(&chain_spec)
+            .without_beacon_root_contract_call()
+            .without_blockhashes_update()
+            .apply_pre_execution_changes(&header, &mut evm)
+            .unwrap();
+        let db = evm.db_mut();
+        assert_eq!(db.storage(BEACON_ROOTS_ADDRESS, U256::from(12)).unwrap(), U256::ZERO);
+        assert_eq!(db.storage(HISTORY_STORAGE_ADDRESS, U256::ZERO).unwrap(), U256::ZERO);
+    }
+
+    #[test]
+    fn post_execution_changes() {
+        let chain_spec = prague_chain_spec();
+        let header = header();
+
+        // RETURN(0, 76), i.e. a single withdrawal request with all fields set to zero
+        let code = Bytes::from_static(&hex!("604c6000f3"));
+        let mut evm = evm_with_contract(&header, WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS, code);
+
+        let requests =
+            SystemCaller::new(&chain_spec).apply_post_execution_changes(&header, &mut evm).unwrap();
+        assert_eq!(
+            requests,
+            vec![Request::WithdrawalRequest(WithdrawalRequest {
+                source_address: Address::ZERO,
+                validator_public_key: FixedBytes::ZERO,
+                amount: 0,
+            })]
+        );
+
+        // The call is not applied if disabled or before Prague
+        let requests = SystemCaller::new(&chain_spec)
+            .without_withdrawal_requests_contract_call()
+            .apply_post_execution_changes(&header, &mut evm)
+            .unwrap();
+        assert!(requests.is_empty());
+        let requests =
+            SystemCaller::new(&MAINNET).apply_post_execution_changes(&header, &mut evm).unwrap();
+        assert!(requests.is_empty());
+    }
+
+    #[test]
+    fn beacon_root_contract_call_mainnet_activation() {
+        let ForkCondition::Timestamp(cancun) = MAINNET.fork(Hardfork::Cancun) else {
+            panic!("cancun is activated by timestamp on mainnet")
+        };
+        let history_buffer_length = 8191;
+
+        for (timestamp, activated) in [(cancun - 12, false), (cancun, true), (cancun + 12, true)] {
+            let header = Header {
+                timestamp,
+                parent_beacon_block_root: Some(B256::with_last_byte(2)),
+                ..header()
+            };
+            let mut evm =
+                evm_with_contract(&header, BEACON_ROOTS_ADDRESS, BEACON_ROOTS_CODE.clone());
+            SystemCaller::new(&MAINNET).apply_pre_execution_changes(&header, &mut evm).unwrap();
+
+            let timestamp_index = U256::from(timestamp % history_buffer_length);
+            let root_index = timestamp_index + U256::from(history_buffer_length);
+            let db = evm.db_mut();
+            let (expected_timestamp, expected_root) =
+                if activated { (U256::from(timestamp), U256::from(2)) } else { Default::default() };
+            assert_eq!(
+                db.storage(BEACON_ROOTS_ADDRESS, timestamp_index).unwrap(),
+                expected_timestamp
+            );
+            assert_eq!(db.storage(BEACON_ROOTS_ADDRESS, root_index).unwrap(), expected_root);
+            // prague is not scheduled on mainnet
+            assert_eq!(db.storage(HISTORY_STORAGE_ADDRESS, U256::ZERO).unwrap(), U256::ZERO);
+        }
+    }
+
+    #[test]
+    fn post_execution_changes_withdrawal_request_predeploy() {
+        let chain_spec = prague_chain_spec();
+        let header = header();
+        let mut evm = evm_with_contract(
+            &header,
+            WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+            WITHDRAWAL_REQUEST_PREDEPLOY_CODE.clone(),
+        );
+
+        // https://github.com/lightclient/7002asm/blob/e0d68e04d15f25057af7b6d180423d94b6b3bdb3/test/Contract.t.sol.in#L49-L64
+        let sender = Address::with_last_byte(0xaa);
+        let validator_public_key = fixed_bytes!("111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111");
+        let withdrawal_amount = fixed_bytes!("2222222222222222");
+        evm.db_mut().insert_account_info(
+            sender,
+            AccountInfo { balance: U256::from(ETH_TO_WEI), ..Default::default() },
+        );
+        evm.tx_mut().caller = sender;
+        evm.tx_mut().transact_to = TxKind::Call(WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS);
+        // `MIN_WITHDRAWAL_REQUEST_FEE`
+        evm.tx_mut().value = U256::from(1);
+        evm.tx_mut().data = [&validator_public_key[..], &withdrawal_amount[..]].concat().into();
+        evm.tx_mut().gas_limit = 1_000_000;
+        assert!(evm.transact_commit().unwrap().is_success());
+
+        let requests =
+            SystemCaller::new(&chain_spec).apply_post_execution_changes(&header, &mut evm).unwrap();
+        assert_eq!(
+            requests,
+            vec![Request::WithdrawalRequest(WithdrawalRequest {
+                source_address: sender,
+                validator_public_key,
+                amount: u64::from_be_bytes(withdrawal_amount.into()),
+            })]
+        );
+
+        // The queue of the contract is drained by the call
+        let requests =
+            SystemCaller::new(&chain_spec).apply_post_execution_changes(&header, &mut evm).unwrap();
+        assert!(requests.is_empty());
+    }
+
+    #[test]
+    fn post_execution_changes_revert() {
+        let chain_spec = prague_chain_spec();
+        let header = header();
+
+        // REVERT(0, 0)
+        let code = Bytes::from_static(&hex!("60006000fd"));
+        let mut evm = evm_with_contract(&header, WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS, code);
+
+        let err = SystemCaller::new(&chain_spec)
+            .apply_post_execution_changes(&header, &mut evm)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            BlockExecutionError::Validation(BlockValidationError::WithdrawalRequestsContractCall {
+                block_number: 1,
+                ..
+            })
+        ));
+    }
+}
@@ -2,9 +2,13 @@ use std::fmt::Debug;
 
 use reth_node_api::{FullNodeComponents, FullNodeTypes, NodeTypes};
 use reth_node_core::node_config::NodeConfig;
+use reth_payload_builder::PayloadEvents;
 use reth_primitives::Head;
 use reth_tasks::TaskExecutor;
-use tokio::sync::mpsc::{Receiver, UnboundedSender};
+use tokio::sync::{
+    mpsc::{Receiver, UnboundedSender},
+    oneshot::error::RecvError,
+};
 
 use crate::{ExExEvent, ExExNotification};
 
@@ -49,6 +53,16 @@ impl<Node: FullNodeComponents> Debug for ExExContext<Node> {
     }
 }
 
+impl<Node: FullNodeComponents> ExExContext<Node> {
+    /// Subscribes to the events of the payload builder: the payload attributes of every payload
+    /// job and the best payload of every job, once it is resolved or ends.
+    ///
+    /// Events are dropped instead of blocking the payload builder if the `ExEx` falls behind.
+    pub async fn payload_events(&self) -> Result<PayloadEvents<Node::Engine>, RecvError> {
+        self.components.payload_builder().subscribe().await
+    }
+}
+
 impl<Node: FullNodeComponents> NodeTypes for ExExContext<Node> {
     type Primitives = Node::Primitives;
     type Engine = Node::Engine;
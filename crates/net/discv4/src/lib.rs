@@ -402,6 +402,14 @@ impl Discv4 {
         });
     }
 
+    /// Returns the EIP-868 [`Enr`] the service announces, including all configured pairs.
+    pub async fn local_eip868_enr(&self) -> Result<Enr<SecretKey>, Discv4Error> {
+        let (tx, rx) = oneshot::channel();
+        let cmd = Discv4Command::LocalEnr(tx);
+        self.to_service.send(cmd)?;
+        Ok(rx.await?)
+    }
+
     /// Returns the receiver half of new listener channel that streams [`DiscoveryUpdate`]s.
     pub async fn update_stream(&self) -> Result<ReceiverStream<DiscoveryUpdate>, Discv4Error> {
         let (tx, rx) = oneshot::channel();
@@ -1624,6 +1632,9 @@ impl Discv4Service {
                         let rx = self.update_stream();
                         let _ = tx.send(rx);
                     }
+                    Discv4Command::LocalEnr(tx) => {
+                        let _ = tx.send(self.local_eip_868_enr.clone());
+                    }
                     Discv4Command::BanPeer(node_id) => self.ban_node(node_id),
                     Discv4Command::Remove(node_id) => {
                         self.remove_node(node_id);
@@ -1938,6 +1949,7 @@ enum Discv4Command {
     Lookup { node_id: Option<PeerId>, tx: Option<NodeRecordSender> },
     SetLookupInterval(Duration),
     Updates(OneshotSender<ReceiverStream<DiscoveryUpdate>>),
+    LocalEnr(OneshotSender<Enr<SecretKey>>),
     Terminated,
 }
 
@@ -2307,6 +2319,18 @@ mod tests {
         assert_eq!(expected, decoded);
     }
 
+    #[tokio::test]
+    async fn test_local_eip868_enr() {
+        let (discv4, service) = create_discv4().await;
+        let _handle = service.spawn();
+
+        let fork = ForkId { hash: ForkHash([0xdc, 0xe9, 0x6c, 0x2d]), next: 0 };
+        discv4.set_eip868_rlp(b"eth".to_vec(), EnrForkIdEntry::from(fork));
+        let enr = discv4.local_eip868_enr().await.unwrap();
+        let eth = enr.get_raw_rlp(b"eth").unwrap();
+        assert_eq!(EnrForkIdEntry::decode(&mut &eth[..]).unwrap().fork_id, fork);
+    }
+
     #[test]
     fn test_enr_forkid_entry_decode() {
         let raw: [u8; 8] = [0xc7, 0xc6, 0x84, 0xdc, 0xe9, 0x6c, 0x2d, 0x80];
@@ -13,7 +13,10 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
-use reth_eth_wire::{capability::Capabilities, DisconnectReason, EthVersion, Status};
+use reth_eth_wire::{
+    capability::{Capabilities, Capability},
+    DisconnectReason, EthVersion, Status,
+};
 use reth_rpc_types::NetworkStatus;
 use std::{future::Future, net::SocketAddr, sync::Arc, time::Instant};
 
@@ -62,6 +65,14 @@ pub trait PeersInfo: Send + Sync {
 
     /// Returns the local ENR of the node.
     fn local_enr(&self) -> enr::Enr<enr::secp256k1::SecretKey>;
+
+    /// Returns the ENR the node announces in discovery, which includes the
+    /// [EIP-868](https://eips.ethereum.org/EIPS/eip-868) entries like the `eth` fork id.
+    ///
+    /// This is the same as [`Self::local_enr`] if discovery is disabled.
+    fn discovery_enr(
+        &self,
+    ) -> impl Future<Output = Result<enr::Enr<enr::secp256k1::SecretKey>, NetworkError>> + Send;
 }
 
 /// Provides an API for managing the peers of the network.
@@ -84,6 +95,15 @@ pub trait Peers: PeersInfo {
     /// Adds a peer to the known peer set, with the given kind.
     fn add_peer_kind(&self, peer: PeerId, kind: PeerKind, addr: SocketAddr);
 
+    /// Adds a peer to the peer set and dials it right away, even if all outbound slots are in use.
+    fn connect_peer(&self, peer: PeerId, addr: SocketAddr) {
+        self.connect_peer_kind(peer, PeerKind::Basic, addr);
+    }
+
+    /// Adds a peer to the peer set with the given kind and dials it right away, even if all
+    /// outbound slots are in use.
+    fn connect_peer_kind(&self, peer: PeerId, kind: PeerKind, addr: SocketAddr);
+
     /// Returns the rpc [`PeerInfo`] for all connected [`PeerKind::Trusted`] peers.
     fn get_trusted_peers(
         &self,
@@ -168,6 +188,8 @@ impl PeerKind {
 pub struct PeerInfo {
     /// Announced capabilities of the peer
     pub capabilities: Arc<Capabilities>,
+    /// The capabilities shared with the peer, with their negotiated versions.
+    pub shared_capabilities: Arc<[Capability]>,
     /// The identifier of the remote peer
     pub remote_id: PeerId,
     /// The client's name and version
@@ -184,6 +206,10 @@ pub struct PeerInfo {
     pub status: Arc<Status>,
     /// The timestamp when the session to that peer has been established.
     pub session_established: Instant,
+    /// The kind of the peer in the peer set.
+    pub kind: PeerKind,
+    /// The reputation of the peer, `None` if the peer set doesn't track it.
+    pub reputation: Option<Reputation>,
 }
 
 /// The direction of the connection.
@@ -66,6 +66,10 @@ impl PeersInfo for NoopNetwork {
         let sk = SecretKey::from_slice(&[0xcd; 32]).unwrap();
         Enr::builder().build(&sk).unwrap()
     }
+
+    async fn discovery_enr(&self) -> Result<Enr<SecretKey>, NetworkError> {
+        Ok(self.local_enr())
+    }
 }
 
 impl Peers for NoopNetwork {
@@ -73,6 +77,8 @@ impl Peers for NoopNetwork {
 
     fn add_peer_kind(&self, _peer: PeerId, _kind: PeerKind, _addr: SocketAddr) {}
 
+    fn connect_peer_kind(&self, _peer: PeerId, _kind: PeerKind, _addr: SocketAddr) {}
+
     async fn get_peers_by_kind(&self, _kind: PeerKind) -> Result<Vec<PeerInfo>, NetworkError> {
         Ok(vec![])
     }
@@ -2,7 +2,7 @@
 
 use crate::{
     eth_requests::EthRequestHandler,
-    transactions::{TransactionsManager, TransactionsManagerConfig},
+    transactions::{AnnouncementPolicy, TransactionsManager, TransactionsManagerConfig},
     NetworkHandle, NetworkManager,
 };
 use reth_transaction_pool::TransactionPool;
@@ -78,3 +78,15 @@ impl<C, Tx, Eth> NetworkBuilder<C, Tx, Eth> {
         NetworkBuilder { network, request_handler, transactions }
     }
 }
+
+impl<C, Pool, Eth> NetworkBuilder<C, TransactionsManager<Pool>, Eth>
+where
+    Pool: TransactionPool,
+{
+    /// Sets the [`AnnouncementPolicy`] of the configured [`TransactionsManager`].
+    pub fn announcement_policy<P: AnnouncementPolicy>(self, policy: P) -> Self {
+        let Self { network, transactions, request_handler } = self;
+        let transactions = transactions.with_announcement_policy(policy);
+        NetworkBuilder { network, request_handler, transactions }
+    }
+}
@@ -43,7 +43,7 @@ use reth_eth_wire::{
 };
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
 use reth_net_common::bandwidth_meter::BandwidthMeter;
-use reth_network_api::ReputationChangeKind;
+use reth_network_api::{PeerInfo, ReputationChangeKind};
 use reth_network_types::PeerId;
 use reth_primitives::{ForkId, NodeRecord};
 use reth_provider::{BlockNumReader, BlockReader};
@@ -535,6 +535,19 @@ where
         }
     }
 
+    /// Sets the kind and the reputation the peer set tracks for the peer of a session.
+    fn with_peer_set_info(&self, mut info: PeerInfo) -> PeerInfo {
+        let peers = self.swarm.state().peers();
+        info.kind = peers.peer_kind(&info.remote_id);
+        info.reputation = peers.get_reputation(&info.remote_id);
+        info
+    }
+
+    /// Sets the kind and the reputation the peer set tracks for the peers of the sessions.
+    fn with_peer_set_infos(&self, infos: Vec<PeerInfo>) -> Vec<PeerInfo> {
+        infos.into_iter().map(|info| self.with_peer_set_info(info)).collect()
+    }
+
     /// Handler for received messages from a handle
     fn on_handle_message(&mut self, msg: NetworkHandleMessage) {
         match msg {
@@ -569,6 +582,12 @@ where
                     self.swarm.state_mut().add_peer_kind(peer, kind, addr);
                 }
             }
+            NetworkHandleMessage::ConnectPeer(peer, kind, addr) => {
+                // only connect to the peer if we are not shutting down
+                if !self.swarm.is_shutting_down() {
+                    self.swarm.state_mut().add_and_connect_kind(peer, kind, addr);
+                }
+            }
             NetworkHandleMessage::RemovePeer(peer_id, kind) => {
                 self.swarm.state_mut().remove_peer(peer_id, kind);
             }
@@ -612,17 +631,21 @@ where
                 }
             }
             NetworkHandleMessage::GetPeerInfos(tx) => {
-                let _ = tx.send(self.swarm.sessions_mut().get_peer_info());
+                let peers = self.swarm.sessions_mut().get_peer_info();
+                let _ = tx.send(self.with_peer_set_infos(peers));
             }
             NetworkHandleMessage::GetPeerInfoById(peer_id, tx) => {
-                let _ = tx.send(self.swarm.sessions_mut().get_peer_info_by_id(peer_id));
+                let peer = self.swarm.sessions_mut().get_peer_info_by_id(peer_id);
+                let _ = tx.send(peer.map(|peer| self.with_peer_set_info(peer)));
             }
             NetworkHandleMessage::GetPeerInfosByIds(peer_ids, tx) => {
-                let _ = tx.send(self.swarm.sessions().get_peer_infos_by_ids(peer_ids));
+                let peers = self.swarm.sessions().get_peer_infos_by_ids(peer_ids);
+                let _ = tx.send(self.with_peer_set_infos(peers));
             }
             NetworkHandleMessage::GetPeerInfosByPeerKind(kind, tx) => {
                 let peers = self.swarm.state().peers().peers_by_kind(kind);
-                let _ = tx.send(self.swarm.sessions().get_peer_infos_by_ids(peers));
+                let peers = self.swarm.sessions().get_peer_infos_by_ids(peers);
+                let _ = tx.send(self.with_peer_set_infos(peers));
             }
             NetworkHandleMessage::AddRlpxSubProtocol(proto) => self.add_rlpx_sub_protocol(proto),
             NetworkHandleMessage::GetTransactionsHandle(tx) => {
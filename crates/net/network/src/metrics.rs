@@ -85,6 +85,17 @@ pub struct SessionManagerMetrics {
     pub(crate) total_dial_successes: Counter,
 }
 
+/// Metrics for the decisions of an
+/// [`AnnouncementPolicy`](crate::transactions::policy::AnnouncementPolicy), labeled by policy.
+#[derive(Metrics)]
+#[metrics(scope = "network.transactions.announcement_policy")]
+pub struct AnnouncementPolicyMetrics {
+    /// Total number of transactions the policy decided to announce
+    pub(crate) announced: Counter,
+    /// Total number of transactions the policy decided to skip
+    pub(crate) skipped: Counter,
+}
+
 /// Metrics for the [`TransactionsManager`](crate::transactions::TransactionsManager).
 #[derive(Metrics)]
 #[metrics(scope = "network")]
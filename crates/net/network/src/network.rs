@@ -257,6 +257,15 @@ impl PeersInfo for NetworkHandle {
         }
         builder.build(&self.inner.secret_key).expect("valid enr")
     }
+
+    async fn discovery_enr(&self) -> Result<Enr<SecretKey>, NetworkError> {
+        match &self.inner.discv4 {
+            Some(discv4) => {
+                discv4.local_eip868_enr().await.map_err(|_| NetworkError::ChannelClosed)
+            }
+            None => Ok(self.local_enr()),
+        }
+    }
 }
 
 impl Peers for NetworkHandle {
@@ -270,6 +279,12 @@ impl Peers for NetworkHandle {
         self.send_message(NetworkHandleMessage::AddPeerAddress(peer, kind, addr));
     }
 
+    /// Sends a message to the [`NetworkManager`](crate::NetworkManager) to add a peer to the known
+    /// set, with the given kind, and to dial it.
+    fn connect_peer_kind(&self, peer: PeerId, kind: PeerKind, addr: SocketAddr) {
+        self.send_message(NetworkHandleMessage::ConnectPeer(peer, kind, addr));
+    }
+
     async fn get_peers_by_kind(&self, kind: PeerKind) -> Result<Vec<PeerInfo>, NetworkError> {
         let (tx, rx) = oneshot::channel();
         let _ = self.manager().send(NetworkHandleMessage::GetPeerInfosByPeerKind(kind, tx));
@@ -432,6 +447,8 @@ pub(crate) enum NetworkHandleMessage {
     AddTrustedPeerId(PeerId),
     /// Adds an address for a peer, including its ID, kind, and socket address.
     AddPeerAddress(PeerId, PeerKind, SocketAddr),
+    /// Adds a peer to the peerset with the given kind and dials it.
+    ConnectPeer(PeerId, PeerKind, SocketAddr),
     /// Removes a peer from the peerset corresponding to the given kind.
     RemovePeer(PeerId, PeerKind),
     /// Disconnects a connection to a peer if it exists, optionally providing a disconnect reason.
@@ -669,6 +669,17 @@ impl PeersManager {
     /// Marks the given peer as trusted.
     pub(crate) fn add_trusted_peer_id(&mut self, peer_id: PeerId) {
         self.trusted_peer_ids.insert(peer_id);
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.kind = PeerKind::Trusted;
+        }
+    }
+
+    /// Returns the kind of the peer, trusted if it's in the trusted set even if it's not tracked.
+    pub(crate) fn peer_kind(&self, peer_id: &PeerId) -> PeerKind {
+        if self.trusted_peer_ids.contains(peer_id) {
+            return PeerKind::Trusted
+        }
+        self.peers.get(peer_id).map(|peer| peer.kind).unwrap_or_default()
     }
 
     /// Called for a newly discovered trusted peer.
@@ -748,17 +759,40 @@ impl PeersManager {
         }
     }
 
-    /// Removes the tracked node from the trusted set.
+    /// Removes the node from the trusted set.
+    ///
+    /// This also removes peers that were only marked as trusted by their id.
     pub(crate) fn remove_peer_from_trusted_set(&mut self, peer_id: PeerId) {
-        let Entry::Occupied(mut entry) = self.peers.entry(peer_id) else { return };
-        if !entry.get().is_trusted() {
+        self.trusted_peer_ids.remove(&peer_id);
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.kind = PeerKind::Basic;
+        }
+    }
+
+    /// Adds the peer with the given kind and dials it right away, even if all outbound slots are
+    /// in use.
+    ///
+    /// This is a noop for banned peers and peers that are already connected or being dialed.
+    pub(crate) fn add_and_connect_kind(
+        &mut self,
+        peer_id: PeerId,
+        kind: PeerKind,
+        addr: SocketAddr,
+    ) {
+        if self.ban_list.is_banned(&peer_id, &addr.ip()) || !self.net_connection_state.is_active() {
             return
         }
 
-        let peer = entry.get_mut();
-        peer.kind = PeerKind::Basic;
+        self.add_peer_kind(peer_id, kind, addr, None);
+        let Some(peer) = self.peers.get_mut(&peer_id) else { return };
+        if !peer.state.is_unconnected() {
+            return
+        }
 
-        self.trusted_peer_ids.remove(&peer_id);
+        trace!(target: "net::peers", ?peer_id, ?addr, "schedule requested outbound connection");
+        peer.state = PeerConnectionState::PendingOut;
+        self.connection_info.inc_pending_out();
+        self.queued_actions.push_back(PeerAction::Connect { peer_id, remote_addr: addr });
     }
 
     /// Returns the idle peer with the highest reputation.
@@ -2514,6 +2548,55 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_add_and_connect_without_outbound_capacity() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::new(PeersConfig::test().with_max_outbound(0));
+        peers.add_and_connect_kind(peer, PeerKind::Basic, socket_addr);
+
+        match event!(peers) {
+            PeerAction::PeerAdded(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, remote_addr } => {
+                assert_eq!(peer_id, peer);
+                assert_eq!(remote_addr, socket_addr);
+            }
+            _ => unreachable!(),
+        }
+
+        // the peer is already being dialed
+        peers.add_and_connect_kind(peer, PeerKind::Basic, socket_addr);
+        assert!(peers.queued_actions.is_empty());
+        assert_eq!(peers.connection_info.num_pending_out, 1);
+    }
+
+    #[tokio::test]
+    async fn test_trusted_peer_id_runtime_changes() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::new(PeersConfig::test().with_max_outbound(0));
+
+        // a peer that is only known by its id
+        peers.add_trusted_peer_id(peer);
+        assert_eq!(peers.peer_kind(&peer), PeerKind::Trusted);
+        peers.remove_peer_from_trusted_set(peer);
+        assert_eq!(peers.peer_kind(&peer), PeerKind::Basic);
+        assert!(!peers.trusted_peer_ids.contains(&peer));
+
+        // a tracked peer becomes trusted
+        peers.add_peer(peer, socket_addr, None);
+        peers.add_trusted_peer_id(peer);
+        assert!(peers.peers.get(&peer).unwrap().is_trusted());
+        peers.remove_peer_from_trusted_set(peer);
+        assert!(!peers.peers.get(&peer).unwrap().is_trusted());
+        assert_eq!(peers.peer_kind(&peer), PeerKind::Basic);
+    }
+
     #[tokio::test]
     async fn test_connect_trusted_nodes_only() {
         let trusted_peer = PeerId::random();
@@ -7,11 +7,11 @@ use crate::{
 };
 use reth_ecies::ECIESError;
 use reth_eth_wire::{
-    capability::{Capabilities, CapabilityMessage},
+    capability::{Capabilities, Capability, CapabilityMessage},
     errors::EthStreamError,
     DisconnectReason, EthVersion, Status,
 };
-use reth_network_api::PeerInfo;
+use reth_network_api::{PeerInfo, PeerKind};
 use reth_network_types::PeerId;
 use std::{io, net::SocketAddr, sync::Arc, time::Instant};
 use tokio::sync::{
@@ -65,6 +65,8 @@ pub struct ActiveSessionHandle {
     pub(crate) established: Instant,
     /// Announced capabilities of the peer.
     pub(crate) capabilities: Arc<Capabilities>,
+    /// The capabilities shared with the peer, with their negotiated versions.
+    pub(crate) shared_capabilities: Arc<[Capability]>,
     /// Sender half of the command channel used send commands _to_ the spawned session
     pub(crate) commands_to_session: mpsc::Sender<SessionCommand>,
     /// The client's name and version
@@ -143,10 +145,14 @@ impl ActiveSessionHandle {
             remote_addr: self.remote_addr,
             local_addr: self.local_addr,
             capabilities: self.capabilities.clone(),
+            shared_capabilities: self.shared_capabilities.clone(),
             client_version: self.client_version.clone(),
             eth_version: self.version,
             status: self.status.clone(),
             session_established: self.established,
+            // tracked by the peer set
+            kind: PeerKind::Basic,
+            reputation: None,
         }
     }
 }
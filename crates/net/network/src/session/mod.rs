@@ -473,6 +473,12 @@ impl SessionManager {
 
                 // negotiated version
                 let version = conn.version();
+                let shared_capabilities = conn
+                    .inner()
+                    .shared_capabilities()
+                    .iter_caps()
+                    .map(|cap| cap.capability().into_owned())
+                    .collect();
 
                 let session = ActiveSession {
                     next_id: 0,
@@ -507,6 +513,7 @@ impl SessionManager {
                     version,
                     established: Instant::now(),
                     capabilities: Arc::clone(&capabilities),
+                    shared_capabilities,
                     commands_to_session,
                     client_version: Arc::clone(&client_version),
                     remote_addr,
@@ -278,6 +278,16 @@ where
         self.peers_manager.add_peer_kind(peer_id, kind, addr, None)
     }
 
+    /// Adds a peer and its address with the given kind to the peerset and dials it.
+    pub(crate) fn add_and_connect_kind(
+        &mut self,
+        peer_id: PeerId,
+        kind: PeerKind,
+        addr: SocketAddr,
+    ) {
+        self.peers_manager.add_and_connect_kind(peer_id, kind, addr)
+    }
+
     pub(crate) fn remove_peer(&mut self, peer_id: PeerId, kind: PeerKind) {
         match kind {
             PeerKind::Basic => self.peers_manager.remove_peer(peer_id),
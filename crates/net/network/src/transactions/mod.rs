@@ -28,7 +28,8 @@ use reth_network_p2p::{
 };
 use reth_network_types::PeerId;
 use reth_primitives::{
-    FromRecoveredPooledTransaction, PooledTransactionsElement, TransactionSigned, TxHash, B256,
+    FromRecoveredPooledTransaction, PooledTransactionsElement, TransactionSigned, TxHash, TxType,
+    B256,
 };
 use reth_tokio_util::EventStream;
 use reth_transaction_pool::{
@@ -56,11 +57,17 @@ pub mod config;
 pub mod constants;
 /// Component responsible for fetching transactions from [`NewPooledTransactionHashes`].
 pub mod fetcher;
+pub mod policy;
 pub mod validation;
 pub use config::{TransactionFetcherConfig, TransactionsManagerConfig};
+pub use policy::{
+    AnnounceAll, AnnouncementCandidate, AnnouncementDecision, AnnouncementPolicy,
+    SkipBlobsToInactivePeers,
+};
 
 use constants::SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE;
 pub(crate) use fetcher::{FetchEvent, TransactionFetcher};
+use policy::AnnouncementFilter;
 pub use validation::*;
 
 pub use self::constants::{
@@ -244,6 +251,8 @@ pub struct TransactionsManager<Pool> {
     pending_transactions: ReceiverStream<TxHash>,
     /// Incoming events from the [`NetworkManager`](crate::NetworkManager).
     transaction_events: UnboundedMeteredReceiver<NetworkTransactionEvent>,
+    /// Decides which transactions are announced to `eth/68` peers.
+    announcement_filter: AnnouncementFilter,
     /// `TransactionsManager` metrics
     metrics: TransactionsManagerMetrics,
 }
@@ -294,9 +303,19 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
                 from_network,
                 NETWORK_POOL_TRANSACTIONS_SCOPE,
             ),
+            announcement_filter: Default::default(),
             metrics,
         }
     }
+
+    /// Sets the [`AnnouncementPolicy`] that is consulted before announcing transaction hashes to
+    /// `eth/68` peers.
+    ///
+    /// By default, all transactions are announced, see [`AnnounceAll`].
+    pub fn with_announcement_policy<P: AnnouncementPolicy>(mut self, policy: P) -> Self {
+        self.announcement_filter = AnnouncementFilter::new(Box::new(policy));
+        self
+    }
 }
 
 // === impl TransactionsManager ===
@@ -364,6 +383,12 @@ where
             // transactions
             peer.seen_transactions.extend(transactions.iter().map(|tx| *tx.hash()));
 
+            // serving requests never consults the announcement policy, it is only informed
+            let served_blob = transactions.iter().any(|tx| tx.is_eip4844());
+            self.announcement_filter
+                .policy_mut()
+                .on_pooled_transactions_served(peer_id, served_blob);
+
             let resp = PooledTransactions(transactions);
             let _ = response.send(Ok(resp));
         }
@@ -420,6 +445,8 @@ where
         // number of connected peers)
         let max_num_full = (self.peers.len() as f64).sqrt() as usize + 1;
 
+        let base_fee = self.pool.block_info().pending_basefee;
+
         // Note: Assuming ~random~ order due to random state of the peers map hasher
         for (peer_idx, (peer_id, peer)) in self.peers.iter_mut().enumerate() {
             // filter all transactions unknown to the peer
@@ -430,58 +457,69 @@ where
             // transaction lists, before deciding whether or not to send full transactions to the
             // peer.
             for tx in &to_propagate {
-                if peer.seen_transactions.insert(tx.hash()) {
-                    hashes.push(tx);
-
-                    // Do not send full 4844 transaction hashes to peers.
-                    //
-                    //  Nodes MUST NOT automatically broadcast blob transactions to their peers.
-                    //  Instead, those transactions are only announced using
-                    //  `NewPooledTransactionHashes` messages, and can then be manually requested
-                    //  via `GetPooledTransactions`.
-                    //
-                    // From: <https://eips.ethereum.org/EIPS/eip-4844#networking>
-                    if !tx.transaction.is_eip4844() {
-                        full_transactions.push(tx);
-                    }
+                if peer.seen_transactions.contains(&tx.hash()) {
+                    continue
+                }
+
+                // transactions skipped by the announcement policy are not marked as seen, so they
+                // can still be announced to the peer later
+                if hashes.is_eth68() &&
+                    !self
+                        .announcement_filter
+                        .should_announce(peer_id, &tx.announcement_candidate(base_fee))
+                {
+                    continue
+                }
+
+                peer.seen_transactions.insert(tx.hash());
+                hashes.push(tx);
+
+                // Do not send full 4844 transaction hashes to peers.
+                //
+                //  Nodes MUST NOT automatically broadcast blob transactions to their peers.
+                //  Instead, those transactions are only announced using
+                //  `NewPooledTransactionHashes` messages, and can then be manually requested
+                //  via `GetPooledTransactions`.
+                //
+                // From: <https://eips.ethereum.org/EIPS/eip-4844#networking>
+                if !tx.transaction.is_eip4844() {
+                    full_transactions.push(tx);
                 }
             }
             let mut new_pooled_hashes = hashes.build();
 
-            if !new_pooled_hashes.is_empty() {
-                // determine whether to send full tx objects or hashes. If there are no full
-                // transactions, try to send hashes.
-                if peer_idx > max_num_full || full_transactions.is_empty() {
-                    // enforce tx soft limit per message for the (unlikely) event the number of
-                    // hashes exceeds it
-                    new_pooled_hashes.truncate(
-                        SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE,
-                    );
-
-                    for hash in new_pooled_hashes.iter_hashes().copied() {
-                        propagated.0.entry(hash).or_default().push(PropagateKind::Hash(*peer_id));
-                    }
+            if new_pooled_hashes.is_empty() && full_transactions.is_empty() {
+                // nothing to propagate
+                continue
+            }
 
-                    trace!(target: "net::tx", ?peer_id, num_txs=?new_pooled_hashes.len(), "Propagating tx hashes to peer");
+            // determine whether to send full tx objects or hashes. If there are no full
+            // transactions, try to send hashes.
+            if peer_idx > max_num_full || full_transactions.is_empty() {
+                // enforce tx soft limit per message for the (unlikely) event the number of
+                // hashes exceeds it
+                new_pooled_hashes
+                    .truncate(SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE);
 
-                    // send hashes of transactions
-                    self.network.send_transactions_hashes(*peer_id, new_pooled_hashes);
-                } else {
-                    let new_full_transactions = full_transactions.build();
+                for hash in new_pooled_hashes.iter_hashes().copied() {
+                    propagated.0.entry(hash).or_default().push(PropagateKind::Hash(*peer_id));
+                }
 
-                    for tx in &new_full_transactions {
-                        propagated
-                            .0
-                            .entry(tx.hash())
-                            .or_default()
-                            .push(PropagateKind::Full(*peer_id));
-                    }
+                trace!(target: "net::tx", ?peer_id, num_txs=?new_pooled_hashes.len(), "Propagating tx hashes to peer");
 
-                    trace!(target: "net::tx", ?peer_id, num_txs=?new_full_transactions.len(), "Propagating full transactions to peer");
+                // send hashes of transactions
+                self.network.send_transactions_hashes(*peer_id, new_pooled_hashes);
+            } else {
+                let new_full_transactions = full_transactions.build();
 
-                    // send full transactions
-                    self.network.send_transactions(*peer_id, new_full_transactions);
+                for tx in &new_full_transactions {
+                    propagated.0.entry(tx.hash()).or_default().push(PropagateKind::Full(*peer_id));
                 }
+
+                trace!(target: "net::tx", ?peer_id, num_txs=?new_full_transactions.len(), "Propagating full transactions to peer");
+
+                // send full transactions
+                self.network.send_transactions(*peer_id, new_full_transactions);
             }
         }
 
@@ -558,11 +596,20 @@ where
 
             let mut propagated = PropagatedTransactions::default();
 
+            let base_fee = self.pool.block_info().pending_basefee;
+
             // check if transaction is known to peer
             let mut hashes = PooledTransactionsHashesBuilder::new(peer.version);
 
             for tx in to_propagate {
                 if !peer.seen_transactions.insert(tx.hash()) {
+                    if hashes.is_eth68() &&
+                        !self
+                            .announcement_filter
+                            .should_announce(&peer_id, &tx.announcement_candidate(base_fee))
+                    {
+                        continue
+                    }
                     hashes.push(&tx);
                 }
             }
@@ -886,6 +933,7 @@ where
             NetworkEvent::SessionClosed { peer_id, .. } => {
                 // remove the peer
                 self.peers.remove(&peer_id);
+                self.announcement_filter.policy_mut().on_session_closed(peer_id);
             }
             NetworkEvent::SessionEstablished {
                 peer_id, client_version, messages, version, ..
@@ -899,6 +947,7 @@ where
                     }
                     Entry::Vacant(entry) => entry.insert(peer),
                 };
+                self.announcement_filter.policy_mut().on_session_established(peer_id);
 
                 // Send a `NewPooledTransactionHashes` to the peer with up to
                 // `SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE`
@@ -915,13 +964,27 @@ where
                     return
                 }
 
+                let base_fee = self.pool.block_info().pending_basefee;
+
                 let mut msg_builder = PooledTransactionsHashesBuilder::new(version);
                 for pooled_tx in pooled_txs {
+                    if msg_builder.is_eth68() {
+                        if let Some(candidate) = pooled_announcement_candidate(&pooled_tx, base_fee)
+                        {
+                            if !self.announcement_filter.should_announce(&peer_id, &candidate) {
+                                continue
+                            }
+                        }
+                    }
                     peer.seen_transactions.insert(*pooled_tx.hash());
                     msg_builder.push_pooled(pooled_tx);
                 }
 
                 let msg = msg_builder.build();
+                if msg.is_empty() {
+                    // all transactions were skipped by the announcement policy
+                    return
+                }
                 self.network.send_transactions_hashes(peer_id, msg);
             }
             _ => {}
@@ -1376,6 +1439,29 @@ impl PropagateTransaction {
         let transaction = Arc::new(tx.transaction.to_recovered_transaction().into_signed());
         Self { size, transaction }
     }
+
+    /// Returns the entry the [`AnnouncementPolicy`] decides on, for the given pending base fee.
+    fn announcement_candidate(&self, base_fee: u64) -> AnnouncementCandidate {
+        AnnouncementCandidate {
+            tx_type: self.transaction.tx_type(),
+            size: self.size,
+            effective_tip: self.transaction.effective_tip_per_gas(Some(base_fee)),
+        }
+    }
+}
+
+/// Returns the entry the [`AnnouncementPolicy`] decides on for a transaction from the pool.
+///
+/// Returns `None` if the pool transaction's type is unknown.
+fn pooled_announcement_candidate<T: PoolTransaction>(
+    tx: &ValidPoolTransaction<T>,
+    base_fee: u64,
+) -> Option<AnnouncementCandidate> {
+    Some(AnnouncementCandidate {
+        tx_type: TxType::try_from(tx.transaction.tx_type()).ok()?,
+        size: tx.encoded_length(),
+        effective_tip: tx.transaction.effective_tip_per_gas(base_fee),
+    })
 }
 
 /// Helper type for constructing the full transaction message that enforces the
@@ -1450,6 +1536,12 @@ impl PooledTransactionsHashesBuilder {
         }
     }
 
+    /// Returns `true` if this builds a [`NewPooledTransactionHashes68`] message, which is subject
+    /// to the [`AnnouncementPolicy`].
+    const fn is_eth68(&self) -> bool {
+        matches!(self, Self::Eth68(_))
+    }
+
     /// Create a builder for the negotiated version of the peer's session
     fn new(version: EthVersion) -> Self {
         match version {
@@ -1617,7 +1709,7 @@ mod tests {
     };
     use reth_primitives::hex;
     use reth_provider::test_utils::NoopProvider;
-    use reth_transaction_pool::test_utils::{testing_pool, MockTransaction};
+    use reth_transaction_pool::test_utils::{testing_pool, MockTransaction, TestPool};
     use secp256k1::SecretKey;
     use std::{fmt, future::poll_fn, hash};
     use tests::fetcher::TxFetchMetadata;
@@ -2132,4 +2224,167 @@ mod tests {
         assert!(tx_fetcher.hashes_pending_fetch.is_empty());
         assert_eq!(tx_fetcher.active_peers.len(), 0);
     }
+
+    async fn new_tx_manager_with_policy<P: AnnouncementPolicy>(
+        policy: P,
+    ) -> (TransactionsManager<TestPool>, TestPool) {
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let config = NetworkConfigBuilder::new(secret_key)
+            .listener_port(0)
+            .disable_discovery()
+            .build(NoopProvider::default());
+        let pool = testing_pool();
+        let transactions_manager_config = config.transactions_manager_config.clone();
+        let (_network_handle, _network, tx_manager, _) = NetworkManager::new(config)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions(pool.clone(), transactions_manager_config)
+            .announcement_policy(policy)
+            .split_with_handle();
+
+        (tx_manager, pool)
+    }
+
+    #[tokio::test]
+    async fn test_announcement_policy_scripted_peer() {
+        reth_tracing::init_test_tracing();
+
+        let (mut tx_manager, pool) =
+            new_tx_manager_with_policy(SkipBlobsToInactivePeers::new(Duration::from_secs(60)))
+                .await;
+
+        // scripted eth/68 peer that hasn't requested any blob transactions
+        let peer_id = PeerId::new([1; 64]);
+        let (peer, _to_mock_session_rx) = new_mock_session(peer_id, EthVersion::Eth68);
+        tx_manager.peers.insert(peer_id, peer);
+
+        let blob_tx = MockTransaction::eip4844();
+        let blob_hash = blob_tx.get_hash();
+        pool.add_external_transaction(blob_tx).await.unwrap();
+
+        let propagated = tx_manager.propagate_transactions(
+            pool.get_all(vec![blob_hash]).into_iter().map(PropagateTransaction::new).collect(),
+        );
+
+        // announcement is skipped and the peer doesn't learn about the transaction
+        assert!(propagated.0.is_empty());
+        assert!(!tx_manager.peers[&peer_id].seen_transactions.contains(&blob_hash));
+
+        // the skipped transaction can still be requested by the peer
+        let (send, receive) = oneshot::channel();
+        tx_manager.on_get_pooled_transactions(
+            peer_id,
+            GetPooledTransactions(vec![blob_hash]),
+            send,
+        );
+        let PooledTransactions(served) = receive.await.unwrap().unwrap();
+        assert_eq!(served.len(), 1);
+        assert!(served[0].is_eip4844());
+
+        // the peer requested a blob transaction recently, so new blob transactions are announced
+        let blob_tx = MockTransaction::eip4844();
+        let blob_hash = blob_tx.get_hash();
+        pool.add_external_transaction(blob_tx).await.unwrap();
+
+        let propagated = tx_manager.propagate_transactions(
+            pool.get_all(vec![blob_hash]).into_iter().map(PropagateTransaction::new).collect(),
+        );
+        assert_eq!(propagated.0[&blob_hash], vec![PropagateKind::Hash(peer_id)]);
+
+        // announced transactions can be served
+        let (send, receive) = oneshot::channel();
+        tx_manager.on_get_pooled_transactions(
+            peer_id,
+            GetPooledTransactions(vec![blob_hash]),
+            send,
+        );
+        let PooledTransactions(served) = receive.await.unwrap().unwrap();
+        assert_eq!(*served[0].hash(), blob_hash);
+    }
+
+    #[tokio::test]
+    async fn test_announcement_policy_skipped_blob_announced_once_active() {
+        reth_tracing::init_test_tracing();
+
+        let (mut tx_manager, pool) =
+            new_tx_manager_with_policy(SkipBlobsToInactivePeers::new(Duration::from_secs(60)))
+                .await;
+
+        let peer_id = PeerId::new([1; 64]);
+        let (peer, _to_mock_session_rx) = new_mock_session(peer_id, EthVersion::Eth68);
+        tx_manager.peers.insert(peer_id, peer);
+
+        let blob_tx = MockTransaction::eip4844();
+        let blob_hash = blob_tx.get_hash();
+        pool.add_external_transaction(blob_tx).await.unwrap();
+
+        // the only transaction to propagate is skipped
+        let propagated = tx_manager.propagate_transactions(
+            pool.get_all(vec![blob_hash]).into_iter().map(PropagateTransaction::new).collect(),
+        );
+        assert!(propagated.0.is_empty());
+        assert!(!tx_manager.peers[&peer_id].seen_transactions.contains(&blob_hash));
+
+        // once the peer is active, the previously skipped transaction is announced
+        tx_manager.announcement_filter.policy_mut().on_pooled_transactions_served(peer_id, true);
+
+        let propagated = tx_manager.propagate_transactions(
+            pool.get_all(vec![blob_hash]).into_iter().map(PropagateTransaction::new).collect(),
+        );
+        assert_eq!(propagated.0[&blob_hash], vec![PropagateKind::Hash(peer_id)]);
+        assert!(tx_manager.peers[&peer_id].seen_transactions.contains(&blob_hash));
+    }
+
+    #[tokio::test]
+    async fn test_announcement_policy_full_transactions() {
+        reth_tracing::init_test_tracing();
+
+        #[derive(Debug)]
+        struct SkipLegacy;
+
+        impl AnnouncementPolicy for SkipLegacy {
+            fn name(&self) -> &'static str {
+                "skip_legacy"
+            }
+
+            fn decide(
+                &self,
+                _peer_id: &PeerId,
+                candidate: &AnnouncementCandidate,
+            ) -> AnnouncementDecision {
+                if candidate.tx_type == TxType::Legacy {
+                    return AnnouncementDecision::Skip
+                }
+                AnnouncementDecision::Announce
+            }
+        }
+
+        let (mut tx_manager, pool) = new_tx_manager_with_policy(SkipLegacy).await;
+
+        // a single peer is always sent full transactions
+        let peer_id = PeerId::new([1; 64]);
+        let (peer, _to_mock_session_rx) = new_mock_session(peer_id, EthVersion::Eth68);
+        tx_manager.peers.insert(peer_id, peer);
+
+        let legacy_tx = MockTransaction::legacy();
+        let legacy_hash = legacy_tx.get_hash();
+        let eip1559_tx = MockTransaction::eip1559();
+        let eip1559_hash = eip1559_tx.get_hash();
+        pool.add_external_transaction(legacy_tx).await.unwrap();
+        pool.add_external_transaction(eip1559_tx).await.unwrap();
+
+        let propagated = tx_manager.propagate_transactions(
+            pool.get_all(vec![legacy_hash, eip1559_hash])
+                .into_iter()
+                .map(PropagateTransaction::new)
+                .collect(),
+        );
+
+        // the skipped transaction is neither sent in full nor marked as seen
+        assert_eq!(propagated.0.len(), 1);
+        assert_eq!(propagated.0[&eip1559_hash], vec![PropagateKind::Full(peer_id)]);
+        assert!(!tx_manager.peers[&peer_id].seen_transactions.contains(&legacy_hash));
+        assert!(tx_manager.peers[&peer_id].seen_transactions.contains(&eip1559_hash));
+    }
 }
@@ -0,0 +1,248 @@
+//! Policies that are consulted before announcing transaction hashes to `eth/68` peers.
+//!
+//! A policy can only ever _remove_ transactions from a
+//! [`NewPooledTransactionHashes68`](reth_eth_wire::NewPooledTransactionHashes68) announcement.
+//! Serving [`GetPooledTransactions`](reth_eth_wire::GetPooledTransactions) requests never consults
+//! the policy, so every hash that is announced to a peer can still be requested by it.
+
+use crate::metrics::AnnouncementPolicyMetrics;
+use reth_network_types::PeerId;
+use reth_primitives::TxType;
+use std::{
+    collections::HashMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Default window in which a peer must have requested a blob transaction from us, for us to keep
+/// announcing blob transactions to it. See [`SkipBlobsToInactivePeers`].
+pub const DEFAULT_BLOB_REQUEST_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Outcome of an [`AnnouncementPolicy`] for a single transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnouncementDecision {
+    /// Include the transaction in the announcement.
+    Announce,
+    /// Leave the transaction out of the announcement.
+    Skip,
+}
+
+// === impl AnnouncementDecision ===
+
+impl AnnouncementDecision {
+    /// Returns `true` if the transaction should be announced.
+    pub const fn is_announce(&self) -> bool {
+        matches!(self, Self::Announce)
+    }
+}
+
+/// The announcement entry the policy decides on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnnouncementCandidate {
+    /// The type of the transaction.
+    pub tx_type: TxType,
+    /// The encoded size of the transaction, as announced in the `eth/68` message.
+    pub size: usize,
+    /// The effective tip of the transaction at the pool's pending base fee.
+    ///
+    /// `None` if the transaction's max fee does not cover the pending base fee.
+    pub effective_tip: Option<u128>,
+}
+
+/// Decides which transactions are announced to an `eth/68` peer in a
+/// [`NewPooledTransactionHashes68`](reth_eth_wire::NewPooledTransactionHashes68) message.
+///
+/// The [`TransactionsManager`](crate::transactions::TransactionsManager) also informs the policy
+/// about session and request activity of the peers, so that stateful policies can be implemented.
+pub trait AnnouncementPolicy: fmt::Debug + Send + Sync + Unpin + 'static {
+    /// Name of the policy, used as label for the policy's metrics.
+    fn name(&self) -> &'static str;
+
+    /// Decides whether the transaction should be announced to the peer.
+    fn decide(&self, peer_id: &PeerId, candidate: &AnnouncementCandidate) -> AnnouncementDecision;
+
+    /// Invoked when a new session with the peer was established.
+    fn on_session_established(&mut self, _peer_id: PeerId) {}
+
+    /// Invoked when the session with the peer was closed.
+    fn on_session_closed(&mut self, _peer_id: PeerId) {}
+
+    /// Invoked when a `GetPooledTransactions` request of the peer was served.
+    ///
+    /// `served_blob` is `true` if the response contained at least one blob transaction.
+    fn on_pooled_transactions_served(&mut self, _peer_id: PeerId, _served_blob: bool) {}
+}
+
+/// Announces all transactions. This is the default policy.
+#[derive(Debug, Default, Clone, Copy)]
+#[non_exhaustive]
+pub struct AnnounceAll;
+
+impl AnnouncementPolicy for AnnounceAll {
+    fn name(&self) -> &'static str {
+        "announce_all"
+    }
+
+    fn decide(
+        &self,
+        _peer_id: &PeerId,
+        _candidate: &AnnouncementCandidate,
+    ) -> AnnouncementDecision {
+        AnnouncementDecision::Announce
+    }
+}
+
+/// Skips blob transaction announcements to peers that haven't requested a blob transaction from us
+/// within the configured window.
+///
+/// The window of a peer starts when the session is established, so that new peers are given the
+/// chance to request blob transactions.
+#[derive(Debug)]
+pub struct SkipBlobsToInactivePeers {
+    /// How long a peer keeps receiving blob announcements after its last blob request.
+    window: Duration,
+    /// Last time each peer requested a blob transaction, or the session was established.
+    last_blob_request: HashMap<PeerId, Instant>,
+}
+
+// === impl SkipBlobsToInactivePeers ===
+
+impl SkipBlobsToInactivePeers {
+    /// Returns a new policy with the given window.
+    pub fn new(window: Duration) -> Self {
+        Self { window, last_blob_request: Default::default() }
+    }
+
+    /// Returns `true` if the peer requested a blob transaction within the window.
+    fn is_active(&self, peer_id: &PeerId) -> bool {
+        self.last_blob_request.get(peer_id).map_or(false, |last| last.elapsed() <= self.window)
+    }
+}
+
+impl Default for SkipBlobsToInactivePeers {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOB_REQUEST_WINDOW)
+    }
+}
+
+impl AnnouncementPolicy for SkipBlobsToInactivePeers {
+    fn name(&self) -> &'static str {
+        "skip_blobs_to_inactive_peers"
+    }
+
+    fn decide(&self, peer_id: &PeerId, candidate: &AnnouncementCandidate) -> AnnouncementDecision {
+        if candidate.tx_type == TxType::Eip4844 && !self.is_active(peer_id) {
+            return AnnouncementDecision::Skip
+        }
+        AnnouncementDecision::Announce
+    }
+
+    fn on_session_established(&mut self, peer_id: PeerId) {
+        self.last_blob_request.insert(peer_id, Instant::now());
+    }
+
+    fn on_session_closed(&mut self, peer_id: PeerId) {
+        self.last_blob_request.remove(&peer_id);
+    }
+
+    fn on_pooled_transactions_served(&mut self, peer_id: PeerId, served_blob: bool) {
+        if served_blob {
+            self.last_blob_request.insert(peer_id, Instant::now());
+        }
+    }
+}
+
+/// Applies an [`AnnouncementPolicy`] and records its decisions.
+#[derive(Debug)]
+pub(crate) struct AnnouncementFilter {
+    policy: Box<dyn AnnouncementPolicy>,
+    metrics: AnnouncementPolicyMetrics,
+}
+
+// === impl AnnouncementFilter ===
+
+impl AnnouncementFilter {
+    /// Returns a new filter for the given policy.
+    pub(crate) fn new(policy: Box<dyn AnnouncementPolicy>) -> Self {
+        let metrics = AnnouncementPolicyMetrics::new_with_labels(&[("policy", policy.name())]);
+        Self { policy, metrics }
+    }
+
+    /// Consults the policy and records the decision.
+    pub(crate) fn should_announce(
+        &self,
+        peer_id: &PeerId,
+        candidate: &AnnouncementCandidate,
+    ) -> bool {
+        match self.policy.decide(peer_id, candidate) {
+            AnnouncementDecision::Announce => {
+                self.metrics.announced.increment(1);
+                true
+            }
+            AnnouncementDecision::Skip => {
+                self.metrics.skipped.increment(1);
+                false
+            }
+        }
+    }
+
+    /// Returns mutable access to the policy, to notify it about peer activity.
+    pub(crate) fn policy_mut(&mut self) -> &mut dyn AnnouncementPolicy {
+        &mut *self.policy
+    }
+}
+
+impl Default for AnnouncementFilter {
+    fn default() -> Self {
+        Self::new(Box::<AnnounceAll>::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blob_candidate() -> AnnouncementCandidate {
+        AnnouncementCandidate { tx_type: TxType::Eip4844, size: 131_072, effective_tip: Some(1) }
+    }
+
+    fn eip1559_candidate() -> AnnouncementCandidate {
+        AnnouncementCandidate { tx_type: TxType::Eip1559, size: 110, effective_tip: Some(1) }
+    }
+
+    #[test]
+    fn announce_all() {
+        let policy = AnnounceAll;
+        let peer_id = PeerId::random();
+        assert!(policy.decide(&peer_id, &blob_candidate()).is_announce());
+        assert!(policy.decide(&peer_id, &eip1559_candidate()).is_announce());
+    }
+
+    #[test]
+    fn skip_blobs_to_inactive_peers() {
+        let mut policy = SkipBlobsToInactivePeers::new(Duration::from_secs(60));
+        let peer_id = PeerId::random();
+
+        // unknown peer receives no blob announcements, but all other types
+        assert_eq!(policy.decide(&peer_id, &blob_candidate()), AnnouncementDecision::Skip);
+        assert!(policy.decide(&peer_id, &eip1559_candidate()).is_announce());
+
+        // new sessions start within the window
+        policy.on_session_established(peer_id);
+        assert!(policy.decide(&peer_id, &blob_candidate()).is_announce());
+
+        // window ran out
+        policy.last_blob_request.insert(peer_id, Instant::now() - Duration::from_secs(61));
+        assert_eq!(policy.decide(&peer_id, &blob_candidate()), AnnouncementDecision::Skip);
+
+        // requests without blobs don't refresh the window
+        policy.on_pooled_transactions_served(peer_id, false);
+        assert_eq!(policy.decide(&peer_id, &blob_candidate()), AnnouncementDecision::Skip);
+
+        policy.on_pooled_transactions_served(peer_id, true);
+        assert!(policy.decide(&peer_id, &blob_candidate()).is_announce());
+
+        policy.on_session_closed(peer_id);
+        assert_eq!(policy.decide(&peer_id, &blob_candidate()), AnnouncementDecision::Skip);
+    }
+}
@@ -4,9 +4,18 @@ use futures::StreamExt;
 use rand::thread_rng;
 use reth_network::{test_utils::Testnet, NetworkEvent, NetworkEvents};
 use reth_network_api::PeersInfo;
-use reth_primitives::{TransactionSigned, TxLegacy, U256};
+use reth_primitives::{TransactionSigned, TxLegacy, MAINNET, U256};
 use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
-use reth_transaction_pool::{test_utils::TransactionGenerator, PoolTransaction, TransactionPool};
+use reth_tasks::{TaskManager, TokioTaskExecutor};
+use reth_transaction_pool::{
+    blobstore::InMemoryBlobStore,
+    journal::{
+        local_transactions_journal_task, LocalTransactionJournal, LocalTransactionJournalConfig,
+    },
+    test_utils::TransactionGenerator,
+    EthTransactionPool, PoolTransaction, TransactionOrigin, TransactionPool,
+    TransactionValidationTaskExecutor,
+};
 use std::sync::Arc;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -47,6 +56,59 @@ async fn test_tx_gossip() {
     assert_eq!(received, hash);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_journaled_tx_gossip_after_restart() {
+    reth_tracing::init_test_tracing();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let journal_config =
+        LocalTransactionJournalConfig::new(temp_dir.path().join("txpool-journal.rlp"));
+
+    let provider = MockEthProvider::default();
+    let mut gen = TransactionGenerator::new(thread_rng());
+    let tx = gen.gen_eip1559_pooled();
+
+    // ensure the sender has balance
+    let sender = tx.sender();
+    provider.add_account(sender, ExtendedAccount::new(0, U256::from(100_000_000)));
+
+    // submit a local tx to a pool with a journal
+    let blob_store = InMemoryBlobStore::default();
+    let pool = EthTransactionPool::eth_pool(
+        TransactionValidationTaskExecutor::eth(
+            provider.clone(),
+            MAINNET.clone(),
+            blob_store.clone(),
+            TokioTaskExecutor::default(),
+        ),
+        blob_store,
+        Default::default(),
+    );
+    let journal = LocalTransactionJournal::load(&pool, journal_config.clone()).await.unwrap();
+    let tasks = TaskManager::current();
+    tasks.executor().spawn_critical_with_graceful_shutdown_signal("journal task", |shutdown| {
+        local_transactions_journal_task(shutdown, pool.clone(), journal, futures::stream::pending())
+    });
+    let hash = pool.add_transaction(TransactionOrigin::Local, tx).await.unwrap();
+
+    // restart the pool with the journal
+    tasks.graceful_shutdown();
+    drop(pool);
+
+    let net = Testnet::create_with(2, provider).await.with_eth_pool();
+    let peer0_pool = net.peers()[0].pool().unwrap().clone();
+    let journal = LocalTransactionJournal::load(&peer0_pool, journal_config).await.unwrap();
+    assert_eq!(journal.len(), 1);
+    assert!(peer0_pool.pending_transactions().iter().any(|tx| *tx.hash() == hash));
+
+    let handle = net.spawn();
+    let mut peer1_tx_listener = handle.peers()[1].pool().unwrap().pending_transactions_listener();
+
+    // ensure the reinserted tx is propagated once the peers are connected
+    handle.connect_peers().await;
+    let received = peer1_tx_listener.recv().await.unwrap();
+    assert_eq!(received, hash);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_4844_tx_gossip_penalization() {
     reth_tracing::init_test_tracing();
@@ -41,6 +41,12 @@ pub struct DebugArgs {
     /// will be written to specified location.
     #[arg(long = "debug.engine-api-store", help_heading = "Debug", value_name = "PATH")]
     pub engine_api_store: Option<PathBuf>,
+
+    /// Records the time spent executing every class of opcodes and every precompile per block.
+    ///
+    /// NOTE: This slows down the block execution and requires the `execution-metrics` feature.
+    #[arg(long = "debug.execution-metrics", help_heading = "Debug")]
+    pub execution_metrics: bool,
 }
 
 #[cfg(test)]
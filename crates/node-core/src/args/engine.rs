@@ -0,0 +1,106 @@
+//! clap [Args](clap::Args) for engine API configuration
+
+use clap::Args;
+use reth_beacon_consensus::MIN_BLOCKS_FOR_PIPELINE_RUN;
+use reth_rpc_types::NodeEngineInfo;
+
+/// Parameters for configuring the engine API
+#[derive(Debug, Clone, Copy, Args, PartialEq, Eq)]
+#[command(next_help_heading = "Engine")]
+pub struct EngineArgs {
+    /// Only validate and import the payloads of the consensus layer, never build payloads.
+    ///
+    /// The payload builder service is not started and `engine_forkchoiceUpdated` calls with
+    /// payload attributes are rejected with `-38003: Invalid payload attributes`. The forkchoice
+    /// state of such calls is still applied.
+    #[arg(long = "engine.validation-only")]
+    pub validation_only: bool,
+
+    /// Accept `engine_forkchoiceUpdated` calls with payload attributes in validation-only mode,
+    /// but ignore the attributes and respond without a payload id.
+    #[arg(long = "engine.ignore-payload-attributes", requires = "validation_only")]
+    pub ignore_payload_attributes: bool,
+
+    /// The largest distance between the canonical tip and the sync target that is synced by
+    /// downloading full blocks.
+    ///
+    /// If the sync target is further ahead, the blocks are backfilled with the pipeline instead.
+    /// A value of 0 always backfills with the pipeline.
+    #[arg(long = "engine.backfill-trigger-distance", default_value_t = MIN_BLOCKS_FOR_PIPELINE_RUN)]
+    pub backfill_trigger_distance: u64,
+}
+
+impl Default for EngineArgs {
+    fn default() -> Self {
+        Self {
+            validation_only: false,
+            ignore_payload_attributes: false,
+            backfill_trigger_distance: MIN_BLOCKS_FOR_PIPELINE_RUN,
+        }
+    }
+}
+
+impl EngineArgs {
+    /// Returns the engine configuration reported by `admin_nodeInfo`.
+    pub const fn engine_info(&self) -> NodeEngineInfo {
+        NodeEngineInfo {
+            validation_only: self.validation_only,
+            ignore_payload_attributes: self.ignore_payload_attributes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn engine_args_default_sanity_check() {
+        let default_args = EngineArgs::default();
+        let args = CommandParser::<EngineArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, default_args);
+        assert_eq!(args.engine_info(), NodeEngineInfo::default());
+    }
+
+    #[test]
+    fn parse_engine_args() {
+        let args =
+            CommandParser::<EngineArgs>::parse_from(["reth", "--engine.validation-only"]).args;
+        assert!(args.validation_only);
+        assert!(!args.ignore_payload_attributes);
+
+        let args = CommandParser::<EngineArgs>::parse_from([
+            "reth",
+            "--engine.validation-only",
+            "--engine.ignore-payload-attributes",
+        ])
+        .args;
+        assert_eq!(
+            args.engine_info(),
+            NodeEngineInfo { validation_only: true, ignore_payload_attributes: true }
+        );
+
+        let args = CommandParser::<EngineArgs>::parse_from([
+            "reth",
+            "--engine.backfill-trigger-distance",
+            "0",
+        ])
+        .args;
+        assert_eq!(args.backfill_trigger_distance, 0);
+
+        // payload attributes can only be ignored in validation-only mode
+        assert!(CommandParser::<EngineArgs>::try_parse_from([
+            "reth",
+            "--engine.ignore-payload-attributes"
+        ])
+        .is_err());
+    }
+}
@@ -0,0 +1,94 @@
+//! clap [Args](clap::Args) for block execution configuration
+
+use clap::{builder::RangedU64ValueParser, Args};
+use reth_evm::parallel::{ParallelExecutionConfig, DEFAULT_MIN_TRANSACTIONS};
+
+/// Parameters for configuring the block execution
+#[derive(Debug, Clone, Copy, Args, PartialEq, Eq)]
+#[command(next_help_heading = "Execution")]
+pub struct ExecutionArgs {
+    /// Execute the transactions of a block optimistically in parallel.
+    ///
+    /// Transactions that conflict with preceding transactions of the block are executed again,
+    /// so the outcome is the same as the sequential execution.
+    #[arg(long = "execution.parallel")]
+    pub parallel: bool,
+
+    /// Maximum number of workers executing transactions in parallel.
+    ///
+    /// Defaults to the number of available CPU cores.
+    #[arg(long = "execution.workers", value_name = "WORKERS", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub workers: Option<usize>,
+
+    /// Number of transactions in a block below which they are executed sequentially.
+    #[arg(long = "execution.min-transactions", default_value_t = DEFAULT_MIN_TRANSACTIONS)]
+    pub min_transactions: usize,
+}
+
+impl Default for ExecutionArgs {
+    fn default() -> Self {
+        Self { parallel: false, workers: None, min_transactions: DEFAULT_MIN_TRANSACTIONS }
+    }
+}
+
+impl ExecutionArgs {
+    /// Returns the parallel execution configuration, or `None` if the parallel execution is
+    /// disabled.
+    pub fn parallel_execution_config(&self) -> Option<ParallelExecutionConfig> {
+        if !self.parallel {
+            return None
+        }
+
+        let mut config =
+            ParallelExecutionConfig::default().with_min_transactions(self.min_transactions);
+        if let Some(workers) = self.workers {
+            config = config.with_workers(workers);
+        }
+        Some(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn execution_args_default_sanity_check() {
+        let default_args = ExecutionArgs::default();
+        let args = CommandParser::<ExecutionArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, default_args);
+        assert!(args.parallel_execution_config().is_none());
+    }
+
+    #[test]
+    fn parse_execution_args() {
+        let args = CommandParser::<ExecutionArgs>::parse_from([
+            "reth",
+            "--execution.parallel",
+            "--execution.workers",
+            "16",
+            "--execution.min-transactions",
+            "32",
+        ])
+        .args;
+        assert_eq!(
+            args.parallel_execution_config(),
+            Some(ParallelExecutionConfig { workers: 16, min_transactions: 32 })
+        );
+
+        assert!(CommandParser::<ExecutionArgs>::try_parse_from([
+            "reth",
+            "--execution.workers",
+            "0"
+        ])
+        .is_err());
+    }
+}
@@ -22,7 +22,11 @@ pub struct GasPriceOracleArgs {
     #[arg(long = "gpo.maxprice", default_value_t = DEFAULT_MAX_GAS_PRICE.to())]
     pub max_price: u64,
 
-    /// The percentile of gas prices to use for the estimate
+    /// Minimum transaction priority fee(or gasprice before London Fork) to be recommended by gpo
+    #[arg(long = "gpo.minprice")]
+    pub min_price: Option<u64>,
+
+    /// The percentile of the rewards of a block to use for the estimate
     #[arg(long = "gpo.percentile", default_value_t = DEFAULT_GAS_PRICE_PERCENTILE)]
     pub percentile: u32,
 }
@@ -30,9 +34,10 @@ pub struct GasPriceOracleArgs {
 impl GasPriceOracleArgs {
     /// Returns a [`GasPriceOracleConfig`] from the arguments.
     pub fn gas_price_oracle_config(&self) -> GasPriceOracleConfig {
-        let Self { blocks, ignore_price, max_price, percentile } = self;
+        let Self { blocks, ignore_price, max_price, min_price, percentile } = self;
         GasPriceOracleConfig {
             max_price: Some(U256::from(*max_price)),
+            min_price: min_price.map(U256::from),
             ignore_price: Some(U256::from(*ignore_price)),
             percentile: *percentile,
             blocks: *blocks,
@@ -47,6 +52,7 @@ impl Default for GasPriceOracleArgs {
             blocks: DEFAULT_GAS_PRICE_BLOCKS,
             ignore_price: DEFAULT_IGNORE_GAS_PRICE.to(),
             max_price: DEFAULT_MAX_GAS_PRICE.to(),
+            min_price: None,
             percentile: DEFAULT_GAS_PRICE_PERCENTILE,
         }
     }
@@ -72,11 +78,21 @@ mod tests {
                 blocks: DEFAULT_GAS_PRICE_BLOCKS,
                 ignore_price: DEFAULT_IGNORE_GAS_PRICE.to(),
                 max_price: DEFAULT_MAX_GAS_PRICE.to(),
+                min_price: None,
                 percentile: DEFAULT_GAS_PRICE_PERCENTILE,
             }
         );
     }
 
+    #[test]
+    fn test_parse_gpo_min_price() {
+        let args =
+            CommandParser::<GasPriceOracleArgs>::parse_from(["reth", "--gpo.minprice", "1000"])
+                .args;
+        assert_eq!(args.min_price, Some(1000));
+        assert_eq!(args.gas_price_oracle_config().min_price, Some(U256::from(1000)));
+    }
+
     #[test]
     fn gpo_args_default_sanity_test() {
         let default_args = GasPriceOracleArgs::default();
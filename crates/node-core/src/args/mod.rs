@@ -51,6 +51,18 @@ pub use dev::DevArgs;
 mod pruning;
 pub use pruning::PruningArgs;
 
+/// TrieArgs for configuring the trie computations
+mod trie;
+pub use trie::TrieArgs;
+
+/// ExecutionArgs for configuring the block execution
+mod execution;
+pub use execution::ExecutionArgs;
+
+/// EngineArgs for configuring the engine API
+mod engine;
+pub use engine::EngineArgs;
+
 /// DatadirArgs for configuring data storage paths
 mod datadir_args;
 pub use datadir_args::DatadirArgs;
@@ -6,10 +6,16 @@ use clap::{
     builder::{RangedU64ValueParser, TypedValueParser},
     Arg, Args, Command,
 };
-use reth_primitives::constants::{
-    ETHEREUM_BLOCK_GAS_LIMIT, MAXIMUM_EXTRA_DATA_SIZE, SLOT_DURATION,
+use reth_primitives::{
+    constants::{ETHEREUM_BLOCK_GAS_LIMIT, MAXIMUM_EXTRA_DATA_SIZE, SLOT_DURATION},
+    U256,
+};
+use std::{
+    borrow::Cow,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    time::Duration,
 };
-use std::{borrow::Cow, ffi::OsStr, time::Duration};
 
 /// Parameters for configuring the Payload Builder
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
@@ -20,6 +26,9 @@ pub struct PayloadBuilderArgs {
     pub extradata: String,
 
     /// Target gas ceiling for built blocks.
+    ///
+    /// The gas limit of built blocks moves from the gas limit of the parent towards this target
+    /// by less than 1/1024 of the parent's gas limit per block.
     #[arg(long = "builder.gaslimit", default_value = "30000000", value_name = "GAS_LIMIT")]
     pub max_gas_limit: u64,
 
@@ -34,6 +43,56 @@ pub struct PayloadBuilderArgs {
     /// Maximum number of tasks to spawn for building a payload.
     #[arg(long = "builder.max-tasks", default_value = "3", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
     pub max_payload_tasks: usize,
+
+    /// The minimum projected fee gain (in wei) of the transactions that became pending since the
+    /// last build attempt that is required to re-pack the payload.
+    ///
+    /// If not set, the payload is re-packed on every interval tick.
+    #[arg(long = "builder.min-fee-improvement", value_name = "WEI")]
+    pub min_fee_improvement: Option<u128>,
+
+    /// Drop pool transactions that revert or halt from locally built payloads.
+    ///
+    /// Later transactions of the same sender are dropped as well, because they can't be included
+    /// without the reverting one. This never affects the validation of payloads.
+    #[arg(long = "builder.drop-reverting-txs")]
+    pub drop_reverting_txs: bool,
+
+    /// File with the senders, one address per line, whose reverting transactions are included
+    /// nonetheless if `--builder.drop-reverting-txs` is set.
+    #[arg(long = "builder.revert-allowlist", value_name = "FILE", requires = "drop_reverting_txs")]
+    pub revert_allowlist: Option<PathBuf>,
+
+    /// Include local transactions in locally built payloads before all other transactions,
+    /// regardless of their tip.
+    ///
+    /// Local transactions are the ones submitted via this node's RPC. They are still included in
+    /// nonce order, a local transaction whose sender has pending non-local transactions with a
+    /// lower nonce isn't prioritized.
+    #[arg(long = "builder.local-tx-priority")]
+    pub local_tx_priority: bool,
+
+    /// Only prioritize local transactions that pay at least this effective tip per gas (in wei)
+    /// if `--builder.local-tx-priority` is set.
+    #[arg(long = "builder.local-tx-min-tip", value_name = "WEI", requires = "local_tx_priority")]
+    pub local_tx_min_tip: Option<u128>,
+
+    /// Persist payload jobs and recently built payloads in the data directory.
+    ///
+    /// After a restart, the jobs whose payloads are still due are restarted and payloads that
+    /// were built before the restart can still be requested.
+    #[arg(long = "builder.persist-payloads")]
+    pub persist_payloads: bool,
+
+    /// Maximum number of built payloads that are persisted if `--builder.persist-payloads` is
+    /// set.
+    #[arg(
+        long = "builder.max-persisted-payloads",
+        value_name = "COUNT",
+        default_value = "16",
+        requires = "persist_payloads"
+    )]
+    pub max_persisted_payloads: usize,
 }
 
 impl Default for PayloadBuilderArgs {
@@ -44,6 +103,13 @@ impl Default for PayloadBuilderArgs {
             interval: Duration::from_secs(1),
             deadline: SLOT_DURATION,
             max_payload_tasks: 3,
+            min_fee_improvement: None,
+            drop_reverting_txs: false,
+            revert_allowlist: None,
+            local_tx_priority: false,
+            local_tx_min_tip: None,
+            persist_payloads: false,
+            max_persisted_payloads: 16,
         }
     }
 }
@@ -68,6 +134,34 @@ impl PayloadBuilderConfig for PayloadBuilderArgs {
     fn max_payload_tasks(&self) -> usize {
         self.max_payload_tasks
     }
+
+    fn min_fee_improvement(&self) -> Option<U256> {
+        self.min_fee_improvement.map(U256::from)
+    }
+
+    fn drop_reverting_txs(&self) -> bool {
+        self.drop_reverting_txs
+    }
+
+    fn revert_allowlist(&self) -> Option<&Path> {
+        self.revert_allowlist.as_deref()
+    }
+
+    fn local_tx_priority(&self) -> bool {
+        self.local_tx_priority
+    }
+
+    fn local_tx_min_tip(&self) -> Option<u128> {
+        self.local_tx_min_tip
+    }
+
+    fn persist_payloads(&self) -> bool {
+        self.persist_payloads
+    }
+
+    fn max_persisted_payloads(&self) -> usize {
+        self.max_persisted_payloads
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -127,6 +221,74 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn test_args_with_min_fee_improvement() {
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.min-fee-improvement",
+            "1000000000",
+        ])
+        .args;
+        assert_eq!(args.min_fee_improvement(), Some(U256::from(1_000_000_000u64)));
+    }
+
+    #[test]
+    fn test_args_revert_allowlist_requires_drop_reverting_txs() {
+        assert!(CommandParser::<PayloadBuilderArgs>::try_parse_from([
+            "reth",
+            "--builder.revert-allowlist",
+            "allowlist.txt"
+        ])
+        .is_err());
+
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.drop-reverting-txs",
+            "--builder.revert-allowlist",
+            "allowlist.txt",
+        ])
+        .args;
+        assert!(args.drop_reverting_txs);
+        assert_eq!(args.revert_allowlist(), Some(Path::new("allowlist.txt")));
+    }
+
+    #[test]
+    fn test_args_local_tx_min_tip_requires_local_tx_priority() {
+        assert!(CommandParser::<PayloadBuilderArgs>::try_parse_from([
+            "reth",
+            "--builder.local-tx-min-tip",
+            "1000000000"
+        ])
+        .is_err());
+
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.local-tx-priority",
+            "--builder.local-tx-min-tip",
+            "1000000000",
+        ])
+        .args;
+        assert!(args.local_tx_priority());
+        assert_eq!(args.local_tx_min_tip(), Some(1_000_000_000));
+    }
+
+    #[test]
+    fn test_args_persist_payloads() {
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from(["reth"]).args;
+        assert!(!args.persist_payloads());
+        assert_eq!(args.max_persisted_payloads(), 16);
+
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.persist-payloads",
+            "--builder.max-persisted-payloads",
+            "4",
+        ])
+        .args;
+        assert!(args.persist_payloads());
+        assert_eq!(args.max_persisted_payloads(), 4);
+    }
+
     #[test]
     fn test_default_extradata() {
         let extradata = default_extradata();
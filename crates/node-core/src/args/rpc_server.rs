@@ -10,24 +10,28 @@ use crate::{
 };
 use alloy_rpc_types_engine::{JwtError, JwtSecret};
 use clap::{
-    builder::{PossibleValue, RangedU64ValueParser, TypedValueParser},
+    builder::{PossibleValue, PossibleValuesParser, RangedU64ValueParser, TypedValueParser},
     Arg, Args, Command,
 };
 use rand::Rng;
 use reth_rpc::eth::{
-    cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig, RPC_DEFAULT_GAS_CAP,
+    cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig, FeeHistoryCacheConfig,
+    RPC_DEFAULT_GAS_CAP,
 };
 use reth_rpc_builder::{
     auth::AuthServerConfig, error::RpcError, EthConfig, Identity, IpcServerBuilder, RethRpcModule,
-    RpcModuleConfig, RpcModuleSelection, RpcServerConfig, ServerBuilder, TransportRpcModuleConfig,
+    RpcCallLimits, RpcModuleConfig, RpcModuleSelection, RpcServerConfig, ServerBuilder,
+    TransportRpcModuleConfig,
 };
 use reth_rpc_server_types::constants;
+use reth_rpc_types::SubscriptionBackpressurePolicy;
 use std::{
     ffi::OsStr,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
+    time::Duration,
 };
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Default max number of subscriptions per connection.
 pub(crate) const RPC_DEFAULT_MAX_SUBS_PER_CONN: u32 = 1024;
@@ -87,6 +91,43 @@ pub struct RpcServerArgs {
     #[arg(long = "ws.api", value_parser = RpcModuleSelectionValueParser::default())]
     pub ws_api: Option<RpcModuleSelection>,
 
+    /// Maximum number of notifications buffered per subscription if the subscriber doesn't keep
+    /// up.
+    ///
+    /// Subscriptions may lower this in the `backpressure` field of their params.
+    #[arg(
+        long = "ws.max-subscription-buffer",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_SUBSCRIPTION_BUFFER
+    )]
+    pub ws_max_subscription_buffer: usize,
+
+    /// What happens to the notifications of a subscription once its buffer is full.
+    ///
+    /// By default, the subscription stops receiving new notifications until the subscriber caught
+    /// up. Subscriptions may override this in the `backpressure` field of their params. Logs
+    /// subscribers that prefer completeness should use `disconnect` and re-sync the missed blocks
+    /// with `eth_getLogs`.
+    #[arg(
+        long = "ws.subscription-backpressure",
+        value_name = "POLICY",
+        value_parser =
+            PossibleValuesParser::new(["wait", "drop-oldest", "drop-newest", "disconnect"])
+                .try_map(|policy| policy.parse::<SubscriptionBackpressurePolicy>()),
+        default_value_t = SubscriptionBackpressurePolicy::default()
+    )]
+    pub ws_subscription_backpressure: SubscriptionBackpressurePolicy,
+
+    /// Maximum number of `to` and `from` addresses of the filter of a `newPendingTransactions`
+    /// subscription.
+    #[arg(
+        long = "ws.max-pending-tx-filter-addresses",
+        value_name = "COUNT",
+        default_value_t = constants::DEFAULT_MAX_TRANSACTION_FILTER_ADDRESSES
+    )]
+    pub ws_max_pending_tx_filter_addresses: usize,
+
     /// Disable the IPC-RPC server
     #[arg(long)]
     pub ipcdisable: bool,
@@ -144,18 +185,195 @@ pub struct RpcServerArgs {
     #[arg(long = "rpc.max-connections", alias = "rpc-max-connections", value_name = "COUNT", default_value_t = RPC_DEFAULT_MAX_CONNECTIONS.into())]
     pub rpc_max_connections: MaxU32,
 
+    /// Maximum number of RPC calls executed concurrently across all connections, for example the
+    /// calls of batch requests. Calls above the limit wait in order of arrival.
+    #[arg(
+        long = "rpc.max-batch-concurrency",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_BATCH_CONCURRENCY
+    )]
+    pub rpc_max_batch_concurrency: usize,
+
+    /// Maximum number of RPC calls of a single connection executed concurrently.
+    #[arg(
+        long = "rpc.max-concurrent-calls-per-connection",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_CONCURRENT_CALLS_PER_CONNECTION
+    )]
+    pub rpc_max_concurrent_calls_per_connection: usize,
+
+    /// Maximum number of RPC calls of a single connection waiting to be executed. Calls above the
+    /// limit are rejected.
+    #[arg(
+        long = "rpc.max-queued-calls-per-connection",
+        value_name = "COUNT",
+        default_value_t = constants::DEFAULT_MAX_QUEUED_CALLS_PER_CONNECTION
+    )]
+    pub rpc_max_queued_calls_per_connection: usize,
+
     /// Maximum number of concurrent tracing requests.
     #[arg(long = "rpc.max-tracing-requests", alias = "rpc-max-tracing-requests", value_name = "COUNT", default_value_t = constants::default_max_tracing_requests())]
     pub rpc_max_tracing_requests: usize,
 
+    /// Maximum number of transaction pre-states held at once when the transactions of a block are
+    /// traced in parallel. (1 = trace sequentially)
+    #[arg(
+        long = "rpc.max-trace-block-checkpoints",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_TRACE_BLOCK_CHECKPOINTS
+    )]
+    pub rpc_max_trace_block_checkpoints: usize,
+
+    /// Maximum time in milliseconds a tracing request of the `debug` or `trace` namespace can take
+    /// before its execution is aborted, all calls traced by the request share the budget.
+    /// Timeouts requested with the `timeout` tracing option above are capped.
+    #[arg(
+        long = "rpc.tracing-timeout",
+        value_name = "MILLIS",
+        default_value_t = constants::DEFAULT_TRACING_TIMEOUT.as_millis() as u64,
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub rpc_tracing_timeout: u64,
+
+    /// Maximum combined size in bytes of the struct logs recorded for a single call traced in the
+    /// `debug` namespace, the remaining instructions are left out of the trace.
+    #[arg(
+        long = "rpc.max-struct-logs-memory",
+        value_name = "BYTES",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_STRUCT_LOGS_MEMORY
+    )]
+    pub rpc_max_struct_logs_memory: usize,
+
+    /// Enable tracing with custom JavaScript tracers in the `debug` namespace.
+    ///
+    /// The tracers have no access to the file system or the network.
+    #[arg(long = "rpc.js-tracer")]
+    pub rpc_js_tracer: bool,
+
+    /// Maximum time in milliseconds a tracing request with a JavaScript tracer can take before its
+    /// execution is aborted, all calls traced by the request share the budget. Requested timeouts
+    /// above are capped.
+    #[arg(
+        long = "rpc.js-tracer-timeout",
+        value_name = "MILLIS",
+        default_value_t = constants::DEFAULT_JS_TRACER_TIMEOUT.as_millis() as u64,
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub rpc_js_tracer_timeout: u64,
+
+    /// Maximum number of threads that execute JavaScript tracers. A tracer that doesn't return
+    /// within the timeout of its request keeps its thread until it returns, JavaScript tracing
+    /// requests fail while all threads are taken.
+    #[arg(
+        long = "rpc.js-tracer-max-threads",
+        value_name = "COUNT",
+        default_value_t = constants::DEFAULT_MAX_JS_TRACER_THREADS,
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub rpc_js_tracer_max_threads: usize,
+
+    /// Maximum number of blocks a single `debug_traceChain` subscription can trace.
+    #[arg(
+        long = "rpc.max-trace-chain-blocks",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_TRACE_CHAIN_BLOCKS
+    )]
+    pub rpc_max_trace_chain_blocks: u64,
+
+    /// Enable `reth_dbGet`, `reth_staticFileGet`, `reth_txpoolDump` and `reth_txpoolRestore` in
+    /// the `reth` namespace.
+    ///
+    /// They return any value stored in the database or the static files and write transaction pool
+    /// snapshots to the datadir, only enable this if the `reth` namespace is not publicly
+    /// reachable.
+    #[arg(long = "rpc.unsafe-debug")]
+    pub rpc_unsafe_debug: bool,
+
+    /// Enable the block submission validation endpoints of the `flashbots` namespace on the auth
+    /// server.
+    ///
+    /// Relays use `flashbots_validateBuilderSubmissionV3` and `V4` to execute and validate the
+    /// blocks submitted by builders.
+    #[arg(long = "rpc.flashbots")]
+    pub rpc_flashbots: bool,
+
+    /// Maximum number of blocks a single `trace_filter` request can scan.
+    #[arg(
+        long = "rpc.max-trace-filter-blocks",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_TRACE_FILTER_BLOCKS
+    )]
+    pub rpc_max_trace_filter_blocks: u64,
+
+    /// Maximum number of traces a single `trace_filter` request can return.
+    #[arg(
+        long = "rpc.max-trace-filter-results",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_TRACE_FILTER_RESULTS
+    )]
+    pub rpc_max_trace_filter_results: usize,
+
+    /// Maximum number of instructions recorded for the `vmTrace` of a single call, the remaining
+    /// instructions are left out of the trace.
+    #[arg(
+        long = "rpc.max-vm-trace-steps",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_VM_TRACE_STEPS
+    )]
+    pub rpc_max_vm_trace_steps: usize,
+
+    /// Maximum combined size in bytes of the memory snapshots recorded for the `vmTrace` of a
+    /// single call, the remaining instructions are left out of the trace.
+    #[arg(
+        long = "rpc.max-vm-trace-memory",
+        value_name = "BYTES",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_VM_TRACE_MEMORY
+    )]
+    pub rpc_max_vm_trace_memory: usize,
+
     /// Maximum number of blocks that could be scanned per filter request. (0 = entire chain)
-    #[arg(long = "rpc.max-blocks-per-filter", alias = "rpc-max-blocks-per-filter", value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_BLOCKS_PER_FILTER))]
+    #[arg(long = "rpc.max-blocks-per-filter", alias = "rpc-max-blocks-per-filter", visible_alias = "rpc.eth-getlogs.max-range", value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_BLOCKS_PER_FILTER))]
     pub rpc_max_blocks_per_filter: ZeroAsNoneU64,
 
     /// Maximum number of logs that can be returned in a single response. (0 = no limit)
-    #[arg(long = "rpc.max-logs-per-response", alias = "rpc-max-logs-per-response", value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64))]
+    #[arg(long = "rpc.max-logs-per-response", alias = "rpc-max-logs-per-response", visible_alias = "rpc.eth-getlogs.max-results", value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64))]
     pub rpc_max_logs_per_response: ZeroAsNoneU64,
 
+    /// Maximum time in milliseconds a single log query can take. (0 = no limit)
+    ///
+    /// Queries that exceed the limit, the max range or the max results fail with an error whose
+    /// data contains a smaller block range to retry with.
+    #[arg(long = "rpc.eth-getlogs.timeout", value_name = "MILLIS", default_value_t = ZeroAsNoneU64(None))]
+    pub rpc_eth_getlogs_timeout: ZeroAsNoneU64,
+
+    /// Time in seconds a filter remains installed without being polled.
+    #[arg(long = "rpc.eth-filter-ttl", value_name = "SECS", default_value_t = constants::DEFAULT_STALE_FILTER_TTL.as_secs(), value_parser = RangedU64ValueParser::<u64>::new().range(1..))]
+    pub rpc_eth_filter_ttl: u64,
+
+    /// Maximum number of filters a single connection can have installed at once. (0 = no limit)
+    #[arg(long = "rpc.max-active-filters", value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_ACTIVE_FILTERS as u64))]
+    pub rpc_max_active_filters: ZeroAsNoneU64,
+
+    /// Persist the installed log filters to the given file, so they're restored after a restart.
+    ///
+    /// The first poll of a restored filter returns the changes of the blocks since its last poll,
+    /// up to `--rpc.eth-filter-catch-up-blocks` blocks.
+    #[arg(long = "rpc.eth-filter-persistence", value_name = "FILE")]
+    pub rpc_eth_filter_persistence: Option<PathBuf>,
+
+    /// Maximum number of blocks the first poll of a restored log filter returns the changes of.
+    #[arg(long = "rpc.eth-filter-catch-up-blocks", value_name = "COUNT", default_value_t = constants::DEFAULT_FILTER_CATCH_UP_BLOCKS)]
+    pub rpc_eth_filter_catch_up_blocks: u64,
+
     /// Maximum gas limit for `eth_call` and call tracing RPC methods.
     #[arg(
         long = "rpc.gascap",
@@ -166,6 +384,63 @@ pub struct RpcServerArgs {
     )]
     pub rpc_gas_cap: u64,
 
+    /// The maximum proof window for historical proof generation.
+    /// This value allows for generating historical proofs up to
+    /// configured number of blocks from current tip (up to `tip - window`).
+    #[arg(
+        long = "rpc.eth-proof-window",
+        default_value_t = constants::DEFAULT_ETH_PROOF_WINDOW,
+        value_parser = RangedU64ValueParser::<u64>::new().range(..=constants::MAX_ETH_PROOF_WINDOW)
+    )]
+    pub rpc_eth_proof_window: u64,
+
+    /// Maximum memory of the EVM in bytes for `eth_call` and tracing RPC methods.
+    ///
+    /// Block execution and replays of mined transactions are not affected and always use the
+    /// protocol defaults.
+    #[arg(
+        long = "rpc.memory-limit",
+        value_name = "BYTES",
+        default_value_t = constants::DEFAULT_RPC_MEMORY_LIMIT,
+        value_parser = RangedU64ValueParser::<u64>::new()
+            .range(constants::MIN_RPC_MEMORY_LIMIT..=constants::MAX_RPC_MEMORY_LIMIT)
+    )]
+    pub rpc_memory_limit: u64,
+
+    /// Default time in milliseconds `eth_sendRawTransactionSync` waits for the transaction to be
+    /// included if the request doesn't specify a timeout.
+    #[arg(
+        long = "rpc.send-raw-tx-sync-timeout",
+        value_name = "MILLIS",
+        default_value_t = constants::DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT.as_millis() as u64,
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub rpc_send_raw_tx_sync_timeout: u64,
+
+    /// Maximum time in milliseconds `eth_sendRawTransactionSync` waits for the transaction to be
+    /// included. Requested timeouts above are capped.
+    #[arg(
+        long = "rpc.max-send-raw-tx-sync-timeout",
+        value_name = "MILLIS",
+        default_value_t = constants::DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT.as_millis() as u64,
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub rpc_max_send_raw_tx_sync_timeout: u64,
+
+    /// Enables `eth_sendRawTransactionConditional`, which submits transactions that are only
+    /// included if the given storage and block conditions hold.
+    #[arg(long = "rpc.enable-tx-conditional", default_value_t = false)]
+    pub rpc_enable_tx_conditional: bool,
+
+    /// Maximum number of storage roots and slots the conditional of an
+    /// `eth_sendRawTransactionConditional` request can check.
+    #[arg(
+        long = "rpc.tx-conditional-max-cost",
+        value_name = "COST",
+        default_value_t = constants::DEFAULT_MAX_TX_CONDITIONAL_COST
+    )]
+    pub rpc_tx_conditional_max_cost: usize,
+
     /// State cache configuration.
     #[command(flatten)]
     pub rpc_state_cache: RpcStateCacheArgs,
@@ -278,19 +553,65 @@ impl RethRpcConfig for RpcServerArgs {
     }
 
     fn eth_config(&self) -> EthConfig {
-        EthConfig::default()
+        let config = EthConfig::default()
             .max_tracing_requests(self.rpc_max_tracing_requests)
+            .max_trace_block_checkpoints(self.rpc_max_trace_block_checkpoints)
+            .tracing_timeout(Duration::from_millis(self.rpc_tracing_timeout))
+            .max_struct_logs_memory(self.rpc_max_struct_logs_memory)
+            .js_tracer(self.rpc_js_tracer)
+            .js_tracer_timeout(Duration::from_millis(self.rpc_js_tracer_timeout))
+            .max_js_tracer_threads(self.rpc_js_tracer_max_threads)
+            .max_trace_chain_blocks(self.rpc_max_trace_chain_blocks)
+            .max_trace_filter_blocks(self.rpc_max_trace_filter_blocks)
+            .max_trace_filter_results(self.rpc_max_trace_filter_results)
+            .max_vm_trace_steps(self.rpc_max_vm_trace_steps)
+            .max_vm_trace_memory(self.rpc_max_vm_trace_memory)
             .max_blocks_per_filter(self.rpc_max_blocks_per_filter.unwrap_or_max())
             .max_logs_per_response(self.rpc_max_logs_per_response.unwrap_or_max() as usize)
+            .stale_filter_ttl(Duration::from_secs(self.rpc_eth_filter_ttl))
+            .max_active_filters(self.rpc_max_active_filters.0.map(|max| max as usize))
+            .filter_catch_up_blocks(self.rpc_eth_filter_catch_up_blocks)
             .rpc_gas_cap(self.rpc_gas_cap)
+            .eth_proof_window(self.rpc_eth_proof_window)
+            .rpc_memory_limit(self.rpc_memory_limit)
+            .send_raw_transaction_sync_timeout(Duration::from_millis(
+                self.rpc_send_raw_tx_sync_timeout,
+            ))
+            .max_send_raw_transaction_sync_timeout(Duration::from_millis(
+                self.rpc_max_send_raw_tx_sync_timeout,
+            ))
+            .tx_conditional(self.rpc_enable_tx_conditional)
+            .max_tx_conditional_cost(self.rpc_tx_conditional_max_cost)
             .state_cache(self.state_cache_config())
             .gpo_config(self.gas_price_oracle_config())
+            .fee_history_cache(FeeHistoryCacheConfig {
+                max_blocks: self.rpc_state_cache.max_fee_history_blocks,
+                ..Default::default()
+            })
+            .max_subscription_buffer(self.ws_max_subscription_buffer)
+            .subscription_backpressure(self.ws_subscription_backpressure)
+            .max_transaction_filter_addresses(self.ws_max_pending_tx_filter_addresses);
+        let config = match &self.rpc_eth_filter_persistence {
+            Some(path) => config.filter_persistence_path(path.clone()),
+            None => config,
+        };
+        match self.rpc_eth_getlogs_timeout.0 {
+            Some(timeout) => config.logs_query_timeout(Duration::from_millis(timeout)),
+            None => config,
+        }
     }
 
     fn state_cache_config(&self) -> EthStateCacheConfig {
+        if self.rpc_state_cache.max_blocks.is_some() {
+            warn!(target: "reth::cli", "--rpc-cache.max-blocks is deprecated, use --rpc-cache.max-block-mb instead");
+        }
+        if self.rpc_state_cache.max_receipts.is_some() {
+            warn!(target: "reth::cli", "--rpc-cache.max-receipts is deprecated, use --rpc-cache.max-receipt-mb instead");
+        }
         EthStateCacheConfig {
-            max_blocks: self.rpc_state_cache.max_blocks,
-            max_receipts: self.rpc_state_cache.max_receipts,
+            max_block_bytes: self.rpc_state_cache.max_block_bytes(),
+            max_receipt_bytes: self.rpc_state_cache.max_receipt_bytes(),
+            max_header_bytes: self.rpc_state_cache.max_header_bytes(),
             max_envs: self.rpc_state_cache.max_envs,
             max_concurrent_db_requests: self.rpc_state_cache.max_concurrent_db_requests,
         }
@@ -352,7 +673,13 @@ impl RethRpcConfig for RpcServerArgs {
     }
 
     fn rpc_server_config(&self) -> RpcServerConfig {
-        let mut config = RpcServerConfig::default().with_jwt_secret(self.rpc_secret_key());
+        let mut config = RpcServerConfig::default()
+            .with_jwt_secret(self.rpc_secret_key())
+            .with_call_limits(RpcCallLimits {
+                max_batch_concurrency: self.rpc_max_batch_concurrency,
+                max_concurrent_calls_per_connection: self.rpc_max_concurrent_calls_per_connection,
+                max_queued_calls_per_connection: self.rpc_max_queued_calls_per_connection,
+            });
 
         if self.http {
             let socket_address = SocketAddr::new(self.http_addr, self.http_port);
@@ -416,6 +743,9 @@ impl Default for RpcServerArgs {
             ws_port: constants::DEFAULT_WS_RPC_PORT,
             ws_allowed_origins: None,
             ws_api: None,
+            ws_max_subscription_buffer: constants::DEFAULT_MAX_SUBSCRIPTION_BUFFER,
+            ws_subscription_backpressure: SubscriptionBackpressurePolicy::default(),
+            ws_max_pending_tx_filter_addresses: constants::DEFAULT_MAX_TRANSACTION_FILTER_ADDRESSES,
             ipcdisable: false,
             ipcpath: constants::DEFAULT_IPC_ENDPOINT.to_string(),
             auth_addr: Ipv4Addr::LOCALHOST.into(),
@@ -428,10 +758,40 @@ impl Default for RpcServerArgs {
             rpc_max_response_size: RPC_DEFAULT_MAX_RESPONSE_SIZE_MB.into(),
             rpc_max_subscriptions_per_connection: RPC_DEFAULT_MAX_SUBS_PER_CONN.into(),
             rpc_max_connections: RPC_DEFAULT_MAX_CONNECTIONS.into(),
+            rpc_max_batch_concurrency: constants::DEFAULT_MAX_BATCH_CONCURRENCY,
+            rpc_max_concurrent_calls_per_connection:
+                constants::DEFAULT_MAX_CONCURRENT_CALLS_PER_CONNECTION,
+            rpc_max_queued_calls_per_connection: constants::DEFAULT_MAX_QUEUED_CALLS_PER_CONNECTION,
             rpc_max_tracing_requests: constants::default_max_tracing_requests(),
+            rpc_max_trace_block_checkpoints: constants::DEFAULT_MAX_TRACE_BLOCK_CHECKPOINTS,
+            rpc_tracing_timeout: constants::DEFAULT_TRACING_TIMEOUT.as_millis() as u64,
+            rpc_max_struct_logs_memory: constants::DEFAULT_MAX_STRUCT_LOGS_MEMORY,
+            rpc_js_tracer: false,
+            rpc_js_tracer_timeout: constants::DEFAULT_JS_TRACER_TIMEOUT.as_millis() as u64,
+            rpc_js_tracer_max_threads: constants::DEFAULT_MAX_JS_TRACER_THREADS,
+            rpc_max_trace_chain_blocks: constants::DEFAULT_MAX_TRACE_CHAIN_BLOCKS,
+            rpc_unsafe_debug: false,
+            rpc_flashbots: false,
+            rpc_max_trace_filter_blocks: constants::DEFAULT_MAX_TRACE_FILTER_BLOCKS,
+            rpc_max_trace_filter_results: constants::DEFAULT_MAX_TRACE_FILTER_RESULTS,
+            rpc_max_vm_trace_steps: constants::DEFAULT_MAX_VM_TRACE_STEPS,
+            rpc_max_vm_trace_memory: constants::DEFAULT_MAX_VM_TRACE_MEMORY,
             rpc_max_blocks_per_filter: constants::DEFAULT_MAX_BLOCKS_PER_FILTER.into(),
             rpc_max_logs_per_response: (constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64).into(),
+            rpc_eth_getlogs_timeout: ZeroAsNoneU64(None),
+            rpc_eth_filter_ttl: constants::DEFAULT_STALE_FILTER_TTL.as_secs(),
+            rpc_max_active_filters: (constants::DEFAULT_MAX_ACTIVE_FILTERS as u64).into(),
+            rpc_eth_filter_persistence: None,
+            rpc_eth_filter_catch_up_blocks: constants::DEFAULT_FILTER_CATCH_UP_BLOCKS,
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
+            rpc_eth_proof_window: constants::DEFAULT_ETH_PROOF_WINDOW,
+            rpc_memory_limit: constants::DEFAULT_RPC_MEMORY_LIMIT,
+            rpc_send_raw_tx_sync_timeout: constants::DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT
+                .as_millis() as u64,
+            rpc_max_send_raw_tx_sync_timeout:
+                constants::DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT.as_millis() as u64,
+            rpc_enable_tx_conditional: false,
+            rpc_tx_conditional_max_cost: constants::DEFAULT_MAX_TX_CONDITIONAL_COST,
             gas_price_oracle: GasPriceOracleArgs::default(),
             rpc_state_cache: RpcStateCacheArgs::default(),
         }
@@ -499,6 +859,382 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn test_rpc_cache_deprecated_count_flags() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.state_cache_config();
+        assert_eq!(config.max_block_bytes, 500 * 1024 * 1024);
+        assert_eq!(config.max_receipt_bytes, 500 * 1024 * 1024);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc-cache.max-blocks",
+            "2500",
+            "--rpc-cache.max-receipts",
+            "4000",
+        ])
+        .args;
+        let config = args.state_cache_config();
+        assert_eq!(config.max_block_bytes, 250 * 1024 * 1024);
+        assert_eq!(config.max_receipt_bytes, 1000 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_rpc_max_trace_block_checkpoints() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.eth_config();
+        assert_eq!(
+            config.max_trace_block_checkpoints,
+            constants::DEFAULT_MAX_TRACE_BLOCK_CHECKPOINTS
+        );
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.max-trace-block-checkpoints",
+            "1",
+        ])
+        .args;
+        let config = args.eth_config();
+        assert_eq!(config.max_trace_block_checkpoints, 1);
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.max-trace-block-checkpoints",
+            "0",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_eth_filter_lifecycle() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.eth_config().filter_config();
+        assert_eq!(config.stale_filter_ttl, constants::DEFAULT_STALE_FILTER_TTL);
+        assert_eq!(config.max_active_filters, Some(constants::DEFAULT_MAX_ACTIVE_FILTERS));
+        assert_eq!(config.persistence_path, None);
+        assert_eq!(config.catch_up_blocks, constants::DEFAULT_FILTER_CATCH_UP_BLOCKS);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.eth-filter-ttl",
+            "60",
+            "--rpc.max-active-filters",
+            "0",
+            "--rpc.eth-filter-persistence",
+            "/tmp/filters.json",
+            "--rpc.eth-filter-catch-up-blocks",
+            "64",
+        ])
+        .args;
+        let config = args.eth_config().filter_config();
+        assert_eq!(config.stale_filter_ttl, Duration::from_secs(60));
+        assert_eq!(config.max_active_filters, None);
+        assert_eq!(config.persistence_path, Some(PathBuf::from("/tmp/filters.json")));
+        assert_eq!(config.catch_up_blocks, 64);
+    }
+
+    #[test]
+    fn test_rpc_max_trace_filter() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.eth_config().trace_config();
+        assert_eq!(config.max_trace_filter_blocks, constants::DEFAULT_MAX_TRACE_FILTER_BLOCKS);
+        assert_eq!(config.max_trace_filter_results, constants::DEFAULT_MAX_TRACE_FILTER_RESULTS);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.max-trace-filter-blocks",
+            "1000",
+            "--rpc.max-trace-filter-results",
+            "50",
+        ])
+        .args;
+        let config = args.eth_config().trace_config();
+        assert_eq!(config.max_trace_filter_blocks, 1000);
+        assert_eq!(config.max_trace_filter_results, 50);
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.max-trace-filter-results",
+            "0",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_max_vm_trace() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.eth_config().trace_config();
+        assert_eq!(config.max_vm_trace_steps, constants::DEFAULT_MAX_VM_TRACE_STEPS);
+        assert_eq!(config.max_vm_trace_memory, constants::DEFAULT_MAX_VM_TRACE_MEMORY);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.max-vm-trace-steps",
+            "1000",
+            "--rpc.max-vm-trace-memory",
+            "65536",
+        ])
+        .args;
+        let config = args.eth_config().trace_config();
+        assert_eq!(config.max_vm_trace_steps, 1000);
+        assert_eq!(config.max_vm_trace_memory, 65536);
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.max-vm-trace-steps",
+            "0",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_tracing_guardrails() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let eth_config = args.eth_config();
+        let config = eth_config.debug_config();
+        assert_eq!(config.tracing_timeout, constants::DEFAULT_TRACING_TIMEOUT);
+        assert_eq!(config.max_struct_logs_memory, constants::DEFAULT_MAX_STRUCT_LOGS_MEMORY);
+        assert_eq!(eth_config.trace_config().tracing_timeout, constants::DEFAULT_TRACING_TIMEOUT);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.tracing-timeout",
+            "1500",
+            "--rpc.max-struct-logs-memory",
+            "65536",
+        ])
+        .args;
+        let eth_config = args.eth_config();
+        let config = eth_config.debug_config();
+        assert_eq!(config.tracing_timeout, Duration::from_millis(1500));
+        assert_eq!(config.max_struct_logs_memory, 65536);
+        assert_eq!(eth_config.trace_config().tracing_timeout, Duration::from_millis(1500));
+
+        let args =
+            CommandParser::<RpcServerArgs>::try_parse_from(["reth", "--rpc.tracing-timeout", "0"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_js_tracer() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.eth_config().debug_config();
+        assert!(!config.js_tracer);
+        assert_eq!(config.js_tracer_timeout, constants::DEFAULT_JS_TRACER_TIMEOUT);
+        assert_eq!(config.max_js_tracer_threads, constants::DEFAULT_MAX_JS_TRACER_THREADS);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.js-tracer",
+            "--rpc.js-tracer-timeout",
+            "500",
+            "--rpc.js-tracer-max-threads",
+            "2",
+        ])
+        .args;
+        let config = args.eth_config().debug_config();
+        assert!(config.js_tracer);
+        assert_eq!(config.js_tracer_timeout, Duration::from_millis(500));
+        assert_eq!(config.max_js_tracer_threads, 2);
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.js-tracer-timeout",
+            "0",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_max_trace_chain_blocks() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.eth_config().debug_config();
+        assert_eq!(config.max_trace_chain_blocks, constants::DEFAULT_MAX_TRACE_CHAIN_BLOCKS);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.max-trace-chain-blocks",
+            "100",
+        ])
+        .args;
+        let config = args.eth_config().debug_config();
+        assert_eq!(config.max_trace_chain_blocks, 100);
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.max-trace-chain-blocks",
+            "0",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_unsafe_debug() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert!(!args.rpc_unsafe_debug);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc.unsafe-debug"]).args;
+        assert!(args.rpc_unsafe_debug);
+    }
+
+    #[test]
+    fn test_rpc_flashbots() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert!(!args.rpc_flashbots);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc.flashbots"]).args;
+        assert!(args.rpc_flashbots);
+    }
+
+    #[test]
+    fn test_rpc_memory_limit() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.eth_config();
+        assert_eq!(config.rpc_memory_limit, constants::DEFAULT_RPC_MEMORY_LIMIT);
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc.memory-limit", "1048576"])
+                .args;
+        let config = args.eth_config();
+        assert_eq!(config.rpc_memory_limit, 1048576);
+
+        let args =
+            CommandParser::<RpcServerArgs>::try_parse_from(["reth", "--rpc.memory-limit", "1024"]);
+        assert!(args.is_err());
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.memory-limit",
+            &(constants::MAX_RPC_MEMORY_LIMIT + 1).to_string(),
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_send_raw_tx_sync_timeout() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.eth_config();
+        assert_eq!(
+            config.send_raw_transaction_sync_timeout,
+            constants::DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT
+        );
+        assert_eq!(
+            config.max_send_raw_transaction_sync_timeout,
+            constants::DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT
+        );
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.send-raw-tx-sync-timeout",
+            "5000",
+            "--rpc.max-send-raw-tx-sync-timeout",
+            "60000",
+        ])
+        .args;
+        let config = args.eth_config();
+        assert_eq!(config.send_raw_transaction_sync_timeout, Duration::from_secs(5));
+        assert_eq!(config.max_send_raw_transaction_sync_timeout, Duration::from_secs(60));
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.send-raw-tx-sync-timeout",
+            "0",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_tx_conditional() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.eth_config();
+        assert!(!config.tx_conditional);
+        assert_eq!(config.max_tx_conditional_cost, constants::DEFAULT_MAX_TX_CONDITIONAL_COST);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.enable-tx-conditional",
+            "--rpc.tx-conditional-max-cost",
+            "10",
+        ])
+        .args;
+        let config = args.eth_config();
+        assert!(config.tx_conditional);
+        assert_eq!(config.max_tx_conditional_cost, 10);
+    }
+
+    #[test]
+    fn test_rpc_call_limits() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.rpc_max_batch_concurrency, constants::DEFAULT_MAX_BATCH_CONCURRENCY);
+        assert_eq!(
+            args.rpc_max_concurrent_calls_per_connection,
+            constants::DEFAULT_MAX_CONCURRENT_CALLS_PER_CONNECTION
+        );
+        assert_eq!(
+            args.rpc_max_queued_calls_per_connection,
+            constants::DEFAULT_MAX_QUEUED_CALLS_PER_CONNECTION
+        );
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.max-batch-concurrency",
+            "64",
+            "--rpc.max-concurrent-calls-per-connection",
+            "4",
+            "--rpc.max-queued-calls-per-connection",
+            "100",
+        ])
+        .args;
+        assert_eq!(args.rpc_max_batch_concurrency, 64);
+        assert_eq!(args.rpc_max_concurrent_calls_per_connection, 4);
+        assert_eq!(args.rpc_max_queued_calls_per_connection, 100);
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.max-concurrent-calls-per-connection",
+            "0",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_ws_subscription_buffer() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.ws_max_subscription_buffer, constants::DEFAULT_MAX_SUBSCRIPTION_BUFFER);
+        assert_eq!(args.ws_subscription_backpressure, SubscriptionBackpressurePolicy::Wait);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--ws.max-subscription-buffer",
+            "16",
+            "--ws.subscription-backpressure",
+            "disconnect",
+        ])
+        .args;
+        let config = args.eth_config().pubsub_config();
+        assert_eq!(config.max_subscription_buffer, 16);
+        assert_eq!(config.backpressure_policy, SubscriptionBackpressurePolicy::Disconnect);
+        assert_eq!(
+            config.max_transaction_filter_addresses,
+            constants::DEFAULT_MAX_TRANSACTION_FILTER_ADDRESSES
+        );
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--ws.max-pending-tx-filter-addresses",
+            "10",
+        ])
+        .args;
+        assert_eq!(args.eth_config().pubsub_config().max_transaction_filter_addresses, 10);
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--ws.subscription-backpressure",
+            "block",
+        ]);
+        assert!(args.is_err());
+    }
+
     #[test]
     fn test_rpc_server_args_parser() {
         let args =
@@ -652,6 +1388,26 @@ mod tests {
         let config = args.eth_config().filter_config();
         assert_eq!(config.max_blocks_per_filter, Some(100));
         assert_eq!(config.max_logs_per_response, Some(200));
+        assert_eq!(config.query_timeout, None);
+    }
+
+    #[test]
+    fn test_eth_getlogs_limits() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.eth-getlogs.max-range",
+            "100",
+            "--rpc.eth-getlogs.max-results",
+            "200",
+            "--rpc.eth-getlogs.timeout",
+            "3000",
+        ])
+        .args;
+
+        let config = args.eth_config().filter_config();
+        assert_eq!(config.max_blocks_per_filter, Some(100));
+        assert_eq!(config.max_logs_per_response, Some(200));
+        assert_eq!(config.query_timeout, Some(Duration::from_secs(3)));
     }
 
     #[test]
@@ -1,28 +1,52 @@
 use clap::Args;
 use reth_rpc_server_types::constants::cache::{
-    DEFAULT_BLOCK_CACHE_MAX_LEN, DEFAULT_CONCURRENT_DB_REQUESTS, DEFAULT_ENV_CACHE_MAX_LEN,
-    DEFAULT_RECEIPT_CACHE_MAX_LEN,
+    DEFAULT_BLOCK_CACHE_MAX_LEN, DEFAULT_BLOCK_CACHE_SIZE_BYTES_MB, DEFAULT_CONCURRENT_DB_REQUESTS,
+    DEFAULT_ENV_CACHE_MAX_LEN, DEFAULT_FEE_HISTORY_CACHE_MAX_LEN,
+    DEFAULT_HEADER_CACHE_SIZE_BYTES_MB, DEFAULT_RECEIPT_CACHE_MAX_LEN,
+    DEFAULT_RECEIPT_CACHE_SIZE_BYTES_MB,
 };
 
 /// Parameters to configure RPC state cache.
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
 #[command(next_help_heading = "RPC State Cache")]
 pub struct RpcStateCacheArgs {
-    /// Max number of blocks in cache.
+    /// Max size of the cached blocks in megabytes.
     #[arg(
-        long = "rpc-cache.max-blocks",
-        default_value_t = DEFAULT_BLOCK_CACHE_MAX_LEN,
+        long = "rpc-cache.max-block-mb",
+        value_name = "MB",
+        default_value_t = DEFAULT_BLOCK_CACHE_SIZE_BYTES_MB,
     )]
-    pub max_blocks: u32,
+    pub max_block_mb: usize,
 
-    /// Max number receipts in cache.
+    /// Max size of the cached receipts in megabytes.
     #[arg(
-        long = "rpc-cache.max-receipts",
-        default_value_t = DEFAULT_RECEIPT_CACHE_MAX_LEN,
+        long = "rpc-cache.max-receipt-mb",
+        value_name = "MB",
+        default_value_t = DEFAULT_RECEIPT_CACHE_SIZE_BYTES_MB,
     )]
-    pub max_receipts: u32,
+    pub max_receipt_mb: usize,
 
-    /// Max number of bytes for cached env data.
+    /// Max size of the cached headers in megabytes.
+    #[arg(
+        long = "rpc-cache.max-header-mb",
+        value_name = "MB",
+        default_value_t = DEFAULT_HEADER_CACHE_SIZE_BYTES_MB,
+    )]
+    pub max_header_mb: usize,
+
+    /// Deprecated: max number of blocks in cache, use `--rpc-cache.max-block-mb` instead.
+    ///
+    /// If set, it's converted to a size based on the default average size of a cached block.
+    #[arg(long = "rpc-cache.max-blocks", hide = true)]
+    pub max_blocks: Option<u32>,
+
+    /// Deprecated: max number receipts in cache, use `--rpc-cache.max-receipt-mb` instead.
+    ///
+    /// If set, it's converted to a size based on the default average size of cached receipts.
+    #[arg(long = "rpc-cache.max-receipts", hide = true)]
+    pub max_receipts: Option<u32>,
+
+    /// Max number of cached env data.
     #[arg(
         long = "rpc-cache.max-envs",
         default_value_t = DEFAULT_ENV_CACHE_MAX_LEN,
@@ -35,15 +59,67 @@ pub struct RpcStateCacheArgs {
         default_value_t = DEFAULT_CONCURRENT_DB_REQUESTS,
     )]
     pub max_concurrent_db_requests: usize,
+
+    /// Max number of blocks in the `eth_feeHistory` cache.
+    #[arg(
+        long = "rpc-cache.max-fee-history-blocks",
+        default_value_t = DEFAULT_FEE_HISTORY_CACHE_MAX_LEN,
+    )]
+    pub max_fee_history_blocks: u64,
+}
+
+impl RpcStateCacheArgs {
+    /// Returns the max size of the cached blocks in bytes.
+    ///
+    /// The deprecated `--rpc-cache.max-blocks` takes precedence if set.
+    pub fn max_block_bytes(&self) -> usize {
+        match self.max_blocks {
+            Some(max_blocks) => count_to_bytes(
+                max_blocks,
+                DEFAULT_BLOCK_CACHE_SIZE_BYTES_MB,
+                DEFAULT_BLOCK_CACHE_MAX_LEN,
+            ),
+            None => self.max_block_mb.saturating_mul(1024 * 1024),
+        }
+    }
+
+    /// Returns the max size of the cached receipts in bytes.
+    ///
+    /// The deprecated `--rpc-cache.max-receipts` takes precedence if set.
+    pub fn max_receipt_bytes(&self) -> usize {
+        match self.max_receipts {
+            Some(max_receipts) => count_to_bytes(
+                max_receipts,
+                DEFAULT_RECEIPT_CACHE_SIZE_BYTES_MB,
+                DEFAULT_RECEIPT_CACHE_MAX_LEN,
+            ),
+            None => self.max_receipt_mb.saturating_mul(1024 * 1024),
+        }
+    }
+
+    /// Returns the max size of the cached headers in bytes.
+    pub const fn max_header_bytes(&self) -> usize {
+        self.max_header_mb.saturating_mul(1024 * 1024)
+    }
+}
+
+/// Converts a deprecated count based cache limit to bytes, assuming the average entry size of the
+/// default limits.
+fn count_to_bytes(count: u32, default_mb: usize, default_count: u32) -> usize {
+    (count as usize).saturating_mul(default_mb * 1024 * 1024) / default_count as usize
 }
 
 impl Default for RpcStateCacheArgs {
     fn default() -> Self {
         Self {
-            max_blocks: DEFAULT_BLOCK_CACHE_MAX_LEN,
-            max_receipts: DEFAULT_RECEIPT_CACHE_MAX_LEN,
+            max_block_mb: DEFAULT_BLOCK_CACHE_SIZE_BYTES_MB,
+            max_receipt_mb: DEFAULT_RECEIPT_CACHE_SIZE_BYTES_MB,
+            max_header_mb: DEFAULT_HEADER_CACHE_SIZE_BYTES_MB,
+            max_blocks: None,
+            max_receipts: None,
             max_envs: DEFAULT_ENV_CACHE_MAX_LEN,
             max_concurrent_db_requests: DEFAULT_CONCURRENT_DB_REQUESTS,
+            max_fee_history_blocks: DEFAULT_FEE_HISTORY_CACHE_MAX_LEN,
         }
     }
 }
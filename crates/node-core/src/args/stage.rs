@@ -50,4 +50,8 @@ pub enum StageEnum {
     ///
     /// Manages historical data related to storage.
     StorageHistory,
+    /// The address transactions stage within the pipeline.
+    ///
+    /// Indexes the blocks with transactions of an address.
+    AddressTransactions,
 }
@@ -0,0 +1,111 @@
+//! clap [Args](clap::Args) for trie computation configuration
+
+use clap::{builder::RangedU64ValueParser, Args};
+use reth_trie::trie_cursor::{TrieNodeCache, DEFAULT_TRIE_NODE_CACHE_SIZE};
+use reth_trie_parallel::{
+    ParallelStateRootConfig, DEFAULT_FALLBACK_THRESHOLD, DEFAULT_MIN_PREFIXES_PER_WORKER,
+};
+
+/// Parameters for configuring the trie computations
+#[derive(Debug, Clone, Copy, Args, PartialEq, Eq)]
+#[command(next_help_heading = "Trie")]
+pub struct TrieArgs {
+    /// Maximum number of workers computing storage roots in parallel during state root
+    /// computation.
+    ///
+    /// Defaults to the number of available CPU cores.
+    #[arg(long = "trie.workers", value_name = "WORKERS", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub workers: Option<usize>,
+
+    /// Minimum number of changed storage tries assigned to a single worker.
+    #[arg(long = "trie.min-prefixes-per-worker", default_value_t = DEFAULT_MIN_PREFIXES_PER_WORKER, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub min_prefixes_per_worker: usize,
+
+    /// Number of changed storage tries below which storage roots are computed sequentially.
+    #[arg(long = "trie.fallback-threshold", default_value_t = DEFAULT_FALLBACK_THRESHOLD)]
+    pub fallback_threshold: usize,
+
+    /// Maximum number of database trie nodes cached for the state root validation of new
+    /// payloads.
+    ///
+    /// Set to 0 to disable the cache.
+    #[arg(long = "trie.node-cache-size", value_name = "NODES", default_value_t = DEFAULT_TRIE_NODE_CACHE_SIZE)]
+    pub node_cache_size: u32,
+}
+
+impl Default for TrieArgs {
+    fn default() -> Self {
+        Self {
+            workers: None,
+            min_prefixes_per_worker: DEFAULT_MIN_PREFIXES_PER_WORKER,
+            fallback_threshold: DEFAULT_FALLBACK_THRESHOLD,
+            node_cache_size: DEFAULT_TRIE_NODE_CACHE_SIZE,
+        }
+    }
+}
+
+impl TrieArgs {
+    /// Returns the parallel state root configuration.
+    pub fn parallel_state_root_config(&self) -> ParallelStateRootConfig {
+        let mut config = ParallelStateRootConfig::default()
+            .with_min_prefixes_per_worker(self.min_prefixes_per_worker)
+            .with_fallback_threshold(self.fallback_threshold);
+        if let Some(workers) = self.workers {
+            config = config.with_workers(workers);
+        }
+        config
+    }
+
+    /// Returns the cache of the database trie nodes, or `None` if it's disabled.
+    pub fn trie_node_cache(&self) -> Option<TrieNodeCache> {
+        (self.node_cache_size > 0).then(|| TrieNodeCache::new(self.node_cache_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn trie_args_default_sanity_check() {
+        let default_args = TrieArgs::default();
+        let args = CommandParser::<TrieArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, default_args);
+    }
+
+    #[test]
+    fn parse_trie_args() {
+        let args = CommandParser::<TrieArgs>::parse_from([
+            "reth",
+            "--trie.workers",
+            "16",
+            "--trie.min-prefixes-per-worker",
+            "2",
+            "--trie.fallback-threshold",
+            "32",
+            "--trie.node-cache-size",
+            "0",
+        ])
+        .args;
+        assert_eq!(
+            args.parallel_state_root_config(),
+            ParallelStateRootConfig {
+                workers: 16,
+                min_prefixes_per_worker: 2,
+                fallback_threshold: 32
+            }
+        );
+
+        assert!(args.trie_node_cache().is_none());
+
+        assert!(CommandParser::<TrieArgs>::try_parse_from(["reth", "--trie.workers", "0"]).is_err());
+    }
+}
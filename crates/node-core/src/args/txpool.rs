@@ -4,9 +4,11 @@ use crate::cli::config::RethTransactionPoolConfig;
 use clap::Args;
 use reth_primitives::Address;
 use reth_transaction_pool::{
-    blobstore::disk::DEFAULT_MAX_CACHED_BLOBS, validate::DEFAULT_MAX_TX_INPUT_BYTES,
-    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-    REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+    blobstore::{disk::DEFAULT_MAX_CACHED_BLOBS, DiskFileBlobStoreConfig, OpenDiskFileBlobStore},
+    validate::DEFAULT_MAX_TX_INPUT_BYTES,
+    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_MAX_QUEUED_LIFETIME,
+    DEFAULT_PRICE_BUMP, REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PENDING_PER_SENDER,
+    TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER, TXPOOL_MAX_ACCOUNT_SLOTS_QUEUED_PER_SENDER,
     TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
 };
 /// Parameters for debugging purposes
@@ -37,6 +39,12 @@ pub struct TxPoolArgs {
     /// Max number of executable transaction slots guaranteed per account
     #[arg(long = "txpool.max-account-slots", alias = "txpool.max_account_slots", default_value_t = TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER)]
     pub max_account_slots: usize,
+    /// Max number of pending transactions per account
+    #[arg(long = "txpool.max-account-slots-pending", alias = "txpool.max_account_slots_pending", default_value_t = TXPOOL_MAX_ACCOUNT_SLOTS_PENDING_PER_SENDER)]
+    pub max_account_slots_pending: usize,
+    /// Max number of queued transactions per account
+    #[arg(long = "txpool.max-account-slots-queued", alias = "txpool.max_account_slots_queued", default_value_t = TXPOOL_MAX_ACCOUNT_SLOTS_QUEUED_PER_SENDER)]
+    pub max_account_slots_queued: usize,
 
     /// Price bump (in %) for the transaction pool underpriced check.
     #[arg(long = "txpool.pricebump", default_value_t = DEFAULT_PRICE_BUMP)]
@@ -46,6 +54,11 @@ pub struct TxPoolArgs {
     #[arg(long = "blobpool.pricebump", default_value_t = REPLACE_BLOB_PRICE_BUMP)]
     pub blob_transaction_price_bump: u128,
 
+    /// Price bump percentage on the max fee per blob gas to replace an already existing blob
+    /// transaction
+    #[arg(long = "blobpool.blobfee-pricebump", default_value_t = REPLACE_BLOB_PRICE_BUMP)]
+    pub blob_fee_price_bump: u128,
+
     /// Max size in bytes of a single transaction allowed to enter the pool
     #[arg(long = "txpool.max-tx-input-bytes", alias = "txpool.max_tx_input_bytes", default_value_t = DEFAULT_MAX_TX_INPUT_BYTES)]
     pub max_tx_input_bytes: usize,
@@ -54,6 +67,12 @@ pub struct TxPoolArgs {
     #[arg(long = "txpool.max-cached-entries", alias = "txpool.max_cached_entries", default_value_t = DEFAULT_MAX_CACHED_BLOBS)]
     pub max_cached_entries: u32,
 
+    /// Max size of the blobs stored on disk in megabytes, unlimited if not set.
+    ///
+    /// If exceeded, the blobs of transactions that are no longer in the pool are evicted.
+    #[arg(long = "txpool.blobstore-max-size", alias = "txpool.blobstore_max_size")]
+    pub blobstore_max_size: Option<usize>,
+
     /// Flag to disable local transaction exemptions.
     #[arg(long = "txpool.nolocals")]
     pub no_locals: bool,
@@ -63,6 +82,11 @@ pub struct TxPoolArgs {
     /// Flag to toggle local transaction propagation.
     #[arg(long = "txpool.no-local-transactions-propagation")]
     pub no_local_transactions_propagation: bool,
+    /// Flag to disable the journal of local transactions.
+    ///
+    /// If disabled, local transactions are not persisted across restarts.
+    #[arg(long = "txpool.nolocals-journal")]
+    pub no_locals_journal: bool,
 }
 
 impl Default for TxPoolArgs {
@@ -75,14 +99,35 @@ impl Default for TxPoolArgs {
             queued_max_count: TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
             queued_max_size: TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+            max_account_slots_pending: TXPOOL_MAX_ACCOUNT_SLOTS_PENDING_PER_SENDER,
+            max_account_slots_queued: TXPOOL_MAX_ACCOUNT_SLOTS_QUEUED_PER_SENDER,
             price_bump: DEFAULT_PRICE_BUMP,
             blob_transaction_price_bump: REPLACE_BLOB_PRICE_BUMP,
+            blob_fee_price_bump: REPLACE_BLOB_PRICE_BUMP,
             max_tx_input_bytes: DEFAULT_MAX_TX_INPUT_BYTES,
             max_cached_entries: DEFAULT_MAX_CACHED_BLOBS,
+            blobstore_max_size: None,
             no_locals: false,
             locals: Default::default(),
             no_local_transactions_propagation: false,
+            no_locals_journal: false,
+        }
+    }
+}
+
+impl TxPoolArgs {
+    /// Returns the configuration of the disk file blob store.
+    ///
+    /// The existing blob store is re-indexed, so that the blobs of the persisted local
+    /// transactions survive a restart.
+    pub fn blob_store_config(&self) -> DiskFileBlobStoreConfig {
+        let mut config = DiskFileBlobStoreConfig::default()
+            .with_max_cached_entries(self.max_cached_entries)
+            .with_open(OpenDiskFileBlobStore::ReIndex);
+        if let Some(max_size) = self.blobstore_max_size {
+            config = config.with_max_size(max_size * 1024 * 1024);
         }
+        config
     }
 }
 
@@ -94,6 +139,7 @@ impl RethTransactionPoolConfig for TxPoolArgs {
                 no_exemptions: self.no_locals,
                 local_addresses: self.locals.clone().into_iter().collect(),
                 propagate_local_transactions: !self.no_local_transactions_propagation,
+                ..Default::default()
             },
             pending_limit: SubPoolLimit {
                 max_txs: self.pending_max_count,
@@ -112,10 +158,14 @@ impl RethTransactionPoolConfig for TxPoolArgs {
                 max_size: self.queued_max_size * 1024 * 1024,
             },
             max_account_slots: self.max_account_slots,
+            max_account_slots_pending: self.max_account_slots_pending,
+            max_account_slots_queued: self.max_account_slots_queued,
             price_bumps: PriceBumpConfig {
                 default_price_bump: self.price_bump,
                 replace_blob_tx_price_bump: self.blob_transaction_price_bump,
+                replace_blob_tx_blob_fee_price_bump: self.blob_fee_price_bump,
             },
+            max_queued_lifetime: DEFAULT_MAX_QUEUED_LIFETIME,
         }
     }
 }
@@ -138,4 +188,49 @@ mod tests {
         let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn txpool_parse_max_account_slots() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.max-account-slots-pending",
+            "4",
+            "--txpool.max-account-slots-queued",
+            "8",
+        ])
+        .args;
+        let config = args.pool_config();
+        assert_eq!(config.max_account_slots_pending, 4);
+        assert_eq!(config.max_account_slots_queued, 8);
+    }
+
+    #[test]
+    fn txpool_parse_price_bumps() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.pricebump",
+            "1",
+            "--blobpool.pricebump",
+            "50",
+            "--blobpool.blobfee-pricebump",
+            "200",
+        ])
+        .args;
+        let config = args.pool_config();
+        assert_eq!(
+            config.price_bumps,
+            PriceBumpConfig {
+                default_price_bump: 1,
+                replace_blob_tx_price_bump: 50,
+                replace_blob_tx_blob_fee_price_bump: 200,
+            }
+        );
+    }
+
+    #[test]
+    fn txpool_parse_nolocals_journal() {
+        let args =
+            CommandParser::<TxPoolArgs>::parse_from(["reth", "--txpool.nolocals-journal"]).args;
+        assert!(args.no_locals_journal);
+    }
 }
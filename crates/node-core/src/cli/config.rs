@@ -2,14 +2,18 @@
 
 use alloy_rpc_types_engine::{JwtError, JwtSecret};
 use reth_network::protocol::IntoRlpxSubProtocol;
-use reth_primitives::Bytes;
+use reth_primitives::{Bytes, U256};
 use reth_rpc::eth::{cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig};
 use reth_rpc_builder::{
     auth::AuthServerConfig, error::RpcError, EthConfig, Identity, IpcServerBuilder,
     RpcServerConfig, ServerBuilder, TransportRpcModuleConfig,
 };
 use reth_transaction_pool::PoolConfig;
-use std::{borrow::Cow, path::PathBuf, time::Duration};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 /// A trait that provides a configured RPC server.
 ///
@@ -100,6 +104,28 @@ pub trait PayloadBuilderConfig {
 
     /// Maximum number of tasks to spawn for building a payload.
     fn max_payload_tasks(&self) -> usize;
+
+    /// The minimum projected fee gain of the new pending transactions that is required to re-pack
+    /// a payload.
+    fn min_fee_improvement(&self) -> Option<U256>;
+
+    /// Whether to drop pool transactions that revert or halt from locally built payloads.
+    fn drop_reverting_txs(&self) -> bool;
+
+    /// The file with the senders whose reverting transactions are included nonetheless.
+    fn revert_allowlist(&self) -> Option<&Path>;
+
+    /// Whether to include local transactions in locally built payloads ahead of the tip ordering.
+    fn local_tx_priority(&self) -> bool;
+
+    /// The minimum effective tip per gas that prioritized local transactions must pay.
+    fn local_tx_min_tip(&self) -> Option<u128>;
+
+    /// Whether to persist payload jobs and recently built payloads across restarts.
+    fn persist_payloads(&self) -> bool;
+
+    /// The maximum number of built payloads that are persisted.
+    fn max_persisted_payloads(&self) -> usize;
 }
 
 /// A trait that represents the configured network and can be used to apply additional configuration
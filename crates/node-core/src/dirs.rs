@@ -323,6 +323,14 @@ impl<D> ChainPath<D> {
         self.data_dir().join("blobstore")
     }
 
+    /// Returns the path to the directory where payload jobs and recently built payloads are
+    /// persisted.
+    ///
+    /// `<DIR>/<CHAIN_ID>/payloads`
+    pub fn payloads(&self) -> PathBuf {
+        self.data_dir().join("payloads")
+    }
+
     /// Returns the path to the local transactions backup file
     ///
     /// `<DIR>/<CHAIN_ID>/txpool-transactions-backup.rlp`
@@ -330,6 +338,21 @@ impl<D> ChainPath<D> {
         self.data_dir().join("txpool-transactions-backup.rlp")
     }
 
+    /// Returns the path to the journal of local transactions
+    ///
+    /// `<DIR>/<CHAIN_ID>/txpool-journal.rlp`
+    pub fn txpool_journal(&self) -> PathBuf {
+        self.data_dir().join("txpool-journal.rlp")
+    }
+
+    /// Returns the path to the directory where snapshots of the transaction pool are written by
+    /// `reth_txpoolDump`.
+    ///
+    /// `<DIR>/<CHAIN_ID>/txpool-snapshots`
+    pub fn txpool_snapshots(&self) -> PathBuf {
+        self.data_dir().join("txpool-snapshots")
+    }
+
     /// Returns the path to the config file for this chain.
     ///
     /// `<DIR>/<CHAIN_ID>/reth.toml`
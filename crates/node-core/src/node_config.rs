@@ -2,8 +2,8 @@
 
 use crate::{
     args::{
-        DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs,
-        PruningArgs, RpcServerArgs, TxPoolArgs,
+        DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, EngineArgs, ExecutionArgs, NetworkArgs,
+        PayloadBuilderArgs, PruningArgs, RpcServerArgs, TrieArgs, TxPoolArgs,
     },
     dirs::{ChainPath, DataDirPath},
     metrics::prometheus_exporter,
@@ -144,6 +144,15 @@ pub struct NodeConfig {
 
     /// All pruning related arguments
     pub pruning: PruningArgs,
+
+    /// All trie related arguments with --trie prefix
+    pub trie: TrieArgs,
+
+    /// All block execution related arguments with --execution prefix
+    pub execution: ExecutionArgs,
+
+    /// All engine API related arguments with --engine prefix
+    pub engine: EngineArgs,
 }
 
 impl NodeConfig {
@@ -238,6 +247,24 @@ impl NodeConfig {
         self
     }
 
+    /// Set the trie args for the node
+    pub const fn with_trie(mut self, trie: TrieArgs) -> Self {
+        self.trie = trie;
+        self
+    }
+
+    /// Set the execution args for the node
+    pub const fn with_execution(mut self, execution: ExecutionArgs) -> Self {
+        self.execution = execution;
+        self
+    }
+
+    /// Set the engine args for the node
+    pub const fn with_engine(mut self, engine: EngineArgs) -> Self {
+        self.engine = engine;
+        self
+    }
+
     /// Returns the initial pipeline target, based on whether or not the node is running in
     /// `debug.tip` mode, `debug.continuous` mode, or neither.
     ///
@@ -441,6 +468,9 @@ impl Default for NodeConfig {
             db: DatabaseArgs::default(),
             dev: DevArgs::default(),
             pruning: PruningArgs::default(),
+            trie: TrieArgs::default(),
+            execution: ExecutionArgs::default(),
+            engine: EngineArgs::default(),
             datadir: DatadirArgs::default(),
         }
     }
@@ -32,6 +32,7 @@ use reth_node_core::{
 };
 use reth_primitives::{constants::eip4844::MAINNET_KZG_TRUSTED_SETUP, ChainSpec};
 use reth_provider::{providers::BlockchainProvider, ChainSpecProvider};
+use reth_revm::cached::PayloadReadsCache;
 use reth_tasks::TaskExecutor;
 use reth_transaction_pool::{PoolConfig, TransactionPool};
 use secp256k1::SecretKey;
@@ -412,17 +413,25 @@ pub struct BuilderContext<Node: FullNodeTypes> {
     pub(crate) executor: TaskExecutor,
     /// Config container
     pub(crate) config_container: WithConfigs,
+    /// The reads of the payloads built by the node, shared with the blockchain tree.
+    pub(crate) payload_reads_cache: PayloadReadsCache,
 }
 
 impl<Node: FullNodeTypes> BuilderContext<Node> {
     /// Create a new instance of [`BuilderContext`]
-    pub const fn new(
+    pub fn new(
         head: Head,
         provider: Node::Provider,
         executor: TaskExecutor,
         config_container: WithConfigs,
     ) -> Self {
-        Self { head, provider, executor, config_container }
+        Self {
+            head,
+            provider,
+            executor,
+            config_container,
+            payload_reads_cache: PayloadReadsCache::default(),
+        }
     }
 
     /// Returns the configured provider to interact with the blockchain.
@@ -457,6 +466,14 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         self.provider().chain_spec()
     }
 
+    /// Returns the cache the payload builder deposits the reads of the built payloads to.
+    ///
+    /// The blockchain tree uses the same cache to warm up the execution of the payloads that are
+    /// received back through `engine_newPayload`.
+    pub const fn payload_reads_cache(&self) -> &PayloadReadsCache {
+        &self.payload_reads_cache
+    }
+
     /// Returns the transaction pool config of the node.
     pub fn pool_config(&self) -> PoolConfig {
         self.config().txpool.pool_config()
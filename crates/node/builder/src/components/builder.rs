@@ -8,6 +8,8 @@ use crate::{
     BuilderContext, ConfigureEvm, FullNodeTypes,
 };
 use reth_evm::execute::BlockExecutorProvider;
+use reth_payload_builder::noop::NoopPayloadBuilderService;
+use reth_tracing::tracing::info;
 use reth_transaction_pool::TransactionPool;
 use std::{future::Future, marker::PhantomData};
 
@@ -64,8 +66,14 @@ impl<Node, PoolB, PayloadB, NetworkB, ExecB>
     }
 
     /// Apply a function to the pool builder.
-    pub fn map_pool(self, f: impl FnOnce(PoolB) -> PoolB) -> Self {
-        Self {
+    ///
+    /// The function may return a pool builder of a different type, for example to configure the
+    /// ordering of the pool's transactions.
+    pub fn map_pool<PB>(
+        self,
+        f: impl FnOnce(PoolB) -> PB,
+    ) -> ComponentsBuilder<Node, PB, PayloadB, NetworkB, ExecB> {
+        ComponentsBuilder {
             pool_builder: f(self.pool_builder),
             payload_builder: self.payload_builder,
             network_builder: self.network_builder,
@@ -250,7 +258,18 @@ where
         let (evm_config, executor) = evm_builder.build_evm(context).await?;
         let pool = pool_builder.build_pool(context).await?;
         let network = network_builder.build_network(context, pool.clone()).await?;
-        let payload_builder = payload_builder.spawn_payload_service(context, pool.clone()).await?;
+
+        // a validation-only node never builds payloads, so the payload builder isn't spawned
+        let payload_builder = if context.config().engine.validation_only {
+            info!(target: "reth::cli", "Engine in validation-only mode, payload builder disabled");
+            let (payload_service, payload_builder) = NoopPayloadBuilderService::new();
+            context
+                .task_executor()
+                .spawn_critical("payload builder service", Box::pin(payload_service));
+            payload_builder
+        } else {
+            payload_builder.spawn_payload_service(context, pool.clone()).await?
+        };
 
         Ok(Components { transaction_pool: pool, evm_config, network, payload_builder, executor })
     }
@@ -1,8 +1,12 @@
 //! Types for launching execution extensions (ExEx).
 use futures::{future::BoxFuture, FutureExt};
-use reth_exex::ExExContext;
+use reth_beacon_consensus::{BackfillAction, BackfillHook, BackfillRequest};
+use reth_exex::{ExExContext, ExExManagerHandle};
 use reth_node_api::FullNodeComponents;
-use std::future::Future;
+use std::{
+    future::Future,
+    task::{ready, Context, Poll},
+};
 
 /// A trait for launching an `ExEx`.
 trait LaunchExEx<Node: FullNodeComponents>: Send {
@@ -61,3 +65,26 @@ where
         self(ctx)
     }
 }
+
+/// A [`BackfillHook`] that delays backfill sync until the `ExEx` manager has capacity for new
+/// notifications.
+///
+/// This gives the `ExEx`'s a chance to process the notifications of the live synced blocks before
+/// the pipeline commits a large range of blocks at once.
+#[derive(Debug)]
+pub(crate) struct ExExBackfillHook(pub(crate) ExExManagerHandle);
+
+impl BackfillHook for ExExBackfillHook {
+    fn name(&self) -> &'static str {
+        "ExEx"
+    }
+
+    fn poll_backfill(
+        &mut self,
+        cx: &mut Context<'_>,
+        _request: &BackfillRequest,
+    ) -> Poll<BackfillAction> {
+        ready!(self.0.poll_ready(cx));
+        Poll::Ready(BackfillAction::Proceed)
+    }
+}
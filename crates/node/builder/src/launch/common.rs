@@ -461,6 +461,20 @@ where
         init_genesis(self.provider_factory().clone())
     }
 
+    /// Drops the index of the blocks with transactions of an address if its stage is disabled.
+    ///
+    /// Without the stage the pipeline doesn't unwind the index, so it would go stale.
+    pub fn with_address_transactions_index(self) -> eyre::Result<Self> {
+        if !self.toml_config().stages.index_address_transactions.enabled {
+            let provider = self.provider_factory().provider_rw()?;
+            if provider.drop_address_transaction_index()? {
+                info!(target: "reth::cli", "Dropped the address transaction index of the disabled IndexAddressTransactions stage");
+                provider.commit()?;
+            }
+        }
+        Ok(self)
+    }
+
     /// Returns the max block that the node should run to, looking it up from the network if
     /// necessary
     pub async fn max_block<C>(&self, client: C) -> eyre::Result<Option<BlockNumber>>
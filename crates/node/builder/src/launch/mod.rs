@@ -3,6 +3,7 @@
 use crate::{
     builder::{NodeAdapter, NodeAddOns, NodeTypesAdapter},
     components::{NodeComponents, NodeComponentsBuilder},
+    exex::ExExBackfillHook,
     hooks::NodeHooks,
     launch::common::WithConfigs,
     node::FullNode,
@@ -31,11 +32,12 @@ use reth_node_core::{
 use reth_node_events::{cl::ConsensusLayerHealthEvents, node};
 use reth_primitives::format_ether;
 use reth_provider::{providers::BlockchainProvider, CanonStateSubscriptions};
-use reth_rpc_engine_api::EngineApi;
+use reth_rpc_engine_api::{EngineApi, PayloadBuildingMode};
 use reth_rpc_types::engine::ClientVersionV1;
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::TransactionPool;
+use reth_trie::CancellationToken;
 use std::{future::Future, sync::Arc};
 use tokio::sync::{mpsc::unbounded_channel, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
@@ -112,6 +114,7 @@ where
                 debug!(target: "reth::cli", chain=%this.chain_id(), genesis=?this.genesis_hash(), "Initializing genesis");
             })
             .with_genesis()?
+            .with_address_transactions_index()?
             .inspect(|this| {
                 info!(target: "reth::cli", "\n{}", this.chain_spec().display_hardforks());
             });
@@ -159,11 +162,28 @@ where
         debug!(target: "reth::cli", "creating components");
         let components = components_builder.build_components(&builder_ctx).await?;
 
+        // Interrupt long-running state root computations on shutdown
+        let state_root_cancellation = CancellationToken::default();
+        ctx.task_executor().spawn_with_signal(|shutdown| {
+            let cancellation = state_root_cancellation.clone();
+            async move {
+                shutdown.await;
+                cancellation.cancel();
+            }
+        });
+
         let tree_externals = TreeExternals::new(
             ctx.provider_factory().clone(),
             consensus.clone(),
             components.block_executor().clone(),
-        );
+        )
+        .with_parallel_state_root_config(ctx.node_config().trie.parallel_state_root_config())
+        .with_state_root_cancellation(state_root_cancellation.clone())
+        .with_payload_reads_cache(builder_ctx.payload_reads_cache().clone());
+        let tree_externals = match ctx.node_config().trie.trie_node_cache() {
+            Some(cache) => tree_externals.with_trie_node_cache(cache),
+            None => tree_externals,
+        };
         let tree = BlockchainTree::new(tree_externals, tree_config, ctx.prune_modes())?
             .with_sync_metrics_tx(sync_metrics_tx.clone())
             // Note: This is required because we need to ensure that both the components and the
@@ -322,6 +342,7 @@ where
                 static_file_producer,
                 node_adapter.components.block_executor().clone(),
                 pipeline_exex_handle,
+                state_root_cancellation.clone(),
             )
             .await?;
 
@@ -345,6 +366,7 @@ where
                 static_file_producer,
                 node_adapter.components.block_executor().clone(),
                 pipeline_exex_handle,
+                state_root_cancellation.clone(),
             )
             .await?;
 
@@ -369,21 +391,26 @@ where
         hooks.add(PruneHook::new(pruner, Box::new(ctx.task_executor().clone())));
 
         // Configure the consensus engine
-        let (beacon_consensus_engine, beacon_engine_handle) = BeaconConsensusEngine::with_channel(
-            client,
-            pipeline,
-            blockchain_db.clone(),
-            Box::new(ctx.task_executor().clone()),
-            Box::new(node_adapter.components.network().clone()),
-            max_block,
-            ctx.node_config().debug.continuous,
-            node_adapter.components.payload_builder().clone(),
-            initial_target,
-            reth_beacon_consensus::MIN_BLOCKS_FOR_PIPELINE_RUN,
-            consensus_engine_tx,
-            Box::pin(consensus_engine_stream),
-            hooks,
-        )?;
+        let (mut beacon_consensus_engine, beacon_engine_handle) =
+            BeaconConsensusEngine::with_channel(
+                client,
+                pipeline,
+                blockchain_db.clone(),
+                Box::new(ctx.task_executor().clone()),
+                Box::new(node_adapter.components.network().clone()),
+                max_block,
+                ctx.node_config().debug.continuous,
+                node_adapter.components.payload_builder().clone(),
+                initial_target,
+                ctx.node_config().engine.backfill_trigger_distance,
+                consensus_engine_tx,
+                Box::pin(consensus_engine_stream),
+                hooks,
+            )?;
+        if let Some(exex_manager_handle) = &exex_manager_handle {
+            beacon_consensus_engine
+                .add_backfill_hook(ExExBackfillHook(exex_manager_handle.clone()));
+        }
         info!(target: "reth::cli", "Consensus engine initialized");
 
         let events = stream_select!(
@@ -417,14 +444,25 @@ where
             version: CARGO_PKG_VERSION.to_string(),
             commit: VERGEN_GIT_SHA.to_string(),
         };
+        let engine_args = ctx.node_config().engine;
+        let payload_building = if !engine_args.validation_only {
+            PayloadBuildingMode::Enabled
+        } else if engine_args.ignore_payload_attributes {
+            PayloadBuildingMode::IgnorePayloadAttributes
+        } else {
+            PayloadBuildingMode::ValidationOnly
+        };
         let engine_api = EngineApi::new(
             blockchain_db.clone(),
             ctx.chain_spec(),
             beacon_engine_handle,
             node_adapter.components.payload_builder().clone().into(),
+            node_adapter.components.pool().clone(),
             Box::new(ctx.task_executor().clone()),
             client,
+            payload_building,
         );
+        let engine_capabilities = engine_api.capabilities().clone();
         info!(target: "reth::cli", "Engine API handler initialized");
 
         // extract the jwt secret from the args if possible
@@ -434,6 +472,8 @@ where
         let (rpc_server_handles, mut rpc_registry) = crate::rpc::launch_rpc_servers(
             node_adapter.clone(),
             engine_api,
+            engine_capabilities,
+            Arc::clone(&consensus),
             ctx.node_config(),
             jwt_secret,
             rpc,
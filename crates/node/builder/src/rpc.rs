@@ -1,13 +1,17 @@
 //! Builder support for rpc components.
 
 use futures::TryFutureExt;
+use reth_consensus::Consensus;
 use reth_network::NetworkHandle;
 use reth_node_api::FullNodeComponents;
 use reth_node_core::{
     cli::config::RethRpcConfig,
     node_config::NodeConfig,
     rpc::{
-        api::EngineApiServer,
+        api::{
+            BlockSubmissionValidationApiServer, EngineApiServer, EngineCapabilities,
+            RethPayloadEventsApiServer,
+        },
         builder::{
             auth::{AuthRpcModule, AuthServerHandle},
             RethModuleRegistry, RpcModuleBuilder, RpcServerHandle, TransportRpcModules,
@@ -15,12 +19,16 @@ use reth_node_core::{
     },
 };
 use reth_payload_builder::PayloadBuilderHandle;
+use reth_provider::providers::RawDatabaseProvider;
+use reth_revm::{replay::ReplayExecutor, witness::ExecutionWitnessGenerator};
+use reth_rpc::{RethPayloadEvents, ValidationApi};
 use reth_rpc_layer::JwtSecret;
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{debug, info};
 use std::{
     fmt,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
 /// Contains the handles to the spawned RPC servers.
@@ -255,6 +263,8 @@ impl<'a, Node: FullNodeComponents> RpcContext<'a, Node> {
 pub(crate) async fn launch_rpc_servers<Node, Engine>(
     node: Node,
     engine_api: Engine,
+    engine_capabilities: EngineCapabilities,
+    consensus: Arc<dyn Consensus>,
     config: &NodeConfig,
     jwt_secret: JwtSecret,
     hooks: RpcHooks<Node>,
@@ -266,7 +276,11 @@ where
     let RpcHooks { on_rpc_started, extend_rpc_modules } = hooks;
 
     let auth_config = config.rpc.auth_server_config(jwt_secret)?;
-    let module_config = config.rpc.transport_rpc_module_config();
+    let mut module_config = config.rpc.transport_rpc_module_config();
+    if let Some(module_config) = module_config.config_mut() {
+        // reported by `admin_nodeInfo`
+        *module_config.engine_info_mut() = config.engine.engine_info();
+    }
     debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
 
     let (mut modules, mut auth_module, registry) = RpcModuleBuilder::default()
@@ -277,8 +291,46 @@ where
         .with_executor(node.task_executor().clone())
         .with_evm_config(node.evm_config().clone())
         .build_with_auth_server(module_config, engine_api);
+    // reports the methods that are served by the auth server to `engine_exchangeCapabilities`
+    auth_module.set_engine_capabilities(engine_capabilities);
 
     let mut registry = RpcRegistry { registry };
+    registry.set_execution_witness_provider(Arc::new(ExecutionWitnessGenerator::new(
+        node.provider().clone(),
+        node.block_executor().clone(),
+    )));
+    registry.set_transaction_replay_provider(Arc::new(ReplayExecutor::new(
+        node.provider().clone(),
+        node.evm_config().clone(),
+        config.chain.clone(),
+    )));
+    registry.set_address_transactions_reader(Arc::new(node.provider().clone()));
+    registry.set_raw_block_reader(Arc::new(node.provider().clone()));
+    if config.rpc.rpc_unsafe_debug {
+        registry.set_raw_database_reader(Arc::new(RawDatabaseProvider::<_, Node::DB>::new(
+            node.provider().clone(),
+        )));
+        registry.enable_txpool_snapshots(config.datadir().txpool_snapshots());
+    }
+
+    if config.rpc.rpc_flashbots {
+        let validation_api = ValidationApi::new(
+            node.provider().clone(),
+            consensus,
+            node.block_executor().clone(),
+            config.kzg_settings()?,
+            Box::new(node.task_executor().clone()),
+        );
+        auth_module.merge_auth_methods(validation_api.into_rpc())?;
+    }
+
+    // payload events are only served to the authenticated consensus client
+    let payload_events = RethPayloadEvents::with_spawner(
+        node.payload_builder().clone(),
+        Box::new(node.task_executor().clone()),
+    );
+    auth_module.merge_auth_methods(payload_events.into_rpc())?;
+
     let ctx = RpcContext {
         node: node.clone(),
         config,
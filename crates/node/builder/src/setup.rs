@@ -18,10 +18,15 @@ use reth_node_core::{
     primitives::{BlockNumber, B256},
 };
 use reth_provider::{HeaderSyncMode, ProviderFactory};
-use reth_stages::{prelude::DefaultStages, stages::ExecutionStage, Pipeline, StageSet};
+use reth_stages::{
+    prelude::DefaultStages,
+    stages::{ExecutionStage, MerkleStage},
+    Pipeline, StageSet,
+};
 use reth_static_file::StaticFileProducer;
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::debug;
+use reth_trie::CancellationToken;
 use std::sync::Arc;
 use tokio::sync::watch;
 
@@ -40,6 +45,7 @@ pub async fn build_networked_pipeline<DB, Client, Executor>(
     static_file_producer: StaticFileProducer<DB>,
     executor: Executor,
     exex_manager_handle: ExExManagerHandle,
+    state_root_cancellation: CancellationToken,
 ) -> eyre::Result<Pipeline<DB>>
 where
     DB: Database + Unpin + Clone + 'static,
@@ -68,6 +74,7 @@ where
         static_file_producer,
         executor,
         exex_manager_handle,
+        state_root_cancellation,
     )
     .await?;
 
@@ -89,6 +96,7 @@ pub async fn build_pipeline<DB, H, B, Executor>(
     static_file_producer: StaticFileProducer<DB>,
     executor: Executor,
     exex_manager_handle: ExExManagerHandle,
+    state_root_cancellation: CancellationToken,
 ) -> eyre::Result<Pipeline<DB>>
 where
     DB: Database + Clone + 'static,
@@ -135,6 +143,10 @@ where
                     exex_manager_handle,
                 )
                 .with_metrics_tx(metrics_tx),
+            )
+            .set(
+                MerkleStage::new_execution(stage_config.merkle.clean_threshold)
+                    .with_cancellation(state_root_cancellation),
             ),
         )
         .build(provider_factory, static_file_producer);
@@ -302,6 +302,12 @@ impl<DB> NodeState<DB> {
             BeaconConsensusEngineEvent::ForkBlockAdded(block) => {
                 info!(number=block.number, hash=?block.hash(), "Block added to fork chain");
             }
+            BeaconConsensusEngineEvent::BackfillSyncStarted { target, reason } => {
+                info!(?target, ?reason, "Backfill sync started");
+            }
+            BeaconConsensusEngineEvent::BackfillSyncFinished { target, reason, block_number } => {
+                info!(?target, ?reason, ?block_number, "Backfill sync finished");
+            }
         }
     }
 
@@ -4,20 +4,22 @@ use crate::{l1::ensure_create2_deployer, OptimismBlockExecutionError, OptimismEv
 use reth_evm::{
     execute::{
         BatchBlockExecutionOutput, BatchExecutor, BlockExecutionError, BlockExecutionInput,
-        BlockExecutionOutput, BlockExecutorProvider, BlockValidationError, Executor, ProviderError,
+        BlockExecutionOutput, BlockExecutorProvider, BlockValidationError, Executor,
+        FailedTransaction, ProviderError,
     },
+    system_calls::SystemCaller,
     ConfigureEvm,
 };
 use reth_optimism_consensus::validate_block_post_execution;
 use reth_primitives::{
-    BlockNumber, BlockWithSenders, ChainSpec, Hardfork, Header, Receipt, Receipts, TxType,
-    Withdrawals, U256,
+    BlockNumHash, BlockNumber, BlockWithSenders, ChainSpec, Hardfork, Header, Receipt, Receipts,
+    TxType, Withdrawals, U256,
 };
 use reth_prune_types::PruneModes;
 use reth_revm::{
     batch::{BlockBatchRecord, BlockExecutorStats},
     db::states::bundle_state::BundleRetention,
-    state_change::{apply_beacon_root_contract_call, post_block_balance_increments},
+    state_change::post_block_balance_increments,
     Evm, State,
 };
 use revm_primitives::{
@@ -119,14 +121,10 @@ where
     where
         DB: Database<Error = ProviderError>,
     {
-        // apply pre execution changes
-        apply_beacon_root_contract_call(
-            &self.chain_spec,
-            block.timestamp,
-            block.number,
-            block.parent_beacon_block_root,
-            &mut evm,
-        )?;
+        // apply pre execution changes, optimism doesn't support the EIP-2935 block hashes history
+        SystemCaller::new(&self.chain_spec)
+            .without_blockhashes_update()
+            .apply_pre_execution_changes(&block.header, &mut evm)?;
 
         // execute transactions
         let is_regolith =
@@ -141,7 +139,17 @@ where
 
         let mut cumulative_gas_used = 0;
         let mut receipts = Vec::with_capacity(block.body.len());
-        for (sender, transaction) in block.transactions_with_sender() {
+        for (index, (sender, transaction)) in block.transactions_with_sender().enumerate() {
+            let failed_transaction = || {
+                Box::new(FailedTransaction {
+                    block: BlockNumHash::new(block.number, block.header.hash_slow()),
+                    index,
+                    // Ensure hash is calculated for error log, if not already done
+                    hash: transaction.recalculate_hash(),
+                    cumulative_gas_used,
+                })
+            };
+
             // The sum of the transaction’s gas limit, Tg, and the gas utilized in this block prior,
             // must be no greater than the block’s gasLimit.
             let block_available_gas = block.header.gas_limit - cumulative_gas_used;
@@ -149,6 +157,7 @@ where
                 (is_regolith || !transaction.is_system_transaction())
             {
                 return Err(BlockValidationError::TransactionGasLimitMoreThanAvailableBlockGas {
+                    transaction: failed_transaction(),
                     transaction_gas_limit: transaction.gas_limit(),
                     block_available_gas,
                 }
@@ -178,11 +187,7 @@ where
 
             // Execute transaction.
             let ResultAndState { result, state } = evm.transact().map_err(move |err| {
-                // Ensure hash is calculated for error log, if not already done
-                BlockValidationError::EVM {
-                    hash: transaction.recalculate_hash(),
-                    error: err.into(),
-                }
+                BlockValidationError::EVM { transaction: failed_transaction(), error: err.into() }
             })?;
 
             trace!(
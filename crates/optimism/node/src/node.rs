@@ -231,6 +231,7 @@ where
             .interval(conf.interval())
             .deadline(conf.deadline())
             .max_payload_tasks(conf.max_payload_tasks())
+            .min_fee_improvement(conf.min_fee_improvement())
             // no extradata for OP
             .extradata(Default::default());
 
@@ -241,7 +242,8 @@ where
             payload_job_config,
             ctx.chain_spec(),
             payload_builder,
-        );
+        )
+        .with_payload_reads_cache(ctx.payload_reads_cache().clone());
         let (payload_service, payload_builder) =
             PayloadBuilderService::new(payload_generator, ctx.provider().canonical_state_stream());
 
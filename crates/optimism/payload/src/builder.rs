@@ -9,14 +9,15 @@ use reth_evm::ConfigureEvm;
 use reth_payload_builder::error::PayloadBuilderError;
 use reth_primitives::{
     constants::{BEACON_NONCE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS},
-    eip4844::calculate_excess_blob_gas,
     proofs,
     revm::env::tx_env_with_recovered,
     Block, ChainSpec, Hardfork, Header, IntoRecoveredTransaction, Receipt, Receipts, TxType,
     EMPTY_OMMER_ROOT_HASH, U256,
 };
 use reth_provider::{BundleStateWithReceipts, StateProviderFactory};
-use reth_revm::database::StateProviderDatabase;
+use reth_revm::{
+    database::StateProviderDatabase, precompile_cache::cached_precompiles_handle_register,
+};
 use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
 use revm::{
     db::states::bundle_state::BundleRetention,
@@ -175,14 +176,19 @@ where
         let mut blob_gas_used = None;
 
         if chain_spec.is_cancun_active_at_timestamp(attributes.payload_attributes.timestamp) {
+            let blob_params =
+                chain_spec.blob_params_at_timestamp(attributes.payload_attributes.timestamp);
             excess_blob_gas = if chain_spec.is_cancun_active_at_timestamp(parent_block.timestamp) {
                 let parent_excess_blob_gas = parent_block.excess_blob_gas.unwrap_or_default();
                 let parent_blob_gas_used = parent_block.blob_gas_used.unwrap_or_default();
-                Some(calculate_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used))
+                Some(
+                    blob_params
+                        .next_block_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used),
+                )
             } else {
                 // for the first post-fork block, both parent.blob_gas_used and
                 // parent.excess_blob_gas are evaluated as 0
-                Some(calculate_excess_blob_gas(0, 0))
+                Some(blob_params.next_block_excess_blob_gas(0, 0))
             };
 
             blob_gas_used = Some(0);
@@ -244,7 +250,16 @@ where
     Client: StateProviderFactory,
     Pool: TransactionPool,
 {
-    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload } = args;
+    let BuildArguments {
+        client,
+        pool,
+        mut cached_reads,
+        precompile_cache,
+        config,
+        cancel,
+        best_payload,
+        ..
+    } = args;
 
     let state_provider = client.state_by_block_hash(config.parent_block.hash())?;
     let state = StateProviderDatabase::new(state_provider);
@@ -351,7 +366,13 @@ where
             tx_env_with_recovered(&sequencer_tx),
         );
 
-        let mut evm = evm_config.evm_with_env(&mut db, env);
+        let mut evm = evm_config
+            .evm_with_env(&mut db, env)
+            .modify()
+            .append_handler_register_box(cached_precompiles_handle_register(
+                precompile_cache.clone(),
+            ))
+            .build();
 
         let ResultAndState { result, state } = match evm.transact() {
             Ok(res) => res,
@@ -431,7 +452,13 @@ where
             );
 
             // Configure the environment for the block.
-            let mut evm = evm_config.evm_with_env(&mut db, env);
+            let mut evm = evm_config
+                .evm_with_env(&mut db, env)
+                .modify()
+                .append_handler_register_box(cached_precompiles_handle_register(
+                    precompile_cache.clone(),
+                ))
+                .build();
 
             let ResultAndState { result, state } = match evm.transact() {
                 Ok(res) => res,
@@ -536,14 +563,19 @@ where
 
     // only determine cancun fields when active
     if chain_spec.is_cancun_active_at_timestamp(attributes.payload_attributes.timestamp) {
+        let blob_params =
+            chain_spec.blob_params_at_timestamp(attributes.payload_attributes.timestamp);
         excess_blob_gas = if chain_spec.is_cancun_active_at_timestamp(parent_block.timestamp) {
             let parent_excess_blob_gas = parent_block.excess_blob_gas.unwrap_or_default();
             let parent_blob_gas_used = parent_block.blob_gas_used.unwrap_or_default();
-            Some(calculate_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used))
+            Some(
+                blob_params
+                    .next_block_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used),
+            )
         } else {
             // for the first post-fork block, both parent.blob_gas_used and
             // parent.excess_blob_gas are evaluated as 0
-            Some(calculate_excess_blob_gas(0, 0))
+            Some(blob_params.next_block_excess_blob_gas(0, 0))
         };
 
         blob_gas_used = Some(0);
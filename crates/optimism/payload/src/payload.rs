@@ -7,7 +7,7 @@ use reth_payload_builder::EthPayloadBuilderAttributes;
 use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
 use reth_primitives::{
     revm::config::revm_spec_by_timestamp_after_merge,
-    revm_primitives::{BlobExcessGasAndPrice, BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, SpecId},
+    revm_primitives::{BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, SpecId},
     Address, BlobTransactionSidecar, ChainSpec, Header, SealedBlock, TransactionSigned,
     Withdrawals, B256, U256,
 };
@@ -65,6 +65,7 @@ impl PayloadBuilderAttributes for OptimismPayloadBuilderAttributes {
             prev_randao: attributes.payload_attributes.prev_randao,
             withdrawals: attributes.payload_attributes.withdrawals.unwrap_or_default().into(),
             parent_beacon_block_root: attributes.payload_attributes.parent_beacon_block_root,
+            gas_limit: None,
         };
 
         Ok(Self {
@@ -103,6 +104,10 @@ impl PayloadBuilderAttributes for OptimismPayloadBuilderAttributes {
         &self.payload_attributes.withdrawals
     }
 
+    fn gas_limit(&self) -> Option<u64> {
+        self.gas_limit
+    }
+
     fn cfg_and_block_env(
         &self,
         chain_spec: &ChainSpec,
@@ -116,8 +121,9 @@ impl PayloadBuilderAttributes for OptimismPayloadBuilderAttributes {
 
         // if the parent block did not have excess blob gas (i.e. it was pre-cancun), but it is
         // cancun now, we need to set the excess blob gas to the default value
+        let blob_params = chain_spec.blob_params_at_timestamp(self.timestamp());
         let blob_excess_gas_and_price = parent
-            .next_block_excess_blob_gas()
+            .next_block_excess_blob_gas(blob_params)
             .or_else(|| {
                 if spec_id.is_enabled_in(SpecId::CANCUN) {
                     // default excess blob gas is zero
@@ -126,7 +132,7 @@ impl PayloadBuilderAttributes for OptimismPayloadBuilderAttributes {
                     None
                 }
             })
-            .map(BlobExcessGasAndPrice::new);
+            .map(|excess_blob_gas| blob_params.blob_excess_gas_and_price(excess_blob_gas));
 
         let block_env = BlockEnv {
             number: U256::from(parent.number + 1),
@@ -134,7 +140,8 @@ impl PayloadBuilderAttributes for OptimismPayloadBuilderAttributes {
             timestamp: U256::from(self.timestamp()),
             difficulty: U256::ZERO,
             prevrandao: Some(self.prev_randao()),
-            gas_limit: U256::from(parent.gas_limit),
+            // the gas limit of the attributes applies instantly, there's no bound on its change
+            gas_limit: U256::from(self.gas_limit.unwrap_or(parent.gas_limit)),
             // calculate basefee based on parent block's gas usage
             basefee: U256::from(
                 parent
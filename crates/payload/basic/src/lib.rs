@@ -10,7 +10,7 @@
 
 use crate::metrics::PayloadBuilderMetrics;
 use futures_core::ready;
-use futures_util::FutureExt;
+use futures_util::{FutureExt, StreamExt};
 use reth_payload_builder::{
     database::CachedReads, error::PayloadBuilderError, KeepPayloadJobAlive, PayloadId, PayloadJob,
     PayloadJobGenerator,
@@ -23,12 +23,16 @@ use reth_primitives::{
 use reth_provider::{
     BlockReaderIdExt, BlockSource, CanonStateNotification, ProviderError, StateProviderFactory,
 };
-use reth_revm::state_change::{
-    apply_beacon_root_contract_call, apply_withdrawal_requests_contract_call,
-    post_block_withdrawals_balance_increments,
+use reth_revm::{
+    cached::PayloadReadsCache,
+    precompile_cache::PrecompileCache,
+    state_change::{
+        apply_beacon_root_contract_call, apply_withdrawal_requests_contract_call,
+        post_block_withdrawals_balance_increments,
+    },
 };
 use reth_tasks::TaskSpawner;
-use reth_transaction_pool::TransactionPool;
+use reth_transaction_pool::{NewSubpoolTransactionStream, PoolTransaction, TransactionPool};
 use revm::{
     primitives::{BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg},
     Database, DatabaseCommit, Evm, State,
@@ -49,6 +53,8 @@ use tokio::{
 use tracing::{debug, trace, warn};
 
 mod metrics;
+mod state_root;
+pub use state_root::{StateRootTask, StateRootTaskError};
 
 /// The [`PayloadJobGenerator`] that creates [`BasicPayloadJob`]s.
 #[derive(Debug)]
@@ -71,6 +77,9 @@ pub struct BasicPayloadJobGenerator<Client, Pool, Tasks, Builder> {
     builder: Builder,
     /// Stored `cached_reads` for new payload jobs.
     pre_cached: Option<PrecachedState>,
+    /// The cache the reads of the resolved payloads are deposited to, so their validation can be
+    /// warmed up.
+    payload_reads_cache: Option<PayloadReadsCache>,
 }
 
 // === impl BasicPayloadJobGenerator ===
@@ -95,9 +104,16 @@ impl<Client, Pool, Tasks, Builder> BasicPayloadJobGenerator<Client, Pool, Tasks,
             chain_spec,
             builder,
             pre_cached: None,
+            payload_reads_cache: None,
         }
     }
 
+    /// Sets the cache the reads of the resolved payloads are deposited to.
+    pub fn with_payload_reads_cache(mut self, cache: PayloadReadsCache) -> Self {
+        self.payload_reads_cache = Some(cache);
+        self
+    }
+
     /// Returns the maximum duration a job should be allowed to run.
     ///
     /// This adheres to the following specification:
@@ -180,6 +196,14 @@ where
 
         let cached_reads = self.maybe_pre_cached(config.parent_block.hash());
 
+        // only track the new pending transactions if rebuilds are gated on the fee improvement
+        let improvement = self.config.min_fee_improvement.map(|min_fee_improvement| {
+            FeeImprovementTracker::new(
+                self.pool.new_pending_pool_transactions_listener(),
+                min_fee_improvement,
+            )
+        });
+
         let mut job = BasicPayloadJob {
             config,
             client: self.client.clone(),
@@ -190,7 +214,11 @@ where
             best_payload: None,
             pending_block: None,
             cached_reads,
+            precompile_cache: PrecompileCache::default(),
+            payload_reads_cache: self.payload_reads_cache.clone(),
             payload_task_guard: self.payload_task_guard.clone(),
+            improvement,
+            stats: Default::default(),
             metrics: Default::default(),
             builder: self.builder.clone(),
         };
@@ -266,6 +294,11 @@ pub struct BasicPayloadJobGeneratorConfig {
     deadline: Duration,
     /// Maximum number of tasks to spawn for building a payload.
     max_payload_tasks: usize,
+    /// The minimum projected fee gain, in wei, of the transactions that became pending since the
+    /// last build attempt, that is required to re-pack the payload.
+    ///
+    /// If `None`, the payload is re-packed on every interval tick.
+    min_fee_improvement: Option<U256>,
 }
 
 // === impl BasicPayloadJobGeneratorConfig ===
@@ -301,6 +334,19 @@ impl BasicPayloadJobGeneratorConfig {
         self.extradata = extradata;
         self
     }
+
+    /// Sets the minimum projected fee gain, in wei, that is required to re-pack the payload.
+    ///
+    /// If set, the job only re-packs the payload on an interval tick if the transactions that
+    /// became pending since the last build attempt are projected to pay at least this much in
+    /// priority fees. Each re-pack resumes from the cached reads of the previous attempt, so only
+    /// the state the new transactions touch has to be read from disk.
+    ///
+    /// By default the payload is re-packed on every interval tick.
+    pub const fn min_fee_improvement(mut self, min_fee_improvement: Option<U256>) -> Self {
+        self.min_fee_improvement = min_fee_improvement;
+        self
+    }
 }
 
 impl Default for BasicPayloadJobGeneratorConfig {
@@ -311,6 +357,7 @@ impl Default for BasicPayloadJobGeneratorConfig {
             // 12s slot time
             deadline: SLOT_DURATION,
             max_payload_tasks: 3,
+            min_fee_improvement: None,
         }
     }
 }
@@ -319,6 +366,7 @@ impl Default for BasicPayloadJobGeneratorConfig {
 #[derive(Debug)]
 pub struct BasicPayloadJob<Client, Pool, Tasks, Builder>
 where
+    Pool: TransactionPool,
     Builder: PayloadBuilder<Pool, Client>,
 {
     /// The configuration for how the payload will be created.
@@ -344,6 +392,15 @@ where
     /// This is used to avoid reading the same state over and over again when new attempts are
     /// triggered, because during the building process we'll repeatedly execute the transactions.
     cached_reads: Option<CachedReads>,
+    /// Caches the results of the precompiles for the lifetime of the job, because the payloads
+    /// repeatedly execute the same transactions.
+    precompile_cache: PrecompileCache,
+    /// The cache the reads of the resolved payload are deposited to.
+    payload_reads_cache: Option<PayloadReadsCache>,
+    /// Tracks the projected fee gain of new pending transactions, if re-packing is gated on it.
+    improvement: Option<FeeImprovementTracker<Pool::Transaction>>,
+    /// Statistics of this job, recorded once it is resolved or timed out.
+    stats: PayloadJobStats,
     /// metrics for this type
     metrics: PayloadBuilderMetrics,
     /// The type responsible for building payloads.
@@ -373,8 +430,14 @@ where
         let payload_config = self.config.clone();
         let best_payload = self.best_payload.clone();
         self.metrics.inc_initiated_payload_builds();
+        self.stats.iterations += 1;
+        if let Some(improvement) = &mut self.improvement {
+            improvement.reset();
+        }
         let cached_reads = self.cached_reads.take().unwrap_or_default();
+        let precompile_cache = self.precompile_cache.clone();
         let builder = self.builder.clone();
+        let executor = Box::new(self.executor.clone());
         self.executor.spawn_blocking(Box::pin(async move {
             // acquire the permit for executing the task
             let _permit = guard.acquire().await;
@@ -382,9 +445,11 @@ where
                 client,
                 pool,
                 cached_reads,
+                precompile_cache,
                 config: payload_config,
                 cancel,
                 best_payload,
+                executor,
             };
             let result = builder.try_build(args);
             let _ = tx.send(result);
@@ -392,6 +457,34 @@ where
 
         self.pending_block = Some(PendingPayload { _cancel, payload: rx });
     }
+
+    /// Returns true if the payload should be re-packed on the next interval tick.
+    ///
+    /// This is always the case if no payload has been built yet.
+    fn should_rebuild(&self) -> bool {
+        self.best_payload.is_none() ||
+            self.improvement.as_ref().map_or(true, |improvement| improvement.is_sufficient())
+    }
+
+    /// Replaces the best payload if the given payload pays higher fees.
+    fn on_better_payload(&mut self, payload: Builder::BuiltPayload) {
+        if !is_better_payload(self.best_payload.as_ref(), payload.fees()) {
+            trace!(target: "payload_builder", worse_fees = %payload.fees(), "discarded payload that is not better than the best payload");
+            return
+        }
+        debug!(target: "payload_builder", value = %payload.fees(), "built better payload");
+        self.stats.on_best_fees(payload.fees());
+        self.best_payload = Some(payload);
+    }
+
+    /// Records the statistics of this job, if it attempted to build a payload.
+    fn record_stats(&mut self) {
+        if self.stats.iterations == 0 {
+            return
+        }
+        self.metrics.record_job(self.stats.iterations, self.stats.fee_improvement());
+        self.stats = Default::default();
+    }
 }
 
 impl<Client, Pool, Tasks, Builder> Future for BasicPayloadJob<Client, Pool, Tasks, Builder>
@@ -411,14 +504,29 @@ where
         // check if the deadline is reached
         if this.deadline.as_mut().poll(cx).is_ready() {
             trace!(target: "payload_builder", "payload building deadline reached");
+            this.record_stats();
             return Poll::Ready(Ok(()))
         }
 
+        // account for the transactions that became pending since the last attempt
+        if let Some(improvement) = &mut this.improvement {
+            let base_fee = this.config.initialized_block_env.basefee.saturating_to();
+            if improvement.poll_new_pending(cx, base_fee).is_ready() {
+                // the pool is gone, fall back to re-packing on every interval tick
+                this.improvement = None;
+            }
+        }
+
         // check if the interval is reached
         while this.interval.poll_tick(cx).is_ready() {
             // start a new job if there is no pending block and we haven't reached the deadline
             if this.pending_block.is_none() {
-                this.spawn_build_job();
+                if this.should_rebuild() {
+                    this.spawn_build_job();
+                } else {
+                    trace!(target: "payload_builder", "skipped payload build, projected fee gain below threshold");
+                    this.metrics.inc_skipped_payload_builds();
+                }
             }
         }
 
@@ -430,8 +538,7 @@ where
                     match outcome {
                         BuildOutcome::Better { payload, cached_reads } => {
                             this.cached_reads = Some(cached_reads);
-                            debug!(target: "payload_builder", value = %payload.fees(), "built better payload");
-                            this.best_payload = Some(payload);
+                            this.on_better_payload(payload);
                         }
                         BuildOutcome::Aborted { fees, cached_reads } => {
                             this.cached_reads = Some(cached_reads);
@@ -489,8 +596,19 @@ where
     }
 
     fn resolve(&mut self) -> (Self::ResolvePayloadFuture, KeepPayloadJobAlive) {
+        self.record_stats();
         let best_payload = self.best_payload.take();
 
+        // The resolved payload is likely to come back for validation, which reads the same state
+        if best_payload.is_some() {
+            if let Some((cache, cached_reads)) =
+                self.payload_reads_cache.as_ref().zip(self.cached_reads.take())
+            {
+                let parent = &self.config.parent_block;
+                cache.insert(parent.num_hash(), cached_reads);
+            }
+        }
+
         if best_payload.is_none() && self.pending_block.is_none() {
             // ensure we have a job scheduled if we don't have a best payload yet and none is active
             self.spawn_build_job();
@@ -506,9 +624,11 @@ where
                 client: self.client.clone(),
                 pool: self.pool.clone(),
                 cached_reads: self.cached_reads.take().unwrap_or_default(),
+                precompile_cache: self.precompile_cache.clone(),
                 config: self.config.clone(),
                 cancel: Cancelled::default(),
                 best_payload: None,
+                executor: Box::new(self.executor.clone()),
             };
 
             match self.builder.on_missing_payload(args) {
@@ -577,7 +697,7 @@ impl<Payload> ResolveBestPayload<Payload> {
 
 impl<Payload> Future for ResolveBestPayload<Payload>
 where
-    Payload: Unpin,
+    Payload: BuiltPayload + Unpin,
 {
     type Output = Result<Payload, PayloadBuilderError>;
 
@@ -589,8 +709,11 @@ where
             if let Poll::Ready(res) = fut.poll(cx) {
                 this.maybe_better = None;
                 if let Ok(BuildOutcome::Better { payload, .. }) = res {
-                    debug!(target: "payload_builder", "resolving better payload");
-                    return Poll::Ready(Ok(payload))
+                    // the best payload must always be the one with the highest fees
+                    if is_better_payload(this.best_payload.as_ref(), payload.fees()) {
+                        debug!(target: "payload_builder", "resolving better payload");
+                        return Poll::Ready(Ok(payload))
+                    }
                 }
             }
         }
@@ -625,6 +748,73 @@ where
     }
 }
 
+/// Tracks the projected fee gain of the transactions that became pending since the last build
+/// attempt of a job.
+#[derive(Debug)]
+struct FeeImprovementTracker<T: PoolTransaction> {
+    /// The stream of transactions that became pending.
+    new_pending: NewSubpoolTransactionStream<T>,
+    /// The minimum projected fee gain that is required to re-pack the payload.
+    min_fee_improvement: U256,
+    /// The projected fee gain of the transactions that became pending since the last attempt.
+    projected: U256,
+}
+
+impl<T: PoolTransaction> FeeImprovementTracker<T> {
+    const fn new(new_pending: NewSubpoolTransactionStream<T>, min_fee_improvement: U256) -> Self {
+        Self { new_pending, min_fee_improvement, projected: U256::ZERO }
+    }
+
+    /// Drains the new pending transactions and adds their priority fees to the projected gain.
+    ///
+    /// The priority fee of a transaction is projected from its gas limit, so this overestimates
+    /// the gain of transactions that use less gas than they are allowed to.
+    ///
+    /// Returns `Poll::Ready` if the stream ended.
+    fn poll_new_pending(&mut self, cx: &mut Context<'_>, base_fee: u64) -> Poll<()> {
+        while let Some(event) = ready!(self.new_pending.poll_next_unpin(cx)) {
+            let tip = event.transaction.effective_tip_per_gas(base_fee).unwrap_or_default();
+            let gain = U256::from(tip) * U256::from(event.transaction.gas_limit());
+            self.projected = self.projected.saturating_add(gain);
+        }
+        Poll::Ready(())
+    }
+
+    /// Returns true if the projected fee gain reached the threshold.
+    fn is_sufficient(&self) -> bool {
+        self.projected >= self.min_fee_improvement
+    }
+
+    /// Resets the projected fee gain, because a new attempt considers all transactions so far.
+    fn reset(&mut self) {
+        self.projected = U256::ZERO;
+    }
+}
+
+/// Statistics of a single payload job.
+#[derive(Debug, Default)]
+struct PayloadJobStats {
+    /// The number of build attempts of the job.
+    iterations: u64,
+    /// The fees of the first payload that was built.
+    first_fees: Option<U256>,
+    /// The fees of the best payload that was built.
+    best_fees: U256,
+}
+
+impl PayloadJobStats {
+    /// Records the fees of a new best payload.
+    fn on_best_fees(&mut self, fees: U256) {
+        self.first_fees.get_or_insert(fees);
+        self.best_fees = fees;
+    }
+
+    /// Returns by how much the best payload improved on the first payload.
+    fn fee_improvement(&self) -> U256 {
+        self.first_fees.map(|first| self.best_fees.saturating_sub(first)).unwrap_or_default()
+    }
+}
+
 /// A future that resolves to the result of the block building job.
 #[derive(Debug)]
 pub struct PendingPayload<P> {
@@ -763,25 +953,43 @@ pub struct BuildArguments<Pool, Client, Attributes, Payload> {
     pub pool: Pool,
     /// Previously cached disk reads
     pub cached_reads: CachedReads,
+    /// The results of the precompiles that were called while building the previous payloads of
+    /// the job.
+    pub precompile_cache: PrecompileCache,
     /// How to configure the payload.
     pub config: PayloadConfig<Attributes>,
     /// A marker that can be used to cancel the job.
     pub cancel: Cancelled,
     /// The best payload achieved so far.
     pub best_payload: Option<Payload>,
+    /// The task executor to spawn the background tasks of the build on, e.g. the
+    /// [`StateRootTask`].
+    pub executor: Box<dyn TaskSpawner>,
 }
 
 impl<Pool, Client, Attributes, Payload> BuildArguments<Pool, Client, Attributes, Payload> {
     /// Create new build arguments.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         client: Client,
         pool: Pool,
         cached_reads: CachedReads,
+        precompile_cache: PrecompileCache,
         config: PayloadConfig<Attributes>,
         cancel: Cancelled,
         best_payload: Option<Payload>,
+        executor: Box<dyn TaskSpawner>,
     ) -> Self {
-        Self { client, pool, cached_reads, config, cancel, best_payload }
+        Self {
+            client,
+            pool,
+            cached_reads,
+            precompile_cache,
+            config,
+            cancel,
+            best_payload,
+            executor,
+        }
     }
 }
 
@@ -1,6 +1,10 @@
 //! Metrics for the payload builder impl
 
-use reth_metrics::{metrics::Counter, Metrics};
+use reth_metrics::{
+    metrics::{Counter, Histogram},
+    Metrics,
+};
+use reth_primitives::U256;
 
 /// Transaction pool metrics
 #[derive(Metrics)]
@@ -12,6 +16,13 @@ pub(crate) struct PayloadBuilderMetrics {
     pub(crate) initiated_payload_builds: Counter,
     /// Total number of failed payload build attempts
     pub(crate) failed_payload_builds: Counter,
+    /// Total number of payload build attempts that were skipped, because the projected fee gain
+    /// of the new pending transactions was below the threshold
+    pub(crate) skipped_payload_builds: Counter,
+    /// The number of build attempts per payload job
+    pub(crate) payload_job_iterations: Histogram,
+    /// The fees in wei the best payload of a job gained over the first payload of the job
+    pub(crate) payload_job_fee_improvement: Histogram,
 }
 
 impl PayloadBuilderMetrics {
@@ -26,4 +37,13 @@ impl PayloadBuilderMetrics {
     pub(crate) fn inc_failed_payload_builds(&self) {
         self.failed_payload_builds.increment(1);
     }
+
+    pub(crate) fn inc_skipped_payload_builds(&self) {
+        self.skipped_payload_builds.increment(1);
+    }
+
+    pub(crate) fn record_job(&self, iterations: u64, fee_improvement: U256) {
+        self.payload_job_iterations.record(iterations as f64);
+        self.payload_job_fee_improvement.record(fee_improvement.saturating_to::<u128>() as f64);
+    }
 }
@@ -0,0 +1,417 @@
+//! Background state root computation overlapping payload building.
+
+use reth_primitives::{
+    constants::EMPTY_ROOT_HASH,
+    trie::{Nibbles, TrieAccount},
+    Account, Bytes, B256,
+};
+use reth_provider::{ProviderError, StateProvider};
+use reth_tasks::TaskSpawner;
+use reth_trie::{
+    blinded::{padded_key, BlindedProvider},
+    sparse::{SparseTrie, SparseTrieError},
+    HashedPostState, HashedStorage,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+};
+use tracing::trace;
+
+/// Errors of the [`StateRootTask`].
+#[derive(Debug, thiserror::Error)]
+pub enum StateRootTaskError {
+    /// Failed to retrieve the proofs of the parent state.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// Failed to reveal or update the sparse trie.
+    #[error(transparent)]
+    SparseTrie(#[from] SparseTrieError),
+    /// The storage of the account changed, but the account itself is missing from the state
+    /// changes.
+    #[error("account {0} with storage changes is missing from the state changes")]
+    MissingAccount(B256),
+    /// The task exited before the state root was computed.
+    #[error("state root task exited before the state root was computed")]
+    TaskClosed,
+}
+
+/// The message sent to the state root task.
+#[derive(Debug)]
+enum StateRootMessage {
+    /// The state changes of a transaction included in the payload.
+    Update(HashedPostState),
+    /// The payload is built, the final state changes of the whole payload.
+    Finish(HashedPostState),
+}
+
+/// Computes the state root of a payload on a blocking task while the payload is being built.
+///
+/// The task keeps the sparse account and storage tries of the parent state that are revealed from
+/// the proofs of the changed accounts and slots. The state changes of every transaction included
+/// in the payload are applied to the sparse tries as they arrive, and the storage roots and account
+/// leaves of the changed accounts are updated whenever there are no pending updates. Once the
+/// building is finished, only the changes since then and the paths to the changed leaves are left
+/// to hash.
+///
+/// The final state changes are authoritative: the changes that weren't sent to the task (e.g.
+/// system calls and withdrawals) or differ from the ones that were are applied on top before the
+/// root is calculated.
+#[derive(Debug)]
+pub struct StateRootTask {
+    /// Sender of the messages to the task.
+    tx: Sender<StateRootMessage>,
+    /// Receiver of the state root, which is sent once the final state changes are processed.
+    state_root: Receiver<Result<B256, StateRootTaskError>>,
+}
+
+impl StateRootTask {
+    /// Spawns the task computing the state root on top of the state of the given provider, which
+    /// is expected to have the given state root.
+    pub fn spawn<P>(executor: &dyn TaskSpawner, provider: P, parent_state_root: B256) -> Self
+    where
+        P: StateProvider + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let (state_root_tx, state_root) = mpsc::channel();
+        executor.spawn_blocking(Box::pin(async move {
+            let mut sparse = SparseStateRoot::new(&provider, parent_state_root);
+            // nothing is sent if the payload building was cancelled
+            if let Some(result) = sparse.run(rx).transpose() {
+                let _ = state_root_tx.send(result);
+            }
+        }));
+        Self { tx, state_root }
+    }
+
+    /// Sends the state changes of a transaction included in the payload to the task.
+    ///
+    /// The changes are expected to contain the account of every changed storage, as
+    /// [`HashedPostState::from_evm_state`] does.
+    pub fn update(&self, state: HashedPostState) {
+        // the task only exits early on error, which is returned on finish
+        let _ = self.tx.send(StateRootMessage::Update(state));
+    }
+
+    /// Sends the final state changes of the payload to the task and waits for the state root.
+    pub fn finish(self, state: HashedPostState) -> Result<B256, StateRootTaskError> {
+        let _ = self.tx.send(StateRootMessage::Finish(state));
+        self.state_root.recv().map_err(|_| StateRootTaskError::TaskClosed)?
+    }
+}
+
+/// The sparse tries of the state along with the changes applied to them.
+#[derive(Debug)]
+struct SparseStateRoot<'a, P> {
+    /// The provider of the parent state.
+    provider: &'a P,
+    /// The sparse account trie.
+    account_trie: SparseTrie,
+    /// The sparse storage tries of the changed accounts.
+    storage_tries: HashMap<B256, SparseTrie>,
+    /// The accounts and slots that have their proofs revealed.
+    revealed: HashMap<B256, HashSet<B256>>,
+    /// The changed accounts, `None` if destroyed.
+    accounts: HashMap<B256, Option<Account>>,
+    /// The storage changes applied to the storage tries.
+    storages: HashMap<B256, HashedStorage>,
+    /// The changed accounts that don't have their leaves updated yet.
+    dirty: HashSet<B256>,
+}
+
+impl<'a, P: StateProvider> SparseStateRoot<'a, P> {
+    fn new(provider: &'a P, parent_state_root: B256) -> Self {
+        Self {
+            provider,
+            account_trie: SparseTrie::blind(parent_state_root),
+            storage_tries: HashMap::default(),
+            revealed: HashMap::default(),
+            accounts: HashMap::default(),
+            storages: HashMap::default(),
+            dirty: HashSet::default(),
+        }
+    }
+
+    /// Runs the task until the final state changes are received or the sender is dropped.
+    fn run(&mut self, rx: Receiver<StateRootMessage>) -> Result<Option<B256>, StateRootTaskError> {
+        loop {
+            let message = match rx.try_recv() {
+                Ok(message) => message,
+                Err(TryRecvError::Empty) => {
+                    // no updates, bring one of the changed accounts up to date
+                    if let Some(hashed_address) = self.next_dirty() {
+                        self.update_account_leaf(hashed_address)?;
+                        continue
+                    }
+
+                    match rx.recv() {
+                        Ok(message) => message,
+                        Err(_) => return Ok(None),
+                    }
+                }
+                // payload building was cancelled
+                Err(TryRecvError::Disconnected) => return Ok(None),
+            };
+
+            match message {
+                StateRootMessage::Update(update) => {
+                    self.reveal(&update)?;
+                    for (hashed_address, storage) in update.storages {
+                        self.apply_storage(hashed_address, &storage)?;
+                        self.storages
+                            .entry(hashed_address)
+                            .or_insert_with(|| HashedStorage::new(false))
+                            .extend(storage);
+                    }
+                    for (hashed_address, account) in update.accounts {
+                        self.accounts.insert(hashed_address, account);
+                        self.dirty.insert(hashed_address);
+                    }
+                }
+                StateRootMessage::Finish(final_state) => {
+                    self.reveal(&final_state)?;
+                    for (hashed_address, storage) in final_state.storages {
+                        // the final storage changes include all slots changed in the payload
+                        if self.storages.get(&hashed_address) != Some(&storage) {
+                            self.apply_storage(hashed_address, &storage)?;
+                        }
+                    }
+                    for (hashed_address, account) in final_state.accounts {
+                        if self.accounts.get(&hashed_address) != Some(&account) {
+                            self.accounts.insert(hashed_address, account);
+                            self.dirty.insert(hashed_address);
+                        }
+                    }
+
+                    trace!(
+                        target: "payload_builder",
+                        remaining = self.dirty.len(),
+                        total = self.accounts.len(),
+                        "Computing the final state root"
+                    );
+                    for hashed_address in std::mem::take(&mut self.dirty) {
+                        self.update_account_leaf(hashed_address)?;
+                    }
+                    return Ok(Some(self.account_trie.root()))
+                }
+            }
+        }
+    }
+
+    /// Reveals the proofs of the changed accounts and slots that aren't revealed yet.
+    fn reveal(&mut self, state: &HashedPostState) -> Result<(), StateRootTaskError> {
+        let mut targets = HashMap::<B256, HashSet<B256>>::default();
+        for hashed_address in state.accounts.keys().chain(state.storages.keys()) {
+            let revealed = self.revealed.get(hashed_address);
+            let slots = state
+                .storages
+                .get(hashed_address)
+                .into_iter()
+                .flat_map(|storage| storage.storage.keys())
+                .filter(|slot| revealed.map_or(true, |revealed| !revealed.contains(*slot)))
+                .copied()
+                .collect::<HashSet<_>>();
+            if revealed.is_none() || !slots.is_empty() {
+                targets.insert(*hashed_address, slots);
+            }
+        }
+        if targets.is_empty() {
+            return Ok(())
+        }
+
+        let multiproof = self.provider.multiproof(HashedPostState::default(), targets.clone())?;
+        self.account_trie.reveal_nodes(&multiproof.account_subtree)?;
+        for (hashed_address, slots) in targets {
+            let storage_proof = multiproof.storages.get(&hashed_address);
+            let storage_trie = self.storage_tries.entry(hashed_address).or_insert_with(|| {
+                SparseTrie::blind(storage_proof.map_or(EMPTY_ROOT_HASH, |proof| proof.root))
+            });
+            if let Some(storage_proof) = storage_proof {
+                storage_trie.reveal_nodes(&storage_proof.subtree)?;
+            }
+            self.revealed.entry(hashed_address).or_default().extend(slots);
+        }
+        Ok(())
+    }
+
+    /// Applies the storage changes of the account to its storage trie.
+    fn apply_storage(
+        &mut self,
+        hashed_address: B256,
+        storage: &HashedStorage,
+    ) -> Result<(), StateRootTaskError> {
+        let storage_trie = self.storage_tries.entry(hashed_address).or_default();
+        if storage.wiped {
+            *storage_trie = SparseTrie::default();
+        }
+
+        let mut blinded_provider = ParentStateBlindedProvider {
+            provider: self.provider,
+            hashed_address: Some(hashed_address),
+        };
+        for (hashed_slot, value) in &storage.storage {
+            let key = Nibbles::unpack(hashed_slot);
+            if value.is_zero() {
+                storage_trie.remove_leaf(&key, &mut blinded_provider)?;
+            } else {
+                let value = alloy_rlp::encode_fixed_size(value).to_vec();
+                storage_trie.update_leaf(&key, value, &mut blinded_provider)?;
+            }
+        }
+        self.dirty.insert(hashed_address);
+        Ok(())
+    }
+
+    /// Returns a changed account that has its leaf outdated and is known.
+    fn next_dirty(&self) -> Option<B256> {
+        self.dirty
+            .iter()
+            .find(|hashed_address| self.accounts.contains_key(*hashed_address))
+            .copied()
+    }
+
+    /// Computes the storage root of the changed account and updates its leaf in the account trie.
+    fn update_account_leaf(&mut self, hashed_address: B256) -> Result<(), StateRootTaskError> {
+        self.dirty.remove(&hashed_address);
+        let account = *self
+            .accounts
+            .get(&hashed_address)
+            .ok_or(StateRootTaskError::MissingAccount(hashed_address))?;
+
+        let key = Nibbles::unpack(hashed_address);
+        let mut blinded_provider =
+            ParentStateBlindedProvider { provider: self.provider, hashed_address: None };
+        if let Some(account) = account {
+            let storage_root = self
+                .storage_tries
+                .get_mut(&hashed_address)
+                .map_or(EMPTY_ROOT_HASH, SparseTrie::root);
+            let value = alloy_rlp::encode(TrieAccount::from((account, storage_root)));
+            self.account_trie.update_leaf(&key, value, &mut blinded_provider)?;
+        } else {
+            self.account_trie.remove_leaf(&key, &mut blinded_provider)?;
+        }
+        Ok(())
+    }
+}
+
+/// Retrieves the blinded nodes of the parent state tries from the proofs of the state provider.
+#[derive(Debug)]
+struct ParentStateBlindedProvider<'a, P> {
+    /// The provider of the parent state.
+    provider: &'a P,
+    /// The hashed address of the account for the storage trie nodes, `None` for the account trie.
+    hashed_address: Option<B256>,
+}
+
+impl<'a, P: StateProvider> BlindedProvider for ParentStateBlindedProvider<'a, P> {
+    type Error = ProviderError;
+
+    fn blinded_node(&mut self, path: &Nibbles) -> Result<Option<Bytes>, Self::Error> {
+        let key = padded_key(path);
+        let Some(hashed_address) = self.hashed_address else {
+            let targets = HashMap::from([(key, HashSet::default())]);
+            let proof = self.provider.multiproof(HashedPostState::default(), targets)?;
+            return Ok(proof.account_subtree.get(path).cloned())
+        };
+
+        let targets = HashMap::from([(hashed_address, HashSet::from([key]))]);
+        let mut proof = self.provider.multiproof(HashedPostState::default(), targets)?;
+        Ok(proof
+            .storages
+            .remove(&hashed_address)
+            .and_then(|storage_proof| storage_proof.subtree.get(path).cloned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{keccak256, Address, StorageEntry, U256};
+    use reth_provider::{
+        test_utils::create_test_provider_factory, HashingWriter, StateProviderFactory,
+        StateRootProvider,
+    };
+    use reth_tasks::TokioTaskExecutor;
+    use reth_trie::StateRoot;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn running_root_matches_full_computation() {
+        let factory = create_test_provider_factory();
+        let addresses = (0..64u64).map(|i| Address::from_word(B256::from(U256::from(i + 1))));
+        let accounts = addresses
+            .clone()
+            .map(|address| (address, Some(Account { nonce: 1, ..Default::default() })))
+            .collect::<Vec<_>>();
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_account_for_hashing(accounts.clone()).unwrap();
+        provider
+            .insert_storage_for_hashing(addresses.clone().map(|address| {
+                let slots = (1..=16u64).map(|slot| StorageEntry {
+                    key: B256::from(U256::from(slot)),
+                    value: U256::from(slot),
+                });
+                (address, slots)
+            }))
+            .unwrap();
+        let (parent_state_root, updates) =
+            StateRoot::from_tx(provider.tx_ref()).root_with_updates().unwrap();
+        updates.flush(provider.tx_ref()).unwrap();
+        provider.commit().unwrap();
+
+        let task = StateRootTask::spawn(
+            &TokioTaskExecutor::default(),
+            factory.latest().unwrap(),
+            parent_state_root,
+        );
+
+        // Every transaction changes a slot, clears a slot and bumps the nonce of an account, the
+        // last one destroys an account.
+        let mut final_state = HashedPostState::default();
+        for (i, address) in addresses.clone().enumerate().step_by(4) {
+            let hashed_address = keccak256(address);
+            let mut storage = HashedStorage::new(false);
+            storage.storage.insert(keccak256(B256::from(U256::from(1))), U256::from(100 + i));
+            storage.storage.insert(keccak256(B256::from(U256::from(2))), U256::ZERO);
+            let update = HashedPostState::default()
+                .with_accounts([(hashed_address, Some(Account { nonce: 2, ..Default::default() }))])
+                .with_storages([(hashed_address, storage)]);
+            task.update(update.clone());
+            final_state.extend(update);
+        }
+        let destroyed = keccak256(addresses.clone().last().unwrap());
+        final_state.extend(
+            HashedPostState::default()
+                .with_accounts([(destroyed, None)])
+                .with_storages([(destroyed, HashedStorage::new(true))]),
+        );
+        // A change that wasn't sent as an update, e.g. a withdrawal.
+        let withdrawal = keccak256(Address::repeat_byte(0xaa));
+        final_state.extend(HashedPostState::default().with_accounts([(
+            withdrawal,
+            Some(Account { balance: U256::from(1), ..Default::default() }),
+        )]));
+
+        let state_root = task.finish(final_state.clone()).unwrap();
+        let expected = factory
+            .latest()
+            .unwrap()
+            .hashed_state_root_with_storage_roots(final_state, HashMap::default())
+            .unwrap();
+        assert_eq!(state_root, expected);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cancelled_task_exits() {
+        let factory = create_test_provider_factory();
+        let task = StateRootTask::spawn(
+            &TokioTaskExecutor::default(),
+            factory.latest().unwrap(),
+            EMPTY_ROOT_HASH,
+        );
+        task.update(HashedPostState::default());
+        let StateRootTask { tx, state_root } = task;
+        drop(tx);
+        assert!(state_root.recv().is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! Error types emitted by types or implementations of this crate.
 
 use reth_errors::{ProviderError, RethError};
+use reth_fs_util::FsPathError;
 use reth_primitives::{revm_primitives::EVMError, B256};
 use reth_transaction_pool::BlobStoreError;
 use tokio::sync::oneshot;
@@ -50,6 +51,20 @@ impl From<ProviderError> for PayloadBuilderError {
     }
 }
 
+/// Possible error variants when persisting payloads.
+#[derive(Debug, thiserror::Error)]
+pub enum PayloadPersistenceError {
+    /// Error accessing the persisted files.
+    #[error(transparent)]
+    Fs(#[from] FsPathError),
+    /// Error (de)compressing a persisted payload.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Error (de)serializing a persisted entry.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
 impl From<oneshot::error::RecvError> for PayloadBuilderError {
     fn from(_: oneshot::error::RecvError) -> Self {
         Self::ChannelClosed
@@ -1,8 +1,9 @@
+use crate::{metrics::PayloadEventsMetrics, PayloadId};
 use reth_engine_primitives::EngineTypes;
 use tokio::sync::broadcast;
 use tokio_stream::{
     wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
-    StreamExt,
+    Stream, StreamExt,
 };
 
 /// Payload builder events.
@@ -11,13 +12,22 @@ pub enum Events<Engine: EngineTypes> {
     /// The payload attributes as
     /// they are received from the CL through the engine api.
     Attributes(Engine::PayloadBuilderAttributes),
-    /// The built payload that has been just built.
-    /// Triggered by the CL whenever it asks for an execution payload.
-    /// This event is only thrown if the CL is a validator.
-    BuiltPayload(Engine::BuiltPayload),
+    /// The best payload of a job.
+    ///
+    /// Triggered by the CL whenever it asks for an execution payload, which is only the case if
+    /// the CL is a validator, and when a job that was not resolved ends.
+    BuiltPayload {
+        /// The identifier of the payload job.
+        id: PayloadId,
+        /// The built payload.
+        payload: Engine::BuiltPayload,
+    },
 }
 
 /// Represents a receiver for various payload events.
+///
+/// The events are broadcast through a bounded channel, so a receiver that falls behind never
+/// blocks the payload builder service, but misses the oldest events instead.
 #[derive(Debug)]
 pub struct PayloadEvents<Engine: EngineTypes> {
     pub receiver: broadcast::Receiver<Events<Engine>>,
@@ -28,6 +38,22 @@ impl<Engine: EngineTypes + 'static> PayloadEvents<Engine> {
     pub fn into_stream(self) -> BroadcastStream<Events<Engine>> {
         BroadcastStream::new(self.receiver)
     }
+
+    /// Converts this receiver into a stream of payload events that skips the events the receiver
+    /// fell behind on.
+    ///
+    /// The number of skipped events is recorded in the `payloads.dropped_events` metric.
+    pub fn into_lossy_stream(self) -> impl Stream<Item = Events<Engine>> {
+        let metrics = PayloadEventsMetrics::default();
+        self.into_stream().filter_map(move |event| match event {
+            Ok(event) => Some(event),
+            Err(BroadcastStreamRecvError::Lagged(dropped)) => {
+                metrics.dropped_events.increment(dropped);
+                None
+            }
+        })
+    }
+
     /// Asynchronously receives the next payload event.
     pub async fn recv(self) -> Option<Result<Events<Engine>, BroadcastStreamRecvError>> {
         let mut event_stream = self.into_stream();
@@ -109,13 +109,17 @@ mod service;
 mod traits;
 
 pub mod noop;
+pub mod persistence;
 
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
-pub use events::Events;
+pub use events::{Events, PayloadEvents};
+pub use persistence::{DiskPayloadPersistence, PayloadPersistence};
 pub use reth_rpc_types::engine::PayloadId;
-pub use service::{PayloadBuilderHandle, PayloadBuilderService, PayloadStore};
+pub use service::{
+    PayloadBuilderHandle, PayloadBuilderService, PayloadStore, DEFAULT_RESOLVE_DEADLINE,
+};
 pub use traits::{KeepPayloadJobAlive, PayloadJob, PayloadJobGenerator};
 
 // re-export the Ethereum engine primitives for convenience
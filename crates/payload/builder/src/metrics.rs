@@ -25,6 +25,14 @@ pub(crate) struct PayloadBuilderServiceMetrics {
     pub(crate) resolved_block: Gauge,
 }
 
+/// Metrics of the subscribers of the payload builder events
+#[derive(Metrics, Clone)]
+#[metrics(scope = "payloads")]
+pub(crate) struct PayloadEventsMetrics {
+    /// Total number of events that were dropped because a subscriber fell behind
+    pub(crate) dropped_events: Counter,
+}
+
 impl PayloadBuilderServiceMetrics {
     pub(crate) fn inc_initiated_jobs(&self) {
         self.initiated_jobs.increment(1);
@@ -0,0 +1,335 @@
+//! Persistence of payload jobs and built payloads across restarts.
+//!
+//! If the node restarts between the `engine_forkchoiceUpdated` call that started a payload job and
+//! the `engine_getPayload` call that resolves it, the consensus client would miss its slot. The
+//! [`PayloadPersistence`] allows the [`PayloadBuilderService`](crate::PayloadBuilderService) to
+//! restart the jobs whose payloads are still due and to answer requests for payloads that were
+//! built before the restart.
+
+use crate::error::PayloadPersistenceError;
+use reth_engine_primitives::EngineTypes;
+use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
+use reth_rpc_types::engine::PayloadId;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tracing::{debug, trace};
+
+/// The default number of built payloads that are kept on disk.
+pub const DEFAULT_MAX_PERSISTED_PAYLOADS: usize = 16;
+
+/// The default time after their timestamp for which persisted entries are kept.
+pub const DEFAULT_PERSISTED_PAYLOADS_MAX_AGE: Duration = Duration::from_secs(2 * 12);
+
+/// The file extension of persisted payload attributes.
+const ATTRIBUTES_EXTENSION: &str = "attributes.json";
+
+/// The file extension of persisted, zstd compressed, built payloads.
+const PAYLOAD_EXTENSION: &str = "payload.json.zst";
+
+/// Stores the attributes of payload jobs and recently built payloads.
+pub trait PayloadPersistence<Engine: EngineTypes>: Send + Sync + fmt::Debug {
+    /// Stores the attributes of a new payload job.
+    fn save_attributes(
+        &self,
+        attributes: &Engine::PayloadBuilderAttributes,
+    ) -> Result<(), PayloadPersistenceError>;
+
+    /// Returns the attributes of all stored payload jobs.
+    fn load_attributes(
+        &self,
+    ) -> Result<Vec<Engine::PayloadBuilderAttributes>, PayloadPersistenceError>;
+
+    /// Stores a payload that was built for the payload job with the given id, replacing any
+    /// payload that was stored for it before.
+    fn save_payload(
+        &self,
+        id: PayloadId,
+        payload: &Engine::BuiltPayload,
+    ) -> Result<(), PayloadPersistenceError>;
+
+    /// Returns the stored payload for the payload job with the given id, if any.
+    fn load_payload(
+        &self,
+        id: PayloadId,
+    ) -> Result<Option<Engine::BuiltPayload>, PayloadPersistenceError>;
+
+    /// Removes all entries that are stale at the given unix timestamp (in seconds).
+    fn remove_stale(&self, now: u64) -> Result<(), PayloadPersistenceError>;
+}
+
+/// A [`PayloadPersistence`] that stores every entry in its own file of a directory.
+///
+/// Files are named `<timestamp>-<payload id>.<extension>`, so stale entries can be identified by
+/// their name alone. Entries are stale once their payload timestamp is older than the configured
+/// max age. Additionally, only the most recent built payloads are kept.
+#[derive(Debug, Clone)]
+pub struct DiskPayloadPersistence {
+    /// The directory the entries are stored in.
+    dir: PathBuf,
+    /// The maximum number of built payloads that are kept.
+    max_payloads: usize,
+    /// The time after their timestamp for which entries are kept.
+    max_age: Duration,
+}
+
+impl DiskPayloadPersistence {
+    /// Creates a new instance that stores the entries in the given directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_payloads: DEFAULT_MAX_PERSISTED_PAYLOADS,
+            max_age: DEFAULT_PERSISTED_PAYLOADS_MAX_AGE,
+        }
+    }
+
+    /// Sets the maximum number of built payloads that are kept.
+    pub const fn with_max_payloads(mut self, max_payloads: usize) -> Self {
+        self.max_payloads = max_payloads;
+        self
+    }
+
+    /// Sets the time after their timestamp for which entries are kept.
+    pub const fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Returns the directory the entries are stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path of the entry for the payload with the given timestamp and id.
+    fn path(&self, timestamp: u64, id: PayloadId, extension: &str) -> PathBuf {
+        self.dir.join(format!("{timestamp}-{id}.{extension}"))
+    }
+
+    /// Writes the file atomically, so a crash never leaves a partially written entry behind.
+    fn write(&self, path: &Path, contents: &[u8]) -> Result<(), PayloadPersistenceError> {
+        reth_fs_util::create_dir_all(&self.dir)?;
+        let tmp_path = path.with_extension("tmp");
+        reth_fs_util::write(&tmp_path, contents)?;
+        reth_fs_util::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Returns all entries with the given extension as `(timestamp, id, path)`.
+    fn entries(
+        &self,
+        extension: &str,
+    ) -> Result<Vec<(u64, String, PathBuf)>, PayloadPersistenceError> {
+        if !self.dir.exists() {
+            return Ok(Vec::new())
+        }
+
+        let mut entries = Vec::new();
+        for entry in reth_fs_util::read_dir(&self.dir)? {
+            let path =
+                entry.map_err(|err| reth_fs_util::FsPathError::read_dir(err, &self.dir))?.path();
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else { continue };
+            let Some((timestamp, id)) = name
+                .strip_suffix(extension)
+                .and_then(|name| name.strip_suffix('.'))
+                .and_then(|name| name.split_once('-'))
+            else {
+                continue
+            };
+            let Ok(timestamp) = timestamp.parse() else { continue };
+            entries.push((timestamp, id.to_string(), path));
+        }
+        Ok(entries)
+    }
+}
+
+impl<Engine> PayloadPersistence<Engine> for DiskPayloadPersistence
+where
+    Engine: EngineTypes,
+    Engine::PayloadBuilderAttributes: Serialize + DeserializeOwned,
+    Engine::BuiltPayload: Serialize + DeserializeOwned,
+{
+    fn save_attributes(
+        &self,
+        attributes: &Engine::PayloadBuilderAttributes,
+    ) -> Result<(), PayloadPersistenceError> {
+        let path = self.path(attributes.timestamp(), attributes.payload_id(), ATTRIBUTES_EXTENSION);
+        trace!(target: "payload_builder", ?path, "persisting payload attributes");
+        self.write(&path, &serde_json::to_vec(attributes)?)
+    }
+
+    fn load_attributes(
+        &self,
+    ) -> Result<Vec<Engine::PayloadBuilderAttributes>, PayloadPersistenceError> {
+        self.entries(ATTRIBUTES_EXTENSION)?
+            .into_iter()
+            .map(|(_, _, path)| Ok(serde_json::from_slice(&reth_fs_util::read(path)?)?))
+            .collect()
+    }
+
+    fn save_payload(
+        &self,
+        id: PayloadId,
+        payload: &Engine::BuiltPayload,
+    ) -> Result<(), PayloadPersistenceError> {
+        let path = self.path(payload.block().timestamp, id, PAYLOAD_EXTENSION);
+        trace!(target: "payload_builder", ?path, "persisting built payload");
+        let compressed = zstd::encode_all(serde_json::to_vec(payload)?.as_slice(), 0)?;
+        self.write(&path, &compressed)
+    }
+
+    fn load_payload(
+        &self,
+        id: PayloadId,
+    ) -> Result<Option<Engine::BuiltPayload>, PayloadPersistenceError> {
+        let id = id.to_string();
+        let Some((_, _, path)) =
+            self.entries(PAYLOAD_EXTENSION)?.into_iter().find(|(_, entry_id, _)| *entry_id == id)
+        else {
+            return Ok(None)
+        };
+        let decompressed = zstd::decode_all(reth_fs_util::read(path)?.as_slice())?;
+        Ok(Some(serde_json::from_slice(&decompressed)?))
+    }
+
+    fn remove_stale(&self, now: u64) -> Result<(), PayloadPersistenceError> {
+        let is_stale = |timestamp: u64| timestamp.saturating_add(self.max_age.as_secs()) < now;
+
+        for (timestamp, _, path) in self.entries(ATTRIBUTES_EXTENSION)? {
+            if is_stale(timestamp) {
+                debug!(target: "payload_builder", ?path, "removing stale payload attributes");
+                reth_fs_util::remove_file(path)?;
+            }
+        }
+
+        // newest first, so everything after the max number of payloads is removed
+        let mut payloads = self.entries(PAYLOAD_EXTENSION)?;
+        payloads.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+        for (idx, (timestamp, _, path)) in payloads.into_iter().enumerate() {
+            if idx >= self.max_payloads || is_stale(timestamp) {
+                debug!(target: "payload_builder", ?path, "removing stale built payload");
+                reth_fs_util::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::test_payload_service, PayloadStore};
+    use reth_ethereum_engine_primitives::{
+        EthBuiltPayload, EthEngineTypes, EthPayloadBuilderAttributes,
+    };
+    use reth_primitives::{Block, Header, B256, U256};
+    use reth_rpc_types::engine::PayloadAttributes;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    type Persistence = dyn PayloadPersistence<EthEngineTypes>;
+
+    fn attributes(timestamp: u64) -> EthPayloadBuilderAttributes {
+        EthPayloadBuilderAttributes::new(
+            B256::random(),
+            PayloadAttributes {
+                timestamp,
+                prev_randao: B256::random(),
+                suggested_fee_recipient: Default::default(),
+                withdrawals: Some(vec![]),
+                parent_beacon_block_root: Some(B256::random()),
+            },
+        )
+    }
+
+    fn payload(attributes: &EthPayloadBuilderAttributes) -> EthBuiltPayload {
+        let block = Block {
+            header: Header { timestamp: attributes.timestamp, ..Default::default() },
+            ..Default::default()
+        };
+        EthBuiltPayload::new(attributes.id, block.seal_slow(), U256::from(1))
+    }
+
+    #[test]
+    fn roundtrip_and_remove_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let disk = DiskPayloadPersistence::new(dir.path()).with_max_age(Duration::from_secs(12));
+        let persistence: &Persistence = &disk;
+
+        let stale = attributes(100);
+        let due = attributes(200);
+        for attributes in [&stale, &due] {
+            persistence.save_attributes(attributes).unwrap();
+            persistence.save_payload(attributes.id, &payload(attributes)).unwrap();
+        }
+
+        let mut loaded = persistence.load_attributes().unwrap();
+        loaded.sort_by_key(|attributes| attributes.timestamp);
+        assert_eq!(loaded, vec![stale.clone(), due.clone()]);
+        let loaded = persistence.load_payload(due.id).unwrap().unwrap();
+        assert_eq!(loaded.block(), payload(&due).block());
+        assert_eq!(loaded.fees(), U256::from(1));
+
+        persistence.remove_stale(150).unwrap();
+        assert_eq!(persistence.load_attributes().unwrap(), vec![due.clone()]);
+        assert!(persistence.load_payload(stale.id).unwrap().is_none());
+        assert!(persistence.load_payload(due.id).unwrap().is_some());
+    }
+
+    #[test]
+    fn keeps_most_recent_payloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let disk = DiskPayloadPersistence::new(dir.path()).with_max_payloads(2);
+        let persistence: &Persistence = &disk;
+
+        let all = [attributes(1), attributes(2), attributes(3)];
+        for attributes in &all {
+            persistence.save_payload(attributes.id, &payload(attributes)).unwrap();
+        }
+        persistence.remove_stale(0).unwrap();
+
+        assert!(persistence.load_payload(all[0].id).unwrap().is_none());
+        assert!(persistence.load_payload(all[1].id).unwrap().is_some());
+        assert!(persistence.load_payload(all[2].id).unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn service_serves_payloads_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        // the payloads of the test jobs have a zero timestamp
+        let persistence = DiskPayloadPersistence::new(dir.path()).with_max_age(Duration::MAX);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let due = attributes(now + 60);
+        let resolved = attributes(now);
+
+        let (service, handle) = test_payload_service::<EthEngineTypes>();
+        tokio::spawn(service.with_persistence(persistence.clone(), Duration::from_secs(1)));
+        let store = PayloadStore::from(handle.clone());
+        for attributes in [&due, &resolved] {
+            handle.new_payload(attributes.clone()).await.unwrap();
+        }
+        let payload = store.resolve(resolved.id).await.unwrap().unwrap();
+
+        // the entries are written on blocking tasks
+        let persisted: &Persistence = &persistence;
+        while persisted.load_payload(resolved.id).unwrap().is_none() ||
+            persisted.load_attributes().unwrap().len() < 2
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        // restart the service
+        let (service, handle) = test_payload_service::<EthEngineTypes>();
+        tokio::spawn(service.with_persistence(persistence, Duration::from_secs(1)));
+        let store = PayloadStore::from(handle);
+
+        // the job whose payload is still due is restarted
+        assert_eq!(store.payload_attributes(due.id).await.unwrap().unwrap(), due);
+        assert!(store.payload_attributes(resolved.id).await.is_none());
+        // the resolved payload is served from disk
+        let persisted = store.resolve(resolved.id).await.unwrap().unwrap();
+        assert_eq!(persisted.block(), payload.block());
+    }
+}
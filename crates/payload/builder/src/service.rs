@@ -7,29 +7,53 @@ use crate::{
     error::PayloadBuilderError,
     events::{Events, PayloadEvents},
     metrics::PayloadBuilderServiceMetrics,
+    persistence::PayloadPersistence,
     traits::PayloadJobGenerator,
     KeepPayloadJobAlive, PayloadJob,
 };
-use futures_util::{future::FutureExt, Stream, StreamExt};
+use futures_util::{
+    future::{self, FutureExt},
+    Stream, StreamExt,
+};
 use reth_engine_primitives::EngineTypes;
 use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
+use reth_primitives::B256;
 use reth_provider::CanonStateNotification;
 use reth_rpc_types::engine::PayloadId;
 use std::{
+    collections::HashMap,
     fmt,
     future::Future,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use tokio::sync::{
-    broadcast, mpsc,
-    oneshot::{self, error::RecvError},
+use tokio::{
+    sync::{
+        broadcast, mpsc,
+        oneshot::{self, error::RecvError},
+    },
+    task::JoinHandle,
+    time::{Interval, MissedTickBehavior},
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{debug, info, trace, warn};
 
 type PayloadFuture<P> = Pin<Box<dyn Future<Output = Result<P, PayloadBuilderError>> + Send + Sync>>;
 
+/// A payload job of any of the generators of the [`PayloadBuilderService`].
+type BoxedPayloadJob<Engine> = Box<dyn ServicePayloadJob<Engine>>;
+
+/// Index of the main generator of the [`PayloadBuilderService`], the additional generators follow
+/// in the order they were registered.
+const MAIN_GENERATOR: usize = 0;
+
+/// The default time the jobs of the additional generators of a payload are given to resolve.
+///
+/// The CL expects the payload within 1s, see [`PayloadJob::resolve`].
+pub const DEFAULT_RESOLVE_DEADLINE: Duration = Duration::from_millis(900);
+
 /// A communication channel to the [`PayloadBuilderService`] that can retrieve payloads.
 #[derive(Debug)]
 pub struct PayloadStore<Engine: EngineTypes> {
@@ -206,7 +230,11 @@ where
 ///
 /// By design, this type relies entirely on the [`PayloadJobGenerator`] to create new payloads and
 /// does know nothing about how to build them, it just drives their jobs to completion.
-#[derive(Debug)]
+///
+/// Additional generators can be registered with
+/// [`PayloadBuilderService::with_additional_generator`], in which case every generator starts a
+/// job for the same payload and the best payload of all of them is returned when the payload is
+/// resolved.
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 pub struct PayloadBuilderService<Gen, St, Engine>
 where
@@ -216,8 +244,13 @@ where
 {
     /// The type that knows how to create new payloads.
     generator: Gen,
-    /// All active payload jobs.
-    payload_jobs: Vec<(Gen::Job, PayloadId)>,
+    /// Additional generators whose jobs race the jobs of the `generator`.
+    additional_generators: Vec<Box<dyn ServicePayloadJobGenerator<Engine>>>,
+    /// All active payload jobs and the index of the generator that created them, there can be one
+    /// job per generator for the same payload.
+    payload_jobs: Vec<(BoxedPayloadJob<Engine>, PayloadId, usize)>,
+    /// The time the jobs of the additional generators of a payload are given to resolve.
+    resolve_deadline: Duration,
     /// Copy of the sender half, so new [`PayloadBuilderHandle`] can be created on demand.
     service_tx: mpsc::UnboundedSender<PayloadServiceCommand<Engine>>,
     /// Receiver half of the command channel.
@@ -228,17 +261,41 @@ where
     chain_events: St,
     /// Payload events handler, used to broadcast and subscribe to payload events.
     payload_events: broadcast::Sender<Events<Engine>>,
+    /// Persists the payload jobs and their payloads across restarts, if enabled.
+    persistence: Option<ServicePersistence<Engine>>,
 }
 
 const PAYLOAD_EVENTS_BUFFER_SIZE: usize = 20;
 
+impl<Gen, St, Engine> fmt::Debug for PayloadBuilderService<Gen, St, Engine>
+where
+    Engine: EngineTypes,
+    Gen: PayloadJobGenerator + fmt::Debug,
+    Gen::Job: PayloadJob<PayloadAttributes = Engine::PayloadBuilderAttributes>,
+    St: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PayloadBuilderService")
+            .field("generator", &self.generator)
+            .field("additional_generators", &self.additional_generators.len())
+            .field(
+                "payload_jobs",
+                &self.payload_jobs.iter().map(|(_, id, _)| id).collect::<Vec<_>>(),
+            )
+            .field("resolve_deadline", &self.resolve_deadline)
+            .field("chain_events", &self.chain_events)
+            .field("persistence", &self.persistence)
+            .finish_non_exhaustive()
+    }
+}
+
 // === impl PayloadBuilderService ===
 
 impl<Gen, St, Engine> PayloadBuilderService<Gen, St, Engine>
 where
     Engine: EngineTypes + 'static,
     Gen: PayloadJobGenerator,
-    Gen::Job: PayloadJob<PayloadAttributes = Engine::PayloadBuilderAttributes>,
+    Gen::Job: PayloadJob<PayloadAttributes = Engine::PayloadBuilderAttributes> + Unpin + 'static,
     <Gen::Job as PayloadJob>::BuiltPayload: Into<Engine::BuiltPayload>,
 {
     /// Creates a new payload builder service and returns the [`PayloadBuilderHandle`] to interact
@@ -253,26 +310,147 @@ where
 
         let service = Self {
             generator,
+            additional_generators: Vec::new(),
             payload_jobs: Vec::new(),
+            resolve_deadline: DEFAULT_RESOLVE_DEADLINE,
             service_tx,
             command_rx: UnboundedReceiverStream::new(command_rx),
             metrics: Default::default(),
             chain_events,
             payload_events,
+            persistence: None,
         };
 
         let handle = service.handle();
         (service, handle)
     }
 
+    /// Registers an additional generator that starts a job for every new payload alongside the
+    /// jobs of the main generator.
+    ///
+    /// When the payload is resolved, the jobs of all generators are resolved concurrently and the
+    /// payload with the highest fees is returned, if the fees are equal the payload that uses
+    /// the most gas wins. Equally good payloads of the main generator win over the ones of the
+    /// additional generators, and the payloads of generators registered earlier over the ones of
+    /// generators registered later. The jobs of a payload that is built by multiple generators are
+    /// always terminated once the payload is resolved.
+    pub fn with_additional_generator<G>(mut self, generator: G) -> Self
+    where
+        G: PayloadJobGenerator + 'static,
+        G::Job: PayloadJob<PayloadAttributes = Engine::PayloadBuilderAttributes> + Unpin + 'static,
+        <G::Job as PayloadJob>::BuiltPayload: Into<Engine::BuiltPayload>,
+    {
+        self.additional_generators.push(Box::new(generator));
+        self
+    }
+
+    /// Sets the time the jobs of the additional generators of a payload are given to resolve, jobs
+    /// that take longer are not considered.
+    ///
+    /// The job of the main generator is always awaited.
+    ///
+    /// Defaults to [`DEFAULT_RESOLVE_DEADLINE`].
+    pub const fn with_resolve_deadline(mut self, deadline: Duration) -> Self {
+        self.resolve_deadline = deadline;
+        self
+    }
+
+    /// Enables the persistence of payload jobs and built payloads, so the payloads can still be
+    /// served after a restart.
+    ///
+    /// This immediately restarts the jobs of the persisted payload attributes whose timestamp is
+    /// still in the future. The best payloads of the active jobs are persisted at the given
+    /// interval and when they are resolved. All writes happen on blocking tasks, so they never
+    /// delay the service or the resolved payloads.
+    pub fn with_persistence(
+        mut self,
+        persistence: impl PayloadPersistence<Engine> + 'static,
+        interval: Duration,
+    ) -> Self {
+        let now = unix_timestamp();
+        if let Err(err) = persistence.remove_stale(now) {
+            warn!(%err, "Failed to remove stale persisted payloads");
+        }
+        match persistence.load_attributes() {
+            Ok(attributes) => {
+                for attr in attributes.into_iter().filter(|attr| attr.timestamp() > now) {
+                    let id = attr.payload_id();
+                    if !self.contains_payload(id) && self.new_payload_job(attr).is_ok() {
+                        info!(%id, "Restarted persisted payload job");
+                    }
+                }
+            }
+            Err(err) => warn!(%err, "Failed to load persisted payload attributes"),
+        }
+
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        self.persistence = Some(ServicePersistence {
+            inner: Arc::new(persistence),
+            interval,
+            persisted: HashMap::new(),
+            pending: None,
+        });
+        self
+    }
+
     /// Returns a handle to the service.
     pub fn handle(&self) -> PayloadBuilderHandle<Engine> {
         PayloadBuilderHandle::new(self.service_tx.clone())
     }
 
+    /// Creates a new payload job for the given attributes.
+    ///
+    /// This creates a job for every generator, but only fails if the main generator fails to
+    /// create its job.
+    fn new_payload_job(
+        &mut self,
+        attr: Engine::PayloadBuilderAttributes,
+    ) -> Result<(), PayloadBuilderError> {
+        let id = attr.payload_id();
+        let parent = attr.parent();
+        match self.generator.new_payload_job(attr.clone()) {
+            Ok(job) => {
+                info!(%id, %parent, "New payload job created");
+                self.metrics.inc_initiated_jobs();
+                self.payload_jobs.push((Box::new(job), id, MAIN_GENERATOR));
+            }
+            Err(err) => {
+                self.metrics.inc_failed_jobs();
+                warn!(%err, %id, "Failed to create payload builder job");
+                return Err(err)
+            }
+        }
+
+        for (idx, generator) in self.additional_generators.iter().enumerate() {
+            match generator.new_boxed_job(attr.clone()) {
+                Ok(job) => {
+                    self.metrics.inc_initiated_jobs();
+                    self.payload_jobs.push((job, id, MAIN_GENERATOR + 1 + idx));
+                }
+                Err(err) => {
+                    self.metrics.inc_failed_jobs();
+                    warn!(%err, %id, "Failed to create additional payload builder job");
+                }
+            }
+        }
+
+        if let Some(persistence) = &self.persistence {
+            let persistence = persistence.inner.clone();
+            let attr = attr.clone();
+            tokio::task::spawn_blocking(move || {
+                if let Err(err) = persistence.save_attributes(&attr) {
+                    warn!(%err, %id, "Failed to persist payload attributes");
+                }
+            });
+        }
+        self.payload_events.send(Events::Attributes(attr)).ok();
+        Ok(())
+    }
+
     /// Returns true if the given payload is currently being built.
     fn contains_payload(&self, id: PayloadId) -> bool {
-        self.payload_jobs.iter().any(|(_, job_id)| *job_id == id)
+        self.payload_jobs.iter().any(|(_, job_id, _)| *job_id == id)
     }
 
     /// Returns the best payload for the given identifier that has been built so far.
@@ -280,11 +458,13 @@ where
         &self,
         id: PayloadId,
     ) -> Option<Result<Engine::BuiltPayload, PayloadBuilderError>> {
-        let res = self
-            .payload_jobs
-            .iter()
-            .find(|(_, job_id)| *job_id == id)
-            .map(|(j, _)| j.best_payload().map(|p| p.into()));
+        let res = select_best_payload(
+            self.payload_jobs
+                .iter()
+                .filter(|(_, job_id, _)| *job_id == id)
+                .map(|(job, _, generator)| (*generator, job.best_payload())),
+        )
+        .or_else(|| self.persisted_payload(id).map(Ok));
         if let Some(Ok(ref best)) = res {
             self.metrics.set_best_revenue(best.block().number, f64::from(best.fees()));
         }
@@ -294,35 +474,107 @@ where
 
     /// Returns the best payload for the given identifier that has been built so far and terminates
     /// the job if requested.
+    ///
+    /// If the payload is built by multiple jobs, all of them are resolved concurrently and
+    /// terminated, and the best of their payloads is returned. The job of the main generator is
+    /// always awaited, the jobs of the additional generators are only considered if they resolve
+    /// within the deadline.
     fn resolve(&mut self, id: PayloadId) -> Option<PayloadFuture<Engine::BuiltPayload>> {
         trace!(%id, "resolving payload job");
 
-        let job = self.payload_jobs.iter().position(|(_, job_id)| *job_id == id)?;
-        let (fut, keep_alive) = self.payload_jobs[job].0.resolve();
+        let mut resolving = Vec::new();
+        for idx in (0..self.payload_jobs.len()).rev() {
+            if self.payload_jobs[idx].1 != id {
+                continue
+            }
+            let (job, _, generator) = &mut self.payload_jobs[idx];
+            let (fut, keep_alive) = job.resolve();
+            resolving.push((*generator, fut));
 
-        if keep_alive == KeepPayloadJobAlive::No {
-            let (_, id) = self.payload_jobs.remove(job);
-            trace!(%id, "terminated resolved job");
+            if keep_alive == KeepPayloadJobAlive::No {
+                self.payload_jobs.swap_remove(idx);
+                trace!(%id, "terminated resolved job");
+            }
         }
 
+        let fut = match resolving.len() {
+            0 => {
+                // the payload may have been built before a restart
+                let payload = self.persisted_payload(id)?;
+                return Some(Box::pin(future::ready(Ok(payload))))
+            }
+            1 => resolving.pop().expect("exists").1,
+            jobs => {
+                // the losing jobs are cancelled
+                self.payload_jobs.retain(|(_, job_id, _)| *job_id != id);
+                trace!(%id, jobs, "racing resolved jobs");
+
+                let deadline = self.resolve_deadline;
+                Box::pin(async move {
+                    let resolved = future::join_all(resolving.into_iter().map(
+                        move |(generator, fut)| async move {
+                            let res = if generator == MAIN_GENERATOR {
+                                fut.await
+                            } else {
+                                tokio::time::timeout(deadline, fut)
+                                    .await
+                                    .unwrap_or(Err(PayloadBuilderError::MissingPayload))
+                            };
+                            (generator, res)
+                        },
+                    ))
+                    .await;
+                    select_best_payload(resolved)
+                        .unwrap_or(Err(PayloadBuilderError::MissingPayload))
+                })
+            }
+        };
+
         // Since the fees will not be known until the payload future is resolved / awaited, we wrap
         // the future in a new future that will update the metrics.
         let resolved_metrics = self.metrics.clone();
         let payload_events = self.payload_events.clone();
+        let persistence = self.persistence.as_ref().map(|persistence| persistence.inner.clone());
 
         let fut = async move {
             let res = fut.await;
             if let Ok(ref payload) = res {
-                payload_events.send(Events::BuiltPayload(payload.clone().into())).ok();
+                payload_events.send(Events::BuiltPayload { id, payload: payload.clone() }).ok();
 
                 resolved_metrics
                     .set_resolved_revenue(payload.block().number, f64::from(payload.fees()));
+
+                // compressing and writing the payload must not delay the response
+                if let Some(persistence) = persistence {
+                    let payload = payload.clone();
+                    tokio::task::spawn_blocking(move || {
+                        if let Err(err) = persistence.save_payload(id, &payload) {
+                            warn!(%err, %id, "Failed to persist resolved payload");
+                        }
+                    });
+                }
             }
-            res.map(|p| p.into())
+            res
         };
 
         Some(Box::pin(fut))
     }
+
+    /// Returns the persisted payload for the given identifier, if persistence is enabled.
+    fn persisted_payload(&self, id: PayloadId) -> Option<Engine::BuiltPayload> {
+        match self.persistence.as_ref()?.inner.load_payload(id) {
+            Ok(payload) => {
+                if payload.is_some() {
+                    debug!(%id, "Serving persisted payload");
+                }
+                payload
+            }
+            Err(err) => {
+                warn!(%err, %id, "Failed to load persisted payload");
+                None
+            }
+        }
+    }
 }
 
 impl<Gen, St, Engine> PayloadBuilderService<Gen, St, Engine>
@@ -336,12 +588,12 @@ where
     fn payload_attributes(
         &self,
         id: PayloadId,
-    ) -> Option<Result<<Gen::Job as PayloadJob>::PayloadAttributes, PayloadBuilderError>> {
+    ) -> Option<Result<Engine::PayloadBuilderAttributes, PayloadBuilderError>> {
         let attributes = self
             .payload_jobs
             .iter()
-            .find(|(_, job_id)| *job_id == id)
-            .map(|(j, _)| j.payload_attributes());
+            .find(|(_, job_id, _)| *job_id == id)
+            .map(|(j, _, _)| j.payload_attributes());
 
         if attributes.is_none() {
             trace!(%id, "no matching payload job found to get attributes for");
@@ -367,6 +619,9 @@ where
         loop {
             // notify the generator of new chain events
             while let Poll::Ready(Some(new_head)) = this.chain_events.poll_next_unpin(cx) {
+                for generator in &mut this.additional_generators {
+                    generator.on_new_canon_state(new_head.clone());
+                }
                 this.generator.on_new_state(new_head);
             }
 
@@ -374,13 +629,17 @@ where
             // requests
             // we don't care about the order of the jobs, so we can just swap_remove them
             for idx in (0..this.payload_jobs.len()).rev() {
-                let (mut job, id) = this.payload_jobs.swap_remove(idx);
+                let (mut job, id, generator) = this.payload_jobs.swap_remove(idx);
 
                 // drain better payloads from the job
                 match job.poll_unpin(cx) {
                     Poll::Ready(Ok(_)) => {
                         this.metrics.set_active_jobs(this.payload_jobs.len());
                         trace!(%id, "payload job finished");
+                        // the job ended without being resolved, report its best payload anyway
+                        if let Ok(payload) = job.best_payload() {
+                            this.payload_events.send(Events::BuiltPayload { id, payload }).ok();
+                        }
                     }
                     Poll::Ready(Err(err)) => {
                         warn!(%err, ?id, "Payload builder job failed; resolving payload");
@@ -389,11 +648,19 @@ where
                     }
                     Poll::Pending => {
                         // still pending, put it back
-                        this.payload_jobs.push((job, id));
+                        this.payload_jobs.push((job, id, generator));
                     }
                 }
             }
 
+            // persist the best payloads of the active jobs
+            if let Some(persistence) = &mut this.persistence {
+                persistence.poll_pending(cx);
+                if persistence.interval.poll_tick(cx).is_ready() {
+                    persistence.persist_best_payloads(&this.payload_jobs);
+                }
+            }
+
             // marker for exit condition
             let mut new_job = false;
 
@@ -408,20 +675,9 @@ where
                             debug!(%id, parent = %attr.parent(), "Payload job already in progress, ignoring.");
                         } else {
                             // no job for this payload yet, create one
-                            let parent = attr.parent();
-                            match this.generator.new_payload_job(attr.clone()) {
-                                Ok(job) => {
-                                    info!(%id, %parent, "New payload job created");
-                                    this.metrics.inc_initiated_jobs();
-                                    new_job = true;
-                                    this.payload_jobs.push((job, id));
-                                    this.payload_events.send(Events::Attributes(attr.clone())).ok();
-                                }
-                                Err(err) => {
-                                    this.metrics.inc_failed_jobs();
-                                    warn!(%err, %id, "Failed to create payload builder job");
-                                    res = Err(err);
-                                }
+                            match this.new_payload_job(attr) {
+                                Ok(()) => new_job = true,
+                                Err(err) => res = Err(err),
                             }
                         }
 
@@ -452,6 +708,177 @@ where
     }
 }
 
+/// The persistence of a [`PayloadBuilderService`].
+#[derive(Debug)]
+struct ServicePersistence<Engine: EngineTypes> {
+    /// Stores the payload attributes and the built payloads.
+    inner: Arc<dyn PayloadPersistence<Engine>>,
+    /// The interval at which the best payloads of the active jobs are persisted.
+    interval: Interval,
+    /// The hash of the last persisted payload of each active job.
+    persisted: HashMap<PayloadId, B256>,
+    /// The blocking task that persists the best payloads, resolves to the payloads it persisted.
+    pending: Option<JoinHandle<Vec<(PayloadId, B256)>>>,
+}
+
+impl<Engine: EngineTypes + 'static> ServicePersistence<Engine> {
+    /// Records the payloads persisted by the pending task once it finished.
+    fn poll_pending(&mut self, cx: &mut Context<'_>) {
+        let Some(pending) = &mut self.pending else { return };
+        let Poll::Ready(res) = pending.poll_unpin(cx) else { return };
+        self.pending = None;
+        match res {
+            Ok(persisted) => self.persisted.extend(persisted),
+            Err(err) => warn!(%err, "Failed to persist best payloads"),
+        }
+    }
+
+    /// Persists the best payloads of the active payloads that changed since they were last
+    /// persisted and removes the stale entries on a blocking task.
+    ///
+    /// This is skipped while the previous task is still running.
+    fn persist_best_payloads(&mut self, jobs: &[(BoxedPayloadJob<Engine>, PayloadId, usize)]) {
+        if self.pending.is_some() {
+            return
+        }
+        self.persisted.retain(|id, _| jobs.iter().any(|(_, job_id, _)| job_id == id));
+
+        let mut payloads = HashMap::<PayloadId, Vec<_>>::new();
+        for (job, id, generator) in jobs {
+            payloads.entry(*id).or_default().push((*generator, job.best_payload()));
+        }
+
+        let mut changed = Vec::new();
+        for (id, payloads) in payloads {
+            let Some(Ok(payload)) = select_best_payload(payloads) else { continue };
+            let hash = payload.block().hash();
+            if self.persisted.get(&id) != Some(&hash) {
+                changed.push((id, hash, payload));
+            }
+        }
+
+        let inner = self.inner.clone();
+        self.pending = Some(tokio::task::spawn_blocking(move || {
+            let mut persisted = Vec::with_capacity(changed.len());
+            for (id, hash, payload) in changed {
+                match inner.save_payload(id, &payload) {
+                    Ok(()) => persisted.push((id, hash)),
+                    Err(err) => warn!(%err, %id, "Failed to persist best payload"),
+                }
+            }
+
+            if let Err(err) = inner.remove_stale(unix_timestamp()) {
+                warn!(%err, "Failed to remove stale persisted payloads");
+            }
+            persisted
+        }));
+    }
+}
+
+/// Returns the current unix timestamp in seconds.
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Returns true if the `payload` is better than the `other` payload: it collects more fees or, if
+/// the fees are equal, uses more gas.
+fn is_better_payload<P: BuiltPayload>(payload: &P, other: &P) -> bool {
+    (payload.fees(), payload.block().gas_used) > (other.fees(), other.block().gas_used)
+}
+
+/// Selects the best of the given payloads of the jobs of the generators with the given indices.
+///
+/// Of equally good payloads, the one of the generator with the lowest index wins, so the main
+/// generator wins ties. Returns the error of the generator with the lowest index if there is no
+/// payload.
+fn select_best_payload<P: BuiltPayload>(
+    payloads: impl IntoIterator<Item = (usize, Result<P, PayloadBuilderError>)>,
+) -> Option<Result<P, PayloadBuilderError>> {
+    let mut best: Option<(usize, Result<P, PayloadBuilderError>)> = None;
+    for (generator, payload) in payloads {
+        let is_better = match (&best, &payload) {
+            (None, _) | (Some((_, Err(_))), Ok(_)) => true,
+            (Some((best_generator, Ok(best))), Ok(payload)) => {
+                is_better_payload(payload, best) ||
+                    (!is_better_payload(best, payload) && generator < *best_generator)
+            }
+            (Some((best_generator, Err(_))), Err(_)) => generator < *best_generator,
+            (Some((_, Ok(_))), Err(_)) => false,
+        };
+        if is_better {
+            best = Some((generator, payload));
+        }
+    }
+    best.map(|(_, payload)| payload)
+}
+
+/// A [`PayloadJob`] whose built payloads are converted into the payloads of the engine, so the
+/// jobs of different [`PayloadJobGenerator`]s can be managed by the same
+/// [`PayloadBuilderService`].
+trait ServicePayloadJob<Engine: EngineTypes>:
+    Future<Output = Result<(), PayloadBuilderError>> + Send + Sync + Unpin
+{
+    /// See [`PayloadJob::best_payload`].
+    fn best_payload(&self) -> Result<Engine::BuiltPayload, PayloadBuilderError>;
+
+    /// See [`PayloadJob::payload_attributes`].
+    fn payload_attributes(&self) -> Result<Engine::PayloadBuilderAttributes, PayloadBuilderError>;
+
+    /// See [`PayloadJob::resolve`].
+    fn resolve(&mut self) -> (PayloadFuture<Engine::BuiltPayload>, KeepPayloadJobAlive);
+}
+
+impl<Engine, Job> ServicePayloadJob<Engine> for Job
+where
+    Engine: EngineTypes + 'static,
+    Job: PayloadJob<PayloadAttributes = Engine::PayloadBuilderAttributes> + Unpin + 'static,
+    Job::BuiltPayload: Into<Engine::BuiltPayload>,
+{
+    fn best_payload(&self) -> Result<Engine::BuiltPayload, PayloadBuilderError> {
+        PayloadJob::best_payload(self).map(Into::into)
+    }
+
+    fn payload_attributes(&self) -> Result<Engine::PayloadBuilderAttributes, PayloadBuilderError> {
+        PayloadJob::payload_attributes(self)
+    }
+
+    fn resolve(&mut self) -> (PayloadFuture<Engine::BuiltPayload>, KeepPayloadJobAlive) {
+        let (fut, keep_alive) = PayloadJob::resolve(self);
+        (Box::pin(fut.map(|res| res.map(Into::into))), keep_alive)
+    }
+}
+
+/// A [`PayloadJobGenerator`] whose jobs are boxed [`ServicePayloadJob`]s.
+trait ServicePayloadJobGenerator<Engine: EngineTypes>: Send + Sync {
+    /// See [`PayloadJobGenerator::new_payload_job`].
+    fn new_boxed_job(
+        &self,
+        attr: Engine::PayloadBuilderAttributes,
+    ) -> Result<BoxedPayloadJob<Engine>, PayloadBuilderError>;
+
+    /// See [`PayloadJobGenerator::on_new_state`].
+    fn on_new_canon_state(&mut self, new_state: CanonStateNotification);
+}
+
+impl<Engine, Gen> ServicePayloadJobGenerator<Engine> for Gen
+where
+    Engine: EngineTypes + 'static,
+    Gen: PayloadJobGenerator,
+    Gen::Job: PayloadJob<PayloadAttributes = Engine::PayloadBuilderAttributes> + Unpin + 'static,
+    <Gen::Job as PayloadJob>::BuiltPayload: Into<Engine::BuiltPayload>,
+{
+    fn new_boxed_job(
+        &self,
+        attr: Engine::PayloadBuilderAttributes,
+    ) -> Result<BoxedPayloadJob<Engine>, PayloadBuilderError> {
+        Ok(Box::new(self.new_payload_job(attr)?))
+    }
+
+    fn on_new_canon_state(&mut self, new_state: CanonStateNotification) {
+        self.on_new_state(new_state)
+    }
+}
+
 /// Message type for the [`PayloadBuilderService`].
 pub enum PayloadServiceCommand<Engine: EngineTypes> {
     /// Start building a new payload.
@@ -495,3 +922,178 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestPayloadJobGenerator;
+    use reth_ethereum_engine_primitives::{
+        EthBuiltPayload, EthEngineTypes, EthPayloadBuilderAttributes,
+    };
+    use reth_primitives::{Block, Bytes, Header, U256};
+    use reth_rpc_types::engine::PayloadAttributes;
+
+    const DUMMY_EXTRA_DATA: &[u8] = b"dummy";
+
+    /// Creates jobs whose payloads collect the given fees and use the given gas, and that resolve
+    /// after the given delay or never.
+    #[derive(Debug, Clone)]
+    struct DummyPayloadJobGenerator {
+        fees: U256,
+        gas_used: u64,
+        resolve_after: Option<Duration>,
+    }
+
+    impl PayloadJobGenerator for DummyPayloadJobGenerator {
+        type Job = DummyPayloadJob;
+
+        fn new_payload_job(
+            &self,
+            attr: EthPayloadBuilderAttributes,
+        ) -> Result<Self::Job, PayloadBuilderError> {
+            Ok(DummyPayloadJob { attr, generator: self.clone() })
+        }
+    }
+
+    struct DummyPayloadJob {
+        attr: EthPayloadBuilderAttributes,
+        generator: DummyPayloadJobGenerator,
+    }
+
+    impl Future for DummyPayloadJob {
+        type Output = Result<(), PayloadBuilderError>;
+
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+            Poll::Pending
+        }
+    }
+
+    impl PayloadJob for DummyPayloadJob {
+        type PayloadAttributes = EthPayloadBuilderAttributes;
+        type ResolvePayloadFuture = PayloadFuture<EthBuiltPayload>;
+        type BuiltPayload = EthBuiltPayload;
+
+        fn best_payload(&self) -> Result<EthBuiltPayload, PayloadBuilderError> {
+            let block = Block {
+                header: Header {
+                    gas_used: self.generator.gas_used,
+                    extra_data: Bytes::from_static(DUMMY_EXTRA_DATA),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            Ok(EthBuiltPayload::new(self.attr.payload_id(), block.seal_slow(), self.generator.fees))
+        }
+
+        fn payload_attributes(&self) -> Result<EthPayloadBuilderAttributes, PayloadBuilderError> {
+            Ok(self.attr.clone())
+        }
+
+        fn resolve(&mut self) -> (Self::ResolvePayloadFuture, KeepPayloadJobAlive) {
+            let payload = self.best_payload();
+            let fut: Self::ResolvePayloadFuture = match self.generator.resolve_after {
+                Some(delay) => Box::pin(async move {
+                    tokio::time::sleep(delay).await;
+                    payload
+                }),
+                None => Box::pin(future::pending()),
+            };
+            (fut, KeepPayloadJobAlive::No)
+        }
+    }
+
+    fn dummy(fees: u64, gas_used: u64, resolve_after: Option<u64>) -> DummyPayloadJobGenerator {
+        DummyPayloadJobGenerator {
+            fees: U256::from(fees),
+            gas_used,
+            resolve_after: resolve_after.map(Duration::from_millis),
+        }
+    }
+
+    fn attributes() -> EthPayloadBuilderAttributes {
+        EthPayloadBuilderAttributes::new(
+            B256::random(),
+            PayloadAttributes {
+                timestamp: 1,
+                prev_randao: B256::random(),
+                suggested_fee_recipient: Default::default(),
+                withdrawals: Some(vec![]),
+                parent_beacon_block_root: Some(B256::random()),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn resolves_best_payload_of_all_generators() {
+        // the payloads of the test generator collect no fees and use no gas
+        let cases = [
+            // more fees
+            (dummy(1, 0, Some(0)), true),
+            // same fees but more gas used
+            (dummy(0, 1, Some(0)), true),
+            // equally good payloads
+            (dummy(0, 0, Some(0)), false),
+            // more fees but not resolved within the deadline
+            (dummy(1, 0, None), false),
+        ];
+
+        for (generator, dummy_wins) in cases {
+            let (service, handle) = PayloadBuilderService::<_, _, EthEngineTypes>::new(
+                TestPayloadJobGenerator::default(),
+                futures_util::stream::empty(),
+            );
+            tokio::spawn(
+                service
+                    .with_additional_generator(generator)
+                    .with_resolve_deadline(Duration::from_millis(100)),
+            );
+            let store = PayloadStore::from(handle.clone());
+
+            let id = handle.new_payload(attributes()).await.unwrap();
+            // poll the service a few times, so the jobs are reordered
+            for _ in 0..3 {
+                store.best_payload(id).await.unwrap().unwrap();
+            }
+
+            let payload = store.resolve(id).await.unwrap().unwrap();
+            assert_eq!(&payload.block().extra_data[..] == DUMMY_EXTRA_DATA, dummy_wins);
+            // the jobs of all generators are terminated
+            assert!(store.payload_attributes(id).await.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn awaits_main_generator_past_deadline() {
+        // the payloads of the test generator collect no fees and use no gas
+        let cases = [
+            // better payload
+            dummy(1, 0, Some(200)),
+            // equally good payload
+            dummy(0, 0, Some(200)),
+        ];
+
+        for generator in cases {
+            let (service, handle) = PayloadBuilderService::<_, _, EthEngineTypes>::new(
+                generator,
+                futures_util::stream::empty(),
+            );
+            tokio::spawn(
+                service
+                    .with_additional_generator(TestPayloadJobGenerator::default())
+                    .with_additional_generator(TestPayloadJobGenerator::default())
+                    .with_resolve_deadline(Duration::from_millis(100)),
+            );
+            let store = PayloadStore::from(handle.clone());
+
+            let id = handle.new_payload(attributes()).await.unwrap();
+            for _ in 0..3 {
+                store.best_payload(id).await.unwrap().unwrap();
+            }
+
+            // the payload of the main generator is returned, although it resolved after the
+            // deadline
+            let payload = store.resolve(id).await.unwrap().unwrap();
+            assert_eq!(&payload.block().extra_data[..], DUMMY_EXTRA_DATA);
+        }
+    }
+}
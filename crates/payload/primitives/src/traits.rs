@@ -63,6 +63,12 @@ pub trait PayloadBuilderAttributes: Send + Sync + std::fmt::Debug {
     /// Returns the withdrawals for the running payload job.
     fn withdrawals(&self) -> &Withdrawals;
 
+    /// Returns the gas limit the payload should target, if it overrides the gas limit derived
+    /// from the parent block.
+    ///
+    /// This is respected by [`PayloadBuilderAttributes::cfg_and_block_env`].
+    fn gas_limit(&self) -> Option<u64>;
+
     /// Returns the configured [`CfgEnvWithHandlerCfg`] and [`BlockEnv`] for the targeted payload
     /// (that has the `parent` as its parent).
     ///
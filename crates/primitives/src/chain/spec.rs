@@ -3,6 +3,7 @@ use crate::{
         EIP1559_INITIAL_BASE_FEE, EMPTY_RECEIPTS, EMPTY_ROOT_HASH, EMPTY_TRANSACTIONS,
         EMPTY_WITHDRAWALS,
     },
+    eip4844::BlobParams,
     holesky_nodes,
     net::{goerli_nodes, mainnet_nodes, sepolia_nodes},
     proofs::state_root_ref_unhashed,
@@ -828,6 +829,18 @@ impl ChainSpec {
         self.is_fork_active_at_timestamp(Hardfork::Prague, timestamp)
     }
 
+    /// Returns the [`BlobParams`] of the blob fee market active at the given timestamp.
+    ///
+    /// This returns the Cancun parameters for timestamps before Cancun, blocks before Cancun
+    /// don't have a blob fee market.
+    pub fn blob_params_at_timestamp(&self, timestamp: u64) -> BlobParams {
+        if self.is_prague_active_at_timestamp(timestamp) {
+            BlobParams::prague()
+        } else {
+            BlobParams::cancun()
+        }
+    }
+
     /// Convenience method to check if [`Hardfork::Byzantium`] is active at a given block number.
     #[inline]
     pub fn is_byzantium_active_at_block(&self, block_number: u64) -> bool {
@@ -3336,6 +3349,18 @@ Post-merge hard forks (timestamp based):
         )
     }
 
+    #[test]
+    fn blob_params_at_timestamp() {
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .cancun_activated()
+            .with_fork(Hardfork::Prague, ForkCondition::Timestamp(100))
+            .build();
+        assert_eq!(chain_spec.blob_params_at_timestamp(0), BlobParams::cancun());
+        assert_eq!(chain_spec.blob_params_at_timestamp(99), BlobParams::cancun());
+        assert_eq!(chain_spec.blob_params_at_timestamp(100), BlobParams::prague());
+        assert_eq!(MAINNET.blob_params_at_timestamp(u64::MAX), BlobParams::cancun());
+    }
+
     #[cfg(feature = "optimism")]
     #[test]
     fn is_bedrock_active() {
@@ -68,6 +68,10 @@ pub const EIP1559_DEFAULT_ELASTICITY_MULTIPLIER: u64 = 2;
 /// Minimum gas limit allowed for transactions.
 pub const MINIMUM_GAS_LIMIT: u64 = 5000;
 
+/// The bound divisor of the gas limit, the gas limit of a block can differ from the gas limit of
+/// its parent by less than `parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR`.
+pub const GAS_LIMIT_BOUND_DIVISOR: u64 = 1024;
+
 /// Deposit contract address
 pub const MAINNET_DEPOSIT_CONTRACT: DepositContract = DepositContract::new(
     address!("00000000219ab540356cbb839cbe05303d7705fa"),
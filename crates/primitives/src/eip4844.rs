@@ -1,5 +1,12 @@
 //! Helpers for working with EIP-4844 blob fee.
 
+use crate::{
+    constants::eip4844::{DATA_GAS_PER_BLOB, MAX_DATA_GAS_PER_BLOCK, TARGET_DATA_GAS_PER_BLOCK},
+    revm_primitives::{
+        fake_exponential, BlobExcessGasAndPrice, BLOB_GASPRICE_UPDATE_FRACTION, MIN_BLOB_GASPRICE,
+    },
+};
+
 // re-exports from revm for calculating blob fee
 pub use crate::revm_primitives::{
     calc_blob_gasprice, calc_excess_blob_gas as calculate_excess_blob_gas,
@@ -7,3 +14,133 @@ pub use crate::revm_primitives::{
 
 #[doc(inline)]
 pub use alloy_eips::eip4844::kzg_to_versioned_hash;
+
+/// The target number of blobs per block, see [EIP-7691](https://eips.ethereum.org/EIPS/eip-7691).
+pub const TARGET_BLOBS_PER_BLOCK_PRAGUE: u64 = 6;
+
+/// The maximum number of blobs per block, see [EIP-7691](https://eips.ethereum.org/EIPS/eip-7691).
+pub const MAX_BLOBS_PER_BLOCK_PRAGUE: u64 = 9;
+
+/// The blob base fee update fraction, see [EIP-7691](https://eips.ethereum.org/EIPS/eip-7691).
+pub const BLOB_GASPRICE_UPDATE_FRACTION_PRAGUE: u64 = 5_007_716;
+
+/// The parameters of the blob fee market of a hardfork.
+///
+/// See also [`ChainSpec::blob_params_at_timestamp`](crate::ChainSpec::blob_params_at_timestamp).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobParams {
+    /// The target blob gas per block.
+    pub target_blob_gas_per_block: u64,
+    /// The maximum blob gas per block.
+    pub max_blob_gas_per_block: u64,
+    /// The update fraction of the blob base fee.
+    pub update_fraction: u64,
+}
+
+impl BlobParams {
+    /// The blob parameters of [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844), active since
+    /// Cancun.
+    pub const fn cancun() -> Self {
+        Self {
+            target_blob_gas_per_block: TARGET_DATA_GAS_PER_BLOCK,
+            max_blob_gas_per_block: MAX_DATA_GAS_PER_BLOCK,
+            update_fraction: BLOB_GASPRICE_UPDATE_FRACTION,
+        }
+    }
+
+    /// The blob parameters of [EIP-7691](https://eips.ethereum.org/EIPS/eip-7691), active since
+    /// Prague.
+    pub const fn prague() -> Self {
+        Self {
+            target_blob_gas_per_block: TARGET_BLOBS_PER_BLOCK_PRAGUE * DATA_GAS_PER_BLOB,
+            max_blob_gas_per_block: MAX_BLOBS_PER_BLOCK_PRAGUE * DATA_GAS_PER_BLOB,
+            update_fraction: BLOB_GASPRICE_UPDATE_FRACTION_PRAGUE,
+        }
+    }
+
+    /// Calculates the blob fee for the given excess blob gas.
+    pub fn calc_blob_fee(&self, excess_blob_gas: u64) -> u128 {
+        fake_exponential(MIN_BLOB_GASPRICE, excess_blob_gas, self.update_fraction)
+    }
+
+    /// Returns the excess blob gas and the blob fee of a block for the EVM block env.
+    ///
+    /// Unlike [`BlobExcessGasAndPrice::new`], this uses the update fraction of these parameters.
+    pub fn blob_excess_gas_and_price(&self, excess_blob_gas: u64) -> BlobExcessGasAndPrice {
+        BlobExcessGasAndPrice {
+            excess_blob_gas,
+            blob_gasprice: self.calc_blob_fee(excess_blob_gas),
+        }
+    }
+
+    /// Calculates the excess blob gas of the next block, given the excess blob gas and the blob
+    /// gas used of the parent block.
+    pub const fn next_block_excess_blob_gas(
+        &self,
+        parent_excess_blob_gas: u64,
+        parent_blob_gas_used: u64,
+    ) -> u64 {
+        (parent_excess_blob_gas + parent_blob_gas_used)
+            .saturating_sub(self.target_blob_gas_per_block)
+    }
+
+    /// Returns the ratio of the given blob gas used to the maximum blob gas per block.
+    pub fn blob_gas_used_ratio(&self, blob_gas_used: u64) -> f64 {
+        blob_gas_used as f64 / self.max_blob_gas_per_block as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test vectors of the EIP-4844 reference implementation, also used by other clients
+    #[test]
+    fn cancun_blob_fee() {
+        let params = BlobParams::cancun();
+        for (excess_blob_gas, blob_fee) in
+            [(0, 1), (2314057, 1), (2314058, 2), (10 * 1024 * 1024, 23)]
+        {
+            assert_eq!(params.calc_blob_fee(excess_blob_gas), blob_fee);
+            assert_eq!(calc_blob_gasprice(excess_blob_gas), blob_fee);
+        }
+    }
+
+    #[test]
+    fn prague_blob_fee() {
+        let params = BlobParams::prague();
+        assert_eq!(params.calc_blob_fee(0), 1);
+        // the update fraction is 1.5 times the one of cancun, so is the excess blob gas needed
+        // for the same fee
+        assert_eq!(params.calc_blob_fee(10 * 1024 * 1024), 8);
+        assert_eq!(
+            BlobParams::cancun().calc_blob_fee(10 * 1024 * 1024),
+            params.calc_blob_fee(15 * 1024 * 1024)
+        );
+    }
+
+    #[test]
+    fn next_block_excess_blob_gas() {
+        let cancun = BlobParams::cancun();
+        let prague = BlobParams::prague();
+        for (excess_blob_gas, blob_gas_used) in
+            [(0, 0), (0, MAX_DATA_GAS_PER_BLOCK), (10 * DATA_GAS_PER_BLOB, 2 * DATA_GAS_PER_BLOB)]
+        {
+            assert_eq!(
+                cancun.next_block_excess_blob_gas(excess_blob_gas, blob_gas_used),
+                calculate_excess_blob_gas(excess_blob_gas, blob_gas_used)
+            );
+        }
+
+        // a full cancun block is below the prague target
+        assert_eq!(
+            cancun.next_block_excess_blob_gas(0, MAX_DATA_GAS_PER_BLOCK),
+            3 * DATA_GAS_PER_BLOB
+        );
+        assert_eq!(prague.next_block_excess_blob_gas(0, MAX_DATA_GAS_PER_BLOCK), 0);
+        assert_eq!(
+            prague.next_block_excess_blob_gas(0, prague.max_blob_gas_per_block),
+            3 * DATA_GAS_PER_BLOB
+        );
+    }
+}
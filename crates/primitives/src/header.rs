@@ -5,9 +5,9 @@ use crate::{
     constants,
     constants::{
         ALLOWED_FUTURE_BLOCK_TIME_SECONDS, EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH,
-        MINIMUM_GAS_LIMIT,
+        GAS_LIMIT_BOUND_DIVISOR, MINIMUM_GAS_LIMIT,
     },
-    eip4844::{calc_blob_gasprice, calculate_excess_blob_gas},
+    eip4844::BlobParams,
     keccak256, Address, BaseFeeParams, BlockHash, BlockNumHash, BlockNumber, Bloom, Bytes,
     ChainSpec, GotExpected, GotExpectedBoxed, Hardfork, B256, B64, U256,
 };
@@ -233,20 +233,22 @@ impl Header {
         self.transactions_root == EMPTY_ROOT_HASH
     }
 
-    /// Returns the blob fee for _this_ block according to the EIP-4844 spec.
+    /// Returns the blob fee for _this_ block according to the EIP-4844 spec, given the
+    /// [`BlobParams`] active at the timestamp of this block.
     ///
     /// Returns `None` if `excess_blob_gas` is None
-    pub fn blob_fee(&self) -> Option<u128> {
-        self.excess_blob_gas.map(calc_blob_gasprice)
+    pub fn blob_fee(&self, blob_params: BlobParams) -> Option<u128> {
+        Some(blob_params.calc_blob_fee(self.excess_blob_gas?))
     }
 
-    /// Returns the blob fee for the next block according to the EIP-4844 spec.
+    /// Returns the blob fee for the next block according to the EIP-4844 spec, given the
+    /// [`BlobParams`] active at the timestamp of the next block.
     ///
     /// Returns `None` if `excess_blob_gas` is None.
     ///
     /// See also [`Self::next_block_excess_blob_gas`]
-    pub fn next_block_blob_fee(&self) -> Option<u128> {
-        self.next_block_excess_blob_gas().map(calc_blob_gasprice)
+    pub fn next_block_blob_fee(&self, blob_params: BlobParams) -> Option<u128> {
+        Some(blob_params.calc_blob_fee(self.next_block_excess_blob_gas(blob_params)?))
     }
 
     /// Calculate base fee for next block according to the EIP-1559 spec.
@@ -261,11 +263,19 @@ impl Header {
         ) as u64)
     }
 
-    /// Calculate excess blob gas for the next block according to the EIP-4844 spec.
+    /// Calculate the gas limit for the next block that moves towards the desired gas limit.
+    ///
+    /// See [`calculate_next_block_gas_limit`].
+    pub fn next_block_gas_limit(&self, desired_gas_limit: u64) -> u64 {
+        calculate_next_block_gas_limit(self.gas_limit, desired_gas_limit)
+    }
+
+    /// Calculate excess blob gas for the next block according to the EIP-4844 spec, given the
+    /// [`BlobParams`] active at the timestamp of the next block.
     ///
     /// Returns a `None` if no excess blob gas is set, no EIP-4844 support
-    pub fn next_block_excess_blob_gas(&self) -> Option<u64> {
-        Some(calculate_excess_blob_gas(self.excess_blob_gas?, self.blob_gas_used?))
+    pub fn next_block_excess_blob_gas(&self, blob_params: BlobParams) -> Option<u64> {
+        Some(blob_params.next_block_excess_blob_gas(self.excess_blob_gas?, self.blob_gas_used?))
     }
 
     /// Seal the header with a known hash.
@@ -511,6 +521,20 @@ impl Decodable for Header {
     }
 }
 
+/// Calculate the gas limit of a block that moves from the gas limit of its parent towards the
+/// desired gas limit, as far as the bound divisor allows.
+///
+/// The gas limit of a block can differ from the gas limit of its parent by less than
+/// `parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR` and the desired gas limit is raised to at least
+/// [`MINIMUM_GAS_LIMIT`].
+pub fn calculate_next_block_gas_limit(parent_gas_limit: u64, desired_gas_limit: u64) -> u64 {
+    let max_change = (parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR).saturating_sub(1);
+    desired_gas_limit.max(MINIMUM_GAS_LIMIT).clamp(
+        parent_gas_limit.saturating_sub(max_change),
+        parent_gas_limit.saturating_add(max_change),
+    )
+}
+
 /// Errors that can occur during header sanity checks.
 #[derive(thiserror::Error, Debug, PartialEq, Eq, Clone)]
 pub enum HeaderValidationError {
@@ -689,7 +713,7 @@ impl SealedHeader {
 
         // Check for an increase in gas limit beyond the allowed threshold.
         if self.gas_limit > parent_gas_limit {
-            if self.gas_limit - parent_gas_limit >= parent_gas_limit / 1024 {
+            if self.gas_limit - parent_gas_limit >= parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR {
                 return Err(HeaderValidationError::GasLimitInvalidIncrease {
                     parent_gas_limit,
                     child_gas_limit: self.gas_limit,
@@ -697,7 +721,7 @@ impl SealedHeader {
             }
         }
         // Check for a decrease in gas limit beyond the allowed threshold.
-        else if parent_gas_limit - self.gas_limit >= parent_gas_limit / 1024 {
+        else if parent_gas_limit - self.gas_limit >= parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR {
             return Err(HeaderValidationError::GasLimitInvalidDecrease {
                 parent_gas_limit,
                 child_gas_limit: self.gas_limit,
@@ -814,7 +838,10 @@ impl SealedHeader {
 
         // ensure that the blob gas fields for this block
         if chain_spec.is_cancun_active_at_timestamp(self.timestamp) {
-            self.validate_4844_header_against_parent(parent)?;
+            self.validate_4844_header_against_parent(
+                parent,
+                chain_spec.blob_params_at_timestamp(self.timestamp),
+            )?;
         }
 
         Ok(())
@@ -824,16 +851,19 @@ impl SealedHeader {
     /// ensures that the `blob_gas_used` and `excess_blob_gas` fields exist in the child header, and
     /// that the `excess_blob_gas` field matches the expected `excess_blob_gas` calculated from the
     /// parent header fields.
+    ///
+    /// The `blob_params` are the [`BlobParams`] active at the timestamp of this block.
     pub fn validate_4844_header_against_parent(
         &self,
         parent: &Self,
+        blob_params: BlobParams,
     ) -> Result<(), HeaderValidationError> {
         // From [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844#header-extension):
         //
         // > For the first post-fork block, both parent.blob_gas_used and parent.excess_blob_gas
         // > are evaluated as 0.
         //
-        // This means in the first post-fork block, calculate_excess_blob_gas will return 0.
+        // This means in the first post-fork block, next_block_excess_blob_gas will return 0.
         let parent_blob_gas_used = parent.blob_gas_used.unwrap_or(0);
         let parent_excess_blob_gas = parent.excess_blob_gas.unwrap_or(0);
 
@@ -844,7 +874,7 @@ impl SealedHeader {
             self.excess_blob_gas.ok_or(HeaderValidationError::ExcessBlobGasMissing)?;
 
         let expected_excess_blob_gas =
-            calculate_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used);
+            blob_params.next_block_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used);
         if expected_excess_blob_gas != excess_blob_gas {
             return Err(HeaderValidationError::ExcessBlobGasDiff {
                 diff: GotExpected { got: excess_blob_gas, expected: expected_excess_blob_gas },
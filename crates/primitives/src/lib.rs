@@ -65,7 +65,9 @@ pub use constants::{
 };
 pub use error::{GotExpected, GotExpectedBoxed};
 pub use genesis::{ChainConfig, Genesis, GenesisAccount};
-pub use header::{Header, HeaderValidationError, HeadersDirection, SealedHeader};
+pub use header::{
+    calculate_next_block_gas_limit, Header, HeaderValidationError, HeadersDirection, SealedHeader,
+};
 pub use integer_list::IntegerList;
 pub use log::{logs_bloom, Log};
 pub use net::{
@@ -81,7 +83,7 @@ pub use static_file::StaticFileSegment;
 pub use storage::StorageEntry;
 
 pub use transaction::{
-    BlobTransaction, BlobTransactionSidecar, FromRecoveredPooledTransaction,
+    BlobAndProofV1, BlobTransaction, BlobTransactionSidecar, FromRecoveredPooledTransaction,
     PooledTransactionsElement, PooledTransactionsElementEcRecovered,
 };
 
@@ -90,11 +92,11 @@ pub use transaction::BlobTransactionValidationError;
 
 pub use transaction::{
     util::secp256k1::{public_key_to_address, recover_signer_unchecked, sign_message},
-    AccessList, AccessListItem, IntoRecoveredTransaction, InvalidTransactionError, Signature,
-    Transaction, TransactionMeta, TransactionSigned, TransactionSignedEcRecovered,
-    TransactionSignedNoHash, TryFromRecoveredTransaction, TxEip1559, TxEip2930, TxEip4844,
-    TxHashOrNumber, TxLegacy, TxType, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID,
-    LEGACY_TX_TYPE_ID,
+    AccessList, AccessListItem, IntoRecoveredTransaction, InvalidTransactionError, KnownAccount,
+    Signature, Transaction, TransactionConditional, TransactionMeta, TransactionSigned,
+    TransactionSignedEcRecovered, TransactionSignedNoHash, TryFromRecoveredTransaction, TxEip1559,
+    TxEip2930, TxEip4844, TxHashOrNumber, TxLegacy, TxType, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID,
+    EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID,
 };
 
 pub use withdrawal::{Withdrawal, Withdrawals};
@@ -64,6 +64,12 @@ impl Receipt {
     }
 }
 
+impl AsRef<Self> for Receipt {
+    fn as_ref(&self) -> &Self {
+        self
+    }
+}
+
 /// A collection of receipts organized as a two-dimensional vector.
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct Receipts {
@@ -17,13 +17,14 @@ pub fn fill_block_env(
     after_merge: bool,
 ) {
     let coinbase = block_coinbase(chain_spec, header, after_merge);
-    fill_block_env_with_coinbase(block_env, header, after_merge, coinbase);
+    fill_block_env_with_coinbase(block_env, chain_spec, header, after_merge, coinbase);
 }
 
 /// Fill block environment with coinbase.
 #[inline]
 pub fn fill_block_env_with_coinbase(
     block_env: &mut BlockEnv,
+    chain_spec: &ChainSpec,
     header: &Header,
     after_merge: bool,
     coinbase: Address,
@@ -43,7 +44,11 @@ pub fn fill_block_env_with_coinbase(
 
     // EIP-4844 excess blob gas of this block, introduced in Cancun
     if let Some(excess_blob_gas) = header.excess_blob_gas {
-        block_env.set_blob_excess_gas_and_price(excess_blob_gas);
+        block_env.blob_excess_gas_and_price = Some(
+            chain_spec
+                .blob_params_at_timestamp(header.timestamp)
+                .blob_excess_gas_and_price(excess_blob_gas),
+        );
     }
 }
 
@@ -371,7 +376,7 @@ pub fn fill_op_tx_env<T: AsRef<Transaction>>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::GOERLI;
+    use crate::{eip4844::BlobParams, ChainSpecBuilder, ForkCondition, Hardfork, GOERLI};
 
     #[test]
     fn test_recover_genesis_goerli_signer() {
@@ -381,4 +386,29 @@ mod tests {
         let block_coinbase = block_coinbase(&chain_spec, &header, false);
         assert_eq!(block_coinbase, header.beneficiary);
     }
+
+    #[test]
+    fn test_fill_block_env_blob_fee_of_fork() {
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .cancun_activated()
+            .with_fork(Hardfork::Prague, ForkCondition::Timestamp(100))
+            .build();
+        let excess_blob_gas = 10 * 1024 * 1024;
+
+        for (timestamp, blob_params) in [(0, BlobParams::cancun()), (100, BlobParams::prague())] {
+            let header =
+                Header { timestamp, excess_blob_gas: Some(excess_blob_gas), ..Default::default() };
+            let mut block_env = BlockEnv::default();
+            fill_block_env(&mut block_env, &chain_spec, &header, true);
+            assert_eq!(block_env.get_blob_excess_gas(), Some(excess_blob_gas));
+            assert_eq!(
+                block_env.get_blob_gasprice(),
+                Some(blob_params.calc_blob_fee(excess_blob_gas))
+            );
+        }
+        assert_ne!(
+            BlobParams::cancun().calc_blob_fee(excess_blob_gas),
+            BlobParams::prague().calc_blob_fee(excess_blob_gas)
+        );
+    }
 }
@@ -213,7 +213,9 @@ impl StageCheckpoint {
             StageId::StorageHashing => {
                 StageUnitCheckpoint::Storage(StorageHashingCheckpoint::default())
             }
-            StageId::IndexStorageHistory | StageId::IndexAccountHistory => {
+            StageId::IndexStorageHistory |
+            StageId::IndexAccountHistory |
+            StageId::IndexAddressTransactions => {
                 StageUnitCheckpoint::IndexHistory(IndexHistoryCheckpoint::default())
             }
             _ => return self,
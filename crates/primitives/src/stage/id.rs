@@ -30,6 +30,10 @@ pub enum StageId {
     IndexStorageHistory,
     /// Index account history stage in the process.
     IndexAccountHistory,
+    /// Index address transactions stage in the process.
+    ///
+    /// The stage is optional, so it's not part of [`StageId::ALL`].
+    IndexAddressTransactions,
     /// Finish stage in the process.
     Finish,
     /// Other custom stage with a provided string identifier.
@@ -80,6 +84,7 @@ impl StageId {
             Self::TransactionLookup => "TransactionLookup",
             Self::IndexAccountHistory => "IndexAccountHistory",
             Self::IndexStorageHistory => "IndexStorageHistory",
+            Self::IndexAddressTransactions => "IndexAddressTransactions",
             Self::Finish => "Finish",
             Self::Other(s) => s,
         }
@@ -123,6 +128,7 @@ mod tests {
         assert_eq!(StageId::MerkleExecute.to_string(), "MerkleExecute");
         assert_eq!(StageId::IndexAccountHistory.to_string(), "IndexAccountHistory");
         assert_eq!(StageId::IndexStorageHistory.to_string(), "IndexStorageHistory");
+        assert_eq!(StageId::IndexAddressTransactions.to_string(), "IndexAddressTransactions");
         assert_eq!(StageId::TransactionLookup.to_string(), "TransactionLookup");
         assert_eq!(StageId::Finish.to_string(), "Finish");
 
@@ -0,0 +1,169 @@
+//! Defines the conditions of a transaction submitted with `eth_sendRawTransactionConditional`.
+
+use crate::{Address, B256, U64};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The expected storage of an account in a [`TransactionConditional`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KnownAccount {
+    /// The expected storage root of the account.
+    StorageRoot(B256),
+    /// The expected values of storage slots of the account.
+    Slots(BTreeMap<B256, B256>),
+}
+
+impl KnownAccount {
+    /// Returns the cost of checking the account, which is one for a storage root and the number of
+    /// slots otherwise.
+    pub fn cost(&self) -> usize {
+        match self {
+            Self::StorageRoot(_) => 1,
+            Self::Slots(slots) => slots.len(),
+        }
+    }
+}
+
+/// Conditions that must hold for a transaction to be included in a block, as submitted with
+/// `eth_sendRawTransactionConditional`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionConditional {
+    /// The expected storage of accounts at the time the transaction is included.
+    #[serde(default)]
+    pub known_accounts: BTreeMap<Address, KnownAccount>,
+    /// The minimum number of the including block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number_min: Option<U64>,
+    /// The maximum number of the including block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number_max: Option<U64>,
+    /// The minimum timestamp of the including block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_min: Option<U64>,
+    /// The maximum timestamp of the including block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_max: Option<U64>,
+}
+
+impl TransactionConditional {
+    /// Returns the cost of checking the known accounts.
+    pub fn cost(&self) -> usize {
+        self.known_accounts.values().map(KnownAccount::cost).sum()
+    }
+
+    /// Returns `true` if no minimum exceeds its maximum.
+    pub fn has_valid_bounds(&self) -> bool {
+        let valid = |min: Option<U64>, max: Option<U64>| match (min, max) {
+            (Some(min), Some(max)) => min <= max,
+            _ => true,
+        };
+        valid(self.block_number_min, self.block_number_max) &&
+            valid(self.timestamp_min, self.timestamp_max)
+    }
+
+    /// Returns `true` if a block with the given number and timestamp is within the bounds.
+    pub fn matches_block(&self, number: u64, timestamp: u64) -> bool {
+        let (number, timestamp) = (U64::from(number), U64::from(timestamp));
+        self.block_number_min.map_or(true, |min| min <= number) &&
+            self.block_number_max.map_or(true, |max| number <= max) &&
+            self.timestamp_min.map_or(true, |min| min <= timestamp) &&
+            self.timestamp_max.map_or(true, |max| timestamp <= max)
+    }
+
+    /// Returns `true` if no block that succeeds the block with the given number and timestamp is
+    /// within the bounds.
+    pub fn has_expired(&self, number: u64, timestamp: u64) -> bool {
+        self.block_number_max.is_some_and(|max| max <= U64::from(number)) ||
+            self.timestamp_max.is_some_and(|max| max <= U64::from(timestamp))
+    }
+
+    /// Returns `true` if the storage of all known accounts matches.
+    ///
+    /// The storage root and the storage slot values of an account are looked up with the given
+    /// functions.
+    pub fn matches_known_accounts<E>(
+        &self,
+        mut storage_root: impl FnMut(Address) -> Result<B256, E>,
+        mut storage: impl FnMut(Address, B256) -> Result<B256, E>,
+    ) -> Result<bool, E> {
+        for (address, account) in &self.known_accounts {
+            match account {
+                KnownAccount::StorageRoot(root) => {
+                    if storage_root(*address)? != *root {
+                        return Ok(false)
+                    }
+                }
+                KnownAccount::Slots(slots) => {
+                    for (slot, value) in slots {
+                        if storage(*address, *slot)? != *value {
+                            return Ok(false)
+                        }
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::b256;
+
+    #[test]
+    fn deserialize_conditional() {
+        let s = r#"{
+            "knownAccounts": {
+                "0x0000000000000000000000000000000000000001": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+                "0x0000000000000000000000000000000000000002": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000002",
+                    "0x0000000000000000000000000000000000000000000000000000000000000003": "0x0000000000000000000000000000000000000000000000000000000000000004"
+                }
+            },
+            "blockNumberMax": "0x10",
+            "timestampMin": "0x64"
+        }"#;
+        let conditional: TransactionConditional = serde_json::from_str(s).unwrap();
+        assert_eq!(
+            conditional.known_accounts[&Address::with_last_byte(1)],
+            KnownAccount::StorageRoot(b256!(
+                "56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+            ))
+        );
+        assert_eq!(conditional.cost(), 3);
+        assert_eq!(conditional.block_number_max, Some(U64::from(16)));
+        assert_eq!(conditional.timestamp_min, Some(U64::from(100)));
+        assert_eq!(conditional.block_number_min, None);
+
+        let json = serde_json::to_string(&conditional).unwrap();
+        assert_eq!(serde_json::from_str::<TransactionConditional>(&json).unwrap(), conditional);
+    }
+
+    #[test]
+    fn conditional_block_bounds() {
+        let conditional = TransactionConditional {
+            block_number_min: Some(U64::from(10)),
+            block_number_max: Some(U64::from(20)),
+            timestamp_max: Some(U64::from(1_000)),
+            ..Default::default()
+        };
+        assert!(conditional.has_valid_bounds());
+        assert!(!conditional.matches_block(9, 0));
+        assert!(conditional.matches_block(20, 1_000));
+        assert!(!conditional.matches_block(15, 1_001));
+
+        assert!(!conditional.has_expired(19, 999));
+        assert!(conditional.has_expired(20, 999));
+        assert!(conditional.has_expired(19, 1_000));
+
+        let conditional = TransactionConditional {
+            timestamp_min: Some(U64::from(2)),
+            timestamp_max: Some(U64::from(1)),
+            ..Default::default()
+        };
+        assert!(!conditional.has_valid_bounds());
+    }
+}
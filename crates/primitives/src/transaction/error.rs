@@ -12,8 +12,13 @@ pub enum InvalidTransactionError {
     /// The nonce is lower than the account's nonce, or there is a nonce gap present.
     ///
     /// This is a consensus error.
-    #[error("transaction nonce is not consistent")]
-    NonceNotConsistent,
+    #[error("transaction nonce is not consistent: next nonce {state}, tx nonce {tx}")]
+    NonceNotConsistent {
+        /// The nonce of the transaction.
+        tx: u64,
+        /// The current nonce of the sender.
+        state: u64,
+    },
     /// The transaction is before Spurious Dragon and has a chain ID.
     #[error("transactions before Spurious Dragon should not have a chain ID")]
     OldLegacyChainId,
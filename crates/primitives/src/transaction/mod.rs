@@ -16,6 +16,7 @@ use serde::{Deserialize, Serialize};
 use std::mem;
 
 pub use access_list::{AccessList, AccessListItem};
+pub use conditional::{KnownAccount, TransactionConditional};
 pub use eip1559::TxEip1559;
 pub use eip2930::TxEip2930;
 pub use eip4844::TxEip4844;
@@ -30,7 +31,7 @@ pub use pooled::{PooledTransactionsElement, PooledTransactionsElementEcRecovered
 pub use sidecar::generate_blob_sidecar;
 #[cfg(feature = "c-kzg")]
 pub use sidecar::BlobTransactionValidationError;
-pub use sidecar::{BlobTransaction, BlobTransactionSidecar};
+pub use sidecar::{BlobAndProofV1, BlobTransaction, BlobTransactionSidecar};
 
 pub use signature::{extract_chain_id, Signature};
 pub use tx_type::{
@@ -39,6 +40,7 @@ pub use tx_type::{
 pub use variant::TransactionSignedVariant;
 
 mod access_list;
+mod conditional;
 mod eip1559;
 mod eip2930;
 mod eip4844;
@@ -1,7 +1,8 @@
 #![cfg_attr(docsrs, doc(cfg(feature = "c-kzg")))]
 
 use crate::{
-    keccak256, Signature, Transaction, TransactionSigned, TxEip4844, TxHash, EIP4844_TX_TYPE_ID,
+    keccak256, Signature, Transaction, TransactionSigned, TxEip4844, TxHash, B256,
+    EIP4844_TX_TYPE_ID,
 };
 use alloy_rlp::{Decodable, Encodable, Error as RlpError, Header};
 use serde::{Deserialize, Serialize};
@@ -12,6 +13,29 @@ pub use alloy_eips::eip4844::BlobTransactionSidecar;
 #[cfg(feature = "c-kzg")]
 pub use alloy_eips::eip4844::BlobTransactionValidationError;
 
+/// A blob of a blob transaction and the KZG proof for its commitment.
+///
+/// This is the response object of `engine_getBlobsV1`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobAndProofV1 {
+    /// The blob data.
+    pub blob: Box<alloy_eips::eip4844::Blob>,
+    /// The KZG proof for the blob's commitment.
+    pub proof: alloy_eips::eip4844::Bytes48,
+}
+
+impl BlobAndProofV1 {
+    /// Returns the blob and proof of the sidecar with the given versioned hash, if the sidecar
+    /// contains it.
+    pub fn from_sidecar(sidecar: &BlobTransactionSidecar, versioned_hash: &B256) -> Option<Self> {
+        let index = sidecar.versioned_hashes().position(|hash| hash == *versioned_hash)?;
+        Some(Self {
+            blob: Box::new(*sidecar.blobs.get(index)?),
+            proof: *sidecar.proofs.get(index)?,
+        })
+    }
+}
+
 /// A response to `GetPooledTransactions` that includes blob data, their commitments, and their
 /// corresponding proofs.
 ///
@@ -16,7 +16,7 @@ pub mod nodes;
 pub use nodes::StoredBranchNode;
 
 mod proofs;
-pub use proofs::{AccountProof, StorageProof};
+pub use proofs::{AccountProof, MultiProof, StorageMultiProof, StorageProof};
 
 mod storage;
 pub use storage::StorageTrieEntry;
@@ -24,4 +24,7 @@ pub use storage::StorageTrieEntry;
 mod subnode;
 pub use subnode::StoredSubNode;
 
+mod witness;
+pub use witness::ExecutionWitness;
+
 pub use alloy_trie::{proof, BranchNodeCompact, HashBuilder, TrieMask, EMPTY_ROOT_HASH};
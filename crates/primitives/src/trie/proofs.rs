@@ -7,6 +7,58 @@ use super::{
 use crate::{keccak256, Account, Address, Bytes, B256, U256};
 use alloy_rlp::encode_fixed_size;
 use alloy_trie::EMPTY_ROOT_HASH;
+use std::collections::{BTreeMap, HashMap};
+
+/// The state multiproof of target accounts and multiproofs of their storage tries.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct MultiProof {
+    /// State trie multiproof for requested accounts, rlp-serialized trie nodes keyed by their
+    /// paths.
+    pub account_subtree: BTreeMap<Nibbles, Bytes>,
+    /// Storage trie multiproofs keyed by hashed address.
+    pub storages: HashMap<B256, StorageMultiProof>,
+}
+
+impl MultiProof {
+    /// Returns the proof nodes of the state trie on the path to the given hashed address, starting
+    /// from the root node.
+    pub fn account_proof_nodes(&self, hashed_address: B256) -> Vec<Bytes> {
+        let nibbles = Nibbles::unpack(hashed_address);
+        self.account_subtree
+            .iter()
+            .filter(|(path, _)| nibbles.starts_with(path))
+            .map(|(_, node)| node.clone())
+            .collect()
+    }
+}
+
+/// The merkle multiproof of the storage trie.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct StorageMultiProof {
+    /// Storage trie root.
+    pub root: B256,
+    /// Storage multiproof for requested slots, rlp-serialized trie nodes keyed by their paths.
+    pub subtree: BTreeMap<Nibbles, Bytes>,
+}
+
+impl Default for StorageMultiProof {
+    fn default() -> Self {
+        Self { root: EMPTY_ROOT_HASH, subtree: BTreeMap::default() }
+    }
+}
+
+impl StorageMultiProof {
+    /// Returns the proof nodes of the storage trie on the path to the given hashed slot, starting
+    /// from the root node.
+    pub fn storage_proof_nodes(&self, hashed_slot: B256) -> Vec<Bytes> {
+        let nibbles = Nibbles::unpack(hashed_slot);
+        self.subtree
+            .iter()
+            .filter(|(path, _)| nibbles.starts_with(path))
+            .map(|(_, node)| node.clone())
+            .collect()
+    }
+}
 
 /// The merkle proof with the relevant account info.
 #[derive(PartialEq, Eq, Debug)]
@@ -0,0 +1,25 @@
+//! Execution witness of a block.
+
+use crate::{BlockNumber, Bytes, B256};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// The witness of the state accessed during the execution of a block.
+///
+/// The witness contains everything that is needed to re-execute the block statelessly on top of
+/// its pre-state root: the trie nodes on the paths to all accessed accounts and storage slots
+/// (including the nodes required to apply deletions), the bytecodes of the executed contracts,
+/// the preimages of the hashed trie keys and the ancestor block hashes accessed with `BLOCKHASH`.
+#[derive(Clone, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionWitness {
+    /// Rlp-serialized state and storage trie nodes keyed by their hashes.
+    pub state: HashMap<B256, Bytes>,
+    /// Bytecodes of the executed contracts keyed by their code hashes.
+    pub codes: HashMap<B256, Bytes>,
+    /// Preimages of the hashed trie keys, i.e. account addresses and storage slots keyed by their
+    /// hashes.
+    pub keys: HashMap<B256, Bytes>,
+    /// Ancestor block hashes accessed with `BLOCKHASH` keyed by block number.
+    pub block_hashes: BTreeMap<BlockNumber, B256>,
+}
@@ -0,0 +1,130 @@
+use reth_prune_types::{PruneInterruptReason, PruneLimiter};
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Deleted entries and time budget of one pruner run, shared between all segment groups that are
+/// pruned in turn.
+///
+/// Every segment reserves a share of the remaining deleted entries before it starts pruning and
+/// releases the unused part of the reservation once it's done, so the total number of deleted
+/// entries never exceeds the configured limit, regardless of how many groups are still pruning.
+#[derive(Debug)]
+pub(crate) struct PruneBudget {
+    /// Number of entries (rows in the database) that are neither deleted nor reserved yet.
+    deleted_entries_left: AtomicUsize,
+    /// Number of segment groups that are still pruning and compete for the budget.
+    active_groups: AtomicUsize,
+    /// Time after which no new segments are allowed to start pruning.
+    deadline: Option<Instant>,
+}
+
+impl PruneBudget {
+    /// Creates a new budget with the given limit on deleted entries and optional timeout. The
+    /// timeout is counted from the moment of creation.
+    pub(crate) fn new(deleted_entries_limit: usize, timeout: Option<Duration>) -> Self {
+        Self {
+            deleted_entries_left: AtomicUsize::new(deleted_entries_limit),
+            active_groups: AtomicUsize::new(1),
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+        }
+    }
+
+    /// Sets the number of segment groups that will share the budget.
+    pub(crate) fn set_active_groups(&self, groups: usize) {
+        self.active_groups.store(groups.max(1), Ordering::SeqCst);
+    }
+
+    /// Marks one of the segment groups as finished, so the remaining groups get bigger shares of
+    /// the budget.
+    pub(crate) fn finish_group(&self) {
+        let _ = self.active_groups.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |groups| {
+            Some(groups.saturating_sub(1).max(1))
+        });
+    }
+
+    /// Reserves a fair share of the remaining budget for one segment and returns a
+    /// [`PruneLimiter`] that enforces it.
+    ///
+    /// Returns the [`PruneInterruptReason`] if the budget is already exhausted.
+    pub(crate) fn reserve(&self) -> Result<PruneLimiter, PruneInterruptReason> {
+        let mut limiter = PruneLimiter::default();
+
+        if let Some(deadline) = self.deadline {
+            let time_left = deadline.saturating_duration_since(Instant::now());
+            if time_left.is_zero() {
+                return Err(PruneInterruptReason::Timeout)
+            }
+            limiter = limiter.set_time_limit(time_left);
+        }
+
+        let active_groups = self.active_groups.load(Ordering::SeqCst).max(1);
+        let left = self
+            .deleted_entries_left
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                Some(left - left.div_ceil(active_groups))
+            })
+            .unwrap_or_default();
+        if left == 0 {
+            return Err(PruneInterruptReason::DeletedEntriesLimitReached)
+        }
+
+        Ok(limiter.set_deleted_entries_limit(left.div_ceil(active_groups)))
+    }
+
+    /// Returns the part of the reservation made with [`Self::reserve`] that wasn't used back to
+    /// the budget.
+    pub(crate) fn release(&self, limiter: &PruneLimiter, deleted_entries: usize) {
+        let unused =
+            limiter.deleted_entries_limit().unwrap_or_default().saturating_sub(deleted_entries);
+        self.deleted_entries_left.fetch_add(unused, Ordering::SeqCst);
+    }
+
+    /// Returns the number of entries (rows in the database) that can still be deleted.
+    pub(crate) fn deleted_entries_left(&self) -> usize {
+        self.deleted_entries_left.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PruneBudget;
+    use assert_matches::assert_matches;
+    use reth_prune_types::PruneInterruptReason;
+    use std::time::Duration;
+
+    #[test]
+    fn reserve_and_release() {
+        let budget = PruneBudget::new(10, None);
+        budget.set_active_groups(3);
+
+        // Each group gets a fair share of what's left
+        let first = budget.reserve().unwrap();
+        assert_eq!(first.deleted_entries_limit(), Some(4));
+        let second = budget.reserve().unwrap();
+        assert_eq!(second.deleted_entries_limit(), Some(2));
+        assert_eq!(budget.deleted_entries_left(), 4);
+
+        // Unused part of the reservation is returned back
+        budget.release(&first, 1);
+        assert_eq!(budget.deleted_entries_left(), 7);
+        budget.release(&second, 2);
+        assert_eq!(budget.deleted_entries_left(), 7);
+
+        // The last group standing can take everything that's left
+        budget.finish_group();
+        budget.finish_group();
+        let third = budget.reserve().unwrap();
+        assert_eq!(third.deleted_entries_limit(), Some(7));
+        budget.release(&third, 7);
+
+        assert_matches!(budget.reserve(), Err(PruneInterruptReason::DeletedEntriesLimitReached));
+    }
+
+    #[test]
+    fn reserve_after_deadline() {
+        let budget = PruneBudget::new(10, Some(Duration::ZERO));
+        assert_matches!(budget.reserve(), Err(PruneInterruptReason::Timeout));
+    }
+}
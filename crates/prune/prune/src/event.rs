@@ -11,6 +11,17 @@ pub enum PrunerEvent {
     Finished {
         tip_block_number: BlockNumber,
         elapsed: Duration,
-        stats: BTreeMap<PruneSegment, (PruneProgress, usize)>,
+        stats: BTreeMap<PruneSegment, PrunedSegmentInfo>,
     },
 }
+
+/// Outcome of pruning a single segment during one [Pruner][crate::Pruner] run.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PrunedSegmentInfo {
+    /// Progress of the segment pruning.
+    pub progress: PruneProgress,
+    /// Number of entries (rows in the database) that were pruned.
+    pub pruned: usize,
+    /// Time spent pruning the segment.
+    pub duration: Duration,
+}
@@ -9,6 +9,7 @@
 #![allow(missing_docs)]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+mod budget;
 mod builder;
 mod error;
 mod event;
@@ -19,7 +20,7 @@ pub mod segments;
 use crate::metrics::Metrics;
 pub use builder::PrunerBuilder;
 pub use error::PrunerError;
-pub use event::PrunerEvent;
+pub use event::{PrunedSegmentInfo, PrunerEvent};
 pub use pruner::{Pruner, PrunerResult, PrunerWithResult};
 
 // Re-export prune types
@@ -1,9 +1,10 @@
 //! Support for pruning.
 
 use crate::{
+    budget::PruneBudget,
     segments,
     segments::{PruneInput, Segment},
-    Metrics, PrunerError, PrunerEvent,
+    Metrics, PrunedSegmentInfo, PrunerError, PrunerEvent,
 };
 use reth_db_api::database::Database;
 use reth_exex_types::FinishedExExHeight;
@@ -11,10 +12,13 @@ use reth_primitives::{BlockNumber, StaticFileSegment};
 use reth_provider::{
     DatabaseProviderRW, ProviderFactory, PruneCheckpointReader, StaticFileProviderFactory,
 };
-use reth_prune_types::{PruneLimiter, PruneMode, PruneProgress, PrunePurpose, PruneSegment};
+use reth_prune_types::{
+    PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PrunePurpose,
+    PruneSegment,
+};
 use reth_tokio_util::{EventSender, EventStream};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, VecDeque},
     time::{Duration, Instant},
 };
 use tokio::sync::watch;
@@ -26,7 +30,7 @@ pub type PrunerResult = Result<PruneProgress, PrunerError>;
 /// The pruner type itself with the result of [`Pruner::run`]
 pub type PrunerWithResult<DB> = (Pruner<DB>, PrunerResult);
 
-type PrunerStats = BTreeMap<PruneSegment, (PruneProgress, usize)>;
+type PrunerStats = BTreeMap<PruneSegment, PrunedSegmentInfo>;
 
 /// Pruning routine. Main pruning logic happens in [`Pruner::run`].
 #[derive(Debug)]
@@ -120,16 +124,10 @@ impl<DB: Database> Pruner<DB> {
             })
             .min(self.prune_max_blocks_per_run);
 
-        let mut limiter = PruneLimiter::default()
-            .set_deleted_entries_limit(self.delete_limit_per_block * blocks_since_last_run);
-        if let Some(timeout) = self.timeout {
-            limiter = limiter.set_time_limit(timeout);
-        };
+        let budget =
+            PruneBudget::new(self.delete_limit_per_block * blocks_since_last_run, self.timeout);
 
-        let provider = self.provider_factory.provider_rw()?;
-        let (stats, deleted_entries, progress) =
-            self.prune_segments(&provider, tip_block_number, &mut limiter)?;
-        provider.commit()?;
+        let (stats, deleted_entries, progress) = self.prune_segments(tip_block_number, &budget)?;
 
         self.previous_tip_block_number = Some(tip_block_number);
 
@@ -146,7 +144,7 @@ impl<DB: Database> Pruner<DB> {
             %tip_block_number,
             ?elapsed,
             ?deleted_entries,
-            ?limiter,
+            ?budget,
             ?progress,
             ?stats,
             "{message}",
@@ -161,12 +159,18 @@ impl<DB: Database> Pruner<DB> {
     /// be pruned according to the highest `static_files`. Segments are parts of the database that
     /// represent one or more tables.
     ///
+    /// Segments are split into [`SegmentGroup`]s by the tables they touch. The database allows
+    /// only one write transaction at a time, so all segments are pruned sequentially in a single
+    /// transaction, taking one segment from each group in turn. This way every group gets a fair
+    /// share of the [`PruneBudget`], and a group with a lot of data to prune can't starve the
+    /// others. A segment that used up its share is pruned again in the next turn of its group, so
+    /// the budget left by the groups that finished goes to the ones that still have data.
+    ///
     /// Returns [`PrunerStats`], total number of entries pruned, and [`PruneProgress`].
     fn prune_segments(
         &mut self,
-        provider: &DatabaseProviderRW<DB>,
         tip_block_number: BlockNumber,
-        limiter: &mut PruneLimiter,
+        budget: &PruneBudget,
     ) -> Result<(PrunerStats, usize, PruneProgress), PrunerError> {
         let static_file_segments = self.static_file_segments();
         let segments = static_file_segments
@@ -174,72 +178,111 @@ impl<DB: Database> Pruner<DB> {
             .map(|segment| (segment, PrunePurpose::StaticFile))
             .chain(self.segments.iter().map(|segment| (segment, PrunePurpose::User)));
 
-        let mut stats = PrunerStats::new();
-        let mut pruned = 0;
-        let mut progress = PruneProgress::Finished;
-
+        let mut groups = BTreeMap::<SegmentGroup, Vec<SegmentTarget<'_, DB>>>::new();
         for (segment, purpose) in segments {
-            if limiter.is_limit_reached() {
-                break
-            }
-
             if let Some((to_block, prune_mode)) = segment
                 .mode()
                 .map(|mode| mode.prune_target_block(tip_block_number, segment.segment(), purpose))
                 .transpose()?
                 .flatten()
             {
-                debug!(
-                    target: "pruner",
-                    segment = ?segment.segment(),
-                    ?purpose,
-                    %to_block,
-                    ?prune_mode,
-                    "Segment pruning started"
-                );
+                groups.entry(segment.segment().into()).or_default().push(SegmentTarget {
+                    segment: segment.as_ref(),
+                    purpose,
+                    to_block,
+                    prune_mode,
+                });
+            } else {
+                debug!(target: "pruner", segment = ?segment.segment(), ?purpose, "Nothing to prune for the segment");
+            }
+        }
 
-                let segment_start = Instant::now();
-                let previous_checkpoint = provider.get_prune_checkpoint(segment.segment())?;
-                let output = segment.prune(
-                    provider,
-                    PruneInput { previous_checkpoint, to_block, limiter: limiter.clone() },
-                )?;
-                if let Some(checkpoint) = output.checkpoint {
-                    segment
-                        .save_checkpoint(provider, checkpoint.as_prune_checkpoint(prune_mode))?;
-                }
-                self.metrics
-                    .get_prune_segment_metrics(segment.segment())
-                    .duration_seconds
-                    .record(segment_start.elapsed());
-                if let Some(highest_pruned_block) =
-                    output.checkpoint.and_then(|checkpoint| checkpoint.block_number)
-                {
-                    self.metrics
-                        .get_prune_segment_metrics(segment.segment())
-                        .highest_pruned_block
-                        .set(highest_pruned_block as f64);
+        budget.set_active_groups(groups.len());
+
+        let provider = self.provider_factory.provider_rw()?;
+        // every target is queued together with the output of its previous turns, if any
+        let mut groups = groups
+            .into_values()
+            .map(|targets| {
+                targets.into_iter().map(|target| (target, None)).collect::<VecDeque<_>>()
+            })
+            .collect::<VecDeque<_>>();
+        let mut outputs = Vec::new();
+
+        while let Some(mut targets) = groups.pop_front() {
+            let Some((target, previous_output)) = targets.pop_front() else { continue };
+
+            let limiter = match budget.reserve() {
+                Ok(limiter) => limiter,
+                Err(reason) => {
+                    debug!(target: "pruner", segment = ?target.segment.segment(), purpose = ?target.purpose, ?reason, "Prune budget exhausted");
+                    let output = previous_output.unwrap_or(SegmentOutput {
+                        info: PrunedSegmentInfo {
+                            progress: PruneProgress::HasMoreData(reason),
+                            pruned: 0,
+                            duration: Duration::ZERO,
+                        },
+                        checkpoint: None,
+                    });
+                    outputs.push((target.segment.segment(), output));
+                    // the segments of the other groups that were interrupted by their share
+                    outputs.extend(
+                        groups.drain(..).flatten().filter_map(|(target, output)| {
+                            Some((target.segment.segment(), output?))
+                        }),
+                    );
+                    break
                 }
+            };
 
-                progress = output.progress;
+            let output = prune_segment(&provider, &target, limiter.clone())?;
+            budget.release(&limiter, output.info.pruned);
 
-                debug!(
-                    target: "pruner",
-                    segment = ?segment.segment(),
-                    ?purpose,
-                    %to_block,
-                    ?prune_mode,
-                    %output.pruned,
-                    "Segment pruning finished"
+            // a segment that used up its share gets a new one in the next turn of its group
+            let share_used = output.info.pruned > 0 &&
+                matches!(
+                    output.info.progress,
+                    PruneProgress::HasMoreData(PruneInterruptReason::DeletedEntriesLimitReached)
                 );
+            let output = match previous_output {
+                Some(previous_output) => previous_output.merge(output),
+                None => output,
+            };
+            if share_used {
+                targets.push_front((target, Some(output)));
+            } else {
+                outputs.push((target.segment.segment(), output));
+            }
 
-                if output.pruned > 0 {
-                    limiter.increment_deleted_entries_count_by(output.pruned);
-                    pruned += output.pruned;
-                    stats.insert(segment.segment(), (output.progress, output.pruned));
-                }
+            if targets.is_empty() {
+                budget.finish_group();
             } else {
-                debug!(target: "pruner", segment = ?segment.segment(), ?purpose, "Nothing to prune for the segment");
+                groups.push_back(targets);
+            }
+        }
+
+        provider.commit()?;
+
+        let mut stats = PrunerStats::new();
+        let mut pruned = 0;
+        let mut progress = PruneProgress::Finished;
+
+        for (segment, output) in outputs {
+            let metrics = self.metrics.get_prune_segment_metrics(segment);
+            metrics.duration_seconds.record(output.info.duration);
+            if let Some(highest_pruned_block) =
+                output.checkpoint.and_then(|checkpoint| checkpoint.block_number)
+            {
+                metrics.highest_pruned_block.set(highest_pruned_block as f64);
+            }
+
+            if progress.is_finished() {
+                progress = output.info.progress;
+            }
+
+            if output.info.pruned > 0 {
+                pruned += output.info.pruned;
+                stats.insert(segment, output.info);
             }
         }
 
@@ -328,14 +371,165 @@ impl<DB: Database> Pruner<DB> {
     }
 }
 
+/// Class of prune segments that touch the same set of database tables.
+///
+/// Segments of different groups don't share any tables, so the order in which they are pruned
+/// doesn't matter. Segments of the same group are always pruned in the order they were
+/// configured, e.g. account and storage history segments both read the changesets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SegmentGroup {
+    /// `CanonicalHeaders`, `Headers` and `HeaderTerminalDifficulties` tables.
+    Headers,
+    /// `Transactions` table.
+    Transactions,
+    /// `Receipts` table.
+    Receipts,
+    /// `TransactionSenders` table.
+    SenderRecovery,
+    /// `TransactionHashNumbers` table.
+    TransactionLookup,
+    /// Changesets and history indices tables.
+    History,
+}
+
+impl From<PruneSegment> for SegmentGroup {
+    fn from(segment: PruneSegment) -> Self {
+        match segment {
+            PruneSegment::Headers => Self::Headers,
+            PruneSegment::Transactions => Self::Transactions,
+            PruneSegment::Receipts | PruneSegment::ContractLogs => Self::Receipts,
+            PruneSegment::SenderRecovery => Self::SenderRecovery,
+            PruneSegment::TransactionLookup => Self::TransactionLookup,
+            PruneSegment::AccountHistory | PruneSegment::StorageHistory => Self::History,
+        }
+    }
+}
+
+/// Segment that has data to prune up to the target block.
+struct SegmentTarget<'a, DB> {
+    segment: &'a dyn Segment<DB>,
+    purpose: PrunePurpose,
+    to_block: BlockNumber,
+    prune_mode: PruneMode,
+}
+
+/// Output of pruning a single segment with [`prune_segment`].
+#[derive(Debug)]
+struct SegmentOutput {
+    info: PrunedSegmentInfo,
+    checkpoint: Option<PruneCheckpoint>,
+}
+
+impl SegmentOutput {
+    /// Merges the output of pruning the same segment again into this one.
+    fn merge(self, next: Self) -> Self {
+        Self {
+            info: PrunedSegmentInfo {
+                progress: next.info.progress,
+                pruned: self.info.pruned + next.info.pruned,
+                duration: self.info.duration + next.info.duration,
+            },
+            checkpoint: next.checkpoint.or(self.checkpoint),
+        }
+    }
+}
+
+/// Prunes a single segment up to its target block within the provided limits and saves the new
+/// checkpoint of the segment.
+fn prune_segment<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+    target: &SegmentTarget<'_, DB>,
+    limiter: PruneLimiter,
+) -> Result<SegmentOutput, PrunerError> {
+    let &SegmentTarget { segment, purpose, to_block, prune_mode } = target;
+
+    debug!(
+        target: "pruner",
+        segment = ?segment.segment(),
+        ?purpose,
+        %to_block,
+        ?prune_mode,
+        "Segment pruning started"
+    );
+
+    let segment_start = Instant::now();
+    let previous_checkpoint = provider.get_prune_checkpoint(segment.segment())?;
+    let output = segment.prune(provider, PruneInput { previous_checkpoint, to_block, limiter })?;
+
+    let checkpoint = output.checkpoint.map(|checkpoint| checkpoint.as_prune_checkpoint(prune_mode));
+    if let Some(checkpoint) = checkpoint {
+        segment.save_checkpoint(provider, checkpoint)?;
+    }
+
+    debug!(
+        target: "pruner",
+        segment = ?segment.segment(),
+        ?purpose,
+        %to_block,
+        ?prune_mode,
+        %output.pruned,
+        "Segment pruning finished"
+    );
+
+    Ok(SegmentOutput {
+        info: PrunedSegmentInfo {
+            progress: output.progress,
+            pruned: output.pruned,
+            duration: segment_start.elapsed(),
+        },
+        checkpoint,
+    })
+}
+
 #[cfg(test)]
 mod tests {
 
-    use crate::Pruner;
+    use crate::{
+        segments::{PruneInput, PruneOutput, Segment},
+        Pruner, PrunerError,
+    };
     use reth_db::test_utils::{create_test_rw_db, create_test_static_files_dir};
+    use reth_db_api::database::Database;
     use reth_exex_types::FinishedExExHeight;
     use reth_primitives::MAINNET;
-    use reth_provider::{providers::StaticFileProvider, ProviderFactory};
+    use reth_provider::{providers::StaticFileProvider, DatabaseProviderRW, ProviderFactory};
+    use reth_prune_types::{PruneInterruptReason, PruneMode, PruneProgress, PruneSegment};
+    use std::sync::{Arc, Mutex};
+
+    /// Segment that deletes as many of its entries as the limiter allows, without touching the
+    /// database.
+    #[derive(Debug)]
+    struct TestSegment {
+        segment: PruneSegment,
+        entries_left: Arc<Mutex<usize>>,
+    }
+
+    impl<DB: Database> Segment<DB> for TestSegment {
+        fn segment(&self) -> PruneSegment {
+            self.segment
+        }
+
+        fn mode(&self) -> Option<PruneMode> {
+            Some(PruneMode::Before(1))
+        }
+
+        fn prune(
+            &self,
+            _provider: &DatabaseProviderRW<DB>,
+            input: PruneInput,
+        ) -> Result<PruneOutput, PrunerError> {
+            let mut entries_left = self.entries_left.lock().unwrap();
+            let pruned = (*entries_left)
+                .min(input.limiter.deleted_entries_limit_left().unwrap_or(usize::MAX));
+            *entries_left -= pruned;
+            let progress = if *entries_left == 0 {
+                PruneProgress::Finished
+            } else {
+                PruneProgress::HasMoreData(PruneInterruptReason::DeletedEntriesLimitReached)
+            };
+            Ok(PruneOutput { progress, pruned, checkpoint: None })
+        }
+    }
 
     #[test]
     fn is_pruning_needed() {
@@ -382,4 +576,38 @@ mod tests {
         finished_exex_height_tx.send(FinishedExExHeight::Height(third_block_number)).unwrap();
         assert!(pruner.is_pruning_needed(third_block_number));
     }
+
+    #[test]
+    fn budget_left_by_finished_groups_is_used() {
+        let db = create_test_rw_db();
+        let (_static_dir, static_dir_path) = create_test_static_files_dir();
+        let provider_factory = ProviderFactory::new(
+            db,
+            MAINNET.clone(),
+            StaticFileProvider::read_write(static_dir_path).unwrap(),
+        );
+        let (_finished_exex_height_tx, finished_exex_height_rx) =
+            tokio::sync::watch::channel(FinishedExExHeight::NoExExs);
+
+        let receipts = Arc::new(Mutex::new(10));
+        let senders = Arc::new(Mutex::new(3));
+        let segments: Vec<Box<dyn Segment<_>>> = vec![
+            Box::new(TestSegment {
+                segment: PruneSegment::Receipts,
+                entries_left: receipts.clone(),
+            }),
+            Box::new(TestSegment {
+                segment: PruneSegment::SenderRecovery,
+                entries_left: senders.clone(),
+            }),
+        ];
+        let mut pruner =
+            Pruner::new(provider_factory, segments, 5, 13, 5, None, finished_exex_height_rx);
+
+        // Receipts use up their share of 7 entries first, then get the rest of the budget once
+        // sender recovery finished
+        assert_eq!(pruner.run(100).unwrap(), PruneProgress::Finished);
+        assert_eq!(*receipts.lock().unwrap(), 0);
+        assert_eq!(*senders.lock().unwrap(), 0);
+    }
 }
@@ -37,6 +37,9 @@ pub use transactions::Transactions;
 /// 2. If [`Segment::prune`] returned a [Some] in `checkpoint` of [`PruneOutput`], call
 ///    [`Segment::save_checkpoint`].
 /// 3. Subtract `pruned` of [`PruneOutput`] from `delete_limit` of next [`PruneInput`].
+///
+/// Segments of one pruner run share a single database transaction, and each of them gets a
+/// limiter reserved from the budget shared by the whole run.
 pub trait Segment<DB: Database>: Debug + Send + Sync {
     /// Segment of data that's pruned.
     fn segment(&self) -> PruneSegment;
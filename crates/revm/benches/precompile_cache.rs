@@ -0,0 +1,65 @@
+#![allow(missing_docs, unreachable_pub)]
+use criterion::{criterion_group, criterion_main, Criterion};
+use reth_primitives::{address, keccak256, sign_message, Address, Bytes, TxKind, B256, U256};
+use reth_revm::precompile_cache::{cached_precompiles_handle_register, PrecompileCache};
+use revm::{
+    db::{CacheDB, EmptyDB},
+    Evm,
+};
+
+/// The number of transactions of the block, each recovers a signer like an ERC-20 permit.
+const TRANSACTIONS: u64 = 200;
+
+/// The address of the ecrecover precompile.
+const ECRECOVER: Address = address!("0000000000000000000000000000000000000001");
+
+/// Compares executing a block of transactions that call ecrecover without the precompile cache,
+/// like the first build of a payload job, with executing it with the results cached by a previous
+/// build of the job.
+pub fn ecrecover_block(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ecrecover block");
+    let inputs = (1..=TRANSACTIONS).map(ecrecover_input).collect::<Vec<_>>();
+
+    let execute = |cache: Option<&PrecompileCache>| {
+        for input in &inputs {
+            let builder =
+                Evm::builder().with_db(CacheDB::new(EmptyDB::default())).modify_tx_env(|tx| {
+                    tx.transact_to = TxKind::Call(ECRECOVER);
+                    tx.data = input.clone();
+                    tx.gas_limit = 100_000;
+                });
+            let mut evm = match cache {
+                Some(cache) => builder
+                    .append_handler_register_box(cached_precompiles_handle_register(cache.clone()))
+                    .build(),
+                None => builder.build(),
+            };
+            let result = evm.transact().unwrap().result;
+            assert!(result.is_success());
+        }
+    };
+
+    group.bench_function("uncached", |b| b.iter(|| execute(None)));
+
+    let cache = PrecompileCache::default();
+    execute(Some(&cache));
+    group.bench_function("cached", |b| b.iter(|| execute(Some(&cache))));
+
+    group.finish();
+}
+
+/// Returns the ecrecover input of a signature of the given signer.
+fn ecrecover_input(signer: u64) -> Bytes {
+    let hash = keccak256(signer.to_be_bytes());
+    let signature = sign_message(B256::from(U256::from(signer)), hash).unwrap();
+
+    let mut input = Vec::with_capacity(128);
+    input.extend_from_slice(hash.as_slice());
+    input.extend_from_slice(B256::with_last_byte(27 + signature.odd_y_parity as u8).as_slice());
+    input.extend_from_slice(&signature.r.to_be_bytes::<32>());
+    input.extend_from_slice(&signature.s.to_be_bytes::<32>());
+    input.into()
+}
+
+criterion_group!(benches, ecrecover_block);
+criterion_main!(benches);
@@ -0,0 +1,253 @@
+//! Database adapters that cache the reads from the underlying database.
+
+use parking_lot::Mutex;
+use reth_primitives::{
+    revm_primitives::{
+        db::{Database, DatabaseRef},
+        AccountInfo, Address, Bytecode, B256,
+    },
+    BlockNumHash, U256,
+};
+use std::{
+    cell::RefCell,
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+};
+
+/// A container type that caches reads from an underlying [`DatabaseRef`].
+///
+/// This is intended to be used in conjunction with `revm::db::State`
+/// during payload building which repeatedly accesses the same data.
+///
+/// # Example
+///
+/// ```
+/// use reth_revm::cached::CachedReads;
+/// use revm::db::{DatabaseRef, State};
+///
+/// fn build_payload<DB: DatabaseRef>(db: DB) {
+///     let mut cached_reads = CachedReads::default();
+///     let db_ref = cached_reads.as_db(db);
+///     // this is `Database` and can be used to build a payload, it never writes to `CachedReads` or the underlying database, but all reads from the underlying database are cached in `CachedReads`.
+///     // Subsequent payload build attempts can use cached reads and avoid hitting the underlying database.
+///     let db = State::builder().with_database_ref(db_ref).build();
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CachedReads {
+    accounts: HashMap<Address, CachedAccount>,
+    contracts: HashMap<B256, Bytecode>,
+    block_hashes: HashMap<U256, B256>,
+}
+
+// === impl CachedReads ===
+
+impl CachedReads {
+    /// Gets a [`DatabaseRef`] that will cache reads from the given database.
+    pub fn as_db<DB>(&mut self, db: DB) -> CachedReadsDBRef<'_, DB> {
+        CachedReadsDBRef { inner: RefCell::new(self.as_db_mut(db)) }
+    }
+
+    /// Gets a [Database] that will cache reads from the given database.
+    pub fn as_db_mut<DB>(&mut self, db: DB) -> CachedReadsDbMut<'_, DB> {
+        CachedReadsDbMut { cached: self, db }
+    }
+
+    /// Inserts an account info into the cache.
+    pub fn insert_account(
+        &mut self,
+        address: Address,
+        info: AccountInfo,
+        storage: HashMap<U256, U256>,
+    ) {
+        self.accounts.insert(address, CachedAccount { info: Some(info), storage });
+    }
+}
+
+/// A [Database] that caches reads inside [`CachedReads`].
+#[derive(Debug)]
+pub struct CachedReadsDbMut<'a, DB> {
+    /// The cache of reads.
+    pub cached: &'a mut CachedReads,
+    /// The underlying database.
+    pub db: DB,
+}
+
+impl<'a, DB: DatabaseRef> Database for CachedReadsDbMut<'a, DB> {
+    type Error = <DB as DatabaseRef>::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let basic = match self.cached.accounts.entry(address) {
+            Entry::Occupied(entry) => entry.get().info.clone(),
+            Entry::Vacant(entry) => {
+                entry.insert(CachedAccount::new(self.db.basic_ref(address)?)).info.clone()
+            }
+        };
+        Ok(basic)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = match self.cached.contracts.entry(code_hash) {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => entry.insert(self.db.code_by_hash_ref(code_hash)?).clone(),
+        };
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        match self.cached.accounts.entry(address) {
+            Entry::Occupied(mut acc_entry) => match acc_entry.get_mut().storage.entry(index) {
+                Entry::Occupied(entry) => Ok(*entry.get()),
+                Entry::Vacant(entry) => Ok(*entry.insert(self.db.storage_ref(address, index)?)),
+            },
+            Entry::Vacant(acc_entry) => {
+                // acc needs to be loaded for us to access slots.
+                let info = self.db.basic_ref(address)?;
+                let (account, value) = if info.is_some() {
+                    let value = self.db.storage_ref(address, index)?;
+                    let mut account = CachedAccount::new(info);
+                    account.storage.insert(index, value);
+                    (account, value)
+                } else {
+                    (CachedAccount::new(info), U256::ZERO)
+                };
+                acc_entry.insert(account);
+                Ok(value)
+            }
+        }
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        let code = match self.cached.block_hashes.entry(number) {
+            Entry::Occupied(entry) => *entry.get(),
+            Entry::Vacant(entry) => *entry.insert(self.db.block_hash_ref(number)?),
+        };
+        Ok(code)
+    }
+}
+
+/// A [`DatabaseRef`] that caches reads inside [`CachedReads`].
+///
+/// This is intended to be used as the [`DatabaseRef`] for
+/// `revm::db::State` for repeated payload build jobs.
+#[derive(Debug)]
+pub struct CachedReadsDBRef<'a, DB> {
+    /// The inner cache reads db mut.
+    pub inner: RefCell<CachedReadsDbMut<'a, DB>>,
+}
+
+impl<'a, DB: DatabaseRef> DatabaseRef for CachedReadsDBRef<'a, DB> {
+    type Error = <DB as DatabaseRef>::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.inner.borrow_mut().basic(address)
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.inner.borrow_mut().code_by_hash(code_hash)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.inner.borrow_mut().storage(address, index)
+    }
+
+    fn block_hash_ref(&self, number: U256) -> Result<B256, Self::Error> {
+        self.inner.borrow_mut().block_hash(number)
+    }
+}
+
+/// The maximum number of blocks the [`PayloadReadsCache`] entries are kept for after their parent
+/// block.
+const MAX_PAYLOAD_READS_DEPTH: u64 = 2;
+
+/// The [`CachedReads`] of the payloads built by the node, keyed by the hash of their parent block.
+///
+/// The payload builder deposits the reads of the payloads it has built, so the payloads received
+/// back through `engine_newPayload` are executed on top of the state that is already in memory
+/// instead of reading it from the database again. The reads are the state of the parent block and
+/// don't depend on the payload itself, so any block with the same parent can use them.
+///
+/// The cache is only used to warm up the execution, it's never required to be populated.
+#[derive(Debug, Clone, Default)]
+pub struct PayloadReadsCache {
+    inner: Arc<Mutex<HashMap<B256, (u64, CachedReads)>>>,
+}
+
+impl PayloadReadsCache {
+    /// Stores the reads of a payload built on top of the given parent block.
+    ///
+    /// This also evicts the entries of the parent blocks that are too old to be built on.
+    pub fn insert(&self, parent: BlockNumHash, cached: CachedReads) {
+        let mut inner = self.inner.lock();
+        inner.retain(|_, (number, _)| *number + MAX_PAYLOAD_READS_DEPTH >= parent.number);
+        inner.insert(parent.hash, (parent.number, cached));
+    }
+
+    /// Removes and returns the reads of the state of the given parent block, if any.
+    pub fn take(&self, parent_hash: &B256) -> Option<CachedReads> {
+        self.inner.lock().remove(parent_hash).map(|(_, cached)| cached)
+    }
+
+    /// Evicts the entries of the parent blocks that are too old relative to the new canonical tip.
+    pub fn on_canonical_tip(&self, tip_number: u64) {
+        self.inner.lock().retain(|_, (number, _)| *number + MAX_PAYLOAD_READS_DEPTH >= tip_number);
+    }
+
+    /// Removes all entries, e.g. on reorg.
+    pub fn clear(&self) {
+        self.inner.lock().clear();
+    }
+
+    /// Returns the number of cached parent blocks.
+    pub fn len(&self) -> usize {
+        self.inner.lock().len()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedAccount {
+    info: Option<AccountInfo>,
+    storage: HashMap<U256, U256>,
+}
+
+impl CachedAccount {
+    fn new(info: Option<AccountInfo>) -> Self {
+        Self { info, storage: HashMap::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_reads_eviction() {
+        let cache = PayloadReadsCache::default();
+        let parent = |number: u64| BlockNumHash::new(number, B256::with_last_byte(number as u8));
+
+        cache.insert(parent(1), CachedReads::default());
+        cache.insert(parent(2), CachedReads::default());
+        assert_eq!(cache.len(), 2);
+
+        // Entries that are too old are evicted on insert
+        cache.insert(parent(4), CachedReads::default());
+        assert_eq!(cache.len(), 2);
+        assert!(cache.take(&parent(1).hash).is_none());
+
+        // Entries are taken only once
+        assert!(cache.take(&parent(2).hash).is_some());
+        assert!(cache.take(&parent(2).hash).is_none());
+
+        cache.on_canonical_tip(7);
+        assert!(cache.is_empty());
+
+        cache.insert(parent(8), CachedReads::default());
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
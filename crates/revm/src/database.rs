@@ -33,6 +33,13 @@ pub trait EvmStateProvider: Send + Sync {
         account: Address,
         storage_key: StorageKey,
     ) -> ProviderResult<Option<StorageValue>>;
+
+    /// Get the storage of the given account for multiple storage slots, in the order of the slots.
+    fn storages(
+        &self,
+        account: Address,
+        storage_keys: &[StorageKey],
+    ) -> ProviderResult<Vec<Option<StorageValue>>>;
 }
 
 // Blanket implementation of EvmStateProvider for any type that implements StateProvider.
@@ -59,6 +66,14 @@ impl<T: reth_storage_api::StateProvider> EvmStateProvider for T {
     ) -> ProviderResult<Option<StorageValue>> {
         <T as reth_storage_api::StateProvider>::storage(self, account, storage_key)
     }
+
+    fn storages(
+        &self,
+        account: Address,
+        storage_keys: &[StorageKey],
+    ) -> ProviderResult<Vec<Option<StorageValue>>> {
+        <T as reth_storage_api::StateProvider>::storages(self, account, storage_keys)
+    }
 }
 
 /// A [Database] and [`DatabaseRef`] implementation that uses [`EvmStateProvider`] as the underlying
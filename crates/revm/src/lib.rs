@@ -13,9 +13,19 @@ pub mod database;
 
 pub mod batch;
 
+pub mod cached;
+
+pub mod precompile_cache;
+
 /// State changes that are not related to transactions.
 pub mod state_change;
 
+pub mod replay;
+
+pub mod state_override;
+
+pub mod witness;
+
 /// Common test helpers
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
@@ -0,0 +1,157 @@
+//! Caches the results of the precompiles across the executions of the same transactions.
+
+use parking_lot::RwLock;
+use reth_primitives::{keccak256, Address, Bytes, B256};
+use revm::{
+    handler::register::{EvmHandler, HandleRegisterBox},
+    precompile::{Precompile, PrecompileResult, StandardPrecompileFn, StatefulPrecompile},
+    primitives::{db::Database, Env},
+    ContextPrecompile,
+};
+use std::{collections::HashMap, fmt, sync::Arc};
+
+/// A cache for the results of the precompiles, keyed by the precompile address and the hash of the
+/// input.
+///
+/// This is intended to be used during payload building, which repeatedly executes the same
+/// transactions and therefore calls the precompiles with the same inputs, see
+/// [`cached_precompiles_handle_register`].
+///
+/// Only the successful results are cached, including the gas they used, so the gas accounting of a
+/// cached call is identical to the actual call. The cache is not bounded and should be scoped to a
+/// single payload job.
+#[derive(Clone, Default)]
+pub struct PrecompileCache {
+    results: Arc<RwLock<HashMap<(Address, B256), (u64, Bytes)>>>,
+}
+
+// === impl PrecompileCache ===
+
+impl PrecompileCache {
+    /// Returns the number of cached results.
+    pub fn len(&self) -> usize {
+        self.results.read().len()
+    }
+
+    /// Returns `true` if no result is cached.
+    pub fn is_empty(&self) -> bool {
+        self.results.read().is_empty()
+    }
+}
+
+impl fmt::Debug for PrecompileCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrecompileCache").field("len", &self.len()).finish()
+    }
+}
+
+/// A standard precompile whose results are cached in a [`PrecompileCache`].
+#[derive(Debug)]
+struct CachedPrecompile {
+    /// The address of the precompile.
+    address: Address,
+    /// The precompile function.
+    run: StandardPrecompileFn,
+    /// The cache of the results.
+    cache: PrecompileCache,
+}
+
+impl StatefulPrecompile for CachedPrecompile {
+    fn call(&self, input: &Bytes, gas_limit: u64, _env: &Env) -> PrecompileResult {
+        let key = (self.address, keccak256(input));
+        if let Some((gas_used, output)) = self.cache.results.read().get(&key) {
+            // the result is only reused if the call has enough gas, otherwise the precompile
+            // reports the error
+            if *gas_used <= gas_limit {
+                return Ok((*gas_used, output.clone()))
+            }
+        }
+
+        let result = (self.run)(input, gas_limit);
+        if let Ok((gas_used, output)) = &result {
+            self.cache.results.write().insert(key, (*gas_used, output.clone()));
+        }
+        result
+    }
+}
+
+/// Returns the handler register that caches the results of the precompiles of the EVM in the given
+/// [`PrecompileCache`].
+///
+/// This wraps the precompiles loaded by the handlers registered before. Only the standard
+/// precompiles are cached, because their result is a pure function of the input and the gas
+/// limit. All other precompiles can depend on the environment or the state and are always called.
+pub fn cached_precompiles_handle_register<EXT, DB: Database>(
+    cache: PrecompileCache,
+) -> HandleRegisterBox<EXT, DB> {
+    Box::new(move |handler: &mut EvmHandler<'_, EXT, DB>| {
+        let load_precompiles = handler.pre_execution.load_precompiles.clone();
+        let cache = cache.clone();
+        handler.pre_execution.load_precompiles = Arc::new(move || {
+            let mut precompiles = load_precompiles();
+            for (address, precompile) in precompiles.iter_mut() {
+                if let ContextPrecompile::Ordinary(Precompile::Standard(run)) = precompile {
+                    let cached =
+                        CachedPrecompile { address: *address, run: *run, cache: cache.clone() };
+                    *precompile =
+                        ContextPrecompile::Ordinary(Precompile::Stateful(Arc::new(cached)));
+                }
+            }
+            precompiles
+        });
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::precompile::PrecompileError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Returns the input and costs the length of the input in gas.
+    fn echo(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        let gas_used = input.len() as u64;
+        if gas_used > gas_limit {
+            return Err(PrecompileError::OutOfGas)
+        }
+        Ok((gas_used, input.clone()))
+    }
+
+    #[test]
+    fn caches_successful_results() {
+        let cache = PrecompileCache::default();
+        let precompile = CachedPrecompile {
+            address: Address::with_last_byte(1),
+            run: echo,
+            cache: cache.clone(),
+        };
+        let env = Env::default();
+        let input = Bytes::from_static(&[1, 2, 3]);
+
+        // not enough gas, nothing is cached
+        assert_eq!(precompile.call(&input, 2, &env), Err(PrecompileError::OutOfGas));
+        assert!(cache.is_empty());
+
+        assert_eq!(precompile.call(&input, 10, &env), Ok((3, input.clone())));
+        assert_eq!(precompile.call(&input, 3, &env), Ok((3, input.clone())));
+        assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.len(), 1);
+
+        // the cached result is not used if the call has not enough gas
+        assert_eq!(precompile.call(&input, 2, &env), Err(PrecompileError::OutOfGas));
+        assert_eq!(CALLS.load(Ordering::SeqCst), 3);
+
+        // the cache is keyed by the address of the precompile
+        let other = CachedPrecompile {
+            address: Address::with_last_byte(2),
+            run: echo,
+            cache: cache.clone(),
+        };
+        assert_eq!(other.call(&input, 10, &env), Ok((3, input)));
+        assert_eq!(CALLS.load(Ordering::SeqCst), 4);
+        assert_eq!(cache.len(), 2);
+    }
+}
@@ -0,0 +1,175 @@
+//! Replay of historical transactions.
+
+use crate::database::StateProviderDatabase;
+use reth_evm::{provider::EvmEnvProvider, system_calls::SystemCaller, ConfigureEvm};
+use reth_execution_errors::{
+    BlockExecutionError, BlockValidationError, FailedTransaction, TransactionReplayError,
+};
+use reth_primitives::{BlockNumHash, ChainSpec, TransactionSigned, B256};
+use reth_storage_api::{
+    BlockReader, StateProviderBox, StateProviderFactory, TransactionReplayProvider,
+    TransactionVariant,
+};
+use reth_storage_errors::provider::ProviderError;
+use revm::{
+    db::{states::bundle_state::BundleRetention, BundleState},
+    inspectors::NoOpInspector,
+    primitives::{EVMError, EnvWithHandlerCfg, ExecutionResult, ResultAndState},
+    DatabaseCommit, GetInspector, State,
+};
+use std::sync::Arc;
+
+/// The database the replayed transaction is executed on: the state of the parent block with the
+/// changes of the system calls and the preceding transactions of the block.
+pub type ReplayDatabase = State<StateProviderDatabase<StateProviderBox>>;
+
+/// Replays historical transactions on top of the exact state they were executed on.
+///
+/// The state of the parent block is loaded, then the pre-execution system calls and the
+/// preceding transactions of the block are executed, before the replayed transaction itself.
+#[derive(Debug, Clone)]
+pub struct ReplayExecutor<Provider, EvmConfig> {
+    /// The provider of blocks and historical state.
+    provider: Provider,
+    /// How to create an EVM.
+    evm_config: EvmConfig,
+    /// The chain spec, used for the system calls.
+    chain_spec: Arc<ChainSpec>,
+}
+
+impl<Provider, EvmConfig> ReplayExecutor<Provider, EvmConfig> {
+    /// Creates a new replay executor.
+    pub const fn new(
+        provider: Provider,
+        evm_config: EvmConfig,
+        chain_spec: Arc<ChainSpec>,
+    ) -> Self {
+        Self { provider, evm_config, chain_spec }
+    }
+}
+
+impl<Provider, EvmConfig> ReplayExecutor<Provider, EvmConfig>
+where
+    Provider: BlockReader + StateProviderFactory + EvmEnvProvider,
+    EvmConfig: ConfigureEvm,
+{
+    /// Replays the transaction with the given hash of the block with the given hash and returns
+    /// its result and the state changes it made.
+    ///
+    /// The inspector, if any, is only attached to the EVM that executes the replayed transaction.
+    pub fn replay_transaction<I>(
+        &self,
+        block_hash: B256,
+        tx_hash: B256,
+        inspector: Option<I>,
+    ) -> Result<(ExecutionResult, BundleState), TransactionReplayError>
+    where
+        I: for<'a> GetInspector<&'a mut ReplayDatabase>,
+    {
+        let block = self
+            .provider
+            .block_with_senders(block_hash.into(), TransactionVariant::WithHash)?
+            .ok_or(ProviderError::BlockHashNotFound(block_hash))?;
+        let index = block
+            .body
+            .iter()
+            .position(|tx| tx.hash() == tx_hash)
+            .ok_or(TransactionReplayError::TransactionNotInBlock { block_hash, tx_hash })?;
+
+        let (cfg, block_env) =
+            self.provider.env_with_header(&block.header, self.evm_config.clone())?;
+        let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, Default::default());
+
+        let state = self.provider.history_by_block_hash(block.parent_hash)?;
+        let mut db = State::builder()
+            .with_database(StateProviderDatabase::new(state))
+            .with_bundle_update()
+            .build();
+
+        // Reach the state right before the transaction
+        let block_num_hash = BlockNumHash::new(block.number, block_hash);
+        let mut cumulative_gas_used = 0;
+        let mut transactions = block.transactions_with_sender();
+        {
+            let mut evm = self.evm_config.evm_with_env(&mut db, env.clone());
+            SystemCaller::new(&self.chain_spec)
+                .apply_pre_execution_changes(&block.header, &mut evm)?;
+
+            for (index, (sender, transaction)) in transactions.by_ref().take(index).enumerate() {
+                EvmConfig::fill_tx_env(evm.tx_mut(), transaction, *sender);
+                let ResultAndState { result, state } = evm.transact().map_err(|error| {
+                    transaction_error(
+                        block_num_hash,
+                        index,
+                        transaction,
+                        cumulative_gas_used,
+                        error,
+                    )
+                })?;
+                evm.db_mut().commit(state);
+                cumulative_gas_used += result.gas_used();
+            }
+        }
+
+        // The changes made so far are part of the pre-state of the transaction, so only the
+        // changes of the transaction itself end up in the bundle.
+        db.merge_transitions(BundleRetention::PlainState);
+        db.take_bundle();
+
+        let (sender, transaction) = transactions.next().expect("transaction is in the block");
+        let result = match inspector {
+            Some(inspector) => {
+                let mut evm = self.evm_config.evm_with_env_and_inspector(&mut db, env, inspector);
+                EvmConfig::fill_tx_env(evm.tx_mut(), transaction, *sender);
+                evm.transact()
+            }
+            None => {
+                let mut evm = self.evm_config.evm_with_env(&mut db, env);
+                EvmConfig::fill_tx_env(evm.tx_mut(), transaction, *sender);
+                evm.transact()
+            }
+        };
+        let ResultAndState { result, state } = result.map_err(|error| {
+            transaction_error(block_num_hash, index, transaction, cumulative_gas_used, error)
+        })?;
+
+        db.commit(state);
+        db.merge_transitions(BundleRetention::Reverts);
+
+        Ok((result, db.take_bundle()))
+    }
+}
+
+impl<Provider, EvmConfig> TransactionReplayProvider for ReplayExecutor<Provider, EvmConfig>
+where
+    Provider: BlockReader + StateProviderFactory + EvmEnvProvider,
+    EvmConfig: ConfigureEvm,
+{
+    fn replay(
+        &self,
+        block_hash: B256,
+        tx_hash: B256,
+    ) -> Result<(ExecutionResult, BundleState), TransactionReplayError> {
+        self.replay_transaction::<NoOpInspector>(block_hash, tx_hash, None)
+    }
+}
+
+/// Returns the error of the transaction that failed to execute.
+fn transaction_error(
+    block: BlockNumHash,
+    index: usize,
+    transaction: &TransactionSigned,
+    cumulative_gas_used: u64,
+    error: EVMError<ProviderError>,
+) -> TransactionReplayError {
+    BlockExecutionError::from(BlockValidationError::EVM {
+        transaction: Box::new(FailedTransaction {
+            block,
+            index,
+            hash: transaction.hash(),
+            cumulative_gas_used,
+        }),
+        error: Box::new(error),
+    })
+    .into()
+}
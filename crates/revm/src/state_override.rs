@@ -0,0 +1,102 @@
+//! State overrides, as used by `eth_call` and block simulation.
+
+pub use alloy_rpc_types::state::{AccountOverride, StateOverride};
+
+use reth_primitives::{Address, U256};
+use revm::{
+    db::{AccountState, CacheDB},
+    primitives::{db::DatabaseRef, Bytecode},
+};
+
+/// Error applying a [`StateOverride`].
+#[derive(Debug, thiserror::Error)]
+pub enum StateOverrideError<E> {
+    /// The override of the account sets both `state` and `stateDiff`.
+    #[error("account {0:?} has both 'state' and 'stateDiff'")]
+    BothStateAndStateDiff(Address),
+    /// Reading the overridden account from the database failed.
+    #[error(transparent)]
+    Database(E),
+}
+
+/// Applies the given state overrides (a set of [`AccountOverride`]) to the [`CacheDB`].
+pub fn apply_state_overrides<DB>(
+    overrides: StateOverride,
+    db: &mut CacheDB<DB>,
+) -> Result<(), StateOverrideError<DB::Error>>
+where
+    DB: DatabaseRef,
+{
+    for (account, account_overrides) in overrides {
+        apply_account_override(account, account_overrides, db)?;
+    }
+    Ok(())
+}
+
+/// Applies a single [`AccountOverride`] to the [`CacheDB`].
+pub fn apply_account_override<DB>(
+    account: Address,
+    account_override: AccountOverride,
+    db: &mut CacheDB<DB>,
+) -> Result<(), StateOverrideError<DB::Error>>
+where
+    DB: DatabaseRef,
+{
+    // we need to fetch the account via the `DatabaseRef` to not update the state of the account,
+    // which is modified via `Database::basic_ref`
+    let mut account_info = DatabaseRef::basic_ref(db, account)
+        .map_err(StateOverrideError::Database)?
+        .unwrap_or_default();
+
+    if let Some(nonce) = account_override.nonce {
+        account_info.nonce = nonce.to();
+    }
+    if let Some(code) = account_override.code {
+        account_info.code = Some(Bytecode::new_raw(code));
+    }
+    if let Some(balance) = account_override.balance {
+        account_info.balance = balance;
+    }
+
+    db.insert_account_info(account, account_info);
+    // an account that was cached as non-existent would hide the overridden info from the EVM
+    if let Some(cached) = db.accounts.get_mut(&account) {
+        if matches!(cached.account_state, AccountState::NotExisting) {
+            cached.account_state = AccountState::StorageCleared;
+        }
+    }
+
+    // We ensure that not both state and state_diff are set.
+    // If state is set, we must mark the account as "NewlyCreated", so that the old storage
+    // isn't read from
+    match (account_override.state, account_override.state_diff) {
+        (Some(_), Some(_)) => return Err(StateOverrideError::BothStateAndStateDiff(account)),
+        (None, None) => {
+            // nothing to do
+        }
+        (Some(new_account_state), None) => {
+            db.replace_account_storage(
+                account,
+                new_account_state
+                    .into_iter()
+                    .map(|(slot, value)| {
+                        (U256::from_be_bytes(slot.0), U256::from_be_bytes(value.0))
+                    })
+                    .collect(),
+            )
+            .map_err(StateOverrideError::Database)?;
+        }
+        (None, Some(account_state_diff)) => {
+            for (slot, value) in account_state_diff {
+                db.insert_account_storage(
+                    account,
+                    U256::from_be_bytes(slot.0),
+                    U256::from_be_bytes(value.0),
+                )
+                .map_err(StateOverrideError::Database)?;
+            }
+        }
+    };
+
+    Ok(())
+}
@@ -1,12 +1,13 @@
 use reth_primitives::{
-    keccak256, trie::AccountProof, Account, Address, BlockNumber, Bytecode, Bytes, StorageKey,
-    B256, U256,
+    keccak256,
+    trie::{AccountProof, MultiProof, StorageMultiProof},
+    Account, Address, BlockNumber, Bytecode, Bytes, StorageKey, B256, U256,
 };
 use reth_storage_api::{AccountReader, BlockHashReader, StateProvider, StateRootProvider};
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::updates::TrieUpdates;
+use reth_trie::{updates::TrieUpdates, HashedPostState, HashedStorage, StorageRange};
 use revm::db::BundleState;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Mock state for testing
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
@@ -75,6 +76,22 @@ impl StateRootProvider for StateProviderTest {
     ) -> ProviderResult<(B256, TrieUpdates)> {
         unimplemented!("state root computation is not supported")
     }
+
+    fn hashed_state_root_with_storage_roots(
+        &self,
+        _hashed_state: HashedPostState,
+        _storage_roots: HashMap<B256, B256>,
+    ) -> ProviderResult<B256> {
+        unimplemented!("state root computation is not supported")
+    }
+
+    fn hashed_storage_root(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        unimplemented!("state root computation is not supported")
+    }
 }
 
 impl StateProvider for StateProviderTest {
@@ -93,4 +110,31 @@ impl StateProvider for StateProviderTest {
     fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
         unimplemented!("proof generation is not supported")
     }
+
+    fn multiproof(
+        &self,
+        _hashed_state: HashedPostState,
+        _targets: HashMap<B256, HashSet<B256>>,
+    ) -> ProviderResult<MultiProof> {
+        unimplemented!("proof generation is not supported")
+    }
+
+    fn storage_multiproof(
+        &self,
+        _address: Address,
+        _slots: &[B256],
+        _hashed_storage: HashedStorage,
+    ) -> ProviderResult<StorageMultiProof> {
+        unimplemented!("proof generation is not supported")
+    }
+
+    fn storage_range(
+        &self,
+        _address: Address,
+        _hashed_storage: HashedStorage,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<StorageRange> {
+        unimplemented!("storage ranges are not supported")
+    }
 }
@@ -0,0 +1,292 @@
+//! Execution witness generation.
+
+use crate::database::StateProviderDatabase;
+use alloy_rlp::Header;
+use reth_evm::execute::{BlockExecutorProvider, Executor};
+use reth_primitives::{
+    keccak256,
+    trie::{ExecutionWitness, Nibbles},
+    Address, BlockNumber, Bytes, B256, U256,
+};
+use reth_storage_api::{
+    BlockReader, ExecutionWitnessProvider, HeaderProvider, StateProvider, StateProviderFactory,
+    TransactionVariant,
+};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use reth_trie::HashedPostState;
+use revm::{
+    db::BundleState,
+    primitives::{AccountInfo, Bytecode},
+    Database,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// A [Database] wrapper that records all the state accessed through it.
+#[derive(Debug)]
+pub struct StateRecorder<DB> {
+    /// The wrapped database.
+    db: DB,
+    /// The state accessed so far.
+    recorded: RecordedState,
+}
+
+impl<DB> StateRecorder<DB> {
+    /// Wraps the given database.
+    pub fn new(db: DB) -> Self {
+        Self { db, recorded: RecordedState::default() }
+    }
+
+    /// Returns the state accessed so far.
+    pub const fn recorded(&self) -> &RecordedState {
+        &self.recorded
+    }
+
+    /// Consumes the recorder and returns the accessed state.
+    pub fn into_recorded(self) -> RecordedState {
+        self.recorded
+    }
+}
+
+impl<DB: Database> Database for StateRecorder<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.db.basic(address)?;
+        self.recorded.accounts.entry(address).or_default();
+        if let Some(code) = info.as_ref().and_then(|info| info.code.as_ref()) {
+            self.recorded.codes.insert(code.hash_slow(), code.original_bytes());
+        }
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.db.code_by_hash(code_hash)?;
+        self.recorded.codes.insert(code_hash, code.original_bytes());
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self.db.storage(address, index)?;
+        self.recorded.accounts.entry(address).or_default().insert(B256::from(index));
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        let hash = self.db.block_hash(number)?;
+        if let Ok(number) = u64::try_from(number) {
+            self.recorded.block_hashes.insert(number, hash);
+        }
+        Ok(hash)
+    }
+}
+
+/// The state accessed through the [`StateRecorder`].
+#[derive(Clone, Default, Debug)]
+pub struct RecordedState {
+    /// Accessed accounts mapped to their accessed storage slots.
+    pub accounts: HashMap<Address, HashSet<B256>>,
+    /// Accessed bytecodes keyed by their code hashes.
+    pub codes: HashMap<B256, Bytes>,
+    /// Accessed ancestor block hashes keyed by block number.
+    pub block_hashes: BTreeMap<BlockNumber, B256>,
+}
+
+impl RecordedState {
+    /// Builds the [`ExecutionWitness`] of the recorded state against the given pre-state.
+    ///
+    /// The `post_state` is the state after the execution and is used to determine which accounts
+    /// and storage slots were deleted. Removing a key from the trie may collapse the branch node
+    /// on its path into the only remaining child, so the witness additionally includes the nodes
+    /// of those remaining siblings.
+    pub fn into_witness(
+        self,
+        state: &dyn StateProvider,
+        post_state: &BundleState,
+    ) -> ProviderResult<ExecutionWitness> {
+        let mut witness = ExecutionWitness {
+            codes: self.codes,
+            block_hashes: self.block_hashes,
+            ..Default::default()
+        };
+
+        let mut targets = HashMap::<B256, HashSet<B256>>::with_capacity(self.accounts.len());
+        for (address, slots) in self.accounts {
+            let hashed_address = keccak256(address);
+            witness.keys.insert(hashed_address, Bytes::copy_from_slice(address.as_slice()));
+
+            let hashed_slots = targets.entry(hashed_address).or_default();
+            for slot in slots {
+                let hashed_slot = keccak256(slot);
+                witness.keys.insert(hashed_slot, Bytes::copy_from_slice(slot.as_slice()));
+                hashed_slots.insert(hashed_slot);
+            }
+        }
+
+        let mut multiproof = state.multiproof(HashedPostState::default(), targets.clone())?;
+
+        // Collect the siblings of the deleted keys that might be collapsed into their parents.
+        let mut sibling_targets = HashMap::<B256, HashSet<B256>>::default();
+        for (address, account) in &post_state.state {
+            let hashed_address = keccak256(address);
+            if account.original_info.is_some() && account.info.is_none() {
+                for sibling in collapsible_siblings(&multiproof.account_subtree, hashed_address) {
+                    sibling_targets.entry(sibling).or_default();
+                }
+                continue
+            }
+
+            // Wiped storage is replaced as a whole, no need to prove the remaining siblings.
+            if account.status.was_destroyed() {
+                continue
+            }
+
+            let Some(storage_multiproof) = multiproof.storages.get(&hashed_address) else {
+                continue
+            };
+            for (slot, value) in &account.storage {
+                if value.original_value().is_zero() || !value.present_value().is_zero() {
+                    continue
+                }
+
+                let hashed_slot = keccak256(B256::from(*slot));
+                for sibling in collapsible_siblings(&storage_multiproof.subtree, hashed_slot) {
+                    sibling_targets.entry(hashed_address).or_default().insert(sibling);
+                }
+            }
+        }
+
+        if !sibling_targets.is_empty() {
+            for (hashed_address, hashed_slots) in sibling_targets {
+                targets.entry(hashed_address).or_default().extend(hashed_slots);
+            }
+            multiproof = state.multiproof(HashedPostState::default(), targets)?;
+        }
+
+        let storage_nodes =
+            multiproof.storages.into_values().flat_map(|storage| storage.subtree.into_values());
+        for node in multiproof.account_subtree.into_values().chain(storage_nodes) {
+            witness.state.insert(keccak256(&node), node);
+        }
+
+        Ok(witness)
+    }
+}
+
+/// Returns the keys that lead to the siblings of the given key in all branch nodes on its path
+/// that have exactly two children, i.e. the branch nodes that collapse if the key is removed.
+fn collapsible_siblings(subtree: &BTreeMap<Nibbles, Bytes>, key: B256) -> Vec<B256> {
+    let nibbles = Nibbles::unpack(key);
+    subtree
+        .iter()
+        .filter(|(path, _)| nibbles.starts_with(path))
+        .filter_map(|(path, node)| {
+            let child = *nibbles.get(path.len())?;
+            let sibling = branch_node_sibling(node, child)?;
+
+            // Pad the path of the sibling to a full key.
+            let mut sibling_nibbles = path.to_vec();
+            sibling_nibbles.push(sibling);
+            sibling_nibbles.resize(64, 0);
+            Some(B256::from_slice(&Nibbles::from_nibbles_unchecked(sibling_nibbles).pack()))
+        })
+        .collect()
+}
+
+/// Returns the nibble of the other child if the rlp-serialized node is a branch node with exactly
+/// two children, one of which is at the given nibble.
+fn branch_node_sibling(node: &[u8], child: u8) -> Option<u8> {
+    let mut buf = node;
+    if !Header::decode(&mut buf).ok()?.list {
+        return None
+    }
+
+    let mut items = 0;
+    let mut children = Vec::with_capacity(2);
+    while !buf.is_empty() {
+        let item = Header::decode(&mut buf).ok()?;
+        if items < 16 && (item.list || item.payload_length > 0) {
+            children.push(items as u8);
+        }
+        buf = buf.get(item.payload_length..)?;
+        items += 1;
+    }
+
+    if items != 17 || children.len() != 2 || !children.contains(&child) {
+        return None
+    }
+    children.into_iter().find(|nibble| *nibble != child)
+}
+
+/// Generates [`ExecutionWitness`]es by re-executing blocks on top of their parent state with the
+/// [`StateRecorder`] wrapped around the state database.
+#[derive(Debug, Clone)]
+pub struct ExecutionWitnessGenerator<Provider, E> {
+    /// The provider of blocks and historical state.
+    provider: Provider,
+    /// The block executor.
+    executor: E,
+}
+
+impl<Provider, E> ExecutionWitnessGenerator<Provider, E> {
+    /// Creates a new witness generator.
+    pub const fn new(provider: Provider, executor: E) -> Self {
+        Self { provider, executor }
+    }
+}
+
+impl<Provider, E> ExecutionWitnessProvider for ExecutionWitnessGenerator<Provider, E>
+where
+    Provider: BlockReader + HeaderProvider + StateProviderFactory,
+    E: BlockExecutorProvider,
+{
+    fn witness(&self, block_hash: B256) -> ProviderResult<ExecutionWitness> {
+        let block = self
+            .provider
+            .block_with_senders(block_hash.into(), TransactionVariant::WithHash)?
+            .ok_or(ProviderError::BlockHashNotFound(block_hash))?;
+        let total_difficulty = self
+            .provider
+            .header_td(&block_hash)?
+            .ok_or(ProviderError::HeaderNotFound(block_hash.into()))?;
+        let state = self.provider.history_by_block_hash(block.parent_hash)?;
+
+        let mut db = StateRecorder::new(StateProviderDatabase::new(&state));
+        let output = self
+            .executor
+            .executor(&mut db)
+            .execute((&block, total_difficulty).into())
+            .map_err(|err| ProviderError::BlockReplay(block_hash, err.to_string()))?;
+
+        db.into_recorded().into_witness(&*state, &output.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn branch_node_siblings() {
+        // Branch node with two children at nibbles 0 and 5.
+        let mut node = vec![0xf8, 0x51];
+        for nibble in 0..17 {
+            if nibble == 0 || nibble == 5 {
+                node.push(0xa0);
+                node.extend_from_slice(B256::repeat_byte(nibble).as_slice());
+            } else {
+                node.push(0x80);
+            }
+        }
+
+        assert_eq!(branch_node_sibling(&node, 0), Some(5));
+        assert_eq!(branch_node_sibling(&node, 5), Some(0));
+        assert_eq!(branch_node_sibling(&node, 3), None);
+
+        let subtree = BTreeMap::from([(Nibbles::from_nibbles([0x1]), Bytes::from(node))]);
+        let mut key = B256::ZERO;
+        key[0] = 0x10;
+        let mut sibling = B256::ZERO;
+        sibling[0] = 0x15;
+        assert_eq!(collapsible_siblings(&subtree, key), vec![sibling]);
+    }
+}
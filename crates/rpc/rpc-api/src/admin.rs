@@ -1,13 +1,13 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_network_types::AnyNode;
 use reth_primitives::NodeRecord;
-use reth_rpc_types::{admin::NodeInfo, PeerInfo};
+use reth_rpc_types::{AdminNodeInfo, AdminPeerInfo};
 
 /// Admin namespace rpc interface that gives access to several non-standard RPC methods.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
 #[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
 pub trait AdminApi {
-    /// Adds the given node record to the peerset.
+    /// Adds the given node record to the peerset and dials it.
     #[method(name = "addPeer")]
     fn add_peer(&self, record: NodeRecord) -> RpcResult<bool>;
 
@@ -18,6 +18,8 @@ pub trait AdminApi {
     fn remove_peer(&self, record: AnyNode) -> RpcResult<bool>;
 
     /// Adds the given node record to the trusted peerset.
+    ///
+    /// Trusted peers can always connect, even if all inbound slots are in use.
     #[method(name = "addTrustedPeer")]
     fn add_trusted_peer(&self, record: AnyNode) -> RpcResult<bool>;
 
@@ -32,8 +34,11 @@ pub trait AdminApi {
     /// connected remote nodes at the networking granularity. These include general information
     /// about the nodes themselves as participants of the devp2p P2P overlay protocol, as well as
     /// specialized information added by each of the running application protocols
+    ///
+    /// In addition to the info geth returns, this includes the direction and duration of the
+    /// connection, whether the peer is trusted, its reputation and its `eth` status.
     #[method(name = "peers")]
-    async fn peers(&self) -> RpcResult<Vec<PeerInfo>>;
+    async fn peers(&self) -> RpcResult<Vec<AdminPeerInfo>>;
 
     /// Creates an RPC subscription which serves events received from the network.
     #[subscription(
@@ -43,7 +48,8 @@ pub trait AdminApi {
     )]
     async fn subscribe_peer_events(&self) -> jsonrpsee::core::SubscriptionResult;
 
-    /// Returns the ENR of the node.
+    /// Returns the info of the node, including the ENR it announces in discovery and the
+    /// configuration of the engine API.
     #[method(name = "nodeInfo")]
-    async fn node_info(&self) -> RpcResult<NodeInfo>;
+    async fn node_info(&self) -> RpcResult<AdminNodeInfo>;
 }
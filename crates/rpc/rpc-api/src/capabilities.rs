@@ -0,0 +1,141 @@
+//! Capabilities of the engine API
+//!
+//! See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/common.md#capabilities>
+
+use std::sync::{Arc, OnceLock};
+
+/// The name of the `engine_exchangeCapabilities` method, which is not part of its own response.
+pub const EXCHANGE_CAPABILITIES_METHOD: &str = "engine_exchangeCapabilities";
+
+/// Error code of the engine API if a method isn't supported for the fork of the payload, see also
+/// <https://github.com/ethereum/execution-apis/blob/main/src/engine/common.md#errors>
+pub const UNSUPPORTED_FORK_CODE: i32 = -38005;
+
+/// A method of the engine API specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EngineApiMethod {
+    /// The name of the method, e.g. `engine_newPayloadV3`.
+    pub name: &'static str,
+    /// The minimum number of params of the method.
+    pub min_params: usize,
+    /// The maximum number of params of the method.
+    pub max_params: usize,
+}
+
+impl EngineApiMethod {
+    /// Creates a new [`EngineApiMethod`] with the given number of params.
+    const fn new(name: &'static str, min_params: usize, max_params: usize) -> Self {
+        Self { name, min_params, max_params }
+    }
+
+    /// Returns `true` if the method accepts the given number of params.
+    pub const fn accepts_params(&self, params: usize) -> bool {
+        self.min_params <= params && params <= self.max_params
+    }
+}
+
+/// All methods of the engine API specification, including versions that are not implemented.
+///
+/// A consensus client may call any of these methods, so the auth server answers the methods it
+/// doesn't implement with an unsupported fork error rather than method not found.
+pub const ENGINE_API_METHODS: &[EngineApiMethod] = &[
+    EngineApiMethod::new("engine_newPayloadV1", 1, 1),
+    EngineApiMethod::new("engine_newPayloadV2", 1, 1),
+    EngineApiMethod::new("engine_newPayloadV3", 3, 3),
+    EngineApiMethod::new("engine_newPayloadV4", 3, 4),
+    EngineApiMethod::new("engine_newPayloadV5", 4, 4),
+    EngineApiMethod::new("engine_forkchoiceUpdatedV1", 1, 2),
+    EngineApiMethod::new("engine_forkchoiceUpdatedV2", 1, 2),
+    EngineApiMethod::new("engine_forkchoiceUpdatedV3", 1, 2),
+    EngineApiMethod::new("engine_forkchoiceUpdatedV4", 1, 2),
+    EngineApiMethod::new("engine_getPayloadV1", 1, 1),
+    EngineApiMethod::new("engine_getPayloadV2", 1, 1),
+    EngineApiMethod::new("engine_getPayloadV3", 1, 1),
+    EngineApiMethod::new("engine_getPayloadV4", 1, 1),
+    EngineApiMethod::new("engine_getPayloadV5", 1, 1),
+    EngineApiMethod::new("engine_getPayloadBodiesByHashV1", 1, 1),
+    EngineApiMethod::new("engine_getPayloadBodiesByHashV2", 1, 1),
+    EngineApiMethod::new("engine_getPayloadBodiesByRangeV1", 2, 2),
+    EngineApiMethod::new("engine_getPayloadBodiesByRangeV2", 2, 2),
+    EngineApiMethod::new("engine_getBlobsV1", 1, 1),
+    EngineApiMethod::new("engine_getBlobsV2", 1, 1),
+    EngineApiMethod::new("engine_exchangeTransitionConfigurationV1", 1, 1),
+    EngineApiMethod::new("engine_getClientVersionV1", 1, 1),
+    EngineApiMethod::new(EXCHANGE_CAPABILITIES_METHOD, 1, 1),
+];
+
+/// Returns the [`EngineApiMethod`] of the specification with the given name.
+pub fn engine_api_method(name: &str) -> Option<EngineApiMethod> {
+    ENGINE_API_METHODS.iter().find(|method| method.name == name).copied()
+}
+
+/// The engine API methods that are served by the auth server.
+///
+/// This is shared between the `engine_exchangeCapabilities` handler and the auth server, which
+/// sets the methods it serves once it is started.
+#[derive(Debug, Clone, Default)]
+pub struct EngineCapabilities {
+    registered: Arc<OnceLock<Vec<String>>>,
+}
+
+impl EngineCapabilities {
+    /// Sets the methods that are registered on the auth server.
+    ///
+    /// Only methods of the `engine_` namespace are kept, except for
+    /// [`EXCHANGE_CAPABILITIES_METHOD`].
+    ///
+    /// Returns `false` if the methods were already set.
+    pub fn set_registered_methods<'a>(&self, methods: impl IntoIterator<Item = &'a str>) -> bool {
+        let mut methods = methods
+            .into_iter()
+            .filter(|method| {
+                method.starts_with("engine_") && *method != EXCHANGE_CAPABILITIES_METHOD
+            })
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        methods.sort_unstable();
+        methods.dedup();
+        self.registered.set(methods).is_ok()
+    }
+
+    /// Returns the methods that are registered on the auth server, if it was started.
+    pub fn registered_methods(&self) -> Option<&[String]> {
+        self.registered.get().map(Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_engine_methods() {
+        let capabilities = EngineCapabilities::default();
+        assert_eq!(capabilities.registered_methods(), None);
+
+        assert!(capabilities.set_registered_methods([
+            "engine_newPayloadV3",
+            "eth_chainId",
+            EXCHANGE_CAPABILITIES_METHOD,
+            "engine_forkchoiceUpdatedV3",
+        ]));
+        // shared between clones, and only set once
+        assert!(!capabilities.clone().set_registered_methods(["engine_newPayloadV1"]));
+        assert_eq!(
+            capabilities.registered_methods(),
+            Some(
+                &["engine_forkchoiceUpdatedV3".to_string(), "engine_newPayloadV3".to_string()][..]
+            )
+        );
+    }
+
+    #[test]
+    fn spec_methods() {
+        let method = engine_api_method("engine_forkchoiceUpdatedV3").unwrap();
+        assert!(!method.accepts_params(0));
+        assert!(method.accepts_params(1));
+        assert!(method.accepts_params(2));
+        assert!(!method.accepts_params(3));
+        assert_eq!(engine_api_method("eth_chainId"), None);
+    }
+}
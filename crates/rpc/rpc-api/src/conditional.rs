@@ -0,0 +1,18 @@
+//! Additional `eth_` function for transactions with inclusion conditions.
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_primitives::{Bytes, TransactionConditional, B256};
+
+/// Rpc Interface for submitting transactions that are only included if their conditions hold.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "eth"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "eth"))]
+pub trait EthConditionalApi {
+    /// Sends a signed transaction that is only included in a block if the given conditions hold
+    /// for that block, and returns its hash.
+    #[method(name = "sendRawTransactionConditional")]
+    async fn send_raw_transaction_conditional(
+        &self,
+        bytes: Bytes,
+        conditional: TransactionConditional,
+    ) -> RpcResult<B256>;
+}
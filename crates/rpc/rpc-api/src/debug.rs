@@ -1,11 +1,9 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256};
+use reth_primitives::{trie::ExecutionWitness, Address, BlockId, BlockNumberOrTag, Bytes, B256};
 use reth_rpc_types::{
-    trace::geth::{
-        BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
-        TraceResult,
-    },
-    Bundle, RichBlock, StateContext, TransactionRequest,
+    trace::geth::{GethDebugTracingCallOptions, GethDebugTracingOptions},
+    Bundle, DebugTrace, DebugTraceResult, ReplayedTransaction, RichBlock, StateContext,
+    StorageRangeResult, TraceCallOptions, TraceChainNotification, TransactionRequest,
 };
 
 /// Debug rpc interface.
@@ -38,14 +36,23 @@ pub trait DebugApi {
     #[method(name = "getBadBlocks")]
     async fn bad_blocks(&self) -> RpcResult<Vec<RichBlock>>;
 
-    /// Returns the structured logs created during the execution of EVM between two blocks
-    /// (excluding start) as a JSON object.
-    #[method(name = "traceChain")]
+    /// Creates a subscription that traces the blocks between two blocks (excluding start), see
+    /// [GethDebugTracingOptions].
+    ///
+    /// The blocks are traced one after another and every block is sent in its own notification,
+    /// followed by a notification that marks the end of the range or the error that stopped the
+    /// tracing, see [TraceChainNotification].
+    #[subscription(
+        name = "traceChain",
+        unsubscribe = "unsubscribeTraceChain",
+        item = TraceChainNotification
+    )]
     async fn debug_trace_chain(
         &self,
         start_exclusive: BlockNumberOrTag,
         end_inclusive: BlockNumberOrTag,
-    ) -> RpcResult<Vec<BlockTraceResult>>;
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult;
 
     /// The `debug_traceBlock` method will return a full stack trace of all invoked opcodes of all
     /// transaction that were included in this block.
@@ -59,7 +66,7 @@ pub trait DebugApi {
         &self,
         rlp_block: Bytes,
         opts: Option<GethDebugTracingOptions>,
-    ) -> RpcResult<Vec<TraceResult>>;
+    ) -> RpcResult<Vec<DebugTraceResult>>;
 
     /// Similar to `debug_traceBlock`, `debug_traceBlockByHash` accepts a block hash and will replay
     /// the block that is already present in the database. For the second parameter see
@@ -69,7 +76,7 @@ pub trait DebugApi {
         &self,
         block: B256,
         opts: Option<GethDebugTracingOptions>,
-    ) -> RpcResult<Vec<TraceResult>>;
+    ) -> RpcResult<Vec<DebugTraceResult>>;
 
     /// Similar to `debug_traceBlockByHash`, `debug_traceBlockByNumber` accepts a block number
     /// [BlockNumberOrTag] and will replay the block that is already present in the database.
@@ -79,7 +86,7 @@ pub trait DebugApi {
         &self,
         block: BlockNumberOrTag,
         opts: Option<GethDebugTracingOptions>,
-    ) -> RpcResult<Vec<TraceResult>>;
+    ) -> RpcResult<Vec<DebugTraceResult>>;
 
     /// The `debug_traceTransaction` debugging method will attempt to run the transaction in the
     /// exact same manner as it was executed on the network. It will replay any transaction that
@@ -90,7 +97,7 @@ pub trait DebugApi {
         &self,
         tx_hash: B256,
         opts: Option<GethDebugTracingOptions>,
-    ) -> RpcResult<GethTrace>;
+    ) -> RpcResult<DebugTrace>;
 
     /// The `debug_traceCall` method lets you run an `eth_call` within the context of the given
     /// block execution using the final state of parent block as the base.
@@ -101,13 +108,16 @@ pub trait DebugApi {
     /// The trace can be configured similar to `debug_traceTransaction`,
     /// see [GethDebugTracingOptions]. The method returns the same output as
     /// `debug_traceTransaction`.
+    ///
+    /// With `txIndex`, the call is traced on top of the state of the parent block followed by the
+    /// first `txIndex` transactions of the block, see [TraceCallOptions].
     #[method(name = "traceCall")]
     async fn debug_trace_call(
         &self,
         request: TransactionRequest,
         block_number: Option<BlockId>,
-        opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<GethTrace>;
+        opts: Option<TraceCallOptions>,
+    ) -> RpcResult<DebugTrace>;
 
     /// The `debug_traceCallMany` method lets you run an `eth_callMany` within the context of the
     /// given block execution using the final state of parent block as the base followed by n
@@ -123,14 +133,28 @@ pub trait DebugApi {
     ///
     /// This methods is similar to many `eth_callMany`, hence this returns nested lists of traces.
     /// Where the length of the outer list is the number of bundles and the length of the inner list
-    /// (`Vec<GethTrace>`) is the number of transactions in the bundle.
+    /// (`Vec<DebugTrace>`) is the number of transactions in the bundle.
     #[method(name = "traceCallMany")]
     async fn debug_trace_call_many(
         &self,
         bundles: Vec<Bundle>,
         state_context: Option<StateContext>,
         opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<Vec<Vec<GethTrace>>>;
+    ) -> RpcResult<Vec<Vec<DebugTrace>>>;
+
+    /// The `debug_executionWitness` method re-executes the given block on top of its parent state
+    /// and returns the witness of all the state accessed during the execution: the state and
+    /// storage trie nodes proving the accessed accounts and slots against the parent state root,
+    /// the executed bytecodes, the preimages of the hashed keys and the accessed ancestor block
+    /// hashes.
+    #[method(name = "executionWitness")]
+    async fn debug_execution_witness(&self, block: BlockId) -> RpcResult<ExecutionWitness>;
+
+    /// The `debug_replayTransaction` method re-executes the given transaction on top of the exact
+    /// state it was executed on, i.e. after the system calls and the preceding transactions of its
+    /// block, and returns its result and the state changes it made.
+    #[method(name = "replayTransaction")]
+    async fn debug_replay_transaction(&self, tx_hash: B256) -> RpcResult<ReplayedTransaction>;
 
     /// Sets the logging backtrace location. When a backtrace location is set and a log message is
     /// emitted at that location, the stack of the goroutine executing the log statement will
@@ -332,9 +356,10 @@ pub trait DebugApi {
     #[method(name = "stopGoTrace")]
     async fn debug_stop_go_trace(&self) -> RpcResult<()>;
 
-    /// Returns the storage at the given block height and transaction index. The result can be
-    /// paged by providing a `maxResult` to cap the number of storage slots returned as well as
-    /// specifying the offset via `keyStart` (hash of storage key).
+    /// Returns the storage of the contract after the first `tx_idx` transactions of the given
+    /// block, ordered by hashed storage slot. The result can be paged by providing a `maxResult`
+    /// to cap the number of storage slots returned as well as specifying the offset via `keyStart`
+    /// (hash of storage key), the `nextKey` of a page is the `keyStart` of the next one.
     #[method(name = "storageRangeAt")]
     async fn debug_storage_range_at(
         &self,
@@ -343,7 +368,7 @@ pub trait DebugApi {
         contract_address: Address,
         key_start: B256,
         max_result: u64,
-    ) -> RpcResult<()>;
+    ) -> RpcResult<StorageRangeResult>;
 
     /// Returns the structured logs created during the execution of EVM against a block pulled
     /// from the pool of bad ones and returns them as a JSON object. For the second parameter see
@@ -1,10 +1,12 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256, B64, U256, U64};
+use reth_primitives::{
+    Address, BlobTransactionSidecar, BlockId, BlockNumberOrTag, Bytes, B256, B64, U256, U64,
+};
 use reth_rpc_types::{
     serde_helpers::JsonStorageKey, state::StateOverride, AccessListWithGasUsed,
-    AnyTransactionReceipt, BlockOverrides, Bundle, EIP1186AccountProofResponse, EthCallResponse,
-    FeeHistory, Header, Index, RichBlock, StateContext, SyncStatus, Transaction,
-    TransactionRequest, Work,
+    AnyTransactionReceipt, BlockOverrides, Bundle, EIP1186AccountProofResponse, EthAccount,
+    EthAccountInfo, EthCallManyResponse, FeeHistory, Header, Index, RichBlock, SimulatePayload,
+    SimulatedBlock, StateContext, SyncStatus, Transaction, TransactionRequest, Work,
 };
 
 /// Eth rpc interface: <https://ethereum.github.io/execution-apis/api-documentation/>
@@ -94,14 +96,33 @@ pub trait EthApi {
 
     /// Returns the EIP-2718 encoded transaction if it exists.
     ///
-    /// If this is a EIP-4844 transaction that is in the pool it will include the sidecar.
+    /// This is the canonical encoding, so the blob sidecar of a pooled EIP-4844 transaction is not
+    /// included, see `eth_getBlobSidecarByTxHash`.
     #[method(name = "getRawTransactionByHash")]
     async fn raw_transaction_by_hash(&self, hash: B256) -> RpcResult<Option<Bytes>>;
 
+    /// Returns the blob sidecar of the EIP-4844 transaction if it is still in the blob store.
+    ///
+    /// Sidecars are kept until the block that includes the transaction is finalized.
+    #[method(name = "getBlobSidecarByTxHash")]
+    async fn blob_sidecar_by_tx_hash(
+        &self,
+        hash: B256,
+    ) -> RpcResult<Option<BlobTransactionSidecar>>;
+
     /// Returns the information about a transaction requested by transaction hash.
     #[method(name = "getTransactionByHash")]
     async fn transaction_by_hash(&self, hash: B256) -> RpcResult<Option<Transaction>>;
 
+    /// Returns the transaction of the sender with the given nonce, either pending in the pool or
+    /// mined.
+    #[method(name = "getTransactionBySenderAndNonce")]
+    async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: U64,
+    ) -> RpcResult<Option<Transaction>>;
+
     /// Returns information about a raw transaction by block hash and transaction index position.
     #[method(name = "getRawTransactionByBlockHashAndIndex")]
     async fn raw_transaction_by_block_hash_and_index(
@@ -183,14 +204,26 @@ pub trait EthApi {
     ) -> RpcResult<Bytes>;
 
     /// Simulate arbitrary number of transactions at an arbitrary blockchain index, with the
-    /// optionality of state overrides
+    /// optionality of state overrides.
+    ///
+    /// The bundles are executed in order, and every call sees the state changes of the previous
+    /// calls. Returns the results of the calls grouped by bundle.
     #[method(name = "callMany")]
     async fn call_many(
         &self,
-        bundle: Bundle,
+        bundles: Vec<Bundle>,
         state_context: Option<StateContext>,
         state_override: Option<StateOverride>,
-    ) -> RpcResult<Vec<EthCallResponse>>;
+    ) -> RpcResult<Vec<Vec<EthCallManyResponse>>>;
+
+    /// Simulates a sequence of blocks of calls on top of the given block, with optional block and
+    /// state overrides per block.
+    #[method(name = "simulateV1")]
+    async fn simulate_v1(
+        &self,
+        opts: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<Vec<SimulatedBlock>>;
 
     /// Generates an access list for a transaction.
     ///
@@ -206,11 +239,15 @@ pub trait EthApi {
     /// when the transaction is actually mined. Adding an accessList to your transaction does
     /// not necessary result in lower gas usage compared to a transaction without an access
     /// list.
+    ///
+    /// The sender, the recipient and the precompiles are only listed if their storage is
+    /// accessed.
     #[method(name = "createAccessList")]
     async fn create_access_list(
         &self,
         request: TransactionRequest,
         block_number: Option<BlockId>,
+        state_override: Option<StateOverride>,
     ) -> RpcResult<AccessListWithGasUsed>;
 
     /// Generates and returns an estimate of how much gas is necessary to allow the transaction to
@@ -221,6 +258,7 @@ pub trait EthApi {
         request: TransactionRequest,
         block_number: Option<BlockId>,
         state_override: Option<StateOverride>,
+        block_override: Option<Box<BlockOverrides>>,
     ) -> RpcResult<U256>;
 
     /// Returns the current price per gas in wei.
@@ -284,6 +322,18 @@ pub trait EthApi {
     #[method(name = "sendRawTransaction")]
     async fn send_raw_transaction(&self, bytes: Bytes) -> RpcResult<B256>;
 
+    /// Sends signed transaction and waits until it is included in a canonical block, returning
+    /// its receipt.
+    ///
+    /// Waits at most `timeout_ms` milliseconds, or the configured default if not specified. The
+    /// timeout is capped by the server.
+    #[method(name = "sendRawTransactionSync")]
+    async fn send_raw_transaction_sync(
+        &self,
+        bytes: Bytes,
+        timeout_ms: Option<u64>,
+    ) -> RpcResult<AnyTransactionReceipt>;
+
     /// Returns an Ethereum specific signature with: sign(keccak256("\x19Ethereum Signed Message:\n"
     /// + len(message) + message))).
     #[method(name = "sign")]
@@ -307,4 +357,20 @@ pub trait EthApi {
         keys: Vec<JsonStorageKey>,
         block_number: Option<BlockId>,
     ) -> RpcResult<EIP1186AccountProofResponse>;
+
+    /// Returns the balance, nonce, code hash and storage root of the account at the given block.
+    #[method(name = "getAccount")]
+    async fn get_account(
+        &self,
+        address: Address,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<EthAccount>;
+
+    /// Returns the balance, nonce and code of the account at the given block.
+    #[method(name = "getAccountInfo")]
+    async fn get_account_info(
+        &self,
+        address: Address,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<EthAccountInfo>;
 }
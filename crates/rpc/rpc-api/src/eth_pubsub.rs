@@ -1,10 +1,13 @@
 use jsonrpsee::proc_macros::rpc;
-use reth_rpc_types::pubsub::{Params, SubscriptionKind};
+use reth_rpc_types::{pubsub::SubscriptionKind, SubscriptionParams};
 
 /// Ethereum pub-sub rpc interface.
 #[rpc(server, namespace = "eth")]
 pub trait EthPubSubApi {
     /// Create an ethereum subscription for the given params
+    ///
+    /// Object params may override the buffer settings of the subscription with a `backpressure`
+    /// field, see [`SubscriptionParams`].
     #[subscription(
         name = "subscribe" => "subscription",
         unsubscribe = "unsubscribe",
@@ -13,6 +16,6 @@ pub trait EthPubSubApi {
     async fn subscribe(
         &self,
         kind: SubscriptionKind,
-        params: Option<Params>,
+        params: Option<SubscriptionParams>,
     ) -> jsonrpsee::core::SubscriptionResult;
 }
@@ -17,6 +17,8 @@
 mod admin;
 mod anvil;
 mod bundle;
+mod capabilities;
+mod conditional;
 mod debug;
 mod engine;
 mod eth;
@@ -29,12 +31,18 @@ mod net;
 mod optimism;
 mod otterscan;
 mod reth;
+mod reth_pubsub;
 mod rpc;
 mod trace;
 mod txpool;
 mod validation;
 mod web3;
 
+pub use capabilities::{
+    engine_api_method, EngineApiMethod, EngineCapabilities, ENGINE_API_METHODS,
+    EXCHANGE_CAPABILITIES_METHOD, UNSUPPORTED_FORK_CODE,
+};
+
 /// re-export of all server traits
 pub use servers::*;
 
@@ -43,6 +51,7 @@ pub mod servers {
     pub use crate::{
         admin::AdminApiServer,
         bundle::{EthBundleApiServer, EthCallBundleApiServer},
+        conditional::EthConditionalApiServer,
         debug::DebugApiServer,
         engine::{EngineApiServer, EngineEthApiServer},
         eth::EthApiServer,
@@ -52,6 +61,7 @@ pub mod servers {
         net::NetApiServer,
         otterscan::OtterscanServer,
         reth::RethApiServer,
+        reth_pubsub::{RethPayloadEventsApiServer, RethPubSubApiServer},
         rpc::RpcApiServer,
         trace::TraceApiServer,
         txpool::TxPoolApiServer,
@@ -71,6 +81,7 @@ pub mod clients {
         admin::AdminApiClient,
         anvil::AnvilApiClient,
         bundle::{EthBundleApiClient, EthCallBundleApiClient},
+        conditional::EthConditionalApiClient,
         debug::DebugApiClient,
         engine::{EngineApiClient, EngineEthApiClient},
         eth::EthApiClient,
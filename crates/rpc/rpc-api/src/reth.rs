@@ -1,5 +1,7 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::{Address, BlockId, U256};
+use reth_primitives::{Address, BlockId, Bytes, U256, U64};
+use reth_rpc_types::{TxpoolDumpResult, TxpoolRestoreResult};
+use reth_storage_api::{RawStaticFileRow, RawTableValue};
 use std::collections::HashMap;
 
 /// Reth API namespace for reth-specific methods
@@ -12,4 +14,36 @@ pub trait RethApi {
         &self,
         block_id: BlockId,
     ) -> RpcResult<HashMap<Address, U256>>;
+
+    /// Returns the value stored under the encoded key in the database table with the given name,
+    /// for tables with duplicate keys the first value of the key.
+    ///
+    /// Only available if the node was started with `--rpc.unsafe-debug`.
+    #[method(name = "dbGet")]
+    async fn reth_db_get(&self, table: String, key: Bytes) -> RpcResult<Option<RawTableValue>>;
+
+    /// Returns the row of the static file segment (`headers`, `transactions` or `receipts`) with
+    /// the given block or transaction number.
+    ///
+    /// Only available if the node was started with `--rpc.unsafe-debug`.
+    #[method(name = "staticFileGet")]
+    async fn reth_static_file_get(
+        &self,
+        segment: String,
+        number: U64,
+    ) -> RpcResult<Option<RawStaticFileRow>>;
+
+    /// Writes a snapshot of all transactions in the pool, including the sidecars of blob
+    /// transactions, to the file with the given name in the snapshot directory of the node.
+    ///
+    /// Only available if the node was started with `--rpc.unsafe-debug`.
+    #[method(name = "txpoolDump")]
+    async fn reth_txpool_dump(&self, name: String) -> RpcResult<TxpoolDumpResult>;
+
+    /// Re-validates and inserts the transactions of the snapshot with the given name in the
+    /// snapshot directory of the node, skipping transactions that are no longer valid.
+    ///
+    /// Only available if the node was started with `--rpc.unsafe-debug`.
+    #[method(name = "txpoolRestore")]
+    async fn reth_txpool_restore(&self, name: String) -> RpcResult<TxpoolRestoreResult>;
 }
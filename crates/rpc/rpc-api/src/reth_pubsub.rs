@@ -0,0 +1,33 @@
+use jsonrpsee::proc_macros::rpc;
+
+/// Reth pub-sub rpc interface for reth-specific subscriptions.
+#[rpc(server, namespace = "reth")]
+pub trait RethPubSubApi {
+    /// Creates a subscription that emits the removed and added blocks of every chain reorg.
+    ///
+    /// Deep reorgs are split into multiple notifications, see
+    /// [`ChainReorg`](reth_rpc_types::ChainReorg). If the subscriber falls behind, the
+    /// subscription is closed with an error rather than skipping reorgs.
+    #[subscription(
+        name = "subscribeChainReorgs" => "chainReorgs",
+        unsubscribe = "unsubscribeChainReorgs",
+        item = reth_rpc_types::ChainReorg
+    )]
+    async fn subscribe_chain_reorgs(&self) -> jsonrpsee::core::SubscriptionResult;
+}
+
+/// Reth pub-sub rpc interface for the payload builder, which is only served by the authenticated
+/// server.
+#[rpc(server, namespace = "reth")]
+pub trait RethPayloadEventsApi {
+    /// Creates a subscription that emits the attributes of every payload the CL requests and the
+    /// best payload of every payload job, once it is resolved or ends.
+    ///
+    /// Events are dropped if the subscriber falls behind.
+    #[subscription(
+        name = "subscribePayloadEvents" => "payloadEvents",
+        unsubscribe = "unsubscribePayloadEvents",
+        item = reth_rpc_types::PayloadEvent
+    )]
+    async fn subscribe_payload_events(&self) -> jsonrpsee::core::SubscriptionResult;
+}
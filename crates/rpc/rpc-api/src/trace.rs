@@ -7,7 +7,8 @@ use reth_rpc_types::{
         opcode::{BlockOpcodeGas, TransactionOpcodeGas},
         parity::*,
     },
-    BlockOverrides, Index, TransactionRequest,
+    BlockOverrides, Index, LimitedTraceResults, LimitedTraceResultsWithTransactionHash,
+    TransactionRequest,
 };
 use std::collections::HashSet;
 
@@ -24,7 +25,7 @@ pub trait TraceApi {
         block_id: Option<BlockId>,
         state_overrides: Option<StateOverride>,
         block_overrides: Option<Box<BlockOverrides>>,
-    ) -> RpcResult<TraceResults>;
+    ) -> RpcResult<LimitedTraceResults>;
 
     /// Performs multiple call traces on top of the same block. i.e. transaction n will be executed
     /// on top of a pending block with all n-1 transactions applied (traced) first. Allows to trace
@@ -34,7 +35,7 @@ pub trait TraceApi {
         &self,
         calls: Vec<(TransactionRequest, HashSet<TraceType>)>,
         block_id: Option<BlockId>,
-    ) -> RpcResult<Vec<TraceResults>>;
+    ) -> RpcResult<Vec<LimitedTraceResults>>;
 
     /// Traces a call to `eth_sendRawTransaction` without making the call, returning the traces.
     ///
@@ -45,23 +46,27 @@ pub trait TraceApi {
         data: Bytes,
         trace_types: HashSet<TraceType>,
         block_id: Option<BlockId>,
-    ) -> RpcResult<TraceResults>;
+    ) -> RpcResult<LimitedTraceResults>;
 
     /// Replays all transactions in a block returning the requested traces for each transaction.
+    ///
+    /// The `vmTrace` of a transaction is cut off at the limits of the node.
     #[method(name = "replayBlockTransactions")]
     async fn replay_block_transactions(
         &self,
         block_id: BlockId,
         trace_types: HashSet<TraceType>,
-    ) -> RpcResult<Option<Vec<TraceResultsWithTransactionHash>>>;
+    ) -> RpcResult<Option<Vec<LimitedTraceResultsWithTransactionHash>>>;
 
     /// Replays a transaction, returning the traces.
+    ///
+    /// The `vmTrace` is cut off at the limits of the node.
     #[method(name = "replayTransaction")]
     async fn replay_transaction(
         &self,
         transaction: B256,
         trace_types: HashSet<TraceType>,
-    ) -> RpcResult<TraceResults>;
+    ) -> RpcResult<LimitedTraceResults>;
 
     /// Returns traces created at given block.
     #[method(name = "block")]
@@ -76,12 +81,10 @@ pub trait TraceApi {
     #[method(name = "filter")]
     async fn trace_filter(&self, filter: TraceFilter) -> RpcResult<Vec<LocalizedTransactionTrace>>;
 
-    /// Returns transaction trace at given index.
-    ///
-    /// `indices` represent the index positions of the traces.
+    /// Returns the transaction trace at the given position in the call tree of the transaction.
     ///
-    /// Note: This expects a list of indices but only one is supported since this function returns a
-    /// single [LocalizedTransactionTrace].
+    /// `indices` is the `traceAddress` of the trace: `[]` is the top-level call, `[0]` its first
+    /// subcall and `[0, 1]` the second subcall of that one.
     #[method(name = "get")]
     async fn trace_get(
         &self,
@@ -1,6 +1,9 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::Address;
-use reth_rpc_types::txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus};
+use reth_rpc_types::{
+    txpool::TxpoolContentFrom, GethTxpoolInspect, TxpoolContentExt, TxpoolContentFilter,
+    TxpoolStatusExt,
+};
 
 /// Txpool rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "txpool"))]
@@ -9,16 +12,21 @@ pub trait TxPoolApi {
     /// Returns the number of transactions currently pending for inclusion in the next block(s), as
     /// well as the ones that are being scheduled for future execution only.
     ///
+    /// In addition to geth, this returns the number of blob transactions that are parked until the
+    /// blob fee drops.
+    ///
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_status) for more details
     #[method(name = "status")]
-    async fn txpool_status(&self) -> RpcResult<TxpoolStatus>;
+    async fn txpool_status(&self) -> RpcResult<TxpoolStatusExt>;
 
     /// Returns a summary of all the transactions currently pending for inclusion in the next
     /// block(s), as well as the ones that are being scheduled for future execution only.
     ///
+    /// The summaries are formatted exactly as geth does.
+    ///
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_inspect) for more details
     #[method(name = "inspect")]
-    async fn txpool_inspect(&self) -> RpcResult<TxpoolInspect>;
+    async fn txpool_inspect(&self) -> RpcResult<GethTxpoolInspect>;
 
     /// Retrieves the transactions contained within the txpool, returning pending as well as queued
     /// transactions of this address, grouped by nonce.
@@ -30,7 +38,13 @@ pub trait TxPoolApi {
     /// Returns the details of all transactions currently pending for inclusion in the next
     /// block(s), as well as the ones that are being scheduled for future execution only.
     ///
+    /// In addition to geth, the transactions can be filtered by origin, minimum tip, senders and
+    /// recipients, and be paginated with a limit and the returned continuation token.
+    ///
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content) for more details
     #[method(name = "content")]
-    async fn txpool_content(&self) -> RpcResult<TxpoolContent>;
+    async fn txpool_content(
+        &self,
+        filter: Option<TxpoolContentFilter>,
+    ) -> RpcResult<TxpoolContentExt>;
 }
@@ -1,8 +1,9 @@
 //! API for block submission validation.
 
 use jsonrpsee::proc_macros::rpc;
-use reth_rpc_types::beacon::relay::{
-    BuilderBlockValidationRequest, BuilderBlockValidationRequestV2,
+use reth_rpc_types::{
+    beacon::relay::{BuilderBlockValidationRequest, BuilderBlockValidationRequestV2},
+    BuilderBlockValidationRequestV3, BuilderBlockValidationRequestV4,
 };
 
 /// Block validation rpc interface.
@@ -22,4 +23,23 @@ pub trait BlockSubmissionValidationApi {
         &self,
         request: BuilderBlockValidationRequestV2,
     ) -> jsonrpsee::core::RpcResult<()>;
+
+    /// A Request to validate a Deneb block submission.
+    ///
+    /// The payload is executed on top of its parent state, the submission is valid if the block is
+    /// valid, matches the bid, pays the bid value to the proposer and commits to the given blobs.
+    #[method(name = "validateBuilderSubmissionV3")]
+    async fn validate_builder_submission_v3(
+        &self,
+        request: BuilderBlockValidationRequestV3,
+    ) -> jsonrpsee::core::RpcResult<()>;
+
+    /// A Request to validate an Electra block submission.
+    ///
+    /// See also [`Self::validate_builder_submission_v3`].
+    #[method(name = "validateBuilderSubmissionV4")]
+    async fn validate_builder_submission_v4(
+        &self,
+        request: BuilderBlockValidationRequestV4,
+    ) -> jsonrpsee::core::RpcResult<()>;
 }
@@ -5,18 +5,25 @@ use jsonrpsee::{
     core::RegisterMethodError,
     http_client::{transport::HttpBackend, HeaderMap},
     server::{AlreadyStoppedError, RpcModule},
+    types::{error::INVALID_PARAMS_CODE, ErrorObject, ErrorObjectOwned, Params},
     Methods,
 };
 use reth_engine_primitives::EngineTypes;
 pub use reth_ipc::server::Builder as IpcServerBuilder;
 use reth_rpc::EthSubscriptionIdProvider;
-use reth_rpc_api::servers::*;
+use reth_rpc_api::{
+    servers::*, EngineApiMethod, EngineCapabilities, ENGINE_API_METHODS, UNSUPPORTED_FORK_CODE,
+};
 use reth_rpc_layer::{
     secret_to_bearer_header, AuthClientLayer, AuthClientService, AuthLayer, JwtAuthValidator,
     JwtSecret,
 };
 use reth_rpc_server_types::constants;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use serde::de::IgnoredAny;
+use std::{
+    collections::HashSet,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+};
 use tower::layer::util::Identity;
 
 /// Server configuration for the auth server.
@@ -48,9 +55,11 @@ impl AuthServerConfig {
     }
 
     /// Convenience function to start a server in one step.
-    pub async fn start(self, module: AuthRpcModule) -> Result<AuthServerHandle, RpcError> {
+    pub async fn start(self, mut module: AuthRpcModule) -> Result<AuthServerHandle, RpcError> {
         let Self { socket_addr, secret, server_config, ipc_server_config, ipc_endpoint } = self;
 
+        module.register_unsupported_engine_methods();
+
         // Create auth middleware.
         let middleware =
             tower::ServiceBuilder::new().layer(AuthLayer::new(JwtAuthValidator::new(secret)));
@@ -189,6 +198,8 @@ impl AuthServerConfigBuilder {
 #[derive(Debug, Clone)]
 pub struct AuthRpcModule {
     pub(crate) inner: RpcModule<()>,
+    /// The engine API methods reported by `engine_exchangeCapabilities`.
+    pub(crate) engine_capabilities: EngineCapabilities,
 }
 
 // === impl AuthRpcModule ===
@@ -202,7 +213,35 @@ impl AuthRpcModule {
     {
         let mut module = RpcModule::new(());
         module.merge(engine.into_rpc()).expect("No conflicting methods");
-        Self { inner: module }
+        Self { inner: module, engine_capabilities: Default::default() }
+    }
+
+    /// Sets the [`EngineCapabilities`] of the engine API handler.
+    ///
+    /// The `engine_` methods that are registered once the server is started are reported to the
+    /// handler, so `engine_exchangeCapabilities` reflects exactly the served methods.
+    pub fn set_engine_capabilities(&mut self, capabilities: EngineCapabilities) {
+        self.engine_capabilities = capabilities;
+    }
+
+    /// Reports the registered `engine_` methods to the [`EngineCapabilities`] and registers the
+    /// methods of the engine API specification that are not implemented.
+    ///
+    /// Consensus clients may call newer versions of engine API methods, these are answered with
+    /// `-38005: Unsupported fork` instead of method not found, or invalid params if the params
+    /// don't match the method.
+    fn register_unsupported_engine_methods(&mut self) {
+        let registered = self.inner.method_names().collect::<HashSet<_>>();
+        self.engine_capabilities.set_registered_methods(registered.iter().copied());
+
+        for method in ENGINE_API_METHODS.iter().filter(|method| !registered.contains(method.name)) {
+            let method = *method;
+            self.inner
+                .register_method(method.name, move |params, _| {
+                    unsupported_engine_method(method, params)
+                })
+                .expect("method is not registered");
+        }
     }
 
     /// Get a reference to the inner `RpcModule`.
@@ -229,6 +268,27 @@ impl AuthRpcModule {
     }
 }
 
+/// Handler for engine API methods that are not implemented.
+///
+/// Returns invalid params if the params don't match the method, and unsupported fork otherwise.
+fn unsupported_engine_method(
+    method: EngineApiMethod,
+    params: Params<'_>,
+) -> Result<(), ErrorObjectOwned> {
+    let len = if params.as_str().is_some() { params.parse::<Vec<IgnoredAny>>()?.len() } else { 0 };
+    if !method.accepts_params(len) {
+        return Err(ErrorObject::owned(
+            INVALID_PARAMS_CODE,
+            format!(
+                "{} expects {} to {} params, got {len}",
+                method.name, method.min_params, method.max_params
+            ),
+            None::<()>,
+        ))
+    }
+    Err(ErrorObject::owned(UNSUPPORTED_FORK_CODE, "Unsupported fork", None::<()>))
+}
+
 /// A handle to the spawned auth server.
 ///
 /// When this type is dropped or [`AuthServerHandle::stop`] has been called the server will be
@@ -0,0 +1,241 @@
+//! Limits on the number of RPC calls executed concurrently.
+//!
+//! Every connection may execute up to
+//! [`max_concurrent_calls_per_connection`](RpcCallLimits::max_concurrent_calls_per_connection)
+//! calls at the same time, for example the calls of a batch request, and the server executes up
+//! to [`max_batch_concurrency`](RpcCallLimits::max_batch_concurrency) calls across all
+//! connections. Calls above the limits wait in first-in-first-out order. A connection only
+//! competes for the server-wide limit with the calls it is allowed to execute, so a large batch
+//! request can't starve the calls of other connections.
+//!
+//! The calls of a connection are executed within the scope of its [`RpcConnectionId`], so
+//! handlers can limit the resources of a connection.
+
+use jsonrpsee::{
+    server::middleware::rpc::RpcServiceT,
+    types::{ErrorObject, Request},
+    MethodResponse,
+};
+use reth_rpc::RpcConnectionId;
+use reth_rpc_server_types::constants;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::Semaphore;
+use tower::Layer;
+
+/// The error code of calls that are rejected because too many calls of the connection are
+/// waiting to be executed.
+pub const CALL_QUEUE_FULL_CODE: i32 = -32005;
+
+/// Limits on the number of RPC calls executed concurrently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcCallLimits {
+    /// The maximum number of calls executed concurrently across all connections.
+    pub max_batch_concurrency: usize,
+    /// The maximum number of calls of a single connection executed concurrently.
+    pub max_concurrent_calls_per_connection: usize,
+    /// The maximum number of calls of a single connection waiting to be executed. Calls above
+    /// the limit are rejected with [`CALL_QUEUE_FULL_CODE`].
+    pub max_queued_calls_per_connection: usize,
+}
+
+impl Default for RpcCallLimits {
+    fn default() -> Self {
+        Self {
+            max_batch_concurrency: constants::DEFAULT_MAX_BATCH_CONCURRENCY,
+            max_concurrent_calls_per_connection:
+                constants::DEFAULT_MAX_CONCURRENT_CALLS_PER_CONNECTION,
+            max_queued_calls_per_connection: constants::DEFAULT_MAX_QUEUED_CALLS_PER_CONNECTION,
+        }
+    }
+}
+
+/// A [`Layer`] that enforces the [`RpcCallLimits`].
+///
+/// The server-wide limit is shared by all clones of the layer.
+#[derive(Debug, Clone)]
+pub(crate) struct RpcCallLimitLayer {
+    limits: RpcCallLimits,
+    /// Permits for the calls executed across all connections.
+    calls: Arc<Semaphore>,
+}
+
+impl RpcCallLimitLayer {
+    pub(crate) fn new(limits: RpcCallLimits) -> Self {
+        Self { limits, calls: Arc::new(Semaphore::new(permits(limits.max_batch_concurrency))) }
+    }
+}
+
+impl<S> Layer<S> for RpcCallLimitLayer {
+    type Service = RpcCallLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcCallLimitService {
+            inner,
+            calls: self.calls.clone(),
+            connection: Arc::new(ConnectionCalls {
+                id: RpcConnectionId::next(),
+                calls: Semaphore::new(permits(self.limits.max_concurrent_calls_per_connection)),
+                queued: AtomicUsize::new(0),
+                max_queued: self.limits.max_queued_calls_per_connection,
+            }),
+        }
+    }
+}
+
+/// The calls of a single connection.
+#[derive(Debug)]
+struct ConnectionCalls {
+    /// The id the calls of the connection are executed with.
+    id: RpcConnectionId,
+    /// Permits for the calls of the connection executed concurrently.
+    calls: Semaphore,
+    /// The number of calls waiting to be executed.
+    queued: AtomicUsize,
+    /// The maximum number of calls waiting to be executed.
+    max_queued: usize,
+}
+
+/// Decrements the number of queued calls of the connection when dropped.
+struct QueuedCall(Arc<ConnectionCalls>);
+
+impl Drop for QueuedCall {
+    fn drop(&mut self) {
+        self.0.queued.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// A [`RpcServiceT`] middleware that enforces the [`RpcCallLimits`].
+///
+/// This is created per connection.
+#[derive(Debug, Clone)]
+pub(crate) struct RpcCallLimitService<S> {
+    inner: S,
+    /// Permits for the calls executed across all connections.
+    calls: Arc<Semaphore>,
+    connection: Arc<ConnectionCalls>,
+}
+
+impl<'a, S> RpcServiceT<'a> for RpcCallLimitService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+    S::Future: Send + 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        let connection = self.connection.clone();
+        if connection.queued.fetch_add(1, Ordering::Relaxed) >= connection.max_queued {
+            connection.queued.fetch_sub(1, Ordering::Relaxed);
+            let err = ErrorObject::owned(
+                CALL_QUEUE_FULL_CODE,
+                "too many queued calls on this connection",
+                None::<()>,
+            );
+            return Box::pin(std::future::ready(MethodResponse::error(req.id, err)))
+        }
+
+        let queued = QueuedCall(connection);
+        let calls = self.calls.clone();
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            // the connection permit is acquired first, so a connection waits for at most
+            // `max_concurrent_calls_per_connection` of the server-wide permits
+            let _connection_permit =
+                queued.0.calls.acquire().await.expect("semaphore is never closed");
+            let _permit = calls.acquire().await.expect("semaphore is never closed");
+            let id = queued.0.id;
+            drop(queued);
+            id.scope(inner.call(req)).await
+        })
+    }
+}
+
+/// Returns the number of permits of a semaphore for the given limit.
+fn permits(limit: usize) -> usize {
+    limit.clamp(1, Semaphore::MAX_PERMITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::{types::Id, ResponsePayload};
+    use std::time::Duration;
+
+    /// A service that sleeps for the duration in milliseconds given as the method name.
+    #[derive(Clone)]
+    struct Sleep;
+
+    impl<'a> RpcServiceT<'a> for Sleep {
+        type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+        fn call(&self, req: Request<'a>) -> Self::Future {
+            Box::pin(async move {
+                let millis = req.method_name().parse().unwrap();
+                tokio::time::sleep(Duration::from_millis(millis)).await;
+                MethodResponse::response(req.id, ResponsePayload::success(()), usize::MAX)
+            })
+        }
+    }
+
+    fn request(millis: u64) -> Request<'static> {
+        Request::new(millis.to_string().into(), None, Id::Number(1))
+    }
+
+    #[tokio::test]
+    async fn rejects_calls_above_queue_depth() {
+        let layer = RpcCallLimitLayer::new(RpcCallLimits {
+            max_batch_concurrency: 10,
+            max_concurrent_calls_per_connection: 1,
+            max_queued_calls_per_connection: 1,
+        });
+        let service = layer.layer(Sleep);
+
+        let executing = tokio::spawn(service.call(request(100)));
+        let queued = tokio::spawn(service.call(request(0)));
+        tokio::task::yield_now().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let rejected = service.call(request(0)).await;
+        assert!(rejected.is_error());
+        assert!(rejected.as_result().contains(&CALL_QUEUE_FULL_CODE.to_string()));
+
+        assert!(executing.await.unwrap().is_success());
+        assert!(queued.await.unwrap().is_success());
+
+        // the queue is drained
+        assert!(service.call(request(0)).await.is_success());
+    }
+
+    #[tokio::test]
+    async fn batch_storm_does_not_starve_other_connections() {
+        let layer = RpcCallLimitLayer::new(RpcCallLimits {
+            max_batch_concurrency: 4,
+            max_concurrent_calls_per_connection: 2,
+            max_queued_calls_per_connection: 1_000,
+        });
+        let storm = layer.layer(Sleep);
+        let client = layer.layer(Sleep);
+
+        // 500 calls of 20ms, executed two at a time take about 5 seconds
+        let storm_calls =
+            (0..500).map(|_| tokio::spawn(storm.call(request(20)))).collect::<Vec<_>>();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        for _ in 0..5 {
+            let started_at = std::time::Instant::now();
+            assert!(client.call(request(20)).await.is_success());
+            assert!(started_at.elapsed() < Duration::from_millis(500));
+        }
+
+        for call in storm_calls {
+            call.abort();
+        }
+    }
+}
@@ -2,13 +2,23 @@ use reth_rpc::{
     eth::{
         cache::{EthStateCache, EthStateCacheConfig},
         gas_oracle::GasPriceOracleConfig,
-        EthFilterConfig, FeeHistoryCacheConfig, RPC_DEFAULT_GAS_CAP,
+        EthFilterConfig, EthPubSubConfig, FeeHistoryCacheConfig, RPC_DEFAULT_GAS_CAP,
     },
-    EthApi, EthFilter, EthPubSub,
+    DebugApiConfig, EthApi, EthFilter, EthPubSub, TraceApiConfig,
 };
 use reth_rpc_server_types::constants::{
-    default_max_tracing_requests, DEFAULT_MAX_BLOCKS_PER_FILTER, DEFAULT_MAX_LOGS_PER_RESPONSE,
+    default_max_tracing_requests, DEFAULT_ETH_PROOF_WINDOW, DEFAULT_FILTER_CATCH_UP_BLOCKS,
+    DEFAULT_JS_TRACER_TIMEOUT, DEFAULT_MAX_ACTIVE_FILTERS, DEFAULT_MAX_BLOCKS_PER_FILTER,
+    DEFAULT_MAX_JS_TRACER_THREADS, DEFAULT_MAX_LOGS_PER_RESPONSE,
+    DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT, DEFAULT_MAX_STRUCT_LOGS_MEMORY,
+    DEFAULT_MAX_SUBSCRIPTION_BUFFER, DEFAULT_MAX_TRACE_BLOCK_CHECKPOINTS,
+    DEFAULT_MAX_TRACE_CHAIN_BLOCKS, DEFAULT_MAX_TRACE_FILTER_BLOCKS,
+    DEFAULT_MAX_TRACE_FILTER_RESULTS, DEFAULT_MAX_TRANSACTION_FILTER_ADDRESSES,
+    DEFAULT_MAX_TX_CONDITIONAL_COST, DEFAULT_MAX_VM_TRACE_MEMORY, DEFAULT_MAX_VM_TRACE_STEPS,
+    DEFAULT_RPC_MEMORY_LIMIT, DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT, DEFAULT_STALE_FILTER_TTL,
+    DEFAULT_TRACING_TIMEOUT,
 };
+use reth_rpc_types::SubscriptionBackpressurePolicy;
 use reth_tasks::pool::BlockingTaskPool;
 use serde::{Deserialize, Serialize};
 
@@ -36,33 +46,172 @@ pub struct EthConfig {
     pub gas_oracle: GasPriceOracleConfig,
     /// The maximum number of tracing calls that can be executed in concurrently.
     pub max_tracing_requests: usize,
+    /// The maximum number of transaction pre-states held at once when tracing the transactions of
+    /// a block in parallel. A value of 1 traces the transactions sequentially.
+    ///
+    /// Defaults to [`DEFAULT_MAX_TRACE_BLOCK_CHECKPOINTS`]
+    pub max_trace_block_checkpoints: usize,
+    /// The maximum time the execution of a call traced in the `debug` or `trace` namespace can
+    /// take.
+    ///
+    /// Defaults to [`DEFAULT_TRACING_TIMEOUT`]
+    pub tracing_timeout: std::time::Duration,
+    /// Maximum combined size in bytes of the struct logs recorded for a single call traced in the
+    /// `debug` namespace.
+    ///
+    /// Defaults to [`DEFAULT_MAX_STRUCT_LOGS_MEMORY`]
+    pub max_struct_logs_memory: usize,
+    /// Whether calls can be traced with custom JavaScript tracers in the `debug` namespace.
+    pub js_tracer: bool,
+    /// The maximum time a call traced with a JavaScript tracer can take.
+    ///
+    /// Defaults to [`DEFAULT_JS_TRACER_TIMEOUT`]
+    pub js_tracer_timeout: std::time::Duration,
+    /// Maximum number of threads that execute JavaScript tracers, including the threads of tracers
+    /// that didn't return in time.
+    ///
+    /// Defaults to [`DEFAULT_MAX_JS_TRACER_THREADS`]
+    pub max_js_tracer_threads: usize,
+    /// Maximum number of blocks a single `debug_traceChain` subscription can trace.
+    ///
+    /// Defaults to [`DEFAULT_MAX_TRACE_CHAIN_BLOCKS`]
+    pub max_trace_chain_blocks: u64,
+    /// Maximum number of blocks a single `trace_filter` request can scan.
+    ///
+    /// Defaults to [`DEFAULT_MAX_TRACE_FILTER_BLOCKS`]
+    pub max_trace_filter_blocks: u64,
+    /// Maximum number of traces a single `trace_filter` request can return.
+    ///
+    /// Defaults to [`DEFAULT_MAX_TRACE_FILTER_RESULTS`]
+    pub max_trace_filter_results: usize,
+    /// Maximum number of instructions recorded for the `vmTrace` of a single call.
+    ///
+    /// Defaults to [`DEFAULT_MAX_VM_TRACE_STEPS`]
+    pub max_vm_trace_steps: usize,
+    /// Maximum combined size in bytes of the memory snapshots recorded for the `vmTrace` of a
+    /// single call.
+    ///
+    /// Defaults to [`DEFAULT_MAX_VM_TRACE_MEMORY`]
+    pub max_vm_trace_memory: usize,
     /// Maximum number of blocks that could be scanned per filter request in `eth_getLogs` calls.
     pub max_blocks_per_filter: u64,
     /// Maximum number of logs that can be returned in a single response in `eth_getLogs` calls.
     pub max_logs_per_response: usize,
+    /// Maximum time a single `eth_getLogs` query can take, `None` if unlimited.
+    pub logs_query_timeout: Option<std::time::Duration>,
     /// Gas limit for `eth_call` and call tracing RPC methods.
     ///
     /// Defaults to [`RPC_DEFAULT_GAS_CAP`]
     pub rpc_gas_cap: u64,
+    /// The maximum number of blocks into the past for generating state proofs.
+    ///
+    /// Defaults to [`DEFAULT_ETH_PROOF_WINDOW`]
+    pub eth_proof_window: u64,
+    /// Memory limit of the EVM in bytes for `eth_call` and the other RPC methods that execute
+    /// calls.
+    ///
+    /// Defaults to [`DEFAULT_RPC_MEMORY_LIMIT`]
+    pub rpc_memory_limit: u64,
+    /// The time `eth_sendRawTransactionSync` waits for the inclusion of the transaction if the
+    /// request doesn't specify a timeout.
+    ///
+    /// Defaults to [`DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT`]
+    pub send_raw_transaction_sync_timeout: std::time::Duration,
+    /// The maximum time `eth_sendRawTransactionSync` waits for the inclusion of the transaction.
+    ///
+    /// Defaults to [`DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT`]
+    pub max_send_raw_transaction_sync_timeout: std::time::Duration,
+    /// Whether `eth_sendRawTransactionConditional` is installed in the `eth` namespace.
+    pub tx_conditional: bool,
+    /// The maximum cost of the conditional of a transaction submitted with
+    /// `eth_sendRawTransactionConditional`.
+    ///
+    /// Defaults to [`DEFAULT_MAX_TX_CONDITIONAL_COST`]
+    pub max_tx_conditional_cost: usize,
     ///
     /// Sets TTL for stale filters
     pub stale_filter_ttl: std::time::Duration,
+    /// Maximum number of filters a single connection can have installed at once, `None` if
+    /// unlimited.
+    ///
+    /// Defaults to [`DEFAULT_MAX_ACTIVE_FILTERS`]
+    pub max_active_filters: Option<usize>,
+    /// The file the installed log filters are persisted to, so they're restored after a restart.
+    pub filter_persistence_path: Option<std::path::PathBuf>,
+    /// Maximum number of blocks the first poll of a restored log filter returns the changes of.
+    ///
+    /// Defaults to [`DEFAULT_FILTER_CATCH_UP_BLOCKS`]
+    pub filter_catch_up_blocks: u64,
     /// Settings for the fee history cache
     pub fee_history_cache: FeeHistoryCacheConfig,
+    /// The maximum number of notifications buffered per subscription if the subscriber doesn't
+    /// keep up.
+    ///
+    /// Defaults to [`DEFAULT_MAX_SUBSCRIPTION_BUFFER`]
+    pub max_subscription_buffer: usize,
+    /// What happens to the notifications of a subscription once its buffer is full.
+    pub subscription_backpressure: SubscriptionBackpressurePolicy,
+    /// The maximum number of addresses of the filter of a `newPendingTransactions` subscription.
+    ///
+    /// Defaults to [`DEFAULT_MAX_TRANSACTION_FILTER_ADDRESSES`]
+    pub max_transaction_filter_addresses: usize,
 }
 
 impl EthConfig {
     /// Returns the filter config for the `eth_filter` handler.
     pub fn filter_config(&self) -> EthFilterConfig {
-        EthFilterConfig::default()
+        let config = EthFilterConfig::default()
             .max_blocks_per_filter(self.max_blocks_per_filter)
             .max_logs_per_response(self.max_logs_per_response)
             .stale_filter_ttl(self.stale_filter_ttl)
+            .catch_up_blocks(self.filter_catch_up_blocks);
+        let config = match self.max_active_filters {
+            Some(max_filters) => config.max_active_filters(max_filters),
+            None => config,
+        };
+        let config = match &self.filter_persistence_path {
+            Some(path) => config.persistence_path(path.clone()),
+            None => config,
+        };
+        match self.logs_query_timeout {
+            Some(timeout) => config.query_timeout(timeout),
+            None => config,
+        }
+    }
+
+    /// Returns the config for the `debug` handler.
+    pub const fn debug_config(&self) -> DebugApiConfig {
+        DebugApiConfig {
+            max_trace_block_checkpoints: self.max_trace_block_checkpoints,
+            tracing_timeout: self.tracing_timeout,
+            max_struct_logs_memory: self.max_struct_logs_memory,
+            js_tracer: self.js_tracer,
+            js_tracer_timeout: self.js_tracer_timeout,
+            max_js_tracer_threads: self.max_js_tracer_threads,
+            max_trace_chain_blocks: self.max_trace_chain_blocks,
+        }
+    }
+
+    /// Returns the config for the `trace` handler.
+    pub const fn trace_config(&self) -> TraceApiConfig {
+        TraceApiConfig {
+            max_trace_filter_blocks: self.max_trace_filter_blocks,
+            max_trace_filter_results: self.max_trace_filter_results,
+            max_trace_block_checkpoints: self.max_trace_block_checkpoints,
+            tracing_timeout: self.tracing_timeout,
+            max_vm_trace_steps: self.max_vm_trace_steps,
+            max_vm_trace_memory: self.max_vm_trace_memory,
+        }
     }
-}
 
-/// Default value for stale filter ttl
-const DEFAULT_STALE_FILTER_TTL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+    /// Returns the subscription config for the `eth_subscribe` handler.
+    pub fn pubsub_config(&self) -> EthPubSubConfig {
+        EthPubSubConfig::default()
+            .max_subscription_buffer(self.max_subscription_buffer)
+            .backpressure_policy(self.subscription_backpressure)
+            .max_transaction_filter_addresses(self.max_transaction_filter_addresses)
+    }
+}
 
 impl Default for EthConfig {
     fn default() -> Self {
@@ -70,11 +219,35 @@ impl Default for EthConfig {
             cache: EthStateCacheConfig::default(),
             gas_oracle: GasPriceOracleConfig::default(),
             max_tracing_requests: default_max_tracing_requests(),
+            max_trace_block_checkpoints: DEFAULT_MAX_TRACE_BLOCK_CHECKPOINTS,
+            tracing_timeout: DEFAULT_TRACING_TIMEOUT,
+            max_struct_logs_memory: DEFAULT_MAX_STRUCT_LOGS_MEMORY,
+            js_tracer: false,
+            js_tracer_timeout: DEFAULT_JS_TRACER_TIMEOUT,
+            max_js_tracer_threads: DEFAULT_MAX_JS_TRACER_THREADS,
+            max_trace_chain_blocks: DEFAULT_MAX_TRACE_CHAIN_BLOCKS,
+            max_trace_filter_blocks: DEFAULT_MAX_TRACE_FILTER_BLOCKS,
+            max_trace_filter_results: DEFAULT_MAX_TRACE_FILTER_RESULTS,
+            max_vm_trace_steps: DEFAULT_MAX_VM_TRACE_STEPS,
+            max_vm_trace_memory: DEFAULT_MAX_VM_TRACE_MEMORY,
             max_blocks_per_filter: DEFAULT_MAX_BLOCKS_PER_FILTER,
             max_logs_per_response: DEFAULT_MAX_LOGS_PER_RESPONSE,
+            logs_query_timeout: None,
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
+            eth_proof_window: DEFAULT_ETH_PROOF_WINDOW,
+            rpc_memory_limit: DEFAULT_RPC_MEMORY_LIMIT,
+            send_raw_transaction_sync_timeout: DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT,
+            max_send_raw_transaction_sync_timeout: DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT,
+            tx_conditional: false,
+            max_tx_conditional_cost: DEFAULT_MAX_TX_CONDITIONAL_COST,
             stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
+            max_active_filters: Some(DEFAULT_MAX_ACTIVE_FILTERS),
+            filter_persistence_path: None,
+            filter_catch_up_blocks: DEFAULT_FILTER_CATCH_UP_BLOCKS,
             fee_history_cache: FeeHistoryCacheConfig::default(),
+            max_subscription_buffer: DEFAULT_MAX_SUBSCRIPTION_BUFFER,
+            subscription_backpressure: SubscriptionBackpressurePolicy::default(),
+            max_transaction_filter_addresses: DEFAULT_MAX_TRANSACTION_FILTER_ADDRESSES,
         }
     }
 }
@@ -98,6 +271,72 @@ impl EthConfig {
         self
     }
 
+    /// Configures the maximum number of transaction pre-states held at once when tracing a block
+    pub const fn max_trace_block_checkpoints(mut self, max_checkpoints: usize) -> Self {
+        self.max_trace_block_checkpoints = max_checkpoints;
+        self
+    }
+
+    /// Configures the maximum time the execution of a traced call can take
+    pub const fn tracing_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.tracing_timeout = timeout;
+        self
+    }
+
+    /// Configures the maximum size of the struct logs recorded for a traced call
+    pub const fn max_struct_logs_memory(mut self, max_memory: usize) -> Self {
+        self.max_struct_logs_memory = max_memory;
+        self
+    }
+
+    /// Configures whether calls can be traced with custom JavaScript tracers
+    pub const fn js_tracer(mut self, enabled: bool) -> Self {
+        self.js_tracer = enabled;
+        self
+    }
+
+    /// Configures the maximum time a call traced with a JavaScript tracer can take
+    pub const fn js_tracer_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.js_tracer_timeout = timeout;
+        self
+    }
+
+    /// Configures the maximum number of threads that execute JavaScript tracers
+    pub const fn max_js_tracer_threads(mut self, max_threads: usize) -> Self {
+        self.max_js_tracer_threads = max_threads;
+        self
+    }
+
+    /// Configures the maximum number of blocks a `debug_traceChain` subscription can trace
+    pub const fn max_trace_chain_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_trace_chain_blocks = max_blocks;
+        self
+    }
+
+    /// Configures the maximum number of blocks a `trace_filter` request can scan
+    pub const fn max_trace_filter_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_trace_filter_blocks = max_blocks;
+        self
+    }
+
+    /// Configures the maximum number of traces a `trace_filter` request can return
+    pub const fn max_trace_filter_results(mut self, max_results: usize) -> Self {
+        self.max_trace_filter_results = max_results;
+        self
+    }
+
+    /// Configures the maximum number of instructions recorded for the `vmTrace` of a call
+    pub const fn max_vm_trace_steps(mut self, max_steps: usize) -> Self {
+        self.max_vm_trace_steps = max_steps;
+        self
+    }
+
+    /// Configures the maximum size of the memory snapshots recorded for the `vmTrace` of a call
+    pub const fn max_vm_trace_memory(mut self, max_memory: usize) -> Self {
+        self.max_vm_trace_memory = max_memory;
+        self
+    }
+
     /// Configures the maximum block length to scan per `eth_getLogs` request
     pub const fn max_blocks_per_filter(mut self, max_blocks: u64) -> Self {
         self.max_blocks_per_filter = max_blocks;
@@ -110,9 +349,109 @@ impl EthConfig {
         self
     }
 
+    /// Configures the maximum time a single `eth_getLogs` query can take
+    pub const fn logs_query_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.logs_query_timeout = Some(timeout);
+        self
+    }
+
+    /// Configures how long a filter remains installed without being polled
+    pub const fn stale_filter_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.stale_filter_ttl = ttl;
+        self
+    }
+
+    /// Configures the maximum number of filters a single connection can have installed at once,
+    /// `None` if unlimited
+    pub const fn max_active_filters(mut self, max_filters: Option<usize>) -> Self {
+        self.max_active_filters = max_filters;
+        self
+    }
+
+    /// Configures the file the installed log filters are persisted to
+    pub fn filter_persistence_path(mut self, path: std::path::PathBuf) -> Self {
+        self.filter_persistence_path = Some(path);
+        self
+    }
+
+    /// Configures the maximum number of blocks the first poll of a restored log filter returns
+    /// the changes of
+    pub const fn filter_catch_up_blocks(mut self, blocks: u64) -> Self {
+        self.filter_catch_up_blocks = blocks;
+        self
+    }
+
     /// Configures the maximum gas limit for `eth_call` and call tracing RPC methods
     pub const fn rpc_gas_cap(mut self, rpc_gas_cap: u64) -> Self {
         self.rpc_gas_cap = rpc_gas_cap;
         self
     }
+
+    /// Configures the maximum proof window for historical proof generation.
+    pub const fn eth_proof_window(mut self, window: u64) -> Self {
+        self.eth_proof_window = window;
+        self
+    }
+
+    /// Configures the memory limit of the EVM for `eth_call` and the other RPC methods that execute
+    /// calls
+    pub const fn rpc_memory_limit(mut self, memory_limit: u64) -> Self {
+        self.rpc_memory_limit = memory_limit;
+        self
+    }
+
+    /// Configures the default timeout of `eth_sendRawTransactionSync`
+    pub const fn send_raw_transaction_sync_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.send_raw_transaction_sync_timeout = timeout;
+        self
+    }
+
+    /// Configures the maximum timeout of `eth_sendRawTransactionSync`
+    pub const fn max_send_raw_transaction_sync_timeout(
+        mut self,
+        timeout: std::time::Duration,
+    ) -> Self {
+        self.max_send_raw_transaction_sync_timeout = timeout;
+        self
+    }
+
+    /// Configures whether `eth_sendRawTransactionConditional` is installed
+    pub const fn tx_conditional(mut self, enabled: bool) -> Self {
+        self.tx_conditional = enabled;
+        self
+    }
+
+    /// Configures the maximum cost of the conditional of `eth_sendRawTransactionConditional`
+    pub const fn max_tx_conditional_cost(mut self, max_cost: usize) -> Self {
+        self.max_tx_conditional_cost = max_cost;
+        self
+    }
+
+    /// Configures the fee history cache settings
+    pub const fn fee_history_cache(mut self, fee_history_cache: FeeHistoryCacheConfig) -> Self {
+        self.fee_history_cache = fee_history_cache;
+        self
+    }
+
+    /// Configures the maximum number of notifications buffered per subscription
+    pub const fn max_subscription_buffer(mut self, max_buffer: usize) -> Self {
+        self.max_subscription_buffer = max_buffer;
+        self
+    }
+
+    /// Configures what happens to the notifications of a subscription once its buffer is full
+    pub const fn subscription_backpressure(
+        mut self,
+        policy: SubscriptionBackpressurePolicy,
+    ) -> Self {
+        self.subscription_backpressure = policy;
+        self
+    }
+
+    /// Configures the maximum number of addresses of the filter of a `newPendingTransactions`
+    /// subscription
+    pub const fn max_transaction_filter_addresses(mut self, max_addresses: usize) -> Self {
+        self.max_transaction_filter_addresses = max_addresses;
+        self
+    }
 }
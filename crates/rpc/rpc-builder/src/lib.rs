@@ -156,8 +156,8 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use crate::{
-    auth::AuthRpcModule, cors::CorsDomainError, error::WsHttpSamePortError,
-    metrics::RpcRequestMetrics, RpcModuleSelection::Selection,
+    auth::AuthRpcModule, concurrency::RpcCallLimitLayer, cors::CorsDomainError,
+    error::WsHttpSamePortError, metrics::RpcRequestMetrics, RpcModuleSelection::Selection,
 };
 use error::{ConflictingModules, RpcError, ServerKind};
 use hyper::{header::AUTHORIZATION, HeaderMap};
@@ -175,8 +175,10 @@ pub use reth_ipc::server::{
 };
 use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
 use reth_provider::{
-    AccountReader, BlockReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-    ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+    AccountReader, AddressTransactionsReader, BlockReader, BlockReaderIdExt,
+    CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader, EvmEnvProvider,
+    ExecutionWitnessProvider, RawBlockReader, RawDatabaseReader, StateProviderFactory,
+    TransactionReplayProvider,
 };
 use reth_rpc::{
     eth::{
@@ -184,14 +186,16 @@ use reth_rpc::{
         fee_history_cache_new_blocks_task,
         gas_oracle::GasPriceOracle,
         traits::RawTransactionForwarder,
-        EthBundle, FeeHistoryCache,
+        EthBundle, EthConditional, FeeHistoryCache,
     },
     AdminApi, DebugApi, EngineEthApi, EthApi, EthFilter, EthPubSub, EthSubscriptionIdProvider,
-    NetApi, OtterscanApi, RPCApi, RethApi, TraceApi, TxPoolApi, Web3Api,
+    NetApi, OtterscanApi, RPCApi, RethApi, RethPubSub, TraceApi, TracerFactory, TracerRegistry,
+    TxPoolApi, Web3Api,
 };
 use reth_rpc_api::servers::*;
 use reth_rpc_layer::{AuthLayer, Claims, JwtAuthValidator, JwtSecret};
 pub use reth_rpc_server_types::constants;
+use reth_rpc_types::NodeEngineInfo;
 use reth_tasks::{
     pool::{BlockingTaskGuard, BlockingTaskPool},
     TaskSpawner, TokioTaskExecutor,
@@ -202,8 +206,9 @@ use std::{
     collections::{HashMap, HashSet},
     fmt,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    path::PathBuf,
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, OnceLock},
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use strum::{AsRefStr, EnumIter, IntoStaticStr, ParseError, VariantArray, VariantNames};
@@ -212,11 +217,17 @@ use tower_http::cors::CorsLayer;
 use tracing::{instrument, trace};
 
 // re-export for convenience
-pub use crate::eth::{EthConfig, EthHandlers};
+pub use crate::{
+    concurrency::{RpcCallLimits, CALL_QUEUE_FULL_CODE},
+    eth::{EthConfig, EthHandlers},
+};
 
 /// Auth server utilities.
 pub mod auth;
 
+/// Limits on the number of concurrently executed calls.
+mod concurrency;
+
 /// Cors utilities.
 mod cors;
 
@@ -560,6 +571,9 @@ impl Default for RpcModuleBuilder<(), (), (), (), (), ()> {
 pub struct RpcModuleConfig {
     /// `eth` namespace settings
     eth: EthConfig,
+    /// The configuration of the engine API reported by `admin_nodeInfo`
+    #[serde(default)]
+    engine_info: NodeEngineInfo,
 }
 
 // === impl RpcModuleConfig ===
@@ -571,8 +585,14 @@ impl RpcModuleConfig {
     }
 
     /// Returns a new RPC module config given the eth namespace config
-    pub const fn new(eth: EthConfig) -> Self {
-        Self { eth }
+    pub fn new(eth: EthConfig) -> Self {
+        Self { eth, engine_info: NodeEngineInfo::default() }
+    }
+
+    /// Sets the configuration of the engine API reported by `admin_nodeInfo`
+    pub const fn with_engine_info(mut self, engine_info: NodeEngineInfo) -> Self {
+        self.engine_info = engine_info;
+        self
     }
 
     /// Get a reference to the eth namespace config
@@ -584,12 +604,23 @@ impl RpcModuleConfig {
     pub fn eth_mut(&mut self) -> &mut EthConfig {
         &mut self.eth
     }
+
+    /// Get a reference to the configuration of the engine API
+    pub const fn engine_info(&self) -> &NodeEngineInfo {
+        &self.engine_info
+    }
+
+    /// Get a mutable reference to the configuration of the engine API
+    pub fn engine_info_mut(&mut self) -> &mut NodeEngineInfo {
+        &mut self.engine_info
+    }
 }
 
 /// Configures [`RpcModuleConfig`]
 #[derive(Clone, Debug, Default)]
 pub struct RpcModuleConfigBuilder {
     eth: Option<EthConfig>,
+    engine_info: Option<NodeEngineInfo>,
 }
 
 // === impl RpcModuleConfigBuilder ===
@@ -601,10 +632,19 @@ impl RpcModuleConfigBuilder {
         self
     }
 
+    /// Configures the configuration of the engine API reported by `admin_nodeInfo`
+    pub const fn engine_info(mut self, engine_info: NodeEngineInfo) -> Self {
+        self.engine_info = Some(engine_info);
+        self
+    }
+
     /// Consumes the type and creates the [`RpcModuleConfig`]
     pub fn build(self) -> RpcModuleConfig {
-        let Self { eth } = self;
-        RpcModuleConfig { eth: eth.unwrap_or_default() }
+        let Self { eth, engine_info } = self;
+        RpcModuleConfig {
+            eth: eth.unwrap_or_default(),
+            engine_info: engine_info.unwrap_or_default(),
+        }
     }
 
     /// Get a reference to the eth namespace config, if any
@@ -970,6 +1010,12 @@ pub struct RethModuleRegistry<Provider, Pool, Network, Tasks, Events, EvmConfig>
     config: RpcModuleConfig,
     /// Holds a clone of all the eth namespace handlers
     eth: Option<EthHandlers<Provider, Pool, Network, Events, EvmConfig>>,
+    /// Holds a clone of the debug namespace handler
+    debug: Option<DebugApi<Provider, EthApi<Provider, Pool, Network, EvmConfig>>>,
+    /// Holds a clone of the reth namespace handler
+    reth: OnceLock<RethApi<Provider>>,
+    /// Holds a clone of the otterscan namespace handler
+    otterscan: Option<OtterscanApi<EthApi<Provider, Pool, Network, EvmConfig>>>,
     /// to put trace calls behind semaphore
     blocking_pool_guard: BlockingTaskGuard,
     /// Contains the [Methods] of a module
@@ -1000,6 +1046,9 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
             network,
             evm_config,
             eth: None,
+            debug: None,
+            reth: OnceLock::new(),
+            otterscan: None,
             executor,
             modules: Default::default(),
             blocking_pool_guard: BlockingTaskGuard::new(config.eth.max_tracing_requests),
@@ -1066,6 +1115,7 @@ where
     /// Instantiates `AdminApi`
     pub fn admin_api(&self) -> AdminApi<Network> {
         AdminApi::new(self.network.clone(), self.provider.chain_spec())
+            .with_engine_info(self.config.engine_info)
     }
 
     /// Instantiates `Web3Api`
@@ -1169,7 +1219,7 @@ where
         let engine_eth = EngineEthApi::new(eth_handlers.api.clone(), eth_handlers.filter);
         module.merge(engine_eth.into_rpc()).expect("No conflicting methods");
 
-        AuthRpcModule { inner: module }
+        AuthRpcModule { inner: module, engine_capabilities: Default::default() }
     }
 
     /// Register Net Namespace
@@ -1252,6 +1302,9 @@ where
             cache: _,
             blocking_task_pool: _,
         } = self.with_eth(|eth| eth.clone());
+        let debug_api = self.debug_api();
+        let reth_api = self.reth_api();
+        let otterscan_api = self.otterscan_api();
 
         // Create a copy, so we can list out all the methods for rpc_ api
         let namespaces: Vec<_> = namespaces.collect();
@@ -1264,21 +1317,23 @@ where
                     .or_insert_with(|| match namespace {
                         RethRpcModule::Admin => {
                             AdminApi::new(self.network.clone(), self.provider.chain_spec())
+                                .with_engine_info(self.config.engine_info)
                                 .into_rpc()
                                 .into()
                         }
-                        RethRpcModule::Debug => DebugApi::new(
-                            self.provider.clone(),
-                            eth_api.clone(),
-                            self.blocking_pool_guard.clone(),
-                        )
-                        .into_rpc()
-                        .into(),
+                        RethRpcModule::Debug => debug_api.clone().into_rpc().into(),
                         RethRpcModule::Eth => {
                             // merge all eth handlers
                             let mut module = eth_api.clone().into_rpc();
                             module.merge(eth_filter.clone().into_rpc()).expect("No conflicts");
                             module.merge(eth_pubsub.clone().into_rpc()).expect("No conflicts");
+                            if self.config.eth.tx_conditional {
+                                let eth_conditional = EthConditional::new(
+                                    eth_api.clone(),
+                                    self.config.eth.max_tx_conditional_cost,
+                                );
+                                module.merge(eth_conditional.into_rpc()).expect("No conflicts");
+                            }
 
                             module.into()
                         }
@@ -1289,6 +1344,7 @@ where
                             self.provider.clone(),
                             eth_api.clone(),
                             self.blocking_pool_guard.clone(),
+                            self.config.eth.trace_config(),
                         )
                         .into_rpc()
                         .into(),
@@ -1304,11 +1360,17 @@ where
                         )
                         .into_rpc()
                         .into(),
-                        RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
+                        RethRpcModule::Ots => otterscan_api.clone().into_rpc().into(),
                         RethRpcModule::Reth => {
-                            RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
-                                .into_rpc()
-                                .into()
+                            // merge the reth handlers and subscriptions
+                            let mut module = reth_api.clone().into_rpc();
+                            let pubsub = RethPubSub::with_spawner(
+                                self.events.clone(),
+                                Box::new(self.executor.clone()),
+                            );
+                            module.merge(pubsub.into_rpc()).expect("No conflicts");
+
+                            module.into()
                         }
                         RethRpcModule::EthCallBundle => {
                             EthBundle::new(eth_api.clone(), self.blocking_pool_guard.clone())
@@ -1351,11 +1413,6 @@ where
             self.executor.clone(),
             self.evm_config.clone(),
         );
-        let gas_oracle = GasPriceOracle::new(
-            self.provider.clone(),
-            self.config.eth.gas_oracle.clone(),
-            cache.clone(),
-        );
         let new_canonical_blocks = self.events.canonical_state_stream();
         let c = cache.clone();
 
@@ -1370,14 +1427,19 @@ where
             FeeHistoryCache::new(cache.clone(), self.config.eth.fee_history_cache.clone());
         let new_canonical_blocks = self.events.canonical_state_stream();
         let fhc = fee_history_cache.clone();
-        let provider_clone = self.provider.clone();
         self.executor.spawn_critical(
             "cache canonical blocks for fee history task",
             Box::pin(async move {
-                fee_history_cache_new_blocks_task(fhc, new_canonical_blocks, provider_clone).await;
+                fee_history_cache_new_blocks_task(fhc, new_canonical_blocks).await;
             }),
         );
 
+        let gas_oracle = GasPriceOracle::new(
+            self.provider.clone(),
+            self.config.eth.gas_oracle.clone(),
+            fee_history_cache.clone(),
+        );
+
         let executor = Box::new(self.executor.clone());
         let blocking_task_pool = BlockingTaskPool::build().expect("failed to build tracing pool");
         let api = EthApi::with_spawner(
@@ -1387,6 +1449,10 @@ where
             cache.clone(),
             gas_oracle,
             self.config.eth.rpc_gas_cap,
+            self.config.eth.eth_proof_window,
+            self.config.eth.rpc_memory_limit,
+            self.config.eth.send_raw_transaction_sync_timeout,
+            self.config.eth.max_send_raw_transaction_sync_timeout,
             executor.clone(),
             blocking_task_pool.clone(),
             fee_history_cache,
@@ -1406,6 +1472,7 @@ where
             self.pool.clone(),
             self.events.clone(),
             self.network.clone(),
+            self.config.eth.pubsub_config(),
             executor,
         );
 
@@ -1439,7 +1506,12 @@ where
     /// If called outside of the tokio runtime. See also [`Self::eth_api`]
     pub fn trace_api(&mut self) -> TraceApi<Provider, EthApi<Provider, Pool, Network, EvmConfig>> {
         let eth = self.eth_handlers();
-        TraceApi::new(self.provider.clone(), eth.api, self.blocking_pool_guard.clone())
+        TraceApi::new(
+            self.provider.clone(),
+            eth.api,
+            self.blocking_pool_guard.clone(),
+            self.config.eth.trace_config(),
+        )
     }
 
     /// Instantiates [`EthBundle`] Api
@@ -1458,8 +1530,23 @@ where
     ///
     /// If called outside of the tokio runtime. See also [`Self::eth_api`]
     pub fn otterscan_api(&mut self) -> OtterscanApi<EthApi<Provider, Pool, Network, EvmConfig>> {
+        if let Some(otterscan) = &self.otterscan {
+            return otterscan.clone()
+        }
         let eth_api = self.eth_api();
-        OtterscanApi::new(eth_api)
+        let otterscan = OtterscanApi::new(eth_api);
+        self.otterscan = Some(otterscan.clone());
+        otterscan
+    }
+
+    /// Sets the reader of the index of the blocks with transactions of an address for
+    /// `ots_searchTransactionsBefore` and `ots_searchTransactionsAfter`.
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [`Self::eth_api`]
+    pub fn set_address_transactions_reader(&mut self, reader: Arc<dyn AddressTransactionsReader>) {
+        self.otterscan_api().set_address_transactions_reader(reader);
     }
 
     /// Instantiates `DebugApi`
@@ -1468,8 +1555,71 @@ where
     ///
     /// If called outside of the tokio runtime. See also [`Self::eth_api`]
     pub fn debug_api(&mut self) -> DebugApi<Provider, EthApi<Provider, Pool, Network, EvmConfig>> {
+        if let Some(debug) = &self.debug {
+            return debug.clone()
+        }
         let eth_api = self.eth_api();
-        DebugApi::new(self.provider.clone(), eth_api, self.blocking_pool_guard.clone())
+        let debug = DebugApi::with_spawner(
+            self.provider.clone(),
+            eth_api,
+            self.blocking_pool_guard.clone(),
+            self.config.eth.debug_config(),
+            Box::new(self.executor.clone()),
+        );
+        self.debug = Some(debug.clone());
+        debug
+    }
+
+    /// Sets the provider of execution witnesses for `debug_executionWitness`.
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [`Self::eth_api`]
+    pub fn set_execution_witness_provider(&mut self, provider: Arc<dyn ExecutionWitnessProvider>) {
+        self.debug_api().set_execution_witness_provider(provider);
+    }
+
+    /// Sets the provider of transaction replays for `debug_replayTransaction`.
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [`Self::eth_api`]
+    pub fn set_transaction_replay_provider(
+        &mut self,
+        provider: Arc<dyn TransactionReplayProvider>,
+    ) {
+        self.debug_api().set_transaction_replay_provider(provider);
+    }
+
+    /// Sets the reader of the encoded canonical blocks served by the raw getters of the `debug`
+    /// namespace.
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [`Self::eth_api`]
+    pub fn set_raw_block_reader(&mut self, reader: Arc<dyn RawBlockReader>) {
+        self.debug_api().set_raw_block_reader(reader);
+    }
+
+    /// Registers a custom tracer of the `debug` namespace under the given name.
+    ///
+    /// See also [`DebugApi::register_tracer`].
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [`Self::eth_api`]
+    pub fn register_tracer(&mut self, name: impl Into<String>, factory: Box<dyn TracerFactory>) {
+        self.debug_api().register_tracer(name, factory);
+    }
+
+    /// Returns the registry of the custom tracers, tracers can be registered in it after the
+    /// modules were configured.
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [`Self::eth_api`]
+    pub fn tracer_registry(&mut self) -> TracerRegistry {
+        self.debug_api().tracer_registry().clone()
     }
 
     /// Instantiates `NetApi`
@@ -1484,7 +1634,25 @@ where
 
     /// Instantiates `RethApi`
     pub fn reth_api(&self) -> RethApi<Provider> {
-        RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
+        self.reth
+            .get_or_init(|| RethApi::new(self.provider.clone(), Box::new(self.executor.clone())))
+            .clone()
+    }
+
+    /// Sets the reader of raw database values and enables `reth_dbGet` and `reth_staticFileGet`.
+    ///
+    /// This exposes the entire database to anyone who can access the `reth` namespace.
+    pub fn set_raw_database_reader(&mut self, reader: Arc<dyn RawDatabaseReader>) {
+        self.reth_api().set_raw_database_reader(reader);
+    }
+
+    /// Enables `reth_txpoolDump` and `reth_txpoolRestore`, which write and read snapshots of the
+    /// pool in the given directory.
+    ///
+    /// This allows anyone who can access the `reth` namespace to write files to the directory.
+    pub fn enable_txpool_snapshots(&mut self, dir: PathBuf) {
+        let pool = Arc::new(self.pool.clone());
+        self.reth_api().set_txpool_snapshots(dir, pool);
     }
 }
 
@@ -1519,6 +1687,8 @@ pub struct RpcServerConfig {
     ipc_endpoint: Option<String>,
     /// JWT secret for authentication
     jwt_secret: Option<JwtSecret>,
+    /// Limits on the number of concurrently executed calls for all transports
+    call_limits: RpcCallLimits,
 }
 
 // === impl RpcServerConfig ===
@@ -1636,6 +1806,14 @@ impl RpcServerConfig {
         self
     }
 
+    /// Configures the limits on the number of concurrently executed calls for all transports.
+    ///
+    /// Default is [`RpcCallLimits::default`]
+    pub const fn with_call_limits(mut self, limits: RpcCallLimits) -> Self {
+        self.call_limits = limits;
+        self
+    }
+
     /// Returns true if any server is configured.
     ///
     /// If no server is configured, no server will be be launched on [`RpcServerConfig::start`].
@@ -1681,6 +1859,7 @@ impl RpcServerConfig {
     async fn build_ws_http(
         &mut self,
         modules: &TransportRpcModules,
+        call_limits: &RpcCallLimitLayer,
     ) -> Result<WsHttpServer, RpcError> {
         let http_socket_addr = self.http_addr.unwrap_or(SocketAddr::V4(SocketAddrV4::new(
             Ipv4Addr::LOCALHOST,
@@ -1725,14 +1904,16 @@ impl RpcServerConfig {
                         .option_layer(self.maybe_jwt_layer()),
                 )
                 .set_rpc_middleware(
-                    RpcServiceBuilder::new().layer(
-                        modules
-                            .http
-                            .as_ref()
-                            .or(modules.ws.as_ref())
-                            .map(RpcRequestMetrics::same_port)
-                            .unwrap_or_default(),
-                    ),
+                    RpcServiceBuilder::new()
+                        .layer(
+                            modules
+                                .http
+                                .as_ref()
+                                .or(modules.ws.as_ref())
+                                .map(RpcRequestMetrics::same_port)
+                                .unwrap_or_default(),
+                        )
+                        .layer(call_limits.clone()),
                 )
                 .build(http_socket_addr)
                 .await
@@ -1763,7 +1944,8 @@ impl RpcServerConfig {
                 )
                 .set_rpc_middleware(
                     RpcServiceBuilder::new()
-                        .layer(modules.ws.as_ref().map(RpcRequestMetrics::ws).unwrap_or_default()),
+                        .layer(modules.ws.as_ref().map(RpcRequestMetrics::ws).unwrap_or_default())
+                        .layer(call_limits.clone()),
                 )
                 .build(ws_socket_addr)
                 .await
@@ -1785,9 +1967,11 @@ impl RpcServerConfig {
                         .option_layer(self.maybe_jwt_layer()),
                 )
                 .set_rpc_middleware(
-                    RpcServiceBuilder::new().layer(
-                        modules.http.as_ref().map(RpcRequestMetrics::http).unwrap_or_default(),
-                    ),
+                    RpcServiceBuilder::new()
+                        .layer(
+                            modules.http.as_ref().map(RpcRequestMetrics::http).unwrap_or_default(),
+                        )
+                        .layer(call_limits.clone()),
                 )
                 .build(http_socket_addr)
                 .await
@@ -1815,14 +1999,16 @@ impl RpcServerConfig {
     /// [`RpcServer::start`]
     pub async fn build(mut self, modules: &TransportRpcModules) -> Result<RpcServer, RpcError> {
         let mut server = RpcServer::empty();
-        server.ws_http = self.build_ws_http(modules).await?;
+        // the server-wide limit is shared by all transports
+        let call_limits = RpcCallLimitLayer::new(self.call_limits);
+        server.ws_http = self.build_ws_http(modules, &call_limits).await?;
 
         if let Some(builder) = self.ipc_server_config {
             let metrics = modules.ipc.as_ref().map(RpcRequestMetrics::ipc).unwrap_or_default();
             let ipc_path =
                 self.ipc_endpoint.unwrap_or_else(|| constants::DEFAULT_IPC_ENDPOINT.into());
             let ipc = builder
-                .set_rpc_middleware(IpcRpcServiceBuilder::new().layer(metrics))
+                .set_rpc_middleware(IpcRpcServiceBuilder::new().layer(metrics).layer(call_limits))
                 .build(ipc_path);
             server.ipc = Some(ipc);
         }
@@ -2061,7 +2247,7 @@ type WsHttpServerKind = Server<
         tower::util::Either<AuthLayer<JwtAuthValidator>, Identity>,
         Stack<tower::util::Either<CorsLayer, Identity>, Identity>,
     >,
-    Stack<RpcRequestMetrics, Identity>,
+    Stack<RpcCallLimitLayer, Stack<RpcRequestMetrics, Identity>>,
 >;
 
 /// Enum for holding the http and ws servers in all possible combinations.
@@ -2125,7 +2311,7 @@ pub struct RpcServer {
     /// Configured ws,http servers
     ws_http: WsHttpServer,
     /// ipc server
-    ipc: Option<IpcServer<Identity, Stack<RpcRequestMetrics, Identity>>>,
+    ipc: Option<IpcServer<Identity, Stack<RpcCallLimitLayer, Stack<RpcRequestMetrics, Identity>>>>,
 }
 
 // === impl RpcServer ===
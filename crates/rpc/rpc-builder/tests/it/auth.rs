@@ -1,15 +1,25 @@
 //! Auth server tests
 
 use crate::utils::launch_auth;
-use jsonrpsee::core::client::{ClientT, SubscriptionClientT};
+use jsonrpsee::{
+    core::{
+        client::{ClientT, SubscriptionClientT},
+        params::{ArrayParams, ObjectParams},
+    },
+    types::error::ErrorCode,
+};
 use reth_ethereum_engine_primitives::EthEngineTypes;
-use reth_primitives::{Block, U64};
-use reth_rpc_api::clients::EngineApiClient;
+use reth_primitives::{Block, B256, U64};
+use reth_rpc_api::{
+    clients::EngineApiClient, ENGINE_API_METHODS, EXCHANGE_CAPABILITIES_METHOD,
+    UNSUPPORTED_FORK_CODE,
+};
 use reth_rpc_layer::JwtSecret;
 use reth_rpc_types::engine::{ForkchoiceState, PayloadId, TransitionConfiguration};
 use reth_rpc_types_compat::engine::payload::{
     block_to_payload_v1, convert_block_to_payload_input_v2,
 };
+use serde_json::{json, Value};
 #[allow(unused_must_use)]
 async fn test_basic_engine_calls<C>(client: &C)
 where
@@ -45,3 +55,65 @@ async fn test_auth_endpoints_ws() {
     let client = handle.ws_client().await;
     test_basic_engine_calls(&client).await
 }
+
+/// Returns the error code of the response, if any.
+fn error_code(resp: Result<Value, jsonrpsee::core::client::Error>) -> Option<i32> {
+    match resp {
+        Err(jsonrpsee::core::client::Error::Call(err)) => Some(err.code()),
+        Err(err) => panic!("unexpected error: {err:?}"),
+        Ok(_) => None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_engine_method_conformance() {
+    reth_tracing::init_test_tracing();
+    let secret = JwtSecret::random();
+    let handle = launch_auth(secret).await;
+    let client = handle.http_client();
+
+    // the capabilities are exactly the registered engine methods
+    let capabilities =
+        EngineApiClient::<EthEngineTypes>::exchange_capabilities(&client, vec![]).await.unwrap();
+    assert!(capabilities.iter().all(|method| method.starts_with("engine_")));
+    assert!(!capabilities.iter().any(|method| method == EXCHANGE_CAPABILITIES_METHOD));
+    assert!(capabilities.iter().any(|method| method == "engine_getBlobsV1"));
+    assert!(!capabilities.iter().any(|method| method == "engine_newPayloadV5"));
+
+    for method in ENGINE_API_METHODS {
+        let mut params = ArrayParams::new();
+        for _ in 0..method.min_params {
+            params.insert(Value::Null).unwrap();
+        }
+        let code = error_code(client.request::<Value, _>(method.name, params).await);
+        assert_ne!(code, Some(ErrorCode::MethodNotFound.code()), "{}", method.name);
+
+        if method.name != EXCHANGE_CAPABILITIES_METHOD &&
+            !capabilities.contains(&method.name.to_string())
+        {
+            // known but unimplemented versions are answered with unsupported fork
+            assert_eq!(code, Some(UNSUPPORTED_FORK_CODE), "{}", method.name);
+
+            // params that don't match the method are invalid
+            let mut params = ArrayParams::new();
+            for _ in 0..=method.max_params {
+                params.insert(Value::Null).unwrap();
+            }
+            let code = error_code(client.request::<Value, _>(method.name, params).await);
+            assert_eq!(code, Some(ErrorCode::InvalidParams.code()), "{}", method.name);
+
+            let mut params = ObjectParams::new();
+            params.insert("payload", Value::Null).unwrap();
+            let code = error_code(client.request::<Value, _>(method.name, params).await);
+            assert_eq!(code, Some(ErrorCode::InvalidParams.code()), "{}", method.name);
+        }
+    }
+
+    // malformed payload fields are invalid params
+    let mut params = ArrayParams::new();
+    params.insert(json!({ "parentHash": "0xzz", "depositRequests": "0x" })).unwrap();
+    params.insert(Vec::<B256>::new()).unwrap();
+    params.insert(B256::ZERO).unwrap();
+    let code = error_code(client.request::<Value, _>("engine_newPayloadV4", params).await);
+    assert_eq!(code, Some(ErrorCode::InvalidParams.code()));
+}
@@ -184,12 +184,15 @@ where
         .await
         .unwrap_err();
     EthApiClient::transaction_by_hash(client, tx_hash).await.unwrap();
+    EthApiClient::transaction_by_sender_and_nonce(client, address, U64::ZERO).await.unwrap();
+    EthApiClient::raw_transaction_by_hash(client, tx_hash).await.unwrap();
+    EthApiClient::blob_sidecar_by_tx_hash(client, tx_hash).await.unwrap();
     EthApiClient::transaction_by_block_hash_and_index(client, hash, index).await.unwrap();
     EthApiClient::transaction_by_block_number_and_index(client, block_number, index).await.unwrap();
-    EthApiClient::create_access_list(client, call_request.clone(), Some(block_number.into()))
+    EthApiClient::create_access_list(client, call_request.clone(), Some(block_number.into()), None)
         .await
         .unwrap();
-    EthApiClient::estimate_gas(client, call_request.clone(), Some(block_number.into()), None)
+    EthApiClient::estimate_gas(client, call_request.clone(), Some(block_number.into()), None, None)
         .await
         .unwrap();
     EthApiClient::call(client, call_request.clone(), Some(block_number.into()), None, None)
@@ -202,6 +205,8 @@ where
     EthApiClient::gas_price(client).await.unwrap_err();
     EthApiClient::max_priority_fee_per_gas(client).await.unwrap_err();
     EthApiClient::get_proof(client, address, vec![], None).await.unwrap();
+    EthApiClient::get_account(client, address, None).await.unwrap();
+    EthApiClient::get_account_info(client, address, None).await.unwrap();
 
     // Unimplemented
     assert!(is_unimplemented(EthApiClient::author(client).await.err().unwrap()));
@@ -313,18 +318,17 @@ where
         .err()
         .unwrap();
 
-    assert!(is_unimplemented(
-        OtterscanClient::search_transactions_before(client, address, block_number, page_size,)
-            .await
-            .err()
-            .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::search_transactions_after(client, address, block_number, page_size,)
+    // an empty chain has no transactions
+    let page =
+        OtterscanClient::search_transactions_before(client, address, block_number, page_size)
             .await
-            .err()
-            .unwrap()
-    ));
+            .unwrap();
+    assert!(page.txs.is_empty());
+    assert!(page.last_page);
+
+    OtterscanClient::search_transactions_after(client, address, block_number, page_size)
+        .await
+        .unwrap();
     assert!(is_unimplemented(
         OtterscanClient::get_transaction_by_sender_and_nonce(client, sender, nonce,)
             .await
@@ -1433,3 +1437,24 @@ async fn test_eth_fee_history_raw() {
     )
     .await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reth_raw_database_access_disabled() {
+    reth_tracing::init_test_tracing();
+
+    // Launch HTTP server without a raw database reader
+    let handle = launch_http(vec![RethRpcModule::Reth]).await;
+    let client = handle.http_client().unwrap();
+
+    for (method, params) in [
+        ("reth_dbGet", rpc_params!["Headers", "0x01"]),
+        ("reth_staticFileGet", rpc_params!["headers", "0x0"]),
+    ] {
+        let err = client.request::<Option<Value>, _>(method, params).await.unwrap_err();
+        let jsonrpsee::core::client::Error::Call(err) = err else {
+            panic!("expected call error for {method}, got {err:?}")
+        };
+        assert_eq!(err.code(), ErrorCode::InternalError.code());
+        assert!(err.message().contains("--rpc.unsafe-debug"), "{}", err.message());
+    }
+}
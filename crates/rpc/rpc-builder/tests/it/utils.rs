@@ -10,11 +10,14 @@ use reth_rpc_builder::{
     RpcModuleBuilder, RpcModuleSelection, RpcServerConfig, RpcServerHandle,
     TransportRpcModuleConfig,
 };
-use reth_rpc_engine_api::EngineApi;
+use reth_rpc_engine_api::{EngineApi, PayloadBuildingMode};
 use reth_rpc_layer::JwtSecret;
 use reth_rpc_types::engine::{ClientCode, ClientVersionV1};
 use reth_tasks::TokioTaskExecutor;
-use reth_transaction_pool::test_utils::{TestPool, TestPoolBuilder};
+use reth_transaction_pool::{
+    noop::NoopTransactionPool,
+    test_utils::{TestPool, TestPoolBuilder},
+};
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use tokio::sync::mpsc::unbounded_channel;
 
@@ -41,10 +44,14 @@ pub async fn launch_auth(secret: JwtSecret) -> AuthServerHandle {
         MAINNET.clone(),
         beacon_engine_handle,
         spawn_test_payload_service().into(),
+        NoopTransactionPool::default(),
         Box::<TokioTaskExecutor>::default(),
         client,
+        PayloadBuildingMode::default(),
     );
-    let module = AuthRpcModule::new(engine_api);
+    let engine_capabilities = engine_api.capabilities().clone();
+    let mut module = AuthRpcModule::new(engine_api);
+    module.set_engine_capabilities(engine_capabilities);
     module.start_server(config).await.unwrap()
 }
 
@@ -9,8 +9,10 @@ use reth_payload_primitives::{
     validate_payload_timestamp, EngineApiMessageVersion, PayloadAttributes,
     PayloadBuilderAttributes, PayloadOrAttributes,
 };
-use reth_primitives::{BlockHash, BlockHashOrNumber, BlockNumber, ChainSpec, Hardfork, B256, U64};
-use reth_rpc_api::EngineApiServer;
+use reth_primitives::{
+    BlobAndProofV1, BlockHash, BlockHashOrNumber, BlockNumber, ChainSpec, Hardfork, B256, U64,
+};
+use reth_rpc_api::{EngineApiServer, EngineCapabilities};
 use reth_rpc_types::engine::{
     CancunPayloadFields, ClientVersionV1, ExecutionPayload, ExecutionPayloadBodiesV1,
     ExecutionPayloadInputV2, ExecutionPayloadV1, ExecutionPayloadV3, ExecutionPayloadV4,
@@ -19,9 +21,11 @@ use reth_rpc_types::engine::{
 };
 use reth_rpc_types_compat::engine::payload::{
     convert_payload_input_v2_to_payload, convert_to_payload_body_v1,
+    convert_to_payload_body_v1_from_parts,
 };
 use reth_storage_api::{BlockReader, HeaderProvider, StateProviderFactory};
 use reth_tasks::TaskSpawner;
+use reth_transaction_pool::TransactionPool;
 use std::{sync::Arc, time::Instant};
 use tokio::sync::oneshot;
 use tracing::{trace, warn};
@@ -32,13 +36,41 @@ pub type EngineApiSender<Ok> = oneshot::Sender<EngineApiResult<Ok>>;
 /// The upper limit for payload bodies request.
 const MAX_PAYLOAD_BODIES_LIMIT: u64 = 1024;
 
+/// The upper limit for blobs in `engine_getBlobsVx`.
+const MAX_BLOB_LIMIT: usize = 128;
+
+/// The supported engine API methods that are not part of [`CAPABILITIES`] yet.
+const EXTRA_CAPABILITIES: &[&str] = &["engine_getBlobsV1"];
+
+/// Determines whether the [`EngineApi`] starts building payloads for the payload attributes of
+/// `engine_forkchoiceUpdated`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PayloadBuildingMode {
+    /// A payload is built for the payload attributes.
+    #[default]
+    Enabled,
+    /// The node only validates payloads, the payload attributes are rejected with
+    /// `-38003: Invalid payload attributes`.
+    ValidationOnly,
+    /// The node only validates payloads, the payload attributes are ignored and no payload id is
+    /// returned.
+    IgnorePayloadAttributes,
+}
+
+impl PayloadBuildingMode {
+    /// Returns `true` if the node never builds payloads.
+    pub const fn is_validation_only(&self) -> bool {
+        !matches!(self, Self::Enabled)
+    }
+}
+
 /// The Engine API implementation that grants the Consensus layer access to data and
 /// functions in the Execution layer that are crucial for the consensus process.
-pub struct EngineApi<Provider, EngineT: EngineTypes> {
-    inner: Arc<EngineApiInner<Provider, EngineT>>,
+pub struct EngineApi<Provider, EngineT: EngineTypes, Pool> {
+    inner: Arc<EngineApiInner<Provider, EngineT, Pool>>,
 }
 
-struct EngineApiInner<Provider, EngineT: EngineTypes> {
+struct EngineApiInner<Provider, EngineT: EngineTypes, Pool> {
     /// The provider to interact with the chain.
     provider: Provider,
     /// Consensus configuration
@@ -53,21 +85,31 @@ struct EngineApiInner<Provider, EngineT: EngineTypes> {
     metrics: EngineApiMetrics,
     /// Identification of the execution client used by the consensus client
     client: ClientVersionV1,
+    /// The transaction pool that holds the blobs of pending blob transactions.
+    tx_pool: Pool,
+    /// Whether payloads are built for the payload attributes of forkchoice updates.
+    payload_building: PayloadBuildingMode,
+    /// The engine API methods that are served by the auth server.
+    capabilities: EngineCapabilities,
 }
 
-impl<Provider, EngineT> EngineApi<Provider, EngineT>
+impl<Provider, EngineT, Pool> EngineApi<Provider, EngineT, Pool>
 where
     Provider: HeaderProvider + BlockReader + StateProviderFactory + EvmEnvProvider + 'static,
     EngineT: EngineTypes + 'static,
+    Pool: TransactionPool + 'static,
 {
     /// Create new instance of [`EngineApi`].
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         provider: Provider,
         chain_spec: Arc<ChainSpec>,
         beacon_consensus: BeaconConsensusEngineHandle<EngineT>,
         payload_store: PayloadStore<EngineT>,
+        tx_pool: Pool,
         task_spawner: Box<dyn TaskSpawner>,
         client: ClientVersionV1,
+        payload_building: PayloadBuildingMode,
     ) -> Self {
         let inner = Arc::new(EngineApiInner {
             provider,
@@ -77,10 +119,21 @@ where
             task_spawner,
             metrics: EngineApiMetrics::default(),
             client,
+            tx_pool,
+            payload_building,
+            capabilities: EngineCapabilities::default(),
         });
         Self { inner }
     }
 
+    /// Returns the [`EngineCapabilities`] that are reported by `engine_exchangeCapabilities`.
+    ///
+    /// Once the auth server that serves this API sets its registered methods, exactly these are
+    /// reported.
+    pub fn capabilities(&self) -> &EngineCapabilities {
+        &self.inner.capabilities
+    }
+
     /// Fetches the client version.
     async fn get_client_version_v1(
         &self,
@@ -379,16 +432,14 @@ where
         self.inner.task_spawner.spawn_blocking(Box::pin(async move {
             if count > MAX_PAYLOAD_BODIES_LIMIT {
                 tx.send(Err(EngineApiError::PayloadRequestTooLarge { len: count })).ok();
-                return;
+                return
             }
 
             if start == 0 || count == 0 {
                 tx.send(Err(EngineApiError::InvalidBodiesRange { start, count })).ok();
-                return;
+                return
             }
 
-            let mut result = Vec::with_capacity(count as usize);
-
             // -1 so range is inclusive
             let mut end = start.saturating_add(count - 1);
 
@@ -400,19 +451,23 @@ where
                 }
             }
 
-            for num in start..=end {
-                let block_result = inner.provider.block(BlockHashOrNumber::Number(num));
-                match block_result {
-                    Ok(block) => {
-                        result.push(block.map(convert_to_payload_body_v1));
-                    }
-                    Err(err) => {
-                        tx.send(Err(EngineApiError::Internal(Box::new(err)))).ok();
-                        return;
-                    }
-                };
-            }
-            tx.send(Ok(result)).ok();
+            // only the transactions and withdrawals of the blocks are read, unknown blocks are
+            // returned as `None`
+            let result = inner
+                .provider
+                .transactions_and_withdrawals_range(start..=end)
+                .map(|bodies| {
+                    bodies
+                        .into_iter()
+                        .map(|body| {
+                            body.map(|(transactions, withdrawals)| {
+                                convert_to_payload_body_v1_from_parts(transactions, withdrawals)
+                            })
+                        })
+                        .collect()
+                })
+                .map_err(|err| EngineApiError::Internal(Box::new(err)));
+            tx.send(result).ok();
         }));
 
         rx.await.map_err(|err| EngineApiError::Internal(Box::new(err)))?
@@ -441,6 +496,33 @@ where
         Ok(result)
     }
 
+    /// Returns the blob and its proof for each of the given versioned hashes from the blob store
+    /// of the transaction pool.
+    ///
+    /// The entry of a versioned hash is `None` if the blob isn't known.
+    fn get_blobs_v1(
+        &self,
+        versioned_hashes: Vec<B256>,
+    ) -> EngineApiResult<Vec<Option<BlobAndProofV1>>> {
+        if versioned_hashes.len() > MAX_BLOB_LIMIT {
+            return Err(EngineApiError::PayloadRequestTooLarge {
+                len: versioned_hashes.len() as u64,
+            })
+        }
+
+        let blobs = self
+            .inner
+            .tx_pool
+            .get_blobs_for_versioned_hashes(&versioned_hashes)
+            .map_err(|err| EngineApiError::Internal(Box::new(err)))?;
+
+        let misses = blobs.iter().filter(|blob| blob.is_none()).count();
+        self.inner.metrics.blob_metrics.blob_count.increment(versioned_hashes.len() as u64);
+        self.inner.metrics.blob_metrics.blob_misses.increment(misses as u64);
+
+        Ok(blobs)
+    }
+
     /// Called to verify network configuration parameters and ensure that Consensus and Execution
     /// layers are using the latest configuration.
     pub async fn exchange_transition_configuration(
@@ -512,12 +594,26 @@ where
     ///
     /// * If the version above [`EngineApiMessageVersion::V3`], then the payload attributes will be
     ///   validated according to the Cancun rules.
+    ///
+    /// If the node only validates payloads, see [`PayloadBuildingMode`], the forkchoice state is
+    /// applied without the payload attributes.
     async fn validate_and_execute_forkchoice(
         &self,
         version: EngineApiMessageVersion,
         state: ForkchoiceState,
         payload_attrs: Option<EngineT::PayloadAttributes>,
     ) -> EngineApiResult<ForkchoiceUpdated> {
+        if payload_attrs.is_some() && self.inner.payload_building.is_validation_only() {
+            let fcu_res = self.inner.beacon_consensus.fork_choice_updated(state, None).await?;
+            // payload attributes are only processed if the forkchoice state is valid
+            if !fcu_res.is_valid() ||
+                self.inner.payload_building == PayloadBuildingMode::IgnorePayloadAttributes
+            {
+                return Ok(fcu_res)
+            }
+            return Err(EngineApiError::PayloadBuildingDisabled)
+        }
+
         if let Some(ref attrs) = payload_attrs {
             let attr_validation_res =
                 attrs.ensure_well_formed_attributes(&self.inner.chain_spec, version);
@@ -551,10 +647,11 @@ where
 }
 
 #[async_trait]
-impl<Provider, EngineT> EngineApiServer<EngineT> for EngineApi<Provider, EngineT>
+impl<Provider, EngineT, Pool> EngineApiServer<EngineT> for EngineApi<Provider, EngineT, Pool>
 where
     Provider: HeaderProvider + BlockReader + StateProviderFactory + EvmEnvProvider + 'static,
     EngineT: EngineTypes + 'static,
+    Pool: TransactionPool + 'static,
 {
     /// Handler for `engine_newPayloadV1`
     /// See also <https://github.com/ethereum/execution-apis/blob/3d627c95a4d3510a8187dd02e0250ecb4331d27e/src/engine/paris.md#engine_newpayloadv1>
@@ -792,6 +889,20 @@ where
         Ok(res?)
     }
 
+    /// Handler for `engine_getBlobsV1`
+    ///
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_getblobsv1>
+    async fn get_blobs_v1(
+        &self,
+        versioned_hashes: Vec<B256>,
+    ) -> RpcResult<Vec<Option<BlobAndProofV1>>> {
+        trace!(target: "rpc::engine", "Serving engine_getBlobsV1");
+        let start = Instant::now();
+        let res = Self::get_blobs_v1(self, versioned_hashes);
+        self.inner.metrics.latency.get_blobs_v1.record(start.elapsed());
+        Ok(res?)
+    }
+
     /// Handler for `engine_exchangeTransitionConfigurationV1`
     /// See also <https://github.com/ethereum/execution-apis/blob/3d627c95a4d3510a8187dd02e0250ecb4331d27e/src/engine/paris.md#engine_exchangeTransitionConfigurationV1>
     async fn exchange_transition_configuration(
@@ -819,12 +930,25 @@ where
 
     /// Handler for `engine_exchangeCapabilitiesV1`
     /// See also <https://github.com/ethereum/execution-apis/blob/6452a6b194d7db269bf1dbd087a267251d3cc7f8/src/engine/common.md#capabilities>
+    ///
+    /// Reports the methods registered on the auth server, or the supported methods if the auth
+    /// server didn't set them. The `engine_getPayloadVx` methods are not advertised if the node
+    /// only validates payloads.
     async fn exchange_capabilities(&self, _capabilities: Vec<String>) -> RpcResult<Vec<String>> {
-        Ok(CAPABILITIES.iter().cloned().map(str::to_owned).collect())
+        let validation_only = self.inner.payload_building.is_validation_only();
+        let methods = match self.inner.capabilities.registered_methods() {
+            Some(methods) => methods.iter().map(String::as_str).collect::<Vec<_>>(),
+            None => CAPABILITIES.iter().chain(EXTRA_CAPABILITIES).copied().collect(),
+        };
+        Ok(methods
+            .into_iter()
+            .filter(|method| !validation_only || !method.starts_with("engine_getPayloadV"))
+            .map(str::to_owned)
+            .collect())
     }
 }
 
-impl<Provider, EngineT> std::fmt::Debug for EngineApi<Provider, EngineT>
+impl<Provider, EngineT, Pool> std::fmt::Debug for EngineApi<Provider, EngineT, Pool>
 where
     EngineT: EngineTypes,
 {
@@ -837,21 +961,35 @@ where
 mod tests {
     use super::*;
     use assert_matches::assert_matches;
-    use reth_beacon_consensus::{BeaconConsensusEngineEvent, BeaconEngineMessage};
+    use reth_beacon_consensus::{
+        BeaconConsensusEngineEvent, BeaconEngineMessage, OnForkChoiceUpdated,
+    };
     use reth_ethereum_engine_primitives::EthEngineTypes;
     use reth_testing_utils::generators::random_block;
 
     use reth_payload_builder::test_utils::spawn_test_payload_service;
-    use reth_primitives::{SealedBlock, B256, MAINNET};
+    use reth_primitives::{
+        alloy_primitives::FixedBytes, BlobTransactionSidecar, SealedBlock, B256, MAINNET,
+    };
     use reth_provider::test_utils::MockEthProvider;
     use reth_rpc_types::engine::{ClientCode, ClientVersionV1};
     use reth_rpc_types_compat::engine::payload::execution_payload_from_sealed_block;
     use reth_tasks::TokioTaskExecutor;
     use reth_tokio_util::EventSender;
+    use reth_transaction_pool::{
+        blobstore::{BlobStore, InMemoryBlobStore},
+        test_utils::{TestPool, TestPoolBuilder},
+    };
     use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 
-    fn setup_engine_api() -> (EngineApiTestHandle, EngineApi<Arc<MockEthProvider>, EthEngineTypes>)
-    {
+    fn setup_engine_api(
+    ) -> (EngineApiTestHandle, EngineApi<Arc<MockEthProvider>, EthEngineTypes, TestPool>) {
+        setup_engine_api_with_payload_building(PayloadBuildingMode::default())
+    }
+
+    fn setup_engine_api_with_payload_building(
+        payload_building: PayloadBuildingMode,
+    ) -> (EngineApiTestHandle, EngineApi<Arc<MockEthProvider>, EthEngineTypes, TestPool>) {
         let client = ClientVersionV1 {
             code: ClientCode::RH,
             name: "Reth".to_string(),
@@ -865,15 +1003,18 @@ mod tests {
         let (to_engine, engine_rx) = unbounded_channel();
         let event_sender: EventSender<BeaconConsensusEngineEvent> = Default::default();
         let task_executor = Box::<TokioTaskExecutor>::default();
+        let blob_store = InMemoryBlobStore::default();
         let api = EngineApi::new(
             provider.clone(),
             chain_spec.clone(),
             BeaconConsensusEngineHandle::new(to_engine, event_sender),
             payload_store.into(),
+            TestPoolBuilder::default().with_blob_store(blob_store.clone()).into(),
             task_executor,
             client,
+            payload_building,
         );
-        let handle = EngineApiTestHandle { chain_spec, provider, from_api: engine_rx };
+        let handle = EngineApiTestHandle { chain_spec, provider, blob_store, from_api: engine_rx };
         (handle, api)
     }
 
@@ -890,9 +1031,32 @@ mod tests {
         assert_eq!(res.unwrap(), vec![client]);
     }
 
+    #[tokio::test]
+    async fn engine_get_blobs_v1() {
+        let (handle, api) = setup_engine_api();
+
+        let sidecar = BlobTransactionSidecar {
+            blobs: vec![Default::default()],
+            commitments: vec![FixedBytes::repeat_byte(1)],
+            proofs: vec![FixedBytes::repeat_byte(2)],
+        };
+        let versioned_hash = sidecar.versioned_hashes().next().unwrap();
+        handle.blob_store.insert(B256::repeat_byte(0x11), sidecar.clone()).unwrap();
+
+        // unknown versioned hashes are null
+        let res = api.get_blobs_v1(vec![B256::repeat_byte(0x22), versioned_hash]).unwrap();
+        let expected =
+            BlobAndProofV1 { blob: Box::new(sidecar.blobs[0]), proof: sidecar.proofs[0] };
+        assert_eq!(res, vec![None, Some(expected)]);
+
+        let res = api.get_blobs_v1(vec![versioned_hash; MAX_BLOB_LIMIT + 1]);
+        assert_matches!(res, Err(EngineApiError::PayloadRequestTooLarge { len }) if len == MAX_BLOB_LIMIT as u64 + 1);
+    }
+
     struct EngineApiTestHandle {
         chain_spec: Arc<ChainSpec>,
         provider: Arc<MockEthProvider>,
+        blob_store: InMemoryBlobStore,
         from_api: UnboundedReceiver<BeaconEngineMessage<EthEngineTypes>>,
     }
 
@@ -908,6 +1072,61 @@ mod tests {
         assert_matches!(handle.from_api.recv().await, Some(BeaconEngineMessage::NewPayload { .. }));
     }
 
+    #[tokio::test]
+    async fn validation_only_strips_payload_attributes() {
+        let (mut handle, api) =
+            setup_engine_api_with_payload_building(PayloadBuildingMode::ValidationOnly);
+
+        let attrs = reth_rpc_types::engine::PayloadAttributes {
+            timestamp: 1,
+            prev_randao: B256::ZERO,
+            suggested_fee_recipient: Default::default(),
+            withdrawals: None,
+            parent_beacon_block_root: None,
+        };
+        let fcu = tokio::spawn(async move {
+            api.fork_choice_updated_v1(ForkchoiceState::default(), Some(attrs)).await
+        });
+
+        let Some(BeaconEngineMessage::ForkchoiceUpdated { payload_attrs, tx, .. }) =
+            handle.from_api.recv().await
+        else {
+            panic!("expected forkchoice update")
+        };
+        assert!(payload_attrs.is_none());
+
+        // the attributes are not rejected if the forkchoice state isn't valid
+        tx.send(Ok(OnForkChoiceUpdated::syncing())).unwrap();
+        assert!(fcu.await.unwrap().unwrap().is_syncing());
+    }
+
+    #[tokio::test]
+    async fn validation_only_capabilities() {
+        let (_, api) = setup_engine_api();
+        let capabilities = api.exchange_capabilities(vec![]).await.unwrap();
+        assert!(capabilities.iter().any(|method| method == "engine_getPayloadV1"));
+
+        let (_, api) =
+            setup_engine_api_with_payload_building(PayloadBuildingMode::IgnorePayloadAttributes);
+        let capabilities = api.exchange_capabilities(vec![]).await.unwrap();
+        assert!(!capabilities.iter().any(|method| method.starts_with("engine_getPayloadV")));
+        assert!(capabilities.iter().any(|method| method == "engine_getPayloadBodiesByHashV1"));
+        assert!(capabilities.iter().any(|method| method == "engine_forkchoiceUpdatedV3"));
+    }
+
+    #[tokio::test]
+    async fn registered_capabilities() {
+        let (_, api) = setup_engine_api();
+        assert!(api.capabilities().set_registered_methods([
+            "engine_newPayloadV3",
+            "engine_getPayloadV3",
+            "engine_exchangeCapabilities",
+            "eth_chainId",
+        ]));
+        let capabilities = api.exchange_capabilities(vec![]).await.unwrap();
+        assert_eq!(capabilities, vec!["engine_getPayloadV3", "engine_newPayloadV3"]);
+    }
+
     // tests covering `engine_getPayloadBodiesByRange` and `engine_getPayloadBodiesByHash`
     mod get_payload_bodies {
         use super::*;
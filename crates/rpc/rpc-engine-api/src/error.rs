@@ -8,13 +8,13 @@ use reth_primitives::{B256, U256};
 use reth_rpc_types::ToRpcError;
 use thiserror::Error;
 
+pub use reth_rpc_api::UNSUPPORTED_FORK_CODE;
+
 /// The Engine API result type
 pub type EngineApiResult<Ok> = Result<Ok, EngineApiError>;
 
 /// Invalid payload attributes code.
 pub const INVALID_PAYLOAD_ATTRIBUTES: i32 = -38003;
-/// Payload unsupported fork code.
-pub const UNSUPPORTED_FORK_CODE: i32 = -38005;
 /// Payload unknown error code.
 pub const UNKNOWN_PAYLOAD_CODE: i32 = -38001;
 /// Request too large error code.
@@ -84,6 +84,9 @@ pub enum EngineApiError {
     /// Fetching the payload failed
     #[error(transparent)]
     GetPayloadError(#[from] PayloadBuilderError),
+    /// Payload attributes were sent to a node that doesn't build payloads.
+    #[error("payload building is disabled")]
+    PayloadBuildingDisabled,
     /// The payload or attributes are known to be malformed before processing.
     #[error(transparent)]
     EngineObjectValidationError(#[from] EngineObjectValidationError),
@@ -133,7 +136,8 @@ impl From<EngineApiError> for jsonrpsee_types::error::ErrorObject<'static> {
             }
             EngineApiError::EngineObjectValidationError(
                 EngineObjectValidationError::PayloadAttributes(_),
-            ) => {
+            ) |
+            EngineApiError::PayloadBuildingDisabled => {
                 // Note: the data field is not required by the spec, but is also included by other
                 // clients
                 jsonrpsee_types::error::ErrorObject::owned(
@@ -258,6 +262,12 @@ mod tests {
             )),
         );
 
+        ensure_engine_rpc_error(
+            INVALID_PAYLOAD_ATTRIBUTES,
+            INVALID_PAYLOAD_ATTRIBUTES_MSG,
+            EngineApiError::PayloadBuildingDisabled,
+        );
+
         ensure_engine_rpc_error(
             UNKNOWN_PAYLOAD_CODE,
             "Unknown payload",
@@ -21,7 +21,7 @@ mod error;
 /// Engine API metrics.
 mod metrics;
 
-pub use engine_api::{EngineApi, EngineApiSender};
+pub use engine_api::{EngineApi, EngineApiSender, PayloadBuildingMode};
 pub use error::*;
 pub use message::EngineApiMessageVersion;
 
@@ -14,6 +14,8 @@ pub(crate) struct EngineApiMetrics {
     pub(crate) fcu_response: ForkchoiceUpdatedResponseMetrics,
     /// Engine API newPayload response type metrics
     pub(crate) new_payload_response: NewPayloadStatusResponseMetrics,
+    /// Engine API getBlobs metrics
+    pub(crate) blob_metrics: BlobMetrics,
 }
 
 /// Beacon consensus engine latency metrics.
@@ -46,6 +48,8 @@ pub(crate) struct EngineApiLatencyMetrics {
     pub(crate) get_payload_bodies_by_range_v1: Histogram,
     /// Latency for `engine_getPayloadBodiesByHashV1`
     pub(crate) get_payload_bodies_by_hash_v1: Histogram,
+    /// Latency for `engine_getBlobsV1`
+    pub(crate) get_blobs_v1: Histogram,
     /// Latency for `engine_exchangeTransitionConfigurationV1`
     pub(crate) exchange_transition_configuration: Histogram,
 }
@@ -73,6 +77,18 @@ pub(crate) struct ForkchoiceUpdatedResponseMetrics {
     pub(crate) forkchoice_updated_error: Counter,
 }
 
+/// Metrics for engine API getBlobs requests.
+///
+/// The ratio of the blobs that were served from the blob store is `1 - misses / requested`.
+#[derive(Metrics)]
+#[metrics(scope = "engine.rpc.blobs")]
+pub(crate) struct BlobMetrics {
+    /// The total count of blobs that were requested.
+    pub(crate) blob_count: Counter,
+    /// The total count of requested blobs that were not found in the blob store.
+    pub(crate) blob_misses: Counter,
+}
+
 /// Metrics for engine API newPayload responses.
 #[derive(Metrics)]
 #[metrics(scope = "engine.rpc")]
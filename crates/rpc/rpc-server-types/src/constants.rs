@@ -1,4 +1,4 @@
-use std::cmp::max;
+use std::{cmp::max, time::Duration};
 
 /// The default port for the http server
 pub const DEFAULT_HTTP_RPC_PORT: u16 = 8545;
@@ -15,6 +15,69 @@ pub const DEFAULT_MAX_BLOCKS_PER_FILTER: u64 = 100_000;
 /// The default maximum of logs in a single response.
 pub const DEFAULT_MAX_LOGS_PER_RESPONSE: usize = 20_000;
 
+/// The default time a filter remains installed without being polled.
+pub const DEFAULT_STALE_FILTER_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// The default maximum number of filters a single connection can have installed at once.
+pub const DEFAULT_MAX_ACTIVE_FILTERS: usize = 1_000;
+
+/// The default maximum number of blocks the first poll of a log filter that was restored after a
+/// restart returns the changes of.
+pub const DEFAULT_FILTER_CATCH_UP_BLOCKS: u64 = 1_000;
+
+/// The default maximum distance from the tip (in blocks) for which `eth_getProof` generates
+/// proofs. Proofs for older blocks are rejected.
+pub const DEFAULT_ETH_PROOF_WINDOW: u64 = 0;
+
+/// The maximum allowed value for the `eth_getProof` window, roughly four weeks of blocks at 12
+/// seconds per block.
+pub const MAX_ETH_PROOF_WINDOW: u64 = 28 * 24 * 60 * 60 / 12;
+
+/// The maximum number of blocks that can be simulated by a single `eth_simulateV1` request,
+/// including the empty blocks filling the gaps between the requested block numbers.
+pub const MAX_SIMULATE_BLOCKS: u64 = 256;
+
+/// The default time `eth_sendRawTransactionSync` waits for the transaction to be included in a
+/// canonical block if the request doesn't specify a timeout.
+pub const DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The default upper bound of the timeout of `eth_sendRawTransactionSync`. Requested timeouts
+/// above are capped.
+pub const DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The default maximum cost of the conditional of a transaction submitted with
+/// `eth_sendRawTransactionConditional`, which is the number of storage roots and slots it checks.
+pub const DEFAULT_MAX_TX_CONDITIONAL_COST: usize = 1000;
+
+/// The default memory limit of the EVM in bytes for `eth_call` and tracing RPC methods, the same
+/// as the default limit of revm.
+pub const DEFAULT_RPC_MEMORY_LIMIT: u64 = (1 << 32) - 1;
+
+/// The minimum allowed value for the EVM memory limit of the RPC methods, 1 MiB.
+pub const MIN_RPC_MEMORY_LIMIT: u64 = 1 << 20;
+
+/// The maximum allowed value for the EVM memory limit of the RPC methods, 64 GiB.
+pub const MAX_RPC_MEMORY_LIMIT: u64 = 1 << 36;
+
+/// The default maximum number of RPC calls executed concurrently across all connections.
+pub const DEFAULT_MAX_BATCH_CONCURRENCY: usize = 1024;
+
+/// The default maximum number of RPC calls of a single connection executed concurrently, e.g.
+/// the calls of a batch request.
+pub const DEFAULT_MAX_CONCURRENT_CALLS_PER_CONNECTION: usize = 32;
+
+/// The default maximum number of RPC calls of a single connection waiting to be executed. Calls
+/// above the limit are rejected.
+pub const DEFAULT_MAX_QUEUED_CALLS_PER_CONNECTION: usize = 10_000;
+
+/// The default maximum number of notifications buffered per subscription for subscribers that
+/// don't keep up.
+pub const DEFAULT_MAX_SUBSCRIPTION_BUFFER: usize = 1024;
+
+/// The default maximum number of addresses of the filter of a `newPendingTransactions`
+/// subscription.
+pub const DEFAULT_MAX_TRANSACTION_FILTER_ADDRESSES: usize = 1000;
+
 /// The default maximum number tracing requests we're allowing concurrently.
 /// Tracing is mostly CPU bound so we're limiting the number of concurrent requests to something
 /// lower that the number of cores, in order to minimize the impact on the rest of the system.
@@ -26,6 +89,39 @@ pub fn default_max_tracing_requests() -> usize {
         .map_or(25, |cpus| max(cpus.get().saturating_sub(RESERVED), RESERVED))
 }
 
+/// The default maximum number of transaction pre-states held at once when the transactions of a
+/// block are traced in parallel, e.g. for `debug_traceBlockByNumber`.
+pub const DEFAULT_MAX_TRACE_BLOCK_CHECKPOINTS: usize = 64;
+
+/// The default maximum time the execution of a traced call can take before it's aborted.
+pub const DEFAULT_TRACING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The default maximum combined size of the struct logs recorded for a single traced call,
+/// 512 MiB.
+pub const DEFAULT_MAX_STRUCT_LOGS_MEMORY: usize = 1 << 29;
+
+/// The default maximum time a call traced with a custom JavaScript tracer can take, same as geth.
+pub const DEFAULT_JS_TRACER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The default maximum number of threads that execute custom JavaScript tracers.
+pub const DEFAULT_MAX_JS_TRACER_THREADS: usize = 16;
+
+/// The default maximum number of blocks a single `debug_traceChain` subscription can trace.
+pub const DEFAULT_MAX_TRACE_CHAIN_BLOCKS: u64 = 10_000;
+
+/// The default maximum number of blocks a single `trace_filter` request can scan.
+pub const DEFAULT_MAX_TRACE_FILTER_BLOCKS: u64 = 100;
+
+/// The default maximum number of traces a single `trace_filter` request can return.
+pub const DEFAULT_MAX_TRACE_FILTER_RESULTS: usize = 10_000;
+
+/// The default maximum number of instructions recorded for the `vmTrace` of a single call.
+pub const DEFAULT_MAX_VM_TRACE_STEPS: usize = 1_000_000;
+
+/// The default maximum combined size of the memory snapshots recorded for the `vmTrace` of a
+/// single call, 512 MiB.
+pub const DEFAULT_MAX_VM_TRACE_MEMORY: usize = 1 << 29;
+
 /// The default IPC endpoint
 #[cfg(windows)]
 pub const DEFAULT_IPC_ENDPOINT: &str = r"\\.\pipe\reth.ipc";
@@ -46,17 +142,18 @@ pub const DEFAULT_ENGINE_API_IPC_ENDPOINT: &str = "/tmp/reth_engine_api.ipc";
 pub mod gas_oracle {
     use alloy_primitives::U256;
 
-    /// The number of transactions sampled in a block
-    pub const SAMPLE_NUMBER: usize = 3_usize;
-
     /// The default maximum number of blocks to use for the gas price oracle.
     pub const MAX_HEADER_HISTORY: u64 = 1024;
 
     /// Number of recent blocks to check for gas price
     pub const DEFAULT_GAS_PRICE_BLOCKS: u32 = 20;
 
-    /// The percentile of gas prices to use for the estimate
-    pub const DEFAULT_GAS_PRICE_PERCENTILE: u32 = 60;
+    /// The minimum number of populated blocks required for the estimate, the default gas price is
+    /// used if fewer blocks are available
+    pub const MIN_GAS_PRICE_BLOCKS: u32 = 5;
+
+    /// The percentile of the rewards of a block to use for the estimate
+    pub const DEFAULT_GAS_PRICE_PERCENTILE: u32 = 20;
 
     /// Maximum transaction priority fee (or gas price before London Fork) to be recommended by the
     /// gas price oracle
@@ -68,7 +165,6 @@ pub mod gas_oracle {
 
 /// Cache specific constants
 pub mod cache {
-    // TODO: memory based limiter is currently disabled pending <https://github.com/paradigmxyz/reth/issues/3503>
     /// Default cache size for the block cache: 500MB
     ///
     /// With an average block size of ~100kb this should be able to cache ~5000 blocks.
@@ -77,13 +173,26 @@ pub mod cache {
     /// Default cache size for the receipts cache: 500MB
     pub const DEFAULT_RECEIPT_CACHE_SIZE_BYTES_MB: usize = 500;
 
+    /// Default cache size for the header cache: 50MB
+    ///
+    /// With an average header size of ~600 bytes this should be able to cache ~80000 headers.
+    pub const DEFAULT_HEADER_CACHE_SIZE_BYTES_MB: usize = 50;
+
     /// Default cache size for the env cache: 1MB
+    #[deprecated(note = "the env cache is limited by count, use `DEFAULT_ENV_CACHE_MAX_LEN`")]
     pub const DEFAULT_ENV_CACHE_SIZE_BYTES_MB: usize = 1;
 
     /// Default cache size for the block cache: 5000 blocks.
+    ///
+    /// The block cache is limited by [`DEFAULT_BLOCK_CACHE_SIZE_BYTES_MB`], this is the number of
+    /// blocks that fit into the default size, used to convert the deprecated count based limit.
     pub const DEFAULT_BLOCK_CACHE_MAX_LEN: u32 = 5000;
 
     /// Default cache size for the receipts cache: 2000 receipts.
+    ///
+    /// The receipts cache is limited by [`DEFAULT_RECEIPT_CACHE_SIZE_BYTES_MB`], this is the
+    /// number of blocks' receipts that fit into the default size, used to convert the deprecated
+    /// count based limit.
     pub const DEFAULT_RECEIPT_CACHE_MAX_LEN: u32 = 2000;
 
     /// Default cache size for the env cache: 1000 envs.
@@ -91,4 +200,10 @@ pub mod cache {
 
     /// Default number of concurrent database requests.
     pub const DEFAULT_CONCURRENT_DB_REQUESTS: usize = 512;
+
+    /// Default cache size for the fee history cache: 1124 blocks.
+    ///
+    /// This is the max block count of `eth_feeHistory` plus some change to also serve slightly
+    /// older blocks from cache.
+    pub const DEFAULT_FEE_HISTORY_CACHE_MAX_LEN: u64 = 1124;
 }
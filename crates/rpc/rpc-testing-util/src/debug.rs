@@ -5,11 +5,8 @@ use jsonrpsee::core::client::Error as RpcError;
 use reth_primitives::{BlockId, TxHash, B256};
 use reth_rpc_api::{clients::DebugApiClient, EthApiClient};
 use reth_rpc_types::{
-    trace::{
-        common::TraceResult,
-        geth::{GethDebugTracerType, GethDebugTracingOptions, GethTrace},
-    },
-    TransactionRequest,
+    trace::geth::{GethDebugTracerType, GethDebugTracingOptions},
+    DebugTraceResult, TransactionRequest,
 };
 use std::{
     future::Future,
@@ -24,8 +21,7 @@ const JS_TRACER_TEMPLATE: &str = include_str!("../assets/tracer-template.js");
 pub type TraceTransactionResult = Result<(serde_json::Value, TxHash), (RpcError, TxHash)>;
 
 /// A result type for the `debug_trace_block` method that also captures the requested block.
-pub type DebugTraceBlockResult =
-    Result<(Vec<TraceResult<GethTrace, String>>, BlockId), (RpcError, BlockId)>;
+pub type DebugTraceBlockResult = Result<(Vec<DebugTraceResult>, BlockId), (RpcError, BlockId)>;
 
 /// An extension trait for the Trace API.
 pub trait DebugApiExt {
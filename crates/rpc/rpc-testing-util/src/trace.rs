@@ -7,10 +7,10 @@ use reth_rpc_api::clients::TraceApiClient;
 use reth_rpc_types::{
     trace::{
         filter::TraceFilter,
-        parity::{LocalizedTransactionTrace, TraceResults, TraceType},
+        parity::{LocalizedTransactionTrace, TraceType},
         tracerequest::TraceCallRequest,
     },
-    Index, TransactionRequest,
+    Index, LimitedTraceResults, TransactionRequest,
 };
 use std::{
     collections::HashSet,
@@ -20,17 +20,17 @@ use std::{
 
 /// A type alias that represents the result of a raw transaction trace stream.
 type RawTransactionTraceResult<'a> =
-    Pin<Box<dyn Stream<Item = Result<(TraceResults, Bytes), (RpcError, Bytes)>> + 'a>>;
+    Pin<Box<dyn Stream<Item = Result<(LimitedTraceResults, Bytes), (RpcError, Bytes)>> + 'a>>;
 
 /// A result type for the `trace_block` method that also captures the requested block.
 pub type TraceBlockResult = Result<(Vec<LocalizedTransactionTrace>, BlockId), (RpcError, BlockId)>;
 
 /// Type alias representing the result of replaying a transaction.
-pub type ReplayTransactionResult = Result<(TraceResults, TxHash), (RpcError, TxHash)>;
+pub type ReplayTransactionResult = Result<(LimitedTraceResults, TxHash), (RpcError, TxHash)>;
 
 /// A type representing the result of calling `trace_call_many` method.
 pub type CallManyTraceResult = Result<
-    (Vec<TraceResults>, Vec<(TransactionRequest, HashSet<TraceType>)>),
+    (Vec<LimitedTraceResults>, Vec<(TransactionRequest, HashSet<TraceType>)>),
     (RpcError, Vec<(TransactionRequest, HashSet<TraceType>)>),
 >;
 
@@ -44,7 +44,7 @@ pub type TraceFilterResult =
     Result<(Vec<LocalizedTransactionTrace>, TraceFilter), (RpcError, TraceFilter)>;
 
 /// Represents the result of a single trace call.
-pub type TraceCallResult = Result<TraceResults, (RpcError, TraceCallRequest)>;
+pub type TraceCallResult = Result<LimitedTraceResults, (RpcError, TraceCallRequest)>;
 
 /// An extension trait for the Trace API.
 pub trait TraceApiExt {
@@ -203,7 +203,7 @@ pub struct RawTransactionTraceStream<'a> {
 }
 
 impl<'a> Stream for RawTransactionTraceStream<'a> {
-    type Item = Result<(TraceResults, Bytes), (RpcError, Bytes)>;
+    type Item = Result<(LimitedTraceResults, Bytes), (RpcError, Bytes)>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         self.stream.as_mut().poll_next(cx)
@@ -4,7 +4,8 @@
 use reth_primitives::{
     constants::{EMPTY_OMMER_ROOT_HASH, MAXIMUM_EXTRA_DATA_SIZE, MIN_PROTOCOL_BASE_FEE_U256},
     proofs::{self},
-    Block, Header, Request, SealedBlock, TransactionSigned, UintTryTo, Withdrawals, B256, U256,
+    Block, Header, Request, SealedBlock, TransactionSigned, TransactionSignedNoHash, UintTryTo,
+    Withdrawals, B256, U256,
 };
 use reth_rpc_types::engine::{
     payload::{ExecutionPayloadBodyV1, ExecutionPayloadFieldV2, ExecutionPayloadInputV2},
@@ -368,6 +369,23 @@ pub fn convert_to_payload_body_v1(value: Block) -> ExecutionPayloadBodyV1 {
     }
 }
 
+/// Converts the transactions and withdrawals of a block into an [`ExecutionPayloadBodyV1`]
+/// without computing the transaction hashes.
+pub fn convert_to_payload_body_v1_from_parts(
+    transactions: Vec<TransactionSignedNoHash>,
+    withdrawals: Option<Withdrawals>,
+) -> ExecutionPayloadBodyV1 {
+    let transactions = transactions.into_iter().map(|tx| {
+        let mut out = Vec::new();
+        tx.transaction.encode_with_signature(&tx.signature, &mut out, false);
+        out.into()
+    });
+    ExecutionPayloadBodyV1 {
+        transactions: transactions.collect(),
+        withdrawals: withdrawals.map(Withdrawals::into_inner),
+    }
+}
+
 /// Transforms a [`SealedBlock`] into a [`ExecutionPayloadV1`]
 pub fn execution_payload_from_sealed_block(value: SealedBlock) -> ExecutionPayloadV1 {
     let transactions = value.raw_transactions();
@@ -1,8 +1,9 @@
 //! Compatibility functions for rpc proof related types.
 
 use reth_primitives::{
-    trie::{AccountProof, StorageProof},
-    U64,
+    constants::EMPTY_ROOT_HASH,
+    trie::{proof::ProofVerificationError, AccountProof, StorageProof},
+    Account, B256, KECCAK_EMPTY, U64,
 };
 use reth_rpc_types::{
     serde_helpers::JsonStorageKey, EIP1186AccountProofResponse, EIP1186StorageProof,
@@ -26,3 +27,92 @@ pub fn from_primitive_account_proof(proof: AccountProof) -> EIP1186AccountProofR
         storage_proof: proof.storage_proofs.into_iter().map(from_primitive_storage_proof).collect(),
     }
 }
+
+/// Creates a primitive account proof from the rpc account proof, e.g. an `eth_getProof` response.
+///
+/// The account is considered nonexistent if it's empty and has no storage.
+pub fn to_primitive_account_proof(response: &EIP1186AccountProofResponse) -> AccountProof {
+    let storage_proofs = response
+        .storage_proof
+        .iter()
+        .map(|storage_proof| {
+            let mut proof = StorageProof::new(storage_proof.key.0);
+            proof.set_value(storage_proof.value);
+            proof.set_proof(storage_proof.proof.clone());
+            proof
+        })
+        .collect();
+
+    let info = Account {
+        nonce: response.nonce.to(),
+        balance: response.balance,
+        // Nonexistent accounts are returned with either empty or zero code hash.
+        bytecode_hash: (response.code_hash != KECCAK_EMPTY && !response.code_hash.is_zero())
+            .then_some(response.code_hash),
+    };
+
+    let mut proof = AccountProof::new(response.address);
+    proof.set_proof(response.account_proof.clone());
+    if info.is_empty() && response.storage_hash == EMPTY_ROOT_HASH {
+        proof.storage_proofs = storage_proofs;
+    } else {
+        proof.set_account(info, response.storage_hash, storage_proofs);
+    }
+    proof
+}
+
+/// Verifies the account and storage proofs of the rpc account proof against the given state root.
+pub fn verify_account_proof(
+    response: &EIP1186AccountProofResponse,
+    expected_root: B256,
+) -> Result<(), ProofVerificationError> {
+    to_primitive_account_proof(response).verify(expected_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Address, StorageEntry, U256};
+    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter};
+    use reth_trie::{proof::Proof, StateRoot};
+
+    #[test]
+    fn verify_eth_get_proof_response() {
+        let factory = create_test_provider_factory();
+        let target = Address::with_last_byte(0x42);
+        let storage = [
+            StorageEntry { key: B256::with_last_byte(0x22), value: U256::from(1) },
+            StorageEntry { key: B256::from(U256::from(0x100)), value: U256::from(2) },
+        ];
+
+        let mut provider = factory.provider_rw().unwrap();
+        provider
+            .insert_account_for_hashing([
+                (target, Some(Account { nonce: 1, balance: U256::from(10), bytecode_hash: None })),
+                (Address::with_last_byte(0x43), Some(Account::default())),
+            ])
+            .unwrap();
+        provider.insert_storage_for_hashing([(target, storage)]).unwrap();
+        let (root, updates) = StateRoot::from_tx(provider.tx_ref()).root_with_updates().unwrap();
+        updates.flush(provider.tx_mut()).unwrap();
+        provider.commit().unwrap();
+
+        // Existent slots and non-existent 0x101
+        let slots = storage
+            .iter()
+            .map(|entry| entry.key)
+            .chain([B256::from(U256::from(0x101))])
+            .collect::<Vec<_>>();
+        let provider = factory.provider().unwrap();
+        let account_proof = Proof::new(provider.tx_ref()).account_proof(target, &slots).unwrap();
+        assert_ne!(account_proof.storage_root, EMPTY_ROOT_HASH);
+
+        let mut response = from_primitive_account_proof(account_proof.clone());
+        assert_eq!(to_primitive_account_proof(&response), account_proof);
+        assert_eq!(verify_account_proof(&response, root), Ok(()));
+
+        // Tampered storage value doesn't match the proof
+        response.storage_proof[0].value += U256::from(1);
+        assert!(verify_account_proof(&response, root).is_err());
+    }
+}
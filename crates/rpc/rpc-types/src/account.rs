@@ -0,0 +1,44 @@
+//! Types for `eth_getAccount` and `eth_getAccountInfo`.
+
+use alloy_primitives::{Bytes, B256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+/// The account returned by `eth_getAccount`, as stored in the state trie.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EthAccount {
+    /// The balance of the account.
+    pub balance: U256,
+    /// The nonce of the account.
+    pub nonce: U64,
+    /// The hash of the code of the account.
+    pub code_hash: B256,
+    /// The root of the storage trie of the account.
+    pub storage_root: B256,
+}
+
+/// The account returned by `eth_getAccountInfo`, including its code.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EthAccountInfo {
+    /// The balance of the account.
+    pub balance: U256,
+    /// The nonce of the account.
+    pub nonce: U64,
+    /// The code of the account.
+    pub code: Bytes,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_eth_account() {
+        let s = r#"{"balance":"0x3e8","nonce":"0x1","codeHash":"0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470","storageRoot":"0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"}"#;
+        let account: EthAccount = serde_json::from_str(s).unwrap();
+        assert_eq!(account.balance, U256::from(1000));
+        assert_eq!(account.nonce, U64::from(1));
+        assert_eq!(serde_json::to_string(&account).unwrap(), s);
+    }
+}
@@ -0,0 +1,20 @@
+//! Types for `eth_callMany`.
+
+use alloy_primitives::{Bytes, U64};
+use serde::{Deserialize, Serialize};
+
+/// The result of a call executed by `eth_callMany`.
+///
+/// Unlike `EthCallResponse`, this includes the gas used by the call.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EthCallManyResponse {
+    /// The output of the call, if it succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Bytes>,
+    /// The gas used by the call.
+    pub gas_used: U64,
+    /// The reason the call reverted or halted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
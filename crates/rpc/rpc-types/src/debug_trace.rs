@@ -0,0 +1,144 @@
+//! Types for the tracing methods of the `debug` namespace.
+
+use alloy_primitives::{Address, B256, U64};
+use alloy_rpc_types_trace::{
+    common::TraceResult,
+    geth::{DefaultFrame, GethTrace},
+};
+use serde::{Deserialize, Serialize};
+
+/// A trace of the `debug` namespace.
+///
+/// Serializes as the [`GethTrace`] of the requested tracer, the struct logs additionally carry
+/// whether they were cut off at the limits of the node.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DebugTrace {
+    /// The struct logs of the default tracer.
+    StructLogs(LimitedDefaultFrame),
+    /// The trace of a system call of a traced block.
+    System(SystemCallFrame),
+    /// The trace of a built-in or JavaScript tracer.
+    Geth(GethTrace),
+    /// The result of a custom tracer registered by the node.
+    ///
+    /// Deserializes as the [`GethTrace::JS`] of [`DebugTrace::Geth`], which has the same form.
+    Custom(serde_json::Value),
+}
+
+impl From<GethTrace> for DebugTrace {
+    fn from(trace: GethTrace) -> Self {
+        match trace {
+            GethTrace::Default(frame) => Self::StructLogs(frame.into()),
+            trace => Self::Geth(trace),
+        }
+    }
+}
+
+impl From<LimitedDefaultFrame> for DebugTrace {
+    fn from(frame: LimitedDefaultFrame) -> Self {
+        Self::StructLogs(frame)
+    }
+}
+
+/// The type of a [`SystemCallFrame`], serialized as `SYSTEM`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SystemCallFrameType {
+    /// A system call of the block.
+    #[default]
+    #[serde(rename = "SYSTEM")]
+    System,
+}
+
+/// Wraps the trace of a system call of a block, e.g. the beacon root contract call, which sets it
+/// apart from the traces of the transactions of the block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SystemCallFrame {
+    /// The type of the frame.
+    #[serde(rename = "type")]
+    pub typ: SystemCallFrameType,
+    /// The caller of the system call.
+    pub from: Address,
+    /// The called system contract.
+    pub to: Option<Address>,
+    /// The trace of the system call, of the requested tracer.
+    pub result: Box<DebugTrace>,
+}
+
+/// The [`DefaultFrame`] of a call with the struct logs cut off at the limits of the node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitedDefaultFrame {
+    /// The struct logs of the call.
+    #[serde(flatten)]
+    pub frame: DefaultFrame,
+    /// Whether instructions are left out because the struct logs exceeded the limits of the node.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+impl From<DefaultFrame> for LimitedDefaultFrame {
+    fn from(frame: DefaultFrame) -> Self {
+        Self { frame, truncated: false }
+    }
+}
+
+/// The [`DebugTrace`] of a transaction of a traced block.
+pub type DebugTraceResult = TraceResult<DebugTrace, String>;
+
+/// The [`DebugTraceResult`]s of the transactions of a block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DebugBlockTraceResult {
+    /// The number of the block.
+    pub block: U64,
+    /// The hash of the block.
+    pub hash: B256,
+    /// The traces of the transactions of the block.
+    pub traces: Vec<DebugTraceResult>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn struct_logs_serialize_as_default_frame() {
+        let frame = DefaultFrame { gas: 21_000, ..Default::default() };
+        let trace = DebugTrace::from(GethTrace::Default(frame.clone()));
+        assert_eq!(
+            serde_json::to_value(&trace).unwrap(),
+            serde_json::to_value(GethTrace::Default(frame.clone())).unwrap()
+        );
+        assert_eq!(
+            serde_json::from_value::<DebugTrace>(serde_json::to_value(&trace).unwrap()).unwrap(),
+            trace
+        );
+
+        let custom = DebugTrace::Custom(serde_json::json!({ "touched": ["0x01"] }));
+        assert_eq!(
+            serde_json::to_value(&custom).unwrap(),
+            serde_json::json!({ "touched": ["0x01"] })
+        );
+
+        let truncated = DebugTrace::StructLogs(LimitedDefaultFrame { frame, truncated: true });
+        let value = serde_json::to_value(&truncated).unwrap();
+        assert_eq!(value["truncated"], true);
+        assert_eq!(value["gas"], 21_000);
+        assert_eq!(serde_json::from_value::<DebugTrace>(value).unwrap(), truncated);
+    }
+
+    #[test]
+    fn system_call_frame_roundtrip() {
+        let call = GethTrace::JS(serde_json::json!({ "gasUsed": 1 }));
+        let trace = DebugTrace::System(SystemCallFrame {
+            typ: SystemCallFrameType::System,
+            from: Address::with_last_byte(1),
+            to: Some(Address::with_last_byte(2)),
+            result: Box::new(call.clone().into()),
+        });
+        let value = serde_json::to_value(&trace).unwrap();
+        assert_eq!(value["type"], "SYSTEM");
+        assert_eq!(value["result"], serde_json::to_value(call).unwrap());
+        assert_eq!(serde_json::from_value::<DebugTrace>(value).unwrap(), trace);
+    }
+}
@@ -10,11 +10,27 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod account;
+mod call;
+mod debug_trace;
 mod eth;
 mod mev;
 mod net;
+mod node_info;
+mod payload_events;
 mod peer;
+mod reorg;
+mod replay;
 mod rpc;
+mod simulate;
+mod storage_range;
+mod subscription;
+mod trace_call;
+mod trace_chain;
+mod trace_results;
+mod txpool_ext;
+mod txpool_snapshot;
+mod validation;
 
 // re-export for convenience
 pub use alloy_rpc_types::serde_helpers;
@@ -43,7 +59,23 @@ pub use eth::{
     transaction::{self, TransactionRequest, TypedTransactionRequest},
 };
 
+pub use account::*;
+pub use call::*;
+pub use debug_trace::*;
 pub use mev::*;
 pub use net::*;
+pub use node_info::*;
+pub use payload_events::*;
 pub use peer::*;
+pub use reorg::*;
+pub use replay::*;
 pub use rpc::*;
+pub use simulate::*;
+pub use storage_range::*;
+pub use subscription::*;
+pub use trace_call::*;
+pub use trace_chain::*;
+pub use trace_results::*;
+pub use txpool_ext::*;
+pub use txpool_snapshot::*;
+pub use validation::*;
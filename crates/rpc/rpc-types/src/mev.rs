@@ -740,6 +740,13 @@ pub struct EthCallBundleTransactionResult {
     /// Contains the return data if the transaction reverted
     #[serde(skip_serializing_if = "Option::is_none")]
     pub revert: Option<Bytes>,
+    /// The error if the transaction reverted, halted or is invalid at its position in the bundle,
+    /// e.g. because of its nonce or the balance of the sender.
+    ///
+    /// Note: an invalid transaction doesn't abort the simulation of the bundle, it's skipped and
+    /// doesn't use any gas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 mod u256_numeric_string {
@@ -972,6 +979,25 @@ mod tests {
         let _call = serde_json::from_str::<EthCallBundleResponse>(s).unwrap();
     }
 
+    #[test]
+    fn can_serialize_invalid_bundle_transaction_result() {
+        let result = EthCallBundleTransactionResult {
+            from_address: Address::with_last_byte(1),
+            tx_hash: B256::with_last_byte(2),
+            error: Some("nonce too low".to_string()),
+            ..Default::default()
+        };
+
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(value["error"], "nonce too low");
+        assert_eq!(value["gasUsed"], "0x0");
+        assert!(value.get("value").is_none());
+        assert!(value.get("revert").is_none());
+
+        let decoded: EthCallBundleTransactionResult = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, result);
+    }
+
     #[test]
     fn can_serialize_deserialize_bundle_stats() {
         let fixtures = [
@@ -0,0 +1,39 @@
+use alloy_rpc_types::admin::NodeInfo;
+use serde::{Deserialize, Serialize};
+
+/// The info `admin_nodeInfo` returns about the node.
+///
+/// This extends the [`NodeInfo`] geth returns with the configuration of the node.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminNodeInfo {
+    /// The info geth returns about the node.
+    #[serde(flatten)]
+    pub info: NodeInfo,
+    /// The configuration of the engine API.
+    pub engine: NodeEngineInfo,
+}
+
+/// The configuration of the engine API of a node.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeEngineInfo {
+    /// Whether the node only validates payloads and never builds them.
+    pub validation_only: bool,
+    /// Whether the payload attributes of `engine_forkchoiceUpdated` are ignored instead of
+    /// rejected in validation-only mode.
+    pub ignore_payload_attributes: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_node_engine_info() {
+        let info = NodeEngineInfo { validation_only: true, ignore_payload_attributes: false };
+        let s = serde_json::to_string(&info).unwrap();
+        assert_eq!(s, r#"{"validationOnly":true,"ignorePayloadAttributes":false}"#);
+        assert_eq!(serde_json::from_str::<NodeEngineInfo>(&s).unwrap(), info);
+    }
+}
@@ -0,0 +1,80 @@
+//! Types for the `reth_subscribePayloadEvents` subscription.
+
+use alloy_primitives::{Address, B256, U256, U64};
+use alloy_rpc_types_engine::PayloadId;
+use serde::{Deserialize, Serialize};
+
+/// An event of the payload builder.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PayloadEvent {
+    /// The CL requested a payload with these attributes.
+    Attributes(PayloadEventAttributes),
+    /// The best payload of a job, once the CL requested it or the job ended.
+    BuiltPayload(BuiltPayloadEvent),
+}
+
+/// The attributes of a requested payload.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadEventAttributes {
+    /// The identifier of the payload job.
+    pub id: PayloadId,
+    /// The hash of the parent block.
+    pub parent_hash: B256,
+    /// The timestamp of the payload.
+    pub timestamp: U64,
+    /// The recipient of the fees of the payload.
+    pub suggested_fee_recipient: Address,
+    /// The randomness of the payload.
+    pub prev_randao: B256,
+    /// The parent beacon block root, if Cancun is active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_beacon_block_root: Option<B256>,
+}
+
+/// A payload that was built.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BuiltPayloadEvent {
+    /// The identifier of the payload job.
+    pub id: PayloadId,
+    /// The hash of the block.
+    pub block_hash: B256,
+    /// The number of the block.
+    pub block_number: U64,
+    /// The fees the block pays to the fee recipient.
+    pub fees: U256,
+    /// The hashes of the transactions of the block.
+    pub txs: Vec<B256>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_built_payload_event() {
+        let event = PayloadEvent::BuiltPayload(BuiltPayloadEvent {
+            id: PayloadId::new([0, 0, 0, 0, 0, 0, 0, 1]),
+            block_hash: B256::with_last_byte(2),
+            block_number: U64::from(3),
+            fees: U256::from(4),
+            txs: vec![B256::with_last_byte(5)],
+        });
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "builtPayload": {
+                    "id": "0x0000000000000001",
+                    "blockHash": B256::with_last_byte(2),
+                    "blockNumber": "0x3",
+                    "fees": "0x4",
+                    "txs": [B256::with_last_byte(5)]
+                }
+            })
+        );
+        assert_eq!(serde_json::from_value::<PayloadEvent>(json).unwrap(), event);
+    }
+}
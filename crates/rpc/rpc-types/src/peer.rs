@@ -1,4 +1,112 @@
-use alloy_primitives::B512;
+use alloy_primitives::{FixedBytes, B256, B512, U256};
+use alloy_rpc_types::PeerInfo;
+use serde::{Deserialize, Serialize};
 
 /// Alias for a peer identifier
 pub type PeerId = B512;
+
+/// The info `admin_peers` returns about a connected peer.
+///
+/// This extends the [`PeerInfo`] geth returns with the state the node tracks about the peer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminPeerInfo {
+    /// The info geth returns about the peer.
+    #[serde(flatten)]
+    pub info: PeerInfo,
+    /// The capabilities shared with the peer, with their negotiated versions.
+    pub shared_caps: Vec<String>,
+    /// Whether the peer connected to the node or the node dialed the peer.
+    pub direction: PeerDirection,
+    /// Whether the peer is in the trusted peer set.
+    pub trusted: bool,
+    /// The number of seconds since the session with the peer was established.
+    pub connection_duration: u64,
+    /// The reputation score of the peer, `None` if it's not tracked.
+    pub reputation: Option<i32>,
+    /// The status the peer sent in the `eth` handshake.
+    pub eth_status: PeerEthStatus,
+}
+
+/// The direction of a peer connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeerDirection {
+    /// The peer connected to the node.
+    Inbound,
+    /// The node dialed the peer.
+    Outbound,
+}
+
+/// The status a peer sent in the `eth` handshake.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerEthStatus {
+    /// The negotiated version of the `eth` protocol.
+    pub version: u8,
+    /// The chain id of the peer.
+    pub network_id: u64,
+    /// The genesis hash of the peer.
+    pub genesis: B256,
+    /// The hash of the head block of the peer.
+    pub head: B256,
+    /// The total difficulty of the head block of the peer.
+    pub total_difficulty: U256,
+    /// The fork id of the peer, see [EIP-2124](https://eips.ethereum.org/EIPS/eip-2124).
+    pub fork_id: PeerForkId,
+}
+
+/// The fork id of a peer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerForkId {
+    /// The checksum of the forks the peer has passed.
+    pub hash: FixedBytes<4>,
+    /// The block number or timestamp of the next fork of the peer, 0 if there's none.
+    pub next: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rpc_types::{PeerNetworkInfo, PeerProtocolsInfo};
+
+    #[test]
+    fn serde_admin_peer_info() {
+        let peer = AdminPeerInfo {
+            info: PeerInfo {
+                id: Some("0x01".to_string()),
+                name: "reth/v1.0.0".to_string(),
+                caps: vec!["eth/68".to_string()],
+                network: PeerNetworkInfo {
+                    remote_address: "127.0.0.1:30303".to_string(),
+                    local_address: "127.0.0.1:30304".to_string(),
+                },
+                protocols: PeerProtocolsInfo { eth: None, pip: None },
+            },
+            shared_caps: vec!["eth/68".to_string()],
+            direction: PeerDirection::Inbound,
+            trusted: true,
+            connection_duration: 12,
+            reputation: Some(-1024),
+            eth_status: PeerEthStatus {
+                version: 68,
+                network_id: 1,
+                genesis: B256::with_last_byte(1),
+                head: B256::with_last_byte(2),
+                total_difficulty: U256::from(3),
+                fork_id: PeerForkId { hash: FixedBytes([0xdc, 0xe9, 0x6c, 0x2d]), next: 0 },
+            },
+        };
+
+        let value = serde_json::to_value(&peer).unwrap();
+        assert_eq!(value["name"], "reth/v1.0.0");
+        assert_eq!(value["sharedCaps"][0], "eth/68");
+        assert_eq!(value["direction"], "inbound");
+        assert_eq!(value["connectionDuration"], 12);
+        assert_eq!(value["ethStatus"]["forkId"]["hash"], "0xdce96c2d");
+
+        let decoded: AdminPeerInfo = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.eth_status, peer.eth_status);
+        assert_eq!(decoded.info.name, peer.info.name);
+    }
+}
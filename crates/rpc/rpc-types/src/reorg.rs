@@ -0,0 +1,71 @@
+//! Types for the `reth_subscribeChainReorgs` subscription.
+
+use alloy_primitives::{B256, U64};
+use serde::{Deserialize, Serialize};
+
+/// A block that was removed or added by a chain reorg.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReorgBlock {
+    /// The number of the block.
+    pub number: U64,
+    /// The hash of the block.
+    pub hash: B256,
+}
+
+/// A chain reorg, the blocks of the old chain that were removed from the canonical chain and the
+/// blocks of the new chain that replaced them.
+///
+/// Deep reorgs are split into multiple notifications, each with at most a fixed number of removed
+/// and added blocks. The notifications of a reorg are sent in order and share the common
+/// ancestor, the reorg is complete once the notification with `part == parts - 1` was received.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainReorg {
+    /// The last block the old and the new chain have in common.
+    pub common_ancestor: ReorgBlock,
+    /// The removed blocks of the old chain, in ascending order.
+    pub removed: Vec<ReorgBlock>,
+    /// The added blocks of the new chain, in ascending order.
+    pub added: Vec<ReorgBlock>,
+    /// The index of this notification of the reorg.
+    pub part: U64,
+    /// The number of notifications of the reorg.
+    pub parts: U64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_chain_reorg() {
+        let reorg = ChainReorg {
+            common_ancestor: ReorgBlock { number: U64::from(1), hash: B256::with_last_byte(1) },
+            removed: vec![ReorgBlock { number: U64::from(2), hash: B256::with_last_byte(2) }],
+            added: vec![ReorgBlock { number: U64::from(2), hash: B256::with_last_byte(3) }],
+            part: U64::ZERO,
+            parts: U64::from(1),
+        };
+        let json = serde_json::to_value(&reorg).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "commonAncestor": {
+                    "number": "0x1",
+                    "hash": "0x0000000000000000000000000000000000000000000000000000000000000001"
+                },
+                "removed": [{
+                    "number": "0x2",
+                    "hash": "0x0000000000000000000000000000000000000000000000000000000000000002"
+                }],
+                "added": [{
+                    "number": "0x2",
+                    "hash": "0x0000000000000000000000000000000000000000000000000000000000000003"
+                }],
+                "part": "0x0",
+                "parts": "0x1"
+            })
+        );
+        assert_eq!(serde_json::from_value::<ChainReorg>(json).unwrap(), reorg);
+    }
+}
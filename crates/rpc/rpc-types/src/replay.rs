@@ -0,0 +1,58 @@
+//! Types for `debug_replayTransaction`.
+
+use alloy_primitives::{Address, Bytes, Log, B256, U256, U64};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A historical transaction replayed on top of the exact state it was executed on.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayedTransaction {
+    /// Whether the transaction succeeded.
+    pub success: bool,
+    /// The gas used by the transaction.
+    pub gas_used: U64,
+    /// The output of the transaction, or the revert data.
+    pub output: Bytes,
+    /// The reason the transaction reverted or halted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The logs emitted by the transaction.
+    pub logs: Vec<Log>,
+    /// The accounts changed by the transaction.
+    pub state_diff: BTreeMap<Address, ReplayedAccountDiff>,
+}
+
+/// The changes of an account made by a replayed transaction.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayedAccountDiff {
+    /// The account before the transaction, `None` if it didn't exist.
+    pub pre: Option<ReplayedAccount>,
+    /// The account after the transaction, `None` if it was destroyed.
+    pub post: Option<ReplayedAccount>,
+    /// The changed storage slots of the account.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<B256, ReplayedStorageDiff>,
+}
+
+/// The state of an account before or after a replayed transaction.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayedAccount {
+    /// The balance of the account.
+    pub balance: U256,
+    /// The nonce of the account.
+    pub nonce: U64,
+    /// The hash of the code of the account.
+    pub code_hash: B256,
+}
+
+/// The value of a storage slot before and after a replayed transaction.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReplayedStorageDiff {
+    /// The value before the transaction.
+    pub pre: U256,
+    /// The value after the transaction.
+    pub post: U256,
+}
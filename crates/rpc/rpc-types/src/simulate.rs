@@ -0,0 +1,139 @@
+//! Types for `eth_simulateV1`.
+
+use crate::{state::AccountOverride, Block, BlockOverrides, Log, TransactionRequest};
+use alloy_primitives::{Address, Bytes, U64};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The input of `eth_simulateV1`: a sequence of blocks to simulate on top of a base block.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// The blocks to simulate, in order.
+    pub block_state_calls: Vec<SimBlock>,
+    /// Whether to add a synthetic log for every transfer of ether.
+    #[serde(default)]
+    pub trace_transfers: bool,
+    /// Whether the calls are validated like transactions, e.g. their nonce, balance and fees.
+    #[serde(default)]
+    pub validation: bool,
+    /// Whether the simulated blocks contain full transactions instead of their hashes.
+    #[serde(default)]
+    pub return_full_transactions: bool,
+}
+
+/// A block to simulate.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBlock {
+    /// Overrides of the block environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
+    /// Overrides of the state, applied before the calls of the block. The overrides remain in
+    /// effect for the following blocks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<HashMap<Address, SimAccountOverride>>,
+    /// The calls of the block, executed in order.
+    #[serde(default)]
+    pub calls: Vec<TransactionRequest>,
+}
+
+/// The state override of an account in a simulated block.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimAccountOverride {
+    /// The overrides of the code, balance, nonce and storage of the account.
+    #[serde(flatten)]
+    pub account: AccountOverride,
+    /// Moves the precompile at the address of the account to the given address, so the code of
+    /// the account can be overridden.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub move_precompile_to_address: Option<Address>,
+}
+
+/// A simulated block with the results of its calls.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+    /// The simulated block.
+    #[serde(flatten)]
+    pub inner: Block,
+    /// The results of the calls of the block.
+    pub calls: Vec<SimCallResult>,
+}
+
+/// The result of a simulated call.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimCallResult {
+    /// The output of the call, or the revert data.
+    pub return_data: Bytes,
+    /// The logs emitted by the call, including the synthetic transfer logs if requested.
+    pub logs: Vec<Log>,
+    /// The gas used by the call.
+    pub gas_used: U64,
+    /// `1` if the call succeeded, `0` otherwise.
+    pub status: U64,
+    /// The reason the call failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulateError>,
+}
+
+/// The error of a failed simulated call.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SimulateError {
+    /// The error code, `-32000` if the call reverted and `-32015` if it halted.
+    pub code: i32,
+    /// The error message.
+    pub message: String,
+    /// The revert data, if the call reverted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Bytes>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, U256};
+
+    #[test]
+    fn deserialize_simulate_payload() {
+        let s = r#"{
+            "blockStateCalls": [{
+                "blockOverrides": { "number": "0x10", "time": "0x64" },
+                "stateOverrides": {
+                    "0x0000000000000000000000000000000000000001": {
+                        "code": "0x00",
+                        "movePrecompileToAddress": "0x0000000000000000000000000000000000000123"
+                    },
+                    "0xc000000000000000000000000000000000000000": { "balance": "0x3e8" }
+                },
+                "calls": [{
+                    "from": "0xc000000000000000000000000000000000000000",
+                    "to": "0xc100000000000000000000000000000000000000",
+                    "value": "0x1"
+                }]
+            }],
+            "traceTransfers": true
+        }"#;
+        let payload: SimulatePayload = serde_json::from_str(s).unwrap();
+        assert!(payload.trace_transfers);
+        assert!(!payload.validation);
+        assert!(!payload.return_full_transactions);
+
+        let block = &payload.block_state_calls[0];
+        assert_eq!(block.block_overrides.as_ref().unwrap().time, Some(100));
+        assert_eq!(block.calls.len(), 1);
+
+        let overrides = block.state_overrides.as_ref().unwrap();
+        let ecrecover = &overrides[&address!("0000000000000000000000000000000000000001")];
+        assert_eq!(
+            ecrecover.move_precompile_to_address,
+            Some(address!("0000000000000000000000000000000000000123"))
+        );
+        assert_eq!(ecrecover.account.code, Some(Bytes::from_static(&[0])));
+        let sender = &overrides[&address!("c000000000000000000000000000000000000000")];
+        assert_eq!(sender.account.balance, Some(U256::from(1000)));
+        assert_eq!(sender.move_precompile_to_address, None);
+    }
+}
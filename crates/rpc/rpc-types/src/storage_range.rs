@@ -0,0 +1,38 @@
+//! Types for `debug_storageRangeAt`.
+
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A page of the storage of a contract returned by `debug_storageRangeAt`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRangeResult {
+    /// The storage slots of the page, keyed by their hashed slot.
+    pub storage: BTreeMap<B256, StorageRangeSlot>,
+    /// The hashed slot to start the next page at, `None` if this is the last page.
+    pub next_key: Option<B256>,
+}
+
+/// A storage slot of a [`StorageRangeResult`].
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StorageRangeSlot {
+    /// The preimage of the hashed slot, `None` if it's unknown.
+    pub key: Option<B256>,
+    /// The value of the slot.
+    pub value: B256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_storage_range_result() {
+        let s = r#"{"storage":{"0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563":{"key":"0x0000000000000000000000000000000000000000000000000000000000000000","value":"0x0000000000000000000000000000000000000000000000000000000000000001"},"0xb10e2d527612073b26eecdfd717e6a320cf44b4afac2b0732d9fcbe2b7fa0cf6":{"key":null,"value":"0x0000000000000000000000000000000000000000000000000000000000000002"}},"nextKey":null}"#;
+        let result: StorageRangeResult = serde_json::from_str(s).unwrap();
+        assert_eq!(result.storage.len(), 2);
+        assert_eq!(result.next_key, None);
+        assert_eq!(serde_json::to_string(&result).unwrap(), s);
+    }
+}
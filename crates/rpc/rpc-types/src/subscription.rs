@@ -0,0 +1,340 @@
+//! Types for the params of `eth_subscribe` subscriptions.
+
+use alloy_primitives::{Address, U256};
+use alloy_rpc_types::pubsub::Params;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::HashSet, fmt, str::FromStr};
+
+/// What happens to the notifications of a subscription once its buffer is full, because the
+/// subscriber doesn't keep up.
+///
+/// By default, the subscription waits for the subscriber and notifications are never dropped,
+/// dropping them is opt-in. Subscribers that need every notification, e.g. `logs` subscribers that
+/// prefer completeness, should use [`Disconnect`](Self::Disconnect) and re-sync the missed range
+/// with `eth_getLogs` after subscribing again.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SubscriptionBackpressurePolicy {
+    /// Stops receiving new notifications until the subscriber caught up.
+    #[default]
+    Wait,
+    /// Drops the oldest buffered notification to make room for the new one.
+    DropOldest,
+    /// Drops the new notification.
+    DropNewest,
+    /// Closes the subscription.
+    Disconnect,
+}
+
+impl SubscriptionBackpressurePolicy {
+    /// Returns the name of the policy.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Wait => "wait",
+            Self::DropOldest => "drop-oldest",
+            Self::DropNewest => "drop-newest",
+            Self::Disconnect => "disconnect",
+        }
+    }
+}
+
+impl fmt::Display for SubscriptionBackpressurePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SubscriptionBackpressurePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wait" => Ok(Self::Wait),
+            "drop-oldest" => Ok(Self::DropOldest),
+            "drop-newest" => Ok(Self::DropNewest),
+            "disconnect" => Ok(Self::Disconnect),
+            _ => Err(format!(
+                "invalid subscription backpressure policy `{s}`, expected one of `wait`, \
+                 `drop-oldest`, `drop-newest` or `disconnect`"
+            )),
+        }
+    }
+}
+
+/// Overrides the buffer settings of the server for a single subscription.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionBufferOverride {
+    /// The maximum number of notifications buffered for the subscription, capped at the buffer
+    /// limit of the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_buffer: Option<usize>,
+    /// What happens to the notifications once the buffer is full.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<SubscriptionBackpressurePolicy>,
+}
+
+/// Filters the transactions of a `newPendingTransactions` subscription.
+///
+/// A transaction matches if it matches every set criterion.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PendingTransactionFilter {
+    /// The recipients of the transactions, contract creations don't match.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub to: HashSet<Address>,
+    /// The senders of the transactions.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub from: HashSet<Address>,
+    /// The minimum effective tip per gas of the transactions, given the base fee of the pending
+    /// block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_tip: Option<U256>,
+}
+
+impl PendingTransactionFilter {
+    /// Returns the number of addresses of the filter.
+    pub fn address_count(&self) -> usize {
+        self.to.len() + self.from.len()
+    }
+
+    /// Returns true if the transaction with the given sender, recipient and effective tip per gas
+    /// matches the filter.
+    ///
+    /// The effective tip is `None` if the transaction doesn't pay the base fee.
+    pub fn matches(
+        &self,
+        from: &Address,
+        to: Option<&Address>,
+        effective_tip: Option<u128>,
+    ) -> bool {
+        if !self.from.is_empty() && !self.from.contains(from) {
+            return false
+        }
+        if !self.to.is_empty() && !to.is_some_and(|to| self.to.contains(to)) {
+            return false
+        }
+        match self.min_tip {
+            Some(min_tip) => effective_tip.is_some_and(|tip| U256::from(tip) >= min_tip),
+            None => true,
+        }
+    }
+}
+
+/// The params of `eth_subscribe`.
+///
+/// These are the [`Params`] of the subscription kind. If the params are an object, it can
+/// override the buffer settings of the subscription with a `backpressure` field, e.g.
+/// `{"address": "0x..", "backpressure": {"maxBuffer": 100, "policy": "disconnect"}}`.
+///
+/// The params of `newPendingTransactions` can also be an object that requests full transactions
+/// and filters them, e.g. `{"fullTransactions": true, "filter": {"to": ["0x.."]}}`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubscriptionParams {
+    /// The params of the subscription kind.
+    pub params: Params,
+    /// The override of the buffer settings.
+    pub backpressure: Option<SubscriptionBufferOverride>,
+    /// The filter of a `newPendingTransactions` subscription.
+    pub transaction_filter: Option<PendingTransactionFilter>,
+}
+
+impl From<Params> for SubscriptionParams {
+    fn from(params: Params) -> Self {
+        Self { params, backpressure: None, transaction_filter: None }
+    }
+}
+
+impl Serialize for SubscriptionParams {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.backpressure.is_none() && self.transaction_filter.is_none() {
+            return self.params.serialize(serializer)
+        }
+
+        let mut value = match self.params {
+            Params::Bool(full_transactions) => {
+                serde_json::json!({ "fullTransactions": full_transactions })
+            }
+            Params::None => serde_json::Value::Object(Default::default()),
+            ref params => serde_json::to_value(params).map_err(serde::ser::Error::custom)?,
+        };
+        let serde_json::Value::Object(ref mut object) = value else {
+            return Err(serde::ser::Error::custom("params must be an object"))
+        };
+        if let Some(backpressure) = self.backpressure {
+            object.insert(
+                "backpressure".to_string(),
+                serde_json::to_value(backpressure).map_err(serde::ser::Error::custom)?,
+            );
+        }
+        if let Some(filter) = &self.transaction_filter {
+            object.insert(
+                "filter".to_string(),
+                serde_json::to_value(filter).map_err(serde::ser::Error::custom)?,
+            );
+        }
+        value.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SubscriptionParams {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Removes the field with the given key from the object and deserializes it.
+        fn take<T: serde::de::DeserializeOwned, E: serde::de::Error>(
+            object: &mut serde_json::Map<String, serde_json::Value>,
+            key: &str,
+        ) -> Result<Option<T>, E> {
+            object
+                .remove(key)
+                .map(|value| serde_json::from_value(value).map_err(E::custom))
+                .transpose()
+        }
+
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+
+        let mut backpressure = None;
+        let mut transaction_filter = None;
+        if let serde_json::Value::Object(ref mut object) = value {
+            backpressure = take(object, "backpressure")?;
+            let full_transactions = take::<bool, _>(object, "fullTransactions")?;
+            transaction_filter = take(object, "filter")?;
+
+            if full_transactions.is_some() || transaction_filter.is_some() {
+                if !object.is_empty() {
+                    return Err(serde::de::Error::custom(
+                        "unexpected fields in newPendingTransactions params",
+                    ))
+                }
+                let params = full_transactions.map_or(Params::None, Params::Bool);
+                return Ok(Self { params, backpressure, transaction_filter })
+            }
+            if backpressure.is_some() && object.is_empty() {
+                return Ok(Self { params: Params::None, backpressure, transaction_filter })
+            }
+        }
+
+        let params = serde_json::from_value(value).map_err(serde::de::Error::custom)?;
+        Ok(Self { params, backpressure, transaction_filter })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_policy() {
+        for policy in [
+            SubscriptionBackpressurePolicy::Wait,
+            SubscriptionBackpressurePolicy::DropOldest,
+            SubscriptionBackpressurePolicy::DropNewest,
+            SubscriptionBackpressurePolicy::Disconnect,
+        ] {
+            assert_eq!(policy.to_string().parse::<SubscriptionBackpressurePolicy>(), Ok(policy));
+            let json = serde_json::to_string(&policy).unwrap();
+            assert_eq!(json, format!("\"{policy}\""));
+        }
+        assert!("drop".parse::<SubscriptionBackpressurePolicy>().is_err());
+    }
+
+    #[test]
+    fn deserialize_params_without_override() {
+        let params: SubscriptionParams = serde_json::from_str("true").unwrap();
+        assert_eq!(params, Params::Bool(true).into());
+
+        let params: SubscriptionParams = serde_json::from_str("null").unwrap();
+        assert_eq!(params, Params::None.into());
+
+        let params: SubscriptionParams =
+            serde_json::from_str(r#"{"address":"0x0000000000000000000000000000000000000001"}"#)
+                .unwrap();
+        assert!(matches!(params.params, Params::Logs(_)));
+        assert!(params.backpressure.is_none());
+    }
+
+    #[test]
+    fn deserialize_params_with_override() {
+        let s = r#"{
+            "address": "0x0000000000000000000000000000000000000001",
+            "backpressure": { "maxBuffer": 100, "policy": "disconnect" }
+        }"#;
+        let params: SubscriptionParams = serde_json::from_str(s).unwrap();
+        assert!(matches!(params.params, Params::Logs(_)));
+        assert_eq!(
+            params.backpressure,
+            Some(SubscriptionBufferOverride {
+                max_buffer: Some(100),
+                policy: Some(SubscriptionBackpressurePolicy::Disconnect),
+            })
+        );
+
+        // the override alone, e.g. for `newHeads`
+        let s = r#"{ "backpressure": { "policy": "drop-newest" } }"#;
+        let params: SubscriptionParams = serde_json::from_str(s).unwrap();
+        assert_eq!(params.params, Params::None);
+        assert_eq!(
+            params.backpressure,
+            Some(SubscriptionBufferOverride {
+                max_buffer: None,
+                policy: Some(SubscriptionBackpressurePolicy::DropNewest),
+            })
+        );
+
+        let roundtrip: SubscriptionParams =
+            serde_json::from_str(&serde_json::to_string(&params).unwrap()).unwrap();
+        assert_eq!(roundtrip, params);
+    }
+
+    #[test]
+    fn deserialize_pending_transaction_filter() {
+        let s = r#"{
+            "fullTransactions": true,
+            "filter": {
+                "to": ["0x0000000000000000000000000000000000000001"],
+                "minTip": "0x3b9aca00"
+            },
+            "backpressure": { "maxBuffer": 100 }
+        }"#;
+        let params: SubscriptionParams = serde_json::from_str(s).unwrap();
+        assert_eq!(params.params, Params::Bool(true));
+        assert_eq!(
+            params.backpressure,
+            Some(SubscriptionBufferOverride { max_buffer: Some(100), policy: None })
+        );
+        let filter = params.transaction_filter.clone().unwrap();
+        assert_eq!(filter.to, HashSet::from([Address::with_last_byte(1)]));
+        assert!(filter.from.is_empty());
+        assert_eq!(filter.min_tip, Some(U256::from(1_000_000_000)));
+
+        let roundtrip: SubscriptionParams =
+            serde_json::from_str(&serde_json::to_string(&params).unwrap()).unwrap();
+        assert_eq!(roundtrip, params);
+
+        // unknown fields of the filter or next to it are rejected
+        let s = r#"{ "filter": { "value": "0x1" } }"#;
+        assert!(serde_json::from_str::<SubscriptionParams>(s).is_err());
+        let s = r#"{ "fullTransactions": true, "address": "0x0000000000000000000000000000000000000001" }"#;
+        assert!(serde_json::from_str::<SubscriptionParams>(s).is_err());
+    }
+
+    #[test]
+    fn pending_transaction_filter_matches() {
+        let (alice, bob, carol) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+
+        let filter = PendingTransactionFilter::default();
+        assert!(filter.matches(&alice, None, None));
+
+        let filter = PendingTransactionFilter {
+            to: HashSet::from([bob]),
+            from: HashSet::from([alice]),
+            min_tip: Some(U256::from(10)),
+        };
+        assert!(filter.matches(&alice, Some(&bob), Some(10)));
+        assert!(!filter.matches(&carol, Some(&bob), Some(10)));
+        assert!(!filter.matches(&alice, Some(&carol), Some(10)));
+        assert!(!filter.matches(&alice, None, Some(10)));
+        assert!(!filter.matches(&alice, Some(&bob), Some(9)));
+        assert!(!filter.matches(&alice, Some(&bob), None));
+    }
+}
@@ -0,0 +1,62 @@
+//! Types for `debug_traceCall`.
+
+use alloy_rpc_types_trace::geth::GethDebugTracingCallOptions;
+use serde::{Deserialize, Serialize};
+
+/// The [`GethDebugTracingCallOptions`] of `debug_traceCall`, with the position in the block the
+/// call is traced at.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceCallOptions {
+    /// The tracer and the state and block overrides.
+    #[serde(flatten)]
+    pub call_options: GethDebugTracingCallOptions,
+    /// Trace the call on top of the first `tx_index` transactions of the block, instead of the
+    /// state after the block.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "alloy_rpc_types::serde_helpers::num::u64_opt_via_ruint"
+    )]
+    pub tx_index: Option<u64>,
+}
+
+impl From<GethDebugTracingCallOptions> for TraceCallOptions {
+    fn from(call_options: GethDebugTracingCallOptions) -> Self {
+        Self { call_options, tx_index: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rpc_types_trace::geth::{GethDebugBuiltInTracerType, GethDebugTracerType};
+
+    #[test]
+    fn deserialize_trace_call_options() {
+        let s = r#"{
+            "tracer": "prestateTracer",
+            "tracerConfig": { "diffMode": true },
+            "stateOverrides": {},
+            "blockOverrides": { "number": "0x2" },
+            "txIndex": "0x1"
+        }"#;
+        let opts: TraceCallOptions = serde_json::from_str(s).unwrap();
+        assert_eq!(opts.tx_index, Some(1));
+        assert_eq!(
+            opts.call_options.tracing_options.tracer,
+            Some(GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::PreStateTracer))
+        );
+        let prestate_config =
+            opts.call_options.tracing_options.tracer_config.into_pre_state_config().unwrap();
+        assert!(prestate_config.is_diff_mode());
+        assert!(opts.call_options.state_overrides.is_some());
+        assert!(opts.call_options.block_overrides.is_some());
+
+        let opts: TraceCallOptions = serde_json::from_str(r#"{ "txIndex": 3 }"#).unwrap();
+        assert_eq!(opts.tx_index, Some(3));
+
+        let opts: TraceCallOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(opts, TraceCallOptions::default());
+    }
+}
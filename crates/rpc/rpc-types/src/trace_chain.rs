@@ -0,0 +1,32 @@
+//! Types for the `debug_traceChain` subscription.
+
+use crate::DebugBlockTraceResult;
+use alloy_primitives::U64;
+use serde::{Deserialize, Serialize};
+
+/// A notification of a `debug_traceChain` subscription.
+///
+/// Every block of the range is sent in its own notification, in ascending order. The last
+/// notification of the subscription is [`TraceChainNotification::Completed`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TraceChainNotification {
+    /// The traces of the transactions of the next block.
+    Block(DebugBlockTraceResult),
+    /// The end of the subscription.
+    Completed {
+        /// Why the subscription ended.
+        completed: TraceChainCompleted,
+    },
+}
+
+/// The end of a `debug_traceChain` subscription.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceChainCompleted {
+    /// The number of blocks that were traced.
+    pub blocks: U64,
+    /// The error that stopped the tracing, `None` if every block of the range was traced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -0,0 +1,35 @@
+//! Types for the replay methods of the `trace` namespace.
+
+use alloy_primitives::B256;
+use alloy_rpc_types_trace::parity::TraceResults;
+use serde::{Deserialize, Serialize};
+
+/// The [`TraceResults`] of a call or transaction with the `vmTrace` cut off at the limits of the
+/// node.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitedTraceResults {
+    /// The requested traces.
+    #[serde(flatten)]
+    pub full_trace: TraceResults,
+    /// Whether the `vmTrace` is incomplete because the execution exceeded the limits of the node.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub vm_trace_truncated: bool,
+}
+
+impl From<TraceResults> for LimitedTraceResults {
+    fn from(full_trace: TraceResults) -> Self {
+        Self { full_trace, vm_trace_truncated: false }
+    }
+}
+
+/// The [`LimitedTraceResults`] of a transaction of a replayed block.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitedTraceResultsWithTransactionHash {
+    /// The requested traces.
+    #[serde(flatten)]
+    pub full_trace: LimitedTraceResults,
+    /// The hash of the traced transaction.
+    pub transaction_hash: B256,
+}
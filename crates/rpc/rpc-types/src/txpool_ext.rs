@@ -0,0 +1,233 @@
+//! Types of the `txpool` namespace that extend or replace the geth types of alloy.
+
+use alloy_primitives::{Address, U128};
+use alloy_rpc_types::txpool::{TxpoolContent, TxpoolInspectSummary, TxpoolStatus};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::BTreeMap, fmt};
+
+/// The response of `txpool_status`.
+///
+/// This extends the [`TxpoolStatus`] geth returns with the number of blob transactions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolStatusExt {
+    /// The number of pending and queued transactions.
+    #[serde(flatten)]
+    pub status: TxpoolStatus,
+    /// The number of transactions that are parked in the blob pool until the blob fee drops.
+    ///
+    /// These are included in the queued transactions.
+    #[serde(default, with = "alloy_rpc_types::serde_helpers::num::u64_via_ruint")]
+    pub blob: u64,
+}
+
+/// The origin of a transaction in the pool, see [`TxpoolContentFilter::origin`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TxpoolOrigin {
+    /// Transactions submitted to this node, for example via `eth_sendRawTransaction`.
+    Local,
+    /// Transactions received from the network.
+    External,
+    /// Transactions submitted to this node that are not propagated.
+    Private,
+}
+
+/// The optional server-side filter of `txpool_content`.
+///
+/// Empty sender and recipient sets match any address.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TxpoolContentFilter {
+    /// Only return transactions that were submitted with this origin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<TxpoolOrigin>,
+    /// Only return transactions with at least this effective tip per gas at the pending base fee.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_tip: Option<U128>,
+    /// Only return transactions sent by one of these addresses.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub senders: Vec<Address>,
+    /// Only return transactions sent to one of these addresses.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recipients: Vec<Address>,
+    /// Maximum number of transactions in the response.
+    ///
+    /// If more transactions match, the response includes a continuation token.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "alloy_rpc_types::serde_helpers::num::u64_opt_via_ruint"
+    )]
+    pub limit: Option<u64>,
+    /// The continuation token of the previous response, to return the next page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+}
+
+/// The response of `txpool_content`.
+///
+/// Without a `limit`, this is serialized exactly like the [`TxpoolContent`] geth returns.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TxpoolContentExt {
+    /// The pending and queued transactions, grouped by sender and nonce.
+    #[serde(flatten)]
+    pub content: TxpoolContent,
+    /// The token to pass as [`TxpoolContentFilter::continuation`] to fetch the next page, if there
+    /// are more matching transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+}
+
+/// The response of `txpool_inspect`, in the exact format of geth.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GethTxpoolInspect {
+    /// The summaries of the pending transactions, grouped by sender and nonce.
+    #[serde(serialize_with = "serialize_checksummed_senders")]
+    pub pending: BTreeMap<Address, BTreeMap<String, GethInspectSummary>>,
+    /// The summaries of the queued transactions, grouped by sender and nonce.
+    #[serde(serialize_with = "serialize_checksummed_senders")]
+    pub queued: BTreeMap<Address, BTreeMap<String, GethInspectSummary>>,
+}
+
+/// The summary of a transaction in the `txpool_inspect` response.
+///
+/// This is serialized as `<to>: <value> wei + <gas> gas × <gasPrice> wei`, with the checksummed
+/// recipient or `contract creation`, like geth does.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GethInspectSummary(pub TxpoolInspectSummary);
+
+impl GethInspectSummary {
+    /// Parses a summary in the format of geth.
+    fn parse(s: &str) -> Option<Self> {
+        let (to, rest) = s.split_once(": ")?;
+        let (value, rest) = rest.split_once(" wei + ")?;
+        let (gas, rest) = rest.split_once(" gas × ")?;
+        let gas_price = rest.strip_suffix(" wei")?;
+
+        let to = if to == "contract creation" { None } else { Some(to.parse().ok()?) };
+        Some(Self(TxpoolInspectSummary {
+            to,
+            value: value.parse().ok()?,
+            gas: gas.parse().ok()?,
+            gas_price: gas_price.parse().ok()?,
+        }))
+    }
+}
+
+impl From<TxpoolInspectSummary> for GethInspectSummary {
+    fn from(summary: TxpoolInspectSummary) -> Self {
+        Self(summary)
+    }
+}
+
+impl fmt::Display for GethInspectSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let TxpoolInspectSummary { to, value, gas, gas_price } = &self.0;
+        match to {
+            Some(to) => f.write_str(&to.to_checksum(None))?,
+            None => f.write_str("contract creation")?,
+        }
+        write!(f, ": {value} wei + {gas} gas × {gas_price} wei")
+    }
+}
+
+impl Serialize for GethInspectSummary {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for GethInspectSummary {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| D::Error::custom(format!("invalid inspect summary: {s}")))
+    }
+}
+
+/// Serializes the senders of the transactions checksummed, like geth does.
+fn serialize_checksummed_senders<S: Serializer>(
+    senders: &BTreeMap<Address, BTreeMap<String, GethInspectSummary>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(senders.iter().map(|(sender, txs)| (sender.to_checksum(None), txs)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `txpool_inspect` response recorded from geth.
+    const GETH_INSPECT: &str = r#"{
+        "pending": {
+            "0x26588a9301b0428d95e6Fc3A5024fcE8BEc12D51": {
+                "31813": "0x3375Ee30428b2A71c428afa5E89e427905F95F7e: 0 wei + 500000 gas × 20000000000 wei"
+            },
+            "0x2a65Aca4D5fC5B5C859090a6c34d164135398226": {
+                "563662": "0x768fb3C8e76B5B1e4AF6ff6cdB2F15883efcd1C0: 1051546810000000000 wei + 90000 gas × 20000000000 wei",
+                "563663": "contract creation: 0 wei + 1000000 gas × 20000000000 wei"
+            }
+        },
+        "queued": {
+            "0x0216D5032f356960Cd3749C31Ab34eEFF21B3395": {
+                "806": "0x24d407e5A0B506E1Cb2fae163100B5DE01F5193C: 34950000000000000000 wei + 90000 gas × 50000000000 wei"
+            }
+        }
+    }"#;
+
+    /// A `txpool_status` response recorded from geth.
+    const GETH_STATUS: &str = r#"{"pending":"0x10","queued":"0x7"}"#;
+
+    #[test]
+    fn geth_txpool_inspect_golden() {
+        let inspect: GethTxpoolInspect = serde_json::from_str(GETH_INSPECT).unwrap();
+        let summary = &inspect.pending
+            [&"0x2a65Aca4D5fC5B5C859090a6c34d164135398226".parse::<Address>().unwrap()]["563663"];
+        assert_eq!(summary.0.to, None);
+        assert_eq!(summary.0.gas, 1_000_000);
+
+        let expected: serde_json::Value = serde_json::from_str(GETH_INSPECT).unwrap();
+        assert_eq!(serde_json::to_value(&inspect).unwrap(), expected);
+    }
+
+    #[test]
+    fn geth_txpool_status_golden() {
+        let status: TxpoolStatusExt = serde_json::from_str(GETH_STATUS).unwrap();
+        assert_eq!(status.status, TxpoolStatus { pending: 16, queued: 7 });
+        assert_eq!(status.blob, 0);
+
+        let status = TxpoolStatusExt { blob: 2, ..status };
+        assert_eq!(
+            serde_json::to_string(&status).unwrap(),
+            r#"{"pending":"0x10","queued":"0x7","blob":"0x2"}"#
+        );
+    }
+
+    #[test]
+    fn txpool_content_filter_serde() {
+        let filter: TxpoolContentFilter = serde_json::from_str(
+            r#"{"origin":"local","minTip":"0x3b9aca00","senders":["0x0216d5032f356960cd3749c31ab34eeff21b3395"],"limit":"0x10"}"#,
+        )
+        .unwrap();
+        assert_eq!(filter.origin, Some(TxpoolOrigin::Local));
+        assert_eq!(filter.min_tip, Some(U128::from(1_000_000_000u64)));
+        assert_eq!(filter.senders.len(), 1);
+        assert!(filter.recipients.is_empty());
+        assert_eq!(filter.limit, Some(16));
+        assert_eq!(filter.continuation, None);
+
+        let filter: TxpoolContentFilter = serde_json::from_str("{}").unwrap();
+        assert_eq!(filter, TxpoolContentFilter::default());
+    }
+
+    #[test]
+    fn txpool_content_without_continuation_is_geth_compatible() {
+        let content = TxpoolContentExt::default();
+        assert_eq!(serde_json::to_string(&content).unwrap(), r#"{"pending":{},"queued":{}}"#);
+
+        let content = TxpoolContentExt { continuation: Some("token".to_string()), ..content };
+        assert_eq!(
+            serde_json::to_string(&content).unwrap(),
+            r#"{"pending":{},"queued":{},"continuation":"token"}"#
+        );
+    }
+}
@@ -0,0 +1,24 @@
+//! Types for `reth_txpoolDump` and `reth_txpoolRestore`.
+
+use serde::{Deserialize, Serialize};
+
+/// The response of `reth_txpoolDump`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolDumpResult {
+    /// The path of the written snapshot on the server.
+    pub path: String,
+    /// The number of transactions in the snapshot.
+    pub transactions: u64,
+}
+
+/// The response of `reth_txpoolRestore`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolRestoreResult {
+    /// The number of transactions that were inserted into the pool.
+    pub restored: u64,
+    /// The number of transactions that were skipped, because they are no longer valid or are
+    /// already in the pool.
+    pub skipped: u64,
+}
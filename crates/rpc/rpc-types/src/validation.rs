@@ -0,0 +1,185 @@
+//! Types for the block validation endpoints of the `flashbots` namespace.
+
+use alloy_primitives::B256;
+use alloy_rpc_types_beacon::{
+    payload::beacon_payload_v3,
+    relay::{BidTrace, SignedBidSubmissionV3},
+    BlsSignature,
+};
+use alloy_rpc_types_engine::{BlobsBundleV1, ExecutionPayloadV4};
+use serde::{Deserialize, Serialize};
+
+/// A request of `flashbots_validateBuilderSubmissionV3` to validate a Deneb block submission.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BuilderBlockValidationRequestV3 {
+    /// The submission of the builder.
+    #[serde(flatten)]
+    pub request: SignedBidSubmissionV3,
+    /// The gas limit the proposer registered with the relay.
+    #[serde(with = "u64_via_string")]
+    pub registered_gas_limit: u64,
+    /// The root of the parent beacon block of the submitted block.
+    pub parent_beacon_block_root: B256,
+}
+
+/// A request of `flashbots_validateBuilderSubmissionV4` to validate an Electra block submission.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BuilderBlockValidationRequestV4 {
+    /// The submission of the builder.
+    #[serde(flatten)]
+    pub request: SignedBidSubmissionV4,
+    /// The gas limit the proposer registered with the relay.
+    #[serde(with = "u64_via_string")]
+    pub registered_gas_limit: u64,
+    /// The root of the parent beacon block of the submitted block.
+    pub parent_beacon_block_root: B256,
+}
+
+/// An Electra block submission of a builder.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SignedBidSubmissionV4 {
+    /// The bid of the builder.
+    pub message: BidTrace,
+    /// The submitted payload, in the format of the beacon API.
+    #[serde(with = "beacon_payload_v4")]
+    pub execution_payload: ExecutionPayloadV4,
+    /// The blobs of the blob transactions of the payload.
+    pub blobs_bundle: BlobsBundleV1,
+    /// The signature of the builder over the bid.
+    pub signature: BlsSignature,
+}
+
+/// (De)serializes a `u64` as a decimal string.
+mod u64_via_string {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
+
+/// (De)serializes an [`ExecutionPayloadV4`] in the format of the beacon API, which is the Deneb
+/// payload with the requests of the block.
+mod beacon_payload_v4 {
+    use super::beacon_payload_v3;
+    use alloy_rpc_types_engine::ExecutionPayloadV4;
+    use serde::{
+        de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use serde_json::Value;
+
+    const DEPOSIT_REQUESTS: &str = "deposit_requests";
+    const WITHDRAWAL_REQUESTS: &str = "withdrawal_requests";
+
+    pub(super) fn serialize<S: Serializer>(
+        payload: &ExecutionPayloadV4,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut value =
+            beacon_payload_v3::serialize(&payload.payload_inner, serde_json::value::Serializer)
+                .map_err(S::Error::custom)?;
+        let fields = value.as_object_mut().ok_or_else(|| S::Error::custom("expected an object"))?;
+        fields.insert(
+            DEPOSIT_REQUESTS.to_string(),
+            serde_json::to_value(&payload.deposit_requests).map_err(S::Error::custom)?,
+        );
+        fields.insert(
+            WITHDRAWAL_REQUESTS.to_string(),
+            serde_json::to_value(&payload.withdrawal_requests).map_err(S::Error::custom)?,
+        );
+        value.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<ExecutionPayloadV4, D::Error> {
+        let mut value = Value::deserialize(deserializer)?;
+        let fields = value.as_object_mut().ok_or_else(|| D::Error::custom("expected an object"))?;
+        let deposit_requests = fields.remove(DEPOSIT_REQUESTS).unwrap_or_default();
+        let withdrawal_requests = fields.remove(WITHDRAWAL_REQUESTS).unwrap_or_default();
+        Ok(ExecutionPayloadV4 {
+            payload_inner: beacon_payload_v3::deserialize(value).map_err(D::Error::custom)?,
+            deposit_requests: requests(deposit_requests).map_err(D::Error::custom)?,
+            withdrawal_requests: requests(withdrawal_requests).map_err(D::Error::custom)?,
+        })
+    }
+
+    /// Deserializes the requests of a payload, which are empty if the field is missing.
+    fn requests<T: serde::de::DeserializeOwned>(value: Value) -> serde_json::Result<Vec<T>> {
+        if value.is_null() {
+            return Ok(Vec::new())
+        }
+        serde_json::from_value(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYLOAD: &str = r#"{
+        "parent_hash": "0x1111111111111111111111111111111111111111111111111111111111111111",
+        "fee_recipient": "0x0000000000000000000000000000000000000001",
+        "state_root": "0x2222222222222222222222222222222222222222222222222222222222222222",
+        "receipts_root": "0x3333333333333333333333333333333333333333333333333333333333333333",
+        "logs_bloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        "prev_randao": "0x4444444444444444444444444444444444444444444444444444444444444444",
+        "block_number": "1",
+        "gas_limit": "30000000",
+        "gas_used": "21000",
+        "timestamp": "12",
+        "extra_data": "0x",
+        "base_fee_per_gas": "7",
+        "block_hash": "0x5555555555555555555555555555555555555555555555555555555555555555",
+        "transactions": [],
+        "withdrawals": [],
+        "blob_gas_used": "0",
+        "excess_blob_gas": "0"
+    }"#;
+
+    fn submission(payload: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "message": {
+                "slot": "1",
+                "parent_hash": "0x1111111111111111111111111111111111111111111111111111111111111111",
+                "block_hash": "0x5555555555555555555555555555555555555555555555555555555555555555",
+                "builder_pubkey": format!("0x{}", "aa".repeat(48)),
+                "proposer_pubkey": format!("0x{}", "bb".repeat(48)),
+                "proposer_fee_recipient": "0x0000000000000000000000000000000000000002",
+                "gas_limit": "30000000",
+                "gas_used": "21000",
+                "value": "1000"
+            },
+            "execution_payload": payload,
+            "blobs_bundle": { "commitments": [], "proofs": [], "blobs": [] },
+            "signature": format!("0x{}", "cc".repeat(96)),
+            "registered_gas_limit": "30000000",
+            "parent_beacon_block_root": "0x6666666666666666666666666666666666666666666666666666666666666666"
+        })
+    }
+
+    #[test]
+    fn serde_builder_block_validation_request_v3() {
+        let s = submission(serde_json::from_str(PAYLOAD).unwrap());
+        let request: BuilderBlockValidationRequestV3 = serde_json::from_value(s.clone()).unwrap();
+        assert_eq!(request.registered_gas_limit, 30_000_000);
+        assert_eq!(request.request.execution_payload.payload_inner.payload_inner.gas_used, 21_000);
+        assert_eq!(serde_json::to_value(&request).unwrap(), s);
+    }
+
+    #[test]
+    fn serde_builder_block_validation_request_v4() {
+        let mut payload: serde_json::Value = serde_json::from_str(PAYLOAD).unwrap();
+        payload["deposit_requests"] = serde_json::json!([]);
+        payload["withdrawal_requests"] = serde_json::json!([]);
+        let s = submission(payload);
+        let request: BuilderBlockValidationRequestV4 = serde_json::from_value(s.clone()).unwrap();
+        assert_eq!(request.registered_gas_limit, 30_000_000);
+        assert!(request.request.execution_payload.deposit_requests.is_empty());
+        assert_eq!(serde_json::to_value(&request).unwrap(), s);
+    }
+}
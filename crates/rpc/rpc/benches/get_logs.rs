@@ -0,0 +1,129 @@
+#![allow(missing_docs, unreachable_pub)]
+use criterion::{criterion_group, criterion_main, Criterion};
+use reth_blockchain_tree::noop::NoopBlockchainTree;
+use reth_db::tables;
+use reth_db_api::{database::Database, transaction::DbTxMut};
+use reth_evm_ethereum::EthEvmConfig;
+use reth_primitives::{
+    logs_bloom,
+    stage::{StageCheckpoint, StageId},
+    Address, Block, Bytes, Header, Log, Receipt, SealedBlockWithSenders, Signature,
+    StaticFileSegment, Transaction, TransactionSigned, TxLegacy, B256,
+};
+use reth_provider::{
+    providers::BlockchainProvider, test_utils::create_test_provider_factory, ProviderFactory,
+    StageCheckpointWriter, StaticFileWriter,
+};
+use reth_rpc::eth::{cache::EthStateCache, EthFilter, EthFilterConfig};
+use reth_rpc_api::EthFilterApiServer;
+use reth_rpc_types::Filter;
+use reth_tasks::TokioTaskExecutor;
+use reth_transaction_pool::test_utils::testing_pool;
+use std::sync::Arc;
+
+/// The number of blocks of the queried range.
+const BLOCKS: u64 = 50_000;
+
+/// Every block with this interval emits the sparse topic.
+const SPARSE_TOPIC_INTERVAL: u64 = 5_000;
+
+pub fn get_logs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eth_getLogs");
+    group.sample_size(10);
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let address = Address::with_last_byte(1);
+    let common_topic = B256::with_last_byte(1);
+    let sparse_topic = B256::with_last_byte(2);
+
+    // the headers are served by static files and the receipts by the database
+    let factory = create_test_provider_factory();
+    generate_test_data(&factory, address, common_topic, sparse_topic);
+    let provider =
+        BlockchainProvider::new(factory, Arc::new(NoopBlockchainTree::default())).unwrap();
+
+    let eth_filter = runtime.block_on(async {
+        let cache =
+            EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+        EthFilter::new(
+            provider,
+            testing_pool(),
+            cache,
+            EthFilterConfig::default(),
+            Box::new(TokioTaskExecutor::default()),
+        )
+    });
+
+    // only the receipts of the blocks whose bloom matches the filter are loaded
+    group.bench_function(format!("sparse topic across {BLOCKS} blocks"), |b| {
+        b.to_async(&runtime).iter(|| async {
+            let filter = Filter::new()
+                .from_block(0)
+                .to_block(BLOCKS - 1)
+                .address(address)
+                .event_signature(sparse_topic);
+            let logs = EthFilterApiServer::logs(&eth_filter, filter).await.unwrap();
+            assert_eq!(logs.len() as u64, BLOCKS / SPARSE_TOPIC_INTERVAL);
+        })
+    });
+
+    // baseline: without addresses and topics the receipts of every block are loaded
+    group.bench_function(format!("full scan across {BLOCKS} blocks"), |b| {
+        b.to_async(&runtime).iter(|| async {
+            let filter = Filter::new().from_block(0).to_block(BLOCKS - 1);
+            let logs = EthFilterApiServer::logs(&eth_filter, filter).await.unwrap();
+            assert_eq!(logs.len() as u64, BLOCKS + BLOCKS / SPARSE_TOPIC_INTERVAL);
+        })
+    });
+}
+
+/// Writes a chain of blocks that each emit a log with the common topic, and every
+/// [`SPARSE_TOPIC_INTERVAL`] blocks another log with the sparse topic.
+fn generate_test_data<DB: Database>(
+    factory: &ProviderFactory<DB>,
+    address: Address,
+    common_topic: B256,
+    sparse_topic: B256,
+) {
+    let provider_rw = factory.provider_rw().unwrap();
+    let mut parent_hash = B256::ZERO;
+    for number in 0..BLOCKS {
+        let mut logs = vec![Log::new_unchecked(address, vec![common_topic], Bytes::new())];
+        if number % SPARSE_TOPIC_INTERVAL == 0 {
+            logs.push(Log::new_unchecked(address, vec![sparse_topic], Bytes::new()));
+        }
+        let receipt = Receipt { success: true, logs, ..Default::default() };
+
+        let header = Header {
+            number,
+            parent_hash,
+            logs_bloom: logs_bloom(&receipt.logs),
+            ..Default::default()
+        }
+        .seal_slow();
+        parent_hash = header.hash();
+
+        let transaction = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy { nonce: number, ..Default::default() }),
+            Signature::default(),
+        );
+        let block =
+            Block { header: header.unseal(), body: vec![transaction], ..Default::default() }
+                .seal_slow();
+        let block = SealedBlockWithSenders::new(block, vec![Address::ZERO]).unwrap();
+
+        let indices = provider_rw.insert_historical_block(block, None).unwrap();
+        provider_rw.tx_ref().put::<tables::Receipts>(indices.first_tx_num, receipt).unwrap();
+    }
+    provider_rw
+        .static_file_provider()
+        .latest_writer(StaticFileSegment::Headers)
+        .unwrap()
+        .commit()
+        .unwrap();
+    provider_rw.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(BLOCKS - 1)).unwrap();
+    provider_rw.commit().unwrap();
+}
+
+criterion_group!(get_logs_benches, get_logs);
+criterion_main!(get_logs_benches);
@@ -0,0 +1,82 @@
+#![allow(missing_docs, unreachable_pub)]
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::RngCore;
+use reth_primitives::{
+    constants::eip4844::MAINNET_KZG_TRUSTED_SETUP,
+    kzg::{
+        Blob, Bytes48, KzgCommitment, KzgProof, KzgSettings, BYTES_PER_BLOB,
+        BYTES_PER_FIELD_ELEMENT,
+    },
+    BlobTransactionSidecar,
+};
+use reth_rpc_types::ExecutionPayload;
+use reth_rpc_types_compat::engine::payload::{block_to_payload_v3, try_into_block};
+use reth_testing_utils::generators::{self, random_block};
+
+/// The number of transactions of the converted block.
+const TRANSACTIONS: u8 = 200;
+
+/// Compares verifying the blobs of a block submission before converting its payload into a block
+/// with doing both in parallel.
+pub fn validate_blobs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validate submission blobs");
+    let kzg_settings = MAINNET_KZG_TRUSTED_SETUP.clone();
+    let mut rng = generators::rng();
+
+    let block = random_block(&mut rng, 1, None, Some(TRANSACTIONS), Some(0));
+    let payload = ExecutionPayload::V3(block_to_payload_v3(block).0);
+    let convert = || {
+        try_into_block(payload.clone(), None).unwrap().seal_slow().try_seal_with_senders().unwrap()
+    };
+
+    for num_blobs in [6, 9] {
+        let sidecar = blob_sidecar(&mut rng, num_blobs, &kzg_settings);
+        let versioned_hashes = sidecar.versioned_hashes().collect::<Vec<_>>();
+        let verify = || sidecar.validate(&versioned_hashes, &kzg_settings).unwrap();
+
+        group.bench_function(format!("sequential | {num_blobs} blobs"), |b| {
+            b.iter(|| {
+                verify();
+                convert()
+            })
+        });
+        group.bench_function(format!("parallel | {num_blobs} blobs"), |b| {
+            b.iter(|| rayon::join(verify, convert))
+        });
+    }
+}
+
+/// Returns a sidecar with random blobs and their valid commitments and proofs.
+fn blob_sidecar(
+    rng: &mut impl RngCore,
+    num_blobs: usize,
+    kzg_settings: &KzgSettings,
+) -> BlobTransactionSidecar {
+    let blobs = (0..num_blobs)
+        .map(|_| {
+            let mut bytes = vec![0u8; BYTES_PER_BLOB];
+            rng.fill_bytes(&mut bytes);
+            // every field element must be smaller than the modulus
+            for element in bytes.chunks_mut(BYTES_PER_FIELD_ELEMENT) {
+                element[0] = 0;
+            }
+            Blob::from_bytes(&bytes).unwrap()
+        })
+        .collect::<Vec<_>>();
+    let commitments = blobs
+        .iter()
+        .map(|blob| KzgCommitment::blob_to_kzg_commitment(blob, kzg_settings).unwrap().to_bytes())
+        .collect::<Vec<Bytes48>>();
+    let proofs = blobs
+        .iter()
+        .zip(&commitments)
+        .map(|(blob, commitment)| {
+            KzgProof::compute_blob_kzg_proof(blob, commitment, kzg_settings).unwrap().to_bytes()
+        })
+        .collect::<Vec<Bytes48>>();
+
+    BlobTransactionSidecar::from_kzg(blobs, commitments, proofs)
+}
+
+criterion_group!(validate, validate_blobs);
+criterion_main!(validate);
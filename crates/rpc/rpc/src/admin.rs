@@ -1,5 +1,5 @@
 use crate::result::ToRpcResult;
-use alloy_primitives::B256;
+use alloy_primitives::{FixedBytes, B256};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_network_api::{NetworkInfo, PeerKind, Peers};
@@ -8,7 +8,8 @@ use reth_primitives::{ChainSpec, NodeRecord};
 use reth_rpc_api::AdminApiServer;
 use reth_rpc_types::{
     admin::{EthProtocolInfo, NodeInfo, Ports, ProtocolInfo},
-    PeerEthProtocolInfo, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
+    AdminNodeInfo, AdminPeerInfo, NodeEngineInfo, PeerDirection, PeerEthProtocolInfo,
+    PeerEthStatus, PeerForkId, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 };
 use std::sync::Arc;
 
@@ -20,12 +21,20 @@ pub struct AdminApi<N> {
     network: N,
     /// The specification of the blockchain's configuration.
     chain_spec: Arc<ChainSpec>,
+    /// The configuration of the engine API reported by `admin_nodeInfo`.
+    engine_info: NodeEngineInfo,
 }
 
 impl<N> AdminApi<N> {
     /// Creates a new instance of `AdminApi`.
     pub fn new(network: N, chain_spec: Arc<ChainSpec>) -> Self {
-        Self { network, chain_spec }
+        Self { network, chain_spec, engine_info: NodeEngineInfo::default() }
+    }
+
+    /// Sets the configuration of the engine API reported by `admin_nodeInfo`.
+    pub const fn with_engine_info(mut self, engine_info: NodeEngineInfo) -> Self {
+        self.engine_info = engine_info;
+        self
     }
 }
 
@@ -36,13 +45,15 @@ where
 {
     /// Handler for `admin_addPeer`
     fn add_peer(&self, record: NodeRecord) -> RpcResult<bool> {
-        self.network.add_peer(record.id, record.tcp_addr());
+        self.network.connect_peer(record.id, record.tcp_addr());
         Ok(true)
     }
 
     /// Handler for `admin_removePeer`
     fn remove_peer(&self, record: AnyNode) -> RpcResult<bool> {
-        self.network.remove_peer(record.peer_id(), PeerKind::Basic);
+        let peer_id = record.peer_id();
+        self.network.remove_peer(peer_id, PeerKind::Basic);
+        self.network.disconnect_peer(peer_id);
         Ok(true)
     }
 
@@ -61,28 +72,56 @@ where
         Ok(true)
     }
 
-    async fn peers(&self) -> RpcResult<Vec<PeerInfo>> {
+    /// Handler for `admin_peers`
+    async fn peers(&self) -> RpcResult<Vec<AdminPeerInfo>> {
         let peers = self.network.get_all_peers().await.to_rpc_result()?;
         let peers = peers
             .into_iter()
-            .map(|peer| PeerInfo {
-                id: Some(peer.remote_id.to_string()),
-                name: peer.client_version.to_string(),
-                caps: peer.capabilities.capabilities().iter().map(|cap| cap.to_string()).collect(),
-                network: PeerNetworkInfo {
-                    remote_address: peer.remote_addr.to_string(),
-                    local_address: peer
-                        .local_addr
-                        .unwrap_or_else(|| self.network.local_addr())
-                        .to_string(),
+            .map(|peer| AdminPeerInfo {
+                info: PeerInfo {
+                    id: Some(peer.remote_id.to_string()),
+                    name: peer.client_version.to_string(),
+                    caps: peer
+                        .capabilities
+                        .capabilities()
+                        .iter()
+                        .map(|cap| cap.to_string())
+                        .collect(),
+                    network: PeerNetworkInfo {
+                        remote_address: peer.remote_addr.to_string(),
+                        local_address: peer
+                            .local_addr
+                            .unwrap_or_else(|| self.network.local_addr())
+                            .to_string(),
+                    },
+                    protocols: PeerProtocolsInfo {
+                        eth: Some(PeerEthProtocolInfo {
+                            difficulty: Some(peer.status.total_difficulty),
+                            head: peer.status.blockhash.to_string(),
+                            version: u8::from(peer.eth_version) as u32,
+                        }),
+                        pip: None,
+                    },
+                },
+                shared_caps: peer.shared_capabilities.iter().map(|cap| cap.to_string()).collect(),
+                direction: if peer.direction.is_incoming() {
+                    PeerDirection::Inbound
+                } else {
+                    PeerDirection::Outbound
                 },
-                protocols: PeerProtocolsInfo {
-                    eth: Some(PeerEthProtocolInfo {
-                        difficulty: Some(peer.status.total_difficulty),
-                        head: peer.status.blockhash.to_string(),
-                        version: peer.status.version as u32,
-                    }),
-                    pip: None,
+                trusted: peer.kind.is_trusted(),
+                connection_duration: peer.session_established.elapsed().as_secs(),
+                reputation: peer.reputation,
+                eth_status: PeerEthStatus {
+                    version: peer.eth_version.into(),
+                    network_id: peer.status.chain.id(),
+                    genesis: peer.status.genesis,
+                    head: peer.status.blockhash,
+                    total_difficulty: peer.status.total_difficulty,
+                    fork_id: PeerForkId {
+                        hash: FixedBytes(peer.status.forkid.hash.0),
+                        next: peer.status.forkid.next,
+                    },
                 },
             })
             .collect();
@@ -91,7 +130,7 @@ where
     }
 
     /// Handler for `admin_nodeInfo`
-    async fn node_info(&self) -> RpcResult<NodeInfo> {
+    async fn node_info(&self) -> RpcResult<AdminNodeInfo> {
         let enode = self.network.local_node_record();
         let status = self.network.network_status().await.to_rpc_result()?;
         let config = self.chain_spec.genesis().config.clone();
@@ -100,7 +139,7 @@ where
             id: B256::from_slice(&enode.id.as_slice()[..32]),
             name: status.client_version,
             enode: enode.to_string(),
-            enr: self.network.local_enr().to_string(),
+            enr: self.network.discovery_enr().await.to_rpc_result()?.to_string(),
             ip: enode.address,
             ports: Ports { discovery: enode.udp_port, listener: enode.tcp_port },
             listen_addr: enode.tcp_addr(),
@@ -116,7 +155,7 @@ where
             },
         };
 
-        Ok(node_info)
+        Ok(AdminNodeInfo { info: node_info, engine: self.engine_info })
     }
 
     /// Handler for `admin_peerEvents`
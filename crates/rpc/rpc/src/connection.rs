@@ -0,0 +1,49 @@
+//! The connection an RPC call was received on.
+//!
+//! The server executes every call of a connection within the scope of the id of the connection,
+//! so handlers can account for resources per connection.
+
+use std::{
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+tokio::task_local! {
+    static CONNECTION_ID: RpcConnectionId;
+}
+
+/// Identifies an RPC connection, unique for the lifetime of the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RpcConnectionId(u64);
+
+impl RpcConnectionId {
+    /// Returns a new unique id.
+    pub fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Returns the id of the connection of the call that is currently executed, `None` if the
+    /// call wasn't received on a connection, e.g. if the handler is called directly.
+    pub fn current() -> Option<Self> {
+        CONNECTION_ID.try_with(|id| *id).ok()
+    }
+
+    /// Executes the future of a call received on this connection.
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        CONNECTION_ID.scope(self, f).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn current_id_within_scope() {
+        assert_eq!(RpcConnectionId::current(), None);
+        let id = RpcConnectionId::next();
+        assert_ne!(id, RpcConnectionId::next());
+        assert_eq!(id.scope(async { RpcConnectionId::current() }).await, Some(id));
+    }
+}
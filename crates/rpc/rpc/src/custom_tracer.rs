@@ -0,0 +1,176 @@
+//! Custom tracers of the `debug` namespace.
+//!
+//! A node can register tracers under a name in a [`TracerRegistry`], the tracing methods then
+//! trace calls with the tracer if the name is requested as the `tracer` option, e.g.
+//! `{"tracer": "myTracer", "tracerConfig": {..}}`.
+
+use crate::eth::error::EthApiError;
+use reth_provider::StateProviderBox;
+use reth_revm::database::StateProviderDatabase;
+use revm::{
+    db::CacheDB,
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    primitives::{Address, Log, ResultAndState, U256},
+    EvmContext, Inspector,
+};
+use std::{collections::HashMap, fmt, sync::Arc};
+
+/// The database the calls traced with custom tracers are executed on.
+pub type TracerDatabase = CacheDB<StateProviderDatabase<StateProviderBox>>;
+
+/// The inspector a call is traced with.
+pub type TracerInspector = Box<dyn for<'a> Inspector<&'a mut TracerDatabase> + Send>;
+
+/// Returns the result of a tracer from the result and the state changes of the traced call, and
+/// the state it was executed on.
+pub type TracerResultFn = Box<
+    dyn FnOnce(&ResultAndState, &TracerDatabase) -> Result<serde_json::Value, CustomTracerError>
+        + Send,
+>;
+
+/// The tracer of a single traced call, created by a [`TracerFactory`].
+///
+/// The inspector usually shares its recorded state with the result function, e.g. through an
+/// `Arc<Mutex<_>>`.
+pub struct CustomTracer {
+    /// The inspector the call is executed with.
+    pub inspector: TracerInspector,
+    /// Returns the result of the tracer once the call was executed.
+    pub into_result: TracerResultFn,
+}
+
+impl fmt::Debug for CustomTracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomTracer").finish_non_exhaustive()
+    }
+}
+
+/// Creates the tracers of a custom tracer name.
+pub trait TracerFactory: Send + Sync {
+    /// Creates the tracer of a call from the `tracerConfig` of the request, which is `null` if
+    /// the request has none.
+    fn create(&self, config: serde_json::Value) -> Result<CustomTracer, CustomTracerError>;
+}
+
+/// The custom tracers registered by a node, keyed by name.
+///
+/// Clones share the registered tracers, so tracers registered after the RPC handlers were created
+/// apply to them as well.
+#[derive(Clone, Default)]
+pub struct TracerRegistry {
+    tracers: Arc<parking_lot::RwLock<HashMap<String, Arc<dyn TracerFactory>>>>,
+}
+
+impl TracerRegistry {
+    /// Registers a tracer under the given name, registering a name again replaces the previous
+    /// tracer.
+    pub fn register(&self, name: impl Into<String>, factory: Box<dyn TracerFactory>) {
+        self.tracers.write().insert(name.into(), Arc::from(factory));
+    }
+
+    /// Returns the tracer registered under the given name.
+    pub fn get(&self, name: &str) -> Option<Arc<dyn TracerFactory>> {
+        self.tracers.read().get(name).cloned()
+    }
+}
+
+impl fmt::Debug for TracerRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TracerRegistry")
+            .field("tracers", &self.tracers.read().keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Errors of custom tracers.
+#[derive(Debug, thiserror::Error)]
+pub enum CustomTracerError {
+    /// The `tracerConfig` of the request is invalid for the tracer.
+    #[error("invalid tracer config: {0}")]
+    InvalidConfig(String),
+    /// The tracer failed to produce its result.
+    #[error("{0}")]
+    Tracer(String),
+}
+
+impl From<CustomTracerError> for EthApiError {
+    fn from(err: CustomTracerError) -> Self {
+        match err {
+            CustomTracerError::InvalidConfig(_) => Self::InvalidParams(err.to_string()),
+            CustomTracerError::Tracer(msg) => Self::CustomTracerError(msg),
+        }
+    }
+}
+
+/// Executes a call with the inspector of a [`CustomTracer`].
+pub(crate) struct CustomInspector(pub(crate) TracerInspector);
+
+impl fmt::Debug for CustomInspector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CustomInspector").finish_non_exhaustive()
+    }
+}
+
+impl<'a> Inspector<&'a mut TracerDatabase> for CustomInspector {
+    fn initialize_interp(
+        &mut self,
+        interp: &mut Interpreter,
+        context: &mut EvmContext<&'a mut TracerDatabase>,
+    ) {
+        self.0.initialize_interp(interp, context)
+    }
+
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<&'a mut TracerDatabase>) {
+        self.0.step(interp, context)
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        context: &mut EvmContext<&'a mut TracerDatabase>,
+    ) {
+        self.0.step_end(interp, context)
+    }
+
+    fn log(&mut self, context: &mut EvmContext<&'a mut TracerDatabase>, log: &Log) {
+        self.0.log(context, log)
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<&'a mut TracerDatabase>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.0.call(context, inputs)
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<&'a mut TracerDatabase>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.0.call_end(context, inputs, outcome)
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<&'a mut TracerDatabase>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.0.create(context, inputs)
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<&'a mut TracerDatabase>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.0.create_end(context, inputs, outcome)
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        Inspector::<&'a mut TracerDatabase>::selfdestruct(&mut *self.0, contract, target, value)
+    }
+}
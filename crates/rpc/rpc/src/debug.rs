@@ -1,43 +1,113 @@
 use crate::{
+    custom_tracer::{CustomInspector, TracerFactory, TracerRegistry},
     eth::{
         error::{EthApiError, EthResult},
-        revm_utils::{prepare_call_env, EvmOverrides},
+        revm_utils::{hashed_cache_storage, prepare_call_env, EvmOverrides},
         EthTransactions,
     },
+    js_tracer::{JsTracer, JsTracerThreads, TransactionContext},
+    parallel_trace::{trace_block_parallel, tx_env_with_cfg},
     result::{internal_rpc_err, ToRpcResult},
+    trace_guard::{StepBudget, TraceBudget, TraceGuard, TracingMetrics},
     EthApiSpec,
 };
 use alloy_rlp::{Decodable, Encodable};
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use jsonrpsee::{
+    core::RpcResult, server::SubscriptionMessage, types::ErrorObject, PendingSubscriptionSink,
+    SubscriptionSink,
+};
+use reth_evm::system_calls::apply_blockhashes_update;
 use reth_primitives::{
-    revm::env::tx_env_with_recovered, Address, Block, BlockId, BlockNumberOrTag, Bytes,
-    TransactionSignedEcRecovered, Withdrawals, B256, U256,
+    revm::env::{
+        fill_tx_env_with_beacon_root_contract_call,
+        fill_tx_env_with_withdrawal_requests_contract_call, tx_env_with_recovered,
+    },
+    trie::ExecutionWitness,
+    Address, Block, BlockId, BlockNumber, BlockNumberOrTag, Bytes, ChainSpec, Hardfork, Header,
+    TransactionSignedEcRecovered, B256, U256, U64,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, HeaderProvider, StateProviderBox, TransactionVariant,
+    BlockReaderIdExt, ChainSpecProvider, ExecutionWitnessProvider, HeaderProvider, ProviderResult,
+    RawBlockReader, StateProviderBox, TransactionReplayProvider, TransactionVariant,
 };
-use reth_revm::database::StateProviderDatabase;
+use reth_revm::{database::StateProviderDatabase, state_change::post_block_balance_increments};
 use reth_rpc_api::DebugApiServer;
+use reth_rpc_server_types::constants::{
+    DEFAULT_JS_TRACER_TIMEOUT, DEFAULT_MAX_JS_TRACER_THREADS, DEFAULT_MAX_STRUCT_LOGS_MEMORY,
+    DEFAULT_MAX_TRACE_BLOCK_CHECKPOINTS, DEFAULT_MAX_TRACE_CHAIN_BLOCKS, DEFAULT_TRACING_TIMEOUT,
+};
 use reth_rpc_types::{
     trace::geth::{
-        BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
-        GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame, TraceResult,
+        FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerConfig, GethDebugTracerType,
+        GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame,
     },
-    BlockError, Bundle, RichBlock, StateContext, TransactionRequest,
+    BlockError, Bundle, DebugBlockTraceResult, DebugTrace, DebugTraceResult, LimitedDefaultFrame,
+    ReplayedAccount, ReplayedAccountDiff, ReplayedStorageDiff, ReplayedTransaction, RichBlock,
+    StateContext, StorageRangeResult, StorageRangeSlot, SystemCallFrame, SystemCallFrameType,
+    TraceCallOptions, TraceChainCompleted, TraceChainNotification, TransactionRequest,
 };
-use reth_tasks::pool::BlockingTaskGuard;
+use reth_tasks::{pool::BlockingTaskGuard, TaskSpawner, TokioTaskExecutor};
+use reth_trie::HashedStorage;
 use revm::{
-    db::CacheDB,
-    primitives::{db::DatabaseCommit, BlockEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg},
+    db::{BundleState, CacheDB},
+    primitives::{
+        db::DatabaseCommit, Account, AccountInfo, BlockEnv, CfgEnvWithHandlerCfg, Env,
+        EnvWithHandlerCfg, ExecutionResult,
+    },
+    Database,
 };
 use revm_inspectors::tracing::{
-    js::{JsInspector, TransactionContext},
     FourByteInspector, MuxInspector, TracingInspector, TracingInspectorConfig,
 };
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::RangeInclusive,
+    sync::Arc,
+    time::Duration,
+};
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
 
+/// Settings of the `debug` namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugApiConfig {
+    /// The maximum number of transaction pre-states held at once when the transactions of a block
+    /// are traced in parallel, a value of 1 traces them sequentially.
+    pub max_trace_block_checkpoints: usize,
+    /// The maximum time a tracing request can take before its execution is aborted, all calls
+    /// traced by the request share the budget. Shorter budgets can be requested with the `timeout`
+    /// tracing option.
+    pub tracing_timeout: Duration,
+    /// The maximum combined size in bytes of the struct logs recorded for a traced call, the
+    /// remaining instructions are left out of the trace.
+    pub max_struct_logs_memory: usize,
+    /// Whether calls can be traced with custom JavaScript tracers.
+    pub js_tracer: bool,
+    /// The maximum time a tracing request with a JavaScript tracer can take before its execution
+    /// is aborted, all calls traced by the request share the budget. Shorter budgets can be
+    /// requested with the `timeout` tracing option.
+    pub js_tracer_timeout: Duration,
+    /// The maximum number of threads that execute JavaScript tracers, including the threads of
+    /// tracers that didn't return within the budget of their request.
+    pub max_js_tracer_threads: usize,
+    /// The maximum number of blocks a single `debug_traceChain` subscription can trace.
+    pub max_trace_chain_blocks: u64,
+}
+
+impl Default for DebugApiConfig {
+    fn default() -> Self {
+        Self {
+            max_trace_block_checkpoints: DEFAULT_MAX_TRACE_BLOCK_CHECKPOINTS,
+            tracing_timeout: DEFAULT_TRACING_TIMEOUT,
+            max_struct_logs_memory: DEFAULT_MAX_STRUCT_LOGS_MEMORY,
+            js_tracer: false,
+            js_tracer_timeout: DEFAULT_JS_TRACER_TIMEOUT,
+            max_js_tracer_threads: DEFAULT_MAX_JS_TRACER_THREADS,
+            max_trace_chain_blocks: DEFAULT_MAX_TRACE_CHAIN_BLOCKS,
+        }
+    }
+}
+
 /// `debug` API implementation.
 ///
 /// This type provides the functionality for handling `debug` related requests.
@@ -49,11 +119,111 @@ pub struct DebugApi<Provider, Eth> {
 
 impl<Provider, Eth> DebugApi<Provider, Eth> {
     /// Create a new instance of the [`DebugApi`]
-    pub fn new(provider: Provider, eth: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
-        let inner = Arc::new(DebugApiInner { provider, eth_api: eth, blocking_task_guard });
+    pub fn new(
+        provider: Provider,
+        eth: Eth,
+        blocking_task_guard: BlockingTaskGuard,
+        config: DebugApiConfig,
+    ) -> Self {
+        Self::with_spawner(
+            provider,
+            eth,
+            blocking_task_guard,
+            config,
+            Box::<TokioTaskExecutor>::default(),
+        )
+    }
+
+    /// Creates a new instance that spawns the tasks of its subscriptions with the given spawner.
+    pub fn with_spawner(
+        provider: Provider,
+        eth: Eth,
+        blocking_task_guard: BlockingTaskGuard,
+        config: DebugApiConfig,
+        task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        let config = DebugApiConfig {
+            max_trace_block_checkpoints: config.max_trace_block_checkpoints.max(1),
+            ..config
+        };
+        let inner = Arc::new(DebugApiInner {
+            provider,
+            eth_api: eth,
+            blocking_task_guard,
+            config,
+            task_spawner,
+            metrics: Default::default(),
+            js_tracer_threads: JsTracerThreads::new(config.max_js_tracer_threads),
+            execution_witness_provider: Default::default(),
+            transaction_replay_provider: Default::default(),
+            raw_block_reader: Default::default(),
+            tracers: Default::default(),
+        });
         Self { inner }
     }
 
+    /// Sets the provider of execution witnesses for `debug_executionWitness`.
+    pub fn set_execution_witness_provider(&self, provider: Arc<dyn ExecutionWitnessProvider>) {
+        self.inner.execution_witness_provider.write().replace(provider);
+    }
+
+    /// Sets the provider of transaction replays for `debug_replayTransaction`.
+    pub fn set_transaction_replay_provider(&self, provider: Arc<dyn TransactionReplayProvider>) {
+        self.inner.transaction_replay_provider.write().replace(provider);
+    }
+
+    /// Sets the reader of the encoded canonical blocks, the raw getters serve canonical blocks
+    /// from it without decoding and re-encoding them.
+    pub fn set_raw_block_reader(&self, reader: Arc<dyn RawBlockReader>) {
+        self.inner.raw_block_reader.write().replace(reader);
+    }
+
+    /// Registers a custom tracer under the given name, calls are traced with it if the name is
+    /// requested as the `tracer` of a tracing option.
+    ///
+    /// The names of the built-in tracers always resolve to the built-in tracers. Registering a
+    /// name again replaces the previous tracer.
+    pub fn register_tracer(&self, name: impl Into<String>, factory: Box<dyn TracerFactory>) {
+        self.inner.tracers.register(name, factory);
+    }
+
+    /// Returns the registry of the custom tracers of this handler.
+    ///
+    /// Tracers registered in the returned registry, or a clone of it, apply to this handler.
+    pub fn tracer_registry(&self) -> &TracerRegistry {
+        &self.inner.tracers
+    }
+
+    /// Returns the custom tracer registered under the given name.
+    fn tracer_factory(&self, name: &str) -> Option<Arc<dyn TracerFactory>> {
+        self.inner.tracers.get(name)
+    }
+
+    /// Reads the encoded form of a canonical block with the raw block reader.
+    ///
+    /// Returns `None` if no reader is set or the block isn't a persisted canonical block, e.g. the
+    /// pending block, in which case the typed block has to be encoded instead.
+    fn read_raw_block<T>(
+        &self,
+        block_id: BlockId,
+        read: impl FnOnce(&dyn RawBlockReader, BlockNumber) -> ProviderResult<Option<T>>,
+    ) -> EthResult<Option<T>>
+    where
+        Provider: BlockReaderIdExt,
+    {
+        let Some(reader) = self.inner.raw_block_reader.read().clone() else { return Ok(None) };
+        let number = match block_id {
+            BlockId::Hash(hash) => self.inner.provider.block_number(hash.block_hash)?,
+            BlockId::Number(number_or_tag) => {
+                self.inner.provider.convert_block_number(number_or_tag)?
+            }
+        };
+        match number {
+            Some(number) => Ok(read(&*reader, number)?),
+            None => Ok(None),
+        }
+    }
+
     /// Access the underlying `Eth` API.
     pub fn eth_api(&self) -> &Eth {
         &self.inner.eth_api
@@ -67,64 +237,380 @@ where
     Provider: BlockReaderIdExt + HeaderProvider + ChainSpecProvider + 'static,
     Eth: EthTransactions + 'static,
 {
+    /// Executes the closure with the database and the env of the call on a blocking task.
+    ///
+    /// Without a transaction index, the call is executed on top of the state at the given block,
+    /// see [`EthTransactions::spawn_with_call_at`]. With a transaction index, the call is executed
+    /// in the env of the block, on top of the state of the parent block, the pre-block system calls
+    /// and the first `tx_index` transactions of the block.
+    ///
+    /// The overrides are applied after the transactions are replayed.
+    async fn spawn_with_trace_call_at<F, R>(
+        &self,
+        call: TransactionRequest,
+        at: BlockId,
+        tx_index: Option<u64>,
+        overrides: EvmOverrides,
+        f: F,
+    ) -> EthResult<R>
+    where
+        F: FnOnce(
+                &mut CacheDB<StateProviderDatabase<StateProviderBox>>,
+                EnvWithHandlerCfg,
+            ) -> EthResult<R>
+            + Send
+            + 'static,
+        R: Send + 'static,
+    {
+        let Some(tx_index) = tx_index else {
+            return self.inner.eth_api.spawn_with_call_at(call, at, overrides, f).await
+        };
+
+        let ((mut cfg, block_env, _), block) = futures::try_join!(
+            self.inner.eth_api.evm_env_at(at),
+            self.inner.eth_api.block_by_id_with_senders(at),
+        )?;
+        let block = block.ok_or(EthApiError::UnknownBlockNumber)?;
+        if tx_index > block.body.len() as u64 {
+            return Err(EthApiError::InvalidParams(format!(
+                "transaction index {tx_index} is out of range, the block has {} transactions",
+                block.body.len()
+            )))
+        }
+
+        let system_calls = BlockSystemCalls::new(&self.inner.provider.chain_spec(), &block.header);
+        let gas_limit = self.inner.eth_api.call_gas_limit();
+        let memory_limit = self.inner.eth_api.call_memory_limit();
+        let this = self.clone();
+        self.inner
+            .eth_api
+            .spawn_with_state_at_block(block.parent_hash.into(), move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+                this.apply_pre_block_system_calls(&mut db, system_calls, &cfg, &block_env, None)?;
+                for tx in block.into_transactions_ecrecovered().take(tx_index as usize) {
+                    let env = tx_env_with_cfg(&cfg, &block_env, &tx);
+                    let (res, _) = this.inner.eth_api.transact(&mut db, env)?;
+                    db.commit(res.state);
+                }
+
+                // only the call is limited by the configured memory limit
+                cfg.memory_limit = memory_limit;
+                let env = prepare_call_env(cfg, block_env, call, gas_limit, &mut db, overrides)?;
+                f(&mut db, env)
+            })
+            .await
+    }
+
+    /// Starts the budget of a request traced with the given options, it is shared by all calls
+    /// the request traces. The requested `timeout` is capped by the configured maximum of the
+    /// tracer.
+    ///
+    /// Returns an error if a JavaScript tracer is requested while they're disabled.
+    fn trace_budget(&self, opts: &GethDebugTracingOptions) -> EthResult<TraceBudget> {
+        let timeout = opts.timeout.as_deref();
+        let timeout = match &opts.tracer {
+            // names that are neither built-in tracers nor registered are JavaScript code
+            Some(GethDebugTracerType::JsTracer(name)) if self.tracer_factory(name).is_none() => {
+                self.js_tracer_timeout(timeout)?
+            }
+            _ => self.tracing_timeout(timeout)?,
+        };
+        Ok(TraceBudget::start(timeout, self.inner.metrics.clone()))
+    }
+
+    /// Returns the timeout of a request traced with a JavaScript tracer, the requested `timeout` is
+    /// capped by the configured maximum.
+    ///
+    /// Returns an error if JavaScript tracers are disabled.
+    fn js_tracer_timeout(&self, timeout: Option<&str>) -> EthResult<Duration> {
+        if !self.inner.config.js_tracer {
+            return Err(EthApiError::Unsupported(
+                "JavaScript tracers are disabled, enable them with `--rpc.js-tracer`",
+            ))
+        }
+        capped_tracer_timeout(timeout, self.inner.config.js_tracer_timeout)
+    }
+
+    /// Returns the timeout of a request traced with a built-in tracer or the struct logger, the
+    /// requested `timeout` is capped by the configured maximum.
+    fn tracing_timeout(&self, timeout: Option<&str>) -> EthResult<Duration> {
+        capped_tracer_timeout(timeout, self.inner.config.tracing_timeout)
+    }
+
     /// Acquires a permit to execute a tracing call.
     async fn acquire_trace_permit(&self) -> Result<OwnedSemaphorePermit, AcquireError> {
         self.inner.blocking_task_guard.clone().acquire_owned().await
     }
 
     /// Trace the entire block asynchronously
+    ///
+    /// The system calls of the block are applied around its transactions, they are traced as
+    /// entries without a transaction hash if the `includeSystemCalls` option of the tracer config
+    /// is set, and always by the `prestateTracer` since their state changes are part of the
+    /// state transition of the block.
     async fn trace_block_with(
         &self,
         at: BlockId,
         transactions: Vec<TransactionSignedEcRecovered>,
+        mut system_calls: BlockSystemCalls,
         cfg: CfgEnvWithHandlerCfg,
         block_env: BlockEnv,
-        opts: GethDebugTracingOptions,
-    ) -> EthResult<Vec<TraceResult>> {
-        if transactions.is_empty() {
+        mut opts: GethDebugTracingOptions,
+    ) -> EthResult<Vec<DebugTraceResult>> {
+        let budget = self.trace_budget(&opts)?;
+        system_calls.traced = traces_system_calls(&mut opts);
+        if transactions.is_empty() && !system_calls.has_traced_calls() {
             // nothing to trace
             return Ok(Vec::new())
         }
 
-        // replay all transactions of the block
+        // replay all transactions of the block, the post-block calls are traced on top of the
+        // state after the last transaction which only the sequential replay keeps
         let this = self.clone();
+        if self.inner.config.max_trace_block_checkpoints > 1 &&
+            transactions.len() > 1 &&
+            !system_calls.traces_post_block_calls()
+        {
+            return self
+                .eth_api()
+                .spawn_blocking(move || {
+                    this.trace_block_parallel(
+                        at,
+                        transactions,
+                        system_calls,
+                        &cfg,
+                        &block_env,
+                        &opts,
+                        &budget,
+                    )
+                })
+                .await
+        }
         self.eth_api()
             .spawn_with_state_at_block(at, move |state| {
-                let block_hash = at.as_block_hash();
-                let mut results = Vec::with_capacity(transactions.len());
-                let mut db = CacheDB::new(StateProviderDatabase::new(state));
-                let mut transactions = transactions.into_iter().enumerate().peekable();
-                while let Some((index, tx)) = transactions.next() {
-                    let tx_hash = tx.hash;
-                    let tx = tx_env_with_recovered(&tx);
-                    let env = EnvWithHandlerCfg {
-                        env: Env::boxed(cfg.cfg_env.clone(), block_env.clone(), tx),
-                        handler_cfg: cfg.handler_cfg,
-                    };
-                    let (result, state_changes) = this.trace_transaction(
-                        opts.clone(),
-                        env,
-                        &mut db,
-                        Some(TransactionContext {
-                            block_hash,
-                            tx_hash: Some(tx_hash),
-                            tx_index: Some(index),
-                        }),
-                    )?;
-
-                    results.push(TraceResult::Success { result, tx_hash: Some(tx_hash) });
-                    if transactions.peek().is_some() {
-                        // need to apply the state changes of this transaction before executing the
-                        // next transaction
-                        db.commit(state_changes)
-                    }
-                }
-
-                Ok(results)
+                this.trace_block_sequential(
+                    at,
+                    state,
+                    transactions,
+                    system_calls,
+                    &cfg,
+                    &block_env,
+                    &opts,
+                    &budget,
+                )
             })
             .await
     }
 
+    /// Traces the transactions of the block one after another on top of the given state.
+    #[allow(clippy::too_many_arguments)]
+    fn trace_block_sequential(
+        &self,
+        at: BlockId,
+        state: StateProviderBox,
+        transactions: Vec<TransactionSignedEcRecovered>,
+        system_calls: BlockSystemCalls,
+        cfg: &CfgEnvWithHandlerCfg,
+        block_env: &BlockEnv,
+        opts: &GethDebugTracingOptions,
+        budget: &TraceBudget,
+    ) -> EthResult<Vec<DebugTraceResult>> {
+        let mut db = CacheDB::new(StateProviderDatabase::new(state));
+        self.trace_block_on(
+            &mut db,
+            at.as_block_hash(),
+            transactions,
+            system_calls,
+            cfg,
+            block_env,
+            opts,
+            budget,
+            false,
+        )
+    }
+
+    /// Traces the transactions of the block one after another on top of the given database.
+    ///
+    /// If `commit` is set, all state changes of the transactions and the system calls of the block
+    /// are committed to the database, even the ones that aren't traced, so that the next block can
+    /// be traced on top of it.
+    #[allow(clippy::too_many_arguments)]
+    fn trace_block_on(
+        &self,
+        db: &mut CacheDB<StateProviderDatabase<StateProviderBox>>,
+        block_hash: Option<B256>,
+        transactions: Vec<TransactionSignedEcRecovered>,
+        system_calls: BlockSystemCalls,
+        cfg: &CfgEnvWithHandlerCfg,
+        block_env: &BlockEnv,
+        opts: &GethDebugTracingOptions,
+        budget: &TraceBudget,
+        commit: bool,
+    ) -> EthResult<Vec<DebugTraceResult>> {
+        let mut results = self.apply_pre_block_system_calls(
+            db,
+            system_calls,
+            cfg,
+            block_env,
+            Some((opts, budget)),
+        )?;
+        results.reserve(transactions.len());
+        let post_block_calls = system_calls.traces_post_block_calls();
+        let mut transactions = transactions.into_iter().enumerate().peekable();
+        while let Some((index, tx)) = transactions.next() {
+            let tx_hash = tx.hash;
+            let (result, state_changes) = self.trace_transaction(
+                opts.clone(),
+                tx_env_with_cfg(cfg, block_env, &tx),
+                db,
+                budget,
+                Some(TransactionContext {
+                    block_hash,
+                    tx_hash: Some(tx_hash),
+                    tx_index: Some(index),
+                }),
+            )?;
+
+            results.push(DebugTraceResult::Success { result, tx_hash: Some(tx_hash) });
+            if transactions.peek().is_some() || post_block_calls || commit {
+                // need to apply the state changes of this transaction before executing the
+                // next transaction or the post-block calls
+                db.commit(state_changes)
+            }
+        }
+
+        if post_block_calls || (commit && system_calls.withdrawal_requests_contract_call) {
+            let mut env = system_call_env(cfg, block_env);
+            fill_tx_env_with_withdrawal_requests_contract_call(&mut env.env);
+            results.extend(self.system_call(
+                db,
+                env,
+                post_block_calls.then_some((opts, budget)),
+            )?);
+        }
+
+        Ok(results)
+    }
+
+    /// Traces the transactions of the block concurrently on the tracing pool.
+    ///
+    /// The results are identical to [`Self::trace_block_sequential`], see also
+    /// [`trace_block_parallel`].
+    fn trace_block_parallel(
+        &self,
+        at: BlockId,
+        transactions: Vec<TransactionSignedEcRecovered>,
+        system_calls: BlockSystemCalls,
+        cfg: &CfgEnvWithHandlerCfg,
+        block_env: &BlockEnv,
+        opts: &GethDebugTracingOptions,
+        budget: &TraceBudget,
+    ) -> EthResult<Vec<DebugTraceResult>> {
+        let block_hash = at.as_block_hash();
+        let mut db = CacheDB::new(StateProviderDatabase::new(self.eth_api().state_at(at)?));
+        let mut results = self.apply_pre_block_system_calls(
+            &mut db,
+            system_calls,
+            cfg,
+            block_env,
+            Some((opts, budget)),
+        )?;
+        let traces = trace_block_parallel(
+            self.eth_api(),
+            at,
+            db,
+            &transactions,
+            cfg,
+            block_env,
+            self.inner.config.max_trace_block_checkpoints,
+            |index, tx, env, db| {
+                let (result, _) = self.trace_transaction(
+                    opts.clone(),
+                    env,
+                    db,
+                    budget,
+                    Some(TransactionContext {
+                        block_hash,
+                        tx_hash: Some(tx.hash),
+                        tx_index: Some(index),
+                    }),
+                )?;
+                Ok(DebugTraceResult::Success { result, tx_hash: Some(tx.hash) })
+            },
+        )?;
+        results.extend(traces);
+        Ok(results)
+    }
+
+    /// Applies the system calls that precede the transactions of the block to the state and
+    /// returns their traces if they are traced.
+    ///
+    /// The calls are traced with the given options within the budget of the request if the
+    /// system calls of the block are traced.
+    fn apply_pre_block_system_calls(
+        &self,
+        db: &mut CacheDB<StateProviderDatabase<StateProviderBox>>,
+        system_calls: BlockSystemCalls,
+        cfg: &CfgEnvWithHandlerCfg,
+        block_env: &BlockEnv,
+        traced: Option<(&GethDebugTracingOptions, &TraceBudget)>,
+    ) -> EthResult<Vec<DebugTraceResult>> {
+        let traced = traced.filter(|_| system_calls.traced);
+        let mut results = Vec::new();
+        if let Some(parent_beacon_block_root) = system_calls.parent_beacon_block_root {
+            let mut env = system_call_env(cfg, block_env);
+            fill_tx_env_with_beacon_root_contract_call(&mut env.env, parent_beacon_block_root);
+            results.extend(self.system_call(db, env, traced)?);
+        }
+        if let Some((number, parent_hash)) = system_calls.blockhashes_update {
+            // the update writes the storage of the history contract directly instead of calling
+            // it, so there's no execution to trace
+            let chain_spec = self.inner.provider.chain_spec();
+            apply_blockhashes_update(
+                db,
+                &chain_spec,
+                block_env.timestamp.to(),
+                number,
+                parent_hash,
+            )
+            .map_err(|err| EthApiError::Internal(err.into()))?;
+        }
+        Ok(results)
+    }
+
+    /// Executes a system call on the given state and commits its state changes, without the
+    /// changes to the system caller and the beneficiary which aren't part of the state
+    /// transition.
+    ///
+    /// Returns the trace of the call, marked as a `SYSTEM` frame, if tracing options are given.
+    fn system_call(
+        &self,
+        db: &mut CacheDB<StateProviderDatabase<StateProviderBox>>,
+        env: EnvWithHandlerCfg,
+        traced: Option<(&GethDebugTracingOptions, &TraceBudget)>,
+    ) -> EthResult<Option<DebugTraceResult>> {
+        let (caller, contract, coinbase) =
+            (env.tx.caller, env.tx.transact_to.to().copied(), env.block.coinbase);
+        let (trace, mut state) = match traced {
+            Some((opts, budget)) => {
+                let (trace, state) = self.trace_transaction(opts.clone(), env, db, budget, None)?;
+                (Some(trace), state)
+            }
+            None => {
+                let (res, _) = self.eth_api().transact(&mut *db, env)?;
+                (None, res.state)
+            }
+        };
+        state.remove(&caller);
+        state.remove(&coinbase);
+        db.commit(state);
+
+        Ok(trace.map(|trace| DebugTraceResult::Success {
+            result: system_call_trace(trace, caller, contract),
+            tx_hash: None,
+        }))
+    }
+
     /// Replays the given block and returns the trace of each transaction.
     ///
     /// This expects a rlp encoded block
@@ -134,13 +620,14 @@ where
         &self,
         rlp_block: Bytes,
         opts: GethDebugTracingOptions,
-    ) -> EthResult<Vec<TraceResult>> {
+    ) -> EthResult<Vec<DebugTraceResult>> {
         let block =
             Block::decode(&mut rlp_block.as_ref()).map_err(BlockError::RlpDecodeRawBlock)?;
 
         let (cfg, block_env) = self.eth_api().evm_env_for_raw_block(&block.header).await?;
         // we trace on top the block's parent block
         let parent = block.parent_hash;
+        let system_calls = BlockSystemCalls::new(&self.inner.provider.chain_spec(), &block.header);
 
         // Depending on EIP-2 we need to recover the transactions differently
         let transactions =
@@ -164,7 +651,118 @@ where
                     .collect::<EthResult<Vec<_>>>()?
             };
 
-        self.trace_block_with(parent.into(), transactions, cfg, block_env, opts).await
+        self.trace_block_with(parent.into(), transactions, system_calls, cfg, block_env, opts).await
+    }
+
+    /// Re-executes the block on top of its parent state and returns the witness of all the state
+    /// accessed during the execution.
+    pub async fn debug_execution_witness(&self, block_id: BlockId) -> EthResult<ExecutionWitness> {
+        let witness_provider = self
+            .inner
+            .execution_witness_provider
+            .read()
+            .clone()
+            .ok_or(EthApiError::Unsupported("execution witness generation is not available"))?;
+        let block_hash = self
+            .inner
+            .provider
+            .block_hash_for_id(block_id)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+
+        self.eth_api().spawn_blocking(move || Ok(witness_provider.witness(block_hash)?)).await
+    }
+
+    /// Replays the transaction on top of the exact state it was executed on and returns its
+    /// result and the state changes it made.
+    pub async fn debug_replay_transaction(&self, tx_hash: B256) -> EthResult<ReplayedTransaction> {
+        let replay_provider = self
+            .inner
+            .transaction_replay_provider
+            .read()
+            .clone()
+            .ok_or(EthApiError::Unsupported("transaction replay is not available"))?;
+        let (_, meta) = self
+            .inner
+            .provider
+            .transaction_by_hash_with_meta(tx_hash)?
+            .ok_or(EthApiError::TransactionNotFound)?;
+
+        self.eth_api()
+            .spawn_blocking(move || {
+                let (result, bundle) = replay_provider.replay(meta.block_hash, tx_hash)?;
+                Ok(replayed_transaction(result, bundle))
+            })
+            .await
+    }
+
+    /// Returns up to `max_result` storage slots of the contract after the first `tx_index`
+    /// transactions of the block, starting at the first hashed slot greater or equal to
+    /// `key_start`.
+    pub async fn debug_storage_range_at(
+        &self,
+        block_hash: B256,
+        tx_index: usize,
+        contract_address: Address,
+        key_start: B256,
+        max_result: u64,
+    ) -> EthResult<StorageRangeResult> {
+        let ((cfg, block_env, _), block) = futures::try_join!(
+            self.inner.eth_api.evm_env_at(block_hash.into()),
+            self.inner.eth_api.block_by_id_with_senders(block_hash.into()),
+        )?;
+        let block = block.ok_or(EthApiError::UnknownBlockNumber)?;
+        if tx_index > block.body.len() {
+            return Err(EthApiError::InvalidParams(format!(
+                "transaction index {tx_index} is out of range, the block has {} transactions",
+                block.body.len()
+            )))
+        }
+        let limit = usize::try_from(max_result).unwrap_or(usize::MAX);
+
+        let parent_hash = block.parent_hash;
+        let system_calls = BlockSystemCalls::new(&self.inner.provider.chain_spec(), &block.header);
+        let this = self.clone();
+        self.inner
+            .eth_api
+            .spawn_with_state_at_block(parent_hash.into(), move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+                this.apply_pre_block_system_calls(&mut db, system_calls, &cfg, &block_env, None)?;
+                for tx in block.into_transactions_ecrecovered().take(tx_index) {
+                    let env = tx_env_with_cfg(&cfg, &block_env, &tx);
+                    let (res, _) = this.inner.eth_api.transact(&mut db, env)?;
+                    db.commit(res.state);
+                }
+
+                // the storage changes of the executed transactions are applied on top of the
+                // storage at the parent block, which also resolves the preimages of the slots
+                // they wrote
+                let account = db.accounts.get(&contract_address);
+                let hashed_storage =
+                    account.map(hashed_cache_storage).unwrap_or_else(|| HashedStorage::new(false));
+                let mut range =
+                    db.db.storage_range(contract_address, hashed_storage, key_start, limit)?;
+                if let Some(account) = account {
+                    range.set_preimages(
+                        account.storage.keys().map(|slot| B256::new(slot.to_be_bytes())),
+                    );
+                }
+
+                Ok(StorageRangeResult {
+                    storage: range
+                        .slots
+                        .into_iter()
+                        .map(|(hashed_slot, entry)| {
+                            let slot = StorageRangeSlot {
+                                key: entry.slot,
+                                value: B256::new(entry.value.to_be_bytes()),
+                            };
+                            (hashed_slot, slot)
+                        })
+                        .collect(),
+                    next_key: range.next_key,
+                })
+            })
+            .await
     }
 
     /// Replays a block and returns the trace of each transaction.
@@ -172,7 +770,7 @@ where
         &self,
         block_id: BlockId,
         opts: GethDebugTracingOptions,
-    ) -> EthResult<Vec<TraceResult>> {
+    ) -> EthResult<Vec<DebugTraceResult>> {
         let block_hash = self
             .inner
             .provider
@@ -188,10 +786,12 @@ where
         // we need to get the state of the parent block because we're replaying this block on top of
         // its parent block's state
         let state_at = block.parent_hash;
+        let system_calls = BlockSystemCalls::new(&self.inner.provider.chain_spec(), &block.header);
 
         self.trace_block_with(
             state_at.into(),
             block.into_transactions_ecrecovered().collect(),
+            system_calls,
             cfg,
             block_env,
             opts,
@@ -199,6 +799,166 @@ where
         .await
     }
 
+    /// Returns the blocks between the two blocks (excluding start) that are traced by
+    /// `debug_traceChain`.
+    ///
+    /// Returns an error if the range is empty or longer than the configured maximum.
+    fn trace_chain_range(
+        &self,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+    ) -> EthResult<RangeInclusive<u64>> {
+        let provider = &self.inner.provider;
+        let start = provider
+            .convert_block_number(start_exclusive)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        let end =
+            provider.convert_block_number(end_inclusive)?.ok_or(EthApiError::UnknownBlockNumber)?;
+        if start >= end {
+            return Err(EthApiError::InvalidParams(format!(
+                "start block {start} must be lower than end block {end}"
+            )))
+        }
+
+        let max_blocks = self.inner.config.max_trace_chain_blocks;
+        if end - start > max_blocks {
+            return Err(EthApiError::InvalidParams(format!(
+                "range of {} blocks exceeds the limit of {max_blocks} blocks",
+                end - start
+            )))
+        }
+        Ok(start + 1..=end)
+    }
+
+    /// Traces the blocks of the range one after another and sends the traces of every block to the
+    /// subscriber, followed by the [`TraceChainCompleted`] notification.
+    ///
+    /// Only the state of the parent of the first block is read from the database, every block is
+    /// traced on top of the state changes of the blocks before it, which are kept in memory until
+    /// the end of the range. The next block is only traced once the notification of the previous
+    /// block was handed to the connection, so a subscriber that doesn't keep up pauses the tracing
+    /// instead of the traces piling up in memory. The tracing stops as soon as the subscriber
+    /// unsubscribes or disconnects.
+    async fn pipe_trace_chain(
+        &self,
+        sink: SubscriptionSink,
+        range: RangeInclusive<u64>,
+        mut opts: GethDebugTracingOptions,
+    ) {
+        let traced_system_calls = traces_system_calls(&mut opts);
+        let mut completed = TraceChainCompleted::default();
+        let mut state = None;
+        for number in range {
+            if sink.is_closed() {
+                return
+            }
+            let traced = tokio::select! {
+                _ = sink.closed() => return,
+                traced = self.trace_chain_block(
+                    number,
+                    state.take(),
+                    traced_system_calls,
+                    opts.clone(),
+                ) => traced,
+            };
+            let block = match traced {
+                Ok((block, next_state)) => {
+                    state = next_state;
+                    block
+                }
+                Err(err) => {
+                    completed.error = Some(err.to_string());
+                    break
+                }
+            };
+
+            let notification = TraceChainNotification::Block(block);
+            let Ok(msg) = SubscriptionMessage::from_json(&notification) else { return };
+            if sink.send(msg).await.is_err() {
+                return
+            }
+            completed.blocks += U64::from(1);
+        }
+
+        let notification = TraceChainNotification::Completed { completed };
+        if let Ok(msg) = SubscriptionMessage::from_json(&notification) {
+            let _ = sink.send(msg).await;
+        }
+    }
+
+    /// Traces the transactions of the block with the given number for `debug_traceChain`, on top
+    /// of the given state of its parent, or of the state of its parent in the database if none is
+    /// given.
+    ///
+    /// Every block is traced within its own budget, like a `debug_traceBlock` request.
+    ///
+    /// Returns the traces and the state after the block. The state is `None` after the DAO fork
+    /// block, since its irregular state change isn't replayed, so the next block is traced on top
+    /// of the state in the database.
+    async fn trace_chain_block(
+        &self,
+        number: u64,
+        state: Option<CacheDB<StateProviderDatabase<StateProviderBox>>>,
+        traced_system_calls: bool,
+        opts: GethDebugTracingOptions,
+    ) -> EthResult<(DebugBlockTraceResult, Option<CacheDB<StateProviderDatabase<StateProviderBox>>>)>
+    {
+        // the permit is taken per block, so a long range doesn't hold up other tracing requests
+        let _permit = self.acquire_trace_permit().await;
+        let budget = self.trace_budget(&opts)?;
+        let block = self
+            .inner
+            .eth_api
+            .block_by_id_with_senders(number.into())
+            .await?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        let hash = block.hash();
+        let (cfg, block_env, _) = self.inner.eth_api.evm_env_at(hash.into()).await?;
+
+        let chain_spec = self.inner.provider.chain_spec();
+        let mut system_calls = BlockSystemCalls::new(&chain_spec, &block.header);
+        system_calls.traced = traced_system_calls;
+        let total_difficulty = self.inner.provider.header_td_by_number(number)?.unwrap_or_default();
+        let balance_increments = post_block_balance_increments(
+            &chain_spec,
+            number,
+            block.header.difficulty,
+            block.header.beneficiary,
+            block.header.timestamp,
+            total_difficulty,
+            &block.ommers,
+            block.withdrawals.as_ref().map(|withdrawals| withdrawals.as_slice()),
+        );
+        let dao_fork = chain_spec.fork(Hardfork::Dao).transitions_at_block(number);
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_blocking(move || {
+                let mut db = match state {
+                    Some(db) => db,
+                    None => CacheDB::new(StateProviderDatabase::new(
+                        this.eth_api().state_at(block.parent_hash.into())?,
+                    )),
+                };
+                let traces = this.trace_block_on(
+                    &mut db,
+                    Some(hash),
+                    block.into_transactions_ecrecovered().collect(),
+                    system_calls,
+                    &cfg,
+                    &block_env,
+                    &opts,
+                    &budget,
+                    true,
+                )?;
+                increment_balances(&mut db, balance_increments)?;
+
+                let block = DebugBlockTraceResult { block: number.into(), hash, traces };
+                Ok((block, (!dao_fork).then_some(db)))
+            })
+            .await
+    }
+
     /// Trace the transaction according to the provided options.
     ///
     /// Ref: <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers>
@@ -206,7 +966,8 @@ where
         &self,
         tx_hash: B256,
         opts: GethDebugTracingOptions,
-    ) -> EthResult<GethTrace> {
+    ) -> EthResult<DebugTrace> {
+        let budget = self.trace_budget(&opts)?;
         let (transaction, block) = match self.inner.eth_api.transaction_and_block(tx_hash).await? {
             None => return Err(EthApiError::TransactionNotFound),
             Some(res) => res,
@@ -245,6 +1006,7 @@ where
                     opts,
                     env,
                     &mut db,
+                    &budget,
                     Some(TransactionContext {
                         block_hash: Some(block_hash),
                         tx_index: Some(index),
@@ -261,138 +1023,27 @@ where
     ///
     /// Differences compare to `eth_call`:
     ///  - `debug_traceCall` executes with __enabled__ basefee check, `eth_call` does not: <https://github.com/paradigmxyz/reth/issues/6240>
+    ///
+    /// The state overrides are applied before the call is executed, so tracers that report the
+    /// pre-state of the call, like the `prestateTracer`, report the overridden state.
     pub async fn debug_trace_call(
         &self,
         call: TransactionRequest,
         block_id: Option<BlockId>,
-        opts: GethDebugTracingCallOptions,
-    ) -> EthResult<GethTrace> {
+        opts: TraceCallOptions,
+    ) -> EthResult<DebugTrace> {
         let at = block_id.unwrap_or_default();
+        let TraceCallOptions { call_options, tx_index } = opts;
         let GethDebugTracingCallOptions { tracing_options, state_overrides, block_overrides } =
-            opts;
+            call_options;
         let overrides = EvmOverrides::new(state_overrides, block_overrides.map(Box::new));
-        let GethDebugTracingOptions { config, tracer, tracer_config, .. } = tracing_options;
+        let budget = self.trace_budget(&tracing_options)?;
 
         let this = self.clone();
-        if let Some(tracer) = tracer {
-            return match tracer {
-                GethDebugTracerType::BuiltInTracer(tracer) => match tracer {
-                    GethDebugBuiltInTracerType::FourByteTracer => {
-                        let mut inspector = FourByteInspector::default();
-                        let inspector = self
-                            .inner
-                            .eth_api
-                            .spawn_with_call_at(call, at, overrides, move |db, env| {
-                                this.eth_api().inspect(db, env, &mut inspector)?;
-                                Ok(inspector)
-                            })
-                            .await?;
-                        return Ok(FourByteFrame::from(inspector).into())
-                    }
-                    GethDebugBuiltInTracerType::CallTracer => {
-                        let call_config = tracer_config
-                            .into_call_config()
-                            .map_err(|_| EthApiError::InvalidTracerConfig)?;
-
-                        let mut inspector = TracingInspector::new(
-                            TracingInspectorConfig::from_geth_call_config(&call_config),
-                        );
-
-                        let frame = self
-                            .inner
-                            .eth_api
-                            .spawn_with_call_at(call, at, overrides, move |db, env| {
-                                let (res, _) = this.eth_api().inspect(db, env, &mut inspector)?;
-                                let frame = inspector
-                                    .into_geth_builder()
-                                    .geth_call_traces(call_config, res.result.gas_used());
-                                Ok(frame.into())
-                            })
-                            .await?;
-                        return Ok(frame)
-                    }
-                    GethDebugBuiltInTracerType::PreStateTracer => {
-                        let prestate_config = tracer_config
-                            .into_pre_state_config()
-                            .map_err(|_| EthApiError::InvalidTracerConfig)?;
-                        let mut inspector = TracingInspector::new(
-                            TracingInspectorConfig::from_geth_prestate_config(&prestate_config),
-                        );
-
-                        let frame =
-                            self.inner
-                                .eth_api
-                                .spawn_with_call_at(call, at, overrides, move |db, env| {
-                                    let (res, _) =
-                                        this.eth_api().inspect(&mut *db, env, &mut inspector)?;
-                                    let frame = inspector
-                                        .into_geth_builder()
-                                        .geth_prestate_traces(&res, prestate_config, db)?;
-                                    Ok(frame)
-                                })
-                                .await?;
-                        return Ok(frame.into())
-                    }
-                    GethDebugBuiltInTracerType::NoopTracer => Ok(NoopFrame::default().into()),
-                    GethDebugBuiltInTracerType::MuxTracer => {
-                        let mux_config = tracer_config
-                            .into_mux_config()
-                            .map_err(|_| EthApiError::InvalidTracerConfig)?;
-
-                        let mut inspector = MuxInspector::try_from_config(mux_config)?;
-
-                        let frame = self
-                            .inner
-                            .eth_api
-                            .spawn_with_call_at(call, at, overrides, move |db, env| {
-                                let (res, _) =
-                                    this.eth_api().inspect(&mut *db, env, &mut inspector)?;
-                                let frame = inspector.try_into_mux_frame(&res, db)?;
-                                Ok(frame.into())
-                            })
-                            .await?;
-                        return Ok(frame)
-                    }
-                },
-                GethDebugTracerType::JsTracer(code) => {
-                    let config = tracer_config.into_json();
-
-                    let (_, _, at) = self.inner.eth_api.evm_env_at(at).await?;
-
-                    let res = self
-                        .inner
-                        .eth_api
-                        .spawn_with_call_at(call, at, overrides, move |db, env| {
-                            let mut inspector = JsInspector::new(code, config)?;
-                            let (res, _) =
-                                this.eth_api().inspect(&mut *db, env.clone(), &mut inspector)?;
-                            Ok(inspector.json_result(res, &env, db)?)
-                        })
-                        .await?;
-
-                    Ok(GethTrace::JS(res))
-                }
-            }
-        }
-
-        // default structlog tracer
-        let inspector_config = TracingInspectorConfig::from_geth_config(&config);
-
-        let mut inspector = TracingInspector::new(inspector_config);
-
-        let (res, inspector) = self
-            .inner
-            .eth_api
-            .spawn_with_call_at(call, at, overrides, move |db, env| {
-                let (res, _) = this.eth_api().inspect(db, env, &mut inspector)?;
-                Ok((res, inspector))
-            })
-            .await?;
-        let gas_used = res.result.gas_used();
-        let return_value = res.result.into_output().unwrap_or_default();
-        let frame = inspector.into_geth_builder().geth_traces(gas_used, return_value, config);
-
-        Ok(frame.into())
+        self.spawn_with_trace_call_at(call, at, tx_index, overrides, move |db, env| {
+            this.trace_transaction(tracing_options, env, db, &budget, None).map(|(trace, _)| trace)
+        })
+        .await
     }
 
     /// The `debug_traceCallMany` method lets you run an `eth_callMany` within the context of the
@@ -403,7 +1054,7 @@ where
         bundles: Vec<Bundle>,
         state_context: Option<StateContext>,
         opts: Option<GethDebugTracingCallOptions>,
-    ) -> EthResult<Vec<Vec<GethTrace>>> {
+    ) -> EthResult<Vec<Vec<DebugTrace>>> {
         if bundles.is_empty() {
             return Err(EthApiError::InvalidParams(String::from("bundles are empty.")))
         }
@@ -412,7 +1063,7 @@ where
         let transaction_index = transaction_index.unwrap_or_default();
 
         let target_block = block_number.unwrap_or_default();
-        let ((cfg, mut block_env, _), block) = futures::try_join!(
+        let ((mut cfg, mut block_env, _), block) = futures::try_join!(
             self.inner.eth_api.evm_env_at(target_block),
             self.inner.eth_api.block_by_id_with_senders(target_block),
         )?;
@@ -420,6 +1071,7 @@ where
         let opts = opts.unwrap_or_default();
         let block = block.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
         let GethDebugTracingCallOptions { tracing_options, mut state_overrides, .. } = opts;
+        let budget = self.trace_budget(&tracing_options)?;
         let gas_limit = self.inner.eth_api.call_gas_limit();
 
         // we're essentially replaying the transactions in the block here, hence we need the state
@@ -462,6 +1114,9 @@ where
                     }
                 }
 
+                // only the calls are limited by the configured memory limit
+                cfg.memory_limit = this.inner.eth_api.call_memory_limit();
+
                 // Trace all bundles
                 let mut bundles = bundles.into_iter().peekable();
                 while let Some(bundle) = bundles.next() {
@@ -485,8 +1140,13 @@ where
                             overrides,
                         )?;
 
-                        let (trace, state) =
-                            this.trace_transaction(tracing_options.clone(), env, &mut db, None)?;
+                        let (trace, state) = this.trace_transaction(
+                            tracing_options.clone(),
+                            env,
+                            &mut db,
+                            &budget,
+                            None,
+                        )?;
 
                         // If there is more transactions, commit the database
                         // If there is no transactions, but more bundles, commit to the database too
@@ -510,6 +1170,10 @@ where
     ///
     /// Returns the trace frame and the state that got updated after executing the transaction.
     ///
+    /// The execution is aborted once the budget of the request is exhausted, see
+    /// [`Self::trace_budget`]. Struct logs are recorded until they exceed the configured memory
+    /// budget, see [`DebugApiConfig::max_struct_logs_memory`].
+    ///
     /// Note: this does not apply any state overrides if they're configured in the `opts`.
     ///
     /// Caution: this is blocking and should be performed on a blocking task.
@@ -518,98 +1182,143 @@ where
         opts: GethDebugTracingOptions,
         env: EnvWithHandlerCfg,
         db: &mut CacheDB<StateProviderDatabase<StateProviderBox>>,
+        budget: &TraceBudget,
         transaction_context: Option<TransactionContext>,
-    ) -> EthResult<(GethTrace, revm_primitives::EvmState)> {
+    ) -> EthResult<(DebugTrace, revm_primitives::EvmState)> {
         let GethDebugTracingOptions { config, tracer, tracer_config, .. } = opts;
 
-        if let Some(tracer) = tracer {
-            return match tracer {
-                GethDebugTracerType::BuiltInTracer(tracer) => match tracer {
-                    GethDebugBuiltInTracerType::FourByteTracer => {
-                        let mut inspector = FourByteInspector::default();
-                        let (res, _) = self.eth_api().inspect(db, env, &mut inspector)?;
-                        return Ok((FourByteFrame::from(inspector).into(), res.state))
-                    }
-                    GethDebugBuiltInTracerType::CallTracer => {
-                        let call_config = tracer_config
-                            .into_call_config()
-                            .map_err(|_| EthApiError::InvalidTracerConfig)?;
-
-                        let mut inspector = TracingInspector::new(
-                            TracingInspectorConfig::from_geth_call_config(&call_config),
-                        );
-
-                        let (res, _) = self.eth_api().inspect(db, env, &mut inspector)?;
+        // names that are neither built-in tracers nor registered are JavaScript code
+        if let Some(GethDebugTracerType::JsTracer(name)) = &tracer {
+            if let Some(factory) = self.tracer_factory(name) {
+                let custom = factory.create(tracer_config.into_json())?;
+                let mut inspector =
+                    TraceGuard::new(CustomInspector(custom.inspector), budget.clone());
+                let (res, _) = self.eth_api().inspect(&mut *db, env, &mut inspector)?;
+                inspector.into_inner()?;
+
+                let result = (custom.into_result)(&res, db)?;
+                return Ok((DebugTrace::Custom(result), res.state))
+            }
+        }
 
-                        let frame = inspector
-                            .into_geth_builder()
-                            .geth_call_traces(call_config, res.result.gas_used());
+        if let Some(GethDebugTracerType::JsTracer(code)) = tracer {
+            let config = tracer_config.into_json();
+            let transaction_context = transaction_context.unwrap_or_default();
+            let this = self.clone();
+            let tracer_budget = budget.clone();
+            let (result, state) = self.inner.js_tracer_threads.run(db, budget, move |db| {
+                let mut inspector =
+                    JsTracer::new(code, config, transaction_context, tracer_budget)?;
+                let (res, env) = this.eth_api().inspect(&mut *db, env, &mut inspector)?;
+
+                let state = res.state.clone();
+                let result = inspector.into_result(res, &env, &*db)?;
+                Ok((result, state))
+            })?;
+            return Ok((GethTrace::JS(result).into(), state))
+        }
 
-                        return Ok((frame.into(), res.state))
-                    }
-                    GethDebugBuiltInTracerType::PreStateTracer => {
-                        let prestate_config = tracer_config
-                            .into_pre_state_config()
-                            .map_err(|_| EthApiError::InvalidTracerConfig)?;
-
-                        let mut inspector = TracingInspector::new(
-                            TracingInspectorConfig::from_geth_prestate_config(&prestate_config),
-                        );
-                        let (res, _) = self.eth_api().inspect(&mut *db, env, &mut inspector)?;
-
-                        let frame = inspector.into_geth_builder().geth_prestate_traces(
-                            &res,
-                            prestate_config,
-                            db,
-                        )?;
+        if let Some(GethDebugTracerType::BuiltInTracer(tracer)) = tracer {
+            return match tracer {
+                GethDebugBuiltInTracerType::FourByteTracer => {
+                    let mut inspector =
+                        TraceGuard::new(FourByteInspector::default(), budget.clone());
+                    let (res, _) = self.eth_api().inspect(db, env, &mut inspector)?;
+                    let inspector = inspector.into_inner()?;
+                    Ok((GethTrace::from(FourByteFrame::from(inspector)).into(), res.state))
+                }
+                GethDebugBuiltInTracerType::CallTracer => {
+                    let call_config = tracer_config
+                        .into_call_config()
+                        .map_err(|_| EthApiError::InvalidTracerConfig)?;
+
+                    let mut inspector = TraceGuard::new(
+                        TracingInspector::new(TracingInspectorConfig::from_geth_call_config(
+                            &call_config,
+                        )),
+                        budget.clone(),
+                    );
+
+                    let (res, _) = self.eth_api().inspect(db, env, &mut inspector)?;
+
+                    let frame = inspector
+                        .into_inner()?
+                        .into_geth_builder()
+                        .geth_call_traces(call_config, res.result.gas_used());
+
+                    Ok((GethTrace::from(frame).into(), res.state))
+                }
+                GethDebugBuiltInTracerType::PreStateTracer => {
+                    let prestate_config = tracer_config
+                        .into_pre_state_config()
+                        .map_err(|_| EthApiError::InvalidTracerConfig)?;
+
+                    let mut inspector = TraceGuard::new(
+                        TracingInspector::new(TracingInspectorConfig::from_geth_prestate_config(
+                            &prestate_config,
+                        )),
+                        budget.clone(),
+                    );
+                    let (res, _) = self.eth_api().inspect(&mut *db, env, &mut inspector)?;
+
+                    let frame = inspector.into_inner()?.into_geth_builder().geth_prestate_traces(
+                        &res,
+                        prestate_config,
+                        db,
+                    )?;
 
-                        return Ok((frame.into(), res.state))
-                    }
-                    GethDebugBuiltInTracerType::NoopTracer => {
-                        Ok((NoopFrame::default().into(), Default::default()))
-                    }
-                    GethDebugBuiltInTracerType::MuxTracer => {
-                        let mux_config = tracer_config
-                            .into_mux_config()
-                            .map_err(|_| EthApiError::InvalidTracerConfig)?;
+                    Ok((GethTrace::from(frame).into(), res.state))
+                }
+                GethDebugBuiltInTracerType::NoopTracer => {
+                    Ok((GethTrace::from(NoopFrame::default()).into(), Default::default()))
+                }
+                GethDebugBuiltInTracerType::MuxTracer => {
+                    let mux_config = tracer_config
+                        .into_mux_config()
+                        .map_err(|_| EthApiError::InvalidTracerConfig)?;
 
-                        let mut inspector = MuxInspector::try_from_config(mux_config)?;
+                    let mut inspector =
+                        TraceGuard::new(MuxInspector::try_from_config(mux_config)?, budget.clone());
 
-                        let (res, _) = self.eth_api().inspect(&mut *db, env, &mut inspector)?;
-                        let frame = inspector.try_into_mux_frame(&res, db)?;
-                        return Ok((frame.into(), res.state))
-                    }
-                },
-                GethDebugTracerType::JsTracer(code) => {
-                    let config = tracer_config.into_json();
-                    let mut inspector = JsInspector::with_transaction_context(
-                        code,
-                        config,
-                        transaction_context.unwrap_or_default(),
-                    )?;
-                    let (res, env) = self.eth_api().inspect(&mut *db, env, &mut inspector)?;
-
-                    let state = res.state.clone();
-                    let result = inspector.json_result(res, &env, db)?;
-                    Ok((GethTrace::JS(result), state))
+                    let (res, _) = self.eth_api().inspect(&mut *db, env, &mut inspector)?;
+                    let frame = inspector.into_inner()?.try_into_mux_frame(&res, db)?;
+                    Ok((GethTrace::from(frame).into(), res.state))
                 }
             }
         }
 
         // default structlog tracer
         let inspector_config = TracingInspectorConfig::from_geth_config(&config);
+        let step_budget = StepBudget {
+            max_memory: self.inner.config.max_struct_logs_memory,
+            record_memory: inspector_config.record_memory_snapshots,
+        };
 
-        let mut inspector = TracingInspector::new(inspector_config);
+        let mut inspector = TraceGuard::with_step_budget(
+            TracingInspector::new(inspector_config),
+            budget.clone(),
+            step_budget,
+        );
 
         let (res, _) = self.eth_api().inspect(db, env, &mut inspector)?;
+        let truncated = inspector.is_truncated();
         let gas_used = res.result.gas_used();
         let return_value = res.result.into_output().unwrap_or_default();
-        let frame = inspector.into_geth_builder().geth_traces(gas_used, return_value, config);
+        let frame =
+            inspector.into_inner()?.into_geth_builder().geth_traces(gas_used, return_value, config);
 
-        Ok((frame.into(), res.state))
+        Ok((LimitedDefaultFrame { frame, truncated }.into(), res.state))
     }
 }
 
+/// Parses the requested `timeout` tracing option and caps it by the given maximum.
+fn capped_tracer_timeout(timeout: Option<&str>, max_timeout: Duration) -> EthResult<Duration> {
+    let Some(timeout) = timeout else { return Ok(max_timeout) };
+    let timeout = humantime::parse_duration(timeout)
+        .map_err(|err| EthApiError::InvalidParams(format!("invalid tracer timeout: {err}")))?;
+    Ok(timeout.min(max_timeout))
+}
+
 #[async_trait]
 impl<Provider, Eth> DebugApiServer for DebugApi<Provider, Eth>
 where
@@ -618,6 +1327,12 @@ where
 {
     /// Handler for `debug_getRawHeader`
     async fn raw_header(&self, block_id: BlockId) -> RpcResult<Bytes> {
+        if let Some(header) =
+            self.read_raw_block(block_id, |reader, number| reader.raw_header(number))?
+        {
+            return Ok(header)
+        }
+
         let header = match block_id {
             BlockId::Hash(hash) => self.inner.provider.header(&hash.into()).to_rpc_result()?,
             BlockId::Number(number_or_tag) => {
@@ -641,14 +1356,16 @@ where
 
     /// Handler for `debug_getRawBlock`
     async fn raw_block(&self, block_id: BlockId) -> RpcResult<Bytes> {
+        if let Some(block) =
+            self.read_raw_block(block_id, |reader, number| reader.raw_block(number))?
+        {
+            return Ok(block)
+        }
+
         let block = self.inner.provider.block_by_id(block_id).to_rpc_result()?;
 
         let mut res = Vec::new();
-        if let Some(mut block) = block {
-            // In RPC withdrawals are always present
-            if block.withdrawals.is_none() {
-                block.withdrawals = Some(Withdrawals::default());
-            }
+        if let Some(block) = block {
             block.encode(&mut res);
         }
 
@@ -661,12 +1378,24 @@ where
     ///
     /// Returns the bytes of the transaction for the given hash.
     async fn raw_transaction(&self, hash: B256) -> RpcResult<Option<Bytes>> {
+        let reader = self.inner.raw_block_reader.read().clone();
+        if let Some(reader) = reader {
+            if let Some(tx) = reader.raw_transaction_by_hash(hash).to_rpc_result()? {
+                return Ok(Some(tx))
+            }
+        }
         Ok(self.inner.eth_api.raw_transaction_by_hash(hash).await?)
     }
 
     /// Handler for `debug_getRawTransactions`
     /// Returns the bytes of the transaction for the given hash.
     async fn raw_transactions(&self, block_id: BlockId) -> RpcResult<Vec<Bytes>> {
+        if let Some(transactions) =
+            self.read_raw_block(block_id, |reader, number| reader.raw_transactions(number))?
+        {
+            return Ok(transactions)
+        }
+
         let block = self
             .inner
             .provider
@@ -678,6 +1407,12 @@ where
 
     /// Handler for `debug_getRawReceipts`
     async fn raw_receipts(&self, block_id: BlockId) -> RpcResult<Vec<Bytes>> {
+        if let Some(receipts) =
+            self.read_raw_block(block_id, |reader, number| reader.raw_receipts(number))?
+        {
+            return Ok(receipts)
+        }
+
         Ok(self
             .inner
             .provider
@@ -697,10 +1432,25 @@ where
     /// Handler for `debug_traceChain`
     async fn debug_trace_chain(
         &self,
-        _start_exclusive: BlockNumberOrTag,
-        _end_inclusive: BlockNumberOrTag,
-    ) -> RpcResult<Vec<BlockTraceResult>> {
-        Err(internal_rpc_err("unimplemented"))
+        pending: PendingSubscriptionSink,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let range = match self.trace_chain_range(start_exclusive, end_inclusive) {
+            Ok(range) => range,
+            Err(err) => {
+                pending.reject(ErrorObject::from(err)).await;
+                return Ok(())
+            }
+        };
+
+        let sink = pending.accept().await?;
+        let this = self.clone();
+        self.inner.task_spawner.spawn(Box::pin(async move {
+            this.pipe_trace_chain(sink, range, opts.unwrap_or_default()).await;
+        }));
+        Ok(())
     }
 
     /// Handler for `debug_traceBlock`
@@ -708,7 +1458,7 @@ where
         &self,
         rlp_block: Bytes,
         opts: Option<GethDebugTracingOptions>,
-    ) -> RpcResult<Vec<TraceResult>> {
+    ) -> RpcResult<Vec<DebugTraceResult>> {
         let _permit = self.acquire_trace_permit().await;
         Ok(Self::debug_trace_raw_block(self, rlp_block, opts.unwrap_or_default()).await?)
     }
@@ -718,7 +1468,7 @@ where
         &self,
         block: B256,
         opts: Option<GethDebugTracingOptions>,
-    ) -> RpcResult<Vec<TraceResult>> {
+    ) -> RpcResult<Vec<DebugTraceResult>> {
         let _permit = self.acquire_trace_permit().await;
         Ok(Self::debug_trace_block(self, block.into(), opts.unwrap_or_default()).await?)
     }
@@ -728,7 +1478,7 @@ where
         &self,
         block: BlockNumberOrTag,
         opts: Option<GethDebugTracingOptions>,
-    ) -> RpcResult<Vec<TraceResult>> {
+    ) -> RpcResult<Vec<DebugTraceResult>> {
         let _permit = self.acquire_trace_permit().await;
         Ok(Self::debug_trace_block(self, block.into(), opts.unwrap_or_default()).await?)
     }
@@ -738,7 +1488,7 @@ where
         &self,
         tx_hash: B256,
         opts: Option<GethDebugTracingOptions>,
-    ) -> RpcResult<GethTrace> {
+    ) -> RpcResult<DebugTrace> {
         let _permit = self.acquire_trace_permit().await;
         Ok(Self::debug_trace_transaction(self, tx_hash, opts.unwrap_or_default()).await?)
     }
@@ -748,8 +1498,8 @@ where
         &self,
         request: TransactionRequest,
         block_number: Option<BlockId>,
-        opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<GethTrace> {
+        opts: Option<TraceCallOptions>,
+    ) -> RpcResult<DebugTrace> {
         let _permit = self.acquire_trace_permit().await;
         Ok(Self::debug_trace_call(self, request, block_number, opts.unwrap_or_default()).await?)
     }
@@ -759,11 +1509,23 @@ where
         bundles: Vec<Bundle>,
         state_context: Option<StateContext>,
         opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<Vec<Vec<GethTrace>>> {
+    ) -> RpcResult<Vec<Vec<DebugTrace>>> {
         let _permit = self.acquire_trace_permit().await;
         Ok(Self::debug_trace_call_many(self, bundles, state_context, opts).await?)
     }
 
+    /// Handler for `debug_executionWitness`
+    async fn debug_execution_witness(&self, block: BlockId) -> RpcResult<ExecutionWitness> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(Self::debug_execution_witness(self, block).await?)
+    }
+
+    /// Handler for `debug_replayTransaction`
+    async fn debug_replay_transaction(&self, tx_hash: B256) -> RpcResult<ReplayedTransaction> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(Self::debug_replay_transaction(self, tx_hash).await?)
+    }
+
     async fn debug_backtrace_at(&self, _location: &str) -> RpcResult<()> {
         Ok(())
     }
@@ -936,15 +1698,25 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_storageRangeAt`
     async fn debug_storage_range_at(
         &self,
-        _block_hash: B256,
-        _tx_idx: usize,
-        _contract_address: Address,
-        _key_start: B256,
-        _max_result: u64,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        tx_idx: usize,
+        contract_address: Address,
+        key_start: B256,
+        max_result: u64,
+    ) -> RpcResult<StorageRangeResult> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(Self::debug_storage_range_at(
+            self,
+            block_hash,
+            tx_idx,
+            contract_address,
+            key_start,
+            max_result,
+        )
+        .await?)
     }
 
     async fn debug_trace_bad_block(
@@ -995,4 +1767,1324 @@ struct DebugApiInner<Provider, Eth> {
     eth_api: Eth,
     // restrict the number of concurrent calls to blocking calls
     blocking_task_guard: BlockingTaskGuard,
+    /// Settings of the `debug` namespace.
+    config: DebugApiConfig,
+    /// Spawns the tasks of the `debug_traceChain` subscriptions.
+    task_spawner: Box<dyn TaskSpawner>,
+    /// Metrics of the guardrails of traced calls.
+    metrics: TracingMetrics,
+    /// Executes the JavaScript tracers.
+    js_tracer_threads: JsTracerThreads,
+    /// Generates the witnesses for `debug_executionWitness`, unsupported if not set.
+    execution_witness_provider: parking_lot::RwLock<Option<Arc<dyn ExecutionWitnessProvider>>>,
+    /// Replays the transactions for `debug_replayTransaction`, unsupported if not set.
+    transaction_replay_provider: parking_lot::RwLock<Option<Arc<dyn TransactionReplayProvider>>>,
+    /// Reads the encoded canonical blocks for the raw getters, which encode the typed blocks if
+    /// not set.
+    raw_block_reader: parking_lot::RwLock<Option<Arc<dyn RawBlockReader>>>,
+    /// The custom tracers registered by the node.
+    tracers: TracerRegistry,
+}
+
+/// The name of the tracer config option that traces the system calls of a block.
+const INCLUDE_SYSTEM_CALLS: &str = "includeSystemCalls";
+
+/// The system calls that are executed around the transactions of a block.
+#[derive(Debug, Clone, Copy, Default)]
+struct BlockSystemCalls {
+    /// The root the [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) beacon root contract is
+    /// called with before the transactions.
+    parent_beacon_block_root: Option<B256>,
+    /// The number and the parent hash of the block, if the
+    /// [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935) block hashes update precedes the
+    /// transactions.
+    blockhashes_update: Option<(u64, B256)>,
+    /// Whether the [EIP-7002](https://eips.ethereum.org/EIPS/eip-7002) withdrawal requests
+    /// contract is called after the transactions.
+    withdrawal_requests_contract_call: bool,
+    /// Whether the calls are traced.
+    traced: bool,
+}
+
+impl BlockSystemCalls {
+    /// Returns the system calls of the block that are activated by the chain spec.
+    fn new(chain_spec: &ChainSpec, header: &Header) -> Self {
+        let cancun = chain_spec.is_cancun_active_at_timestamp(header.timestamp);
+        let prague = chain_spec.is_prague_active_at_timestamp(header.timestamp);
+        // the genesis block has no pre-block calls
+        let pre_block = header.number > 0;
+        Self {
+            parent_beacon_block_root: header
+                .parent_beacon_block_root
+                .filter(|_| cancun && pre_block),
+            blockhashes_update: (prague && pre_block)
+                .then_some((header.number, header.parent_hash)),
+            withdrawal_requests_contract_call: prague,
+            traced: false,
+        }
+    }
+
+    /// Returns true if the calls are traced and the block has calls that can be traced.
+    const fn has_traced_calls(&self) -> bool {
+        self.traced &&
+            (self.parent_beacon_block_root.is_some() || self.withdrawal_requests_contract_call)
+    }
+
+    /// Returns true if the calls that follow the transactions are traced.
+    const fn traces_post_block_calls(&self) -> bool {
+        self.traced && self.withdrawal_requests_contract_call
+    }
+}
+
+/// Returns whether the system calls of a block are traced with the given options, and removes the
+/// `includeSystemCalls` option from them.
+///
+/// The `prestateTracer` always traces them, since their state changes are part of the state
+/// transition of the block.
+fn traces_system_calls(opts: &mut GethDebugTracingOptions) -> bool {
+    take_include_system_calls(&mut opts.tracer_config) ||
+        matches!(
+            opts.tracer,
+            Some(GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::PreStateTracer))
+        )
+}
+
+/// Increments the balances of the accounts by the given amounts, which are the post-block balance
+/// changes of a block, like block rewards and withdrawals.
+fn increment_balances(
+    db: &mut CacheDB<StateProviderDatabase<StateProviderBox>>,
+    increments: HashMap<Address, u128>,
+) -> EthResult<()> {
+    let mut changes = HashMap::with_capacity(increments.len());
+    for (address, increment) in increments {
+        if increment == 0 {
+            continue
+        }
+        let mut info = db.basic(address)?.unwrap_or_default();
+        info.balance = info.balance.saturating_add(U256::from(increment));
+        let mut account = Account::from(info);
+        account.mark_touch();
+        changes.insert(address, account);
+    }
+    db.commit(changes);
+    Ok(())
+}
+
+/// Removes the `includeSystemCalls` option from the tracer config, since it's not an option of
+/// the tracer itself, and returns whether it's set.
+fn take_include_system_calls(config: &mut GethDebugTracerConfig) -> bool {
+    let Some(fields) = config.0.as_object_mut() else { return false };
+    let Some(include) = fields.remove(INCLUDE_SYSTEM_CALLS) else { return false };
+    if fields.is_empty() {
+        // the tracer is configured as if the request had no config
+        config.0 = serde_json::Value::Null;
+    }
+    include.as_bool().unwrap_or_default()
+}
+
+/// Returns the env of a system call of the block, the transaction is filled in by the call.
+fn system_call_env(cfg: &CfgEnvWithHandlerCfg, block_env: &BlockEnv) -> EnvWithHandlerCfg {
+    EnvWithHandlerCfg {
+        env: Env::boxed(cfg.cfg_env.clone(), block_env.clone(), Default::default()),
+        handler_cfg: cfg.handler_cfg,
+    }
+}
+
+/// Wraps the trace of a system call in a `SYSTEM` frame, which sets it apart from the traces of
+/// the transactions of the block.
+fn system_call_trace(trace: DebugTrace, from: Address, to: Option<Address>) -> DebugTrace {
+    DebugTrace::System(SystemCallFrame {
+        typ: SystemCallFrameType::System,
+        from,
+        to,
+        result: Box::new(trace),
+    })
+}
+
+/// Converts the result and the state changes of a replayed transaction into the RPC response.
+fn replayed_transaction(result: ExecutionResult, bundle: BundleState) -> ReplayedTransaction {
+    let error = match &result {
+        ExecutionResult::Success { .. } => None,
+        ExecutionResult::Revert { .. } => Some("execution reverted".to_string()),
+        ExecutionResult::Halt { reason, .. } => Some(format!("{reason:?}")),
+    };
+    let account = |info: AccountInfo| ReplayedAccount {
+        balance: info.balance,
+        nonce: U64::from(info.nonce),
+        code_hash: info.code_hash,
+    };
+
+    let state_diff = bundle
+        .state
+        .into_iter()
+        .filter_map(|(address, account_diff)| {
+            let storage = account_diff
+                .storage
+                .into_iter()
+                .filter(|(_, slot)| slot.is_changed())
+                .map(|(key, slot)| {
+                    let diff = ReplayedStorageDiff {
+                        pre: slot.previous_or_original_value,
+                        post: slot.present_value,
+                    };
+                    (B256::from(key), diff)
+                })
+                .collect::<BTreeMap<_, _>>();
+            if account_diff.info == account_diff.original_info && storage.is_empty() {
+                return None
+            }
+
+            let diff = ReplayedAccountDiff {
+                pre: account_diff.original_info.map(account),
+                post: account_diff.info.map(account),
+                storage,
+            };
+            Some((address, diff))
+        })
+        .collect();
+
+    ReplayedTransaction {
+        success: result.is_success(),
+        gas_used: U64::from(result.gas_used()),
+        output: result.output().cloned().unwrap_or_default(),
+        error,
+        logs: result.into_logs(),
+        state_diff,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eth::test_utils::{self, build_test_eth_api},
+        CustomTracer, CustomTracerError,
+    };
+    use alloy_eips::eip4788::{BEACON_ROOTS_ADDRESS, BEACON_ROOTS_CODE, SYSTEM_ADDRESS};
+    use jsonrpsee::rpc_params;
+    use reth_blockchain_tree::noop::NoopBlockchainTree;
+    use reth_db::tables;
+    use reth_db_api::transaction::DbTxMut;
+    use reth_errors::TransactionReplayError;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{
+        address,
+        constants::ETHEREUM_BLOCK_GAS_LIMIT,
+        hex, keccak256, public_key_to_address,
+        stage::{StageCheckpoint, StageId},
+        ChainSpecBuilder, ForkCondition, Header, Signature, StaticFileSegment, StorageEntry,
+        Transaction, TransactionSigned, TxKind, TxLegacy,
+    };
+    use reth_provider::{
+        providers::BlockchainProvider,
+        test_utils::{create_test_provider_factory, ExtendedAccount, MockEthProvider},
+        StageCheckpointWriter, StaticFileWriter,
+    };
+    use reth_revm::replay::ReplayExecutor;
+    use reth_rpc_types::{
+        state::AccountOverride,
+        trace::geth::{CallConfig, GethDebugTracerConfig, PreStateFrame},
+        AccessList, AccessListItem, BlockOverrides,
+    };
+    use reth_testing_utils::generators::{self, random_block_range, sign_tx_with_key_pair};
+    use revm::{
+        inspectors::NoOpInspector, interpreter::Interpreter, primitives::SpecId, EvmContext,
+        Inspector,
+    };
+    use secp256k1::{Keypair, Secp256k1};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const ALICE: Address = address!("a11ce00000000000000000000000000000000000");
+    const BOB: Address = address!("b0b0000000000000000000000000000000000000");
+    const OUTER: Address = address!("a000000000000000000000000000000000000000");
+    const INNER: Address = address!("b000000000000000000000000000000000000000");
+    const BLOCKHASH: Address = address!("c000000000000000000000000000000000000000");
+
+    /// Increments slot 0.
+    const INNER_CODE: &[u8] = &hex!("60005460010160005500");
+
+    /// Returns the hash of block 1.
+    const BLOCKHASH_CODE: &[u8] = &hex!("60014060005260206000f3");
+
+    /// Calls [`INNER`], then increments slot 0.
+    const OUTER_CODE: &[u8] = &hex!(
+        "6000600060006000600073b0000000000000000000000000000000000000005af15060005460010160005500"
+    );
+
+    type TestEthApi = test_utils::TestEthApi<MockEthProvider>;
+
+    /// Counts the executed instructions.
+    struct StepCounter(Arc<AtomicUsize>);
+
+    impl<DB: Database> Inspector<DB> for StepCounter {
+        fn step(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn call(from: Address, nonce: u64, to: Address) -> TransactionSignedEcRecovered {
+        let transaction = Transaction::Legacy(TxLegacy {
+            chain_id: Some(1),
+            nonce,
+            gas_limit: 100_000,
+            to: TxKind::Call(to),
+            ..Default::default()
+        });
+        TransactionSigned::from_transaction_and_signature(transaction, Signature::default())
+            .with_signer(from)
+    }
+
+    /// Returns a provider with the test contracts and accounts, and the hash of the block to
+    /// trace on top of.
+    fn test_provider() -> (MockEthProvider, B256) {
+        let provider = MockEthProvider::default();
+        let parent = Header::default().seal_slow();
+        let parent_hash = parent.hash();
+        provider.add_block(parent_hash, Block { header: parent.unseal(), ..Default::default() });
+        provider.add_account(
+            OUTER,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(Bytes::from_static(OUTER_CODE)),
+        );
+        provider.add_account(
+            INNER,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(Bytes::from_static(INNER_CODE)),
+        );
+        provider.add_account(ALICE, ExtendedAccount::new(0, U256::from(1_000_000_000u64)));
+        provider.add_account(BOB, ExtendedAccount::new(0, U256::from(1_000_000_000u64)));
+        (provider, parent_hash)
+    }
+
+    fn prestate_tracer(diff_mode: bool) -> GethDebugTracingOptions {
+        GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::BuiltInTracer(
+                GethDebugBuiltInTracerType::PreStateTracer,
+            )),
+            tracer_config: GethDebugTracerConfig(serde_json::json!({ "diffMode": diff_mode })),
+            ..Default::default()
+        }
+    }
+
+    fn js_tracer(code: &str) -> GethDebugTracingOptions {
+        GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::JsTracer(code.to_string())),
+            ..Default::default()
+        }
+    }
+
+    /// Traces a block with a call of [`OUTER`] and a call of [`INNER`].
+    async fn trace_test_block(
+        config: DebugApiConfig,
+        opts: GethDebugTracingOptions,
+    ) -> EthResult<Vec<DebugTraceResult>> {
+        let (provider, parent_hash) = test_provider();
+        let eth_api = build_test_eth_api(provider.clone());
+        let debug_api = DebugApi::new(provider, eth_api, BlockingTaskGuard::new(1), config);
+        debug_api
+            .trace_block_with(
+                parent_hash.into(),
+                vec![call(ALICE, 0, OUTER), call(BOB, 0, INNER)],
+                BlockSystemCalls::default(),
+                CfgEnvWithHandlerCfg::new_with_spec_id(Default::default(), SpecId::CANCUN),
+                BlockEnv::default(),
+                opts,
+            )
+            .await
+    }
+
+    fn js_results(traces: Vec<DebugTraceResult>) -> Vec<serde_json::Value> {
+        traces
+            .into_iter()
+            .map(|trace| match trace {
+                DebugTraceResult::Success {
+                    result: DebugTrace::Geth(GethTrace::JS(result)),
+                    ..
+                } => result,
+                trace => panic!("unexpected trace {trace:?}"),
+            })
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn parallel_block_trace_matches_sequential() {
+        let (provider, parent_hash) = test_provider();
+        let eth_api = build_test_eth_api(provider.clone());
+
+        // every transaction reads the storage written by the previous ones
+        let transactions = vec![
+            call(ALICE, 0, OUTER),
+            call(ALICE, 1, OUTER),
+            call(BOB, 0, INNER),
+            call(ALICE, 2, OUTER),
+            call(BOB, 1, OUTER),
+        ];
+        let cfg = CfgEnvWithHandlerCfg::new_with_spec_id(Default::default(), SpecId::CANCUN);
+        let tracers = [
+            GethDebugTracingOptions::default(),
+            GethDebugTracingOptions::default().with_call_config(CallConfig::default()),
+            GethDebugTracingOptions {
+                tracer: Some(GethDebugTracerType::BuiltInTracer(
+                    GethDebugBuiltInTracerType::PreStateTracer,
+                )),
+                ..Default::default()
+            },
+        ];
+
+        for opts in tracers {
+            let trace = |max_trace_block_checkpoints| {
+                let debug_api = DebugApi::new(
+                    provider.clone(),
+                    eth_api.clone(),
+                    BlockingTaskGuard::new(1),
+                    DebugApiConfig { max_trace_block_checkpoints, ..Default::default() },
+                );
+                let (transactions, cfg, opts) = (transactions.clone(), cfg.clone(), opts.clone());
+                async move {
+                    let traces = debug_api
+                        .trace_block_with(
+                            parent_hash.into(),
+                            transactions,
+                            BlockSystemCalls::default(),
+                            cfg,
+                            BlockEnv::default(),
+                            opts,
+                        )
+                        .await
+                        .unwrap();
+                    serde_json::to_string(&traces).unwrap()
+                }
+            };
+
+            let sequential = trace(1).await;
+            // batches that split the block and a single batch for the whole block
+            for max_trace_block_checkpoints in [2, 64] {
+                assert_eq!(trace(max_trace_block_checkpoints).await, sequential);
+            }
+        }
+    }
+
+    /// geth's `opcount` tracer.
+    const OPCOUNT_TRACER: &str = "{count: 0, step: function() { this.count++ }, \
+        fault: function() {}, result: function() { return this.count }}";
+
+    /// geth's `unigram` tracer.
+    #[cfg(feature = "js-tracer")]
+    const UNIGRAM_TRACER: &str = "{hist: {}, nops: 0, step: function(log, db) { \
+        var op = log.op.toString(); if (this.hist[op]) { this.hist[op]++ } \
+        else { this.hist[op] = 1 } this.nops++ }, \
+        fault: function() {}, result: function() { return this.hist }}";
+
+    #[cfg(feature = "js-tracer")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn js_tracers_match_geth() {
+        let config = DebugApiConfig { js_tracer: true, ..Default::default() };
+
+        // the expected results are derived from the struct logs of the same block
+        let struct_logs = trace_test_block(config, GethDebugTracingOptions::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|trace| match trace {
+                DebugTraceResult::Success {
+                    result: DebugTrace::StructLogs(LimitedDefaultFrame { frame, .. }),
+                    ..
+                } => frame.struct_logs,
+                trace => panic!("unexpected trace {trace:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert!(struct_logs.iter().all(|logs| !logs.is_empty()));
+
+        let traces = trace_test_block(config, js_tracer(OPCOUNT_TRACER)).await.unwrap();
+        assert_eq!(
+            js_results(traces),
+            struct_logs.iter().map(|logs| serde_json::json!(logs.len())).collect::<Vec<_>>()
+        );
+
+        let traces = trace_test_block(config, js_tracer(UNIGRAM_TRACER)).await.unwrap();
+        let unigrams = struct_logs
+            .iter()
+            .map(|logs| {
+                let mut hist = serde_json::Map::new();
+                for log in logs {
+                    let count = hist.entry(log.op.clone()).or_insert(serde_json::json!(0));
+                    *count = serde_json::json!(count.as_u64().unwrap() + 1);
+                }
+                serde_json::Value::Object(hist)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(js_results(traces), unigrams);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn replay_transaction_on_top_of_preceding_transactions() {
+        let (provider, parent_hash) = test_provider();
+        let key_pair = Keypair::new(&Secp256k1::new(), &mut generators::rng());
+        let sender = public_key_to_address(key_pair.public_key());
+        provider.add_account(sender, ExtendedAccount::new(0, U256::from(1_000_000_000u64)));
+
+        // every transaction increments the counter of `INNER`
+        let transactions = (0..3)
+            .map(|nonce| {
+                sign_tx_with_key_pair(
+                    key_pair,
+                    Transaction::Legacy(TxLegacy {
+                        chain_id: Some(1),
+                        nonce,
+                        gas_limit: 100_000,
+                        to: TxKind::Call(INNER),
+                        ..Default::default()
+                    }),
+                )
+            })
+            .collect::<Vec<_>>();
+        let header = Header { number: 1, parent_hash, ..Default::default() };
+        let block_hash = header.hash_slow();
+        provider.add_block(
+            block_hash,
+            Block { header, body: transactions.clone(), ..Default::default() },
+        );
+
+        let eth_api = build_test_eth_api(provider.clone());
+        let debug_api = DebugApi::new(
+            provider.clone(),
+            eth_api,
+            BlockingTaskGuard::new(1),
+            DebugApiConfig::default(),
+        );
+        let err = debug_api.debug_replay_transaction(transactions[2].hash()).await.unwrap_err();
+        assert!(matches!(err, EthApiError::Unsupported(_)), "{err:?}");
+
+        let replay_executor =
+            ReplayExecutor::new(provider.clone(), EthEvmConfig::default(), provider.chain_spec());
+        debug_api.set_transaction_replay_provider(Arc::new(replay_executor.clone()));
+
+        // the preceding transactions are part of the pre-state
+        let replayed = debug_api.debug_replay_transaction(transactions[2].hash()).await.unwrap();
+        assert!(replayed.success);
+        assert_eq!(
+            replayed.state_diff[&INNER].storage,
+            BTreeMap::from([(
+                B256::ZERO,
+                ReplayedStorageDiff { pre: U256::from(2), post: U256::from(3) }
+            )])
+        );
+        let sender_diff = &replayed.state_diff[&sender];
+        assert_eq!(sender_diff.pre.as_ref().unwrap().nonce, U64::from(2));
+        assert_eq!(sender_diff.post.as_ref().unwrap().nonce, U64::from(3));
+
+        let err = debug_api.debug_replay_transaction(B256::random()).await.unwrap_err();
+        assert!(matches!(err, EthApiError::TransactionNotFound), "{err:?}");
+
+        // the transaction has to be included in the given block
+        let tx_hash = B256::random();
+        let err = replay_executor
+            .replay_transaction::<NoOpInspector>(block_hash, tx_hash, None)
+            .unwrap_err();
+        assert!(
+            matches!(err, TransactionReplayError::TransactionNotInBlock { block_hash: hash, tx_hash: tx } if hash == block_hash && tx == tx_hash),
+            "{err:?}"
+        );
+
+        // the inspector only observes the replayed transaction
+        let steps = Arc::new(AtomicUsize::new(0));
+        replay_executor
+            .replay_transaction(
+                block_hash,
+                transactions[2].hash(),
+                Some(StepCounter(steps.clone())),
+            )
+            .unwrap();
+        assert_eq!(steps.load(Ordering::Relaxed), 7);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn js_tracer_disabled() {
+        let err = trace_test_block(DebugApiConfig::default(), js_tracer(OPCOUNT_TRACER))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthApiError::Unsupported(_)), "{err:?}");
+    }
+
+    #[cfg(feature = "js-tracer")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn js_tracer_timeout_aborts_execution() {
+        let config = DebugApiConfig { js_tracer: true, ..Default::default() };
+        let opts = GethDebugTracingOptions {
+            timeout: Some("0s".to_string()),
+            ..js_tracer(OPCOUNT_TRACER)
+        };
+        let err = trace_test_block(config, opts).await.unwrap_err();
+        assert!(matches!(err, EthApiError::ExecutionTimedOut(_)), "{err:?}");
+
+        let opts = GethDebugTracingOptions {
+            timeout: Some("soon".to_string()),
+            ..js_tracer(OPCOUNT_TRACER)
+        };
+        let err = trace_test_block(config, opts).await.unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)), "{err:?}");
+    }
+
+    /// Counts the executed instructions like [`OPCOUNT_TRACER`].
+    struct OpcountTracer;
+
+    impl TracerFactory for OpcountTracer {
+        fn create(&self, config: serde_json::Value) -> Result<CustomTracer, CustomTracerError> {
+            if !config.is_null() {
+                return Err(CustomTracerError::InvalidConfig(config.to_string()))
+            }
+
+            let count = Arc::new(AtomicUsize::new(0));
+            Ok(CustomTracer {
+                inspector: Box::new(StepCounter(count.clone())),
+                into_result: Box::new(move |_, _| Ok(count.load(Ordering::Relaxed).into())),
+            })
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn custom_tracer_by_name() {
+        let (provider, parent_hash) = test_provider();
+        let eth_api = build_test_eth_api(provider.clone());
+        let debug_api =
+            DebugApi::new(provider, eth_api, BlockingTaskGuard::new(1), DebugApiConfig::default());
+        debug_api.register_tracer("opcountTracer", Box::new(OpcountTracer));
+        // the names of built-in tracers can't be replaced
+        debug_api.register_tracer("callTracer", Box::new(OpcountTracer));
+
+        let trace = |opts| {
+            debug_api.trace_block_with(
+                parent_hash.into(),
+                vec![call(ALICE, 0, OUTER), call(BOB, 0, INNER)],
+                BlockSystemCalls::default(),
+                CfgEnvWithHandlerCfg::new_with_spec_id(Default::default(), SpecId::CANCUN),
+                BlockEnv::default(),
+                opts,
+            )
+        };
+
+        let traces = trace(js_tracer("opcountTracer")).await.unwrap();
+        let results = traces
+            .into_iter()
+            .map(|trace| match trace {
+                DebugTraceResult::Success { result: DebugTrace::Custom(result), .. } => result,
+                trace => panic!("unexpected trace {trace:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![serde_json::json!(23), serde_json::json!(7)]);
+
+        let traces =
+            trace(GethDebugTracingOptions::default().with_call_config(CallConfig::default()))
+                .await
+                .unwrap();
+        assert!(traces.iter().all(|trace| matches!(
+            trace,
+            DebugTraceResult::Success { result: DebugTrace::Geth(GethTrace::CallTracer(_)), .. }
+        )));
+
+        let opts = GethDebugTracingOptions {
+            tracer_config: GethDebugTracerConfig(serde_json::json!({ "unknown": true })),
+            ..js_tracer("opcountTracer")
+        };
+        let err = trace(opts).await.unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)), "{err:?}");
+
+        let opts = GethDebugTracingOptions {
+            timeout: Some("0s".to_string()),
+            ..js_tracer("opcountTracer")
+        };
+        let err = trace(opts).await.unwrap_err();
+        assert!(matches!(err, EthApiError::ExecutionTimedOut(_)), "{err:?}");
+
+        // unknown names are still JavaScript tracers
+        let err = trace(js_tracer("unknownTracer")).await.unwrap_err();
+        assert!(matches!(err, EthApiError::Unsupported(_)), "{err:?}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn tracing_timeout_aborts_execution() {
+        let opts =
+            GethDebugTracingOptions { timeout: Some("0s".to_string()), ..Default::default() };
+        let err = trace_test_block(DebugApiConfig::default(), opts).await.unwrap_err();
+        assert!(matches!(err, EthApiError::ExecutionTimedOut(_)), "{err:?}");
+
+        // requested timeouts are capped by the configured timeout
+        let config = DebugApiConfig { tracing_timeout: Duration::ZERO, ..Default::default() };
+        let opts = GethDebugTracingOptions {
+            timeout: Some("1h".to_string()),
+            ..GethDebugTracingOptions::default().with_call_config(CallConfig::default())
+        };
+        let err = trace_test_block(config, opts).await.unwrap_err();
+        assert!(matches!(err, EthApiError::ExecutionTimedOut(_)), "{err:?}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn struct_logs_truncated_at_memory_budget() {
+        let trace_with = |max_struct_logs_memory| async move {
+            let config = DebugApiConfig { max_struct_logs_memory, ..Default::default() };
+            let traces = trace_test_block(config, Default::default()).await.unwrap();
+            traces
+                .into_iter()
+                .map(|trace| serde_json::to_value(trace).unwrap()["result"].clone())
+                .collect::<Vec<_>>()
+        };
+
+        for trace in trace_with(DEFAULT_MAX_STRUCT_LOGS_MEMORY).await {
+            assert!(!trace["structLogs"].as_array().unwrap().is_empty());
+            assert!(trace.get("truncated").is_none());
+        }
+        for trace in trace_with(1).await {
+            assert!(trace["structLogs"].as_array().unwrap().is_empty());
+            assert_eq!(trace["truncated"], serde_json::Value::Bool(true));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_call_prestate_diff_with_state_overrides() {
+        let (provider, parent_hash) = test_provider();
+        let eth_api = build_test_eth_api(provider.clone());
+        let debug_api =
+            DebugApi::new(provider, eth_api, BlockingTaskGuard::new(1), Default::default());
+
+        // an account that doesn't exist in the database, its code and storage only exist in the
+        // overrides
+        let counter = Address::with_last_byte(0xc0);
+        let state_overrides = [(
+            counter,
+            AccountOverride {
+                code: Some(Bytes::from_static(INNER_CODE)),
+                state_diff: Some([(B256::ZERO, B256::with_last_byte(5))].into_iter().collect()),
+                ..Default::default()
+            },
+        )]
+        .into_iter()
+        .collect();
+        let opts = GethDebugTracingCallOptions {
+            tracing_options: prestate_tracer(true),
+            state_overrides: Some(state_overrides),
+            block_overrides: None,
+        };
+        // the access list is read from the database before the call, it must not cache the
+        // account as non-existent underneath the overrides
+        let request = TransactionRequest {
+            from: Some(ALICE),
+            to: Some(TxKind::Call(counter)),
+            access_list: Some(AccessList(vec![AccessListItem {
+                address: counter,
+                storage_keys: vec![B256::ZERO],
+            }])),
+            ..Default::default()
+        };
+        let trace =
+            debug_api.debug_trace_call(request, Some(parent_hash.into()), opts.into()).await;
+
+        // the override is the pre-state of the call, the execution result its post-state
+        let Ok(DebugTrace::Geth(GethTrace::PreStateTracer(PreStateFrame::Diff(diff)))) = trace
+        else {
+            panic!("unexpected trace {trace:?}")
+        };
+        assert_eq!(diff.pre[&counter].storage[&B256::ZERO], B256::with_last_byte(5));
+        assert_eq!(diff.post[&counter].storage[&B256::ZERO], B256::with_last_byte(6));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_call_block_hash_override() {
+        // the env of the mock provider has the latest spec, so `BLOCKHASH` reads the history
+        // storage contract, see also `block_hash_overrides_are_read_by_blockhash`
+        let (provider, parent_hash) = test_provider();
+        let eth_api = build_test_eth_api(provider.clone());
+        let debug_api =
+            DebugApi::new(provider, eth_api, BlockingTaskGuard::new(1), Default::default());
+
+        let block_hash = B256::repeat_byte(0x11);
+        let trace_block_hash = |block_hashes: Option<BTreeMap<u64, B256>>| {
+            let state_overrides = [(
+                BLOCKHASH,
+                AccountOverride {
+                    code: Some(Bytes::from_static(BLOCKHASH_CODE)),
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect();
+            let block_overrides = BlockOverrides {
+                number: Some(U256::from(2)),
+                block_hash: block_hashes,
+                ..Default::default()
+            };
+            let opts = GethDebugTracingCallOptions {
+                tracing_options: Default::default(),
+                state_overrides: Some(state_overrides),
+                block_overrides: Some(block_overrides),
+            };
+            let request = TransactionRequest {
+                from: Some(ALICE),
+                to: Some(TxKind::Call(BLOCKHASH)),
+                ..Default::default()
+            };
+            let debug_api = debug_api.clone();
+            async move {
+                let trace = debug_api
+                    .debug_trace_call(request, Some(parent_hash.into()), opts.into())
+                    .await;
+                let Ok(DebugTrace::StructLogs(LimitedDefaultFrame { frame, .. })) = trace else {
+                    panic!("unexpected trace {trace:?}")
+                };
+                frame.return_value
+            }
+        };
+
+        let overridden = trace_block_hash(Some(BTreeMap::from([(1, block_hash)]))).await;
+        assert_eq!(overridden, Bytes::copy_from_slice(block_hash.as_slice()));
+
+        let not_overridden = trace_block_hash(None).await;
+        assert_ne!(not_overridden, overridden);
+    }
+
+    /// Adds a block with signed calls of [`OUTER`] from a funded sender on top of the given parent
+    /// and returns its hash.
+    fn add_block_calling_outer(
+        provider: &MockEthProvider,
+        parent_hash: B256,
+        number: u64,
+        calls: u64,
+    ) -> B256 {
+        let mut rng = generators::rng();
+        let key = generators::generate_keys(&mut rng, 1)[0];
+        let sender = public_key_to_address(key.public_key());
+        provider.add_account(sender, ExtendedAccount::new(0, U256::ZERO));
+
+        let body = (0..calls)
+            .map(|nonce| {
+                let transaction = Transaction::Legacy(TxLegacy {
+                    chain_id: Some(1),
+                    nonce,
+                    gas_limit: 100_000,
+                    to: TxKind::Call(OUTER),
+                    ..Default::default()
+                });
+                sign_tx_with_key_pair(key, transaction)
+            })
+            .collect();
+        let header = Header {
+            number,
+            parent_hash,
+            gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            ..Default::default()
+        };
+        let block_hash = header.hash_slow();
+        provider.add_block(block_hash, Block { header, body, ..Default::default() });
+        block_hash
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_call_at_transaction_index() {
+        let (provider, parent_hash) = test_provider();
+        // both transactions of the block increment slot 0 of `OUTER`
+        let block_hash = add_block_calling_outer(&provider, parent_hash, 1, 2);
+
+        let eth_api = build_test_eth_api(provider.clone());
+        let debug_api =
+            DebugApi::new(provider, eth_api, BlockingTaskGuard::new(1), Default::default());
+
+        let trace_at = |tx_index| {
+            let opts = TraceCallOptions {
+                call_options: GethDebugTracingCallOptions {
+                    tracing_options: prestate_tracer(false),
+                    ..Default::default()
+                },
+                tx_index,
+            };
+            let request = TransactionRequest {
+                from: Some(ALICE),
+                to: Some(TxKind::Call(OUTER)),
+                ..Default::default()
+            };
+            let debug_api = debug_api.clone();
+            async move { debug_api.debug_trace_call(request, Some(block_hash.into()), opts).await }
+        };
+        // the value of slot 0 of `OUTER` the call read
+        let outer_slot = |trace: EthResult<DebugTrace>| {
+            let Ok(DebugTrace::Geth(GethTrace::PreStateTracer(PreStateFrame::Default(prestate)))) =
+                trace
+            else {
+                panic!("unexpected trace {trace:?}")
+            };
+            prestate.0[&OUTER].storage.get(&B256::ZERO).copied().unwrap_or_default()
+        };
+
+        for tx_index in 0..=2 {
+            let slot = outer_slot(trace_at(Some(tx_index)).await);
+            assert_eq!(slot, B256::with_last_byte(tx_index as u8));
+        }
+
+        let err = trace_at(Some(3)).await.unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)), "{err:?}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_call_at_transaction_index_applies_system_calls() {
+        let (mut provider, parent_hash) = test_provider();
+        provider.chain_spec = Arc::new(
+            ChainSpecBuilder::mainnet()
+                .cancun_activated()
+                .with_fork(Hardfork::Prague, ForkCondition::Timestamp(0))
+                .build(),
+        );
+        let header = Header { number: 1, parent_hash, ..Default::default() };
+        let block_hash = header.hash_slow();
+        provider.add_block(block_hash, Block { header, ..Default::default() });
+
+        let eth_api = build_test_eth_api(provider.clone());
+        let debug_api =
+            DebugApi::new(provider, eth_api, BlockingTaskGuard::new(1), Default::default());
+
+        // returns the hash of block 0, which the EIP-2935 block hashes update of block 1 writes to
+        // the history storage contract
+        let state_overrides = [(
+            BLOCKHASH,
+            AccountOverride {
+                code: Some(Bytes::from_static(&hex!("60004060005260206000f3"))),
+                ..Default::default()
+            },
+        )]
+        .into_iter()
+        .collect();
+        let opts = TraceCallOptions {
+            call_options: GethDebugTracingCallOptions {
+                tracing_options: Default::default(),
+                state_overrides: Some(state_overrides),
+                block_overrides: Some(BlockOverrides {
+                    number: Some(U256::from(1)),
+                    ..Default::default()
+                }),
+            },
+            tx_index: Some(0),
+        };
+        let request = TransactionRequest {
+            from: Some(ALICE),
+            to: Some(TxKind::Call(BLOCKHASH)),
+            ..Default::default()
+        };
+        let trace = debug_api.debug_trace_call(request, Some(block_hash.into()), opts).await;
+        let Ok(DebugTrace::StructLogs(LimitedDefaultFrame { frame, .. })) = trace else {
+            panic!("unexpected trace {trace:?}")
+        };
+        assert_eq!(frame.return_value, Bytes::copy_from_slice(parent_hash.as_slice()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn storage_range_pagination() {
+        let (provider, parent_hash) = test_provider();
+        // slot 0 of `OUTER` is only written by the transactions of the block
+        let slots = (1..=300u64).map(|slot| (B256::from(U256::from(slot)), U256::from(slot)));
+        provider.add_account(
+            OUTER,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(Bytes::from_static(OUTER_CODE))
+                .extend_storage(slots),
+        );
+        let block_hash = add_block_calling_outer(&provider, parent_hash, 1, 2);
+
+        let eth_api = build_test_eth_api(provider.clone());
+        let debug_api =
+            DebugApi::new(provider, eth_api, BlockingTaskGuard::new(1), Default::default());
+
+        let storage_at = |tx_index| {
+            let debug_api = debug_api.clone();
+            async move {
+                let mut storage = BTreeMap::new();
+                let mut key_start = B256::ZERO;
+                loop {
+                    let page = debug_api
+                        .debug_storage_range_at(block_hash, tx_index, OUTER, key_start, 64)
+                        .await
+                        .unwrap();
+                    assert!(page.storage.len() <= 64);
+                    assert!(page.storage.keys().all(|hashed_slot| *hashed_slot >= key_start));
+                    storage.extend(page.storage);
+                    match page.next_key {
+                        Some(next_key) => key_start = next_key,
+                        None => break,
+                    }
+                }
+                storage
+            }
+        };
+
+        // every slot holds its own number, except slot 0 which counts the executed calls
+        for (tx_index, slot_zero) in [(0, None), (1, Some(1u8)), (2, Some(2))] {
+            let storage = storage_at(tx_index).await;
+            assert_eq!(storage.len(), 300 + slot_zero.is_some() as usize);
+            for slot in 1..=300u64 {
+                let slot = B256::from(U256::from(slot));
+                assert_eq!(
+                    storage[&keccak256(slot)],
+                    StorageRangeSlot { key: Some(slot), value: slot }
+                );
+            }
+            if let Some(value) = slot_zero {
+                assert_eq!(
+                    storage[&keccak256(B256::ZERO)],
+                    StorageRangeSlot { key: Some(B256::ZERO), value: B256::with_last_byte(value) }
+                );
+            }
+        }
+
+        // a single page of the whole storage
+        let page = debug_api
+            .debug_storage_range_at(block_hash, 2, OUTER, B256::ZERO, 1_000)
+            .await
+            .unwrap();
+        assert_eq!(page.storage.len(), 301);
+        assert_eq!(page.next_key, None);
+
+        let err = debug_api
+            .debug_storage_range_at(block_hash, 3, OUTER, B256::ZERO, 64)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn storage_range_at_historical_block() {
+        let factory = create_test_provider_factory();
+        let blocks = random_block_range(&mut generators::rng(), 0..=2, B256::ZERO, 0..1);
+        let block_hash = blocks[2].hash();
+        let provider_rw = factory.provider_rw().unwrap();
+        for block in blocks {
+            provider_rw
+                .insert_historical_block(block.try_seal_with_senders().unwrap(), None)
+                .unwrap();
+        }
+        provider_rw
+            .static_file_provider()
+            .latest_writer(StaticFileSegment::Headers)
+            .unwrap()
+            .commit()
+            .unwrap();
+        provider_rw.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(2)).unwrap();
+
+        // slot 1 is changed and slot 2 is cleared by block 2, slot 3 is left untouched
+        let [changed, cleared, kept] = [1, 2, 3].map(B256::with_last_byte);
+        let hashed_address = keccak256(OUTER);
+        let tx = provider_rw.tx_ref();
+        for (slot, value) in [(changed, U256::from(10)), (kept, U256::from(30))] {
+            tx.put::<tables::PlainStorageState>(OUTER, StorageEntry { key: slot, value }).unwrap();
+            tx.put::<tables::HashedStorages>(
+                hashed_address,
+                StorageEntry { key: keccak256(slot), value },
+            )
+            .unwrap();
+        }
+        for (slot, value) in [(changed, U256::from(1)), (cleared, U256::from(2))] {
+            tx.put::<tables::StorageChangeSets>(
+                (2, OUTER).into(),
+                StorageEntry { key: slot, value },
+            )
+            .unwrap();
+        }
+        provider_rw.commit().unwrap();
+        let provider =
+            BlockchainProvider::new(factory, Arc::new(NoopBlockchainTree::default())).unwrap();
+
+        let eth_api = build_test_eth_api(provider.clone());
+        let debug_api =
+            DebugApi::new(provider, eth_api, BlockingTaskGuard::new(1), Default::default());
+
+        // the storage at the start of block 2 is paginated from the storage history
+        let mut storage = BTreeMap::new();
+        let mut key_start = B256::ZERO;
+        loop {
+            let page =
+                debug_api.debug_storage_range_at(block_hash, 0, OUTER, key_start, 1).await.unwrap();
+            assert!(page.storage.len() <= 1);
+            storage.extend(page.storage);
+            match page.next_key {
+                Some(next_key) => key_start = next_key,
+                None => break,
+            }
+        }
+        assert_eq!(
+            storage,
+            BTreeMap::from([
+                (
+                    keccak256(changed),
+                    StorageRangeSlot { key: Some(changed), value: B256::with_last_byte(1) }
+                ),
+                // the cleared slot isn't in the plain storage anymore
+                (
+                    keccak256(cleared),
+                    StorageRangeSlot { key: None, value: B256::with_last_byte(2) }
+                ),
+                (
+                    keccak256(kept),
+                    StorageRangeSlot { key: Some(kept), value: B256::with_last_byte(30) }
+                ),
+            ])
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_chain_subscription() {
+        let (provider, genesis_hash) = test_provider();
+        let first = add_block_calling_outer(&provider, genesis_hash, 1, 2);
+        let second = add_block_calling_outer(&provider, first, 2, 1);
+
+        let eth_api = build_test_eth_api(provider.clone());
+        let config = DebugApiConfig { max_trace_chain_blocks: 2, ..Default::default() };
+        let module = DebugApi::new(provider, eth_api, BlockingTaskGuard::new(1), config).into_rpc();
+
+        let mut sub = module
+            .subscribe_unbounded(
+                "debug_traceChain",
+                rpc_params![BlockNumberOrTag::Number(0), BlockNumberOrTag::Number(2)],
+            )
+            .await
+            .unwrap();
+        let mut notifications = Vec::new();
+        for _ in 0..3 {
+            let (notification, _) =
+                tokio::time::timeout(Duration::from_secs(5), sub.next::<TraceChainNotification>())
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .unwrap();
+            notifications.push(notification);
+        }
+
+        let expected = [(1u64, first, 2), (2, second, 1)];
+        for (notification, (number, hash, traces)) in notifications.iter().zip(expected) {
+            let TraceChainNotification::Block(block) = notification else {
+                panic!("expected the traces of block {number}, got {notification:?}")
+            };
+            assert_eq!(block.block, U64::from(number));
+            assert_eq!(block.hash, hash);
+            assert_eq!(block.traces.len(), traces);
+        }
+        let Some(TraceChainNotification::Completed { completed }) = notifications.last() else {
+            panic!("expected the end of the subscription")
+        };
+        assert_eq!(completed, &TraceChainCompleted { blocks: U64::from(2), error: None });
+
+        // the range exceeds the limit
+        let res = module
+            .subscribe_unbounded(
+                "debug_traceChain",
+                rpc_params![BlockNumberOrTag::Number(0), BlockNumberOrTag::Number(3)],
+            )
+            .await;
+        assert!(res.is_err());
+
+        // the range is empty
+        let res = module
+            .subscribe_unbounded(
+                "debug_traceChain",
+                rpc_params![BlockNumberOrTag::Number(2), BlockNumberOrTag::Number(2)],
+            )
+            .await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_chain_blocks_on_top_of_previous_blocks() {
+        let (provider, genesis_hash) = test_provider();
+        let first = add_block_calling_outer(&provider, genesis_hash, 1, 2);
+        add_block_calling_outer(&provider, first, 2, 1);
+
+        let eth_api = build_test_eth_api(provider.clone());
+        let debug_api =
+            DebugApi::new(provider, eth_api, BlockingTaskGuard::new(1), Default::default());
+
+        // the values of slot 0 of `OUTER` the transactions of the block read
+        let outer_slots = |block: &DebugBlockTraceResult| {
+            block
+                .traces
+                .iter()
+                .map(|trace| {
+                    let DebugTraceResult::Success {
+                        result:
+                            DebugTrace::Geth(GethTrace::PreStateTracer(PreStateFrame::Default(
+                                prestate,
+                            ))),
+                        ..
+                    } = trace
+                    else {
+                        panic!("unexpected trace {trace:?}")
+                    };
+                    prestate.0[&OUTER].storage.get(&B256::ZERO).copied().unwrap_or_default()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let (block, state) =
+            debug_api.trace_chain_block(1, None, false, prestate_tracer(false)).await.unwrap();
+        assert_eq!(outer_slots(&block), vec![B256::ZERO, B256::with_last_byte(1)]);
+
+        // the mock provider has the same state at every block, so the changes of block 1 are only
+        // visible if they're carried over
+        let (block, _) =
+            debug_api.trace_chain_block(2, state, false, prestate_tracer(false)).await.unwrap();
+        assert_eq!(outer_slots(&block), vec![B256::with_last_byte(2)]);
+        let (block, _) =
+            debug_api.trace_chain_block(2, None, false, prestate_tracer(false)).await.unwrap();
+        assert_eq!(outer_slots(&block), vec![B256::ZERO]);
+    }
+
+    /// Counts the traced transactions.
+    struct CountingTracer(Arc<AtomicUsize>);
+
+    impl TracerFactory for CountingTracer {
+        fn create(&self, _config: serde_json::Value) -> Result<CustomTracer, CustomTracerError> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(CustomTracer {
+                inspector: Box::new(NoOpInspector),
+                into_result: Box::new(|_, _| Ok(serde_json::Value::Null)),
+            })
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_chain_subscription_backpressure() {
+        const BLOCKS: u64 = 8;
+        let (provider, mut parent_hash) = test_provider();
+        for number in 1..=BLOCKS {
+            parent_hash = add_block_calling_outer(&provider, parent_hash, number, 1);
+        }
+
+        let eth_api = build_test_eth_api(provider.clone());
+        let debug_api =
+            DebugApi::new(provider, eth_api, BlockingTaskGuard::new(1), Default::default());
+        let traced = Arc::new(AtomicUsize::new(0));
+        debug_api.register_tracer("countingTracer", Box::new(CountingTracer(traced.clone())));
+        let module = debug_api.into_rpc();
+
+        // the subscriber buffers a single notification and doesn't read it
+        let params = rpc_params![
+            BlockNumberOrTag::Number(0),
+            BlockNumberOrTag::Number(BLOCKS),
+            js_tracer("countingTracer")
+        ];
+        let mut sub = module.subscribe("debug_traceChain", params, 1).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let paused = traced.load(Ordering::Relaxed);
+        assert!(paused < BLOCKS as usize, "{paused} blocks were traced without being read");
+
+        // the tracing resumes as the notifications are read
+        for _ in 0..=BLOCKS {
+            tokio::time::timeout(Duration::from_secs(5), sub.next::<TraceChainNotification>())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+        }
+        assert_eq!(traced.load(Ordering::Relaxed), BLOCKS as usize);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_block_system_calls() {
+        let (provider, parent_hash) = test_provider();
+        provider.add_account(
+            BEACON_ROOTS_ADDRESS,
+            ExtendedAccount::new(1, U256::ZERO).with_bytecode(BEACON_ROOTS_CODE.clone()),
+        );
+        let eth_api = build_test_eth_api(provider.clone());
+
+        let chain_spec = ChainSpecBuilder::mainnet().cancun_activated().build();
+        let timestamp = 12;
+        let parent_beacon_block_root = B256::with_last_byte(1);
+        let header = Header {
+            number: 1,
+            parent_hash,
+            timestamp,
+            parent_beacon_block_root: Some(parent_beacon_block_root),
+            ..Default::default()
+        };
+        let system_calls = BlockSystemCalls::new(&chain_spec, &header);
+        let block_env = BlockEnv {
+            number: U256::from(1),
+            timestamp: U256::from(timestamp),
+            ..Default::default()
+        };
+
+        let trace = |max_trace_block_checkpoints, opts| {
+            let debug_api = DebugApi::new(
+                provider.clone(),
+                eth_api.clone(),
+                BlockingTaskGuard::new(1),
+                DebugApiConfig { max_trace_block_checkpoints, ..Default::default() },
+            );
+            let block_env = block_env.clone();
+            async move {
+                debug_api
+                    .trace_block_with(
+                        parent_hash.into(),
+                        vec![call(ALICE, 0, OUTER), call(BOB, 0, INNER)],
+                        system_calls,
+                        CfgEnvWithHandlerCfg::new_with_spec_id(Default::default(), SpecId::CANCUN),
+                        block_env,
+                        opts,
+                    )
+                    .await
+                    .unwrap()
+            }
+        };
+        // the result of the `SYSTEM` frame of the beacon root contract call
+        let system_call_result = |trace: &DebugTraceResult| {
+            let DebugTraceResult::Success { result: DebugTrace::System(frame), tx_hash: None } =
+                trace
+            else {
+                panic!("unexpected trace {trace:?}")
+            };
+            assert_eq!(frame.to, Some(BEACON_ROOTS_ADDRESS));
+            (*frame.result).clone()
+        };
+
+        for max_trace_block_checkpoints in [1, 4] {
+            // the state changes of the call are always part of the prestate diff
+            let traces = trace(max_trace_block_checkpoints, prestate_tracer(true)).await;
+            assert_eq!(traces.len(), 3);
+            let DebugTrace::Geth(GethTrace::PreStateTracer(PreStateFrame::Diff(diff))) =
+                system_call_result(&traces[0])
+            else {
+                panic!("unexpected trace {:?}", traces[0])
+            };
+            let storage = &diff.post[&BEACON_ROOTS_ADDRESS].storage;
+            let timestamp_slot = U256::from(timestamp % 8191);
+            assert_eq!(storage[&B256::from(timestamp_slot)], B256::from(U256::from(timestamp)));
+            assert_eq!(
+                storage[&B256::from(timestamp_slot + U256::from(8191))],
+                parent_beacon_block_root
+            );
+            assert!(traces[1..]
+                .iter()
+                .all(|trace| matches!(trace, DebugTraceResult::Success { tx_hash: Some(_), .. })));
+
+            // other tracers only trace the calls if requested
+            let call_tracer =
+                GethDebugTracingOptions::default().with_call_config(CallConfig::default());
+            let traces = trace(max_trace_block_checkpoints, call_tracer.clone()).await;
+            assert_eq!(traces.len(), 2);
+
+            let opts = GethDebugTracingOptions {
+                tracer_config: GethDebugTracerConfig(
+                    serde_json::json!({ "includeSystemCalls": true }),
+                ),
+                ..call_tracer
+            };
+            let traces = trace(max_trace_block_checkpoints, opts).await;
+            assert_eq!(traces.len(), 3);
+            let DebugTrace::Geth(GethTrace::CallTracer(frame)) = system_call_result(&traces[0])
+            else {
+                panic!("unexpected trace {:?}", traces[0])
+            };
+            assert_eq!(frame.from, SYSTEM_ADDRESS);
+            assert_eq!(frame.to, Some(BEACON_ROOTS_ADDRESS));
+            assert!(matches!(
+                &traces[1],
+                DebugTraceResult::Success {
+                    result: DebugTrace::Geth(GethTrace::CallTracer(_)),
+                    tx_hash: Some(_)
+                }
+            ));
+        }
+    }
 }
@@ -9,10 +9,13 @@ use crate::{
 };
 use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
-use reth_primitives::{BlockId, TransactionMeta};
-use reth_provider::{BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
+use reth_primitives::{BlockId, Receipt, SealedBlock, TransactionMeta};
+use reth_provider::{
+    BlockReaderIdExt, BlockSource, ChainSpecProvider, EvmEnvProvider, ReceiptProviderIdExt,
+    StateProviderFactory,
+};
 use reth_rpc_types::{AnyTransactionReceipt, Header, Index, RichBlock};
-use reth_rpc_types_compat::block::{from_block, uncle_block_from_header};
+use reth_rpc_types_compat::block::{from_block, from_primitive_with_hash, uncle_block_from_header};
 use reth_transaction_pool::TransactionPool;
 use std::sync::Arc;
 
@@ -26,38 +29,43 @@ where
 {
     /// Returns the uncle headers of the given block
     ///
-    /// Returns an empty vec if there are none.
-    pub(crate) fn ommers(
+    /// Returns an empty vec if there are none, and `None` if the block does not exist.
+    pub(crate) async fn ommers(
         &self,
         block_id: impl Into<BlockId>,
     ) -> EthResult<Option<Vec<reth_primitives::Header>>> {
         let block_id = block_id.into();
+
+        if block_id.is_pending() {
+            // the same pending block as served by `block_with_senders`, built locally if the CL
+            // didn't provide one
+            return Ok(self.block_with_senders(block_id).await?.map(|block| block.block.ommers))
+        }
+
         Ok(self.provider().ommers_by_id(block_id)?)
     }
 
+    /// Returns the uncle at the index of the given block.
+    ///
+    /// Returns `None` if the block does not exist or the index is out of range.
     pub(crate) async fn ommer_by_block_and_index(
         &self,
         block_id: impl Into<BlockId>,
         index: Index,
     ) -> EthResult<Option<RichBlock>> {
-        let block_id = block_id.into();
-
-        let uncles = if block_id.is_pending() {
-            // Pending block can be fetched directly without need for caching
-            self.provider().pending_block()?.map(|block| block.ommers)
-        } else {
-            self.provider().ommers_by_id(block_id)?
-        }
-        .unwrap_or_default();
-
-        let index = usize::from(index);
-        let uncle =
-            uncles.into_iter().nth(index).map(|header| uncle_block_from_header(header).into());
+        let uncle = self
+            .ommers(block_id)
+            .await?
+            .and_then(|uncles| uncles.into_iter().nth(index.into()))
+            .map(|header| uncle_block_from_header(header).into());
         Ok(uncle)
     }
 
     /// Returns all transaction receipts in the block.
     ///
+    /// The `pending` block is the locally built pending block, block hashes also match blocks that
+    /// are only kept in memory, including blocks of side chains.
+    ///
     /// Returns `None` if the block wasn't found.
     pub(crate) async fn block_receipts(
         &self,
@@ -67,11 +75,16 @@ where
 
         if block_id.is_pending() {
             block_and_receipts = self
-                .provider()
-                .pending_block_and_receipts()?
-                .map(|(sb, receipts)| (sb, Arc::new(receipts)));
+                .local_pending_block_and_receipts()
+                .await?
+                .map(|(block, receipts)| (block.block, receipts));
         } else if let Some(block_hash) = self.provider().block_hash_for_id(block_id)? {
             block_and_receipts = self.cache().get_block_and_receipts(block_hash).await?;
+
+            // blocks that are not persisted yet are not served by the cache
+            if block_and_receipts.is_none() && matches!(block_id, BlockId::Hash(_)) {
+                block_and_receipts = self.in_memory_block_and_receipts(block_id)?;
+            }
         }
 
         if let Some((block, receipts)) = block_and_receipts {
@@ -80,6 +93,7 @@ where
             let block_hash = block.hash();
             let excess_blob_gas = block.excess_blob_gas;
             let timestamp = block.timestamp;
+            let blob_params = self.provider().chain_spec().blob_params_at_timestamp(timestamp);
             let block = block.unseal();
 
             #[cfg(feature = "optimism")]
@@ -113,6 +127,7 @@ where
                         meta,
                         receipt.clone(),
                         &receipts,
+                        blob_params,
                         #[cfg(feature = "optimism")]
                         op_tx_meta,
                     )
@@ -124,6 +139,24 @@ where
         Ok(None)
     }
 
+    /// Returns the block with the given hash and its receipts from the in-memory state.
+    fn in_memory_block_and_receipts(
+        &self,
+        block_id: BlockId,
+    ) -> EthResult<Option<(SealedBlock, Arc<Vec<Receipt>>)>> {
+        let BlockId::Hash(block_hash) = block_id else { return Ok(None) };
+        let Some(receipts) = self.provider().receipts_by_block_id(block_id)? else {
+            return Ok(None)
+        };
+        let Some(block) =
+            self.provider().find_block_by_hash(block_hash.block_hash, BlockSource::Any)?
+        else {
+            return Ok(None)
+        };
+
+        Ok(Some((block.seal(block_hash.block_hash), Arc::new(receipts))))
+    }
+
     /// Returns the number transactions in the given block.
     ///
     /// Returns `None` if the block does not exist
@@ -134,8 +167,9 @@ where
         let block_id = block_id.into();
 
         if block_id.is_pending() {
-            // Pending block can be fetched directly without need for caching
-            return Ok(self.provider().pending_block()?.map(|block| block.body.len()))
+            // the same pending block as served by `block_with_senders`, built locally if the CL
+            // didn't provide one
+            return Ok(self.block_with_senders(block_id).await?.map(|block| block.body.len()))
         }
 
         let block_hash = match self.provider().block_hash_for_id(block_id)? {
@@ -185,30 +219,404 @@ where
     ///
     /// If `full` is true, the block object will contain all transaction objects, otherwise it will
     /// only contain the transaction hashes.
+    ///
+    /// The hash and nonce of the `pending` block are `null`.
     pub(crate) async fn rpc_block(
         &self,
         block_id: impl Into<BlockId>,
         full: bool,
     ) -> EthResult<Option<RichBlock>> {
+        let block_id = block_id.into();
         let block = match self.block_with_senders(block_id).await? {
             Some(block) => block,
             None => return Ok(None),
         };
         let block_hash = block.hash();
-        let total_difficulty = self
-            .provider()
-            .header_td_by_number(block.number)?
-            .ok_or(EthApiError::UnknownBlockNumber)?;
-        let block = from_block(block.unseal(), total_difficulty, full.into(), Some(block_hash))?;
+        let total_difficulty = if block_id.is_pending() {
+            // the pending block is not part of the chain yet, and it's built without difficulty
+            self.provider().header_td(&block.parent_hash)?
+        } else {
+            self.provider().header_td_by_number(block.number)?
+        }
+        .ok_or(EthApiError::UnknownBlockNumber)?;
+        let mut block =
+            from_block(block.unseal(), total_difficulty, full.into(), Some(block_hash))?;
+        if block_id.is_pending() {
+            block.header.hash = None;
+            block.header.nonce = None;
+        }
         Ok(Some(block.into()))
     }
 
     /// Returns the block header for the given block id.
+    ///
+    /// Headers of canonical and cached blocks are served from the header cache, without loading
+    /// the body of the block.
     pub(crate) async fn rpc_block_header(
         &self,
         block_id: impl Into<BlockId>,
     ) -> EthResult<Option<Header>> {
-        let header = self.rpc_block(block_id, false).await?.map(|block| block.inner.header);
-        Ok(header)
+        let block_id = block_id.into();
+        if block_id.is_pending() {
+            return Ok(self.rpc_block(block_id, false).await?.map(|block| block.inner.header))
+        }
+
+        let Some(block_hash) = self.provider().block_hash_for_id(block_id)? else {
+            return Ok(None)
+        };
+        let Some(header) = self.cache().get_header(block_hash).await? else { return Ok(None) };
+        let total_difficulty = self
+            .provider()
+            .header_td_by_number(header.number)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        let mut header = from_primitive_with_hash(header.seal(block_hash));
+        header.total_difficulty = Some(total_difficulty);
+        Ok(Some(header))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::test_utils::build_test_eth_api;
+    use reth_blockchain_tree::{
+        error::{BlockchainTreeError, CanonicalError, InsertBlockError},
+        noop::NoopBlockchainTree,
+        BlockValidationKind, BlockchainTreeEngine, BlockchainTreeViewer, CanonicalOutcome,
+        InsertPayloadOk,
+    };
+    use reth_primitives::{
+        stage::{StageCheckpoint, StageId},
+        BlockHash, BlockNumHash, BlockNumber, BlockNumberOrTag, SealedBlockWithSenders,
+        SealedHeader, StaticFileSegment, B256,
+    };
+    use reth_provider::{
+        providers::BlockchainProvider,
+        test_utils::{create_test_provider_factory, MockEthProvider},
+        BlockchainTreePendingStateProvider, CanonStateNotifications, CanonStateSubscriptions,
+        FullBundleStateDataProvider, ProviderError, ProviderResult, StageCheckpointWriter,
+        StaticFileWriter,
+    };
+    use reth_testing_utils::generators::{self, random_block, random_receipt, Rng};
+    use std::collections::BTreeMap;
+
+    /// A blockchain tree that only contains a single executed block, which is not canonical.
+    #[derive(Debug)]
+    struct SidechainTree {
+        block: SealedBlock,
+        receipts: Vec<Receipt>,
+        noop: NoopBlockchainTree,
+    }
+
+    impl BlockchainTreeEngine for SidechainTree {
+        fn buffer_block(&self, block: SealedBlockWithSenders) -> Result<(), InsertBlockError> {
+            self.noop.buffer_block(block)
+        }
+
+        fn insert_block(
+            &self,
+            block: SealedBlockWithSenders,
+            validation_kind: BlockValidationKind,
+        ) -> Result<InsertPayloadOk, InsertBlockError> {
+            self.noop.insert_block(block, validation_kind)
+        }
+
+        fn finalize_block(&self, finalized_block: BlockNumber) -> ProviderResult<()> {
+            self.noop.finalize_block(finalized_block)
+        }
+
+        fn connect_buffered_blocks_to_canonical_hashes_and_finalize(
+            &self,
+            last_finalized_block: BlockNumber,
+        ) -> Result<(), CanonicalError> {
+            self.noop.connect_buffered_blocks_to_canonical_hashes_and_finalize(last_finalized_block)
+        }
+
+        fn connect_buffered_blocks_to_canonical_hashes(&self) -> Result<(), CanonicalError> {
+            self.noop.connect_buffered_blocks_to_canonical_hashes()
+        }
+
+        fn make_canonical(
+            &self,
+            block_hash: BlockHash,
+        ) -> Result<CanonicalOutcome, CanonicalError> {
+            Err(BlockchainTreeError::BlockHashNotFoundInChain { block_hash }.into())
+        }
+
+        fn update_block_hashes_and_clear_buffered(
+            &self,
+        ) -> Result<BTreeMap<BlockNumber, BlockHash>, CanonicalError> {
+            self.noop.update_block_hashes_and_clear_buffered()
+        }
+    }
+
+    impl BlockchainTreeViewer for SidechainTree {
+        fn header_by_hash(&self, hash: BlockHash) -> Option<SealedHeader> {
+            self.block_by_hash(hash).map(|block| block.header)
+        }
+
+        fn block_by_hash(&self, hash: BlockHash) -> Option<SealedBlock> {
+            (hash == self.block.hash()).then(|| self.block.clone())
+        }
+
+        fn block_with_senders_by_hash(&self, hash: BlockHash) -> Option<SealedBlockWithSenders> {
+            self.block_by_hash(hash).and_then(|block| block.seal_with_senders())
+        }
+
+        fn buffered_header_by_hash(&self, block_hash: BlockHash) -> Option<SealedHeader> {
+            self.noop.buffered_header_by_hash(block_hash)
+        }
+
+        fn is_canonical(&self, block_hash: BlockHash) -> Result<bool, ProviderError> {
+            self.noop.is_canonical(block_hash)
+        }
+
+        fn lowest_buffered_ancestor(&self, hash: BlockHash) -> Option<SealedBlockWithSenders> {
+            self.noop.lowest_buffered_ancestor(hash)
+        }
+
+        fn canonical_tip(&self) -> BlockNumHash {
+            self.noop.canonical_tip()
+        }
+
+        fn pending_block_num_hash(&self) -> Option<BlockNumHash> {
+            self.noop.pending_block_num_hash()
+        }
+
+        fn pending_block_and_receipts(&self) -> Option<(SealedBlock, Vec<Receipt>)> {
+            self.noop.pending_block_and_receipts()
+        }
+
+        fn receipts_by_block_hash(&self, block_hash: BlockHash) -> Option<Vec<Receipt>> {
+            (block_hash == self.block.hash()).then(|| self.receipts.clone())
+        }
+    }
+
+    impl BlockchainTreePendingStateProvider for SidechainTree {
+        fn find_pending_state_provider(
+            &self,
+            block_hash: BlockHash,
+        ) -> Option<Box<dyn FullBundleStateDataProvider>> {
+            self.noop.find_pending_state_provider(block_hash)
+        }
+    }
+
+    impl CanonStateSubscriptions for SidechainTree {
+        fn subscribe_to_canonical_state(&self) -> CanonStateNotifications {
+            self.noop.subscribe_to_canonical_state()
+        }
+    }
+
+    /// Returns a block with three transactions and their receipts.
+    fn block_with_receipts<R: Rng>(
+        rng: &mut R,
+        number: u64,
+        parent: B256,
+    ) -> (SealedBlock, Vec<Receipt>) {
+        let block = random_block(rng, number, Some(parent), Some(3), Some(0));
+
+        let mut cumulative_gas_used = 0;
+        let receipts = block
+            .body
+            .iter()
+            .map(|tx| {
+                let mut receipt = random_receipt(rng, tx, Some(2));
+                cumulative_gas_used += 21_000;
+                receipt.cumulative_gas_used = cumulative_gas_used;
+                receipt
+            })
+            .collect::<Vec<_>>();
+        (block, receipts)
+    }
+
+    /// Adds a block with three transactions and their receipts to the provider.
+    fn add_block_with_receipts<R: Rng>(
+        rng: &mut R,
+        provider: &MockEthProvider,
+        number: u64,
+        parent: B256,
+    ) -> (SealedBlock, Vec<Receipt>) {
+        let (block, receipts) = block_with_receipts(rng, number, parent);
+        provider.add_block(block.hash(), block.clone().unseal());
+        provider.add_receipts(block.hash(), receipts.clone());
+        (block, receipts)
+    }
+
+    /// Asserts that the rpc receipts are the receipts of the block, with the log indices and gas
+    /// used derived from all receipts of the block.
+    fn assert_block_receipts(
+        rpc_receipts: Option<Vec<AnyTransactionReceipt>>,
+        block: &SealedBlock,
+        receipts: &[Receipt],
+    ) {
+        let rpc_receipts = rpc_receipts.expect("block exists");
+        assert_eq!(rpc_receipts.len(), receipts.len());
+
+        let mut log_index = 0;
+        let mut cumulative_gas_used = 0;
+        for ((rpc_receipt, receipt), tx) in rpc_receipts.iter().zip(receipts).zip(&block.body) {
+            let rpc_receipt = &rpc_receipt.inner;
+            assert_eq!(rpc_receipt.block_hash, Some(block.hash()));
+            assert_eq!(rpc_receipt.block_number, Some(block.number));
+            assert_eq!(rpc_receipt.transaction_hash, tx.hash());
+            assert_eq!(
+                rpc_receipt.gas_used,
+                (receipt.cumulative_gas_used - cumulative_gas_used) as u128
+            );
+            cumulative_gas_used = receipt.cumulative_gas_used;
+
+            let rpc_receipt = &rpc_receipt.inner.inner.receipt;
+            assert_eq!(rpc_receipt.cumulative_gas_used, receipt.cumulative_gas_used as u128);
+            assert_eq!(rpc_receipt.logs.len(), receipt.logs.len());
+            for log in &rpc_receipt.logs {
+                assert_eq!(log.log_index, Some(log_index));
+                log_index += 1;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn block_receipts_by_number_tag_and_hash() {
+        let mut rng = generators::rng();
+        let provider = MockEthProvider::default();
+        let (genesis, genesis_receipts) =
+            add_block_with_receipts(&mut rng, &provider, 0, B256::ZERO);
+        let (first, first_receipts) =
+            add_block_with_receipts(&mut rng, &provider, 1, genesis.hash());
+        let (latest, latest_receipts) =
+            add_block_with_receipts(&mut rng, &provider, 2, first.hash());
+        let eth_api = build_test_eth_api(provider);
+
+        let receipts = eth_api.block_receipts(BlockNumberOrTag::Number(1).into()).await.unwrap();
+        assert_block_receipts(receipts, &first, &first_receipts);
+
+        let receipts = eth_api.block_receipts(first.hash().into()).await.unwrap();
+        assert_block_receipts(receipts, &first, &first_receipts);
+
+        let receipts = eth_api.block_receipts(BlockNumberOrTag::Latest.into()).await.unwrap();
+        assert_block_receipts(receipts, &latest, &latest_receipts);
+
+        let receipts = eth_api.block_receipts(BlockNumberOrTag::Earliest.into()).await.unwrap();
+        assert_block_receipts(receipts, &genesis, &genesis_receipts);
+
+        // no safe or finalized block yet
+        for tag in [BlockNumberOrTag::Safe, BlockNumberOrTag::Finalized] {
+            let err = eth_api.block_receipts(tag.into()).await.unwrap_err();
+            assert!(matches!(err, EthApiError::UnknownSafeOrFinalizedBlock));
+        }
+    }
+
+    #[tokio::test]
+    async fn block_receipts_of_fork_block() {
+        let mut rng = generators::rng();
+        let factory = create_test_provider_factory();
+        let (genesis, _) = block_with_receipts(&mut rng, 0, B256::ZERO);
+        let (canonical, _) = block_with_receipts(&mut rng, 1, genesis.hash());
+        let provider_rw = factory.provider_rw().unwrap();
+        for block in [&genesis, &canonical] {
+            provider_rw
+                .insert_historical_block(block.clone().try_seal_with_senders().unwrap(), None)
+                .unwrap();
+        }
+        provider_rw
+            .static_file_provider()
+            .latest_writer(StaticFileSegment::Headers)
+            .unwrap()
+            .commit()
+            .unwrap();
+        provider_rw.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(1)).unwrap();
+        provider_rw.commit().unwrap();
+
+        // the fork block is only in memory, so it's not served by the cache
+        let (fork, fork_receipts) = block_with_receipts(&mut rng, 1, genesis.hash());
+        let tree = SidechainTree {
+            block: fork.clone(),
+            receipts: fork_receipts.clone(),
+            noop: NoopBlockchainTree::default(),
+        };
+        let provider = BlockchainProvider::new(factory, Arc::new(tree)).unwrap();
+        let eth_api = build_test_eth_api(provider);
+
+        let receipts = eth_api.block_receipts(fork.hash().into()).await.unwrap();
+        assert_block_receipts(receipts, &fork, &fork_receipts);
+    }
+
+    #[tokio::test]
+    async fn block_receipts_of_unknown_block() {
+        let mut rng = generators::rng();
+        let provider = MockEthProvider::default();
+        add_block_with_receipts(&mut rng, &provider, 0, B256::ZERO);
+        let eth_api = build_test_eth_api(provider);
+
+        let receipts = eth_api.block_receipts(rng.gen::<B256>().into()).await.unwrap();
+        assert!(receipts.is_none());
+
+        let receipts = eth_api.block_receipts(BlockNumberOrTag::Number(1).into()).await.unwrap();
+        assert!(receipts.is_none());
+    }
+
+    #[tokio::test]
+    async fn ommers_of_unknown_block_and_out_of_range_index() {
+        let mut rng = generators::rng();
+        let provider = MockEthProvider::default();
+        let block = random_block(&mut rng, 0, Some(B256::ZERO), Some(0), Some(2));
+        provider.add_block(block.hash(), block.clone().unseal());
+        let eth_api = build_test_eth_api(provider);
+
+        for id in [BlockId::from(block.hash()), BlockNumberOrTag::Number(0).into()] {
+            assert_eq!(eth_api.ommers(id).await.unwrap().map(|ommers| ommers.len()), Some(2));
+            let uncle = eth_api.ommer_by_block_and_index(id, Index::from(1)).await.unwrap();
+            assert_eq!(uncle.unwrap().inner.header.hash, Some(block.ommers[1].hash_slow()));
+            let uncle = eth_api.ommer_by_block_and_index(id, Index::from(2)).await.unwrap();
+            assert!(uncle.is_none());
+        }
+
+        // blocks that don't exist yet or are unknown
+        for id in [BlockId::from(rng.gen::<B256>()), BlockNumberOrTag::Number(1).into()] {
+            assert!(eth_api.ommers(id).await.unwrap().is_none());
+            let uncle = eth_api.ommer_by_block_and_index(id, Index::from(0)).await.unwrap();
+            assert!(uncle.is_none());
+        }
+
+        // the pending block is built locally without ommers
+        let ommers = eth_api.ommers(BlockNumberOrTag::Pending).await.unwrap();
+        assert_eq!(ommers.map(|ommers| ommers.len()), Some(0));
+        let uncle =
+            eth_api.ommer_by_block_and_index(BlockNumberOrTag::Pending, Index::from(0)).await;
+        assert!(uncle.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn block_receipts_of_pending_block() {
+        let mut rng = generators::rng();
+        let provider = MockEthProvider::default();
+        add_block_with_receipts(&mut rng, &provider, 0, B256::ZERO);
+        let eth_api = build_test_eth_api(provider);
+
+        // the pending block is built locally from the empty pool
+        let (pending, _) = eth_api.local_pending_block_and_receipts().await.unwrap().unwrap();
+        assert!(pending.body.is_empty());
+
+        let receipts = eth_api.block_receipts(BlockNumberOrTag::Pending.into()).await.unwrap();
+        assert_eq!(receipts.map(|receipts| receipts.len()), Some(0));
+    }
+
+    #[tokio::test]
+    async fn rpc_block_of_pending_block() {
+        let mut rng = generators::rng();
+        let provider = MockEthProvider::default();
+        let (latest, _) = add_block_with_receipts(&mut rng, &provider, 0, B256::ZERO);
+        let eth_api = build_test_eth_api(provider);
+
+        let block = eth_api.rpc_block(BlockNumberOrTag::Pending, true).await.unwrap().unwrap();
+        let header = &block.inner.header;
+        assert_eq!(header.hash, None);
+        assert_eq!(header.nonce, None);
+        assert_eq!(header.number, Some(latest.number + 1));
+        assert_eq!(header.parent_hash, latest.hash());
+        assert_eq!(header.gas_used, 0);
+
+        let count = eth_api.block_transaction_count(BlockNumberOrTag::Pending).await.unwrap();
+        assert_eq!(count, Some(0));
     }
 }
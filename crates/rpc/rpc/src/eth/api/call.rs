@@ -4,9 +4,9 @@ use crate::{
     eth::{
         error::{ensure_success, EthApiError, EthResult, RevertError, RpcInvalidTransactionError},
         revm_utils::{
-            apply_state_overrides, build_call_evm_env, caller_gas_allowance,
-            cap_tx_gas_limit_with_caller_allowance, get_precompiles, prepare_call_env,
-            EvmOverrides,
+            apply_block_overrides, apply_state_overrides, build_call_evm_env, caller_gas_allowance,
+            cap_tx_gas_limit_with_caller_allowance, get_precompiles, prefetch_access_list,
+            prepare_call_env, EvmOverrides,
         },
         EthTransactions,
     },
@@ -14,32 +14,41 @@ use crate::{
 };
 use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
-use reth_primitives::{revm::env::tx_env_with_recovered, BlockId, Bytes, TxKind, U256};
+use reth_primitives::{
+    revm::env::tx_env_with_recovered, Address, BlockId, Bytes, TxKind, U256, U64,
+};
 use reth_provider::{
     BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProvider, StateProviderFactory,
 };
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_types::{
-    state::StateOverride, AccessListWithGasUsed, Bundle, EthCallResponse, StateContext,
-    TransactionRequest,
+    state::StateOverride, AccessList, AccessListWithGasUsed, Bundle, EthCallManyResponse,
+    StateContext, TransactionRequest,
 };
 use reth_transaction_pool::TransactionPool;
 use revm::{
     db::{CacheDB, DatabaseRef},
+    interpreter::gas::validate_initial_tx_gas,
     primitives::{
         BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, HaltReason, TransactTo,
     },
     DatabaseCommit,
 };
 use revm_inspectors::access_list::AccessListInspector;
+use std::collections::HashSet;
 use tracing::trace;
 
 // Gas per transaction not creating a contract.
 const MIN_TRANSACTION_GAS: u64 = 21_000u64;
+/// The gas that must be left for an `SSTORE` and that is forwarded to the callee of a call with
+/// value.
+const CALL_STIPEND: u64 = 2_300u64;
 /// Allowed error ratio for gas estimation
 /// Taken from Geth's implementation in order to pass the hive tests
 /// <https://github.com/ethereum/go-ethereum/blob/a5a4fa7032bb248f5a7c40f4e8df2b131c4186a4/internal/ethapi/api.go#L56>
 const ESTIMATE_GAS_ERROR_RATIO: f64 = 0.015;
+/// The maximum number of executions of `eth_createAccessList` until the access list is stable.
+const MAX_ACCESS_LIST_ITERATIONS: usize = 10;
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
 where
@@ -54,13 +63,14 @@ where
         &self,
         request: TransactionRequest,
         at: BlockId,
-        state_override: Option<StateOverride>,
+        overrides: EvmOverrides,
     ) -> EthResult<U256> {
-        let (cfg, block_env, at) = self.evm_env_at(at).await?;
+        let (mut cfg, block_env, at) = self.evm_env_at(at).await?;
+        cfg.memory_limit = self.call_memory_limit();
 
         self.on_blocking_task(|this| async move {
             let state = this.state_at(at)?;
-            this.estimate_gas_with(cfg, block_env, request, state, state_override)
+            this.estimate_gas_with(cfg, block_env, request, state, overrides)
         })
         .await
     }
@@ -79,15 +89,20 @@ where
     }
 
     /// Simulate arbitrary number of transactions at an arbitrary blockchain index, with the
-    /// optionality of state overrides
+    /// optionality of state overrides.
+    ///
+    /// The bundles are executed in order on a shared state, so every call sees the state changes
+    /// of the previous calls. The state overrides are applied once, before the first call. Each
+    /// following bundle increments the block number by 1 and the block timestamp by 12 seconds.
+    ///
+    /// The gas cap applies to the total gas of all calls.
     pub async fn call_many(
         &self,
-        bundle: Bundle,
+        bundles: Vec<Bundle>,
         state_context: Option<StateContext>,
         mut state_override: Option<StateOverride>,
-    ) -> EthResult<Vec<EthCallResponse>> {
-        let Bundle { transactions, block_override } = bundle;
-        if transactions.is_empty() {
+    ) -> EthResult<Vec<Vec<EthCallManyResponse>>> {
+        if bundles.is_empty() || bundles.iter().all(|bundle| bundle.transactions.is_empty()) {
             return Err(EthApiError::InvalidParams(String::from("transactions are empty.")))
         }
 
@@ -97,13 +112,13 @@ where
         let target_block = block_number.unwrap_or_default();
         let is_block_target_pending = target_block.is_pending();
 
-        let ((cfg, block_env, _), block) = futures::try_join!(
+        let ((mut cfg, mut block_env, _), block) = futures::try_join!(
             self.evm_env_at(target_block),
             self.block_with_senders(target_block)
         )?;
 
         let Some(block) = block else { return Err(EthApiError::UnknownBlockNumber) };
-        let gas_limit = self.inner.gas_cap;
+        let mut remaining_gas = self.inner.gas_cap;
 
         // we're essentially replaying the transactions in the block here, hence we need the state
         // that points to the beginning of the block, which is the state at the parent block
@@ -121,7 +136,7 @@ where
 
         let this = self.clone();
         self.spawn_with_state_at_block(at.into(), move |state| {
-            let mut results = Vec::with_capacity(transactions.len());
+            let mut all_bundles = Vec::with_capacity(bundles.len());
             let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
             if replay_block_txs {
@@ -137,40 +152,55 @@ where
                 }
             }
 
-            let block_overrides = block_override.map(Box::new);
-
-            let mut transactions = transactions.into_iter().peekable();
-            while let Some(tx) = transactions.next() {
-                // apply state overrides only once, before the first transaction
-                let state_overrides = state_override.take();
-                let overrides = EvmOverrides::new(state_overrides, block_overrides.clone());
-
-                let env = prepare_call_env(
-                    cfg.clone(),
-                    block_env.clone(),
-                    tx,
-                    gas_limit,
-                    &mut db,
-                    overrides,
-                )?;
-                let (res, _) = this.transact(&mut db, env)?;
-
-                match ensure_success(res.result) {
-                    Ok(output) => {
-                        results.push(EthCallResponse { value: Some(output), error: None });
-                    }
-                    Err(err) => {
-                        results.push(EthCallResponse { value: None, error: Some(err.to_string()) });
-                    }
-                }
+            // only the calls are limited by the configured memory limit
+            cfg.memory_limit = this.call_memory_limit();
+
+            for bundle in bundles {
+                let Bundle { transactions, block_override } = bundle;
+                let mut results = Vec::with_capacity(transactions.len());
+                let block_overrides = block_override.map(Box::new);
+
+                for tx in transactions {
+                    // apply state overrides only once, before the first transaction
+                    let state_overrides = state_override.take();
+                    let overrides = EvmOverrides::new(state_overrides, block_overrides.clone());
+
+                    let mut env = prepare_call_env(
+                        cfg.clone(),
+                        block_env.clone(),
+                        tx,
+                        remaining_gas,
+                        &mut db,
+                        overrides,
+                    )?;
+                    // the gas cap is shared by all calls
+                    env.tx.gas_limit = env.tx.gas_limit.min(remaining_gas);
+                    let (res, _) = this.transact(&mut db, env)?;
 
-                if transactions.peek().is_some() {
-                    // need to apply the state changes of this call before executing the next call
+                    let gas_used = res.result.gas_used();
+                    remaining_gas -= gas_used;
+                    let (value, error) = match ensure_success(res.result) {
+                        Ok(output) => (Some(output), None),
+                        Err(err) => (None, Some(err.to_string())),
+                    };
+                    results.push(EthCallManyResponse {
+                        value,
+                        gas_used: U64::from(gas_used),
+                        error,
+                    });
+
+                    // the state changes are only applied to the cache of this request
                     db.commit(res.state);
                 }
+
+                // Increment block_env number and timestamp for the next bundle
+                block_env.number += U256::from(1);
+                block_env.timestamp += U256::from(12);
+
+                all_bundles.push(results);
             }
 
-            Ok(results)
+            Ok(all_bundles)
         })
         .await
     }
@@ -180,12 +210,29 @@ where
     /// This will execute the [`TransactionRequest`] and find the best gas limit via binary search
     pub fn estimate_gas_with<S>(
         &self,
-        mut cfg: CfgEnvWithHandlerCfg,
+        cfg: CfgEnvWithHandlerCfg,
         block: BlockEnv,
         request: TransactionRequest,
         state: S,
-        state_override: Option<StateOverride>,
+        overrides: EvmOverrides,
     ) -> EthResult<U256>
+    where
+        S: StateProvider,
+    {
+        self.estimate_gas_and_count_executions(cfg, block, request, state, overrides)
+            .map(|(gas, _)| gas)
+    }
+
+    /// Estimates the gas usage of the `request` with the state, see
+    /// [`Self::estimate_gas_with`], and returns the number of executions of the transaction.
+    fn estimate_gas_and_count_executions<S>(
+        &self,
+        mut cfg: CfgEnvWithHandlerCfg,
+        mut block: BlockEnv,
+        request: TransactionRequest,
+        state: S,
+        overrides: EvmOverrides,
+    ) -> EthResult<(U256, usize)>
     where
         S: StateProvider,
     {
@@ -198,25 +245,41 @@ where
         // <https://github.com/ethereum/go-ethereum/blob/ee8e83fa5f6cb261dad2ed0a7bbcde4930c41e6c/internal/ethapi/api.go#L985>
         cfg.disable_base_fee = true;
 
+        let mut db = CacheDB::new(StateProviderDatabase::new(state));
+
+        // Apply any block overrides first, so they take effect when the evm env is created, e.g.
+        // the basefee and the block gas limit
+        if let Some(block_overrides) = overrides.block {
+            apply_block_overrides(*block_overrides, cfg.handler_cfg.spec_id, &mut db, &mut block)?;
+        }
+
         // Keep a copy of gas related request values
         let tx_request_gas_limit = request.gas;
         let tx_request_gas_price = request.gas_price;
         let block_env_gas_limit = block.gas_limit;
 
-        // Determine the highest possible gas limit, considering both the request's specified limit
-        // and the block's limit.
+        // The highest possible gas limit is the request's limit if specified, otherwise the
+        // block's limit, capped by the gas cap
         let mut highest_gas_limit = tx_request_gas_limit
-            .map(|tx_gas_limit| U256::from(tx_gas_limit).max(block_env_gas_limit))
-            .unwrap_or(block_env_gas_limit);
+            .map(U256::from)
+            .unwrap_or(block_env_gas_limit)
+            .min(U256::from(self.call_gas_limit()));
+
+        let mut executions = 0;
+        let mut transact = |db: &mut CacheDB<StateProviderDatabase<S>>, env| {
+            executions += 1;
+            self.transact(db, env)
+        };
 
         // Configure the evm env
         let mut env = build_call_evm_env(cfg, block, request)?;
-        let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
-        // Apply any state overrides if specified.
-        if let Some(state_override) = state_override {
+        // Apply any state overrides if specified, before the access list is prefetched so the
+        // overridden state isn't read from the database.
+        if let Some(state_override) = overrides.state {
             apply_state_overrides(state_override, &mut db)?;
         }
+        prefetch_access_list(&mut db, &env.tx.access_list)?;
 
         // Optimize for simple transfer transactions, potentially reducing the gas estimate.
         if env.tx.data.is_empty() {
@@ -231,9 +294,9 @@ where
                         // with the minimum gas limit to make sure.
                         let mut env = env.clone();
                         env.tx.gas_limit = MIN_TRANSACTION_GAS;
-                        if let Ok((res, _)) = self.transact(&mut db, env) {
+                        if let Ok((res, _)) = transact(&mut db, env) {
                             if res.result.is_success() {
-                                return Ok(U256::from(MIN_TRANSACTION_GAS))
+                                return Ok((U256::from(MIN_TRANSACTION_GAS), executions))
                             }
                         }
                     }
@@ -257,8 +320,9 @@ where
 
         trace!(target: "rpc::eth::estimate", ?env, "Starting gas estimation");
 
-        // Execute the transaction with the highest possible gas limit.
-        let (mut res, mut env) = match self.transact(&mut db, env.clone()) {
+        // Execute the transaction with the highest possible gas limit, this fails fast if the
+        // transaction reverts regardless of the gas limit.
+        let (mut res, mut env) = match transact(&mut db, env.clone()) {
             // Handle the exceptional case where the transaction initialization uses too much gas.
             // If the gas price or gas limit was specified in the request, retry the transaction
             // with the block's gas limit to determine if the failure was due to
@@ -296,25 +360,31 @@ where
         //
         // NOTE: this is the gas the transaction used, which is less than the
         // transaction requires to succeed.
-        let mut gas_used = res.result.gas_used();
-        // the lowest value is capped by the gas used by the unconstrained transaction
-        let mut lowest_gas_limit = gas_used.saturating_sub(1);
+        let gas_used = res.result.gas_used();
+        // the lowest value is capped by the gas used by the unconstrained transaction and by the
+        // intrinsic gas, which the transaction must always pay for
+        let intrinsic_gas = validate_initial_tx_gas(
+            env.handler_cfg.spec_id,
+            &env.tx.data,
+            env.tx.transact_to.is_create(),
+            &env.tx.access_list,
+        );
+        let mut lowest_gas_limit = gas_used.max(intrinsic_gas).saturating_sub(1);
 
         // As stated in Geth, there is a good chance that the transaction will pass if we set the
         // gas limit to the execution gas used plus the gas refund, so we check this first
         // <https://github.com/ethereum/go-ethereum/blob/a5a4fa7032bb248f5a7c40f4e8df2b131c4186a4/eth/gasestimator/gasestimator.go#L135
         //
-        // Calculate the optimistic gas limit by adding gas used and gas refund,
-        // then applying a 64/63 multiplier to account for gas forwarding rules.
-        let optimistic_gas_limit = (gas_used + gas_refund) * 64 / 63;
+        // Calculate the optimistic gas limit by adding gas used, gas refund and the call stipend,
+        // which an `SSTORE` at the end of the execution requires to be left, then applying a
+        // 64/63 multiplier to account for gas forwarding rules.
+        let optimistic_gas_limit = (gas_used + gas_refund + CALL_STIPEND) * 64 / 63;
         if optimistic_gas_limit < highest_gas_limit {
             // Set the transaction's gas limit to the calculated optimistic gas limit.
             env.tx.gas_limit = optimistic_gas_limit;
             // Re-execute the transaction with the new gas limit and update the result and
             // environment.
-            (res, env) = self.transact(&mut db, env)?;
-            // Update the gas used based on the new result.
-            gas_used = res.result.gas_used();
+            (res, env) = transact(&mut db, env)?;
             // Update the gas limit estimates (highest and lowest) based on the execution result.
             update_estimated_gas_range(
                 res.result,
@@ -325,10 +395,7 @@ where
         };
 
         // Pick a point that's close to the estimated gas
-        let mut mid_gas_limit = std::cmp::min(
-            gas_used * 3,
-            ((highest_gas_limit as u128 + lowest_gas_limit as u128) / 2) as u64,
-        );
+        let mut mid_gas_limit = binary_search_midpoint(lowest_gas_limit, highest_gas_limit);
 
         trace!(target: "rpc::eth::estimate", ?env, ?highest_gas_limit, ?lowest_gas_limit, ?mid_gas_limit, "Starting binary search for gas");
 
@@ -347,7 +414,7 @@ where
             env.tx.gas_limit = mid_gas_limit;
 
             // Execute transaction and handle potential gas errors, adjusting limits accordingly.
-            match self.transact(&mut db, env.clone()) {
+            match transact(&mut db, env.clone()) {
                 // Check if the error is due to gas being too high.
                 Err(EthApiError::InvalidTransaction(RpcInvalidTransactionError::GasTooHigh)) => {
                     // Increase the lowest gas limit if gas is too high
@@ -368,31 +435,42 @@ where
             }
 
             // New midpoint
-            mid_gas_limit = ((highest_gas_limit as u128 + lowest_gas_limit as u128) / 2) as u64;
+            mid_gas_limit = binary_search_midpoint(lowest_gas_limit, highest_gas_limit);
         }
 
-        Ok(U256::from(highest_gas_limit))
+        trace!(target: "rpc::eth::estimate", ?highest_gas_limit, ?executions, "Estimated gas");
+        Ok((U256::from(highest_gas_limit), executions))
     }
 
-    /// Creates the `AccessList` for the `request` at the [`BlockId`] or latest.
+    /// Creates the `AccessList` for the `request` at the [`BlockId`] or latest, with the given
+    /// state overrides applied.
     pub(crate) async fn create_access_list_at(
         &self,
         request: TransactionRequest,
         block_number: Option<BlockId>,
+        state_override: Option<StateOverride>,
     ) -> EthResult<AccessListWithGasUsed> {
         self.on_blocking_task(|this| async move {
-            this.create_access_list_with(request, block_number).await
+            this.create_access_list_with(request, block_number, state_override).await
         })
         .await
     }
 
+    /// Creates the access list by executing the request with the access list of the previous
+    /// execution, until the access list is stable.
+    ///
+    /// Applying an access list warms up the listed accounts and slots, which changes the gas
+    /// costs of the execution and therefore possibly its code path. The returned gas used is the
+    /// gas used of the execution with the returned access list.
     async fn create_access_list_with(
         &self,
         mut request: TransactionRequest,
         at: Option<BlockId>,
+        state_override: Option<StateOverride>,
     ) -> EthResult<AccessListWithGasUsed> {
         let block_id = at.unwrap_or_default();
-        let (cfg, block, at) = self.evm_env_at(block_id).await?;
+        let (mut cfg, block, at) = self.evm_env_at(block_id).await?;
+        cfg.memory_limit = self.call_memory_limit();
         let state = self.state_at(at)?;
 
         let mut env = build_call_evm_env(cfg, block, request.clone())?;
@@ -408,6 +486,10 @@ where
 
         let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
+        if let Some(state_override) = state_override {
+            apply_state_overrides(state_override, &mut db)?;
+        }
+
         if request.gas.is_none() && env.tx.gas_price > U256::ZERO {
             // no gas limit was provided in the request, so we need to cap the request's gas limit
             cap_tx_gas_limit_with_caller_allowance(&mut db, &mut env.tx)?;
@@ -421,35 +503,46 @@ where
             from.create(nonce)
         };
 
-        // can consume the list since we're not using the request anymore
-        let initial = request.access_list.take().unwrap_or_default();
+        // the sender, the recipient and the precompiles are warm anyway
+        let excluded = get_precompiles(env.handler_cfg.spec_id)
+            .into_iter()
+            .chain([from, to])
+            .collect::<HashSet<_>>();
 
-        let precompiles = get_precompiles(env.handler_cfg.spec_id);
-        let mut inspector = AccessListInspector::new(initial, from, to, precompiles);
-        let (result, env) = self.inspect(&mut db, env, &mut inspector)?;
+        // can consume the list since we're not using the request anymore
+        let mut access_list =
+            exclude_accounts(request.access_list.take().unwrap_or_default(), &excluded);
+
+        let mut iterations = 0;
+        loop {
+            iterations += 1;
+            env.tx.access_list = access_list.clone().into_flattened();
+
+            let mut inspector =
+                AccessListInspector::new(access_list.clone(), from, to, excluded.iter().copied());
+            let (result, tx_env) = self.inspect(&mut db, env, &mut inspector)?;
+            env = tx_env;
+
+            let gas_used = match result.result {
+                ExecutionResult::Halt { reason, .. } => Err(match reason {
+                    HaltReason::NonceOverflow => RpcInvalidTransactionError::NonceMaxValue,
+                    halt => RpcInvalidTransactionError::EvmHalt(halt),
+                }),
+                ExecutionResult::Revert { output, .. } => {
+                    Err(RpcInvalidTransactionError::Revert(RevertError::new(output)))
+                }
+                ExecutionResult::Success { gas_used, .. } => Ok(gas_used),
+            }?;
 
-        match result.result {
-            ExecutionResult::Halt { reason, .. } => Err(match reason {
-                HaltReason::NonceOverflow => RpcInvalidTransactionError::NonceMaxValue,
-                halt => RpcInvalidTransactionError::EvmHalt(halt),
-            }),
-            ExecutionResult::Revert { output, .. } => {
-                Err(RpcInvalidTransactionError::Revert(RevertError::new(output)))
+            let next = exclude_accounts(inspector.into_access_list(), &excluded);
+            if next == access_list || iterations >= MAX_ACCESS_LIST_ITERATIONS {
+                if next != access_list {
+                    trace!(target: "rpc::eth", ?iterations, "Access list did not converge");
+                }
+                return Ok(AccessListWithGasUsed { access_list, gas_used: U256::from(gas_used) })
             }
-            ExecutionResult::Success { .. } => Ok(()),
-        }?;
-
-        let access_list = inspector.into_access_list();
-
-        let cfg_with_spec_id =
-            CfgEnvWithHandlerCfg { cfg_env: env.cfg.clone(), handler_cfg: env.handler_cfg };
-
-        // calculate the gas used using the access list
-        request.access_list = Some(access_list.clone());
-        let gas_used =
-            self.estimate_gas_with(cfg_with_spec_id, env.block.clone(), request, &*db.db, None)?;
-
-        Ok(AccessListWithGasUsed { access_list, gas_used })
+            access_list = next;
+        }
     }
 
     /// Executes the requests again after an out of gas error to check if the error is gas related
@@ -487,6 +580,29 @@ where
     }
 }
 
+/// Removes the excluded accounts from the access list, unless storage slots of the account are
+/// accessed.
+fn exclude_accounts(access_list: AccessList, excluded: &HashSet<Address>) -> AccessList {
+    AccessList(
+        access_list
+            .0
+            .into_iter()
+            .filter(|item| !item.storage_keys.is_empty() || !excluded.contains(&item.address))
+            .collect(),
+    )
+}
+
+/// Returns the next gas limit of the binary search between the lowest failing and the highest
+/// succeeding gas limit.
+///
+/// Most transactions don't need a much higher gas limit than the gas they use, so the midpoint is
+/// skewed towards the lowest gas limit.
+#[inline]
+fn binary_search_midpoint(lowest_gas_limit: u64, highest_gas_limit: u64) -> u64 {
+    let mid = ((highest_gas_limit as u128 + lowest_gas_limit as u128) / 2) as u64;
+    mid.min(lowest_gas_limit.saturating_mul(2))
+}
+
 /// Updates the highest and lowest gas limits for binary search based on the execution result.
 ///
 /// This function refines the gas limit estimates used in a binary search to find the optimal gas
@@ -531,3 +647,172 @@ fn update_estimated_gas_range(
     };
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::test_utils::build_test_eth_api;
+    use reth_primitives::{address, hex, Address, Block, Header, B256};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_types::{BlockOverrides, TransactionInput};
+    use revm::primitives::SpecId;
+
+    const CONTRACT: Address = address!("c000000000000000000000000000000000000000");
+
+    /// Returns a provider with a block and the given code deployed at [`CONTRACT`], and the hash
+    /// of the block.
+    fn provider_with_contract(code: &'static [u8]) -> (MockEthProvider, B256) {
+        let provider = MockEthProvider::default();
+        let header = Header { number: 1, ..Default::default() }.seal_slow();
+        let hash = header.hash();
+        provider.add_block(hash, Block { header: header.unseal(), ..Default::default() });
+        provider.add_account(
+            CONTRACT,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(Bytes::from_static(code)),
+        );
+        (provider, hash)
+    }
+
+    fn block_overrides(time: Option<u64>) -> Box<BlockOverrides> {
+        Box::new(BlockOverrides {
+            number: Some(U256::from(1234)),
+            time,
+            gas_limit: Some(30_000_000),
+            base_fee: Some(U256::from(7)),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn call_with_block_overrides() {
+        // returns `TIMESTAMP`, `NUMBER` and `BASEFEE`
+        let (provider, hash) = provider_with_contract(&hex!("42600052436020524860405260606000f3"));
+        let eth_api = build_test_eth_api(provider);
+        let request = TransactionRequest { to: Some(TxKind::Call(CONTRACT)), ..Default::default() };
+
+        let overrides = EvmOverrides::new(None, Some(block_overrides(Some(1_700_000_000))));
+        let output = eth_api.call(request.clone(), Some(hash.into()), overrides).await.unwrap();
+        let expected = [U256::from(1_700_000_000), U256::from(1234), U256::from(7)]
+            .iter()
+            .flat_map(|word| word.to_be_bytes::<32>())
+            .collect::<Vec<_>>();
+        assert_eq!(output, Bytes::from(expected));
+
+        // the fee cap is checked against the overridden basefee
+        let request = TransactionRequest { max_fee_per_gas: Some(5), ..request };
+        let overrides = EvmOverrides::new(None, Some(block_overrides(None)));
+        let err = eth_api.call(request, Some(hash.into()), overrides).await.unwrap_err();
+        assert!(matches!(
+            err,
+            EthApiError::InvalidTransaction(RpcInvalidTransactionError::FeeCapTooLow)
+        ));
+    }
+
+    #[tokio::test]
+    async fn estimate_gas_with_block_overrides() {
+        // reverts unless `TIMESTAMP` is 1_700_000_000
+        let (provider, hash) = provider_with_contract(&hex!("42636553f10014600e57600080fd5b00"));
+        let eth_api = build_test_eth_api(provider);
+        let request = TransactionRequest { to: Some(TxKind::Call(CONTRACT)), ..Default::default() };
+
+        let overrides = EvmOverrides::new(None, Some(block_overrides(None)));
+        let err = eth_api.estimate_gas_at(request.clone(), hash.into(), overrides).await;
+        assert!(matches!(
+            err,
+            Err(EthApiError::InvalidTransaction(RpcInvalidTransactionError::Revert(_)))
+        ));
+
+        let overrides = EvmOverrides::new(None, Some(block_overrides(Some(1_700_000_000))));
+        let gas = eth_api.estimate_gas_at(request, hash.into(), overrides).await.unwrap();
+        assert!(gas > U256::from(MIN_TRANSACTION_GAS));
+    }
+
+    #[tokio::test]
+    async fn estimate_erc20_transfer_executions() {
+        // `transfer(to, amount)` of a token with the balances stored at the addresses, guarded by
+        // a lock in slot 0 that is released with an `SSTORE` at the end
+        const CODE: &[u8] = &hex!(
+            "60026000553354602435818111602d5780820333556004358054820190556001600055600160005260206000f35b600080fd"
+        );
+        let (provider, hash) = provider_with_contract(CODE);
+        let sender = Address::with_last_byte(1);
+        provider.add_account(
+            CONTRACT,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(Bytes::from_static(CODE))
+                .extend_storage([
+                    (B256::ZERO, U256::from(1)),
+                    (sender.into_word(), U256::from(1000)),
+                ]),
+        );
+        let eth_api = build_test_eth_api(provider.clone());
+
+        let mut input = hex!("a9059cbb").to_vec();
+        input.extend_from_slice(Address::repeat_byte(0x22).into_word().as_slice());
+        input.extend_from_slice(&U256::from(100).to_be_bytes::<32>());
+        let request = TransactionRequest {
+            from: Some(sender),
+            to: Some(TxKind::Call(CONTRACT)),
+            input: TransactionInput::new(input.into()),
+            ..Default::default()
+        };
+
+        let cfg = CfgEnvWithHandlerCfg::new_with_spec_id(Default::default(), SpecId::CANCUN);
+        let (gas, executions) = eth_api
+            .estimate_gas_and_count_executions(
+                cfg,
+                BlockEnv::default(),
+                request.clone(),
+                provider.latest().unwrap(),
+                EvmOverrides::default(),
+            )
+            .unwrap();
+        // an execution with the highest gas limit, the optimistic gas limit that leaves the call
+        // stipend for the final `SSTORE` and a few steps of the binary search
+        assert!(executions <= 5, "{executions} executions");
+
+        let request = TransactionRequest { gas: Some(gas.to()), ..request };
+        let output = eth_api.call(request, Some(hash.into()), Default::default()).await.unwrap();
+        assert_eq!(output, Bytes::from(U256::from(1).to_be_bytes_vec()));
+    }
+
+    #[tokio::test]
+    async fn call_many_with_shared_state() {
+        // a minimal allowance: calls with calldata approve the amount in the first word, calls
+        // without calldata spend the allowance and revert if there is none
+        let (provider, hash) = provider_with_contract(&hex!(
+            "3615600c57600035600055005b600054601757600080fd5b6000600055600160005260206000f3"
+        ));
+        let eth_api = build_test_eth_api(provider);
+        let transfer_from =
+            TransactionRequest { to: Some(TxKind::Call(CONTRACT)), ..Default::default() };
+        let approve = TransactionRequest {
+            input: TransactionInput::new(Bytes::from(U256::from(100).to_be_bytes_vec())),
+            ..transfer_from.clone()
+        };
+        let state_context =
+            || Some(StateContext { block_number: Some(hash.into()), transaction_index: None });
+
+        let bundles = vec![
+            Bundle { transactions: vec![approve, transfer_from.clone()], block_override: None },
+            Bundle { transactions: vec![transfer_from.clone()], block_override: None },
+        ];
+        let results = eth_api.call_many(bundles, state_context(), None).await.unwrap();
+        let [approval, second_bundle] = &results[..] else { panic!("expected two bundles") };
+
+        // the transfer sees the approval
+        assert_eq!(approval[0].error, None);
+        assert_eq!(approval[1].error, None);
+        assert_eq!(approval[1].value, Some(Bytes::from(U256::from(1).to_be_bytes_vec())));
+        assert!(approval[1].gas_used > U64::from(MIN_TRANSACTION_GAS));
+
+        // the allowance was spent by the first bundle
+        assert_eq!(second_bundle[0].value, None);
+        assert!(second_bundle[0].error.is_some());
+
+        // nothing is committed
+        let bundles = vec![Bundle { transactions: vec![transfer_from], block_override: None }];
+        let results = eth_api.call_many(bundles, state_context(), None).await.unwrap();
+        assert!(results[0][0].error.is_some());
+    }
+}
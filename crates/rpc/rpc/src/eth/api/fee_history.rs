@@ -1,43 +1,40 @@
 //! Consist of types adjacent to the fee history cache and its configs
 
-use crate::eth::{cache::EthStateCache, error::EthApiError};
-use futures::{
-    future::{Fuse, FusedFuture},
-    FutureExt, Stream, StreamExt,
-};
-use metrics::atomics::AtomicU64;
+use crate::eth::{cache::EthStateCache, error::EthResult};
+use futures::{future::try_join_all, Stream, StreamExt};
+use parking_lot::Mutex;
 use reth_primitives::{
-    basefee::calc_next_block_base_fee,
-    eip4844::{calc_blob_gasprice, calculate_excess_blob_gas},
-    ChainSpec, Receipt, SealedBlock, TransactionSigned, B256,
+    basefee::calc_next_block_base_fee, eip4844::BlobParams, Address, ChainSpec, Receipt,
+    SealedBlockWithSenders, SealedHeader, TransactionSigned, B256,
 };
-use reth_provider::{BlockReaderIdExt, CanonStateNotification, ChainSpecProvider};
-use reth_rpc_server_types::constants::gas_oracle::MAX_HEADER_HISTORY;
+use reth_provider::CanonStateNotification;
+use reth_rpc_server_types::constants::cache::DEFAULT_FEE_HISTORY_CACHE_MAX_LEN;
 use reth_rpc_types::TxGasAndReward;
+use schnellru::{ByLength, LruMap};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, VecDeque},
-    fmt::Debug,
-    sync::{atomic::Ordering::SeqCst, Arc},
+    fmt::{self, Debug},
+    sync::Arc,
 };
 use tracing::trace;
 
-/// Contains cached fee history entries for blocks.
+/// Contains cached fee history entries for blocks, keyed by block hash.
 ///
-/// Purpose for this is to provide cached data for `eth_feeHistory`.
+/// Purpose for this is to provide cached data for `eth_feeHistory`, so the receipts of the blocks
+/// don't need to be read again to compute the reward percentiles.
 #[derive(Debug, Clone)]
 pub struct FeeHistoryCache {
     inner: Arc<FeeHistoryCacheInner>,
 }
 
 impl FeeHistoryCache {
-    /// Creates new `FeeHistoryCache` instance, initialize it with the more recent data, set bounds
+    /// Creates new `FeeHistoryCache` instance
     pub fn new(eth_cache: EthStateCache, config: FeeHistoryCacheConfig) -> Self {
         let inner = FeeHistoryCacheInner {
-            lower_bound: Default::default(),
-            upper_bound: Default::default(),
+            entries: Mutex::new(LruMap::new(ByLength::new(
+                config.max_blocks.try_into().unwrap_or(u32::MAX),
+            ))),
             config,
-            entries: Default::default(),
             eth_cache,
         };
         Self { inner: Arc::new(inner) }
@@ -55,111 +52,64 @@ impl FeeHistoryCache {
         self.config().resolution
     }
 
-    /// Returns all blocks that are missing in the cache in the [`lower_bound`, `upper_bound`]
-    /// range.
+    /// Generates predefined set of percentiles
     ///
-    /// This function is used to populate the cache with missing blocks, which can happen if the
-    /// node switched to stage sync node.
-    async fn missing_consecutive_blocks(&self) -> VecDeque<u64> {
-        let entries = self.inner.entries.read().await;
-        (self.lower_bound()..self.upper_bound())
-            .rev()
-            .filter(|&block_number| !entries.contains_key(&block_number))
-            .collect()
+    /// This returns 100 * resolution points
+    pub fn predefined_percentiles(&self) -> Vec<f64> {
+        let res = self.resolution() as f64;
+        (0..=100 * self.resolution()).map(|p| p as f64 / res).collect()
     }
 
     /// Insert block data into the cache.
-    async fn insert_blocks<I>(&self, blocks: I)
+    fn insert_blocks<'a, I>(&self, blocks: I)
     where
-        I: IntoIterator<Item = (SealedBlock, Arc<Vec<Receipt>>)>,
+        I: IntoIterator<Item = (&'a SealedBlockWithSenders, Vec<Receipt>)>,
     {
-        let mut entries = self.inner.entries.write().await;
-
-        let percentiles = self.predefined_percentiles();
-        // Insert all new blocks and calculate approximated rewards
+        let mut entries = self.inner.entries.lock();
         for (block, receipts) in blocks {
-            let mut fee_history_entry = FeeHistoryEntry::new(&block);
-            fee_history_entry.rewards = calculate_reward_percentiles_for_block(
-                &percentiles,
-                fee_history_entry.gas_used,
-                fee_history_entry.base_fee_per_gas,
+            let entry = FeeHistoryEntry::new(&block.header).with_rewards(
                 &block.body,
+                &block.senders,
                 &receipts,
-            )
-            .unwrap_or_default();
-            entries.insert(block.number, fee_history_entry);
-        }
-
-        // enforce bounds by popping the oldest entries
-        while entries.len() > self.inner.config.max_blocks as usize {
-            entries.pop_first();
-        }
-
-        if entries.len() == 0 {
-            self.inner.upper_bound.store(0, SeqCst);
-            self.inner.lower_bound.store(0, SeqCst);
-            return
-        }
-
-        let upper_bound = *entries.last_entry().expect("Contains at least one entry").key();
-
-        // also enforce proper lower bound in case we have gaps
-        let target_lower = upper_bound.saturating_sub(self.inner.config.max_blocks);
-        while entries.len() > 1 && *entries.first_key_value().unwrap().0 < target_lower {
-            entries.pop_first();
+            );
+            entries.insert(block.hash(), entry);
         }
-
-        let lower_bound = *entries.first_entry().expect("Contains at least one entry").key();
-        self.inner.upper_bound.store(upper_bound, SeqCst);
-        self.inner.lower_bound.store(lower_bound, SeqCst);
     }
 
-    /// Get `UpperBound` value for `FeeHistoryCache`
-    pub fn upper_bound(&self) -> u64 {
-        self.inner.upper_bound.load(SeqCst)
+    /// Returns the cached entry of the block with the given hash.
+    pub fn get(&self, block_hash: &B256) -> Option<FeeHistoryEntry> {
+        self.inner.entries.lock().get(block_hash).cloned()
     }
 
-    /// Get `LowerBound` value for `FeeHistoryCache`
-    pub fn lower_bound(&self) -> u64 {
-        self.inner.lower_bound.load(SeqCst)
+    /// Returns the entries of the given headers, including the rewards of their transactions.
+    ///
+    /// Entries that are not cached yet are computed from the receipts of the block and cached. If
+    /// the receipts of a block are not available, because they were pruned, the entry has no
+    /// rewards and is not cached.
+    pub async fn get_history(&self, headers: &[SealedHeader]) -> EthResult<Vec<FeeHistoryEntry>> {
+        try_join_all(headers.iter().map(|header| self.get_or_fetch(header))).await
     }
 
-    /// Collect fee history for given range.
-    ///
-    /// This function retrieves fee history entries from the cache for the specified range.
-    /// If the requested range (`start_block` to `end_block`) is within the cache bounds,
-    /// it returns the corresponding entries.
-    /// Otherwise it returns None.
-    pub async fn get_history(
-        &self,
-        start_block: u64,
-        end_block: u64,
-    ) -> Option<Vec<FeeHistoryEntry>> {
-        let lower_bound = self.lower_bound();
-        let upper_bound = self.upper_bound();
-        if start_block >= lower_bound && end_block <= upper_bound {
-            let entries = self.inner.entries.read().await;
-            let result = entries
-                .range(start_block..=end_block)
-                .map(|(_, fee_entry)| fee_entry.clone())
-                .collect::<Vec<_>>();
-
-            if result.is_empty() {
-                return None
-            }
+    /// Returns the entry of the given header, computing it from the receipts if it isn't cached.
+    async fn get_or_fetch(&self, header: &SealedHeader) -> EthResult<FeeHistoryEntry> {
+        let block_hash = header.hash();
+        if let Some(entry) = self.get(&block_hash) {
+            return Ok(entry)
+        }
 
-            Some(result)
-        } else {
-            None
+        trace!(target: "rpc::fee", ?block_hash, "Fetching missing block for fee history cache");
+        let entry = FeeHistoryEntry::new(header);
+        let block = self.inner.eth_cache.get_block_with_senders(block_hash);
+        let receipts = self.inner.eth_cache.get_receipts(block_hash);
+        let (block, receipts) = futures::try_join!(block, receipts)?;
+        let Some((block, receipts)) = block.zip(receipts) else { return Ok(entry) };
+        if block.body.len() != receipts.len() || block.body.len() != block.senders.len() {
+            return Ok(entry)
         }
-    }
 
-    /// Generates predefined set of percentiles
-    ///
-    /// This returns 100 * resolution points
-    pub fn predefined_percentiles(&self) -> Vec<f64> {
-        let res = self.resolution() as f64;
-        (0..=100 * self.resolution()).map(|p| p as f64 / res).collect()
+        let entry = entry.with_rewards(&block.body, &block.senders, &receipts);
+        self.inner.entries.lock().insert(block_hash, entry.clone());
+        Ok(entry)
     }
 }
 
@@ -169,116 +119,75 @@ impl FeeHistoryCache {
 pub struct FeeHistoryCacheConfig {
     /// Max number of blocks in cache.
     ///
-    /// Default is [`MAX_HEADER_HISTORY`] plus some change to also serve slightly older blocks from
-    /// cache, since `fee_history` supports the entire range
+    /// Default is [`DEFAULT_FEE_HISTORY_CACHE_MAX_LEN`], to serve the entire range of
+    /// `eth_feeHistory` from the cache.
     pub max_blocks: u64,
     /// Percentile approximation resolution
     ///
     /// Default is 4 which means 0.25
+    ///
+    /// Rewards are computed exactly from the cached transactions, this only affects
+    /// [`FeeHistoryCache::predefined_percentiles`].
     pub resolution: u64,
 }
 
 impl Default for FeeHistoryCacheConfig {
     fn default() -> Self {
-        Self { max_blocks: MAX_HEADER_HISTORY + 100, resolution: 4 }
+        Self { max_blocks: DEFAULT_FEE_HISTORY_CACHE_MAX_LEN, resolution: 4 }
     }
 }
 
 /// Container type for shared state in [`FeeHistoryCache`]
-#[derive(Debug)]
 struct FeeHistoryCacheInner {
-    /// Stores the lower bound of the cache
-    lower_bound: AtomicU64,
-    /// Stores the upper bound of the cache
-    upper_bound: AtomicU64,
-    /// Config for `FeeHistoryCache`, consists of resolution for percentile approximation
-    /// and max number of blocks
+    /// Config for `FeeHistoryCache`, consists of the max number of blocks
     config: FeeHistoryCacheConfig,
-    /// Stores the entries of the cache
-    entries: tokio::sync::RwLock<BTreeMap<u64, FeeHistoryEntry>>,
+    /// Stores the entries of the cache, by block hash
+    entries: Mutex<LruMap<B256, FeeHistoryEntry, ByLength>>,
     eth_cache: EthStateCache,
 }
 
+impl Debug for FeeHistoryCacheInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FeeHistoryCacheInner")
+            .field("config", &self.config)
+            .field("entries", &self.entries.lock().len())
+            .field("eth_cache", &self.eth_cache)
+            .finish()
+    }
+}
+
 /// Awaits for new chain events and directly inserts them into the cache so they're available
 /// immediately before they need to be fetched from disk.
-pub async fn fee_history_cache_new_blocks_task<St, Provider>(
+///
+/// Older blocks are added to the cache once they are requested.
+pub async fn fee_history_cache_new_blocks_task<St>(
     fee_history_cache: FeeHistoryCache,
     mut events: St,
-    provider: Provider,
 ) where
     St: Stream<Item = CanonStateNotification> + Unpin + 'static,
-    Provider: BlockReaderIdExt + ChainSpecProvider + 'static,
 {
-    // We're listening for new blocks emitted when the node is in live sync.
-    // If the node transitions to stage sync, we need to fetch the missing blocks
-    let mut missing_blocks = VecDeque::new();
-    let mut fetch_missing_block = Fuse::terminated();
-
-    loop {
-        if fetch_missing_block.is_terminated() {
-            if let Some(block_number) = missing_blocks.pop_front() {
-                trace!(target: "rpc::fee", ?block_number, "Fetching missing block for fee history cache");
-                if let Ok(Some(hash)) = provider.block_hash(block_number) {
-                    // fetch missing block
-                    fetch_missing_block = fee_history_cache
-                        .inner
-                        .eth_cache
-                        .get_block_and_receipts(hash)
-                        .boxed()
-                        .fuse();
-                }
-            }
-        }
-
-        tokio::select! {
-            res = &mut fetch_missing_block =>  {
-                if let Ok(res) = res {
-                    fee_history_cache.insert_blocks(res.into_iter()).await;
-                }
-            }
-            event = events.next() =>  {
-                let Some(event) = event else {
-                     // the stream ended, we are done
-                    break;
-                };
-                let (blocks, receipts): (Vec<_>, Vec<_>) = event
-                    .committed()
-                    .blocks_and_receipts()
-                    .map(|(block, receipts)| {
-                        (block.block.clone(), Arc::new(receipts.iter().flatten().cloned().collect::<Vec<_>>()))
-                    })
-                    .unzip();
-                fee_history_cache.insert_blocks(blocks.into_iter().zip(receipts)).await;
-
-                // keep track of missing blocks
-                missing_blocks = fee_history_cache.missing_consecutive_blocks().await;
-            }
-        }
+    while let Some(event) = events.next().await {
+        fee_history_cache.insert_blocks(
+            event
+                .committed()
+                .blocks_and_receipts()
+                .map(|(block, receipts)| (block, receipts.iter().flatten().cloned().collect())),
+        );
     }
 }
 
-/// Calculates reward percentiles for transactions in a block header.
-/// Given a list of percentiles and a sealed block header, this function computes
-/// the corresponding rewards for the transactions at each percentile.
-///
-/// The results are returned as a vector of U256 values.
-pub(crate) fn calculate_reward_percentiles_for_block(
-    percentiles: &[f64],
-    gas_used: u64,
+/// Returns the gas used and the effective priority fee of every transaction, in block order.
+pub(crate) fn transaction_rewards(
     base_fee_per_gas: u64,
     transactions: &[TransactionSigned],
     receipts: &[Receipt],
-) -> Result<Vec<u128>, EthApiError> {
-    let mut transactions = transactions
+) -> Vec<TxGasAndReward> {
+    transactions
         .iter()
         .zip(receipts)
         .scan(0, |previous_gas, (tx, receipt)| {
             // Convert the cumulative gas used in the receipts
             // to the gas usage by the transaction
-            //
-            // While we will sum up the gas again later, it is worth
-            // noting that the order of the transactions will be different,
-            // so the sum will also be different for each receipt.
             let gas_used = receipt.cumulative_gas_used - *previous_gas;
             *previous_gas = receipt.cumulative_gas_used;
 
@@ -287,34 +196,63 @@ pub(crate) fn calculate_reward_percentiles_for_block(
                 reward: tx.effective_tip_per_gas(Some(base_fee_per_gas)).unwrap_or_default(),
             })
         })
-        .collect::<Vec<_>>();
+        .collect()
+}
 
-    // Sort the transactions by their rewards in ascending order
-    transactions.sort_by_key(|tx| tx.reward);
+/// Calculates the reward percentiles of a block, given its transactions sorted by their rewards.
+///
+/// The percentiles are weighted by the gas used: every transaction is placed at the midpoint of
+/// the gas it occupies in the block, and the reward at a percentile of the gas used by the block
+/// is linearly interpolated between the rewards of the two surrounding transactions. Percentiles
+/// before the first or after the last midpoint return the lowest or highest reward. Empty blocks
+/// return a row of zeros.
+///
+/// The percentiles must be monotonically increasing.
+pub(crate) fn calculate_reward_percentiles(
+    percentiles: &[f64],
+    gas_used: u64,
+    transactions: &[TxGasAndReward],
+) -> Vec<u128> {
+    if transactions.is_empty() {
+        return vec![0; percentiles.len()]
+    }
 
-    // Find the transaction that corresponds to the given percentile
+    // The midpoints are kept in units of half gas, so they stay integers.
+    let midpoint =
+        |gas_before: u64, tx: &TxGasAndReward| 2 * gas_before as u128 + tx.gas_used as u128;
+
+    // Find the transactions that surround the given percentile
     //
     // We use a `tx_index` here that is shared across all percentiles, since we know
     // the percentiles are monotonically increasing.
     let mut tx_index = 0;
-    let mut cumulative_gas_used = transactions.first().map(|tx| tx.gas_used).unwrap_or_default();
-    let mut rewards_in_block = Vec::new();
+    let mut gas_before = 0;
+    let mut rewards_in_block = Vec::with_capacity(percentiles.len());
     for percentile in percentiles {
-        // Empty blocks should return in a zero row
-        if transactions.is_empty() {
-            rewards_in_block.push(0);
-            continue
-        }
-
-        let threshold = (gas_used as f64 * percentile / 100.) as u64;
-        while cumulative_gas_used < threshold && tx_index < transactions.len() - 1 {
+        let threshold = (2. * gas_used as f64 * percentile / 100.) as u128;
+        while tx_index < transactions.len() - 1 &&
+            midpoint(gas_before + transactions[tx_index].gas_used, &transactions[tx_index + 1]) <=
+                threshold
+        {
+            gas_before += transactions[tx_index].gas_used;
             tx_index += 1;
-            cumulative_gas_used += transactions[tx_index].gas_used;
         }
-        rewards_in_block.push(transactions[tx_index].reward);
+
+        let lower = &transactions[tx_index];
+        let lower_midpoint = midpoint(gas_before, lower);
+        let reward = match transactions.get(tx_index + 1) {
+            Some(upper) if threshold > lower_midpoint => {
+                let upper_midpoint = midpoint(gas_before + lower.gas_used, upper);
+                lower.reward +
+                    (upper.reward - lower.reward) * (threshold - lower_midpoint) /
+                        (upper_midpoint - lower_midpoint)
+            }
+            _ => lower.reward,
+        };
+        rewards_in_block.push(reward);
     }
 
-    Ok(rewards_in_block)
+    rewards_in_block
 }
 
 /// A cached entry for a block's fee history.
@@ -324,14 +262,6 @@ pub struct FeeHistoryEntry {
     pub base_fee_per_gas: u64,
     /// Gas used ratio this block.
     pub gas_used_ratio: f64,
-    /// The base per blob gas for EIP-4844.
-    /// For pre EIP-4844 equals to zero.
-    pub base_fee_per_blob_gas: Option<u128>,
-    /// Blob gas used ratio for this block.
-    ///
-    /// Calculated as the ratio of blob gas used and the available blob data gas per block.
-    /// Will be zero if no blob gas was used or pre EIP-4844.
-    pub blob_gas_used_ratio: f64,
     /// The excess blob gas of the block.
     pub excess_blob_gas: Option<u64>,
     /// The total amount of blob gas consumed by the transactions within the block,
@@ -343,31 +273,104 @@ pub struct FeeHistoryEntry {
     pub gas_limit: u64,
     /// Hash of the block.
     pub header_hash: B256,
-    /// Approximated rewards for the configured percentiles.
-    pub rewards: Vec<u128>,
+    /// The gas used and the effective priority fee of the transactions, sorted by the fee.
+    ///
+    /// `None` if the receipts of the block are not available.
+    pub transaction_rewards: Option<Arc<Vec<TxGasAndReward>>>,
+    /// The gas used and the effective priority fee of the transactions sent by the beneficiary
+    /// of the block, sorted by the fee.
+    ///
+    /// These are part of the [`Self::transaction_rewards`] as well.
+    pub beneficiary_rewards: Vec<TxGasAndReward>,
+    /// The beneficiary of the block.
+    pub beneficiary: Address,
     /// The timestamp of the block.
     pub timestamp: u64,
 }
 
 impl FeeHistoryEntry {
-    /// Creates a new entry from a sealed block.
+    /// Creates a new entry from a sealed header.
     ///
-    /// Note: This does not calculate the rewards for the block.
-    pub fn new(block: &SealedBlock) -> Self {
+    /// Note: This does not calculate the rewards for the block, see [`Self::with_rewards`].
+    pub fn new(header: &SealedHeader) -> Self {
         Self {
-            base_fee_per_gas: block.base_fee_per_gas.unwrap_or_default(),
-            gas_used_ratio: block.gas_used as f64 / block.gas_limit as f64,
-            base_fee_per_blob_gas: block.blob_fee(),
-            blob_gas_used_ratio: block.blob_gas_used() as f64 /
-                reth_primitives::constants::eip4844::MAX_DATA_GAS_PER_BLOCK as f64,
-            excess_blob_gas: block.excess_blob_gas,
-            blob_gas_used: block.blob_gas_used,
-            gas_used: block.gas_used,
-            header_hash: block.hash(),
-            gas_limit: block.gas_limit,
-            rewards: Vec::new(),
-            timestamp: block.timestamp,
+            base_fee_per_gas: header.base_fee_per_gas.unwrap_or_default(),
+            gas_used_ratio: header.gas_used as f64 / header.gas_limit as f64,
+            excess_blob_gas: header.excess_blob_gas,
+            blob_gas_used: header.blob_gas_used,
+            gas_used: header.gas_used,
+            header_hash: header.hash(),
+            gas_limit: header.gas_limit,
+            transaction_rewards: None,
+            beneficiary_rewards: Vec::new(),
+            beneficiary: header.beneficiary,
+            timestamp: header.timestamp,
+        }
+    }
+
+    /// Sets the rewards of the transactions of the block, given the transactions, their senders
+    /// and their receipts.
+    pub fn with_rewards(
+        mut self,
+        transactions: &[TransactionSigned],
+        senders: &[Address],
+        receipts: &[Receipt],
+    ) -> Self {
+        let mut rewards = transaction_rewards(self.base_fee_per_gas, transactions, receipts);
+        let mut beneficiary_rewards = rewards
+            .iter()
+            .zip(senders)
+            .filter(|(_, sender)| **sender == self.beneficiary)
+            .map(|(reward, _)| reward.clone())
+            .collect::<Vec<_>>();
+
+        // Sort the transactions by their rewards in ascending order
+        rewards.sort_by_key(|tx| tx.reward);
+        beneficiary_rewards.sort_by_key(|tx| tx.reward);
+
+        self.transaction_rewards = Some(Arc::new(rewards));
+        self.beneficiary_rewards = beneficiary_rewards;
+        self
+    }
+
+    /// Returns the rewards of the block at the given percentiles.
+    ///
+    /// If the receipts of the block were not available, all rewards are zero.
+    pub fn rewards(&self, percentiles: &[f64]) -> Vec<u128> {
+        match &self.transaction_rewards {
+            Some(transactions) => {
+                calculate_reward_percentiles(percentiles, self.gas_used, transactions)
+            }
+            None => vec![0; percentiles.len()],
+        }
+    }
+
+    /// Returns the rewards of the block at the given percentiles, ignoring the transactions sent
+    /// by the beneficiary of the block, which don't pay the block producer.
+    ///
+    /// Returns `None` if the receipts of the block were not available.
+    pub fn rewards_excluding_beneficiary(&self, percentiles: &[f64]) -> Option<Vec<u128>> {
+        let transactions = self.transaction_rewards.as_ref()?;
+        if self.beneficiary_rewards.is_empty() {
+            return Some(calculate_reward_percentiles(percentiles, self.gas_used, transactions))
         }
+
+        // both lists are sorted the same way, so the beneficiary's transactions can be skipped
+        // in a single pass
+        let mut excluded = self.beneficiary_rewards.iter().peekable();
+        let transactions = transactions
+            .iter()
+            .filter(|tx| {
+                let is_excluded = excluded.peek() == Some(tx);
+                if is_excluded {
+                    excluded.next();
+                }
+                !is_excluded
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let gas_used = transactions.iter().map(|tx| tx.gas_used).sum();
+        Some(calculate_reward_percentiles(percentiles, gas_used, &transactions))
     }
 
     /// Returns the base fee for the next block according to the EIP-1559 spec.
@@ -380,19 +383,198 @@ impl FeeHistoryEntry {
         ) as u64
     }
 
+    /// Returns the [`BlobParams`] of the block.
+    fn blob_params(&self, chain_spec: &ChainSpec) -> BlobParams {
+        chain_spec.blob_params_at_timestamp(self.timestamp)
+    }
+
+    /// Returns the blob fee of the block according to the EIP-4844 spec.
+    ///
+    /// Returns `None` if `excess_blob_gas` is None, pre EIP-4844.
+    pub fn blob_fee(&self, chain_spec: &ChainSpec) -> Option<u128> {
+        Some(self.blob_params(chain_spec).calc_blob_fee(self.excess_blob_gas?))
+    }
+
+    /// Returns the ratio of the blob gas used and the maximum blob gas of the block.
+    ///
+    /// Zero if no blob gas was used or pre EIP-4844.
+    pub fn blob_gas_used_ratio(&self, chain_spec: &ChainSpec) -> f64 {
+        self.blob_params(chain_spec).blob_gas_used_ratio(self.blob_gas_used.unwrap_or_default())
+    }
+
+    /// Returns the [`BlobParams`] of the next block.
+    ///
+    /// The next block is assumed to be in the next slot: 12s, so that the projection switches to
+    /// the parameters of a new fork at its first block.
+    fn next_block_blob_params(&self, chain_spec: &ChainSpec) -> BlobParams {
+        chain_spec.blob_params_at_timestamp(self.timestamp + 12)
+    }
+
     /// Returns the blob fee for the next block according to the EIP-4844 spec.
     ///
     /// Returns `None` if `excess_blob_gas` is None.
     ///
     /// See also [`Self::next_block_excess_blob_gas`]
-    pub fn next_block_blob_fee(&self) -> Option<u128> {
-        self.next_block_excess_blob_gas().map(calc_blob_gasprice)
+    pub fn next_block_blob_fee(&self, chain_spec: &ChainSpec) -> Option<u128> {
+        let excess_blob_gas = self.next_block_excess_blob_gas(chain_spec)?;
+        Some(self.next_block_blob_params(chain_spec).calc_blob_fee(excess_blob_gas))
     }
 
     /// Calculate excess blob gas for the next block according to the EIP-4844 spec.
     ///
     /// Returns a `None` if no excess blob gas is set, no EIP-4844 support
-    pub fn next_block_excess_blob_gas(&self) -> Option<u64> {
-        Some(calculate_excess_blob_gas(self.excess_blob_gas?, self.blob_gas_used?))
+    pub fn next_block_excess_blob_gas(&self, chain_spec: &ChainSpec) -> Option<u64> {
+        Some(
+            self.next_block_blob_params(chain_spec)
+                .next_block_excess_blob_gas(self.excess_blob_gas?, self.blob_gas_used?),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{
+        constants::eip4844::DATA_GAS_PER_BLOB, ChainSpecBuilder, ForkCondition, Hardfork, Header,
+        Signature, Transaction, TxEip1559,
+    };
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_testing_utils::generators::{self, random_block, random_receipt};
+
+    #[test]
+    fn reward_percentiles_are_gas_weighted() {
+        let transactions = [
+            TxGasAndReward { gas_used: 21_000, reward: 1 },
+            TxGasAndReward { gas_used: 79_000, reward: 5 },
+        ];
+
+        // the midpoints are at 10_500 and 60_500 gas
+        let rewards = calculate_reward_percentiles(
+            &[0., 10., 10.5, 23., 35.5, 60.5, 100.],
+            100_000,
+            &transactions,
+        );
+        assert_eq!(rewards, vec![1, 1, 1, 2, 3, 5, 5]);
+
+        let transactions = [TxGasAndReward { gas_used: 21_000, reward: 7 }];
+        let rewards = calculate_reward_percentiles(&[0., 50., 100.], 21_000, &transactions);
+        assert_eq!(rewards, vec![7, 7, 7]);
+
+        let rewards = calculate_reward_percentiles(&[0., 50., 100.], 0, &[]);
+        assert_eq!(rewards, vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn next_block_blob_fee_at_fork_boundary() {
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .cancun_activated()
+            .with_fork(Hardfork::Prague, ForkCondition::Timestamp(30))
+            .build();
+        let excess_blob_gas = 10 * DATA_GAS_PER_BLOB;
+        let blob_gas_used = 6 * DATA_GAS_PER_BLOB;
+        let header = |timestamp| Header {
+            timestamp,
+            excess_blob_gas: Some(excess_blob_gas),
+            blob_gas_used: Some(blob_gas_used),
+            ..Default::default()
+        };
+
+        // the last cancun block projects the blob fee of the first prague block
+        let cancun = BlobParams::cancun();
+        let prague = BlobParams::prague();
+        let entry = FeeHistoryEntry::new(&header(20).seal_slow());
+        assert_eq!(entry.blob_fee(&chain_spec), Some(cancun.calc_blob_fee(excess_blob_gas)));
+        assert_eq!(entry.blob_gas_used_ratio(&chain_spec), 1.0);
+        assert_eq!(
+            entry.next_block_excess_blob_gas(&chain_spec),
+            Some(prague.next_block_excess_blob_gas(excess_blob_gas, blob_gas_used))
+        );
+        assert_eq!(entry.next_block_excess_blob_gas(&chain_spec), Some(excess_blob_gas));
+        assert_eq!(
+            entry.next_block_blob_fee(&chain_spec),
+            Some(prague.calc_blob_fee(excess_blob_gas))
+        );
+
+        // a cancun block with a cancun successor
+        let entry = FeeHistoryEntry::new(&header(10).seal_slow());
+        assert_eq!(
+            entry.next_block_blob_fee(&chain_spec),
+            Some(cancun.calc_blob_fee(excess_blob_gas + 3 * DATA_GAS_PER_BLOB))
+        );
+    }
+
+    #[test]
+    fn rewards_excluding_beneficiary() {
+        let beneficiary = Address::with_last_byte(1);
+        let header = Header { beneficiary, base_fee_per_gas: Some(1), ..Default::default() };
+        let transactions = [1u128, 100, 5]
+            .map(|max_priority_fee_per_gas| {
+                TransactionSigned::from_transaction_and_signature(
+                    Transaction::Eip1559(TxEip1559 {
+                        max_fee_per_gas: 1 + max_priority_fee_per_gas,
+                        max_priority_fee_per_gas,
+                        ..Default::default()
+                    }),
+                    Signature::default(),
+                )
+            })
+            .to_vec();
+        let receipts = [21_000, 42_000, 63_000]
+            .map(|cumulative_gas_used| Receipt { cumulative_gas_used, ..Default::default() })
+            .to_vec();
+        // the beneficiary pays itself the highest tip
+        let senders = vec![Address::with_last_byte(2), beneficiary, Address::with_last_byte(3)];
+
+        let mut entry = FeeHistoryEntry::new(&header.seal_slow()).with_rewards(
+            &transactions,
+            &senders,
+            &receipts,
+        );
+        entry.gas_used = 63_000;
+        assert_eq!(entry.rewards(&[100.]), vec![100]);
+        assert_eq!(entry.rewards_excluding_beneficiary(&[0., 100.]), Some(vec![1, 5]));
+
+        let entry = FeeHistoryEntry::new(&SealedHeader::default());
+        assert_eq!(entry.rewards_excluding_beneficiary(&[50.]), None);
+    }
+
+    #[tokio::test]
+    async fn fee_history_cache_by_block_hash() {
+        let mut rng = generators::rng();
+        let provider = MockEthProvider::default();
+
+        let block = random_block(&mut rng, 1, None, Some(2), Some(0));
+        let mut cumulative_gas_used = 0;
+        let receipts = block
+            .body
+            .iter()
+            .map(|tx| {
+                let mut receipt = random_receipt(&mut rng, tx, Some(0));
+                cumulative_gas_used += 21_000;
+                receipt.cumulative_gas_used = cumulative_gas_used;
+                receipt
+            })
+            .collect();
+        provider.add_block(block.hash(), block.clone().unseal());
+        provider.add_receipts(block.hash(), receipts);
+
+        // a block at the same height whose receipts are pruned
+        let pruned = random_block(&mut rng, 1, None, Some(2), Some(0));
+        provider.add_block(pruned.hash(), pruned.clone().unseal());
+
+        let eth_cache = EthStateCache::spawn(provider, Default::default(), EthEvmConfig::default());
+        let cache = FeeHistoryCache::new(eth_cache, FeeHistoryCacheConfig::default());
+
+        let entries =
+            cache.get_history(&[block.header.clone(), pruned.header.clone()]).await.unwrap();
+        assert_eq!(entries[0].header_hash, block.hash());
+        assert_eq!(entries[0].transaction_rewards.as_ref().map(|rewards| rewards.len()), Some(2));
+        assert_eq!(entries[1].header_hash, pruned.hash());
+        assert_eq!(entries[1].rewards(&[10., 50., 90.]), vec![0, 0, 0]);
+
+        // only entries with rewards are cached
+        assert!(cache.get(&block.hash()).is_some());
+        assert!(cache.get(&pruned.hash()).is_none());
     }
 }
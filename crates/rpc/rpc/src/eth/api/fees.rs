@@ -2,7 +2,7 @@
 
 use crate::{
     eth::{
-        api::fee_history::{calculate_reward_percentiles_for_block, FeeHistoryEntry},
+        api::fee_history::FeeHistoryEntry,
         error::{EthApiError, EthResult},
     },
     EthApi,
@@ -23,22 +23,32 @@ where
     Network: NetworkInfo + Send + Sync + 'static,
     EvmConfig: ConfigureEvm + 'static,
 {
-    /// Returns a suggestion for a gas price for legacy transactions.
+    /// Returns a suggestion for a gas price for legacy transactions: the base fee of the next
+    /// block plus the suggested priority fee.
     ///
     /// See also: <https://github.com/ethereum/pm/issues/328#issuecomment-853234014>
     pub(crate) async fn gas_price(&self) -> EthResult<U256> {
-        let header = self.block(BlockNumberOrTag::Latest);
-        let suggested_tip = self.suggested_priority_fee();
-        let (header, suggested_tip) = futures::try_join!(header, suggested_tip)?;
-        let base_fee = header.and_then(|h| h.base_fee_per_gas).unwrap_or_default();
-        Ok(suggested_tip + U256::from(base_fee))
+        let suggested_tip = self.suggested_priority_fee().await?;
+        Ok(suggested_tip + U256::from(self.next_block_base_fee()?.unwrap_or_default()))
     }
 
-    /// Returns a suggestion for a base fee for blob transactions.
+    /// Returns the base fee of the block after the latest block.
+    ///
+    /// Returns `None` if the latest block is pre EIP-1559.
+    pub(crate) fn next_block_base_fee(&self) -> EthResult<Option<u64>> {
+        let chain_spec = self.provider().chain_spec();
+        Ok(self.provider().latest_header()?.and_then(|header| {
+            header.next_block_base_fee(chain_spec.base_fee_params_at_timestamp(header.timestamp))
+        }))
+    }
+
+    /// Returns a suggestion for a base fee for blob transactions: the blob fee of the block after
+    /// the latest block.
     pub(crate) async fn blob_base_fee(&self) -> EthResult<U256> {
-        self.block(BlockNumberOrTag::Latest)
-            .await?
-            .and_then(|h: reth_primitives::SealedBlock| h.next_block_blob_fee())
+        let chain_spec = self.provider().chain_spec();
+        self.provider()
+            .latest_header()?
+            .and_then(|header| FeeHistoryEntry::new(&header).next_block_blob_fee(&chain_spec))
             .ok_or(EthApiError::ExcessBlobGasNotSet)
             .map(U256::from)
     }
@@ -50,8 +60,8 @@ where
 
     /// Reports the fee history, for the given amount of blocks, up until the given newest block.
     ///
-    /// If `reward_percentiles` are provided the [`FeeHistory`] will include the _approximated_
-    /// rewards for the requested range.
+    /// If `reward_percentiles` are provided the [`FeeHistory`] will include the rewards for the
+    /// requested range. Blocks whose receipts are not available anymore have zero rewards.
     pub(crate) async fn fee_history(
         &self,
         mut block_count: u64,
@@ -92,9 +102,10 @@ where
         // If reward percentiles were specified, we
         // need to validate that they are monotonically
         // increasing and 0 <= p <= 100
-        // Note: The types used ensure that the percentiles are never < 0
         if let Some(percentiles) = &reward_percentiles {
-            if percentiles.windows(2).any(|w| w[0] > w[1] || w[0] > 100.) {
+            if percentiles.iter().any(|p| !(0.0..=100.0).contains(p)) ||
+                percentiles.windows(2).any(|w| w[0] > w[1])
+            {
                 return Err(EthApiError::InvalidRewardPercentiles)
             }
         }
@@ -106,99 +117,55 @@ where
         // NOTE: We ensured that block count is capped
         let start_block = end_block_plus - block_count;
 
-        // Collect base fees, gas usage ratios and (optionally) reward percentile data
-        let mut base_fee_per_gas: Vec<u128> = Vec::new();
-        let mut gas_used_ratio: Vec<f64> = Vec::new();
-
-        let mut base_fee_per_blob_gas: Vec<u128> = Vec::new();
-        let mut blob_gas_used_ratio: Vec<f64> = Vec::new();
-
-        let mut rewards: Vec<Vec<u128>> = Vec::new();
+        // read the requested header range
+        let headers = self.provider().sealed_headers_range(start_block..=end_block)?;
+        if headers.len() != block_count as usize {
+            return Err(EthApiError::InvalidBlockRange)
+        }
 
-        // Check if the requested range is within the cache bounds
-        let fee_entries = self.fee_history_cache().get_history(start_block, end_block).await;
+        // The rewards are only computed if percentiles were requested, from the cached entries
+        // or the receipts of the blocks
+        let entries = if reward_percentiles.is_some() {
+            self.fee_history_cache().get_history(&headers).await?
+        } else {
+            headers.iter().map(FeeHistoryEntry::new).collect()
+        };
 
-        if let Some(fee_entries) = fee_entries {
-            if fee_entries.len() != block_count as usize {
-                return Err(EthApiError::InvalidBlockRange)
-            }
+        // Collect base fees, gas usage ratios and (optionally) reward percentile data
+        let mut base_fee_per_gas: Vec<u128> = Vec::with_capacity(entries.len() + 1);
+        let mut gas_used_ratio: Vec<f64> = Vec::with_capacity(entries.len());
 
-            for entry in &fee_entries {
-                base_fee_per_gas.push(entry.base_fee_per_gas as u128);
-                gas_used_ratio.push(entry.gas_used_ratio);
-                base_fee_per_blob_gas.push(entry.base_fee_per_blob_gas.unwrap_or_default());
-                blob_gas_used_ratio.push(entry.blob_gas_used_ratio);
-
-                if let Some(percentiles) = &reward_percentiles {
-                    let mut block_rewards = Vec::with_capacity(percentiles.len());
-                    for &percentile in percentiles {
-                        block_rewards.push(self.approximate_percentile(entry, percentile));
-                    }
-                    rewards.push(block_rewards);
-                }
-            }
-            let last_entry = fee_entries.last().expect("is not empty");
+        let mut base_fee_per_blob_gas: Vec<u128> = Vec::with_capacity(entries.len() + 1);
+        let mut blob_gas_used_ratio: Vec<f64> = Vec::with_capacity(entries.len());
 
-            // Also need to include the `base_fee_per_gas` and `base_fee_per_blob_gas` for the next
-            // block
-            base_fee_per_gas
-                .push(last_entry.next_block_base_fee(&self.provider().chain_spec()) as u128);
+        let mut rewards: Vec<Vec<u128>> = Vec::new();
 
-            base_fee_per_blob_gas.push(last_entry.next_block_blob_fee().unwrap_or_default());
-        } else {
-            // read the requested header range
-            let headers = self.provider().sealed_headers_range(start_block..=end_block)?;
-            if headers.len() != block_count as usize {
-                return Err(EthApiError::InvalidBlockRange)
+        let chain_spec = self.provider().chain_spec();
+        for entry in &entries {
+            base_fee_per_gas.push(entry.base_fee_per_gas as u128);
+            gas_used_ratio.push(entry.gas_used_ratio);
+            // blocks before Cancun have no blob fee market and report zeros
+            base_fee_per_blob_gas.push(entry.blob_fee(&chain_spec).unwrap_or_default());
+            blob_gas_used_ratio.push(entry.blob_gas_used_ratio(&chain_spec));
+
+            // Blocks whose receipts are not available anymore have zero rewards
+            if let Some(percentiles) = &reward_percentiles {
+                rewards.push(entry.rewards(percentiles));
             }
+        }
 
-            for header in &headers {
-                base_fee_per_gas.push(header.base_fee_per_gas.unwrap_or_default() as u128);
-                gas_used_ratio.push(header.gas_used as f64 / header.gas_limit as f64);
-                base_fee_per_blob_gas.push(header.blob_fee().unwrap_or_default());
-                blob_gas_used_ratio.push(
-                    header.blob_gas_used.unwrap_or_default() as f64 /
-                        reth_primitives::constants::eip4844::MAX_DATA_GAS_PER_BLOCK as f64,
-                );
-
-                // Percentiles were specified, so we need to collect reward percentile ino
-                if let Some(percentiles) = &reward_percentiles {
-                    let (transactions, receipts) = self
-                        .cache()
-                        .get_transactions_and_receipts(header.hash())
-                        .await?
-                        .ok_or(EthApiError::InvalidBlockRange)?;
-                    rewards.push(
-                        calculate_reward_percentiles_for_block(
-                            percentiles,
-                            header.gas_used,
-                            header.base_fee_per_gas.unwrap_or_default(),
-                            &transactions,
-                            &receipts,
-                        )
-                        .unwrap_or_default(),
-                    );
-                }
-            }
+        // The spec states that `base_fee_per_gas` "[..] includes the next block after the
+        // newest of the returned range, because this value can be derived from the
+        // newest block"
+        //
+        // The unwrap is safe since we checked earlier that we got at least 1 header.
+        let last_entry = entries.last().expect("is present");
+        base_fee_per_gas.push(last_entry.next_block_base_fee(&chain_spec) as u128);
 
-            // The spec states that `base_fee_per_gas` "[..] includes the next block after the
-            // newest of the returned range, because this value can be derived from the
-            // newest block"
-            //
-            // The unwrap is safe since we checked earlier that we got at least 1 header.
-            let last_header = headers.last().expect("is present");
-            base_fee_per_gas.push(
-                self.provider().chain_spec().base_fee_params_at_timestamp(last_header.timestamp).next_block_base_fee(
-                    last_header.gas_used as u128,
-                    last_header.gas_limit as u128,
-                    last_header.base_fee_per_gas.unwrap_or_default() as u128,
-                ));
-
-            // Same goes for the `base_fee_per_blob_gas`:
-            // > "[..] includes the next block after the newest of the returned range, because this value can be derived from the newest block.
-            base_fee_per_blob_gas
-                .push(last_header.next_block_blob_fee().unwrap_or_default());
-        };
+        // Same goes for the `base_fee_per_blob_gas`:
+        // > "[..] includes the next block after the newest of the returned range, because this
+        // > value can be derived from the newest block.
+        base_fee_per_blob_gas.push(last_entry.next_block_blob_fee(&chain_spec).unwrap_or_default());
 
         Ok(FeeHistory {
             base_fee_per_gas,
@@ -209,18 +176,4 @@ where
             reward: reward_percentiles.map(|_| rewards),
         })
     }
-
-    /// Approximates reward at a given percentile for a specific block
-    /// Based on the configured resolution
-    fn approximate_percentile(&self, entry: &FeeHistoryEntry, requested_percentile: f64) -> u128 {
-        let resolution = self.fee_history_cache().resolution();
-        let rounded_percentile =
-            (requested_percentile * resolution as f64).round() / resolution as f64;
-        let clamped_percentile = rounded_percentile.clamp(0.0, 100.0);
-
-        // Calculate the index in the precomputed rewards array
-        let index = (clamped_percentile / (1.0 / resolution as f64)).round() as usize;
-        // Fetch the reward from the FeeHistoryEntry
-        entry.rewards.get(index).cloned().unwrap_or_default()
-    }
 }
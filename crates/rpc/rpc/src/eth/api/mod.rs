@@ -18,22 +18,18 @@ use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
     revm_primitives::{BlockEnv, CfgEnvWithHandlerCfg},
-    Address, BlockId, BlockNumberOrTag, ChainInfo, SealedBlockWithSenders, SealedHeader, B256,
-    U256, U64,
+    Address, BlockId, BlockNumberOrTag, ChainInfo, Receipt, SealedBlockWithSenders, SealedHeader,
+    B256, U256, U64,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory,
+    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, ReceiptProviderIdExt, StateProviderBox,
+    StateProviderFactory,
 };
 use reth_rpc_types::{SyncInfo, SyncStatus};
 use reth_tasks::{pool::BlockingTaskPool, TaskSpawner, TokioTaskExecutor};
-use reth_transaction_pool::TransactionPool;
+use reth_transaction_pool::{TransactionListenerKind, TransactionPool};
 use revm_primitives::{CfgEnv, SpecId};
-use std::{
-    fmt::Debug,
-    future::Future,
-    sync::Arc,
-    time::{Duration, Instant},
-};
+use std::{fmt::Debug, future::Future, sync::Arc, time::Duration};
 use tokio::sync::{oneshot, Mutex};
 
 mod block;
@@ -46,9 +42,11 @@ mod optimism;
 mod pending_block;
 mod server;
 mod sign;
+mod simulate;
 mod state;
 mod transactions;
 
+pub use simulate::EthSimulateError;
 pub use transactions::{EthTransactions, TransactionSource};
 
 /// `Eth` API trait.
@@ -110,6 +108,10 @@ where
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Provider>,
         gas_cap: impl Into<GasCap>,
+        eth_proof_window: u64,
+        memory_limit: u64,
+        send_raw_transaction_sync_timeout: Duration,
+        max_send_raw_transaction_sync_timeout: Duration,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache,
         evm_config: EvmConfig,
@@ -122,6 +124,10 @@ where
             eth_cache,
             gas_oracle,
             gas_cap.into().into(),
+            eth_proof_window,
+            memory_limit,
+            send_raw_transaction_sync_timeout,
+            max_send_raw_transaction_sync_timeout,
             Box::<TokioTaskExecutor>::default(),
             blocking_task_pool,
             fee_history_cache,
@@ -139,6 +145,10 @@ where
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Provider>,
         gas_cap: u64,
+        eth_proof_window: u64,
+        memory_limit: u64,
+        send_raw_transaction_sync_timeout: Duration,
+        max_send_raw_transaction_sync_timeout: Duration,
         task_spawner: Box<dyn TaskSpawner>,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache,
@@ -161,6 +171,10 @@ where
             eth_cache,
             gas_oracle,
             gas_cap,
+            eth_proof_window,
+            memory_limit,
+            send_raw_transaction_sync_timeout,
+            max_send_raw_transaction_sync_timeout,
             starting_block: U256::from(latest_block),
             task_spawner,
             pending_block: Default::default(),
@@ -210,6 +224,26 @@ where
         self.inner.gas_cap
     }
 
+    /// Returns the configured memory limit of the EVM for `eth_call` and tracing related calls
+    pub fn memory_limit(&self) -> u64 {
+        self.inner.memory_limit
+    }
+
+    /// Returns the maximum number of blocks into the past for generating state proofs.
+    pub fn eth_proof_window(&self) -> u64 {
+        self.inner.eth_proof_window
+    }
+
+    /// Returns the time `eth_sendRawTransactionSync` waits for the inclusion of the transaction,
+    /// given the timeout of the request if any.
+    ///
+    /// The requested timeout is capped by the configured maximum.
+    pub fn send_raw_transaction_sync_timeout(&self, requested: Option<Duration>) -> Duration {
+        requested
+            .unwrap_or(self.inner.send_raw_transaction_sync_timeout)
+            .min(self.inner.max_send_raw_transaction_sync_timeout)
+    }
+
     /// Returns the inner `Provider`
     pub fn provider(&self) -> &Provider {
         &self.inner.provider
@@ -305,7 +339,9 @@ where
             );
 
             // update excess blob gas consumed above target
-            latest_header.excess_blob_gas = latest_header.next_block_excess_blob_gas();
+            latest_header.excess_blob_gas = latest_header.next_block_excess_blob_gas(
+                chain_spec.blob_params_at_timestamp(latest_header.timestamp),
+            );
 
             // we're reusing the same block hash because we need this to lookup the block's state
             let latest = SealedHeader::new(latest_header, block_hash);
@@ -330,43 +366,60 @@ where
 
     /// Returns the locally built pending block
     pub(crate) async fn local_pending_block(&self) -> EthResult<Option<SealedBlockWithSenders>> {
+        Ok(self.local_pending_block_and_receipts().await?.map(|(block, _)| block))
+    }
+
+    /// Returns the locally built pending block and the receipts of its transactions
+    pub(crate) async fn local_pending_block_and_receipts(
+        &self,
+    ) -> EthResult<Option<(SealedBlockWithSenders, Arc<Vec<Receipt>>)>> {
         let pending = self.pending_block_env_and_cfg()?;
         if pending.origin.is_actual_pending() {
-            return Ok(pending.origin.into_actual_pending())
+            let Some(block) = pending.origin.into_actual_pending() else { return Ok(None) };
+            let receipts =
+                self.provider().receipts_by_block_id(BlockNumberOrTag::Pending.into())?;
+            return Ok(receipts.map(|receipts| (block, Arc::new(receipts))))
         }
 
         // no pending block from the CL yet, so we need to build it ourselves via txpool
         self.on_blocking_task(|this| async move {
             let mut lock = this.inner.pending_block.lock().await;
-            let now = Instant::now();
 
-            // check if the block is still good
-            if let Some(pending_block) = lock.as_ref() {
+            // the block is still good until the next canonical block or until new pending
+            // transactions would change it
+            if let Some(pending_block) = lock.as_mut() {
                 // this is guaranteed to be the `latest` header
                 if pending.block_env.number.to::<u64>() == pending_block.block.number &&
                     pending.origin.header().hash() == pending_block.block.parent_hash &&
-                    now <= pending_block.expires_at
+                    !pending_block.has_new_pending_transactions(this.pool())
                 {
-                    return Ok(Some(pending_block.block.clone()))
+                    return Ok(Some((pending_block.block.clone(), pending_block.receipts.clone())))
                 }
             }
 
-            // we rebuild the block
-            let pending_block = match pending.build_block(this.provider(), this.pool()) {
-                Ok(block) => block,
-                Err(err) => {
-                    tracing::debug!(target: "rpc", "Failed to build pending block: {:?}", err);
-                    return Ok(None)
-                }
-            };
+            // subscribe before building the block, so transactions that become pending while the
+            // block is built are checked as well
+            let new_pending_transactions =
+                this.pool().pending_transactions_listener_for(TransactionListenerKind::All);
 
-            let now = Instant::now();
-            *lock = Some(PendingBlock {
-                block: pending_block.clone(),
-                expires_at: now + Duration::from_secs(1),
-            });
-
-            Ok(Some(pending_block))
+            // we rebuild the block
+            let (pending_block, receipts) =
+                match pending.build_block(this.provider(), this.pool(), &this.inner.evm_config) {
+                    Ok(block_and_receipts) => block_and_receipts,
+                    Err(err) => {
+                        tracing::debug!(target: "rpc", "Failed to build pending block: {:?}", err);
+                        return Ok(None)
+                    }
+                };
+
+            let receipts = Arc::new(receipts);
+            *lock = Some(PendingBlock::new(
+                pending_block.clone(),
+                receipts.clone(),
+                new_pending_transactions,
+            ));
+
+            Ok(Some((pending_block, receipts)))
         })
         .await
     }
@@ -486,6 +539,15 @@ struct EthApiInner<Provider, Pool, Network, EvmConfig> {
     gas_oracle: GasPriceOracle<Provider>,
     /// Maximum gas limit for `eth_call` and call tracing RPC methods.
     gas_cap: u64,
+    /// The maximum number of blocks into the past for generating state proofs.
+    eth_proof_window: u64,
+    /// Memory limit of the EVM in bytes for `eth_call` and tracing RPC methods.
+    memory_limit: u64,
+    /// The time `eth_sendRawTransactionSync` waits for the inclusion of the transaction if the
+    /// request doesn't specify a timeout.
+    send_raw_transaction_sync_timeout: Duration,
+    /// The maximum time `eth_sendRawTransactionSync` waits for the inclusion of the transaction.
+    max_send_raw_transaction_sync_timeout: Duration,
     /// The block number at which the node started
     starting_block: U256,
     /// The type that can spawn tasks which would otherwise block.
@@ -2,12 +2,13 @@
 
 use crate::eth::error::{EthApiError, EthResult};
 use reth_errors::ProviderError;
+use reth_evm::ConfigureEvm;
 use reth_primitives::{
-    constants::{eip4844::MAX_DATA_GAS_PER_BLOCK, BEACON_NONCE},
+    constants::BEACON_NONCE,
     proofs,
     revm::env::tx_env_with_recovered,
     revm_primitives::{
-        BlockEnv, CfgEnvWithHandlerCfg, EVMError, Env, InvalidTransaction, ResultAndState, SpecId,
+        BlockEnv, CfgEnvWithHandlerCfg, EVMError, InvalidTransaction, ResultAndState, SpecId,
     },
     trie::EMPTY_ROOT_HASH,
     Block, BlockId, BlockNumberOrTag, ChainSpec, Header, IntoRecoveredTransaction, Receipt,
@@ -21,10 +22,11 @@ use reth_revm::{
         post_block_withdrawals_balance_increments,
     },
 };
-use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
+use reth_transaction_pool::{BestTransactionsAttributes, PoolTransaction, TransactionPool};
 use revm::{db::states::bundle_state::BundleRetention, Database, DatabaseCommit, State};
 use revm_primitives::EnvWithHandlerCfg;
-use std::time::Instant;
+use std::sync::Arc;
+use tokio::sync::mpsc::{error::TryRecvError, Receiver};
 
 /// Configured [`BlockEnv`] and [`CfgEnvWithHandlerCfg`] for a pending block
 #[derive(Debug, Clone)]
@@ -44,14 +46,18 @@ impl PendingBlockEnv {
     ///
     /// After Cancun, if the origin is the actual pending block, the block includes the EIP-4788 pre
     /// block contract call using the parent beacon block root received from the CL.
-    pub(crate) fn build_block<Client, Pool>(
+    ///
+    /// Returns the block and the receipts of its transactions.
+    pub(crate) fn build_block<Client, Pool, EvmConfig>(
         self,
         client: &Client,
         pool: &Pool,
-    ) -> EthResult<SealedBlockWithSenders>
+        evm_config: &EvmConfig,
+    ) -> EthResult<(SealedBlockWithSenders, Vec<Receipt>)>
     where
         Client: StateProviderFactory + ChainSpecProvider,
         Pool: TransactionPool,
+        EvmConfig: ConfigureEvm,
     {
         let Self { cfg, block_env, origin } = self;
 
@@ -81,6 +87,9 @@ impl PendingBlockEnv {
         };
 
         let chain_spec = client.chain_spec();
+        let max_blob_gas_per_block = chain_spec
+            .blob_params_at_timestamp(block_env.timestamp.to::<u64>())
+            .max_blob_gas_per_block;
 
         let parent_beacon_block_root = if origin.is_actual_pending() {
             // apply eip-4788 pre block contract call if we got the block from the CL with the real
@@ -132,7 +141,7 @@ impl PendingBlockEnv {
             // the EIP-4844 can still fit in the block
             if let Some(blob_tx) = tx.transaction.as_eip4844() {
                 let tx_blob_gas = blob_tx.blob_gas();
-                if sum_blob_gas_used + tx_blob_gas > MAX_DATA_GAS_PER_BLOCK {
+                if sum_blob_gas_used + tx_blob_gas > max_blob_gas_per_block {
                     // we can't fit this _blob_ transaction into the block, so we mark it as
                     // invalid, which removes its dependent transactions from
                     // the iterator. This is similar to the gas limit condition
@@ -143,10 +152,13 @@ impl PendingBlockEnv {
             }
 
             // Configure the environment for the block.
-            let env =
-                Env::boxed(cfg.cfg_env.clone(), block_env.clone(), tx_env_with_recovered(&tx));
+            let env = EnvWithHandlerCfg::new_with_cfg_env(
+                cfg.clone(),
+                block_env.clone(),
+                tx_env_with_recovered(&tx),
+            );
 
-            let mut evm = revm::Evm::builder().with_env(env).with_db(&mut db).build();
+            let mut evm = evm_config.evm_with_env(&mut db, env);
 
             let ResultAndState { result, state } = match evm.transact() {
                 Ok(res) => res,
@@ -180,7 +192,7 @@ impl PendingBlockEnv {
                 sum_blob_gas_used += tx_blob_gas;
 
                 // if we've reached the max data gas per block, we can skip blob txs entirely
-                if sum_blob_gas_used == MAX_DATA_GAS_PER_BLOCK {
+                if sum_blob_gas_used == max_blob_gas_per_block {
                     best_txs.skip_blobs();
                 }
             }
@@ -221,6 +233,7 @@ impl PendingBlockEnv {
         // merge all transitions into bundle state.
         db.merge_transitions(BundleRetention::PlainState);
 
+        let block_receipts = receipts.iter().flatten().cloned().collect();
         let bundle = BundleStateWithReceipts::new(
             db.take_bundle(),
             Receipts::from_vec(vec![receipts]),
@@ -287,7 +300,7 @@ impl PendingBlockEnv {
 
         // seal the block
         let block = Block { header, body: executed_txs, ommers: vec![], withdrawals, requests };
-        Ok(SealedBlockWithSenders { block: block.seal_slow(), senders })
+        Ok((SealedBlockWithSenders { block: block.seal_slow(), senders }, block_receipts))
     }
 }
 
@@ -421,6 +434,126 @@ impl PendingBlockEnvOrigin {
 pub(crate) struct PendingBlock {
     /// The cached pending block
     pub(crate) block: SealedBlockWithSenders,
-    /// Timestamp when the pending block is considered outdated
-    pub(crate) expires_at: Instant,
+    /// The receipts of the transactions of the pending block
+    pub(crate) receipts: Arc<Vec<Receipt>>,
+    /// Yields the transactions that became pending after the block was built
+    pub(crate) new_pending_transactions: Receiver<B256>,
+    /// The lowest priority fee paid by a transaction of the block, `None` if the block is empty
+    pub(crate) min_priority_fee: Option<u128>,
+}
+
+impl PendingBlock {
+    /// Creates a new cached pending block, which is outdated once the given listener yields a
+    /// transaction that would be part of the block.
+    pub(crate) fn new(
+        block: SealedBlockWithSenders,
+        receipts: Arc<Vec<Receipt>>,
+        new_pending_transactions: Receiver<B256>,
+    ) -> Self {
+        let min_priority_fee = block
+            .body
+            .iter()
+            .map(|tx| tx.effective_tip_per_gas(block.base_fee_per_gas).unwrap_or_default())
+            .min();
+        Self { block, receipts, new_pending_transactions, min_priority_fee }
+    }
+
+    /// Returns true if a transaction became pending in the pool since the block was built that
+    /// would be part of the block if it was rebuilt, in which case the block is outdated.
+    ///
+    /// A new pending transaction changes the block if it fits into the remaining gas of the
+    /// block, or if it pays a higher priority fee than a transaction of the block, so it would be
+    /// included first.
+    pub(crate) fn has_new_pending_transactions<Pool: TransactionPool>(
+        &mut self,
+        pool: &Pool,
+    ) -> bool {
+        let base_fee = self.block.base_fee_per_gas.unwrap_or_default();
+        let remaining_gas = self.block.gas_limit.saturating_sub(self.block.gas_used);
+        loop {
+            let hash = match self.new_pending_transactions.try_recv() {
+                Ok(hash) => hash,
+                Err(TryRecvError::Empty) => return false,
+                Err(TryRecvError::Disconnected) => return true,
+            };
+            // the transaction may have been mined or replaced in the meantime
+            let Some(tx) = pool.get(&hash) else { continue };
+            let Some(priority_fee) = tx.transaction.effective_tip_per_gas(base_fee) else {
+                continue
+            };
+            if tx.transaction.gas_limit() <= remaining_gas ||
+                self.min_priority_fee.is_some_and(|min| priority_fee > min)
+            {
+                return true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Address, Signature, Transaction, TransactionSigned, TxEip1559};
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        TransactionOrigin,
+    };
+    use tokio::sync::mpsc::channel;
+
+    #[tokio::test]
+    async fn pending_block_outdated_by_includable_transactions() {
+        let pool = testing_pool();
+
+        // the block has room for 21_000 more gas, and its transaction pays a priority fee of 10
+        let tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Eip1559(TxEip1559 {
+                max_fee_per_gas: 20,
+                max_priority_fee_per_gas: 10,
+                gas_limit: 79_000,
+                ..Default::default()
+            }),
+            Signature::default(),
+        );
+        let header = Header {
+            base_fee_per_gas: Some(1),
+            gas_limit: 100_000,
+            gas_used: 79_000,
+            ..Default::default()
+        };
+        let block = Block { header, body: vec![tx], ..Default::default() }.seal_slow();
+        let block = SealedBlockWithSenders::new(block, vec![Address::ZERO]).unwrap();
+        let (sender, receiver) = channel(8);
+        let mut pending_block = PendingBlock::new(block, Default::default(), receiver);
+        assert_eq!(pending_block.min_priority_fee, Some(10));
+        assert!(!pending_block.has_new_pending_transactions(&pool));
+
+        let too_large_and_cheap =
+            MockTransaction::eip1559().with_gas_limit(30_000).with_max_fee(20).with_priority_fee(5);
+        let too_large_but_better_paying = MockTransaction::eip1559()
+            .with_gas_limit(30_000)
+            .with_max_fee(20)
+            .with_priority_fee(15);
+        let fitting =
+            MockTransaction::eip1559().with_gas_limit(21_000).with_max_fee(20).with_priority_fee(5);
+        for tx in [&too_large_and_cheap, &too_large_but_better_paying, &fitting] {
+            pool.add_transaction(TransactionOrigin::External, tx.clone()).await.unwrap();
+        }
+
+        // transactions that wouldn't be included, or that aren't in the pool anymore, are ignored
+        sender.send(too_large_and_cheap.get_hash()).await.unwrap();
+        sender.send(B256::random()).await.unwrap();
+        assert!(!pending_block.has_new_pending_transactions(&pool));
+
+        // a transaction that pays more than a transaction of the block would be included first
+        sender.send(too_large_but_better_paying.get_hash()).await.unwrap();
+        assert!(pending_block.has_new_pending_transactions(&pool));
+
+        // a transaction that fits into the remaining gas would be included
+        sender.send(fitting.get_hash()).await.unwrap();
+        assert!(pending_block.has_new_pending_transactions(&pool));
+
+        // without the listener the block can't be validated anymore
+        drop(sender);
+        assert!(pending_block.has_new_pending_transactions(&pool));
+    }
 }
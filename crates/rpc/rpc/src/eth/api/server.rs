@@ -4,7 +4,9 @@
 use jsonrpsee::core::RpcResult as Result;
 use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256, B64, U256, U64};
+use reth_primitives::{
+    Address, BlobTransactionSidecar, BlockId, BlockNumberOrTag, Bytes, B256, B64, U256, U64,
+};
 use reth_provider::{
     BlockIdReader, BlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider,
     HeaderProvider, StateProviderFactory,
@@ -12,11 +14,13 @@ use reth_provider::{
 use reth_rpc_api::EthApiServer;
 use reth_rpc_types::{
     serde_helpers::JsonStorageKey, state::StateOverride, AccessListWithGasUsed,
-    AnyTransactionReceipt, BlockOverrides, Bundle, EIP1186AccountProofResponse, EthCallResponse,
-    FeeHistory, Header, Index, RichBlock, StateContext, SyncStatus, TransactionRequest, Work,
+    AnyTransactionReceipt, BlockOverrides, Bundle, EIP1186AccountProofResponse, EthAccount,
+    EthAccountInfo, EthCallManyResponse, FeeHistory, Header, Index, RichBlock, SimulatePayload,
+    SimulatedBlock, StateContext, SyncStatus, TransactionRequest, Work,
 };
 use reth_transaction_pool::TransactionPool;
 use serde_json::Value;
+use std::time::Duration;
 use tracing::trace;
 
 use crate::{
@@ -117,13 +121,13 @@ where
     /// Handler for: `eth_getUncleCountByBlockHash`
     async fn block_uncles_count_by_hash(&self, hash: B256) -> Result<Option<U256>> {
         trace!(target: "rpc::eth", ?hash, "Serving eth_getUncleCountByBlockHash");
-        Ok(Self::ommers(self, hash)?.map(|ommers| U256::from(ommers.len())))
+        Ok(Self::ommers(self, hash).await?.map(|ommers| U256::from(ommers.len())))
     }
 
     /// Handler for: `eth_getUncleCountByBlockNumber`
     async fn block_uncles_count_by_number(&self, number: BlockNumberOrTag) -> Result<Option<U256>> {
         trace!(target: "rpc::eth", ?number, "Serving eth_getUncleCountByBlockNumber");
-        Ok(Self::ommers(self, number)?.map(|ommers| U256::from(ommers.len())))
+        Ok(Self::ommers(self, number).await?.map(|ommers| U256::from(ommers.len())))
     }
 
     /// Handler for: `eth_getBlockReceipts`
@@ -161,12 +165,30 @@ where
         Ok(EthTransactions::raw_transaction_by_hash(self, hash).await?)
     }
 
+    /// Handler for: `eth_getBlobSidecarByTxHash`
+    async fn blob_sidecar_by_tx_hash(&self, hash: B256) -> Result<Option<BlobTransactionSidecar>> {
+        trace!(target: "rpc::eth", ?hash, "Serving eth_getBlobSidecarByTxHash");
+        Ok(EthTransactions::blob_sidecar_by_hash(self, hash)?)
+    }
+
     /// Handler for: `eth_getTransactionByHash`
     async fn transaction_by_hash(&self, hash: B256) -> Result<Option<reth_rpc_types::Transaction>> {
         trace!(target: "rpc::eth", ?hash, "Serving eth_getTransactionByHash");
         Ok(EthTransactions::transaction_by_hash(self, hash).await?.map(Into::into))
     }
 
+    /// Handler for: `eth_getTransactionBySenderAndNonce`
+    async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: U64,
+    ) -> Result<Option<reth_rpc_types::Transaction>> {
+        trace!(target: "rpc::eth", ?sender, ?nonce, "Serving eth_getTransactionBySenderAndNonce");
+        Ok(EthTransactions::transaction_by_sender_and_nonce(self, sender, nonce.to())
+            .await?
+            .map(Into::into))
+    }
+
     /// Handler for: `eth_getRawTransactionByBlockHashAndIndex`
     async fn raw_transaction_by_block_hash_and_index(
         &self,
@@ -283,12 +305,22 @@ where
     /// Handler for: `eth_callMany`
     async fn call_many(
         &self,
-        bundle: Bundle,
+        bundles: Vec<Bundle>,
         state_context: Option<StateContext>,
         state_override: Option<StateOverride>,
-    ) -> Result<Vec<EthCallResponse>> {
-        trace!(target: "rpc::eth", ?bundle, ?state_context, ?state_override, "Serving eth_callMany");
-        Ok(Self::call_many(self, bundle, state_context, state_override).await?)
+    ) -> Result<Vec<Vec<EthCallManyResponse>>> {
+        trace!(target: "rpc::eth", ?bundles, ?state_context, ?state_override, "Serving eth_callMany");
+        Ok(Self::call_many(self, bundles, state_context, state_override).await?)
+    }
+
+    /// Handler for: `eth_simulateV1`
+    async fn simulate_v1(
+        &self,
+        opts: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> Result<Vec<SimulatedBlock>> {
+        trace!(target: "rpc::eth", ?block_number, "Serving eth_simulateV1");
+        Ok(Self::simulate_v1(self, opts, block_number).await?)
     }
 
     /// Handler for: `eth_createAccessList`
@@ -296,9 +328,11 @@ where
         &self,
         request: TransactionRequest,
         block_number: Option<BlockId>,
+        state_override: Option<StateOverride>,
     ) -> Result<AccessListWithGasUsed> {
         trace!(target: "rpc::eth", ?request, ?block_number, "Serving eth_createAccessList");
-        let access_list_with_gas_used = self.create_access_list_at(request, block_number).await?;
+        let access_list_with_gas_used =
+            self.create_access_list_at(request, block_number, state_override).await?;
 
         Ok(access_list_with_gas_used)
     }
@@ -309,9 +343,16 @@ where
         request: TransactionRequest,
         block_number: Option<BlockId>,
         state_override: Option<StateOverride>,
+        block_override: Option<Box<BlockOverrides>>,
     ) -> Result<U256> {
         trace!(target: "rpc::eth", ?request, ?block_number, "Serving eth_estimateGas");
-        Ok(self.estimate_gas_at(request, block_number.unwrap_or_default(), state_override).await?)
+        Ok(self
+            .estimate_gas_at(
+                request,
+                block_number.unwrap_or_default(),
+                EvmOverrides::new(state_override, block_override),
+            )
+            .await?)
     }
 
     /// Handler for: `eth_gasPrice`
@@ -388,6 +429,17 @@ where
         Ok(EthTransactions::send_raw_transaction(self, tx).await?)
     }
 
+    /// Handler for: `eth_sendRawTransactionSync`
+    async fn send_raw_transaction_sync(
+        &self,
+        tx: Bytes,
+        timeout_ms: Option<u64>,
+    ) -> Result<AnyTransactionReceipt> {
+        trace!(target: "rpc::eth", ?tx, ?timeout_ms, "Serving eth_sendRawTransactionSync");
+        let timeout = self.send_raw_transaction_sync_timeout(timeout_ms.map(Duration::from_millis));
+        Ok(EthTransactions::send_raw_transaction_sync(self, tx, timeout).await?)
+    }
+
     /// Handler for: `eth_sign`
     async fn sign(&self, address: Address, message: Bytes) -> Result<Bytes> {
         trace!(target: "rpc::eth", ?address, ?message, "Serving eth_sign");
@@ -422,64 +474,61 @@ where
             _ => e.into(),
         })?)
     }
+
+    /// Handler for: `eth_getAccount`
+    async fn get_account(
+        &self,
+        address: Address,
+        block_number: Option<BlockId>,
+    ) -> Result<EthAccount> {
+        trace!(target: "rpc::eth", ?address, ?block_number, "Serving eth_getAccount");
+        Ok(Self::get_account(self, address, block_number).await?)
+    }
+
+    /// Handler for: `eth_getAccountInfo`
+    async fn get_account_info(
+        &self,
+        address: Address,
+        block_number: Option<BlockId>,
+    ) -> Result<EthAccountInfo> {
+        trace!(target: "rpc::eth", ?address, ?block_number, "Serving eth_getAccountInfo");
+        Ok(self
+            .on_blocking_task(|this| async move { this.get_account_info(address, block_number) })
+            .await?)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
         eth::{
-            cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache,
-            FeeHistoryCacheConfig,
+            error::EthApiError,
+            test_utils::{build_test_eth_api, TestEthApi},
         },
         EthApi,
     };
     use jsonrpsee::types::error::INVALID_PARAMS_CODE;
-    use reth_evm_ethereum::EthEvmConfig;
-    use reth_network_api::noop::NoopNetwork;
     use reth_primitives::{
-        constants::ETHEREUM_BLOCK_GAS_LIMIT, BaseFeeParams, Block, BlockNumberOrTag, Header,
-        TransactionSigned, B256, U64,
-    };
-    use reth_provider::{
-        test_utils::{MockEthProvider, NoopProvider},
-        BlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory,
+        constants::{eip4844::DATA_GAS_PER_BLOB, ETHEREUM_BLOCK_GAS_LIMIT},
+        eip4844::BlobParams,
+        hex_literal::hex,
+        Address, BaseFeeParams, BlobTransactionSidecar, Block, BlockNumberOrTag, Bytes,
+        ChainSpecBuilder, ForkCondition, Hardfork, Header, IntoRecoveredTransaction,
+        TransactionSigned, TxKind, B256, EIP4844_TX_TYPE_ID, U256, U64,
     };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider, NoopProvider};
     use reth_rpc_api::EthApiServer;
-    use reth_rpc_types::FeeHistory;
-    use reth_tasks::pool::BlockingTaskPool;
+    use reth_rpc_types::{
+        state::{AccountOverride, StateOverride},
+        AccessList, AccessListItem, FeeHistory, TransactionRequest,
+    };
     use reth_testing_utils::{generators, generators::Rng};
-    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
-
-    fn build_test_eth_api<
-        P: BlockReaderIdExt
-            + BlockReader
-            + ChainSpecProvider
-            + EvmEnvProvider
-            + StateProviderFactory
-            + Unpin
-            + Clone
-            + 'static,
-    >(
-        provider: P,
-    ) -> EthApi<P, TestPool, NoopNetwork, EthEvmConfig> {
-        let evm_config = EthEvmConfig::default();
-        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
-        let fee_history_cache =
-            FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
-
-        EthApi::new(
-            provider.clone(),
-            testing_pool(),
-            NoopNetwork::default(),
-            cache.clone(),
-            GasPriceOracle::new(provider, Default::default(), cache),
-            ETHEREUM_BLOCK_GAS_LIMIT,
-            BlockingTaskPool::build().expect("failed to build tracing pool"),
-            fee_history_cache,
-            evm_config,
-            None,
-        )
-    }
+    use reth_transaction_pool::{
+        blobstore::{BlobStoreCanonTracker, BlobStoreUpdates},
+        test_utils::MockTransaction,
+        CanonicalStateUpdate, TransactionPool, TransactionPoolExt,
+    };
+    use std::sync::Arc;
 
     // Function to prepare the EthApi with mock data
     fn prepare_eth_api(
@@ -487,7 +536,7 @@ mod tests {
         mut oldest_block: Option<B256>,
         block_count: u64,
         mock_provider: MockEthProvider,
-    ) -> (EthApi<MockEthProvider, TestPool, NoopNetwork, EthEvmConfig>, Vec<u128>, Vec<f64>) {
+    ) -> (TestEthApi<MockEthProvider>, Vec<u128>, Vec<f64>) {
         let mut rng = generators::rng();
 
         // Build mock data
@@ -684,6 +733,65 @@ mod tests {
         );
     }
 
+    /// Percentiles that are not sorted or out of range are rejected
+    #[tokio::test]
+    async fn test_fee_history_invalid_percentiles() {
+        let block_count = 10;
+        let newest_block = 1337;
+        let oldest_block = None;
+
+        let (eth_api, _, _) =
+            prepare_eth_api(newest_block, oldest_block, block_count, MockEthProvider::default());
+
+        for percentiles in [vec![50.0, 10.0], vec![10.0, 100.5], vec![-1.0, 50.0]] {
+            let err = eth_api
+                .fee_history(block_count, newest_block.into(), Some(percentiles))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, EthApiError::InvalidRewardPercentiles));
+        }
+    }
+
+    /// The gas price is the base fee of the next block plus the suggested priority fee
+    #[tokio::test]
+    async fn test_gas_price_uses_next_block_base_fee() {
+        let mock_provider = MockEthProvider::default();
+        // a full block raises the base fee of the next block by 12.5%
+        let header = Header {
+            number: 0,
+            gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            gas_used: ETHEREUM_BLOCK_GAS_LIMIT,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        mock_provider.add_block(B256::with_last_byte(1), Block { header, ..Default::default() });
+        let eth_api = build_test_eth_api(mock_provider);
+
+        assert_eq!(eth_api.next_block_base_fee().unwrap(), Some(1_125_000_000));
+        let suggested_tip = EthApiServer::max_priority_fee_per_gas(&eth_api).await.unwrap();
+        let gas_price = EthApiServer::gas_price(&eth_api).await.unwrap();
+        assert_eq!(gas_price, suggested_tip + U256::from(1_125_000_000u64));
+    }
+
+    /// Blocks without receipts have zero rewards
+    #[tokio::test]
+    async fn test_fee_history_rewards_without_receipts() {
+        let block_count = 10;
+        let newest_block = 1337;
+        let oldest_block = None;
+
+        let (eth_api, _, gas_used_ratios) =
+            prepare_eth_api(newest_block, oldest_block, block_count, MockEthProvider::default());
+
+        let fee_history = eth_api
+            .fee_history(block_count, newest_block.into(), Some(vec![10.0, 50.0, 90.0]))
+            .await
+            .unwrap();
+
+        assert_eq!(&fee_history.gas_used_ratio, &gas_used_ratios);
+        assert_eq!(fee_history.reward, Some(vec![vec![0, 0, 0]; block_count as usize]));
+    }
+
     /// Requesting all blocks should be ok
     #[tokio::test]
     async fn test_fee_history_all_blocks() {
@@ -720,4 +828,214 @@ mod tests {
             "all: no percentiles were requested, so there should be no rewards result"
         );
     }
+
+    /// Slots that are only accessed if another slot is warm are found by executing the request
+    /// with the access list again
+    #[tokio::test]
+    async fn test_create_access_list_with_warm_dependent_slot() {
+        let mock_provider = MockEthProvider::default();
+        let header = Header { gas_limit: ETHEREUM_BLOCK_GAS_LIMIT, ..Default::default() };
+        let hash = header.hash_slow();
+        mock_provider.add_block(hash, Block { header: header.clone(), ..Default::default() });
+        mock_provider.add_header(hash, header);
+        let eth_api = build_test_eth_api(mock_provider);
+
+        // loads slot 1 only if loading slot 0 costs less than 1024 gas, i.e. slot 0 is warm
+        let code = hex!("5a600054505a900361040011601057005b6001545000");
+        let contract = Address::random();
+        let overrides = StateOverride::from([(
+            contract,
+            AccountOverride { code: Some(Bytes::from_static(&code)), ..Default::default() },
+        )]);
+        let request = TransactionRequest {
+            from: Some(Address::random()),
+            to: Some(TxKind::Call(contract)),
+            gas: Some(100_000),
+            ..Default::default()
+        };
+
+        let result =
+            eth_api.create_access_list_at(request.clone(), None, Some(overrides)).await.unwrap();
+        // the slots of the recipient are listed, the sender isn't
+        assert_eq!(
+            result.access_list,
+            AccessList(vec![AccessListItem {
+                address: contract,
+                storage_keys: vec![B256::ZERO, B256::with_last_byte(1)],
+            }])
+        );
+        // the intrinsic gas, the access list and the execution with both slots warm
+        assert_eq!(result.gas_used, U256::from(21_000 + 2_400 + 2 * 1_900 + 240));
+
+        // without the override the recipient has no code
+        let result = eth_api.create_access_list_at(request, None, None).await.unwrap();
+        assert_eq!(result.access_list, AccessList::default());
+        assert_eq!(result.gas_used, U256::from(21_000));
+    }
+
+    /// Blocks before Cancun have zero blob fees, blocks after Prague use the EIP-7691 schedule
+    #[tokio::test]
+    async fn test_fee_history_blob_fees() {
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .shanghai_activated()
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(10))
+            .with_fork(Hardfork::Prague, ForkCondition::Timestamp(30))
+            .build();
+        let mock_provider =
+            MockEthProvider { chain_spec: Arc::new(chain_spec), ..Default::default() };
+
+        let excess_blob_gas = 10 * 1024 * 1024;
+        let blob_gas_used = 3 * DATA_GAS_PER_BLOB;
+        for number in 0..4 {
+            let cancun = number >= 2;
+            let header = Header {
+                number,
+                timestamp: number * 10,
+                gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+                base_fee_per_gas: Some(7),
+                excess_blob_gas: cancun.then_some(excess_blob_gas),
+                blob_gas_used: cancun.then_some(blob_gas_used),
+                ..Default::default()
+            };
+            let hash = header.hash_slow();
+            mock_provider.add_block(hash, Block { header: header.clone(), ..Default::default() });
+            mock_provider.add_header(hash, header);
+        }
+        let eth_api = build_test_eth_api(mock_provider);
+
+        let fee_history = eth_api.fee_history(4, BlockNumberOrTag::Latest, None).await.unwrap();
+
+        let prague = BlobParams::prague();
+        let next_block_blob_fee =
+            prague.calc_blob_fee(prague.next_block_excess_blob_gas(excess_blob_gas, blob_gas_used));
+        assert_eq!(fee_history.base_fee_per_blob_gas, vec![0, 0, 23, 8, next_block_blob_fee]);
+        assert_eq!(fee_history.blob_gas_used_ratio, vec![0.0, 0.0, 0.5, 1.0 / 3.0]);
+
+        let blob_base_fee = eth_api.blob_base_fee().await.unwrap();
+        assert_eq!(blob_base_fee, U256::from(next_block_blob_fee));
+    }
+
+    #[tokio::test]
+    async fn test_blob_transaction_in_pool_and_after_inclusion() {
+        let provider = MockEthProvider::default();
+        let eth_api = build_test_eth_api(provider.clone());
+
+        let sidecar = BlobTransactionSidecar {
+            blobs: vec![Default::default()],
+            commitments: vec![Default::default()],
+            proofs: vec![Default::default()],
+        };
+        let pooled = MockTransaction::eip4844_with_sidecar(sidecar.clone());
+        let hash = *pooled.get_hash();
+        let tx = pooled.to_recovered_transaction().into_signed();
+        let versioned_hashes = sidecar.versioned_hashes().collect::<Vec<_>>();
+        eth_api.pool().add_external_transaction(pooled).await.unwrap();
+
+        let assert_blob_fields = |rpc_tx: reth_rpc_types::Transaction| {
+            assert_eq!(rpc_tx.hash, hash);
+            assert_eq!(rpc_tx.transaction_type, Some(EIP4844_TX_TYPE_ID));
+            assert_eq!(rpc_tx.blob_versioned_hashes.as_ref(), Some(&versioned_hashes));
+            assert_eq!(rpc_tx.max_fee_per_blob_gas, tx.max_fee_per_blob_gas());
+            rpc_tx
+        };
+
+        // in the pool
+        let rpc_tx = EthApiServer::transaction_by_hash(&eth_api, hash).await.unwrap().unwrap();
+        assert!(assert_blob_fields(rpc_tx).block_hash.is_none());
+        let raw = EthApiServer::raw_transaction_by_hash(&eth_api, hash).await.unwrap();
+        assert_eq!(raw, Some(tx.envelope_encoded()));
+        let pooled_sidecar = eth_api.blob_sidecar_by_tx_hash(hash).await.unwrap();
+        assert_eq!(pooled_sidecar, Some(sidecar.clone()));
+
+        // mined in a block, the pool removes the transaction but keeps the sidecar
+        let block = Block {
+            header: Header { number: 1, base_fee_per_gas: Some(7), ..Default::default() },
+            body: vec![tx.clone()],
+            ..Default::default()
+        }
+        .seal_slow();
+        provider.add_block(block.hash(), block.clone().unseal());
+        eth_api.pool().on_canonical_state_change(CanonicalStateUpdate {
+            new_tip: &block,
+            pending_block_base_fee: 7,
+            pending_block_blob_fee: None,
+            changed_accounts: vec![],
+            mined_transactions: vec![hash],
+        });
+        assert!(eth_api.pool().get(&hash).is_none());
+
+        let rpc_tx = EthApiServer::transaction_by_hash(&eth_api, hash).await.unwrap().unwrap();
+        assert_eq!(assert_blob_fields(rpc_tx).block_hash, Some(block.hash()));
+        let raw = EthApiServer::raw_transaction_by_hash(&eth_api, hash).await.unwrap();
+        assert_eq!(raw, Some(tx.envelope_encoded()));
+        let included_sidecar = eth_api.blob_sidecar_by_tx_hash(hash).await.unwrap();
+        assert_eq!(included_sidecar, Some(sidecar));
+
+        // the sidecar is deleted once the block is finalized, like the pool maintenance does
+        let mut tracker = BlobStoreCanonTracker::default();
+        tracker.add_block(block.number, [hash]);
+        let BlobStoreUpdates::Finalized(finalized) = tracker.on_finalized_block(block.number)
+        else {
+            panic!("expected finalized blob transactions")
+        };
+        eth_api.pool().delete_blobs(finalized);
+        assert_eq!(eth_api.blob_sidecar_by_tx_hash(hash).await.unwrap(), None);
+        let rpc_tx = EthApiServer::transaction_by_hash(&eth_api, hash).await.unwrap().unwrap();
+        assert_blob_fields(rpc_tx);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_by_sender_and_nonce() {
+        let provider = MockEthProvider::default();
+        let eth_api = build_test_eth_api(provider.clone());
+
+        // https://etherscan.io/tx/0xa694b71e6c128a2ed8e2e0f6770bddbe52e3bb8f10e8472f9a79ab81497a8b5d
+        let raw = hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3");
+        let tx = TransactionSigned::decode_enveloped(&mut &raw[..]).unwrap();
+        let sender = tx.recover_signer().unwrap();
+        let nonce = tx.nonce();
+        provider.add_account(sender, ExtendedAccount::new(nonce, U256::MAX));
+
+        // a future nonce is neither pending nor mined
+        let found =
+            EthApiServer::transaction_by_sender_and_nonce(&eth_api, sender, U64::from(nonce))
+                .await
+                .unwrap();
+        assert!(found.is_none());
+
+        // pending in the pool
+        let hash = eth_api.send_raw_transaction(Bytes::from(raw)).await.unwrap();
+        let found =
+            EthApiServer::transaction_by_sender_and_nonce(&eth_api, sender, U64::from(nonce))
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(found.hash, hash);
+        assert!(found.block_hash.is_none());
+
+        // mined and removed from the pool, the state of the mock is the same at every block, so
+        // the nonce was incremented by the first block
+        let block_hash = B256::random();
+        let block = Block { header: Header::default(), body: vec![tx], ..Default::default() };
+        provider.add_block(block_hash, block);
+        provider.add_account(sender, ExtendedAccount::new(nonce + 1, U256::MAX));
+        eth_api.pool().remove_transactions(vec![hash]);
+
+        let found =
+            EthApiServer::transaction_by_sender_and_nonce(&eth_api, sender, U64::from(nonce))
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(found.hash, hash);
+        assert_eq!(found.block_hash, Some(block_hash));
+        assert_eq!(found.block_number, Some(0));
+        assert_eq!(found.transaction_index, Some(0));
+
+        // the next nonce of the account isn't used yet
+        let found =
+            EthApiServer::transaction_by_sender_and_nonce(&eth_api, sender, U64::from(nonce + 1))
+                .await
+                .unwrap();
+        assert!(found.is_none());
+    }
 }
@@ -0,0 +1,1077 @@
+//! Contains RPC handler implementations for `eth_simulateV1`.
+
+use crate::{
+    eth::{
+        error::{EthApiError, EthResult, RevertError, RpcInvalidTransactionError},
+        revm_utils::{
+            apply_state_overrides, build_call_evm_env, get_precompiles, hashed_cache_storage,
+        },
+        EthTransactions,
+    },
+    result::rpc_error_with_code,
+    EthApi,
+};
+use jsonrpsee::types::ErrorObject;
+use reth_evm::{precompile::moved_precompiles_handle_register, ConfigureEvm};
+use reth_network_api::NetworkInfo;
+use reth_primitives::{
+    address, b256,
+    constants::{eip4844::DATA_GAS_PER_BLOB, BEACON_NONCE, EIP1559_INITIAL_BASE_FEE},
+    keccak256, logs_bloom, proofs,
+    revm::compat::into_reth_acc,
+    trie::EMPTY_ROOT_HASH,
+    Address, Block, BlockId, BlockWithSenders, Bloom, Bytes, ChainSpec, Hardfork, Header, Log,
+    Receipt, Requests, SealedHeader, Signature, Transaction, TransactionSigned, TxEip1559,
+    TxEip2930, TxEip4844, TxKind, TxLegacy, Withdrawals, B256, EMPTY_OMMER_ROOT_HASH, U256, U64,
+};
+use reth_provider::{
+    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory,
+};
+use reth_revm::database::StateProviderDatabase;
+use reth_rpc_server_types::constants::MAX_SIMULATE_BLOCKS;
+use reth_rpc_types::{
+    state::StateOverride, BlockOverrides, SimAccountOverride, SimBlock, SimCallResult,
+    SimulateError, SimulatePayload, SimulatedBlock, ToRpcError, TransactionRequest,
+};
+use reth_rpc_types_compat::block::from_block;
+use reth_transaction_pool::TransactionPool;
+use reth_trie::HashedPostState;
+use revm::{
+    db::CacheDB,
+    inspectors::NoOpInspector,
+    interpreter::{CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome},
+    primitives::{
+        BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, ResultAndState,
+        SpecId, TxEnv,
+    },
+    Database, DatabaseCommit, EvmContext, GetInspector, Inspector,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+/// The state the blocks are simulated on: the state of the base block with the overrides and the
+/// changes of the simulated blocks.
+type SimulationDb = CacheDB<StateProviderDatabase<StateProviderBox>>;
+
+/// The time between two simulated blocks, unless the timestamp is overridden.
+const SIMULATED_BLOCK_TIME: u64 = 12;
+
+/// The address the synthetic logs of ether transfers are emitted from.
+const TRANSFER_LOG_EMITTER: Address = address!("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee");
+
+/// The topic of the synthetic logs of ether transfers: the signature of the ERC-20
+/// `Transfer(address,address,uint256)` event.
+const TRANSFER_EVENT_TOPIC: B256 =
+    b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+
+/// The error code of a simulated call that reverted.
+const REVERTED_ERROR_CODE: i32 = -32000;
+
+/// The error code of a simulated call that halted.
+const VM_ERROR_CODE: i32 = -32015;
+
+impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
+where
+    Pool: TransactionPool + Clone + 'static,
+    Provider:
+        BlockReaderIdExt + ChainSpecProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+    EvmConfig: ConfigureEvm + 'static,
+{
+    /// Simulates a sequence of blocks of calls on top of the given block (`eth_simulateV1`).
+    ///
+    /// The state overrides and changes of a block are part of the state of the following blocks.
+    /// Gaps between the block numbers are filled with empty blocks.
+    pub async fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> EthResult<Vec<SimulatedBlock>> {
+        let SimulatePayload {
+            block_state_calls,
+            trace_transfers,
+            validation,
+            return_full_transactions,
+        } = payload;
+        if block_state_calls.is_empty() {
+            return Err(EthApiError::InvalidParams(String::from("empty input")))
+        }
+
+        let block_id = block_number.unwrap_or_default();
+        let (base, at) = match self.provider().sealed_header_by_id(block_id)? {
+            // the state of the pending block can only be identified by the tag
+            Some(header) if block_id.is_pending() => (header, block_id),
+            Some(header) => {
+                let hash = header.hash();
+                (header, hash.into())
+            }
+            None if block_id.is_pending() => {
+                let latest =
+                    self.provider().latest_header()?.ok_or(EthApiError::UnknownBlockNumber)?;
+                let hash = latest.hash();
+                (latest, hash.into())
+            }
+            None => return Err(EthApiError::UnknownBlockNumber),
+        };
+        let blocks = sanitize_blocks(&base, block_state_calls)?;
+
+        let mut context = SimulationContext {
+            chain_spec: self.provider().chain_spec(),
+            total_difficulty: self.provider().header_td_by_number(base.number)?.unwrap_or_default(),
+            trace_transfers,
+            validation,
+            return_full_transactions,
+            moved_precompiles: HashMap::new(),
+        };
+
+        let this = self.clone();
+        self.spawn_with_state_at_block(at, move |state| {
+            let mut db = CacheDB::new(StateProviderDatabase::new(state));
+            let mut parent = base;
+            let mut results = Vec::with_capacity(blocks.len());
+            for block in blocks {
+                let (header, simulated) =
+                    this.simulate_block(&mut db, &mut context, &parent, block)?;
+                parent = header;
+                results.push(simulated);
+            }
+            Ok(results)
+        })
+        .await
+    }
+
+    /// Simulates the calls of a block on top of the parent and returns the header of the block
+    /// and the simulated block.
+    ///
+    /// The changes of the block are committed to the database.
+    fn simulate_block(
+        &self,
+        db: &mut SimulationDb,
+        context: &mut SimulationContext,
+        parent: &SealedHeader,
+        block: SimBlock,
+    ) -> EthResult<(SealedHeader, SimulatedBlock)> {
+        let SimBlock { block_overrides, state_overrides, calls } = block;
+        let mut block_overrides = block_overrides.unwrap_or_default();
+        if let Some(block_hashes) = block_overrides.block_hash.take() {
+            db.block_hashes
+                .extend(block_hashes.into_iter().map(|(num, hash)| (U256::from(num), hash)));
+        }
+        let mut header =
+            simulated_header(&context.chain_spec, parent, block_overrides, context.validation);
+
+        let mut cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::LATEST);
+        let mut block_env = BlockEnv::default();
+        EvmConfig::fill_cfg_and_block_env(
+            &mut cfg,
+            &mut block_env,
+            &context.chain_spec,
+            &header,
+            context.total_difficulty,
+        );
+        cfg.memory_limit = self.inner.memory_limit;
+        // the gas of the calls is limited by the gas pool of the block instead
+        cfg.disable_block_gas_limit = true;
+        if !context.validation {
+            cfg.disable_base_fee = true;
+            cfg.disable_eip3607 = true;
+        }
+
+        if let Some(state_overrides) = state_overrides {
+            context.apply_state_overrides(cfg.handler_cfg.spec_id, state_overrides, db)?;
+        }
+
+        let gas_pool = header.gas_limit.min(self.inner.gas_cap);
+        let mut cumulative_gas_used = 0;
+        let mut blob_gas_used = 0;
+        let mut transactions = Vec::with_capacity(calls.len());
+        let mut senders = Vec::with_capacity(calls.len());
+        let mut receipts = Vec::with_capacity(calls.len());
+        let mut results = Vec::with_capacity(calls.len());
+        let mut call_logs = Vec::with_capacity(calls.len());
+
+        for mut request in calls {
+            let sender = request.from.unwrap_or_default();
+            let nonce = match request.nonce {
+                Some(nonce) => nonce,
+                None => db.basic(sender)?.map(|account| account.nonce).unwrap_or_default(),
+            };
+            request.nonce = Some(nonce);
+
+            let remaining_gas = gas_pool - cumulative_gas_used;
+            let gas_limit = match request.gas.map(|gas| u64::try_from(gas).unwrap_or(u64::MAX)) {
+                Some(gas) if gas > remaining_gas => {
+                    return Err(EthSimulateError::BlockGasLimitReached.into())
+                }
+                Some(gas) => gas,
+                None => remaining_gas,
+            };
+            request.gas = Some(gas_limit.into());
+
+            if context.validation &&
+                request.gas_price.is_none() &&
+                request.max_fee_per_gas.is_none()
+            {
+                // validated calls pay at least the base fee
+                request.max_fee_per_gas = Some(block_env.basefee.saturating_to());
+            }
+
+            let mut env = build_call_evm_env(cfg.clone(), block_env.clone(), request.clone())
+                .map_err(EthSimulateError::map_invalid_transaction)?;
+            if !context.validation {
+                env.tx.nonce = None;
+            }
+            let transaction = simulated_transaction(&request, &env.tx, cfg.chain_id);
+
+            let (ResultAndState { result, state }, transfer_logs) = if context.trace_transfers {
+                let (res, inspector) =
+                    self.transact_simulated(db, env, context, TransferLogInspector::default())?;
+                (res, Some(inspector.logs))
+            } else {
+                (self.transact_simulated(db, env, context, NoOpInspector)?.0, None)
+            };
+            db.commit(state);
+
+            cumulative_gas_used += result.gas_used();
+            blob_gas_used += transaction
+                .blob_versioned_hashes()
+                .map_or(0, |hashes| hashes.len() as u64 * DATA_GAS_PER_BLOB);
+
+            let gas_used = U64::from(result.gas_used());
+            let (call_result, logs) = match result {
+                ExecutionResult::Success { output, logs, .. } => (
+                    SimCallResult {
+                        return_data: output.into_data(),
+                        gas_used,
+                        status: U64::from(1),
+                        ..Default::default()
+                    },
+                    logs,
+                ),
+                ExecutionResult::Revert { output, .. } => {
+                    let error = SimulateError {
+                        code: REVERTED_ERROR_CODE,
+                        message: RevertError::new(output.clone()).to_string(),
+                        data: (!output.is_empty()).then(|| output.clone()),
+                    };
+                    let call_result = SimCallResult {
+                        return_data: output,
+                        gas_used,
+                        error: Some(error),
+                        ..Default::default()
+                    };
+                    (call_result, Vec::new())
+                }
+                ExecutionResult::Halt { reason, .. } => {
+                    let error = SimulateError {
+                        code: VM_ERROR_CODE,
+                        message: RpcInvalidTransactionError::halt(reason, gas_limit).to_string(),
+                        data: None,
+                    };
+                    (
+                        SimCallResult { gas_used, error: Some(error), ..Default::default() },
+                        Vec::new(),
+                    )
+                }
+            };
+
+            call_logs.push(transfer_logs.unwrap_or_else(|| logs.clone()));
+            receipts.push(Receipt {
+                tx_type: transaction.tx_type(),
+                success: call_result.error.is_none(),
+                cumulative_gas_used,
+                logs,
+                #[cfg(feature = "optimism")]
+                deposit_nonce: None,
+                #[cfg(feature = "optimism")]
+                deposit_receipt_version: None,
+            });
+            results.push(call_result);
+            transactions.push(transaction);
+            senders.push(sender);
+        }
+
+        header.gas_used = cumulative_gas_used;
+        if header.blob_gas_used.is_some() {
+            header.blob_gas_used = Some(blob_gas_used);
+        }
+        header.transactions_root = proofs::calculate_transaction_root(&transactions);
+        let receipts_ref = receipts.iter().collect::<Vec<_>>();
+        #[cfg(feature = "optimism")]
+        {
+            header.receipts_root = proofs::calculate_receipt_root_no_memo_optimism(
+                &receipts_ref,
+                &context.chain_spec,
+                header.timestamp,
+            );
+        }
+        #[cfg(not(feature = "optimism"))]
+        {
+            header.receipts_root = proofs::calculate_receipt_root_no_memo(&receipts_ref);
+        }
+        header.logs_bloom = logs_bloom(receipts.iter().flat_map(|receipt| &receipt.logs));
+        header.state_root =
+            db.db.hashed_state_root_with_storage_roots(hashed_cache_state(db), HashMap::new())?;
+
+        let header = header.seal_slow();
+        db.block_hashes.insert(U256::from(header.number), header.hash());
+
+        let tx_hashes = transactions.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+        let block = Block {
+            header: header.clone().unseal(),
+            body: transactions,
+            ommers: Vec::new(),
+            withdrawals: header.withdrawals_root.is_some().then(Withdrawals::default),
+            requests: header.requests_root.is_some().then(Requests::default),
+        };
+        let block = from_block(
+            BlockWithSenders { block, senders },
+            context.total_difficulty,
+            context.return_full_transactions.into(),
+            Some(header.hash()),
+        )?;
+
+        let mut log_index = 0;
+        let calls = results
+            .into_iter()
+            .zip(call_logs)
+            .enumerate()
+            .map(|(tx_index, (mut result, logs))| {
+                result.logs = logs
+                    .into_iter()
+                    .map(|inner| {
+                        let log = reth_rpc_types::Log {
+                            inner,
+                            block_hash: Some(header.hash()),
+                            block_number: Some(header.number),
+                            block_timestamp: Some(header.timestamp),
+                            transaction_hash: Some(tx_hashes[tx_index]),
+                            transaction_index: Some(tx_index as u64),
+                            log_index: Some(log_index),
+                            removed: false,
+                        };
+                        log_index += 1;
+                        log
+                    })
+                    .collect();
+                result
+            })
+            .collect();
+
+        Ok((header, SimulatedBlock { inner: block, calls }))
+    }
+
+    /// Executes the env on the simulation database with the moved precompiles and the given
+    /// inspector, without committing the changes.
+    fn transact_simulated<I>(
+        &self,
+        db: &mut SimulationDb,
+        env: EnvWithHandlerCfg,
+        context: &SimulationContext,
+        inspector: I,
+    ) -> EthResult<(ResultAndState, I)>
+    where
+        I: for<'a> GetInspector<&'a mut SimulationDb>,
+    {
+        let mut evm = self.inner.evm_config.evm_with_env_and_inspector(db, env, inspector);
+        if !context.moved_precompiles.is_empty() {
+            evm = evm
+                .modify()
+                .append_handler_register_box(moved_precompiles_handle_register(Arc::new(
+                    context.moved_precompiles.clone(),
+                )))
+                .build();
+        }
+        let res =
+            evm.transact().map_err(|err| EthSimulateError::map_invalid_transaction(err.into()))?;
+        Ok((res, evm.context.external))
+    }
+}
+
+/// The options of an `eth_simulateV1` request and the state that is carried over between the
+/// simulated blocks, besides the database.
+#[derive(Debug)]
+struct SimulationContext {
+    /// The chain spec.
+    chain_spec: Arc<ChainSpec>,
+    /// The total difficulty of the base block, which is also the one of the simulated blocks.
+    total_difficulty: U256,
+    /// Whether to add a synthetic log for every transfer of ether.
+    trace_transfers: bool,
+    /// Whether the calls are validated like transactions.
+    validation: bool,
+    /// Whether the simulated blocks contain full transactions.
+    return_full_transactions: bool,
+    /// The address of every moved precompile, keyed by its original address.
+    moved_precompiles: HashMap<Address, Address>,
+}
+
+impl SimulationContext {
+    /// Applies the state overrides of a block, after moving the precompiles.
+    fn apply_state_overrides(
+        &mut self,
+        spec_id: SpecId,
+        overrides: HashMap<Address, SimAccountOverride>,
+        db: &mut SimulationDb,
+    ) -> EthResult<()> {
+        let mut destinations = HashSet::new();
+        let mut state_overrides = StateOverride::default();
+        for (address, account_override) in overrides {
+            let SimAccountOverride { account, move_precompile_to_address } = account_override;
+            if let Some(to) = move_precompile_to_address {
+                if !destinations.insert(to) {
+                    return Err(EthApiError::InvalidParams(format!(
+                        "multiple precompiles moved to {to}"
+                    )))
+                }
+                self.move_precompile(spec_id, address, to)?;
+            }
+            state_overrides.insert(address, account);
+        }
+        apply_state_overrides(state_overrides, db)
+    }
+
+    /// Moves the precompile at `from` to `to`, which also applies to the following blocks.
+    fn move_precompile(&mut self, spec_id: SpecId, from: Address, to: Address) -> EthResult<()> {
+        if from == to {
+            return Err(EthApiError::InvalidParams(format!(
+                "precompile {from} can't be moved to itself"
+            )))
+        }
+
+        // the precompile may have been moved to `from` by a previous block
+        let original = match self.moved_precompiles.iter().find(|(_, moved)| **moved == from) {
+            Some((original, _)) => *original,
+            None if !self.moved_precompiles.contains_key(&from) &&
+                get_precompiles(spec_id).into_iter().any(|address| address == from) =>
+            {
+                from
+            }
+            None => {
+                return Err(EthApiError::InvalidParams(format!(
+                    "account {from} is not a precompile"
+                )))
+            }
+        };
+        self.moved_precompiles.insert(original, to);
+        Ok(())
+    }
+}
+
+/// Fills in the number and timestamp of every block and adds empty blocks for the gaps between
+/// the block numbers.
+///
+/// Returns an error if the numbers or timestamps don't strictly increase, or if more than
+/// [`MAX_SIMULATE_BLOCKS`] blocks would be simulated.
+fn sanitize_blocks(
+    base: &Header,
+    blocks: Vec<SimBlock>,
+) -> Result<Vec<SimBlock>, EthSimulateError> {
+    let mut number = base.number;
+    let mut timestamp = base.timestamp;
+    let mut sanitized = Vec::with_capacity(blocks.len());
+
+    for mut block in blocks {
+        let overrides = block.block_overrides.get_or_insert_with(Default::default);
+
+        let block_number = overrides.number.map_or(number + 1, |number| number.saturating_to());
+        if block_number <= number {
+            return Err(EthSimulateError::BlockNumberInvalid {
+                number: block_number,
+                parent: number,
+            })
+        }
+        if block_number - base.number > MAX_SIMULATE_BLOCKS {
+            return Err(EthSimulateError::TooManyBlocks)
+        }
+
+        for gap in number + 1..block_number {
+            timestamp += SIMULATED_BLOCK_TIME;
+            sanitized.push(SimBlock {
+                block_overrides: Some(BlockOverrides {
+                    number: Some(U256::from(gap)),
+                    time: Some(timestamp),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+        }
+
+        let block_timestamp = overrides.time.unwrap_or(timestamp + SIMULATED_BLOCK_TIME);
+        if block_timestamp <= timestamp {
+            return Err(EthSimulateError::BlockTimestampInvalid {
+                timestamp: block_timestamp,
+                parent: timestamp,
+            })
+        }
+
+        overrides.number = Some(U256::from(block_number));
+        overrides.time = Some(block_timestamp);
+        number = block_number;
+        timestamp = block_timestamp;
+        sanitized.push(block);
+    }
+
+    Ok(sanitized)
+}
+
+/// Returns the header of a block simulated on top of the parent, before its calls are executed.
+///
+/// Unless overridden, the base fee is zero if the calls are not validated.
+fn simulated_header(
+    chain_spec: &ChainSpec,
+    parent: &SealedHeader,
+    overrides: BlockOverrides,
+    validation: bool,
+) -> Header {
+    let BlockOverrides {
+        number,
+        difficulty,
+        time,
+        gas_limit,
+        coinbase,
+        random,
+        base_fee,
+        block_hash: _,
+    } = overrides;
+
+    let number = number.map_or(parent.number + 1, |number| number.saturating_to());
+    let timestamp = time.unwrap_or(parent.timestamp + SIMULATED_BLOCK_TIME);
+    let base_fee_per_gas = match base_fee {
+        Some(base_fee) => Some(base_fee.saturating_to()),
+        None if chain_spec.is_fork_active_at_block(Hardfork::London, number) => {
+            Some(if validation {
+                parent
+                    .next_block_base_fee(chain_spec.base_fee_params_at_timestamp(timestamp))
+                    .unwrap_or(EIP1559_INITIAL_BASE_FEE)
+            } else {
+                0
+            })
+        }
+        None => None,
+    };
+    let cancun = chain_spec.is_cancun_active_at_timestamp(timestamp);
+
+    Header {
+        parent_hash: parent.hash(),
+        ommers_hash: EMPTY_OMMER_ROOT_HASH,
+        beneficiary: coinbase.unwrap_or(parent.beneficiary),
+        state_root: B256::ZERO,
+        transactions_root: EMPTY_ROOT_HASH,
+        receipts_root: EMPTY_ROOT_HASH,
+        withdrawals_root: chain_spec
+            .is_shanghai_active_at_timestamp(timestamp)
+            .then_some(EMPTY_ROOT_HASH),
+        logs_bloom: Bloom::ZERO,
+        timestamp,
+        mix_hash: random.unwrap_or_default(),
+        nonce: BEACON_NONCE,
+        base_fee_per_gas,
+        number,
+        gas_limit: gas_limit.unwrap_or(parent.gas_limit),
+        difficulty: difficulty.unwrap_or_default(),
+        gas_used: 0,
+        blob_gas_used: cancun.then_some(0),
+        excess_blob_gas: cancun.then(|| {
+            parent
+                .next_block_excess_blob_gas(chain_spec.blob_params_at_timestamp(timestamp))
+                .unwrap_or_default()
+        }),
+        extra_data: Bytes::new(),
+        parent_beacon_block_root: cancun.then_some(B256::ZERO),
+        requests_root: chain_spec
+            .is_prague_active_at_timestamp(timestamp)
+            .then_some(EMPTY_ROOT_HASH),
+    }
+}
+
+/// Returns the transaction of a simulated call.
+///
+/// The sender of the call can't sign it, so the transaction has an empty signature and its
+/// sender is taken from the call.
+fn simulated_transaction(
+    request: &TransactionRequest,
+    tx: &TxEnv,
+    chain_id: u64,
+) -> TransactionSigned {
+    let nonce = request.nonce.unwrap_or_default();
+    let to = request.to.unwrap_or(TxKind::Create);
+    let access_list = request.access_list.clone().unwrap_or_default();
+    let gas_price = tx.gas_price.saturating_to();
+    let max_fee_per_gas = request.max_fee_per_gas.unwrap_or(gas_price);
+    let max_priority_fee_per_gas = request.max_priority_fee_per_gas.unwrap_or_default();
+
+    let transaction = if !tx.blob_hashes.is_empty() {
+        Transaction::Eip4844(TxEip4844 {
+            chain_id,
+            nonce,
+            gas_limit: tx.gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            placeholder: Some(()),
+            to: to.to().copied().unwrap_or_default(),
+            value: tx.value,
+            access_list,
+            blob_versioned_hashes: tx.blob_hashes.clone(),
+            max_fee_per_blob_gas: tx.max_fee_per_blob_gas.unwrap_or_default().saturating_to(),
+            input: tx.data.clone(),
+        })
+    } else if request.max_fee_per_gas.is_some() || request.max_priority_fee_per_gas.is_some() {
+        Transaction::Eip1559(TxEip1559 {
+            chain_id,
+            nonce,
+            gas_limit: tx.gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            to,
+            value: tx.value,
+            access_list,
+            input: tx.data.clone(),
+        })
+    } else if request.access_list.is_some() {
+        Transaction::Eip2930(TxEip2930 {
+            chain_id,
+            nonce,
+            gas_price,
+            gas_limit: tx.gas_limit,
+            to,
+            value: tx.value,
+            access_list,
+            input: tx.data.clone(),
+        })
+    } else {
+        Transaction::Legacy(TxLegacy {
+            chain_id: Some(chain_id),
+            nonce,
+            gas_price,
+            gas_limit: tx.gas_limit,
+            to,
+            value: tx.value,
+            input: tx.data.clone(),
+        })
+    };
+
+    TransactionSigned::from_transaction_and_signature(transaction, Signature::default())
+}
+
+/// Returns the cached state of the [`CacheDB`] as a [`HashedPostState`] on top of the state it
+/// wraps.
+///
+/// Accounts and storage slots that were only read are included as well, with their unchanged
+/// values.
+fn hashed_cache_state<DB>(db: &CacheDB<DB>) -> HashedPostState {
+    let mut state = HashedPostState::default();
+    for (address, account) in &db.accounts {
+        let hashed_address = keccak256(address);
+        state.accounts.insert(hashed_address, account.info().map(into_reth_acc));
+        state.storages.insert(hashed_address, hashed_cache_storage(account));
+    }
+    state
+}
+
+/// Returns the synthetic log of an ether transfer.
+fn transfer_log(from: Address, to: Address, value: U256) -> Log {
+    Log::new_unchecked(
+        TRANSFER_LOG_EMITTER,
+        vec![TRANSFER_EVENT_TOPIC, from.into_word(), to.into_word()],
+        Bytes::from(value.to_be_bytes::<32>()),
+    )
+}
+
+/// Collects the logs of a simulated call, with a synthetic log for every transfer of ether, in
+/// the order they are emitted.
+///
+/// The logs of reverted calls are discarded, including their transfers.
+#[derive(Debug, Default)]
+struct TransferLogInspector {
+    /// The logs emitted so far.
+    logs: Vec<Log>,
+    /// The number of logs at the start of every call or create being executed.
+    frames: Vec<usize>,
+    /// The index of the transfer log of every create being executed, if it transfers ether.
+    ///
+    /// The recipient of the transfer is only known once the contract is created.
+    creates: Vec<Option<usize>>,
+}
+
+impl TransferLogInspector {
+    /// Starts a new call or create.
+    fn enter(&mut self) {
+        self.frames.push(self.logs.len());
+    }
+
+    /// Ends the current call or create and discards its logs if it failed.
+    fn exit(&mut self, success: bool) {
+        if let Some(logs) = self.frames.pop() {
+            if !success {
+                self.logs.truncate(logs);
+            }
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for TransferLogInspector {
+    fn log(&mut self, _context: &mut EvmContext<DB>, log: &Log) {
+        self.logs.push(log.clone());
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.enter();
+        // `CALLCODE` and `DELEGATECALL` don't transfer ether to another account
+        if inputs.scheme == CallScheme::Call {
+            if let Some(value) = inputs.transfer_value().filter(|value| !value.is_zero()) {
+                self.logs.push(transfer_log(inputs.caller, inputs.target_address, value));
+            }
+        }
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.exit(outcome.result.result.is_ok());
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.enter();
+        let transfer = (!inputs.value.is_zero()).then(|| {
+            self.logs.push(transfer_log(inputs.caller, Address::ZERO, inputs.value));
+            self.logs.len() - 1
+        });
+        self.creates.push(transfer);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        if let (Some(Some(index)), Some(address)) = (self.creates.pop(), outcome.address) {
+            self.logs[index] = transfer_log(inputs.caller, address, inputs.value);
+        }
+        self.exit(outcome.result.result.is_ok());
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if !value.is_zero() {
+            self.logs.push(transfer_log(contract, target, value));
+        }
+    }
+}
+
+/// Errors of `eth_simulateV1` with their dedicated error codes.
+#[derive(Debug, thiserror::Error)]
+pub enum EthSimulateError {
+    /// The nonce of a validated call is lower than the nonce of the sender.
+    #[error("nonce too low")]
+    NonceTooLow,
+    /// The nonce of a validated call is higher than the nonce of the sender.
+    #[error("nonce too high")]
+    NonceTooHigh,
+    /// The fee cap of a validated call is lower than the base fee.
+    #[error("max fee per gas less than block base fee")]
+    BaseFeeTooLow,
+    /// The gas limit of a call is lower than its intrinsic gas.
+    #[error("intrinsic gas too low")]
+    IntrinsicGas,
+    /// The sender can't pay for the gas and value of a call.
+    #[error("insufficient funds for gas * price + value")]
+    InsufficientFunds,
+    /// The calls of a block use more gas than the block has.
+    #[error("block gas limit reached")]
+    BlockGasLimitReached,
+    /// The number of a block is not higher than the number of its parent.
+    #[error("block numbers must be in order: {number} <= {parent}")]
+    BlockNumberInvalid {
+        /// The number of the block.
+        number: u64,
+        /// The number of the parent.
+        parent: u64,
+    },
+    /// The timestamp of a block is not higher than the timestamp of its parent.
+    #[error("block timestamps must be in order: {timestamp} <= {parent}")]
+    BlockTimestampInvalid {
+        /// The timestamp of the block.
+        timestamp: u64,
+        /// The timestamp of the parent.
+        parent: u64,
+    },
+    /// The sender of a validated call is a contract.
+    #[error("sender is not an EOA")]
+    SenderIsNotEOA,
+    /// The init code of a call exceeds the maximum size.
+    #[error("max initcode size exceeded")]
+    MaxInitCodeSizeExceeded,
+    /// More than [`MAX_SIMULATE_BLOCKS`] blocks would be simulated.
+    #[error("too many blocks")]
+    TooManyBlocks,
+}
+
+impl EthSimulateError {
+    /// Returns the error code of the error.
+    pub const fn error_code(&self) -> i32 {
+        match self {
+            Self::NonceTooLow => -38010,
+            Self::NonceTooHigh => -38011,
+            Self::BaseFeeTooLow => -38012,
+            Self::IntrinsicGas => -38013,
+            Self::InsufficientFunds => -38014,
+            Self::BlockGasLimitReached => -38015,
+            Self::BlockNumberInvalid { .. } => -38020,
+            Self::BlockTimestampInvalid { .. } => -38021,
+            Self::SenderIsNotEOA => -38024,
+            Self::MaxInitCodeSizeExceeded => -38025,
+            Self::TooManyBlocks => -38026,
+        }
+    }
+
+    /// Converts the invalid transaction errors that have a dedicated error code, keeping the
+    /// other errors as they are.
+    fn map_invalid_transaction(err: EthApiError) -> EthApiError {
+        let EthApiError::InvalidTransaction(err) = err else { return err };
+        let err = match err {
+            RpcInvalidTransactionError::NonceTooLow => Self::NonceTooLow,
+            RpcInvalidTransactionError::NonceTooHigh => Self::NonceTooHigh,
+            RpcInvalidTransactionError::FeeCapTooLow => Self::BaseFeeTooLow,
+            RpcInvalidTransactionError::GasTooLow | RpcInvalidTransactionError::GasTooHigh => {
+                Self::IntrinsicGas
+            }
+            RpcInvalidTransactionError::InsufficientFunds |
+            RpcInvalidTransactionError::InsufficientFundsForTransfer => Self::InsufficientFunds,
+            RpcInvalidTransactionError::SenderNoEOA => Self::SenderIsNotEOA,
+            RpcInvalidTransactionError::MaxInitCodeSizeExceeded => Self::MaxInitCodeSizeExceeded,
+            err => return EthApiError::InvalidTransaction(err),
+        };
+        err.into()
+    }
+}
+
+impl ToRpcError for EthSimulateError {
+    fn to_rpc_error(&self) -> ErrorObject<'static> {
+        rpc_error_with_code(self.error_code(), self.to_string())
+    }
+}
+
+impl From<EthSimulateError> for EthApiError {
+    fn from(err: EthSimulateError) -> Self {
+        Self::other(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::test_utils::build_test_eth_api;
+    use reth_primitives::hex;
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_types::{state::AccountOverride, TransactionInput};
+
+    const SENDER: Address = address!("c000000000000000000000000000000000000000");
+    const RECIPIENT: Address = address!("c100000000000000000000000000000000000000");
+
+    fn base_header() -> Header {
+        Header {
+            number: 20_000_000,
+            timestamp: 1_720_000_000,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            excess_blob_gas: Some(0),
+            blob_gas_used: Some(0),
+            ..Default::default()
+        }
+    }
+
+    /// Returns a provider with the base block and the sender, and the hash of the base block.
+    fn provider_with_base_block() -> (MockEthProvider, B256) {
+        let provider = MockEthProvider::default();
+        let header = base_header().seal_slow();
+        let hash = header.hash();
+        provider.add_block(hash, Block { header: header.unseal(), ..Default::default() });
+        provider.add_account(SENDER, ExtendedAccount::new(1, U256::from(1)));
+        (provider, hash)
+    }
+
+    fn block_with_calls(calls: Vec<TransactionRequest>) -> SimBlock {
+        SimBlock { calls, ..Default::default() }
+    }
+
+    fn call(from: Address, to: Address) -> TransactionRequest {
+        TransactionRequest { from: Some(from), to: Some(TxKind::Call(to)), ..Default::default() }
+    }
+
+    fn block_at(number: Option<u64>, time: Option<u64>) -> SimBlock {
+        SimBlock {
+            block_overrides: Some(BlockOverrides {
+                number: number.map(U256::from),
+                time,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sanitize_simulated_blocks() {
+        let base = base_header();
+
+        let blocks =
+            sanitize_blocks(&base, vec![block_at(None, None), block_at(Some(20_000_004), None)])
+                .unwrap();
+        let blocks = blocks
+            .iter()
+            .map(|block| {
+                let overrides = block.block_overrides.as_ref().unwrap();
+                (overrides.number.unwrap().to::<u64>(), overrides.time.unwrap())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            blocks,
+            vec![
+                (20_000_001, 1_720_000_012),
+                (20_000_002, 1_720_000_024),
+                (20_000_003, 1_720_000_036),
+                (20_000_004, 1_720_000_048),
+            ]
+        );
+
+        let err = sanitize_blocks(
+            &base,
+            vec![block_at(Some(20_000_002), None), block_at(Some(20_000_002), None)],
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            EthSimulateError::BlockNumberInvalid { number: 20_000_002, parent: 20_000_002 }
+        ));
+
+        let err = sanitize_blocks(&base, vec![block_at(None, Some(1_720_000_000))]).unwrap_err();
+        assert!(matches!(err, EthSimulateError::BlockTimestampInvalid { .. }));
+
+        let err = sanitize_blocks(
+            &base,
+            vec![block_at(Some(20_000_000 + MAX_SIMULATE_BLOCKS + 1), None)],
+        )
+        .unwrap_err();
+        assert!(matches!(err, EthSimulateError::TooManyBlocks));
+    }
+
+    #[tokio::test]
+    async fn simulate_empty_input() {
+        let (provider, hash) = provider_with_base_block();
+        let eth_api = build_test_eth_api(provider);
+
+        let err = eth_api.simulate_v1(SimulatePayload::default(), Some(hash.into())).await;
+        assert!(matches!(err, Err(EthApiError::InvalidParams(_))));
+    }
+
+    #[tokio::test]
+    async fn simulate_trace_transfers() {
+        let (provider, hash) = provider_with_base_block();
+        let eth_api = build_test_eth_api(provider);
+
+        // the ether received in the first block is sent back in the second one
+        let payload = SimulatePayload {
+            block_state_calls: vec![
+                block_with_calls(vec![TransactionRequest {
+                    value: Some(U256::from(1)),
+                    ..call(SENDER, RECIPIENT)
+                }]),
+                block_with_calls(vec![TransactionRequest {
+                    value: Some(U256::from(1)),
+                    ..call(RECIPIENT, SENDER)
+                }]),
+            ],
+            trace_transfers: true,
+            ..Default::default()
+        };
+        let blocks = eth_api.simulate_v1(payload, Some(hash.into())).await.unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1].inner.header.parent_hash, blocks[0].inner.header.hash.unwrap());
+
+        for (block, (from, to)) in blocks.iter().zip([(SENDER, RECIPIENT), (RECIPIENT, SENDER)]) {
+            let result = &block.calls[0];
+            assert_eq!(result.status, U64::from(1));
+            assert_eq!(result.logs.len(), 1);
+            assert_eq!(result.logs[0].inner, transfer_log(from, to, U256::from(1)));
+            assert_eq!(result.logs[0].block_hash, block.inner.header.hash);
+        }
+    }
+
+    #[tokio::test]
+    async fn simulate_moved_precompile() {
+        let (provider, hash) = provider_with_base_block();
+        let eth_api = build_test_eth_api(provider);
+
+        let identity = address!("0000000000000000000000000000000000000004");
+        let moved_identity = address!("0000000000000000000000000000000000000123");
+        let input = Bytes::from_static(b"identity");
+        let identity_call = |to| TransactionRequest {
+            input: TransactionInput::new(input.clone()),
+            ..call(SENDER, to)
+        };
+
+        // the code of the identity precompile only emits an empty log
+        let account_override = SimAccountOverride {
+            account: AccountOverride {
+                code: Some(Bytes::from_static(&hex!("60006000a000"))),
+                ..Default::default()
+            },
+            move_precompile_to_address: Some(moved_identity),
+        };
+        let payload = SimulatePayload {
+            block_state_calls: vec![
+                SimBlock {
+                    state_overrides: Some(HashMap::from([(identity, account_override)])),
+                    calls: vec![identity_call(identity), identity_call(moved_identity)],
+                    ..Default::default()
+                },
+                block_with_calls(vec![identity_call(identity), identity_call(moved_identity)]),
+            ],
+            ..Default::default()
+        };
+        let blocks = eth_api.simulate_v1(payload, Some(hash.into())).await.unwrap();
+
+        for block in blocks {
+            let [overridden, moved] = &block.calls[..] else { panic!("expected two calls") };
+            assert_eq!(overridden.status, U64::from(1));
+            assert_eq!(overridden.logs.len(), 1);
+            assert_eq!(overridden.logs[0].inner.address, identity);
+            assert_eq!(moved.status, U64::from(1));
+            assert_eq!(moved.return_data, input);
+        }
+    }
+
+    #[tokio::test]
+    async fn simulate_validation_error_code() {
+        let (provider, hash) = provider_with_base_block();
+        let eth_api = build_test_eth_api(provider);
+
+        let payload = SimulatePayload {
+            block_state_calls: vec![block_with_calls(vec![TransactionRequest {
+                nonce: Some(0),
+                ..call(SENDER, RECIPIENT)
+            }])],
+            validation: true,
+            ..Default::default()
+        };
+        let err = eth_api.simulate_v1(payload, Some(hash.into())).await.unwrap_err();
+        assert_eq!(ErrorObject::from(err).code(), -38010);
+    }
+}
@@ -4,14 +4,28 @@ use crate::{
     eth::error::{EthApiError, EthResult, RpcInvalidTransactionError},
     EthApi,
 };
+use reth_errors::{ProviderError, RethError};
 use reth_evm::ConfigureEvm;
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256, U256};
+use reth_primitives::{
+    constants::EMPTY_ROOT_HASH,
+    keccak256,
+    trie::{AccountProof, StorageProof},
+    Address, BlockId, BlockNumber, Bytes, B256, KECCAK_EMPTY, U256, U64,
+};
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProvider, StateProviderFactory,
+    BlockIdReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider,
+    ProviderResult, StateProvider, StateProviderFactory,
+};
+use reth_rpc_types::{
+    serde_helpers::JsonStorageKey, EIP1186AccountProofResponse, EthAccount, EthAccountInfo,
 };
-use reth_rpc_types::{serde_helpers::JsonStorageKey, EIP1186AccountProofResponse};
 use reth_rpc_types_compat::proof::from_primitive_account_proof;
 use reth_transaction_pool::{PoolTransaction, TransactionPool};
+use reth_trie::HashedStorage;
+
+/// The number of requested storage slots above which the storage proofs of `eth_getProof` are
+/// taken from a single storage multiproof.
+const STORAGE_MULTIPROOF_THRESHOLD: usize = 16;
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
 where
@@ -38,8 +52,8 @@ where
 
     /// Returns the number of transactions sent from an address at the given block identifier.
     ///
-    /// If this is [`BlockNumberOrTag::Pending`] then this will look up the highest transaction in
-    /// pool and return the next nonce (highest + 1).
+    /// If this is [`BlockNumberOrTag::Pending`](reth_primitives::BlockNumberOrTag::Pending) then
+    /// this will look up the highest transaction in pool and return the next nonce (highest + 1).
     pub(crate) fn get_transaction_count(
         &self,
         address: Address,
@@ -81,70 +95,197 @@ where
         keys: Vec<JsonStorageKey>,
         block_id: Option<BlockId>,
     ) -> EthResult<EIP1186AccountProofResponse> {
-        let chain_info = self.provider().chain_info()?;
         let block_id = block_id.unwrap_or_default();
-
-        // if we are trying to create a proof for the latest block, but have a BlockId as input
-        // that is not BlockNumberOrTag::Latest, then we need to figure out whether or not the
-        // BlockId corresponds to the latest block
-        let is_latest_block = match block_id {
-            BlockId::Number(BlockNumberOrTag::Number(num)) => num == chain_info.best_number,
-            BlockId::Hash(hash) => hash == chain_info.best_hash.into(),
-            BlockId::Number(BlockNumberOrTag::Latest) => true,
-            _ => false,
-        };
-
-        // TODO: remove when HistoricalStateProviderRef::proof is implemented
-        if !is_latest_block {
-            return Err(EthApiError::InvalidBlockRange)
-        }
+        let (block_number, is_latest_block) = self.block_number_in_proof_window(block_id)?;
 
         let this = self.clone();
         self.inner
             .blocking_task_pool
             .spawn(move || {
-                let state = this.state_at_block_id(block_id)?;
+                let state = this.state_at_block_id(block_id).map_err(map_pruned_state_err)?;
                 let storage_keys = keys.iter().map(|key| key.0).collect::<Vec<_>>();
-                let proof = state.proof(address, &storage_keys)?;
+                if is_latest_block {
+                    let proof = if storage_keys.len() > STORAGE_MULTIPROOF_THRESHOLD {
+                        account_proof_with_storage_multiproof(&*state, address, &storage_keys)?
+                    } else {
+                        state.proof(address, &storage_keys)?
+                    };
+                    return Ok(from_primitive_account_proof(proof))
+                }
+
+                // The historical trie is not persisted, so make sure the proof generated on top of
+                // the reverted state matches the state root of the block.
+                let state_root = this
+                    .provider()
+                    .header_by_number(block_number)?
+                    .ok_or(EthApiError::UnknownBlockNumber)?
+                    .state_root;
+                let proof = state
+                    .proof(address, &storage_keys)
+                    .map_err(|err| map_pruned_state_err(err.into()))?;
+                proof
+                    .verify(state_root)
+                    .map_err(|err| EthApiError::Internal(RethError::other(err)))?;
                 Ok(from_primitive_account_proof(proof))
             })
             .await
             .map_err(|_| EthApiError::InternalBlockingTaskError)?
     }
+
+    /// Returns the balance, nonce, code hash and storage root of the account at the given block.
+    ///
+    /// Accounts that don't exist are returned as empty accounts.
+    pub(crate) async fn get_account(
+        &self,
+        address: Address,
+        block_id: Option<BlockId>,
+    ) -> EthResult<EthAccount> {
+        let block_id = block_id.unwrap_or_default();
+        // the storage root is computed like the proofs of `eth_getProof`, so the same window
+        // applies
+        self.block_number_in_proof_window(block_id)?;
+
+        let this = self.clone();
+        self.inner
+            .blocking_task_pool
+            .spawn(move || {
+                let state = this.state_at_block_id(block_id).map_err(map_pruned_state_err)?;
+                let Some(account) = state.basic_account(address)? else {
+                    return Ok(EthAccount {
+                        balance: U256::ZERO,
+                        nonce: U64::ZERO,
+                        code_hash: KECCAK_EMPTY,
+                        storage_root: EMPTY_ROOT_HASH,
+                    })
+                };
+                let storage_root = state
+                    .proof(address, &[])
+                    .map_err(|err| map_pruned_state_err(err.into()))?
+                    .storage_root;
+                Ok(EthAccount {
+                    balance: account.balance,
+                    nonce: U64::from(account.nonce),
+                    code_hash: account.bytecode_hash.unwrap_or(KECCAK_EMPTY),
+                    storage_root,
+                })
+            })
+            .await
+            .map_err(|_| EthApiError::InternalBlockingTaskError)?
+    }
+
+    /// Returns the balance, nonce and code of the account at the given block.
+    pub(crate) fn get_account_info(
+        &self,
+        address: Address,
+        block_id: Option<BlockId>,
+    ) -> EthResult<EthAccountInfo> {
+        let state = self.state_at_block_id_or_latest(block_id)?;
+        let Some(account) = state.basic_account(address)? else {
+            return Ok(EthAccountInfo::default())
+        };
+        let code = match account.bytecode_hash {
+            Some(code_hash) if code_hash != KECCAK_EMPTY => state
+                .bytecode_by_hash(code_hash)?
+                .map(|code| code.original_bytes())
+                .unwrap_or_default(),
+            _ => Bytes::new(),
+        };
+        Ok(EthAccountInfo { balance: account.balance, nonce: U64::from(account.nonce), code })
+    }
+
+    /// Returns the number of the block and whether it's the latest block.
+    ///
+    /// Fails if the distance of the block to the tip exceeds the configured proof window. Proofs
+    /// for blocks behind the tip are generated on top of the state reverted back to the block, so
+    /// the cost grows with the distance.
+    fn block_number_in_proof_window(&self, block_id: BlockId) -> EthResult<(BlockNumber, bool)> {
+        let chain_info = self.provider().chain_info()?;
+        let block_number = self
+            .provider()
+            .block_number_for_id(block_id)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        if chain_info.best_number.saturating_sub(block_number) > self.eth_proof_window() {
+            return Err(EthApiError::ExceedsMaxProofWindow)
+        }
+        // compare the hashes, a block with the tip's number may be a sibling of the tip
+        let block_hash = self.provider().block_hash_for_id(block_id)?;
+        Ok((block_number, block_hash == Some(chain_info.best_hash)))
+    }
+}
+
+/// Converts the error about the pruned historical state into
+/// [`EthApiError::ProofStateUnavailable`].
+fn map_pruned_state_err(err: EthApiError) -> EthApiError {
+    match err {
+        EthApiError::Internal(RethError::Provider(ProviderError::StateAtBlockPruned(block))) => {
+            EthApiError::ProofStateUnavailable(block)
+        }
+        err => err,
+    }
+}
+
+/// Generates the account proof with the storage proofs of all requested slots extracted from a
+/// single storage multiproof, instead of collecting the proof nodes for every slot separately.
+fn account_proof_with_storage_multiproof(
+    state: &dyn StateProvider,
+    address: Address,
+    keys: &[B256],
+) -> ProviderResult<AccountProof> {
+    let mut proof = state.proof(address, &[])?;
+    let storage_multiproof = state.storage_multiproof(address, keys, HashedStorage::new(false))?;
+    proof.storage_proofs = keys
+        .iter()
+        .map(|key| {
+            let hashed_key = keccak256(key);
+            let mut storage_proof = StorageProof::new_with_hashed(*key, hashed_key);
+            storage_proof.set_value(state.storage(address, *key)?.unwrap_or_default());
+            storage_proof.set_proof(storage_multiproof.storage_proof_nodes(hashed_key));
+            Ok(storage_proof)
+        })
+        .collect::<ProviderResult<_>>()?;
+    Ok(proof)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::eth::{
-        cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache, FeeHistoryCacheConfig,
+        cache::EthStateCache, gas_oracle::GasPriceOracle, test_utils::build_test_eth_api,
+        FeeHistoryCache, FeeHistoryCacheConfig,
     };
+    use assert_matches::assert_matches;
+    use reth_blockchain_tree::noop::NoopBlockchainTree;
+    use reth_db::tables;
+    use reth_db_api::transaction::DbTxMut;
     use reth_evm_ethereum::EthEvmConfig;
-    use reth_primitives::{constants::ETHEREUM_BLOCK_GAS_LIMIT, StorageKey, StorageValue};
-    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider, NoopProvider};
+    use reth_primitives::{
+        constants::ETHEREUM_BLOCK_GAS_LIMIT,
+        proofs::storage_root_unhashed,
+        stage::{StageCheckpoint, StageId},
+        Account, Header, StaticFileSegment, StorageEntry, StorageKey, StorageValue,
+    };
+    use reth_provider::{
+        providers::BlockchainProvider,
+        test_utils::{
+            create_test_provider_factory, ExtendedAccount, MockEthProvider, NoopProvider,
+        },
+        PruneCheckpointWriter, StageCheckpointWriter, StaticFileWriter,
+    };
+    use reth_prune_types::{PruneCheckpoint, PruneMode, PruneSegment};
+    use reth_rpc_server_types::constants::{
+        DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT, DEFAULT_RPC_MEMORY_LIMIT,
+        DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT,
+    };
+    use reth_rpc_types::error::EthRpcErrorCode;
     use reth_tasks::pool::BlockingTaskPool;
+    use reth_testing_utils::generators::{self, random_block_range};
     use reth_transaction_pool::test_utils::testing_pool;
-    use std::collections::HashMap;
+    use std::{collections::HashMap, sync::Arc};
 
     #[tokio::test]
     async fn test_storage() {
         // === Noop ===
-        let pool = testing_pool();
-        let evm_config = EthEvmConfig::default();
-
-        let cache = EthStateCache::spawn(NoopProvider::default(), Default::default(), evm_config);
-        let eth_api = EthApi::new(
-            NoopProvider::default(),
-            pool.clone(),
-            (),
-            cache.clone(),
-            GasPriceOracle::new(NoopProvider::default(), Default::default(), cache.clone()),
-            ETHEREUM_BLOCK_GAS_LIMIT,
-            BlockingTaskPool::build().expect("failed to build tracing pool"),
-            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
-            evm_config,
-            None,
-        );
+        let eth_api = build_test_eth_api(NoopProvider::default());
         let address = Address::random();
         let storage = eth_api.storage_at(address, U256::ZERO.into(), None).unwrap();
         assert_eq!(storage, U256::ZERO.to_be_bytes());
@@ -157,22 +298,165 @@ mod tests {
         let account = ExtendedAccount::new(0, U256::ZERO).extend_storage(storage);
         mock_provider.add_account(address, account);
 
-        let cache = EthStateCache::spawn(mock_provider.clone(), Default::default(), evm_config);
+        let eth_api = build_test_eth_api(mock_provider);
+
+        let storage_key: U256 = storage_key.into();
+        let storage = eth_api.storage_at(address, storage_key.into(), None).unwrap();
+        assert_eq!(storage, storage_value.to_be_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_get_account() {
+        let mock_provider = MockEthProvider::default();
+        mock_provider.add_header(B256::random(), Header::default());
+        let address = Address::random();
+        let code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+        mock_provider.add_account(
+            address,
+            ExtendedAccount::new(1, U256::from(1000)).with_bytecode(code.clone()),
+        );
+
+        let eth_api = build_test_eth_api(mock_provider);
+
+        let account = eth_api.get_account(address, None).await.unwrap();
+        assert_eq!(account.balance, U256::from(1000));
+        assert_eq!(account.nonce, U64::from(1));
+        assert_eq!(account.code_hash, keccak256(&code));
+        assert_eq!(account.storage_root, EMPTY_ROOT_HASH);
+
+        let info = eth_api.get_account_info(address, None).unwrap();
+        assert_eq!(info, EthAccountInfo { balance: U256::from(1000), nonce: U64::from(1), code });
+
+        let missing = Address::random();
+        let account = eth_api.get_account(missing, None).await.unwrap();
+        assert_eq!(
+            account,
+            EthAccount {
+                balance: U256::ZERO,
+                nonce: U64::ZERO,
+                code_hash: KECCAK_EMPTY,
+                storage_root: EMPTY_ROOT_HASH,
+            }
+        );
+        assert_eq!(eth_api.get_account_info(missing, None).unwrap(), EthAccountInfo::default());
+    }
+
+    #[tokio::test]
+    async fn test_get_account_storage_root() {
+        let factory = create_test_provider_factory();
+        let blocks = random_block_range(&mut generators::rng(), 0..=0, B256::ZERO, 0..1);
+        let provider_rw = factory.provider_rw().unwrap();
+        for block in blocks {
+            provider_rw
+                .insert_historical_block(block.try_seal_with_senders().unwrap(), None)
+                .unwrap();
+        }
+        provider_rw
+            .static_file_provider()
+            .latest_writer(StaticFileSegment::Headers)
+            .unwrap()
+            .commit()
+            .unwrap();
+        provider_rw.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(0)).unwrap();
+
+        let address = Address::random();
+        let account = Account { nonce: 1, balance: U256::from(1000), bytecode_hash: None };
+        let storage =
+            [(B256::with_last_byte(1), U256::from(1)), (B256::with_last_byte(2), U256::MAX)];
+        let hashed_address = keccak256(address);
+        let tx = provider_rw.tx_ref();
+        tx.put::<tables::PlainAccountState>(address, account).unwrap();
+        tx.put::<tables::HashedAccounts>(hashed_address, account).unwrap();
+        for (slot, value) in storage {
+            tx.put::<tables::HashedStorages>(
+                hashed_address,
+                StorageEntry { key: keccak256(slot), value },
+            )
+            .unwrap();
+        }
+        provider_rw.commit().unwrap();
+        let provider =
+            BlockchainProvider::new(factory, Arc::new(NoopBlockchainTree::default())).unwrap();
+
+        let eth_api = build_test_eth_api(provider);
+
+        let account = eth_api.get_account(address, None).await.unwrap();
+        assert_eq!(account.balance, U256::from(1000));
+        assert_eq!(account.nonce, U64::from(1));
+        assert_eq!(account.code_hash, KECCAK_EMPTY);
+        assert_ne!(account.storage_root, EMPTY_ROOT_HASH);
+        assert_eq!(account.storage_root, storage_root_unhashed(storage));
+    }
+
+    #[tokio::test]
+    async fn test_get_proof_window() {
+        let factory = create_test_provider_factory();
+        let blocks = random_block_range(&mut generators::rng(), 0..=10, B256::ZERO, 0..1);
+        let provider_rw = factory.provider_rw().unwrap();
+        for block in blocks {
+            provider_rw
+                .insert_historical_block(block.try_seal_with_senders().unwrap(), None)
+                .unwrap();
+        }
+        provider_rw
+            .static_file_provider()
+            .latest_writer(StaticFileSegment::Headers)
+            .unwrap()
+            .commit()
+            .unwrap();
+        provider_rw.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(10)).unwrap();
+        // the history up to block 7 is pruned
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            provider_rw
+                .save_prune_checkpoint(
+                    segment,
+                    PruneCheckpoint {
+                        block_number: Some(7),
+                        tx_number: None,
+                        prune_mode: PruneMode::Before(8),
+                    },
+                )
+                .unwrap();
+        }
+        provider_rw.commit().unwrap();
+        let provider =
+            BlockchainProvider::new(factory, Arc::new(NoopBlockchainTree::default())).unwrap();
+
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let fee_history_cache =
+            FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
         let eth_api = EthApi::new(
-            mock_provider.clone(),
-            pool,
+            provider.clone(),
+            testing_pool(),
             (),
-            cache.clone(),
-            GasPriceOracle::new(mock_provider, Default::default(), cache.clone()),
+            cache,
+            GasPriceOracle::new(provider, Default::default(), fee_history_cache.clone()),
             ETHEREUM_BLOCK_GAS_LIMIT,
+            5,
+            DEFAULT_RPC_MEMORY_LIMIT,
+            DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT,
+            DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT,
             BlockingTaskPool::build().expect("failed to build tracing pool"),
-            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            fee_history_cache,
             evm_config,
             None,
         );
+        let address = Address::random();
 
-        let storage_key: U256 = storage_key.into();
-        let storage = eth_api.storage_at(address, storage_key.into(), None).unwrap();
-        assert_eq!(storage, storage_value.to_be_bytes());
+        let err = eth_api.get_proof(address, vec![], Some(4.into())).await.unwrap_err();
+        assert_matches!(err, EthApiError::ExceedsMaxProofWindow);
+        let err = jsonrpsee::types::ErrorObject::from(err);
+        assert_eq!(err.code(), jsonrpsee::types::error::INVALID_PARAMS_CODE);
+
+        let err = eth_api.get_proof(address, vec![], Some(6.into())).await.unwrap_err();
+        assert_matches!(err, EthApiError::ProofStateUnavailable(_));
+        let err = jsonrpsee::types::ErrorObject::from(err);
+        assert_eq!(err.code(), EthRpcErrorCode::ResourceNotFound.code());
+
+        // the tip is served from the latest state, by tag and by number
+        let proof = eth_api.get_proof(address, vec![], None).await.unwrap();
+        assert_eq!(proof.storage_hash, EMPTY_ROOT_HASH);
+        eth_api.get_proof(address, vec![], Some(10.into())).await.unwrap();
     }
 }
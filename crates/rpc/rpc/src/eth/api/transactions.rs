@@ -10,19 +10,22 @@ use crate::{
 };
 use alloy_primitives::TxKind as RpcTransactionKind;
 use async_trait::async_trait;
+use futures::StreamExt;
 use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
-    eip4844::calc_blob_gasprice,
+    eip4844::BlobParams,
     revm::env::{fill_block_env_with_coinbase, tx_env_with_recovered},
-    Address, BlockId, BlockNumberOrTag, Bytes, FromRecoveredPooledTransaction, Header,
-    IntoRecoveredTransaction, Receipt, SealedBlock, SealedBlockWithSenders, TransactionMeta,
-    TransactionSigned, TransactionSignedEcRecovered,
+    Address, BlobTransactionSidecar, BlockId, BlockNumberOrTag, Bytes,
+    FromRecoveredPooledTransaction, Header, IntoRecoveredTransaction, Receipt, SealedBlock,
+    SealedBlockWithSenders, TransactionConditional, TransactionMeta, TransactionSigned,
+    TransactionSignedEcRecovered,
     TxKind::{Call, Create},
     B256, U256,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory,
+    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, ProviderError, ProviderResult,
+    StateProvider, StateProviderBox, StateProviderFactory,
 };
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_types::{
@@ -35,7 +38,7 @@ use reth_rpc_types::{
     WithOtherFields,
 };
 use reth_rpc_types_compat::transaction::from_recovered_with_block_context;
-use reth_transaction_pool::{TransactionOrigin, TransactionPool};
+use reth_transaction_pool::{FullTransactionEvent, TransactionOrigin, TransactionPool};
 use revm::{
     db::CacheDB,
     primitives::{
@@ -45,7 +48,7 @@ use revm::{
     GetInspector, Inspector,
 };
 use revm_inspectors::tracing::{TracingInspector, TracingInspectorConfig};
-use std::future::Future;
+use std::{future::Future, time::Duration};
 
 use crate::eth::revm_utils::FillableTransaction;
 #[cfg(feature = "optimism")]
@@ -143,6 +146,12 @@ pub trait EthTransactions: Send + Sync {
     /// Returns default gas limit to use for `eth_call` and tracing RPC methods.
     fn call_gas_limit(&self) -> u64;
 
+    /// Returns the EVM memory limit to use for `eth_call` and the other RPC methods that execute
+    /// calls.
+    ///
+    /// Replays of mined transactions keep the protocol default.
+    fn call_memory_limit(&self) -> u64;
+
     /// Executes the future on a new blocking task.
     ///
     /// Note: This is expected for futures that are dominated by blocking IO operations, for tracing
@@ -221,13 +230,20 @@ pub trait EthTransactions: Send + Sync {
 
     /// Returns the EIP-2718 encoded transaction by hash.
     ///
-    /// If this is a pooled EIP-4844 transaction, the blob sidecar is included.
+    /// This is the canonical encoding, so the blob sidecar of a pooled EIP-4844 transaction is not
+    /// included, see [`EthTransactions::blob_sidecar_by_hash`].
     ///
     /// Checks the pool and state.
     ///
     /// Returns `Ok(None)` if no matching transaction was found.
     async fn raw_transaction_by_hash(&self, hash: B256) -> EthResult<Option<Bytes>>;
 
+    /// Returns the blob sidecar of the EIP-4844 transaction with the given hash.
+    ///
+    /// Returns `Ok(None)` if the sidecar is not in the blob store of the pool, which keeps the
+    /// sidecars until the block that includes the transaction is finalized.
+    fn blob_sidecar_by_hash(&self, hash: B256) -> EthResult<Option<BlobTransactionSidecar>>;
+
     /// Returns the transaction by hash.
     ///
     /// Checks the pool and state.
@@ -235,6 +251,19 @@ pub trait EthTransactions: Send + Sync {
     /// Returns `Ok(None)` if no matching transaction was found.
     async fn transaction_by_hash(&self, hash: B256) -> EthResult<Option<TransactionSource>>;
 
+    /// Returns the transaction of the sender with the given nonce.
+    ///
+    /// Checks the pool and, if the nonce is below the nonce of the account, the block in which
+    /// the nonce of the account was incremented past it.
+    ///
+    /// Returns `Ok(None)` if no matching transaction was found and an error if the history of the
+    /// account is pruned.
+    async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: u64,
+    ) -> EthResult<Option<TransactionSource>>;
+
     /// Returns the transaction by including its corresponding [BlockId]
     ///
     /// Note: this supports pending transactions
@@ -260,6 +289,29 @@ pub trait EthTransactions: Send + Sync {
     /// Returns the hash of the transaction.
     async fn send_raw_transaction(&self, tx: Bytes) -> EthResult<B256>;
 
+    /// Submits the raw transaction like [EthTransactions::send_raw_transaction] and waits until it
+    /// is included in a canonical block.
+    ///
+    /// Returns the receipt of the transaction, or
+    /// [EthApiError::TransactionConfirmationTimeout] if the transaction isn't included within the
+    /// given timeout.
+    async fn send_raw_transaction_sync(
+        &self,
+        tx: Bytes,
+        timeout: Duration,
+    ) -> EthResult<AnyTransactionReceipt>;
+
+    /// Submits the raw transaction like [EthTransactions::send_raw_transaction] with a
+    /// [TransactionConditional] that must hold for the block that includes the transaction.
+    ///
+    /// Returns [EthApiError::ConditionalRejected] if the conditional already doesn't hold for the
+    /// latest state.
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> EthResult<B256>;
+
     /// Signs transaction with a matching signer, if any and submits the transaction to the pool.
     /// Returns the hash of the signed transaction.
     async fn send_transaction(&self, request: TransactionRequest) -> EthResult<B256>;
@@ -630,6 +682,10 @@ where
         self.inner.gas_cap
     }
 
+    fn call_memory_limit(&self) -> u64 {
+        self.inner.memory_limit
+    }
+
     async fn spawn_blocking_future<F, R>(&self, c: F) -> EthResult<R>
     where
         F: Future<Output = EthResult<R>> + Send + 'static,
@@ -674,9 +730,9 @@ where
         &self,
         at: BlockId,
     ) -> EthResult<(CfgEnvWithHandlerCfg, BlockEnv, BlockId)> {
-        if at.is_pending() {
+        let (cfg, block_env, at) = if at.is_pending() {
             let PendingBlockEnv { cfg, block_env, origin } = self.pending_block_env_and_cfg()?;
-            Ok((cfg, block_env, origin.state_block_id()))
+            (cfg, block_env, origin.state_block_id())
         } else {
             // Use cached values if there is no pending block
             let block_hash = self
@@ -684,8 +740,9 @@ where
                 .block_hash_for_id(at)?
                 .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
             let (cfg, env) = self.cache().get_evm_env(block_hash).await?;
-            Ok((cfg, env, block_hash.into()))
-        }
+            (cfg, env, block_hash.into())
+        };
+        Ok((cfg, block_env, at))
     }
 
     async fn evm_env_for_raw_block(
@@ -696,7 +753,13 @@ where
         let (cfg, mut block_env, _) = self.evm_env_at(header.parent_hash.into()).await?;
 
         let after_merge = cfg.handler_cfg.spec_id >= SpecId::MERGE;
-        fill_block_env_with_coinbase(&mut block_env, header, after_merge, header.beneficiary);
+        fill_block_env_with_coinbase(
+            &mut block_env,
+            &self.provider().chain_spec(),
+            header,
+            after_merge,
+            header.beneficiary,
+        );
 
         Ok((cfg, block_env))
     }
@@ -728,8 +791,11 @@ where
 
     async fn raw_transaction_by_hash(&self, hash: B256) -> EthResult<Option<Bytes>> {
         // Note: this is mostly used to fetch pooled transactions so we check the pool first
+        //
+        // This returns the consensus encoding, because the network encoding of pooled EIP-4844
+        // transactions includes the blob sidecar.
         if let Some(tx) =
-            self.pool().get_pooled_transaction_element(hash).map(|tx| tx.envelope_encoded())
+            self.pooled_consensus_transaction(hash).map(|tx| tx.into_signed().envelope_encoded())
         {
             return Ok(Some(tx))
         }
@@ -740,6 +806,10 @@ where
         .await
     }
 
+    fn blob_sidecar_by_hash(&self, hash: B256) -> EthResult<Option<BlobTransactionSidecar>> {
+        Ok(self.pool().get_blob(hash)?)
+    }
+
     async fn transaction_by_hash(&self, hash: B256) -> EthResult<Option<TransactionSource>> {
         // Try to find the transaction on disk
         let mut resp = self
@@ -769,9 +839,7 @@ where
 
         if resp.is_none() {
             // tx not found on disk, check pool
-            if let Some(tx) =
-                self.pool().get(&hash).map(|tx| tx.transaction.to_recovered_transaction())
-            {
+            if let Some(tx) = self.pooled_consensus_transaction(hash) {
                 resp = Some(TransactionSource::Pool(tx));
             }
         }
@@ -779,6 +847,69 @@ where
         Ok(resp)
     }
 
+    async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: u64,
+    ) -> EthResult<Option<TransactionSource>> {
+        if let Some(tx) = self.pool().get_transactions_by_sender_and_nonce(sender, nonce) {
+            return Ok(Some(TransactionSource::Pool(tx.transaction.to_recovered_transaction())))
+        }
+
+        let block_number = self
+            .on_blocking_task(|this| async move {
+                // the nonce of the account is the nonce of its next transaction
+                let account_nonce =
+                    this.provider().latest()?.account_nonce(sender)?.unwrap_or_default();
+                if nonce >= account_nonce {
+                    return Ok(None)
+                }
+
+                let nonce_after = |block_number: u64| -> ProviderResult<u64> {
+                    this.provider()
+                        .history_by_block_number(block_number)?
+                        .account_nonce(sender)
+                        .map(Option::unwrap_or_default)
+                };
+                let best_block = this.provider().best_block_number()?;
+                let block_number = find_nonce_block(best_block, nonce, &nonce_after)?;
+
+                // if the history before the block is pruned, the nonce may be in a pruned block
+                let pruned_before = block_number > 0 &&
+                    matches!(
+                        nonce_after(block_number - 1),
+                        Err(ProviderError::StateAtBlockPruned(_))
+                    );
+                Ok(Some((block_number, pruned_before)))
+            })
+            .await?;
+        let Some((block_number, pruned_before)) = block_number else { return Ok(None) };
+
+        let Some(block) = self.block_with_senders(BlockNumberOrTag::Number(block_number)).await?
+        else {
+            return Ok(None)
+        };
+        let block_hash = block.hash();
+        let base_fee = block.base_fee_per_gas;
+        let tx = block
+            .into_transactions_ecrecovered()
+            .enumerate()
+            .find(|(_, tx)| tx.signer() == sender && tx.nonce() == nonce);
+        match tx {
+            Some((index, transaction)) => Ok(Some(TransactionSource::Block {
+                transaction,
+                index: index as u64,
+                block_hash,
+                block_number,
+                base_fee,
+            })),
+            // the nonce wasn't incremented by a transaction of the block, which is either a
+            // contract creation of the account or the first block after the pruned history
+            None if pruned_before => Err(EthApiError::TransactionHistoryPruned { sender, nonce }),
+            None => Ok(None),
+        }
+    }
+
     async fn transaction_by_hash_at(
         &self,
         transaction_hash: B256,
@@ -864,6 +995,95 @@ where
         Ok(hash)
     }
 
+    async fn send_raw_transaction_sync(
+        &self,
+        tx: Bytes,
+        timeout: Duration,
+    ) -> EthResult<AnyTransactionReceipt> {
+        // subscribe before submitting the transaction so no event is missed
+        let mut events = self.pool().all_transactions_event_listener();
+        let hash = self.send_raw_transaction(tx).await?;
+
+        let wait_for_receipt = async {
+            let mut last_block_hash = None;
+            while let Some(event) = events.next().await {
+                // the pool emits an event for every included pool transaction once a block becomes
+                // canonical, so this is the time to look for the receipt. The receipt is read
+                // from the canonical chain, so if the block has been reorged out in the meantime,
+                // this keeps waiting for the transaction to be included again.
+                let FullTransactionEvent::Mined { block_hash, .. } = event else { continue };
+                if last_block_hash.replace(block_hash) == Some(block_hash) {
+                    continue
+                }
+                if let Some(receipt) = self.transaction_receipt(hash).await? {
+                    return Ok(receipt)
+                }
+            }
+            Err(EthApiError::InternalEthError)
+        };
+
+        tokio::time::timeout(timeout, wait_for_receipt)
+            .await
+            .map_err(|_| EthApiError::TransactionConfirmationTimeout { hash, timeout })?
+    }
+
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> EthResult<B256> {
+        // a forwarded transaction is included by a node that doesn't know about its conditional
+        if self.inner.raw_transaction_forwarder.read().is_some() {
+            return Err(EthApiError::Unsupported(
+                "transaction conditionals are not supported when forwarding raw transactions",
+            ))
+        }
+        if !conditional.has_valid_bounds() {
+            return Err(EthApiError::InvalidParams(
+                "transaction conditional minimum exceeds its maximum".to_string(),
+            ))
+        }
+
+        let recovered = recover_raw_transaction(tx)?;
+
+        // reject the transaction right away if no future block can satisfy the conditional
+        let conditional = self
+            .on_blocking_task(|this| async move {
+                let header =
+                    this.provider().latest_header()?.ok_or(EthApiError::UnknownBlockNumber)?;
+                if conditional.has_expired(header.number, header.timestamp) {
+                    return Err(EthApiError::ConditionalRejected(
+                        "block number or timestamp range has passed",
+                    ))
+                }
+
+                let state = this.provider().latest()?;
+                let matches = conditional.matches_known_accounts(
+                    |address| Ok::<_, EthApiError>(state.proof(address, &[])?.storage_root),
+                    |address, slot| Ok(state.storage(address, slot)?.unwrap_or_default().into()),
+                )?;
+                if !matches {
+                    return Err(EthApiError::ConditionalRejected("known account storage mismatch"))
+                }
+                Ok(conditional)
+            })
+            .await?;
+
+        let pool_transaction = <Pool::Transaction>::from_recovered_pooled_transaction(recovered);
+
+        // submit the transaction to the pool with a `Local` origin
+        let hash = self
+            .pool()
+            .add_transaction_with_conditional(
+                TransactionOrigin::Local,
+                pool_transaction,
+                conditional,
+            )
+            .await?;
+
+        Ok(hash)
+    }
+
     async fn send_transaction(&self, mut request: TransactionRequest) -> EthResult<B256> {
         let from = match request.from {
             Some(from) => from,
@@ -879,7 +1099,8 @@ where
 
         let chain_id = self.chain_id();
 
-        let estimated_gas = self.estimate_gas_at(request.clone(), BlockId::pending(), None).await?;
+        let estimated_gas =
+            self.estimate_gas_at(request.clone(), BlockId::pending(), Default::default()).await?;
         let gas_limit = estimated_gas;
 
         let TransactionRequest {
@@ -1070,7 +1291,8 @@ where
         F: FnOnce(&mut StateCacheDB, EnvWithHandlerCfg) -> EthResult<R> + Send + 'static,
         R: Send + 'static,
     {
-        let (cfg, block_env, at) = self.evm_env_at(at).await?;
+        let (mut cfg, block_env, at) = self.evm_env_at(at).await?;
+        cfg.memory_limit = self.call_memory_limit();
         let this = self.clone();
         self.inner
             .blocking_task_pool
@@ -1388,6 +1610,17 @@ where
     Network: NetworkInfo + 'static,
     EvmConfig: ConfigureEvm,
 {
+    /// Returns the consensus transaction of the pooled transaction with the given hash.
+    ///
+    /// Both the typed and the raw responses for pooled transactions are derived from this, for
+    /// EIP-4844 transactions it contains all blob fields but not the blob sidecar.
+    pub(crate) fn pooled_consensus_transaction(
+        &self,
+        hash: B256,
+    ) -> Option<TransactionSignedEcRecovered> {
+        self.pool().get(&hash).map(|tx| tx.transaction.to_recovered_transaction())
+    }
+
     /// Returns the gas price if it is set, otherwise fetches a suggested gas price for legacy
     /// transactions.
     pub(crate) async fn legacy_gas_price(&self, gas_price: Option<U256>) -> EthResult<U256> {
@@ -1518,7 +1751,8 @@ where
             Some(recpts) => recpts,
             None => return Err(EthApiError::UnknownBlockNumber),
         };
-        build_transaction_receipt_with_block_receipts(tx, meta, receipt, &all_receipts)
+        let blob_params = self.provider().chain_spec().blob_params_at_timestamp(meta.timestamp);
+        build_transaction_receipt_with_block_receipts(tx, meta, receipt, &all_receipts, blob_params)
     }
 
     /// Helper function for `eth_getTransactionReceipt` (optimism)
@@ -1541,11 +1775,13 @@ where
         let l1_block_info = reth_evm_optimism::extract_l1_info(&block).ok();
         let optimism_tx_meta = self.build_op_tx_meta(&tx, l1_block_info, block.timestamp)?;
 
+        let blob_params = self.provider().chain_spec().blob_params_at_timestamp(meta.timestamp);
         build_transaction_receipt_with_block_receipts(
             tx,
             meta,
             receipt,
             &receipts,
+            blob_params,
             optimism_tx_meta,
         )
     }
@@ -1680,6 +1916,32 @@ impl From<TransactionSource> for Transaction {
     }
 }
 
+/// Returns the first block of `0..=best_block` after which the nonce of an account is higher than
+/// `nonce`, given the nonce of the account after a block.
+///
+/// This is the block of the transaction with the nonce, unless the nonce was incremented by a
+/// contract creation. The nonce after the best block must be higher than `nonce`.
+///
+/// Each lookup resolves the block of the nonce with the account history index and the
+/// changesets. Blocks whose history is pruned are treated like the blocks before the nonce, so
+/// this returns the first block with history if the nonce is in the pruned history.
+fn find_nonce_block(
+    best_block: u64,
+    nonce: u64,
+    mut nonce_after: impl FnMut(u64) -> ProviderResult<u64>,
+) -> ProviderResult<u64> {
+    let (mut low, mut high) = (0, best_block);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match nonce_after(mid) {
+            Ok(mid_nonce) if mid_nonce > nonce => high = mid,
+            Ok(_) | Err(ProviderError::StateAtBlockPruned(_)) => low = mid + 1,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(low)
+}
+
 /// Helper function to construct a transaction receipt
 ///
 /// Note: This requires _all_ block receipts because we need to calculate the gas used by the
@@ -1689,6 +1951,7 @@ pub(crate) fn build_transaction_receipt_with_block_receipts(
     meta: TransactionMeta,
     receipt: Receipt,
     all_receipts: &[Receipt],
+    blob_params: BlobParams,
     #[cfg(feature = "optimism")] optimism_tx_meta: crate::eth::api::optimism::OptimismTxMeta,
 ) -> EthResult<AnyTransactionReceipt> {
     // Note: we assume this transaction is valid, because it's mined (or part of pending block) and
@@ -1709,7 +1972,8 @@ pub(crate) fn build_transaction_receipt_with_block_receipts(
 
     let blob_gas_used = transaction.transaction.blob_gas_used();
     // Blob gas price should only be present if the transaction is a blob transaction
-    let blob_gas_price = blob_gas_used.and_then(|_| meta.excess_blob_gas.map(calc_blob_gasprice));
+    let blob_gas_price = blob_gas_used
+        .and_then(|_| meta.excess_blob_gas.map(|excess| blob_params.calc_blob_fee(excess)));
     let logs_bloom = receipt.bloom_slow();
 
     // get number of logs in the block
@@ -1799,38 +2063,25 @@ pub(crate) fn build_transaction_receipt_with_block_receipts(
 mod tests {
     use super::*;
     use crate::eth::{
-        cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache, FeeHistoryCacheConfig,
+        error::CONDITIONAL_COST_EXCEEDED_CODE, test_utils::build_test_eth_api_with_pool,
+        EthConditional,
     };
-    use reth_evm_ethereum::EthEvmConfig;
-    use reth_network_api::noop::NoopNetwork;
-    use reth_primitives::{constants::ETHEREUM_BLOCK_GAS_LIMIT, hex_literal::hex};
-    use reth_provider::test_utils::NoopProvider;
-    use reth_tasks::pool::BlockingTaskPool;
-    use reth_transaction_pool::test_utils::testing_pool;
+    use jsonrpsee::types::ErrorObject;
+    use reth_primitives::{hex_literal::hex, KnownAccount, U64};
+    use reth_provider::test_utils::{MockEthProvider, NoopProvider};
+    use reth_rpc_server_types::constants::{
+        DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT, DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT,
+    };
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, TestPool},
+        CanonicalStateUpdate, TransactionPoolExt,
+    };
+    use std::collections::BTreeMap;
 
     #[tokio::test]
     async fn send_raw_transaction() {
-        let noop_provider = NoopProvider::default();
-        let noop_network_provider = NoopNetwork::default();
-
         let pool = testing_pool();
-
-        let evm_config = EthEvmConfig::default();
-        let cache = EthStateCache::spawn(noop_provider, Default::default(), evm_config);
-        let fee_history_cache =
-            FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
-        let eth_api = EthApi::new(
-            noop_provider,
-            pool.clone(),
-            noop_network_provider,
-            cache.clone(),
-            GasPriceOracle::new(noop_provider, Default::default(), cache.clone()),
-            ETHEREUM_BLOCK_GAS_LIMIT,
-            BlockingTaskPool::build().expect("failed to build tracing pool"),
-            fee_history_cache,
-            evm_config,
-            None,
-        );
+        let eth_api = build_test_eth_api_with_pool(NoopProvider::default(), pool.clone());
 
         // https://etherscan.io/tx/0xa694b71e6c128a2ed8e2e0f6770bddbe52e3bb8f10e8472f9a79ab81497a8b5d
         let tx_1 = Bytes::from(hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3"));
@@ -1857,4 +2108,195 @@ mod tests {
         assert!(pool.get(&tx_1_result).is_some(), "tx1 not found in the pool");
         assert!(pool.get(&tx_2_result).is_some(), "tx2 not found in the pool");
     }
+
+    #[tokio::test]
+    async fn send_raw_transaction_sync_timeout() {
+        let pool = testing_pool();
+        let eth_api = build_test_eth_api_with_pool(NoopProvider::default(), pool.clone());
+
+        assert_eq!(
+            eth_api.send_raw_transaction_sync_timeout(None),
+            DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT
+        );
+        assert_eq!(
+            eth_api.send_raw_transaction_sync_timeout(Some(Duration::from_secs(24 * 60 * 60))),
+            DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT
+        );
+
+        // https://etherscan.io/tx/0xa694b71e6c128a2ed8e2e0f6770bddbe52e3bb8f10e8472f9a79ab81497a8b5d
+        let tx = Bytes::from(hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3"));
+        let timeout = Duration::from_millis(50);
+
+        // the transaction is never mined, so the submission times out but still reports the hash
+        let err = eth_api.send_raw_transaction_sync(tx, timeout).await.unwrap_err();
+        let EthApiError::TransactionConfirmationTimeout { hash, timeout: waited } = err else {
+            panic!("expected a timeout, got {err:?}")
+        };
+        assert_eq!(waited, timeout);
+        assert!(pool.get(&hash).is_some(), "tx not found in the pool");
+    }
+
+    /// Returns a block at the given height that includes the transaction, and its receipts.
+    fn block_with_transaction(
+        number: u64,
+        parent_hash: B256,
+        transaction: TransactionSigned,
+    ) -> (SealedBlock, Vec<Receipt>) {
+        let block = reth_primitives::Block {
+            header: Header { number, parent_hash, ..Default::default() },
+            body: vec![transaction],
+            ..Default::default()
+        }
+        .seal_slow();
+        let receipt = Receipt { success: true, cumulative_gas_used: 21_000, ..Default::default() };
+        (block, vec![receipt])
+    }
+
+    /// Makes the pool process the block as the new canonical tip that includes the transaction.
+    fn mine_transaction(pool: &TestPool, block: &SealedBlock, hash: B256) {
+        pool.on_canonical_state_change(CanonicalStateUpdate {
+            new_tip: block,
+            pending_block_base_fee: 0,
+            pending_block_blob_fee: None,
+            changed_accounts: vec![],
+            mined_transactions: vec![hash],
+        });
+    }
+
+    /// Waits until the transaction has been submitted to the pool.
+    async fn wait_for_pool_transaction(pool: &TestPool, hash: B256) {
+        while pool.get(&hash).is_none() {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn send_raw_transaction_sync_returns_mined_receipt() {
+        let pool = testing_pool();
+        let provider = MockEthProvider::default();
+        let eth_api = build_test_eth_api_with_pool(provider.clone(), pool.clone());
+
+        // https://etherscan.io/tx/0xa694b71e6c128a2ed8e2e0f6770bddbe52e3bb8f10e8472f9a79ab81497a8b5d
+        let tx = Bytes::from(hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3"));
+        let transaction = TransactionSigned::decode_enveloped(&mut tx.as_ref()).unwrap();
+        let hash = transaction.hash();
+
+        let api = eth_api.clone();
+        let submission = tokio::spawn(async move {
+            api.send_raw_transaction_sync(tx, Duration::from_secs(10)).await
+        });
+        wait_for_pool_transaction(&pool, hash).await;
+
+        let (block, receipts) = block_with_transaction(1, B256::ZERO, transaction);
+        provider.add_block(block.hash(), block.clone().unseal());
+        provider.add_receipts(block.hash(), receipts);
+        mine_transaction(&pool, &block, hash);
+
+        let receipt = submission.await.unwrap().unwrap();
+        assert_eq!(receipt.transaction_hash, hash);
+        assert_eq!(receipt.block_hash, Some(block.hash()));
+        assert_eq!(receipt.block_number, Some(1));
+        assert!(pool.get(&hash).is_none());
+    }
+
+    #[tokio::test]
+    async fn send_raw_transaction_sync_waits_after_reorg() {
+        let pool = testing_pool();
+        let provider = MockEthProvider::default();
+        let eth_api = build_test_eth_api_with_pool(provider.clone(), pool.clone());
+
+        // https://etherscan.io/tx/0xa694b71e6c128a2ed8e2e0f6770bddbe52e3bb8f10e8472f9a79ab81497a8b5d
+        let tx = Bytes::from(hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3"));
+        let transaction = TransactionSigned::decode_enveloped(&mut tx.as_ref()).unwrap();
+        let hash = transaction.hash();
+
+        let api = eth_api.clone();
+        let raw = tx.clone();
+        let submission = tokio::spawn(async move {
+            api.send_raw_transaction_sync(raw, Duration::from_secs(10)).await
+        });
+        wait_for_pool_transaction(&pool, hash).await;
+
+        // the transaction is mined in a block that is reorged out before its receipt is looked
+        // up, so it's not part of the canonical chain of the provider
+        let (reorged, _) = block_with_transaction(1, B256::ZERO, transaction.clone());
+        mine_transaction(&pool, &reorged, hash);
+        assert!(pool.get(&hash).is_none());
+
+        // the reorg puts the transaction back into the pool
+        eth_api.send_raw_transaction(tx).await.unwrap();
+        assert!(!submission.is_finished());
+
+        let (block, receipts) = block_with_transaction(1, B256::with_last_byte(1), transaction);
+        provider.add_block(block.hash(), block.clone().unseal());
+        provider.add_receipts(block.hash(), receipts);
+        mine_transaction(&pool, &block, hash);
+
+        let receipt = submission.await.unwrap().unwrap();
+        assert_eq!(receipt.transaction_hash, hash);
+        assert_eq!(receipt.block_hash, Some(block.hash()));
+    }
+
+    #[tokio::test]
+    async fn send_raw_transaction_conditional_rejected() {
+        let pool = testing_pool();
+        let eth_conditional = EthConditional::new(
+            build_test_eth_api_with_pool(NoopProvider::default(), pool.clone()),
+            1,
+        );
+
+        // https://etherscan.io/tx/0xa694b71e6c128a2ed8e2e0f6770bddbe52e3bb8f10e8472f9a79ab81497a8b5d
+        let tx = Bytes::from(hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3"));
+
+        // the conditional checks two slots, which exceeds the cost limit
+        let conditional = TransactionConditional {
+            known_accounts: BTreeMap::from([(
+                Address::ZERO,
+                KnownAccount::Slots(BTreeMap::from([
+                    (B256::ZERO, B256::ZERO),
+                    (B256::with_last_byte(1), B256::ZERO),
+                ])),
+            )]),
+            ..Default::default()
+        };
+        let err = eth_conditional
+            .send_raw_transaction_conditional(tx.clone(), conditional)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthApiError::ConditionalCostExceeded { cost: 2, max_cost: 1 }));
+        assert_eq!(ErrorObject::from(err).code(), CONDITIONAL_COST_EXCEEDED_CODE);
+
+        let conditional = TransactionConditional {
+            block_number_min: Some(U64::from(2)),
+            block_number_max: Some(U64::from(1)),
+            ..Default::default()
+        };
+        let err =
+            eth_conditional.send_raw_transaction_conditional(tx, conditional).await.unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)));
+
+        assert_eq!(pool.len(), 0);
+    }
+
+    #[test]
+    fn find_nonce_block_in_history() {
+        // the nonce of the account after each block, the second nonce was used by a contract
+        // creation of the account
+        let nonces = [0, 1, 1, 3, 3, 4];
+        let nonce_after = |block: u64| -> ProviderResult<u64> { Ok(nonces[block as usize]) };
+        assert_eq!(find_nonce_block(5, 0, nonce_after).unwrap(), 1);
+        assert_eq!(find_nonce_block(5, 1, nonce_after).unwrap(), 3);
+        assert_eq!(find_nonce_block(5, 2, nonce_after).unwrap(), 3);
+        assert_eq!(find_nonce_block(5, 3, nonce_after).unwrap(), 5);
+
+        // the history of the first three blocks is pruned
+        let pruned = |block: u64| -> ProviderResult<u64> {
+            if block < 3 {
+                return Err(ProviderError::StateAtBlockPruned(block))
+            }
+            Ok(nonces[block as usize])
+        };
+        assert_eq!(find_nonce_block(5, 0, pruned).unwrap(), 3);
+        assert_eq!(find_nonce_block(5, 3, pruned).unwrap(), 5);
+    }
 }
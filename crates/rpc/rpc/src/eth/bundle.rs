@@ -19,7 +19,7 @@ use reth_rpc_types::{EthCallBundle, EthCallBundleResponse, EthCallBundleTransact
 use reth_tasks::pool::BlockingTaskGuard;
 use revm::{
     db::CacheDB,
-    primitives::{ResultAndState, TxEnv},
+    primitives::{EVMError, ExecutionResult, ResultAndState, TxEnv},
 };
 use revm_primitives::{EnvWithHandlerCfg, MAX_BLOB_GAS_PER_BLOCK};
 use std::sync::Arc;
@@ -45,6 +45,10 @@ where
     /// another (or the same) block. This can be used to simulate future blocks with the current
     /// state, or it can be used to simulate a past block. The sender is responsible for signing the
     /// transactions and using the correct nonce and ensuring validity
+    ///
+    /// Like on inclusion, a transaction that is invalid at its position in the bundle, e.g.
+    /// because of its nonce or the balance of the sender, is skipped and marked with the error in
+    /// its result, the remaining transactions are still simulated.
     pub async fn call_bundle(&self, bundle: EthCallBundle) -> EthResult<EthCallBundleResponse> {
         let EthCallBundle { txs, block_number, state_block_number, timestamp } = bundle;
         if txs.is_empty() {
@@ -86,7 +90,8 @@ where
         }
 
         let block_id: reth_rpc_types::BlockId = state_block_number.into();
-        let (cfg, mut block_env, at) = self.inner.eth_api.evm_env_at(block_id).await?;
+        let (mut cfg, mut block_env, at) = self.inner.eth_api.evm_env_at(block_id).await?;
+        cfg.memory_limit = self.inner.eth_api.call_memory_limit();
 
         // need to adjust the timestamp for the next block
         if let Some(timestamp) = timestamp {
@@ -133,11 +138,29 @@ where
                     let tx = tx.into_ecrecovered_transaction(signer);
 
                     hash_bytes.extend_from_slice(tx.hash().as_slice());
-                    let gas_price = tx
-                        .effective_tip_per_gas(basefee)
-                        .ok_or_else(|| RpcInvalidTransactionError::FeeCapTooLow)?;
+                    let invalid =
+                        |err: RpcInvalidTransactionError| EthCallBundleTransactionResult {
+                            from_address: tx.signer(),
+                            to_address: tx.to(),
+                            tx_hash: tx.hash(),
+                            error: Some(err.to_string()),
+                            ..Default::default()
+                        };
+
+                    let Some(gas_price) = tx.effective_tip_per_gas(basefee) else {
+                        results.push(invalid(RpcInvalidTransactionError::FeeCapTooLow));
+                        continue
+                    };
                     tx.try_fill_tx_env(evm.tx_mut())?;
-                    let ResultAndState { result, state } = evm.transact()?;
+                    let ResultAndState { result, state } = match evm.transact() {
+                        Ok(res) => res,
+                        Err(EVMError::Transaction(err)) => {
+                            // the transaction is skipped, so its state changes aren't applied
+                            results.push(invalid(err.into()));
+                            continue
+                        }
+                        Err(err) => return Err(err.into()),
+                    };
 
                     let gas_used = result.gas_used();
                     total_gas_used += gas_used;
@@ -156,12 +179,17 @@ where
                     coinbase_balance_before_tx = coinbase_balance_after_tx;
 
                     // set the return data for the response
-                    let (value, revert) = if result.is_success() {
-                        let value = result.into_output().unwrap_or_default();
-                        (Some(value), None)
-                    } else {
-                        let revert = result.into_output().unwrap_or_default();
-                        (None, Some(revert))
+                    let (value, revert, error) = match result {
+                        ExecutionResult::Success { output, .. } => {
+                            (Some(output.into_data()), None, None)
+                        }
+                        ExecutionResult::Revert { output, .. } => {
+                            (None, Some(output), Some("execution reverted".to_string()))
+                        }
+                        ExecutionResult::Halt { reason, .. } => {
+                            let err = RpcInvalidTransactionError::halt(reason, tx.gas_limit());
+                            (None, None, Some(err.to_string()))
+                        }
                     };
 
                     let tx_res = EthCallBundleTransactionResult {
@@ -175,6 +203,7 @@ where
                         tx_hash: tx.hash(),
                         value,
                         revert,
+                        error,
                     };
                     results.push(tx_res);
 
@@ -5,15 +5,19 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EthStateCacheConfig {
-    /// Max number of blocks in cache.
+    /// Max number of bytes of the cached blocks.
     ///
-    /// Default is 5000.
-    pub max_blocks: u32,
-    /// Max number receipts in cache.
+    /// Default is 500MB.
+    pub max_block_bytes: usize,
+    /// Max number of bytes of the cached receipts.
     ///
-    /// Default is 2000.
-    pub max_receipts: u32,
-    /// Max number of bytes for cached env data.
+    /// Default is 500MB.
+    pub max_receipt_bytes: usize,
+    /// Max number of bytes of the cached headers.
+    ///
+    /// Default is 50MB.
+    pub max_header_bytes: usize,
+    /// Max number of cached env data.
     ///
     /// Default is 1000.
     pub max_envs: u32,
@@ -26,8 +30,9 @@ pub struct EthStateCacheConfig {
 impl Default for EthStateCacheConfig {
     fn default() -> Self {
         Self {
-            max_blocks: DEFAULT_BLOCK_CACHE_MAX_LEN,
-            max_receipts: DEFAULT_RECEIPT_CACHE_MAX_LEN,
+            max_block_bytes: DEFAULT_BLOCK_CACHE_SIZE_BYTES_MB * 1024 * 1024,
+            max_receipt_bytes: DEFAULT_RECEIPT_CACHE_SIZE_BYTES_MB * 1024 * 1024,
+            max_header_bytes: DEFAULT_HEADER_CACHE_SIZE_BYTES_MB * 1024 * 1024,
             max_envs: DEFAULT_ENV_CACHE_MAX_LEN,
             max_concurrent_db_requests: DEFAULT_CONCURRENT_DB_REQUESTS,
         }
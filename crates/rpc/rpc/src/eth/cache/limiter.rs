@@ -0,0 +1,196 @@
+//! Memory based limiter for the caches.
+
+use reth_metrics::metrics::{Counter, Gauge};
+use reth_primitives::{Address, BlockWithSenders, Header, Log, Receipt, B256};
+use schnellru::Limiter;
+use std::{mem, sync::Arc};
+
+/// A heuristic for the in-memory size of a cached value.
+pub trait InMemorySize {
+    /// Returns the approximate number of bytes the value occupies, including heap allocations.
+    fn size(&self) -> usize;
+}
+
+impl InMemorySize for BlockWithSenders {
+    fn size(&self) -> usize {
+        self.block.size() + self.senders.capacity() * mem::size_of::<Address>()
+    }
+}
+
+impl InMemorySize for Header {
+    fn size(&self) -> usize {
+        Self::size(self)
+    }
+}
+
+impl InMemorySize for Log {
+    fn size(&self) -> usize {
+        mem::size_of::<Self>() +
+            self.data.topics().len() * mem::size_of::<B256>() +
+            self.data.data.len()
+    }
+}
+
+impl InMemorySize for Receipt {
+    fn size(&self) -> usize {
+        mem::size_of::<Self>() +
+            self.logs.iter().map(InMemorySize::size).sum::<usize>() +
+            (self.logs.capacity() - self.logs.len()) * mem::size_of::<Log>()
+    }
+}
+
+impl<T: InMemorySize> InMemorySize for Arc<Vec<T>> {
+    fn size(&self) -> usize {
+        mem::size_of::<Vec<T>>() +
+            self.iter().map(InMemorySize::size).sum::<usize>() +
+            (self.capacity() - self.len()) * mem::size_of::<T>()
+    }
+}
+
+/// A [`Limiter`] that evicts the least recently used values once the total [`InMemorySize`] of
+/// the cached values exceeds the configured number of bytes.
+///
+/// Values larger than the whole budget are not cached.
+#[derive(Debug)]
+pub struct ByMemorySize {
+    /// The maximum total size of the cached values.
+    max_bytes: usize,
+    /// The total size of the cached values.
+    size: usize,
+    /// Tracks the total size of the cached values.
+    cached_bytes: Gauge,
+    /// Tracks the total size of the evicted values.
+    evicted_bytes: Counter,
+}
+
+impl ByMemorySize {
+    /// Creates a new limiter for the given number of bytes that reports the cached and evicted
+    /// bytes to the given metrics.
+    pub(crate) fn new(max_bytes: usize, cached_bytes: Gauge, evicted_bytes: Counter) -> Self {
+        Self { max_bytes, size: 0, cached_bytes, evicted_bytes }
+    }
+
+    /// Returns the maximum total size of the cached values.
+    pub const fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// Returns the total size of the cached values.
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    fn add(&mut self, size: usize) {
+        self.size += size;
+        self.cached_bytes.increment(size as f64);
+    }
+
+    fn sub(&mut self, size: usize) {
+        self.size = self.size.saturating_sub(size);
+        self.cached_bytes.decrement(size as f64);
+    }
+}
+
+impl<K, V: InMemorySize> Limiter<K, V> for ByMemorySize {
+    type KeyToInsert<'a> = K;
+    type LinkType = u32;
+
+    fn is_over_the_limit(&self, _length: usize) -> bool {
+        self.size > self.max_bytes
+    }
+
+    fn on_insert(&mut self, _length: usize, key: K, value: V) -> Option<(K, V)> {
+        let size = value.size();
+        if size > self.max_bytes {
+            return None
+        }
+        self.add(size);
+        Some((key, value))
+    }
+
+    fn on_replace(
+        &mut self,
+        _length: usize,
+        _old_key: &mut K,
+        _new_key: K,
+        old_value: &mut V,
+        new_value: &mut V,
+    ) -> bool {
+        self.sub(old_value.size());
+        self.add(new_value.size());
+        true
+    }
+
+    fn on_removed(&mut self, _key: &mut K, value: &mut V) {
+        let size = value.size();
+        self.sub(size);
+        self.evicted_bytes.increment(size as u64);
+    }
+
+    fn on_cleared(&mut self) {
+        self.cached_bytes.set(0.0);
+        self.size = 0;
+    }
+
+    fn on_grow(&mut self, _new_memory_usage: usize) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::cache::MultiConsumerLruCache;
+
+    type HeaderCache = MultiConsumerLruCache<B256, Header, ByMemorySize, ()>;
+
+    fn header(number: u64) -> (B256, Header) {
+        let header = Header { number, ..Default::default() };
+        (header.hash_slow(), header)
+    }
+
+    #[test]
+    fn evicts_least_recently_used_over_budget() {
+        let header_size = InMemorySize::size(&Header::default());
+        let mut cache = HeaderCache::with_max_bytes(header_size * 5 / 2, "test");
+
+        let (first_hash, first) = header(1);
+        let (second_hash, second) = header(2);
+        let (third_hash, third) = header(3);
+        assert!(cache.insert(first_hash, first));
+        assert!(cache.insert(second_hash, second));
+
+        // promote the first header, so the second one is evicted
+        assert!(cache.get(&first_hash).is_some());
+        assert!(cache.insert(third_hash, third));
+
+        assert!(cache.get(&first_hash).is_some());
+        assert!(cache.get(&second_hash).is_none());
+        assert!(cache.get(&third_hash).is_some());
+    }
+
+    #[test]
+    fn rejects_values_over_budget() {
+        let header_size = InMemorySize::size(&Header::default());
+        let mut cache = HeaderCache::with_max_bytes(header_size - 1, "test");
+
+        let (hash, header) = header(1);
+        assert!(!cache.insert(hash, header));
+        assert!(cache.get(&hash).is_none());
+    }
+
+    #[test]
+    fn receipts_size_includes_logs() {
+        let receipt = Receipt::default();
+        let mut with_log = receipt.clone();
+        with_log.logs.push(Log::new_unchecked(
+            Address::ZERO,
+            vec![B256::ZERO, B256::ZERO],
+            vec![0; 100].into(),
+        ));
+
+        let receipts = Arc::new(vec![receipt]);
+        let receipts_with_log = Arc::new(vec![with_log]);
+        assert!(receipts_with_log.size() >= receipts.size() + 2 * 32 + 100);
+    }
+}
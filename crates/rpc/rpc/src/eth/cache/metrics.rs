@@ -12,4 +12,8 @@ pub(crate) struct CacheMetrics {
     pub(crate) hits_total: Counter,
     /// The number of cache misses.
     pub(crate) misses_total: Counter,
+    /// The total size of the cached entities in bytes, if the cache is limited by memory.
+    pub(crate) cached_bytes: Gauge,
+    /// The total size of the evicted entities in bytes, if the cache is limited by memory.
+    pub(crate) evicted_bytes_total: Counter,
 }
@@ -4,12 +4,12 @@ use futures::{future::Either, Stream, StreamExt};
 use reth_errors::{ProviderError, ProviderResult};
 use reth_evm::ConfigureEvm;
 use reth_primitives::{
-    Block, BlockHashOrNumber, BlockWithSenders, Receipt, SealedBlock, SealedBlockWithSenders,
-    TransactionSigned, TransactionSignedEcRecovered, B256,
+    Block, BlockHashOrNumber, BlockWithSenders, Header, Receipt, SealedBlock,
+    SealedBlockWithSenders, TransactionSigned, TransactionSignedEcRecovered, B256,
 };
 use reth_provider::{
-    BlockReader, CanonStateNotification, Chain, EvmEnvProvider, StateProviderFactory,
-    TransactionVariant,
+    BlockReader, CanonStateNotification, Chain, EvmEnvProvider, HeaderProvider,
+    StateProviderFactory, TransactionVariant,
 };
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use revm::primitives::{BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, SpecId};
@@ -29,6 +29,9 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 mod config;
 pub use config::*;
 
+mod limiter;
+pub use limiter::{ByMemorySize, InMemorySize};
+
 mod metrics;
 
 mod multi_consumer;
@@ -44,6 +47,9 @@ type BlockWithSendersResponseSender = oneshot::Sender<ProviderResult<Option<Bloc
 /// The type that can send the response to the requested receipts of a block.
 type ReceiptsResponseSender = oneshot::Sender<ProviderResult<Option<Arc<Vec<Receipt>>>>>;
 
+/// The type that can send the response to a requested [Header]
+type HeaderResponseSender = oneshot::Sender<ProviderResult<Option<Header>>>;
+
 /// The type that can send the response to a requested env
 type EnvResponseSender = oneshot::Sender<ProviderResult<(CfgEnvWithHandlerCfg, BlockEnv)>>;
 
@@ -57,6 +63,8 @@ type BlockLruCache<L> = MultiConsumerLruCache<
 type ReceiptsLruCache<L> =
     MultiConsumerLruCache<B256, Arc<Vec<Receipt>>, L, ReceiptsResponseSender>;
 
+type HeaderLruCache<L> = MultiConsumerLruCache<B256, Header, L, HeaderResponseSender>;
+
 type EnvLruCache<L> =
     MultiConsumerLruCache<B256, (CfgEnvWithHandlerCfg, BlockEnv), L, EnvResponseSender>;
 
@@ -75,21 +83,26 @@ impl EthStateCache {
         provider: Provider,
         action_task_spawner: Tasks,
         evm_config: EvmConfig,
-        max_blocks: u32,
-        max_receipts: u32,
-        max_envs: u32,
-        max_concurrent_db_operations: usize,
+        config: EthStateCacheConfig,
     ) -> (Self, EthStateCacheService<Provider, Tasks, EvmConfig>) {
+        let EthStateCacheConfig {
+            max_block_bytes,
+            max_receipt_bytes,
+            max_header_bytes,
+            max_envs,
+            max_concurrent_db_requests,
+        } = config;
         let (to_service, rx) = unbounded_channel();
         let service = EthStateCacheService {
             provider,
-            full_block_cache: BlockLruCache::new(max_blocks, "blocks"),
-            receipts_cache: ReceiptsLruCache::new(max_receipts, "receipts"),
+            full_block_cache: BlockLruCache::with_max_bytes(max_block_bytes, "blocks"),
+            receipts_cache: ReceiptsLruCache::with_max_bytes(max_receipt_bytes, "receipts"),
+            headers_cache: HeaderLruCache::with_max_bytes(max_header_bytes, "headers"),
             evm_env_cache: EnvLruCache::new(max_envs, "evm_env"),
             action_tx: to_service.clone(),
             action_rx: UnboundedReceiverStream::new(rx),
             action_task_spawner,
-            rate_limiter: Arc::new(Semaphore::new(max_concurrent_db_operations)),
+            rate_limiter: Arc::new(Semaphore::new(max_concurrent_db_requests)),
             evm_config,
         };
         let cache = Self { to_service };
@@ -115,7 +128,7 @@ impl EthStateCache {
     /// Creates a new async LRU backed cache service task and spawns it to a new task via the given
     /// spawner.
     ///
-    /// The cache is memory limited by the given max bytes values.
+    /// The block, receipt and header caches are memory limited by the given max bytes values.
     pub fn spawn_with<Provider, Tasks, EvmConfig>(
         provider: Provider,
         config: EthStateCacheConfig,
@@ -127,17 +140,7 @@ impl EthStateCache {
         Tasks: TaskSpawner + Clone + 'static,
         EvmConfig: ConfigureEvm + 'static,
     {
-        let EthStateCacheConfig { max_blocks, max_receipts, max_envs, max_concurrent_db_requests } =
-            config;
-        let (this, service) = Self::create(
-            provider,
-            executor.clone(),
-            evm_config,
-            max_blocks,
-            max_receipts,
-            max_envs,
-            max_concurrent_db_requests,
-        );
+        let (this, service) = Self::create(provider, executor.clone(), evm_config, config);
         executor.spawn_critical("eth state cache", Box::pin(service));
         this
     }
@@ -250,6 +253,15 @@ impl EthStateCache {
         Ok(block.zip(receipts))
     }
 
+    /// Requests the [Header] for the block hash
+    ///
+    /// Returns `None` if the header does not exist.
+    pub async fn get_header(&self, block_hash: B256) -> ProviderResult<Option<Header>> {
+        let (response_tx, rx) = oneshot::channel();
+        let _ = self.to_service.send(CacheAction::GetHeader { block_hash, response_tx });
+        rx.await.map_err(|_| ProviderError::CacheServiceUnavailable)?
+    }
+
     /// Requests the evm env config for the block hash.
     ///
     /// Returns an error if the corresponding header (required for populating the envs) was not
@@ -271,6 +283,9 @@ impl EthStateCache {
 /// missing in the cache it is fetched and inserted into the cache afterwards. While fetching data
 /// from disk is sync, this service is async since requests and data is shared via channels.
 ///
+/// All data is keyed by block hash, so reorgs don't require any invalidation: the data of
+/// reorged blocks is still correct for their hash and ages out of the cache.
+///
 /// This type is an endless future that listens for incoming messages from the user facing
 /// [`EthStateCache`] via a channel. If the requested data is not cached then it spawns a new task
 /// that does the IO and sends the result back to it. This way the caching service only
@@ -284,20 +299,24 @@ pub(crate) struct EthStateCacheService<
     Provider,
     Tasks,
     EvmConfig,
-    LimitBlocks = ByLength,
-    LimitReceipts = ByLength,
+    LimitBlocks = ByMemorySize,
+    LimitReceipts = ByMemorySize,
+    LimitHeaders = ByMemorySize,
     LimitEnvs = ByLength,
 > where
     LimitBlocks: Limiter<B256, BlockWithSenders>,
     LimitReceipts: Limiter<B256, Arc<Vec<Receipt>>>,
+    LimitHeaders: Limiter<B256, Header>,
     LimitEnvs: Limiter<B256, (CfgEnvWithHandlerCfg, BlockEnv)>,
 {
     /// The type used to lookup data from disk
     provider: Provider,
     /// The LRU cache for full blocks grouped by their hash.
     full_block_cache: BlockLruCache<LimitBlocks>,
-    /// The LRU cache for full blocks grouped by their hash.
+    /// The LRU cache for receipts grouped by their block hash.
     receipts_cache: ReceiptsLruCache<LimitReceipts>,
+    /// The LRU cache for headers grouped by their hash.
+    headers_cache: HeaderLruCache<LimitHeaders>,
     /// The LRU cache for revm environments
     evm_env_cache: EnvLruCache<LimitEnvs>,
     /// Sender half of the action channel.
@@ -360,41 +379,24 @@ where
         }
     }
 
-    fn on_reorg_block(&mut self, block_hash: B256, res: ProviderResult<Option<BlockWithSenders>>) {
-        if let Some(queued) = self.full_block_cache.remove(&block_hash) {
+    fn on_new_header(&mut self, block_hash: B256, res: ProviderResult<Option<Header>>) {
+        if let Some(queued) = self.headers_cache.remove(&block_hash) {
             // send the response to queued senders
             for tx in queued {
-                match tx {
-                    Either::Left(block_with_senders) => {
-                        let _ = block_with_senders.send(res.clone());
-                    }
-                    Either::Right(transaction_tx) => {
-                        let _ = transaction_tx.send(
-                            res.clone()
-                                .map(|maybe_block| maybe_block.map(|block| block.block.body)),
-                        );
-                    }
-                }
+                let _ = tx.send(res.clone());
             }
         }
-    }
 
-    fn on_reorg_receipts(
-        &mut self,
-        block_hash: B256,
-        res: ProviderResult<Option<Arc<Vec<Receipt>>>>,
-    ) {
-        if let Some(queued) = self.receipts_cache.remove(&block_hash) {
-            // send the response to queued senders
-            for tx in queued {
-                let _ = tx.send(res.clone());
-            }
+        // cache good header
+        if let Ok(Some(header)) = res {
+            self.headers_cache.insert(block_hash, header);
         }
     }
 
     fn update_cached_metrics(&self) {
         self.full_block_cache.update_cached_metrics();
         self.receipts_cache.update_cached_metrics();
+        self.headers_cache.update_cached_metrics();
         self.evm_env_cache.update_cached_metrics();
     }
 }
@@ -496,6 +498,27 @@ where
                                 }));
                             }
                         }
+                        CacheAction::GetHeader { block_hash, response_tx } => {
+                            // check if header is cached
+                            if let Some(header) = this.headers_cache.get(&block_hash).cloned() {
+                                let _ = response_tx.send(Ok(Some(header)));
+                                continue
+                            }
+
+                            // header is not in the cache, request it if this is the first consumer
+                            if this.headers_cache.queue(block_hash, response_tx) {
+                                let provider = this.provider.clone();
+                                let action_tx = this.action_tx.clone();
+                                let rate_limiter = this.rate_limiter.clone();
+                                this.action_task_spawner.spawn_blocking(Box::pin(async move {
+                                    // Acquire permit
+                                    let _permit = rate_limiter.acquire().await;
+                                    let res = provider.header(&block_hash);
+                                    let _ = action_tx
+                                        .send(CacheAction::HeaderResult { block_hash, res });
+                                }));
+                            }
+                        }
                         CacheAction::GetEnv { block_hash, response_tx } => {
                             // check if env data is cached
                             if let Some(env) = this.evm_env_cache.get(&block_hash).cloned() {
@@ -536,6 +559,9 @@ where
                         CacheAction::ReceiptsResult { block_hash, res } => {
                             this.on_new_receipts(block_hash, res);
                         }
+                        CacheAction::HeaderResult { block_hash, res } => {
+                            this.on_new_header(block_hash, res);
+                        }
                         CacheAction::BlockWithSendersResult { block_hash, res } => match res {
                             Ok(Some(block_with_senders)) => {
                                 this.on_new_block(block_hash, Ok(Some(block_with_senders)));
@@ -563,25 +589,16 @@ where
                         }
                         CacheAction::CacheNewCanonicalChain { chain_change } => {
                             for block in chain_change.blocks {
-                                this.on_new_block(block.hash(), Ok(Some(block.unseal())));
-                            }
-
-                            for block_receipts in chain_change.receipts {
-                                this.on_new_receipts(
-                                    block_receipts.block_hash,
-                                    Ok(Some(Arc::new(
-                                        block_receipts.receipts.into_iter().flatten().collect(),
-                                    ))),
+                                let block_hash = block.hash();
+                                this.on_new_header(
+                                    block_hash,
+                                    Ok(Some(block.header.header().clone())),
                                 );
-                            }
-                        }
-                        CacheAction::RemoveReorgedChain { chain_change } => {
-                            for block in chain_change.blocks {
-                                this.on_reorg_block(block.hash(), Ok(Some(block.unseal())));
+                                this.on_new_block(block_hash, Ok(Some(block.unseal())));
                             }
 
                             for block_receipts in chain_change.receipts {
-                                this.on_reorg_receipts(
+                                this.on_new_receipts(
                                     block_receipts.block_hash,
                                     Ok(Some(Arc::new(
                                         block_receipts.receipts.into_iter().flatten().collect(),
@@ -603,11 +620,12 @@ enum CacheAction {
     GetBlockTransactions { block_hash: B256, response_tx: BlockTransactionsResponseSender },
     GetEnv { block_hash: B256, response_tx: EnvResponseSender },
     GetReceipts { block_hash: B256, response_tx: ReceiptsResponseSender },
+    GetHeader { block_hash: B256, response_tx: HeaderResponseSender },
     BlockWithSendersResult { block_hash: B256, res: ProviderResult<Option<BlockWithSenders>> },
     ReceiptsResult { block_hash: B256, res: ProviderResult<Option<Arc<Vec<Receipt>>>> },
+    HeaderResult { block_hash: B256, res: ProviderResult<Option<Header>> },
     EnvResult { block_hash: B256, res: Box<ProviderResult<(CfgEnvWithHandlerCfg, BlockEnv)>> },
     CacheNewCanonicalChain { chain_change: ChainChange },
-    RemoveReorgedChain { chain_change: ChainChange },
 }
 
 struct BlockReceipts {
@@ -638,19 +656,12 @@ impl ChainChange {
 /// Awaits for new chain events and directly inserts them into the cache so they're available
 /// immediately before they need to be fetched from disk.
 ///
-/// Reorged blocks are removed from the cache.
+/// Reorged blocks don't need to be removed from the cache, since all data is keyed by block hash.
 pub async fn cache_new_blocks_task<St>(eth_state_cache: EthStateCache, mut events: St)
 where
     St: Stream<Item = CanonStateNotification> + Unpin + 'static,
 {
     while let Some(event) = events.next().await {
-        if let Some(reverted) = event.reverted() {
-            let chain_change = ChainChange::new(reverted);
-
-            let _ =
-                eth_state_cache.to_service.send(CacheAction::RemoveReorgedChain { chain_change });
-        }
-
         let chain_change = ChainChange::new(event.committed());
 
         let _ =
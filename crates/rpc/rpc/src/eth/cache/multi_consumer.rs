@@ -1,4 +1,7 @@
-use super::metrics::CacheMetrics;
+use super::{
+    limiter::{ByMemorySize, InMemorySize},
+    metrics::CacheMetrics,
+};
 use schnellru::{ByLength, Limiter, LruMap};
 use std::{
     collections::{hash_map::Entry, HashMap},
@@ -111,3 +114,21 @@ where
         }
     }
 }
+
+impl<K, V, S> MultiConsumerLruCache<K, V, ByMemorySize, S>
+where
+    K: Hash + Eq,
+    V: InMemorySize,
+{
+    /// Creates a new empty map that holds values up to a total size of `max_bytes` and metric
+    /// label.
+    pub fn with_max_bytes(max_bytes: usize, cache_id: &str) -> Self {
+        let metrics = CacheMetrics::new_with_labels(&[("cache", cache_id.to_string())]);
+        let limiter = ByMemorySize::new(
+            max_bytes,
+            metrics.cached_bytes.clone(),
+            metrics.evicted_bytes_total.clone(),
+        );
+        Self { cache: LruMap::new(limiter), queued: Default::default(), metrics }
+    }
+}
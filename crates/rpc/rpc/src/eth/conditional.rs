@@ -0,0 +1,60 @@
+//! `Eth` transaction conditional implementation.
+
+use crate::eth::{
+    error::{EthApiError, EthResult},
+    EthTransactions,
+};
+use jsonrpsee::core::RpcResult;
+use reth_primitives::{Bytes, TransactionConditional, B256};
+use reth_rpc_api::EthConditionalApiServer;
+
+/// `Eth` API implementation for submitting transactions with a [`TransactionConditional`].
+///
+/// This is not a standard endpoint, so it's only installed if enabled.
+#[derive(Debug, Clone)]
+pub struct EthConditional<Eth> {
+    /// Access to commonly used code of the `eth` namespace
+    eth_api: Eth,
+    /// The maximum cost of a conditional, see [`TransactionConditional::cost`].
+    max_cost: usize,
+}
+
+impl<Eth> EthConditional<Eth> {
+    /// Create a new `EthConditional` instance.
+    pub const fn new(eth_api: Eth, max_cost: usize) -> Self {
+        Self { eth_api, max_cost }
+    }
+}
+
+impl<Eth> EthConditional<Eth>
+where
+    Eth: EthTransactions + 'static,
+{
+    /// Submits the raw transaction with the given conditional to the pool, if the cost of the
+    /// conditional doesn't exceed the limit.
+    pub async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> EthResult<B256> {
+        let cost = conditional.cost();
+        if cost > self.max_cost {
+            return Err(EthApiError::ConditionalCostExceeded { cost, max_cost: self.max_cost })
+        }
+        self.eth_api.send_raw_transaction_conditional(tx, conditional).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<Eth> EthConditionalApiServer for EthConditional<Eth>
+where
+    Eth: EthTransactions + 'static,
+{
+    async fn send_raw_transaction_conditional(
+        &self,
+        bytes: Bytes,
+        conditional: TransactionConditional,
+    ) -> RpcResult<B256> {
+        Ok(Self::send_raw_transaction_conditional(self, bytes, conditional).await?)
+    }
+}
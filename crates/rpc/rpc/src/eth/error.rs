@@ -1,24 +1,41 @@
 //! Implementation specific Errors for the `eth_` namespace.
 
-use crate::result::{internal_rpc_err, invalid_params_rpc_err, rpc_err, rpc_error_with_code};
+use crate::result::{
+    internal_rpc_err, internal_rpc_err_with_data, invalid_params_rpc_err, rpc_err,
+    rpc_error_with_code,
+};
 use alloy_sol_types::decode_revert_reason;
 use jsonrpsee::types::{error::CALL_EXECUTION_FAILED_CODE, ErrorObject};
 use reth_errors::RethError;
-use reth_primitives::{revm_primitives::InvalidHeader, Address, Bytes};
+use reth_primitives::{
+    revm_primitives::InvalidHeader, Address, BlockNumber, Bytes, InvalidTransactionError, B256,
+    U256,
+};
 use reth_rpc_types::{
     error::EthRpcErrorCode, request::TransactionInputError, BlockError, ToRpcError,
 };
-use reth_transaction_pool::error::{
-    Eip4844PoolTransactionError, InvalidPoolTransactionError, PoolError, PoolErrorKind,
-    PoolTransactionError,
+use reth_transaction_pool::{
+    error::{
+        Eip4844PoolTransactionError, InvalidPoolTransactionError, PoolError, PoolErrorKind,
+        PoolTransactionError,
+    },
+    BlobStoreError, PoolSnapshotError,
 };
 use revm::primitives::{EVMError, ExecutionResult, HaltReason, OutOfGasError};
-use revm_inspectors::tracing::{js::JsInspectorError, MuxError};
+#[cfg(feature = "js-tracer")]
+use revm_inspectors::tracing::js::JsInspectorError;
+use revm_inspectors::tracing::MuxError;
 use std::time::Duration;
 
 /// Result alias
 pub type EthResult<T> = Result<T, EthApiError>;
 
+/// Error code for a transaction that is rejected because its conditional doesn't hold.
+pub const CONDITIONAL_REJECTED_CODE: i32 = -32003;
+
+/// Error code for a transaction conditional whose cost exceeds the configured limit.
+pub const CONDITIONAL_COST_EXCEEDED_CODE: i32 = -32005;
+
 /// Errors that can occur when interacting with the `eth_` namespace
 #[derive(Debug, thiserror::Error)]
 pub enum EthApiError {
@@ -52,6 +69,15 @@ pub enum EthApiError {
     /// When an invalid block range is provided
     #[error("invalid block range")]
     InvalidBlockRange,
+    /// Thrown when the target block for proof computation exceeds the maximum configured window.
+    #[error("distance to target block exceeds maximum proof window")]
+    ExceedsMaxProofWindow,
+    /// Thrown when the state required for generating a historical proof has been pruned.
+    #[error("state for proof generation at block {0} is not available due to pruning")]
+    ProofStateUnavailable(BlockNumber),
+    /// Thrown when the historical state required to execute a transaction has been pruned.
+    #[error("state at block {0} is not available due to pruning")]
+    StateAtBlockPruned(BlockNumber),
     /// An internal error where prevrandao is not set in the evm's environment
     #[error("prevrandao not in the EVM's environment after merge")]
     PrevrandaoNotSet,
@@ -80,6 +106,15 @@ pub enum EthApiError {
     /// Thrown when a requested transaction is not found
     #[error("transaction not found")]
     TransactionNotFound,
+    /// Thrown when the transaction of a sender and nonce can't be located because the history of
+    /// the account has been pruned.
+    #[error("transaction of {sender} with nonce {nonce} is not available due to pruning")]
+    TransactionHistoryPruned {
+        /// The sender of the transaction.
+        sender: Address,
+        /// The nonce of the transaction.
+        nonce: u64,
+    },
     /// Some feature is unsupported
     #[error("unsupported")]
     Unsupported(&'static str),
@@ -104,9 +139,34 @@ pub enum EthApiError {
     /// Error thrown when a (tracing) call exceeds the configured timeout
     #[error("execution aborted (timeout = {0:?})")]
     ExecutionTimedOut(Duration),
+    /// Error thrown when a transaction submitted with `eth_sendRawTransactionSync` isn't included
+    /// in a canonical block within the timeout.
+    #[error("transaction {hash} was not included within {timeout:?}")]
+    TransactionConfirmationTimeout {
+        /// The hash of the submitted transaction.
+        hash: B256,
+        /// The time waited for the inclusion of the transaction.
+        timeout: Duration,
+    },
+    /// Error thrown when a transaction submitted with `eth_sendRawTransactionConditional` is
+    /// rejected because its conditional doesn't hold.
+    #[error("transaction conditional not met: {0}")]
+    ConditionalRejected(&'static str),
+    /// Error thrown when the cost of the conditional of a transaction submitted with
+    /// `eth_sendRawTransactionConditional` exceeds the limit.
+    #[error("transaction conditional cost {cost} exceeds the limit of {max_cost}")]
+    ConditionalCostExceeded {
+        /// The cost of the conditional.
+        cost: usize,
+        /// The configured maximum cost.
+        max_cost: usize,
+    },
     /// Internal Error thrown by the javascript tracer
     #[error("{0}")]
     InternalJsTracerError(String),
+    /// Error of a custom tracer registered by the node
+    #[error("{0}")]
+    CustomTracerError(String),
     #[error(transparent)]
     /// Call Input error when both `data` and `input` fields are set and not equal.
     TransactionInputError(#[from] TransactionInputError),
@@ -138,6 +198,7 @@ impl From<EthApiError> for ErrorObject<'static> {
             EthApiError::InvalidTransactionSignature |
             EthApiError::EmptyRawTransactionData |
             EthApiError::InvalidBlockRange |
+            EthApiError::ExceedsMaxProofWindow |
             EthApiError::ConflictingFeeFieldsInRequest |
             EthApiError::Signing(_) |
             EthApiError::BothStateAndStateDiffInOverride(_) |
@@ -152,7 +213,11 @@ impl From<EthApiError> for ErrorObject<'static> {
             EthApiError::TransactionNotFound |
             EthApiError::EvmCustom(_) |
             EthApiError::InvalidRewardPercentiles => internal_rpc_err(error.to_string()),
-            EthApiError::UnknownBlockNumber | EthApiError::UnknownBlockOrTxIndex => {
+            EthApiError::UnknownBlockNumber |
+            EthApiError::UnknownBlockOrTxIndex |
+            EthApiError::ProofStateUnavailable(_) |
+            EthApiError::StateAtBlockPruned(_) |
+            EthApiError::TransactionHistoryPruned { .. } => {
                 rpc_error_with_code(EthRpcErrorCode::ResourceNotFound.code(), error.to_string())
             }
             EthApiError::UnknownSafeOrFinalizedBlock => {
@@ -160,10 +225,20 @@ impl From<EthApiError> for ErrorObject<'static> {
             }
             EthApiError::Unsupported(msg) => internal_rpc_err(msg),
             EthApiError::InternalJsTracerError(msg) => internal_rpc_err(msg),
+            EthApiError::CustomTracerError(msg) => internal_rpc_err(msg),
             EthApiError::InvalidParams(msg) => invalid_params_rpc_err(msg),
             err @ EthApiError::ExecutionTimedOut(_) => {
                 rpc_error_with_code(CALL_EXECUTION_FAILED_CODE, err.to_string())
             }
+            EthApiError::TransactionConfirmationTimeout { hash, .. } => {
+                internal_rpc_err_with_data(error.to_string(), hash.as_slice())
+            }
+            EthApiError::ConditionalRejected(_) => {
+                rpc_error_with_code(CONDITIONAL_REJECTED_CODE, error.to_string())
+            }
+            EthApiError::ConditionalCostExceeded { .. } => {
+                rpc_error_with_code(CONDITIONAL_COST_EXCEEDED_CODE, error.to_string())
+            }
             err @ EthApiError::InternalBlockingTaskError | err @ EthApiError::InternalEthError => {
                 internal_rpc_err(err.to_string())
             }
@@ -174,6 +249,7 @@ impl From<EthApiError> for ErrorObject<'static> {
     }
 }
 
+#[cfg(feature = "js-tracer")]
 impl From<JsInspectorError> for EthApiError {
     fn from(error: JsInspectorError) -> Self {
         match error {
@@ -205,11 +281,40 @@ impl From<reth_errors::ProviderError> for EthApiError {
             ProviderError::FinalizedBlockNotFound | ProviderError::SafeBlockNotFound => {
                 Self::UnknownSafeOrFinalizedBlock
             }
+            err @ ProviderError::UnknownTable(_) => Self::InvalidParams(err.to_string()),
             err => Self::Internal(err.into()),
         }
     }
 }
 
+impl From<BlobStoreError> for EthApiError {
+    fn from(error: BlobStoreError) -> Self {
+        Self::Internal(RethError::other(error))
+    }
+}
+
+impl From<PoolSnapshotError> for EthApiError {
+    fn from(error: PoolSnapshotError) -> Self {
+        if error.is_invalid_snapshot() {
+            Self::InvalidParams(error.to_string())
+        } else {
+            Self::Internal(RethError::other(error))
+        }
+    }
+}
+
+impl From<reth_errors::TransactionReplayError> for EthApiError {
+    fn from(error: reth_errors::TransactionReplayError) -> Self {
+        use reth_errors::TransactionReplayError;
+        match error {
+            TransactionReplayError::TransactionNotInBlock { .. } => Self::TransactionNotFound,
+            TransactionReplayError::StateAtBlockPruned(number) => Self::StateAtBlockPruned(number),
+            TransactionReplayError::Execution(err) => Self::Internal(err.into()),
+            TransactionReplayError::Provider(err) => err.into(),
+        }
+    }
+}
+
 impl<T> From<EVMError<T>> for EthApiError
 where
     T: Into<Self>,
@@ -456,14 +561,13 @@ impl From<revm::primitives::InvalidTransaction> for RpcInvalidTransactionError {
     }
 }
 
-impl From<reth_primitives::InvalidTransactionError> for RpcInvalidTransactionError {
-    fn from(err: reth_primitives::InvalidTransactionError) -> Self {
-        use reth_primitives::InvalidTransactionError;
+impl From<InvalidTransactionError> for RpcInvalidTransactionError {
+    fn from(err: InvalidTransactionError) -> Self {
         // This conversion is used to convert any transaction errors that could occur inside the
         // txpool (e.g. `eth_sendRawTransaction`) to their corresponding RPC
         match err {
             InvalidTransactionError::InsufficientFunds { .. } => Self::InsufficientFunds,
-            InvalidTransactionError::NonceNotConsistent => Self::NonceTooLow,
+            InvalidTransactionError::NonceNotConsistent { .. } => Self::NonceTooLow,
             InvalidTransactionError::OldLegacyChainId => {
                 // Note: this should be unreachable since Spurious Dragon now enabled
                 Self::OldLegacyChainId
@@ -526,6 +630,9 @@ impl std::fmt::Display for RevertError {
 impl std::error::Error for RevertError {}
 
 /// A helper error type that's mainly used to mirror `geth` Txpool's error messages
+///
+/// Downstream tooling matches these messages, so they must be kept identical to the messages of
+/// geth's transaction pool, including the details of the rejection.
 #[derive(Debug, thiserror::Error)]
 pub enum RpcPoolError {
     /// When the transaction is already known
@@ -537,12 +644,39 @@ pub enum RpcPoolError {
     /// When the transaction is underpriced
     #[error("transaction underpriced")]
     Underpriced,
+    /// When the priority fee of the transaction is below the minimum priority fee of the pool
+    #[error("transaction underpriced: gas tip cap {tip}, minimum needed {minimum}")]
+    TipBelowMinimum {
+        /// The priority fee of the transaction.
+        tip: u128,
+        /// The minimum priority fee of the pool.
+        minimum: u128,
+    },
+    /// When the nonce of the transaction is lower than the nonce of the sender
+    #[error("nonce too low: next nonce {next_nonce}, tx nonce {tx_nonce}")]
+    NonceTooLow {
+        /// The next nonce of the sender.
+        next_nonce: u64,
+        /// The nonce of the transaction.
+        tx_nonce: u64,
+    },
+    /// When the balance of the sender doesn't cover the cost of the transaction
+    #[error(
+        "insufficient funds for gas * price + value: balance {balance}, tx cost {cost}, overshot {}",
+        .cost - .balance
+    )]
+    InsufficientFunds {
+        /// The balance of the sender.
+        balance: U256,
+        /// The maximum cost of the transaction.
+        cost: U256,
+    },
     /// When the transaction pool is full
     #[error("txpool is full")]
     TxPoolOverflow,
-    /// When the replacement transaction is underpriced
-    #[error("replacement transaction underpriced")]
-    ReplaceUnderpriced,
+    /// When the replacement transaction is underpriced, with the required price bump (in %)
+    #[error("replacement transaction underpriced: requires a {0}% price bump")]
+    ReplaceUnderpriced(u128),
     /// When the transaction exceeds the block gas limit
     #[error("exceeds block gas limit")]
     ExceedsGasLimit,
@@ -550,11 +684,21 @@ pub enum RpcPoolError {
     #[error("negative value")]
     NegativeValue,
     /// When oversized data is encountered
-    #[error("oversized data")]
-    OversizedData,
+    #[error("oversized data: transaction size {size}, limit {limit}")]
+    OversizedData {
+        /// The size of the transaction.
+        size: usize,
+        /// The maximum size of a transaction.
+        limit: usize,
+    },
     /// When the max initcode size is exceeded
-    #[error("max initcode size exceeded")]
-    ExceedsMaxInitCodeSize,
+    #[error("max initcode size exceeded: code size {size}, limit {limit}")]
+    ExceedsMaxInitCodeSize {
+        /// The size of the init code.
+        size: usize,
+        /// The maximum size of the init code.
+        limit: usize,
+    },
     /// Errors related to invalid transactions
     #[error(transparent)]
     Invalid(#[from] RpcInvalidTransactionError),
@@ -577,9 +721,10 @@ pub enum RpcPoolError {
 
 impl From<RpcPoolError> for ErrorObject<'static> {
     fn from(error: RpcPoolError) -> Self {
+        // geth returns all rejections of the pool with the same code
         match error {
-            RpcPoolError::Invalid(err) => err.into(),
-            error => internal_rpc_err(error.to_string()),
+            RpcPoolError::Other(err) => internal_rpc_err(err.to_string()),
+            error => rpc_error_with_code(EthRpcErrorCode::InvalidInput.code(), error.to_string()),
         }
     }
 }
@@ -587,11 +732,14 @@ impl From<RpcPoolError> for ErrorObject<'static> {
 impl From<PoolError> for RpcPoolError {
     fn from(err: PoolError) -> Self {
         match err.kind {
-            PoolErrorKind::ReplacementUnderpriced => Self::ReplaceUnderpriced,
-            PoolErrorKind::FeeCapBelowMinimumProtocolFeeCap(_) => Self::Underpriced,
-            PoolErrorKind::SpammerExceededCapacity(_) | PoolErrorKind::DiscardedOnInsert => {
-                Self::TxPoolOverflow
+            PoolErrorKind::ReplacementUnderpriced(bump) => Self::ReplaceUnderpriced(bump),
+            PoolErrorKind::FeeCapBelowMinimumProtocolFeeCap(_) => {
+                // the fee cap is below the base fee of any block
+                Self::Invalid(RpcInvalidTransactionError::FeeCapTooLow)
             }
+            PoolErrorKind::SpammerExceededCapacity(_) |
+            PoolErrorKind::ExceedsMaxAccountSlots(_) |
+            PoolErrorKind::DiscardedOnInsert => Self::TxPoolOverflow,
             PoolErrorKind::InvalidTransaction(err) => err.into(),
             PoolErrorKind::Other(err) => Self::Other(err),
             PoolErrorKind::AlreadyImported => Self::AlreadyKnown,
@@ -603,16 +751,28 @@ impl From<PoolError> for RpcPoolError {
 impl From<InvalidPoolTransactionError> for RpcPoolError {
     fn from(err: InvalidPoolTransactionError) -> Self {
         match err {
+            InvalidPoolTransactionError::Consensus(
+                InvalidTransactionError::NonceNotConsistent { tx, state },
+            ) => Self::NonceTooLow { next_nonce: state, tx_nonce: tx },
+            InvalidPoolTransactionError::Consensus(InvalidTransactionError::InsufficientFunds(
+                funds,
+            )) => Self::InsufficientFunds { balance: funds.got, cost: funds.expected },
             InvalidPoolTransactionError::Consensus(err) => Self::Invalid(err.into()),
             InvalidPoolTransactionError::ExceedsGasLimit(_, _) => Self::ExceedsGasLimit,
-            InvalidPoolTransactionError::ExceedsMaxInitCodeSize(_, _) => {
-                Self::ExceedsMaxInitCodeSize
+            InvalidPoolTransactionError::ExceedsMaxInitCodeSize(size, limit) => {
+                Self::ExceedsMaxInitCodeSize { size, limit }
             }
             InvalidPoolTransactionError::IntrinsicGasTooLow => {
                 Self::Invalid(RpcInvalidTransactionError::GasTooLow)
             }
-            InvalidPoolTransactionError::OversizedData(_, _) => Self::OversizedData,
+            InvalidPoolTransactionError::OversizedData(size, limit) => {
+                Self::OversizedData { size, limit }
+            }
             InvalidPoolTransactionError::Underpriced => Self::Underpriced,
+            InvalidPoolTransactionError::PriorityFeeBelowMinimum {
+                priority_fee,
+                minimum_priority_fee,
+            } => Self::TipBelowMinimum { tip: priority_fee, minimum: minimum_priority_fee },
             InvalidPoolTransactionError::Other(err) => Self::PoolTransactionError(err),
             InvalidPoolTransactionError::Eip4844(err) => Self::Eip4844(err),
             InvalidPoolTransactionError::Overdraft => {
@@ -671,4 +831,133 @@ mod tests {
         let err = EthApiError::ExecutionTimedOut(Duration::from_secs(10));
         assert_eq!(err.to_string(), "execution aborted (timeout = 10s)");
     }
+
+    #[test]
+    fn replay_state_pruned_error() {
+        let err = EthApiError::from(reth_errors::TransactionReplayError::from(
+            reth_errors::ProviderError::StateAtBlockPruned(10),
+        ));
+        assert!(matches!(err, EthApiError::StateAtBlockPruned(10)));
+        let err: ErrorObject<'static> = err.into();
+        assert_eq!(err.code(), EthRpcErrorCode::ResourceNotFound.code());
+        assert_eq!(err.message(), "state at block 10 is not available due to pruning");
+    }
+
+    /// Asserts that the pool error is returned with the given message and geth's error code.
+    fn assert_pool_rejection(kind: impl Into<PoolErrorKind>, msg: &str) {
+        let err: ErrorObject<'static> = EthApiError::from(PoolError::new(B256::ZERO, kind)).into();
+        assert_eq!(err.message(), msg);
+        assert_eq!(err.code(), EthRpcErrorCode::InvalidInput.code());
+    }
+
+    #[test]
+    fn geth_compatible_pool_rejections() {
+        assert_pool_rejection(PoolErrorKind::AlreadyImported, "already known");
+        assert_pool_rejection(
+            PoolErrorKind::ReplacementUnderpriced(10),
+            "replacement transaction underpriced: requires a 10% price bump",
+        );
+        assert_pool_rejection(PoolErrorKind::DiscardedOnInsert, "txpool is full");
+        assert_pool_rejection(
+            PoolErrorKind::SpammerExceededCapacity(Address::ZERO),
+            "txpool is full",
+        );
+        assert_pool_rejection(
+            PoolErrorKind::ExceedsMaxAccountSlots(Address::ZERO),
+            "txpool is full",
+        );
+        assert_pool_rejection(
+            PoolErrorKind::ExistingConflictingTransactionType(Address::ZERO, 3),
+            "address already reserved",
+        );
+        assert_pool_rejection(
+            PoolErrorKind::FeeCapBelowMinimumProtocolFeeCap(1),
+            "max fee per gas less than block base fee",
+        );
+    }
+
+    #[test]
+    fn geth_compatible_invalid_transaction_rejections() {
+        assert_pool_rejection(
+            InvalidPoolTransactionError::Consensus(InvalidTransactionError::NonceNotConsistent {
+                tx: 3,
+                state: 5,
+            }),
+            "nonce too low: next nonce 5, tx nonce 3",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::Consensus(InvalidTransactionError::InsufficientFunds(
+                (U256::from(100), U256::from(121)).into(),
+            )),
+            "insufficient funds for gas * price + value: balance 100, tx cost 121, overshot 21",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::Overdraft,
+            "insufficient funds for gas * price + value",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::Consensus(InvalidTransactionError::FeeCapTooLow),
+            "max fee per gas less than block base fee",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::Consensus(InvalidTransactionError::TipAboveFeeCap),
+            "max priority fee per gas higher than max fee per gas",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::Consensus(InvalidTransactionError::ChainIdMismatch),
+            "invalid chain ID",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::Consensus(InvalidTransactionError::TxTypeNotSupported),
+            "transaction type not supported",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::Consensus(
+                InvalidTransactionError::SignerAccountHasBytecode,
+            ),
+            "sender is not an EOA",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::PriorityFeeBelowMinimum {
+                priority_fee: 1,
+                minimum_priority_fee: 2,
+            },
+            "transaction underpriced: gas tip cap 1, minimum needed 2",
+        );
+        assert_pool_rejection(InvalidPoolTransactionError::Underpriced, "transaction underpriced");
+        assert_pool_rejection(
+            InvalidPoolTransactionError::ExceedsGasLimit(30_000_001, 30_000_000),
+            "exceeds block gas limit",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::IntrinsicGasTooLow,
+            "intrinsic gas too low",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::OversizedData(131_073, 131_072),
+            "oversized data: transaction size 131073, limit 131072",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::ExceedsMaxInitCodeSize(49_153, 49_152),
+            "max initcode size exceeded: code size 49153, limit 49152",
+        );
+    }
+
+    #[test]
+    fn geth_compatible_blob_transaction_rejections() {
+        assert_pool_rejection(
+            InvalidPoolTransactionError::Eip4844(Eip4844PoolTransactionError::NoEip4844Blobs),
+            "blobless blob transaction",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::Eip4844(
+                Eip4844PoolTransactionError::TooManyEip4844Blobs { have: 7, permitted: 6 },
+            ),
+            "too many blobs in transaction: have 7, permitted 6",
+        );
+        assert_pool_rejection(
+            InvalidPoolTransactionError::Eip4844(Eip4844PoolTransactionError::Eip4844NonceGap),
+            "nonce too high",
+        );
+    }
 }
@@ -5,15 +5,21 @@ use crate::{
         logs_utils::{self, append_matching_block_logs},
     },
     result::{rpc_error_with_code, ToRpcResult},
-    EthSubscriptionIdProvider,
+    EthSubscriptionIdProvider, RpcConnectionId,
 };
 use core::fmt;
 
 use async_trait::async_trait;
+use futures::future::try_join_all;
 use jsonrpsee::{core::RpcResult, server::IdProvider};
-use reth_primitives::{ChainInfo, IntoRecoveredTransaction, TxHash};
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
+use reth_primitives::{ChainInfo, IntoRecoveredTransaction, TxHash, U64};
 use reth_provider::{BlockIdReader, BlockReader, EvmEnvProvider, ProviderError};
 use reth_rpc_api::EthFilterApiServer;
+use reth_rpc_server_types::constants::{DEFAULT_FILTER_CATCH_UP_BLOCKS, DEFAULT_STALE_FILTER_TTL};
 use reth_rpc_types::{
     BlockNumHash, Filter, FilterBlockOption, FilterChanges, FilterId, FilteredParams, Log,
     PendingTransactionFilterKind,
@@ -21,22 +27,34 @@ use reth_rpc_types::{
 
 use reth_tasks::TaskSpawner;
 use reth_transaction_pool::{NewSubpoolTransactionStream, PoolTransaction, TransactionPool};
+use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     iter::StepBy,
     ops::RangeInclusive,
-    sync::Arc,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 use tokio::{
-    sync::{mpsc::Receiver, Mutex},
+    sync::{mpsc::Receiver, oneshot, Mutex},
     time::MissedTickBehavior,
 };
-use tracing::trace;
+use tracing::{trace, warn};
 
 /// The maximum number of headers we read at once when handling a range filter.
 const MAX_HEADERS_RANGE: u64 = 1_000; // with ~530bytes per header this is ~500kb
 
+/// The interval at which changes of the installed log filters are persisted.
+const FILTER_PERSISTENCE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The error code of log queries that exceed a configured limit, see also
+/// [EIP-1474](https://eips.ethereum.org/EIPS/eip-1474).
+pub const QUERY_LIMIT_EXCEEDED_CODE: i32 = -32005;
+
 /// `Eth` filter RPC implementation.
 pub struct EthFilter<Provider, Pool> {
     /// All nested fields bundled together
@@ -56,6 +74,9 @@ where
     /// See also [`EthFilterConfig`].
     ///
     /// This also spawns a task that periodically clears stale filters.
+    ///
+    /// If a persistence path is configured, the log filters persisted there are restored and a
+    /// task is spawned that persists their changes.
     pub fn new(
         provider: Provider,
         pool: Pool,
@@ -63,20 +84,54 @@ where
         config: EthFilterConfig,
         task_spawner: Box<dyn TaskSpawner>,
     ) -> Self {
-        let EthFilterConfig { max_blocks_per_filter, max_logs_per_response, stale_filter_ttl } =
-            config;
+        let EthFilterConfig {
+            max_blocks_per_filter,
+            max_logs_per_response,
+            query_timeout,
+            stale_filter_ttl,
+            max_active_filters,
+            persistence_path,
+            catch_up_blocks,
+        } = config;
+
+        let persistence = persistence_path.map(FilterPersistence::new);
+        let restored = persistence
+            .as_ref()
+            .map(FilterPersistence::load)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|PersistedFilter { id, filter, block }| {
+                let filter = ActiveFilter {
+                    block,
+                    last_poll_timestamp: Instant::now(),
+                    kind: FilterKind::Log(Box::new(filter)),
+                    restored: true,
+                    // the connection that installed the filter is gone
+                    connection: None,
+                };
+                (id, filter)
+            })
+            .collect::<HashMap<_, _>>();
+        let metrics = FilterMetrics::default();
+        metrics.active_filters.set(restored.len() as f64);
+
         let inner = EthFilterInner {
             provider,
-            active_filters: Default::default(),
+            active_filters: ActiveFilters { inner: Arc::new(Mutex::new(restored)) },
             pool,
             id_provider: Arc::new(EthSubscriptionIdProvider::default()),
             eth_cache,
             max_headers_range: MAX_HEADERS_RANGE,
             task_spawner,
             stale_filter_ttl,
+            max_active_filters,
+            catch_up_blocks,
+            persistence,
+            metrics,
             // if not set, use the max value, which is effectively no limit
             max_blocks_per_filter: max_blocks_per_filter.unwrap_or(u64::MAX),
             max_logs_per_response: max_logs_per_response.unwrap_or(usize::MAX),
+            query_timeout,
         };
 
         let eth_filter = Self { inner: Arc::new(inner) };
@@ -89,6 +144,13 @@ where
             }),
         );
 
+        if eth_filter.inner.persistence.is_some() {
+            let this = eth_filter.clone();
+            eth_filter.inner.task_spawner.spawn(Box::pin(async move {
+                this.watch_and_persist_filters().await;
+            }));
+        }
+
         eth_filter
     }
 
@@ -108,19 +170,39 @@ where
         }
     }
 
+    /// Endless future that persists the changes of the log filters every
+    /// [`FILTER_PERSISTENCE_INTERVAL`], so the filters are written at most once per interval.
+    async fn watch_and_persist_filters(&self) {
+        let mut interval = tokio::time::interval(FILTER_PERSISTENCE_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.inner.persist_filters().await;
+        }
+    }
+
     /// Clears all filters that have not been polled for longer than the configured
     /// `stale_filter_ttl` at the given instant.
     pub async fn clear_stale_filters(&self, now: Instant) {
         trace!(target: "rpc::eth", "clear stale filters");
-        self.active_filters().inner.lock().await.retain(|id, filter| {
+        let mut filters = self.active_filters().inner.lock().await;
+        let mut evicted = 0;
+        filters.retain(|id, filter| {
             let is_valid = (now - filter.last_poll_timestamp) < self.inner.stale_filter_ttl;
 
             if !is_valid {
                 trace!(target: "rpc::eth", "evict filter with id: {:?}", id);
+                evicted += 1;
             }
 
             is_valid
-        })
+        });
+
+        if evicted > 0 {
+            self.inner.metrics.evicted_filters_total.increment(evicted);
+            self.inner.metrics.active_filters.set(filters.len() as f64);
+            self.inner.log_filters_changed();
+        }
     }
 }
 
@@ -140,6 +222,15 @@ where
         let (start_block, kind) = {
             let mut filters = self.inner.active_filters.inner.lock().await;
             let filter = filters.get_mut(&id).ok_or(FilterError::FilterNotFound(id))?;
+            filter.last_poll_timestamp = Instant::now();
+
+            if filter.restored {
+                // the filter was restored after a restart, the changes of the blocks since its
+                // last poll are returned, up to the catch up window
+                filter.restored = false;
+                filter.block =
+                    filter.block.max((best_number + 1).saturating_sub(self.inner.catch_up_blocks));
+            }
 
             if filter.block > best_number {
                 // no new blocks since the last poll
@@ -151,9 +242,13 @@ where
             // block to `best_block +1`, the next from which we should start fetching changes again
             let mut block = best_number + 1;
             std::mem::swap(&mut filter.block, &mut block);
-            filter.last_poll_timestamp = Instant::now();
+            let kind = filter.kind.clone();
+
+            if matches!(kind, FilterKind::Log(_)) {
+                self.inner.log_filters_changed();
+            }
 
-            (block, filter.kind.clone())
+            (block, kind)
         };
 
         match kind {
@@ -205,10 +300,11 @@ where
     /// Handler for `eth_getFilterLogs`
     pub async fn filter_logs(&self, id: FilterId) -> Result<Vec<Log>, FilterError> {
         let filter = {
-            let filters = self.inner.active_filters.inner.lock().await;
-            if let FilterKind::Log(ref filter) =
-                filters.get(&id).ok_or_else(|| FilterError::FilterNotFound(id.clone()))?.kind
-            {
+            let mut filters = self.inner.active_filters.inner.lock().await;
+            let active =
+                filters.get_mut(&id).ok_or_else(|| FilterError::FilterNotFound(id.clone()))?;
+            active.last_poll_timestamp = Instant::now();
+            if let FilterKind::Log(ref filter) = active.kind {
                 *filter.clone()
             } else {
                 // Not a log filter
@@ -286,8 +382,12 @@ where
     async fn uninstall_filter(&self, id: FilterId) -> RpcResult<bool> {
         trace!(target: "rpc::eth", "Serving eth_uninstallFilter");
         let mut filters = self.inner.active_filters.inner.lock().await;
-        if filters.remove(&id).is_some() {
+        if let Some(filter) = filters.remove(&id) {
             trace!(target: "rpc::eth::filter", ?id, "uninstalled filter");
+            self.inner.metrics.active_filters.set(filters.len() as f64);
+            if matches!(filter.kind, FilterKind::Log(_)) {
+                self.inner.log_filters_changed();
+            }
             Ok(true)
         } else {
             Ok(false)
@@ -330,6 +430,8 @@ struct EthFilterInner<Provider, Pool> {
     max_blocks_per_filter: u64,
     /// Maximum number of logs that can be returned in a response
     max_logs_per_response: usize,
+    /// Maximum time a range query may take, `None` if unlimited
+    query_timeout: Option<Duration>,
     /// The async cache frontend for eth related data
     eth_cache: EthStateCache,
     /// maximum number of headers to read at once for range filter
@@ -338,6 +440,50 @@ struct EthFilterInner<Provider, Pool> {
     task_spawner: Box<dyn TaskSpawner>,
     /// Duration since the last filter poll, after which the filter is considered stale
     stale_filter_ttl: Duration,
+    /// Maximum number of filters a connection can have installed at once, `None` if unlimited
+    max_active_filters: Option<usize>,
+    /// Maximum number of blocks the first poll of a restored log filter returns the changes of
+    catch_up_blocks: u64,
+    /// Persists the installed log filters, `None` if they aren't persisted
+    persistence: Option<FilterPersistence>,
+    /// Metrics of the installed filters
+    metrics: FilterMetrics,
+}
+
+impl<Provider, Pool> EthFilterInner<Provider, Pool> {
+    /// Records that the log filters changed, they're persisted with the next
+    /// [`Self::persist_filters`].
+    fn log_filters_changed(&self) {
+        if let Some(persistence) = &self.persistence {
+            persistence.dirty.store(true, Ordering::Release);
+        }
+    }
+
+    /// Persists the log filters if they changed since they were last persisted.
+    ///
+    /// The filters are only locked to take a snapshot of them, the file is written on a blocking
+    /// task.
+    async fn persist_filters(&self) {
+        let Some(persistence) = &self.persistence else { return };
+        let _write = persistence.write_lock.lock().await;
+        if !persistence.dirty.swap(false, Ordering::AcqRel) {
+            return
+        }
+
+        let filters = persisted_filters(&*self.active_filters.inner.lock().await);
+        let path = persistence.path.clone();
+        let (tx, rx) = oneshot::channel();
+        self.task_spawner.spawn_blocking(Box::pin(async move {
+            let _ = tx.send(write_filters(&path, &filters));
+        }));
+
+        let res = rx.await.unwrap_or_else(|_| Err(std::io::Error::other("persist task dropped")));
+        if let Err(err) = res {
+            warn!(target: "rpc::eth::filter", %err, path = ?persistence.path, "failed to persist filters");
+            // retried with the next write
+            persistence.dirty.store(true, Ordering::Release);
+        }
+    }
 }
 
 impl<Provider, Pool> EthFilterInner<Provider, Pool>
@@ -403,16 +549,32 @@ where
     /// Installs a new filter and returns the new identifier.
     async fn install_filter(&self, kind: FilterKind) -> RpcResult<FilterId> {
         let last_poll_block_number = self.provider.best_block_number().to_rpc_result()?;
-        let id = FilterId::from(self.id_provider.next_id());
         let mut filters = self.active_filters.inner.lock().await;
+        let connection = RpcConnectionId::current();
+        if let (Some(max_filters), Some(connection)) = (self.max_active_filters, connection) {
+            let installed =
+                filters.values().filter(|filter| filter.connection == Some(connection)).count();
+            if installed >= max_filters {
+                return Err(FilterError::TooManyFilters(max_filters).into())
+            }
+        }
+
+        let id = FilterId::from(self.id_provider.next_id());
+        let is_log_filter = matches!(kind, FilterKind::Log(_));
         filters.insert(
             id.clone(),
             ActiveFilter {
                 block: last_poll_block_number,
                 last_poll_timestamp: Instant::now(),
                 kind,
+                restored: false,
+                connection,
             },
         );
+        self.metrics.active_filters.set(filters.len() as f64);
+        if is_log_filter {
+            self.log_filters_changed();
+        }
         Ok(id)
     }
 
@@ -420,7 +582,8 @@ where
     ///
     /// Returns an error if:
     ///  - underlying database error
-    ///  - amount of matches exceeds configured limit
+    ///  - the range, amount of matches or duration exceeds the configured limits, the error
+    ///    suggests a smaller range that starts at `from_block`
     async fn get_logs_in_block_range(
         &self,
         filter: &Filter,
@@ -436,8 +599,16 @@ where
         }
 
         if to_block - from_block > self.max_blocks_per_filter {
-            return Err(FilterError::QueryExceedsMaxBlocks(self.max_blocks_per_filter))
+            return Err(FilterError::QueryExceedsMaxBlocks {
+                max: self.max_blocks_per_filter,
+                range: SuggestedBlockRange::new(
+                    from_block,
+                    from_block + self.max_blocks_per_filter,
+                ),
+            })
         }
+        let deadline = self.query_timeout.map(|timeout| Instant::now() + timeout);
+        let is_past_deadline = || deadline.is_some_and(|deadline| Instant::now() >= deadline);
 
         let mut all_logs = Vec::new();
         let filter_params = FilteredParams::new(Some(filter.clone()));
@@ -466,50 +637,75 @@ where
         // derive bloom filters from filter input, so we can check headers for matching logs
         let address_filter = FilteredParams::address_filter(&filter.address);
         let topics_filter = FilteredParams::topics_filter(&filter.topics);
+        // without addresses and topics every block is a candidate, so the blooms are not checked
+        let has_bloom_filter =
+            !filter.address.is_empty() || filter.topics.iter().any(|topic| !topic.is_empty());
+        let is_multi_block_range = from_block != to_block;
 
         // loop over the range of new blocks and check logs if the filter matches the log's bloom
         // filter
         for (from, to) in
             BlockRangeInclusiveIter::new(from_block..=to_block, self.max_headers_range)
         {
-            let headers = self.provider.headers_range(from..=to)?;
-
-            for (idx, header) in headers.iter().enumerate() {
-                // only if filter matches
-                if FilteredParams::matches_address(header.logs_bloom, &address_filter) &&
-                    FilteredParams::matches_topics(header.logs_bloom, &topics_filter)
-                {
-                    // these are consecutive headers, so we can use the parent hash of the next
-                    // block to get the current header's hash
-                    let block_hash = match headers.get(idx + 1) {
-                        Some(parent) => parent.parent_hash,
-                        None => self
-                            .provider
-                            .block_hash(header.number)?
-                            .ok_or(ProviderError::HeaderNotFound(header.number.into()))?,
-                    };
-
-                    if let Some(receipts) = self.eth_cache.get_receipts(block_hash).await? {
-                        append_matching_block_logs(
-                            &mut all_logs,
-                            &self.provider,
-                            &filter_params,
-                            BlockNumHash::new(header.number, block_hash),
-                            &receipts,
-                            false,
-                            header.timestamp,
-                        )?;
-
-                        // size check but only if range is multiple blocks, so we always return all
-                        // logs of a single block
-                        let is_multi_block_range = from_block != to_block;
-                        if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
-                            return Err(FilterError::QueryExceedsMaxResults(
-                                self.max_logs_per_response,
-                            ))
-                        }
+            // the logs blooms of the chunk's headers are scanned first, the sealed headers are
+            // served by the static files together with their hashes
+            let candidates = self
+                .provider
+                .sealed_headers_range(from..=to)?
+                .into_iter()
+                .filter(|header| {
+                    !has_bloom_filter ||
+                        (FilteredParams::matches_address(header.logs_bloom, &address_filter) &&
+                            FilteredParams::matches_topics(header.logs_bloom, &topics_filter))
+                })
+                .collect::<Vec<_>>();
+
+            // the receipts are only loaded for the candidates, false positives of the blooms are
+            // filtered out by the exact match of the logs
+            let receipts = try_join_all(
+                candidates.iter().map(|header| self.eth_cache.get_receipts(header.hash())),
+            )
+            .await?;
+
+            for (header, receipts) in candidates.iter().zip(receipts) {
+                if let Some(receipts) = receipts {
+                    append_matching_block_logs(
+                        &mut all_logs,
+                        &self.provider,
+                        &filter_params,
+                        BlockNumHash::new(header.number, header.hash()),
+                        &receipts,
+                        false,
+                        header.timestamp,
+                    )?;
+
+                    // size check but only if range is multiple blocks, so we always return all
+                    // logs of a single block
+                    if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
+                        // the logs of the previous blocks are within the limit, if this is the
+                        // first block its logs are returned for the single block range
+                        let to =
+                            if header.number > from_block { header.number - 1 } else { from_block };
+                        return Err(FilterError::QueryExceedsMaxResults {
+                            max: self.max_logs_per_response,
+                            range: SuggestedBlockRange::new(from_block, to),
+                        })
                     }
                 }
+
+                if header.number < to_block && is_past_deadline() {
+                    return Err(FilterError::QueryTimeout {
+                        timeout: self.query_timeout.unwrap_or_default(),
+                        range: SuggestedBlockRange::new(from_block, header.number),
+                    })
+                }
+            }
+
+            if to < to_block && is_past_deadline() {
+                return Err(FilterError::QueryTimeout {
+                    timeout: self.query_timeout.unwrap_or_default(),
+                    range: SuggestedBlockRange::new(from_block, to),
+                })
             }
         }
 
@@ -528,11 +724,30 @@ pub struct EthFilterConfig {
     ///
     /// If `None` then no limit is enforced.
     pub max_logs_per_response: Option<usize>,
+    /// Maximum time that a single log query can take.
+    ///
+    /// If `None` then no limit is enforced.
+    pub query_timeout: Option<Duration>,
     /// How long a filter remains valid after the last poll.
     ///
     /// A filter is considered stale if it has not been polled for longer than this duration and
     /// will be removed.
     pub stale_filter_ttl: Duration,
+    /// Maximum number of filters a single connection can have installed at once, see
+    /// [`RpcConnectionId`]. Filters installed by calls that weren't received on a connection
+    /// aren't limited.
+    ///
+    /// If `None` then no limit is enforced.
+    pub max_active_filters: Option<usize>,
+    /// The file the installed log filters are persisted to, so they're restored after a restart.
+    /// Changes of the filters are written at most once per second.
+    ///
+    /// If `None` then filters are not persisted.
+    pub persistence_path: Option<PathBuf>,
+    /// Maximum number of blocks the first poll of a restored log filter returns the changes of.
+    ///
+    /// This bounds the work of the first poll if the node was down for a long time.
+    pub catch_up_blocks: u64,
 }
 
 impl EthFilterConfig {
@@ -549,11 +764,36 @@ impl EthFilterConfig {
         self
     }
 
+    /// Sets the maximum time that a single log query can take.
+    pub const fn query_timeout(mut self, timeout: Duration) -> Self {
+        self.query_timeout = Some(timeout);
+        self
+    }
+
     /// Sets how long a filter remains valid after the last poll before it will be removed.
     pub const fn stale_filter_ttl(mut self, duration: Duration) -> Self {
         self.stale_filter_ttl = duration;
         self
     }
+
+    /// Sets the maximum number of filters a single connection can have installed at once.
+    pub const fn max_active_filters(mut self, num: usize) -> Self {
+        self.max_active_filters = Some(num);
+        self
+    }
+
+    /// Sets the file the installed log filters are persisted to.
+    pub fn persistence_path(mut self, path: PathBuf) -> Self {
+        self.persistence_path = Some(path);
+        self
+    }
+
+    /// Sets the maximum number of blocks the first poll of a restored log filter returns the
+    /// changes of.
+    pub const fn catch_up_blocks(mut self, num: u64) -> Self {
+        self.catch_up_blocks = num;
+        self
+    }
 }
 
 impl Default for EthFilterConfig {
@@ -561,8 +801,11 @@ impl Default for EthFilterConfig {
         Self {
             max_blocks_per_filter: None,
             max_logs_per_response: None,
-            // 5min
-            stale_filter_ttl: Duration::from_secs(5 * 60),
+            query_timeout: None,
+            stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
+            max_active_filters: None,
+            persistence_path: None,
+            catch_up_blocks: DEFAULT_FILTER_CATCH_UP_BLOCKS,
         }
     }
 }
@@ -582,6 +825,100 @@ struct ActiveFilter {
     last_poll_timestamp: Instant,
     /// What kind of filter it is.
     kind: FilterKind,
+    /// Whether the filter was restored after a restart and wasn't polled since.
+    restored: bool,
+    /// The connection that installed the filter, `None` if it wasn't installed by a call
+    /// received on a connection.
+    connection: Option<RpcConnectionId>,
+}
+
+/// A log filter as it's persisted.
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedFilter {
+    id: FilterId,
+    filter: Filter,
+    /// The block the next poll of the filter starts at.
+    block: u64,
+}
+
+/// Persists the installed log filters to a file, so they're restored after a restart.
+///
+/// Block and pending transaction filters are not persisted.
+#[derive(Debug)]
+struct FilterPersistence {
+    path: PathBuf,
+    /// Whether the log filters changed since they were last persisted.
+    dirty: AtomicBool,
+    /// Serializes the writes of the file.
+    write_lock: Mutex<()>,
+}
+
+impl FilterPersistence {
+    /// Persists the filters to the given file.
+    fn new(path: PathBuf) -> Self {
+        Self { path, dirty: AtomicBool::new(false), write_lock: Mutex::new(()) }
+    }
+
+    /// Loads the persisted filters, none if the file doesn't exist or is invalid.
+    fn load(&self) -> Vec<PersistedFilter> {
+        let data = match std::fs::read(&self.path) {
+            Ok(data) => data,
+            Err(err) => {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    warn!(
+                        target: "rpc::eth::filter",
+                        %err,
+                        path = ?self.path,
+                        "failed to read persisted filters"
+                    );
+                }
+                return Vec::new()
+            }
+        };
+        serde_json::from_slice(&data).unwrap_or_else(|err| {
+            warn!(
+                target: "rpc::eth::filter",
+                %err,
+                path = ?self.path,
+                "failed to decode persisted filters"
+            );
+            Vec::new()
+        })
+    }
+}
+
+/// Returns the log filters among the given filters as they're persisted.
+fn persisted_filters(filters: &HashMap<FilterId, ActiveFilter>) -> Vec<PersistedFilter> {
+    filters
+        .iter()
+        .filter_map(|(id, filter)| match &filter.kind {
+            FilterKind::Log(log_filter) => Some(PersistedFilter {
+                id: id.clone(),
+                filter: (**log_filter).clone(),
+                block: filter.block,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Replaces the persisted filters in the given file.
+fn write_filters(path: &Path, filters: &[PersistedFilter]) -> std::io::Result<()> {
+    // the filters are written to a temporary file first, so a crash never leaves a partially
+    // written file behind
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, serde_json::to_vec(filters)?)?;
+    std::fs::rename(tmp_path, path)
+}
+
+/// Metrics of the installed filters.
+#[derive(Metrics)]
+#[metrics(scope = "rpc.eth_filter")]
+struct FilterMetrics {
+    /// The number of installed filters.
+    active_filters: Gauge,
+    /// The number of filters evicted because they weren't polled within the TTL.
+    evicted_filters_total: Counter,
 }
 
 /// A receiver for pending transactions that returns all new transactions since the last poll.
@@ -688,10 +1025,14 @@ pub enum FilterError {
     FilterNotFound(FilterId),
     #[error("invalid block range params")]
     InvalidBlockRangeParams,
-    #[error("query exceeds max block range {0}")]
-    QueryExceedsMaxBlocks(u64),
-    #[error("query exceeds max results {0}")]
-    QueryExceedsMaxResults(usize),
+    #[error("too many installed filters, a connection can install at most {0} filters")]
+    TooManyFilters(usize),
+    #[error("query exceeds max block range {max}, retry with the range in the error data")]
+    QueryExceedsMaxBlocks { max: u64, range: SuggestedBlockRange },
+    #[error("query exceeds max results {max}, retry with the range in the error data")]
+    QueryExceedsMaxResults { max: usize, range: SuggestedBlockRange },
+    #[error("query exceeds timeout {timeout:?}, retry with the range in the error data")]
+    QueryTimeout { timeout: Duration, range: SuggestedBlockRange },
     #[error(transparent)]
     EthAPIError(#[from] EthApiError),
     /// Error thrown when a spawned task failed to deliver a response.
@@ -711,15 +1052,40 @@ impl From<FilterError> for jsonrpsee::types::error::ErrorObject<'static> {
                 rpc_error_with_code(jsonrpsee::types::error::INTERNAL_ERROR_CODE, err.to_string())
             }
             FilterError::EthAPIError(err) => err.into(),
-            err @ FilterError::InvalidBlockRangeParams |
-            err @ FilterError::QueryExceedsMaxBlocks(_) |
-            err @ FilterError::QueryExceedsMaxResults(_) => {
+            err @ FilterError::InvalidBlockRangeParams => {
                 rpc_error_with_code(jsonrpsee::types::error::INVALID_PARAMS_CODE, err.to_string())
             }
+            err @ FilterError::TooManyFilters(_) => {
+                rpc_error_with_code(QUERY_LIMIT_EXCEEDED_CODE, err.to_string())
+            }
+            FilterError::QueryExceedsMaxBlocks { range, .. } |
+            FilterError::QueryExceedsMaxResults { range, .. } |
+            FilterError::QueryTimeout { range, .. } => {
+                Self::owned(QUERY_LIMIT_EXCEEDED_CODE, err.to_string(), Some(range))
+            }
         }
     }
 }
 
+/// The block range that a log query which exceeds a limit can be retried with.
+///
+/// This is the data of the error, which is the same format other providers use, so clients can
+/// split the query: `{"from": "0x0", "to": "0x64"}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuggestedBlockRange {
+    /// The first block of the range.
+    pub from: U64,
+    /// The last block of the range, inclusive.
+    pub to: U64,
+}
+
+impl SuggestedBlockRange {
+    /// Creates a new _inclusive_ range.
+    pub fn new(from: u64, to: u64) -> Self {
+        Self { from: U64::from(from), to: U64::from(to) }
+    }
+}
+
 impl From<ProviderError> for FilterError {
     fn from(err: ProviderError) -> Self {
         Self::EthAPIError(err.into())
@@ -757,6 +1123,14 @@ impl Iterator for BlockRangeInclusiveIter {
 mod tests {
     use super::*;
     use rand::{thread_rng, Rng};
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{
+        logs_bloom, Address, Block, Bloom, Bytes, Header, Log as PrimitiveLog, Receipt,
+        TransactionSigned, B256,
+    };
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_tasks::TokioTaskExecutor;
+    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
 
     #[test]
     fn test_block_range_iter() {
@@ -780,4 +1154,249 @@ mod tests {
             assert_eq!(end, *range.end());
         }
     }
+
+    #[tokio::test]
+    async fn logs_in_range_skip_bloom_false_positives() {
+        let provider = MockEthProvider::default();
+        let address = Address::with_last_byte(1);
+        let topic = B256::with_last_byte(1);
+        let matching = PrimitiveLog::new_unchecked(address, vec![topic], Bytes::new());
+        let other =
+            PrimitiveLog::new_unchecked(address, vec![B256::with_last_byte(2)], Bytes::new());
+
+        // the bloom of the second block matches every filter, but none of its logs
+        let blocks = [
+            (matching.clone(), None),
+            (other.clone(), Some(Bloom::repeat_byte(0xff))),
+            (other, None),
+        ];
+        let mut parent_hash = B256::ZERO;
+        for (number, (log, bloom)) in blocks.into_iter().enumerate() {
+            let receipt = Receipt { success: true, logs: vec![log], ..Default::default() };
+            let header = Header {
+                number: number as u64,
+                parent_hash,
+                logs_bloom: bloom.unwrap_or_else(|| logs_bloom(&receipt.logs)),
+                ..Default::default()
+            }
+            .seal_slow();
+            parent_hash = header.hash();
+            let block = Block {
+                header: header.clone().unseal(),
+                body: vec![TransactionSigned::default()],
+                ..Default::default()
+            };
+            provider.add_block(header.hash(), block);
+            provider.add_receipts(header.hash(), vec![receipt]);
+        }
+
+        let cache =
+            EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+        let eth_filter = EthFilter::new(
+            provider,
+            testing_pool(),
+            cache,
+            EthFilterConfig::default(),
+            Box::new(TokioTaskExecutor::default()),
+        );
+
+        let filter =
+            Filter::new().from_block(0).to_block(2).address(address).event_signature(topic);
+        let logs = EthFilterApiServer::logs(&eth_filter, filter).await.unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].inner, matching);
+        assert_eq!(logs[0].block_number, Some(0));
+
+        // without addresses and topics, the logs of every block match
+        let filter = Filter::new().from_block(0).to_block(2);
+        let logs = EthFilterApiServer::logs(&eth_filter, filter).await.unwrap();
+        assert_eq!(logs.len(), 3);
+    }
+
+    /// Returns a filter for a chain of `num_blocks` blocks with `logs_per_block` logs each.
+    fn filter_with_logs(
+        num_blocks: u64,
+        logs_per_block: usize,
+        config: EthFilterConfig,
+    ) -> EthFilter<MockEthProvider, TestPool> {
+        let provider = MockEthProvider::default();
+        let mut parent_hash = B256::ZERO;
+        for number in 0..num_blocks {
+            let log = PrimitiveLog::new_unchecked(Address::with_last_byte(1), vec![], Bytes::new());
+            let receipt =
+                Receipt { success: true, logs: vec![log; logs_per_block], ..Default::default() };
+            let header = Header {
+                number,
+                parent_hash,
+                logs_bloom: logs_bloom(&receipt.logs),
+                ..Default::default()
+            }
+            .seal_slow();
+            parent_hash = header.hash();
+            let block = Block {
+                header: header.clone().unseal(),
+                body: vec![TransactionSigned::default()],
+                ..Default::default()
+            };
+            provider.add_block(header.hash(), block);
+            provider.add_receipts(header.hash(), vec![receipt]);
+        }
+
+        let cache =
+            EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+        EthFilter::new(
+            provider,
+            testing_pool(),
+            cache,
+            config,
+            Box::new(TokioTaskExecutor::default()),
+        )
+    }
+
+    /// Fetches the logs of `from..=to` like a client that retries with the suggested range of
+    /// the errors and continues after it, returns the logs and the number of queries.
+    async fn paginate_logs(
+        eth_filter: &EthFilter<MockEthProvider, TestPool>,
+        from: u64,
+        to: u64,
+    ) -> (Vec<Log>, usize) {
+        let mut logs = Vec::new();
+        let mut range = (from, to);
+        for queries in 1..=100 {
+            let filter = Filter::new().from_block(range.0).to_block(range.1);
+            match EthFilterApiServer::logs(eth_filter, filter).await {
+                Ok(page) => {
+                    logs.extend(page);
+                    if range.1 == to {
+                        return (logs, queries)
+                    }
+                    range = (range.1 + 1, to);
+                }
+                Err(err) => {
+                    assert_eq!(err.code(), QUERY_LIMIT_EXCEEDED_CODE);
+                    let suggested: SuggestedBlockRange =
+                        serde_json::from_str(err.data().unwrap().get()).unwrap();
+                    assert_eq!(suggested.from.to::<u64>(), range.0);
+                    assert!(suggested.to.to::<u64>() < range.1);
+                    range = (range.0, suggested.to.to());
+                }
+            }
+        }
+        panic!("pagination did not complete")
+    }
+
+    #[tokio::test]
+    async fn suggested_range_paginates_to_completion() {
+        let config = EthFilterConfig::default().max_blocks_per_filter(3).max_logs_per_response(5);
+        let eth_filter = filter_with_logs(10, 2, config);
+
+        let (logs, queries) = paginate_logs(&eth_filter, 0, 9).await;
+        assert_eq!(logs.len(), 20);
+        assert!(queries > 1);
+        for (idx, log) in logs.iter().enumerate() {
+            assert_eq!(log.block_number, Some(idx as u64 / 2));
+        }
+
+        // the logs of a single block are always returned
+        let eth_filter =
+            filter_with_logs(4, 3, EthFilterConfig::default().max_logs_per_response(2));
+        let (logs, _) = paginate_logs(&eth_filter, 0, 3).await;
+        assert_eq!(logs.len(), 12);
+    }
+
+    #[tokio::test]
+    async fn timeout_suggests_processed_range() {
+        // every query times out after its first block
+        let config = EthFilterConfig::default().query_timeout(Duration::ZERO);
+        let eth_filter = filter_with_logs(5, 1, config);
+
+        let (logs, queries) = paginate_logs(&eth_filter, 0, 4).await;
+        assert_eq!(logs.len(), 5);
+        // a failed and a successful query for all but the last block
+        assert_eq!(queries, 9);
+    }
+
+    #[tokio::test]
+    async fn filter_logs_enforces_limits() {
+        let config = EthFilterConfig::default().max_blocks_per_filter(1);
+        let eth_filter = filter_with_logs(3, 1, config);
+
+        let id =
+            EthFilterApiServer::new_filter(&eth_filter, Filter::new().from_block(0).to_block(2))
+                .await
+                .unwrap();
+        let err = EthFilterApiServer::filter_logs(&eth_filter, id).await.unwrap_err();
+        assert_eq!(err.code(), QUERY_LIMIT_EXCEEDED_CODE);
+        let suggested: SuggestedBlockRange =
+            serde_json::from_str(err.data().unwrap().get()).unwrap();
+        assert_eq!(suggested, SuggestedBlockRange::new(0, 1));
+    }
+
+    #[tokio::test]
+    async fn install_filter_enforces_max_active_filters() {
+        let config = EthFilterConfig::default().max_active_filters(2);
+        let eth_filter = filter_with_logs(1, 1, config);
+        let connection = RpcConnectionId::next();
+
+        let id = connection.scope(EthFilterApiServer::new_block_filter(&eth_filter)).await.unwrap();
+        connection.scope(EthFilterApiServer::new_filter(&eth_filter, Filter::new())).await.unwrap();
+        let err =
+            connection.scope(EthFilterApiServer::new_block_filter(&eth_filter)).await.unwrap_err();
+        assert_eq!(err.code(), QUERY_LIMIT_EXCEEDED_CODE);
+        assert_eq!(err.message(), FilterError::TooManyFilters(2).to_string());
+
+        // the filters of one connection don't count against other connections
+        let other = RpcConnectionId::next();
+        other.scope(EthFilterApiServer::new_block_filter(&eth_filter)).await.unwrap();
+
+        // uninstalling a filter makes room for a new one
+        assert!(EthFilterApiServer::uninstall_filter(&eth_filter, id).await.unwrap());
+        connection.scope(EthFilterApiServer::new_block_filter(&eth_filter)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn persisted_log_filters_are_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = EthFilterConfig::default()
+            .persistence_path(dir.path().join("filters.json"))
+            .catch_up_blocks(3);
+
+        // the filters are installed at block 9
+        let eth_filter = filter_with_logs(10, 1, config.clone());
+        let log_filter = EthFilterApiServer::new_filter(&eth_filter, Filter::new()).await.unwrap();
+        let block_filter = EthFilterApiServer::new_block_filter(&eth_filter).await.unwrap();
+        eth_filter.inner.persist_filters().await;
+
+        // the node restarts at block 19, the first poll returns the logs of the last 3 blocks
+        let eth_filter = filter_with_logs(20, 1, config.clone());
+        assert!(eth_filter.filter_changes(block_filter).await.is_err());
+        let FilterChanges::Logs(logs) =
+            eth_filter.filter_changes(log_filter.clone()).await.unwrap()
+        else {
+            panic!("expected logs")
+        };
+        assert_eq!(
+            logs.iter().map(|log| log.block_number.unwrap()).collect::<Vec<_>>(),
+            [17, 18, 19]
+        );
+        eth_filter.inner.persist_filters().await;
+
+        // the polled block is persisted, nothing changed since the last poll
+        let eth_filter = filter_with_logs(20, 1, config.clone());
+        assert!(matches!(
+            eth_filter.filter_changes(log_filter).await.unwrap(),
+            FilterChanges::Empty
+        ));
+
+        // polls without changes don't write the filters
+        let persistence = eth_filter.inner.persistence.as_ref().unwrap();
+        assert!(!persistence.dirty.load(Ordering::Acquire));
+
+        // evicted filters are removed from the persisted filters
+        eth_filter.clear_stale_filters(Instant::now() + config.stale_filter_ttl).await;
+        assert!(eth_filter.active_filters().inner.lock().await.is_empty());
+        eth_filter.inner.persist_filters().await;
+        let eth_filter = filter_with_logs(20, 1, config);
+        assert!(eth_filter.active_filters().inner.lock().await.is_empty());
+    }
 }
@@ -2,16 +2,13 @@
 //! previous blocks.
 
 use crate::eth::{
-    cache::EthStateCache,
-    error::{EthApiError, EthResult, RpcInvalidTransactionError},
+    api::fee_history::{FeeHistoryCache, FeeHistoryEntry},
+    error::{EthApiError, EthResult},
 };
-use derive_more::{Deref, DerefMut};
 use reth_primitives::{constants::GWEI_TO_WEI, BlockNumberOrTag, B256, U256};
 use reth_provider::BlockReaderIdExt;
 use reth_rpc_server_types::constants::gas_oracle::*;
-use schnellru::{ByLength, LruMap};
 use serde::{Deserialize, Serialize};
-use std::fmt::{self, Debug, Formatter};
 use tokio::sync::Mutex;
 use tracing::warn;
 
@@ -22,7 +19,7 @@ pub struct GasPriceOracleConfig {
     /// The number of populated blocks to produce the gas price estimate
     pub blocks: u32,
 
-    /// The percentile of gas prices to use for the estimate
+    /// The percentile of the rewards of a block to use for the estimate
     pub percentile: u32,
 
     /// The maximum number of headers to keep in the cache
@@ -31,12 +28,15 @@ pub struct GasPriceOracleConfig {
     /// The maximum number of blocks for estimating gas price
     pub max_block_history: u64,
 
-    /// The default gas price to use if there are no blocks to use
+    /// The default gas price to use if there are not enough blocks to use
     pub default: Option<U256>,
 
     /// The maximum gas price to use for the estimate
     pub max_price: Option<U256>,
 
+    /// The minimum gas price to use for the estimate
+    pub min_price: Option<U256>,
+
     /// The minimum gas price, under which the sample will be ignored
     pub ignore_price: Option<U256>,
 }
@@ -50,25 +50,28 @@ impl Default for GasPriceOracleConfig {
             max_block_history: MAX_HEADER_HISTORY,
             default: None,
             max_price: Some(DEFAULT_MAX_GAS_PRICE),
+            min_price: None,
             ignore_price: Some(DEFAULT_IGNORE_GAS_PRICE),
         }
     }
 }
 
 /// Calculates a gas price depending on recent blocks.
+///
+/// The suggested priority fee is the average of the configured reward percentile of the most
+/// recent non-empty blocks, taken from the [`FeeHistoryCache`].
 #[derive(Debug)]
 pub struct GasPriceOracle<Provider> {
     /// The type used to subscribe to block events and get block info
     provider: Provider,
-    /// The cache for blocks
-    cache: EthStateCache,
+    /// The cache for the rewards of the blocks
+    fee_history_cache: FeeHistoryCache,
     /// The config for the oracle
     oracle_config: GasPriceOracleConfig,
     /// The price under which the sample will be ignored.
     ignore_price: Option<u128>,
-    /// Stores the latest calculated price and its block hash and Cache stores the lowest effective
-    /// tip values of recent blocks
-    inner: Mutex<GasPriceOracleInner>,
+    /// Stores the latest calculated price and its block hash
+    last_price: Mutex<GasPriceOracleResult>,
 }
 
 impl<Provider> GasPriceOracle<Provider>
@@ -79,7 +82,7 @@ where
     pub fn new(
         provider: Provider,
         mut oracle_config: GasPriceOracleConfig,
-        cache: EthStateCache,
+        fee_history_cache: FeeHistoryCache,
     ) -> Self {
         // sanitize the percentile to be less than 100
         if oracle_config.percentile > 100 {
@@ -88,16 +91,13 @@ where
         }
         let ignore_price = oracle_config.ignore_price.map(|price| price.saturating_to());
 
-        // this is the number of blocks that we will cache the values for
-        let cached_values = (oracle_config.blocks * 5).max(oracle_config.max_block_history as u32);
-        let inner = Mutex::new(GasPriceOracleInner {
+        Self {
+            provider,
+            oracle_config,
+            fee_history_cache,
+            ignore_price,
             last_price: Default::default(),
-            lowest_effective_tip_cache: EffectiveTipLruCache(LruMap::new(ByLength::new(
-                cached_values,
-            ))),
-        });
-
-        Self { provider, oracle_config, cache, ignore_price, inner }
+        }
     }
 
     /// Returns the configuration of the gas price oracle.
@@ -105,169 +105,74 @@ where
         &self.oracle_config
     }
 
-    /// Suggests a gas price estimate based on recent blocks, using the configured percentile.
+    /// Suggests a priority fee based on recent blocks, using the configured reward percentile.
     pub async fn suggest_tip_cap(&self) -> EthResult<U256> {
         let header = self
             .provider
             .sealed_header_by_number_or_tag(BlockNumberOrTag::Latest)?
             .ok_or(EthApiError::UnknownBlockNumber)?;
 
-        let mut inner = self.inner.lock().await;
+        let mut last_price = self.last_price.lock().await;
 
         // if we have stored a last price, then we check whether or not it was for the same head
-        if inner.last_price.block_hash == header.hash() {
-            return Ok(inner.last_price.price)
+        if last_price.block_hash == header.hash() {
+            return Ok(last_price.price)
         }
 
-        // if all responses are empty, then we can return a maximum of 2*check_block blocks' worth
-        // of prices
-        //
-        // we only return more than check_block blocks' worth of prices if one or more return empty
-        // transactions
-        let mut current_hash = header.hash();
-        let mut results = Vec::new();
-        let mut populated_blocks = 0;
-
-        // we only check a maximum of 2 * max_block_history, or the number of blocks in the chain
-        let max_blocks = if self.oracle_config.max_block_history * 2 > header.number {
-            header.number
-        } else {
-            self.oracle_config.max_block_history * 2
-        };
-
-        for _ in 0..max_blocks {
-            // Check if current hash is in cache
-            let (parent_hash, block_values) =
-                if let Some(vals) = inner.lowest_effective_tip_cache.get(&current_hash) {
-                    vals.to_owned()
-                } else {
-                    // Otherwise we fetch it using get_block_values
-                    let (parent_hash, block_values) = self
-                        .get_block_values(current_hash, SAMPLE_NUMBER)
-                        .await?
-                        .ok_or(EthApiError::UnknownBlockNumber)?;
-                    inner
-                        .lowest_effective_tip_cache
-                        .insert(current_hash, (parent_hash, block_values.clone()));
-                    (parent_hash, block_values)
-                };
-
-            if block_values.is_empty() {
-                results.push(U256::from(inner.last_price.price));
-            } else {
-                results.extend(block_values);
-                populated_blocks += 1;
-            }
-
-            // break when we have enough populated blocks
-            if populated_blocks >= self.oracle_config.blocks {
-                break
-            }
-
-            current_hash = parent_hash;
-        }
-
-        // sort results then take the configured percentile result
-        let mut price = if !results.is_empty() {
-            results.sort_unstable();
-            *results.get((results.len() - 1) * self.oracle_config.percentile as usize / 100).expect(
-                "gas price index is a percent of nonzero array length, so a value always exists",
-            )
-        } else {
-            inner.last_price.price
-        };
-
-        // constrain to the max price
-        if let Some(max_price) = self.oracle_config.max_price {
-            if price > max_price {
-                price = max_price;
-            }
-        }
+        // look back at most twice the number of blocks, skipping the empty blocks
+        let lookback = (self.oracle_config.blocks as u64 * 2)
+            .min(self.oracle_config.max_block_history)
+            .min(header.number + 1);
+        let headers = self
+            .provider
+            .sealed_headers_range(header.number + 1 - lookback..=header.number)?
+            .into_iter()
+            .rev()
+            .filter(|header| header.gas_used > 0)
+            .take(self.oracle_config.blocks as usize)
+            .collect::<Vec<_>>();
+        let entries = self.fee_history_cache.get_history(&headers).await?;
 
-        inner.last_price = GasPriceOracleResult { block_hash: header.hash(), price };
+        let price = self.suggest_from_entries(&entries);
+        *last_price = GasPriceOracleResult { block_hash: header.hash(), price };
 
         Ok(price)
     }
 
-    /// Get the `limit` lowest effective tip values for the given block. If the oracle has a
-    /// configured `ignore_price` threshold, then tip values under that threshold will be ignored
-    /// before returning a result.
-    ///
-    /// If the block cannot be found, then this will return `None`.
+    /// Computes the suggested priority fee from the fee history entries of recent non-empty
+    /// blocks.
     ///
-    /// This method also returns the parent hash for the given block.
-    async fn get_block_values(
-        &self,
-        block_hash: B256,
-        limit: usize,
-    ) -> EthResult<Option<(B256, Vec<U256>)>> {
-        // check the cache (this will hit the disk if the block is not cached)
-        let mut block = match self.cache.get_block(block_hash).await? {
-            Some(block) => block,
-            None => return Ok(None),
+    /// Transactions sent by the beneficiary of a block are ignored. Blocks whose rewards are not
+    /// available, or whose reward at the configured percentile is under the `ignore_price`
+    /// threshold, are skipped. If fewer than [`MIN_GAS_PRICE_BLOCKS`]
+    /// blocks remain, the configured default is used instead.
+    fn suggest_from_entries(&self, entries: &[FeeHistoryEntry]) -> U256 {
+        let percentile = [self.oracle_config.percentile as f64];
+        let rewards = entries
+            .iter()
+            .filter_map(|entry| entry.rewards_excluding_beneficiary(&percentile))
+            .map(|rewards| rewards[0])
+            .filter(|reward| self.ignore_price.map_or(true, |ignore_under| *reward >= ignore_under))
+            .collect::<Vec<_>>();
+
+        let min_blocks = MIN_GAS_PRICE_BLOCKS.min(self.oracle_config.blocks).max(1) as usize;
+        let mut price = if rewards.len() < min_blocks {
+            self.oracle_config.default.unwrap_or_else(|| GasPriceOracleResult::default().price)
+        } else {
+            let total =
+                rewards.iter().fold(U256::ZERO, |total, reward| total + U256::from(*reward));
+            total / U256::from(rewards.len())
         };
 
-        let base_fee_per_gas = block.base_fee_per_gas;
-        let parent_hash = block.parent_hash;
-
-        // sort the functions by ascending effective tip first
-        block.body.sort_by_cached_key(|tx| tx.effective_tip_per_gas(base_fee_per_gas));
-
-        let mut prices = Vec::with_capacity(limit);
-
-        for tx in &block.body {
-            let mut effective_gas_tip = None;
-            // ignore transactions with a tip under the configured threshold
-            if let Some(ignore_under) = self.ignore_price {
-                let tip = tx.effective_tip_per_gas(base_fee_per_gas);
-                effective_gas_tip = Some(tip);
-                if tip < Some(ignore_under) {
-                    continue
-                }
-            }
-
-            // check if the sender was the coinbase, if so, ignore
-            if let Some(sender) = tx.recover_signer() {
-                if sender == block.beneficiary {
-                    continue
-                }
-            }
-
-            // a `None` effective_gas_tip represents a transaction where the max_fee_per_gas is
-            // less than the base fee which would be invalid
-            let effective_gas_tip = effective_gas_tip
-                .unwrap_or_else(|| tx.effective_tip_per_gas(base_fee_per_gas))
-                .ok_or(RpcInvalidTransactionError::FeeCapTooLow)?;
-
-            prices.push(U256::from(effective_gas_tip));
-
-            // we have enough entries
-            if prices.len() >= limit {
-                break
-            }
+        // constrain to the min and max price
+        if let Some(min_price) = self.oracle_config.min_price {
+            price = price.max(min_price);
+        }
+        if let Some(max_price) = self.oracle_config.max_price {
+            price = price.min(max_price);
         }
 
-        Ok(Some((parent_hash, prices)))
-    }
-}
-
-/// Container type for mutable inner state of the [`GasPriceOracle`]
-#[derive(Debug)]
-struct GasPriceOracleInner {
-    last_price: GasPriceOracleResult,
-    lowest_effective_tip_cache: EffectiveTipLruCache,
-}
-
-/// Wrapper struct for `LruMap`
-#[derive(Deref, DerefMut)]
-pub struct EffectiveTipLruCache(LruMap<B256, (B256, Vec<U256>), ByLength>);
-
-impl Debug for EffectiveTipLruCache {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.debug_struct("EffectiveTipLruCache")
-            .field("cache_length", &self.len())
-            .field("cache_memory_usage", &self.memory_usage())
-            .finish()
+        price
     }
 }
 
@@ -289,6 +194,12 @@ impl Default for GasPriceOracleResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::eth::{cache::EthStateCache, FeeHistoryCacheConfig};
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::SealedHeader;
+    use reth_provider::test_utils::NoopProvider;
+    use reth_rpc_types::TxGasAndReward;
+    use std::sync::Arc;
 
     #[test]
     fn max_price_sanity() {
@@ -300,4 +211,100 @@ mod tests {
     fn ignore_price_sanity() {
         assert_eq!(DEFAULT_IGNORE_GAS_PRICE, U256::from(2u64));
     }
+
+    fn test_oracle(config: GasPriceOracleConfig) -> GasPriceOracle<NoopProvider> {
+        let cache = EthStateCache::spawn(
+            NoopProvider::default(),
+            Default::default(),
+            EthEvmConfig::default(),
+        );
+        let fee_history_cache = FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default());
+        GasPriceOracle::new(NoopProvider::default(), config, fee_history_cache)
+    }
+
+    /// Returns an entry of a block with transactions of equal gas paying the given rewards.
+    fn entry(rewards: impl IntoIterator<Item = u128>) -> FeeHistoryEntry {
+        let mut entry = FeeHistoryEntry::new(&SealedHeader::default());
+        let mut transactions = rewards
+            .into_iter()
+            .map(|reward| TxGasAndReward { gas_used: 21_000, reward })
+            .collect::<Vec<_>>();
+        transactions.sort_by_key(|tx| tx.reward);
+        entry.gas_used = transactions.iter().map(|tx| tx.gas_used).sum();
+        entry.transaction_rewards = Some(Arc::new(transactions));
+        entry
+    }
+
+    /// Returns the entries of `count` blocks, where block `k` pays `k + 1` to `k + 10` gwei.
+    fn entries(count: u128) -> Vec<FeeHistoryEntry> {
+        let gwei = GWEI_TO_WEI as u128;
+        (0..count).map(|k| entry((1..=10).map(|tip| (tip + k) * gwei))).collect()
+    }
+
+    #[tokio::test]
+    async fn suggest_reward_percentile() {
+        let gwei = U256::from(GWEI_TO_WEI);
+
+        // the 20th percentile of block `k` is interpolated to `k + 2.5` gwei
+        let oracle = test_oracle(GasPriceOracleConfig { percentile: 20, ..Default::default() });
+        assert_eq!(oracle.suggest_from_entries(&entries(5)), gwei * U256::from(9) / U256::from(2));
+        assert_eq!(oracle.suggest_from_entries(&entries(20)), U256::from(12) * gwei);
+
+        let oracle = test_oracle(GasPriceOracleConfig { percentile: 100, ..Default::default() });
+        assert_eq!(oracle.suggest_from_entries(&entries(5)), U256::from(12) * gwei);
+
+        // blocks without rewards and blocks with tips under the ignored price are skipped
+        let oracle = test_oracle(GasPriceOracleConfig {
+            percentile: 20,
+            ignore_price: Some(gwei),
+            ..Default::default()
+        });
+        let mut with_skipped = entries(5);
+        with_skipped.push(FeeHistoryEntry::new(&SealedHeader::default()));
+        with_skipped.push(entry([1, 1, 1, 1, 1]));
+        assert_eq!(
+            oracle.suggest_from_entries(&with_skipped),
+            gwei * U256::from(9) / U256::from(2)
+        );
+    }
+
+    #[tokio::test]
+    async fn suggest_default_without_enough_blocks() {
+        let gwei = U256::from(GWEI_TO_WEI);
+
+        let oracle = test_oracle(GasPriceOracleConfig::default());
+        assert_eq!(oracle.suggest_from_entries(&[]), gwei);
+        assert_eq!(oracle.suggest_from_entries(&entries(MIN_GAS_PRICE_BLOCKS as u128 - 1)), gwei);
+
+        let oracle = test_oracle(GasPriceOracleConfig {
+            default: Some(U256::from(3) * gwei),
+            ..Default::default()
+        });
+        assert_eq!(oracle.suggest_from_entries(&entries(1)), U256::from(3) * gwei);
+
+        // fewer blocks are enough if the oracle is configured to use fewer blocks
+        let oracle =
+            test_oracle(GasPriceOracleConfig { blocks: 1, percentile: 20, ..Default::default() });
+        assert_eq!(oracle.suggest_from_entries(&entries(1)), gwei * U256::from(5) / U256::from(2));
+    }
+
+    #[tokio::test]
+    async fn suggest_clamped() {
+        let gwei = U256::from(GWEI_TO_WEI);
+
+        let oracle = test_oracle(GasPriceOracleConfig {
+            percentile: 20,
+            max_price: Some(U256::from(3) * gwei),
+            ..Default::default()
+        });
+        assert_eq!(oracle.suggest_from_entries(&entries(5)), U256::from(3) * gwei);
+
+        let oracle = test_oracle(GasPriceOracleConfig {
+            percentile: 20,
+            min_price: Some(U256::from(5) * gwei),
+            ..Default::default()
+        });
+        assert_eq!(oracle.suggest_from_entries(&entries(5)), U256::from(5) * gwei);
+        assert_eq!(oracle.suggest_from_entries(&[]), U256::from(5) * gwei);
+    }
 }
@@ -3,6 +3,7 @@
 mod api;
 pub mod bundle;
 pub mod cache;
+mod conditional;
 pub mod error;
 mod filter;
 pub mod gas_oracle;
@@ -14,15 +15,19 @@ mod signer;
 pub mod traits;
 pub(crate) mod utils;
 
+#[cfg(test)]
+pub(crate) mod test_utils;
+
 #[cfg(feature = "optimism")]
 pub mod optimism;
 
 pub use api::{
     fee_history::{fee_history_cache_new_blocks_task, FeeHistoryCache, FeeHistoryCacheConfig},
-    EthApi, EthApiSpec, EthTransactions, TransactionSource, RPC_DEFAULT_GAS_CAP,
+    EthApi, EthApiSpec, EthSimulateError, EthTransactions, TransactionSource, RPC_DEFAULT_GAS_CAP,
 };
 
 pub use bundle::EthBundle;
-pub use filter::{EthFilter, EthFilterConfig};
+pub use conditional::EthConditional;
+pub use filter::{EthFilter, EthFilterConfig, FilterError, SuggestedBlockRange};
 pub use id_provider::EthSubscriptionIdProvider;
-pub use pubsub::EthPubSub;
+pub use pubsub::{EthPubSub, EthPubSubConfig};
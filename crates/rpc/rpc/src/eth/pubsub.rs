@@ -4,25 +4,34 @@ use crate::{
     eth::logs_utils,
     result::{internal_rpc_err, invalid_params_rpc_err},
 };
-use futures::StreamExt;
+use futures::{
+    future::{Fuse, FusedFuture},
+    stream::BoxStream,
+    FutureExt, StreamExt,
+};
 use jsonrpsee::{
     server::SubscriptionMessage, types::ErrorObject, PendingSubscriptionSink, SubscriptionSink,
 };
+use reth_metrics::{metrics::Counter, Metrics};
 use reth_network_api::NetworkInfo;
 use reth_primitives::{IntoRecoveredTransaction, TxHash};
 use reth_provider::{BlockReader, CanonStateSubscriptions, EvmEnvProvider};
 use reth_rpc_api::EthPubSubApiServer;
+use reth_rpc_server_types::constants::{
+    DEFAULT_MAX_SUBSCRIPTION_BUFFER, DEFAULT_MAX_TRANSACTION_FILTER_ADDRESSES,
+};
 use reth_rpc_types::{
     pubsub::{
         Params, PubSubSyncStatus, SubscriptionKind, SubscriptionResult as EthSubscriptionResult,
         SyncStatusMetadata,
     },
-    FilteredParams, Header, Log,
+    FilteredParams, Header, Log, PendingTransactionFilter, SubscriptionBackpressurePolicy,
+    SubscriptionBufferOverride, SubscriptionParams,
 };
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
-use reth_transaction_pool::{NewTransactionEvent, TransactionPool};
+use reth_transaction_pool::{NewTransactionEvent, PoolTransaction, TransactionPool};
 use serde::Serialize;
-use std::sync::Arc;
+use std::{collections::VecDeque, pin::pin, sync::Arc};
 use tokio_stream::{
     wrappers::{BroadcastStream, ReceiverStream},
     Stream,
@@ -42,7 +51,7 @@ pub struct EthPubSub<Provider, Pool, Events, Network> {
 // === impl EthPubSub ===
 
 impl<Provider, Pool, Events, Network> EthPubSub<Provider, Pool, Events, Network> {
-    /// Creates a new, shareable instance.
+    /// Creates a new, shareable instance with the default [`EthPubSubConfig`].
     ///
     /// Subscription tasks are spawned via [`tokio::task::spawn`]
     pub fn new(provider: Provider, pool: Pool, chain_events: Events, network: Network) -> Self {
@@ -51,6 +60,7 @@ impl<Provider, Pool, Events, Network> EthPubSub<Provider, Pool, Events, Network>
             pool,
             chain_events,
             network,
+            EthPubSubConfig::default(),
             Box::<TokioTaskExecutor>::default(),
         )
     }
@@ -61,13 +71,73 @@ impl<Provider, Pool, Events, Network> EthPubSub<Provider, Pool, Events, Network>
         pool: Pool,
         chain_events: Events,
         network: Network,
+        config: EthPubSubConfig,
         subscription_task_spawner: Box<dyn TaskSpawner>,
     ) -> Self {
-        let inner = EthPubSubInner { provider, pool, chain_events, network };
+        let inner = EthPubSubInner { provider, pool, chain_events, network, config };
         Self { inner: Arc::new(inner), subscription_task_spawner }
     }
 }
 
+/// Settings for the subscriptions of [`EthPubSub`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EthPubSubConfig {
+    /// The maximum number of notifications buffered per subscription if the subscriber doesn't
+    /// keep up.
+    pub max_subscription_buffer: usize,
+    /// What happens to the notifications of a subscription once its buffer is full.
+    pub backpressure_policy: SubscriptionBackpressurePolicy,
+    /// The maximum number of addresses of the filter of a `newPendingTransactions`
+    /// subscription.
+    pub max_transaction_filter_addresses: usize,
+}
+
+impl EthPubSubConfig {
+    /// Sets the maximum number of notifications buffered per subscription.
+    pub const fn max_subscription_buffer(mut self, max_buffer: usize) -> Self {
+        self.max_subscription_buffer = max_buffer;
+        self
+    }
+
+    /// Sets what happens to the notifications of a subscription once its buffer is full.
+    pub const fn backpressure_policy(mut self, policy: SubscriptionBackpressurePolicy) -> Self {
+        self.backpressure_policy = policy;
+        self
+    }
+
+    /// Sets the maximum number of addresses of the filter of a `newPendingTransactions`
+    /// subscription.
+    pub const fn max_transaction_filter_addresses(mut self, max_addresses: usize) -> Self {
+        self.max_transaction_filter_addresses = max_addresses;
+        self
+    }
+
+    /// Applies the override of a single subscription.
+    ///
+    /// The buffer can only be lowered, it's clamped to the buffer limit of the server.
+    fn with_override(mut self, buffer_override: Option<SubscriptionBufferOverride>) -> Self {
+        if let Some(buffer_override) = buffer_override {
+            if let Some(max_buffer) = buffer_override.max_buffer {
+                self.max_subscription_buffer = max_buffer.min(self.max_subscription_buffer);
+            }
+            if let Some(policy) = buffer_override.policy {
+                self.backpressure_policy = policy;
+            }
+        }
+        self
+    }
+}
+
+impl Default for EthPubSubConfig {
+    fn default() -> Self {
+        Self {
+            max_subscription_buffer: DEFAULT_MAX_SUBSCRIPTION_BUFFER,
+            backpressure_policy: SubscriptionBackpressurePolicy::default(),
+            max_transaction_filter_addresses: DEFAULT_MAX_TRANSACTION_FILTER_ADDRESSES,
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl<Provider, Pool, Events, Network> EthPubSubApiServer
     for EthPubSub<Provider, Pool, Events, Network>
@@ -82,12 +152,50 @@ where
         &self,
         pending: PendingSubscriptionSink,
         kind: SubscriptionKind,
-        params: Option<Params>,
+        params: Option<SubscriptionParams>,
     ) -> jsonrpsee::core::SubscriptionResult {
+        if let Some(filter) = params.as_ref().and_then(|params| params.transaction_filter.as_ref())
+        {
+            if !matches!(kind, SubscriptionKind::NewPendingTransactions) {
+                pending
+                    .reject(invalid_params_rpc_err(
+                        "filter is only supported for newPendingTransactions",
+                    ))
+                    .await;
+                return Ok(())
+            }
+            let max_addresses = self.inner.config.max_transaction_filter_addresses;
+            if filter.address_count() > max_addresses {
+                pending
+                    .reject(invalid_params_rpc_err(format!(
+                        "filter exceeds the limit of {max_addresses} addresses"
+                    )))
+                    .await;
+                return Ok(())
+            }
+        }
+
+        let params = params.unwrap_or_default();
+
+        // The pool listener is registered before the subscription is accepted, so that the
+        // subscriber observes every transaction that becomes pending once it has the subscription
+        // id.
+        let pending_transactions = if kind == SubscriptionKind::NewPendingTransactions {
+            match self.inner.pending_transaction_results(&params) {
+                Ok(stream) => Some(stream),
+                Err(err) => {
+                    pending.reject(err).await;
+                    return Ok(())
+                }
+            }
+        } else {
+            None
+        };
+
         let sink = pending.accept().await?;
         let pubsub = self.inner.clone();
         self.subscription_task_spawner.spawn(Box::pin(async move {
-            let _ = handle_accepted(pubsub, sink, kind, params).await;
+            let _ = handle_accepted(pubsub, sink, kind, params, pending_transactions).await;
         }));
 
         Ok(())
@@ -95,11 +203,15 @@ where
 }
 
 /// The actual handler for an accepted [`EthPubSub::subscribe`] call.
+///
+/// The notifications of a `newPendingTransactions` subscription are the `pending_transactions`
+/// created before the subscription was accepted.
 async fn handle_accepted<Provider, Pool, Events, Network>(
     pubsub: Arc<EthPubSubInner<Provider, Pool, Events, Network>>,
     accepted_sink: SubscriptionSink,
     kind: SubscriptionKind,
-    params: Option<Params>,
+    params: SubscriptionParams,
+    pending_transactions: Option<BoxStream<'static, EthSubscriptionResult>>,
 ) -> Result<(), ErrorObject<'static>>
 where
     Provider: BlockReader + EvmEnvProvider + Clone + 'static,
@@ -107,56 +219,29 @@ where
     Events: CanonStateSubscriptions + Clone + 'static,
     Network: NetworkInfo + Clone + 'static,
 {
+    let buffer = NotificationBuffer::new(pubsub.config.with_override(params.backpressure), &kind);
     match kind {
         SubscriptionKind::NewHeads => {
             let stream = pubsub
                 .new_headers_stream()
                 .map(|block| EthSubscriptionResult::Header(Box::new(block.into())));
-            pipe_from_stream(accepted_sink, stream).await
+            pipe_from_stream(accepted_sink, stream, buffer).await
         }
         SubscriptionKind::Logs => {
             // if no params are provided, used default filter params
-            let filter = match params {
-                Some(Params::Logs(filter)) => FilteredParams::new(Some(*filter)),
-                Some(Params::Bool(_)) => {
-                    return Err(invalid_params_rpc_err("Invalid params for logs"))
-                }
-                _ => FilteredParams::default(),
+            let filter = match params.params {
+                Params::Logs(filter) => FilteredParams::new(Some(*filter)),
+                Params::Bool(_) => return Err(invalid_params_rpc_err("Invalid params for logs")),
+                Params::None => FilteredParams::default(),
             };
             let stream =
                 pubsub.log_stream(filter).map(|log| EthSubscriptionResult::Log(Box::new(log)));
-            pipe_from_stream(accepted_sink, stream).await
-        }
-        SubscriptionKind::NewPendingTransactions => {
-            if let Some(params) = params {
-                match params {
-                    Params::Bool(true) => {
-                        // full transaction objects requested
-                        let stream = pubsub.full_pending_transaction_stream().map(|tx| {
-                            EthSubscriptionResult::FullTransaction(Box::new(
-                                reth_rpc_types_compat::transaction::from_recovered(
-                                    tx.transaction.to_recovered_transaction(),
-                                ),
-                            ))
-                        });
-                        return pipe_from_stream(accepted_sink, stream).await
-                    }
-                    Params::Bool(false) | Params::None => {
-                        // only hashes requested
-                    }
-                    Params::Logs(_) => {
-                        return Err(invalid_params_rpc_err(
-                            "Invalid params for newPendingTransactions",
-                        ))
-                    }
-                }
-            }
-
-            let stream = pubsub
-                .pending_transaction_hashes_stream()
-                .map(EthSubscriptionResult::TransactionHash);
-            pipe_from_stream(accepted_sink, stream).await
+            pipe_from_stream(accepted_sink, stream, buffer).await
         }
+        SubscriptionKind::NewPendingTransactions => match pending_transactions {
+            Some(stream) => pipe_from_stream(accepted_sink, stream, buffer).await,
+            None => Ok(()),
+        },
         SubscriptionKind::Syncing => {
             // get new block subscription
             let mut canon_state =
@@ -212,37 +297,139 @@ impl From<SubscriptionSerializeError> for ErrorObject<'static> {
 }
 
 /// Pipes all stream items to the subscription sink.
+///
+/// The stream is drained while the subscriber receives the previous notifications. Notifications
+/// that can't be sent yet are kept in the [`NotificationBuffer`]. Once the buffer is full, the
+/// stream is only drained further if the [`SubscriptionBackpressurePolicy`] drops notifications or
+/// closes the subscription.
 async fn pipe_from_stream<T, St>(
     sink: SubscriptionSink,
     mut stream: St,
+    mut buffer: NotificationBuffer,
 ) -> Result<(), ErrorObject<'static>>
 where
     St: Stream<Item = T> + Unpin,
     T: Serialize,
 {
+    let mut sending = pin!(Fuse::terminated());
+    let mut stream_ended = false;
     loop {
+        if sending.is_terminated() {
+            match buffer.pop() {
+                Some(msg) => sending.set(sink.send(msg).fuse()),
+                // all notifications are sent
+                None if stream_ended => break Ok(()),
+                None => {}
+            }
+        }
+
         tokio::select! {
             _ = sink.closed() => {
                 // connection dropped
                 break Ok(())
             },
-            maybe_item = stream.next() => {
-                let item = match maybe_item {
-                    Some(item) => item,
-                    None => {
-                        // stream ended
-                        break  Ok(())
-                    },
+            sent = &mut sending, if !sending.is_terminated() => {
+                if sent.is_err() {
+                    break Ok(())
+                }
+            },
+            maybe_item = stream.next(), if !stream_ended && buffer.accepts() => {
+                let Some(item) = maybe_item else {
+                    stream_ended = true;
+                    continue
                 };
                 let msg = SubscriptionMessage::from_json(&item).map_err(SubscriptionSerializeError::new)?;
-                if sink.send(msg).await.is_err() {
-                    break Ok(());
+                if !buffer.push(msg) {
+                    // the subscriber doesn't keep up, close the subscription
+                    break Ok(())
                 }
             }
         }
     }
 }
 
+/// The notifications of a subscription that are waiting to be sent.
+struct NotificationBuffer {
+    messages: VecDeque<SubscriptionMessage>,
+    max_len: usize,
+    policy: SubscriptionBackpressurePolicy,
+    metrics: SubscriptionMetrics,
+}
+
+impl NotificationBuffer {
+    fn new(config: EthPubSubConfig, kind: &SubscriptionKind) -> Self {
+        Self {
+            messages: VecDeque::new(),
+            max_len: config.max_subscription_buffer.max(1),
+            policy: config.backpressure_policy,
+            metrics: SubscriptionMetrics::new_with_labels(&[(
+                "kind",
+                subscription_kind_name(kind),
+            )]),
+        }
+    }
+
+    /// Returns `false` if the buffer is full and the policy waits for the subscriber.
+    fn accepts(&self) -> bool {
+        self.policy != SubscriptionBackpressurePolicy::Wait || self.messages.len() < self.max_len
+    }
+
+    /// Buffers the message according to the [`SubscriptionBackpressurePolicy`].
+    ///
+    /// Returns `false` if the buffer is full and the subscription should be closed.
+    fn push(&mut self, msg: SubscriptionMessage) -> bool {
+        if self.messages.len() < self.max_len {
+            self.messages.push_back(msg);
+            return true
+        }
+
+        match self.policy {
+            SubscriptionBackpressurePolicy::Wait => {
+                // not reached, no messages are received while the buffer is full
+                self.messages.push_back(msg);
+            }
+            SubscriptionBackpressurePolicy::DropOldest => {
+                self.messages.pop_front();
+                self.messages.push_back(msg);
+                self.metrics.dropped_notifications_total.increment(1);
+            }
+            SubscriptionBackpressurePolicy::DropNewest => {
+                self.metrics.dropped_notifications_total.increment(1);
+            }
+            SubscriptionBackpressurePolicy::Disconnect => {
+                self.metrics.disconnected_subscriptions_total.increment(1);
+                return false
+            }
+        }
+        true
+    }
+
+    /// Returns the oldest buffered message.
+    fn pop(&mut self) -> Option<SubscriptionMessage> {
+        self.messages.pop_front()
+    }
+}
+
+/// Returns the name of the subscription kind used in `eth_subscribe`.
+const fn subscription_kind_name(kind: &SubscriptionKind) -> &'static str {
+    match kind {
+        SubscriptionKind::NewHeads => "newHeads",
+        SubscriptionKind::Logs => "logs",
+        SubscriptionKind::NewPendingTransactions => "newPendingTransactions",
+        SubscriptionKind::Syncing => "syncing",
+    }
+}
+
+/// Metrics of the subscriptions, labeled by the subscription kind.
+#[derive(Metrics)]
+#[metrics(scope = "rpc.eth_pubsub")]
+struct SubscriptionMetrics {
+    /// The number of notifications dropped because the subscriber didn't keep up.
+    dropped_notifications_total: Counter,
+    /// The number of subscriptions closed because the subscriber didn't keep up.
+    disconnected_subscriptions_total: Counter,
+}
+
 impl<Provider, Pool, Events, Network> std::fmt::Debug
     for EthPubSub<Provider, Pool, Events, Network>
 {
@@ -262,6 +449,8 @@ struct EthPubSubInner<Provider, Pool, Events, Network> {
     chain_events: Events,
     /// The network.
     network: Network,
+    /// The settings of the subscriptions.
+    config: EthPubSubConfig,
 }
 
 // == impl EthPubSubInner ===
@@ -291,6 +480,45 @@ impl<Provider, Pool, Events, Network> EthPubSubInner<Provider, Pool, Events, Net
 where
     Pool: TransactionPool + 'static,
 {
+    /// Returns the notifications of a `newPendingTransactions` subscription with the given
+    /// params.
+    ///
+    /// This registers the listener of the pool right away.
+    fn pending_transaction_results(
+        &self,
+        params: &SubscriptionParams,
+    ) -> Result<BoxStream<'static, EthSubscriptionResult>, ErrorObject<'static>> {
+        let full_transactions = match params.params {
+            Params::Bool(full_transactions) => full_transactions,
+            Params::None => false,
+            Params::Logs(_) => {
+                return Err(invalid_params_rpc_err("Invalid params for newPendingTransactions"))
+            }
+        };
+        let to_result = move |event: NewTransactionEvent<Pool::Transaction>| {
+            if full_transactions {
+                EthSubscriptionResult::FullTransaction(Box::new(
+                    reth_rpc_types_compat::transaction::from_recovered(
+                        event.transaction.to_recovered_transaction(),
+                    ),
+                ))
+            } else {
+                EthSubscriptionResult::TransactionHash(*event.transaction.hash())
+            }
+        };
+
+        if let Some(filter) = params.transaction_filter.clone() {
+            return Ok(self.filtered_pending_transaction_stream(filter).map(to_result).boxed())
+        }
+        if full_transactions {
+            return Ok(self.full_pending_transaction_stream().map(to_result).boxed())
+        }
+        Ok(self
+            .pending_transaction_hashes_stream()
+            .map(EthSubscriptionResult::TransactionHash)
+            .boxed())
+    }
+
     /// Returns a stream that yields all transaction hashes emitted by the txpool.
     fn pending_transaction_hashes_stream(&self) -> impl Stream<Item = TxHash> {
         ReceiverStream::new(self.pool.pending_transactions_listener())
@@ -302,6 +530,26 @@ where
     ) -> impl Stream<Item = NewTransactionEvent<<Pool as TransactionPool>::Transaction>> {
         self.pool.new_pending_pool_transactions_listener()
     }
+
+    /// Returns a stream that yields the transactions emitted by the txpool that match the given
+    /// filter.
+    ///
+    /// The effective tip of the transactions is computed with the base fee of the pending block.
+    fn filtered_pending_transaction_stream(
+        &self,
+        filter: PendingTransactionFilter,
+    ) -> impl Stream<Item = NewTransactionEvent<<Pool as TransactionPool>::Transaction>> {
+        let pool = self.pool.clone();
+        self.pool.new_pending_pool_transactions_listener().filter(move |event| {
+            let tx = &event.transaction.transaction;
+            let base_fee = pool.block_info().pending_basefee;
+            futures::future::ready(filter.matches(
+                &tx.sender(),
+                tx.to().as_ref(),
+                tx.effective_tip_per_gas(base_fee),
+            ))
+        })
+    }
 }
 
 impl<Provider, Pool, Events, Network> EthPubSubInner<Provider, Pool, Events, Network>
@@ -324,9 +572,10 @@ where
     /// Returns a stream that yields all logs that match the given filter.
     fn log_stream(&self, filter: FilteredParams) -> impl Stream<Item = Log> {
         BroadcastStream::new(self.chain_events.subscribe_to_canonical_state())
-            .map(move |canon_state| {
-                canon_state.expect("new block subscription never ends").block_receipts()
-            })
+            // the subscription lags if the stream isn't drained fast enough, the skipped blocks
+            // are lost
+            .filter_map(|canon_state| futures::future::ready(canon_state.ok()))
+            .map(|canon_state| canon_state.block_receipts())
             .flat_map(futures::stream::iter)
             .flat_map(move |(block_receipts, removed)| {
                 let all_logs = logs_utils::matching_block_logs_with_tx_hashes(
@@ -339,3 +588,147 @@ where
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::rpc_params;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::Address;
+    use reth_provider::test_utils::{NoopProvider, TestCanonStateSubscriptions};
+    use reth_transaction_pool::test_utils::{testing_pool, MockTransaction};
+    use std::time::Duration;
+
+    fn buffer(policy: SubscriptionBackpressurePolicy) -> NotificationBuffer {
+        let config =
+            EthPubSubConfig::default().max_subscription_buffer(2).backpressure_policy(policy);
+        NotificationBuffer::new(config, &SubscriptionKind::Logs)
+    }
+
+    fn message(n: u64) -> SubscriptionMessage {
+        SubscriptionMessage::from_json(&format!("notification {n}")).unwrap()
+    }
+
+    fn drain(buffer: &mut NotificationBuffer) -> Vec<String> {
+        std::iter::from_fn(|| buffer.pop()).map(|msg| format!("{msg:?}")).collect()
+    }
+
+    #[test]
+    fn wait_until_buffer_has_room() {
+        let mut buffer = buffer(SubscriptionBackpressurePolicy::Wait);
+        assert!(buffer.push(message(0)));
+        assert!(buffer.accepts());
+        assert!(buffer.push(message(1)));
+        assert!(!buffer.accepts());
+        buffer.pop();
+        assert!(buffer.accepts());
+    }
+
+    #[test]
+    fn drop_oldest_keeps_latest_notifications() {
+        let mut buffer = buffer(SubscriptionBackpressurePolicy::DropOldest);
+        assert!((0..4).all(|n| buffer.push(message(n))));
+        let buffered = drain(&mut buffer);
+        assert_eq!(buffered.len(), 2);
+        assert!(buffered[0].contains("notification 2"));
+        assert!(buffered[1].contains("notification 3"));
+    }
+
+    #[test]
+    fn drop_newest_keeps_first_notifications() {
+        let mut buffer = buffer(SubscriptionBackpressurePolicy::DropNewest);
+        assert!((0..4).all(|n| buffer.push(message(n))));
+        let buffered = drain(&mut buffer);
+        assert_eq!(buffered.len(), 2);
+        assert!(buffered[0].contains("notification 0"));
+        assert!(buffered[1].contains("notification 1"));
+    }
+
+    #[test]
+    fn disconnect_once_buffer_is_full() {
+        let mut buffer = buffer(SubscriptionBackpressurePolicy::Disconnect);
+        assert!(buffer.push(message(0)));
+        assert!(buffer.push(message(1)));
+        assert!(!buffer.push(message(2)));
+    }
+
+    #[tokio::test]
+    async fn pending_transactions_with_to_filter() {
+        let pool = testing_pool();
+        let module = EthPubSub::new(
+            NoopProvider::default(),
+            pool.clone(),
+            TestCanonStateSubscriptions::default(),
+            NoopNetwork::default(),
+        )
+        .into_rpc();
+
+        let recipient = Address::random();
+        let params = serde_json::json!({ "filter": { "to": [recipient] } });
+        let mut sub = module
+            .subscribe_unbounded("eth_subscribe", rpc_params!["newPendingTransactions", params])
+            .await
+            .unwrap();
+
+        let other = MockTransaction::eip1559();
+        let matching = MockTransaction::eip1559().with_to(recipient);
+        let replacement = matching.inc_price_by(10).rng_hash();
+        for tx in [other, matching.clone(), replacement.clone()] {
+            pool.add_external_transaction(tx).await.unwrap();
+        }
+
+        // the replacement of a matching transaction is emitted as well
+        for expected in [matching.get_hash(), replacement.get_hash()] {
+            let (hash, _) = tokio::time::timeout(Duration::from_secs(1), sub.next::<TxHash>())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            assert_eq!(hash, expected);
+        }
+        assert!(tokio::time::timeout(Duration::from_millis(100), sub.next::<TxHash>())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn reject_oversized_transaction_filter() {
+        let module = EthPubSub::with_spawner(
+            NoopProvider::default(),
+            testing_pool(),
+            TestCanonStateSubscriptions::default(),
+            NoopNetwork::default(),
+            EthPubSubConfig::default().max_transaction_filter_addresses(1),
+            Box::<TokioTaskExecutor>::default(),
+        )
+        .into_rpc();
+
+        let params = serde_json::json!({
+            "filter": { "to": [Address::random()], "from": [Address::random()] }
+        });
+        let res = module
+            .subscribe_unbounded("eth_subscribe", rpc_params!["newPendingTransactions", params])
+            .await;
+        assert!(res.is_err());
+
+        // filters are only supported for pending transactions
+        let params = serde_json::json!({ "filter": { "to": [Address::random()] } });
+        let res =
+            module.subscribe_unbounded("eth_subscribe", rpc_params!["newHeads", params]).await;
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn subscription_overrides_config() {
+        let config = EthPubSubConfig::default()
+            .with_override(Some(SubscriptionBufferOverride { max_buffer: Some(10), policy: None }));
+        assert_eq!(config.max_subscription_buffer, 10);
+        assert_eq!(config.backpressure_policy, SubscriptionBackpressurePolicy::Wait);
+
+        // the override can't raise the buffer above the server limit
+        let config = EthPubSubConfig::default().max_subscription_buffer(100).with_override(Some(
+            SubscriptionBufferOverride { max_buffer: Some(usize::MAX), policy: None },
+        ));
+        assert_eq!(config.max_subscription_buffer, 100);
+    }
+}
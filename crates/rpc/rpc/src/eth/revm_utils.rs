@@ -1,26 +1,30 @@
 //! utilities for working with revm
 
 use crate::eth::error::{EthApiError, EthResult, RpcInvalidTransactionError};
+use alloy_eips::eip2935::HISTORY_STORAGE_ADDRESS;
+use reth_evm::system_calls::HISTORY_SERVE_WINDOW;
 #[cfg(feature = "optimism")]
 use reth_primitives::revm::env::fill_op_tx_env;
 #[cfg(not(feature = "optimism"))]
 use reth_primitives::revm::env::fill_tx_env;
 use reth_primitives::{
-    revm::env::fill_tx_env_with_recovered, Address, TransactionSigned,
+    keccak256, revm::env::fill_tx_env_with_recovered, Address, TransactionSigned,
     TransactionSignedEcRecovered, TxHash, TxKind, B256, U256,
 };
-use reth_rpc_types::{
-    state::{AccountOverride, StateOverride},
-    BlockOverrides, TransactionRequest,
+use reth_revm::{
+    database::{EvmStateProvider, StateProviderDatabase},
+    state_override::{self, StateOverrideError},
 };
+use reth_rpc_types::{state::StateOverride, BlockOverrides, TransactionRequest};
+use reth_trie::HashedStorage;
 #[cfg(feature = "optimism")]
 use revm::primitives::{Bytes, OptimismFields};
 use revm::{
-    db::CacheDB,
+    db::{AccountState, CacheDB, DbAccount},
     precompile::{PrecompileSpecId, Precompiles},
     primitives::{
-        db::DatabaseRef, BlockEnv, Bytecode, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, SpecId,
-        TransactTo, TxEnv,
+        db::DatabaseRef, BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, SpecId, TransactTo,
+        TxEnv,
     },
     Database,
 };
@@ -36,7 +40,7 @@ pub struct EvmOverrides {
     pub state: Option<StateOverride>,
     /// Applies overrides to the block before execution.
     ///
-    /// This is a `Box` because less common and only available in debug trace endpoints.
+    /// This is a `Box` because block overrides are less common than state overrides.
     pub block: Option<Box<BlockOverrides>>,
 }
 
@@ -134,12 +138,11 @@ pub(crate) fn prepare_call_env<DB>(
     mut block: BlockEnv,
     request: TransactionRequest,
     gas_limit: u64,
-    db: &mut CacheDB<DB>,
+    db: &mut CacheDB<StateProviderDatabase<DB>>,
     overrides: EvmOverrides,
 ) -> EthResult<EnvWithHandlerCfg>
 where
-    DB: DatabaseRef,
-    EthApiError: From<<DB as DatabaseRef>::Error>,
+    DB: EvmStateProvider,
 {
     // we want to disable this in eth_call, since this is common practice used by other node
     // impls and providers <https://github.com/foundry-rs/foundry/issues/4388>
@@ -156,13 +159,15 @@ where
 
     // apply block overrides, we need to apply them first so that they take effect when we we create
     // the evm env via `build_call_evm_env`, e.g. basefee
-    if let Some(mut block_overrides) = overrides.block {
-        if let Some(block_hashes) = block_overrides.block_hash.take() {
-            // override block hashes
-            db.block_hashes
-                .extend(block_hashes.into_iter().map(|(num, hash)| (U256::from(num), hash)))
-        }
-        apply_block_overrides(*block_overrides, &mut block);
+    if let Some(block_overrides) = overrides.block {
+        apply_block_overrides(*block_overrides, cfg.handler_cfg.spec_id, db, &mut block)?;
+    }
+
+    // apply state overrides before anything is read from the database, so every read of the call,
+    // including the pre-state that tracers read back after the execution, sees the overridden
+    // state
+    if let Some(state_overrides) = overrides.state {
+        apply_state_overrides(state_overrides, db)?;
     }
 
     let request_gas = request.gas;
@@ -170,10 +175,7 @@ where
     // set nonce to None so that the next nonce is used when transacting the call
     env.tx.nonce = None;
 
-    // apply state overrides
-    if let Some(state_overrides) = overrides.state {
-        apply_state_overrides(state_overrides, db)?;
-    }
+    prefetch_access_list(db, &env.tx.access_list)?;
 
     if request_gas.is_none() {
         // No gas limit was provided in the request, so we need to cap the transaction gas limit
@@ -447,8 +449,24 @@ impl CallFees {
     }
 }
 
-/// Applies the given block overrides to the env
-fn apply_block_overrides(overrides: BlockOverrides, env: &mut BlockEnv) {
+/// Applies the given block overrides to the env and the block hashes of the [`CacheDB`].
+///
+/// Since Prague, the `BLOCKHASH` opcode reads the hashes from the history storage contract of
+/// [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935), so the overridden hashes are written to
+/// its storage as well.
+///
+/// This must be done before the [`TxEnv`] is created, so the fees of the request are checked
+/// against the overridden basefee.
+pub(crate) fn apply_block_overrides<DB>(
+    overrides: BlockOverrides,
+    spec_id: SpecId,
+    db: &mut CacheDB<DB>,
+    env: &mut BlockEnv,
+) -> EthResult<()>
+where
+    DB: DatabaseRef,
+    EthApiError: From<<DB as DatabaseRef>::Error>,
+{
     let BlockOverrides {
         number,
         difficulty,
@@ -457,9 +475,20 @@ fn apply_block_overrides(overrides: BlockOverrides, env: &mut BlockEnv) {
         coinbase,
         random,
         base_fee,
-        block_hash: _,
+        block_hash,
     } = overrides;
 
+    if let Some(block_hashes) = block_hash {
+        // override block hashes
+        for (num, hash) in block_hashes {
+            db.block_hashes.insert(U256::from(num), hash);
+            if SpecId::enabled(spec_id, SpecId::PRAGUE) {
+                let slot = U256::from(num % HISTORY_SERVE_WINDOW);
+                db.insert_account_storage(HISTORY_STORAGE_ADDRESS, slot, hash.into())?;
+            }
+        }
+    }
+
     if let Some(number) = number {
         env.number = number;
     }
@@ -481,85 +510,85 @@ fn apply_block_overrides(overrides: BlockOverrides, env: &mut BlockEnv) {
     if let Some(base_fee) = base_fee {
         env.basefee = base_fee;
     }
+
+    Ok(())
 }
 
-/// Applies the given state overrides (a set of [`AccountOverride`]) to the [`CacheDB`].
-pub(crate) fn apply_state_overrides<DB>(
-    overrides: StateOverride,
-    db: &mut CacheDB<DB>,
+/// Returns the cached storage of the account as a [`HashedStorage`] on top of the state the
+/// [`CacheDB`] wraps.
+///
+/// Storage slots that were only read are included as well, with their unchanged values.
+pub(crate) fn hashed_cache_storage(account: &DbAccount) -> HashedStorage {
+    let wiped =
+        matches!(account.account_state, AccountState::NotExisting | AccountState::StorageCleared);
+    let storage = account
+        .storage
+        .iter()
+        .map(|(slot, value)| (keccak256(B256::new(slot.to_be_bytes())), *value));
+    HashedStorage::from_iter(wiped, storage)
+}
+
+/// Loads the accounts and storage slots of the access list into the [`CacheDB`], so the execution
+/// only reads the state that was not declared from the database.
+///
+/// The storage slots of an account are read at once. Slots that are already cached are kept.
+pub(crate) fn prefetch_access_list<DB>(
+    db: &mut CacheDB<StateProviderDatabase<DB>>,
+    access_list: &[(Address, Vec<U256>)],
 ) -> EthResult<()>
 where
-    DB: DatabaseRef,
-    EthApiError: From<<DB as DatabaseRef>::Error>,
+    DB: EvmStateProvider,
 {
-    for (account, account_overrides) in overrides {
-        apply_account_override(account, account_overrides, db)?;
+    for (address, slots) in access_list {
+        let account = db.load_account(*address)?;
+        if matches!(account.account_state, AccountState::NotExisting | AccountState::StorageCleared)
+        {
+            // the storage is known to be empty or is fully cached
+            continue
+        }
+
+        let slots = slots
+            .iter()
+            .filter(|slot| !account.storage.contains_key(*slot))
+            .copied()
+            .collect::<Vec<_>>();
+        if slots.is_empty() {
+            continue
+        }
+
+        let keys = slots.iter().map(|slot| B256::new(slot.to_be_bytes())).collect::<Vec<_>>();
+        let values = db.db.storages(*address, &keys)?;
+        db.load_account(*address)?
+            .storage
+            .extend(slots.into_iter().zip(values.into_iter().map(Option::unwrap_or_default)));
     }
     Ok(())
 }
 
-/// Applies a single [`AccountOverride`] to the [`CacheDB`].
-fn apply_account_override<DB>(
-    account: Address,
-    account_override: AccountOverride,
+/// Applies the given state overrides (a set of
+/// [`AccountOverride`](reth_rpc_types::state::AccountOverride)) to the [`CacheDB`].
+pub(crate) fn apply_state_overrides<DB>(
+    overrides: StateOverride,
     db: &mut CacheDB<DB>,
 ) -> EthResult<()>
 where
     DB: DatabaseRef,
     EthApiError: From<<DB as DatabaseRef>::Error>,
 {
-    // we need to fetch the account via the `DatabaseRef` to not update the state of the account,
-    // which is modified via `Database::basic_ref`
-    let mut account_info = DatabaseRef::basic_ref(db, account)?.unwrap_or_default();
-
-    if let Some(nonce) = account_override.nonce {
-        account_info.nonce = nonce.to();
-    }
-    if let Some(code) = account_override.code {
-        account_info.code = Some(Bytecode::new_raw(code));
-    }
-    if let Some(balance) = account_override.balance {
-        account_info.balance = balance;
-    }
-
-    db.insert_account_info(account, account_info);
-
-    // We ensure that not both state and state_diff are set.
-    // If state is set, we must mark the account as "NewlyCreated", so that the old storage
-    // isn't read from
-    match (account_override.state, account_override.state_diff) {
-        (Some(_), Some(_)) => return Err(EthApiError::BothStateAndStateDiffInOverride(account)),
-        (None, None) => {
-            // nothing to do
+    state_override::apply_state_overrides(overrides, db).map_err(|err| match err {
+        StateOverrideError::BothStateAndStateDiff(account) => {
+            EthApiError::BothStateAndStateDiffInOverride(account)
         }
-        (Some(new_account_state), None) => {
-            db.replace_account_storage(
-                account,
-                new_account_state
-                    .into_iter()
-                    .map(|(slot, value)| {
-                        (U256::from_be_bytes(slot.0), U256::from_be_bytes(value.0))
-                    })
-                    .collect(),
-            )?;
-        }
-        (None, Some(account_state_diff)) => {
-            for (slot, value) in account_state_diff {
-                db.insert_account_storage(
-                    account,
-                    U256::from_be_bytes(slot.0),
-                    U256::from_be_bytes(value.0),
-                )?;
-            }
-        }
-    };
-
-    Ok(())
+        StateOverrideError::Database(err) => err.into(),
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use reth_primitives::constants::GWEI_TO_WEI;
+    use reth_primitives::{constants::GWEI_TO_WEI, hex, Bytes};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_types::state::AccountOverride;
+    use std::collections::BTreeMap;
 
     use super::*;
 
@@ -664,4 +693,151 @@ mod tests {
         );
         assert!(call_fees.is_err());
     }
+
+    #[test]
+    fn prefetch_access_list_caches_declared_slots() {
+        let address = Address::with_last_byte(1);
+        let missing = Address::with_last_byte(2);
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            address,
+            ExtendedAccount::new(0, U256::ZERO)
+                .extend_storage([(B256::with_last_byte(1), U256::from(42))]),
+        );
+        let mut db = CacheDB::new(StateProviderDatabase::new(provider));
+        db.insert_account_storage(address, U256::from(2), U256::from(7)).unwrap();
+
+        prefetch_access_list(
+            &mut db,
+            &[
+                (address, vec![U256::from(1), U256::from(2), U256::from(3)]),
+                (missing, vec![U256::from(1)]),
+            ],
+        )
+        .unwrap();
+
+        let storage = &db.accounts[&address].storage;
+        assert_eq!(storage.get(&U256::from(1)), Some(&U256::from(42)));
+        // cached slots are kept
+        assert_eq!(storage.get(&U256::from(2)), Some(&U256::from(7)));
+        assert_eq!(storage.get(&U256::from(3)), Some(&U256::ZERO));
+        assert_eq!(db.accounts[&missing].account_state, AccountState::NotExisting);
+        assert!(db.accounts[&missing].storage.is_empty());
+    }
+
+    #[test]
+    fn prepare_call_env_prefetches_on_top_of_state_overrides() {
+        let diff = Address::with_last_byte(1);
+        let replaced = Address::with_last_byte(2);
+        let provider = MockEthProvider::default();
+        for address in [diff, replaced] {
+            provider.add_account(
+                address,
+                ExtendedAccount::new(0, U256::ZERO).extend_storage([
+                    (B256::with_last_byte(1), U256::from(42)),
+                    (B256::with_last_byte(2), U256::from(43)),
+                ]),
+            );
+        }
+        let mut db = CacheDB::new(StateProviderDatabase::new(provider));
+
+        let slots = vec![B256::with_last_byte(1), B256::with_last_byte(2)];
+        let request = TransactionRequest {
+            access_list: Some(reth_rpc_types::AccessList(vec![
+                reth_rpc_types::AccessListItem { address: diff, storage_keys: slots.clone() },
+                reth_rpc_types::AccessListItem { address: replaced, storage_keys: slots },
+            ])),
+            ..Default::default()
+        };
+        let state_override = [
+            (
+                diff,
+                AccountOverride {
+                    state_diff: Some(
+                        [(B256::with_last_byte(1), B256::with_last_byte(1))].into_iter().collect(),
+                    ),
+                    ..Default::default()
+                },
+            ),
+            (
+                replaced,
+                AccountOverride {
+                    state: Some(
+                        [(B256::with_last_byte(1), B256::with_last_byte(1))].into_iter().collect(),
+                    ),
+                    ..Default::default()
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        prepare_call_env(
+            CfgEnvWithHandlerCfg::new_with_spec_id(Default::default(), SpecId::CANCUN),
+            BlockEnv::default(),
+            request,
+            30_000_000,
+            &mut db,
+            EvmOverrides::new(Some(state_override), None),
+        )
+        .unwrap();
+
+        // the overridden slot is kept, the other declared slot is read from the database
+        let storage = &db.accounts[&diff].storage;
+        assert_eq!(storage.get(&U256::from(1)), Some(&U256::from(1)));
+        assert_eq!(storage.get(&U256::from(2)), Some(&U256::from(43)));
+
+        // the storage of the account is replaced entirely
+        let storage = &db.accounts[&replaced].storage;
+        assert_eq!(storage.get(&U256::from(1)), Some(&U256::from(1)));
+        assert_eq!(storage.get(&U256::from(2)), None);
+    }
+
+    #[test]
+    fn block_hash_overrides_are_read_by_blockhash() {
+        let contract = Address::with_last_byte(1);
+        // returns the hash of block 1
+        let code = Bytes::from_static(&hex!("60014060005260206000f3"));
+        let block_hash = B256::repeat_byte(0x11);
+
+        // the hash is read from the block hashes of the database before Prague and from the
+        // history storage contract since Prague
+        for spec_id in [SpecId::CANCUN, SpecId::PRAGUE] {
+            let provider = MockEthProvider::default();
+            provider.add_account(
+                contract,
+                ExtendedAccount::new(0, U256::ZERO).with_bytecode(code.clone()),
+            );
+            let mut db = CacheDB::new(StateProviderDatabase::new(provider));
+
+            let mut block_env = BlockEnv::default();
+            let overrides = BlockOverrides {
+                number: Some(U256::from(2)),
+                block_hash: Some(BTreeMap::from([(1, block_hash)])),
+                ..Default::default()
+            };
+            apply_block_overrides(overrides, spec_id, &mut db, &mut block_env).unwrap();
+
+            let env = EnvWithHandlerCfg::new_with_cfg_env(
+                CfgEnvWithHandlerCfg::new_with_spec_id(Default::default(), spec_id),
+                block_env,
+                TxEnv {
+                    transact_to: TransactTo::Call(contract),
+                    gas_limit: 100_000,
+                    ..Default::default()
+                },
+            );
+            let res = revm::Evm::builder()
+                .with_db(&mut db)
+                .with_env_with_handler_cfg(env)
+                .build()
+                .transact()
+                .unwrap();
+            assert_eq!(
+                res.result.output(),
+                Some(&Bytes::copy_from_slice(block_hash.as_slice())),
+                "{spec_id:?}"
+            );
+        }
+    }
 }
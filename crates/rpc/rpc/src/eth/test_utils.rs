@@ -0,0 +1,71 @@
+//! Utilities for testing the `eth` namespace.
+
+use crate::eth::{
+    cache::EthStateCache, gas_oracle::GasPriceOracle, EthApi, FeeHistoryCache,
+    FeeHistoryCacheConfig,
+};
+use reth_evm_ethereum::EthEvmConfig;
+use reth_network_api::noop::NoopNetwork;
+use reth_primitives::constants::ETHEREUM_BLOCK_GAS_LIMIT;
+use reth_provider::{
+    BlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory,
+};
+use reth_rpc_server_types::constants::{
+    DEFAULT_ETH_PROOF_WINDOW, DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT,
+    DEFAULT_RPC_MEMORY_LIMIT, DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT,
+};
+use reth_tasks::pool::BlockingTaskPool;
+use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+
+/// The [`EthApi`] built by [`build_test_eth_api`].
+pub(crate) type TestEthApi<P> = EthApi<P, TestPool, NoopNetwork, EthEvmConfig>;
+
+/// Builds an [`EthApi`] with the default configuration on top of the given provider and an empty
+/// testing pool.
+pub(crate) fn build_test_eth_api<P>(provider: P) -> TestEthApi<P>
+where
+    P: BlockReaderIdExt
+        + BlockReader
+        + ChainSpecProvider
+        + EvmEnvProvider
+        + StateProviderFactory
+        + Unpin
+        + Clone
+        + 'static,
+{
+    build_test_eth_api_with_pool(provider, testing_pool())
+}
+
+/// Builds an [`EthApi`] with the default configuration on top of the given provider and pool.
+pub(crate) fn build_test_eth_api_with_pool<P>(provider: P, pool: TestPool) -> TestEthApi<P>
+where
+    P: BlockReaderIdExt
+        + BlockReader
+        + ChainSpecProvider
+        + EvmEnvProvider
+        + StateProviderFactory
+        + Unpin
+        + Clone
+        + 'static,
+{
+    let evm_config = EthEvmConfig::default();
+    let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+    let fee_history_cache = FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
+
+    EthApi::new(
+        provider.clone(),
+        pool,
+        NoopNetwork::default(),
+        cache.clone(),
+        GasPriceOracle::new(provider, Default::default(), fee_history_cache.clone()),
+        ETHEREUM_BLOCK_GAS_LIMIT,
+        DEFAULT_ETH_PROOF_WINDOW,
+        DEFAULT_RPC_MEMORY_LIMIT,
+        DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT,
+        DEFAULT_MAX_SEND_RAW_TRANSACTION_SYNC_TIMEOUT,
+        BlockingTaskPool::build().expect("failed to build tracing pool"),
+        fee_history_cache,
+        evm_config,
+        None,
+    )
+}
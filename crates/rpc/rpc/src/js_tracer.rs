@@ -0,0 +1,482 @@
+//! Custom JavaScript tracers of the `debug` namespace.
+//!
+//! The tracers are executed by the embedded [boa](https://github.com/boa-dev/boa) engine which
+//! has no access to the file system or the network. The tracers share the wall-clock budget of
+//! their request, once it is exceeded the execution of the EVM is aborted and no function of the
+//! tracer is called anymore.
+//!
+//! A single function of a tracer can't be interrupted by the engine, so every traced call runs on
+//! a dedicated thread that reads the state through the caller, see [`JsTracerThreads`]. If the
+//! tracer doesn't return within the budget, the request fails and the thread is abandoned.
+
+use crate::{
+    eth::error::{EthApiError, EthResult},
+    trace_guard::TraceBudget,
+};
+use reth_primitives::{Address, B256, U256};
+use revm::{
+    primitives::{AccountInfo, Bytecode, EvmState, ResultAndState},
+    Database, DatabaseRef,
+};
+use revm_primitives::Env;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    mpsc::{self, RecvTimeoutError, Sender, SyncSender},
+    Arc,
+};
+
+#[cfg(feature = "js-tracer")]
+pub(crate) use revm_inspectors::tracing::js::TransactionContext;
+
+#[cfg(feature = "js-tracer")]
+use crate::trace_guard::timed_out_result;
+#[cfg(feature = "js-tracer")]
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CreateInputs, CreateOutcome, InstructionResult, Interpreter,
+    },
+    primitives::Log,
+    EvmContext, Inspector,
+};
+#[cfg(feature = "js-tracer")]
+use revm_inspectors::tracing::js::JsInspector;
+
+/// What the `result` function of a tracer returns and the state changed by the traced call.
+pub(crate) type JsTraceOutput = (serde_json::Value, EvmState);
+
+/// Caps the number of threads that execute JavaScript tracers.
+///
+/// A thread whose tracer didn't return within the budget of its request keeps its slot until the
+/// tracer returns, if ever, so tracers that loop forever can't take down the node but only the
+/// JavaScript tracing capacity.
+#[derive(Debug, Clone)]
+pub(crate) struct JsTracerThreads {
+    /// The maximum number of threads.
+    max_threads: usize,
+    /// The number of threads that are running, including abandoned ones.
+    running: Arc<AtomicUsize>,
+}
+
+impl JsTracerThreads {
+    /// Creates the cap of the given number of threads.
+    pub(crate) fn new(max_threads: usize) -> Self {
+        Self { max_threads, running: Default::default() }
+    }
+
+    /// Takes a slot if not all threads are running.
+    fn try_acquire(&self) -> Option<JsTracerThreadSlot> {
+        self.running
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |running| {
+                (running < self.max_threads).then_some(running + 1)
+            })
+            .ok()?;
+        Some(JsTracerThreadSlot { running: self.running.clone() })
+    }
+
+    /// Executes the tracer on a dedicated thread and waits for it until the budget of the request
+    /// is exhausted.
+    ///
+    /// The tracer reads the state from the given database through a [`ChannelDatabase`], the
+    /// database never leaves the calling thread.
+    ///
+    /// Caution: this is blocking and should be performed on a blocking task.
+    pub(crate) fn run<DB, F>(
+        &self,
+        db: &mut DB,
+        budget: &TraceBudget,
+        f: F,
+    ) -> EthResult<JsTraceOutput>
+    where
+        DB: Database,
+        <DB as Database>::Error: Into<EthApiError>,
+        F: FnOnce(&mut ChannelDatabase) -> EthResult<JsTraceOutput> + Send + 'static,
+    {
+        let slot = self.try_acquire().ok_or_else(|| {
+            EthApiError::InternalJsTracerError(
+                "too many JavaScript tracers are running".to_string(),
+            )
+        })?;
+
+        let (messages, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("js-tracer".to_string())
+            .spawn(move || {
+                let _slot = slot;
+                let mut db = ChannelDatabase { messages: messages.clone() };
+                let output = f(&mut db);
+                let _ = messages.send(TracerMessage::Done(output));
+            })
+            .map_err(|err| EthApiError::InternalJsTracerError(err.to_string()))?;
+
+        loop {
+            match rx.recv_timeout(budget.remaining()) {
+                Ok(TracerMessage::Read(request)) => request.serve(db),
+                Ok(TracerMessage::Done(output)) => return output,
+                // dropping the receiver fails the next read of the tracer
+                Err(RecvTimeoutError::Timeout) => return Err(budget.timed_out()),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(EthApiError::InternalJsTracerError(
+                        "JavaScript tracer panicked".to_string(),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// A slot of [`JsTracerThreads`] that is released when the thread holding it exits.
+#[derive(Debug)]
+struct JsTracerThreadSlot {
+    running: Arc<AtomicUsize>,
+}
+
+impl Drop for JsTracerThreadSlot {
+    fn drop(&mut self) {
+        self.running.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// A message of a tracer thread to the thread that waits for it.
+enum TracerMessage {
+    /// The tracer reads from the database of the waiting thread.
+    Read(DbRequest),
+    /// The tracer returned.
+    Done(EthResult<JsTraceOutput>),
+}
+
+/// A read of a [`ChannelDatabase`] and the channel of its response.
+enum DbRequest {
+    Basic(Address, SyncSender<EthResult<Option<AccountInfo>>>),
+    CodeByHash(B256, SyncSender<EthResult<Bytecode>>),
+    Storage(Address, U256, SyncSender<EthResult<U256>>),
+    BlockHash(U256, SyncSender<EthResult<B256>>),
+}
+
+impl DbRequest {
+    /// Reads from the database and responds to the tracer.
+    fn serve<DB>(self, db: &mut DB)
+    where
+        DB: Database,
+        <DB as Database>::Error: Into<EthApiError>,
+    {
+        // the tracer thread is blocked on the response, so the sends can't fail
+        match self {
+            Self::Basic(address, tx) => {
+                let _ = tx.send(db.basic(address).map_err(Into::into));
+            }
+            Self::CodeByHash(code_hash, tx) => {
+                let _ = tx.send(db.code_by_hash(code_hash).map_err(Into::into));
+            }
+            Self::Storage(address, index, tx) => {
+                let _ = tx.send(db.storage(address, index).map_err(Into::into));
+            }
+            Self::BlockHash(number, tx) => {
+                let _ = tx.send(db.block_hash(number).map_err(Into::into));
+            }
+        }
+    }
+}
+
+/// The database of a tracer thread, every read is served by the thread that waits for the tracer.
+///
+/// Reads fail once the waiting thread gave up on the tracer.
+pub(crate) struct ChannelDatabase {
+    messages: Sender<TracerMessage>,
+}
+
+impl ChannelDatabase {
+    /// Sends the request to the waiting thread and blocks until it responds.
+    fn read<T>(&self, request: impl FnOnce(SyncSender<EthResult<T>>) -> DbRequest) -> EthResult<T> {
+        let abandoned =
+            || EthApiError::InternalJsTracerError("JavaScript tracer was abandoned".to_string());
+        let (tx, rx) = mpsc::sync_channel(1);
+        self.messages.send(TracerMessage::Read(request(tx))).map_err(|_| abandoned())?;
+        rx.recv().map_err(|_| abandoned())?
+    }
+}
+
+impl DatabaseRef for ChannelDatabase {
+    type Error = EthApiError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.read(|tx| DbRequest::Basic(address, tx))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.read(|tx| DbRequest::CodeByHash(code_hash, tx))
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.read(|tx| DbRequest::Storage(address, index, tx))
+    }
+
+    fn block_hash_ref(&self, number: U256) -> Result<B256, Self::Error> {
+        self.read(|tx| DbRequest::BlockHash(number, tx))
+    }
+}
+
+impl Database for ChannelDatabase {
+    type Error = EthApiError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.basic_ref(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.code_by_hash_ref(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.storage_ref(address, index)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        self.block_hash_ref(number)
+    }
+}
+
+/// Traces a call with a custom JavaScript tracer and halts the execution once the budget of the
+/// request is exhausted.
+#[cfg(feature = "js-tracer")]
+pub(crate) struct JsTracer {
+    inspector: JsInspector,
+    /// The budget of the request.
+    budget: TraceBudget,
+    /// Whether the execution was aborted.
+    timed_out: bool,
+}
+
+#[cfg(feature = "js-tracer")]
+impl JsTracer {
+    /// Compiles the tracer, the compilation counts against the budget of the request.
+    pub(crate) fn new(
+        code: String,
+        config: serde_json::Value,
+        transaction_context: TransactionContext,
+        budget: TraceBudget,
+    ) -> EthResult<Self> {
+        let inspector = JsInspector::with_transaction_context(code, config, transaction_context)?;
+        Ok(Self { inspector, budget, timed_out: false })
+    }
+
+    /// Returns whether the budget of the request is exhausted.
+    ///
+    /// Once exhausted, none of the hooks of the tracer are called anymore, so the call stack of
+    /// the tracer may be incomplete, which is fine since its result is discarded.
+    fn is_timed_out(&mut self) -> bool {
+        if !self.timed_out && self.budget.is_exceeded() {
+            self.timed_out = true;
+        }
+        self.timed_out
+    }
+
+    /// Returns what the `result` function of the tracer returns for the traced call.
+    ///
+    /// Returns an error if the execution was aborted.
+    pub(crate) fn into_result<DB>(
+        mut self,
+        res: ResultAndState,
+        env: &Env,
+        db: &DB,
+    ) -> EthResult<serde_json::Value>
+    where
+        DB: DatabaseRef,
+        <DB as DatabaseRef>::Error: std::fmt::Display,
+    {
+        if self.timed_out {
+            return Err(self.budget.timed_out())
+        }
+        Ok(self.inspector.json_result(res, env, db)?)
+    }
+}
+
+#[cfg(feature = "js-tracer")]
+impl<DB> Inspector<DB> for JsTracer
+where
+    DB: Database,
+    JsInspector: Inspector<DB>,
+{
+    fn initialize_interp(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if !self.is_timed_out() {
+            self.inspector.initialize_interp(interp, context)
+        }
+    }
+
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if self.is_timed_out() {
+            // halt every frame that is still executing, so the whole execution unwinds
+            interp.instruction_result = InstructionResult::OutOfGas;
+            return
+        }
+        self.inspector.step(interp, context)
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if !self.is_timed_out() {
+            self.inspector.step_end(interp, context)
+        }
+    }
+
+    fn log(&mut self, context: &mut EvmContext<DB>, log: &Log) {
+        if !self.is_timed_out() {
+            self.inspector.log(context, log)
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        if self.is_timed_out() {
+            return Some(CallOutcome::new(
+                timed_out_result(inputs.gas_limit),
+                inputs.return_memory_offset.clone(),
+            ))
+        }
+        self.inspector.call(context, inputs)
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        if self.is_timed_out() {
+            return outcome
+        }
+        self.inspector.call_end(context, inputs, outcome)
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        if self.is_timed_out() {
+            return Some(CreateOutcome::new(timed_out_result(inputs.gas_limit), None))
+        }
+        self.inspector.create(context, inputs)
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        if self.is_timed_out() {
+            return outcome
+        }
+        self.inspector.create_end(context, inputs, outcome)
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if !self.is_timed_out() {
+            Inspector::<DB>::selfdestruct(&mut self.inspector, contract, target, value)
+        }
+    }
+}
+
+/// Stand-in for the JavaScript tracer if reth is built without the `js-tracer` feature, it can't
+/// be constructed.
+#[cfg(not(feature = "js-tracer"))]
+pub(crate) enum JsTracer {}
+
+#[cfg(not(feature = "js-tracer"))]
+impl JsTracer {
+    /// Always returns an error, JavaScript tracers are not supported.
+    pub(crate) fn new(
+        _code: String,
+        _config: serde_json::Value,
+        _transaction_context: TransactionContext,
+        _budget: TraceBudget,
+    ) -> EthResult<Self> {
+        Err(EthApiError::Unsupported("reth was built without JavaScript tracer support"))
+    }
+
+    /// Returns what the `result` function of the tracer returns for the traced call.
+    pub(crate) fn into_result<DB>(
+        self,
+        _res: ResultAndState,
+        _env: &Env,
+        _db: &DB,
+    ) -> EthResult<serde_json::Value> {
+        match self {}
+    }
+}
+
+#[cfg(not(feature = "js-tracer"))]
+impl<DB: Database> revm::Inspector<DB> for JsTracer {}
+
+/// The context of the traced transaction that is exposed to JavaScript tracers.
+#[cfg(not(feature = "js-tracer"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct TransactionContext {
+    /// Hash of the block the transaction is contained within.
+    pub(crate) block_hash: Option<B256>,
+    /// Index of the transaction within a block.
+    pub(crate) tx_index: Option<usize>,
+    /// Hash of the transaction.
+    pub(crate) tx_hash: Option<B256>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace_guard::TracingMetrics;
+    use reth_provider::test_utils::NoopProvider;
+    use reth_revm::database::StateProviderDatabase;
+    use revm::db::CacheDB;
+    use std::time::Duration;
+
+    fn test_db() -> CacheDB<StateProviderDatabase<NoopProvider>> {
+        CacheDB::new(StateProviderDatabase::new(NoopProvider::default()))
+    }
+
+    #[test]
+    fn tracer_reads_through_the_caller() {
+        let address = Address::with_last_byte(1);
+        let mut db = test_db();
+        db.insert_account_info(
+            address,
+            AccountInfo { balance: U256::from(7), ..Default::default() },
+        );
+
+        let threads = JsTracerThreads::new(1);
+        let budget = TraceBudget::start(Duration::from_secs(5), TracingMetrics::default());
+        let (result, _) = threads
+            .run(&mut db, &budget, move |db| {
+                let balance = db.basic(address)?.unwrap_or_default().balance;
+                Ok((serde_json::json!(balance), Default::default()))
+            })
+            .unwrap();
+        assert_eq!(result, serde_json::json!(U256::from(7)));
+    }
+
+    #[test]
+    fn tracer_that_doesnt_return_is_abandoned() {
+        let threads = JsTracerThreads::new(1);
+        let (release, released) = mpsc::channel::<()>();
+        let budget = TraceBudget::start(Duration::from_millis(50), TracingMetrics::default());
+        let err = threads
+            .run(&mut test_db(), &budget, move |_| {
+                let _ = released.recv();
+                Ok(Default::default())
+            })
+            .unwrap_err();
+        assert!(matches!(err, EthApiError::ExecutionTimedOut(_)), "{err:?}");
+
+        // the abandoned tracer still holds the only thread
+        let budget = TraceBudget::start(Duration::from_secs(5), TracingMetrics::default());
+        let err = threads.run(&mut test_db(), &budget, |_| Ok(Default::default())).unwrap_err();
+        assert!(matches!(err, EthApiError::InternalJsTracerError(_)), "{err:?}");
+
+        release.send(()).unwrap();
+        while threads.running.load(Ordering::Acquire) > 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        threads.run(&mut test_db(), &budget, |_| Ok(Default::default())).unwrap();
+    }
+}
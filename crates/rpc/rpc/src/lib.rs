@@ -33,25 +33,42 @@ use pin_project as _;
 use tower as _;
 
 mod admin;
+mod connection;
+mod custom_tracer;
 mod debug;
 mod engine;
 pub mod eth;
+mod js_tracer;
 mod net;
 mod otterscan;
+mod parallel_trace;
+mod payload_events;
 mod reth;
+mod reth_pubsub;
 mod rpc;
 mod trace;
+mod trace_guard;
 mod txpool;
+mod validation;
+mod vm_trace;
 mod web3;
 pub use admin::AdminApi;
-pub use debug::DebugApi;
+pub use connection::RpcConnectionId;
+pub use custom_tracer::{
+    CustomTracer, CustomTracerError, TracerDatabase, TracerFactory, TracerInspector,
+    TracerRegistry, TracerResultFn,
+};
+pub use debug::{DebugApi, DebugApiConfig};
 pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthApiSpec, EthFilter, EthPubSub, EthSubscriptionIdProvider};
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
+pub use payload_events::RethPayloadEvents;
 pub use reth::RethApi;
+pub use reth_pubsub::{RethPubSub, MAX_REORG_BLOCKS_PER_NOTIFICATION};
 pub use rpc::RPCApi;
-pub use trace::TraceApi;
+pub use trace::{TraceApi, TraceApiConfig};
 pub use txpool::TxPoolApi;
+pub use validation::{ValidationApi, ValidationApiError};
 pub use web3::Web3Api;
 pub mod result;
@@ -4,30 +4,257 @@ use jsonrpsee::core::RpcResult;
 use revm_inspectors::transfer::{TransferInspector, TransferKind};
 use revm_primitives::ExecutionResult;
 
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, TxHash, B256};
+use reth_primitives::{Address, BlockId, BlockNumber, BlockNumberOrTag, TxHash, B256};
+use reth_provider::AddressTransactionsReader;
 use reth_rpc_api::{EthApiServer, OtterscanServer};
 use reth_rpc_types::{
     trace::otterscan::{
         BlockDetails, ContractCreator, InternalOperation, OperationType, OtsBlockTransactions,
         OtsReceipt, OtsTransactionReceipt, TraceEntry, TransactionsWithReceipts,
     },
-    BlockTransactions, Transaction,
+    AnyTransactionReceipt, BlockTransactions, Transaction,
 };
+use std::sync::Arc;
 
-use crate::{eth::EthTransactions, result::internal_rpc_err};
+use crate::{
+    eth::{error::EthApiError, EthTransactions},
+    result::internal_rpc_err,
+};
 
 const API_LEVEL: u64 = 8;
 
+/// The API level if the blocks with transactions of an address are indexed, which makes the
+/// search of the transactions of an address cheap.
+const INDEXED_API_LEVEL: u64 = 9;
+
+/// The maximum number of blocks that are scanned by a search for the transactions of an address,
+/// if the blocks are not indexed.
+///
+/// Once reached, the transactions found so far are returned as a page that is not the last one.
+pub const DEFAULT_MAX_SCANNED_BLOCKS: u64 = 1_000;
+
 /// Otterscan API.
-#[derive(Debug)]
 pub struct OtterscanApi<Eth> {
     eth: Eth,
+    /// The index of the blocks with transactions of an address, the blocks are scanned if unset.
+    address_transactions: Arc<parking_lot::RwLock<Option<Arc<dyn AddressTransactionsReader>>>>,
+    /// The maximum number of blocks that are scanned per search request.
+    max_scanned_blocks: u64,
 }
 
 impl<Eth> OtterscanApi<Eth> {
     /// Creates a new instance of `Otterscan`.
-    pub const fn new(eth: Eth) -> Self {
-        Self { eth }
+    pub fn new(eth: Eth) -> Self {
+        Self {
+            eth,
+            address_transactions: Default::default(),
+            max_scanned_blocks: DEFAULT_MAX_SCANNED_BLOCKS,
+        }
+    }
+
+    /// Sets the maximum number of blocks that are scanned per search request if the blocks are
+    /// not indexed, see [`DEFAULT_MAX_SCANNED_BLOCKS`].
+    pub fn with_max_scanned_blocks(mut self, max_scanned_blocks: u64) -> Self {
+        self.max_scanned_blocks = max_scanned_blocks.max(1);
+        self
+    }
+
+    /// Sets the reader of the index of the blocks with transactions of an address, which is used
+    /// by `ots_searchTransactionsBefore` and `ots_searchTransactionsAfter` for the blocks it
+    /// covers.
+    pub fn set_address_transactions_reader(&self, reader: Arc<dyn AddressTransactionsReader>) {
+        self.address_transactions.write().replace(reader);
+    }
+
+    /// Returns the index of the blocks with transactions of an address and the highest block it
+    /// covers, if the index exists.
+    fn address_transactions_index(
+        &self,
+    ) -> RpcResult<Option<(Arc<dyn AddressTransactionsReader>, BlockNumber)>> {
+        let Some(reader) = self.address_transactions.read().clone() else { return Ok(None) };
+        let checkpoint = reader.address_transactions_checkpoint().map_err(EthApiError::from)?;
+        Ok(checkpoint.map(|checkpoint| (reader, checkpoint)))
+    }
+}
+
+impl<Eth: Clone> Clone for OtterscanApi<Eth> {
+    fn clone(&self) -> Self {
+        Self {
+            eth: self.eth.clone(),
+            address_transactions: self.address_transactions.clone(),
+            max_scanned_blocks: self.max_scanned_blocks,
+        }
+    }
+}
+
+impl<Eth> std::fmt::Debug for OtterscanApi<Eth> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OtterscanApi").finish_non_exhaustive()
+    }
+}
+
+impl<Eth> OtterscanApi<Eth>
+where
+    Eth: EthApiServer + EthTransactions,
+{
+    /// Returns the transactions of the block the address is part of, with their receipts, in
+    /// block order.
+    ///
+    /// An address is part of a transaction if it's the sender, the recipient or the created
+    /// contract of the transaction, or if it emitted a log during its execution. A block that
+    /// doesn't exist has no transactions.
+    async fn address_transactions_in_block(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+    ) -> RpcResult<Vec<(Transaction, OtsTransactionReceipt)>> {
+        let block = self.eth.block_by_number(block_number.into(), true);
+        let receipts = self.eth.block_receipts(BlockId::Number(block_number.into()));
+        let (block, receipts) = futures::try_join!(block, receipts)?;
+
+        let Some(block) = block else { return Ok(Vec::new()) };
+        let receipts = receipts.ok_or_else(|| internal_rpc_err("receipts not found"))?;
+        let BlockTransactions::Full(transactions) = block.inner.transactions else {
+            return Err(internal_rpc_err("block is not full"))
+        };
+        if transactions.len() != receipts.len() {
+            return Err(internal_rpc_err(
+                "the number of transactions does not match the number of receipts",
+            ))
+        }
+
+        let timestamp = Some(block.inner.header.timestamp);
+        Ok(transactions
+            .into_iter()
+            .zip(receipts)
+            .filter(|(_, receipt)| {
+                receipt.inner.from == address ||
+                    receipt.inner.to == Some(address) ||
+                    receipt.inner.contract_address == Some(address) ||
+                    receipt
+                        .inner
+                        .inner
+                        .inner
+                        .receipt
+                        .logs
+                        .iter()
+                        .any(|log| log.inner.address == address)
+            })
+            .map(|(tx, receipt)| (tx, ots_receipt(receipt, timestamp)))
+            .collect())
+    }
+
+    /// Searches the transactions of the address in the blocks below the given block, or from the
+    /// tip if it's `0`, until at least `page_size` transactions are found.
+    ///
+    /// The blocks that are indexed are looked up in the index, the others are scanned, at most
+    /// `max_scanned_blocks` per request.
+    async fn search_transactions_before_block(
+        &self,
+        address: Address,
+        block_number: BlockNumberOrTag,
+        page_size: usize,
+    ) -> RpcResult<TransactionsWithReceipts> {
+        let tip = self.eth.block_number()?.saturating_to::<BlockNumber>();
+        let first_page = !matches!(block_number, BlockNumberOrTag::Number(number) if number != 0);
+        // the transactions are searched below this block
+        let mut block = match block_number {
+            BlockNumberOrTag::Number(number) if number != 0 => number.min(tip + 1),
+            _ => tip + 1,
+        };
+        let index = self.address_transactions_index()?;
+
+        let mut transactions = Vec::new();
+        let mut scanned = 0;
+        let mut has_more = block > 0;
+        while has_more && transactions.len() < page_size {
+            let blocks = match &index {
+                Some((reader, checkpoint)) if block <= checkpoint + 1 => reader
+                    .address_transaction_blocks_before(address, block, page_size)
+                    .map_err(EthApiError::from)?,
+                _ if scanned >= self.max_scanned_blocks => break,
+                _ => {
+                    scanned += 1;
+                    vec![block - 1]
+                }
+            };
+            if blocks.is_empty() {
+                has_more = false;
+                break
+            }
+
+            for number in blocks {
+                block = number;
+                let mut found = self.address_transactions_in_block(address, number).await?;
+                found.reverse();
+                transactions.extend(found);
+                if transactions.len() >= page_size {
+                    break
+                }
+            }
+            has_more = block > 0;
+        }
+
+        let (txs, receipts) = transactions.into_iter().unzip();
+        Ok(TransactionsWithReceipts { txs, receipts, first_page, last_page: !has_more })
+    }
+
+    /// Searches the transactions of the address in the blocks above the given block until at
+    /// least `page_size` transactions are found.
+    ///
+    /// The blocks that are indexed are looked up in the index, the others are scanned, at most
+    /// `max_scanned_blocks` per request.
+    async fn search_transactions_after_block(
+        &self,
+        address: Address,
+        block_number: BlockNumberOrTag,
+        page_size: usize,
+    ) -> RpcResult<TransactionsWithReceipts> {
+        let tip = self.eth.block_number()?.saturating_to::<BlockNumber>();
+        // the transactions are searched above this block
+        let mut block = block_number.as_number().unwrap_or_default();
+        let last_page = block == 0;
+        let index = self.address_transactions_index()?;
+
+        let mut transactions = Vec::new();
+        let mut scanned = 0;
+        let mut has_more = block < tip;
+        while has_more && transactions.len() < page_size {
+            let blocks = match &index {
+                Some((reader, checkpoint)) if block < *checkpoint => {
+                    let mut blocks = reader
+                        .address_transaction_blocks_after(address, block, page_size)
+                        .map_err(EthApiError::from)?;
+                    blocks.retain(|number| number <= checkpoint);
+                    if blocks.is_empty() {
+                        // no more indexed blocks, scan the rest
+                        block = *checkpoint;
+                        has_more = block < tip;
+                        continue
+                    }
+                    blocks
+                }
+                _ if scanned >= self.max_scanned_blocks => break,
+                _ => {
+                    scanned += 1;
+                    vec![block + 1]
+                }
+            };
+
+            for number in blocks {
+                block = number;
+                transactions.extend(self.address_transactions_in_block(address, number).await?);
+                if transactions.len() >= page_size {
+                    break
+                }
+            }
+            has_more = block < tip;
+        }
+
+        // the transactions are returned in descending order
+        transactions.reverse();
+        let (txs, receipts) = transactions.into_iter().unzip();
+        Ok(TransactionsWithReceipts { txs, receipts, first_page: !has_more, last_page })
     }
 }
 
@@ -42,7 +269,12 @@ where
     }
 
     /// Handler for `ots_getApiLevel`
+    ///
+    /// Returns a higher API level if the blocks with transactions of an address are indexed.
     async fn get_api_level(&self) -> RpcResult<u64> {
+        if self.address_transactions_index()?.is_some() {
+            return Ok(INDEXED_API_LEVEL)
+        }
         Ok(API_LEVEL)
     }
 
@@ -134,7 +366,7 @@ where
 
         // make sure the block is full
         let BlockTransactions::Full(transactions) = &mut block.inner.transactions else {
-            return Err(internal_rpc_err("block is not full"));
+            return Err(internal_rpc_err("block is not full"))
         };
 
         // Crop page
@@ -156,17 +388,7 @@ where
         let timestamp = Some(block.header.timestamp);
         let receipts = receipts
             .drain(page_start..page_end)
-            .map(|receipt| {
-                let receipt = receipt.inner.map_inner(|receipt| OtsReceipt {
-                    status: receipt.inner.receipt.status,
-                    cumulative_gas_used: receipt.inner.receipt.cumulative_gas_used as u64,
-                    logs: None,
-                    logs_bloom: None,
-                    r#type: receipt.r#type,
-                });
-
-                OtsTransactionReceipt { receipt, timestamp }
-            })
+            .map(|receipt| ots_receipt(receipt, timestamp))
             .collect();
         Ok(OtsBlockTransactions { fullblock: block.inner.into(), receipts })
     }
@@ -174,21 +396,21 @@ where
     /// Handler for `searchTransactionsBefore`
     async fn search_transactions_before(
         &self,
-        _address: Address,
-        _block_number: BlockNumberOrTag,
-        _page_size: usize,
+        address: Address,
+        block_number: BlockNumberOrTag,
+        page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        self.search_transactions_before_block(address, block_number, page_size).await
     }
 
     /// Handler for `searchTransactionsAfter`
     async fn search_transactions_after(
         &self,
-        _address: Address,
-        _block_number: BlockNumberOrTag,
-        _page_size: usize,
+        address: Address,
+        block_number: BlockNumberOrTag,
+        page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        self.search_transactions_after_block(address, block_number, page_size).await
     }
 
     /// Handler for `getTransactionBySenderAndNonce`
@@ -205,3 +427,204 @@ where
         Err(internal_rpc_err("unimplemented"))
     }
 }
+
+/// Converts the receipt of a transaction into the receipt of the Otterscan API, without logs.
+fn ots_receipt(receipt: AnyTransactionReceipt, timestamp: Option<u64>) -> OtsTransactionReceipt {
+    let receipt = receipt.inner.map_inner(|receipt| OtsReceipt {
+        status: receipt.inner.receipt.status,
+        cumulative_gas_used: receipt.inner.receipt.cumulative_gas_used as u64,
+        logs: None,
+        logs_bloom: None,
+        r#type: receipt.r#type,
+    });
+
+    OtsTransactionReceipt { receipt, timestamp }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::test_utils::{build_test_eth_api, TestEthApi};
+    use reth_primitives::{
+        public_key_to_address, Block, Header, Receipt, Transaction, TxKind, TxLegacy,
+    };
+    use reth_provider::{test_utils::MockEthProvider, ProviderResult};
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use secp256k1::{Keypair, Secp256k1};
+
+    /// The blocks with a transaction of the searched address.
+    const ADDRESS_BLOCKS: [BlockNumber; 3] = [2, 5, 8];
+
+    /// The last block of the test chain.
+    const TIP: BlockNumber = 9;
+
+    /// An index that covers the blocks up to its checkpoint.
+    struct TestIndex {
+        checkpoint: BlockNumber,
+        blocks: Vec<BlockNumber>,
+    }
+
+    impl AddressTransactionsReader for TestIndex {
+        fn address_transactions_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+            Ok(Some(self.checkpoint))
+        }
+
+        fn address_transaction_blocks_before(
+            &self,
+            _address: Address,
+            block_number: BlockNumber,
+            limit: usize,
+        ) -> ProviderResult<Vec<BlockNumber>> {
+            Ok(self
+                .blocks
+                .iter()
+                .rev()
+                .copied()
+                .filter(|b| *b < block_number)
+                .take(limit)
+                .collect())
+        }
+
+        fn address_transaction_blocks_after(
+            &self,
+            _address: Address,
+            block_number: BlockNumber,
+            limit: usize,
+        ) -> ProviderResult<Vec<BlockNumber>> {
+            Ok(self.blocks.iter().copied().filter(|b| *b > block_number).take(limit).collect())
+        }
+    }
+
+    /// Returns the API on top of a chain with a transaction in every block, which is sent by the
+    /// returned address in [`ADDRESS_BLOCKS`] and by another account in the other blocks.
+    fn test_api() -> (OtterscanApi<TestEthApi<MockEthProvider>>, Address) {
+        let provider = MockEthProvider::default();
+        let mut rng = generators::rng();
+        let secp = Secp256k1::new();
+        let sender = Keypair::new(&secp, &mut rng);
+        let other = Keypair::new(&secp, &mut rng);
+
+        let mut parent_hash = B256::ZERO;
+        for number in 0..=TIP {
+            let key_pair = if ADDRESS_BLOCKS.contains(&number) { sender } else { other };
+            let transaction = sign_tx_with_key_pair(
+                key_pair,
+                Transaction::Legacy(TxLegacy {
+                    chain_id: Some(1),
+                    nonce: number,
+                    gas_limit: 21_000,
+                    to: TxKind::Call(Address::with_last_byte(1)),
+                    ..Default::default()
+                }),
+            );
+            let header = Header { number, parent_hash, gas_used: 21_000, ..Default::default() };
+            let hash = header.hash_slow();
+            provider
+                .add_block(hash, Block { header, body: vec![transaction], ..Default::default() });
+            provider.add_receipts(
+                hash,
+                vec![Receipt { success: true, cumulative_gas_used: 21_000, ..Default::default() }],
+            );
+            parent_hash = hash;
+        }
+
+        (
+            OtterscanApi::new(build_test_eth_api(provider)),
+            public_key_to_address(sender.public_key()),
+        )
+    }
+
+    /// Returns the blocks of the transactions of the page and whether it's the first and the last
+    /// page.
+    fn page(page: TransactionsWithReceipts) -> (Vec<BlockNumber>, bool, bool) {
+        let blocks = page.txs.iter().map(|tx| tx.block_number.unwrap()).collect();
+        (blocks, page.first_page, page.last_page)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn search_transactions_pages() {
+        let (api, address) = test_api();
+
+        let before = |block, page_size| {
+            let api = api.clone();
+            async move {
+                page(
+                    api.search_transactions_before_block(
+                        address,
+                        BlockNumberOrTag::Number(block),
+                        page_size,
+                    )
+                    .await
+                    .unwrap(),
+                )
+            }
+        };
+        assert_eq!(before(0, 2).await, (vec![8, 5], true, false));
+        assert_eq!(before(5, 2).await, (vec![2], false, true));
+
+        let after = |block, page_size| {
+            let api = api.clone();
+            async move {
+                page(
+                    api.search_transactions_after_block(
+                        address,
+                        BlockNumberOrTag::Number(block),
+                        page_size,
+                    )
+                    .await
+                    .unwrap(),
+                )
+            }
+        };
+        assert_eq!(after(0, 2).await, (vec![5, 2], false, true));
+        assert_eq!(after(5, 2).await, (vec![8], true, false));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn search_transactions_caps_scanned_blocks() {
+        let (api, address) = test_api();
+        let api = api.with_max_scanned_blocks(2);
+
+        // blocks 9 and 8 are scanned, the page is not the last one
+        let before = api
+            .search_transactions_before_block(address, BlockNumberOrTag::Number(0), 2)
+            .await
+            .unwrap();
+        assert_eq!(page(before), (vec![8], true, false));
+
+        // blocks 1 and 2 are scanned
+        let after = api
+            .search_transactions_after_block(address, BlockNumberOrTag::Number(0), 2)
+            .await
+            .unwrap();
+        assert_eq!(page(after), (vec![2], false, true));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn search_transactions_with_index() {
+        let (api, address) = test_api();
+        // the blocks above the checkpoint are scanned, the indexed blocks are not capped
+        let api = api.with_max_scanned_blocks(3);
+        api.set_address_transactions_reader(Arc::new(TestIndex {
+            checkpoint: 6,
+            blocks: ADDRESS_BLOCKS.into_iter().filter(|block| *block <= 6).collect(),
+        }));
+
+        let before = api
+            .search_transactions_before_block(address, BlockNumberOrTag::Number(0), 2)
+            .await
+            .unwrap();
+        assert_eq!(page(before), (vec![8, 5], true, false));
+        let before = api
+            .search_transactions_before_block(address, BlockNumberOrTag::Number(5), 2)
+            .await
+            .unwrap();
+        assert_eq!(page(before), (vec![2], false, true));
+
+        let after = api
+            .search_transactions_after_block(address, BlockNumberOrTag::Number(0), 2)
+            .await
+            .unwrap();
+        assert_eq!(page(after), (vec![5, 2], false, true));
+    }
+}
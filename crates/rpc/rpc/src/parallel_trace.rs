@@ -0,0 +1,138 @@
+//! Tracing the transactions of a block in parallel.
+
+use crate::eth::{error::EthResult, EthTransactions};
+use rayon::prelude::*;
+use reth_primitives::{
+    revm::env::tx_env_with_recovered, Address, BlockId, TransactionSignedEcRecovered, B256,
+};
+use reth_provider::StateProviderBox;
+use reth_revm::database::StateProviderDatabase;
+use revm::{
+    db::{AccountState, CacheDB, DbAccount},
+    primitives::{
+        db::DatabaseCommit, Account, BlockEnv, Bytecode, CfgEnvWithHandlerCfg, Env,
+        EnvWithHandlerCfg, HashMap,
+    },
+};
+
+/// Executes the transactions of a block on top of the given database, which holds the state at
+/// `at` and the changes that precede the transactions, and traces them concurrently.
+///
+/// The block is executed once without an inspector to capture the state every transaction is
+/// executed on, then `trace` is called for every transaction on top of its own pre-state on the
+/// current rayon pool, which is the tracing pool if this is called from a tracing task. The
+/// results are in block order and identical to tracing the transactions one after another.
+///
+/// The transactions are traced in batches of `max_checkpoints`. The pre-state of a transaction is
+/// not copied, instead the state changes of the database at the start of its batch are captured
+/// once per batch and the pre-state is rebuilt from them and the state changes of the prior
+/// transactions of the batch.
+pub(crate) fn trace_block_parallel<Eth, F, R>(
+    eth_api: &Eth,
+    at: BlockId,
+    mut db: CacheDB<StateProviderDatabase<StateProviderBox>>,
+    transactions: &[TransactionSignedEcRecovered],
+    cfg: &CfgEnvWithHandlerCfg,
+    block_env: &BlockEnv,
+    max_checkpoints: usize,
+    trace: F,
+) -> EthResult<Vec<R>>
+where
+    Eth: EthTransactions,
+    F: Fn(
+            usize,
+            &TransactionSignedEcRecovered,
+            EnvWithHandlerCfg,
+            &mut CacheDB<StateProviderDatabase<StateProviderBox>>,
+        ) -> EthResult<R>
+        + Sync,
+    R: Send,
+{
+    let mut results = Vec::with_capacity(transactions.len());
+    let transactions = transactions.iter().enumerate().collect::<Vec<_>>();
+    for batch in transactions.chunks(max_checkpoints.max(1)) {
+        let checkpoint = PreStateCheckpoint::new(&db);
+        let mut diffs = Vec::with_capacity(batch.len());
+        for &(index, tx) in batch {
+            if index + 1 < transactions.len() {
+                let (res, _) = eth_api.transact(&mut db, tx_env_with_cfg(cfg, block_env, tx))?;
+                diffs.push(state_diff(&res.state));
+                db.commit(res.state)
+            }
+        }
+
+        let traces = batch
+            .par_iter()
+            .enumerate()
+            .map(|(pos, &(index, tx))| {
+                let mut db = checkpoint.to_db(eth_api.state_at(at)?, &diffs[..pos]);
+                trace(index, tx, tx_env_with_cfg(cfg, block_env, tx), &mut db)
+            })
+            .collect::<EthResult<Vec<_>>>()?;
+        results.extend(traces);
+    }
+
+    Ok(results)
+}
+
+/// Returns the env to execute the given transaction of a block with.
+pub(crate) fn tx_env_with_cfg(
+    cfg: &CfgEnvWithHandlerCfg,
+    block_env: &BlockEnv,
+    tx: &TransactionSignedEcRecovered,
+) -> EnvWithHandlerCfg {
+    EnvWithHandlerCfg {
+        env: Env::boxed(cfg.cfg_env.clone(), block_env.clone(), tx_env_with_recovered(tx)),
+        handler_cfg: cfg.handler_cfg,
+    }
+}
+
+/// Returns the accounts of the state that were changed by a transaction.
+fn state_diff(state: &HashMap<Address, Account>) -> HashMap<Address, Account> {
+    state.iter().filter(|(_, account)| account.is_touched()).map(|(k, v)| (*k, v.clone())).collect()
+}
+
+/// The state changes that precede a batch of transactions of a block.
+///
+/// Only the accounts that were changed are captured, the accounts and storage that were merely
+/// read are loaded from the state again.
+struct PreStateCheckpoint {
+    accounts: HashMap<Address, DbAccount>,
+    contracts: HashMap<B256, Bytecode>,
+}
+
+impl PreStateCheckpoint {
+    /// Captures the state changes of the given database.
+    fn new<DB>(db: &CacheDB<DB>) -> Self {
+        let accounts = db
+            .accounts
+            .iter()
+            .filter(|(_, account)| !matches!(account.account_state, AccountState::None))
+            .map(|(address, account)| (*address, account.clone()))
+            .collect::<HashMap<_, _>>();
+        let contracts = accounts
+            .values()
+            .filter_map(|account| {
+                let code_hash = account.info.code_hash;
+                db.contracts.get(&code_hash).map(|code| (code_hash, code.clone()))
+            })
+            .collect();
+        Self { accounts, contracts }
+    }
+
+    /// Returns a database with the captured state changes and the given state changes of the
+    /// prior transactions of the batch on top of the given state.
+    fn to_db(
+        &self,
+        state: StateProviderBox,
+        diffs: &[HashMap<Address, Account>],
+    ) -> CacheDB<StateProviderDatabase<StateProviderBox>> {
+        let mut db = CacheDB::new(StateProviderDatabase::new(state));
+        db.accounts.clone_from(&self.accounts);
+        db.contracts.extend(self.contracts.iter().map(|(hash, code)| (*hash, code.clone())));
+        for diff in diffs {
+            db.commit(diff.clone())
+        }
+        db
+    }
+}
@@ -0,0 +1,182 @@
+//! `reth_subscribePayloadEvents` RPC handler implementation
+
+use crate::result::internal_rpc_err;
+use futures::{Stream, StreamExt};
+use jsonrpsee::{server::SubscriptionMessage, PendingSubscriptionSink, SubscriptionSink};
+use reth_engine_primitives::EngineTypes;
+use reth_payload_builder::{Events, PayloadBuilderHandle};
+use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
+use reth_primitives::U64;
+use reth_rpc_api::RethPayloadEventsApiServer;
+use reth_rpc_types::{BuiltPayloadEvent, PayloadEvent, PayloadEventAttributes};
+use reth_tasks::{TaskSpawner, TokioTaskExecutor};
+
+/// `reth` payload events RPC implementation.
+///
+/// This handles the `reth_subscribePayloadEvents` RPC calls, which are only served by the
+/// authenticated server.
+pub struct RethPayloadEvents<Engine: EngineTypes> {
+    /// The handle to the payload builder service the events are subscribed from.
+    payload_builder: PayloadBuilderHandle<Engine>,
+    /// The type that's used to spawn subscription tasks.
+    subscription_task_spawner: Box<dyn TaskSpawner>,
+}
+
+// === impl RethPayloadEvents ===
+
+impl<Engine: EngineTypes> RethPayloadEvents<Engine> {
+    /// Creates a new instance.
+    ///
+    /// Subscription tasks are spawned via [`tokio::task::spawn`]
+    pub fn new(payload_builder: PayloadBuilderHandle<Engine>) -> Self {
+        Self::with_spawner(payload_builder, Box::<TokioTaskExecutor>::default())
+    }
+
+    /// Creates a new instance with the given task spawner.
+    pub fn with_spawner(
+        payload_builder: PayloadBuilderHandle<Engine>,
+        subscription_task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        Self { payload_builder, subscription_task_spawner }
+    }
+}
+
+#[async_trait::async_trait]
+impl<Engine> RethPayloadEventsApiServer for RethPayloadEvents<Engine>
+where
+    Engine: EngineTypes + 'static,
+{
+    /// Handler for `reth_subscribePayloadEvents`
+    async fn subscribe_payload_events(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        // subscribe before accepting, so no event after the subscription is missed
+        let Ok(events) = self.payload_builder.subscribe().await else {
+            pending.reject(internal_rpc_err("payload builder service is unavailable")).await;
+            return Ok(())
+        };
+        let sink = pending.accept().await?;
+        self.subscription_task_spawner
+            .spawn(Box::pin(pipe_payload_events(sink, events.into_lossy_stream())));
+        Ok(())
+    }
+}
+
+/// Sends the payload builder events to the subscription sink, until the subscriber disconnects.
+async fn pipe_payload_events<Engine: EngineTypes>(
+    sink: SubscriptionSink,
+    stream: impl Stream<Item = Events<Engine>> + Unpin,
+) {
+    let mut stream = stream;
+    loop {
+        tokio::select! {
+            _ = sink.closed() => {
+                // connection dropped
+                break
+            },
+            maybe_event = stream.next() => {
+                let Some(event) = maybe_event else { break };
+                let Ok(msg) = SubscriptionMessage::from_json(&payload_event(event)) else { return };
+                if sink.send(msg).await.is_err() {
+                    return
+                }
+            }
+        }
+    }
+}
+
+/// Converts the payload builder event into its RPC representation.
+fn payload_event<Engine: EngineTypes>(event: Events<Engine>) -> PayloadEvent {
+    match event {
+        Events::Attributes(attributes) => PayloadEvent::Attributes(PayloadEventAttributes {
+            id: attributes.payload_id(),
+            parent_hash: attributes.parent(),
+            timestamp: U64::from(attributes.timestamp()),
+            suggested_fee_recipient: attributes.suggested_fee_recipient(),
+            prev_randao: attributes.prev_randao(),
+            parent_beacon_block_root: attributes.parent_beacon_block_root(),
+        }),
+        Events::BuiltPayload { id, payload } => {
+            let block = payload.block();
+            PayloadEvent::BuiltPayload(BuiltPayloadEvent {
+                id,
+                block_hash: block.hash(),
+                block_number: U64::from(block.number),
+                fees: payload.fees(),
+                txs: block.body.iter().map(|tx| tx.hash()).collect(),
+            })
+        }
+    }
+}
+
+impl<Engine: EngineTypes> std::fmt::Debug for RethPayloadEvents<Engine> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RethPayloadEvents").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::{core::server::Subscription, rpc_params};
+    use reth_ethereum_engine_primitives::EthEngineTypes;
+    use reth_payload_builder::{
+        test_utils::spawn_test_payload_service, EthPayloadBuilderAttributes, PayloadStore,
+    };
+    use reth_primitives::{Address, Block, B256};
+    use reth_rpc_types::engine::PayloadAttributes;
+    use std::time::Duration;
+
+    async fn next_event(sub: &mut Subscription) -> PayloadEvent {
+        tokio::time::timeout(Duration::from_secs(1), sub.next::<PayloadEvent>())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap()
+            .0
+    }
+
+    #[tokio::test]
+    async fn subscribe_payload_events() {
+        let payload_builder = spawn_test_payload_service::<EthEngineTypes>();
+        let module = RethPayloadEvents::new(payload_builder.clone()).into_rpc();
+        let mut sub =
+            module.subscribe_unbounded("reth_subscribePayloadEvents", rpc_params![]).await.unwrap();
+
+        let attributes = EthPayloadBuilderAttributes::new(
+            B256::with_last_byte(1),
+            PayloadAttributes {
+                timestamp: 2,
+                prev_randao: B256::with_last_byte(3),
+                suggested_fee_recipient: Address::with_last_byte(4),
+                withdrawals: Some(vec![]),
+                parent_beacon_block_root: Some(B256::with_last_byte(5)),
+            },
+        );
+        let id = payload_builder.new_payload(attributes).await.unwrap();
+        PayloadStore::from(payload_builder).resolve(id).await.unwrap().unwrap();
+
+        assert_eq!(
+            next_event(&mut sub).await,
+            PayloadEvent::Attributes(PayloadEventAttributes {
+                id,
+                parent_hash: B256::with_last_byte(1),
+                timestamp: U64::from(2),
+                suggested_fee_recipient: Address::with_last_byte(4),
+                prev_randao: B256::with_last_byte(3),
+                parent_beacon_block_root: Some(B256::with_last_byte(5)),
+            })
+        );
+        assert_eq!(
+            next_event(&mut sub).await,
+            PayloadEvent::BuiltPayload(BuiltPayloadEvent {
+                id,
+                block_hash: Block::default().seal_slow().hash(),
+                block_number: U64::ZERO,
+                fees: Default::default(),
+                txs: Vec::new(),
+            })
+        );
+    }
+}
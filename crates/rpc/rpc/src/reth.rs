@@ -2,11 +2,22 @@ use crate::eth::error::{EthApiError, EthResult};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_errors::RethResult;
-use reth_primitives::{Address, BlockId, U256};
-use reth_provider::{BlockReaderIdExt, ChangeSetReader, StateProviderFactory};
+use reth_primitives::{static_file::StaticFileSegment, Address, BlockId, Bytes, U256, U64};
+use reth_provider::{
+    BlockReaderIdExt, ChangeSetReader, RawDatabaseReader, RawStaticFileRow, RawTableValue,
+    StateProviderFactory,
+};
 use reth_rpc_api::RethApiServer;
+use reth_rpc_types::{TxpoolDumpResult, TxpoolRestoreResult};
 use reth_tasks::TaskSpawner;
-use std::{collections::HashMap, future::Future, sync::Arc};
+use reth_transaction_pool::{PoolSnapshotError, PoolSnapshots};
+use std::{
+    collections::HashMap,
+    future::Future,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 use tokio::sync::oneshot;
 
 /// `reth` API implementation.
@@ -26,9 +37,56 @@ impl<Provider> RethApi<Provider> {
 
     /// Create a new instance of the [`RethApi`]
     pub fn new(provider: Provider, task_spawner: Box<dyn TaskSpawner>) -> Self {
-        let inner = Arc::new(RethApiInner { provider, task_spawner });
+        let inner = Arc::new(RethApiInner {
+            provider,
+            task_spawner,
+            raw_database_reader: Default::default(),
+            txpool_snapshots: Default::default(),
+        });
         Self { inner }
     }
+
+    /// Sets the reader of raw database values for `reth_dbGet` and `reth_staticFileGet`.
+    ///
+    /// Both methods are disabled until this is set.
+    pub fn set_raw_database_reader(&self, reader: Arc<dyn RawDatabaseReader>) {
+        self.inner.raw_database_reader.write().replace(reader);
+    }
+
+    /// Returns the reader of raw database values, or an error if raw database access is disabled.
+    fn raw_database_reader(&self) -> EthResult<Arc<dyn RawDatabaseReader>> {
+        self.inner.raw_database_reader.read().clone().ok_or(EthApiError::Unsupported(
+            "raw database access is disabled, start reth with --rpc.unsafe-debug",
+        ))
+    }
+
+    /// Sets the pool whose snapshots are written to and read from the given directory by
+    /// `reth_txpoolDump` and `reth_txpoolRestore`.
+    ///
+    /// Both methods are disabled until this is set.
+    pub fn set_txpool_snapshots(&self, dir: PathBuf, snapshots: Arc<dyn PoolSnapshots>) {
+        self.inner.txpool_snapshots.write().replace((dir, snapshots));
+    }
+
+    /// Returns the pool snapshots and the path of the snapshot with the given name, or an error if
+    /// snapshots are disabled.
+    ///
+    /// Only plain file names are accepted, so that no file outside of the snapshot directory can be
+    /// written or read.
+    fn txpool_snapshot(&self, name: &str) -> EthResult<(Arc<dyn PoolSnapshots>, PathBuf)> {
+        let Some((dir, snapshots)) = self.inner.txpool_snapshots.read().clone() else {
+            return Err(EthApiError::Unsupported(
+                "transaction pool snapshots are disabled, start reth with --rpc.unsafe-debug",
+            ))
+        };
+        let mut components = Path::new(name).components();
+        if !matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)) {
+            return Err(EthApiError::InvalidParams(format!(
+                "invalid snapshot name {name}, expected a file name"
+            )))
+        }
+        Ok((snapshots, dir.join(name)))
+    }
 }
 
 impl<Provider> RethApi<Provider>
@@ -81,6 +139,64 @@ where
         )?;
         Ok(hash_map)
     }
+
+    /// Returns the value stored under the encoded key in the table with the given name.
+    pub async fn db_get(&self, table: String, key: Bytes) -> EthResult<Option<RawTableValue>> {
+        self.on_blocking_task(|this| async move { this.try_db_get(&table, &key) }).await
+    }
+
+    fn try_db_get(&self, table: &str, key: &[u8]) -> EthResult<Option<RawTableValue>> {
+        Ok(self.raw_database_reader()?.raw_table_value(table, key)?)
+    }
+
+    /// Returns the row of the static file segment with the given block or transaction number.
+    pub async fn static_file_get(
+        &self,
+        segment: String,
+        number: u64,
+    ) -> EthResult<Option<RawStaticFileRow>> {
+        let segment = StaticFileSegment::from_str(&segment).map_err(|_| {
+            EthApiError::InvalidParams(format!("unknown static file segment {segment}"))
+        })?;
+        self.on_blocking_task(|this| async move { this.try_static_file_get(segment, number) }).await
+    }
+
+    fn try_static_file_get(
+        &self,
+        segment: StaticFileSegment,
+        number: u64,
+    ) -> EthResult<Option<RawStaticFileRow>> {
+        Ok(self.raw_database_reader()?.raw_static_file_row(segment, number)?)
+    }
+
+    /// Writes a snapshot of the pool to the file with the given name in the snapshot directory.
+    pub async fn txpool_dump(&self, name: String) -> EthResult<TxpoolDumpResult> {
+        let (snapshots, path) = self.txpool_snapshot(&name)?;
+        self.on_blocking_task(|_| async move {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(PoolSnapshotError::from)?;
+            }
+            let transactions = snapshots.dump_to_file(&path)?;
+            Ok(TxpoolDumpResult {
+                path: path.display().to_string(),
+                transactions: transactions as u64,
+            })
+        })
+        .await
+    }
+
+    /// Restores the snapshot with the given name in the snapshot directory.
+    pub async fn txpool_restore(&self, name: String) -> EthResult<TxpoolRestoreResult> {
+        let (snapshots, path) = self.txpool_snapshot(&name)?;
+        self.on_blocking_task(|_| async move {
+            let outcome = snapshots.restore_from_file(&path).await?;
+            Ok(TxpoolRestoreResult {
+                restored: outcome.restored as u64,
+                skipped: outcome.skipped as u64,
+            })
+        })
+        .await
+    }
 }
 
 #[async_trait]
@@ -95,6 +211,30 @@ where
     ) -> RpcResult<HashMap<Address, U256>> {
         Ok(Self::balance_changes_in_block(self, block_id).await?)
     }
+
+    /// Handler for `reth_dbGet`
+    async fn reth_db_get(&self, table: String, key: Bytes) -> RpcResult<Option<RawTableValue>> {
+        Ok(Self::db_get(self, table, key).await?)
+    }
+
+    /// Handler for `reth_staticFileGet`
+    async fn reth_static_file_get(
+        &self,
+        segment: String,
+        number: U64,
+    ) -> RpcResult<Option<RawStaticFileRow>> {
+        Ok(Self::static_file_get(self, segment, number.to()).await?)
+    }
+
+    /// Handler for `reth_txpoolDump`
+    async fn reth_txpool_dump(&self, name: String) -> RpcResult<TxpoolDumpResult> {
+        Ok(Self::txpool_dump(self, name).await?)
+    }
+
+    /// Handler for `reth_txpoolRestore`
+    async fn reth_txpool_restore(&self, name: String) -> RpcResult<TxpoolRestoreResult> {
+        Ok(Self::txpool_restore(self, name).await?)
+    }
 }
 
 impl<Provider> std::fmt::Debug for RethApi<Provider> {
@@ -114,4 +254,8 @@ struct RethApiInner<Provider> {
     provider: Provider,
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
+    /// Reads raw database values, only set if raw database access is enabled.
+    raw_database_reader: parking_lot::RwLock<Option<Arc<dyn RawDatabaseReader>>>,
+    /// The snapshot directory and the pool it snapshots, only set if snapshots are enabled.
+    txpool_snapshots: parking_lot::RwLock<Option<(PathBuf, Arc<dyn PoolSnapshots>)>>,
 }
@@ -0,0 +1,252 @@
+//! `reth_` `PubSub` RPC handler implementation
+
+use jsonrpsee::{server::SubscriptionMessage, PendingSubscriptionSink, SubscriptionSink};
+use reth_primitives::U64;
+use reth_provider::{
+    CanonStateNotification, CanonStateNotifications, CanonStateSubscriptions, Chain,
+};
+use reth_rpc_api::RethPubSubApiServer;
+use reth_rpc_types::{ChainReorg, ReorgBlock};
+use reth_tasks::{TaskSpawner, TokioTaskExecutor};
+use tokio::sync::{broadcast::error::RecvError, oneshot};
+
+/// The maximum number of removed and the maximum number of added blocks of a single
+/// [`ChainReorg`] notification.
+///
+/// Deeper reorgs are split into multiple notifications to stay below the message size limit.
+pub const MAX_REORG_BLOCKS_PER_NOTIFICATION: usize = 256;
+
+/// `reth` pubsub RPC implementation.
+///
+/// This handles the `reth_subscribeChainReorgs` RPC calls.
+#[derive(Clone)]
+pub struct RethPubSub<Events> {
+    /// A type that allows to create new event subscriptions.
+    chain_events: Events,
+    /// The type that's used to spawn subscription tasks.
+    subscription_task_spawner: Box<dyn TaskSpawner>,
+}
+
+// === impl RethPubSub ===
+
+impl<Events> RethPubSub<Events> {
+    /// Creates a new instance.
+    ///
+    /// Subscription tasks are spawned via [`tokio::task::spawn`]
+    pub fn new(chain_events: Events) -> Self {
+        Self::with_spawner(chain_events, Box::<TokioTaskExecutor>::default())
+    }
+
+    /// Creates a new instance with the given task spawner.
+    pub fn with_spawner(
+        chain_events: Events,
+        subscription_task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        Self { chain_events, subscription_task_spawner }
+    }
+}
+
+#[async_trait::async_trait]
+impl<Events> RethPubSubApiServer for RethPubSub<Events>
+where
+    Events: CanonStateSubscriptions + Clone + 'static,
+{
+    /// Handler for `reth_subscribeChainReorgs`
+    async fn subscribe_chain_reorgs(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        // subscribe before accepting, so no reorg after the subscription is missed
+        let notifications = self.chain_events.subscribe_to_canonical_state();
+        let sink = pending.accept().await?;
+        let (tx, rx) = oneshot::channel();
+        self.subscription_task_spawner.spawn(Box::pin(async move {
+            let _ = tx.send(pipe_chain_reorgs(sink, notifications).await);
+        }));
+        // a subscriber that lagged behind missed reorgs, so the subscription is closed with an
+        // error instead of skipping them
+        if let Ok(Err(skipped)) = rx.await {
+            return Err(format!(
+                "chain reorg subscription lagged behind, {skipped} notifications were skipped"
+            )
+            .into())
+        }
+        Ok(())
+    }
+}
+
+/// Sends the reorgs of the canonical state notifications to the subscription sink, until the
+/// subscriber disconnects.
+///
+/// Returns the number of skipped notifications if the subscriber lagged behind.
+async fn pipe_chain_reorgs(
+    sink: SubscriptionSink,
+    mut notifications: CanonStateNotifications,
+) -> Result<(), u64> {
+    loop {
+        tokio::select! {
+            _ = sink.closed() => {
+                // connection dropped
+                break
+            },
+            notification = notifications.recv() => {
+                let notification = match notification {
+                    Ok(notification) => notification,
+                    Err(RecvError::Lagged(skipped)) => return Err(skipped),
+                    Err(RecvError::Closed) => break,
+                };
+                let CanonStateNotification::Reorg { old, new } = notification else { continue };
+                // the notifications are only converted for subscribers
+                let reorgs =
+                    chain_reorg_notifications(&old, &new, MAX_REORG_BLOCKS_PER_NOTIFICATION);
+                for reorg in reorgs {
+                    let Ok(msg) = SubscriptionMessage::from_json(&reorg) else { return Ok(()) };
+                    if sink.send(msg).await.is_err() {
+                        return Ok(())
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the notifications of the reorg from the `old` to the `new` chain, with at most
+/// `max_blocks` removed and `max_blocks` added blocks per notification.
+fn chain_reorg_notifications(old: &Chain, new: &Chain, max_blocks: usize) -> Vec<ChainReorg> {
+    let fork_block = old.fork_block();
+    let common_ancestor =
+        ReorgBlock { number: U64::from(fork_block.number), hash: fork_block.hash };
+    let removed = reorg_blocks(old);
+    let added = reorg_blocks(new);
+
+    let max_blocks = max_blocks.max(1);
+    let parts = removed.len().max(added.len()).div_ceil(max_blocks).max(1);
+    let mut removed = removed.chunks(max_blocks);
+    let mut added = added.chunks(max_blocks);
+    (0..parts)
+        .map(|part| ChainReorg {
+            common_ancestor,
+            removed: removed.next().map(<[_]>::to_vec).unwrap_or_default(),
+            added: added.next().map(<[_]>::to_vec).unwrap_or_default(),
+            part: U64::from(part),
+            parts: U64::from(parts),
+        })
+        .collect()
+}
+
+/// Returns the blocks of the chain in ascending order.
+fn reorg_blocks(chain: &Chain) -> Vec<ReorgBlock> {
+    chain
+        .blocks_iter()
+        .map(|block| ReorgBlock { number: U64::from(block.number), hash: block.hash() })
+        .collect()
+}
+
+impl<Events> std::fmt::Debug for RethPubSub<Events> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RethPubSub").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::rpc_params;
+    use reth_primitives::{SealedBlockWithSenders, B256};
+    use reth_provider::test_utils::TestCanonStateSubscriptions;
+    use reth_testing_utils::generators::{self, random_block};
+    use std::{ops::RangeInclusive, sync::Arc, time::Duration};
+
+    /// Returns a chain of empty blocks with the given numbers on top of the parent.
+    fn chain(numbers: RangeInclusive<u64>, parent: B256) -> Chain {
+        let mut rng = generators::rng();
+        let mut parent = parent;
+        let blocks = numbers.map(|number| {
+            let block = random_block(&mut rng, number, Some(parent), Some(0), Some(0));
+            parent = block.hash();
+            SealedBlockWithSenders { block, senders: Vec::new() }
+        });
+        Chain::new(blocks.collect::<Vec<_>>(), Default::default(), None)
+    }
+
+    #[test]
+    fn split_deep_reorg() {
+        let ancestor = B256::random();
+        let old = chain(3..=7, ancestor);
+        let new = chain(3..=5, ancestor);
+
+        let reorgs = chain_reorg_notifications(&old, &new, 2);
+        assert_eq!(reorgs.len(), 3);
+        for (part, reorg) in reorgs.iter().enumerate() {
+            assert_eq!(reorg.common_ancestor, ReorgBlock { number: U64::from(2), hash: ancestor });
+            assert_eq!(reorg.part, U64::from(part));
+            assert_eq!(reorg.parts, U64::from(3));
+        }
+
+        let removed = reorgs.iter().flat_map(|reorg| reorg.removed.clone()).collect::<Vec<_>>();
+        assert_eq!(removed, reorg_blocks(&old));
+        let added = reorgs.iter().flat_map(|reorg| reorg.added.clone()).collect::<Vec<_>>();
+        assert_eq!(added, reorg_blocks(&new));
+        assert_eq!(
+            reorgs.iter().map(|reorg| (reorg.removed.len(), reorg.added.len())).collect::<Vec<_>>(),
+            vec![(2, 2), (2, 1), (1, 0)]
+        );
+
+        // shallow reorgs are sent in a single notification
+        assert_eq!(
+            chain_reorg_notifications(&old, &new, MAX_REORG_BLOCKS_PER_NOTIFICATION).len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn subscribe_chain_reorgs() {
+        let events = TestCanonStateSubscriptions::default();
+        let module = RethPubSub::new(events.clone()).into_rpc();
+        let mut sub =
+            module.subscribe_unbounded("reth_subscribeChainReorgs", rpc_params![]).await.unwrap();
+
+        let ancestor = B256::random();
+        let old = Arc::new(chain(1..=2, ancestor));
+        let new = Arc::new(chain(1..=3, ancestor));
+        // commits are not reorgs
+        events.add_next_commit(old.clone());
+        events.add_next_reorg(old.clone(), new.clone());
+
+        let (reorg, _) = tokio::time::timeout(Duration::from_secs(1), sub.next::<ChainReorg>())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(reorg.common_ancestor.hash, ancestor);
+        assert_eq!(reorg.removed, reorg_blocks(&old));
+        assert_eq!(reorg.added, reorg_blocks(&new));
+        assert_eq!(reorg.parts, U64::from(1));
+        assert!(tokio::time::timeout(Duration::from_millis(100), sub.next::<ChainReorg>())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn subscribe_chain_reorgs_closed_on_lag() {
+        let events = TestCanonStateSubscriptions::default();
+        let module = RethPubSub::new(events.clone()).into_rpc();
+        let mut sub =
+            module.subscribe_unbounded("reth_subscribeChainReorgs", rpc_params![]).await.unwrap();
+
+        // overflow the notification channel before the subscription task runs
+        let ancestor = B256::random();
+        let old = Arc::new(chain(1..=2, ancestor));
+        let new = Arc::new(chain(1..=3, ancestor));
+        for _ in 0..200 {
+            events.add_next_commit(old.clone());
+        }
+        events.add_next_reorg(old, new);
+
+        // the subscription is closed with an error instead of skipping to the next reorg
+        let next =
+            tokio::time::timeout(Duration::from_secs(1), sub.next::<ChainReorg>()).await.unwrap();
+        assert!(next.is_none());
+    }
+}
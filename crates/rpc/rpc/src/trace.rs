@@ -1,25 +1,38 @@
-use crate::eth::{
-    error::{EthApiError, EthResult},
-    revm_utils::{prepare_call_env, EvmOverrides},
-    utils::recover_raw_transaction,
-    EthTransactions,
+use crate::{
+    eth::{
+        error::{EthApiError, EthResult},
+        revm_utils::{prepare_call_env, EvmOverrides},
+        utils::recover_raw_transaction,
+        EthTransactions, FilterError, SuggestedBlockRange,
+    },
+    parallel_trace::trace_block_parallel,
+    trace_guard::{TraceBudget, TraceGuard, TracingMetrics},
+    vm_trace::{VmTraceLimits, VmTracer},
 };
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult as Result;
 use reth_consensus_common::calc::{base_block_reward, block_reward};
-use reth_primitives::{revm::env::tx_env_with_recovered, BlockId, Bytes, SealedHeader, B256, U256};
+use reth_primitives::{
+    revm::env::tx_env_with_recovered, Address, Block, BlockId, Bytes, SealedHeader, B256, U256,
+};
 use reth_provider::{BlockReader, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::TraceApiServer;
+use reth_rpc_server_types::constants::{
+    DEFAULT_MAX_TRACE_BLOCK_CHECKPOINTS, DEFAULT_MAX_TRACE_FILTER_BLOCKS,
+    DEFAULT_MAX_TRACE_FILTER_RESULTS, DEFAULT_MAX_VM_TRACE_MEMORY, DEFAULT_MAX_VM_TRACE_STEPS,
+    DEFAULT_TRACING_TIMEOUT,
+};
 use reth_rpc_types::{
     state::StateOverride,
     trace::{
-        filter::TraceFilter,
+        filter::{TraceFilter, TraceFilterMode},
         opcode::{BlockOpcodeGas, TransactionOpcodeGas},
         parity::*,
         tracerequest::TraceCallRequest,
     },
-    BlockError, BlockOverrides, Index, TransactionRequest,
+    BlockError, BlockOverrides, Index, LimitedTraceResults, LimitedTraceResultsWithTransactionHash,
+    TransactionInfo, TransactionRequest,
 };
 use reth_tasks::pool::BlockingTaskGuard;
 use revm::{
@@ -30,9 +43,42 @@ use revm_inspectors::{
     opcode::OpcodeGasInspector,
     tracing::{parity::populate_state_diff, TracingInspector, TracingInspectorConfig},
 };
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
 
+/// Settings of the `trace` namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceApiConfig {
+    /// The maximum number of blocks a `trace_filter` request can scan.
+    pub max_trace_filter_blocks: u64,
+    /// The maximum number of traces a `trace_filter` request can return.
+    pub max_trace_filter_results: usize,
+    /// The maximum number of transaction pre-states held at once when the transactions of a block
+    /// are traced in parallel, a value of 1 traces them sequentially.
+    pub max_trace_block_checkpoints: usize,
+    /// The maximum time a tracing request can take before its execution is aborted, all calls
+    /// traced by the request share the budget.
+    pub tracing_timeout: Duration,
+    /// The maximum number of instructions recorded for the `vmTrace` of a call.
+    pub max_vm_trace_steps: usize,
+    /// The maximum combined size in bytes of the memory snapshots recorded for the `vmTrace` of a
+    /// call.
+    pub max_vm_trace_memory: usize,
+}
+
+impl Default for TraceApiConfig {
+    fn default() -> Self {
+        Self {
+            max_trace_filter_blocks: DEFAULT_MAX_TRACE_FILTER_BLOCKS,
+            max_trace_filter_results: DEFAULT_MAX_TRACE_FILTER_RESULTS,
+            max_trace_block_checkpoints: DEFAULT_MAX_TRACE_BLOCK_CHECKPOINTS,
+            tracing_timeout: DEFAULT_TRACING_TIMEOUT,
+            max_vm_trace_steps: DEFAULT_MAX_VM_TRACE_STEPS,
+            max_vm_trace_memory: DEFAULT_MAX_VM_TRACE_MEMORY,
+        }
+    }
+}
+
 /// `trace` API implementation.
 ///
 /// This type provides the functionality for handling `trace` related requests.
@@ -49,8 +95,23 @@ impl<Provider, Eth> TraceApi<Provider, Eth> {
     }
 
     /// Create a new instance of the [`TraceApi`]
-    pub fn new(provider: Provider, eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
-        let inner = Arc::new(TraceApiInner { provider, eth_api, blocking_task_guard });
+    pub fn new(
+        provider: Provider,
+        eth_api: Eth,
+        blocking_task_guard: BlockingTaskGuard,
+        config: TraceApiConfig,
+    ) -> Self {
+        let config = TraceApiConfig {
+            max_trace_block_checkpoints: config.max_trace_block_checkpoints.max(1),
+            ..config
+        };
+        let inner = Arc::new(TraceApiInner {
+            provider,
+            eth_api,
+            blocking_task_guard,
+            config,
+            metrics: Default::default(),
+        });
         Self { inner }
     }
 
@@ -65,6 +126,30 @@ impl<Provider, Eth> TraceApi<Provider, Eth> {
     pub fn eth_api(&self) -> &Eth {
         &self.inner.eth_api
     }
+
+    /// Starts the budget of a traced request with the configured timeout, it is shared by all
+    /// calls the request traces.
+    fn budget(&self) -> TraceBudget {
+        TraceBudget::start(self.inner.config.tracing_timeout, self.inner.metrics.clone())
+    }
+
+    /// Guards the given inspector of a request that traces a single call.
+    fn guard<I>(&self, inspector: I) -> TraceGuard<I> {
+        TraceGuard::new(inspector, self.budget())
+    }
+
+    /// Creates the `vmTrace` aware tracer for the given trace types.
+    fn vm_tracer(&self, trace_types: &HashSet<TraceType>) -> VmTracer {
+        VmTracer::new(trace_types, self.vm_trace_limits(), self.inner.metrics.clone())
+    }
+
+    /// Returns the limits of the `vmTrace` of a call.
+    fn vm_trace_limits(&self) -> VmTraceLimits {
+        VmTraceLimits {
+            max_steps: self.inner.config.max_vm_trace_steps,
+            max_memory: self.inner.config.max_vm_trace_memory,
+        }
+    }
 }
 
 // === impl TraceApi ===
@@ -75,17 +160,19 @@ where
     Eth: EthTransactions + 'static,
 {
     /// Executes the given call and returns a number of possible traces for it.
-    pub async fn trace_call(&self, trace_request: TraceCallRequest) -> EthResult<TraceResults> {
+    pub async fn trace_call(
+        &self,
+        trace_request: TraceCallRequest,
+    ) -> EthResult<LimitedTraceResults> {
         let at = trace_request.block_id.unwrap_or_default();
-        let config = TracingInspectorConfig::from_parity_config(&trace_request.trace_types);
         let overrides =
             EvmOverrides::new(trace_request.state_overrides, trace_request.block_overrides);
-        let mut inspector = TracingInspector::new(config);
+        let mut inspector = self.guard(self.vm_tracer(&trace_request.trace_types));
         let this = self.clone();
         self.eth_api()
             .spawn_with_call_at(trace_request.call, at, overrides, move |db, env| {
                 let (res, _) = this.eth_api().inspect(&mut *db, env, &mut inspector)?;
-                let trace_res = inspector.into_parity_builder().into_trace_results_with_state(
+                let trace_res = inspector.into_inner()?.into_trace_results_with_state(
                     &res,
                     &trace_request.trace_types,
                     &db,
@@ -101,23 +188,23 @@ where
         tx: Bytes,
         trace_types: HashSet<TraceType>,
         block_id: Option<BlockId>,
-    ) -> EthResult<TraceResults> {
+    ) -> EthResult<LimitedTraceResults> {
         let tx = recover_raw_transaction(tx)?;
 
-        let (cfg, block, at) = self.inner.eth_api.evm_env_at(block_id.unwrap_or_default()).await?;
+        let (mut cfg, block, at) =
+            self.inner.eth_api.evm_env_at(block_id.unwrap_or_default()).await?;
+        cfg.memory_limit = self.inner.eth_api.call_memory_limit();
         let tx = tx_env_with_recovered(&tx.into_ecrecovered_transaction());
         let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block, tx);
 
-        let config = TracingInspectorConfig::from_parity_config(&trace_types);
-
-        self.inner
-            .eth_api
-            .spawn_trace_at_with_state(env, config, at, move |inspector, res, db| {
-                Ok(inspector.into_parity_builder().into_trace_results_with_state(
-                    &res,
-                    &trace_types,
-                    &db,
-                )?)
+        let mut inspector = self.guard(self.vm_tracer(&trace_types));
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(at, move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+                let (res, _) = this.eth_api().inspect(&mut db, env, &mut inspector)?;
+                let inspector = inspector.into_inner()?;
+                Ok(inspector.into_trace_results_with_state(&res, &trace_types, &db)?)
             })
             .await
     }
@@ -130,11 +217,13 @@ where
         &self,
         calls: Vec<(TransactionRequest, HashSet<TraceType>)>,
         block_id: Option<BlockId>,
-    ) -> EthResult<Vec<TraceResults>> {
+    ) -> EthResult<Vec<LimitedTraceResults>> {
         let at = block_id.unwrap_or(BlockId::pending());
-        let (cfg, block_env, at) = self.inner.eth_api.evm_env_at(at).await?;
+        let (mut cfg, block_env, at) = self.inner.eth_api.evm_env_at(at).await?;
+        cfg.memory_limit = self.inner.eth_api.call_memory_limit();
 
         let gas_limit = self.inner.eth_api.call_gas_limit();
+        let budget = self.budget();
         let this = self.clone();
         // execute all transactions on top of each other and record the traces
         self.eth_api()
@@ -153,11 +242,11 @@ where
                         &mut db,
                         Default::default(),
                     )?;
-                    let config = TracingInspectorConfig::from_parity_config(&trace_types);
-                    let mut inspector = TracingInspector::new(config);
+                    let mut inspector =
+                        TraceGuard::new(this.vm_tracer(&trace_types), budget.clone());
                     let (res, _) = this.eth_api().inspect(&mut db, env, &mut inspector)?;
 
-                    let trace_res = inspector.into_parity_builder().into_trace_results_with_state(
+                    let trace_res = inspector.into_inner()?.into_trace_results_with_state(
                         &res,
                         &trace_types,
                         &db,
@@ -184,131 +273,174 @@ where
         &self,
         hash: B256,
         trace_types: HashSet<TraceType>,
-    ) -> EthResult<TraceResults> {
-        let config = TracingInspectorConfig::from_parity_config(&trace_types);
+    ) -> EthResult<LimitedTraceResults> {
+        let inspector = self.guard(self.vm_tracer(&trace_types));
         self.inner
             .eth_api
-            .spawn_trace_transaction_in_block(hash, config, move |_, inspector, res, db| {
-                let trace_res = inspector.into_parity_builder().into_trace_results_with_state(
-                    &res,
-                    &trace_types,
-                    &db,
-                )?;
-                Ok(trace_res)
-            })
+            .spawn_trace_transaction_in_block_with_inspector(
+                hash,
+                inspector,
+                move |_, inspector, res, db| {
+                    let trace_res = inspector.into_inner()?.into_trace_results_with_state(
+                        &res,
+                        &trace_types,
+                        &db,
+                    )?;
+                    Ok(trace_res)
+                },
+            )
             .await
             .transpose()
             .ok_or_else(|| EthApiError::TransactionNotFound)?
     }
 
-    /// Returns transaction trace objects at the given index
+    /// Returns the trace of the transaction at the given position in its call tree.
     ///
-    /// Note: For compatibility reasons this only supports 1 single index, since this method is
-    /// supposed to return a single trace. See also: <https://github.com/ledgerwatch/erigon/blob/862faf054b8a0fa15962a9c73839b619886101eb/turbo/jsonrpc/trace_filtering.go#L114-L133>
+    /// The indices are the `traceAddress` of the trace as in OpenEthereum: `[]` is the top-level
+    /// call, `[0]` its first subcall and `[0, 1]` the second subcall of that one.
     ///
-    /// This returns `None` if `indices` is empty
+    /// Returns `None` if there is no trace at that position.
     pub async fn trace_get(
         &self,
         hash: B256,
         indices: Vec<usize>,
     ) -> EthResult<Option<LocalizedTransactionTrace>> {
-        if indices.len() != 1 {
-            // The OG impl failed if it gets more than a single index
-            return Ok(None)
-        }
-        self.trace_get_index(hash, indices[0]).await
+        Ok(self.trace_transaction(hash).await?.and_then(|traces| {
+            traces.into_iter().find(|trace| trace.trace.trace_address == indices)
+        }))
     }
 
-    /// Returns transaction trace object at the given index.
+    /// Returns all traces of the filter's block range that match the filter.
     ///
-    /// Returns `None` if the trace object at that index does not exist
-    pub async fn trace_get_index(
-        &self,
-        hash: B256,
-        index: usize,
-    ) -> EthResult<Option<LocalizedTransactionTrace>> {
-        Ok(self.trace_transaction(hash).await?.and_then(|traces| traces.into_iter().nth(index)))
-    }
-
-    /// Returns all transaction traces that match the given filter.
+    /// The transactions of every block in the range are traced in parallel with the parity tracer,
+    /// the matching traces are paginated with the filter's `after` and `count` in the order of the
+    /// range. Traces are matched by the sender and recipient of their action, see
+    /// [`TraceFilterMode`].
     ///
-    /// This is similar to [`Self::trace_block`] but only returns traces for transactions that match
-    /// the filter.
+    /// Returns an error if:
+    ///  - underlying database error
+    ///  - the range or the amount of matches exceeds the configured limits, the error suggests a
+    ///    smaller range that starts at `fromBlock`
     pub async fn trace_filter(
         &self,
         filter: TraceFilter,
-    ) -> EthResult<Vec<LocalizedTransactionTrace>> {
-        let matcher = filter.matcher();
-        let TraceFilter { from_block, to_block, after: _after, count: _count, .. } = filter;
-        let start = from_block.unwrap_or(0);
-        let end = if let Some(to_block) = to_block {
-            to_block
-        } else {
-            self.provider().best_block_number()?
+    ) -> std::result::Result<Vec<LocalizedTransactionTrace>, FilterError> {
+        let TraceApiConfig { max_trace_filter_blocks, max_trace_filter_results, .. } =
+            self.inner.config;
+        let matcher = TraceMatcher::new(&filter);
+        let start = filter.from_block.unwrap_or(0);
+        let end = match filter.to_block {
+            Some(to_block) => to_block,
+            None => self.provider().best_block_number()?,
         };
 
-        // ensure that the range is not too large, since we need to fetch all blocks in the range
-        let distance = end.saturating_sub(start);
-        if distance > 100 {
-            return Err(EthApiError::InvalidParams(
-                "Block range too large; currently limited to 100 blocks".to_string(),
-            ))
+        if end < start {
+            return Err(FilterError::InvalidBlockRangeParams)
+        }
+        if end - start > max_trace_filter_blocks {
+            return Err(FilterError::QueryExceedsMaxBlocks {
+                max: max_trace_filter_blocks,
+                range: SuggestedBlockRange::new(start, start + max_trace_filter_blocks),
+            })
         }
 
-        // fetch all blocks in that range
-        let blocks = self.provider().block_range(start..=end)?;
-
-        // find relevant blocks to trace
-        let mut target_blocks = Vec::new();
-        for block in blocks {
-            let mut transaction_indices = HashSet::new();
-            let mut highest_matching_index = 0;
-            for (tx_idx, tx) in block.body.iter().enumerate() {
-                let from = tx.recover_signer_unchecked().ok_or(BlockError::InvalidSignature)?;
-                let to = tx.to();
-                if matcher.matches(from, to) {
-                    let idx = tx_idx as u64;
-                    transaction_indices.insert(idx);
-                    highest_matching_index = idx;
-                }
+        let budget = self.budget();
+        let mut skip = filter.after.unwrap_or_default() as usize;
+        let count = filter.count.map_or(usize::MAX, |count| count as usize);
+        let mut traces = Vec::new();
+        if count == 0 {
+            return Ok(traces)
+        }
+
+        for block in self.provider().block_range(start..=end)? {
+            // The logs bloom of a block can't be used to skip it: calls, contract creations and
+            // self-destructs of the filtered addresses don't necessarily emit logs.
+            if block.body.is_empty() {
+                continue
             }
-            if !transaction_indices.is_empty() {
-                target_blocks.push((block.number, transaction_indices, highest_matching_index));
+            let number = block.number;
+            for trace in self.trace_block_parity(block, budget.clone()).await? {
+                if !matcher.matches(&trace.trace) {
+                    continue
+                }
+                if skip > 0 {
+                    skip -= 1;
+                    continue
+                }
+                traces.push(trace);
+                if traces.len() == count {
+                    return Ok(traces)
+                }
+
+                // size check but only if range is multiple blocks, so we always return all
+                // traces of a single block
+                if start < end && traces.len() > max_trace_filter_results {
+                    // the traces of the previous blocks are within the limit, if this is the
+                    // first block its traces are returned for the single block range
+                    let to = if number > start { number - 1 } else { start };
+                    return Err(FilterError::QueryExceedsMaxResults {
+                        max: max_trace_filter_results,
+                        range: SuggestedBlockRange::new(start, to),
+                    })
+                }
             }
         }
 
-        // trace all relevant blocks
-        let mut block_traces = Vec::with_capacity(target_blocks.len());
-        for (num, indices, highest_idx) in target_blocks {
-            let traces = self.inner.eth_api.trace_block_until(
-                num.into(),
-                Some(highest_idx),
-                TracingInspectorConfig::default_parity(),
-                move |tx_info, inspector, res, _, _| {
-                    if let Some(idx) = tx_info.index {
-                        if !indices.contains(&idx) {
-                            // only record traces for relevant transactions
-                            return Ok(None)
-                        }
-                    }
-                    let traces = inspector
-                        .with_transaction_gas_used(res.gas_used())
-                        .into_parity_builder()
-                        .into_localized_transaction_traces(tx_info);
-                    Ok(Some(traces))
-                },
-            );
-            block_traces.push(traces);
-        }
+        Ok(traces)
+    }
 
-        let block_traces = futures::future::try_join_all(block_traces).await?;
-        let all_traces = block_traces
-            .into_iter()
-            .flatten()
-            .flat_map(|traces| traces.into_iter().flatten().flat_map(|traces| traces.into_iter()))
-            .collect();
+    /// Traces the transactions of the given block in parallel with the parity tracer and returns
+    /// their traces in block order within the given budget.
+    async fn trace_block_parity(
+        &self,
+        block: Block,
+        budget: TraceBudget,
+    ) -> EthResult<Vec<LocalizedTransactionTrace>> {
+        let block_hash = block.header.hash_slow();
+        let block = block.with_recovered_senders().ok_or(BlockError::InvalidSignature)?;
+        let (cfg, block_env, _) = self.eth_api().evm_env_at(block_hash.into()).await?;
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_blocking(move || {
+                // we need to get the state of the parent block because we're replaying this block
+                // on top of its parent block's state
+                let state_at = block.parent_hash;
+                let block_number = block.number;
+                let base_fee = block_env.basefee.saturating_to::<u128>();
+                let transactions = block.into_transactions_ecrecovered().collect::<Vec<_>>();
 
-        Ok(all_traces)
+                let db = CacheDB::new(StateProviderDatabase::new(
+                    this.eth_api().state_at(state_at.into())?,
+                ));
+                let traces = trace_block_parallel(
+                    this.eth_api(),
+                    state_at.into(),
+                    db,
+                    &transactions,
+                    &cfg,
+                    &block_env,
+                    this.inner.config.max_trace_block_checkpoints,
+                    |index, tx, env, db| {
+                        let mut inspector = TraceGuard::new(
+                            TracingInspector::new(TracingInspectorConfig::default_parity()),
+                            budget.clone(),
+                        );
+                        let (res, _) = this.eth_api().inspect(db, env, &mut inspector)?;
+                        let inspector = inspector.into_inner()?;
+                        let tx_info = TransactionInfo {
+                            hash: Some(tx.hash()),
+                            index: Some(index as u64),
+                            block_hash: Some(block_hash),
+                            block_number: Some(block_number),
+                            base_fee: Some(base_fee),
+                        };
+                        Ok(localized_transaction_traces(inspector, res.result.gas_used(), tx_info))
+                    },
+                )?;
+                Ok(traces.into_iter().flatten().collect())
+            })
+            .await
     }
 
     /// Returns all traces for the given transaction hash
@@ -318,15 +450,15 @@ where
     ) -> EthResult<Option<Vec<LocalizedTransactionTrace>>> {
         self.inner
             .eth_api
-            .spawn_trace_transaction_in_block(
+            .spawn_trace_transaction_in_block_with_inspector(
                 hash,
-                TracingInspectorConfig::default_parity(),
+                self.guard(TracingInspector::new(TracingInspectorConfig::default_parity())),
                 move |tx_info, inspector, res, _| {
-                    let traces = inspector
-                        .with_transaction_gas_used(res.result.gas_used())
-                        .into_parity_builder()
-                        .into_localized_transaction_traces(tx_info);
-                    Ok(traces)
+                    Ok(localized_transaction_traces(
+                        inspector.into_inner()?,
+                        res.result.gas_used(),
+                        tx_info,
+                    ))
                 },
             )
             .await
@@ -337,15 +469,17 @@ where
         &self,
         block_id: BlockId,
     ) -> EthResult<Option<Vec<LocalizedTransactionTrace>>> {
-        let traces = self.inner.eth_api.trace_block_with(
+        let budget = self.budget();
+        let traces = self.inner.eth_api.trace_block_with_inspector(
             block_id,
-            TracingInspectorConfig::default_parity(),
+            move || {
+                TraceGuard::new(
+                    TracingInspector::new(TracingInspectorConfig::default_parity()),
+                    budget.clone(),
+                )
+            },
             |tx_info, inspector, res, _, _| {
-                let traces = inspector
-                    .with_transaction_gas_used(res.gas_used())
-                    .into_parity_builder()
-                    .into_localized_transaction_traces(tx_info);
-                Ok(traces)
+                Ok(localized_transaction_traces(inspector.into_inner()?, res.gas_used(), tx_info))
             },
         );
 
@@ -397,23 +531,26 @@ where
         &self,
         block_id: BlockId,
         trace_types: HashSet<TraceType>,
-    ) -> EthResult<Option<Vec<TraceResultsWithTransactionHash>>> {
+    ) -> EthResult<Option<Vec<LimitedTraceResultsWithTransactionHash>>> {
+        let budget = self.budget();
+        let this = self.clone();
+        let inspector_trace_types = trace_types.clone();
         self.inner
             .eth_api
-            .trace_block_with(
+            .trace_block_with_inspector(
                 block_id,
-                TracingInspectorConfig::from_parity_config(&trace_types),
+                move || TraceGuard::new(this.vm_tracer(&inspector_trace_types), budget.clone()),
                 move |tx_info, inspector, res, state, db| {
                     let mut full_trace =
-                        inspector.into_parity_builder().into_trace_results(&res, &trace_types);
+                        inspector.into_inner()?.into_trace_results(&res, &trace_types);
 
                     // If statediffs were requested, populate them with the account balance and
                     // nonce from pre-state
-                    if let Some(ref mut state_diff) = full_trace.state_diff {
+                    if let Some(ref mut state_diff) = full_trace.full_trace.state_diff {
                         populate_state_diff(state_diff, db, state.iter())?;
                     }
 
-                    let trace = TraceResultsWithTransactionHash {
+                    let trace = LimitedTraceResultsWithTransactionHash {
                         transaction_hash: tx_info.hash.expect("tx hash is set"),
                         full_trace,
                     };
@@ -433,11 +570,11 @@ where
             .eth_api
             .spawn_trace_transaction_in_block_with_inspector(
                 tx_hash,
-                OpcodeGasInspector::default(),
+                self.guard(OpcodeGasInspector::default()),
                 move |_tx_info, inspector, _res, _| {
                     let trace = TransactionOpcodeGas {
                         transaction_hash: tx_hash,
-                        opcode_gas: inspector.opcode_gas_iter().collect(),
+                        opcode_gas: inspector.into_inner()?.opcode_gas_iter().collect(),
                     };
                     Ok(trace)
                 },
@@ -453,16 +590,17 @@ where
         &self,
         block_id: BlockId,
     ) -> EthResult<Option<BlockOpcodeGas>> {
+        let budget = self.budget();
         let res = self
             .inner
             .eth_api
             .trace_block_with_inspector(
                 block_id,
-                OpcodeGasInspector::default,
+                move || TraceGuard::new(OpcodeGasInspector::default(), budget.clone()),
                 move |tx_info, inspector, _res, _, _| {
                     let trace = TransactionOpcodeGas {
                         transaction_hash: tx_info.hash.expect("tx hash is set"),
-                        opcode_gas: inspector.opcode_gas_iter().collect(),
+                        opcode_gas: inspector.into_inner()?.opcode_gas_iter().collect(),
                     };
                     Ok(trace)
                 },
@@ -497,7 +635,7 @@ where
         block_id: Option<BlockId>,
         state_overrides: Option<StateOverride>,
         block_overrides: Option<Box<BlockOverrides>>,
-    ) -> Result<TraceResults> {
+    ) -> Result<LimitedTraceResults> {
         let _permit = self.acquire_trace_permit().await;
         let request =
             TraceCallRequest { call, trace_types, block_id, state_overrides, block_overrides };
@@ -509,7 +647,7 @@ where
         &self,
         calls: Vec<(TransactionRequest, HashSet<TraceType>)>,
         block_id: Option<BlockId>,
-    ) -> Result<Vec<TraceResults>> {
+    ) -> Result<Vec<LimitedTraceResults>> {
         let _permit = self.acquire_trace_permit().await;
         Ok(Self::trace_call_many(self, calls, block_id).await?)
     }
@@ -520,7 +658,7 @@ where
         data: Bytes,
         trace_types: HashSet<TraceType>,
         block_id: Option<BlockId>,
-    ) -> Result<TraceResults> {
+    ) -> Result<LimitedTraceResults> {
         let _permit = self.acquire_trace_permit().await;
         Ok(Self::trace_raw_transaction(self, data, trace_types, block_id).await?)
     }
@@ -530,7 +668,7 @@ where
         &self,
         block_id: BlockId,
         trace_types: HashSet<TraceType>,
-    ) -> Result<Option<Vec<TraceResultsWithTransactionHash>>> {
+    ) -> Result<Option<Vec<LimitedTraceResultsWithTransactionHash>>> {
         let _permit = self.acquire_trace_permit().await;
         Ok(Self::replay_block_transactions(self, block_id, trace_types).await?)
     }
@@ -540,7 +678,7 @@ where
         &self,
         transaction: B256,
         trace_types: HashSet<TraceType>,
-    ) -> Result<TraceResults> {
+    ) -> Result<LimitedTraceResults> {
         let _permit = self.acquire_trace_permit().await;
         Ok(Self::replay_transaction(self, transaction, trace_types).await?)
     }
@@ -557,14 +695,10 @@ where
     /// Handler for `trace_filter`
     ///
     /// This is similar to `eth_getLogs` but for traces.
-    ///
-    /// # Limitations
-    /// This currently requires block filter fields, since reth does not have address indices yet.
     async fn trace_filter(&self, filter: TraceFilter) -> Result<Vec<LocalizedTransactionTrace>> {
         Ok(Self::trace_filter(self, filter).await?)
     }
 
-    /// Returns transaction trace at given index.
     /// Handler for `trace_get`
     async fn trace_get(
         &self,
@@ -618,6 +752,82 @@ struct TraceApiInner<Provider, Eth> {
     eth_api: Eth,
     // restrict the number of concurrent calls to `trace_*`
     blocking_task_guard: BlockingTaskGuard,
+    /// Limits of the `trace` namespace.
+    config: TraceApiConfig,
+    /// Metrics of the guardrails of traced calls.
+    metrics: TracingMetrics,
+}
+
+/// Matches the traces of `trace_filter` by the addresses of their action.
+///
+/// The sender of a call or contract creation and the self-destructed contract are matched against
+/// `fromAddress`, the callee, the created contract, the refund address of a self-destruct and the
+/// author of a reward against `toAddress`. An empty address list is ignored, if both lists are
+/// given, [`TraceFilterMode::Union`] matches a trace if either its sender or recipient matches and
+/// [`TraceFilterMode::Intersection`] only if both match.
+#[derive(Debug)]
+struct TraceMatcher {
+    from_addresses: HashSet<Address>,
+    to_addresses: HashSet<Address>,
+    mode: TraceFilterMode,
+}
+
+impl TraceMatcher {
+    fn new(filter: &TraceFilter) -> Self {
+        Self {
+            from_addresses: filter.from_address.iter().copied().collect(),
+            to_addresses: filter.to_address.iter().copied().collect(),
+            mode: filter.mode,
+        }
+    }
+
+    /// Returns true if the trace matches the filter.
+    fn matches(&self, trace: &TransactionTrace) -> bool {
+        let (from, to) = match &trace.action {
+            Action::Call(call) => (Some(call.from), Some(call.to)),
+            Action::Create(create) => {
+                let created = match &trace.result {
+                    Some(TraceOutput::Create(output)) => Some(output.address),
+                    _ => None,
+                };
+                (Some(create.from), created)
+            }
+            Action::Selfdestruct(selfdestruct) => {
+                (Some(selfdestruct.address), Some(selfdestruct.refund_address))
+            }
+            Action::Reward(reward) => (None, Some(reward.author)),
+        };
+        let from_matches = from.is_some_and(|from| self.from_addresses.contains(&from));
+        let to_matches = to.is_some_and(|to| self.to_addresses.contains(&to));
+
+        match (self.from_addresses.is_empty(), self.to_addresses.is_empty()) {
+            (true, true) => true,
+            (false, true) => from_matches,
+            (true, false) => to_matches,
+            (false, false) => match self.mode {
+                TraceFilterMode::Union => from_matches || to_matches,
+                TraceFilterMode::Intersection => from_matches && to_matches,
+            },
+        }
+    }
+}
+
+/// Returns the parity traces of the calls of a transaction in the order of its call tree.
+///
+/// The traces are ordered depth-first, so every call directly follows its parent and precedes its
+/// later siblings. With [`TracingInspectorConfig::default_parity`] the calls of precompiles are not
+/// traced, so the subcalls of every call are numbered consecutively from 0 in their
+/// `traceAddress`. Reverted calls and their subcalls are traced like any other call, with the
+/// `error` of the reverted call set.
+fn localized_transaction_traces(
+    inspector: TracingInspector,
+    gas_used: u64,
+    tx_info: TransactionInfo,
+) -> Vec<LocalizedTransactionTrace> {
+    inspector
+        .with_transaction_gas_used(gas_used)
+        .into_parity_builder()
+        .into_localized_transaction_traces(tx_info)
 }
 
 /// Helper to construct a [`LocalizedTransactionTrace`] that describes a reward to the block
@@ -637,3 +847,483 @@ fn reward_trace(header: &SealedHeader, reward: RewardAction) -> LocalizedTransac
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::test_utils::{self, build_test_eth_api};
+    use reth_primitives::{
+        address, constants::ETHEREUM_BLOCK_GAS_LIMIT, hex, public_key_to_address, Header,
+        Transaction, TxKind, TxLegacy,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+
+    const OUTER: Address = address!("a000000000000000000000000000000000000000");
+    const INNER: Address = address!("b000000000000000000000000000000000000000");
+
+    /// Increments slot 0.
+    const INNER_CODE: &[u8] = &hex!("60005460010160005500");
+
+    /// Calls [`INNER`], then increments slot 0.
+    const OUTER_CODE: &[u8] = &hex!(
+        "6000600060006000600073b0000000000000000000000000000000000000005af15060005460010160005500"
+    );
+
+    type TestEthApi = test_utils::TestEthApi<MockEthProvider>;
+
+    /// A chain of an empty genesis block and two blocks with calls of `alice` and `bob`:
+    ///
+    /// 1. `alice` calls [`OUTER`] which calls [`INNER`], `bob` calls [`INNER`] and `alice` calls
+    ///    `bob`
+    /// 2. `bob` calls [`OUTER`] which calls [`INNER`]
+    struct TestChain {
+        trace_api: TraceApi<MockEthProvider, TestEthApi>,
+        alice: Address,
+        bob: Address,
+    }
+
+    impl TestChain {
+        fn new(config: TraceApiConfig) -> Self {
+            let mut rng = generators::rng();
+            let keys = generators::generate_keys(&mut rng, 2);
+            let [alice, bob] =
+                [keys[0], keys[1]].map(|key| public_key_to_address(key.public_key()));
+
+            let provider = MockEthProvider::default();
+            provider.add_account(
+                OUTER,
+                ExtendedAccount::new(0, U256::ZERO).with_bytecode(Bytes::from_static(OUTER_CODE)),
+            );
+            provider.add_account(
+                INNER,
+                ExtendedAccount::new(0, U256::ZERO).with_bytecode(Bytes::from_static(INNER_CODE)),
+            );
+            provider.add_account(alice, ExtendedAccount::new(0, U256::from(1_000_000_000u64)));
+            provider.add_account(bob, ExtendedAccount::new(0, U256::from(1_000_000_000u64)));
+
+            // the state of the mock provider is the same at every block, so the nonces of every
+            // block start at 0
+            let call = |sender: usize, nonce: u64, to: Address| {
+                let transaction = Transaction::Legacy(TxLegacy {
+                    chain_id: Some(1),
+                    nonce,
+                    gas_limit: 100_000,
+                    to: TxKind::Call(to),
+                    ..Default::default()
+                });
+                sign_tx_with_key_pair(keys[sender], transaction)
+            };
+            let bodies = [
+                vec![],
+                vec![call(0, 0, OUTER), call(1, 0, INNER), call(0, 1, bob)],
+                vec![call(1, 0, OUTER)],
+            ];
+
+            let mut parent_hash = B256::ZERO;
+            for (number, body) in bodies.into_iter().enumerate() {
+                let header = Header {
+                    number: number as u64,
+                    parent_hash,
+                    gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+                    ..Default::default()
+                };
+                parent_hash = header.hash_slow();
+                provider.add_block(parent_hash, Block { header, body, ..Default::default() });
+            }
+
+            let eth_api = build_test_eth_api(provider.clone());
+            let trace_api = TraceApi::new(provider, eth_api, BlockingTaskGuard::new(1), config);
+            Self { trace_api, alice, bob }
+        }
+
+        async fn trace_filter(
+            &self,
+            filter: TraceFilter,
+        ) -> std::result::Result<Vec<LocalizedTransactionTrace>, FilterError> {
+            self.trace_api.trace_filter(filter).await
+        }
+    }
+
+    fn filter(from_block: u64, to_block: u64) -> TraceFilter {
+        TraceFilter {
+            from_block: Some(from_block),
+            to_block: Some(to_block),
+            from_address: Vec::new(),
+            to_address: Vec::new(),
+            mode: TraceFilterMode::default(),
+            after: None,
+            count: None,
+        }
+    }
+
+    /// Returns the block number, transaction index, caller and callee of the call traces.
+    fn calls(traces: Vec<LocalizedTransactionTrace>) -> Vec<(u64, u64, Address, Address)> {
+        traces
+            .into_iter()
+            .map(|trace| match trace.trace.action {
+                Action::Call(call) => (
+                    trace.block_number.unwrap(),
+                    trace.transaction_position.unwrap(),
+                    call.from,
+                    call.to,
+                ),
+                action => panic!("unexpected action {action:?}"),
+            })
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_filter_matches_addresses() {
+        for max_trace_block_checkpoints in [1, 64] {
+            let config = TraceApiConfig { max_trace_block_checkpoints, ..Default::default() };
+            let TestChain { trace_api, alice, bob } = TestChain::new(config);
+            let trace_filter = |from_address: Vec<Address>, to_address, mode| {
+                let filter = TraceFilter { from_address, to_address, mode, ..filter(0, 2) };
+                let trace_api = trace_api.clone();
+                async move { calls(trace_api.trace_filter(filter).await.unwrap()) }
+            };
+
+            let all = trace_filter(vec![], vec![], TraceFilterMode::Intersection).await;
+            assert_eq!(
+                all,
+                vec![
+                    (1, 0, alice, OUTER),
+                    (1, 0, OUTER, INNER),
+                    (1, 1, bob, INNER),
+                    (1, 2, alice, bob),
+                    (2, 0, bob, OUTER),
+                    (2, 0, OUTER, INNER),
+                ]
+            );
+
+            // internal calls are matched by their own caller and callee
+            let from_outer = trace_filter(vec![OUTER], vec![], TraceFilterMode::Union).await;
+            assert_eq!(from_outer, vec![(1, 0, OUTER, INNER), (2, 0, OUTER, INNER)]);
+            let to_inner = trace_filter(vec![], vec![INNER], TraceFilterMode::Intersection).await;
+            assert_eq!(
+                to_inner,
+                vec![(1, 0, OUTER, INNER), (1, 1, bob, INNER), (2, 0, OUTER, INNER)]
+            );
+
+            // both the caller and the callee have to match
+            let intersection =
+                trace_filter(vec![alice, bob], vec![INNER, bob], TraceFilterMode::Intersection)
+                    .await;
+            assert_eq!(intersection, vec![(1, 1, bob, INNER), (1, 2, alice, bob)]);
+            let intersection =
+                trace_filter(vec![alice], vec![INNER], TraceFilterMode::Intersection).await;
+            assert!(intersection.is_empty());
+
+            // either the caller or the callee has to match
+            let union = trace_filter(vec![alice], vec![INNER], TraceFilterMode::Union).await;
+            assert_eq!(
+                union,
+                vec![
+                    (1, 0, alice, OUTER),
+                    (1, 0, OUTER, INNER),
+                    (1, 1, bob, INNER),
+                    (1, 2, alice, bob),
+                    (2, 0, OUTER, INNER),
+                ]
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_filter_paginates() {
+        let chain = TestChain::new(TraceApiConfig::default());
+        let (alice, bob) = (chain.alice, chain.bob);
+
+        let page = chain
+            .trace_filter(TraceFilter { after: Some(1), count: Some(3), ..filter(0, 2) })
+            .await
+            .unwrap();
+        assert_eq!(calls(page), vec![(1, 0, OUTER, INNER), (1, 1, bob, INNER), (1, 2, alice, bob)]);
+
+        // pagination applies to the matching traces
+        let page = chain
+            .trace_filter(TraceFilter {
+                to_address: vec![INNER],
+                after: Some(1),
+                count: Some(5),
+                ..filter(0, 2)
+            })
+            .await
+            .unwrap();
+        assert_eq!(calls(page), vec![(1, 1, bob, INNER), (2, 0, OUTER, INNER)]);
+
+        let page =
+            chain.trace_filter(TraceFilter { after: Some(6), ..filter(0, 2) }).await.unwrap();
+        assert!(page.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_filter_limits() {
+        let chain = TestChain::new(TraceApiConfig {
+            max_trace_filter_blocks: 1,
+            max_trace_filter_results: 3,
+            ..Default::default()
+        });
+
+        let err = chain.trace_filter(filter(2, 1)).await.unwrap_err();
+        assert!(matches!(err, FilterError::InvalidBlockRangeParams));
+
+        let err = chain.trace_filter(filter(0, 2)).await.unwrap_err();
+        assert!(matches!(
+            err,
+            FilterError::QueryExceedsMaxBlocks { max: 1, range }
+                if range == SuggestedBlockRange::new(0, 1)
+        ));
+
+        // the 4 traces of block 1 exceed the limit, the suggested range is the single block
+        let err = chain.trace_filter(filter(1, 2)).await.unwrap_err();
+        assert!(matches!(
+            err,
+            FilterError::QueryExceedsMaxResults { max: 3, range }
+                if range == SuggestedBlockRange::new(1, 1)
+        ));
+        assert_eq!(chain.trace_filter(filter(1, 1)).await.unwrap().len(), 4);
+
+        // a page within the limit can be requested for the range
+        let page =
+            chain.trace_filter(TraceFilter { count: Some(3), ..filter(1, 2) }).await.unwrap();
+        assert_eq!(page.len(), 3);
+    }
+
+    /// Replays block 2 and returns the traces of its only transaction, `bob` calling [`OUTER`].
+    async fn replay_outer_call(
+        config: TraceApiConfig,
+        trace_types: HashSet<TraceType>,
+    ) -> LimitedTraceResults {
+        let chain = TestChain::new(config);
+        let mut traces = chain
+            .trace_api
+            .replay_block_transactions(BlockId::Number(2.into()), trace_types)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(traces.len(), 1);
+        traces.remove(0).full_trace
+    }
+
+    /// Returns the program counters of the recorded instructions.
+    fn pcs(vm_trace: &VmTrace) -> Vec<usize> {
+        vm_trace.ops.iter().map(|op| op.pc).collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn replay_vm_trace() {
+        let traces = replay_outer_call(
+            TraceApiConfig::default(),
+            HashSet::from([TraceType::Trace, TraceType::VmTrace]),
+        )
+        .await;
+        assert!(!traces.vm_trace_truncated);
+        assert!(serde_json::to_value(&traces).unwrap().get("vmTraceTruncated").is_none());
+        assert_eq!(traces.full_trace.trace.len(), 2);
+
+        // every instruction of [`OUTER_CODE`], the call of [`INNER`] is made at pc 32
+        let vm_trace = traces.full_trace.vm_trace.unwrap();
+        assert_eq!(pcs(&vm_trace), [0, 2, 4, 6, 8, 10, 31, 32, 33, 34, 36, 37, 39, 40, 42, 43]);
+        assert_eq!(vm_trace.ops[0].ex.as_ref().unwrap().push, vec![U256::ZERO]);
+        assert!(vm_trace.ops.iter().enumerate().all(|(i, op)| op.sub.is_some() == (i == 7)));
+
+        // every instruction of [`INNER_CODE`], which stores 1 in slot 0
+        let sub = vm_trace.ops[7].sub.as_ref().unwrap();
+        assert_eq!(pcs(sub), [0, 2, 3, 5, 6, 8, 9]);
+        assert_eq!(
+            sub.ops[5].ex.as_ref().unwrap().store,
+            Some(StorageDelta { key: U256::ZERO, val: U256::from(1) })
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn replay_vm_trace_truncated() {
+        let config = TraceApiConfig { max_vm_trace_steps: 10, ..Default::default() };
+        let traces =
+            replay_outer_call(config, HashSet::from([TraceType::Trace, TraceType::VmTrace])).await;
+        assert!(traces.vm_trace_truncated);
+        assert_eq!(serde_json::to_value(&traces).unwrap()["vmTraceTruncated"], true);
+        // the traces of the calls are complete
+        assert_eq!(traces.full_trace.trace.len(), 2);
+
+        // the first 8 instructions of [`OUTER_CODE`] and the first 2 of [`INNER_CODE`]
+        let vm_trace = traces.full_trace.vm_trace.unwrap();
+        assert_eq!(pcs(&vm_trace), [0, 2, 4, 6, 8, 10, 31, 32]);
+        assert_eq!(pcs(vm_trace.ops[7].sub.as_ref().unwrap()), [0, 2]);
+
+        // the limits only apply to the `vmTrace`
+        let config = TraceApiConfig { max_vm_trace_steps: 1, ..Default::default() };
+        let traces = replay_outer_call(config, HashSet::from([TraceType::Trace])).await;
+        assert!(!traces.vm_trace_truncated);
+        assert!(traces.full_trace.vm_trace.is_none());
+        assert_eq!(traces.full_trace.trace.len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn tracing_timeout_aborts_execution() {
+        let chain = TestChain::new(TraceApiConfig {
+            tracing_timeout: Duration::ZERO,
+            ..Default::default()
+        });
+        let block = BlockId::Number(2.into());
+
+        let err = chain
+            .trace_api
+            .replay_block_transactions(block, HashSet::from([TraceType::Trace]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthApiError::ExecutionTimedOut(_)), "{err:?}");
+
+        let err = chain.trace_api.trace_block(block).await.unwrap_err();
+        assert!(matches!(err, EthApiError::ExecutionTimedOut(_)), "{err:?}");
+    }
+
+    const NESTED_A: Address = address!("1100000000000000000000000000000000000000");
+    const NESTED_B: Address = address!("2200000000000000000000000000000000000000");
+    const NESTED_C: Address = address!("3300000000000000000000000000000000000000");
+    const NESTED_D: Address = address!("4400000000000000000000000000000000000000");
+    const NESTED_E: Address = address!("5500000000000000000000000000000000000000");
+    const NESTED_F: Address = address!("6600000000000000000000000000000000000000");
+
+    /// Calls [`NESTED_B`], then [`NESTED_C`].
+    const NESTED_A_CODE: &[u8] = &hex!(
+        "600060006000600060007322000000000000000000000000000000000000005af150"
+        "600060006000600060007333000000000000000000000000000000000000005af15000"
+    );
+
+    /// Calls [`NESTED_D`], then [`NESTED_E`].
+    const NESTED_B_CODE: &[u8] = &hex!(
+        "600060006000600060007344000000000000000000000000000000000000005af150"
+        "600060006000600060007355000000000000000000000000000000000000005af15000"
+    );
+
+    /// Calls [`NESTED_F`], then reverts.
+    const NESTED_D_CODE: &[u8] =
+        &hex!("600060006000600060007366000000000000000000000000000000000000005af15060006000fd");
+
+    /// Stops right away.
+    const STOP_CODE: &[u8] = &hex!("00");
+
+    /// Adds a block with a single transaction that calls [`NESTED_A`], which makes the calls:
+    ///
+    /// ```text
+    /// []        NESTED_A
+    /// [0]       └─ NESTED_B
+    /// [0, 0]       ├─ NESTED_D (reverts)
+    /// [0, 0, 0]    │  └─ NESTED_F
+    /// [0, 1]       └─ NESTED_E
+    /// [1]       └─ NESTED_C
+    /// ```
+    ///
+    /// Returns the trace API and the hash of the transaction.
+    fn nested_calls() -> (TraceApi<MockEthProvider, TestEthApi>, B256) {
+        let mut rng = generators::rng();
+        let key = generators::generate_keys(&mut rng, 1)[0];
+        let sender = public_key_to_address(key.public_key());
+
+        let provider = MockEthProvider::default();
+        provider.add_account(sender, ExtendedAccount::new(0, U256::from(1_000_000_000u64)));
+        for (address, code) in [
+            (NESTED_A, NESTED_A_CODE),
+            (NESTED_B, NESTED_B_CODE),
+            (NESTED_C, STOP_CODE),
+            (NESTED_D, NESTED_D_CODE),
+            (NESTED_E, STOP_CODE),
+            (NESTED_F, STOP_CODE),
+        ] {
+            provider.add_account(
+                address,
+                ExtendedAccount::new(0, U256::ZERO).with_bytecode(Bytes::from_static(code)),
+            );
+        }
+
+        let transaction = sign_tx_with_key_pair(
+            key,
+            Transaction::Legacy(TxLegacy {
+                chain_id: Some(1),
+                gas_limit: 200_000,
+                to: TxKind::Call(NESTED_A),
+                ..Default::default()
+            }),
+        );
+        let tx_hash = transaction.hash();
+        let genesis = Header { gas_limit: ETHEREUM_BLOCK_GAS_LIMIT, ..Default::default() };
+        let genesis_hash = genesis.hash_slow();
+        provider.add_block(genesis_hash, Block { header: genesis, ..Default::default() });
+        let header = Header {
+            number: 1,
+            parent_hash: genesis_hash,
+            gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            ..Default::default()
+        };
+        let block = Block { header: header.clone(), body: vec![transaction], ..Default::default() };
+        provider.add_block(header.hash_slow(), block);
+
+        let eth_api = build_test_eth_api(provider.clone());
+        let trace_api =
+            TraceApi::new(provider, eth_api, BlockingTaskGuard::new(1), Default::default());
+        (trace_api, tx_hash)
+    }
+
+    /// Returns the trace address, the number of subtraces, the callee and the error of the call
+    /// trace.
+    fn call_tree_entry(
+        trace: &LocalizedTransactionTrace,
+    ) -> (Vec<usize>, usize, Address, Option<&str>) {
+        let Action::Call(call) = &trace.trace.action else {
+            panic!("unexpected action {:?}", trace.trace.action)
+        };
+        (
+            trace.trace.trace_address.clone(),
+            trace.trace.subtraces,
+            call.to,
+            trace.trace.error.as_deref(),
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_transaction_nested_calls() {
+        let (trace_api, tx_hash) = nested_calls();
+
+        let traces = trace_api.trace_transaction(tx_hash).await.unwrap().unwrap();
+        assert_eq!(
+            traces.iter().map(call_tree_entry).collect::<Vec<_>>(),
+            vec![
+                (vec![], 2, NESTED_A, None),
+                (vec![0], 2, NESTED_B, None),
+                (vec![0, 0], 1, NESTED_D, Some("Reverted")),
+                (vec![0, 0, 0], 0, NESTED_F, None),
+                (vec![0, 1], 0, NESTED_E, None),
+                (vec![1], 0, NESTED_C, None),
+            ]
+        );
+        // the reverted call has no result
+        assert!(traces[2].trace.result.is_none());
+        assert!(traces.iter().all(|trace| trace.transaction_hash == Some(tx_hash)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_get_by_trace_address() {
+        let (trace_api, tx_hash) = nested_calls();
+
+        for (indices, to) in [
+            (vec![], NESTED_A),
+            (vec![0], NESTED_B),
+            (vec![0, 0], NESTED_D),
+            (vec![0, 0, 0], NESTED_F),
+            (vec![0, 1], NESTED_E),
+            (vec![1], NESTED_C),
+        ] {
+            let trace = trace_api.trace_get(tx_hash, indices.clone()).await.unwrap().unwrap();
+            assert_eq!(trace.trace.trace_address, indices);
+            assert_eq!(call_tree_entry(&trace).2, to);
+        }
+
+        for indices in [vec![2], vec![0, 2], vec![0, 0, 1], vec![1, 0]] {
+            assert!(trace_api.trace_get(tx_hash, indices).await.unwrap().is_none());
+        }
+        assert!(trace_api.trace_get(B256::ZERO, vec![0]).await.unwrap().is_none());
+    }
+}
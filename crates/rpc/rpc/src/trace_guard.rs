@@ -0,0 +1,301 @@
+//! Guardrails of the calls traced in the `debug` and `trace` namespaces.
+//!
+//! Every traced request has a wall-clock budget that starts with the request and is shared by all
+//! calls it traces, once it is exceeded the execution of the EVM is aborted. The struct logs of the
+//! `debug` namespace are additionally recorded within a memory budget, once it is exceeded the
+//! remaining instructions are no longer recorded.
+
+use crate::eth::error::{EthApiError, EthResult};
+use reth_metrics::{metrics::Counter, Metrics};
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CreateInputs, CreateOutcome, Gas, InstructionResult, Interpreter,
+        InterpreterResult,
+    },
+    primitives::{Address, Bytes, Log, U256},
+    Database, EvmContext, Inspector,
+};
+use revm_inspectors::tracing::types::CallTraceStep;
+use std::time::{Duration, Instant};
+
+/// The wall-clock budget of a traced request.
+#[derive(Debug, Clone)]
+pub(crate) struct TraceBudget {
+    /// The budget of the request.
+    timeout: Duration,
+    /// The time at which the execution is aborted.
+    deadline: Instant,
+    /// Metrics of the guardrails.
+    metrics: TracingMetrics,
+}
+
+impl TraceBudget {
+    /// Starts the budget of a request right away.
+    pub(crate) fn start(timeout: Duration, metrics: TracingMetrics) -> Self {
+        Self { timeout, deadline: Instant::now() + timeout, metrics }
+    }
+
+    /// Returns whether the budget is exhausted.
+    pub(crate) fn is_exceeded(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    /// Returns the time left until the budget is exhausted.
+    pub(crate) fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// Returns the metrics of the guardrails.
+    pub(crate) const fn metrics(&self) -> &TracingMetrics {
+        &self.metrics
+    }
+
+    /// Records that a traced call was aborted and returns the error of the request.
+    pub(crate) fn timed_out(&self) -> EthApiError {
+        self.metrics.aborted_traces_total.increment(1);
+        EthApiError::ExecutionTimedOut(self.timeout)
+    }
+}
+
+/// The memory budget of the recorded instructions of a traced call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct StepBudget {
+    /// The maximum combined size of the recorded instructions in bytes.
+    pub(crate) max_memory: usize,
+    /// Whether a snapshot of the memory is recorded with every instruction.
+    pub(crate) record_memory: bool,
+}
+
+/// Aborts the execution of a traced call once its budget is exhausted, and stops recording
+/// instructions once their memory budget is exceeded.
+#[derive(Debug)]
+pub(crate) struct TraceGuard<I> {
+    inspector: I,
+    /// The budget of the request.
+    budget: TraceBudget,
+    /// Whether the execution was aborted.
+    timed_out: bool,
+    /// The memory budget if the instructions are recorded.
+    step_budget: Option<StepBudget>,
+    /// The combined size of the recorded instructions.
+    recorded_memory: usize,
+    /// Whether each instruction that is still executing was recorded, nested calls are executed
+    /// within the instruction that made them.
+    open_steps: Vec<bool>,
+    /// Whether any instruction was not recorded.
+    truncated: bool,
+}
+
+impl<I> TraceGuard<I> {
+    /// Guards the given inspector within the budget of its request.
+    pub(crate) fn new(inspector: I, budget: TraceBudget) -> Self {
+        Self {
+            inspector,
+            budget,
+            timed_out: false,
+            step_budget: None,
+            recorded_memory: 0,
+            open_steps: Vec::new(),
+            truncated: false,
+        }
+    }
+
+    /// Guards an inspector that records the executed instructions within the given budget.
+    pub(crate) fn with_step_budget(
+        inspector: I,
+        budget: TraceBudget,
+        step_budget: StepBudget,
+    ) -> Self {
+        Self { step_budget: Some(step_budget), ..Self::new(inspector, budget) }
+    }
+
+    /// Returns whether instructions were left out because their memory budget was exceeded.
+    pub(crate) const fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns the guarded inspector.
+    ///
+    /// Returns an error if the execution was aborted.
+    pub(crate) fn into_inner(self) -> EthResult<I> {
+        if self.timed_out {
+            return Err(self.budget.timed_out())
+        }
+        if self.truncated {
+            self.budget.metrics().truncated_traces_total.increment(1);
+        }
+        Ok(self.inspector)
+    }
+
+    /// Returns whether the execution is aborted, which is checked before every instruction and
+    /// every nested call.
+    fn is_timed_out(&mut self) -> bool {
+        if !self.timed_out && self.budget.is_exceeded() {
+            self.timed_out = true;
+        }
+        self.timed_out
+    }
+
+    /// Returns whether the instruction about to be executed is recorded and accounts for it.
+    fn record_step(&mut self, interp: &Interpreter) -> bool {
+        let Some(budget) = self.step_budget else { return true };
+        let mut size =
+            std::mem::size_of::<CallTraceStep>() + interp.stack.len() * std::mem::size_of::<U256>();
+        if budget.record_memory {
+            size += interp.shared_memory.len();
+        }
+        if self.truncated || self.recorded_memory.saturating_add(size) > budget.max_memory {
+            self.truncated = true;
+            return false
+        }
+        self.recorded_memory += size;
+        true
+    }
+}
+
+impl<DB, I> Inspector<DB> for TraceGuard<I>
+where
+    DB: Database,
+    I: Inspector<DB>,
+{
+    fn initialize_interp(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.inspector.initialize_interp(interp, context)
+    }
+
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if self.is_timed_out() {
+            // halt every frame that is still executing, so the whole execution unwinds
+            interp.instruction_result = InstructionResult::OutOfGas;
+            self.open_steps.push(false);
+            return
+        }
+        let record = self.record_step(interp);
+        self.open_steps.push(record);
+        if record {
+            self.inspector.step(interp, context)
+        }
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if self.open_steps.pop().unwrap_or_default() {
+            self.inspector.step_end(interp, context)
+        }
+    }
+
+    fn log(&mut self, context: &mut EvmContext<DB>, log: &Log) {
+        self.inspector.log(context, log)
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        if self.is_timed_out() {
+            // a call without instructions, e.g. of a precompile, is never stepped into
+            return Some(CallOutcome::new(
+                timed_out_result(inputs.gas_limit),
+                inputs.return_memory_offset.clone(),
+            ))
+        }
+        self.inspector.call(context, inputs)
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.inspector.call_end(context, inputs, outcome)
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        if self.is_timed_out() {
+            return Some(CreateOutcome::new(timed_out_result(inputs.gas_limit), None))
+        }
+        self.inspector.create(context, inputs)
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.inspector.create_end(context, inputs, outcome)
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        Inspector::<DB>::selfdestruct(&mut self.inspector, contract, target, value)
+    }
+}
+
+/// The result of a call that is not executed because the budget of its request is exhausted.
+pub(crate) fn timed_out_result(gas_limit: u64) -> InterpreterResult {
+    InterpreterResult {
+        result: InstructionResult::OutOfGas,
+        output: Bytes::new(),
+        gas: Gas::new(gas_limit),
+    }
+}
+
+/// Metrics of the guardrails of traced calls.
+#[derive(Clone, Metrics)]
+#[metrics(scope = "rpc.tracing")]
+pub(crate) struct TracingMetrics {
+    /// The number of traced calls that were aborted because they exceeded their time budget.
+    pub(crate) aborted_traces_total: Counter,
+    /// The number of traces that were truncated because they exceeded their memory budget.
+    pub(crate) truncated_traces_total: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        inspector_handle_register,
+        inspectors::NoOpInspector,
+        primitives::TxKind,
+        Evm,
+    };
+
+    /// Executes a call of the identity precompile, which doesn't execute any instructions, with
+    /// the guarded inspector and returns the result of the guard.
+    fn call_precompile(budget: TraceBudget) -> EthResult<NoOpInspector> {
+        let mut evm = Evm::builder()
+            .with_db(CacheDB::new(EmptyDB::default()))
+            .modify_tx_env(|tx| {
+                tx.transact_to = TxKind::Call(Address::with_last_byte(4));
+                tx.gas_limit = 100_000;
+            })
+            .with_external_context(TraceGuard::new(NoOpInspector, budget))
+            .append_handler_register(inspector_handle_register)
+            .build();
+        evm.transact().unwrap();
+        evm.context.external.into_inner()
+    }
+
+    #[test]
+    fn exhausted_budget_aborts_calls_without_instructions() {
+        let budget = TraceBudget::start(Duration::ZERO, TracingMetrics::default());
+        let err = call_precompile(budget).unwrap_err();
+        assert!(matches!(err, EthApiError::ExecutionTimedOut(_)), "{err:?}");
+    }
+
+    #[test]
+    fn budget_is_shared_by_the_calls_of_a_request() {
+        let budget = TraceBudget::start(Duration::from_millis(100), TracingMetrics::default());
+        call_precompile(budget.clone()).unwrap();
+
+        // the budget started with the request, not with the call
+        std::thread::sleep(Duration::from_millis(150));
+        let err = call_precompile(budget).unwrap_err();
+        assert!(matches!(err, EthApiError::ExecutionTimedOut(_)), "{err:?}");
+    }
+}
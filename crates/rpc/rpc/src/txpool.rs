@@ -1,13 +1,18 @@
+use crate::result::invalid_params_rpc_err;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult as Result;
 use reth_primitives::Address;
 use reth_rpc_api::TxPoolApiServer;
 use reth_rpc_types::{
-    txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus},
-    Transaction,
+    txpool::{TxpoolContentFrom, TxpoolInspectSummary, TxpoolStatus},
+    GethInspectSummary, GethTxpoolInspect, Transaction, TxpoolContentExt, TxpoolContentFilter,
+    TxpoolOrigin, TxpoolStatusExt,
 };
-use reth_transaction_pool::{AllPoolTransactions, PoolTransaction, TransactionPool};
-use std::collections::BTreeMap;
+use reth_transaction_pool::{
+    AllPoolTransactions, PoolTransaction, TransactionFilter, TransactionOrigin, TransactionPool,
+    ValidPoolTransaction,
+};
+use std::{collections::BTreeMap, sync::Arc};
 use tracing::trace;
 
 /// `txpool` API implementation.
@@ -30,32 +35,104 @@ impl<Pool> TxPoolApi<Pool>
 where
     Pool: TransactionPool + 'static,
 {
-    fn content(&self) -> TxpoolContent {
+    fn content(&self, filter: TxpoolContentFilter) -> Result<TxpoolContentExt> {
         #[inline]
         fn insert<T: PoolTransaction>(
             tx: &T,
             content: &mut BTreeMap<Address, BTreeMap<String, Transaction>>,
         ) {
-            content.entry(tx.sender()).or_default().insert(
-                tx.nonce().to_string(),
-                reth_rpc_types_compat::transaction::from_recovered(tx.to_recovered_transaction()),
-            );
+            content.entry(tx.sender()).or_default().insert(tx.nonce().to_string(), to_rpc(tx));
         }
 
-        let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
+        let TxpoolContentFilter { origin, min_tip, senders, recipients, limit, continuation } =
+            filter;
+        if limit == Some(0) {
+            return Err(invalid_params_rpc_err("limit must be greater than zero"))
+        }
+        let after = continuation.as_deref().map(parse_continuation).transpose()?;
+        let origin = origin.map(|origin| match origin {
+            TxpoolOrigin::Local => TransactionOrigin::Local,
+            TxpoolOrigin::External => TransactionOrigin::External,
+            TxpoolOrigin::Private => TransactionOrigin::Private,
+        });
+        let filter = TransactionFilter {
+            senders: senders.into_iter().collect(),
+            recipients: recipients.into_iter().collect(),
+            min_tip: min_tip.map(|min_tip| min_tip.to()),
+        };
+        let matches = |tx: &ValidPoolTransaction<Pool::Transaction>| {
+            origin.map_or(true, |origin| tx.origin == origin) &&
+                after.map_or(true, |after| (tx.sender(), tx.nonce()) > after)
+        };
 
-        let mut content = TxpoolContent::default();
-        for pending in pending {
-            insert(&pending.transaction, &mut content.pending);
+        // all matching transactions, ordered by sender and nonce, and whether they are pending
+        let AllPoolTransactions { pending, queued } =
+            self.pool.pooled_transactions_matching(&filter);
+        let mut transactions = pending
+            .into_iter()
+            .map(|tx| (tx, true))
+            .chain(queued.into_iter().map(|tx| (tx, false)))
+            .filter(|(tx, _)| matches(tx))
+            .collect::<Vec<_>>();
+        transactions.sort_unstable_by_key(|(tx, _)| (tx.sender(), tx.nonce()));
+
+        let mut content = TxpoolContentExt::default();
+        if let Some(limit) = limit.map(|limit| limit as usize) {
+            if transactions.len() > limit {
+                transactions.truncate(limit);
+                content.continuation =
+                    transactions.last().map(|(tx, _)| encode_continuation(tx.sender(), tx.nonce()));
+            }
         }
-        for queued in queued {
-            insert(&queued.transaction, &mut content.queued);
+
+        for (tx, is_pending) in transactions {
+            if is_pending {
+                insert(&tx.transaction, &mut content.content.pending);
+            } else {
+                insert(&tx.transaction, &mut content.content.queued);
+            }
         }
 
-        content
+        Ok(content)
+    }
+
+    fn content_from(&self, from: Address) -> TxpoolContentFrom {
+        #[inline]
+        fn by_nonce<T: PoolTransaction>(
+            txs: Vec<Arc<ValidPoolTransaction<T>>>,
+        ) -> BTreeMap<String, Transaction> {
+            txs.iter().map(|tx| (tx.nonce().to_string(), to_rpc(&tx.transaction))).collect()
+        }
+
+        let AllPoolTransactions { pending, queued } = self
+            .pool
+            .pooled_transactions_matching(&TransactionFilter::default().with_senders([from]));
+        TxpoolContentFrom { pending: by_nonce(pending), queued: by_nonce(queued) }
     }
 }
 
+/// Encodes the continuation token of a `txpool_content` page that ends with the given
+/// transaction.
+fn encode_continuation(sender: Address, nonce: u64) -> String {
+    format!("{sender}:{nonce}")
+}
+
+/// Parses a continuation token of [`encode_continuation`] into the sender and nonce of the last
+/// transaction of the previous page.
+fn parse_continuation(token: &str) -> Result<(Address, u64)> {
+    token
+        .split_once(':')
+        .and_then(|(sender, nonce)| Some((sender.parse().ok()?, nonce.parse().ok()?)))
+        .ok_or_else(|| invalid_params_rpc_err(format!("invalid continuation token: {token}")))
+}
+
+/// Converts a pool transaction into its rpc representation, incl. the blob fee and the versioned
+/// hashes of blob transactions.
+#[inline]
+fn to_rpc<T: PoolTransaction>(tx: &T) -> Transaction {
+    reth_rpc_types_compat::transaction::from_recovered(tx.to_recovered_transaction())
+}
+
 #[async_trait]
 impl<Pool> TxPoolApiServer for TxPoolApi<Pool>
 where
@@ -66,10 +143,16 @@ where
     /// Ref: [Here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_status)
     ///
     /// Handler for `txpool_status`
-    async fn txpool_status(&self) -> Result<TxpoolStatus> {
+    async fn txpool_status(&self) -> Result<TxpoolStatusExt> {
         trace!(target: "rpc::eth", "Serving txpool_status");
-        let all = self.pool.all_transactions();
-        Ok(TxpoolStatus { pending: all.pending.len() as u64, queued: all.queued.len() as u64 })
+        let size = self.pool.pool_size();
+        Ok(TxpoolStatusExt {
+            status: TxpoolStatus {
+                pending: size.pending as u64,
+                queued: (size.basefee + size.queued + size.blob) as u64,
+            },
+            blob: size.blob as u64,
+        })
     }
 
     /// Returns a summary of all the transactions currently pending for inclusion in the next
@@ -78,30 +161,31 @@ where
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_inspect) for more details
     ///
     /// Handler for `txpool_inspect`
-    async fn txpool_inspect(&self) -> Result<TxpoolInspect> {
+    async fn txpool_inspect(&self) -> Result<GethTxpoolInspect> {
         trace!(target: "rpc::eth", "Serving txpool_inspect");
 
         #[inline]
         fn insert<T: PoolTransaction>(
             tx: &T,
-            inspect: &mut BTreeMap<Address, BTreeMap<String, TxpoolInspectSummary>>,
+            inspect: &mut BTreeMap<Address, BTreeMap<String, GethInspectSummary>>,
         ) {
             let entry = inspect.entry(tx.sender()).or_default();
             let tx = tx.to_recovered_transaction();
+            // like geth, the gas price of dynamic fee transactions is their fee cap
             entry.insert(
                 tx.nonce().to_string(),
-                TxpoolInspectSummary {
+                GethInspectSummary(TxpoolInspectSummary {
                     to: tx.to(),
                     value: tx.value(),
                     gas: tx.gas_limit() as u128,
                     gas_price: tx.transaction.max_fee_per_gas(),
-                },
+                }),
             );
         }
 
         let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
 
-        Ok(TxpoolInspect {
+        Ok(GethTxpoolInspect {
             pending: pending.iter().fold(Default::default(), |mut acc, tx| {
                 insert(&tx.transaction, &mut acc);
                 acc
@@ -120,7 +204,7 @@ where
     /// Handler for `txpool_contentFrom`
     async fn txpool_content_from(&self, from: Address) -> Result<TxpoolContentFrom> {
         trace!(target: "rpc::eth", ?from, "Serving txpool_contentFrom");
-        Ok(self.content().remove_from(&from))
+        Ok(self.content_from(from))
     }
 
     /// Returns the details of all transactions currently pending for inclusion in the next
@@ -128,9 +212,12 @@ where
     ///
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content) for more details
     /// Handler for `txpool_content`
-    async fn txpool_content(&self) -> Result<TxpoolContent> {
-        trace!(target: "rpc::eth", "Serving txpool_content");
-        Ok(self.content())
+    async fn txpool_content(
+        &self,
+        filter: Option<TxpoolContentFilter>,
+    ) -> Result<TxpoolContentExt> {
+        trace!(target: "rpc::eth", ?filter, "Serving txpool_content");
+        self.content(filter.unwrap_or_default())
     }
 }
 
@@ -139,3 +226,82 @@ impl<Pool> std::fmt::Debug for TxPoolApi<Pool> {
         f.debug_struct("TxpoolApi").finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_transaction_pool::test_utils::{testing_pool, MockTransaction};
+
+    fn content_len(content: &TxpoolContentExt) -> usize {
+        content
+            .content
+            .pending
+            .values()
+            .chain(content.content.queued.values())
+            .map(BTreeMap::len)
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn content_filtered_by_origin() {
+        let pool = testing_pool();
+        let api = TxPoolApi::new(pool.clone());
+
+        // received via p2p
+        let external = MockTransaction::eip1559();
+        pool.add_external_transaction(external.clone()).await.unwrap();
+        // submitted via rpc
+        let local = MockTransaction::eip1559();
+        pool.add_transaction(TransactionOrigin::Local, local.clone()).await.unwrap();
+
+        let filter =
+            TxpoolContentFilter { origin: Some(TxpoolOrigin::Local), ..Default::default() };
+        let content = api.txpool_content(Some(filter)).await.unwrap();
+        assert_eq!(content_len(&content), 1);
+        assert!(
+            content.content.pending.contains_key(&local.get_sender()) ||
+                content.content.queued.contains_key(&local.get_sender())
+        );
+        assert!(!content.content.pending.contains_key(&external.get_sender()));
+        assert!(!content.content.queued.contains_key(&external.get_sender()));
+
+        let filter =
+            TxpoolContentFilter { origin: Some(TxpoolOrigin::Private), ..Default::default() };
+        assert_eq!(content_len(&api.txpool_content(Some(filter)).await.unwrap()), 0);
+
+        let filter =
+            TxpoolContentFilter { senders: vec![external.get_sender()], ..Default::default() };
+        assert_eq!(content_len(&api.txpool_content(Some(filter)).await.unwrap()), 1);
+
+        let content = api.txpool_content(None).await.unwrap();
+        assert_eq!(content_len(&content), 2);
+        assert_eq!(content.continuation, None);
+    }
+
+    #[tokio::test]
+    async fn content_paginated() {
+        let pool = testing_pool();
+        let api = TxPoolApi::new(pool.clone());
+
+        let tx = MockTransaction::eip1559();
+        for tx in [tx.clone(), tx.next(), tx.next().next()] {
+            pool.add_external_transaction(tx).await.unwrap();
+        }
+
+        let filter = TxpoolContentFilter { limit: Some(2), ..Default::default() };
+        let page = api.txpool_content(Some(filter.clone())).await.unwrap();
+        assert_eq!(content_len(&page), 2);
+        let continuation = page.continuation.expect("more transactions match");
+
+        let filter = TxpoolContentFilter { continuation: Some(continuation), ..filter };
+        let page = api.txpool_content(Some(filter)).await.unwrap();
+        assert_eq!(content_len(&page), 1);
+        assert_eq!(page.continuation, None);
+
+        let filter = TxpoolContentFilter { limit: Some(0), ..Default::default() };
+        assert!(api.txpool_content(Some(filter)).await.is_err());
+        let filter =
+            TxpoolContentFilter { continuation: Some("invalid".to_string()), ..Default::default() };
+        assert!(api.txpool_content(Some(filter)).await.is_err());
+    }
+}
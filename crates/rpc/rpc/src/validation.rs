@@ -0,0 +1,892 @@
+//! Implementation of the block submission validation endpoints of the `flashbots` namespace.
+
+use crate::result::{internal_rpc_err, rpc_error_with_code};
+use async_trait::async_trait;
+use jsonrpsee::{core::RpcResult, types::ErrorObject};
+use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
+use reth_errors::{BlockExecutionError, ProviderError};
+use reth_evm::execute::{BlockExecutorProvider, Executor};
+use reth_payload_validator::ExecutionPayloadValidator;
+use reth_primitives::{
+    eip4844::kzg_to_versioned_hash, kzg::KzgSettings, BlobTransactionSidecar,
+    BlobTransactionValidationError, GotExpected, Receipt, SealedBlockWithSenders, SealedHeader,
+    B256, U256,
+};
+use reth_provider::{ChainSpecProvider, HeaderProvider, StateProviderFactory, StateRootProvider};
+use reth_revm::database::StateProviderDatabase;
+use reth_rpc_api::BlockSubmissionValidationApiServer;
+use reth_rpc_types::{
+    beacon::relay::{BidTrace, BuilderBlockValidationRequest, BuilderBlockValidationRequestV2},
+    engine::{BlobsBundleV1, CancunPayloadFields},
+    BuilderBlockValidationRequestV3, BuilderBlockValidationRequestV4, ExecutionPayload,
+    PayloadError,
+};
+use reth_tasks::TaskSpawner;
+use revm::db::BundleState;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+
+/// The error code of submissions whose block is invalid or doesn't match the bid.
+pub const INVALID_BLOCK_CODE: i32 = -38200;
+/// The error code of submissions that don't pay the bid value to the proposer.
+pub const PROPOSER_PAYMENT_CODE: i32 = -38201;
+/// The error code of submissions whose blobs don't match the blob transactions of the block.
+pub const BLOBS_MISMATCH_CODE: i32 = -38202;
+/// The error code of submissions whose parent block is unknown.
+pub const UNKNOWN_PARENT_CODE: i32 = -38203;
+
+/// `flashbots` API implementation for validating the block submissions of builders.
+///
+/// The block of a submission is executed on top of the state of its parent block, with the same
+/// payload validation, consensus rules and executor as blocks received by the engine API.
+pub struct ValidationApi<Provider, E> {
+    inner: Arc<ValidationApiInner<Provider, E>>,
+}
+
+impl<Provider, E> ValidationApi<Provider, E>
+where
+    Provider: ChainSpecProvider,
+{
+    /// Create a new instance of the [`ValidationApi`]
+    pub fn new(
+        provider: Provider,
+        consensus: Arc<dyn Consensus>,
+        executor_provider: E,
+        kzg_settings: Arc<KzgSettings>,
+        task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        let payload_validator = ExecutionPayloadValidator::new(provider.chain_spec());
+        let inner = Arc::new(ValidationApiInner {
+            provider,
+            consensus,
+            executor_provider,
+            payload_validator,
+            kzg_settings,
+            task_spawner,
+        });
+        Self { inner }
+    }
+}
+
+impl<Provider, E> ValidationApi<Provider, E>
+where
+    Provider: HeaderProvider + StateProviderFactory + ChainSpecProvider + 'static,
+    E: BlockExecutorProvider,
+{
+    /// Validates the submission on a blocking task.
+    async fn validate_submission(&self, submission: BlockSubmission) -> RpcResult<()> {
+        let (tx, rx) = oneshot::channel();
+        let this = self.clone();
+        self.inner.task_spawner.spawn_blocking(Box::pin(async move {
+            let _ = tx.send(this.validate_submission_blocking(submission));
+        }));
+        let res = rx.await.map_err(|_| internal_rpc_err("failed to validate the submission"))?;
+        Ok(res?)
+    }
+
+    /// Validates the block of a submission and the payment of the bid value to the proposer.
+    ///
+    /// Caution: this is blocking
+    fn validate_submission_blocking(
+        &self,
+        submission: BlockSubmission,
+    ) -> Result<(), ValidationApiError> {
+        let BlockSubmission {
+            message,
+            payload,
+            blobs_bundle,
+            registered_gas_limit,
+            parent_beacon_block_root,
+        } = submission;
+
+        let (versioned_hashes, sidecar) = blobs_bundle_into_sidecar(blobs_bundle)?;
+
+        // The proofs of the blobs are verified in one batch on the rayon pool, in parallel with
+        // the conversion and the pre-execution checks of the block, which don't depend on them.
+        let (blobs, block) = rayon::join(
+            || sidecar.validate(&versioned_hashes, &self.inner.kzg_settings),
+            || {
+                self.pre_validate_block(
+                    &message,
+                    payload,
+                    versioned_hashes.clone(),
+                    parent_beacon_block_root,
+                    registered_gas_limit,
+                )
+            },
+        );
+        // the blobs are checked first, so the error is the same as if they were verified before
+        // the block
+        blobs?;
+        let PreValidatedBlock { block, total_difficulty } = block?;
+
+        let parent_hash = block.parent_hash;
+        let state = self.inner.provider.history_by_block_hash(parent_hash)?;
+        let unsealed = block.clone().unseal();
+        let output = self
+            .inner
+            .executor_provider
+            .executor(StateProviderDatabase::new(&state))
+            .execute((&unsealed, total_difficulty).into())?;
+        self.inner.consensus.validate_block_post_execution(
+            &unsealed,
+            PostExecutionInput::new(&output.receipts, &output.requests),
+        )?;
+
+        let state_root = state.state_root(&output.state)?;
+        if state_root != block.state_root {
+            return Err(ConsensusError::BodyStateRootDiff(
+                GotExpected { got: state_root, expected: block.state_root }.into(),
+            )
+            .into())
+        }
+
+        ensure_proposer_payment(&message, &block, &output.state, &output.receipts)
+    }
+
+    /// Converts the payload of a submission into a block and runs all checks that don't require
+    /// its execution.
+    fn pre_validate_block(
+        &self,
+        message: &BidTrace,
+        payload: ExecutionPayload,
+        versioned_hashes: Vec<B256>,
+        parent_beacon_block_root: B256,
+        registered_gas_limit: u64,
+    ) -> Result<PreValidatedBlock, ValidationApiError> {
+        let block = self
+            .inner
+            .payload_validator
+            .ensure_well_formed_payload(
+                payload,
+                Some(CancunPayloadFields { parent_beacon_block_root, versioned_hashes }).into(),
+            )
+            .map_err(|err| match err {
+                PayloadError::InvalidVersionedHashes => ValidationApiError::BlobVersionedHashes,
+                err => ValidationApiError::Payload(err),
+            })?;
+        validate_message_against_block(message, &block.header)?;
+
+        let parent_hash = block.parent_hash;
+        let parent = self
+            .inner
+            .provider
+            .header(&parent_hash)?
+            .ok_or(ValidationApiError::UnknownParent(parent_hash))?
+            .seal(parent_hash);
+        validate_gas_limit(registered_gas_limit, &parent, &block.header)?;
+
+        let total_difficulty = self
+            .inner
+            .provider
+            .header_td(&parent_hash)?
+            .ok_or(ValidationApiError::UnknownParent(parent_hash))?;
+        let consensus = &self.inner.consensus;
+        consensus.validate_header_with_total_difficulty(&block.header, total_difficulty)?;
+        consensus.validate_header(&block.header)?;
+        consensus.validate_header_against_parent(&block.header, &parent)?;
+        consensus.validate_block_pre_execution(&block)?;
+
+        let block = block
+            .try_seal_with_senders()
+            .map_err(|_| ValidationApiError::InvalidTransactionSignature)?;
+
+        Ok(PreValidatedBlock { block, total_difficulty })
+    }
+}
+
+#[async_trait]
+impl<Provider, E> BlockSubmissionValidationApiServer for ValidationApi<Provider, E>
+where
+    Provider: HeaderProvider + StateProviderFactory + ChainSpecProvider + 'static,
+    E: BlockExecutorProvider,
+{
+    /// Handler for `flashbots_validateBuilderSubmissionV1`
+    async fn validate_builder_submission_v1(
+        &self,
+        _request: BuilderBlockValidationRequest,
+    ) -> RpcResult<()> {
+        Err(internal_rpc_err("pre-Deneb submissions are not supported"))
+    }
+
+    /// Handler for `flashbots_validateBuilderSubmissionV2`
+    async fn validate_builder_submission_v2(
+        &self,
+        _request: BuilderBlockValidationRequestV2,
+    ) -> RpcResult<()> {
+        Err(internal_rpc_err("pre-Deneb submissions are not supported"))
+    }
+
+    /// Handler for `flashbots_validateBuilderSubmissionV3`
+    async fn validate_builder_submission_v3(
+        &self,
+        request: BuilderBlockValidationRequestV3,
+    ) -> RpcResult<()> {
+        let BuilderBlockValidationRequestV3 {
+            request,
+            registered_gas_limit,
+            parent_beacon_block_root,
+        } = request;
+        self.validate_submission(BlockSubmission {
+            message: request.message,
+            payload: ExecutionPayload::V3(request.execution_payload),
+            blobs_bundle: request.blobs_bundle,
+            registered_gas_limit,
+            parent_beacon_block_root,
+        })
+        .await
+    }
+
+    /// Handler for `flashbots_validateBuilderSubmissionV4`
+    async fn validate_builder_submission_v4(
+        &self,
+        request: BuilderBlockValidationRequestV4,
+    ) -> RpcResult<()> {
+        let BuilderBlockValidationRequestV4 {
+            request,
+            registered_gas_limit,
+            parent_beacon_block_root,
+        } = request;
+        self.validate_submission(BlockSubmission {
+            message: request.message,
+            payload: ExecutionPayload::V4(request.execution_payload),
+            blobs_bundle: request.blobs_bundle,
+            registered_gas_limit,
+            parent_beacon_block_root,
+        })
+        .await
+    }
+}
+
+impl<Provider, E> std::fmt::Debug for ValidationApi<Provider, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidationApi").finish_non_exhaustive()
+    }
+}
+
+impl<Provider, E> Clone for ValidationApi<Provider, E> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+struct ValidationApiInner<Provider, E> {
+    /// The provider of the parent blocks and their state.
+    provider: Provider,
+    /// The consensus rules the blocks are validated against.
+    consensus: Arc<dyn Consensus>,
+    /// Executes the blocks.
+    executor_provider: E,
+    /// Converts the payloads into blocks.
+    payload_validator: ExecutionPayloadValidator,
+    /// The settings the proofs of the blobs are verified with.
+    kzg_settings: Arc<KzgSettings>,
+    /// The type that can spawn tasks which would otherwise block.
+    task_spawner: Box<dyn TaskSpawner>,
+}
+
+/// A block submission of any version.
+#[derive(Debug)]
+struct BlockSubmission {
+    message: BidTrace,
+    payload: ExecutionPayload,
+    blobs_bundle: BlobsBundleV1,
+    registered_gas_limit: u64,
+    parent_beacon_block_root: B256,
+}
+
+/// A block of a submission that passed all checks that don't require its execution.
+#[derive(Debug)]
+struct PreValidatedBlock {
+    block: SealedBlockWithSenders,
+    total_difficulty: U256,
+}
+
+/// Ensures the blobs bundle has as many proofs and blobs as commitments, and returns the versioned
+/// hashes of the commitments with the sidecar the proofs can be verified with.
+fn blobs_bundle_into_sidecar(
+    blobs_bundle: BlobsBundleV1,
+) -> Result<(Vec<B256>, BlobTransactionSidecar), ValidationApiError> {
+    let BlobsBundleV1 { commitments, proofs, blobs } = blobs_bundle;
+    if commitments.len() != proofs.len() || commitments.len() != blobs.len() {
+        return Err(ValidationApiError::BlobsBundleLength {
+            commitments: commitments.len(),
+            proofs: proofs.len(),
+            blobs: blobs.len(),
+        })
+    }
+
+    let versioned_hashes = commitments
+        .iter()
+        .map(|commitment| kzg_to_versioned_hash(commitment.as_slice()))
+        .collect::<Vec<_>>();
+    Ok((versioned_hashes, BlobTransactionSidecar { blobs, commitments, proofs }))
+}
+
+/// Ensures the bid describes the block.
+fn validate_message_against_block(
+    message: &BidTrace,
+    header: &SealedHeader,
+) -> Result<(), ValidationApiError> {
+    if message.block_hash != header.hash() {
+        return Err(ValidationApiError::BlockHashMismatch(GotExpected {
+            got: message.block_hash,
+            expected: header.hash(),
+        }))
+    }
+    if message.parent_hash != header.parent_hash {
+        return Err(ValidationApiError::ParentHashMismatch(GotExpected {
+            got: message.parent_hash,
+            expected: header.parent_hash,
+        }))
+    }
+    if message.gas_limit != header.gas_limit {
+        return Err(ValidationApiError::GasLimitMismatch(GotExpected {
+            got: message.gas_limit,
+            expected: header.gas_limit,
+        }))
+    }
+    if message.gas_used != header.gas_used {
+        return Err(ValidationApiError::GasUsedMismatch(GotExpected {
+            got: message.gas_used,
+            expected: header.gas_used,
+        }))
+    }
+    Ok(())
+}
+
+/// Ensures the gas limit of the block moves from the gas limit of the parent towards the gas
+/// limit registered by the proposer as far as the bound divisor allows.
+fn validate_gas_limit(
+    registered_gas_limit: u64,
+    parent: &SealedHeader,
+    header: &SealedHeader,
+) -> Result<(), ValidationApiError> {
+    let expected = parent.next_block_gas_limit(registered_gas_limit);
+    if header.gas_limit != expected {
+        return Err(ValidationApiError::RegisteredGasLimit(GotExpected {
+            got: header.gas_limit,
+            expected,
+        }))
+    }
+    Ok(())
+}
+
+/// Ensures the block pays the bid value to the proposer.
+///
+/// The payment is either the increase of the balance of the fee recipient of the proposer, not
+/// counting withdrawals, or a plain transfer of the value to the fee recipient as the last
+/// transaction of the block.
+fn ensure_proposer_payment(
+    message: &BidTrace,
+    block: &SealedBlockWithSenders,
+    state: &BundleState,
+    receipts: &[Receipt],
+) -> Result<(), ValidationApiError> {
+    let fee_recipient = message.proposer_fee_recipient;
+    let (mut balance_before, balance_after) = state
+        .state
+        .get(&fee_recipient)
+        .map(|account| {
+            (
+                account.original_info.as_ref().map(|info| info.balance).unwrap_or_default(),
+                account.info.as_ref().map(|info| info.balance).unwrap_or_default(),
+            )
+        })
+        .unwrap_or_default();
+    for withdrawal in block.withdrawals.iter().flat_map(|withdrawals| withdrawals.iter()) {
+        if withdrawal.address == fee_recipient {
+            balance_before += withdrawal.amount_wei();
+        }
+    }
+    if balance_after >= balance_before.saturating_add(message.value) {
+        return Ok(())
+    }
+
+    let (Some(tx), Some(receipt)) = (block.body.last(), receipts.last()) else {
+        return Err(ValidationApiError::ProposerPayment("no payment transaction"))
+    };
+    if !receipt.success {
+        return Err(ValidationApiError::ProposerPayment("payment transaction failed"))
+    }
+    if tx.to() != Some(fee_recipient) {
+        return Err(ValidationApiError::ProposerPayment(
+            "payment transaction is not sent to the fee recipient",
+        ))
+    }
+    if tx.value() != message.value {
+        return Err(ValidationApiError::ProposerPayment(
+            "payment transaction value doesn't match the bid value",
+        ))
+    }
+    if !tx.input().is_empty() {
+        return Err(ValidationApiError::ProposerPayment("payment transaction has calldata"))
+    }
+    if tx.effective_tip_per_gas(block.base_fee_per_gas).unwrap_or_default() != 0 {
+        return Err(ValidationApiError::ProposerPayment("payment transaction has a priority fee"))
+    }
+    Ok(())
+}
+
+/// Errors of the block submission validation endpoints.
+#[derive(Debug, thiserror::Error)]
+pub enum ValidationApiError {
+    /// The block hash of the bid doesn't match the block.
+    #[error("block hash mismatch: {0}")]
+    BlockHashMismatch(GotExpected<B256>),
+    /// The parent hash of the bid doesn't match the block.
+    #[error("parent hash mismatch: {0}")]
+    ParentHashMismatch(GotExpected<B256>),
+    /// The gas limit of the bid doesn't match the block.
+    #[error("gas limit mismatch: {0}")]
+    GasLimitMismatch(GotExpected<u64>),
+    /// The gas used of the bid doesn't match the block.
+    #[error("gas used mismatch: {0}")]
+    GasUsedMismatch(GotExpected<u64>),
+    /// The gas limit of the block doesn't follow the gas limit registered by the proposer.
+    #[error("incorrect gas limit set: {0}")]
+    RegisteredGasLimit(GotExpected<u64>),
+    /// The parent of the block is unknown.
+    #[error("parent block {0} not found")]
+    UnknownParent(B256),
+    /// The payload can't be converted into a valid block.
+    #[error(transparent)]
+    Payload(PayloadError),
+    /// The sender of a transaction of the block can't be recovered.
+    #[error("invalid transaction signature")]
+    InvalidTransactionSignature,
+    /// The block violates the consensus rules.
+    #[error(transparent)]
+    Consensus(#[from] ConsensusError),
+    /// The block can't be executed.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// The block doesn't pay the bid value to the proposer.
+    #[error("proposer payment: {0}")]
+    ProposerPayment(&'static str),
+    /// The blobs bundle has different numbers of commitments, proofs and blobs.
+    #[error("blobs bundle has {commitments} commitments, {proofs} proofs and {blobs} blobs")]
+    BlobsBundleLength {
+        /// The number of commitments.
+        commitments: usize,
+        /// The number of proofs.
+        proofs: usize,
+        /// The number of blobs.
+        blobs: usize,
+    },
+    /// The proofs of the blobs don't match their commitments.
+    #[error("invalid blobs: {0}")]
+    InvalidBlobs(#[from] BlobTransactionValidationError),
+    /// The commitments of the blobs don't match the blob transactions of the block.
+    #[error("blob commitments don't match the versioned hashes of the block")]
+    BlobVersionedHashes,
+    /// Failed to read the parent block or its state.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+}
+
+impl ValidationApiError {
+    /// Returns the JSON-RPC error code of the category of the error.
+    pub const fn error_code(&self) -> i32 {
+        match self {
+            Self::ProposerPayment(_) => PROPOSER_PAYMENT_CODE,
+            Self::BlobsBundleLength { .. } | Self::InvalidBlobs(_) | Self::BlobVersionedHashes => {
+                BLOBS_MISMATCH_CODE
+            }
+            Self::UnknownParent(_) => UNKNOWN_PARENT_CODE,
+            Self::Provider(_) => jsonrpsee::types::error::INTERNAL_ERROR_CODE,
+            _ => INVALID_BLOCK_CODE,
+        }
+    }
+}
+
+impl From<ValidationApiError> for ErrorObject<'static> {
+    fn from(err: ValidationApiError) -> Self {
+        rpc_error_with_code(err.error_code(), err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::eip4788::{BEACON_ROOTS_ADDRESS, BEACON_ROOTS_CODE};
+    use reth_ethereum_consensus::EthBeaconConsensus;
+    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_primitives::{
+        constants::{eip4844::MAINNET_KZG_TRUSTED_SETUP, EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH},
+        kzg, proofs, public_key_to_address, Address, ChainSpecBuilder, Header, ReceiptWithBloom,
+        SealedBlock, Transaction, TransactionSigned, TxEip1559, TxKind, TxType, Withdrawal,
+        Withdrawals,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_types_compat::engine::payload::block_to_payload_v3;
+    use reth_tasks::TokioTaskExecutor;
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use revm::primitives::{AccountInfo, HashMap};
+
+    const GAS_LIMIT: u64 = 30_000_000;
+    const BID: u64 = 1_000_000;
+    const FEE_RECIPIENT: Address = Address::with_last_byte(0xfe);
+
+    fn sealed_header(gas_limit: u64) -> SealedHeader {
+        Header { gas_limit, ..Default::default() }.seal_slow()
+    }
+
+    /// A Cancun chain with a parent block, and a submission of a block on top of it whose last
+    /// transaction pays the bid to the proposer.
+    struct TestSubmission {
+        api: ValidationApi<MockEthProvider, EthExecutorProvider>,
+        parent: SealedHeader,
+        payment: TransactionSigned,
+    }
+
+    impl TestSubmission {
+        fn new() -> Self {
+            let chain_spec = Arc::new(ChainSpecBuilder::mainnet().cancun_activated().build());
+            let mut provider = MockEthProvider::default();
+            provider.chain_spec = chain_spec.clone();
+
+            let parent = Header {
+                gas_limit: GAS_LIMIT,
+                timestamp: 1,
+                base_fee_per_gas: Some(7),
+                withdrawals_root: Some(EMPTY_ROOT_HASH),
+                blob_gas_used: Some(0),
+                excess_blob_gas: Some(0),
+                parent_beacon_block_root: Some(B256::ZERO),
+                ..Default::default()
+            }
+            .seal_slow();
+            provider.add_header(parent.hash(), parent.header().clone());
+            provider.add_account(
+                BEACON_ROOTS_ADDRESS,
+                ExtendedAccount::new(1, U256::ZERO).with_bytecode(BEACON_ROOTS_CODE.clone()),
+            );
+
+            let key = generators::generate_keys(&mut generators::rng(), 1)[0];
+            let sender = public_key_to_address(key.public_key());
+            provider.add_account(sender, ExtendedAccount::new(0, U256::from(u64::MAX)));
+            let payment = sign_tx_with_key_pair(
+                key,
+                Transaction::Eip1559(TxEip1559 {
+                    chain_id: 1,
+                    nonce: 0,
+                    gas_limit: 21_000,
+                    max_fee_per_gas: 7,
+                    max_priority_fee_per_gas: 0,
+                    to: TxKind::Call(FEE_RECIPIENT),
+                    value: U256::from(BID),
+                    ..Default::default()
+                }),
+            );
+
+            let api = ValidationApi::new(
+                provider,
+                Arc::new(EthBeaconConsensus::new(chain_spec.clone())),
+                EthExecutorProvider::ethereum(chain_spec),
+                MAINNET_KZG_TRUSTED_SETUP.clone(),
+                Box::<TokioTaskExecutor>::default(),
+            );
+            Self { api, parent, payment }
+        }
+
+        /// Returns a valid block paying the bid, modified by the given function before it's sealed.
+        fn block(&self, f: impl FnOnce(&mut Header)) -> SealedBlock {
+            let body = vec![self.payment.clone()];
+            let receipt = Receipt {
+                tx_type: TxType::Eip1559,
+                success: true,
+                cumulative_gas_used: 21_000,
+                logs: vec![],
+                ..Default::default()
+            };
+            let mut header = Header {
+                parent_hash: self.parent.hash(),
+                ommers_hash: EMPTY_OMMER_ROOT_HASH,
+                beneficiary: Address::with_last_byte(0xb0),
+                transactions_root: proofs::calculate_transaction_root(&body),
+                receipts_root: proofs::calculate_receipt_root(&[ReceiptWithBloom::from(receipt)]),
+                withdrawals_root: Some(EMPTY_ROOT_HASH),
+                number: 1,
+                gas_limit: GAS_LIMIT,
+                gas_used: 21_000,
+                timestamp: 2,
+                base_fee_per_gas: Some(7),
+                blob_gas_used: Some(0),
+                excess_blob_gas: Some(0),
+                parent_beacon_block_root: Some(B256::ZERO),
+                // the state root of the mock provider
+                state_root: B256::ZERO,
+                ..Default::default()
+            };
+            f(&mut header);
+            SealedBlock {
+                header: header.seal_slow(),
+                body,
+                ommers: vec![],
+                withdrawals: Some(Withdrawals::default()),
+                requests: None,
+            }
+        }
+
+        /// Returns the submission of the block with a bid of the given value.
+        fn submission(&self, block: SealedBlock, value: u64) -> BlockSubmission {
+            let message = BidTrace {
+                slot: 1,
+                parent_hash: block.parent_hash,
+                block_hash: block.hash(),
+                builder_pubkey: Default::default(),
+                proposer_pubkey: Default::default(),
+                proposer_fee_recipient: FEE_RECIPIENT,
+                gas_limit: block.gas_limit,
+                gas_used: block.gas_used,
+                value: U256::from(value),
+            };
+            let (payload, parent_beacon_block_root) = block_to_payload_v3(block);
+            BlockSubmission {
+                message,
+                payload: ExecutionPayload::V3(payload),
+                blobs_bundle: BlobsBundleV1 { commitments: vec![], proofs: vec![], blobs: vec![] },
+                registered_gas_limit: GAS_LIMIT,
+                parent_beacon_block_root: parent_beacon_block_root.unwrap(),
+            }
+        }
+
+        fn validate(&self, submission: BlockSubmission) -> Result<(), ValidationApiError> {
+            self.api.validate_submission_blocking(submission)
+        }
+    }
+
+    /// A blobs bundle with a single valid blob.
+    fn blobs_bundle() -> BlobsBundleV1 {
+        let settings = MAINNET_KZG_TRUSTED_SETUP.as_ref();
+        let blob = kzg::Blob::from_bytes(&[1; kzg::BYTES_PER_BLOB]).unwrap();
+        let commitment = kzg::KzgCommitment::blob_to_kzg_commitment(&blob, settings).unwrap();
+        let commitment = commitment.to_bytes();
+        let proof = kzg::KzgProof::compute_blob_kzg_proof(&blob, &commitment, settings).unwrap();
+        let sidecar =
+            BlobTransactionSidecar::from_kzg(vec![blob], vec![commitment], vec![proof.to_bytes()]);
+        BlobsBundleV1 {
+            commitments: sidecar.commitments,
+            proofs: sidecar.proofs,
+            blobs: sidecar.blobs,
+        }
+    }
+
+    #[test]
+    fn valid_submission() {
+        let test = TestSubmission::new();
+        test.validate(test.submission(test.block(|_| {}), BID)).unwrap();
+    }
+
+    #[test]
+    fn proposer_payment_mismatch() {
+        let test = TestSubmission::new();
+        let err = test.validate(test.submission(test.block(|_| {}), BID + 1)).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ValidationApiError::ProposerPayment(
+                    "payment transaction value doesn't match the bid value"
+                )
+            ),
+            "{err:?}"
+        );
+        assert_eq!(err.error_code(), PROPOSER_PAYMENT_CODE);
+    }
+
+    #[test]
+    fn execution_mismatch() {
+        let test = TestSubmission::new();
+        // the header claims more gas than the execution of the block uses
+        let block = test.block(|header| header.gas_used = 42_000);
+        let err = test.validate(test.submission(block, BID)).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ValidationApiError::Consensus(ConsensusError::BlockGasUsed {
+                    gas: GotExpected { got: 21_000, expected: 42_000 },
+                    ..
+                })
+            ),
+            "{err:?}"
+        );
+        assert_eq!(err.error_code(), INVALID_BLOCK_CODE);
+    }
+
+    #[test]
+    fn state_root_mismatch() {
+        let test = TestSubmission::new();
+        let block = test.block(|header| header.state_root = B256::with_last_byte(1));
+        let err = test.validate(test.submission(block, BID)).unwrap_err();
+        assert!(
+            matches!(err, ValidationApiError::Consensus(ConsensusError::BodyStateRootDiff(_))),
+            "{err:?}"
+        );
+        assert_eq!(err.error_code(), INVALID_BLOCK_CODE);
+    }
+
+    #[test]
+    fn blob_mismatch() {
+        let test = TestSubmission::new();
+
+        // the blob has no blob transaction in the block
+        let mut submission = test.submission(test.block(|_| {}), BID);
+        submission.blobs_bundle = blobs_bundle();
+        let err = test.validate(submission).unwrap_err();
+        assert!(matches!(err, ValidationApiError::BlobVersionedHashes), "{err:?}");
+        assert_eq!(err.error_code(), BLOBS_MISMATCH_CODE);
+
+        // the proof doesn't match the blob
+        let mut submission = test.submission(test.block(|_| {}), BID);
+        submission.blobs_bundle = blobs_bundle();
+        submission.blobs_bundle.proofs[0] = Default::default();
+        let err = test.validate(submission).unwrap_err();
+        assert!(matches!(err, ValidationApiError::InvalidBlobs(_)), "{err:?}");
+        assert_eq!(err.error_code(), BLOBS_MISMATCH_CODE);
+    }
+
+    /// Returns a block whose last transaction sends the value to the fee recipient.
+    fn block_with_payment(value: u64, max_priority_fee_per_gas: u128) -> SealedBlockWithSenders {
+        let transaction = Transaction::Eip1559(TxEip1559 {
+            chain_id: 1,
+            gas_limit: 21_000,
+            max_fee_per_gas: 7 + max_priority_fee_per_gas,
+            max_priority_fee_per_gas,
+            to: TxKind::Call(FEE_RECIPIENT),
+            value: U256::from(value),
+            ..Default::default()
+        });
+        let key = generators::generate_keys(&mut generators::rng(), 1)[0];
+        let block = SealedBlock {
+            header: Header { base_fee_per_gas: Some(7), ..Default::default() }.seal_slow(),
+            body: vec![sign_tx_with_key_pair(key, transaction)],
+            withdrawals: Some(Withdrawals::new(vec![Withdrawal {
+                address: FEE_RECIPIENT,
+                amount: 1,
+                ..Default::default()
+            }])),
+            ..Default::default()
+        };
+        block.try_seal_with_senders().unwrap()
+    }
+
+    /// Returns the state with the balance change of the fee recipient.
+    fn fee_recipient_state(before: u64, after: u64) -> BundleState {
+        let info =
+            |balance| Some(AccountInfo { balance: U256::from(balance), ..Default::default() });
+        BundleState::new(
+            vec![(FEE_RECIPIENT, info(before), info(after), HashMap::default())],
+            vec![vec![(FEE_RECIPIENT, Some(info(before)), vec![])]],
+            vec![],
+        )
+    }
+
+    fn receipt(success: bool) -> Receipt {
+        Receipt {
+            tx_type: TxType::Eip1559,
+            success,
+            cumulative_gas_used: 21_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn proposer_payment() {
+        let message = |value| BidTrace {
+            slot: 1,
+            parent_hash: B256::ZERO,
+            block_hash: B256::ZERO,
+            builder_pubkey: Default::default(),
+            proposer_pubkey: Default::default(),
+            proposer_fee_recipient: FEE_RECIPIENT,
+            gas_limit: 0,
+            gas_used: 0,
+            value: U256::from(value),
+        };
+        let payment = |value, tip, state: BundleState, success| {
+            ensure_proposer_payment(
+                &message(value),
+                &block_with_payment(value, tip),
+                &state,
+                &[receipt(success)],
+            )
+        };
+
+        // the balance of the fee recipient increases by the bid, not counting the withdrawal of 1
+        // gwei
+        let gwei = 1_000_000_000;
+        payment(100, 0, fee_recipient_state(0, gwei + 100), true).unwrap();
+
+        // the fee recipient spent more than it received, the last transaction pays the bid
+        payment(100, 0, fee_recipient_state(1_000, 500), true).unwrap();
+        let err = payment(100, 0, fee_recipient_state(1_000, 500), false).unwrap_err();
+        assert!(matches!(err, ValidationApiError::ProposerPayment("payment transaction failed")));
+        let err = payment(100, 1, fee_recipient_state(1_000, 500), true).unwrap_err();
+        assert!(matches!(
+            err,
+            ValidationApiError::ProposerPayment("payment transaction has a priority fee")
+        ));
+
+        // the withdrawal alone doesn't pay the bid, and the last transaction pays less
+        let err = ensure_proposer_payment(
+            &message(200),
+            &block_with_payment(100, 0),
+            &fee_recipient_state(0, gwei + 100),
+            &[receipt(true)],
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ValidationApiError::ProposerPayment(
+                "payment transaction value doesn't match the bid value"
+            )
+        ));
+        assert_eq!(err.error_code(), PROPOSER_PAYMENT_CODE);
+    }
+
+    #[test]
+    fn registered_gas_limit() {
+        let parent = sealed_header(30_000_000);
+        // the gas limit can move by less than 1/1024 of the parent gas limit per block
+        let max_change = 30_000_000 / 1024 - 1;
+
+        validate_gas_limit(30_000_000, &parent, &sealed_header(30_000_000)).unwrap();
+        validate_gas_limit(36_000_000, &parent, &sealed_header(30_000_000 + max_change)).unwrap();
+        validate_gas_limit(20_000_000, &parent, &sealed_header(30_000_000 - max_change)).unwrap();
+        validate_gas_limit(30_010_000, &parent, &sealed_header(30_010_000)).unwrap();
+
+        let err = validate_gas_limit(36_000_000, &parent, &sealed_header(30_000_000)).unwrap_err();
+        assert!(matches!(
+            err,
+            ValidationApiError::RegisteredGasLimit(GotExpected { got: 30_000_000, expected })
+                if expected == 30_000_000 + max_change
+        ));
+        assert_eq!(err.error_code(), INVALID_BLOCK_CODE);
+    }
+
+    #[test]
+    fn blobs_bundle_length_mismatch() {
+        let blobs_bundle = BlobsBundleV1 {
+            commitments: vec![Default::default(); 2],
+            proofs: vec![Default::default(); 2],
+            blobs: vec![Default::default()],
+        };
+        let err = blobs_bundle_into_sidecar(blobs_bundle).unwrap_err();
+        assert!(matches!(
+            err,
+            ValidationApiError::BlobsBundleLength { commitments: 2, proofs: 2, blobs: 1 }
+        ));
+        assert_eq!(err.error_code(), BLOBS_MISMATCH_CODE);
+    }
+
+    #[test]
+    fn error_codes() {
+        assert_eq!(
+            ValidationApiError::ProposerPayment("no payment transaction").error_code(),
+            PROPOSER_PAYMENT_CODE
+        );
+        assert_eq!(ValidationApiError::BlobVersionedHashes.error_code(), BLOBS_MISMATCH_CODE);
+        assert_eq!(ValidationApiError::UnknownParent(B256::ZERO).error_code(), UNKNOWN_PARENT_CODE);
+    }
+}
@@ -0,0 +1,184 @@
+//! Recording of the `vmTrace` of the `trace` namespace within the limits of the node.
+//!
+//! A `vmTrace` records every executed instruction together with a snapshot of the memory, so a
+//! single call can easily take gigabytes. Once a limit is exceeded the remaining instructions are
+//! no longer recorded, the traces of the calls are still complete.
+
+use crate::trace_guard::TracingMetrics;
+use reth_rpc_types::{trace::parity::TraceType, LimitedTraceResults};
+use revm::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    primitives::{db::DatabaseRef, Address, ExecutionResult, Log, ResultAndState, U256},
+    Database, EvmContext, Inspector,
+};
+use revm_inspectors::tracing::{TracingInspector, TracingInspectorConfig};
+use std::collections::HashSet;
+
+/// The limits of a `vmTrace`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct VmTraceLimits {
+    /// The maximum number of recorded instructions.
+    pub(crate) max_steps: usize,
+    /// The maximum combined size of the recorded memory snapshots in bytes.
+    pub(crate) max_memory: usize,
+}
+
+/// Traces a call for the parity trace types and stops recording instructions for the `vmTrace`
+/// once its limits are exceeded.
+#[derive(Debug)]
+pub(crate) struct VmTracer {
+    inspector: TracingInspector,
+    /// The limits if a `vmTrace` is recorded.
+    limits: Option<VmTraceLimits>,
+    /// The number of recorded instructions.
+    recorded_steps: usize,
+    /// The combined size of the recorded memory snapshots.
+    recorded_memory: usize,
+    /// Whether each instruction that is still executing was recorded, nested calls are executed
+    /// within the instruction that made them.
+    open_steps: Vec<bool>,
+    /// Whether any instruction was not recorded.
+    truncated: bool,
+    /// Metrics of the guardrails.
+    metrics: TracingMetrics,
+}
+
+impl VmTracer {
+    /// Creates a tracer for the given trace types.
+    pub(crate) fn new(
+        trace_types: &HashSet<TraceType>,
+        limits: VmTraceLimits,
+        metrics: TracingMetrics,
+    ) -> Self {
+        let config = TracingInspectorConfig::from_parity_config(trace_types);
+        Self {
+            inspector: TracingInspector::new(config),
+            limits: trace_types.contains(&TraceType::VmTrace).then_some(limits),
+            recorded_steps: 0,
+            recorded_memory: 0,
+            open_steps: Vec::new(),
+            truncated: false,
+            metrics,
+        }
+    }
+
+    /// Returns the requested traces of the traced call.
+    pub(crate) fn into_trace_results(
+        self,
+        res: &ExecutionResult,
+        trace_types: &HashSet<TraceType>,
+    ) -> LimitedTraceResults {
+        self.record_truncation();
+        LimitedTraceResults {
+            full_trace: self.inspector.into_parity_builder().into_trace_results(res, trace_types),
+            vm_trace_truncated: self.truncated,
+        }
+    }
+
+    /// Returns the requested traces of the traced call, the state diff is completed with the
+    /// state the call was executed on.
+    pub(crate) fn into_trace_results_with_state<DB: DatabaseRef>(
+        self,
+        res: &ResultAndState,
+        trace_types: &HashSet<TraceType>,
+        db: DB,
+    ) -> Result<LimitedTraceResults, DB::Error> {
+        self.record_truncation();
+        Ok(LimitedTraceResults {
+            full_trace: self.inspector.into_parity_builder().into_trace_results_with_state(
+                res,
+                trace_types,
+                db,
+            )?,
+            vm_trace_truncated: self.truncated,
+        })
+    }
+
+    /// Counts the trace if its `vmTrace` was truncated.
+    fn record_truncation(&self) {
+        if self.truncated {
+            self.metrics.truncated_traces_total.increment(1);
+        }
+    }
+
+    /// Returns whether the instruction about to be executed is recorded and accounts for it.
+    fn record_step(&mut self, interp: &Interpreter) -> bool {
+        let Some(limits) = self.limits else { return true };
+        let memory = interp.shared_memory.len();
+        if self.truncated ||
+            self.recorded_steps >= limits.max_steps ||
+            self.recorded_memory.saturating_add(memory) > limits.max_memory
+        {
+            self.truncated = true;
+            return false
+        }
+        self.recorded_steps += 1;
+        self.recorded_memory += memory;
+        true
+    }
+}
+
+impl<DB> Inspector<DB> for VmTracer
+where
+    DB: Database,
+{
+    fn initialize_interp(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.inspector.initialize_interp(interp, context)
+    }
+
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        let record = self.record_step(interp);
+        self.open_steps.push(record);
+        if record {
+            self.inspector.step(interp, context)
+        }
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if self.open_steps.pop().unwrap_or_default() {
+            self.inspector.step_end(interp, context)
+        }
+    }
+
+    fn log(&mut self, context: &mut EvmContext<DB>, log: &Log) {
+        self.inspector.log(context, log)
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.inspector.call(context, inputs)
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.inspector.call_end(context, inputs, outcome)
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.inspector.create(context, inputs)
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.inspector.create_end(context, inputs, outcome)
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        Inspector::<DB>::selfdestruct(&mut self.inspector, contract, target, value)
+    }
+}
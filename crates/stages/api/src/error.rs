@@ -119,6 +119,10 @@ pub enum StageError {
     /// Internal error
     #[error(transparent)]
     Internal(#[from] RethError),
+    /// The stage was cancelled, e.g. on shutdown. The progress made before the cancellation
+    /// was already committed, so the stage resumes from it on the next run.
+    #[error("stage was cancelled")]
+    Cancelled,
     /// The stage encountered a recoverable error.
     ///
     /// These types of errors are caught by the [Pipeline][crate::Pipeline] and trigger a restart
@@ -147,6 +151,7 @@ impl StageError {
                 Self::InconsistentBlockNumber { .. } |
                 Self::InconsistentTxNumber { .. } |
                 Self::Internal(_) |
+                Self::Cancelled |
                 Self::Fatal(_)
         )
     }
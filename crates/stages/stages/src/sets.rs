@@ -36,8 +36,8 @@
 use crate::{
     stages::{
         AccountHashingStage, BodyStage, ExecutionStage, FinishStage, HeaderStage,
-        IndexAccountHistoryStage, IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage,
-        StorageHashingStage, TransactionLookupStage,
+        IndexAccountHistoryStage, IndexAddressTransactionsStage, IndexStorageHistoryStage,
+        MerkleStage, SenderRecoveryStage, StorageHashingStage, TransactionLookupStage,
     },
     StageSet, StageSetBuilder,
 };
@@ -46,6 +46,7 @@ use reth_consensus::Consensus;
 use reth_db_api::database::Database;
 use reth_evm::execute::BlockExecutorProvider;
 use reth_network_p2p::{bodies::downloader::BodyDownloader, headers::downloader::HeaderDownloader};
+use reth_primitives::stage::StageId;
 use reth_provider::{HeaderSyncGapProvider, HeaderSyncMode};
 use reth_prune_types::PruneModes;
 use std::sync::Arc;
@@ -70,6 +71,7 @@ use std::sync::Arc;
 /// - [`TransactionLookupStage`]
 /// - [`IndexStorageHistoryStage`]
 /// - [`IndexAccountHistoryStage`]
+/// - [`IndexAddressTransactionsStage`] (if enabled)
 /// - [`FinishStage`]
 #[derive(Debug)]
 pub struct DefaultStages<Provider, H, B, EF> {
@@ -381,5 +383,12 @@ impl<DB: Database> StageSet<DB> for HistoryIndexingStages {
                 self.stages_config.etl.clone(),
                 self.prune_modes.storage_history,
             ))
+            .add_stage(IndexAddressTransactionsStage::new(
+                self.stages_config.index_address_transactions,
+                self.stages_config.etl.clone(),
+            ))
+            .disable_if(StageId::IndexAddressTransactions, || {
+                !self.stages_config.index_address_transactions.enabled
+            })
     }
 }
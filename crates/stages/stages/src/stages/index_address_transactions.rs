@@ -0,0 +1,274 @@
+use super::load_history_indices;
+use reth_config::config::{EtlConfig, IndexAddressTransactionsConfig};
+use reth_db::{tables, BlockNumberList};
+use reth_db_api::{database::Database, models::ShardedKey, table::Decode, transaction::DbTxMut};
+use reth_etl::Collector;
+use reth_primitives::{
+    stage::{StageCheckpoint, StageId},
+    Address,
+};
+use reth_provider::{DatabaseProviderRW, HistoryWriter};
+use reth_stages_api::{ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput};
+use tracing::info;
+
+/// Number of blocks whose addresses are read at once before they're pushed to the [`Collector`].
+const DEFAULT_CACHE_THRESHOLD: u64 = 1_000;
+
+/// Stage is indexing the blocks with transactions an address is part of, from the senders,
+/// transactions and receipts of the blocks. For more information on index sharding take a look at
+/// [`tables::AddressTransactionIndex`].
+///
+/// The stage is optional and only part of the pipeline if it's enabled in the
+/// [`IndexAddressTransactionsConfig`]. Once the stage caught up, the index is also kept up to date
+/// with the blocks that are appended outside of the pipeline. The index is dropped when the node
+/// starts with the stage disabled, since the pipeline doesn't unwind it without the stage.
+#[derive(Debug)]
+pub struct IndexAddressTransactionsStage {
+    /// Number of blocks after which the control
+    /// flow will be returned to the pipeline for commit.
+    pub commit_threshold: u64,
+    /// ETL configuration
+    pub etl_config: EtlConfig,
+}
+
+impl IndexAddressTransactionsStage {
+    /// Create new instance of [`IndexAddressTransactionsStage`].
+    pub const fn new(config: IndexAddressTransactionsConfig, etl_config: EtlConfig) -> Self {
+        Self { commit_threshold: config.commit_threshold, etl_config }
+    }
+}
+
+impl Default for IndexAddressTransactionsStage {
+    fn default() -> Self {
+        Self { commit_threshold: 100_000, etl_config: EtlConfig::default() }
+    }
+}
+
+impl<DB: Database> Stage<DB> for IndexAddressTransactionsStage {
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::IndexAddressTransactions
+    }
+
+    /// Execute the stage.
+    fn execute(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        let (range, is_final_range) = input.next_block_range_with_threshold(self.commit_threshold);
+        let first_sync = input.checkpoint().block_number == 0;
+
+        // On first sync we might have an index left from a previous run. We clear the table since
+        // it's faster to rebuild from scratch.
+        if first_sync {
+            provider.tx_ref().clear::<tables::AddressTransactionIndex>()?;
+        }
+
+        info!(target: "sync::stages::index_address_transactions::exec", ?first_sync, ?range, "Collecting indices");
+        let mut collector = Collector::new(self.etl_config.file_size, self.etl_config.dir.clone());
+        for start in range.clone().step_by(DEFAULT_CACHE_THRESHOLD as usize) {
+            let end = (start + DEFAULT_CACHE_THRESHOLD - 1).min(*range.end());
+            for (address, blocks) in provider.address_transactions_with_range(start..=end)? {
+                let last = *blocks.last().expect("not empty");
+                collector.insert(
+                    ShardedKey::new(address, last),
+                    BlockNumberList::new_pre_sorted(blocks),
+                )?;
+            }
+        }
+
+        info!(target: "sync::stages::index_address_transactions::exec", "Loading indices into database");
+        load_history_indices::<_, tables::AddressTransactionIndex, _>(
+            provider.tx_ref(),
+            collector,
+            first_sync,
+            ShardedKey::new,
+            ShardedKey::<Address>::decode,
+            |key| key.key,
+        )?;
+
+        Ok(ExecOutput { checkpoint: StageCheckpoint::new(*range.end()), done: is_final_range })
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        let (range, unwind_progress, _) =
+            input.unwind_block_range_with_threshold(self.commit_threshold);
+
+        provider.unwind_address_transaction_indices(range)?;
+
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(unwind_progress) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{StorageKind, TestStageDB};
+    use reth_db_api::{cursor::DbCursorRO, transaction::DbTx};
+    use reth_primitives::{BlockNumber, B256};
+    use reth_provider::{AddressTransactionsReader, StageCheckpointWriter};
+    use reth_testing_utils::{
+        generators,
+        generators::{random_block_range, random_receipt},
+    };
+    use std::collections::BTreeMap;
+
+    const MAX_BLOCK: BlockNumber = 10;
+
+    /// Inserts blocks with senders and receipts and returns the expected index.
+    fn setup(db: &TestStageDB) -> BTreeMap<Address, Vec<BlockNumber>> {
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, 1..=MAX_BLOCK, B256::ZERO, 0..3);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).unwrap();
+
+        let mut expected = BTreeMap::<Address, Vec<BlockNumber>>::new();
+        let mut senders = Vec::new();
+        let mut receipts = Vec::new();
+        for block in &blocks {
+            let mut insert = |address: Address| {
+                let blocks = expected.entry(address).or_default();
+                if blocks.last() != Some(&block.number) {
+                    blocks.push(block.number);
+                }
+            };
+
+            for transaction in &block.body {
+                let tx_number = senders.len() as u64;
+                let sender = transaction.recover_signer().unwrap();
+                insert(sender);
+                match transaction.to() {
+                    Some(to) => insert(to),
+                    None => insert(sender.create(transaction.nonce())),
+                }
+
+                let receipt = random_receipt(&mut rng, transaction, Some(2));
+                receipt.logs.iter().for_each(|log| insert(log.address));
+
+                senders.push((tx_number, sender));
+                receipts.push((tx_number, receipt));
+            }
+        }
+        db.insert_transaction_senders(senders).unwrap();
+        db.insert_receipts(receipts).unwrap();
+
+        expected
+    }
+
+    fn run(db: &TestStageDB, run_to: u64, input_checkpoint: Option<BlockNumber>) {
+        let input = ExecInput {
+            target: Some(run_to),
+            checkpoint: input_checkpoint.map(StageCheckpoint::new),
+        };
+        let mut stage = IndexAddressTransactionsStage::default();
+        let provider = db.factory.provider_rw().unwrap();
+        let out = stage.execute(&provider, input).unwrap();
+        assert_eq!(out, ExecOutput { checkpoint: StageCheckpoint::new(run_to), done: true });
+        provider.commit().unwrap();
+    }
+
+    fn unwind(db: &TestStageDB, unwind_from: u64, unwind_to: u64) {
+        let input = UnwindInput {
+            checkpoint: StageCheckpoint::new(unwind_from),
+            unwind_to,
+            ..Default::default()
+        };
+        let mut stage = IndexAddressTransactionsStage::default();
+        let provider = db.factory.provider_rw().unwrap();
+        let out = stage.unwind(&provider, input).unwrap();
+        assert_eq!(out, UnwindOutput { checkpoint: StageCheckpoint::new(unwind_to) });
+        provider.commit().unwrap();
+    }
+
+    /// Asserts that the index of every address has the expected blocks.
+    fn assert_index(db: &TestStageDB, expected: &BTreeMap<Address, Vec<BlockNumber>>) {
+        let provider = db.factory.provider().unwrap();
+        let mut cursor =
+            provider.tx_ref().cursor_read::<tables::AddressTransactionIndex>().unwrap();
+        for (address, blocks) in expected {
+            let indexed = cursor
+                .walk(Some(ShardedKey::new(*address, 0)))
+                .unwrap()
+                .map(Result::unwrap)
+                .take_while(|(key, _)| key.key == *address)
+                .flat_map(|(_, list)| list.iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            assert_eq!(&indexed, blocks);
+        }
+    }
+
+    #[tokio::test]
+    async fn index_and_unwind() {
+        let db = TestStageDB::default();
+        let expected = setup(&db);
+
+        // build the index in two runs
+        run(&db, 4, None);
+        run(&db, MAX_BLOCK, Some(4));
+        assert_index(&db, &expected);
+
+        // unwind
+        unwind(&db, MAX_BLOCK, 4);
+        let unwound = expected
+            .iter()
+            .map(|(address, blocks)| {
+                (*address, blocks.iter().copied().filter(|block| *block <= 4).collect())
+            })
+            .collect();
+        assert_index(&db, &unwound);
+    }
+
+    #[tokio::test]
+    async fn search_index() {
+        let db = TestStageDB::default();
+        let expected = setup(&db);
+        run(&db, MAX_BLOCK, None);
+
+        let provider = db.factory.provider().unwrap();
+        for (address, blocks) in &expected {
+            let mut before = blocks.iter().copied().filter(|block| *block < 5).collect::<Vec<_>>();
+            before.reverse();
+            assert_eq!(
+                provider.address_transaction_blocks_before(*address, 5, 10).unwrap(),
+                before
+            );
+
+            let after =
+                blocks.iter().copied().filter(|block| *block > 5).take(1).collect::<Vec<_>>();
+            assert_eq!(provider.address_transaction_blocks_after(*address, 5, 1).unwrap(), after);
+        }
+    }
+
+    #[tokio::test]
+    async fn drop_index() {
+        let db = TestStageDB::default();
+        setup(&db);
+        run(&db, MAX_BLOCK, None);
+        let provider = db.factory.provider_rw().unwrap();
+        provider
+            .save_stage_checkpoint(
+                StageId::IndexAddressTransactions,
+                StageCheckpoint::new(MAX_BLOCK),
+            )
+            .unwrap();
+        provider.commit().unwrap();
+
+        let provider = db.factory.provider_rw().unwrap();
+        assert!(provider.drop_address_transaction_index().unwrap());
+        provider.commit().unwrap();
+
+        let provider = db.factory.provider_rw().unwrap();
+        assert_eq!(provider.address_transactions_checkpoint().unwrap(), None);
+        assert_eq!(provider.tx_ref().entries::<tables::AddressTransactionIndex>().unwrap(), 0);
+        assert!(!provider.drop_address_transaction_index().unwrap());
+    }
+}
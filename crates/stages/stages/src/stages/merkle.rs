@@ -5,6 +5,7 @@ use reth_db_api::{
     database::Database,
     transaction::{DbTx, DbTxMut},
 };
+use reth_execution_errors::StateRootError;
 use reth_primitives::{
     stage::{EntitiesCheckpoint, MerkleCheckpoint, StageCheckpoint, StageId},
     trie::StoredSubNode,
@@ -17,7 +18,7 @@ use reth_provider::{
 use reth_stages_api::{
     BlockErrorKind, ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput,
 };
-use reth_trie::{IntermediateStateRootState, StateRoot, StateRootProgress};
+use reth_trie::{CancellationToken, IntermediateStateRootState, StateRoot, StateRootProgress};
 use std::fmt::Debug;
 use tracing::*;
 
@@ -71,6 +72,9 @@ pub enum MerkleStage {
         /// The threshold (in number of blocks) for switching from incremental trie building
         /// of changes to whole rebuild.
         clean_threshold: u64,
+        /// The token that interrupts the trie computation, saving the intermediate progress to
+        /// the checkpoint.
+        cancellation: CancellationToken,
     },
     /// The unwind portion of the merkle stage.
     Unwind,
@@ -85,8 +89,8 @@ pub enum MerkleStage {
 
 impl MerkleStage {
     /// Stage default for the [`MerkleStage::Execution`].
-    pub const fn default_execution() -> Self {
-        Self::Execution { clean_threshold: MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD }
+    pub fn default_execution() -> Self {
+        Self::new_execution(MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD)
     }
 
     /// Stage default for the [`MerkleStage::Unwind`].
@@ -95,8 +99,20 @@ impl MerkleStage {
     }
 
     /// Create new instance of [`MerkleStage::Execution`].
-    pub const fn new_execution(clean_threshold: u64) -> Self {
-        Self::Execution { clean_threshold }
+    pub fn new_execution(clean_threshold: u64) -> Self {
+        Self::Execution { clean_threshold, cancellation: CancellationToken::default() }
+    }
+
+    /// Set the cancellation token of the [`MerkleStage::Execution`].
+    ///
+    /// Once the token is cancelled, the trie computation is interrupted and the intermediate
+    /// progress is saved to the checkpoint, so it can be resumed on the next run. All following
+    /// executions of the stage fail with [`StageError::Cancelled`] until a new token is set.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        if let Self::Execution { cancellation, .. } = &mut self {
+            *cancellation = token;
+        }
+        self
     }
 
     /// Gets the hashing progress
@@ -151,14 +167,19 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         provider: &DatabaseProviderRW<DB>,
         input: ExecInput,
     ) -> Result<ExecOutput, StageError> {
-        let threshold = match self {
+        let (threshold, cancellation) = match self {
             Self::Unwind => {
                 info!(target: "sync::stages::merkle::unwind", "Stage is always skipped");
                 return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
             }
-            Self::Execution { clean_threshold } => *clean_threshold,
+            Self::Execution { clean_threshold, cancellation } => {
+                if cancellation.is_cancelled() {
+                    return Err(StageError::Cancelled)
+                }
+                (*clean_threshold, cancellation.clone())
+            }
             #[cfg(any(test, feature = "test-utils"))]
-            Self::Both { clean_threshold } => *clean_threshold,
+            Self::Both { clean_threshold } => (*clean_threshold, CancellationToken::default()),
         };
 
         let range = input.next_block_range();
@@ -171,9 +192,12 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         let target_block_root = target_block.state_root;
 
         let mut checkpoint = self.get_execution_checkpoint(provider)?;
+        // The checkpoint of an interrupted computation towards another target leaves the trie
+        // tables partially updated, so the trie has to be rebuilt.
+        let stale_checkpoint = checkpoint.as_ref().is_some_and(|c| c.target_block != to_block);
         let (trie_root, entities_checkpoint) = if range.is_empty() {
             (target_block_root, input.checkpoint().entities_stage_checkpoint().unwrap_or_default())
-        } else if to_block - from_block > threshold || from_block == 1 {
+        } else if to_block - from_block > threshold || from_block == 1 || stale_checkpoint {
             // if there are more blocks than threshold it is faster to rebuild the trie
             let mut entities_checkpoint = if let Some(checkpoint) =
                 checkpoint.as_ref().filter(|c| c.target_block == to_block)
@@ -213,8 +237,12 @@ impl<DB: Database> Stage<DB> for MerkleStage {
             let tx = provider.tx_ref();
             let progress = StateRoot::from_tx(tx)
                 .with_intermediate_state(checkpoint.map(IntermediateStateRootState::from))
+                .with_cancellation(cancellation)
                 .root_with_progress()
                 .map_err(|e| {
+                    if e == StateRootError::Cancelled {
+                        return StageError::Cancelled
+                    }
                     error!(target: "sync::stages::merkle", %e, ?current_block_number, ?to_block, "State root with progress failed! {INVALID_STATE_ROOT_ERROR_MESSAGE}");
                     StageError::Fatal(Box::new(e))
                 })?;
@@ -248,14 +276,48 @@ impl<DB: Database> Stage<DB> for MerkleStage {
                 }
             }
         } else {
-            debug!(target: "sync::stages::merkle::exec", current = ?current_block_number, target = ?to_block, "Updating trie");
-            let (root, updates) =
-                StateRoot::incremental_root_with_updates(provider.tx_ref(), range)
-                    .map_err(|e| {
-                        error!(target: "sync::stages::merkle", %e, ?current_block_number, ?to_block, "Incremental state root failed! {INVALID_STATE_ROOT_ERROR_MESSAGE}");
-                        StageError::Fatal(Box::new(e))
-                    })?;
-            updates.flush(provider.tx_ref())?;
+            debug!(
+                target: "sync::stages::merkle::exec",
+                current = ?current_block_number,
+                target = ?to_block,
+                last_account_key = ?checkpoint.as_ref().map(|c| c.last_account_key),
+                "Updating trie"
+            );
+            let progress = StateRoot::incremental_root_calculator(provider.tx_ref(), range)
+                .and_then(|calculator| {
+                    calculator
+                        .with_no_threshold()
+                        .with_intermediate_state(checkpoint.map(IntermediateStateRootState::from))
+                        .with_cancellation(cancellation)
+                        .root_with_progress()
+                })
+                .map_err(|e| {
+                    if e == StateRootError::Cancelled {
+                        return StageError::Cancelled
+                    }
+                    error!(target: "sync::stages::merkle", %e, ?current_block_number, ?to_block, "Incremental state root failed! {INVALID_STATE_ROOT_ERROR_MESSAGE}");
+                    StageError::Fatal(Box::new(e))
+                })?;
+            let root = match progress {
+                StateRootProgress::Progress(state, _, updates) => {
+                    // The computation was interrupted, save the progress to continue from it
+                    updates.flush(provider.tx_ref())?;
+
+                    let checkpoint = MerkleCheckpoint::new(
+                        to_block,
+                        state.last_account_key,
+                        state.walker_stack.into_iter().map(StoredSubNode::from).collect(),
+                        state.hash_builder.into(),
+                    );
+                    self.save_execution_checkpoint(provider, Some(checkpoint))?;
+
+                    return Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+                }
+                StateRootProgress::Complete(root, _, updates) => {
+                    updates.flush(provider.tx_ref())?;
+                    root
+                }
+            };
 
             let total_hashed_entries = (provider.count_entries::<tables::HashedAccounts>()? +
                 provider.count_entries::<tables::HashedStorages>()?)
@@ -464,6 +526,20 @@ mod tests {
         assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
     }
 
+    /// Cancelled stage doesn't make any progress and fails instead of looping with `done: false`
+    #[test]
+    fn execute_cancelled_merkle() {
+        let db = TestStageDB::default();
+        let cancellation = CancellationToken::default();
+        cancellation.cancel();
+
+        let mut stage = MerkleStage::default_execution().with_cancellation(cancellation);
+        let input = ExecInput { target: Some(1), checkpoint: Some(StageCheckpoint::new(0)) };
+        let provider = db.factory.provider_rw().unwrap();
+        assert_matches!(stage.execute(&provider, input), Err(StageError::Cancelled));
+        assert_matches!(stage.execute(&provider, input), Err(StageError::Cancelled));
+    }
+
     struct MerkleTestRunner {
         db: TestStageDB,
         clean_threshold: u64,
@@ -12,6 +12,8 @@ mod hashing_storage;
 mod headers;
 /// Index history of account changes
 mod index_account_history;
+/// Index the transactions of addresses
+mod index_address_transactions;
 /// Index history of storage changes
 mod index_storage_history;
 /// Stage for computing state root.
@@ -28,6 +30,7 @@ pub use hashing_account::*;
 pub use hashing_storage::*;
 pub use headers::*;
 pub use index_account_history::*;
+pub use index_address_transactions::*;
 pub use index_storage_history::*;
 pub use merkle::*;
 
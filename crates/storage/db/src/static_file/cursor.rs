@@ -1,8 +1,11 @@
-use super::mask::{ColumnSelectorOne, ColumnSelectorThree, ColumnSelectorTwo};
+use super::{
+    mask::{ColumnSelectorOne, ColumnSelectorThree, ColumnSelectorTwo},
+    WireEncode,
+};
 use derive_more::{Deref, DerefMut};
 use reth_db_api::table::Decompress;
 use reth_nippy_jar::{DataReader, NippyJar, NippyJarCursor};
-use reth_primitives::{static_file::SegmentHeader, B256};
+use reth_primitives::{static_file::SegmentHeader, Bytes, B256};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use std::sync::Arc;
 
@@ -65,6 +68,22 @@ impl<'a> StaticFileCursor<'a> {
         }
     }
 
+    /// Gets the network encoding of one column value from a row, see [`WireEncode`].
+    pub fn get_one_wire<M: ColumnSelectorOne>(
+        &mut self,
+        key_or_num: KeyOrNumber<'_>,
+    ) -> ProviderResult<Option<Bytes>>
+    where
+        M::FIRST: WireEncode,
+    {
+        let row = self.get(key_or_num, M::MASK)?;
+
+        match row {
+            Some(row) => Ok(Some(M::FIRST::stored_to_wire(row[0])?)),
+            None => Ok(None),
+        }
+    }
+
     /// Gets two column values from a row.
     pub fn get_two<M: ColumnSelectorTwo>(
         &mut self,
@@ -21,6 +21,9 @@ use reth_primitives::{
 
 mod masks;
 
+mod wire;
+pub use wire::WireEncode;
+
 /// Alias type for a map of [`StaticFileSegment`] and sorted lists of existing static file ranges.
 type SortedStaticFiles =
     HashMap<StaticFileSegment, Vec<(SegmentRangeInclusive, Option<SegmentRangeInclusive>)>>;
@@ -0,0 +1,43 @@
+use reth_db_api::{table::Decompress, DatabaseError};
+use reth_primitives::{Bytes, Header, Receipt, TransactionSignedNoHash};
+
+/// A value of a static file column that has a canonical network encoding, e.g. the RLP encoding of
+/// a header or the EIP-2718 encoding of a transaction.
+///
+/// Static file columns store their values in the compact form, so a raw read decompresses the
+/// column value and encodes it straight to its network form, without assembling the typed block or
+/// looking up anything else.
+pub trait WireEncode: Decompress {
+    /// Appends the network encoding of the value to the buffer.
+    fn encode_wire(&self, out: &mut Vec<u8>);
+
+    /// Returns the network encoding of the value.
+    fn wire_encoded(&self) -> Bytes {
+        let mut out = Vec::new();
+        self.encode_wire(&mut out);
+        out.into()
+    }
+
+    /// Returns the network encoding of a stored value.
+    fn stored_to_wire(stored: &[u8]) -> Result<Bytes, DatabaseError> {
+        Ok(Self::decompress(stored)?.wire_encoded())
+    }
+}
+
+impl WireEncode for Header {
+    fn encode_wire(&self, out: &mut Vec<u8>) {
+        alloy_rlp::Encodable::encode(self, out)
+    }
+}
+
+impl WireEncode for TransactionSignedNoHash {
+    fn encode_wire(&self, out: &mut Vec<u8>) {
+        self.transaction.encode_with_signature(&self.signature, out, false)
+    }
+}
+
+impl WireEncode for Receipt {
+    fn encode_wire(&self, out: &mut Vec<u8>) {
+        self.with_bloom_ref().encode_inner(out, false)
+    }
+}
@@ -360,6 +360,14 @@ tables! {
     /// Code example can be found in `reth_provider::HistoricalStateProviderRef`
     table StoragesHistory<Key = StorageShardedKey, Value = BlockNumberList>;
 
+    /// Stores pointers to the blocks with transactions an address is part of.
+    ///
+    /// An address is part of a transaction if it's the sender, the recipient or the created
+    /// contract of the transaction, or if it emitted a log during its execution. The table is
+    /// sharded the same way as [`AccountsHistory`] and only written if the address transaction
+    /// index is enabled.
+    table AddressTransactionIndex<Key = ShardedKey<Address>, Value = BlockNumberList>;
+
     /// Stores the state of an account before a certain transaction changed it.
     /// Change on state can be: account is created, selfdestructed, touched while empty
     /// or changed balance,nonce.
@@ -101,12 +101,21 @@ pub enum ProviderError {
     /// Root mismatch during unwind
     #[error("unwind merkle trie {0}")]
     UnwindStateRootMismatch(Box<RootMismatch>),
+    /// The state root computation was cancelled before completion.
+    #[error("state root computation was cancelled")]
+    StateRootCancelled,
     /// State is not available for the given block number because it is pruned.
     #[error("state at block #{0} is pruned")]
     StateAtBlockPruned(BlockNumber),
+    /// Replaying the block on top of its parent state failed.
+    #[error("failed to replay block {0}: {1}")]
+    BlockReplay(B256, String),
     /// Provider does not support this particular request.
     #[error("this provider does not support this request")]
     UnsupportedProvider,
+    /// There's no database table with the given name.
+    #[error("unknown table {0}")]
+    UnknownTable(String),
     /// Static File is not found at specified path.
     #[error("not able to find {0} static file at {1}")]
     MissingStaticFilePath(StaticFileSegment, PathBuf),
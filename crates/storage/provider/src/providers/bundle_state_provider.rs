@@ -1,10 +1,15 @@
 use crate::{
     AccountReader, BlockHashReader, BundleStateDataProvider, StateProvider, StateRootProvider,
 };
-use reth_primitives::{trie::AccountProof, Account, Address, BlockNumber, Bytecode, B256};
+use reth_primitives::{
+    keccak256,
+    trie::{AccountProof, MultiProof, StorageMultiProof},
+    Account, Address, BlockNumber, Bytecode, B256,
+};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
-use reth_trie::updates::TrieUpdates;
+use reth_trie::{updates::TrieUpdates, HashedPostState, HashedStorage, StorageRange};
 use revm::db::BundleState;
+use std::collections::{HashMap, HashSet};
 
 /// A state provider that either resolves to data in a wrapped [`crate::BundleStateWithReceipts`],
 /// or an underlying state provider.
@@ -74,6 +79,31 @@ impl<SP: StateProvider, BSDP: BundleStateDataProvider> StateRootProvider
         state.extend(bundle_state.clone());
         self.state_provider.state_root_with_updates(&state)
     }
+
+    fn hashed_state_root_with_storage_roots(
+        &self,
+        hashed_state: HashedPostState,
+        storage_roots: HashMap<B256, B256>,
+    ) -> ProviderResult<B256> {
+        let bundle_state = self.bundle_state_data_provider.state().state();
+        let mut state = HashedPostState::from_bundle_state(&bundle_state.state);
+        state.extend(hashed_state);
+        self.state_provider.hashed_state_root_with_storage_roots(state, storage_roots)
+    }
+
+    fn hashed_storage_root(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        let bundle_state = self.bundle_state_data_provider.state().state();
+        let mut storage = HashedPostState::from_bundle_state(&bundle_state.state)
+            .storages
+            .remove(&hashed_address)
+            .unwrap_or_else(|| HashedStorage::new(false));
+        storage.extend(hashed_storage);
+        self.state_provider.hashed_storage_root(hashed_address, storage)
+    }
 }
 
 impl<SP: StateProvider, BSDP: BundleStateDataProvider> StateProvider
@@ -94,6 +124,34 @@ impl<SP: StateProvider, BSDP: BundleStateDataProvider> StateProvider
         self.state_provider.storage(account, storage_key)
     }
 
+    fn storages(
+        &self,
+        account: Address,
+        storage_keys: &[reth_primitives::StorageKey],
+    ) -> ProviderResult<Vec<Option<reth_primitives::StorageValue>>> {
+        let bundle_state = self.bundle_state_data_provider.state();
+        let mut values = storage_keys
+            .iter()
+            .map(|storage_key| bundle_state.storage(&account, (*storage_key).into()))
+            .collect::<Vec<_>>();
+
+        // The slots that are not in the bundle state are read at once
+        let missing = storage_keys
+            .iter()
+            .zip(&values)
+            .filter(|(_, value)| value.is_none())
+            .map(|(storage_key, _)| *storage_key)
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            let mut missing_values = self.state_provider.storages(account, &missing)?.into_iter();
+            for value in values.iter_mut().filter(|value| value.is_none()) {
+                *value = missing_values.next().flatten();
+            }
+        }
+
+        Ok(values)
+    }
+
     fn bytecode_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>> {
         if let Some(bytecode) = self.bundle_state_data_provider.state().bytecode(&code_hash) {
             return Ok(Some(bytecode))
@@ -105,4 +163,130 @@ impl<SP: StateProvider, BSDP: BundleStateDataProvider> StateProvider
     fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
         Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
     }
+
+    fn multiproof(
+        &self,
+        hashed_state: HashedPostState,
+        targets: HashMap<B256, HashSet<B256>>,
+    ) -> ProviderResult<MultiProof> {
+        let bundle_state = self.bundle_state_data_provider.state().state();
+        let mut state = HashedPostState::from_bundle_state(&bundle_state.state);
+        state.extend(hashed_state);
+        self.state_provider.multiproof(state, targets)
+    }
+
+    fn storage_multiproof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<StorageMultiProof> {
+        let bundle_state = self.bundle_state_data_provider.state().state();
+        let mut storage = HashedPostState::from_bundle_state(&bundle_state.state)
+            .storages
+            .remove(&keccak256(address))
+            .unwrap_or_else(|| HashedStorage::new(false));
+        storage.extend(hashed_storage);
+        self.state_provider.storage_multiproof(address, slots, storage)
+    }
+
+    fn storage_range(
+        &self,
+        address: Address,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<StorageRange> {
+        let bundle_state = self.bundle_state_data_provider.state().state();
+        let mut storage = HashedPostState::from_bundle_state(&bundle_state.state)
+            .storages
+            .remove(&keccak256(address))
+            .unwrap_or_else(|| HashedStorage::new(false));
+        storage.extend(hashed_storage);
+        let mut range = self.state_provider.storage_range(address, storage, start, limit)?;
+
+        // The slots written by the bundle state might not be in the plain storage yet.
+        if let Some(account) = bundle_state.account(&address) {
+            range.set_preimages(account.storage.keys().map(|slot| B256::from(*slot)));
+        }
+        Ok(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::create_test_provider_factory, BundleStateWithReceipts, HashingWriter,
+        StateProviderFactory,
+    };
+    use reth_primitives::{trie::StorageProof, Receipts, StorageEntry, U256};
+    use reth_trie::test_utils::storage_root_prehashed;
+    use revm::primitives::AccountInfo;
+
+    #[test]
+    fn storage_multiproof_of_touched_account() {
+        let factory = create_test_provider_factory();
+        let address = Address::with_last_byte(1);
+        let (slot1, slot2) = (B256::with_last_byte(1), B256::with_last_byte(2));
+
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_account_for_hashing([(address, Some(Account::default()))]).unwrap();
+        provider
+            .insert_storage_for_hashing([(
+                address,
+                [StorageEntry { key: slot1, value: U256::from(1) }],
+            )])
+            .unwrap();
+        provider.commit().unwrap();
+
+        // The bundle state writes a new slot of the account on top of the database state.
+        let bundle = BundleState::builder(1..=1)
+            .state_present_account_info(address, AccountInfo::default())
+            .state_storage(
+                address,
+                revm::primitives::HashMap::from([(U256::from(2), (U256::ZERO, U256::from(2)))]),
+            )
+            .build();
+        let bundle_state = BundleStateWithReceipts {
+            bundle,
+            receipts: Receipts::from_vec(vec![vec![]]),
+            first_block: 1,
+        };
+        let state = BundleStateProvider::new(factory.latest().unwrap(), bundle_state);
+
+        let multiproof =
+            state.storage_multiproof(address, &[slot1, slot2], HashedStorage::new(false)).unwrap();
+        assert_eq!(
+            multiproof.root,
+            storage_root_prehashed([
+                (keccak256(slot1), U256::from(1)),
+                (keccak256(slot2), U256::from(2))
+            ])
+        );
+
+        for (slot, value) in [(slot1, U256::from(1)), (slot2, U256::from(2))] {
+            let mut proof = StorageProof::new(slot);
+            proof.set_value(value);
+            proof.set_proof(multiproof.storage_proof_nodes(keccak256(slot)));
+            assert_eq!(proof.verify(multiproof.root), Ok(()), "slot {slot}");
+        }
+
+        // The state multiproof is generated on top of the bundle state as well.
+        let hashed_address = keccak256(address);
+        let multiproof = state
+            .multiproof(
+                HashedPostState::default(),
+                HashMap::from([(hashed_address, HashSet::from([keccak256(slot2)]))]),
+            )
+            .unwrap();
+        assert_eq!(
+            multiproof.storages[&hashed_address].root,
+            storage_root_prehashed([
+                (keccak256(slot1), U256::from(1)),
+                (keccak256(slot2), U256::from(2))
+            ])
+        );
+        assert!(!multiproof.account_subtree.is_empty());
+    }
 }
@@ -2,11 +2,11 @@ use crate::{
     providers::{state::latest::LatestStateProvider, StaticFileProvider},
     to_range,
     traits::{BlockSource, ReceiptProvider},
-    BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HeaderSyncMode,
-    ProviderError, PruneCheckpointReader, RequestsProvider, StageCheckpointReader,
-    StateProviderBox, StaticFileProviderFactory, TransactionVariant, TransactionsProvider,
-    WithdrawalsProvider,
+    AddressTransactionsReader, BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider,
+    DatabaseProviderFactory, EvmEnvProvider, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider,
+    HeaderSyncMode, ProviderError, PruneCheckpointReader, RawBlockReader, RequestsProvider,
+    StageCheckpointReader, StateProviderBox, StaticFileProviderFactory, TransactionVariant,
+    TransactionsProvider, WithdrawalsProvider,
 };
 use reth_db::{init_db, mdbx::DatabaseArguments, DatabaseEnv};
 use reth_db_api::{database::Database, models::StoredBlockBodyIndices};
@@ -14,7 +14,7 @@ use reth_errors::{RethError, RethResult};
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
     stage::{StageCheckpoint, StageId},
-    Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, ChainInfo,
+    Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Bytes, ChainInfo,
     ChainSpec, Header, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader,
     StaticFileSegment, TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash,
     TxNumber, Withdrawal, Withdrawals, B256, U256,
@@ -339,6 +339,13 @@ impl<DB: Database> BlockReader for ProviderFactory<DB> {
     ) -> ProviderResult<Vec<BlockWithSenders>> {
         self.provider()?.block_with_senders_range(range)
     }
+
+    fn transactions_and_withdrawals_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<Option<(Vec<TransactionSignedNoHash>, Option<Withdrawals>)>>> {
+        self.provider()?.transactions_and_withdrawals_range(range)
+    }
 }
 
 impl<DB: Database> TransactionsProvider for ProviderFactory<DB> {
@@ -484,6 +491,52 @@ impl<DB: Database> StageCheckpointReader for ProviderFactory<DB> {
     }
 }
 
+impl<DB: Database> AddressTransactionsReader for ProviderFactory<DB> {
+    fn address_transactions_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.provider()?.address_transactions_checkpoint()
+    }
+
+    fn address_transaction_blocks_before(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.provider()?.address_transaction_blocks_before(address, block_number, limit)
+    }
+
+    fn address_transaction_blocks_after(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.provider()?.address_transaction_blocks_after(address, block_number, limit)
+    }
+}
+
+impl<DB: Database> RawBlockReader for ProviderFactory<DB> {
+    fn raw_header(&self, number: BlockNumber) -> ProviderResult<Option<Bytes>> {
+        self.provider()?.raw_header(number)
+    }
+
+    fn raw_block(&self, number: BlockNumber) -> ProviderResult<Option<Bytes>> {
+        self.provider()?.raw_block(number)
+    }
+
+    fn raw_transactions(&self, number: BlockNumber) -> ProviderResult<Option<Vec<Bytes>>> {
+        self.provider()?.raw_transactions(number)
+    }
+
+    fn raw_receipts(&self, number: BlockNumber) -> ProviderResult<Option<Vec<Bytes>>> {
+        self.provider()?.raw_receipts(number)
+    }
+
+    fn raw_transaction_by_hash(&self, hash: TxHash) -> ProviderResult<Option<Bytes>> {
+        self.provider()?.raw_transaction_by_hash(hash)
+    }
+}
+
 impl<DB: Database> EvmEnvProvider for ProviderFactory<DB> {
     fn fill_env_at<EvmConfig>(
         &self,
@@ -584,7 +637,7 @@ mod tests {
     use super::*;
     use crate::{
         providers::{StaticFileProvider, StaticFileWriter},
-        test_utils::create_test_provider_factory,
+        test_utils::{create_test_provider_factory, create_test_provider_factory_with_chain_spec},
         BlockHashReader, BlockNumReader, BlockWriter, HeaderSyncGapProvider, HeaderSyncMode,
         TransactionsProvider,
     };
@@ -596,14 +649,16 @@ mod tests {
         tables,
         test_utils::{create_test_static_files_dir, ERROR_TEMPDIR},
     };
+    use reth_db_api::transaction::DbTxMut;
     use reth_primitives::{
-        hex_literal::hex, ChainSpecBuilder, SealedBlock, StaticFileSegment, TxNumber, B256, U256,
+        hex_literal::hex, AccessList, AccessListItem, ChainSpecBuilder, ForkCondition, Hardfork,
+        Log, SealedBlock, StaticFileSegment, Transaction, TxEip2930, TxKind, TxNumber, B256, U256,
     };
     use reth_prune_types::{PruneMode, PruneModes};
     use reth_storage_errors::provider::ProviderError;
     use reth_testing_utils::{
         generators,
-        generators::{random_block, random_header},
+        generators::{random_block, random_block_range, random_header},
     };
     use std::{ops::RangeInclusive, sync::Arc};
     use tokio::sync::watch;
@@ -733,6 +788,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn transactions_and_withdrawals_range() {
+        // shanghai activates at block 7
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .paris_activated()
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(7 * 12))
+            .build();
+        let factory = create_test_provider_factory_with_chain_spec(Arc::new(chain_spec));
+        let provider = factory.provider_rw().unwrap();
+
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, 0..=9, B256::ZERO, 0..3)
+            .into_iter()
+            .map(|block| {
+                let mut block = block.unseal();
+                block.header.timestamp = block.number * 12;
+                block
+            })
+            .collect::<Vec<_>>();
+        for block in &blocks {
+            let block = block.clone().seal_slow().try_seal_with_senders().unwrap();
+            provider.insert_block(block, None).unwrap();
+        }
+
+        // the body of a block is unknown without its body indices
+        provider.tx_ref().delete::<tables::BlockBodyIndices>(5, None).unwrap();
+
+        let expected = blocks
+            .into_iter()
+            .map(|block| {
+                (block.number != 5).then(|| {
+                    // blocks past shanghai have withdrawals, even if empty
+                    let withdrawals = (block.number >= 7).then(Default::default);
+                    (block.body.into_iter().map(Into::into).collect::<Vec<_>>(), withdrawals)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // the range is truncated to the last block
+        assert_eq!(provider.transactions_and_withdrawals_range(0..=20).unwrap(), expected);
+        assert_eq!(provider.transactions_and_withdrawals_range(6..=7).unwrap(), expected[6..=7]);
+        assert!(provider.transactions_and_withdrawals_range(10..=20).unwrap().is_empty());
+    }
+
     #[test]
     fn header_sync_gap_lookup() {
         let factory = create_test_provider_factory();
@@ -765,4 +864,222 @@ mod tests {
         assert_eq!(gap.local_head, head);
         assert_eq!(gap.target.tip(), consensus_tip.into());
     }
+
+    /// Blocks with mainnet and sepolia transactions of the legacy, EIP-2930, EIP-1559 and EIP-4844
+    /// eras.
+    fn raw_test_blocks() -> Vec<(SealedBlockWithSenders, Vec<Receipt>)> {
+        let mut rng = generators::rng();
+        let decode = |data: &[u8]| TransactionSigned::decode_enveloped(&mut &data[..]).unwrap();
+
+        // <https://etherscan.io/tx/0x280cde7cdefe4b188750e76c888f13bd05ce9a4d7767730feefe8a0e50ca6fc4>
+        let legacy = decode(&hex!("f9015482078b8505d21dba0083022ef1947a250d5630b4cf539739df2c5dacb4c659f2488d880c46549a521b13d8b8e47ff36ab50000000000000000000000000000000000000000000066ab5a608bd00a23f2fe000000000000000000000000000000000000000000000000000000000000008000000000000000000000000048c04ed5691981c42154c6167398f95e8f38a7ff00000000000000000000000000000000000000000000000000000000632ceac70000000000000000000000000000000000000000000000000000000000000002000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc20000000000000000000000006c6ee5e31d828de241282b9606c8e98ea48526e225a0c9077369501641a92ef7399ff81c21639ed4fd8fc69cb793cfa1dbfab342e10aa0615facb2f1bcf3274a354cfe384a38d0cc008a11c2dd23a69111bc6930ba27a8"));
+        // unprotected legacy transaction with a high `s` value, from before EIP-2
+        // <https://etherscan.io/tx/0x2084b8144eea4031c2fa7dfe343498c5e665ca85ed17825f2925f0b5b01c36ac>
+        let pre_eip2 = decode(&hex!("f8ea0c850ba43b7400832dc6c0942935aa0a2d2fbb791622c29eb1c117b65b7a908580b884590528a9000000000000000000000001878ace42092b7f1ae1f28d16c1272b1aa80ca4670000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000d02ab486cedc0000000000000000000000000000000000000000000000000000557fe293cabc08cf1ca05bfaf3fda0a56b49cc78b22125feb5ae6a99d2b4781f00507d8b02c173771c85a0b5da0dbe6c5bc53740d0071fc83eb17ba0f709e49e9ae7df60dee625ef51afc5"));
+        // <https://etherscan.io/tx/0xce4dc6d7a7549a98ee3b071b67e970879ff51b5b95d1c340bacd80fa1e1aab31>
+        let eip1559 = decode(&hex!("02f86f0102843b9aca0085029e7822d68298f094d9e1459a7a482635700cbc20bbaf52d495ab9c9680841b55ba3ac080a0c199674fcb29f353693dd779c017823b954b3c69dffa3cd6b2a6ff7888798039a028ca912de909e7e6cdef9cdcaf24c54dd8c1032946dfa1d85c206b32a9064fe8"));
+        // <https://etherscan.io/tx/0x86718885c4b4218c6af87d3d0b0d83e3cc465df2a05c048aa4db9f1a6f9de91f>
+        let other_eip1559 = decode(&hex!("02f872018307910d808507204d2cb1827d0094388c818ca8b9251b393131c08a736a67ccb19297880320d04823e2701c80c001a0cf024f4815304df2867a1a74e9d2707b6abda0337d2d54a4438d453f4160f190a07ac0e6b3bc9395b5b9c8b9e6d77204a236577a5b18467b9175c01de4faa208d9"));
+        // <https://sepolia.etherscan.io/tx/0x9a22ccb0029bc8b0ddd073be1a1d923b7ae2b2ea52100bae0db4424f9107e9c0>
+        let eip4844 = decode(&hex!("03f9011d83aa36a7820fa28477359400852e90edd0008252089411e9ca82a3a762b4b5bd264d4173a242e7a770648080c08504a817c800f8a5a0012ec3d6f66766bedb002a190126b3549fce0047de0d4c25cffce0dc1c57921aa00152d8e24762ff22b1cfd9f8c0683786a7ca63ba49973818b3d1e9512cd2cec4a0013b98c6c83e066d5b14af2b85199e3d4fc7d1e778dd53130d180f5077e2d1c7a001148b495d6e859114e670ca54fb6e2657f0cbae5b08063605093a4b3dc9f8f1a0011ac212f13c5dff2b2c6b600a79635103d6f580a4221079951181b25c7e654901a0c8de4cced43169f9aa3d36506363b2d2c44f6c49fc1fd91ea114c86f3757077ea01e11fdd0d1934eda0492606ee0bb80a7bf8f35cc5f86ec60fe5031ba48bfd544"));
+        // there is no recorded EIP-2930 transaction in the tree, so sign one with the same shape
+        let eip2930 = generators::sign_tx_with_random_key_pair(
+            &mut rng,
+            Transaction::Eip2930(TxEip2930 {
+                chain_id: 1,
+                nonce: 7,
+                gas_price: 30_000_000_000,
+                gas_limit: 50_000,
+                to: TxKind::Call(Address::with_last_byte(1)),
+                access_list: AccessList(vec![AccessListItem {
+                    address: Address::with_last_byte(1),
+                    storage_keys: vec![B256::with_last_byte(2)],
+                }]),
+                ..Default::default()
+            }),
+        );
+        // deposit contract log <https://etherscan.io/tx/0xa5239d4c542063d29022545835815b78b09f571f2bf1c8427f4765d6f5abbce9>
+        let deposit_logs: Vec<Log> = serde_json::from_str(
+            r#"[{"address":"0x00000000219ab540356cbb839cbe05303d7705fa","topics":["0x649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5"],"data":"0x00000000000000000000000000000000000000000000000000000000000000a000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000000140000000000000000000000000000000000000000000000000000000000000018000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000030998c8086669bf65e24581cda47d8537966e9f5066fc6ffdcba910a1bfb91eae7a4873fcce166a1c4ea217e6b1afd396200000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000002001000000000000000000000001c340fb72ed14d4eaa71f7633ee9e33b88d4f3900000000000000000000000000000000000000000000000000000000000000080040597307000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000006098ddbffd700c1aac324cfdf0492ff289223661eb26718ce3651ba2469b22f480d56efab432ed91af05a006bde0c1ea68134e0acd8cacca0c13ad1f716db874b44abfcc966368019753174753bca3af2ea84bc569c46f76592a91e97f311eddec0000000000000000000000000000000000000000000000000000000000000008e474160000000000000000000000000000000000000000000000000000000000","blockHash":"0x8d1289c5a7e0965b1d1bb75cdc4c3f73dda82d4ebb94ff5b98d1389cebd53b56","blockNumber":"0x12f0d8d","transactionHash":"0xa5239d4c542063d29022545835815b78b09f571f2bf1c8427f4765d6f5abbce9","transactionIndex":"0xc4","logIndex":"0x18f","removed":false}]"#,
+        )
+        .unwrap();
+
+        let eras = [
+            (vec![pre_eip2], 0, None),
+            (vec![legacy, eip2930], 1, None),
+            (vec![eip1559, other_eip1559], 2, None),
+            (
+                vec![eip4844],
+                // cancun activation on mainnet
+                1710338135,
+                Some(Withdrawals::new(vec![Withdrawal {
+                    index: 1,
+                    validator_index: 2,
+                    address: Address::with_last_byte(4),
+                    amount: 5,
+                }])),
+            ),
+        ];
+        eras.into_iter()
+            .enumerate()
+            .map(|(number, (body, timestamp, withdrawals))| {
+                let senders =
+                    body.iter().map(|tx| tx.recover_signer_unchecked().unwrap()).collect();
+                let mut cumulative_gas_used = 0;
+                let receipts = body
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, tx)| {
+                        cumulative_gas_used += tx.gas_limit();
+                        #[allow(clippy::needless_update)] // side-effect of optimism fields
+                        Receipt {
+                            tx_type: tx.tx_type(),
+                            success: idx % 2 == 0,
+                            cumulative_gas_used,
+                            logs: if idx == body.len() - 1 {
+                                deposit_logs.clone()
+                            } else {
+                                Vec::new()
+                            },
+                            ..Default::default()
+                        }
+                    })
+                    .collect();
+                let header = Header {
+                    number: number as u64,
+                    timestamp,
+                    base_fee_per_gas: (number >= 2).then_some(7),
+                    withdrawals_root: withdrawals.as_ref().map(|_| B256::with_last_byte(5)),
+                    blob_gas_used: withdrawals.as_ref().map(|_| 1 << 17),
+                    excess_blob_gas: withdrawals.as_ref().map(|_| 0),
+                    parent_beacon_block_root: withdrawals.as_ref().map(|_| B256::with_last_byte(6)),
+                    ..Default::default()
+                };
+                // the first block has an ommer
+                let ommers = if number == 0 {
+                    vec![random_header(&mut rng, 0, None).unseal()]
+                } else {
+                    Vec::new()
+                };
+                let block = SealedBlock {
+                    header: header.seal_slow(),
+                    body,
+                    ommers,
+                    withdrawals,
+                    requests: None,
+                };
+                (SealedBlockWithSenders::new(block, senders).unwrap(), receipts)
+            })
+            .collect()
+    }
+
+    /// Asserts that the raw reads of the provider match the encodings of the typed reads and of
+    /// the blocks.
+    fn assert_raw_reads<DB: Database>(
+        factory: &ProviderFactory<DB>,
+        blocks: &[(SealedBlockWithSenders, Vec<Receipt>)],
+    ) {
+        for (block, receipts) in blocks {
+            let number = block.number;
+            let typed = factory.block(number.into()).unwrap().unwrap();
+            assert_eq!(typed, block.block.clone().unseal());
+
+            assert_eq!(
+                factory.raw_header(number).unwrap(),
+                Some(alloy_rlp::encode(block.header.header()).into())
+            );
+            assert_eq!(factory.raw_block(number).unwrap(), Some(alloy_rlp::encode(&typed).into()));
+            assert_eq!(
+                factory.raw_transactions(number).unwrap(),
+                Some(typed.body.iter().map(|tx| tx.envelope_encoded()).collect())
+            );
+            let typed_receipts = factory.receipts_by_block(number.into()).unwrap().unwrap();
+            assert_eq!(&typed_receipts, receipts);
+            assert_eq!(
+                factory.raw_receipts(number).unwrap(),
+                Some(
+                    typed_receipts
+                        .into_iter()
+                        .map(|receipt| receipt.with_bloom().envelope_encoded())
+                        .collect()
+                )
+            );
+            for tx in &block.body {
+                assert_eq!(
+                    factory.raw_transaction_by_hash(tx.hash).unwrap(),
+                    Some(tx.envelope_encoded())
+                );
+            }
+        }
+
+        let next = blocks.len() as u64;
+        assert_eq!(factory.raw_header(next).unwrap(), None);
+        assert_eq!(factory.raw_block(next).unwrap(), None);
+        assert_eq!(factory.raw_transactions(next).unwrap(), None);
+        assert_eq!(factory.raw_receipts(next).unwrap(), None);
+        assert_eq!(factory.raw_transaction_by_hash(B256::ZERO).unwrap(), None);
+    }
+
+    #[test]
+    fn raw_reads_match_typed_reads() {
+        let factory = create_test_provider_factory();
+        let blocks = raw_test_blocks();
+
+        let provider = factory.provider_rw().unwrap();
+        let mut tx_num = 0;
+        for (block, receipts) in &blocks {
+            provider.insert_block(block.clone(), None).unwrap();
+            for receipt in receipts {
+                provider.tx_ref().put::<tables::Receipts>(tx_num, receipt.clone()).unwrap();
+                tx_num += 1;
+            }
+        }
+        provider.commit().unwrap();
+
+        // read from the database
+        assert_raw_reads(&factory, &blocks);
+
+        // move the blocks to the static files
+        let static_file_provider = factory.static_file_provider();
+        let mut headers = static_file_provider.get_writer(0, StaticFileSegment::Headers).unwrap();
+        for (block, _) in &blocks {
+            headers.append_header(block.header.header().clone(), U256::ZERO, block.hash()).unwrap();
+        }
+        headers.commit().unwrap();
+        drop(headers);
+
+        let mut transactions =
+            static_file_provider.get_writer(0, StaticFileSegment::Transactions).unwrap();
+        let mut tx_num = 0;
+        for (block, _) in &blocks {
+            transactions.increment_block(StaticFileSegment::Transactions, block.number).unwrap();
+            for tx in &block.body {
+                transactions.append_transaction(tx_num, tx.clone().into()).unwrap();
+                tx_num += 1;
+            }
+        }
+        transactions.commit().unwrap();
+        drop(transactions);
+
+        let mut receipts = static_file_provider.get_writer(0, StaticFileSegment::Receipts).unwrap();
+        let mut tx_num = 0;
+        for (block, block_receipts) in &blocks {
+            receipts.increment_block(StaticFileSegment::Receipts, block.number).unwrap();
+            for receipt in block_receipts {
+                receipts.append_receipt(tx_num, receipt.clone()).unwrap();
+                tx_num += 1;
+            }
+        }
+        receipts.commit().unwrap();
+        drop(receipts);
+
+        let provider = factory.provider_rw().unwrap();
+        provider.tx_ref().clear::<tables::Headers>().unwrap();
+        provider.tx_ref().clear::<tables::Transactions>().unwrap();
+        provider.tx_ref().clear::<tables::Receipts>().unwrap();
+        provider.commit().unwrap();
+
+        // read from the static files
+        assert_raw_reads(&factory, &blocks);
+    }
 }
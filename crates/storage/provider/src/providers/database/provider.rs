@@ -5,16 +5,18 @@ use crate::{
     traits::{
         AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
     },
-    AccountReader, BlockExecutionWriter, BlockHashReader, BlockNumReader, BlockReader, BlockWriter,
-    Chain, EvmEnvProvider, FinalizedBlockReader, FinalizedBlockWriter, HashingWriter,
-    HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HeaderSyncMode, HistoricalStateProvider,
-    HistoryWriter, LatestStateProvider, OriginalValuesKnown, ProviderError, PruneCheckpointReader,
-    PruneCheckpointWriter, RequestsProvider, StageCheckpointReader, StateProviderBox, StateWriter,
+    AccountReader, AddressTransactionsReader, BlockExecutionWriter, BlockHashReader,
+    BlockNumReader, BlockReader, BlockWriter, Chain, EvmEnvProvider, FinalizedBlockReader,
+    FinalizedBlockWriter, HashingWriter, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider,
+    HeaderSyncMode, HistoricalStateProvider, HistoryWriter, LatestStateProvider,
+    OriginalValuesKnown, ProviderError, PruneCheckpointReader, PruneCheckpointWriter,
+    RawBlockReader, RequestsProvider, StageCheckpointReader, StateProviderBox, StateWriter,
     StatsReader, StorageReader, TransactionVariant, TransactionsProvider, TransactionsProviderExt,
     WithdrawalsProvider,
 };
+use alloy_rlp::Encodable;
 use itertools::{izip, Itertools};
-use reth_db::{tables, BlockNumberList};
+use reth_db::{static_file::WireEncode, tables, BlockNumberList};
 use reth_db_api::{
     common::KeyValue,
     cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, RangeWalker},
@@ -34,7 +36,7 @@ use reth_primitives::{
     revm::{config::revm_spec, env::fill_block_env},
     stage::{StageCheckpoint, StageId},
     trie::Nibbles,
-    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders,
+    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Bytes,
     ChainInfo, ChainSpec, GotExpected, Head, Header, Receipt, Requests, SealedBlock,
     SealedBlockWithSenders, SealedHeader, StaticFileSegment, StorageEntry, TransactionMeta,
     TransactionSigned, TransactionSignedEcRecovered, TransactionSignedNoHash, TxHash, TxNumber,
@@ -358,6 +360,84 @@ impl<TX: DbTx> DatabaseProvider<TX> {
     pub fn chain_spec(&self) -> &ChainSpec {
         &self.chain_spec
     }
+
+    /// Returns the addresses that are part of the transactions of the blocks in the range, with
+    /// the blocks they are part of in ascending order.
+    ///
+    /// An address is part of a transaction if it's the sender, the recipient or the created
+    /// contract of the transaction, or if it emitted a log during its execution. Missing senders
+    /// are recovered, missing receipts are skipped.
+    ///
+    /// NOTE: Get inclusive range of blocks.
+    pub fn address_transactions_with_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, Vec<BlockNumber>>> {
+        let mut address_transactions = BTreeMap::<Address, Vec<BlockNumber>>::new();
+
+        let body_indices = self
+            .tx
+            .cursor_read::<tables::BlockBodyIndices>()?
+            .walk_range(range)?
+            .collect::<Result<Vec<_>, _>>()?;
+        let (Some((_, first)), Some((_, last))) = (body_indices.first(), body_indices.last())
+        else {
+            return Ok(address_transactions)
+        };
+
+        let tx_range = first.first_tx_num()..last.next_tx_num();
+        let transactions = self.transactions_by_tx_range(tx_range.clone())?;
+        // senders and receipts may be pruned, only use them if all of them are present
+        let senders = Some(self.senders_by_tx_range(tx_range.clone())?)
+            .filter(|senders| senders.len() == transactions.len());
+        let receipts = Some(self.receipts_by_tx_range(tx_range)?)
+            .filter(|receipts| receipts.len() == transactions.len());
+
+        for (block_number, indices) in body_indices {
+            let mut insert = |address: Address| {
+                let blocks = address_transactions.entry(address).or_default();
+                if blocks.last() != Some(&block_number) {
+                    blocks.push(block_number);
+                }
+            };
+
+            for tx_number in indices.tx_num_range() {
+                let index = (tx_number - first.first_tx_num()) as usize;
+                let transaction = &transactions[index];
+
+                let sender = match &senders {
+                    Some(senders) => Some(senders[index]),
+                    None => match self.transaction_sender(tx_number)? {
+                        Some(sender) => Some(sender),
+                        None => transaction.recover_signer(),
+                    },
+                };
+                if let Some(sender) = sender {
+                    insert(sender);
+                    if transaction.kind().is_create() {
+                        insert(sender.create(transaction.nonce()));
+                    }
+                }
+                if let Some(to) = transaction.to() {
+                    insert(to);
+                }
+
+                let pruned_receipt;
+                let receipt = match &receipts {
+                    Some(receipts) => Some(&receipts[index]),
+                    None => {
+                        pruned_receipt = self.receipt(tx_number)?;
+                        pruned_receipt.as_ref()
+                    }
+                };
+                for log in receipt.iter().flat_map(|receipt| &receipt.logs) {
+                    insert(log.address);
+                }
+            }
+        }
+
+        Ok(address_transactions)
+    }
 }
 
 impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
@@ -366,6 +446,25 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         Ok(self.tx.commit()?)
     }
 
+    /// Removes the optional index of the blocks with transactions of an address and its
+    /// checkpoint, if it exists.
+    ///
+    /// The index is only kept up to date while the
+    /// [`IndexAddressTransactions`](StageId::IndexAddressTransactions) stage is part of the
+    /// pipeline, since the pipeline doesn't unwind it otherwise. Returns whether the index
+    /// existed.
+    pub fn drop_address_transaction_index(&self) -> ProviderResult<bool> {
+        if self.get_stage_checkpoint(StageId::IndexAddressTransactions)?.is_none() {
+            return Ok(false)
+        }
+        self.tx.clear::<tables::AddressTransactionIndex>()?;
+        self.tx.delete::<tables::StageCheckpoints>(
+            StageId::IndexAddressTransactions.to_string(),
+            None,
+        )?;
+        Ok(true)
+    }
+
     // TODO(joshie) TEMPORARY should be moved to trait providers
     /// Unwind or peek at last N blocks of state recreating the [`BundleStateWithReceipts`].
     ///
@@ -1317,6 +1416,42 @@ impl<TX: DbTx> BlockNumReader for DatabaseProvider<TX> {
 }
 
 impl<Tx: DbTx> DatabaseProvider<Tx> {
+    /// Returns the first block of the given inclusive range that is past shanghai, `None` if there
+    /// is none.
+    ///
+    /// Since block timestamps are increasing, this only reads the headers of the first and the last
+    /// block, unless the range spans the activation of shanghai.
+    fn first_shanghai_block_in_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Option<BlockNumber>> {
+        let is_shanghai = |number| -> ProviderResult<bool> {
+            Ok(self.header_by_number(number)?.is_some_and(|header| {
+                self.chain_spec.is_shanghai_active_at_timestamp(header.timestamp)
+            }))
+        };
+
+        let (start, end) = range.into_inner();
+        if is_shanghai(start)? {
+            return Ok(Some(start))
+        }
+        if start >= end || !is_shanghai(end)? {
+            return Ok(None)
+        }
+
+        // binary search for the activation block, `low` is before and `high` past shanghai
+        let (mut low, mut high) = (start, end);
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if is_shanghai(mid)? {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        Ok(Some(high))
+    }
+
     fn process_block_range<F, R>(
         &self,
         range: RangeInclusive<BlockNumber>,
@@ -1572,6 +1707,71 @@ impl<TX: DbTx> BlockReader for DatabaseProvider<TX> {
                 .map_err(|_| ProviderError::SenderRecoveryError)
         })
     }
+
+    /// Returns the transactions and withdrawals of the blocks in the given range.
+    ///
+    /// The transactions of all blocks are read at once from the static files or the database,
+    /// which are located by the body indices of the blocks. Headers are only read to find out
+    /// whether the blocks are past shanghai.
+    fn transactions_and_withdrawals_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<Option<(Vec<TransactionSignedNoHash>, Option<Withdrawals>)>>> {
+        let start = *range.start();
+        let end = (*range.end()).min(self.last_block_number()?);
+        if start > end {
+            return Ok(Vec::new())
+        }
+
+        let mut block_body_cursor = self.tx.cursor_read::<tables::BlockBodyIndices>()?;
+        let indices = (start..=end)
+            .map(|number| Ok(block_body_cursor.seek_exact(number)?.map(|(_, indices)| indices)))
+            .collect::<ProviderResult<Vec<_>>>()?;
+
+        // the transactions of the blocks are contiguous, so they're read as a single range
+        let first_tx = indices.iter().flatten().map(|indices| indices.first_tx_num()).next();
+        let last_tx = indices.iter().flatten().map(|indices| indices.next_tx_num()).last();
+        let mut next_tx = first_tx.unwrap_or_default();
+        let mut transactions = match first_tx.zip(last_tx) {
+            Some((first_tx, last_tx)) if first_tx < last_tx => {
+                self.transactions_by_tx_range(first_tx..last_tx)?.into_iter()
+            }
+            _ => Vec::new().into_iter(),
+        };
+
+        let first_shanghai_block = self.first_shanghai_block_in_range(start..=end)?;
+        let mut withdrawals_cursor = self.tx.cursor_read::<tables::BlockWithdrawals>()?;
+
+        (start..=end)
+            .zip(indices)
+            .map(|(number, indices)| {
+                let Some(indices) = indices else { return Ok(None) };
+
+                // skip the transactions of blocks without body indices
+                let skip = indices.first_tx_num().saturating_sub(next_tx) as usize;
+                if skip > 0 {
+                    transactions.nth(skip - 1);
+                }
+                let block_transactions =
+                    transactions.by_ref().take(indices.tx_count() as usize).collect();
+                next_tx = indices.next_tx_num();
+
+                // If we are past shanghai, then all blocks should have a withdrawal list, even if
+                // empty
+                let withdrawals = if first_shanghai_block.is_some_and(|first| number >= first) {
+                    Some(
+                        withdrawals_cursor
+                            .seek_exact(number)?
+                            .map(|(_, w)| w.withdrawals)
+                            .unwrap_or_default(),
+                    )
+                } else {
+                    None
+                };
+                Ok(Some((block_transactions, withdrawals)))
+            })
+            .collect()
+    }
 }
 
 impl<TX: DbTx> TransactionsProviderExt for DatabaseProvider<TX> {
@@ -2008,6 +2208,139 @@ impl<TX: DbTx> StageCheckpointReader for DatabaseProvider<TX> {
     }
 }
 
+impl<TX: DbTx> AddressTransactionsReader for DatabaseProvider<TX> {
+    fn address_transactions_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(self
+            .get_stage_checkpoint(StageId::IndexAddressTransactions)?
+            .map(|checkpoint| checkpoint.block_number))
+    }
+
+    fn address_transaction_blocks_before(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let mut blocks = Vec::new();
+        let mut cursor = self.tx.cursor_read::<tables::AddressTransactionIndex>()?;
+
+        // The last shard of an address has the `u64::MAX` key, so this is the shard with the
+        // block, if the address has any.
+        let mut item = cursor.seek(ShardedKey::new(address, block_number))?;
+        while let Some((sharded_key, list)) = item {
+            if sharded_key.key != address || blocks.len() >= limit {
+                break
+            }
+
+            let shard = list.iter().take_while(|block| *block < block_number).collect::<Vec<_>>();
+            blocks.extend(shard.into_iter().rev().take(limit - blocks.len()));
+            item = cursor.prev()?;
+        }
+
+        Ok(blocks)
+    }
+
+    fn address_transaction_blocks_after(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let mut blocks = Vec::new();
+        let mut cursor = self.tx.cursor_read::<tables::AddressTransactionIndex>()?;
+
+        for entry in cursor.walk(Some(ShardedKey::new(address, block_number.saturating_add(1))))? {
+            let (sharded_key, list) = entry?;
+            if sharded_key.key != address || blocks.len() >= limit {
+                break
+            }
+
+            blocks.extend(
+                list.iter().filter(|block| *block > block_number).take(limit - blocks.len()),
+            );
+        }
+
+        Ok(blocks)
+    }
+}
+
+impl<TX: DbTx> RawBlockReader for DatabaseProvider<TX> {
+    fn raw_header(&self, number: BlockNumber) -> ProviderResult<Option<Bytes>> {
+        self.static_file_provider.get_with_static_file_or_database(
+            StaticFileSegment::Headers,
+            number,
+            |static_file| static_file.raw_header_by_number(number),
+            || Ok(self.tx.get::<tables::Headers>(number)?.map(|header| header.wire_encoded())),
+        )
+    }
+
+    fn raw_block(&self, number: BlockNumber) -> ProviderResult<Option<Bytes>> {
+        // the header is needed to tell which fields the body has, the other parts of the body are
+        // small compared to the transactions
+        let Some(header) = self.header_by_number(number)? else { return Ok(None) };
+        let Some(transactions) = self.raw_transactions(number)? else { return Ok(None) };
+        let ommers = self.ommers(number.into())?.unwrap_or_default();
+        let withdrawals = self.withdrawals_by_block(number.into(), header.timestamp)?;
+        let requests = self.requests_by_block(number.into(), header.timestamp)?;
+
+        Ok(Some(encode_raw_block(
+            &header,
+            &transactions,
+            &ommers,
+            withdrawals.as_ref(),
+            requests.as_ref(),
+        )))
+    }
+
+    fn raw_transactions(&self, number: BlockNumber) -> ProviderResult<Option<Vec<Bytes>>> {
+        let Some(body) = self.block_body_indices(number)? else { return Ok(None) };
+        self.static_file_provider
+            .get_range_with_static_file_or_database(
+                StaticFileSegment::Transactions,
+                body.tx_num_range(),
+                |static_file, range, _| static_file.raw_transactions_by_tx_range(range),
+                |range, _| {
+                    Ok(self
+                        .cursor_read_collect::<tables::Transactions>(range)?
+                        .iter()
+                        .map(WireEncode::wire_encoded)
+                        .collect())
+                },
+                |_| true,
+            )
+            .map(Some)
+    }
+
+    fn raw_receipts(&self, number: BlockNumber) -> ProviderResult<Option<Vec<Bytes>>> {
+        let Some(body) = self.block_body_indices(number)? else { return Ok(None) };
+        self.static_file_provider
+            .get_range_with_static_file_or_database(
+                StaticFileSegment::Receipts,
+                body.tx_num_range(),
+                |static_file, range, _| static_file.raw_receipts_by_tx_range(range),
+                |range, _| {
+                    Ok(self
+                        .cursor_read_collect::<tables::Receipts>(range)?
+                        .iter()
+                        .map(WireEncode::wire_encoded)
+                        .collect())
+                },
+                |_| true,
+            )
+            .map(Some)
+    }
+
+    fn raw_transaction_by_hash(&self, hash: TxHash) -> ProviderResult<Option<Bytes>> {
+        let Some(id) = self.transaction_id(hash)? else { return Ok(None) };
+        self.static_file_provider.get_with_static_file_or_database(
+            StaticFileSegment::Transactions,
+            id,
+            |static_file| static_file.raw_transaction_by_id(id),
+            || Ok(self.tx.get::<tables::Transactions>(id)?.map(|tx| tx.wire_encoded())),
+        )
+    }
+}
+
 impl<TX: DbTxMut> StageCheckpointWriter for DatabaseProvider<TX> {
     /// Save stage checkpoint.
     fn save_stage_checkpoint(
@@ -2416,6 +2749,46 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
         )
     }
 
+    fn unwind_address_transaction_indices(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<usize> {
+        let address_transactions = self.address_transactions_with_range(range)?;
+
+        let mut cursor = self.tx.cursor_write::<tables::AddressTransactionIndex>()?;
+        for (&address, blocks) in &address_transactions {
+            // the lowest block of the range the address is part of
+            let rem_index = blocks[0];
+            let partial_shard = unwind_history_shards::<_, tables::AddressTransactionIndex, _>(
+                &mut cursor,
+                ShardedKey::last(address),
+                rem_index,
+                |sharded_key| sharded_key.key == address,
+            )?;
+
+            // Check the last returned partial shard.
+            // If it's not empty, the shard needs to be reinserted.
+            if !partial_shard.is_empty() {
+                cursor.insert(
+                    ShardedKey::last(address),
+                    BlockNumberList::new_pre_sorted(partial_shard),
+                )?;
+            }
+        }
+
+        Ok(address_transactions.len())
+    }
+
+    fn insert_address_transaction_index(
+        &self,
+        address_transactions: BTreeMap<Address, Vec<u64>>,
+    ) -> ProviderResult<()> {
+        self.append_history_index::<_, tables::AddressTransactionIndex>(
+            address_transactions,
+            ShardedKey::new,
+        )
+    }
+
     fn update_history_indices(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()> {
         // account history stage
         {
@@ -2472,6 +2845,19 @@ impl<TX: DbTxMut + DbTx> BlockExecutionWriter for DatabaseProvider<TX> {
             // Unwind storage history indices.
             self.unwind_storage_history_indices(storage_range)?;
 
+            // Unwind the address transaction index if it covers the range. The index is optional,
+            // so its checkpoint isn't updated with the pipeline stages.
+            if self
+                .get_stage_checkpoint(StageId::IndexAddressTransactions)?
+                .is_some_and(|checkpoint| checkpoint.block_number >= *range.start())
+            {
+                self.unwind_address_transaction_indices(range.clone())?;
+                self.save_stage_checkpoint(
+                    StageId::IndexAddressTransactions,
+                    StageCheckpoint::new(range.start().saturating_sub(1)),
+                )?;
+            }
+
             // Calculate the reverted merkle root.
             // This is the same as `StateRoot::incremental_root_with_updates`, only the prefix sets
             // are pre-loaded.
@@ -2709,6 +3095,19 @@ impl<TX: DbTxMut + DbTx> BlockWriter for DatabaseProvider<TX> {
         durations_recorder.record_relative(metrics::Action::InsertHashes);
 
         self.update_history_indices(first_number..=last_block_number)?;
+
+        // The address transaction index is optional, it's only extended if it's up to date.
+        if self
+            .get_stage_checkpoint(StageId::IndexAddressTransactions)?
+            .is_some_and(|checkpoint| checkpoint.block_number + 1 == first_number)
+        {
+            let indices = self.address_transactions_with_range(first_number..=last_block_number)?;
+            self.insert_address_transaction_index(indices)?;
+            self.save_stage_checkpoint(
+                StageId::IndexAddressTransactions,
+                StageCheckpoint::new(last_block_number),
+            )?;
+        }
         durations_recorder.record_relative(metrics::Action::InsertHistoryIndices);
 
         // Update pipeline progress
@@ -2777,6 +3176,57 @@ impl<TX: DbTxMut> FinalizedBlockWriter for DatabaseProvider<TX> {
     }
 }
 
+/// Returns the RLP encoding of a block from its parts, with the transactions in their EIP-2718
+/// encoding.
+fn encode_raw_block(
+    header: &Header,
+    transactions: &[Bytes],
+    ommers: &[Header],
+    withdrawals: Option<&Withdrawals>,
+    requests: Option<&Requests>,
+) -> Bytes {
+    // In the body of a block, typed transactions are encoded as a string of their EIP-2718
+    // encoding, while legacy transactions are a list.
+    let transaction_header = |transaction: &Bytes| {
+        (transaction.first().map_or(true, |first| *first < alloy_rlp::EMPTY_LIST_CODE))
+            .then(|| alloy_rlp::Header { list: false, payload_length: transaction.len() })
+    };
+    let transactions_length = transactions
+        .iter()
+        .map(|transaction| {
+            transaction_header(transaction).map_or(0, |header| header.length()) + transaction.len()
+        })
+        .sum::<usize>();
+    let transactions_header = alloy_rlp::Header { list: true, payload_length: transactions_length };
+
+    let payload_length = header.length() +
+        transactions_header.length() +
+        transactions_length +
+        ommers.length() +
+        withdrawals.map_or(0, Encodable::length) +
+        requests.map_or(0, Encodable::length);
+    let block_header = alloy_rlp::Header { list: true, payload_length };
+
+    let mut out = Vec::with_capacity(block_header.length() + payload_length);
+    block_header.encode(&mut out);
+    header.encode(&mut out);
+    transactions_header.encode(&mut out);
+    for transaction in transactions {
+        if let Some(header) = transaction_header(transaction) {
+            header.encode(&mut out);
+        }
+        out.extend_from_slice(transaction);
+    }
+    ommers.encode(&mut out);
+    if let Some(withdrawals) = withdrawals {
+        withdrawals.encode(&mut out);
+    }
+    if let Some(requests) = requests {
+        requests.encode(&mut out);
+    }
+    out.into()
+}
+
 fn range_size_hint(range: &impl RangeBounds<TxNumber>) -> Option<usize> {
     let start = match range.start_bound().cloned() {
         Bound::Included(start) => start,
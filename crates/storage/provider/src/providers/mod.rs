@@ -1,11 +1,12 @@
 use crate::{
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    BlockSource, BlockchainTreePendingStateProvider, CanonChainTracker, CanonStateNotifications,
-    CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
-    EvmEnvProvider, FullBundleStateDataProvider, HeaderProvider, ProviderError,
-    PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt, RequestsProvider,
-    StageCheckpointReader, StateProviderBox, StateProviderFactory, StaticFileProviderFactory,
-    TransactionVariant, TransactionsProvider, TreeViewer, WithdrawalsProvider,
+    AccountReader, AddressTransactionsReader, BlockHashReader, BlockIdReader, BlockNumReader,
+    BlockReader, BlockReaderIdExt, BlockSource, BlockchainTreePendingStateProvider,
+    CanonChainTracker, CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider,
+    ChangeSetReader, DatabaseProviderFactory, EvmEnvProvider, FullBundleStateDataProvider,
+    HeaderProvider, ProviderError, PruneCheckpointReader, RawBlockReader, ReceiptProvider,
+    ReceiptProviderIdExt, RequestsProvider, StageCheckpointReader, StateProviderBox,
+    StateProviderFactory, StaticFileProviderFactory, TransactionVariant, TransactionsProvider,
+    TreeViewer, WithdrawalsProvider,
 };
 use reth_blockchain_tree_api::{
     error::{CanonicalError, InsertBlockError},
@@ -20,7 +21,7 @@ use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
     stage::{StageCheckpoint, StageId},
     Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumHash, BlockNumber,
-    BlockNumberOrTag, BlockWithSenders, ChainInfo, ChainSpec, Header, Receipt, SealedBlock,
+    BlockNumberOrTag, BlockWithSenders, Bytes, ChainInfo, ChainSpec, Header, Receipt, SealedBlock,
     SealedBlockWithSenders, SealedHeader, TransactionMeta, TransactionSigned,
     TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256, U256,
 };
@@ -56,6 +57,9 @@ pub use bundle_state_provider::BundleStateProvider;
 mod chain_info;
 use chain_info::ChainInfoTracker;
 
+mod raw;
+pub use raw::RawDatabaseProvider;
+
 mod consistent_view;
 use alloy_rpc_types_engine::ForkchoiceState;
 pub use consistent_view::{ConsistentDbView, ConsistentViewError};
@@ -337,6 +341,13 @@ where
     ) -> ProviderResult<Vec<BlockWithSenders>> {
         self.database.block_with_senders_range(range)
     }
+
+    fn transactions_and_withdrawals_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<Option<(Vec<TransactionSignedNoHash>, Option<Withdrawals>)>>> {
+        self.database.transactions_and_withdrawals_range(range)
+    }
 }
 
 impl<DB> TransactionsProvider for BlockchainProvider<DB>
@@ -500,6 +511,58 @@ where
     }
 }
 
+impl<DB> AddressTransactionsReader for BlockchainProvider<DB>
+where
+    DB: Database,
+{
+    fn address_transactions_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.database.provider()?.address_transactions_checkpoint()
+    }
+
+    fn address_transaction_blocks_before(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.provider()?.address_transaction_blocks_before(address, block_number, limit)
+    }
+
+    fn address_transaction_blocks_after(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.provider()?.address_transaction_blocks_after(address, block_number, limit)
+    }
+}
+
+impl<DB> RawBlockReader for BlockchainProvider<DB>
+where
+    DB: Database,
+{
+    fn raw_header(&self, number: BlockNumber) -> ProviderResult<Option<Bytes>> {
+        self.database.provider()?.raw_header(number)
+    }
+
+    fn raw_block(&self, number: BlockNumber) -> ProviderResult<Option<Bytes>> {
+        self.database.provider()?.raw_block(number)
+    }
+
+    fn raw_transactions(&self, number: BlockNumber) -> ProviderResult<Option<Vec<Bytes>>> {
+        self.database.provider()?.raw_transactions(number)
+    }
+
+    fn raw_receipts(&self, number: BlockNumber) -> ProviderResult<Option<Vec<Bytes>>> {
+        self.database.provider()?.raw_receipts(number)
+    }
+
+    fn raw_transaction_by_hash(&self, hash: TxHash) -> ProviderResult<Option<Bytes>> {
+        self.database.provider()?.raw_transaction_by_hash(hash)
+    }
+}
+
 impl<DB> EvmEnvProvider for BlockchainProvider<DB>
 where
     DB: Database,
@@ -0,0 +1,180 @@
+use crate::{DatabaseProviderFactory, StaticFileProviderFactory};
+use reth_db::{
+    static_file::{
+        ColumnSelectorOne, ColumnSelectorThree, HeaderMask, ReceiptMask, TransactionMask,
+    },
+    RawKey, RawTable, Receipts, TableViewer, Tables, Transactions,
+};
+use reth_db_api::{
+    database::Database,
+    models::CompactU256,
+    table::{Decompress, Table},
+    transaction::DbTx,
+};
+use reth_primitives::{BlockHash, Bytes, Header, StaticFileSegment};
+use reth_storage_api::{RawDatabaseReader, RawStaticFileRow, RawTableValue};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use std::{marker::PhantomData, str::FromStr};
+
+/// Reads the raw content of the database and the static files of a provider.
+#[derive(Debug)]
+pub struct RawDatabaseProvider<P, DB> {
+    provider: P,
+    _db: PhantomData<fn() -> DB>,
+}
+
+impl<P, DB> RawDatabaseProvider<P, DB> {
+    /// Creates a new reader of the content of the provider.
+    pub const fn new(provider: P) -> Self {
+        Self { provider, _db: PhantomData }
+    }
+}
+
+impl<P, DB> RawDatabaseReader for RawDatabaseProvider<P, DB>
+where
+    P: DatabaseProviderFactory<DB> + StaticFileProviderFactory + Send + Sync,
+    DB: Database,
+{
+    fn raw_table_value(&self, table: &str, key: &[u8]) -> ProviderResult<Option<RawTableValue>> {
+        let table =
+            Tables::from_str(table).map_err(|_| ProviderError::UnknownTable(table.to_string()))?;
+        let provider = self.provider.database_provider_ro()?;
+        table.view(&RawTableValueViewer { tx: provider.tx_ref(), key })
+    }
+
+    fn raw_static_file_row(
+        &self,
+        segment: StaticFileSegment,
+        number: u64,
+    ) -> ProviderResult<Option<RawStaticFileRow>> {
+        // all columns of the segment
+        let mask = match segment {
+            StaticFileSegment::Headers => <HeaderMask<Header, CompactU256, BlockHash>>::MASK,
+            StaticFileSegment::Transactions => {
+                <TransactionMask<<Transactions as Table>::Value>>::MASK
+            }
+            StaticFileSegment::Receipts => <ReceiptMask<<Receipts as Table>::Value>>::MASK,
+        };
+
+        let columns =
+            self.provider.static_file_provider().find_static_file(segment, |provider| {
+                let mut cursor = provider.cursor()?;
+                cursor.get(number.into(), mask).map(|row| {
+                    row.map(|columns| {
+                        columns.iter().map(|column| Bytes::copy_from_slice(column)).collect()
+                    })
+                })
+            })?;
+
+        Ok(columns.map(|columns: Vec<Bytes>| {
+            let decoded = decode_static_file_row(segment, &columns);
+            RawStaticFileRow { columns, decoded }
+        }))
+    }
+}
+
+/// Looks up the encoded key in a table.
+struct RawTableValueViewer<'a, TX> {
+    tx: &'a TX,
+    key: &'a [u8],
+}
+
+impl<TX: DbTx> TableViewer<Option<RawTableValue>> for RawTableValueViewer<'_, TX> {
+    type Error = ProviderError;
+
+    fn view<T: Table>(&self) -> Result<Option<RawTableValue>, Self::Error> {
+        let key = RawKey::<T::Key>::from_vec(self.key.to_vec());
+        let decoded_key = key.key().ok().and_then(|key| serde_json::to_value(key).ok());
+        let Some(value) = self.tx.get::<RawTable<T>>(key)? else { return Ok(None) };
+        let decoded = value.value().ok().and_then(|value| serde_json::to_value(value).ok());
+
+        Ok(Some(RawTableValue { key: decoded_key, value: value.into_value().into(), decoded }))
+    }
+}
+
+/// Decodes the columns of a static file row, `None` if they don't decode.
+fn decode_static_file_row(
+    segment: StaticFileSegment,
+    columns: &[Bytes],
+) -> Option<serde_json::Value> {
+    match (segment, columns) {
+        (StaticFileSegment::Headers, [header, difficulty, hash]) => {
+            let header = Header::decompress(header).ok()?;
+            let difficulty = CompactU256::decompress(difficulty).ok()?;
+            let hash = BlockHash::decompress(hash).ok()?;
+            Some(serde_json::json!({
+                "header": header,
+                "totalDifficulty": difficulty,
+                "hash": hash,
+            }))
+        }
+        (StaticFileSegment::Transactions, [transaction]) => {
+            let transaction = <Transactions as Table>::Value::decompress(transaction).ok()?;
+            serde_json::to_value(transaction).ok()
+        }
+        (StaticFileSegment::Receipts, [receipt]) => {
+            let receipt = <Receipts as Table>::Value::decompress(receipt).ok()?;
+            serde_json::to_value(receipt).ok()
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{providers::StaticFileWriter, test_utils::create_test_provider_factory};
+    use reth_db::tables;
+    use reth_db_api::{table::Encode, transaction::DbTxMut};
+    use reth_primitives::{B256, U256};
+
+    #[test]
+    fn reads_raw_table_values() {
+        let factory = create_test_provider_factory();
+        let header = Header { number: 1, gas_limit: 30_000_000, ..Default::default() };
+        let provider = factory.provider_rw().unwrap();
+        provider.tx_ref().put::<tables::Headers>(1, header.clone()).unwrap();
+        provider.commit().unwrap();
+
+        let reader = RawDatabaseProvider::new(factory);
+        let value = reader.raw_table_value("Headers", &1u64.encode()).unwrap().unwrap();
+        assert_eq!(value.key, Some(serde_json::json!(1)));
+        assert_eq!(Header::decompress(&value.value).unwrap(), header);
+        assert_eq!(value.decoded, Some(serde_json::to_value(&header).unwrap()));
+
+        assert_eq!(reader.raw_table_value("Headers", &2u64.encode()).unwrap(), None);
+        assert_eq!(
+            reader.raw_table_value("Foo", &1u64.encode()).unwrap_err(),
+            ProviderError::UnknownTable("Foo".to_string())
+        );
+    }
+
+    #[test]
+    fn reads_raw_static_file_rows() {
+        let factory = create_test_provider_factory();
+        let header = Header { gas_limit: 30_000_000, ..Default::default() };
+        let difficulty = CompactU256::from(U256::from(7));
+        let hash = B256::with_last_byte(1);
+        let mut writer =
+            factory.static_file_provider().latest_writer(StaticFileSegment::Headers).unwrap();
+        writer.append_header(header.clone(), difficulty.clone().into(), hash).unwrap();
+        writer.commit().unwrap();
+        drop(writer);
+
+        let reader = RawDatabaseProvider::new(factory);
+        let row = reader.raw_static_file_row(StaticFileSegment::Headers, 0).unwrap().unwrap();
+        assert_eq!(row.columns.len(), 3);
+        assert_eq!(Header::decompress(&row.columns[0]).unwrap(), header);
+        assert_eq!(
+            row.decoded,
+            Some(serde_json::json!({
+                "header": header,
+                "totalDifficulty": difficulty,
+                "hash": hash,
+            }))
+        );
+
+        assert_eq!(reader.raw_static_file_row(StaticFileSegment::Headers, 1).unwrap(), None);
+        assert_eq!(reader.raw_static_file_row(StaticFileSegment::Receipts, 0).unwrap(), None);
+    }
+}
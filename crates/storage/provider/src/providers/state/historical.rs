@@ -10,13 +10,25 @@ use reth_db_api::{
     transaction::DbTx,
 };
 use reth_primitives::{
-    constants::EPOCH_SLOTS, trie::AccountProof, Account, Address, BlockNumber, Bytecode,
-    StaticFileSegment, StorageKey, StorageValue, B256,
+    constants::EPOCH_SLOTS,
+    keccak256,
+    trie::{AccountProof, MultiProof, StorageMultiProof},
+    Account, Address, BlockNumber, Bytecode, StaticFileSegment, StorageKey, StorageValue, B256,
 };
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::{updates::TrieUpdates, HashedPostState};
+use reth_trie::{
+    updates::TrieUpdates, HashedPostState, HashedStorage, StorageRange,
+    MAX_PLAIN_STORAGE_PREIMAGE_SCAN,
+};
 use revm::db::BundleState;
-use std::fmt::Debug;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+};
+
+/// The number of reverted blocks above which the revert state is hashed in parallel, see
+/// [`HashedPostState::from_revert_range_parallel`].
+const PARALLEL_REVERT_STATE_THRESHOLD: u64 = 64;
 
 /// State provider for a given block number which takes a tx reference.
 ///
@@ -130,7 +142,12 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
             );
         }
 
-        Ok(HashedPostState::from_revert_range(self.tx, self.block_number..=tip)?)
+        let range = self.block_number..=tip;
+        if tip.saturating_sub(self.block_number) > PARALLEL_REVERT_STATE_THRESHOLD {
+            Ok(HashedPostState::from_revert_range_parallel(self.tx, range)?)
+        } else {
+            Ok(HashedPostState::from_revert_range(self.tx, range)?)
+        }
     }
 
     fn history_info<T, K>(
@@ -269,6 +286,35 @@ impl<'b, TX: DbTx> StateRootProvider for HistoricalStateProviderRef<'b, TX> {
             .state_root_with_updates(self.tx)
             .map_err(|err| ProviderError::Database(err.into()))
     }
+
+    fn hashed_state_root_with_storage_roots(
+        &self,
+        hashed_state: HashedPostState,
+        storage_roots: HashMap<B256, B256>,
+    ) -> ProviderResult<B256> {
+        let mut revert_state = self.revert_state()?;
+        revert_state.extend(hashed_state);
+        revert_state
+            .state_root_with_storage_roots(self.tx, storage_roots)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    fn hashed_storage_root(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        let mut revert_storage = self
+            .revert_state()?
+            .storages
+            .remove(&hashed_address)
+            .unwrap_or_else(|| HashedStorage::new(false));
+        revert_storage.extend(hashed_storage);
+        HashedPostState::default()
+            .with_storages([(hashed_address, revert_storage)])
+            .storage_root(self.tx, hashed_address)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
 }
 
 impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
@@ -307,9 +353,69 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
         self.tx.get::<tables::Bytecodes>(code_hash).map_err(Into::into)
     }
 
-    /// Get account and storage proofs.
-    fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
-        Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
+    /// Get the account proof on top of the reverted state.
+    fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof> {
+        self.revert_state()?
+            .account_proof(self.tx, address, keys)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    /// Get the state multiproof on top of the reverted state.
+    fn multiproof(
+        &self,
+        hashed_state: HashedPostState,
+        targets: HashMap<B256, HashSet<B256>>,
+    ) -> ProviderResult<MultiProof> {
+        let mut revert_state = self.revert_state()?;
+        revert_state.extend(hashed_state);
+        revert_state
+            .multiproof(self.tx, &targets)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    /// Get the storage multiproof on top of the reverted state.
+    fn storage_multiproof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<StorageMultiProof> {
+        let hashed_address = keccak256(address);
+        let hashed_slots = slots.iter().map(keccak256).collect();
+        let mut revert_state = self.revert_state()?;
+        revert_state
+            .storages
+            .entry(hashed_address)
+            .or_insert_with(|| HashedStorage::new(false))
+            .extend(hashed_storage);
+        revert_state
+            .storage_multiproof(self.tx, hashed_address, &hashed_slots)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    /// Get the storage range on top of the reverted state.
+    ///
+    /// The plain storage is the latest state, so the preimages of the slots that were cleared
+    /// after the block remain unknown.
+    fn storage_range(
+        &self,
+        address: Address,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<StorageRange> {
+        let hashed_address = keccak256(address);
+        let mut revert_storage = self
+            .revert_state()?
+            .storages
+            .remove(&hashed_address)
+            .unwrap_or_else(|| HashedStorage::new(false));
+        revert_storage.extend(hashed_storage);
+        let mut range = HashedPostState::default()
+            .with_storages([(hashed_address, revert_storage)])
+            .storage_range(self.tx, hashed_address, start, limit)?;
+        range.set_plain_storage_preimages(self.tx, address, MAX_PLAIN_STORAGE_PREIMAGE_SCAN)?;
+        Ok(range)
     }
 }
 
@@ -411,8 +517,10 @@ mod tests {
         models::{storage_sharded_key::StorageShardedKey, AccountBeforeTx, ShardedKey},
         transaction::{DbTx, DbTxMut},
     };
-    use reth_primitives::{address, b256, Account, Address, StorageEntry, B256, U256};
+    use reth_primitives::{address, b256, keccak256, Account, Address, StorageEntry, B256, U256};
     use reth_storage_errors::provider::ProviderError;
+    use reth_trie::HashedStorage;
+    use std::collections::BTreeMap;
 
     const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
     const HIGHER_ADDRESS: Address = address!("0000000000000000000000000000000000000005");
@@ -549,6 +657,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn history_provider_storage_range() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+        let static_file_provider = factory.static_file_provider();
+
+        let kept = B256::with_last_byte(1);
+        let written = B256::with_last_byte(2);
+        let cleared = B256::with_last_byte(3);
+
+        // `written` is set and `cleared` is cleared at block 5, the tip of the chain
+        tx.put::<tables::CanonicalHeaders>(5, B256::ZERO).unwrap();
+        tx.put::<tables::StorageChangeSets>(
+            (5, ADDRESS).into(),
+            StorageEntry { key: written, value: U256::ZERO },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSets>(
+            (5, ADDRESS).into(),
+            StorageEntry { key: cleared, value: U256::from(30) },
+        )
+        .unwrap();
+        for (slot, value) in [(kept, U256::from(10)), (written, U256::from(20))] {
+            tx.put::<tables::PlainStorageState>(ADDRESS, StorageEntry { key: slot, value })
+                .unwrap();
+            tx.put::<tables::HashedStorages>(
+                keccak256(ADDRESS),
+                StorageEntry { key: keccak256(slot), value },
+            )
+            .unwrap();
+        }
+        tx.commit().unwrap();
+
+        let tx = factory.provider().unwrap().into_tx();
+        let collect = |block_number| {
+            let provider =
+                HistoricalStateProviderRef::new(&tx, block_number, static_file_provider.clone());
+            let mut slots = BTreeMap::new();
+            let mut start = B256::ZERO;
+            loop {
+                let range =
+                    provider.storage_range(ADDRESS, HashedStorage::new(false), start, 1).unwrap();
+                assert!(range.slots.len() <= 1);
+                slots.extend(
+                    range
+                        .slots
+                        .into_iter()
+                        .map(|(hashed_slot, entry)| (hashed_slot, (entry.slot, entry.value))),
+                );
+                match range.next_key {
+                    Some(next_key) => start = next_key,
+                    None => break,
+                }
+            }
+            slots
+        };
+
+        // the state before block 5 has the old values, the preimage of the cleared slot is unknown
+        assert_eq!(
+            collect(5),
+            BTreeMap::from([
+                (keccak256(kept), (Some(kept), U256::from(10))),
+                (keccak256(cleared), (None, U256::from(30))),
+            ])
+        );
+        // the state after the tip is the latest state
+        assert_eq!(
+            collect(6),
+            BTreeMap::from([
+                (keccak256(kept), (Some(kept), U256::from(10))),
+                (keccak256(written), (Some(written), U256::from(20))),
+            ])
+        );
+    }
+
     #[test]
     fn history_provider_get_storage() {
         let factory = create_test_provider_factory();
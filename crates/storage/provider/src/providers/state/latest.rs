@@ -8,12 +8,17 @@ use reth_db_api::{
     transaction::DbTx,
 };
 use reth_primitives::{
-    trie::AccountProof, Account, Address, BlockNumber, Bytecode, StaticFileSegment, StorageKey,
-    StorageValue, B256,
+    keccak256,
+    trie::{AccountProof, MultiProof, StorageMultiProof},
+    Account, Address, BlockNumber, Bytecode, StaticFileSegment, StorageKey, StorageValue, B256,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
-use reth_trie::{proof::Proof, updates::TrieUpdates, HashedPostState};
+use reth_trie::{
+    proof::Proof, updates::TrieUpdates, HashedPostState, HashedStorage, StorageRange,
+    MAX_PLAIN_STORAGE_PREIMAGE_SCAN,
+};
 use revm::db::BundleState;
+use std::collections::{HashMap, HashSet};
 
 /// State provider over latest state that takes tx reference.
 #[derive(Debug)]
@@ -89,6 +94,27 @@ impl<'b, TX: DbTx> StateRootProvider for LatestStateProviderRef<'b, TX> {
             .state_root_with_updates(self.tx)
             .map_err(|err| ProviderError::Database(err.into()))
     }
+
+    fn hashed_state_root_with_storage_roots(
+        &self,
+        hashed_state: HashedPostState,
+        storage_roots: HashMap<B256, B256>,
+    ) -> ProviderResult<B256> {
+        hashed_state
+            .state_root_with_storage_roots(self.tx, storage_roots)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    fn hashed_storage_root(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        HashedPostState::default()
+            .with_storages([(hashed_address, hashed_storage)])
+            .storage_root(self.tx, hashed_address)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
 }
 
 impl<'b, TX: DbTx> StateProvider for LatestStateProviderRef<'b, TX> {
@@ -107,6 +133,24 @@ impl<'b, TX: DbTx> StateProvider for LatestStateProviderRef<'b, TX> {
         Ok(None)
     }
 
+    fn storages(
+        &self,
+        account: Address,
+        storage_keys: &[StorageKey],
+    ) -> ProviderResult<Vec<Option<StorageValue>>> {
+        // All slots are read with the same cursor
+        let mut cursor = self.tx.cursor_dup_read::<tables::PlainStorageState>()?;
+        storage_keys
+            .iter()
+            .map(|storage_key| {
+                Ok(cursor
+                    .seek_by_key_subkey(account, *storage_key)?
+                    .filter(|entry| entry.key == *storage_key)
+                    .map(|entry| entry.value))
+            })
+            .collect()
+    }
+
     /// Get account code by its hash
     fn bytecode_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>> {
         self.tx.get::<tables::Bytecodes>(code_hash).map_err(Into::into)
@@ -117,6 +161,45 @@ impl<'b, TX: DbTx> StateProvider for LatestStateProviderRef<'b, TX> {
             .account_proof(address, slots)
             .map_err(Into::<reth_db::DatabaseError>::into)?)
     }
+
+    fn multiproof(
+        &self,
+        hashed_state: HashedPostState,
+        targets: HashMap<B256, HashSet<B256>>,
+    ) -> ProviderResult<MultiProof> {
+        hashed_state
+            .multiproof(self.tx, &targets)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    fn storage_multiproof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<StorageMultiProof> {
+        let hashed_address = keccak256(address);
+        let hashed_slots = slots.iter().map(keccak256).collect();
+        HashedPostState::default()
+            .with_storages([(hashed_address, hashed_storage)])
+            .storage_multiproof(self.tx, hashed_address, &hashed_slots)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    fn storage_range(
+        &self,
+        address: Address,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<StorageRange> {
+        let hashed_address = keccak256(address);
+        let mut range = HashedPostState::default()
+            .with_storages([(hashed_address, hashed_storage)])
+            .storage_range(self.tx, hashed_address, start, limit)?;
+        range.set_plain_storage_preimages(self.tx, address, MAX_PLAIN_STORAGE_PREIMAGE_SCAN)?;
+        Ok(range)
+    }
 }
 
 /// State provider for the latest state.
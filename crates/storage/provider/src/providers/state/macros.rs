@@ -33,6 +33,8 @@ macro_rules! delegate_provider_impls {
             StateRootProvider $(where [$($generics)*])? {
                 fn state_root(&self, state: &revm::db::BundleState) -> reth_storage_errors::provider::ProviderResult<reth_primitives::B256>;
                 fn state_root_with_updates(&self, state: &revm::db::BundleState) -> reth_storage_errors::provider::ProviderResult<(reth_primitives::B256, reth_trie::updates::TrieUpdates)>;
+                fn hashed_state_root_with_storage_roots(&self, hashed_state: reth_trie::HashedPostState, storage_roots: std::collections::HashMap<reth_primitives::B256, reth_primitives::B256>) -> reth_storage_errors::provider::ProviderResult<reth_primitives::B256>;
+                fn hashed_storage_root(&self, hashed_address: reth_primitives::B256, hashed_storage: reth_trie::HashedStorage) -> reth_storage_errors::provider::ProviderResult<reth_primitives::B256>;
             }
             AccountReader $(where [$($generics)*])? {
                 fn basic_account(&self, address: reth_primitives::Address) -> reth_storage_errors::provider::ProviderResult<Option<reth_primitives::Account>>;
@@ -43,7 +45,11 @@ macro_rules! delegate_provider_impls {
             }
             StateProvider $(where [$($generics)*])?{
                 fn storage(&self, account: reth_primitives::Address, storage_key: reth_primitives::StorageKey) -> reth_storage_errors::provider::ProviderResult<Option<reth_primitives::StorageValue>>;
+                fn storages(&self, account: reth_primitives::Address, storage_keys: &[reth_primitives::StorageKey]) -> reth_storage_errors::provider::ProviderResult<Vec<Option<reth_primitives::StorageValue>>>;
                 fn proof(&self, address: reth_primitives::Address, keys: &[reth_primitives::B256]) -> reth_storage_errors::provider::ProviderResult<reth_primitives::trie::AccountProof>;
+                fn multiproof(&self, hashed_state: reth_trie::HashedPostState, targets: std::collections::HashMap<reth_primitives::B256, std::collections::HashSet<reth_primitives::B256>>) -> reth_storage_errors::provider::ProviderResult<reth_primitives::trie::MultiProof>;
+                fn storage_multiproof(&self, address: reth_primitives::Address, slots: &[reth_primitives::B256], hashed_storage: reth_trie::HashedStorage) -> reth_storage_errors::provider::ProviderResult<reth_primitives::trie::StorageMultiProof>;
+                fn storage_range(&self, address: reth_primitives::Address, hashed_storage: reth_trie::HashedStorage, start: reth_primitives::B256, limit: usize) -> reth_storage_errors::provider::ProviderResult<reth_trie::StorageRange>;
                 fn bytecode_by_hash(&self, code_hash: reth_primitives::B256) -> reth_storage_errors::provider::ProviderResult<Option<reth_primitives::Bytecode>>;
             }
         );
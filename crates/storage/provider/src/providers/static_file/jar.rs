@@ -9,8 +9,9 @@ use crate::{
 use reth_db::static_file::{HeaderMask, ReceiptMask, StaticFileCursor, TransactionMask};
 use reth_db_api::models::CompactU256;
 use reth_primitives::{
-    Address, BlockHash, BlockHashOrNumber, BlockNumber, ChainInfo, Header, Receipt, SealedHeader,
-    TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, B256, U256,
+    Address, BlockHash, BlockHashOrNumber, BlockNumber, Bytes, ChainInfo, Header, Receipt,
+    SealedHeader, TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber,
+    B256, U256,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use std::{
@@ -71,6 +72,24 @@ impl<'a> StaticFileJarProvider<'a> {
         self.metrics = Some(metrics);
         self
     }
+
+    /// Returns the RLP encoding of the header with the given number, see
+    /// [`WireEncode`](reth_db::static_file::WireEncode).
+    pub fn raw_header_by_number(&self, num: BlockNumber) -> ProviderResult<Option<Bytes>> {
+        self.cursor()?.get_one_wire::<HeaderMask<Header>>(num.into())
+    }
+
+    /// Returns the EIP-2718 encoding of the transaction with the given number, see
+    /// [`WireEncode`](reth_db::static_file::WireEncode).
+    pub fn raw_transaction_by_id(&self, num: TxNumber) -> ProviderResult<Option<Bytes>> {
+        self.cursor()?.get_one_wire::<TransactionMask<TransactionSignedNoHash>>(num.into())
+    }
+
+    /// Returns the EIP-2718 encoding of the receipt with the given transaction number, see
+    /// [`WireEncode`](reth_db::static_file::WireEncode).
+    pub fn raw_receipt(&self, num: TxNumber) -> ProviderResult<Option<Bytes>> {
+        self.cursor()?.get_one_wire::<ReceiptMask<Receipt>>(num.into())
+    }
 }
 
 impl<'a> HeaderProvider for StaticFileJarProvider<'a> {
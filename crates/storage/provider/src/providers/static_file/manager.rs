@@ -25,10 +25,10 @@ use reth_primitives::{
     keccak256,
     stage::{PipelineTarget, StageId},
     static_file::{find_fixed_range, HighestStaticFiles, SegmentHeader, SegmentRangeInclusive},
-    Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, ChainInfo, Header,
-    Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, StaticFileSegment, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
-    U256,
+    Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Bytes, ChainInfo,
+    Header, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, StaticFileSegment,
+    TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal,
+    Withdrawals, B256, U256,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use std::{
@@ -978,6 +978,64 @@ impl StaticFileProvider {
         Ok(data)
     }
 
+    /// Returns the RLP encoding of the header with the given number, see
+    /// [`WireEncode`](reth_db::static_file::WireEncode).
+    pub fn raw_header_by_number(&self, num: BlockNumber) -> ProviderResult<Option<Bytes>> {
+        self.get_segment_provider_from_block(StaticFileSegment::Headers, num, None)
+            .and_then(|provider| provider.raw_header_by_number(num))
+            .or_else(|err| {
+                if let ProviderError::MissingStaticFileBlock(_, _) = err {
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            })
+    }
+
+    /// Returns the EIP-2718 encoding of the transaction with the given number, see
+    /// [`WireEncode`](reth_db::static_file::WireEncode).
+    pub fn raw_transaction_by_id(&self, num: TxNumber) -> ProviderResult<Option<Bytes>> {
+        self.get_segment_provider_from_transaction(StaticFileSegment::Transactions, num, None)
+            .and_then(|provider| provider.raw_transaction_by_id(num))
+            .or_else(|err| {
+                if let ProviderError::MissingStaticFileTx(_, _) = err {
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            })
+    }
+
+    /// Returns the EIP-2718 encodings of the transactions in the given range, see
+    /// [`WireEncode`](reth_db::static_file::WireEncode).
+    pub fn raw_transactions_by_tx_range(
+        &self,
+        range: impl RangeBounds<TxNumber>,
+    ) -> ProviderResult<Vec<Bytes>> {
+        self.fetch_range_with_predicate(
+            StaticFileSegment::Transactions,
+            to_range(range),
+            |cursor, number| {
+                cursor.get_one_wire::<TransactionMask<TransactionSignedNoHash>>(number.into())
+            },
+            |_| true,
+        )
+    }
+
+    /// Returns the EIP-2718 encodings of the receipts of the transactions in the given range, see
+    /// [`WireEncode`](reth_db::static_file::WireEncode).
+    pub fn raw_receipts_by_tx_range(
+        &self,
+        range: impl RangeBounds<TxNumber>,
+    ) -> ProviderResult<Vec<Bytes>> {
+        self.fetch_range_with_predicate(
+            StaticFileSegment::Receipts,
+            to_range(range),
+            |cursor, number| cursor.get_one_wire::<ReceiptMask<Receipt>>(number.into()),
+            |_| true,
+        )
+    }
+
     #[cfg(any(test, feature = "test-utils"))]
     /// Returns `static_files` directory
     pub fn path(&self) -> &Path {
@@ -10,20 +10,23 @@ use parking_lot::Mutex;
 use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
-    keccak256, trie::AccountProof, Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId,
-    BlockNumber, BlockWithSenders, Bytecode, Bytes, ChainInfo, ChainSpec, Header, Receipt,
-    SealedBlock, SealedBlockWithSenders, SealedHeader, StorageKey, StorageValue, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
-    U256,
+    keccak256,
+    trie::{AccountProof, MultiProof, StorageMultiProof},
+    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumber, BlockWithSenders,
+    Bytecode, Bytes, ChainInfo, ChainSpec, Header, Receipt, SealedBlock, SealedBlockWithSenders,
+    SealedHeader, StorageKey, StorageValue, TransactionMeta, TransactionSigned,
+    TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256, U256,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
-use reth_trie::updates::TrieUpdates;
+use reth_trie::{
+    updates::TrieUpdates, HashedPostState, HashedStorage, StorageRange, StorageRangeEntry,
+};
 use revm::{
     db::BundleState,
     primitives::{BlockEnv, CfgEnvWithHandlerCfg},
 };
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     ops::{RangeBounds, RangeInclusive},
     sync::Arc,
 };
@@ -35,6 +38,8 @@ pub struct MockEthProvider {
     pub blocks: Arc<Mutex<HashMap<B256, Block>>>,
     /// Local header store
     pub headers: Arc<Mutex<HashMap<B256, Header>>>,
+    /// Local receipts store, by block hash
+    pub receipts: Arc<Mutex<HashMap<B256, Vec<Receipt>>>>,
     /// Local account store
     pub accounts: Arc<Mutex<HashMap<Address, ExtendedAccount>>>,
     /// Local chain spec
@@ -46,6 +51,7 @@ impl Default for MockEthProvider {
         Self {
             blocks: Default::default(),
             headers: Default::default(),
+            receipts: Default::default(),
             accounts: Default::default(),
             chain_spec: Arc::new(reth_primitives::ChainSpecBuilder::mainnet().build()),
         }
@@ -116,6 +122,11 @@ impl MockEthProvider {
         }
     }
 
+    /// Add the receipts of a block to local receipts store
+    pub fn add_receipts(&self, hash: B256, receipts: Vec<Receipt>) {
+        self.receipts.lock().insert(hash, receipts);
+    }
+
     /// Add account to local account store
     pub fn add_account(&self, address: Address, account: ExtendedAccount) {
         self.accounts.lock().insert(address, account);
@@ -206,7 +217,10 @@ impl TransactionsProvider for MockEthProvider {
 
     fn transaction_by_id(&self, id: TxNumber) -> ProviderResult<Option<TransactionSigned>> {
         let lock = self.blocks.lock();
-        let transaction = lock.values().flat_map(|block| &block.body).nth(id as usize).cloned();
+        let mut blocks = lock.values().collect::<Vec<_>>();
+        blocks.sort_by_key(|block| block.number);
+        let transaction =
+            blocks.into_iter().flat_map(|block| &block.body).nth(id as usize).cloned();
 
         Ok(transaction)
     }
@@ -216,8 +230,10 @@ impl TransactionsProvider for MockEthProvider {
         id: TxNumber,
     ) -> ProviderResult<Option<TransactionSignedNoHash>> {
         let lock = self.blocks.lock();
-        let transaction = lock
-            .values()
+        let mut blocks = lock.values().collect::<Vec<_>>();
+        blocks.sort_by_key(|block| block.number);
+        let transaction = blocks
+            .into_iter()
             .flat_map(|block| &block.body)
             .nth(id as usize)
             .map(|tx| Into::<TransactionSignedNoHash>::into(tx.clone()));
@@ -343,12 +359,21 @@ impl ReceiptProvider for MockEthProvider {
         Ok(None)
     }
 
-    fn receipt_by_hash(&self, _hash: TxHash) -> ProviderResult<Option<Receipt>> {
-        Ok(None)
+    fn receipt_by_hash(&self, hash: TxHash) -> ProviderResult<Option<Receipt>> {
+        let Some((_, meta)) = self.transaction_by_hash_with_meta(hash)? else { return Ok(None) };
+        Ok(self
+            .receipts
+            .lock()
+            .get(&meta.block_hash)
+            .and_then(|receipts| receipts.get(meta.index as usize).cloned()))
     }
 
-    fn receipts_by_block(&self, _block: BlockHashOrNumber) -> ProviderResult<Option<Vec<Receipt>>> {
-        Ok(None)
+    fn receipts_by_block(&self, block: BlockHashOrNumber) -> ProviderResult<Option<Vec<Receipt>>> {
+        let hash = match block {
+            BlockHashOrNumber::Hash(hash) => Some(hash),
+            BlockHashOrNumber::Number(num) => self.block_hash(num)?,
+        };
+        Ok(hash.and_then(|hash| self.receipts.lock().get(&hash).cloned()))
     }
 
     fn receipts_by_tx_range(
@@ -459,20 +484,28 @@ impl BlockReader for MockEthProvider {
         Ok(None)
     }
 
-    fn ommers(&self, _id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Header>>> {
-        Ok(None)
+    fn ommers(&self, id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Header>>> {
+        Ok(self.block(id)?.map(|block| block.ommers))
     }
 
-    fn block_body_indices(&self, _num: u64) -> ProviderResult<Option<StoredBlockBodyIndices>> {
-        Ok(None)
+    fn block_body_indices(&self, num: u64) -> ProviderResult<Option<StoredBlockBodyIndices>> {
+        let lock = self.blocks.lock();
+        let Some(block) = lock.values().find(|block| block.number == num) else { return Ok(None) };
+        // the transactions are numbered in the order of the blocks
+        let first_tx_num = lock
+            .values()
+            .filter(|block| block.number < num)
+            .map(|block| block.body.len() as u64)
+            .sum();
+        Ok(Some(StoredBlockBodyIndices { first_tx_num, tx_count: block.body.len() as u64 }))
     }
 
     fn block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
+        id: BlockHashOrNumber,
         _transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<BlockWithSenders>> {
-        Ok(None)
+        Ok(self.block(id)?.and_then(|block| block.with_recovered_senders()))
     }
 
     fn block_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Vec<Block>> {
@@ -537,6 +570,21 @@ impl StateRootProvider for MockEthProvider {
     ) -> ProviderResult<(B256, TrieUpdates)> {
         Ok((B256::default(), Default::default()))
     }
+    fn hashed_state_root_with_storage_roots(
+        &self,
+        _hashed_state: HashedPostState,
+        _storage_roots: HashMap<B256, B256>,
+    ) -> ProviderResult<B256> {
+        Ok(B256::default())
+    }
+
+    fn hashed_storage_root(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        Ok(B256::default())
+    }
 }
 
 impl StateProvider for MockEthProvider {
@@ -564,6 +612,51 @@ impl StateProvider for MockEthProvider {
     fn proof(&self, address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
         Ok(AccountProof::new(address))
     }
+
+    fn multiproof(
+        &self,
+        _hashed_state: HashedPostState,
+        _targets: HashMap<B256, HashSet<B256>>,
+    ) -> ProviderResult<MultiProof> {
+        Ok(MultiProof::default())
+    }
+
+    fn storage_multiproof(
+        &self,
+        _address: Address,
+        _slots: &[B256],
+        _hashed_storage: HashedStorage,
+    ) -> ProviderResult<StorageMultiProof> {
+        Ok(StorageMultiProof::default())
+    }
+
+    fn storage_range(
+        &self,
+        address: Address,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<StorageRange> {
+        let lock = self.accounts.lock();
+        let plain_storage = lock.get(&address).map(|account| &account.storage);
+
+        let mut storage = BTreeMap::new();
+        if !hashed_storage.wiped {
+            storage.extend(
+                plain_storage.into_iter().flatten().map(|(slot, value)| (keccak256(slot), *value)),
+            );
+        }
+        storage.extend(hashed_storage.storage);
+
+        let mut slots = storage.range(start..).filter(|(_, value)| !value.is_zero());
+        let mut range = StorageRange::default();
+        for (hashed_slot, value) in slots.by_ref().take(limit) {
+            range.slots.insert(*hashed_slot, StorageRangeEntry { slot: None, value: *value });
+        }
+        range.next_key = slots.next().map(|(hashed_slot, _)| *hashed_slot);
+        range.set_preimages(plain_storage.into_iter().flat_map(|storage| storage.keys().copied()));
+        Ok(range)
+    }
 }
 
 impl EvmEnvProvider for MockEthProvider {
@@ -10,7 +10,7 @@ use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
     stage::{StageCheckpoint, StageId},
-    trie::AccountProof,
+    trie::{AccountProof, MultiProof, StorageMultiProof},
     Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumber, BlockWithSenders,
     Bytecode, ChainInfo, ChainSpec, Header, Receipt, SealedBlock, SealedBlockWithSenders,
     SealedHeader, StorageKey, StorageValue, TransactionMeta, TransactionSigned,
@@ -18,12 +18,13 @@ use reth_primitives::{
 };
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::updates::TrieUpdates;
+use reth_trie::{updates::TrieUpdates, HashedPostState, HashedStorage, StorageRange};
 use revm::{
     db::BundleState,
     primitives::{BlockEnv, CfgEnvWithHandlerCfg},
 };
 use std::{
+    collections::{HashMap, HashSet},
     ops::{RangeBounds, RangeInclusive},
     sync::Arc,
 };
@@ -304,6 +305,21 @@ impl StateRootProvider for NoopProvider {
     ) -> ProviderResult<(B256, TrieUpdates)> {
         Ok((B256::default(), TrieUpdates::default()))
     }
+    fn hashed_state_root_with_storage_roots(
+        &self,
+        _hashed_state: HashedPostState,
+        _storage_roots: HashMap<B256, B256>,
+    ) -> ProviderResult<B256> {
+        Ok(B256::default())
+    }
+
+    fn hashed_storage_root(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256> {
+        Ok(B256::default())
+    }
 }
 
 impl StateProvider for NoopProvider {
@@ -322,6 +338,33 @@ impl StateProvider for NoopProvider {
     fn proof(&self, address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
         Ok(AccountProof::new(address))
     }
+
+    fn multiproof(
+        &self,
+        _hashed_state: HashedPostState,
+        _targets: HashMap<B256, HashSet<B256>>,
+    ) -> ProviderResult<MultiProof> {
+        Ok(MultiProof::default())
+    }
+
+    fn storage_multiproof(
+        &self,
+        _address: Address,
+        _slots: &[B256],
+        _hashed_storage: HashedStorage,
+    ) -> ProviderResult<StorageMultiProof> {
+        Ok(StorageMultiProof::default())
+    }
+
+    fn storage_range(
+        &self,
+        _address: Address,
+        _hashed_storage: HashedStorage,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<StorageRange> {
+        Ok(StorageRange::default())
+    }
 }
 
 impl EvmEnvProvider for NoopProvider {
@@ -1,9 +1,9 @@
 //! Helper provider traits to encapsulate all provider traits for simplicity.
 
 use crate::{
-    AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    DatabaseProviderFactory, EvmEnvProvider, StageCheckpointReader, StateProviderFactory,
-    StaticFileProviderFactory,
+    AccountReader, AddressTransactionsReader, BlockReaderIdExt, CanonStateSubscriptions,
+    ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory, EvmEnvProvider, RawBlockReader,
+    StageCheckpointReader, StateProviderFactory, StaticFileProviderFactory,
 };
 use reth_db_api::database::Database;
 
@@ -19,6 +19,8 @@ pub trait FullProvider<DB: Database>:
     + ChangeSetReader
     + CanonStateSubscriptions
     + StageCheckpointReader
+    + AddressTransactionsReader
+    + RawBlockReader
     + Clone
     + Unpin
     + 'static
@@ -36,6 +38,8 @@ impl<T, DB: Database> FullProvider<DB> for T where
         + ChangeSetReader
         + CanonStateSubscriptions
         + StageCheckpointReader
+        + AddressTransactionsReader
+        + RawBlockReader
         + Clone
         + Unpin
         + 'static
@@ -38,6 +38,20 @@ pub trait HistoryWriter: Send + Sync {
         storage_transitions: BTreeMap<(Address, B256), Vec<u64>>,
     ) -> ProviderResult<()>;
 
+    /// Unwind and clear the address transaction indices of the blocks in the range.
+    ///
+    /// Returns number of addresses walked.
+    fn unwind_address_transaction_indices(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<usize>;
+
+    /// Insert address transaction index to database. Used inside IndexAddressTransactions stage
+    fn insert_address_transaction_index(
+        &self,
+        address_transactions: BTreeMap<Address, Vec<u64>>,
+    ) -> ProviderResult<()>;
+
     /// Read account/storage changesets and update account/storage history indices.
     fn update_history_indices(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()>;
 }
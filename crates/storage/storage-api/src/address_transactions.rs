@@ -0,0 +1,30 @@
+use reth_primitives::{Address, BlockNumber};
+use reth_storage_errors::provider::ProviderResult;
+
+/// Reader of the index of the blocks with transactions an address is part of.
+///
+/// The index is optional and only covers the blocks up to the checkpoint of the
+/// [`IndexAddressTransactions`](reth_primitives::stage::StageId::IndexAddressTransactions) stage.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait AddressTransactionsReader: Send + Sync {
+    /// Returns the highest block that is indexed, or `None` if the index was never built.
+    fn address_transactions_checkpoint(&self) -> ProviderResult<Option<BlockNumber>>;
+
+    /// Returns up to `limit` indexed blocks with transactions of the address that are below the
+    /// given block, in descending order.
+    fn address_transaction_blocks_before(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+
+    /// Returns up to `limit` indexed blocks with transactions of the address that are above the
+    /// given block, in ascending order.
+    fn address_transaction_blocks_after(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+}
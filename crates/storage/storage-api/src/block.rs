@@ -5,7 +5,8 @@ use crate::{
 use reth_db_api::models::StoredBlockBodyIndices;
 use reth_primitives::{
     Block, BlockHashOrNumber, BlockId, BlockNumber, BlockNumberOrTag, BlockWithSenders, Header,
-    Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, B256,
+    Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, TransactionSignedNoHash,
+    Withdrawals, B256,
 };
 use reth_storage_errors::provider::ProviderResult;
 use std::ops::RangeInclusive;
@@ -131,6 +132,29 @@ pub trait BlockReader:
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<Vec<BlockWithSenders>>;
+
+    /// Returns the transactions and withdrawals of the blocks in the given inclusive range, `None`
+    /// for the blocks that are not found.
+    ///
+    /// Unlike [`BlockReader::block_range`], implementers should only read the transactions and
+    /// withdrawals of the blocks, but not their headers, ommers and senders. This is intended for
+    /// serving block bodies, e.g. `engine_getPayloadBodiesByRange`.
+    ///
+    /// Note: the range is truncated to the last known block.
+    #[allow(clippy::type_complexity)]
+    fn transactions_and_withdrawals_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<Option<(Vec<TransactionSignedNoHash>, Option<Withdrawals>)>>> {
+        let end = (*range.end()).min(self.last_block_number()?);
+        (*range.start()..=end)
+            .map(|number| {
+                Ok(self.block_by_number(number)?.map(|block| {
+                    (block.body.into_iter().map(Into::into).collect(), block.withdrawals)
+                }))
+            })
+            .collect()
+    }
 }
 
 /// Trait extension for `BlockReader`, for types that implement `BlockId` conversion.
@@ -13,6 +13,9 @@ pub use reth_storage_errors as errors;
 mod account;
 pub use account::*;
 
+mod address_transactions;
+pub use address_transactions::*;
+
 mod block;
 pub use block::*;
 
@@ -28,9 +31,18 @@ pub use header::*;
 mod prune_checkpoint;
 pub use prune_checkpoint::*;
 
+mod raw;
+pub use raw::*;
+
+mod raw_block;
+pub use raw_block::*;
+
 mod receipts;
 pub use receipts::*;
 
+mod replay;
+pub use replay::*;
+
 mod requests;
 pub use requests::*;
 
@@ -51,3 +63,6 @@ pub use trie::*;
 
 mod withdrawals;
 pub use withdrawals::*;
+
+mod witness;
+pub use witness::*;
@@ -0,0 +1,50 @@
+use reth_primitives::{Bytes, StaticFileSegment};
+use reth_storage_errors::provider::ProviderResult;
+use serde::{Deserialize, Serialize};
+
+/// A value as it is stored in a database table.
+///
+/// This is also the response of the `reth_dbGet` RPC method.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawTableValue {
+    /// The key decoded with the codec of the table, `None` if it doesn't decode.
+    pub key: Option<serde_json::Value>,
+    /// The encoded value.
+    #[serde(rename = "raw")]
+    pub value: Bytes,
+    /// The value decoded as the value type of the table, `None` if it doesn't decode.
+    pub decoded: Option<serde_json::Value>,
+}
+
+/// A row as it is stored in a static file.
+///
+/// This is also the response of the `reth_staticFileGet` RPC method.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawStaticFileRow {
+    /// The encoded columns of the row.
+    pub columns: Vec<Bytes>,
+    /// The decoded columns, `None` if they don't decode.
+    pub decoded: Option<serde_json::Value>,
+}
+
+/// A type that can read the raw content of the database and the static files, e.g. to debug a
+/// running node.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait RawDatabaseReader: Send + Sync {
+    /// Returns the value stored under the encoded key in the table with the given name.
+    ///
+    /// For tables with duplicate keys this is the first value of the key. Returns
+    /// [`ProviderError::UnknownTable`](reth_storage_errors::provider::ProviderError::UnknownTable)
+    /// if there's no table with the name.
+    fn raw_table_value(&self, table: &str, key: &[u8]) -> ProviderResult<Option<RawTableValue>>;
+
+    /// Returns the row of the static file segment with the given number, which is a block number
+    /// for headers and a transaction number for transactions and receipts.
+    fn raw_static_file_row(
+        &self,
+        segment: StaticFileSegment,
+        number: u64,
+    ) -> ProviderResult<Option<RawStaticFileRow>>;
+}
@@ -0,0 +1,25 @@
+use reth_primitives::{BlockNumber, Bytes, TxHash};
+use reth_storage_errors::provider::ProviderResult;
+
+/// A type that reads canonical blocks, transactions and receipts in their network encoding.
+///
+/// Unlike encoding the values returned by [`BlockReader`](crate::BlockReader), this reads the
+/// encoded rows of the static files without building the typed block, e.g. without recovering
+/// the senders of the transactions.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait RawBlockReader: Send + Sync {
+    /// Returns the RLP encoding of the header of the block.
+    fn raw_header(&self, number: BlockNumber) -> ProviderResult<Option<Bytes>>;
+
+    /// Returns the RLP encoding of the block.
+    fn raw_block(&self, number: BlockNumber) -> ProviderResult<Option<Bytes>>;
+
+    /// Returns the EIP-2718 encodings of the transactions of the block.
+    fn raw_transactions(&self, number: BlockNumber) -> ProviderResult<Option<Vec<Bytes>>>;
+
+    /// Returns the EIP-2718 encodings of the receipts of the block.
+    fn raw_receipts(&self, number: BlockNumber) -> ProviderResult<Option<Vec<Bytes>>>;
+
+    /// Returns the EIP-2718 encoding of the canonical transaction with the given hash.
+    fn raw_transaction_by_hash(&self, hash: TxHash) -> ProviderResult<Option<Bytes>>;
+}
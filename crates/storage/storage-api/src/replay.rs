@@ -0,0 +1,16 @@
+use reth_execution_errors::TransactionReplayError;
+use reth_primitives::B256;
+use revm::{db::BundleState, primitives::ExecutionResult};
+
+/// A type that can replay historical transactions on top of the exact state they were executed on.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait TransactionReplayProvider: Send + Sync {
+    /// Re-executes the preceding transactions of the block with the given hash on top of its
+    /// parent state, then executes the transaction with the given hash and returns its result and
+    /// the state changes it made.
+    fn replay(
+        &self,
+        block_hash: B256,
+        tx_hash: B256,
+    ) -> Result<(ExecutionResult, BundleState), TransactionReplayError>;
+}
@@ -2,10 +2,13 @@ use super::{AccountReader, BlockHashReader, BlockIdReader, StateRootProvider};
 use auto_impl::auto_impl;
 use reth_execution_types::BundleStateWithReceipts;
 use reth_primitives::{
-    trie::AccountProof, Address, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag,
-    Bytecode, StorageKey, StorageValue, B256, KECCAK_EMPTY, U256,
+    trie::{AccountProof, MultiProof, StorageMultiProof},
+    Address, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag, Bytecode, StorageKey,
+    StorageValue, B256, KECCAK_EMPTY, U256,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use reth_trie::{HashedPostState, HashedStorage, StorageRange};
+use std::collections::{HashMap, HashSet};
 
 /// Type alias of boxed [`StateProvider`].
 pub type StateProviderBox = Box<dyn StateProvider>;
@@ -20,12 +23,55 @@ pub trait StateProvider: BlockHashReader + AccountReader + StateRootProvider + S
         storage_key: StorageKey,
     ) -> ProviderResult<Option<StorageValue>>;
 
+    /// Get the storage of the given account for multiple storage slots, in the order of the slots.
+    /// A more efficient version than calling [`StateProvider::storage`] repeatedly.
+    fn storages(
+        &self,
+        account: Address,
+        storage_keys: &[StorageKey],
+    ) -> ProviderResult<Vec<Option<StorageValue>>> {
+        storage_keys.iter().map(|storage_key| self.storage(account, *storage_key)).collect()
+    }
+
     /// Get account code by its hash
     fn bytecode_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>>;
 
     /// Get account and storage proofs.
     fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof>;
 
+    /// Get the state multiproof for the target accounts and their storage slots with the
+    /// [`HashedPostState`] changes applied on top of the current state. The targets are hashed
+    /// addresses mapped to the sets of hashed storage slots.
+    fn multiproof(
+        &self,
+        hashed_state: HashedPostState,
+        targets: HashMap<B256, HashSet<B256>>,
+    ) -> ProviderResult<MultiProof>;
+
+    /// Get the storage multiproof of the account for the given storage slots along with the
+    /// storage root, with the [`HashedStorage`] changes applied on top of the current state.
+    /// Unlike [`StateProvider::proof`], the account trie is not traversed.
+    fn storage_multiproof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<StorageMultiProof>;
+
+    /// Get up to `limit` storage slots of the account with the [`HashedStorage`] changes applied on
+    /// top of the current state, ordered by hashed slot and starting at the first hashed slot
+    /// greater or equal to `start`.
+    ///
+    /// The preimages of the hashed slots are set for the slots that are in the plain storage of the
+    /// account.
+    fn storage_range(
+        &self,
+        address: Address,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<StorageRange>;
+
     /// Get account code by its address.
     ///
     /// Returns `None` if the account doesn't exist or account is not a contract
@@ -1,7 +1,8 @@
 use reth_primitives::B256;
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::updates::TrieUpdates;
+use reth_trie::{updates::TrieUpdates, HashedPostState, HashedStorage};
 use revm::db::BundleState;
+use std::collections::HashMap;
 
 /// A type that can compute the state root of a given post state.
 #[auto_impl::auto_impl(&, Box, Arc)]
@@ -21,4 +22,23 @@ pub trait StateRootProvider: Send + Sync {
         &self,
         bundle_state: &BundleState,
     ) -> ProviderResult<(B256, TrieUpdates)>;
+
+    /// Returns the state root of the [`HashedPostState`] on top of the current state, using the
+    /// pre-computed storage roots of the changed accounts keyed by hashed address.
+    ///
+    /// The storage roots must be computed against the same storage changes, e.g. with
+    /// [`StateRootProvider::hashed_storage_root`].
+    fn hashed_state_root_with_storage_roots(
+        &self,
+        hashed_state: HashedPostState,
+        storage_roots: HashMap<B256, B256>,
+    ) -> ProviderResult<B256>;
+
+    /// Returns the storage root of the account with the [`HashedStorage`] changes applied on top
+    /// of the current state.
+    fn hashed_storage_root(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+    ) -> ProviderResult<B256>;
 }
@@ -0,0 +1,10 @@
+use reth_primitives::{trie::ExecutionWitness, B256};
+use reth_storage_errors::provider::ProviderResult;
+
+/// A type that can generate execution witnesses of blocks.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait ExecutionWitnessProvider: Send + Sync {
+    /// Re-executes the block with the given hash on top of its parent state and returns the
+    /// witness of all the state that was accessed during the execution.
+    fn witness(&self, block_hash: B256) -> ProviderResult<ExecutionWitness>;
+}
@@ -0,0 +1,82 @@
+#![allow(missing_docs)]
+use criterion::{criterion_group, criterion_main, Criterion};
+use reth_primitives::{
+    Address, Signature, Transaction, TransactionSigned, TxEip1559, TxKind, MAINNET, U256,
+};
+use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+use reth_tasks::TokioTaskExecutor;
+use reth_transaction_pool::{
+    blobstore::InMemoryBlobStore, EthPooledTransaction, TransactionOrigin,
+    TransactionValidationTaskExecutor, TransactionValidator,
+};
+
+/// Number of transactions that are ingested at once.
+const NUM_TRANSACTIONS: usize = 500;
+
+/// Number of transactions per sender.
+const TRANSACTIONS_PER_SENDER: usize = 10;
+
+/// Validates a burst of transactions one by one and as a single batch.
+fn validate_burst(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.enter();
+
+    let provider = MockEthProvider::default();
+    let mut transactions = Vec::with_capacity(NUM_TRANSACTIONS);
+    for _ in 0..NUM_TRANSACTIONS / TRANSACTIONS_PER_SENDER {
+        let sender = Address::random();
+        provider.add_account(sender, ExtendedAccount::new(0, U256::MAX));
+        for nonce in 0..TRANSACTIONS_PER_SENDER as u64 {
+            transactions.push(transaction(sender, nonce));
+        }
+    }
+
+    let validator = TransactionValidationTaskExecutor::eth_builder(MAINNET.clone())
+        .with_additional_tasks(3)
+        .build_with_tasks(provider, TokioTaskExecutor::default(), InMemoryBlobStore::default());
+
+    let mut group = c.benchmark_group("Transaction Validation");
+    group.sample_size(20);
+
+    group.bench_function(format!("{NUM_TRANSACTIONS} txs | one by one"), |b| {
+        b.iter(|| {
+            runtime.block_on(futures_util::future::join_all(
+                transactions.iter().map(|tx| {
+                    validator.validate_transaction(TransactionOrigin::External, tx.clone())
+                }),
+            ))
+        })
+    });
+
+    group.bench_function(format!("{NUM_TRANSACTIONS} txs | batch"), |b| {
+        b.iter(|| {
+            runtime.block_on(
+                validator
+                    .validate_transactions_batch(TransactionOrigin::External, transactions.clone()),
+            )
+        })
+    });
+
+    group.finish();
+}
+
+/// Creates a transfer of the given sender.
+fn transaction(sender: Address, nonce: u64) -> EthPooledTransaction {
+    let transaction = Transaction::Eip1559(TxEip1559 {
+        chain_id: MAINNET.chain.id(),
+        nonce,
+        gas_limit: 21_000,
+        max_fee_per_gas: 10_000_000_000,
+        max_priority_fee_per_gas: 1_000_000_000,
+        to: TxKind::Call(Address::random()),
+        value: U256::from(1),
+        ..Default::default()
+    });
+    let signed =
+        TransactionSigned::from_transaction_and_signature(transaction, Signature::default());
+    let encoded_length = signed.length_without_header();
+    EthPooledTransaction::new(signed.with_signer(sender), encoded_length)
+}
+
+criterion_group!(validation, validate_burst);
+criterion_main!(validation);
@@ -1,11 +1,23 @@
 //! A simple diskstore for blobs
 
-use crate::blobstore::{BlobStore, BlobStoreCleanupStat, BlobStoreError, BlobStoreSize};
+use crate::{
+    blobstore::{
+        BlobStore, BlobStoreCleanupStat, BlobStoreError, BlobStoreSize, VersionedHashIndex,
+    },
+    metrics::DiskFileBlobStoreMetrics,
+};
 use alloy_rlp::{Decodable, Encodable};
 use parking_lot::{Mutex, RwLock};
-use reth_primitives::{BlobTransactionSidecar, TxHash, B256};
+use reth_primitives::{BlobAndProofV1, BlobTransactionSidecar, TxHash, B256};
 use schnellru::{ByLength, LruMap};
-use std::{collections::HashSet, fmt, fs, io, path::PathBuf, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt, fs, io,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    time::SystemTime,
+};
 use tracing::{debug, trace};
 
 /// How many [`BlobTransactionSidecar`] to cache in memory.
@@ -16,6 +28,9 @@ pub const DEFAULT_MAX_CACHED_BLOBS: u32 = 100;
 /// The type uses deferred deletion, meaning that blobs are not immediately deleted from disk, but
 /// it's expected that the maintenance task will call [`BlobStore::cleanup`] to remove the deleted
 /// blobs from disk.
+///
+/// If the store exceeds its configured max size, the deleted blobs are evicted from disk right
+/// away, oldest first.
 #[derive(Clone, Debug)]
 pub struct DiskFileBlobStore {
     inner: Arc<DiskFileBlobStoreInner>,
@@ -28,12 +43,21 @@ impl DiskFileBlobStore {
         opts: DiskFileBlobStoreConfig,
     ) -> Result<Self, DiskFileBlobStoreError> {
         let blob_dir = blob_dir.into();
-        let DiskFileBlobStoreConfig { max_cached_entries, .. } = opts;
-        let inner = DiskFileBlobStoreInner::new(blob_dir, max_cached_entries);
+        let DiskFileBlobStoreConfig { max_cached_entries, max_size, open } = opts;
+        let inner = DiskFileBlobStoreInner::new(blob_dir, max_cached_entries, max_size);
 
         // initialize the blob store
-        inner.delete_all()?;
-        inner.create_blob_dir()?;
+        match open {
+            OpenDiskFileBlobStore::Clear => {
+                inner.delete_all()?;
+                inner.create_blob_dir()?;
+            }
+            OpenDiskFileBlobStore::ReIndex => {
+                inner.create_blob_dir()?;
+                inner.reindex()?;
+                inner.evict_unreferenced();
+            }
+        }
 
         Ok(Self { inner: Arc::new(inner) })
     }
@@ -76,27 +100,8 @@ impl BlobStore for DiskFileBlobStore {
             let mut txs_to_delete = self.inner.txs_to_delete.write();
             std::mem::take(&mut *txs_to_delete)
         };
-        let mut stat = BlobStoreCleanupStat::default();
-        let mut subsize = 0;
         debug!(target:"txpool::blob", num_blobs=%txs_to_delete.len(), "Removing blobs from disk");
-        for tx in txs_to_delete {
-            let path = self.inner.blob_disk_file(tx);
-            let filesize = fs::metadata(&path).map_or(0, |meta| meta.len());
-            match fs::remove_file(&path) {
-                Ok(_) => {
-                    stat.delete_succeed += 1;
-                    subsize += filesize;
-                }
-                Err(e) => {
-                    stat.delete_failed += 1;
-                    let err = DiskFileBlobStoreError::DeleteFile(tx, path, e);
-                    debug!(target:"txpool::blob", %err);
-                }
-            };
-        }
-        self.inner.size_tracker.sub_size(subsize as usize);
-        self.inner.size_tracker.sub_len(stat.delete_succeed);
-        stat
+        self.inner.delete_blobs(txs_to_delete)
     }
 
     fn get(&self, tx: B256) -> Result<Option<BlobTransactionSidecar>, BlobStoreError> {
@@ -124,6 +129,28 @@ impl BlobStore for DiskFileBlobStore {
         self.inner.get_exact(txs)
     }
 
+    fn get_by_versioned_hashes(
+        &self,
+        versioned_hashes: &[B256],
+    ) -> Result<Vec<Option<BlobAndProofV1>>, BlobStoreError> {
+        let mut res = Vec::with_capacity(versioned_hashes.len());
+        for versioned_hash in versioned_hashes {
+            let tx = self.inner.versioned_hashes.read().get(versioned_hash);
+            let blob = match tx {
+                Some(tx) => self
+                    .inner
+                    .get_one(tx)?
+                    .and_then(|sidecar| BlobAndProofV1::from_sidecar(&sidecar, versioned_hash)),
+                None => {
+                    self.inner.metrics.blobstore_failed_lookups.increment(1);
+                    None
+                }
+            };
+            res.push(blob);
+        }
+        Ok(res)
+    }
+
     fn data_size_hint(&self) -> Option<usize> {
         Some(self.inner.size_tracker.data_size())
     }
@@ -137,19 +164,31 @@ struct DiskFileBlobStoreInner {
     blob_dir: PathBuf,
     blob_cache: Mutex<LruMap<TxHash, BlobTransactionSidecar, ByLength>>,
     size_tracker: BlobStoreSize,
+    /// The maximum size of the blobs stored on disk in bytes.
+    max_size: Option<usize>,
     file_lock: RwLock<()>,
     txs_to_delete: RwLock<HashSet<B256>>,
+    /// Maps the versioned hashes of the stored blobs to their transaction.
+    versioned_hashes: RwLock<VersionedHashIndex>,
+    /// The blobs stored on disk in insertion order.
+    stored: Mutex<StoredBlobs>,
+    metrics: DiskFileBlobStoreMetrics,
 }
 
 impl DiskFileBlobStoreInner {
-    /// Creates a new empty disk file blob store with the given maximum length of the blob cache.
-    fn new(blob_dir: PathBuf, max_length: u32) -> Self {
+    /// Creates a new empty disk file blob store with the given maximum length of the blob cache
+    /// and the maximum size of the blobs on disk.
+    fn new(blob_dir: PathBuf, max_length: u32, max_size: Option<usize>) -> Self {
         Self {
             blob_dir,
             blob_cache: Mutex::new(LruMap::new(ByLength::new(max_length))),
             size_tracker: Default::default(),
+            max_size,
             file_lock: Default::default(),
             txs_to_delete: Default::default(),
+            versioned_hashes: Default::default(),
+            stored: Default::default(),
+            metrics: Default::default(),
         }
     }
 
@@ -172,15 +211,67 @@ impl DiskFileBlobStoreInner {
         Ok(())
     }
 
+    /// Re-indexes the blob files of an existing blob store and deletes all files that aren't
+    /// valid blobs, e.g. because the node crashed while writing them.
+    ///
+    /// The re-indexed blobs are unreferenced until the pool inserts them again, otherwise they're
+    /// deleted by the next [`BlobStore::cleanup`].
+    fn reindex(&self) -> Result<(), DiskFileBlobStoreError> {
+        let open_err = |err| DiskFileBlobStoreError::Open(self.blob_dir.clone(), err);
+        let mut blobs = Vec::new();
+        let mut orphaned = Vec::new();
+        for entry in fs::read_dir(&self.blob_dir).map_err(open_err)? {
+            let entry = entry.map_err(open_err)?;
+            match read_blob_file(&entry) {
+                Some(blob) => blobs.push(blob),
+                None => orphaned.push(entry.path()),
+            }
+        }
+
+        for path in orphaned {
+            debug!(target:"txpool::blob", ?path, "Removing orphaned blob file");
+            if let Err(err) = fs::remove_file(&path) {
+                debug!(target:"txpool::blob", %err, ?path, "Failed to remove orphaned blob file");
+            }
+        }
+
+        // restore the insertion order
+        blobs.sort_by_key(|(modified, ..)| *modified);
+        let num_blobs = blobs.len();
+        {
+            let mut versioned_hashes = self.versioned_hashes.write();
+            let mut txs_to_delete = self.txs_to_delete.write();
+            let mut stored = self.stored.lock();
+            for (_, tx, sidecar, size) in blobs {
+                versioned_hashes.insert(tx, &sidecar);
+                txs_to_delete.insert(tx);
+                stored.insert(tx, size);
+                self.size_tracker.add_size(size);
+            }
+        }
+        self.size_tracker.inc_len(num_blobs);
+        self.update_stored_bytes();
+
+        debug!(target:"txpool::blob", blob_dir = ?self.blob_dir, num_blobs, "Re-indexed blob store");
+        Ok(())
+    }
+
     /// Ensures blob is in the blob cache and written to the disk.
     fn insert_one(&self, tx: B256, data: BlobTransactionSidecar) -> Result<(), BlobStoreError> {
         let mut buf = Vec::with_capacity(data.fields_len());
         data.encode(&mut buf);
+        // the blob is referenced again if it was deleted before
+        self.txs_to_delete.write().remove(&tx);
+        self.versioned_hashes.write().insert(tx, &data);
         self.blob_cache.lock().insert(tx, data);
         let size = self.write_one_encoded(tx, &buf)?;
-
-        self.size_tracker.add_size(size);
-        self.size_tracker.inc_len(1);
+        if size > 0 {
+            self.stored.lock().insert(tx, size);
+            self.size_tracker.add_size(size);
+            self.size_tracker.inc_len(1);
+        }
+        self.update_stored_bytes();
+        self.evict_unreferenced();
         Ok(())
     }
 
@@ -191,13 +282,21 @@ impl DiskFileBlobStoreInner {
             .map(|(tx, data)| {
                 let mut buf = Vec::with_capacity(data.fields_len());
                 data.encode(&mut buf);
-                (self.blob_disk_file(*tx), buf)
+                (*tx, self.blob_disk_file(*tx), buf)
             })
             .collect::<Vec<_>>();
 
         {
+            let mut txs_to_delete = self.txs_to_delete.write();
+            for (tx, _) in &txs {
+                txs_to_delete.remove(tx);
+            }
+        }
+        {
+            let mut versioned_hashes = self.versioned_hashes.write();
             let mut cache = self.blob_cache.lock();
             for (tx, data) in txs {
+                versioned_hashes.insert(tx, &data);
                 cache.insert(tx, data);
             }
         }
@@ -205,12 +304,14 @@ impl DiskFileBlobStoreInner {
         let mut num = 0;
         {
             let _lock = self.file_lock.write();
-            for (path, data) in raw {
+            let mut stored = self.stored.lock();
+            for (tx, path, data) in raw {
                 if path.exists() {
                     debug!(target:"txpool::blob", ?path, "Blob already exists");
                 } else if let Err(err) = fs::write(&path, &data) {
                     debug!(target:"txpool::blob", %err, ?path, "Failed to write blob file");
                 } else {
+                    stored.insert(tx, data.len());
                     add += data.len();
                     num += 1;
                 }
@@ -218,10 +319,89 @@ impl DiskFileBlobStoreInner {
         }
         self.size_tracker.add_size(add);
         self.size_tracker.inc_len(num);
+        self.update_stored_bytes();
+        self.evict_unreferenced();
 
         Ok(())
     }
 
+    /// Evicts the blobs of transactions that are no longer in the pool, oldest first, until the
+    /// store doesn't exceed its max size anymore.
+    ///
+    /// Blobs of transactions that are still in the pool are never evicted.
+    fn evict_unreferenced(&self) {
+        let Some(max_size) = self.max_size else { return };
+        let mut size = self.size_tracker.data_size();
+        if size <= max_size {
+            return
+        }
+
+        let evicted = {
+            let mut txs_to_delete = self.txs_to_delete.write();
+            let stored = self.stored.lock();
+            let mut evicted = HashSet::new();
+            for (tx, blob_size) in stored.iter() {
+                if size <= max_size {
+                    break
+                }
+                if txs_to_delete.remove(&tx) {
+                    size = size.saturating_sub(blob_size);
+                    evicted.insert(tx);
+                }
+            }
+            evicted
+        };
+        if evicted.is_empty() {
+            return
+        }
+
+        debug!(target:"txpool::blob", num_blobs=%evicted.len(), max_size, "Evicting blobs from disk");
+        let stat = self.delete_blobs(evicted);
+        self.metrics.blobstore_evictions.increment(stat.delete_succeed as u64);
+    }
+
+    /// Removes the blobs of the given transactions from the index, the blob cache and the disk.
+    fn delete_blobs(&self, txs: HashSet<B256>) -> BlobStoreCleanupStat {
+        self.versioned_hashes.write().remove_txs(&txs);
+        {
+            let mut cache = self.blob_cache.lock();
+            for tx in &txs {
+                cache.remove(tx);
+            }
+        }
+
+        let mut stat = BlobStoreCleanupStat::default();
+        let mut subsize = 0;
+        {
+            let mut stored = self.stored.lock();
+            for tx in txs {
+                stored.remove(&tx);
+                let path = self.blob_disk_file(tx);
+                let filesize = fs::metadata(&path).map_or(0, |meta| meta.len());
+                match fs::remove_file(&path) {
+                    Ok(_) => {
+                        stat.delete_succeed += 1;
+                        subsize += filesize;
+                    }
+                    Err(e) => {
+                        stat.delete_failed += 1;
+                        let err = DiskFileBlobStoreError::DeleteFile(tx, path, e);
+                        debug!(target:"txpool::blob", %err);
+                    }
+                };
+            }
+        }
+        self.size_tracker.sub_size(subsize as usize);
+        self.size_tracker.sub_len(stat.delete_succeed);
+        self.update_stored_bytes();
+        stat
+    }
+
+    /// Updates the metric of the bytes stored on disk.
+    fn update_stored_bytes(&self) {
+        self.metrics.blobstore_disk_stored_bytes.set(self.size_tracker.data_size() as f64);
+    }
+
     /// Returns true if the blob for the given transaction hash is in the blob cache or on disk.
     fn contains(&self, tx: B256) -> Result<bool, BlobStoreError> {
         if self.blob_cache.lock().get(&tx).is_some() {
@@ -237,8 +417,11 @@ impl DiskFileBlobStoreInner {
             return Ok(Some(blob.clone()))
         }
         let blob = self.read_one(tx)?;
-        if let Some(blob) = &blob {
-            self.blob_cache.lock().insert(tx, blob.clone());
+        match &blob {
+            Some(blob) => {
+                self.blob_cache.lock().insert(tx, blob.clone());
+            }
+            None => self.metrics.blobstore_failed_lookups.increment(1),
         }
         Ok(blob)
     }
@@ -344,7 +527,9 @@ impl DiskFileBlobStoreInner {
         if cache_miss.is_empty() {
             return Ok(res)
         }
+        let num_cache_miss = cache_miss.len();
         let from_disk = self.read_many_decoded(cache_miss);
+        self.metrics.blobstore_failed_lookups.increment((num_cache_miss - from_disk.len()) as u64);
         if from_disk.is_empty() {
             return Ok(res)
         }
@@ -376,12 +561,63 @@ impl fmt::Debug for DiskFileBlobStoreInner {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("DiskFileBlobStoreInner")
             .field("blob_dir", &self.blob_dir)
+            .field("max_size", &self.max_size)
             .field("cached_blobs", &self.blob_cache.try_lock().map(|lock| lock.len()))
             .field("txs_to_delete", &self.txs_to_delete.try_read())
             .finish()
     }
 }
 
+/// Reads a file of the blob store directory.
+///
+/// Returns the modification time, the transaction hash, the decoded blob and the size of the file,
+/// or `None` if the file isn't a valid blob.
+fn read_blob_file(
+    entry: &fs::DirEntry,
+) -> Option<(SystemTime, TxHash, BlobTransactionSidecar, usize)> {
+    let tx = entry.file_name().to_str().and_then(|name| TxHash::from_str(name).ok())?;
+    let modified = entry.metadata().and_then(|meta| meta.modified()).ok()?;
+    let data = fs::read(entry.path()).ok()?;
+    let sidecar = BlobTransactionSidecar::decode(&mut data.as_slice()).ok()?;
+    Some((modified, tx, sidecar, data.len()))
+}
+
+/// Keeps track of the blobs that are stored on disk, in the order they were inserted.
+#[derive(Debug, Default)]
+struct StoredBlobs {
+    /// The insertion sequence number and the size of the stored blobs.
+    blobs: HashMap<TxHash, (u64, usize)>,
+    /// The stored blobs by insertion sequence number.
+    by_insertion: BTreeMap<u64, TxHash>,
+    /// The sequence number of the next inserted blob.
+    next_seq: u64,
+}
+
+impl StoredBlobs {
+    /// Records a stored blob, if it isn't recorded yet.
+    fn insert(&mut self, tx: TxHash, size: usize) {
+        if self.blobs.contains_key(&tx) {
+            return
+        }
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.blobs.insert(tx, (seq, size));
+        self.by_insertion.insert(seq, tx);
+    }
+
+    /// Removes a stored blob and returns its size.
+    fn remove(&mut self, tx: &TxHash) -> Option<usize> {
+        let (seq, size) = self.blobs.remove(tx)?;
+        self.by_insertion.remove(&seq);
+        Some(size)
+    }
+
+    /// Returns the stored blobs and their size, oldest first.
+    fn iter(&self) -> impl Iterator<Item = (TxHash, usize)> + '_ {
+        self.by_insertion.values().map(|tx| (*tx, self.blobs[tx].1))
+    }
+}
+
 /// Errors that can occur when interacting with a disk file blob store.
 #[derive(Debug, thiserror::Error)]
 pub enum DiskFileBlobStoreError {
@@ -414,13 +650,22 @@ impl From<DiskFileBlobStoreError> for BlobStoreError {
 pub struct DiskFileBlobStoreConfig {
     /// The maximum number of blobs to keep in the in memory blob cache.
     pub max_cached_entries: u32,
+    /// The maximum size of the blobs stored on disk in bytes, unlimited if `None`.
+    ///
+    /// If exceeded, the blobs of transactions that are no longer in the pool are evicted in the
+    /// order they were inserted.
+    pub max_size: Option<usize>,
     /// How to open the blob store.
     pub open: OpenDiskFileBlobStore,
 }
 
 impl Default for DiskFileBlobStoreConfig {
     fn default() -> Self {
-        Self { max_cached_entries: DEFAULT_MAX_CACHED_BLOBS, open: Default::default() }
+        Self {
+            max_cached_entries: DEFAULT_MAX_CACHED_BLOBS,
+            max_size: None,
+            open: Default::default(),
+        }
     }
 }
 
@@ -430,6 +675,18 @@ impl DiskFileBlobStoreConfig {
         self.max_cached_entries = max_cached_entries;
         self
     }
+
+    /// Set the maximum size of the blobs stored on disk in bytes.
+    pub const fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Set how to open the blob store.
+    pub const fn with_open(mut self, open: OpenDiskFileBlobStore) -> Self {
+        self.open = open;
+        self
+    }
 }
 
 /// How to open a disk file blob store.
@@ -438,13 +695,17 @@ pub enum OpenDiskFileBlobStore {
     /// Clear everything in the blob store.
     #[default]
     Clear,
-    /// Keep the existing blob store and index
+    /// Keep the existing blob store and index it again.
+    ///
+    /// Files that aren't valid blobs are deleted, and the existing blobs are deleted by the next
+    /// [`BlobStore::cleanup`] unless the pool inserts them again.
     ReIndex,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reth_primitives::alloy_primitives::FixedBytes;
     use std::sync::atomic::Ordering;
 
     fn tmp_store() -> (DiskFileBlobStore, tempfile::TempDir) {
@@ -465,6 +726,53 @@ mod tests {
             .collect()
     }
 
+    fn sidecar_with_blobs(num: u8) -> BlobTransactionSidecar {
+        let mut rng = rand::thread_rng();
+        let mut sidecar = BlobTransactionSidecar::default();
+        for i in 0..num {
+            sidecar.blobs.push(FixedBytes::repeat_byte(i));
+            sidecar.commitments.push(FixedBytes::random_with(&mut rng));
+            sidecar.proofs.push(FixedBytes::random_with(&mut rng));
+        }
+        sidecar
+    }
+
+    #[test]
+    fn disk_get_by_versioned_hashes() {
+        let (store, _dir) = tmp_store();
+
+        let tx = TxHash::random();
+        let sidecar = sidecar_with_blobs(2);
+        let versioned_hashes = sidecar.versioned_hashes().collect::<Vec<_>>();
+        store.insert(tx, sidecar.clone()).unwrap();
+
+        // unknown versioned hashes are null, the others are returned in the requested order
+        let request = vec![versioned_hashes[1], B256::random(), versioned_hashes[0]];
+        let blobs = store.get_by_versioned_hashes(&request).unwrap();
+        assert_eq!(blobs.len(), 3);
+        let blob = blobs[0].as_ref().unwrap();
+        assert_eq!((*blob.blob, blob.proof), (sidecar.blobs[1], sidecar.proofs[1]));
+        assert!(blobs[1].is_none());
+        let blob = blobs[2].as_ref().unwrap();
+        assert_eq!((*blob.blob, blob.proof), (sidecar.blobs[0], sidecar.proofs[0]));
+
+        // served from disk
+        store.clear_cache();
+        assert!(store
+            .get_by_versioned_hashes(&versioned_hashes)
+            .unwrap()
+            .iter()
+            .all(Option::is_some));
+
+        store.delete(tx).unwrap();
+        store.cleanup();
+        assert!(store
+            .get_by_versioned_hashes(&versioned_hashes)
+            .unwrap()
+            .iter()
+            .all(Option::is_none));
+    }
+
     #[test]
     fn disk_insert_all_get_all() {
         let (store, _dir) = tmp_store();
@@ -499,4 +807,85 @@ mod tests {
         assert_eq!(store.data_size_hint(), Some(0));
         assert_eq!(store.inner.size_tracker.num_blobs.load(Ordering::Relaxed), 0);
     }
+
+    #[test]
+    fn disk_evicts_unreferenced_blobs_past_max_size() {
+        let blobs = rng_blobs(6);
+        let hashes = blobs.iter().map(|(tx, _)| *tx).collect::<Vec<_>>();
+        let mut buf = Vec::new();
+        blobs[0].1.encode(&mut buf);
+        let blob_size = buf.len();
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = DiskFileBlobStoreConfig::default().with_max_size(5 * blob_size);
+        let store = DiskFileBlobStore::open(dir.path(), config).unwrap();
+
+        // blobs of transactions that are still in the pool are never evicted
+        store.insert_all(blobs[..5].to_vec()).unwrap();
+        assert_eq!(store.data_size_hint(), Some(5 * blob_size));
+
+        store.delete_all(vec![hashes[3], hashes[0], hashes[2]]).unwrap();
+
+        // the oldest deleted blob is evicted first
+        store.insert(blobs[5].0, blobs[5].1.clone()).unwrap();
+        assert_eq!(store.data_size_hint(), Some(5 * blob_size));
+        assert!(store.get(hashes[0]).unwrap().is_none());
+        assert!(!store.contains(hashes[0]).unwrap());
+        assert!(store.contains(hashes[2]).unwrap());
+        assert!(store.contains(hashes[3]).unwrap());
+
+        // reinserting a deleted blob references it again
+        store.insert(blobs[0].0, blobs[0].1.clone()).unwrap();
+        assert_eq!(store.data_size_hint(), Some(5 * blob_size));
+        assert!(store.contains(hashes[0]).unwrap());
+        assert!(!store.contains(hashes[2]).unwrap());
+        assert!(store.contains(hashes[3]).unwrap());
+
+        // an evicted blob is a miss
+        let all = store.get_all(hashes.clone()).unwrap();
+        assert_eq!(all.len(), 5);
+        assert!(matches!(store.get_exact(vec![hashes[2]]), Err(BlobStoreError::MissingSidecar(_))));
+    }
+
+    #[test]
+    fn disk_reindex() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = DiskFileBlobStoreConfig::default().with_open(OpenDiskFileBlobStore::ReIndex);
+
+        let tx = TxHash::random();
+        let sidecar = sidecar_with_blobs(1);
+        let versioned_hashes = sidecar.versioned_hashes().collect::<Vec<_>>();
+        let blobs = rng_blobs(2);
+        {
+            let store = DiskFileBlobStore::open(dir.path(), config.clone()).unwrap();
+            store.insert(tx, sidecar.clone()).unwrap();
+            store.insert_all(blobs.clone()).unwrap();
+        }
+
+        // files that were left behind by a crash
+        let partial = TxHash::random();
+        let mut buf = Vec::new();
+        sidecar.encode(&mut buf);
+        fs::write(dir.path().join(format!("{partial:x}")), &buf[..buf.len() / 2]).unwrap();
+        fs::write(dir.path().join("unknown"), b"unknown").unwrap();
+
+        let store = DiskFileBlobStore::open(dir.path(), config).unwrap();
+        assert!(!dir.path().join(format!("{partial:x}")).exists());
+        assert!(!dir.path().join("unknown").exists());
+        assert_eq!(store.blobs_len(), 3);
+        assert_eq!(store.get(tx).unwrap(), Some(sidecar.clone()));
+        assert!(store
+            .get_by_versioned_hashes(&versioned_hashes)
+            .unwrap()
+            .iter()
+            .all(Option::is_some));
+
+        // only the blobs the pool inserts again survive the cleanup
+        store.insert(tx, sidecar).unwrap();
+        let stat = store.cleanup();
+        assert_eq!(stat.delete_succeed, 2);
+        assert!(store.contains(tx).unwrap());
+        assert!(!store.contains(blobs[0].0).unwrap());
+        assert_eq!(store.blobs_len(), 1);
+    }
 }
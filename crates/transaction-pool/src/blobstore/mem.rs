@@ -1,8 +1,9 @@
 use crate::blobstore::{
     BlobStore, BlobStoreCleanupStat, BlobStoreError, BlobStoreSize, BlobTransactionSidecar,
+    VersionedHashIndex,
 };
 use parking_lot::RwLock;
-use reth_primitives::B256;
+use reth_primitives::{BlobAndProofV1, B256};
 use std::{collections::HashMap, sync::Arc};
 
 /// An in-memory blob store.
@@ -16,6 +17,8 @@ struct InMemoryBlobStoreInner {
     /// Storage for all blob data.
     store: RwLock<HashMap<B256, BlobTransactionSidecar>>,
     size_tracker: BlobStoreSize,
+    /// Maps the versioned hashes of the stored blobs to their transaction.
+    versioned_hashes: RwLock<VersionedHashIndex>,
 }
 
 impl PartialEq for InMemoryBlobStoreInner {
@@ -27,6 +30,7 @@ impl PartialEq for InMemoryBlobStoreInner {
 impl BlobStore for InMemoryBlobStore {
     fn insert(&self, tx: B256, data: BlobTransactionSidecar) -> Result<(), BlobStoreError> {
         let mut store = self.inner.store.write();
+        self.inner.versioned_hashes.write().insert(tx, &data);
         self.inner.size_tracker.add_size(insert_size(&mut store, tx, data));
         self.inner.size_tracker.update_len(store.len());
         Ok(())
@@ -37,8 +41,10 @@ impl BlobStore for InMemoryBlobStore {
             return Ok(())
         }
         let mut store = self.inner.store.write();
+        let mut versioned_hashes = self.inner.versioned_hashes.write();
         let mut total_add = 0;
         for (tx, data) in txs {
+            versioned_hashes.insert(tx, &data);
             let add = insert_size(&mut store, tx, data);
             total_add += add;
         }
@@ -49,7 +55,7 @@ impl BlobStore for InMemoryBlobStore {
 
     fn delete(&self, tx: B256) -> Result<(), BlobStoreError> {
         let mut store = self.inner.store.write();
-        let sub = remove_size(&mut store, &tx);
+        let sub = remove_size(&mut store, &mut self.inner.versioned_hashes.write(), &tx);
         self.inner.size_tracker.sub_size(sub);
         self.inner.size_tracker.update_len(store.len());
         Ok(())
@@ -60,9 +66,10 @@ impl BlobStore for InMemoryBlobStore {
             return Ok(())
         }
         let mut store = self.inner.store.write();
+        let mut versioned_hashes = self.inner.versioned_hashes.write();
         let mut total_sub = 0;
         for tx in txs {
-            total_sub += remove_size(&mut store, &tx);
+            total_sub += remove_size(&mut store, &mut versioned_hashes, &tx);
         }
         self.inner.size_tracker.sub_size(total_sub);
         self.inner.size_tracker.update_len(store.len());
@@ -113,6 +120,21 @@ impl BlobStore for InMemoryBlobStore {
         Ok(items)
     }
 
+    fn get_by_versioned_hashes(
+        &self,
+        versioned_hashes: &[B256],
+    ) -> Result<Vec<Option<BlobAndProofV1>>, BlobStoreError> {
+        let store = self.inner.store.read();
+        let index = self.inner.versioned_hashes.read();
+        Ok(versioned_hashes
+            .iter()
+            .map(|versioned_hash| {
+                let sidecar = store.get(&index.get(versioned_hash)?)?;
+                BlobAndProofV1::from_sidecar(sidecar, versioned_hash)
+            })
+            .collect())
+    }
+
     fn data_size_hint(&self) -> Option<usize> {
         Some(self.inner.size_tracker.data_size())
     }
@@ -124,8 +146,18 @@ impl BlobStore for InMemoryBlobStore {
 
 /// Removes the given blob from the store and returns the size of the blob that was removed.
 #[inline]
-fn remove_size(store: &mut HashMap<B256, BlobTransactionSidecar>, tx: &B256) -> usize {
-    store.remove(tx).map(|rem| rem.size()).unwrap_or_default()
+fn remove_size(
+    store: &mut HashMap<B256, BlobTransactionSidecar>,
+    versioned_hashes: &mut VersionedHashIndex,
+    tx: &B256,
+) -> usize {
+    store
+        .remove(tx)
+        .map(|rem| {
+            versioned_hashes.remove(tx, &rem);
+            rem.size()
+        })
+        .unwrap_or_default()
 }
 
 /// Inserts the given blob into the store and returns the size of the blob that was added.
@@ -3,8 +3,9 @@
 pub use disk::{DiskFileBlobStore, DiskFileBlobStoreConfig, OpenDiskFileBlobStore};
 pub use mem::InMemoryBlobStore;
 pub use noop::NoopBlobStore;
-use reth_primitives::{BlobTransactionSidecar, B256};
+use reth_primitives::{BlobAndProofV1, BlobTransactionSidecar, B256};
 use std::{
+    collections::{HashMap, HashSet},
     fmt,
     sync::atomic::{AtomicUsize, Ordering},
 };
@@ -64,6 +65,15 @@ pub trait BlobStore: fmt::Debug + Send + Sync + 'static {
     /// Returns an error if any of the blobs are not found in the blob store.
     fn get_exact(&self, txs: Vec<B256>) -> Result<Vec<BlobTransactionSidecar>, BlobStoreError>;
 
+    /// Returns the blob and its proof for each of the given versioned hashes, in the order they
+    /// were requested.
+    ///
+    /// The entry of a versioned hash is `None` if no stored blob matches it.
+    fn get_by_versioned_hashes(
+        &self,
+        versioned_hashes: &[B256],
+    ) -> Result<Vec<Option<BlobAndProofV1>>, BlobStoreError>;
+
     /// Data size of all transactions in the blob store.
     fn data_size_hint(&self) -> Option<usize>;
 
@@ -140,6 +150,42 @@ impl PartialEq for BlobStoreSize {
     }
 }
 
+/// Maps the versioned hashes of the stored blobs to the transaction they belong to.
+#[derive(Debug, Default)]
+pub(crate) struct VersionedHashIndex {
+    txs: HashMap<B256, B256>,
+}
+
+impl VersionedHashIndex {
+    /// Adds the versioned hashes of the transaction's blobs to the index.
+    pub(crate) fn insert(&mut self, tx: B256, sidecar: &BlobTransactionSidecar) {
+        for versioned_hash in sidecar.versioned_hashes() {
+            self.txs.insert(versioned_hash, tx);
+        }
+    }
+
+    /// Removes the versioned hashes of the transaction's blobs from the index.
+    ///
+    /// Versioned hashes that were inserted again by another transaction are kept.
+    pub(crate) fn remove(&mut self, tx: &B256, sidecar: &BlobTransactionSidecar) {
+        for versioned_hash in sidecar.versioned_hashes() {
+            if self.txs.get(&versioned_hash) == Some(tx) {
+                self.txs.remove(&versioned_hash);
+            }
+        }
+    }
+
+    /// Removes all versioned hashes of the given transactions from the index.
+    pub(crate) fn remove_txs(&mut self, txs: &HashSet<B256>) {
+        self.txs.retain(|_, tx| !txs.contains(tx))
+    }
+
+    /// Returns the transaction the blob with the versioned hash belongs to.
+    pub(crate) fn get(&self, versioned_hash: &B256) -> Option<B256> {
+        self.txs.get(versioned_hash).copied()
+    }
+}
+
 /// Statistics for the cleanup operation.
 #[derive(Debug, Clone, Default)]
 pub struct BlobStoreCleanupStat {
@@ -46,25 +46,61 @@ impl BlobStoreCanonTracker {
         self.add_blocks(blob_txs);
     }
 
-    /// Invoked when a block is finalized.
+    /// Removes the given blocks of a reorged out chain from the tracker.
     ///
-    /// This returns all blob transactions that were included in blocks that are now finalized.
-    pub fn on_finalized_block(&mut self, finalized_block: BlockNumber) -> BlobStoreUpdates {
-        let mut finalized = Vec::new();
-        while let Some(entry) = self.blob_txs_in_blocks.first_entry() {
-            if *entry.key() <= finalized_block {
-                finalized.extend(entry.remove_entry().1);
-            } else {
-                break
-            }
+    /// The blob transactions of these blocks are re-injected into the pool, so their sidecars must
+    /// no longer be deleted together with the blocks.
+    pub fn remove_blocks(&mut self, block_numbers: impl IntoIterator<Item = BlockNumber>) {
+        for block_number in block_numbers {
+            self.blob_txs_in_blocks.remove(&block_number);
         }
+    }
 
+    /// Invoked when a block is finalized.
+    ///
+    /// This returns all blob transactions that were included in blocks up to and including the
+    /// given block.
+    pub fn on_finalized_block(&mut self, finalized_block: BlockNumber) -> BlobStoreUpdates {
+        let finalized = self.remove_blocks_until(finalized_block);
         if finalized.is_empty() {
             BlobStoreUpdates::None
         } else {
             BlobStoreUpdates::Finalized(finalized)
         }
     }
+
+    /// Invoked with the current canonical tip.
+    ///
+    /// The sidecars of mined blob transactions are retained until their block is finalized, so
+    /// that the transactions can be re-injected into the pool if their block is reorged out. If
+    /// the chain doesn't finalize, this bounds the retained sidecars: this returns all blob
+    /// transactions that were included in blocks more than `retention_window` blocks below the
+    /// tip.
+    pub fn on_new_tip(&mut self, tip: BlockNumber, retention_window: u64) -> BlobStoreUpdates {
+        let Some(last_expired) = tip.checked_sub(retention_window + 1) else {
+            return BlobStoreUpdates::None
+        };
+        let expired = self.remove_blocks_until(last_expired);
+        if expired.is_empty() {
+            BlobStoreUpdates::None
+        } else {
+            BlobStoreUpdates::BeyondRetentionWindow(expired)
+        }
+    }
+
+    /// Removes all blocks up to and including the given block and returns their blob
+    /// transactions.
+    fn remove_blocks_until(&mut self, block_number: BlockNumber) -> Vec<B256> {
+        let mut removed = Vec::new();
+        while let Some(entry) = self.blob_txs_in_blocks.first_entry() {
+            if *entry.key() <= block_number {
+                removed.extend(entry.remove_entry().1);
+            } else {
+                break
+            }
+        }
+        removed
+    }
 }
 
 /// Updates that should be applied to the blob store.
@@ -74,6 +110,9 @@ pub enum BlobStoreUpdates {
     None,
     /// Delete the given finalized transactions from the blob store.
     Finalized(Vec<B256>),
+    /// Delete the given transactions that were included in blocks beyond the retention window from
+    /// the blob store.
+    BeyondRetentionWindow(Vec<B256>),
 }
 
 #[cfg(test)]
@@ -98,4 +137,24 @@ mod tests {
             BlobStoreUpdates::Finalized(block2.into_iter().chain(block3).collect::<Vec<_>>())
         );
     }
+
+    #[test]
+    fn test_retention_tracker() {
+        let mut tracker = BlobStoreCanonTracker::default();
+
+        let block1 = vec![B256::random()];
+        let block2 = vec![B256::random()];
+        let block3 = vec![B256::random()];
+        tracker.add_block(1, block1.clone());
+        tracker.add_block(2, block2);
+        tracker.add_block(3, block3.clone());
+
+        // block 2 is reorged out and its transactions are re-injected
+        tracker.remove_blocks([2]);
+
+        assert_eq!(tracker.on_new_tip(3, 2), BlobStoreUpdates::None);
+        assert_eq!(tracker.on_new_tip(4, 2), BlobStoreUpdates::BeyondRetentionWindow(block1));
+        assert_eq!(tracker.on_new_tip(5, 2), BlobStoreUpdates::None);
+        assert_eq!(tracker.on_new_tip(6, 2), BlobStoreUpdates::BeyondRetentionWindow(block3));
+    }
 }
@@ -0,0 +1,75 @@
+//! Encodings shared by the on-disk formats of the pool, the [`journal`](crate::journal) and the
+//! [`snapshot`](crate::snapshot).
+
+use crate::TransactionOrigin;
+use alloy_rlp::{Decodable, Encodable, Error as RlpError, Header};
+use reth_primitives::bytes::BufMut;
+
+/// A transaction together with the origin it was submitted with.
+///
+/// This is encoded as the RLP list `[origin, transaction]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OriginTaggedTransaction<T> {
+    /// The origin the transaction was submitted with.
+    pub origin: TransactionOrigin,
+    /// The transaction.
+    pub transaction: T,
+}
+
+impl<T> OriginTaggedTransaction<T> {
+    /// Returns the id the origin is encoded as.
+    pub(crate) const fn origin_id(&self) -> u8 {
+        match self.origin {
+            TransactionOrigin::Local => 0,
+            TransactionOrigin::External => 1,
+            TransactionOrigin::Private => 2,
+        }
+    }
+}
+
+impl<T: Encodable> OriginTaggedTransaction<T> {
+    fn payload_length(&self) -> usize {
+        self.origin_id().length() + self.transaction.length()
+    }
+}
+
+impl<T: Encodable> Encodable for OriginTaggedTransaction<T> {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.origin_id().encode(out);
+        self.transaction.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl<T: Decodable> Decodable for OriginTaggedTransaction<T> {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(RlpError::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let origin = match u8::decode(buf)? {
+            0 => TransactionOrigin::Local,
+            1 => TransactionOrigin::External,
+            2 => TransactionOrigin::Private,
+            _ => return Err(RlpError::Custom("unknown transaction origin")),
+        };
+        let transaction = T::decode(buf)?;
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(RlpError::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+
+        Ok(Self { origin, transaction })
+    }
+}
@@ -1,9 +1,15 @@
 use crate::{PoolSize, TransactionOrigin};
 use reth_primitives::{Address, EIP4844_TX_TYPE_ID};
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Duration};
 /// Guarantees max transactions for one sender, compatible with geth/erigon
 pub const TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER: usize = 16;
 
+/// The default maximum number of transactions of one sender in the pending sub-pool.
+pub const TXPOOL_MAX_ACCOUNT_SLOTS_PENDING_PER_SENDER: usize = TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER;
+
+/// The default maximum number of transactions of one sender in the queued sub-pool.
+pub const TXPOOL_MAX_ACCOUNT_SLOTS_QUEUED_PER_SENDER: usize = TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER;
+
 /// The default maximum allowed number of transactions in the given subpool.
 pub const TXPOOL_SUBPOOL_MAX_TXS_DEFAULT: usize = 10_000;
 
@@ -18,6 +24,9 @@ pub const DEFAULT_PRICE_BUMP: u128 = 10;
 /// This enforces that a blob transaction requires a 100% price bump to be replaced
 pub const REPLACE_BLOB_PRICE_BUMP: u128 = 100;
 
+/// Default maximum amount of time a transaction can stay in the queued sub-pool: 3 hours.
+pub const DEFAULT_MAX_QUEUED_LIFETIME: Duration = Duration::from_secs(3 * 60 * 60);
+
 /// Configuration options for the Transaction pool.
 #[derive(Debug, Clone)]
 pub struct PoolConfig {
@@ -31,8 +40,22 @@ pub struct PoolConfig {
     pub blob_limit: SubPoolLimit,
     /// Max number of executable transaction slots guaranteed per account
     pub max_account_slots: usize,
+    /// Max number of transactions of a single sender in the pending sub-pool.
+    ///
+    /// A new transaction that would exceed this is rejected.
+    pub max_account_slots_pending: usize,
+    /// Max number of transactions of a single sender in the queued sub-pool.
+    ///
+    /// If a new transaction would exceed this, the sender's queued transaction with the lowest
+    /// priority is evicted, or the new transaction is rejected if it has the lowest priority.
+    pub max_account_slots_queued: usize,
     /// Price bump (in %) for the transaction pool underpriced check.
     pub price_bumps: PriceBumpConfig,
+    /// Maximum amount of time a transaction can stay in the queued sub-pool, measured from its
+    /// insertion.
+    ///
+    /// Local transactions use [`LocalTransactionConfig::max_queued_lifetime`] instead.
+    pub max_queued_lifetime: Duration,
     /// How to handle locally received transactions:
     /// [`TransactionOrigin::Local`](crate::TransactionOrigin).
     pub local_transactions_config: LocalTransactionConfig,
@@ -57,7 +80,10 @@ impl Default for PoolConfig {
             queued_limit: Default::default(),
             blob_limit: Default::default(),
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+            max_account_slots_pending: TXPOOL_MAX_ACCOUNT_SLOTS_PENDING_PER_SENDER,
+            max_account_slots_queued: TXPOOL_MAX_ACCOUNT_SLOTS_QUEUED_PER_SENDER,
             price_bumps: Default::default(),
+            max_queued_lifetime: DEFAULT_MAX_QUEUED_LIFETIME,
             local_transactions_config: Default::default(),
         }
     }
@@ -101,7 +127,11 @@ pub struct PriceBumpConfig {
     /// Default price bump (in %) for the transaction pool underpriced check.
     pub default_price_bump: u128,
     /// Replace blob price bump (in %) for the transaction pool underpriced check.
+    ///
+    /// This applies to the max fee and the max priority fee of blob transactions.
     pub replace_blob_tx_price_bump: u128,
+    /// Price bump (in %) on the max fee per blob gas required to replace a blob transaction.
+    pub replace_blob_tx_blob_fee_price_bump: u128,
 }
 
 impl PriceBumpConfig {
@@ -113,6 +143,12 @@ impl PriceBumpConfig {
         }
         self.default_price_bump
     }
+
+    /// Returns the price bump required on the max fee per blob gas to replace a blob transaction.
+    #[inline]
+    pub(crate) const fn blob_fee_price_bump(&self) -> u128 {
+        self.replace_blob_tx_blob_fee_price_bump
+    }
 }
 
 impl Default for PriceBumpConfig {
@@ -120,6 +156,7 @@ impl Default for PriceBumpConfig {
         Self {
             default_price_bump: DEFAULT_PRICE_BUMP,
             replace_blob_tx_price_bump: REPLACE_BLOB_PRICE_BUMP,
+            replace_blob_tx_blob_fee_price_bump: REPLACE_BLOB_PRICE_BUMP,
         }
     }
 }
@@ -139,6 +176,11 @@ pub struct LocalTransactionConfig {
     pub local_addresses: HashSet<Address>,
     /// Flag indicating whether local transactions should be propagated.
     pub propagate_local_transactions: bool,
+    /// Maximum amount of time a local transaction can stay in the queued sub-pool.
+    ///
+    /// If not set, local transactions are never evicted from the queued sub-pool because of their
+    /// age.
+    pub max_queued_lifetime: Option<Duration>,
 }
 
 impl Default for LocalTransactionConfig {
@@ -147,6 +189,7 @@ impl Default for LocalTransactionConfig {
             no_exemptions: false,
             local_addresses: HashSet::default(),
             propagate_local_transactions: true,
+            max_queued_lifetime: None,
         }
     }
 }
@@ -185,6 +228,12 @@ impl LocalTransactionConfig {
         self.propagate_local_transactions = propagate_local_txs;
         self
     }
+
+    /// Sets the maximum amount of time a local transaction can stay in the queued sub-pool.
+    pub const fn with_max_queued_lifetime(mut self, max_queued_lifetime: Duration) -> Self {
+        self.max_queued_lifetime = Some(max_queued_lifetime);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -41,14 +41,18 @@ pub enum PoolErrorKind {
     #[error("already imported")]
     AlreadyImported,
     /// Thrown if a replacement transaction's gas price is below the already imported transaction
-    #[error("insufficient gas price to replace existing transaction")]
-    ReplacementUnderpriced,
+    #[error("insufficient gas price to replace existing transaction, requires a {0}% price bump")]
+    ReplacementUnderpriced(u128),
     /// The fee cap of the transaction is below the minimum fee cap determined by the protocol
     #[error("transaction feeCap {0} below chain minimum")]
     FeeCapBelowMinimumProtocolFeeCap(u128),
     /// Thrown when the number of unique transactions of a sender exceeded the slot capacity.
     #[error("rejected due to {0} being identified as a spammer")]
     SpammerExceededCapacity(Address),
+    /// Thrown when a new transaction would exceed the sender's slots in the pending or queued
+    /// sub-pool.
+    #[error("sender {0} exceeds its max account slots")]
+    ExceedsMaxAccountSlots(Address),
     /// Thrown when a new transaction is added to the pool, but then immediately discarded to
     /// respect the size limits of the pool.
     #[error("transaction discarded outright due to pool size constraints")]
@@ -98,7 +102,7 @@ impl PoolError {
                 // already imported but not bad
                 false
             }
-            PoolErrorKind::ReplacementUnderpriced => {
+            PoolErrorKind::ReplacementUnderpriced(_) => {
                 // already imported but not bad
                 false
             }
@@ -116,6 +120,10 @@ impl PoolError {
                 // (pool lags behind) and old transaction still occupy a slot in the pool
                 false
             }
+            PoolErrorKind::ExceedsMaxAccountSlots(_) => {
+                // same as above, the sender's transactions occupy too many slots of a sub-pool
+                false
+            }
             PoolErrorKind::DiscardedOnInsert => {
                 // valid tx but dropped due to size constraints
                 false
@@ -192,6 +200,14 @@ pub enum InvalidPoolTransactionError {
     /// Thrown if the transaction's fee is below the minimum fee
     #[error("transaction underpriced")]
     Underpriced,
+    /// Thrown if the transaction's priority fee is below the minimum priority fee of the pool.
+    #[error("transaction priority fee {priority_fee} below minimum {minimum_priority_fee}")]
+    PriorityFeeBelowMinimum {
+        /// The priority fee of the transaction.
+        priority_fee: u128,
+        /// The minimum priority fee required by the pool.
+        minimum_priority_fee: u128,
+    },
     /// Thrown if the transaction's would require an account to be overdrawn
     #[error("transaction overdraws from account")]
     Overdraft,
@@ -225,7 +241,7 @@ impl InvalidPoolTransactionError {
                 // intentionally caused by the sender
                 match err {
                     InvalidTransactionError::InsufficientFunds { .. } |
-                    InvalidTransactionError::NonceNotConsistent => {
+                    InvalidTransactionError::NonceNotConsistent { .. } => {
                         // transaction could just have arrived late/early
                         false
                     }
@@ -255,7 +271,7 @@ impl InvalidPoolTransactionError {
             Self::ExceedsGasLimit(_, _) => true,
             Self::ExceedsMaxInitCodeSize(_, _) => true,
             Self::OversizedData(_, _) => true,
-            Self::Underpriced => {
+            Self::Underpriced | Self::PriorityFeeBelowMinimum { .. } => {
                 // local setting
                 false
             }
@@ -293,7 +309,7 @@ impl InvalidPoolTransactionError {
 
     /// Returns `true` if an import failed due to nonce gap.
     pub const fn is_nonce_gap(&self) -> bool {
-        matches!(self, Self::Consensus(InvalidTransactionError::NonceNotConsistent)) ||
+        matches!(self, Self::Consensus(InvalidTransactionError::NonceNotConsistent { .. })) ||
             matches!(self, Self::Eip4844(Eip4844PoolTransactionError::Eip4844NonceGap))
     }
 }
@@ -0,0 +1,510 @@
+//! Journal of local transactions that is persisted across restarts.
+//!
+//! Local transactions are appended to the journal file as soon as they are accepted by the pool.
+//! The journal is compacted periodically, dropping the transactions that were mined or are no
+//! longer in the pool. On startup, the surviving transactions are re-validated and re-inserted
+//! into the pool.
+//!
+//! A backup written by
+//! [`backup_local_transactions_task`](crate::maintain::backup_local_transactions_task)
+//! can be imported into the journal once on startup, see
+//! [`LocalTransactionJournalConfig::with_legacy_backup`].
+
+use crate::{
+    codec::OriginTaggedTransaction,
+    maintain::{recovered_into_pool_transaction, TransactionsBackupError},
+    NewTransactionEvent, PoolTransaction, TransactionListenerKind, TransactionOrigin,
+    TransactionPool, ValidPoolTransaction,
+};
+use alloy_rlp::{Decodable, Encodable};
+use futures_util::{Stream, StreamExt};
+use reth_fs_util::FsPathError;
+use reth_primitives::{BlockNumber, IntoRecoveredTransaction, TransactionSigned, TxHash};
+use reth_provider::CanonStateNotification;
+use reth_tasks::shutdown::GracefulShutdown;
+use std::{
+    collections::HashSet,
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::sync::mpsc::Receiver;
+use tracing::{debug, info, trace, warn};
+
+/// Default number of blocks between two compactions of the journal.
+pub const DEFAULT_JOURNAL_COMPACTION_INTERVAL: u64 = 64;
+
+/// Default interval in which appended transactions are flushed to the journal file.
+pub const DEFAULT_JOURNAL_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Settings for the local transactions journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalTransactionJournalConfig {
+    /// Path to the journal file.
+    pub path: PathBuf,
+    /// Number of blocks after which the journal is compacted.
+    ///
+    /// Default: [`DEFAULT_JOURNAL_COMPACTION_INTERVAL`]
+    pub compaction_interval: u64,
+    /// Interval in which appended transactions are flushed to the journal file.
+    ///
+    /// Default: [`DEFAULT_JOURNAL_FLUSH_INTERVAL`]
+    pub flush_interval: Duration,
+    /// Path to a backup of local transactions that is imported into the journal and removed on
+    /// startup.
+    pub legacy_backup_path: Option<PathBuf>,
+}
+
+impl LocalTransactionJournalConfig {
+    /// Creates a new config for the journal at the given path.
+    pub const fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            compaction_interval: DEFAULT_JOURNAL_COMPACTION_INTERVAL,
+            flush_interval: DEFAULT_JOURNAL_FLUSH_INTERVAL,
+            legacy_backup_path: None,
+        }
+    }
+
+    /// Sets the number of blocks after which the journal is compacted.
+    pub const fn with_compaction_interval(mut self, compaction_interval: u64) -> Self {
+        self.compaction_interval = compaction_interval;
+        self
+    }
+
+    /// Sets the interval in which appended transactions are flushed to the journal file.
+    pub const fn with_flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    /// Sets the path to a backup written by
+    /// [`backup_local_transactions_task`](crate::maintain::backup_local_transactions_task) that is
+    /// imported into the journal on startup.
+    pub fn with_legacy_backup(mut self, path: PathBuf) -> Self {
+        self.legacy_backup_path = Some(path);
+        self
+    }
+}
+
+/// A single record of the journal: a transaction together with its origin.
+///
+/// Blob transactions are journaled without their sidecar, which is kept in the blob store.
+pub type JournalEntry = OriginTaggedTransaction<TransactionSigned>;
+
+impl JournalEntry {
+    fn from_pool_transaction<T: PoolTransaction>(tx: &ValidPoolTransaction<T>) -> Self {
+        Self { origin: tx.origin, transaction: tx.to_recovered_transaction().into_signed() }
+    }
+}
+
+/// An append-only journal of the local transactions of a pool.
+///
+/// See the [module](self) docs for more details.
+#[derive(Debug)]
+pub struct LocalTransactionJournal<T: PoolTransaction> {
+    config: LocalTransactionJournalConfig,
+    /// Buffered handle to the journal file, opened in append mode.
+    ///
+    /// Appended transactions are flushed to the file in the configured flush interval.
+    file: BufWriter<File>,
+    /// Hashes of the transactions that are still considered live in the journal file.
+    journaled: HashSet<TxHash>,
+    /// The block at which the journal was last compacted.
+    last_compaction: Option<BlockNumber>,
+    /// Listener for the local transactions that are inserted into the pool.
+    new_transactions: Receiver<NewTransactionEvent<T>>,
+}
+
+impl<T: PoolTransaction> LocalTransactionJournal<T> {
+    /// Loads the journal from disk and re-inserts the journaled transactions into the pool.
+    ///
+    /// The transactions are re-validated by the pool, transactions that are no longer valid are
+    /// dropped from the journal. The transactions of the configured legacy backup are imported as
+    /// local transactions and the backup is removed once they are journaled. Transactions that are
+    /// accepted by the pool after this returns are
+    /// journaled once the journal is driven by [`local_transactions_journal_task`], so this should
+    /// be called before the pool accepts new local transactions, e.g. before the RPC server is
+    /// started.
+    pub async fn load<P>(
+        pool: &P,
+        config: LocalTransactionJournalConfig,
+    ) -> Result<Self, TransactionsBackupError>
+    where
+        P: TransactionPool<Transaction = T>,
+    {
+        let mut entries = read_journal(&config.path)?;
+
+        let mut legacy_backup = config.legacy_backup_path.as_deref().filter(|path| path.exists());
+        if let Some(path) = legacy_backup {
+            match read_legacy_backup(path) {
+                Ok(transactions) => {
+                    info!(target: "txpool", txs_file=?path, num_txs=transactions.len(), "Importing local transactions backup into journal");
+                    entries.extend(transactions.into_iter().map(|transaction| JournalEntry {
+                        origin: TransactionOrigin::Local,
+                        transaction,
+                    }));
+                }
+                Err(err) => {
+                    warn!(target: "txpool", %err, txs_file=?path, "Failed to read local transactions backup");
+                    legacy_backup = None;
+                }
+            }
+        }
+        let num_entries = entries.len();
+
+        let mut journaled = HashSet::with_capacity(num_entries);
+        for origin in [TransactionOrigin::Local, TransactionOrigin::Private] {
+            let transactions = entries
+                .iter()
+                .filter(|entry| entry.origin == origin)
+                .filter_map(|entry| entry.transaction.clone().try_ecrecovered())
+                .filter_map(|tx| recovered_into_pool_transaction(pool, tx))
+                .collect::<Vec<_>>();
+            if transactions.is_empty() {
+                continue
+            }
+            let outcome = pool.add_transactions(origin, transactions).await;
+            journaled.extend(outcome.into_iter().filter_map(Result::ok));
+        }
+
+        if num_entries > 0 {
+            info!(target: "txpool", journal=?config.path, num_entries, num_txs=journaled.len(), "Reinserted local transactions from journal");
+        }
+
+        // subscribe after the reinsertion, the reinserted transactions are already journaled
+        let new_transactions =
+            pool.new_transactions_listener_for(TransactionListenerKind::LocalOnly);
+
+        if let Some(parent) = config.path.parent() {
+            reth_fs_util::create_dir_all(parent)?;
+        }
+        let file = open_append(&config.path)?;
+        let mut journal = Self { config, file, journaled, last_compaction: None, new_transactions };
+
+        // drop the transactions that were not accepted again
+        journal.compact(pool).await?;
+
+        // the imported transactions are journaled now
+        if let Some(path) = legacy_backup {
+            reth_fs_util::remove_file(path)?;
+        }
+
+        Ok(journal)
+    }
+
+    /// Returns the path to the journal file.
+    pub fn path(&self) -> &Path {
+        &self.config.path
+    }
+
+    /// Returns the number of transactions that are considered live in the journal.
+    pub fn len(&self) -> usize {
+        self.journaled.len()
+    }
+
+    /// Returns `true` if there are no live transactions in the journal.
+    pub fn is_empty(&self) -> bool {
+        self.journaled.is_empty()
+    }
+
+    /// Appends the local transaction to the journal.
+    ///
+    /// The entry is buffered until the next [`Self::flush`].
+    fn on_new_transaction(&mut self, tx: &ValidPoolTransaction<T>) -> Result<(), FsPathError> {
+        if !self.journaled.insert(*tx.hash()) {
+            return Ok(())
+        }
+
+        trace!(target: "txpool", hash=%tx.hash(), "Journaling local transaction");
+        let entry = JournalEntry::from_pool_transaction(tx);
+        let mut buf = Vec::with_capacity(entry.length());
+        entry.encode(&mut buf);
+        self.file.write_all(&buf).map_err(|err| FsPathError::write(err, &self.config.path))
+    }
+
+    /// Flushes the buffered entries to the journal file.
+    fn flush(&mut self) -> Result<(), FsPathError> {
+        self.file.flush().map_err(|err| FsPathError::write(err, &self.config.path))
+    }
+
+    /// Appends all transactions that were inserted into the pool since the last call.
+    fn drain_new_transactions(&mut self) -> Result<(), FsPathError> {
+        while let Ok(event) = self.new_transactions.try_recv() {
+            self.on_new_transaction(&event.transaction)?;
+        }
+        Ok(())
+    }
+
+    /// Drops the mined transactions and compacts the journal if the compaction interval has
+    /// passed since the last compaction.
+    async fn on_canonical_state_change<P>(
+        &mut self,
+        pool: &P,
+        event: CanonStateNotification,
+    ) -> Result<(), FsPathError>
+    where
+        P: TransactionPool<Transaction = T>,
+    {
+        // journal the transactions accepted before this block first, so that the mined ones are
+        // dropped below
+        self.drain_new_transactions()?;
+
+        let chain = event.committed();
+        let (blocks, _) = chain.inner();
+        for hash in blocks.transaction_hashes() {
+            self.journaled.remove(&hash);
+        }
+
+        let tip = blocks.tip().number;
+        let due = self
+            .last_compaction
+            .map_or(true, |last| tip.saturating_sub(last) >= self.config.compaction_interval);
+        if due {
+            self.compact(pool).await?;
+            self.last_compaction = Some(tip);
+        }
+        Ok(())
+    }
+
+    /// Rewrites the journal with the journaled transactions that are still in the pool.
+    ///
+    /// The file is rewritten on a blocking task.
+    async fn compact<P>(&mut self, pool: &P) -> Result<(), FsPathError>
+    where
+        P: TransactionPool<Transaction = T>,
+    {
+        let mut transactions = pool.get_all(self.journaled.iter().copied().collect());
+        // keep the nonce order of each sender, so that reinserted transactions are not queued
+        transactions.sort_by_key(|tx| (tx.sender(), tx.nonce()));
+        self.journaled = transactions.iter().map(|tx| *tx.hash()).collect();
+
+        let mut buf = Vec::new();
+        for tx in &transactions {
+            JournalEntry::from_pool_transaction(tx).encode(&mut buf);
+        }
+
+        let path = self.config.path.clone();
+        let file = tokio::task::spawn_blocking(move || {
+            // write to a temporary file first, so that a crash can't corrupt the journal
+            let tmp_path = path.with_extension("tmp");
+            reth_fs_util::write(&tmp_path, buf)?;
+            reth_fs_util::rename(&tmp_path, &path)?;
+            open_append(&path)
+        })
+        .await
+        .unwrap_or_else(|err| Err(FsPathError::write(io::Error::other(err), &self.config.path)))?;
+        // the buffered entries are part of the rewritten journal, discard them instead of flushing
+        // them to the replaced file
+        let replaced = std::mem::replace(&mut self.file, file);
+        let _ = replaced.into_parts();
+
+        debug!(target: "txpool", journal=?self.config.path, num_txs=transactions.len(), "Compacted local transactions journal");
+        Ok(())
+    }
+}
+
+/// Reads all entries of the journal file.
+///
+/// A corrupted tail, e.g. from a crash during an append, is skipped.
+fn read_journal(path: &Path) -> Result<Vec<JournalEntry>, FsPathError> {
+    if !path.exists() {
+        return Ok(Vec::new())
+    }
+
+    let data = reth_fs_util::read(path)?;
+    let mut buf = data.as_slice();
+    let mut entries = Vec::new();
+    while !buf.is_empty() {
+        match JournalEntry::decode(&mut buf) {
+            Ok(entry) => entries.push(entry),
+            Err(err) => {
+                warn!(target: "txpool", %err, journal=?path, remaining=buf.len(), "Skipping corrupted tail of local transactions journal");
+                break
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Reads the transactions of a backup written by
+/// [`backup_local_transactions_task`](crate::maintain::backup_local_transactions_task).
+fn read_legacy_backup(path: &Path) -> Result<Vec<TransactionSigned>, TransactionsBackupError> {
+    let data = reth_fs_util::read(path)?;
+    if data.is_empty() {
+        return Ok(Vec::new())
+    }
+    Ok(Decodable::decode(&mut data.as_slice())?)
+}
+
+fn open_append(path: &Path) -> Result<BufWriter<File>, FsPathError> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map(BufWriter::new)
+        .map_err(|err| FsPathError::open(err, path))
+}
+
+/// Task that keeps the local transactions journal up to date.
+///
+/// Appends all local transactions that are accepted by the pool, flushes them to the journal file
+/// in the configured flush interval and compacts the journal on new canonical blocks. The journal
+/// is compacted one last time on shutdown.
+pub async fn local_transactions_journal_task<P, St>(
+    mut shutdown: GracefulShutdown,
+    pool: P,
+    mut journal: LocalTransactionJournal<P::Transaction>,
+    mut events: St,
+) where
+    P: TransactionPool,
+    St: Stream<Item = CanonStateNotification> + Send + Unpin,
+{
+    let mut flush_interval = tokio::time::interval(journal.config.flush_interval);
+    flush_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        let res = tokio::select! {
+            graceful_guard = &mut shutdown => {
+                let res = match journal.drain_new_transactions() {
+                    Ok(()) => journal.compact(&pool).await,
+                    Err(err) => Err(err),
+                };
+                if let Err(err) = res {
+                    warn!(target: "txpool", %err, journal=?journal.path(), "Failed to write local transactions journal");
+                }
+                drop(graceful_guard);
+                return
+            }
+            Some(event) = journal.new_transactions.recv() => {
+                journal.on_new_transaction(&event.transaction)
+            }
+            _ = flush_interval.tick() => {
+                journal.flush()
+            }
+            Some(event) = events.next() => {
+                journal.on_canonical_state_change(&pool, event).await
+            }
+        };
+
+        if let Err(err) = res {
+            warn!(target: "txpool", %err, journal=?journal.path(), "Failed to write local transactions journal");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::eth_testing_pool, EthPooledTransaction};
+    use reth_primitives::{hex, FromRecoveredPooledTransaction, PooledTransactionsElement, U256};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_tasks::TaskManager;
+
+    fn transaction() -> EthPooledTransaction {
+        let tx_bytes = hex!("02f87201830655c2808505ef61f08482565f94388c818ca8b9251b393131c08a736a67ccb192978801049e39c4b5b1f580c001a01764ace353514e8abdfb92446de356b260e3c1225b73fc4c8876a6258d12a129a04f02294aa61ca7676061cd99f29275491218b4754b46a0248e5e42bc5091f507");
+        let tx = PooledTransactionsElement::decode_enveloped(&mut &tx_bytes[..]).unwrap();
+        EthPooledTransaction::from_recovered_pooled_transaction(tx.try_into_ecrecovered().unwrap())
+    }
+
+    #[test]
+    fn journal_entry_roundtrip() {
+        let transaction = transaction().to_recovered_transaction().into_signed();
+        for origin in
+            [TransactionOrigin::Local, TransactionOrigin::External, TransactionOrigin::Private]
+        {
+            let entry = JournalEntry { origin, transaction: transaction.clone() };
+            let mut buf = Vec::new();
+            entry.encode(&mut buf);
+            assert_eq!(buf.len(), entry.length());
+            assert_eq!(JournalEntry::decode(&mut buf.as_slice()).unwrap(), entry);
+        }
+    }
+
+    #[test]
+    fn read_journal_skips_corrupted_tail() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("journal.rlp");
+
+        let entry = JournalEntry {
+            origin: TransactionOrigin::Local,
+            transaction: transaction().to_recovered_transaction().into_signed(),
+        };
+        let mut buf = Vec::new();
+        entry.encode(&mut buf);
+        let len = buf.len();
+        // an interrupted append
+        entry.encode(&mut buf);
+        buf.truncate(len + len / 2);
+        reth_fs_util::write(&path, buf).unwrap();
+
+        assert_eq!(read_journal(&path).unwrap(), vec![entry]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reinsert_journaled_transactions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = LocalTransactionJournalConfig::new(temp_dir.path().join("journal.rlp"));
+
+        let provider = MockEthProvider::default();
+        let transaction = transaction();
+        provider.add_account(transaction.sender(), ExtendedAccount::new(42, U256::MAX));
+
+        let pool = eth_testing_pool(provider.clone());
+        let journal = LocalTransactionJournal::load(&pool, config.clone()).await.unwrap();
+        assert!(journal.is_empty());
+
+        let manager = TaskManager::new(tokio::runtime::Handle::current());
+        manager.executor().spawn_critical_with_graceful_shutdown_signal(
+            "journal task",
+            |shutdown| {
+                local_transactions_journal_task(
+                    shutdown,
+                    pool.clone(),
+                    journal,
+                    futures_util::stream::pending(),
+                )
+            },
+        );
+
+        let hash = pool.add_transaction(TransactionOrigin::Local, transaction).await.unwrap();
+
+        // restart the pool
+        manager.graceful_shutdown();
+        drop(pool);
+
+        let pool = eth_testing_pool(provider);
+        let journal = LocalTransactionJournal::load(&pool, config.clone()).await.unwrap();
+        assert_eq!(journal.len(), 1);
+        let pending = pool.pending_transactions();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(*pending[0].hash(), hash);
+        assert_eq!(pending[0].origin, TransactionOrigin::Local);
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn import_legacy_backup() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let backup_path = temp_dir.path().join("txpool-transactions-backup.rlp");
+        let config = LocalTransactionJournalConfig::new(temp_dir.path().join("journal.rlp"))
+            .with_legacy_backup(backup_path.clone());
+
+        let provider = MockEthProvider::default();
+        let transaction = transaction();
+        provider.add_account(transaction.sender(), ExtendedAccount::new(42, U256::MAX));
+
+        let mut buf = Vec::new();
+        alloy_rlp::encode_list(&[transaction.to_recovered_transaction().into_signed()], &mut buf);
+        reth_fs_util::write(&backup_path, buf).unwrap();
+
+        let pool = eth_testing_pool(provider);
+        let journal = LocalTransactionJournal::load(&pool, config.clone()).await.unwrap();
+        assert_eq!(journal.len(), 1);
+        assert_eq!(pool.get_local_transactions().len(), 1);
+        assert!(!backup_path.exists());
+        assert_eq!(read_journal(&config.path).unwrap().len(), 1);
+    }
+}
@@ -153,25 +153,31 @@
 use crate::{identifier::TransactionId, pool::PoolInner};
 use aquamarine as _;
 use reth_eth_wire_types::HandleMempoolData;
-use reth_primitives::{Address, BlobTransactionSidecar, PooledTransactionsElement, TxHash, U256};
+use reth_primitives::{
+    Address, BlobAndProofV1, BlobTransactionSidecar, PooledTransactionsElement,
+    TransactionConditional, TxHash, B256, U256,
+};
 use reth_provider::StateProviderFactory;
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, sync::Arc, time::Instant};
 use tokio::sync::mpsc::Receiver;
 use tracing::{instrument, trace};
 
 pub use crate::{
     blobstore::{BlobStore, BlobStoreError},
     config::{
-        LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-        REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
-        TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
+        LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit,
+        DEFAULT_MAX_QUEUED_LIFETIME, DEFAULT_PRICE_BUMP, REPLACE_BLOB_PRICE_BUMP,
+        TXPOOL_MAX_ACCOUNT_SLOTS_PENDING_PER_SENDER, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+        TXPOOL_MAX_ACCOUNT_SLOTS_QUEUED_PER_SENDER, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+        TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
     },
     error::PoolResult,
     ordering::{CoinbaseTipOrdering, Priority, TransactionOrdering},
     pool::{
-        blob_tx_priority, fee_delta, state::SubPool, AllTransactionsEvents, FullTransactionEvent,
-        TransactionEvent, TransactionEvents,
+        blob_tx_priority, fee_delta, state::SubPool, AllTransactionsEvents, DiscardReason,
+        FullTransactionEvent, TransactionEvent, TransactionEvents, TransactionFilter,
     },
+    snapshot::{PoolSnapshotError, PoolSnapshots, RestoreOutcome},
     traits::*,
     validate::{
         EthTransactionValidator, TransactionValidationOutcome, TransactionValidationTaskExecutor,
@@ -179,11 +185,14 @@ pub use crate::{
     },
 };
 
+pub mod codec;
 pub mod error;
+pub mod journal;
 pub mod maintain;
 pub mod metrics;
 pub mod noop;
 pub mod pool;
+pub mod snapshot;
 pub mod validate;
 
 pub mod blobstore;
@@ -241,8 +250,13 @@ where
         origin: TransactionOrigin,
         transactions: impl IntoIterator<Item = V::Transaction>,
     ) -> Vec<(TxHash, TransactionValidationOutcome<V::Transaction>)> {
-        futures_util::future::join_all(transactions.into_iter().map(|tx| self.validate(origin, tx)))
+        self.pool
+            .validator()
+            .validate_transactions_batch(origin, transactions.into_iter().collect())
             .await
+            .into_iter()
+            .map(|outcome| (outcome.tx_hash(), outcome))
+            .collect()
     }
 
     /// Validates the given transaction
@@ -272,6 +286,25 @@ where
     pub fn is_exceeded(&self) -> bool {
         self.pool.is_exceeded()
     }
+
+    /// Writes a snapshot of all transactions in the pool, including their origins and the
+    /// sidecars of blob transactions, and returns the number of written transactions.
+    ///
+    /// See [`snapshot`] for the format.
+    pub fn dump(&self, writer: impl std::io::Write) -> Result<usize, PoolSnapshotError> {
+        snapshot::dump_pool(self, writer)
+    }
+
+    /// Re-validates and inserts the transactions of a snapshot that was written with
+    /// [`Pool::dump`].
+    ///
+    /// Fails without inserting anything if the snapshot is invalid.
+    pub async fn restore(
+        &self,
+        reader: impl std::io::Read + std::io::Seek,
+    ) -> Result<RestoreOutcome, PoolSnapshotError> {
+        snapshot::restore_pool(self, reader).await
+    }
 }
 
 impl<Client, S> EthTransactionPool<Client, S>
@@ -352,6 +385,20 @@ where
         results.pop().expect("result length is the same as the input")
     }
 
+    async fn add_transaction_with_conditional(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        conditional: TransactionConditional,
+    ) -> PoolResult<TxHash> {
+        let (_, tx) = self.validate(origin, transaction).await;
+        let mut results = self.pool.add_transactions_with_conditionals(
+            origin,
+            std::iter::once((tx, Some(Box::new(conditional)))),
+        );
+        results.pop().expect("result length is the same as the input")
+    }
+
     async fn add_transactions(
         &self,
         origin: TransactionOrigin,
@@ -373,6 +420,13 @@ where
         self.pool.add_all_transactions_event_listener()
     }
 
+    fn transaction_event_listener_filtered(
+        &self,
+        filter: TransactionFilter,
+    ) -> AllTransactionsEvents<Self::Transaction> {
+        self.pool.add_filtered_transactions_event_listener(filter)
+    }
+
     fn pending_transactions_listener_for(&self, kind: TransactionListenerKind) -> Receiver<TxHash> {
         self.pool.add_pending_listener(kind)
     }
@@ -501,6 +555,13 @@ where
         self.pool.get_transactions_by_origin(origin)
     }
 
+    fn pooled_transactions_matching(
+        &self,
+        filter: &TransactionFilter,
+    ) -> AllPoolTransactions<Self::Transaction> {
+        self.pool.pooled_transactions_matching(filter)
+    }
+
     fn unique_senders(&self) -> HashSet<Address> {
         self.pool.unique_senders()
     }
@@ -522,6 +583,13 @@ where
     ) -> Result<Vec<BlobTransactionSidecar>, BlobStoreError> {
         self.pool.blob_store().get_exact(tx_hashes)
     }
+
+    fn get_blobs_for_versioned_hashes(
+        &self,
+        versioned_hashes: &[B256],
+    ) -> Result<Vec<Option<BlobAndProofV1>>, BlobStoreError> {
+        self.pool.blob_store().get_by_versioned_hashes(versioned_hashes)
+    }
 }
 
 impl<V, T, S> TransactionPoolExt for Pool<V, T, S>
@@ -555,6 +623,10 @@ where
     fn cleanup_blobs(&self) {
         self.pool.cleanup_blobs()
     }
+
+    fn remove_expired_queued_transactions(&self) -> Vec<TxHash> {
+        self.pool.discard_expired_queued(Instant::now())
+    }
 }
 
 impl<V, T: TransactionOrdering, S> Clone for Pool<V, T, S> {
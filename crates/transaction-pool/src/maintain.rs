@@ -15,7 +15,7 @@ use reth_fs_util::FsPathError;
 use reth_primitives::{
     Address, BlockHash, BlockNumber, BlockNumberOrTag, FromRecoveredPooledTransaction,
     IntoRecoveredTransaction, PooledTransactionsElementEcRecovered, TransactionSigned,
-    TryFromRecoveredTransaction,
+    TransactionSignedEcRecovered, TryFromRecoveredTransaction,
 };
 use reth_provider::{
     BlockReaderIdExt, BundleStateWithReceipts, CanonStateNotification, ChainSpecProvider,
@@ -27,6 +27,7 @@ use std::{
     collections::HashSet,
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
 use tokio::sync::oneshot;
 use tracing::{debug, error, info, trace, warn};
@@ -43,11 +44,37 @@ pub struct MaintainPoolConfig {
     ///
     /// Default: 100
     pub max_reload_accounts: usize,
+    /// Minimum interval between two sweeps for queued transactions that exceeded their lifetime.
+    ///
+    /// Default: 60s
+    pub queued_expiry_interval: Duration,
+    /// Number of blocks below the finalized block for which the sidecars of mined blob
+    /// transactions are still retained in the blob store.
+    ///
+    /// Default: 32 (1 epoch)
+    pub blob_finalization_buffer: u64,
+    /// Maximum number of blocks below the tip for which the sidecars of mined blob transactions
+    /// are retained if their block is not finalized yet, this bounds the blob store if the chain
+    /// stops finalizing.
+    ///
+    /// Default: 8192
+    pub blob_retention_window: u64,
+    /// Maximum number of sidecars that are deleted from the blob store at once.
+    ///
+    /// Default: 256
+    pub blob_cleanup_batch_size: usize,
 }
 
 impl Default for MaintainPoolConfig {
     fn default() -> Self {
-        Self { max_update_depth: 64, max_reload_accounts: 100 }
+        Self {
+            max_update_depth: 64,
+            max_reload_accounts: 100,
+            queued_expiry_interval: Duration::from_secs(60),
+            blob_finalization_buffer: 32,
+            blob_retention_window: 8192,
+            blob_cleanup_batch_size: 256,
+        }
     }
 }
 
@@ -101,7 +128,14 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
     Tasks: TaskSpawner + 'static,
 {
     let metrics = MaintainPoolMetrics::default();
-    let MaintainPoolConfig { max_update_depth, max_reload_accounts, .. } = config;
+    let MaintainPoolConfig {
+        max_update_depth,
+        max_reload_accounts,
+        queued_expiry_interval,
+        blob_finalization_buffer,
+        blob_retention_window,
+        blob_cleanup_batch_size,
+    } = config;
     // ensure the pool points to latest state
     if let Ok(Some(latest)) = client.header_by_number_or_tag(BlockNumberOrTag::Latest) {
         let latest = latest.seal_slow();
@@ -112,7 +146,8 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
             pending_basefee: latest
                 .next_block_base_fee(chain_spec.base_fee_params_at_timestamp(latest.timestamp + 12))
                 .unwrap_or_default(),
-            pending_blob_fee: latest.next_block_blob_fee(),
+            pending_blob_fee: latest
+                .next_block_blob_fee(chain_spec.blob_params_at_timestamp(latest.timestamp + 12)),
         };
         pool.set_block_info(info);
     }
@@ -133,6 +168,9 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
     // the future that reloads accounts from state
     let mut reload_accounts_fut = Fuse::terminated();
 
+    // keeps track of the last sweep for expired queued transactions
+    let mut last_queued_expiry = Instant::now();
+
     // The update loop that waits for new blocks and reorgs and performs pool updated
     // Listen for new chain events and derive the update action for the pool
     loop {
@@ -187,14 +225,11 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
         if let Some(finalized) =
             last_finalized_block.update(client.finalized_block_number().ok().flatten())
         {
-            match blob_store_tracker.on_finalized_block(finalized) {
-                BlobStoreUpdates::None => {}
-                BlobStoreUpdates::Finalized(blobs) => {
-                    metrics.inc_deleted_tracked_blobs(blobs.len());
-                    // remove all finalized blobs from the blob store
-                    pool.delete_blobs(blobs);
-                }
-            }
+            // the sidecars of mined blob transactions are retained until their block is finalized
+            // plus the buffer
+            let updates = blob_store_tracker
+                .on_finalized_block(finalized.saturating_sub(blob_finalization_buffer));
+            apply_blob_store_updates(&pool, updates, blob_cleanup_batch_size, &metrics);
             // also do periodic cleanup of the blob store
             let pool = pool.clone();
             task_spawner.spawn_blocking(Box::pin(async move {
@@ -203,6 +238,19 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
             }));
         }
 
+        // evict queued transactions that exceeded their lifetime, checked on every new block
+        if last_queued_expiry.elapsed() >= queued_expiry_interval {
+            let expired = pool.remove_expired_queued_transactions();
+            trace!(target: "txpool", count=expired.len(), "evicted expired queued transactions");
+            last_queued_expiry = Instant::now();
+        }
+
+        // if the chain doesn't finalize, the sidecars of mined blob transactions are only retained
+        // for the retention window
+        let updates =
+            blob_store_tracker.on_new_tip(pool_info.last_seen_block_number, blob_retention_window);
+        apply_blob_store_updates(&pool, updates, blob_cleanup_batch_size, &metrics);
+
         // outcomes of the futures we are waiting on
         let mut event = None;
         let mut reloaded = None;
@@ -270,7 +318,9 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                         chain_spec.base_fee_params_at_timestamp(new_tip.timestamp + 12),
                     )
                     .unwrap_or_default();
-                let pending_block_blob_fee = new_tip.next_block_blob_fee();
+                let pending_block_blob_fee = new_tip.next_block_blob_fee(
+                    chain_spec.blob_params_at_timestamp(new_tip.timestamp + 12),
+                );
 
                 // we know all changed account in the new chain
                 let new_changed_accounts: HashSet<_> =
@@ -313,35 +363,13 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
 
                 // update the pool then re-inject the pruned transactions
                 // find all transactions that were mined in the old chain but not in the new chain
-                let pruned_old_transactions = old_blocks
-                    .transactions_ecrecovered()
-                    .filter(|tx| !new_mined_transactions.contains(&tx.hash))
-                    .filter_map(|tx| {
-                        if tx.is_eip4844() {
-                            // reorged blobs no longer include the blob, which is necessary for
-                            // validating the transaction. Even though the transaction could have
-                            // been validated previously, we still need the blob in order to
-                            // accurately set the transaction's
-                            // encoded-length which is propagated over the network.
-                            pool.get_blob(tx.hash)
-                                .ok()
-                                .flatten()
-                                .and_then(|sidecar| {
-                                    PooledTransactionsElementEcRecovered::try_from_blob_transaction(
-                                        tx, sidecar,
-                                    )
-                                    .ok()
-                                })
-                                .map(
-                                    <P as TransactionPool>::Transaction::from_recovered_pooled_transaction,
-                                )
-                        } else {
-                            <P as TransactionPool>::Transaction::try_from_recovered_transaction(
-                                tx,
-                            ).ok()
-                        }
-                    })
-                    .collect::<Vec<_>>();
+                let pruned_old_transactions = reorged_pool_transactions(
+                    &pool,
+                    old_blocks
+                        .transactions_ecrecovered()
+                        .filter(|tx| !new_mined_transactions.contains(&tx.hash)),
+                    &metrics,
+                );
 
                 // update the pool first
                 let update = CanonicalStateUpdate {
@@ -363,6 +391,10 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 metrics.inc_reinserted_transactions(pruned_old_transactions.len());
                 let _ = pool.add_external_transactions(pruned_old_transactions).await;
 
+                // the blob transactions of the old chain are back in the pool, so their sidecars
+                // must not be deleted together with the old blocks
+                blob_store_tracker.remove_blocks(old_blocks.iter().map(|(num, _)| *num));
+
                 // keep track of new mined blob transactions
                 blob_store_tracker.add_new_chain_blocks(&new_blocks);
             }
@@ -377,7 +409,8 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                         chain_spec.base_fee_params_at_timestamp(tip.timestamp + 12),
                     )
                     .unwrap_or_default();
-                let pending_block_blob_fee = tip.next_block_blob_fee();
+                let pending_block_blob_fee = tip
+                    .next_block_blob_fee(chain_spec.blob_params_at_timestamp(tip.timestamp + 12));
 
                 let first_block = blocks.first();
                 trace!(
@@ -442,6 +475,72 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
     }
 }
 
+/// Converts the transactions of reorged out blocks into pool transactions, so they can be
+/// re-injected into the pool.
+///
+/// Mined blob transactions no longer include their sidecar, which is necessary for validating the
+/// transaction and for its encoded length that is propagated over the network. The sidecars of
+/// mined blob transactions are retained in the blob store until their block is finalized, blob
+/// transactions whose sidecar can't be recovered from there are dropped.
+fn reorged_pool_transactions<P>(
+    pool: &P,
+    transactions: impl IntoIterator<Item = TransactionSignedEcRecovered>,
+    metrics: &MaintainPoolMetrics,
+) -> Vec<<P as TransactionPool>::Transaction>
+where
+    P: TransactionPool,
+{
+    transactions
+        .into_iter()
+        .filter_map(|tx| {
+            if tx.is_eip4844() {
+                let hash = tx.hash;
+                let transaction = pool.get_blob(hash).ok().flatten().and_then(|sidecar| {
+                    PooledTransactionsElementEcRecovered::try_from_blob_transaction(tx, sidecar)
+                        .ok()
+                });
+                if transaction.is_none() {
+                    debug!(target: "txpool", ?hash, "dropping reorged blob transaction without sidecar");
+                    metrics.inc_dropped_reorged_blob_transactions(1);
+                }
+                transaction
+                    .map(<P as TransactionPool>::Transaction::from_recovered_pooled_transaction)
+            } else {
+                <P as TransactionPool>::Transaction::try_from_recovered_transaction(tx).ok()
+            }
+        })
+        .collect()
+}
+
+/// Deletes the blobs of the given [`BlobStoreUpdates`] from the blob store, in batches of at most
+/// `batch_size` blobs.
+fn apply_blob_store_updates<P>(
+    pool: &P,
+    updates: BlobStoreUpdates,
+    batch_size: usize,
+    metrics: &MaintainPoolMetrics,
+) where
+    P: TransactionPoolExt,
+{
+    match updates {
+        BlobStoreUpdates::None => {}
+        BlobStoreUpdates::Finalized(blobs) => {
+            metrics.inc_finalized_cleanup_deleted(blobs.len());
+            // remove all finalized blobs from the blob store
+            for batch in blobs.chunks(batch_size.max(1)) {
+                pool.delete_blobs(batch.to_vec());
+            }
+        }
+        BlobStoreUpdates::BeyondRetentionWindow(blobs) => {
+            metrics.inc_deleted_retained_blobs(blobs.len());
+            // these transactions can no longer be re-injected after a reorg
+            for batch in blobs.chunks(batch_size.max(1)) {
+                pool.delete_blobs(batch.to_vec());
+            }
+        }
+    }
+}
+
 struct FinalizedBlockTracker {
     last_finalized_block: Option<BlockNumber>,
 }
@@ -590,10 +689,7 @@ where
     let pool_transactions = txs_signed
         .into_iter()
         .filter_map(|tx| tx.try_ecrecovered())
-        .filter_map(|tx| {
-            // Filter out errors
-            <P as TransactionPool>::Transaction::try_from_recovered_transaction(tx).ok()
-        })
+        .filter_map(|tx| recovered_into_pool_transaction(&pool, tx))
         .collect::<Vec<_>>();
 
     let outcome = pool.add_transactions(crate::TransactionOrigin::Local, pool_transactions).await;
@@ -603,6 +699,31 @@ where
     Ok(())
 }
 
+/// Converts a persisted transaction into a pool transaction.
+///
+/// Blob transactions are persisted without their sidecar, which is kept in the blob store if it
+/// was re-indexed. Returns `None` if the transaction can't be converted or its sidecar is missing.
+pub(crate) fn recovered_into_pool_transaction<P>(
+    pool: &P,
+    tx: TransactionSignedEcRecovered,
+) -> Option<P::Transaction>
+where
+    P: TransactionPool,
+{
+    if tx.is_eip4844() {
+        pool.get_blob(tx.hash)
+            .ok()
+            .flatten()
+            .and_then(|sidecar| {
+                PooledTransactionsElementEcRecovered::try_from_blob_transaction(tx, sidecar).ok()
+            })
+            .map(<P as TransactionPool>::Transaction::from_recovered_pooled_transaction)
+    } else {
+        // Filter out errors
+        <P as TransactionPool>::Transaction::try_from_recovered_transaction(tx).ok()
+    }
+}
+
 fn save_local_txs_backup<P>(pool: P, file_path: &Path)
 where
     P: TransactionPool,
@@ -655,7 +776,7 @@ pub async fn backup_local_transactions_task<P>(
     pool: P,
     config: LocalTransactionBackupConfig,
 ) where
-    P: TransactionPool + Clone,
+    P: TransactionPoolExt + Clone,
 {
     let Some(transactions_path) = config.transactions_path else {
         // nothing to do
@@ -666,6 +787,10 @@ pub async fn backup_local_transactions_task<P>(
         error!(target: "txpool", "{}", err)
     }
 
+    // remove the blobs of a re-indexed blob store that are not referenced by the reinserted
+    // transactions
+    pool.cleanup_blobs();
+
     let graceful_guard = shutdown.await;
 
     // write transactions to disk
@@ -678,11 +803,13 @@ pub async fn backup_local_transactions_task<P>(
 mod tests {
     use super::*;
     use crate::{
-        blobstore::InMemoryBlobStore, validate::EthTransactionValidatorBuilder,
+        blobstore::InMemoryBlobStore,
+        test_utils::{testing_pool, MockTransaction},
+        validate::EthTransactionValidatorBuilder,
         CoinbaseTipOrdering, EthPooledTransaction, Pool, PoolTransaction, TransactionOrigin,
     };
     use reth_fs_util as fs;
-    use reth_primitives::{hex, PooledTransactionsElement, MAINNET, U256};
+    use reth_primitives::{hex, PooledTransactionsElement, SealedBlock, MAINNET, U256};
     use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
     use reth_tasks::TaskManager;
 
@@ -746,4 +873,104 @@ mod tests {
 
         temp_dir.close().unwrap();
     }
+
+    #[tokio::test]
+    async fn reinject_reorged_blob_transaction() {
+        let pool = testing_pool();
+        let metrics = MaintainPoolMetrics::default();
+        let mut new_txs = pool.new_transactions_listener();
+
+        let blob_tx = MockTransaction::eip4844();
+        pool.add_external_transaction(blob_tx.clone()).await.unwrap();
+        assert_eq!(*new_txs.try_recv().unwrap().transaction.hash(), blob_tx.get_hash());
+
+        // the transaction is mined, its sidecar is retained in the blob store
+        let tip = SealedBlock::default();
+        pool.on_canonical_state_change(CanonicalStateUpdate {
+            new_tip: &tip,
+            pending_block_base_fee: 0,
+            pending_block_blob_fee: None,
+            changed_accounts: vec![],
+            mined_transactions: vec![blob_tx.get_hash()],
+        });
+        assert!(!pool.contains(&blob_tx.get_hash()));
+        assert!(pool.get_blob(blob_tx.get_hash()).unwrap().is_some());
+
+        // the block is reorged out, together with a blob transaction whose sidecar is unknown
+        let unknown_blob_tx = MockTransaction::eip4844();
+        let reorged = reorged_pool_transactions(
+            &pool,
+            [blob_tx.to_recovered_transaction(), unknown_blob_tx.to_recovered_transaction()],
+            &metrics,
+        );
+        assert_eq!(reorged.len(), 1);
+        pool.add_external_transactions(reorged).await;
+
+        // the transaction is back in the pool and announced to peers again
+        assert!(pool.contains(&blob_tx.get_hash()));
+        let event = new_txs.try_recv().unwrap();
+        assert_eq!(*event.transaction.hash(), blob_tx.get_hash());
+        assert!(event.transaction.propagate);
+        assert!(!pool.contains(&unknown_blob_tx.get_hash()));
+    }
+
+    #[tokio::test]
+    async fn retain_blob_sidecars_until_finalized() {
+        let pool = testing_pool();
+        let metrics = MaintainPoolMetrics::default();
+        let MaintainPoolConfig {
+            max_update_depth,
+            blob_finalization_buffer,
+            blob_retention_window,
+            blob_cleanup_batch_size,
+            ..
+        } = MaintainPoolConfig::default();
+        let mut tracker = BlobStoreCanonTracker::default();
+
+        let blob_tx = MockTransaction::eip4844();
+        let hash = blob_tx.get_hash();
+        pool.add_external_transaction(blob_tx.clone()).await.unwrap();
+
+        // the transaction is mined in block 1
+        let tip = SealedBlock::default();
+        let mined = || CanonicalStateUpdate {
+            new_tip: &tip,
+            pending_block_base_fee: 0,
+            pending_block_blob_fee: None,
+            changed_accounts: vec![],
+            mined_transactions: vec![hash],
+        };
+        pool.on_canonical_state_change(mined());
+        tracker.add_block(1, [hash]);
+
+        // block 1 is finalized but within the buffer, and the tip is beyond the reorg depth
+        let updates = tracker.on_finalized_block(1u64.saturating_sub(blob_finalization_buffer));
+        apply_blob_store_updates(&pool, updates, blob_cleanup_batch_size, &metrics);
+        let updates = tracker.on_new_tip(2 + max_update_depth, blob_retention_window);
+        apply_blob_store_updates(&pool, updates, blob_cleanup_batch_size, &metrics);
+        assert!(pool.get_blob(hash).unwrap().is_some());
+
+        // block 1 is reorged out and the transaction is re-injected with its sidecar
+        let reorged =
+            reorged_pool_transactions(&pool, [blob_tx.to_recovered_transaction()], &metrics);
+        assert_eq!(reorged.len(), 1);
+        pool.add_external_transactions(reorged).await;
+        tracker.remove_blocks([1]);
+        assert!(pool.contains(&hash));
+
+        // finalizing past the reorged out block doesn't delete the sidecar of the pooled
+        // transaction
+        assert_eq!(tracker.on_finalized_block(1), BlobStoreUpdates::None);
+        assert!(pool.get_blob(hash).unwrap().is_some());
+
+        // the transaction is mined again in block 2, which is finalized beyond the buffer
+        pool.on_canonical_state_change(mined());
+        tracker.add_block(2, [hash]);
+        let finalized = 2 + blob_finalization_buffer;
+        let updates =
+            tracker.on_finalized_block(finalized.saturating_sub(blob_finalization_buffer));
+        assert_eq!(updates, BlobStoreUpdates::Finalized(vec![hash]));
+        apply_blob_store_updates(&pool, updates, blob_cleanup_batch_size, &metrics);
+        assert!(pool.get_blob(hash).unwrap().is_none());
+    }
 }
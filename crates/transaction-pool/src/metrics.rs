@@ -15,6 +15,8 @@ pub struct TxPoolMetrics {
     pub(crate) invalid_transactions: Counter,
     /// Number of removed transactions from the pool
     pub(crate) removed_transactions: Counter,
+    /// Number of queued transactions removed from the pool because they exceeded their lifetime
+    pub(crate) expired_transactions: Counter,
 
     /// Number of transactions in the pending sub-pool
     pub(crate) pending_pool_transactions: Gauge,
@@ -52,11 +54,23 @@ pub struct BlobStoreMetrics {
     /// Number of failed deletes into the blobstore
     pub(crate) blobstore_failed_deletes: Counter,
     /// The number of bytes the blobs in the blobstore take up
-    pub(crate) blobstore_byte_size: Gauge,
+    pub(crate) blobstore_size_bytes: Gauge,
     /// How many blobs are currently in the blobstore
     pub(crate) blobstore_entries: Gauge,
 }
 
+/// Disk file blobstore metrics
+#[derive(Metrics)]
+#[metrics(scope = "transaction_pool")]
+pub struct DiskFileBlobStoreMetrics {
+    /// The number of bytes the blobs stored on disk take up
+    pub(crate) blobstore_disk_stored_bytes: Gauge,
+    /// Number of blobs that were evicted because the blobstore exceeded its max size
+    pub(crate) blobstore_evictions: Counter,
+    /// Number of lookups of blobs that are not in the blobstore
+    pub(crate) blobstore_failed_lookups: Counter,
+}
+
 /// Transaction pool maintenance metrics
 #[derive(Metrics)]
 #[metrics(scope = "transaction_pool")]
@@ -68,8 +82,15 @@ pub struct MaintainPoolMetrics {
     pub(crate) drift_count: Counter,
     /// Number of transaction reinserted into the pool after reorg.
     pub(crate) reinserted_transactions: Counter,
-    /// Number of transactions finalized blob transactions we were tracking.
-    pub(crate) deleted_tracked_finalized_blobs: Counter,
+    /// Number of sidecars of mined blob transactions that were deleted from the blob store after
+    /// their block was finalized.
+    pub(crate) finalized_cleanup_deleted: Counter,
+    /// Number of sidecars of mined blob transactions that were deleted from the blob store because
+    /// their block is beyond the retention window.
+    pub(crate) deleted_tracked_retained_blobs: Counter,
+    /// Number of blob transactions of reorged out blocks that were dropped because their sidecar
+    /// could not be recovered.
+    pub(crate) dropped_reorged_blob_transactions: Counter,
 }
 
 impl MaintainPoolMetrics {
@@ -84,8 +105,18 @@ impl MaintainPoolMetrics {
     }
 
     #[inline]
-    pub(crate) fn inc_deleted_tracked_blobs(&self, count: usize) {
-        self.deleted_tracked_finalized_blobs.increment(count as u64);
+    pub(crate) fn inc_finalized_cleanup_deleted(&self, count: usize) {
+        self.finalized_cleanup_deleted.increment(count as u64);
+    }
+
+    #[inline]
+    pub(crate) fn inc_deleted_retained_blobs(&self, count: usize) {
+        self.deleted_tracked_retained_blobs.increment(count as u64);
+    }
+
+    #[inline]
+    pub(crate) fn inc_dropped_reorged_blob_transactions(&self, count: usize) {
+        self.dropped_reorged_blob_transactions.increment(count as u64);
     }
 
     #[inline]
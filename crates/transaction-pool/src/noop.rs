@@ -13,11 +13,14 @@ use crate::{
     validate::ValidTransaction,
     AllPoolTransactions, AllTransactionsEvents, BestTransactions, BlockInfo, EthPoolTransaction,
     EthPooledTransaction, NewTransactionEvent, PoolResult, PoolSize, PoolTransaction,
-    PooledTransactionsElement, PropagatedTransactions, TransactionEvents, TransactionOrigin,
-    TransactionPool, TransactionValidationOutcome, TransactionValidator, ValidPoolTransaction,
+    PooledTransactionsElement, PropagatedTransactions, TransactionEvents, TransactionFilter,
+    TransactionOrigin, TransactionPool, TransactionValidationOutcome, TransactionValidator,
+    ValidPoolTransaction,
 };
 use reth_eth_wire_types::HandleMempoolData;
-use reth_primitives::{Address, BlobTransactionSidecar, TxHash, U256};
+use reth_primitives::{
+    Address, BlobAndProofV1, BlobTransactionSidecar, TransactionConditional, TxHash, B256, U256,
+};
 use std::{collections::HashSet, marker::PhantomData, sync::Arc};
 use tokio::sync::{mpsc, mpsc::Receiver};
 
@@ -63,6 +66,16 @@ impl TransactionPool for NoopTransactionPool {
         Err(PoolError::other(hash, Box::new(NoopInsertError::new(transaction))))
     }
 
+    async fn add_transaction_with_conditional(
+        &self,
+        _origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        _conditional: TransactionConditional,
+    ) -> PoolResult<TxHash> {
+        let hash = *transaction.hash();
+        Err(PoolError::other(hash, Box::new(NoopInsertError::new(transaction))))
+    }
+
     async fn add_transactions(
         &self,
         _origin: TransactionOrigin,
@@ -85,6 +98,13 @@ impl TransactionPool for NoopTransactionPool {
         AllTransactionsEvents::new(mpsc::channel(1).1)
     }
 
+    fn transaction_event_listener_filtered(
+        &self,
+        _filter: TransactionFilter,
+    ) -> AllTransactionsEvents<Self::Transaction> {
+        AllTransactionsEvents::new(mpsc::channel(1).1)
+    }
+
     fn pending_transactions_listener_for(
         &self,
         _kind: TransactionListenerKind,
@@ -218,6 +238,13 @@ impl TransactionPool for NoopTransactionPool {
         vec![]
     }
 
+    fn pooled_transactions_matching(
+        &self,
+        _filter: &TransactionFilter,
+    ) -> AllPoolTransactions<Self::Transaction> {
+        AllPoolTransactions::default()
+    }
+
     fn unique_senders(&self) -> HashSet<Address> {
         Default::default()
     }
@@ -242,6 +269,13 @@ impl TransactionPool for NoopTransactionPool {
         }
         Err(BlobStoreError::MissingSidecar(tx_hashes[0]))
     }
+
+    fn get_blobs_for_versioned_hashes(
+        &self,
+        versioned_hashes: &[B256],
+    ) -> Result<Vec<Option<BlobAndProofV1>>, BlobStoreError> {
+        Ok(vec![None; versioned_hashes.len()])
+    }
 }
 
 /// A [`TransactionValidator`] that does nothing.
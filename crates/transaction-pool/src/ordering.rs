@@ -24,6 +24,16 @@ impl<T: Ord + Clone> From<Option<T>> for Priority<T> {
 /// Decides how transactions should be ordered within the pool, depending on a `Priority` value.
 ///
 /// The returned priority must reflect [total order](https://en.wikipedia.org/wiki/Total_order).
+///
+/// The ordering determines the order of the pending subpool and therefore the order in which
+/// [`best_transactions`](crate::TransactionPool::best_transactions) yields transactions to the
+/// payload builder. An ordering must uphold the following invariants:
+///
+/// - The priority only depends on the transaction and the base fee. The pool caches priorities and
+///   only recomputes them when the base fee changes.
+/// - The priority is monotonic along the nonce chain of a sender: a transaction must not rank
+///   higher than its same-sender predecessor. The pool only yields a sender's transactions in nonce
+///   order, so a descendant that ranks higher is held back by its ancestor anyway.
 pub trait TransactionOrdering: Send + Sync + 'static {
     /// Priority of a transaction.
     ///
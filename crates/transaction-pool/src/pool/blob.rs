@@ -118,6 +118,11 @@ impl<T: PoolTransaction> BlobTransactions<T> {
         transactions
     }
 
+    /// Returns an iterator over all transactions in the pool
+    pub(crate) fn all(&self) -> impl Iterator<Item = Arc<ValidPoolTransaction<T>>> + '_ {
+        self.by_id.values().map(|tx| tx.transaction.clone())
+    }
+
     /// Returns true if the pool exceeds the given limit
     #[inline]
     pub(crate) fn exceeds(&self, limit: &SubPoolLimit) -> bool {
@@ -28,8 +28,13 @@ pub enum FullTransactionEvent<T: PoolTransaction> {
         /// The transaction that replaced the event subject.
         replaced_by: TxHash,
     },
-    /// Transaction was dropped due to configured limits.
-    Discarded(TxHash),
+    /// Transaction was dropped from the pool.
+    Discarded {
+        /// The hash of the discarded transaction.
+        tx_hash: TxHash,
+        /// Why the transaction was discarded.
+        reason: DiscardReason,
+    },
     /// Transaction became invalid indefinitely.
     Invalid(TxHash),
     /// Transaction was propagated to peers.
@@ -47,7 +52,9 @@ impl<T: PoolTransaction> Clone for FullTransactionEvent<T> {
             Self::Replaced { transaction, replaced_by } => {
                 Self::Replaced { transaction: Arc::clone(transaction), replaced_by: *replaced_by }
             }
-            Self::Discarded(hash) => Self::Discarded(*hash),
+            Self::Discarded { tx_hash, reason } => {
+                Self::Discarded { tx_hash: *tx_hash, reason: *reason }
+            }
             Self::Invalid(hash) => Self::Invalid(*hash),
             Self::Propagated(propagated) => Self::Propagated(Arc::clone(propagated)),
         }
@@ -68,8 +75,8 @@ pub enum TransactionEvent {
     ///
     /// E.g. same (sender + nonce) pair
     Replaced(TxHash),
-    /// Transaction was dropped due to configured limits.
-    Discarded,
+    /// Transaction was dropped from the pool.
+    Discarded(DiscardReason),
     /// Transaction became invalid indefinitely.
     Invalid,
     /// Transaction was propagated to peers.
@@ -80,6 +87,16 @@ impl TransactionEvent {
     /// Returns `true` if the event is final and no more events are expected for this transaction
     /// hash.
     pub const fn is_final(&self) -> bool {
-        matches!(self, Self::Replaced(_) | Self::Mined(_) | Self::Discarded)
+        matches!(self, Self::Replaced(_) | Self::Mined(_) | Self::Discarded(_))
     }
 }
+
+/// The reason a transaction was discarded from the pool.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DiscardReason {
+    /// Transaction was dropped due to configured limits.
+    Capacity,
+    /// Transaction stayed in the queued sub-pool for longer than its configured lifetime.
+    Timeout,
+}
@@ -0,0 +1,91 @@
+//! Filters for transaction events.
+
+use crate::PoolTransaction;
+use reth_primitives::Address;
+use std::collections::HashSet;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A filter that selects pool transactions by sender, recipient and minimum effective tip.
+///
+/// An empty sender or recipient set matches any address, so the default filter matches all
+/// transactions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", default))]
+pub struct TransactionFilter {
+    /// Only match transactions sent by one of these addresses.
+    pub senders: HashSet<Address>,
+    /// Only match transactions sent to one of these addresses.
+    ///
+    /// Contract creations never match a non-empty recipient set.
+    pub recipients: HashSet<Address>,
+    /// Only match transactions with at least this effective tip per gas.
+    pub min_tip: Option<u128>,
+}
+
+impl TransactionFilter {
+    /// Sets the senders to match.
+    pub fn with_senders(mut self, senders: impl IntoIterator<Item = Address>) -> Self {
+        self.senders = senders.into_iter().collect();
+        self
+    }
+
+    /// Sets the recipients to match.
+    pub fn with_recipients(mut self, recipients: impl IntoIterator<Item = Address>) -> Self {
+        self.recipients = recipients.into_iter().collect();
+        self
+    }
+
+    /// Sets the minimum effective tip per gas to match.
+    pub const fn with_min_tip(mut self, min_tip: u128) -> Self {
+        self.min_tip = Some(min_tip);
+        self
+    }
+
+    /// Returns `true` if the transaction matches this filter, computing the effective tip with
+    /// the given base fee.
+    pub fn matches<T: PoolTransaction>(&self, transaction: &T, base_fee: u64) -> bool {
+        if !self.senders.is_empty() && !self.senders.contains(&transaction.sender()) {
+            return false
+        }
+
+        if !self.recipients.is_empty() &&
+            !transaction.to().is_some_and(|to| self.recipients.contains(&to))
+        {
+            return false
+        }
+
+        if let Some(min_tip) = self.min_tip {
+            return transaction.effective_tip_per_gas(base_fee).is_some_and(|tip| tip >= min_tip)
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockTransaction;
+
+    #[test]
+    fn match_sender_recipient_and_tip() {
+        let tx = MockTransaction::eip1559().with_max_fee(100).with_priority_fee(10);
+        let base_fee = 95;
+
+        assert!(TransactionFilter::default().matches(&tx, base_fee));
+        assert!(TransactionFilter::default().with_senders([tx.sender()]).matches(&tx, base_fee));
+        assert!(!TransactionFilter::default()
+            .with_senders([Address::random()])
+            .matches(&tx, base_fee));
+        assert!(!TransactionFilter::default()
+            .with_recipients([Address::random()])
+            .matches(&tx, base_fee));
+
+        // the effective tip is capped by the max fee
+        assert!(TransactionFilter::default().with_min_tip(5).matches(&tx, base_fee));
+        assert!(!TransactionFilter::default().with_min_tip(6).matches(&tx, base_fee));
+    }
+}
@@ -1,14 +1,17 @@
 //! Listeners for the transaction-pool
 
 use crate::{
-    pool::events::{FullTransactionEvent, TransactionEvent},
+    pool::{
+        events::{DiscardReason, FullTransactionEvent, TransactionEvent},
+        filter::TransactionFilter,
+    },
     traits::PropagateKind,
     PoolTransaction, ValidPoolTransaction,
 };
 use futures_util::Stream;
 use reth_primitives::{TxHash, B256};
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -76,24 +79,33 @@ impl<T: PoolTransaction> Stream for AllTransactionsEvents<T> {
 pub(crate) struct PoolEventBroadcast<T: PoolTransaction> {
     /// All listeners for all transaction events.
     all_events_broadcaster: AllPoolEventsBroadcaster<T>,
+    /// All listeners for events of transactions that match a filter.
+    filtered_listeners: Vec<FilteredPoolEventsListener<T>>,
     /// All listeners for events for a certain transaction hash.
     broadcasters_by_hash: HashMap<TxHash, PoolEventBroadcaster>,
+    /// The pending base fee used to compute the effective tip for filtered listeners.
+    pending_basefee: u64,
 }
 
 impl<T: PoolTransaction> Default for PoolEventBroadcast<T> {
     fn default() -> Self {
         Self {
             all_events_broadcaster: AllPoolEventsBroadcaster::default(),
+            filtered_listeners: Vec::new(),
             broadcasters_by_hash: HashMap::default(),
+            pending_basefee: 0,
         }
     }
 }
 
 impl<T: PoolTransaction> PoolEventBroadcast<T> {
     /// Calls the broadcast callback with the `PoolEventBroadcaster` that belongs to the hash.
+    ///
+    /// The transaction, if known, is used to match the event against filtered listeners.
     fn broadcast_event(
         &mut self,
         hash: &TxHash,
+        transaction: Option<&ValidPoolTransaction<T>>,
         event: TransactionEvent,
         pool_event: FullTransactionEvent<T>,
     ) {
@@ -106,10 +118,22 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
             }
         }
 
+        // Broadcast to all listeners that track or match the transaction.
+        let pending_basefee = self.pending_basefee;
+        self.filtered_listeners.retain_mut(|listener| {
+            listener.on_event(hash, transaction, &event, &pool_event, pending_basefee)
+        });
+
         // Broadcast to all listeners for all transactions.
         self.all_events_broadcaster.broadcast(pool_event);
     }
 
+    /// Sets the pending base fee used to compute the effective tip of transactions for filtered
+    /// listeners.
+    pub(crate) fn set_pending_basefee(&mut self, pending_basefee: u64) {
+        self.pending_basefee = pending_basefee;
+    }
+
     /// Create a new subscription for the given transaction hash.
     pub(crate) fn subscribe(&mut self, tx_hash: TxHash) -> TransactionEvents {
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
@@ -132,13 +156,37 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
         AllTransactionsEvents::new(rx)
     }
 
+    /// Create a new subscription for all transactions that match the given filter.
+    ///
+    /// The `matched` hashes are transactions that already match the filter, their events are
+    /// delivered until they are final.
+    pub(crate) fn subscribe_filtered(
+        &mut self,
+        filter: TransactionFilter,
+        matched: HashSet<TxHash>,
+    ) -> AllTransactionsEvents<T> {
+        let (sender, rx) = tokio::sync::mpsc::channel(TX_POOL_EVENT_CHANNEL_SIZE);
+        self.filtered_listeners.push(FilteredPoolEventsListener { filter, matched, sender });
+        AllTransactionsEvents::new(rx)
+    }
+
     /// Notify listeners about a transaction that was added to the pending queue.
-    pub(crate) fn pending(&mut self, tx: &TxHash, replaced: Option<Arc<ValidPoolTransaction<T>>>) {
-        self.broadcast_event(tx, TransactionEvent::Pending, FullTransactionEvent::Pending(*tx));
+    pub(crate) fn pending(
+        &mut self,
+        tx: &ValidPoolTransaction<T>,
+        replaced: Option<Arc<ValidPoolTransaction<T>>>,
+    ) {
+        let hash = *tx.hash();
+        self.broadcast_event(
+            &hash,
+            Some(tx),
+            TransactionEvent::Pending,
+            FullTransactionEvent::Pending(hash),
+        );
 
         if let Some(replaced) = replaced {
             // notify listeners that this transaction was replaced
-            self.replaced(replaced, *tx);
+            self.replaced(replaced, hash);
         }
     }
 
@@ -147,14 +195,21 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
         let transaction = Arc::clone(&tx);
         self.broadcast_event(
             tx.hash(),
+            Some(tx.as_ref()),
             TransactionEvent::Replaced(replaced_by),
             FullTransactionEvent::Replaced { transaction, replaced_by },
         );
     }
 
     /// Notify listeners about a transaction that was added to the queued pool.
-    pub(crate) fn queued(&mut self, tx: &TxHash) {
-        self.broadcast_event(tx, TransactionEvent::Queued, FullTransactionEvent::Queued(*tx));
+    pub(crate) fn queued(&mut self, tx: &ValidPoolTransaction<T>) {
+        let hash = *tx.hash();
+        self.broadcast_event(
+            &hash,
+            Some(tx),
+            TransactionEvent::Queued,
+            FullTransactionEvent::Queued(hash),
+        );
     }
 
     /// Notify listeners about a transaction that was propagated.
@@ -162,6 +217,7 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
         let peers = Arc::new(peers);
         self.broadcast_event(
             tx,
+            None,
             TransactionEvent::Propagated(Arc::clone(&peers)),
             FullTransactionEvent::Propagated(peers),
         );
@@ -169,13 +225,28 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
 
     /// Notify listeners about a transaction that was discarded.
     pub(crate) fn discarded(&mut self, tx: &TxHash) {
-        self.broadcast_event(tx, TransactionEvent::Discarded, FullTransactionEvent::Discarded(*tx));
+        self.discarded_with_reason(tx, DiscardReason::Capacity)
+    }
+
+    /// Notify listeners that the transaction was discarded because it exceeded its lifetime.
+    pub(crate) fn expired(&mut self, tx: &TxHash) {
+        self.discarded_with_reason(tx, DiscardReason::Timeout)
+    }
+
+    fn discarded_with_reason(&mut self, tx: &TxHash, reason: DiscardReason) {
+        self.broadcast_event(
+            tx,
+            None,
+            TransactionEvent::Discarded(reason),
+            FullTransactionEvent::Discarded { tx_hash: *tx, reason },
+        );
     }
 
     /// Notify listeners that the transaction was mined
     pub(crate) fn mined(&mut self, tx: &TxHash, block_hash: B256) {
         self.broadcast_event(
             tx,
+            None,
             TransactionEvent::Mined(block_hash),
             FullTransactionEvent::Mined { tx_hash: *tx, block_hash },
         );
@@ -207,6 +278,48 @@ impl<T: PoolTransaction> AllPoolEventsBroadcaster<T> {
     }
 }
 
+/// Sender half of the event channel for transactions that match a [`TransactionFilter`].
+#[derive(Debug)]
+struct FilteredPoolEventsListener<T: PoolTransaction> {
+    /// The filter transactions must match.
+    filter: TransactionFilter,
+    /// Transactions that matched the filter and whose events are delivered until they are final.
+    matched: HashSet<TxHash>,
+    /// Corresponding sender half for event listener channel
+    sender: Sender<FullTransactionEvent<T>>,
+}
+
+impl<T: PoolTransaction> FilteredPoolEventsListener<T> {
+    /// Delivers the event if the transaction was previously matched or matches the filter.
+    ///
+    /// Returns `false` if the listener was dropped.
+    fn on_event(
+        &mut self,
+        hash: &TxHash,
+        transaction: Option<&ValidPoolTransaction<T>>,
+        event: &TransactionEvent,
+        pool_event: &FullTransactionEvent<T>,
+        pending_basefee: u64,
+    ) -> bool {
+        let is_match = self.matched.contains(hash) ||
+            transaction.is_some_and(|tx| self.filter.matches(&tx.transaction, pending_basefee));
+        if !is_match {
+            return true
+        }
+
+        if event.is_final() {
+            self.matched.remove(hash);
+        } else {
+            self.matched.insert(*hash);
+        }
+
+        match self.sender.try_send(pool_event.clone()) {
+            Ok(_) | Err(TrySendError::Full(_)) => true,
+            Err(TrySendError::Closed(_)) => false,
+        }
+    }
+}
+
 /// All Sender half(s) of the event channels for a specific transaction.
 ///
 /// This mimics [`tokio::sync::broadcast`] but uses separate channels and is unbounded.
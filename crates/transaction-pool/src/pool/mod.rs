@@ -85,7 +85,7 @@ use parking_lot::{Mutex, RwLock, RwLockReadGuard};
 use reth_eth_wire_types::HandleMempoolData;
 use reth_primitives::{
     Address, BlobTransaction, BlobTransactionSidecar, IntoRecoveredTransaction,
-    PooledTransactionsElement, TransactionSigned, TxHash, B256,
+    PooledTransactionsElement, TransactionConditional, TransactionSigned, TxHash, B256,
 };
 use std::{
     collections::{HashMap, HashSet},
@@ -105,13 +105,15 @@ use crate::{
 };
 pub use best::BestTransactionFilter;
 pub use blob::{blob_tx_priority, fee_delta};
-pub use events::{FullTransactionEvent, TransactionEvent};
+pub use events::{DiscardReason, FullTransactionEvent, TransactionEvent};
+pub use filter::TransactionFilter;
 pub use listener::{AllTransactionsEvents, TransactionEvents};
 pub use parked::{BasefeeOrd, ParkedOrd, ParkedPool, QueuedOrd};
 pub use pending::PendingPool;
 
 mod best;
 mod blob;
+mod filter;
 mod listener;
 mod parked;
 pub(crate) mod pending;
@@ -191,7 +193,8 @@ where
     }
     /// Returns the currently tracked block
     pub(crate) fn set_block_info(&self, info: BlockInfo) {
-        self.pool.write().set_block_info(info)
+        self.pool.write().set_block_info(info);
+        self.event_listener.write().set_pending_basefee(info.pending_basefee);
     }
 
     /// Returns the internal `SenderId` for this address
@@ -276,6 +279,26 @@ where
         self.event_listener.write().subscribe_all()
     }
 
+    /// Adds a listener for events of transactions that match the given filter.
+    ///
+    /// Transactions already in the pool that match the filter are tracked as well, so that their
+    /// replacement, removal or inclusion is delivered.
+    pub(crate) fn add_filtered_transactions_event_listener(
+        &self,
+        filter: TransactionFilter,
+    ) -> AllTransactionsEvents<T::Transaction> {
+        let matched = {
+            let pool = self.get_pool_data();
+            let pending_basefee = pool.block_info().pending_basefee;
+            pool.all()
+                .transactions_iter()
+                .filter(|tx| filter.matches(&tx.transaction, pending_basefee))
+                .map(|tx| *tx.hash())
+                .collect()
+        };
+        self.event_listener.write().subscribe_filtered(filter, matched)
+    }
+
     /// Returns a read lock to the pool's data.
     pub(crate) fn get_pool_data(&self) -> RwLockReadGuard<'_, TxPool<T>> {
         self.pool.read()
@@ -379,6 +402,7 @@ where
         // update the pool
         let outcome = self.pool.write().on_canonical_state_change(
             block_info,
+            new_tip.timestamp,
             mined_transactions,
             changed_senders,
         );
@@ -386,6 +410,8 @@ where
         // This will discard outdated transactions based on the account's nonce
         self.delete_discarded_blobs(outcome.discarded.iter());
 
+        self.event_listener.write().set_pending_basefee(block_info.pending_basefee);
+
         // notify listeners about updates
         self.notify_on_new_state(outcome);
     }
@@ -399,7 +425,7 @@ where
             self.pool.write().update_accounts(changed_senders);
         let mut listener = self.event_listener.write();
 
-        promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
+        promoted.iter().for_each(|tx| listener.pending(tx, None));
         discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
 
         // This deletes outdated blob txs from the blob store, based on the account's nonce. This is
@@ -410,12 +436,15 @@ where
     /// Add a single validated transaction into the pool.
     ///
     /// Note: this is only used internally by [`Self::add_transactions()`], all new transaction(s)
-    /// come in through that function, either as a batch or `std::iter::once`.
+    /// come in through that function, either as a batch or `std::iter::once`. The listeners for
+    /// new transactions are notified by [`Self::add_transactions()`], once the pool size limits
+    /// are enforced.
     fn add_transaction(
         &self,
         origin: TransactionOrigin,
         tx: TransactionValidationOutcome<T::Transaction>,
-    ) -> PoolResult<TxHash> {
+        conditional: Option<Box<TransactionConditional>>,
+    ) -> PoolResult<AddedTransaction<T::Transaction>> {
         match tx {
             TransactionValidationOutcome::Valid {
                 balance,
@@ -444,6 +473,7 @@ where
                     propagate,
                     timestamp: Instant::now(),
                     origin,
+                    conditional,
                 };
 
                 let added = self.pool.write().add_transaction(tx, balance, state_nonce)?;
@@ -462,11 +492,6 @@ where
                     self.delete_blob(replaced);
                 }
 
-                // Notify about new pending transactions
-                if let Some(pending) = added.as_pending() {
-                    self.on_new_pending_transaction(pending);
-                }
-
                 // Notify tx event listeners
                 self.notify_event_listeners(&added);
 
@@ -474,10 +499,7 @@ where
                     self.delete_discarded_blobs(discarded.iter());
                 }
 
-                // Notify listeners for _all_ transactions
-                self.on_new_transaction(added.into_new_transaction_event());
-
-                Ok(hash)
+                Ok(added)
             }
             TransactionValidationOutcome::Invalid(tx, err) => {
                 let mut listener = self.event_listener.write();
@@ -512,33 +534,61 @@ where
         origin: TransactionOrigin,
         transactions: impl IntoIterator<Item = TransactionValidationOutcome<T::Transaction>>,
     ) -> Vec<PoolResult<TxHash>> {
-        let mut added =
-            transactions.into_iter().map(|tx| self.add_transaction(origin, tx)).collect::<Vec<_>>();
+        self.add_transactions_with_conditionals(
+            origin,
+            transactions.into_iter().map(|tx| (tx, None)),
+        )
+    }
+
+    /// Adds all transactions in the iterator to the pool, each with the conditions that must hold
+    /// for its inclusion, returning a list of results.
+    pub(crate) fn add_transactions_with_conditionals(
+        &self,
+        origin: TransactionOrigin,
+        transactions: impl IntoIterator<
+            Item = (
+                TransactionValidationOutcome<T::Transaction>,
+                Option<Box<TransactionConditional>>,
+            ),
+        >,
+    ) -> Vec<PoolResult<TxHash>> {
+        let added = transactions
+            .into_iter()
+            .map(|(tx, conditional)| self.add_transaction(origin, tx, conditional))
+            .collect::<Vec<_>>();
 
         // If at least one transaction was added successfully, then we enforce the pool size limits.
         let discarded =
             if added.iter().any(Result::is_ok) { self.discard_worst() } else { Default::default() };
 
-        if discarded.is_empty() {
-            return added
-        }
-
-        {
+        if !discarded.is_empty() {
             let mut listener = self.event_listener.write();
             discarded.iter().for_each(|tx| listener.discarded(tx));
         }
 
-        // It may happen that a newly added transaction is immediately discarded, so we need to
-        // adjust the result here
-        for res in &mut added {
-            if let Ok(hash) = res {
-                if discarded.contains(hash) {
-                    *res = Err(PoolError::new(*hash, PoolErrorKind::DiscardedOnInsert))
+        added
+            .into_iter()
+            .map(|res| {
+                let added = res?;
+                let hash = *added.hash();
+
+                // It may happen that a newly added transaction is immediately discarded, such a
+                // transaction is never announced to the listeners
+                if discarded.contains(&hash) {
+                    return Err(PoolError::new(hash, PoolErrorKind::DiscardedOnInsert))
                 }
-            }
-        }
 
-        added
+                // Notify about new pending transactions
+                if let Some(pending) = added.as_pending() {
+                    self.on_new_pending_transaction(pending);
+                }
+
+                // Notify listeners for _all_ transactions
+                self.on_new_transaction(added.into_new_transaction_event());
+
+                Ok(hash)
+            })
+            .collect()
     }
 
     /// Notify all listeners about a new pending transaction.
@@ -562,9 +612,9 @@ where
     fn on_new_transaction(&self, event: NewTransactionEvent<T::Transaction>) {
         let mut transaction_listeners = self.transaction_listener.lock();
         transaction_listeners.retain_mut(|listener| {
-            if listener.kind.is_propagate_only() && !event.transaction.propagate {
-                // only emit this hash to listeners that are only allowed to receive propagate only
-                // transactions, such as network
+            if !listener.kind.includes(&event.transaction) {
+                // only emit this transaction to listeners that are interested in it, e.g. the
+                // network only receives transactions that are allowed to be propagated
                 return !listener.sender.is_closed()
             }
 
@@ -620,7 +670,7 @@ where
         let mut listener = self.event_listener.write();
 
         mined.iter().for_each(|tx| listener.mined(tx, block_hash));
-        promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
+        promoted.iter().for_each(|tx| listener.pending(tx, None));
         discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
     }
 
@@ -632,15 +682,16 @@ where
             AddedTransaction::Pending(tx) => {
                 let AddedPendingTransaction { transaction, promoted, discarded, replaced } = tx;
 
-                listener.pending(transaction.hash(), replaced.clone());
-                promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
+                listener.pending(transaction, replaced.clone());
+                promoted.iter().for_each(|tx| listener.pending(tx, None));
                 discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
             }
-            AddedTransaction::Parked { transaction, replaced, .. } => {
-                listener.queued(transaction.hash());
+            AddedTransaction::Parked { transaction, replaced, discarded, .. } => {
+                listener.queued(transaction);
                 if let Some(replaced) = replaced {
                     listener.replaced(replaced.clone(), *transaction.hash());
                 }
+                discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
             }
         }
     }
@@ -733,6 +784,15 @@ where
         self.get_pool_data().all().transactions_iter().filter(|tx| tx.origin == origin).collect()
     }
 
+    /// Returns the pending and queued transactions that match the given [`TransactionFilter`], at
+    /// the pool's pending base fee.
+    pub(crate) fn pooled_transactions_matching(
+        &self,
+        filter: &TransactionFilter,
+    ) -> AllPoolTransactions<T::Transaction> {
+        self.get_pool_data().transactions_matching(filter)
+    }
+
     /// Returns all the transactions belonging to the hashes.
     ///
     /// If no transaction exists, it is skipped.
@@ -794,6 +854,23 @@ where
         self.update_blob_store_metrics();
     }
 
+    /// Removes all queued transactions that exceeded their lifetime at the given instant and
+    /// returns their hashes.
+    pub(crate) fn discard_expired_queued(&self, now: Instant) -> Vec<TxHash> {
+        let discarded = self.pool.write().discard_expired_queued(now);
+        if discarded.is_empty() {
+            return Vec::new()
+        }
+
+        let mut listener = self.event_listener.write();
+        discarded.iter().for_each(|tx| listener.expired(tx.hash()));
+        drop(listener);
+
+        self.delete_discarded_blobs(discarded.iter());
+        debug!(target: "txpool", count=discarded.len(), "Discarded expired queued transactions");
+        discarded.into_iter().map(|tx| *tx.hash()).collect()
+    }
+
     /// Delete a blob from the blob store
     pub(crate) fn delete_blob(&self, blob: TxHash) {
         let _ = self.blob_store.delete(blob);
@@ -802,6 +879,7 @@ where
     /// Delete all blobs from the blob store
     pub(crate) fn delete_blobs(&self, txs: Vec<TxHash>) {
         let _ = self.blob_store.delete_all(txs);
+        self.update_blob_store_metrics();
     }
 
     /// Cleans up the blob store
@@ -813,7 +891,7 @@ where
 
     fn update_blob_store_metrics(&self) {
         if let Some(data_size) = self.blob_store.data_size_hint() {
-            self.blob_store_metrics.blobstore_byte_size.set(data_size as f64);
+            self.blob_store_metrics.blobstore_size_bytes.set(data_size as f64);
         }
         self.blob_store_metrics.blobstore_entries.set(self.blob_store.blobs_len() as f64);
     }
@@ -967,7 +1045,7 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             let next = self.iter.next()?;
-            if self.kind.is_propagate_only() && !next.propagate {
+            if !self.kind.includes(next) {
                 continue
             }
             return Some(*next.hash())
@@ -991,7 +1069,7 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             let next = self.iter.next()?;
-            if self.kind.is_propagate_only() && !next.propagate {
+            if !self.kind.includes(next) {
                 continue
             }
             return Some(NewTransactionEvent {
@@ -1014,6 +1092,8 @@ pub enum AddedTransaction<T: PoolTransaction> {
         transaction: Arc<ValidPoolTransaction<T>>,
         /// Replaced transaction.
         replaced: Option<Arc<ValidPoolTransaction<T>>>,
+        /// Transactions of the same sender that were evicted to make room for this transaction.
+        discarded: Vec<Arc<ValidPoolTransaction<T>>>,
         /// The subpool it was moved to.
         subpool: SubPool,
     },
@@ -1040,7 +1120,7 @@ impl<T: PoolTransaction> AddedTransaction<T> {
     pub(crate) fn discarded_transactions(&self) -> Option<&[Arc<ValidPoolTransaction<T>>]> {
         match self {
             Self::Pending(tx) => Some(&tx.discarded),
-            Self::Parked { .. } => None,
+            Self::Parked { discarded, .. } => Some(discarded),
         }
     }
 
@@ -1133,12 +1213,19 @@ impl<T: PoolTransaction> OnNewCanonicalStateOutcome<T> {
 mod tests {
     use crate::{
         blobstore::{BlobStore, InMemoryBlobStore},
+        error::{PoolError, PoolErrorKind},
         test_utils::{MockTransaction, TestPoolBuilder},
         validate::ValidTransaction,
-        BlockInfo, PoolConfig, SubPoolLimit, TransactionOrigin, TransactionValidationOutcome, U256,
+        BlockInfo, DiscardReason, FullTransactionEvent, PoolConfig, PoolTransaction, SubPoolLimit,
+        TransactionFilter, TransactionListenerKind, TransactionOrigin,
+        TransactionValidationOutcome, U256,
     };
     use reth_primitives::{kzg::Blob, transaction::generate_blob_sidecar};
-    use std::{fs, path::PathBuf};
+    use std::{
+        fs,
+        path::PathBuf,
+        time::{Duration, Instant},
+    };
 
     #[test]
     fn test_discard_blobs_on_blob_tx_eviction() {
@@ -1203,6 +1290,7 @@ mod tests {
                         },
                         propagate: true,
                     },
+                    None,
                 )
                 .unwrap();
 
@@ -1219,4 +1307,114 @@ mod tests {
         // Assert that the pool's blob store matches the expected blob store.
         assert_eq!(*test_pool.blob_store(), blob_store);
     }
+
+    #[test]
+    fn test_discarded_on_insert_is_not_announced() {
+        let pending_limit = SubPoolLimit::new(1, usize::MAX);
+        let test_pool = &TestPoolBuilder::default()
+            .with_config(PoolConfig { pending_limit, ..Default::default() })
+            .pool;
+        let mut pending_hashes = test_pool.add_pending_listener(TransactionListenerKind::All);
+        let mut new_transactions =
+            test_pool.add_new_transaction_listener(TransactionListenerKind::All);
+
+        let transactions = [
+            MockTransaction::eip1559().with_priority_fee(100).with_max_fee(100),
+            MockTransaction::eip1559().with_priority_fee(1).with_max_fee(100),
+        ];
+        let results = test_pool.add_transactions(
+            TransactionOrigin::External,
+            transactions.into_iter().map(|transaction| TransactionValidationOutcome::Valid {
+                balance: U256::from(1_000),
+                state_nonce: 0,
+                transaction: ValidTransaction::Valid(transaction),
+                propagate: true,
+            }),
+        );
+
+        // one of the transactions exceeds the limit of the pending pool
+        let added = results.iter().filter_map(|res| res.as_ref().ok()).collect::<Vec<_>>();
+        assert_eq!(added.len(), 1);
+        assert!(results.iter().any(|res| matches!(
+            res,
+            Err(PoolError { kind: PoolErrorKind::DiscardedOnInsert, .. })
+        )));
+
+        // only the transaction that is still in the pool is announced
+        assert_eq!(pending_hashes.try_recv().unwrap(), *added[0]);
+        assert!(pending_hashes.try_recv().is_err());
+        assert_eq!(new_transactions.try_recv().unwrap().transaction.hash(), added[0]);
+        assert!(new_transactions.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_expired_queued_is_discarded_with_timeout() {
+        let test_pool = &TestPoolBuilder::default().pool;
+
+        // queued because of a nonce gap
+        let transaction = MockTransaction::eip1559().inc_nonce();
+        let hash = transaction.get_hash();
+        test_pool
+            .add_transaction(
+                TransactionOrigin::External,
+                TransactionValidationOutcome::Valid {
+                    balance: U256::from(1_000),
+                    state_nonce: 0,
+                    transaction: ValidTransaction::Valid(transaction),
+                    propagate: true,
+                },
+                None,
+            )
+            .unwrap();
+        let mut events = test_pool.add_all_transactions_event_listener();
+
+        let now = Instant::now() + test_pool.config().max_queued_lifetime + Duration::from_secs(1);
+        assert_eq!(test_pool.discard_expired_queued(now), vec![hash]);
+        assert!(!test_pool.get_pool_data().contains(&hash));
+        assert!(matches!(
+            events.events.try_recv().unwrap(),
+            FullTransactionEvent::Discarded { tx_hash, reason: DiscardReason::Timeout }
+                if tx_hash == hash
+        ));
+    }
+
+    #[test]
+    fn test_pooled_transactions_by_origin_and_filter() {
+        let test_pool = &TestPoolBuilder::default().pool;
+
+        let add = |origin, transaction: MockTransaction| {
+            test_pool
+                .add_transaction(
+                    origin,
+                    TransactionValidationOutcome::Valid {
+                        balance: U256::MAX,
+                        state_nonce: 0,
+                        transaction: ValidTransaction::Valid(transaction),
+                        propagate: true,
+                    },
+                    None,
+                )
+                .unwrap()
+        };
+        let external = MockTransaction::eip1559();
+        let local = MockTransaction::eip1559();
+        add(TransactionOrigin::External, external.clone());
+        add(TransactionOrigin::Local, local.clone());
+
+        let local_txs = test_pool.get_transactions_by_origin(TransactionOrigin::Local);
+        assert_eq!(local_txs.len(), 1);
+        assert_eq!(*local_txs[0].hash(), local.get_hash());
+
+        let matching = test_pool.pooled_transactions_matching(
+            &TransactionFilter::default().with_senders([external.sender()]),
+        );
+        assert_eq!(matching.pending.len(), 1);
+        assert!(matching.queued.is_empty());
+        assert_eq!(*matching.pending[0].hash(), external.get_hash());
+
+        assert_eq!(
+            test_pool.pooled_transactions_matching(&TransactionFilter::default()).pending.len(),
+            2
+        );
+    }
 }
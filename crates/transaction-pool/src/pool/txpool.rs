@@ -12,9 +12,9 @@ use crate::{
         pending::PendingPool,
         state::{SubPool, TxState},
         update::{Destination, PoolUpdate},
-        AddedPendingTransaction, AddedTransaction, OnNewCanonicalStateOutcome,
+        AddedPendingTransaction, AddedTransaction, OnNewCanonicalStateOutcome, TransactionFilter,
     },
-    traits::{BestTransactionsAttributes, BlockInfo, PoolSize},
+    traits::{AllPoolTransactions, BestTransactionsAttributes, BlockInfo, PoolSize},
     PoolConfig, PoolResult, PoolTransaction, PriceBumpConfig, TransactionOrdering,
     ValidPoolTransaction, U256,
 };
@@ -33,6 +33,7 @@ use std::{
     fmt,
     ops::Bound::{Excluded, Unbounded},
     sync::Arc,
+    time::Instant,
 };
 use tracing::trace;
 
@@ -323,9 +324,32 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.pending_pool.all().collect()
     }
 
-    /// Returns all transactions from parked pools
+    /// Returns all transactions from parked pools, including the blob transactions that are
+    /// parked until the blob fee drops
     pub(crate) fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
-        self.basefee_pool.all().chain(self.queued_pool.all()).collect()
+        self.basefee_pool.all().chain(self.queued_pool.all()).chain(self.blob_pool.all()).collect()
+    }
+
+    /// Returns the pending and queued transactions that match the given [`TransactionFilter`], at
+    /// the pending base fee.
+    pub(crate) fn transactions_matching(
+        &self,
+        filter: &TransactionFilter,
+    ) -> AllPoolTransactions<T::Transaction> {
+        let base_fee = self.block_info().pending_basefee;
+        let matches = |tx: &Arc<ValidPoolTransaction<T::Transaction>>| {
+            filter.matches(&tx.transaction, base_fee)
+        };
+        AllPoolTransactions {
+            pending: self.pending_pool.all().filter(matches).collect(),
+            queued: self
+                .basefee_pool
+                .all()
+                .chain(self.queued_pool.all())
+                .chain(self.blob_pool.all())
+                .filter(matches)
+                .collect(),
+        }
     }
 
     /// Returns queued and pending transactions for the specified sender
@@ -401,15 +425,20 @@ impl<T: TransactionOrdering> TxPool<T> {
     /// Updates the entire pool after a new block was mined.
     ///
     /// This removes all mined transactions, updates according to the new base fee and rechecks
-    /// sender allowance.
+    /// sender allowance. Transactions with a [`TransactionConditional`] that can no longer be
+    /// met after a block with the given timestamp are discarded.
+    ///
+    /// [`TransactionConditional`]: reth_primitives::TransactionConditional
     pub(crate) fn on_canonical_state_change(
         &mut self,
         block_info: BlockInfo,
+        block_timestamp: u64,
         mined_transactions: Vec<TxHash>,
         changed_senders: HashMap<SenderId, SenderInfo>,
     ) -> OnNewCanonicalStateOutcome<T::Transaction> {
         // update block info
         let block_hash = block_info.last_seen_block_hash;
+        let block_number = block_info.last_seen_block_number;
         self.all_transactions.set_block_info(block_info);
 
         // Remove all transaction that were included in the block
@@ -420,13 +449,87 @@ impl<T: TransactionOrdering> TxPool<T> {
             }
         }
 
-        let UpdateOutcome { promoted, discarded } = self.update_accounts(changed_senders);
+        let UpdateOutcome { promoted, mut discarded } = self.update_accounts(changed_senders);
+        discarded.extend(self.discard_expired_conditionals(block_number, block_timestamp));
 
         self.metrics.performed_state_updates.increment(1);
 
         OnNewCanonicalStateOutcome { block_hash, mined: mined_transactions, promoted, discarded }
     }
 
+    /// Removes all transactions whose conditional can't be met by any block after the block with
+    /// the given number and timestamp.
+    ///
+    /// The descendants of a removed transaction are parked, because they now have a nonce gap.
+    fn discard_expired_conditionals(
+        &mut self,
+        block_number: u64,
+        block_timestamp: u64,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let expired = self
+            .all_transactions
+            .transactions_iter()
+            .filter(|tx| {
+                tx.conditional().is_some_and(|conditional| {
+                    conditional.has_expired(block_number, block_timestamp)
+                })
+            })
+            .map(|tx| *tx.id())
+            .collect::<Vec<_>>();
+
+        let mut discarded = Vec::with_capacity(expired.len());
+        for id in expired {
+            if let Some(tx) = self.evict_transaction(&id) {
+                self.metrics.removed_transactions.increment(1);
+                discarded.push(tx);
+            }
+        }
+        discarded
+    }
+
+    /// Removes all transactions of the queued sub-pool that were inserted longer than their
+    /// lifetime before the given instant.
+    ///
+    /// Local transactions use the lifetime of the [`LocalTransactionConfig`], transactions of all
+    /// other sub-pools are exempt.
+    pub(crate) fn discard_expired_queued(
+        &mut self,
+        now: Instant,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let local_config = &self.config.local_transactions_config;
+        let expired = self
+            .all_transactions
+            .txs
+            .values()
+            .filter(|tx| tx.subpool == SubPool::Queued)
+            .filter(|tx| {
+                let tx = &tx.transaction;
+                let lifetime = if local_config.is_local(tx.origin, tx.sender()) {
+                    local_config.max_queued_lifetime
+                } else {
+                    Some(self.config.max_queued_lifetime)
+                };
+                lifetime
+                    .is_some_and(|lifetime| now.saturating_duration_since(tx.timestamp) > lifetime)
+            })
+            .map(|tx| *tx.transaction.id())
+            .collect::<Vec<_>>();
+
+        let mut discarded = Vec::with_capacity(expired.len());
+        for id in expired {
+            if let Some(tx) = self.evict_transaction(&id) {
+                self.metrics.removed_transactions.increment(1);
+                self.metrics.expired_transactions.increment(1);
+                discarded.push(tx);
+            }
+        }
+
+        if !discarded.is_empty() {
+            self.update_size_metrics();
+        }
+        discarded
+    }
+
     /// Update sub-pools size metrics.
     pub(crate) fn update_size_metrics(&self) {
         let stats = self.size();
@@ -486,12 +589,25 @@ impl<T: TransactionOrdering> TxPool<T> {
             Ok(InsertOk { transaction, move_to, replaced_tx, updates, .. }) => {
                 // replace the new tx and remove the replaced in the subpool(s)
                 self.add_new_transaction(transaction.clone(), replaced_tx.clone(), move_to);
-                // Update inserted transactions metric
-                self.metrics.inserted_transactions.increment(1);
-                let UpdateOutcome { promoted, discarded } = self.process_updates(updates);
+                let UpdateOutcome { promoted, mut discarded } = self.process_updates(updates);
 
                 let replaced = replaced_tx.map(|(tx, _)| tx);
 
+                // a replacement doesn't occupy an additional slot of the sender
+                if replaced.is_none() {
+                    match self.enforce_account_slots(&transaction, move_to) {
+                        Ok(evicted) => discarded.extend(evicted),
+                        Err(err) => {
+                            self.metrics.invalid_transactions.increment(1);
+                            self.update_size_metrics();
+                            return Err(err)
+                        }
+                    }
+                }
+
+                // Update inserted transactions metric
+                self.metrics.inserted_transactions.increment(1);
+
                 // This transaction was moved to the pending pool.
                 let res = if move_to.is_pending() {
                     AddedTransaction::Pending(AddedPendingTransaction {
@@ -501,7 +617,7 @@ impl<T: TransactionOrdering> TxPool<T> {
                         replaced,
                     })
                 } else {
-                    AddedTransaction::Parked { transaction, subpool: move_to, replaced }
+                    AddedTransaction::Parked { transaction, subpool: move_to, replaced, discarded }
                 };
 
                 // Update size metrics after adding and potentially moving transactions.
@@ -513,10 +629,12 @@ impl<T: TransactionOrdering> TxPool<T> {
                 // Update invalid transactions metric
                 self.metrics.invalid_transactions.increment(1);
                 match err {
-                    InsertErr::Underpriced { existing: _, transaction } => Err(PoolError::new(
-                        *transaction.hash(),
-                        PoolErrorKind::ReplacementUnderpriced,
-                    )),
+                    InsertErr::Underpriced { existing: _, transaction, required_price_bump } => {
+                        Err(PoolError::new(
+                            *transaction.hash(),
+                            PoolErrorKind::ReplacementUnderpriced(required_price_bump),
+                        ))
+                    }
                     InsertErr::FeeCapBelowMinimumProtocolFeeCap { transaction, fee_cap } => {
                         Err(PoolError::new(
                             *transaction.hash(),
@@ -564,6 +682,78 @@ impl<T: TransactionOrdering> TxPool<T> {
         }
     }
 
+    /// Enforces the sender's slots in the pending and queued sub-pools after the new transaction
+    /// was added to the given sub-pool.
+    ///
+    /// If the new transaction exceeds the sender's queued slots, the sender's queued transaction
+    /// with the lowest priority is evicted. If that is the new transaction, or if the new
+    /// transaction exceeds the sender's pending slots, the new transaction is removed again and an
+    /// error is returned.
+    ///
+    /// Returns the evicted transactions.
+    fn enforce_account_slots(
+        &mut self,
+        transaction: &Arc<ValidPoolTransaction<T::Transaction>>,
+        move_to: SubPool,
+    ) -> PoolResult<Vec<Arc<ValidPoolTransaction<T::Transaction>>>> {
+        if self
+            .all_transactions
+            .local_transactions_config
+            .is_local(transaction.origin, transaction.sender())
+        {
+            return Ok(Vec::new())
+        }
+
+        let sender = transaction.sender_id();
+        let exceeds_slots = match move_to {
+            SubPool::Pending => {
+                // the new transaction may also have promoted its descendants
+                self.all_transactions.subpool_tx_count(sender, SubPool::Pending) >
+                    self.config.max_account_slots_pending
+            }
+            SubPool::Queued => {
+                if self.all_transactions.subpool_tx_count(sender, SubPool::Queued) <=
+                    self.config.max_account_slots_queued
+                {
+                    return Ok(Vec::new())
+                }
+                let worst = self
+                    .all_transactions
+                    .lowest_priority_tx(sender, SubPool::Queued)
+                    .expect("sender has queued transactions");
+                if worst != *transaction.id() {
+                    trace!(target: "txpool", ?worst, "evicting queued transaction of sender at its max account slots");
+                    return Ok(self.evict_transaction(&worst).into_iter().collect())
+                }
+                true
+            }
+            SubPool::BaseFee | SubPool::Blob => false,
+        };
+
+        if exceeds_slots {
+            // removing the new transaction parks the transactions it promoted again
+            self.evict_transaction(transaction.id());
+            return Err(PoolError::new(
+                *transaction.hash(),
+                PoolErrorKind::ExceedsMaxAccountSlots(transaction.sender()),
+            ))
+        }
+
+        Ok(Vec::new())
+    }
+
+    /// Removes the transaction from the __entire__ pool and parks all its descendants, because
+    /// they now have a nonce gap.
+    fn evict_transaction(
+        &mut self,
+        id: &TransactionId,
+    ) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let (tx, pool, updates) = self.all_transactions.evict_transaction(id)?;
+        let tx = self.remove_from_subpool(pool, tx.id());
+        self.process_updates(updates);
+        tx
+    }
+
     /// Maintenance task to apply a series of updates.
     ///
     /// This will move/discard the given transaction according to the `PoolUpdate`
@@ -1248,6 +1438,51 @@ impl<T: PoolTransaction> AllTransactions<T> {
         Some((tx, internal.subpool))
     }
 
+    /// Removes a transaction from the set and marks all its descendants with a nonce gap.
+    ///
+    /// Returns the removed transaction, the sub-pool it was in, and the updates for the
+    /// descendants that must be parked.
+    pub(crate) fn evict_transaction(
+        &mut self,
+        id: &TransactionId,
+    ) -> Option<(Arc<ValidPoolTransaction<T>>, SubPool, Vec<PoolUpdate>)> {
+        let (tx, pool) = self.remove_transaction(id)?;
+
+        let mut updates = Vec::new();
+        let descendant = id.descendant();
+        for (_, tx) in self.descendant_txs_mut(&descendant) {
+            tx.state.remove(TxState::NO_NONCE_GAPS | TxState::NO_PARKED_ANCESTORS);
+            Self::record_subpool_update(&mut updates, tx);
+        }
+
+        Some((tx, pool, updates))
+    }
+
+    /// Returns the number of transactions of the sender in the given sub-pool.
+    pub(crate) fn subpool_tx_count(&self, sender: SenderId, subpool: SubPool) -> usize {
+        self.txs_iter(sender).filter(|(_, tx)| tx.subpool == subpool).count()
+    }
+
+    /// Returns the transaction of the sender in the given sub-pool with the lowest priority.
+    ///
+    /// The priority is the max fee per gas, between transactions with the same fee the one with
+    /// the higher nonce has the lower priority.
+    pub(crate) fn lowest_priority_tx(
+        &self,
+        sender: SenderId,
+        subpool: SubPool,
+    ) -> Option<TransactionId> {
+        self.txs_iter(sender)
+            .filter(|(_, tx)| tx.subpool == subpool)
+            .min_by(|(a_id, a), (b_id, b)| {
+                a.transaction
+                    .max_fee_per_gas()
+                    .cmp(&b.transaction.max_fee_per_gas())
+                    .then_with(|| b_id.nonce.cmp(&a_id.nonce))
+            })
+            .map(|(id, _)| *id)
+    }
+
     /// Removes a transaction from the set.
     ///
     /// This will _not_ trigger additional updates, because descendants without nonce gaps are
@@ -1380,20 +1615,20 @@ impl<T: PoolTransaction> AllTransactions<T> {
         Ok(new_blob_tx)
     }
 
-    /// Returns true if the replacement candidate is underpriced and can't replace the existing
-    /// transaction.
+    /// Returns the price bump (in %) the replacement candidate failed to meet, if it is
+    /// underpriced and can't replace the existing transaction.
     #[inline]
-    fn is_underpriced(
+    fn underpriced_replacement_bump(
         existing_transaction: &ValidPoolTransaction<T>,
         maybe_replacement: &ValidPoolTransaction<T>,
         price_bumps: &PriceBumpConfig,
-    ) -> bool {
+    ) -> Option<u128> {
         let price_bump = price_bumps.price_bump(existing_transaction.tx_type());
 
         if maybe_replacement.max_fee_per_gas() <=
             existing_transaction.max_fee_per_gas() * (100 + price_bump) / 100
         {
-            return true
+            return Some(price_bump)
         }
 
         let existing_max_priority_fee_per_gas =
@@ -1406,7 +1641,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
             existing_max_priority_fee_per_gas != 0 &&
             replacement_max_priority_fee_per_gas != 0
         {
-            return true
+            return Some(price_bump)
         }
 
         // check max blob fee per gas
@@ -1414,16 +1649,17 @@ impl<T: PoolTransaction> AllTransactions<T> {
             existing_transaction.transaction.max_fee_per_blob_gas()
         {
             // this enforces that blob txs can only be replaced by blob txs
+            let blob_fee_price_bump = price_bumps.blob_fee_price_bump();
             let replacement_max_blob_fee_per_gas =
                 maybe_replacement.transaction.max_fee_per_blob_gas().unwrap_or(0);
             if replacement_max_blob_fee_per_gas <=
-                existing_max_blob_fee_per_gas * (100 + price_bump) / 100
+                existing_max_blob_fee_per_gas * (100 + blob_fee_price_bump) / 100
             {
-                return true
+                return Some(blob_fee_price_bump)
             }
         }
 
-        false
+        None
     }
 
     /// Inserts a new _valid_ transaction into the pool.
@@ -1540,11 +1776,15 @@ impl<T: PoolTransaction> AllTransactions<T> {
                 let maybe_replacement = transaction.as_ref();
 
                 // Ensure the new transaction is not underpriced
-                if Self::is_underpriced(existing_transaction, maybe_replacement, &self.price_bumps)
-                {
+                if let Some(required_price_bump) = Self::underpriced_replacement_bump(
+                    existing_transaction,
+                    maybe_replacement,
+                    &self.price_bumps,
+                ) {
                     return Err(InsertErr::Underpriced {
                         transaction: pool_tx.transaction,
                         existing: *entry.get().transaction.hash(),
+                        required_price_bump,
                     })
                 }
                 let new_hash = *pool_tx.transaction.hash();
@@ -1719,6 +1959,8 @@ pub(crate) enum InsertErr<T: PoolTransaction> {
         transaction: Arc<ValidPoolTransaction<T>>,
         #[allow(dead_code)]
         existing: TxHash,
+        /// The price bump (in %) the replacement did not meet.
+        required_price_bump: u128,
     },
     /// Attempted to insert a blob transaction with a nonce gap
     BlobTxHasNonceGap { transaction: Arc<ValidPoolTransaction<T>> },
@@ -1849,7 +2091,8 @@ impl SenderInfo {
 
 #[cfg(test)]
 mod tests {
-    use reth_primitives::{address, TxType};
+    use reth_primitives::{address, TransactionConditional, TxType, U64};
+    use std::time::Duration;
 
     use super::*;
     use crate::{
@@ -2007,6 +2250,30 @@ mod tests {
         assert!(pool.blob_pool.is_empty());
     }
 
+    #[test]
+    fn test_queued_transactions_include_blob_pool() {
+        let on_chain_balance = U256::MAX;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        let tx = MockTransaction::eip4844().inc_price().inc_limit();
+
+        // park the tx in the blob pool
+        let mut block_info = pool.block_info();
+        block_info.pending_blob_fee = Some(tx.max_fee_per_blob_gas().unwrap() + 1);
+        pool.set_block_info(block_info);
+
+        let validated = f.validated(tx);
+        let hash = *validated.hash();
+        pool.add_transaction(validated, on_chain_balance, on_chain_nonce).unwrap();
+        assert_eq!(pool.blob_pool.len(), 1);
+
+        assert!(pool.pending_transactions().is_empty());
+        let queued = pool.queued_transactions();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(*queued[0].hash(), hash);
+    }
+
     /// A struct representing a txpool promotion test instance
     #[derive(Debug, PartialEq, Eq, Clone, Hash)]
     struct PromotionTest {
@@ -2395,6 +2662,76 @@ mod tests {
         assert_eq!(pool.len(), 1);
     }
 
+    #[test]
+    fn insert_replace_exact_configured_price_bump() {
+        let on_chain_balance = U256::MAX;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let config = PoolConfig {
+            price_bumps: PriceBumpConfig { default_price_bump: 1, ..Default::default() },
+            ..Default::default()
+        };
+        let mut pool = AllTransactions::new(&config);
+        let tx = MockTransaction::eip1559().with_priority_fee(100).with_max_fee(100);
+        let first = f.validated(tx.clone());
+        pool.insert_tx(first.clone(), on_chain_balance, on_chain_nonce).unwrap();
+
+        // a bump of exactly 1% is not enough
+        let replacement =
+            f.validated(tx.clone().rng_hash().with_priority_fee(101).with_max_fee(101));
+        let err = pool.insert_tx(replacement, on_chain_balance, on_chain_nonce).unwrap_err();
+        assert!(matches!(err, InsertErr::Underpriced { required_price_bump: 1, .. }));
+        assert!(pool.contains(first.hash()));
+
+        let replacement =
+            f.validated(tx.clone().rng_hash().with_priority_fee(102).with_max_fee(102));
+        pool.insert_tx(replacement.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        assert!(!pool.contains(first.hash()));
+        assert!(pool.contains(replacement.hash()));
+    }
+
+    #[test]
+    fn insert_replace_blob_exact_configured_price_bump() {
+        let on_chain_balance = U256::MAX;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let config = PoolConfig {
+            price_bumps: PriceBumpConfig {
+                replace_blob_tx_price_bump: 20,
+                replace_blob_tx_blob_fee_price_bump: 50,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pool = AllTransactions::new(&config);
+        let tx =
+            MockTransaction::eip4844().with_priority_fee(100).with_max_fee(100).with_blob_fee(100);
+        let first = f.validated(tx.clone());
+        pool.insert_tx(first.clone(), on_chain_balance, on_chain_nonce).unwrap();
+
+        // a bump of exactly 20% on the fees is not enough
+        let replacement = f.validated(
+            tx.clone().rng_hash().with_priority_fee(120).with_max_fee(120).with_blob_fee(151),
+        );
+        let err = pool.insert_tx(replacement, on_chain_balance, on_chain_nonce).unwrap_err();
+        assert!(matches!(err, InsertErr::Underpriced { required_price_bump: 20, .. }));
+
+        // a bump of exactly 50% on the blob fee is not enough
+        let replacement = f.validated(
+            tx.clone().rng_hash().with_priority_fee(121).with_max_fee(121).with_blob_fee(150),
+        );
+        let err = pool.insert_tx(replacement, on_chain_balance, on_chain_nonce).unwrap_err();
+        assert!(matches!(err, InsertErr::Underpriced { required_price_bump: 50, .. }));
+        assert!(pool.contains(first.hash()));
+
+        let replacement = f.validated(
+            tx.clone().rng_hash().with_priority_fee(121).with_max_fee(121).with_blob_fee(151),
+        );
+        pool.insert_tx(replacement.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        assert!(!pool.contains(first.hash()));
+        assert!(pool.contains(replacement.hash()));
+    }
+
     #[test]
     fn insert_conflicting_type_normal_to_blob() {
         let on_chain_balance = U256::from(10_000);
@@ -2982,4 +3319,235 @@ mod tests {
             vec![1, 2, 3]
         );
     }
+
+    #[test]
+    fn rejects_tx_exceeding_pending_account_slots() {
+        let on_chain_balance = U256::from(10_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(
+            MockOrdering::default(),
+            PoolConfig { max_account_slots_pending: 2, ..Default::default() },
+        );
+
+        let tx_0 = MockTransaction::eip1559().set_gas_price(100).inc_limit();
+        let tx_1 = tx_0.next();
+        let tx_2 = tx_1.next();
+        pool.add_transaction(f.validated(tx_0), on_chain_balance, on_chain_nonce).unwrap();
+        pool.add_transaction(f.validated(tx_1), on_chain_balance, on_chain_nonce).unwrap();
+
+        let err = pool
+            .add_transaction(f.validated(tx_2.clone()), on_chain_balance, on_chain_nonce)
+            .unwrap_err();
+        assert!(matches!(err.kind, PoolErrorKind::ExceedsMaxAccountSlots(_)));
+        assert_eq!(pool.pending_transactions().len(), 2);
+        pool.assert_invariants();
+
+        // the descendants of the rejected transaction are queued because of the nonce gap
+        let tx_3 = tx_2.next();
+        let tx_4 = tx_3.next();
+        pool.add_transaction(f.validated(tx_3), on_chain_balance, on_chain_nonce).unwrap();
+        pool.add_transaction(f.validated(tx_4), on_chain_balance, on_chain_nonce).unwrap();
+        assert_eq!(pool.queued_transactions().len(), 2);
+
+        // closing the gap would promote the descendants, which is rejected again
+        let err =
+            pool.add_transaction(f.validated(tx_2), on_chain_balance, on_chain_nonce).unwrap_err();
+        assert!(matches!(err.kind, PoolErrorKind::ExceedsMaxAccountSlots(_)));
+        assert_eq!(pool.pending_transactions().len(), 2);
+        assert_eq!(pool.queued_transactions().len(), 2);
+        assert!(pool.queued_transactions().iter().all(|tx| pool
+            .all()
+            .get(tx.id())
+            .unwrap()
+            .state
+            .has_nonce_gap()));
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn evicts_lowest_priority_queued_tx_at_account_slots() {
+        let on_chain_balance = U256::from(10_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(
+            MockOrdering::default(),
+            PoolConfig { max_account_slots_queued: 3, ..Default::default() },
+        );
+
+        // nonce 0 is missing, so all transactions are queued
+        let tx_0 = MockTransaction::eip1559().set_gas_price(100).inc_limit();
+        let tx_1 = tx_0.next();
+        let tx_2 = tx_1.next().with_gas_price(10);
+        let tx_3 = tx_2.next().with_gas_price(100);
+        for tx in [&tx_1, &tx_2, &tx_3] {
+            pool.add_transaction(f.validated(tx.clone()), on_chain_balance, on_chain_nonce)
+                .unwrap();
+        }
+        assert_eq!(pool.queued_transactions().len(), 3);
+
+        // a higher priority transaction evicts the sender's lowest priority queued transaction
+        let tx_5 = tx_3.skip(1).with_gas_price(200);
+        let added = pool
+            .add_transaction(f.validated(tx_5.clone()), on_chain_balance, on_chain_nonce)
+            .unwrap();
+        let discarded = added.discarded_transactions().unwrap();
+        assert_eq!(discarded.len(), 1);
+        assert_eq!(*discarded[0].hash(), tx_2.get_hash());
+        assert_eq!(pool.queued_transactions().len(), 3);
+        pool.assert_invariants();
+
+        // a lower priority transaction is rejected
+        let tx_6 = tx_5.next().with_gas_price(10);
+        let err =
+            pool.add_transaction(f.validated(tx_6), on_chain_balance, on_chain_nonce).unwrap_err();
+        assert!(matches!(err.kind, PoolErrorKind::ExceedsMaxAccountSlots(_)));
+        assert_eq!(pool.queued_transactions().len(), 3);
+
+        // the evicted transaction left a nonce gap, so closing the first gap only promotes nonce 1
+        pool.add_transaction(f.validated(tx_0), on_chain_balance, on_chain_nonce).unwrap();
+        let pending =
+            pool.pending_transactions().iter().map(|tx| tx.nonce()).sorted().collect_vec();
+        assert_eq!(pending, vec![0, 1]);
+        let queued = pool.queued_transactions().iter().map(|tx| tx.nonce()).sorted().collect_vec();
+        assert_eq!(queued, vec![3, 5]);
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn replacement_at_account_slots() {
+        let on_chain_balance = U256::from(10_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(
+            MockOrdering::default(),
+            PoolConfig { max_account_slots_queued: 2, ..Default::default() },
+        );
+
+        let tx_1 = MockTransaction::eip1559().set_gas_price(100).inc_limit().inc_nonce();
+        let tx_2 = tx_1.next();
+        pool.add_transaction(f.validated(tx_1), on_chain_balance, on_chain_nonce).unwrap();
+        pool.add_transaction(f.validated(tx_2.clone()), on_chain_balance, on_chain_nonce).unwrap();
+
+        // a replacement doesn't occupy an additional slot
+        let replacement = tx_2.rng_hash().inc_price_by(100);
+        let added = pool
+            .add_transaction(f.validated(replacement), on_chain_balance, on_chain_nonce)
+            .unwrap();
+        assert!(added.replaced().is_some());
+        assert!(added.discarded_transactions().unwrap().is_empty());
+        assert_eq!(pool.queued_transactions().len(), 2);
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn discard_worst_after_account_slots_eviction() {
+        let on_chain_balance = U256::from(10_000);
+        let mut f = MockTransactionFactory::default();
+        let queued_limit = SubPoolLimit::new(4, usize::MAX);
+        let mut pool = TxPool::new(
+            MockOrdering::default(),
+            PoolConfig { queued_limit, max_account_slots_queued: 3, ..Default::default() },
+        );
+
+        // two senders fill their queued slots, with increasing fees
+        for _ in 0..2 {
+            let mut tx = MockTransaction::eip1559().set_gas_price(100).inc_limit().inc_nonce();
+            for _ in 0..4 {
+                tx = tx.next().inc_price_by(10);
+                pool.add_transaction(f.validated(tx.clone()), on_chain_balance, 0).unwrap();
+                pool.assert_invariants();
+            }
+            let sender = f.ids.sender_id(&tx.get_sender()).unwrap();
+            assert_eq!(pool.all().tx_count(sender), 3);
+        }
+
+        assert_eq!(pool.size().queued, 6);
+        let discarded = pool.discard_worst();
+        assert!(discarded.len() >= 2);
+        assert!(pool.size().queued <= queued_limit.max_txs);
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn discard_expired_conditional_on_canonical_state_change() {
+        let on_chain_balance = U256::from(1_000);
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let tx = MockTransaction::eip1559();
+        let mut validated = f.validated(tx.clone());
+        validated.conditional = Some(Box::new(TransactionConditional {
+            block_number_max: Some(U64::from(10)),
+            ..Default::default()
+        }));
+        pool.add_transaction(validated, on_chain_balance, 0).unwrap();
+        let descendant = tx.next();
+        pool.add_transaction(f.validated(descendant.clone()), on_chain_balance, 0).unwrap();
+        assert_eq!(pool.len(), 2);
+
+        // the conditional can still be met by the next block
+        let mut block_info = pool.block_info();
+        block_info.last_seen_block_number = 9;
+        let outcome = pool.on_canonical_state_change(block_info, 0, vec![], Default::default());
+        assert!(outcome.discarded.is_empty());
+        assert_eq!(pool.len(), 2);
+
+        block_info.last_seen_block_number = 10;
+        let outcome = pool.on_canonical_state_change(block_info, 0, vec![], Default::default());
+        assert_eq!(outcome.discarded.len(), 1);
+        assert_eq!(*outcome.discarded[0].hash(), tx.get_hash());
+        assert!(!pool.contains(&tx.get_hash()));
+
+        // the descendant now has a nonce gap
+        assert_eq!(pool.len(), 1);
+        let id = *f.validated(descendant).id();
+        assert_eq!(pool.all_transactions.txs.get(&id).unwrap().subpool, SubPool::Queued);
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn discard_expired_queued() {
+        let on_chain_balance = U256::MAX;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        // queued because of a nonce gap
+        let queued = MockTransaction::eip1559().inc_nonce();
+        pool.add_transaction(f.validated(queued.clone()), on_chain_balance, 0).unwrap();
+        let local_queued = MockTransaction::eip1559().inc_nonce();
+        pool.add_transaction(
+            f.validated_with_origin(TransactionOrigin::Local, local_queued.clone()),
+            on_chain_balance,
+            0,
+        )
+        .unwrap();
+        let pending = MockTransaction::eip1559();
+        pool.add_transaction(f.validated(pending.clone()), on_chain_balance, 0).unwrap();
+        assert_eq!(pool.size().queued, 2);
+        assert_eq!(pool.size().pending, 1);
+
+        let inserted = Instant::now();
+        assert!(pool.discard_expired_queued(inserted).is_empty());
+
+        // only the external queued transaction expires
+        let now = inserted + pool.config.max_queued_lifetime + Duration::from_secs(1);
+        let discarded = pool.discard_expired_queued(now);
+        assert_eq!(discarded.len(), 1);
+        assert_eq!(*discarded[0].hash(), queued.get_hash());
+        assert!(pool.contains(&local_queued.get_hash()));
+        assert!(pool.contains(&pending.get_hash()));
+        pool.assert_invariants();
+
+        // local transactions expire with their own lifetime
+        let local_lifetime = pool.config.max_queued_lifetime * 2;
+        pool.config.local_transactions_config.max_queued_lifetime = Some(local_lifetime);
+        assert!(pool.discard_expired_queued(now).is_empty());
+        let discarded =
+            pool.discard_expired_queued(inserted + local_lifetime + Duration::from_secs(1));
+        assert_eq!(discarded.len(), 1);
+        assert_eq!(*discarded[0].hash(), local_queued.get_hash());
+        assert_eq!(pool.len(), 1);
+        pool.assert_invariants();
+    }
 }
@@ -0,0 +1,609 @@
+//! Snapshots of the entire pool, for operational dumps and restores.
+//!
+//! A snapshot contains all transactions of the pool, regardless of their origin, together with the
+//! sidecars of blob transactions. It is written as:
+//!
+//! ```text
+//! magic (8 bytes) | version (1 byte) | entry* | 0u32 | entry count (u64 BE)
+//! ```
+//!
+//! where every entry is prefixed with its length as big endian `u32` and is the RLP encoding of
+//! `[origin, pooled transaction]`. The footer is used to detect truncated files.
+//!
+//! Restoring a snapshot reads and checks the entire file before the transactions are re-validated
+//! and inserted, so a corrupted snapshot never partially restores. The file is then read a second
+//! time and the transactions are inserted in batches of [`RESTORE_BATCH_SIZE`], so the snapshot
+//! is never held in memory at once.
+
+use crate::{
+    codec::OriginTaggedTransaction, AllPoolTransactions, PoolTransaction, TransactionOrigin,
+    TransactionPool,
+};
+use alloy_rlp::{Decodable, Encodable, Error as RlpError};
+use futures_util::future::BoxFuture;
+use reth_fs_util::FsPathError;
+use reth_primitives::{FromRecoveredPooledTransaction, PooledTransactionsElement};
+use std::{
+    ffi::OsString,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Seek, Write},
+    path::{Path, PathBuf},
+};
+use tracing::{debug, info};
+
+/// Magic bytes at the start of every pool snapshot.
+pub const POOL_SNAPSHOT_MAGIC: [u8; 8] = *b"rethpool";
+
+/// Current version of the pool snapshot format.
+pub const POOL_SNAPSHOT_VERSION: u8 = 1;
+
+/// Maximum encoded size of a single snapshot entry.
+///
+/// This is well above the size of a blob transaction with the maximum number of blobs and guards
+/// against allocating huge buffers for corrupted length prefixes.
+pub const MAX_POOL_SNAPSHOT_ENTRY_SIZE: usize = 4 * 1024 * 1024;
+
+/// Maximum number of entries that are held in memory and inserted at once when a snapshot is
+/// restored.
+pub const RESTORE_BATCH_SIZE: usize = 64;
+
+/// Errors that can occur when writing or reading a pool snapshot.
+#[derive(Debug, thiserror::Error)]
+pub enum PoolSnapshotError {
+    /// Failed to write or read the snapshot.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// Failed to access the snapshot file.
+    #[error(transparent)]
+    FsPath(#[from] FsPathError),
+    /// The data doesn't start with [`POOL_SNAPSHOT_MAGIC`].
+    #[error("not a transaction pool snapshot")]
+    InvalidMagic,
+    /// The snapshot was written with an unsupported version of the format.
+    #[error("unsupported transaction pool snapshot version {0}, expected {POOL_SNAPSHOT_VERSION}")]
+    UnsupportedVersion(u8),
+    /// The snapshot ended before its footer.
+    #[error("transaction pool snapshot is truncated")]
+    Truncated,
+    /// An entry exceeds [`MAX_POOL_SNAPSHOT_ENTRY_SIZE`].
+    #[error("entry {index} of transaction pool snapshot is too large: {size} bytes")]
+    EntryTooLarge {
+        /// Index of the entry.
+        index: u64,
+        /// Encoded size of the entry.
+        size: usize,
+    },
+    /// An entry doesn't decode.
+    #[error("entry {index} of transaction pool snapshot is corrupted: {err}")]
+    Corrupted {
+        /// Index of the entry.
+        index: u64,
+        /// The decoding error.
+        err: RlpError,
+    },
+    /// The number of entries doesn't match the footer.
+    #[error("transaction pool snapshot has {found} entries, footer expects {expected}")]
+    CountMismatch {
+        /// Number of entries according to the footer.
+        expected: u64,
+        /// Number of entries that were read.
+        found: u64,
+    },
+    /// There is data after the footer.
+    #[error("unexpected data after transaction pool snapshot footer")]
+    TrailingData,
+}
+
+// === impl PoolSnapshotError ===
+
+impl PoolSnapshotError {
+    /// Returns `true` if the snapshot itself is invalid, as opposed to failing to access it.
+    pub const fn is_invalid_snapshot(&self) -> bool {
+        !matches!(self, Self::Io(_) | Self::FsPath(_))
+    }
+}
+
+/// Outcome of restoring a snapshot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RestoreOutcome {
+    /// Number of transactions that were inserted into the pool.
+    pub restored: usize,
+    /// Number of transactions that were skipped, because they are no longer valid or are already
+    /// in the pool.
+    pub skipped: usize,
+}
+
+/// A single entry of a snapshot: a pooled transaction, including the blob sidecar of blob
+/// transactions, together with its origin.
+pub type PoolSnapshotEntry = OriginTaggedTransaction<PooledTransactionsElement>;
+
+/// Writes the entries of a snapshot and its footer.
+#[derive(Debug)]
+struct SnapshotWriter<W> {
+    writer: W,
+    entries: u64,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    /// Writes the header of the snapshot.
+    fn new(mut writer: W) -> Result<Self, PoolSnapshotError> {
+        writer.write_all(&POOL_SNAPSHOT_MAGIC)?;
+        writer.write_all(&[POOL_SNAPSHOT_VERSION])?;
+        Ok(Self { writer, entries: 0, buf: Vec::new() })
+    }
+
+    fn append(&mut self, entry: &PoolSnapshotEntry) -> Result<(), PoolSnapshotError> {
+        self.buf.clear();
+        entry.encode(&mut self.buf);
+        if self.buf.len() > MAX_POOL_SNAPSHOT_ENTRY_SIZE {
+            return Err(PoolSnapshotError::EntryTooLarge {
+                index: self.entries,
+                size: self.buf.len(),
+            })
+        }
+        self.writer.write_all(&(self.buf.len() as u32).to_be_bytes())?;
+        self.writer.write_all(&self.buf)?;
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Writes the footer and returns the number of entries.
+    fn finish(mut self) -> Result<u64, PoolSnapshotError> {
+        self.writer.write_all(&0u32.to_be_bytes())?;
+        self.writer.write_all(&self.entries.to_be_bytes())?;
+        self.writer.flush()?;
+        Ok(self.entries)
+    }
+}
+
+/// Reads exactly `buf.len()` bytes, an early end of the data means the snapshot is truncated.
+fn read_exact(reader: &mut impl Read, buf: &mut [u8]) -> Result<(), PoolSnapshotError> {
+    reader.read_exact(buf).map_err(|err| {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            PoolSnapshotError::Truncated
+        } else {
+            err.into()
+        }
+    })
+}
+
+/// Reads the entries of a snapshot one at a time and checks its footer.
+#[derive(Debug)]
+struct SnapshotReader<R> {
+    reader: R,
+    entries: u64,
+    buf: Vec<u8>,
+}
+
+impl<R: Read> SnapshotReader<R> {
+    /// Reads and checks the header of the snapshot.
+    fn new(mut reader: R) -> Result<Self, PoolSnapshotError> {
+        let mut magic = [0u8; 8];
+        read_exact(&mut reader, &mut magic)?;
+        if magic != POOL_SNAPSHOT_MAGIC {
+            return Err(PoolSnapshotError::InvalidMagic)
+        }
+        let mut version = [0u8; 1];
+        read_exact(&mut reader, &mut version)?;
+        if version[0] != POOL_SNAPSHOT_VERSION {
+            return Err(PoolSnapshotError::UnsupportedVersion(version[0]))
+        }
+        Ok(Self { reader, entries: 0, buf: Vec::new() })
+    }
+
+    /// Returns the next entry, or `None` once the footer was read and checked.
+    fn next_entry(&mut self) -> Result<Option<PoolSnapshotEntry>, PoolSnapshotError> {
+        let index = self.entries;
+        let mut len = [0u8; 4];
+        read_exact(&mut self.reader, &mut len)?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len == 0 {
+            self.read_footer()?;
+            return Ok(None)
+        }
+        if len > MAX_POOL_SNAPSHOT_ENTRY_SIZE {
+            return Err(PoolSnapshotError::EntryTooLarge { index, size: len })
+        }
+
+        self.buf.resize(len, 0);
+        read_exact(&mut self.reader, &mut self.buf)?;
+        let mut data = self.buf.as_slice();
+        let entry = PoolSnapshotEntry::decode(&mut data)
+            .map_err(|err| PoolSnapshotError::Corrupted { index, err })?;
+        if !data.is_empty() {
+            return Err(PoolSnapshotError::Corrupted { index, err: RlpError::UnexpectedLength })
+        }
+        self.entries += 1;
+        Ok(Some(entry))
+    }
+
+    /// Checks the entry count of the footer and that there is no data after it.
+    fn read_footer(&mut self) -> Result<(), PoolSnapshotError> {
+        let mut count = [0u8; 8];
+        read_exact(&mut self.reader, &mut count)?;
+        let expected = u64::from_be_bytes(count);
+        if expected != self.entries {
+            return Err(PoolSnapshotError::CountMismatch { expected, found: self.entries })
+        }
+        if self.reader.read(&mut [0u8; 1])? != 0 {
+            return Err(PoolSnapshotError::TrailingData)
+        }
+        Ok(())
+    }
+}
+
+/// Reads and checks all entries of a snapshot, without keeping them in memory, and returns the
+/// number of entries.
+pub fn check_snapshot(reader: impl Read) -> Result<u64, PoolSnapshotError> {
+    let mut reader = SnapshotReader::new(reader)?;
+    while reader.next_entry()?.is_some() {}
+    Ok(reader.entries)
+}
+
+/// Writes a snapshot of all transactions in the pool and returns the number of written
+/// transactions.
+///
+/// Transactions are written ordered by sender and nonce. Blob transactions whose sidecar is no
+/// longer in the blob store are skipped.
+pub fn dump_pool<P>(pool: &P, writer: impl Write) -> Result<usize, PoolSnapshotError>
+where
+    P: TransactionPool,
+{
+    let AllPoolTransactions { pending, queued } = pool.all_transactions();
+    let mut transactions = pending;
+    transactions.extend(queued);
+    transactions.sort_unstable_by_key(|tx| (tx.sender(), tx.nonce()));
+
+    let mut writer = SnapshotWriter::new(writer)?;
+    for tx in transactions {
+        // this also fetches the sidecar of blob transactions
+        let Some(transaction) = pool.get_pooled_transaction_element(*tx.hash()) else {
+            debug!(target: "txpool", hash=%tx.hash(), "Skipping transaction that can't be converted to a pooled transaction for the snapshot");
+            continue
+        };
+        writer.append(&PoolSnapshotEntry { origin: tx.origin, transaction })?;
+    }
+    Ok(writer.finish()? as usize)
+}
+
+/// Restores the transactions of a snapshot.
+///
+/// The entire snapshot is read and checked first, nothing is inserted if this fails. The
+/// snapshot is then read again and its transactions are re-validated and inserted with their
+/// original origin in batches of [`RESTORE_BATCH_SIZE`], transactions that are no longer valid
+/// are skipped.
+pub async fn restore_pool<P>(
+    pool: &P,
+    mut reader: impl Read + Seek,
+) -> Result<RestoreOutcome, PoolSnapshotError>
+where
+    P: TransactionPool,
+{
+    check_snapshot(&mut reader)?;
+    reader.rewind()?;
+
+    let mut outcome = RestoreOutcome::default();
+    let mut entries = SnapshotReader::new(reader)?;
+    let mut batch = Vec::with_capacity(RESTORE_BATCH_SIZE);
+    while let Some(entry) = entries.next_entry()? {
+        batch.push(entry);
+        if batch.len() == RESTORE_BATCH_SIZE {
+            restore_batch(pool, batch.drain(..), &mut outcome).await;
+        }
+    }
+    restore_batch(pool, batch, &mut outcome).await;
+
+    Ok(outcome)
+}
+
+/// Re-validates and inserts the transactions of the given entries, grouped by their origin.
+async fn restore_batch<P>(
+    pool: &P,
+    entries: impl IntoIterator<Item = PoolSnapshotEntry>,
+    outcome: &mut RestoreOutcome,
+) where
+    P: TransactionPool,
+{
+    let mut batches = [
+        (TransactionOrigin::Local, Vec::new()),
+        (TransactionOrigin::External, Vec::new()),
+        (TransactionOrigin::Private, Vec::new()),
+    ];
+    for entry in entries {
+        let origin_id = entry.origin_id() as usize;
+        match entry.transaction.try_into_ecrecovered() {
+            Ok(tx) => {
+                batches[origin_id].1.push(P::Transaction::from_recovered_pooled_transaction(tx))
+            }
+            Err(tx) => {
+                debug!(target: "txpool", hash=%tx.hash(), "Skipping snapshot transaction with invalid signature");
+                outcome.skipped += 1;
+            }
+        }
+    }
+
+    for (origin, transactions) in batches {
+        if transactions.is_empty() {
+            continue
+        }
+        for result in pool.add_transactions(origin, transactions).await {
+            match result {
+                Ok(_) => outcome.restored += 1,
+                Err(err) => {
+                    debug!(target: "txpool", ?err, "Skipping snapshot transaction");
+                    outcome.skipped += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Writes a snapshot of the pool to the file at the given path, see [`dump_pool`].
+///
+/// The snapshot is written to a temporary file next to the path first, so that an existing
+/// snapshot is only replaced by a complete one.
+pub fn dump_pool_to_file<P>(pool: &P, path: &Path) -> Result<usize, PoolSnapshotError>
+where
+    P: TransactionPool,
+{
+    let mut tmp_path = OsString::from(path.as_os_str());
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    let file = File::create(&tmp_path).map_err(|err| FsPathError::create_file(err, &tmp_path))?;
+    let num_txs = match write_to_file(pool, file) {
+        Ok(num_txs) => num_txs,
+        Err(err) => {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(err)
+        }
+    };
+    reth_fs_util::rename(&tmp_path, path)?;
+
+    info!(target: "txpool", snapshot=?path, num_txs, "Wrote transaction pool snapshot");
+    Ok(num_txs)
+}
+
+fn write_to_file<P>(pool: &P, file: File) -> Result<usize, PoolSnapshotError>
+where
+    P: TransactionPool,
+{
+    let mut writer = BufWriter::new(file);
+    let num_txs = dump_pool(pool, &mut writer)?;
+    writer.into_inner().map_err(|err| err.into_error())?.sync_all()?;
+    Ok(num_txs)
+}
+
+/// Restores the snapshot at the given path, see [`restore_pool`].
+pub async fn restore_pool_from_file<P>(
+    pool: &P,
+    path: &Path,
+) -> Result<RestoreOutcome, PoolSnapshotError>
+where
+    P: TransactionPool,
+{
+    let file = File::open(path).map_err(|err| FsPathError::open(err, path))?;
+    let outcome = restore_pool(pool, BufReader::new(file)).await?;
+
+    info!(target: "txpool", snapshot=?path, restored=outcome.restored, skipped=outcome.skipped, "Restored transaction pool snapshot");
+    Ok(outcome)
+}
+
+/// Object safe access to the snapshots of a pool, e.g. for the `reth_txpoolDump` and
+/// `reth_txpoolRestore` endpoints.
+pub trait PoolSnapshots: Send + Sync {
+    /// Writes a snapshot of the pool to the file at the given path and returns the number of
+    /// written transactions.
+    fn dump_to_file(&self, path: &Path) -> Result<usize, PoolSnapshotError>;
+
+    /// Restores the snapshot at the given path.
+    fn restore_from_file(
+        &self,
+        path: &Path,
+    ) -> BoxFuture<'static, Result<RestoreOutcome, PoolSnapshotError>>;
+}
+
+impl<P> PoolSnapshots for P
+where
+    P: TransactionPool + 'static,
+{
+    fn dump_to_file(&self, path: &Path) -> Result<usize, PoolSnapshotError> {
+        dump_pool_to_file(self, path)
+    }
+
+    fn restore_from_file(
+        &self,
+        path: &Path,
+    ) -> BoxFuture<'static, Result<RestoreOutcome, PoolSnapshotError>> {
+        let pool = self.clone();
+        let path = path.to_path_buf();
+        Box::pin(async move { restore_pool_from_file(&pool, &path).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{eth_testing_pool, EthTestPool, TransactionBuilder},
+        EthPooledTransaction,
+    };
+    use reth_primitives::{
+        kzg::Blob, sign_message, transaction::generate_blob_sidecar, Address, Transaction,
+        TransactionSigned, TxEip4844, TxHash, B256, MAINNET, U256,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use std::{collections::HashSet, io::Cursor};
+
+    const MAX_FEE_PER_GAS: u128 = 100_000_000_000;
+
+    fn eip1559(signer: B256, nonce: u64) -> PooledTransactionsElement {
+        let tx = TransactionBuilder::default()
+            .signer(signer)
+            .nonce(nonce)
+            .gas_limit(21_000)
+            .max_fee_per_gas(MAX_FEE_PER_GAS)
+            .max_priority_fee_per_gas(1_000_000_000)
+            .to(Address::random())
+            .into_eip1559();
+        PooledTransactionsElement::try_from(tx).unwrap()
+    }
+
+    fn eip4844(signer: B256, nonce: u64) -> PooledTransactionsElement {
+        let blob = Blob::from_hex(
+            serde_json::from_str::<serde_json::Value>(include_str!("../test_data/blob1.json"))
+                .unwrap()["data"]
+                .as_str()
+                .unwrap(),
+        )
+        .unwrap();
+        let sidecar = generate_blob_sidecar(vec![blob]);
+
+        let transaction = Transaction::Eip4844(TxEip4844 {
+            chain_id: MAINNET.chain.id(),
+            nonce,
+            gas_limit: 21_000,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: Address::random(),
+            blob_versioned_hashes: sidecar.versioned_hashes().collect(),
+            max_fee_per_blob_gas: MAX_FEE_PER_GAS,
+            ..Default::default()
+        });
+        let signature = sign_message(signer, transaction.signature_hash()).unwrap();
+        let tx = TransactionSigned::from_transaction_and_signature(transaction, signature);
+        PooledTransactionsElement::BlobTransaction(
+            reth_primitives::BlobTransaction::try_from_signed(tx, sidecar).unwrap(),
+        )
+    }
+
+    fn hashes<T: PoolTransaction>(
+        txs: Vec<std::sync::Arc<crate::ValidPoolTransaction<T>>>,
+    ) -> HashSet<TxHash> {
+        txs.into_iter().map(|tx| *tx.hash()).collect()
+    }
+
+    /// Returns a pool with a pending and a queued regular transaction and a pending blob
+    /// transaction, and the provider that backs it.
+    async fn populated_pool() -> (EthTestPool<MockEthProvider>, MockEthProvider) {
+        let provider = MockEthProvider::default();
+        let pool = eth_testing_pool(provider.clone());
+
+        let (regular, blob) = (B256::random(), B256::random());
+        let transactions = [
+            (TransactionOrigin::External, eip1559(regular, 0)),
+            // nonce gap
+            (TransactionOrigin::Local, eip1559(regular, 2)),
+            (TransactionOrigin::Private, eip4844(blob, 0)),
+        ];
+        for (origin, tx) in transactions {
+            let tx = tx.try_into_ecrecovered().unwrap();
+            provider.add_account(tx.signer(), ExtendedAccount::new(0, U256::MAX));
+            pool.add_transaction(
+                origin,
+                EthPooledTransaction::from_recovered_pooled_transaction(tx),
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(pool.pending_transactions().len(), 2);
+        assert_eq!(pool.queued_transactions().len(), 1);
+
+        (pool, provider)
+    }
+
+    #[test]
+    fn snapshot_entry_roundtrip() {
+        for origin in
+            [TransactionOrigin::Local, TransactionOrigin::External, TransactionOrigin::Private]
+        {
+            let entry = PoolSnapshotEntry { origin, transaction: eip1559(B256::random(), 0) };
+            let mut buf = Vec::new();
+            entry.encode(&mut buf);
+            assert_eq!(buf.len(), entry.length());
+            assert_eq!(PoolSnapshotEntry::decode(&mut buf.as_slice()).unwrap(), entry);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dump_and_restore_through_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("txpool.snapshot");
+
+        let (pool, provider) = populated_pool().await;
+        assert_eq!(dump_pool_to_file(&pool, &path).unwrap(), 3);
+
+        let restored = eth_testing_pool(provider);
+        let outcome = restore_pool_from_file(&restored, &path).await.unwrap();
+        assert_eq!(outcome, RestoreOutcome { restored: 3, skipped: 0 });
+
+        assert_eq!(hashes(restored.pending_transactions()), hashes(pool.pending_transactions()));
+        assert_eq!(hashes(restored.queued_transactions()), hashes(pool.queued_transactions()));
+        let AllPoolTransactions { pending, queued } = pool.all_transactions();
+        for tx in pending.into_iter().chain(queued) {
+            assert_eq!(restored.get(tx.hash()).unwrap().origin, tx.origin);
+            if tx.is_eip4844() {
+                // the sidecar was restored with the transaction
+                assert!(restored.get_blob(*tx.hash()).unwrap().is_some());
+            }
+        }
+
+        // restoring again skips the transactions that are already in the pool
+        let outcome = restore_pool_from_file(&restored, &path).await.unwrap();
+        assert_eq!(outcome, RestoreOutcome { restored: 0, skipped: 3 });
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn restore_rejects_invalid_snapshots() {
+        let (pool, provider) = populated_pool().await;
+        let mut snapshot = Vec::new();
+        dump_pool(&pool, &mut snapshot).unwrap();
+
+        assert_eq!(check_snapshot(Cursor::new(&snapshot)).unwrap(), 3);
+        let restored = eth_testing_pool(provider);
+
+        let mut wrong_version = snapshot.clone();
+        wrong_version[POOL_SNAPSHOT_MAGIC.len()] = POOL_SNAPSHOT_VERSION + 1;
+        assert!(matches!(
+            restore_pool(&restored, Cursor::new(wrong_version)).await,
+            Err(PoolSnapshotError::UnsupportedVersion(_))
+        ));
+
+        let mut wrong_magic = snapshot.clone();
+        wrong_magic[0] ^= 0xff;
+        assert!(matches!(
+            restore_pool(&restored, Cursor::new(wrong_magic)).await,
+            Err(PoolSnapshotError::InvalidMagic)
+        ));
+
+        // cut off in the middle of the last entry
+        let truncated = &snapshot[..snapshot.len() - 100];
+        assert!(matches!(
+            restore_pool(&restored, Cursor::new(truncated)).await,
+            Err(PoolSnapshotError::Truncated)
+        ));
+
+        // flip a byte in the first entry, behind its length prefix and list header
+        let mut corrupted = snapshot.clone();
+        corrupted[POOL_SNAPSHOT_MAGIC.len() + 1 + 4 + 3] = 0xff;
+        let err = restore_pool(&restored, Cursor::new(corrupted)).await.unwrap_err();
+        assert!(err.is_invalid_snapshot(), "{err}");
+
+        let mut trailing = snapshot.clone();
+        trailing.push(0);
+        assert!(matches!(
+            restore_pool(&restored, Cursor::new(trailing)).await,
+            Err(PoolSnapshotError::TrailingData)
+        ));
+
+        // nothing was inserted
+        assert!(restored.is_empty());
+        assert_eq!(
+            restore_pool(&restored, Cursor::new(&snapshot)).await.unwrap(),
+            RestoreOutcome { restored: 3, skipped: 0 }
+        );
+    }
+}
@@ -16,10 +16,11 @@ use reth_primitives::{
     constants::{eip4844::DATA_GAS_PER_BLOB, MIN_PROTOCOL_BASE_FEE},
     transaction::TryFromRecoveredTransactionError,
     AccessList, Address, BlobTransactionSidecar, BlobTransactionValidationError, Bytes, ChainId,
-    FromRecoveredPooledTransaction, IntoRecoveredTransaction, PooledTransactionsElementEcRecovered,
-    Signature, Transaction, TransactionSigned, TransactionSignedEcRecovered,
-    TryFromRecoveredTransaction, TxEip1559, TxEip2930, TxEip4844, TxHash, TxKind, TxLegacy, TxType,
-    B256, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID, U256,
+    FromRecoveredPooledTransaction, IntoRecoveredTransaction, PooledTransactionsElement,
+    PooledTransactionsElementEcRecovered, Signature, Transaction, TransactionSigned,
+    TransactionSignedEcRecovered, TryFromRecoveredTransaction, TxEip1559, TxEip2930, TxEip4844,
+    TxHash, TxKind, TxLegacy, TxType, B256, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID,
+    EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID, U256,
 };
 use std::{ops::Range, sync::Arc, time::Instant, vec::IntoIter};
 
@@ -424,6 +425,17 @@ impl MockTransaction {
         self
     }
 
+    /// Sets the recipient of the transaction using a fluent interface.
+    pub fn with_to(mut self, recipient: Address) -> Self {
+        match self {
+            Self::Legacy { ref mut to, .. } |
+            Self::Eip1559 { ref mut to, .. } |
+            Self::Eip2930 { ref mut to, .. } => *to = recipient.into(),
+            Self::Eip4844 { ref mut to, .. } => *to = recipient,
+        }
+        self
+    }
+
     /// Gets the gas price for the transaction.
     pub const fn get_gas_price(&self) -> u128 {
         match self {
@@ -867,10 +879,20 @@ impl TryFromRecoveredTransaction for MockTransaction {
 
 impl FromRecoveredPooledTransaction for MockTransaction {
     fn from_recovered_pooled_transaction(tx: PooledTransactionsElementEcRecovered) -> Self {
-        TryFromRecoveredTransaction::try_from_recovered_transaction(
+        let blob_sidecar = match &*tx {
+            PooledTransactionsElement::BlobTransaction(blob_tx) => Some(blob_tx.sidecar.clone()),
+            _ => None,
+        };
+        let mut transaction: Self = TryFromRecoveredTransaction::try_from_recovered_transaction(
             tx.into_ecrecovered_transaction(),
         )
-        .expect("Failed to convert from PooledTransactionsElementEcRecovered to MockTransaction")
+        .expect("Failed to convert from PooledTransactionsElementEcRecovered to MockTransaction");
+        if let (Self::Eip4844 { sidecar, .. }, Some(blob_sidecar)) =
+            (&mut transaction, blob_sidecar)
+        {
+            *sidecar = blob_sidecar;
+        }
+        transaction
     }
 }
 
@@ -1136,6 +1158,7 @@ impl MockTransactionFactory {
             transaction,
             timestamp: Instant::now(),
             origin,
+            conditional: None,
         }
     }
 
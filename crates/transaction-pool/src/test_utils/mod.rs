@@ -1,6 +1,12 @@
 //! Internal helpers for testing.
 
-use crate::{blobstore::InMemoryBlobStore, noop::MockTransactionValidator, Pool, PoolConfig};
+use crate::{
+    blobstore::InMemoryBlobStore,
+    noop::MockTransactionValidator,
+    validate::{EthTransactionValidator, EthTransactionValidatorBuilder},
+    CoinbaseTipOrdering, EthPooledTransaction, Pool, PoolConfig,
+};
+use reth_primitives::MAINNET;
 use std::ops::Deref;
 
 mod gen;
@@ -15,6 +21,14 @@ mod pool;
 pub type TestPool =
     Pool<MockTransactionValidator<MockTransaction>, MockOrdering, InMemoryBlobStore>;
 
+/// A [Pool] that validates transactions with an [`EthTransactionValidator`] for mainnet, used for
+/// testing
+pub type EthTestPool<Client> = Pool<
+    EthTransactionValidator<Client, EthPooledTransaction>,
+    CoinbaseTipOrdering<EthPooledTransaction>,
+    InMemoryBlobStore,
+>;
+
 /// Structure encapsulating a [`TestPool`] used for testing
 #[derive(Debug, Clone)]
 pub struct TestPoolBuilder(TestPool);
@@ -90,3 +104,11 @@ impl Deref for TestPoolBuilder {
 pub fn testing_pool() -> TestPool {
     TestPoolBuilder::default().into()
 }
+
+/// Returns a new [`EthTestPool`] that validates transactions against the state of the given client
+pub fn eth_testing_pool<Client>(client: Client) -> EthTestPool<Client> {
+    let blob_store = InMemoryBlobStore::default();
+    let validator =
+        EthTransactionValidatorBuilder::new(MAINNET.clone()).build(client, blob_store.clone());
+    Pool::new(validator, CoinbaseTipOrdering::default(), blob_store, PoolConfig::default())
+}
@@ -3,7 +3,7 @@
 use crate::{
     blobstore::BlobStoreError,
     error::PoolResult,
-    pool::{state::SubPool, BestTransactionFilter, TransactionEvents},
+    pool::{state::SubPool, BestTransactionFilter, TransactionEvents, TransactionFilter},
     validate::ValidPoolTransaction,
     AllTransactionsEvents,
 };
@@ -11,10 +11,11 @@ use futures_util::{ready, Stream};
 use reth_eth_wire_types::HandleMempoolData;
 use reth_primitives::{
     kzg::KzgSettings, transaction::TryFromRecoveredTransactionError, AccessList, Address,
-    BlobTransactionSidecar, BlobTransactionValidationError, FromRecoveredPooledTransaction,
-    IntoRecoveredTransaction, PooledTransactionsElement, PooledTransactionsElementEcRecovered,
-    SealedBlock, Transaction, TransactionSignedEcRecovered, TryFromRecoveredTransaction, TxHash,
-    TxKind, B256, EIP1559_TX_TYPE_ID, EIP4844_TX_TYPE_ID, U256,
+    BlobAndProofV1, BlobTransactionSidecar, BlobTransactionValidationError,
+    FromRecoveredPooledTransaction, IntoRecoveredTransaction, PooledTransactionsElement,
+    PooledTransactionsElementEcRecovered, SealedBlock, Transaction, TransactionConditional,
+    TransactionSignedEcRecovered, TryFromRecoveredTransaction, TxHash, TxKind, B256,
+    EIP1559_TX_TYPE_ID, EIP4844_TX_TYPE_ID, U256,
 };
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -97,6 +98,17 @@ pub trait TransactionPool: Send + Sync + Clone {
         transaction: Self::Transaction,
     ) -> impl Future<Output = PoolResult<TxHash>> + Send;
 
+    /// Adds an _unvalidated_ transaction into the pool that may only be included in a block if
+    /// the given [`TransactionConditional`] holds.
+    ///
+    /// Consumer: RPC
+    fn add_transaction_with_conditional(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        conditional: TransactionConditional,
+    ) -> impl Future<Output = PoolResult<TxHash>> + Send;
+
     /// Adds the given _unvalidated_ transaction into the pool.
     ///
     /// Returns a list of results.
@@ -116,6 +128,16 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// Returns a new transaction change event stream for _all_ transactions in the pool.
     fn all_transactions_event_listener(&self) -> AllTransactionsEvents<Self::Transaction>;
 
+    /// Returns a new transaction change event stream for transactions in the pool that match the
+    /// given filter.
+    ///
+    /// Once a transaction matched the filter, all of its events are delivered until it was
+    /// mined, replaced or discarded, even if the replacing transaction does not match.
+    fn transaction_event_listener_filtered(
+        &self,
+        filter: TransactionFilter,
+    ) -> AllTransactionsEvents<Self::Transaction>;
+
     /// Returns a new Stream that yields transactions hashes for new __pending__ transactions
     /// inserted into the pool that are allowed to be propagated.
     ///
@@ -360,6 +382,15 @@ pub trait TransactionPool: Send + Sync + Clone {
         self.get_transactions_by_origin(TransactionOrigin::External)
     }
 
+    /// Returns the pending and queued transactions in the pool that match the given
+    /// [TransactionFilter].
+    ///
+    /// The effective tip of the transactions is computed with the pool's pending base fee.
+    fn pooled_transactions_matching(
+        &self,
+        filter: &TransactionFilter,
+    ) -> AllPoolTransactions<Self::Transaction>;
+
     /// Returns a set of all senders of transactions in the pool
     fn unique_senders(&self) -> HashSet<Address>;
 
@@ -385,6 +416,16 @@ pub trait TransactionPool: Send + Sync + Clone {
         &self,
         tx_hashes: Vec<TxHash>,
     ) -> Result<Vec<BlobTransactionSidecar>, BlobStoreError>;
+
+    /// Returns the [BlobAndProofV1] for each of the given versioned hashes, in the order they were
+    /// requested.
+    ///
+    /// The entry of a versioned hash is `None` if the blob store holds no blob with this versioned
+    /// hash.
+    fn get_blobs_for_versioned_hashes(
+        &self,
+        versioned_hashes: &[B256],
+    ) -> Result<Vec<Option<BlobAndProofV1>>, BlobStoreError>;
 }
 
 /// Extension for [TransactionPool] trait that allows to set the current block info.
@@ -421,6 +462,14 @@ pub trait TransactionPoolExt: TransactionPool {
 
     /// Maintenance function to cleanup blobs that are no longer needed.
     fn cleanup_blobs(&self);
+
+    /// Removes all transactions of the queued sub-pool that exceeded their configured lifetime
+    /// and returns their hashes.
+    ///
+    /// Listeners receive a [`TransactionEvent::Discarded`](crate::TransactionEvent::Discarded)
+    /// event with [`DiscardReason::Timeout`](crate::DiscardReason::Timeout) for each removed
+    /// transaction.
+    fn remove_expired_queued_transactions(&self) -> Vec<TxHash>;
 }
 
 /// Determines what kind of new transactions should be emitted by a stream of transactions.
@@ -434,6 +483,9 @@ pub enum TransactionListenerKind {
     ///
     /// See also [`ValidPoolTransaction`]
     PropagateOnly,
+    /// Only transactions that were submitted locally, i.e. with a [`TransactionOrigin::Local`] or
+    /// [`TransactionOrigin::Private`] origin.
+    LocalOnly,
 }
 
 impl TransactionListenerKind {
@@ -442,6 +494,22 @@ impl TransactionListenerKind {
     pub const fn is_propagate_only(&self) -> bool {
         matches!(self, Self::PropagateOnly)
     }
+
+    /// Returns true if we're only interested in transactions that were submitted locally.
+    #[inline]
+    pub const fn is_local_only(&self) -> bool {
+        matches!(self, Self::LocalOnly)
+    }
+
+    /// Returns true if the transaction should be emitted to a listener of this kind.
+    #[inline]
+    pub(crate) const fn includes<T: PoolTransaction>(&self, tx: &ValidPoolTransaction<T>) -> bool {
+        match self {
+            Self::All => true,
+            Self::PropagateOnly => tx.propagate,
+            Self::LocalOnly => !tx.origin.is_external(),
+        }
+    }
 }
 
 /// A Helper type that bundles all transactions in the pool.
@@ -451,7 +519,8 @@ pub struct AllPoolTransactions<T: PoolTransaction> {
     pub pending: Vec<Arc<ValidPoolTransaction<T>>>,
     /// Transactions that are ready for inclusion in _future_ blocks, but are currently parked,
     /// because they depend on other transactions that are not yet included in the pool (nonce gap)
-    /// or otherwise blocked.
+    /// or otherwise blocked, e.g. blob transactions with a blob fee cap below the current blob
+    /// fee.
     pub queued: Vec<Arc<ValidPoolTransaction<T>>>,
 }
 
@@ -16,12 +16,15 @@ use reth_primitives::{
     },
     kzg::KzgSettings,
     revm::compat::calculate_intrinsic_gas_after_merge,
-    ChainSpec, GotExpected, InvalidTransactionError, SealedBlock, EIP1559_TX_TYPE_ID,
-    EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID,
+    Account, Address, ChainSpec, GotExpected, InvalidTransactionError, SealedBlock,
+    EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID,
+};
+use reth_provider::{
+    AccountReader, BlockReaderIdExt, ProviderResult, StateProviderBox, StateProviderFactory,
 };
-use reth_provider::{AccountReader, BlockReaderIdExt, StateProviderFactory};
 use reth_tasks::TaskSpawner;
 use std::{
+    collections::HashMap,
     marker::PhantomData,
     sync::{atomic::AtomicBool, Arc},
 };
@@ -64,6 +67,9 @@ where
 
     /// Validates all given transactions.
     ///
+    /// All transactions are validated against the same state and the account of each sender is
+    /// only read once.
+    ///
     /// Returns all outcomes for the given transactions in the same order.
     ///
     /// See also [`Self::validate_one`]
@@ -71,7 +77,7 @@ where
         &self,
         transactions: Vec<(TransactionOrigin, Tx)>,
     ) -> Vec<TransactionValidationOutcome<Tx>> {
-        transactions.into_iter().map(|(origin, tx)| self.validate_one(origin, tx)).collect()
+        self.inner.validate_all(transactions)
     }
 }
 
@@ -149,9 +155,32 @@ where
 {
     /// Validates a single transaction.
     fn validate_one(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Tx,
+    ) -> TransactionValidationOutcome<Tx> {
+        self.validate_one_with_state(origin, transaction, &mut BatchState::default())
+    }
+
+    /// Validates all given transactions against the same state.
+    fn validate_all(
+        &self,
+        transactions: Vec<(TransactionOrigin, Tx)>,
+    ) -> Vec<TransactionValidationOutcome<Tx>> {
+        let mut state = BatchState::default();
+        transactions
+            .into_iter()
+            .map(|(origin, tx)| self.validate_one_with_state(origin, tx, &mut state))
+            .collect()
+    }
+
+    /// Validates a single transaction, reading the sender's account through the given
+    /// [`BatchState`].
+    fn validate_one_with_state(
         &self,
         origin: TransactionOrigin,
         mut transaction: Tx,
+        state: &mut BatchState,
     ) -> TransactionValidationOutcome<Tx> {
         // Checks for tx_type
         match transaction.tx_type() {
@@ -236,13 +265,21 @@ where
         // Drop non-local transactions with a fee lower than the configured fee for acceptance into
         // the pool.
         if !self.local_transactions_config.is_local(origin, transaction.sender()) &&
-            transaction.is_eip1559() &&
-            transaction.max_priority_fee_per_gas() < self.minimum_priority_fee
+            transaction.is_eip1559()
         {
-            return TransactionValidationOutcome::Invalid(
-                transaction,
-                InvalidPoolTransactionError::Underpriced,
-            )
+            if let (Some(priority_fee), Some(minimum_priority_fee)) =
+                (transaction.max_priority_fee_per_gas(), self.minimum_priority_fee)
+            {
+                if priority_fee < minimum_priority_fee {
+                    return TransactionValidationOutcome::Invalid(
+                        transaction,
+                        InvalidPoolTransactionError::PriorityFeeBelowMinimum {
+                            priority_fee,
+                            minimum_priority_fee,
+                        },
+                    )
+                }
+            }
         }
 
         // Checks for chainid
@@ -296,12 +333,8 @@ where
             }
         }
 
-        let account = match self
-            .client
-            .latest()
-            .and_then(|state| state.basic_account(transaction.sender()))
-        {
-            Ok(account) => account.unwrap_or_default(),
+        let account = match state.basic_account(&self.client, transaction.sender()) {
+            Ok(account) => account,
             Err(err) => {
                 return TransactionValidationOutcome::Error(*transaction.hash(), Box::new(err))
             }
@@ -320,7 +353,11 @@ where
         if transaction.nonce() < account.nonce {
             return TransactionValidationOutcome::Invalid(
                 transaction,
-                InvalidTransactionError::NonceNotConsistent.into(),
+                InvalidTransactionError::NonceNotConsistent {
+                    tx: transaction.nonce(),
+                    state: account.nonce,
+                }
+                .into(),
             )
         }
 
@@ -668,6 +705,39 @@ impl EthTransactionValidatorBuilder {
     }
 }
 
+/// The state a batch of transactions is validated against.
+///
+/// The state provider is opened once for the whole batch and the account of each sender is only
+/// read once.
+#[derive(Default)]
+struct BatchState {
+    /// The latest state, opened on first access.
+    state: Option<StateProviderBox>,
+    /// Accounts that were already read.
+    accounts: HashMap<Address, Account>,
+}
+
+impl BatchState {
+    /// Returns the account of the given address, or the default account if it doesn't exist.
+    fn basic_account<Client: StateProviderFactory>(
+        &mut self,
+        client: &Client,
+        address: Address,
+    ) -> ProviderResult<Account> {
+        if let Some(account) = self.accounts.get(&address) {
+            return Ok(*account)
+        }
+
+        let state: &StateProviderBox = match self.state {
+            Some(ref state) => state,
+            None => self.state.insert(client.latest()?),
+        };
+        let account = state.basic_account(address)?.unwrap_or_default();
+        self.accounts.insert(address, account);
+        Ok(account)
+    }
+}
+
 /// Keeps track of whether certain forks are activated
 #[derive(Debug)]
 pub(crate) struct ForkTracker {
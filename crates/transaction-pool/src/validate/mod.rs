@@ -6,7 +6,7 @@ use crate::{
     traits::{PoolTransaction, TransactionOrigin},
 };
 use reth_primitives::{
-    Address, BlobTransactionSidecar, IntoRecoveredTransaction, SealedBlock,
+    Address, BlobTransactionSidecar, IntoRecoveredTransaction, SealedBlock, TransactionConditional,
     TransactionSignedEcRecovered, TxHash, B256, U256,
 };
 use std::{fmt, future::Future, time::Instant};
@@ -202,6 +202,19 @@ pub trait TransactionValidator: Send + Sync {
         }
     }
 
+    /// Validates a batch of transactions with the same origin.
+    ///
+    /// Must return all outcomes for the given transactions in the same order.
+    ///
+    /// See also [`Self::validate_transactions`].
+    fn validate_transactions_batch(
+        &self,
+        origin: TransactionOrigin,
+        transactions: Vec<Self::Transaction>,
+    ) -> impl Future<Output = Vec<TransactionValidationOutcome<Self::Transaction>>> + Send {
+        self.validate_transactions(transactions.into_iter().map(|tx| (origin, tx)).collect())
+    }
+
     /// Invoked when the head block changes.
     ///
     /// This can be used to update fork specific values (timestamp).
@@ -225,6 +238,9 @@ pub struct ValidPoolTransaction<T: PoolTransaction> {
     pub timestamp: Instant,
     /// Where this transaction originated from.
     pub origin: TransactionOrigin,
+    /// The conditions that must hold for the transaction to be included, if it was submitted
+    /// with any.
+    pub conditional: Option<Box<TransactionConditional>>,
 }
 
 // === impl ValidPoolTransaction ===
@@ -250,6 +266,11 @@ impl<T: PoolTransaction> ValidPoolTransaction<T> {
         self.transaction.to()
     }
 
+    /// Returns the conditions that must hold for the transaction to be included.
+    pub fn conditional(&self) -> Option<&TransactionConditional> {
+        self.conditional.as_deref()
+    }
+
     /// Returns the internal identifier for the sender of this transaction
     pub(crate) const fn sender_id(&self) -> SenderId {
         self.transaction_id.sender
@@ -354,6 +375,7 @@ impl<T: PoolTransaction + Clone> Clone for ValidPoolTransaction<T> {
             propagate: self.propagate,
             timestamp: self.timestamp,
             origin: self.origin,
+            conditional: self.conditional.clone(),
         }
     }
 }
@@ -7,16 +7,20 @@ use crate::{
     TransactionValidator,
 };
 use futures_util::{lock::Mutex, StreamExt};
-use reth_primitives::{ChainSpec, SealedBlock};
+use reth_primitives::{ChainSpec, SealedBlock, TxHash};
 use reth_provider::BlockReaderIdExt;
 use reth_tasks::TaskSpawner;
-use std::{future::Future, pin::Pin, sync::Arc};
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc};
 use tokio::{
     sync,
     sync::{mpsc, oneshot},
 };
 use tokio_stream::wrappers::ReceiverStream;
 
+/// The maximum number of transactions of a batch that are validated by a single validation job,
+/// unless they share a sender.
+const MAX_VALIDATION_JOB_SIZE: usize = 64;
+
 /// Represents a future outputting unit type and is sendable.
 type ValidationFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
 
@@ -208,7 +212,103 @@ where
         }
     }
 
+    /// Splits the batch into chunks that are validated in parallel by the validation tasks.
+    ///
+    /// All transactions of a sender are validated by the same job, so that validators that cache
+    /// state per batch only read the sender's account once.
+    async fn validate_transactions(
+        &self,
+        transactions: Vec<(TransactionOrigin, Self::Transaction)>,
+    ) -> Vec<TransactionValidationOutcome<Self::Transaction>> {
+        let num_transactions = transactions.len();
+        let num_jobs = num_transactions.div_ceil(MAX_VALIDATION_JOB_SIZE);
+
+        let mut jobs = (0..num_jobs).map(|_| Vec::new()).collect::<Vec<_>>();
+        let mut job_by_sender = HashMap::new();
+        for (idx, (origin, transaction)) in transactions.into_iter().enumerate() {
+            let next_job = job_by_sender.len() % num_jobs;
+            let job = *job_by_sender.entry(transaction.sender()).or_insert(next_job);
+            jobs[job].push((idx, (origin, transaction)));
+        }
+
+        let mut pending = Vec::with_capacity(num_jobs);
+        {
+            let to_validation_task = self.to_validation_task.lock().await;
+            for job in jobs.into_iter().filter(|job| !job.is_empty()) {
+                let (indices, transactions): (Vec<_>, Vec<_>) = job.into_iter().unzip();
+                let hashes = transactions.iter().map(|(_, tx)| *tx.hash()).collect::<Vec<TxHash>>();
+                let (tx, rx) = oneshot::channel();
+                let validator = self.validator.clone();
+                let res = to_validation_task
+                    .send(Box::pin(async move {
+                        let res = validator.validate_transactions(transactions).await;
+                        let _ = tx.send(res);
+                    }))
+                    .await;
+                pending.push((indices, hashes, res.ok().map(|_| rx)));
+            }
+        }
+
+        let mut outcomes = (0..num_transactions).map(|_| None).collect::<Vec<_>>();
+        for (indices, hashes, rx) in pending {
+            let res = match rx {
+                Some(rx) => rx.await.ok(),
+                None => None,
+            };
+            match res {
+                Some(res) => {
+                    for (idx, outcome) in indices.into_iter().zip(res) {
+                        outcomes[idx] = Some(outcome);
+                    }
+                }
+                None => {
+                    for (idx, hash) in indices.into_iter().zip(hashes) {
+                        outcomes[idx] = Some(TransactionValidationOutcome::Error(
+                            hash,
+                            Box::new(TransactionValidatorError::ValidationServiceUnreachable),
+                        ));
+                    }
+                }
+            }
+        }
+
+        outcomes.into_iter().map(|outcome| outcome.expect("all transactions validated")).collect()
+    }
+
     fn on_new_head_block(&self, new_tip_block: &SealedBlock) {
         self.validator.on_new_head_block(new_tip_block)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{noop::MockTransactionValidator, test_utils::MockTransaction};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn validate_transactions_in_order() {
+        let (to_validation_task, task) = ValidationTask::new();
+        for _ in 0..2 {
+            tokio::spawn(task.clone().run());
+        }
+        let executor = TransactionValidationTaskExecutor {
+            validator: MockTransactionValidator::<MockTransaction>::default(),
+            to_validation_task: Arc::new(sync::Mutex::new(to_validation_task)),
+        };
+
+        // interleave the nonce chains of a few senders across multiple validation jobs
+        let mut senders = (0..10).map(|_| MockTransaction::eip1559()).collect::<Vec<_>>();
+        let mut transactions = Vec::new();
+        for _ in 0..30 {
+            for tx in &mut senders {
+                transactions.push((TransactionOrigin::External, tx.clone()));
+                *tx = tx.next();
+            }
+        }
+        let hashes = transactions.iter().map(|(_, tx)| *tx.hash()).collect::<Vec<_>>();
+
+        let outcomes = executor.validate_transactions(transactions).await;
+        assert!(outcomes.iter().all(|outcome| outcome.is_valid()));
+        assert_eq!(outcomes.iter().map(|outcome| outcome.tx_hash()).collect::<Vec<_>>(), hashes);
+    }
+}
@@ -1,11 +1,13 @@
 use assert_matches::assert_matches;
+use futures_util::Stream;
+use reth_primitives::Address;
 use reth_transaction_pool::{
     noop::MockTransactionValidator,
-    test_utils::{MockTransactionFactory, TestPoolBuilder},
-    FullTransactionEvent, TransactionEvent, TransactionListenerKind, TransactionOrigin,
-    TransactionPool,
+    test_utils::{MockTransaction, MockTransactionFactory, TestPoolBuilder},
+    FullTransactionEvent, TransactionEvent, TransactionFilter, TransactionListenerKind,
+    TransactionOrigin, TransactionPool,
 };
-use std::{future::poll_fn, task::Poll};
+use std::{future::poll_fn, pin::Pin, task::Poll};
 use tokio_stream::StreamExt;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -89,3 +91,70 @@ async fn txpool_listener_new_propagate_only() {
     })
     .await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_listener_new_local_only() {
+    let txpool = TestPoolBuilder::default();
+    let mut mock_tx_factory = MockTransactionFactory::default();
+    let mut listener_local =
+        txpool.new_transactions_listener_for(TransactionListenerKind::LocalOnly);
+
+    let external = mock_tx_factory.create_eip1559();
+    txpool
+        .add_transaction(TransactionOrigin::External, external.transaction.clone())
+        .await
+        .unwrap();
+    let local = mock_tx_factory.create_eip1559();
+    let expected = *local.hash();
+    txpool.add_transaction(TransactionOrigin::Local, local.transaction.clone()).await.unwrap();
+
+    // the external transaction is never sent to the listener
+    let inserted = listener_local.recv().await.unwrap();
+    assert_eq!(*inserted.transaction.hash(), expected);
+
+    poll_fn(|cx| {
+        assert!(listener_local.poll_recv(cx).is_pending());
+        Poll::Ready(())
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_listener_filtered() {
+    let txpool = TestPoolBuilder::default();
+    let recipient = Address::random();
+    let mut filtered_events = txpool.transaction_event_listener_filtered(
+        TransactionFilter::default().with_recipients([recipient]),
+    );
+
+    // a transaction that doesn't match the filter
+    let other = MockTransaction::eip1559();
+    txpool.add_transaction(TransactionOrigin::External, other).await.unwrap();
+
+    let transaction =
+        MockTransaction::eip1559().with_to(recipient).with_max_fee(1000).with_priority_fee(100);
+    let hash =
+        txpool.add_transaction(TransactionOrigin::External, transaction.clone()).await.unwrap();
+    assert_matches!(
+        filtered_events.next().await,
+        Some(FullTransactionEvent::Pending(pending)) if pending == hash
+    );
+
+    // the replacement doesn't match the filter, but the replaced transaction did
+    let replacement =
+        transaction.rng_hash().with_to(Address::random()).with_max_fee(2000).with_priority_fee(200);
+    let replaced_by =
+        txpool.add_transaction(TransactionOrigin::External, replacement).await.unwrap();
+    assert_matches!(
+        filtered_events.next().await,
+        Some(FullTransactionEvent::Replaced { transaction, replaced_by: by })
+            if *transaction.hash() == hash && by == replaced_by
+    );
+
+    poll_fn(|cx| {
+        // no events for non matching transactions
+        assert!(Pin::new(&mut filtered_events).poll_next(cx).is_pending());
+        Poll::Ready(())
+    })
+    .await;
+}
@@ -181,6 +181,7 @@ where
         }
 
         let root = hash_builder.root();
+        tracker.add_walk_stats(account_node_iter.stats());
 
         trie_updates.finalize_state_updates(
             account_node_iter.walker,
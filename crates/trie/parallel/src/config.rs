@@ -0,0 +1,88 @@
+use std::num::NonZeroUsize;
+
+/// Default minimum number of changed prefixes (storage tries) assigned to a single worker.
+pub const DEFAULT_MIN_PREFIXES_PER_WORKER: usize = 4;
+
+/// Default number of changed prefixes (storage tries) below which the storage roots are computed
+/// sequentially.
+pub const DEFAULT_FALLBACK_THRESHOLD: usize = 8;
+
+/// Configuration of the parallel state root computation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ParallelStateRootConfig {
+    /// Maximum number of workers computing storage roots concurrently.
+    pub workers: usize,
+    /// Minimum number of changed prefixes (storage tries) assigned to a single worker. Prevents
+    /// spawning a worker for every storage trie when there are only a few of them.
+    pub min_prefixes_per_worker: usize,
+    /// Number of changed prefixes (storage tries) below which storage roots are computed
+    /// sequentially on the calling thread.
+    pub fallback_threshold: usize,
+}
+
+impl Default for ParallelStateRootConfig {
+    fn default() -> Self {
+        Self {
+            workers: std::thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            min_prefixes_per_worker: DEFAULT_MIN_PREFIXES_PER_WORKER,
+            fallback_threshold: DEFAULT_FALLBACK_THRESHOLD,
+        }
+    }
+}
+
+impl ParallelStateRootConfig {
+    /// Sets the maximum number of workers.
+    pub const fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Sets the minimum number of changed prefixes assigned to a single worker.
+    pub const fn with_min_prefixes_per_worker(mut self, min_prefixes_per_worker: usize) -> Self {
+        self.min_prefixes_per_worker = min_prefixes_per_worker;
+        self
+    }
+
+    /// Sets the number of changed prefixes below which the computation falls back to the
+    /// sequential path.
+    pub const fn with_fallback_threshold(mut self, fallback_threshold: usize) -> Self {
+        self.fallback_threshold = fallback_threshold;
+        self
+    }
+
+    /// Returns `true` if the storage roots for the given number of changed prefixes should be
+    /// computed sequentially.
+    pub const fn should_fallback(&self, prefixes: usize) -> bool {
+        prefixes < self.fallback_threshold || self.workers <= 1
+    }
+
+    /// Returns the number of workers that the given number of changed prefixes should be split
+    /// between, respecting both the maximum number of workers and the minimum number of prefixes
+    /// per worker.
+    pub fn workers_for(&self, prefixes: usize) -> usize {
+        (prefixes / self.min_prefixes_per_worker.max(1)).clamp(1, self.workers.max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workers_for_prefixes() {
+        let config = ParallelStateRootConfig::default()
+            .with_workers(8)
+            .with_min_prefixes_per_worker(4)
+            .with_fallback_threshold(10);
+
+        assert!(config.should_fallback(9));
+        assert!(!config.should_fallback(10));
+
+        assert_eq!(config.workers_for(0), 1);
+        assert_eq!(config.workers_for(7), 1);
+        assert_eq!(config.workers_for(12), 3);
+        assert_eq!(config.workers_for(1000), 8);
+
+        assert!(config.with_workers(1).should_fallback(1000));
+    }
+}
@@ -7,6 +7,11 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+mod config;
+pub use config::{
+    ParallelStateRootConfig, DEFAULT_FALLBACK_THRESHOLD, DEFAULT_MIN_PREFIXES_PER_WORKER,
+};
+
 mod storage_root_targets;
 pub use storage_root_targets::StorageRootTargets;
 
@@ -21,6 +26,10 @@ pub mod async_root;
 #[cfg(feature = "parallel")]
 pub mod parallel_root;
 
+/// Implementation of parallel multiproof generation.
+#[cfg(feature = "parallel")]
+pub mod parallel_proof;
+
 /// Parallel state root metrics.
 #[cfg(feature = "metrics")]
 pub mod metrics;
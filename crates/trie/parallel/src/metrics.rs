@@ -1,5 +1,5 @@
 use crate::stats::ParallelTrieStats;
-use metrics::Histogram;
+use metrics::{Counter, Histogram};
 use reth_metrics::Metrics;
 use reth_trie::metrics::{TrieRootMetrics, TrieType};
 
@@ -30,6 +30,13 @@ impl ParallelStateRootMetrics {
         self.state_trie.record(stats.trie_stats());
         self.parallel.precomputed_storage_roots.record(stats.precomputed_storage_roots() as f64);
         self.parallel.missed_leaves.record(stats.missed_leaves() as f64);
+        self.parallel.storage_root_tasks.record(stats.storage_root_tasks() as f64);
+        self.parallel
+            .longest_storage_root_task_duration_seconds
+            .record(stats.longest_storage_root_task());
+        if stats.sequential_fallback() {
+            self.parallel.sequential_fallbacks.increment(1);
+        }
     }
 }
 
@@ -41,4 +48,10 @@ pub struct ParallelTrieMetrics {
     pub precomputed_storage_roots: Histogram,
     /// The number of leaves for which we did not pre-compute the storage roots.
     pub missed_leaves: Histogram,
+    /// The number of spawned tasks that pre-computed storage roots.
+    pub storage_root_tasks: Histogram,
+    /// The duration of the longest storage root pre-computation task.
+    pub longest_storage_root_task_duration_seconds: Histogram,
+    /// The number of computations that pre-computed storage roots sequentially.
+    pub sequential_fallbacks: Counter,
 }
@@ -0,0 +1,202 @@
+use crate::{parallel_root::ParallelStateRootError, ParallelStateRootConfig};
+use rayon::prelude::*;
+use reth_db_api::database::Database;
+use reth_execution_errors::StateRootError;
+use reth_primitives::{
+    trie::{MultiProof, StorageMultiProof},
+    B256,
+};
+use reth_provider::{providers::ConsistentDbView, DatabaseProviderFactory, ProviderError};
+use reth_trie::proof::Proof;
+use std::collections::{HashMap, HashSet};
+use tracing::*;
+
+/// Parallel multiproof generator.
+///
+/// The generator computes storage multiproofs of the target accounts in parallel, each worker
+/// using its own database transaction opened through [`ConsistentDbView`]. Once that's done, the
+/// state trie is walked on the calling thread and the pre-computed storage multiproofs are
+/// assembled into the resulting [`MultiProof`].
+///
+/// The result is identical to the one generated sequentially by [`Proof::multiproof`].
+#[derive(Debug)]
+pub struct ParallelProof<DB, Provider> {
+    /// Consistent view of the database.
+    view: ConsistentDbView<DB, Provider>,
+    /// Parallelism configuration.
+    config: ParallelStateRootConfig,
+}
+
+impl<DB, Provider> ParallelProof<DB, Provider> {
+    /// Create new parallel multiproof generator.
+    pub fn new(view: ConsistentDbView<DB, Provider>) -> Self {
+        Self { view, config: ParallelStateRootConfig::default() }
+    }
+
+    /// Set the parallelism configuration.
+    pub const fn with_config(mut self, config: ParallelStateRootConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl<DB, Provider> ParallelProof<DB, Provider>
+where
+    DB: Database,
+    Provider: DatabaseProviderFactory<DB> + Send + Sync,
+{
+    /// Generate a state multiproof for the target accounts and their storage slots. The targets
+    /// are hashed addresses mapped to the sets of hashed storage slots.
+    pub fn multiproof(
+        self,
+        targets: HashMap<B256, HashSet<B256>>,
+    ) -> Result<MultiProof, ParallelStateRootError> {
+        let storage_targets =
+            targets.iter().filter(|(_, slots)| !slots.is_empty()).collect::<Vec<_>>();
+        let targets_len = storage_targets.len();
+        let sequential_fallback = self.config.should_fallback(targets_len);
+        let workers = if sequential_fallback { 1 } else { self.config.workers_for(targets_len) };
+        debug!(target: "trie::parallel_proof", len = targets_len, workers, sequential_fallback, "pre-calculating storage multiproofs");
+
+        let chunk_size = targets_len.div_ceil(workers).max(1);
+        let calculate_storage_multiproofs =
+            |chunk: &[(&B256, &HashSet<B256>)]| -> Result<_, ParallelStateRootError> {
+                let provider_ro = self.view.provider_ro()?;
+                let proof = Proof::new(provider_ro.tx_ref());
+                chunk
+                    .iter()
+                    .map(|(hashed_address, hashed_slots)| {
+                        let storage_multiproof =
+                            proof.storage_multiproof(**hashed_address, hashed_slots)?;
+                        Ok((**hashed_address, storage_multiproof))
+                    })
+                    .collect::<Result<Vec<_>, ParallelStateRootError>>()
+            };
+        let chunk_results = if sequential_fallback {
+            storage_targets
+                .chunks(chunk_size)
+                .map(calculate_storage_multiproofs)
+                .collect::<Vec<_>>()
+        } else {
+            storage_targets
+                .par_chunks(chunk_size)
+                .map(calculate_storage_multiproofs)
+                .collect::<Vec<_>>()
+        };
+
+        let mut storage_multiproofs =
+            HashMap::<B256, StorageMultiProof>::with_capacity(targets_len);
+        for result in chunk_results {
+            storage_multiproofs.extend(result?);
+        }
+
+        trace!(target: "trie::parallel_proof", "calculating account multiproof");
+        let provider_ro = self.view.provider_ro()?;
+        let multiproof = Proof::new(provider_ro.tx_ref())
+            .multiproof_with_storages(&targets, storage_multiproofs)
+            .map_err(|error| match error {
+                StateRootError::DB(error) => ParallelStateRootError::Provider(error.into()),
+                StateRootError::StorageRootError(error) => error.into(),
+                StateRootError::Cancelled => ParallelStateRootError::Cancelled,
+            })?;
+
+        Ok(multiproof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use reth_primitives::{keccak256, Account, Address, StorageEntry, U256};
+    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter};
+    use reth_trie::StateRoot;
+
+    #[test]
+    fn random_parallel_multiproof() {
+        let factory = create_test_provider_factory();
+        let consistent_view = ConsistentDbView::new(factory.clone(), None);
+
+        let mut rng = rand::thread_rng();
+        let state = (0..100)
+            .map(|_| {
+                let address = Address::random();
+                let account =
+                    Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+                let mut storage = HashMap::<B256, U256>::default();
+                let has_storage = rng.gen_bool(0.7);
+                if has_storage {
+                    for _ in 0..100 {
+                        storage.insert(
+                            B256::from(U256::from(rng.gen::<u64>())),
+                            U256::from(rng.gen::<u64>()),
+                        );
+                    }
+                }
+                (address, (account, storage))
+            })
+            .collect::<HashMap<_, _>>();
+
+        {
+            let provider_rw = factory.provider_rw().unwrap();
+            provider_rw
+                .insert_account_for_hashing(
+                    state.iter().map(|(address, (account, _))| (*address, Some(*account))),
+                )
+                .unwrap();
+            provider_rw
+                .insert_storage_for_hashing(state.iter().map(|(address, (_, storage))| {
+                    (
+                        *address,
+                        storage
+                            .iter()
+                            .map(|(slot, value)| StorageEntry { key: *slot, value: *value }),
+                    )
+                }))
+                .unwrap();
+            let (_, updates) =
+                StateRoot::from_tx(provider_rw.tx_ref()).root_with_updates().unwrap();
+            updates.flush(provider_rw.tx_ref()).unwrap();
+            provider_rw.commit().unwrap();
+        }
+
+        for _ in 0..10 {
+            // Random targets including non-existent accounts and slots.
+            let mut targets = HashMap::<B256, HashSet<B256>>::default();
+            for (address, (_, storage)) in &state {
+                if rng.gen_bool(0.3) {
+                    let mut slots = HashSet::default();
+                    for slot in storage.keys() {
+                        if rng.gen_bool(0.2) {
+                            slots.insert(keccak256(slot));
+                        }
+                    }
+                    for _ in 0..rng.gen_range(0..3) {
+                        slots.insert(B256::random());
+                    }
+                    targets.insert(keccak256(address), slots);
+                }
+            }
+            for _ in 0..rng.gen_range(0..5) {
+                targets.insert(B256::random(), HashSet::from([B256::random()]));
+            }
+
+            let provider = factory.provider().unwrap();
+            let expected = Proof::new(provider.tx_ref()).multiproof(&targets).unwrap();
+
+            for config in [
+                ParallelStateRootConfig::default(),
+                ParallelStateRootConfig::default().with_fallback_threshold(usize::MAX),
+                ParallelStateRootConfig::default().with_workers(3).with_min_prefixes_per_worker(1),
+            ] {
+                assert_eq!(
+                    ParallelProof::new(consistent_view.clone())
+                        .with_config(config)
+                        .multiproof(targets.clone())
+                        .unwrap(),
+                    expected
+                );
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{stats::ParallelTrieTracker, storage_root_targets::StorageRootTargets};
+use crate::{
+    stats::ParallelTrieTracker, storage_root_targets::StorageRootTargets, ParallelStateRootConfig,
+};
 use alloy_rlp::{BufMut, Encodable};
 use rayon::prelude::*;
 use reth_db_api::database::Database;
@@ -11,12 +13,13 @@ use reth_provider::{providers::ConsistentDbView, DatabaseProviderFactory, Provid
 use reth_trie::{
     hashed_cursor::{HashedCursorFactory, HashedPostStateCursorFactory},
     node_iter::{TrieElement, TrieNodeIter},
-    trie_cursor::TrieCursorFactory,
+    prefix_set::PrefixSet,
+    trie_cursor::{CachedTrieCursorFactory, TrieCursorFactory, TrieNodeCache},
     updates::TrieUpdates,
     walker::TrieWalker,
-    HashedPostState, StorageRoot,
+    CancellationToken, HashedPostState, StorageRoot, DEFAULT_PROGRESS_INTERVAL,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Instant};
 use thiserror::Error;
 use tracing::*;
 
@@ -34,6 +37,9 @@ use crate::metrics::ParallelStateRootMetrics;
 /// the last transaction is open.
 /// See docs of using [`ConsistentDbView`] for caveats.
 ///
+/// The work is split between the workers according to [`ParallelStateRootConfig`]. If there are
+/// only a few changed storage tries, their roots are computed sequentially on the calling thread.
+///
 /// If possible, use more optimized `AsyncStateRoot` instead.
 #[derive(Debug)]
 pub struct ParallelStateRoot<DB, Provider> {
@@ -41,6 +47,12 @@ pub struct ParallelStateRoot<DB, Provider> {
     view: ConsistentDbView<DB, Provider>,
     /// Changed hashed state.
     hashed_state: HashedPostState,
+    /// Parallelism configuration.
+    config: ParallelStateRootConfig,
+    /// The token to check for cancellation.
+    cancellation: Option<CancellationToken>,
+    /// The cache of the database trie nodes.
+    trie_node_cache: Option<TrieNodeCache>,
     /// Parallel state root metrics.
     #[cfg(feature = "metrics")]
     metrics: ParallelStateRootMetrics,
@@ -52,10 +64,42 @@ impl<DB, Provider> ParallelStateRoot<DB, Provider> {
         Self {
             view,
             hashed_state,
+            config: ParallelStateRootConfig::default(),
+            cancellation: None,
+            trie_node_cache: None,
             #[cfg(feature = "metrics")]
             metrics: ParallelStateRootMetrics::default(),
         }
     }
+
+    /// Set the parallelism configuration.
+    pub const fn with_config(mut self, config: ParallelStateRootConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Set the cancellation token that is checked before every storage root and every
+    /// [`DEFAULT_PROGRESS_INTERVAL`] walked accounts.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Set the cache of the database trie nodes.
+    ///
+    /// The cache must be in sync with the database the view is consistent with.
+    pub fn with_trie_node_cache(mut self, cache: TrieNodeCache) -> Self {
+        self.trie_node_cache = Some(cache);
+        self
+    }
+
+    /// Returns an error if the calculation was cancelled.
+    fn ensure_not_cancelled(&self) -> Result<(), ParallelStateRootError> {
+        if self.cancellation.as_ref().is_some_and(|cancellation| cancellation.is_cancelled()) {
+            return Err(ParallelStateRootError::Cancelled)
+        }
+        Ok(())
+    }
 }
 
 impl<DB, Provider> ParallelStateRoot<DB, Provider>
@@ -87,25 +131,57 @@ where
         );
         let hashed_state_sorted = self.hashed_state.into_sorted();
 
-        // Pre-calculate storage roots in parallel for accounts which were changed.
-        tracker.set_precomputed_storage_roots(storage_root_targets.len() as u64);
-        debug!(target: "trie::parallel_state_root", len = storage_root_targets.len(), "pre-calculating storage roots");
-        let mut storage_roots = storage_root_targets
-            .into_par_iter()
-            .map(|(hashed_address, prefix_set)| {
+        // Pre-calculate storage roots for accounts which were changed. The targets are split into
+        // chunks, one per worker, and each chunk is processed with its own database transaction.
+        let targets_len = storage_root_targets.len();
+        let sequential_fallback = self.config.should_fallback(targets_len);
+        let workers = if sequential_fallback { 1 } else { self.config.workers_for(targets_len) };
+        tracker.set_precomputed_storage_roots(targets_len as u64);
+        tracker.set_storage_root_tasks(workers as u64);
+        tracker.set_sequential_fallback(sequential_fallback);
+        debug!(target: "trie::parallel_state_root", len = targets_len, workers, sequential_fallback, "pre-calculating storage roots");
+
+        let targets = storage_root_targets.into_iter().collect::<Vec<_>>();
+        let chunk_size = targets_len.div_ceil(workers).max(1);
+        let calculate_storage_roots =
+            |chunk: &[(B256, PrefixSet)]| -> Result<_, ParallelStateRootError> {
+                let start = Instant::now();
                 let provider_ro = self.view.provider_ro()?;
-                let storage_root_result = StorageRoot::new_hashed(
-                    provider_ro.tx_ref(),
-                    HashedPostStateCursorFactory::new(provider_ro.tx_ref(), &hashed_state_sorted),
-                    hashed_address,
-                    #[cfg(feature = "metrics")]
-                    self.metrics.storage_trie.clone(),
-                )
-                .with_prefix_set(prefix_set)
-                .calculate(retain_updates);
-                Ok((hashed_address, storage_root_result?))
-            })
-            .collect::<Result<HashMap<_, _>, ParallelStateRootError>>()?;
+                let hashed_cursor_factory =
+                    HashedPostStateCursorFactory::new(provider_ro.tx_ref(), &hashed_state_sorted);
+                let storage_roots = chunk
+                    .iter()
+                    .map(|(hashed_address, prefix_set)| {
+                        self.ensure_not_cancelled()?;
+                        let storage_root_result = StorageRoot::new_hashed(
+                            CachedTrieCursorFactory::new(
+                                provider_ro.tx_ref(),
+                                self.trie_node_cache.clone(),
+                            ),
+                            hashed_cursor_factory.clone(),
+                            *hashed_address,
+                            #[cfg(feature = "metrics")]
+                            self.metrics.storage_trie.clone(),
+                        )
+                        .with_prefix_set(prefix_set.clone())
+                        .calculate(retain_updates)?;
+                        Ok((*hashed_address, storage_root_result))
+                    })
+                    .collect::<Result<Vec<_>, ParallelStateRootError>>()?;
+                Ok((storage_roots, start.elapsed()))
+            };
+        let chunk_results = if sequential_fallback {
+            targets.chunks(chunk_size).map(calculate_storage_roots).collect::<Vec<_>>()
+        } else {
+            targets.par_chunks(chunk_size).map(calculate_storage_roots).collect::<Vec<_>>()
+        };
+
+        let mut storage_roots = HashMap::with_capacity(targets_len);
+        for result in chunk_results {
+            let (chunk_storage_roots, duration): (Vec<_>, _) = result?;
+            tracker.record_storage_root_task(duration);
+            storage_roots.extend(chunk_storage_roots);
+        }
 
         trace!(target: "trie::parallel_state_root", "calculating state root");
         let mut trie_updates = TrieUpdates::default();
@@ -113,7 +189,8 @@ where
         let provider_ro = self.view.provider_ro()?;
         let hashed_cursor_factory =
             HashedPostStateCursorFactory::new(provider_ro.tx_ref(), &hashed_state_sorted);
-        let trie_cursor_factory = provider_ro.tx_ref();
+        let trie_cursor_factory =
+            CachedTrieCursorFactory::new(provider_ro.tx_ref(), self.trie_node_cache.clone());
 
         let walker = TrieWalker::new(
             trie_cursor_factory.account_trie_cursor().map_err(ProviderError::Database)?,
@@ -127,12 +204,18 @@ where
 
         let mut hash_builder = HashBuilder::default().with_updates(retain_updates);
         let mut account_rlp = Vec::with_capacity(128);
+        let mut accounts_walked = 0u64;
         while let Some(node) = account_node_iter.try_next().map_err(ProviderError::Database)? {
             match node {
                 TrieElement::Branch(node) => {
                     hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
                 }
                 TrieElement::Leaf(hashed_address, account) => {
+                    accounts_walked += 1;
+                    if accounts_walked % DEFAULT_PROGRESS_INTERVAL == 0 {
+                        self.ensure_not_cancelled()?;
+                    }
+
                     let (storage_root, _, updates) = match storage_roots.remove(&hashed_address) {
                         Some(result) => result,
                         // Since we do not store all intermediate nodes in the database, there might
@@ -140,7 +223,7 @@ where
                         None => {
                             tracker.inc_missed_leaves();
                             StorageRoot::new_hashed(
-                                trie_cursor_factory,
+                                trie_cursor_factory.clone(),
                                 hashed_cursor_factory.clone(),
                                 hashed_address,
                                 #[cfg(feature = "metrics")]
@@ -163,6 +246,7 @@ where
         }
 
         let root = hash_builder.root();
+        tracker.add_walk_stats(account_node_iter.stats());
 
         trie_updates.finalize_state_updates(
             account_node_iter.walker,
@@ -183,6 +267,9 @@ where
             leaves_added = stats.leaves_added(),
             missed_leaves = stats.missed_leaves(),
             precomputed_storage_roots = stats.precomputed_storage_roots(),
+            storage_root_tasks = stats.storage_root_tasks(),
+            longest_storage_root_task = ?stats.longest_storage_root_task(),
+            sequential_fallback = stats.sequential_fallback(),
             "calculated state root"
         );
 
@@ -199,6 +286,9 @@ pub enum ParallelStateRootError {
     /// Provider error.
     #[error(transparent)]
     Provider(#[from] ProviderError),
+    /// The calculation was cancelled.
+    #[error("state root computation was cancelled")]
+    Cancelled,
 }
 
 impl From<ParallelStateRootError> for ProviderError {
@@ -208,6 +298,7 @@ impl From<ParallelStateRootError> for ProviderError {
             ParallelStateRootError::StorageRoot(StorageRootError::DB(error)) => {
                 Self::Database(error)
             }
+            ParallelStateRootError::Cancelled => Self::StateRootCancelled,
         }
     }
 }
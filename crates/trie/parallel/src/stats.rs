@@ -1,5 +1,6 @@
 use derive_more::Deref;
-use reth_trie::stats::{TrieStats, TrieTracker};
+use reth_trie::stats::{TrieStats, TrieTracker, TrieWalkStats};
+use std::time::Duration;
 
 /// Trie stats.
 #[derive(Deref, Clone, Copy, Debug)]
@@ -8,6 +9,9 @@ pub struct ParallelTrieStats {
     trie: TrieStats,
     precomputed_storage_roots: u64,
     missed_leaves: u64,
+    storage_root_tasks: u64,
+    longest_storage_root_task: Duration,
+    sequential_fallback: bool,
 }
 
 impl ParallelTrieStats {
@@ -25,6 +29,21 @@ impl ParallelTrieStats {
     pub const fn missed_leaves(&self) -> u64 {
         self.missed_leaves
     }
+
+    /// The number of spawned tasks that pre-computed the storage roots.
+    pub const fn storage_root_tasks(&self) -> u64 {
+        self.storage_root_tasks
+    }
+
+    /// The duration of the longest storage root pre-computation task.
+    pub const fn longest_storage_root_task(&self) -> Duration {
+        self.longest_storage_root_task
+    }
+
+    /// Whether the storage roots were pre-computed sequentially instead of in parallel.
+    pub const fn sequential_fallback(&self) -> bool {
+        self.sequential_fallback
+    }
 }
 
 /// Trie metrics tracker.
@@ -34,6 +53,9 @@ pub struct ParallelTrieTracker {
     trie: TrieTracker,
     precomputed_storage_roots: u64,
     missed_leaves: u64,
+    storage_root_tasks: u64,
+    longest_storage_root_task: Duration,
+    sequential_fallback: bool,
 }
 
 impl ParallelTrieTracker {
@@ -42,6 +64,21 @@ impl ParallelTrieTracker {
         self.precomputed_storage_roots = count;
     }
 
+    /// Set the number of spawned storage root tasks.
+    pub fn set_storage_root_tasks(&mut self, count: u64) {
+        self.storage_root_tasks = count;
+    }
+
+    /// Record the duration of a finished storage root task, keeping the longest one.
+    pub fn record_storage_root_task(&mut self, duration: Duration) {
+        self.longest_storage_root_task = self.longest_storage_root_task.max(duration);
+    }
+
+    /// Mark the storage roots as pre-computed sequentially.
+    pub fn set_sequential_fallback(&mut self, fallback: bool) {
+        self.sequential_fallback = fallback;
+    }
+
     /// Increment the number of branches added to the hash builder during the calculation.
     pub fn inc_branch(&mut self) {
         self.trie.inc_branch();
@@ -52,6 +89,11 @@ impl ParallelTrieTracker {
         self.trie.inc_leaf();
     }
 
+    /// Add the stats of the trie walk performed during the calculation.
+    pub fn add_walk_stats(&mut self, stats: TrieWalkStats) {
+        self.trie.add_walk_stats(stats);
+    }
+
     /// Increment the number of added leaf nodes for which we did not precompute the storage root.
     pub fn inc_missed_leaves(&mut self) {
         self.missed_leaves += 1;
@@ -63,6 +105,9 @@ impl ParallelTrieTracker {
             trie: self.trie.finish(),
             precomputed_storage_roots: self.precomputed_storage_roots,
             missed_leaves: self.missed_leaves,
+            storage_root_tasks: self.storage_root_tasks,
+            longest_storage_root_task: self.longest_storage_root_task,
+            sequential_fallback: self.sequential_fallback,
         }
     }
 }
@@ -0,0 +1,158 @@
+#![allow(missing_docs, unreachable_pub)]
+use alloy_rlp::encode_fixed_size;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use proptest::{prelude::*, strategy::ValueTree, test_runner::TestRunner};
+use reth_primitives::{
+    trie::{Nibbles, TrieAccount},
+    Account, B256, U256,
+};
+use reth_provider::{bundle_state::HashedStateChanges, test_utils::create_test_provider_factory};
+use reth_trie::{
+    blinded::{BlindedProviderFactory, DatabaseBlindedProviderFactory},
+    proof::Proof,
+    sparse::SparseTrie,
+    HashedPostState, HashedStorage, StateRoot,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Compares the state root computation of a full block from scratch with the sparse tries of the
+/// background state root task, which are revealed from the proofs of the changed slots while the
+/// block is being built and only need the changes applied and the changed paths re-hashed.
+pub fn sparse_trie(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Sparse Trie");
+    group.sample_size(20);
+
+    for size in [300, 1_000, 3_000] {
+        let (db_state, updated_state) = generate_test_data(size);
+        let provider_factory = create_test_provider_factory();
+        let state_root = {
+            let provider_rw = provider_factory.provider_rw().unwrap();
+            HashedStateChanges(db_state.clone()).write_to_db(provider_rw.tx_ref()).unwrap();
+            let (state_root, updates) =
+                StateRoot::from_tx(provider_rw.tx_ref()).root_with_updates().unwrap();
+            updates.flush(provider_rw.tx_ref()).unwrap();
+            provider_rw.commit().unwrap();
+            state_root
+        };
+
+        let provider = provider_factory.provider().unwrap();
+        let targets = updated_state
+            .storages
+            .iter()
+            .map(|(hashed_address, storage)| {
+                (*hashed_address, storage.storage.keys().copied().collect::<HashSet<_>>())
+            })
+            .collect::<HashMap<_, _>>();
+        let multiproof = Proof::new(provider.tx_ref()).multiproof(&targets).unwrap();
+        let mut account_trie = SparseTrie::blind(state_root);
+        account_trie.reveal_nodes(&multiproof.account_subtree).unwrap();
+        let storage_tries = multiproof
+            .storages
+            .iter()
+            .map(|(hashed_address, storage_proof)| {
+                let mut storage_trie = SparseTrie::blind(storage_proof.root);
+                storage_trie.reveal_nodes(&storage_proof.subtree).unwrap();
+                (*hashed_address, storage_trie)
+            })
+            .collect::<HashMap<_, _>>();
+
+        group.bench_function(BenchmarkId::new("full state root", size), |b| {
+            b.iter(|| updated_state.state_root(provider.tx_ref()).unwrap())
+        });
+
+        group.bench_function(BenchmarkId::new("revealed sparse trie", size), |b| {
+            let blinded = DatabaseBlindedProviderFactory::new(provider.tx_ref());
+            b.iter_batched(
+                || (account_trie.clone(), storage_tries.clone()),
+                |(mut account_trie, mut storage_tries)| {
+                    let mut account_provider = blinded.account_node_provider();
+                    for (hashed_address, storage) in &updated_state.storages {
+                        let storage_trie = storage_tries.get_mut(hashed_address).unwrap();
+                        let mut storage_provider = blinded.storage_node_provider(*hashed_address);
+                        for (hashed_slot, value) in &storage.storage {
+                            storage_trie
+                                .update_leaf(
+                                    &Nibbles::unpack(hashed_slot),
+                                    encode_fixed_size(value).to_vec(),
+                                    &mut storage_provider,
+                                )
+                                .unwrap();
+                        }
+
+                        let account = db_state.accounts[hashed_address].unwrap();
+                        let account = TrieAccount::from((account, storage_trie.root()));
+                        account_trie
+                            .update_leaf(
+                                &Nibbles::unpack(hashed_address),
+                                alloy_rlp::encode(account),
+                                &mut account_provider,
+                            )
+                            .unwrap();
+                    }
+                    account_trie.root()
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+}
+
+fn generate_test_data(size: usize) -> (HashedPostState, HashedPostState) {
+    let storage_size = 100;
+    let mut runner = TestRunner::new(ProptestConfig::default());
+
+    use proptest::{collection::hash_map, sample::subsequence};
+    let db_state = hash_map(
+        any::<B256>(),
+        (
+            any::<Account>().prop_filter("non empty account", |a| !a.is_empty()),
+            hash_map(
+                any::<B256>(),
+                any::<U256>().prop_filter("non zero value", |v| !v.is_zero()),
+                storage_size,
+            ),
+        ),
+        size,
+    )
+    .new_tree(&mut runner)
+    .unwrap()
+    .current();
+
+    let keys = db_state.keys().cloned().collect::<Vec<_>>();
+    let keys_to_update = subsequence(keys, size / 2).new_tree(&mut runner).unwrap().current();
+
+    let updated_storages = keys_to_update
+        .into_iter()
+        .map(|address| {
+            let (_, storage) = db_state.get(&address).unwrap();
+            let slots = storage.keys().cloned().collect::<Vec<_>>();
+            let slots_to_update =
+                subsequence(slots, storage_size / 2).new_tree(&mut runner).unwrap().current();
+            (
+                address,
+                slots_to_update
+                    .into_iter()
+                    .map(|slot| (slot, any::<U256>().new_tree(&mut runner).unwrap().current()))
+                    .collect::<HashMap<_, _>>(),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    (
+        HashedPostState::default()
+            .with_accounts(
+                db_state.iter().map(|(address, (account, _))| (*address, Some(*account))),
+            )
+            .with_storages(db_state.into_iter().map(|(address, (_, storage))| {
+                (address, HashedStorage::from_iter(false, storage))
+            })),
+        HashedPostState::default().with_storages(
+            updated_storages
+                .into_iter()
+                .map(|(address, storage)| (address, HashedStorage::from_iter(false, storage))),
+        ),
+    )
+}
+
+criterion_group!(state_root, sparse_trie);
+criterion_main!(state_root);
@@ -0,0 +1,329 @@
+use crate::{
+    hashed_cursor::HashedPostStateCursorFactory, prefix_set::TriePrefixSets, proof::Proof,
+    HashedPostState, HashedPostStateSorted,
+};
+use reth_db_api::transaction::DbTx;
+use reth_execution_errors::{StateRootError, StorageRootError};
+use reth_primitives::{trie::Nibbles, Bytes, B256};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+
+/// Factory for instantiating providers capable of retrieving blinded trie nodes.
+pub trait BlindedProviderFactory {
+    /// Type capable of fetching blinded account trie nodes.
+    type AccountNodeProvider: BlindedProvider;
+    /// Type capable of fetching blinded storage trie nodes.
+    type StorageNodeProvider: BlindedProvider;
+
+    /// Returns the provider of the blinded account trie nodes.
+    fn account_node_provider(&self) -> Self::AccountNodeProvider;
+
+    /// Returns the provider of the blinded storage trie nodes of the given account.
+    fn storage_node_provider(&self, hashed_address: B256) -> Self::StorageNodeProvider;
+}
+
+/// Trie node provider for retrieving blinded nodes.
+pub trait BlindedProvider {
+    /// The error type of the provider.
+    type Error;
+
+    /// Returns the rlp-serialized trie node at the given path, or `None` if the trie has no node
+    /// at this path.
+    fn blinded_node(&mut self, path: &Nibbles) -> Result<Option<Bytes>, Self::Error>;
+}
+
+/// The [`BlindedProviderFactory`] that computes the requested trie nodes from the database.
+///
+/// Unlike the nodes revealed from a pre-computed multiproof, any path of the trie can be
+/// requested, so the nodes missed by the targeted prefetch are still available.
+///
+/// The nodes can be computed on top of a [`HashedPostState`] overlay, e.g. the reverts of a
+/// historical block or the changes of the blocks that haven't been persisted yet, see
+/// [`DatabaseBlindedProviderFactory::with_hashed_state`].
+#[derive(Clone, Debug)]
+pub struct DatabaseBlindedProviderFactory<'a, TX> {
+    /// A reference to the database transaction.
+    tx: &'a TX,
+    /// The sorted hashed state overlaid on top of the database.
+    hashed_state: Arc<HashedPostStateSorted>,
+    /// The prefix sets of the overlaid hashed state.
+    prefix_sets: TriePrefixSets,
+}
+
+impl<'a, TX> DatabaseBlindedProviderFactory<'a, TX> {
+    /// Create new factory.
+    pub fn new(tx: &'a TX) -> Self {
+        Self {
+            tx,
+            hashed_state: Arc::new(HashedPostState::default().into_sorted()),
+            prefix_sets: TriePrefixSets::default(),
+        }
+    }
+
+    /// Set the hashed state that is overlaid on top of the database.
+    pub fn with_hashed_state(mut self, hashed_state: &HashedPostState) -> Self {
+        self.prefix_sets = hashed_state.construct_prefix_sets();
+        self.hashed_state = Arc::new(hashed_state.clone().into_sorted());
+        self
+    }
+}
+
+impl<'a, TX: DbTx> BlindedProviderFactory for DatabaseBlindedProviderFactory<'a, TX> {
+    type AccountNodeProvider = DatabaseAccountBlindedProvider<'a, TX>;
+    type StorageNodeProvider = DatabaseStorageBlindedProvider<'a, TX>;
+
+    fn account_node_provider(&self) -> Self::AccountNodeProvider {
+        DatabaseAccountBlindedProvider {
+            tx: self.tx,
+            hashed_state: self.hashed_state.clone(),
+            prefix_sets: self.prefix_sets.clone(),
+            nodes: HashMap::default(),
+        }
+    }
+
+    fn storage_node_provider(&self, hashed_address: B256) -> Self::StorageNodeProvider {
+        DatabaseStorageBlindedProvider {
+            tx: self.tx,
+            hashed_state: self.hashed_state.clone(),
+            prefix_sets: self.prefix_sets.clone(),
+            hashed_address,
+            nodes: HashMap::default(),
+        }
+    }
+}
+
+/// Provider of the blinded account trie nodes that computes the requested nodes from the
+/// database, see [`DatabaseBlindedProviderFactory`].
+#[derive(Debug)]
+pub struct DatabaseAccountBlindedProvider<'a, TX> {
+    /// A reference to the database transaction.
+    tx: &'a TX,
+    /// The sorted hashed state overlaid on top of the database.
+    hashed_state: Arc<HashedPostStateSorted>,
+    /// The prefix sets of the overlaid hashed state.
+    prefix_sets: TriePrefixSets,
+    /// The nodes computed so far keyed by their paths.
+    nodes: HashMap<Nibbles, Option<Bytes>>,
+}
+
+impl<'a, TX: DbTx> BlindedProvider for DatabaseAccountBlindedProvider<'a, TX> {
+    type Error = StateRootError;
+
+    fn blinded_node(&mut self, path: &Nibbles) -> Result<Option<Bytes>, Self::Error> {
+        if let Some(node) = self.nodes.get(path) {
+            return Ok(node.clone())
+        }
+
+        let targets = HashMap::from([(padded_key(path), HashSet::default())]);
+        let proof = Proof::new(self.tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                self.tx,
+                &self.hashed_state,
+            ))
+            .with_prefix_sets(self.prefix_sets.clone())
+            .multiproof(&targets)?;
+        Ok(memoize(&mut self.nodes, path, proof.account_subtree))
+    }
+}
+
+/// Provider of the blinded storage trie nodes of a single account that computes the requested
+/// nodes from the database, see [`DatabaseBlindedProviderFactory`].
+#[derive(Debug)]
+pub struct DatabaseStorageBlindedProvider<'a, TX> {
+    /// A reference to the database transaction.
+    tx: &'a TX,
+    /// The sorted hashed state overlaid on top of the database.
+    hashed_state: Arc<HashedPostStateSorted>,
+    /// The prefix sets of the overlaid hashed state.
+    prefix_sets: TriePrefixSets,
+    /// The hashed address of the account.
+    hashed_address: B256,
+    /// The nodes computed so far keyed by their paths.
+    nodes: HashMap<Nibbles, Option<Bytes>>,
+}
+
+impl<'a, TX: DbTx> BlindedProvider for DatabaseStorageBlindedProvider<'a, TX> {
+    type Error = StorageRootError;
+
+    fn blinded_node(&mut self, path: &Nibbles) -> Result<Option<Bytes>, Self::Error> {
+        if let Some(node) = self.nodes.get(path) {
+            return Ok(node.clone())
+        }
+
+        let targets = HashSet::from([padded_key(path)]);
+        let proof = Proof::new(self.tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                self.tx,
+                &self.hashed_state,
+            ))
+            .with_prefix_sets(self.prefix_sets.clone())
+            .storage_multiproof(self.hashed_address, &targets)?;
+        Ok(memoize(&mut self.nodes, path, proof.subtree))
+    }
+}
+
+/// Returns the key that has the given path as a prefix. The proof of this key contains all nodes
+/// on the path, including the node at the path itself if there is one.
+pub fn padded_key(path: &Nibbles) -> B256 {
+    let mut key = path.pack();
+    key.resize(32, 0);
+    B256::from_slice(key.as_slice())
+}
+
+/// Stores all nodes of the proof, since the ancestors of the requested node are likely to be
+/// requested as well, and returns the node at the given path.
+fn memoize(
+    nodes: &mut HashMap<Nibbles, Option<Bytes>>,
+    path: &Nibbles,
+    proof: BTreeMap<Nibbles, Bytes>,
+) -> Option<Bytes> {
+    for (node_path, node) in proof {
+        nodes.insert(node_path, Some(node));
+    }
+    nodes.entry(path.clone()).or_default().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HashedStorage, StateRoot};
+    use reth_db::tables;
+    use reth_db_api::{cursor::DbCursorRW, transaction::DbTxMut};
+    use reth_primitives::{keccak256, Account, StorageEntry, U256};
+    use reth_provider::test_utils::create_test_provider_factory;
+
+    #[test]
+    fn account_nodes_missed_by_prefetch() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let hashed_addresses =
+            (0..256u64).map(|i| keccak256(B256::from(U256::from(i)))).collect::<Vec<_>>();
+        let mut hashed_account_cursor = tx.cursor_write::<tables::HashedAccounts>().unwrap();
+        for (i, hashed_address) in hashed_addresses.iter().enumerate() {
+            let account = Account { nonce: i as u64, ..Default::default() };
+            hashed_account_cursor.upsert(*hashed_address, account).unwrap();
+        }
+        let (_, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        updates.flush(tx).unwrap();
+
+        // Prefetch only the first half of the accounts that are going to be accessed.
+        let accessed = &hashed_addresses[..16];
+        let prefetched = Proof::new(tx)
+            .multiproof(&accessed[..8].iter().map(|key| (*key, HashSet::default())).collect())
+            .unwrap();
+        let full = Proof::new(tx)
+            .multiproof(&accessed.iter().map(|key| (*key, HashSet::default())).collect())
+            .unwrap();
+
+        let mut blinded_provider = DatabaseBlindedProviderFactory::new(tx).account_node_provider();
+        let mut missed = 0;
+        for (path, node) in &full.account_subtree {
+            if !prefetched.account_subtree.contains_key(path) {
+                missed += 1;
+            }
+            assert_eq!(blinded_provider.blinded_node(path).unwrap().as_ref(), Some(node));
+        }
+        assert!(missed > 0);
+
+        // Repeated reveals are served from memory.
+        let (path, node) = full.account_subtree.last_key_value().unwrap();
+        assert_eq!(blinded_provider.nodes.get(path), Some(&Some(node.clone())));
+        assert_eq!(blinded_provider.blinded_node(path).unwrap().as_ref(), Some(node));
+
+        // There is no node at the full path of the key.
+        let path = Nibbles::unpack(hashed_addresses[0]);
+        assert_eq!(blinded_provider.blinded_node(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn storage_nodes_missed_by_prefetch() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let hashed_address = B256::random();
+        let hashed_slots =
+            (1..=256u64).map(|i| keccak256(B256::from(U256::from(i)))).collect::<Vec<_>>();
+        let mut hashed_storage_cursor = tx.cursor_dup_write::<tables::HashedStorages>().unwrap();
+        for (i, hashed_slot) in hashed_slots.iter().enumerate() {
+            let entry = StorageEntry { key: *hashed_slot, value: U256::from(i + 1) };
+            hashed_storage_cursor.upsert(hashed_address, entry).unwrap();
+        }
+        tx.put::<tables::HashedAccounts>(hashed_address, Account::default()).unwrap();
+        let (_, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        updates.flush(tx).unwrap();
+
+        // Prefetch only the first half of the slots that are going to be accessed.
+        let accessed = &hashed_slots[..16];
+        let prefetched = Proof::new(tx)
+            .storage_multiproof(hashed_address, &accessed[..8].iter().copied().collect())
+            .unwrap();
+        let full = Proof::new(tx)
+            .storage_multiproof(hashed_address, &accessed.iter().copied().collect())
+            .unwrap();
+
+        let mut blinded_provider =
+            DatabaseBlindedProviderFactory::new(tx).storage_node_provider(hashed_address);
+        let mut missed = 0;
+        for (path, node) in &full.subtree {
+            if !prefetched.subtree.contains_key(path) {
+                missed += 1;
+            }
+            assert_eq!(blinded_provider.blinded_node(path).unwrap().as_ref(), Some(node));
+        }
+        assert!(missed > 0);
+    }
+
+    #[test]
+    fn storage_nodes_on_top_of_hashed_state() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let hashed_address = B256::random();
+        let hashed_slots =
+            (1..=256u64).map(|i| keccak256(B256::from(U256::from(i)))).collect::<Vec<_>>();
+        let mut hashed_storage_cursor = tx.cursor_dup_write::<tables::HashedStorages>().unwrap();
+        for (i, hashed_slot) in hashed_slots.iter().enumerate() {
+            let entry = StorageEntry { key: *hashed_slot, value: U256::from(i + 1) };
+            hashed_storage_cursor.upsert(hashed_address, entry).unwrap();
+        }
+        tx.put::<tables::HashedAccounts>(hashed_address, Account::default()).unwrap();
+        let (_, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        updates.flush(tx).unwrap();
+
+        // Clear half of the slots and insert new ones, without updating the trie tables.
+        let inserted = (0..64).map(|_| B256::random()).collect::<Vec<_>>();
+        let changes = hashed_slots
+            .iter()
+            .step_by(2)
+            .map(|slot| (*slot, U256::ZERO))
+            .chain(inserted.iter().map(|slot| (*slot, U256::from(1))));
+        let mut hashed_state = HashedPostState::default();
+        hashed_state.storages.insert(hashed_address, HashedStorage::from_iter(false, changes));
+
+        let targets = hashed_slots.iter().chain(&inserted).copied().collect::<HashSet<_>>();
+        let proof = hashed_state.storage_multiproof(tx, hashed_address, &targets).unwrap();
+        assert_eq!(proof.root, hashed_state.storage_root(tx, hashed_address).unwrap());
+
+        let mut blinded_provider = DatabaseBlindedProviderFactory::new(tx)
+            .with_hashed_state(&hashed_state)
+            .storage_node_provider(hashed_address);
+        for (path, node) in &proof.subtree {
+            assert_eq!(blinded_provider.blinded_node(path).unwrap().as_ref(), Some(node));
+        }
+
+        // Without the overlay, the nodes of the database trie are returned.
+        let root_node = proof.subtree.get(&Nibbles::default()).unwrap();
+        let mut blinded_provider =
+            DatabaseBlindedProviderFactory::new(tx).storage_node_provider(hashed_address);
+        assert_ne!(
+            blinded_provider.blinded_node(&Nibbles::default()).unwrap().as_ref(),
+            Some(root_node)
+        );
+    }
+}
@@ -36,16 +36,28 @@ pub use state::*;
 /// Merkle proof generation.
 pub mod proof;
 
+/// Paginated ranges of the hashed storage of accounts.
+mod storage_range;
+pub use storage_range::{StorageRange, StorageRangeEntry, MAX_PLAIN_STORAGE_PREIMAGE_SCAN};
+
+/// Providers of the blinded trie nodes.
+pub mod blinded;
+
+/// Sparse trie that is partially revealed from proofs and updated in memory.
+pub mod sparse;
+
 /// The implementation of the Merkle Patricia Trie.
 mod trie;
-pub use trie::{StateRoot, StorageRoot};
+pub use trie::{StateRoot, StorageRoot, DEFAULT_PROGRESS_INTERVAL};
 
 /// Buffer for trie updates.
 pub mod updates;
 
 /// Utilities for state root checkpoint progress.
 mod progress;
-pub use progress::{IntermediateStateRootState, StateRootProgress};
+pub use progress::{
+    CancellationToken, IntermediateStateRootState, StateRootProgress, TrieProgress,
+};
 
 /// Trie calculation stats.
 pub mod stats;
@@ -1,5 +1,5 @@
 use crate::stats::TrieStats;
-use metrics::Histogram;
+use metrics::{Counter, Gauge, Histogram};
 use reth_metrics::Metrics;
 
 /// Wrapper for state root metrics.
@@ -30,6 +30,15 @@ pub struct TrieRootMetrics {
     branches_added: Histogram,
     /// The number of leaves added during trie root calculation.
     leaves_added: Histogram,
+    /// The number of intermediate branch nodes consumed by the walker during trie root
+    /// calculation.
+    branch_nodes_consumed: Histogram,
+    /// The number of subtrees skipped by the walker during trie root calculation.
+    subtrees_skipped: Histogram,
+    /// The number of trie cursor seeks during trie root calculation.
+    trie_seeks: Histogram,
+    /// The number of hashed cursor seeks during trie root calculation.
+    hashed_seeks: Histogram,
 }
 
 impl TrieRootMetrics {
@@ -43,9 +52,25 @@ impl TrieRootMetrics {
         self.duration_seconds.record(stats.duration().as_secs_f64());
         self.branches_added.record(stats.branches_added() as f64);
         self.leaves_added.record(stats.leaves_added() as f64);
+        self.branch_nodes_consumed.record(stats.branch_nodes_consumed() as f64);
+        self.subtrees_skipped.record(stats.subtrees_skipped() as f64);
+        self.trie_seeks.record(stats.trie_seeks() as f64);
+        self.hashed_seeks.record(stats.hashed_seeks() as f64);
     }
 }
 
+/// Metrics for the trie node cache.
+#[derive(Metrics)]
+#[metrics(scope = "trie.node_cache")]
+pub(crate) struct TrieNodeCacheMetrics {
+    /// The number of trie node lookups served from the cache.
+    pub(crate) hits: Counter,
+    /// The number of trie node lookups that went to the database.
+    pub(crate) misses: Counter,
+    /// The number of cached trie nodes.
+    pub(crate) entries: Gauge,
+}
+
 /// Trie type for differentiating between various trie calculations.
 #[derive(Clone, Copy, Debug)]
 pub enum TrieType {
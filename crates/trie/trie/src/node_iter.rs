@@ -1,4 +1,6 @@
-use crate::{hashed_cursor::HashedCursor, trie_cursor::TrieCursor, walker::TrieWalker};
+use crate::{
+    hashed_cursor::HashedCursor, stats::TrieWalkStats, trie_cursor::TrieCursor, walker::TrieWalker,
+};
 use reth_db::DatabaseError;
 use reth_primitives::{trie::Nibbles, B256};
 
@@ -44,6 +46,8 @@ pub struct TrieNodeIter<C, H: HashedCursor> {
     current_hashed_entry: Option<(B256, <H as HashedCursor>::Value)>,
     /// Flag indicating whether we should check the current walker key.
     current_walker_key_checked: bool,
+    /// The number of seeks performed on the hashed cursor.
+    hashed_seeks: u64,
 }
 
 impl<C, H: HashedCursor> TrieNodeIter<C, H> {
@@ -55,6 +59,7 @@ impl<C, H: HashedCursor> TrieNodeIter<C, H> {
             previous_hashed_key: None,
             current_hashed_entry: None,
             current_walker_key_checked: false,
+            hashed_seeks: 0,
         }
     }
 
@@ -64,6 +69,11 @@ impl<C, H: HashedCursor> TrieNodeIter<C, H> {
         self.previous_hashed_key = Some(previous_hashed_key);
         self
     }
+
+    /// Returns the stats of the walk over the intermediate trie nodes and the hashed entries.
+    pub const fn stats(&self) -> TrieWalkStats {
+        TrieWalkStats { hashed_seeks: self.hashed_seeks, ..self.walker.stats() }
+    }
 }
 
 impl<C, H> TrieNodeIter<C, H>
@@ -121,6 +131,7 @@ where
                 Some(hashed_key) => {
                     // Seek to the previous hashed key and get the next hashed entry
                     self.hashed_cursor.seek(hashed_key)?;
+                    self.hashed_seeks += 1;
                     self.current_hashed_entry = self.hashed_cursor.next()?;
                 }
                 None => {
@@ -131,6 +142,7 @@ where
                         None => break, // no more keys
                     };
                     self.current_hashed_entry = self.hashed_cursor.seek(seek_key)?;
+                    self.hashed_seeks += 1;
                     self.walker.advance()?;
                 }
             }
@@ -1,5 +1,6 @@
 use super::{PrefixSetMut, TriePrefixSets};
 use derive_more::Deref;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use reth_db::tables;
 use reth_db_api::{
     cursor::DbCursorRO,
@@ -68,4 +69,125 @@ impl<'a, TX: DbTx> PrefixSetLoader<'a, TX> {
             destroyed_accounts,
         })
     }
+
+    /// Load all account and storage changes for the given block range in parallel.
+    ///
+    /// The range is split into sub-ranges, one per rayon worker, each of which is loaded with
+    /// [`Self::load`] into locally sorted prefix sets. The results are then merged with
+    /// deduplication, so the result is identical to [`Self::load`] over the whole range.
+    ///
+    /// Destroyed accounts are determined by the current plain state in both cases, so an account
+    /// destroyed in one sub-range and recreated in a later one is not considered destroyed.
+    pub fn load_parallel(
+        self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<TriePrefixSets, DatabaseError> {
+        let tx = self.0;
+        let (start, end) = range.into_inner();
+        let len = end.saturating_add(1).saturating_sub(start);
+        let chunk_size = len.div_ceil(rayon::current_num_threads() as u64).max(1);
+        let sub_ranges = (start..=end)
+            .step_by(chunk_size as usize)
+            .map(|chunk_start| chunk_start..=chunk_start.saturating_add(chunk_size - 1).min(end))
+            .collect::<Vec<_>>();
+
+        let loaded = sub_ranges
+            .into_par_iter()
+            .map(|sub_range| PrefixSetLoader::new(tx).load(sub_range))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Merge the sorted prefix sets. Sorting on freeze detects the already sorted runs, so this
+        // is effectively a merge of the sub-range results.
+        let mut account_prefix_set = PrefixSetMut::default();
+        let mut storage_prefix_sets = HashMap::<B256, PrefixSetMut>::default();
+        let mut destroyed_accounts = HashSet::default();
+        for prefix_sets in loaded {
+            account_prefix_set.extend(prefix_sets.account_prefix_set.iter().cloned());
+            for (hashed_address, prefix_set) in prefix_sets.storage_prefix_sets {
+                storage_prefix_sets
+                    .entry(hashed_address)
+                    .or_default()
+                    .extend(prefix_set.iter().cloned());
+            }
+            destroyed_accounts.extend(prefix_sets.destroyed_accounts);
+        }
+
+        Ok(TriePrefixSets {
+            account_prefix_set: account_prefix_set.freeze(),
+            storage_prefix_sets: storage_prefix_sets
+                .into_iter()
+                .map(|(k, v)| (k, v.freeze()))
+                .collect(),
+            destroyed_accounts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::{prelude::ProptestConfig, proptest};
+    use reth_db::test_utils::create_test_rw_db;
+    use reth_db_api::{database::Database, transaction::DbTxMut};
+    use reth_primitives::{Account, Address};
+
+    #[test]
+    fn load_parallel() {
+        proptest!(
+            ProptestConfig::with_cases(10),
+            |(
+                account_changes: Vec<(u8, u8)>,
+                storage_changes: Vec<(u8, u8, u8)>,
+                existing_accounts: Vec<u8>,
+                range_start: u8,
+                range_end: u8,
+            )| {
+                let db = create_test_rw_db();
+                let tx = db.tx_mut().expect("failed to create transaction");
+
+                // Small key spaces make sure that accounts and slots change in multiple sub-ranges
+                for (block, address) in account_changes {
+                    let address = Address::with_last_byte(address % 16);
+                    tx.put::<tables::AccountChangeSets>(
+                        block as BlockNumber,
+                        AccountBeforeTx { address, info: None },
+                    )
+                    .unwrap();
+                }
+                for (block, address, slot) in storage_changes {
+                    let address = Address::with_last_byte(address % 16);
+                    tx.put::<tables::StorageChangeSets>(
+                        BlockNumberAddress((block as BlockNumber, address)),
+                        StorageEntry { key: B256::with_last_byte(slot % 16), value: Default::default() },
+                    )
+                    .unwrap();
+                }
+                // Accounts missing from the plain state are destroyed
+                for address in existing_accounts {
+                    tx.put::<tables::PlainAccountState>(
+                        Address::with_last_byte(address % 16),
+                        Account::default(),
+                    )
+                    .unwrap();
+                }
+
+                let range = range_start as BlockNumber..=range_end as BlockNumber;
+                let expected = PrefixSetLoader::new(&tx).load(range.clone()).unwrap();
+                let loaded = PrefixSetLoader::new(&tx).load_parallel(range).unwrap();
+
+                assert_eq!(
+                    loaded.account_prefix_set.iter().collect::<Vec<_>>(),
+                    expected.account_prefix_set.iter().collect::<Vec<_>>()
+                );
+                assert_eq!(loaded.storage_prefix_sets.len(), expected.storage_prefix_sets.len());
+                for (hashed_address, prefix_set) in expected.storage_prefix_sets {
+                    assert_eq!(
+                        loaded.storage_prefix_sets[&hashed_address].iter().collect::<Vec<_>>(),
+                        prefix_set.iter().collect::<Vec<_>>()
+                    );
+                }
+                assert_eq!(loaded.destroyed_accounts, expected.destroyed_accounts);
+            }
+        );
+    }
 }
@@ -8,7 +8,7 @@ mod loader;
 pub use loader::PrefixSetLoader;
 
 /// Collection of trie prefix sets.
-#[derive(Default, Debug)]
+#[derive(Default, Clone, Debug)]
 pub struct TriePrefixSets {
     /// A set of account prefixes that have changed.
     pub account_prefix_set: PrefixSet,
@@ -102,6 +102,12 @@ impl PrefixSetMut {
         self.keys.push(nibbles);
     }
 
+    /// Inserts all the given `nibbles` into the set.
+    pub fn extend(&mut self, nibbles: impl IntoIterator<Item = Nibbles>) {
+        self.sorted = false;
+        self.keys.extend(nibbles);
+    }
+
     /// Returns the number of elements in the set.
     pub fn len(&self) -> usize {
         self.keys.len()
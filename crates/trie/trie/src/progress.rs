@@ -1,5 +1,9 @@
 use crate::{trie_cursor::CursorSubNode, updates::TrieUpdates};
 use reth_primitives::{stage::MerkleCheckpoint, trie::hash_builder::HashBuilder, B256};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
 /// The progress of the state root computation.
 #[derive(Debug)]
@@ -31,3 +35,35 @@ impl From<MerkleCheckpoint> for IntermediateStateRootState {
         }
     }
 }
+
+/// The progress report of the state root computation, see [`crate::StateRoot::with_progress`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct TrieProgress {
+    /// The number of account trie branch nodes walked so far.
+    pub branches_walked: u64,
+    /// The number of accounts walked so far.
+    pub accounts_walked: u64,
+    /// The number of hashed entries (accounts and storage slots) walked so far.
+    pub hashed_entries_walked: u64,
+    /// The last hashed account key processed.
+    pub last_account_key: B256,
+}
+
+/// A cooperative cancellation token for the state root computation, see
+/// [`crate::StateRoot::with_cancellation`].
+///
+/// The token is cheap to clone, all clones share the same cancellation state.
+#[derive(Clone, Default, Debug)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Cancels all computations that observe this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
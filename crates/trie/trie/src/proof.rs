@@ -1,7 +1,7 @@
 use crate::{
     hashed_cursor::{HashedCursorFactory, HashedStorageCursor},
     node_iter::{TrieElement, TrieNodeIter},
-    prefix_set::PrefixSetMut,
+    prefix_set::{PrefixSet, PrefixSetMut, TriePrefixSets},
     trie_cursor::{DatabaseAccountTrieCursor, DatabaseStorageTrieCursor},
     walker::TrieWalker,
 };
@@ -12,27 +12,67 @@ use reth_execution_errors::{StateRootError, StorageRootError};
 use reth_primitives::{
     constants::EMPTY_ROOT_HASH,
     keccak256,
-    trie::{proof::ProofRetainer, AccountProof, HashBuilder, Nibbles, StorageProof, TrieAccount},
+    trie::{
+        proof::ProofRetainer, AccountProof, HashBuilder, MultiProof, Nibbles, StorageMultiProof,
+        StorageProof, TrieAccount,
+    },
     Address, B256,
 };
+use std::collections::{HashMap, HashSet};
 
 /// A struct for generating merkle proofs.
 ///
 /// Proof generator adds the target address and slots to the prefix set, enables the proof retainer
 /// on the hash builder and follows the same algorithm as the state root calculator.
 /// See `StateRoot::root` for more info.
+///
+/// If the hashed state is overlaid with changes that are not reflected in the intermediate trie
+/// nodes (e.g. state reverts), the prefix sets of those changes must be provided with
+/// [`Proof::with_prefix_sets`], so that the affected nodes are recomputed.
 #[derive(Debug)]
 pub struct Proof<'a, TX, H> {
     /// A reference to the database transaction.
     tx: &'a TX,
     /// The factory for hashed cursors.
     hashed_cursor_factory: H,
+    /// A set of prefix sets that have changed on top of the intermediate trie nodes.
+    prefix_sets: TriePrefixSets,
 }
 
 impl<'a, TX> Proof<'a, TX, &'a TX> {
     /// Create a new [Proof] instance.
-    pub const fn new(tx: &'a TX) -> Self {
-        Self { tx, hashed_cursor_factory: tx }
+    pub fn new(tx: &'a TX) -> Self {
+        Self { tx, hashed_cursor_factory: tx, prefix_sets: TriePrefixSets::default() }
+    }
+}
+
+impl<'a, TX, H> Proof<'a, TX, H> {
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> Proof<'a, TX, HF> {
+        Proof { tx: self.tx, hashed_cursor_factory, prefix_sets: self.prefix_sets }
+    }
+
+    /// Set the prefix sets of the changes that are not reflected in the intermediate trie nodes.
+    pub fn with_prefix_sets(mut self, prefix_sets: TriePrefixSets) -> Self {
+        self.prefix_sets = prefix_sets;
+        self
+    }
+
+    /// Returns the account prefix set extended with the given target nibbles.
+    fn account_prefix_set(&self, targets: impl IntoIterator<Item = Nibbles>) -> PrefixSet {
+        PrefixSetMut::from(self.prefix_sets.account_prefix_set.iter().cloned().chain(targets))
+            .freeze()
+    }
+
+    /// Returns the storage prefix set of the account extended with the given target nibbles.
+    fn storage_prefix_set(
+        &self,
+        hashed_address: B256,
+        targets: impl IntoIterator<Item = Nibbles>,
+    ) -> PrefixSet {
+        let changed = self.prefix_sets.storage_prefix_sets.get(&hashed_address);
+        PrefixSetMut::from(changed.into_iter().flat_map(|set| set.iter().cloned()).chain(targets))
+            .freeze()
     }
 }
 
@@ -56,9 +96,8 @@ where
             DatabaseAccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
 
         // Create the walker.
-        let mut prefix_set = PrefixSetMut::default();
-        prefix_set.insert(target_nibbles.clone());
-        let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
+        let prefix_set = self.account_prefix_set([target_nibbles.clone()]);
+        let walker = TrieWalker::new(trie_cursor, prefix_set);
 
         // Create a hash builder to rebuild the root node since it is not available in the database.
         let retainer = ProofRetainer::from_iter([target_nibbles]);
@@ -98,6 +137,118 @@ where
         Ok(account_proof)
     }
 
+    /// Generate a state multiproof for the target accounts and their storage slots. The targets
+    /// are hashed addresses mapped to the sets of hashed storage slots.
+    pub fn multiproof(
+        &self,
+        targets: &HashMap<B256, HashSet<B256>>,
+    ) -> Result<MultiProof, StateRootError> {
+        self.multiproof_with_storages(targets, HashMap::default())
+    }
+
+    /// Generate a state multiproof for the target accounts using the pre-computed storage
+    /// multiproofs. Storage multiproofs of target accounts that are missing from
+    /// `storage_multiproofs` are computed on the fly.
+    ///
+    /// The result is identical to [`Self::multiproof`] as long as the pre-computed storage
+    /// multiproofs were generated for the same targets against the same state.
+    pub fn multiproof_with_storages(
+        &self,
+        targets: &HashMap<B256, HashSet<B256>>,
+        mut storage_multiproofs: HashMap<B256, StorageMultiProof>,
+    ) -> Result<MultiProof, StateRootError> {
+        let hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
+        let trie_cursor =
+            DatabaseAccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
+
+        // Create the walker.
+        let target_nibbles = targets.keys().map(|key| Nibbles::unpack(key)).collect::<Vec<_>>();
+        let prefix_set = self.account_prefix_set(target_nibbles.clone());
+        let walker = TrieWalker::new(trie_cursor, prefix_set);
+
+        // Create a hash builder to rebuild the root node since it is not available in the database.
+        let retainer = ProofRetainer::from_iter(target_nibbles);
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+
+        let mut storages = HashMap::default();
+        let mut account_rlp = Vec::with_capacity(128);
+        let mut account_node_iter = TrieNodeIter::new(walker, hashed_account_cursor);
+        while let Some(account_node) = account_node_iter.try_next()? {
+            match account_node {
+                TrieElement::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                TrieElement::Leaf(hashed_address, account) => {
+                    let storage_root = if let Some(slots) = targets.get(&hashed_address) {
+                        let storage_multiproof = match storage_multiproofs.remove(&hashed_address) {
+                            Some(storage_multiproof) => storage_multiproof,
+                            None => self.storage_multiproof(hashed_address, slots)?,
+                        };
+                        let storage_root = storage_multiproof.root;
+                        storages.insert(hashed_address, storage_multiproof);
+                        storage_root
+                    } else {
+                        self.storage_root(hashed_address)?
+                    };
+
+                    account_rlp.clear();
+                    let account = TrieAccount::from((account, storage_root));
+                    account.encode(&mut account_rlp as &mut dyn BufMut);
+
+                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                }
+            }
+        }
+
+        let _ = hash_builder.root();
+
+        Ok(MultiProof { account_subtree: hash_builder.take_proofs(), storages })
+    }
+
+    /// Generate a storage multiproof for the target hashed slots of the account.
+    pub fn storage_multiproof(
+        &self,
+        hashed_address: B256,
+        hashed_slots: &HashSet<B256>,
+    ) -> Result<StorageMultiProof, StorageRootError> {
+        let mut hashed_storage_cursor =
+            self.hashed_cursor_factory.hashed_storage_cursor(hashed_address)?;
+
+        // short circuit on empty storage
+        if hashed_storage_cursor.is_storage_empty()? {
+            return Ok(StorageMultiProof::default())
+        }
+
+        let target_nibbles =
+            hashed_slots.iter().map(|slot| Nibbles::unpack(slot)).collect::<Vec<_>>();
+        let prefix_set = self.storage_prefix_set(hashed_address, target_nibbles.clone());
+        let trie_cursor = DatabaseStorageTrieCursor::new(
+            self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
+            hashed_address,
+        );
+        let walker = TrieWalker::new(trie_cursor, prefix_set);
+
+        let retainer = ProofRetainer::from_iter(target_nibbles);
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+        let mut storage_node_iter = TrieNodeIter::new(walker, hashed_storage_cursor);
+        while let Some(node) = storage_node_iter.try_next()? {
+            match node {
+                TrieElement::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                TrieElement::Leaf(hashed_slot, value) => {
+                    hash_builder.add_leaf(
+                        Nibbles::unpack(hashed_slot),
+                        alloy_rlp::encode_fixed_size(&value).as_ref(),
+                    );
+                }
+            }
+        }
+
+        let root = hash_builder.root();
+        Ok(StorageMultiProof { root, subtree: hash_builder.take_proofs() })
+    }
+
     /// Compute storage root.
     pub fn storage_root(&self, hashed_address: B256) -> Result<B256, StorageRootError> {
         let (storage_root, _) = self.storage_root_with_proofs(hashed_address, &[])?;
@@ -121,7 +272,7 @@ where
         }
 
         let target_nibbles = proofs.iter().map(|p| p.nibbles.clone()).collect::<Vec<_>>();
-        let prefix_set = PrefixSetMut::from(target_nibbles.clone()).freeze();
+        let prefix_set = self.storage_prefix_set(hashed_address, target_nibbles.clone());
         let trie_cursor = DatabaseStorageTrieCursor::new(
             self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
             hashed_address,
@@ -166,11 +317,13 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::StateRoot;
+    use crate::{HashedStorage, StateRoot};
     use once_cell::sync::Lazy;
     use reth_db_api::database::Database;
     use reth_primitives::{Account, Bytes, Chain, ChainSpec, StorageEntry, HOLESKY, MAINNET, U256};
-    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter, ProviderFactory};
+    use reth_provider::{
+        test_utils::create_test_provider_factory, HashingWriter, ProviderFactory, StateProvider,
+    };
     use reth_storage_errors::provider::ProviderResult;
     use std::{str::FromStr, sync::Arc};
 
@@ -449,4 +602,73 @@ mod tests {
         similar_asserts::assert_eq!(account_proof, expected);
         assert_eq!(account_proof.verify(root), Ok(()));
     }
+
+    #[test]
+    fn holesky_deposit_contract_multiproof() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, HOLESKY.clone()).unwrap();
+
+        let target = Address::from_str("0x4242424242424242424242424242424242424242").unwrap();
+        let nonexistent = Address::from_str("0x0000000000000000000000000000000000000001").unwrap();
+        let slots = [0x22_u64, 0x23, 0x24, 0x100].map(|slot| B256::from(U256::from(slot)));
+
+        let provider = factory.provider().unwrap();
+        let proof = Proof::new(provider.tx_ref());
+        let targets = HashMap::from([
+            (keccak256(target), slots.iter().map(keccak256).collect::<HashSet<_>>()),
+            (keccak256(nonexistent), HashSet::default()),
+        ]);
+        let multiproof = proof.multiproof(&targets).unwrap();
+
+        // Multiproof contains the same nodes as the individual account and storage proofs.
+        for address in [target, nonexistent] {
+            let account_proof = proof.account_proof(address, &slots).unwrap();
+            assert_eq!(multiproof.account_proof_nodes(keccak256(address)), account_proof.proof);
+
+            if account_proof.info.is_some() {
+                let storage_multiproof = &multiproof.storages[&keccak256(address)];
+                assert_eq!(storage_multiproof.root, account_proof.storage_root);
+                for storage_proof in account_proof.storage_proofs {
+                    assert_eq!(
+                        storage_multiproof.storage_proof_nodes(keccak256(storage_proof.key)),
+                        storage_proof.proof
+                    );
+                }
+            } else {
+                assert!(!multiproof.storages.contains_key(&keccak256(address)));
+            }
+        }
+    }
+
+    #[test]
+    fn holesky_deposit_contract_storage_multiproof() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, HOLESKY.clone()).unwrap();
+
+        let target = Address::from_str("0x4242424242424242424242424242424242424242").unwrap();
+        // Existent slots 0x22..0x40 and non-existent 0x100.
+        let slots = (0x22..0x40)
+            .chain([0x100])
+            .map(|slot| B256::from(U256::from(slot)))
+            .collect::<Vec<_>>();
+
+        let provider = factory.provider().unwrap();
+        let storage_root =
+            Proof::new(provider.tx_ref()).account_proof(target, &[]).unwrap().storage_root;
+
+        let state = factory.latest().unwrap();
+        let storage_multiproof =
+            state.storage_multiproof(target, &slots, HashedStorage::new(false)).unwrap();
+        assert_eq!(storage_multiproof.root, storage_root);
+
+        for slot in slots {
+            let hashed_slot = keccak256(slot);
+            let mut proof = StorageProof::new_with_hashed(slot, hashed_slot);
+            proof.set_value(state.storage(target, slot).unwrap().unwrap_or_default());
+            proof.set_proof(storage_multiproof.storage_proof_nodes(hashed_slot));
+            assert_eq!(proof.verify(storage_root), Ok(()), "slot {slot}");
+        }
+    }
 }
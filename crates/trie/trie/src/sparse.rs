@@ -0,0 +1,714 @@
+use crate::blinded::BlindedProvider;
+use alloy_rlp::{Encodable, Header, EMPTY_STRING_CODE};
+use reth_primitives::{constants::EMPTY_ROOT_HASH, keccak256, trie::Nibbles, Bytes, B256};
+use std::collections::HashMap;
+
+/// Errors that can occur while revealing or updating a [`SparseTrie`].
+#[derive(Debug, thiserror::Error)]
+pub enum SparseTrieError {
+    /// The node couldn't be decoded.
+    #[error("failed to decode trie node at path {path:?}: {err}")]
+    Rlp {
+        /// The path of the node.
+        path: Nibbles,
+        /// The decoding error.
+        err: alloy_rlp::Error,
+    },
+    /// The revealed node doesn't match the hash it's referenced by.
+    #[error("revealed trie node at path {path:?} doesn't match the hash {expected}")]
+    NodeHashMismatch {
+        /// The path of the node.
+        path: Nibbles,
+        /// The hash the node is referenced by.
+        expected: B256,
+    },
+    /// The blinded node is referenced by its parent, but the provider doesn't have it.
+    #[error("blinded trie node at path {0:?} is missing")]
+    BlindedNodeMissing(Nibbles),
+    /// The blinded node provider failed.
+    #[error("failed to retrieve blinded trie node: {0}")]
+    BlindedProvider(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// A node of the [`SparseTrie`]. The children are not part of the node and are stored at their
+/// own paths instead.
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum SparseNode {
+    /// The trie is empty.
+    Empty,
+    /// The node that hasn't been revealed yet, only its hash is known.
+    Hash(B256),
+    /// The leaf node with the remaining key of the leaf and the value.
+    Leaf { key: Nibbles, value: Vec<u8> },
+    /// The extension node with the shared key, the child is at the path extended by the key.
+    Extension { key: Nibbles },
+    /// The branch node with the mask of the nibbles that have children.
+    Branch { state_mask: u16 },
+}
+
+/// Merkle Patricia Trie that only holds the revealed parts of the trie in memory, the remaining
+/// subtries are represented by their hashes.
+///
+/// The nodes are revealed from the proofs of the keys that are going to be updated, see
+/// [`SparseTrie::reveal_node`]. The nodes that are missed by the proofs, e.g. the siblings that
+/// are collapsed into their parent when a leaf is removed, are retrieved from the
+/// [`BlindedProvider`] on demand. The references of the unchanged nodes are cached, so only the
+/// paths to the updated leaves are re-hashed when the root is calculated.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SparseTrie {
+    /// The revealed nodes keyed by their paths.
+    nodes: HashMap<Nibbles, SparseNode>,
+    /// The rlp-encoded references of the nodes that haven't changed since they were computed.
+    references: HashMap<Nibbles, Vec<u8>>,
+}
+
+impl Default for SparseTrie {
+    fn default() -> Self {
+        Self::blind(EMPTY_ROOT_HASH)
+    }
+}
+
+impl SparseTrie {
+    /// Creates the trie with the given root, none of the nodes are revealed.
+    pub fn blind(root: B256) -> Self {
+        let root_node =
+            if root == EMPTY_ROOT_HASH { SparseNode::Empty } else { SparseNode::Hash(root) };
+        Self { nodes: HashMap::from([(Nibbles::default(), root_node)]), references: HashMap::new() }
+    }
+
+    /// Reveals the rlp-encoded trie nodes keyed by their paths, e.g. the nodes of a multiproof.
+    /// The parents are expected to come before their children.
+    pub fn reveal_nodes<'a>(
+        &mut self,
+        nodes: impl IntoIterator<Item = (&'a Nibbles, &'a Bytes)>,
+    ) -> Result<(), SparseTrieError> {
+        for (path, node) in nodes {
+            self.reveal_node(path.clone(), node)?;
+        }
+        Ok(())
+    }
+
+    /// Reveals the rlp-encoded trie node at the given path.
+    ///
+    /// The node is ignored if it's already revealed or if its parent isn't revealed, since
+    /// there's nothing to attach it to.
+    pub fn reveal_node(&mut self, path: Nibbles, node: &[u8]) -> Result<(), SparseTrieError> {
+        match self.nodes.get(&path) {
+            Some(SparseNode::Hash(hash)) => {
+                if keccak256(node) != *hash {
+                    return Err(SparseTrieError::NodeHashMismatch { path, expected: *hash })
+                }
+            }
+            _ => return Ok(()),
+        }
+        self.insert_decoded(path, node)
+    }
+
+    /// Updates the value of the leaf with the given full key, inserting the leaf if it doesn't
+    /// exist.
+    pub fn update_leaf<P>(
+        &mut self,
+        full_key: &Nibbles,
+        value: Vec<u8>,
+        provider: &mut P,
+    ) -> Result<(), SparseTrieError>
+    where
+        P: BlindedProvider,
+        P::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let mut current = 0;
+        loop {
+            let path = Nibbles::from_nibbles_unchecked(&full_key[..current]);
+            self.references.remove(&path);
+            let remaining = &full_key[current..];
+
+            match self.nodes.get(&path).cloned() {
+                None | Some(SparseNode::Empty) => {
+                    let key = Nibbles::from_nibbles_unchecked(remaining);
+                    self.nodes.insert(path, SparseNode::Leaf { key, value });
+                    return Ok(())
+                }
+                Some(SparseNode::Hash(_)) => self.reveal_blinded(path, provider)?,
+                Some(SparseNode::Leaf { key, .. }) if key[..] == *remaining => {
+                    self.nodes.insert(path, SparseNode::Leaf { key, value });
+                    return Ok(())
+                }
+                Some(SparseNode::Leaf { key, value: existing_value }) => {
+                    let common = common_prefix_length(&key, remaining);
+                    let branch_path = join(&path, &key[..common]);
+                    if common > 0 {
+                        let key = Nibbles::from_nibbles_unchecked(&key[..common]);
+                        self.nodes.insert(path, SparseNode::Extension { key });
+                    }
+
+                    self.insert_node(
+                        join(&branch_path, &key[common..=common]),
+                        SparseNode::Leaf {
+                            key: Nibbles::from_nibbles_unchecked(&key[common + 1..]),
+                            value: existing_value,
+                        },
+                    );
+                    self.insert_node(
+                        join(&branch_path, &remaining[common..=common]),
+                        SparseNode::Leaf {
+                            key: Nibbles::from_nibbles_unchecked(&remaining[common + 1..]),
+                            value,
+                        },
+                    );
+                    let state_mask = 1 << key[common] | 1 << remaining[common];
+                    self.insert_node(branch_path, SparseNode::Branch { state_mask });
+                    return Ok(())
+                }
+                Some(SparseNode::Extension { key }) if remaining.starts_with(&key) => {
+                    current += key.len();
+                }
+                Some(SparseNode::Extension { key }) => {
+                    // Split the extension with a branch at the first diverging nibble, the child
+                    // of the extension stays at its path.
+                    let common = common_prefix_length(&key, remaining);
+                    let branch_path = join(&path, &key[..common]);
+                    if common > 0 {
+                        let key = Nibbles::from_nibbles_unchecked(&key[..common]);
+                        self.nodes.insert(path, SparseNode::Extension { key });
+                    }
+
+                    if key.len() > common + 1 {
+                        self.insert_node(
+                            join(&branch_path, &key[common..=common]),
+                            SparseNode::Extension {
+                                key: Nibbles::from_nibbles_unchecked(&key[common + 1..]),
+                            },
+                        );
+                    }
+                    self.insert_node(
+                        join(&branch_path, &remaining[common..=common]),
+                        SparseNode::Leaf {
+                            key: Nibbles::from_nibbles_unchecked(&remaining[common + 1..]),
+                            value,
+                        },
+                    );
+                    let state_mask = 1 << key[common] | 1 << remaining[common];
+                    self.insert_node(branch_path, SparseNode::Branch { state_mask });
+                    return Ok(())
+                }
+                Some(SparseNode::Branch { state_mask }) => {
+                    let nibble = remaining[0];
+                    if state_mask & (1 << nibble) == 0 {
+                        let state_mask = state_mask | 1 << nibble;
+                        self.nodes.insert(path.clone(), SparseNode::Branch { state_mask });
+                        self.insert_node(
+                            join(&path, &remaining[..1]),
+                            SparseNode::Leaf {
+                                key: Nibbles::from_nibbles_unchecked(&remaining[1..]),
+                                value,
+                            },
+                        );
+                        return Ok(())
+                    }
+                    current += 1;
+                }
+            }
+        }
+    }
+
+    /// Removes the leaf with the given full key, collapsing the branch nodes that are left with a
+    /// single child. Nothing is done if the leaf doesn't exist.
+    pub fn remove_leaf<P>(
+        &mut self,
+        full_key: &Nibbles,
+        provider: &mut P,
+    ) -> Result<(), SparseTrieError>
+    where
+        P: BlindedProvider,
+        P::Error: std::error::Error + Send + Sync + 'static,
+    {
+        // The paths of the branch and extension nodes leading to the leaf.
+        let mut ancestors = Vec::new();
+        let mut current = 0;
+        let leaf_path = loop {
+            let path = Nibbles::from_nibbles_unchecked(&full_key[..current]);
+            let remaining = &full_key[current..];
+
+            match self.nodes.get(&path) {
+                None | Some(SparseNode::Empty) => return Ok(()),
+                Some(SparseNode::Hash(_)) => self.reveal_blinded(path, provider)?,
+                Some(SparseNode::Leaf { key, .. }) => {
+                    if key[..] != *remaining {
+                        return Ok(())
+                    }
+                    break path
+                }
+                Some(SparseNode::Extension { key }) => {
+                    if !remaining.starts_with(key) {
+                        return Ok(())
+                    }
+                    current += key.len();
+                    ancestors.push(path);
+                }
+                Some(SparseNode::Branch { state_mask }) => {
+                    if state_mask & (1u16 << remaining[0]) == 0 {
+                        return Ok(())
+                    }
+                    current += 1;
+                    ancestors.push(path);
+                }
+            }
+        };
+
+        for path in &ancestors {
+            self.references.remove(path);
+        }
+        self.remove_node(&leaf_path);
+
+        let Some(branch_path) = ancestors.pop() else {
+            self.nodes.insert(leaf_path, SparseNode::Empty);
+            return Ok(())
+        };
+        let Some(SparseNode::Branch { state_mask }) = self.nodes.get(&branch_path) else {
+            unreachable!("the parent of a leaf is always a branch")
+        };
+        let state_mask = *state_mask & !(1u16 << leaf_path[branch_path.len()]);
+        if state_mask.count_ones() > 1 {
+            self.nodes.insert(branch_path, SparseNode::Branch { state_mask });
+            return Ok(())
+        }
+
+        // The branch is left with a single child, which is merged into the branch's place.
+        let nibble = state_mask.trailing_zeros() as u8;
+        let child_path = join(&branch_path, &[nibble]);
+        if let Some(SparseNode::Hash(_)) = self.nodes.get(&child_path) {
+            self.reveal_blinded(child_path.clone(), provider)?;
+        }
+        let new_node = match self.nodes.get(&child_path).cloned() {
+            Some(SparseNode::Leaf { key, value }) => {
+                self.remove_node(&child_path);
+                SparseNode::Leaf { key: join(&[nibble], &key), value }
+            }
+            Some(SparseNode::Extension { key }) => {
+                self.remove_node(&child_path);
+                SparseNode::Extension { key: join(&[nibble], &key) }
+            }
+            Some(SparseNode::Branch { .. }) => {
+                SparseNode::Extension { key: Nibbles::from_nibbles_unchecked([nibble]) }
+            }
+            _ => return Err(SparseTrieError::BlindedNodeMissing(child_path)),
+        };
+
+        // The extension above the branch absorbs the merged leaf or extension.
+        if let Some(SparseNode::Extension { key: parent_key }) =
+            ancestors.last().and_then(|path| self.nodes.get(path)).cloned()
+        {
+            let merged = match new_node {
+                SparseNode::Leaf { key, value } => {
+                    SparseNode::Leaf { key: join(&parent_key, &key), value }
+                }
+                SparseNode::Extension { key } => {
+                    SparseNode::Extension { key: join(&parent_key, &key) }
+                }
+                _ => unreachable!("branch is merged into an extension"),
+            };
+            let parent_path = ancestors.pop().expect("exists");
+            self.remove_node(&branch_path);
+            self.nodes.insert(parent_path, merged);
+        } else {
+            self.nodes.insert(branch_path, new_node);
+        }
+        Ok(())
+    }
+
+    /// Calculates the root of the trie. Only the nodes that changed since the last calculation
+    /// are re-hashed.
+    pub fn root(&mut self) -> B256 {
+        let root_path = Nibbles::default();
+        match self.nodes.get(&root_path) {
+            None | Some(SparseNode::Empty) => EMPTY_ROOT_HASH,
+            Some(SparseNode::Hash(hash)) => *hash,
+            Some(_) => keccak256(self.node_rlp(&root_path)),
+        }
+    }
+
+    /// Retrieves the blinded node at the given path from the provider and reveals it.
+    fn reveal_blinded<P>(&mut self, path: Nibbles, provider: &mut P) -> Result<(), SparseTrieError>
+    where
+        P: BlindedProvider,
+        P::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let node = provider
+            .blinded_node(&path)
+            .map_err(|err| SparseTrieError::BlindedProvider(Box::new(err)))?
+            .ok_or_else(|| SparseTrieError::BlindedNodeMissing(path.clone()))?;
+        self.reveal_node(path, &node)
+    }
+
+    /// Inserts the decoded node at the given path along with the placeholders of its children.
+    /// The children embedded into the node are revealed right away.
+    fn insert_decoded(&mut self, path: Nibbles, node: &[u8]) -> Result<(), SparseTrieError> {
+        let rlp_error = |err| SparseTrieError::Rlp { path: path.clone(), err };
+        match decode_node(node).map_err(rlp_error)? {
+            DecodedNode::Leaf { key, value } => {
+                self.nodes.insert(path, SparseNode::Leaf { key, value: value.to_vec() });
+            }
+            DecodedNode::Extension { key, child } => {
+                let child_path = join(&path, &key);
+                self.nodes.insert(path, SparseNode::Extension { key });
+                self.insert_child(child_path, child)?;
+            }
+            DecodedNode::Branch { children } => {
+                let mut state_mask = 0u16;
+                for (nibble, child) in children.iter().enumerate() {
+                    if let Some(child) = child {
+                        state_mask |= 1 << nibble;
+                        self.insert_child(join(&path, &[nibble as u8]), child)?;
+                    }
+                }
+                self.nodes.insert(path, SparseNode::Branch { state_mask });
+            }
+        }
+        Ok(())
+    }
+
+    /// Inserts the child by its rlp-encoded reference, which is either the hash of the child or
+    /// the embedded child itself.
+    fn insert_child(&mut self, path: Nibbles, reference: &[u8]) -> Result<(), SparseTrieError> {
+        if reference.len() == B256::len_bytes() + 1 && reference[0] == EMPTY_STRING_CODE + 32 {
+            self.nodes.entry(path).or_insert(SparseNode::Hash(B256::from_slice(&reference[1..])));
+            Ok(())
+        } else {
+            self.insert_decoded(path, reference)
+        }
+    }
+
+    /// Inserts the node, dropping the cached reference of the node that was at this path.
+    fn insert_node(&mut self, path: Nibbles, node: SparseNode) {
+        self.references.remove(&path);
+        self.nodes.insert(path, node);
+    }
+
+    /// Removes the node and its cached reference.
+    fn remove_node(&mut self, path: &Nibbles) {
+        self.references.remove(path);
+        self.nodes.remove(path);
+    }
+
+    /// Returns the rlp-encoded reference of the node at the given path: the node itself if it's
+    /// shorter than 32 bytes, otherwise its hash.
+    fn reference(&mut self, path: &Nibbles) -> Vec<u8> {
+        if let Some(reference) = self.references.get(path) {
+            return reference.clone()
+        }
+
+        let reference = match self.nodes.get(path) {
+            Some(SparseNode::Hash(hash)) => encode_hash(hash),
+            _ => {
+                let node = self.node_rlp(path);
+                if node.len() < B256::len_bytes() {
+                    node
+                } else {
+                    encode_hash(&keccak256(&node))
+                }
+            }
+        };
+        self.references.insert(path.clone(), reference.clone());
+        reference
+    }
+
+    /// Returns the rlp encoding of the revealed node at the given path.
+    fn node_rlp(&mut self, path: &Nibbles) -> Vec<u8> {
+        let mut payload = Vec::new();
+        match self.nodes.get(path).cloned() {
+            None | Some(SparseNode::Empty) => return vec![EMPTY_STRING_CODE],
+            Some(SparseNode::Hash(hash)) => return encode_hash(&hash),
+            Some(SparseNode::Leaf { key, value }) => {
+                encode_path(&key, true).as_slice().encode(&mut payload);
+                value.as_slice().encode(&mut payload);
+            }
+            Some(SparseNode::Extension { key }) => {
+                encode_path(&key, false).as_slice().encode(&mut payload);
+                payload.extend(self.reference(&join(path, &key)));
+            }
+            Some(SparseNode::Branch { state_mask }) => {
+                for nibble in 0..16u8 {
+                    if state_mask & (1 << nibble) != 0 {
+                        payload.extend(self.reference(&join(path, &[nibble])));
+                    } else {
+                        payload.push(EMPTY_STRING_CODE);
+                    }
+                }
+                // branch nodes never have values in the hashed tries
+                payload.push(EMPTY_STRING_CODE);
+            }
+        }
+
+        let mut node = Vec::with_capacity(payload.len() + 3);
+        Header { list: true, payload_length: payload.len() }.encode(&mut node);
+        node.extend(payload);
+        node
+    }
+}
+
+/// The trie node decoded from its rlp encoding, the references of the children are left encoded.
+enum DecodedNode<'a> {
+    Leaf { key: Nibbles, value: &'a [u8] },
+    Extension { key: Nibbles, child: &'a [u8] },
+    Branch { children: [Option<&'a [u8]>; 16] },
+}
+
+/// Decodes the rlp-encoded trie node.
+fn decode_node(mut buf: &[u8]) -> alloy_rlp::Result<DecodedNode<'_>> {
+    let header = Header::decode(&mut buf)?;
+    if !header.list {
+        return Err(alloy_rlp::Error::UnexpectedString)
+    }
+    let mut payload = buf.get(..header.payload_length).ok_or(alloy_rlp::Error::InputTooShort)?;
+
+    // Collect the items including their headers.
+    let mut items = Vec::with_capacity(17);
+    while !payload.is_empty() {
+        let mut rest = payload;
+        let item = Header::decode(&mut rest)?;
+        let length = payload.len() - rest.len() + item.payload_length;
+        items.push(payload.get(..length).ok_or(alloy_rlp::Error::InputTooShort)?);
+        payload = &payload[length..];
+    }
+
+    match items.len() {
+        2 => {
+            let (key, is_leaf) = decode_path(string_payload(items[0])?)?;
+            if is_leaf {
+                Ok(DecodedNode::Leaf { key, value: string_payload(items[1])? })
+            } else {
+                Ok(DecodedNode::Extension { key, child: items[1] })
+            }
+        }
+        17 => {
+            let mut children = [None; 16];
+            for (child, item) in children.iter_mut().zip(&items) {
+                if *item != [EMPTY_STRING_CODE].as_slice() {
+                    *child = Some(*item);
+                }
+            }
+            Ok(DecodedNode::Branch { children })
+        }
+        _ => Err(alloy_rlp::Error::Custom("invalid number of trie node items")),
+    }
+}
+
+/// Returns the payload of the rlp-encoded string.
+fn string_payload(item: &[u8]) -> alloy_rlp::Result<&[u8]> {
+    let mut buf = item;
+    let header = Header::decode(&mut buf)?;
+    if header.list {
+        return Err(alloy_rlp::Error::UnexpectedList)
+    }
+    buf.get(..header.payload_length).ok_or(alloy_rlp::Error::InputTooShort)
+}
+
+/// Encodes the path of the leaf or extension node with the hex-prefix encoding.
+fn encode_path(path: &[u8], is_leaf: bool) -> Vec<u8> {
+    let mut flag = if is_leaf { 0x20 } else { 0x00 };
+    let mut rest = path;
+    if path.len() % 2 == 1 {
+        flag |= 0x10 | path[0];
+        rest = &path[1..];
+    }
+
+    let mut encoded = Vec::with_capacity(path.len() / 2 + 1);
+    encoded.push(flag);
+    encoded.extend(rest.chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+    encoded
+}
+
+/// Decodes the hex-prefix encoded path, returns the path and whether it belongs to a leaf.
+fn decode_path(encoded: &[u8]) -> alloy_rlp::Result<(Nibbles, bool)> {
+    let (flag, rest) = encoded.split_first().ok_or(alloy_rlp::Error::InputTooShort)?;
+    let mut path = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 0x10 != 0 {
+        path.push(flag & 0x0f);
+    }
+    for byte in rest {
+        path.push(byte >> 4);
+        path.push(byte & 0x0f);
+    }
+    Ok((Nibbles::from_nibbles_unchecked(path), flag & 0x20 != 0))
+}
+
+/// Returns the rlp-encoded reference of the node by its hash.
+fn encode_hash(hash: &B256) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(B256::len_bytes() + 1);
+    hash.encode(&mut encoded);
+    encoded
+}
+
+/// Concatenates the nibbles.
+fn join(a: &[u8], b: &[u8]) -> Nibbles {
+    Nibbles::from_nibbles_unchecked([a, b].concat())
+}
+
+/// Returns the length of the common prefix of the nibbles.
+fn common_prefix_length(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        blinded::{BlindedProviderFactory, DatabaseBlindedProviderFactory},
+        proof::Proof,
+        test_utils::storage_root_prehashed,
+        StateRoot,
+    };
+    use alloy_rlp::encode_fixed_size;
+    use proptest::prelude::*;
+    use reth_db::tables;
+    use reth_db_api::{cursor::DbCursorRW, transaction::DbTxMut};
+    use reth_primitives::{Account, StorageEntry, U256};
+    use reth_provider::test_utils::create_test_provider_factory;
+    use std::collections::{BTreeMap, HashSet};
+
+    /// The provider for the tries that are fully revealed.
+    struct RevealedProvider;
+
+    impl BlindedProvider for RevealedProvider {
+        type Error = std::convert::Infallible;
+
+        fn blinded_node(&mut self, _path: &Nibbles) -> Result<Option<Bytes>, Self::Error> {
+            Ok(None)
+        }
+    }
+
+    fn storage_value(value: U256) -> Vec<u8> {
+        encode_fixed_size(&value).to_vec()
+    }
+
+    #[test]
+    fn empty_trie() {
+        let mut trie = SparseTrie::default();
+        assert_eq!(trie.root(), EMPTY_ROOT_HASH);
+
+        let key = Nibbles::unpack(B256::with_last_byte(1));
+        trie.update_leaf(&key, storage_value(U256::from(1)), &mut RevealedProvider).unwrap();
+        trie.remove_leaf(&key, &mut RevealedProvider).unwrap();
+        assert_eq!(trie.root(), EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn embedded_nodes() {
+        // The leaves with keys that share all but the last nibble are short enough to be embedded
+        // into their parent branch.
+        let mut trie = SparseTrie::default();
+        let mut storage = BTreeMap::new();
+        for nibble in [0x1, 0x2, 0xf] {
+            let mut key = B256::ZERO;
+            key[31] = nibble;
+            storage.insert(key, U256::from(nibble));
+            trie.update_leaf(
+                &Nibbles::unpack(key),
+                storage_value(U256::from(nibble)),
+                &mut RevealedProvider,
+            )
+            .unwrap();
+            assert_eq!(trie.root(), storage_root_prehashed(storage.clone()));
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn fuzz_updates_and_removals(
+            updates in proptest::collection::vec(
+                proptest::collection::btree_map(any::<[u8; 2]>(), any::<u64>(), 1..32),
+                1..8
+            )
+        ) {
+            let mut trie = SparseTrie::default();
+            let mut storage = BTreeMap::new();
+            for update in updates {
+                for (key, value) in update {
+                    // Short keys make for long shared prefixes and collapsing branches.
+                    let mut slot = B256::ZERO;
+                    slot[..2].copy_from_slice(&key);
+                    let full_key = Nibbles::unpack(slot);
+                    if value % 4 == 0 {
+                        storage.remove(&slot);
+                        trie.remove_leaf(&full_key, &mut RevealedProvider).unwrap();
+                    } else {
+                        let value = U256::from(value);
+                        storage.insert(slot, value);
+                        trie.update_leaf(&full_key, storage_value(value), &mut RevealedProvider)
+                            .unwrap();
+                    }
+                }
+                prop_assert_eq!(trie.root(), storage_root_prehashed(storage.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn updates_on_top_of_database_trie() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let hashed_address = B256::random();
+        let mut storage = (1..=512u64)
+            .map(|i| (keccak256(B256::from(U256::from(i))), U256::from(i)))
+            .collect::<BTreeMap<_, _>>();
+        let mut hashed_storage_cursor = tx.cursor_dup_write::<tables::HashedStorages>().unwrap();
+        for (hashed_slot, value) in &storage {
+            let entry = StorageEntry { key: *hashed_slot, value: *value };
+            hashed_storage_cursor.upsert(hashed_address, entry).unwrap();
+        }
+        tx.put::<tables::HashedAccounts>(hashed_address, Account::default()).unwrap();
+        let (_, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        updates.flush(tx).unwrap();
+
+        // Reveal the proofs of the slots that are going to be updated, the siblings of the
+        // removed slots are retrieved from the database on demand.
+        let updated = storage.keys().step_by(3).copied().collect::<Vec<_>>();
+        let removed = storage.keys().skip(1).step_by(3).copied().collect::<Vec<_>>();
+        let inserted = (0..64).map(|_| B256::random()).collect::<Vec<_>>();
+        let targets =
+            updated.iter().chain(&removed).chain(&inserted).copied().collect::<HashSet<_>>();
+        let proof = Proof::new(tx).storage_multiproof(hashed_address, &targets).unwrap();
+
+        let mut trie = SparseTrie::blind(proof.root);
+        trie.reveal_nodes(&proof.subtree).unwrap();
+        assert_eq!(trie.root(), storage_root_prehashed(storage.clone()));
+
+        let mut blinded_provider =
+            DatabaseBlindedProviderFactory::new(tx).storage_node_provider(hashed_address);
+        for (i, hashed_slot) in updated.iter().chain(&inserted).enumerate() {
+            let value = U256::from(1_000 + i);
+            storage.insert(*hashed_slot, value);
+            trie.update_leaf(
+                &Nibbles::unpack(hashed_slot),
+                storage_value(value),
+                &mut blinded_provider,
+            )
+            .unwrap();
+        }
+        for hashed_slot in &removed {
+            storage.remove(hashed_slot);
+            trie.remove_leaf(&Nibbles::unpack(hashed_slot), &mut blinded_provider).unwrap();
+        }
+        assert_eq!(trie.root(), storage_root_prehashed(storage.clone()));
+
+        // Remove all remaining slots, which collapses the siblings that were never revealed.
+        for hashed_slot in storage.keys() {
+            trie.remove_leaf(&Nibbles::unpack(hashed_slot), &mut blinded_provider).unwrap();
+        }
+        assert_eq!(trie.root(), EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn reveal_hash_mismatch() {
+        let mut trie = SparseTrie::blind(B256::random());
+        let node = [0xc2, 0x20, 0x01];
+        assert!(matches!(
+            trie.reveal_node(Nibbles::default(), &node),
+            Err(SparseTrieError::NodeHashMismatch { .. })
+        ));
+    }
+}
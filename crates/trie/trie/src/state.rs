@@ -1,8 +1,9 @@
 use crate::{
-    hashed_cursor::HashedPostStateCursorFactory,
+    hashed_cursor::{HashedCursorFactory, HashedPostStateCursorFactory},
     prefix_set::{PrefixSetMut, TriePrefixSets},
+    proof::Proof,
     updates::TrieUpdates,
-    StateRoot,
+    StateRoot, StorageRange, StorageRoot,
 };
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use reth_db::{tables, DatabaseError};
@@ -11,17 +12,60 @@ use reth_db_api::{
     models::{AccountBeforeTx, BlockNumberAddress},
     transaction::DbTx,
 };
-use reth_execution_errors::StateRootError;
+use reth_execution_errors::{StateRootError, StorageRootError};
 use reth_primitives::{
-    keccak256, revm::compat::into_reth_acc, trie::Nibbles, Account, Address, BlockNumber, B256,
-    U256,
+    keccak256,
+    revm::compat::into_reth_acc,
+    trie::{AccountProof, MultiProof, Nibbles, StorageMultiProof},
+    Account, Address, BlockNumber, B256, U256,
 };
-use revm::db::BundleAccount;
+use revm::{db::BundleAccount, primitives::EvmState};
 use std::{
     collections::{hash_map, HashMap, HashSet},
     ops::RangeInclusive,
 };
 
+/// Plain values of accounts and storage slots before their first change in a block range.
+#[derive(Default, Debug)]
+struct RevertChanges {
+    /// Mapping of address to account info before the first change.
+    accounts: HashMap<Address, Option<Account>>,
+    /// Mapping of address to storage slot values before the first change.
+    storages: HashMap<Address, HashMap<B256, U256>>,
+}
+
+impl RevertChanges {
+    /// Walks the account and storage changesets in the given block range and records the values
+    /// before the first occurring change of each account and storage slot.
+    fn collect<TX: DbTx>(
+        tx: &TX,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Self, DatabaseError> {
+        let mut changes = Self::default();
+
+        // Iterate over account changesets and record value before first occurring account change.
+        let mut account_changesets_cursor = tx.cursor_read::<tables::AccountChangeSets>()?;
+        for entry in account_changesets_cursor.walk_range(range.clone())? {
+            let (_, AccountBeforeTx { address, info }) = entry?;
+            if let hash_map::Entry::Vacant(entry) = changes.accounts.entry(address) {
+                entry.insert(info);
+            }
+        }
+
+        // Iterate over storage changesets and record value before first occurring storage change.
+        let mut storage_changesets_cursor = tx.cursor_read::<tables::StorageChangeSets>()?;
+        for entry in storage_changesets_cursor.walk_range(BlockNumberAddress::range(range))? {
+            let (BlockNumberAddress((_, address)), storage) = entry?;
+            let account_storage = changes.storages.entry(address).or_default();
+            if let hash_map::Entry::Vacant(entry) = account_storage.entry(storage.key) {
+                entry.insert(storage.value);
+            }
+        }
+
+        Ok(changes)
+    }
+}
+
 /// Representation of in-memory hashed state.
 #[derive(PartialEq, Eq, Clone, Default, Debug)]
 pub struct HashedPostState {
@@ -62,6 +106,27 @@ impl HashedPostState {
         Self { accounts, storages }
     }
 
+    /// Initialize [`HashedPostState`] from the state changes of a single transaction.
+    /// Hashes all touched accounts and their changed storage entries. The storage of the
+    /// self-destructed accounts is marked as wiped.
+    pub fn from_evm_state(state: &EvmState) -> Self {
+        let mut this = Self::default();
+        for (address, account) in state.iter().filter(|(_, account)| account.is_touched()) {
+            let hashed_address = keccak256(address);
+            let destroyed = account.is_selfdestructed();
+            let hashed_account = (!destroyed).then(|| into_reth_acc(account.info.clone()));
+            let hashed_storage = HashedStorage::from_iter(
+                destroyed,
+                account.storage.iter().filter(|(_, slot)| slot.is_changed()).map(|(key, slot)| {
+                    (keccak256(B256::new(key.to_be_bytes())), slot.present_value)
+                }),
+            );
+            this.accounts.insert(hashed_address, hashed_account);
+            this.storages.insert(hashed_address, hashed_storage);
+        }
+        this
+    }
+
     /// Initialize [`HashedPostState`] from revert range.
     /// Iterate over state reverts in the specified block range and
     /// apply them to hashed state in reverse.
@@ -72,26 +137,7 @@ impl HashedPostState {
         tx: &TX,
         range: RangeInclusive<BlockNumber>,
     ) -> Result<Self, DatabaseError> {
-        // Iterate over account changesets and record value before first occurring account change.
-        let mut accounts = HashMap::<Address, Option<Account>>::default();
-        let mut account_changesets_cursor = tx.cursor_read::<tables::AccountChangeSets>()?;
-        for entry in account_changesets_cursor.walk_range(range.clone())? {
-            let (_, AccountBeforeTx { address, info }) = entry?;
-            if let hash_map::Entry::Vacant(entry) = accounts.entry(address) {
-                entry.insert(info);
-            }
-        }
-
-        // Iterate over storage changesets and record value before first occurring storage change.
-        let mut storages = HashMap::<Address, HashMap<B256, U256>>::default();
-        let mut storage_changesets_cursor = tx.cursor_read::<tables::StorageChangeSets>()?;
-        for entry in storage_changesets_cursor.walk_range(BlockNumberAddress::range(range))? {
-            let (BlockNumberAddress((_, address)), storage) = entry?;
-            let account_storage = storages.entry(address).or_default();
-            if let hash_map::Entry::Vacant(entry) = account_storage.entry(storage.key) {
-                entry.insert(storage.value);
-            }
-        }
+        let RevertChanges { accounts, storages } = RevertChanges::collect(tx, range)?;
 
         let hashed_accounts = HashMap::from_iter(
             accounts.into_iter().map(|(address, info)| (keccak256(address), info)),
@@ -113,6 +159,40 @@ impl HashedPostState {
         Ok(Self { accounts: hashed_accounts, storages: hashed_storages })
     }
 
+    /// Initialize [`HashedPostState`] from revert range, hashing the changed accounts and storage
+    /// slots in parallel.
+    ///
+    /// The result is the same as of [`Self::from_revert_range`], but for large ranges the
+    /// computation is considerably faster.
+    ///
+    /// NOTE: In order to have the resulting [`HashedPostState`] be a correct
+    /// overlay of the plain state, the end of the range must be the current tip.
+    pub fn from_revert_range_parallel<TX: DbTx>(
+        tx: &TX,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Self, DatabaseError> {
+        let RevertChanges { accounts, storages } = RevertChanges::collect(tx, range)?;
+
+        let hashed_accounts = accounts
+            .into_par_iter()
+            .map(|(address, info)| (keccak256(address), info))
+            .collect::<HashMap<_, _>>();
+
+        let hashed_storages = storages
+            .into_par_iter()
+            .map(|(address, storage)| {
+                let hashed_storage = storage
+                    .into_par_iter()
+                    .map(|(slot, value)| (keccak256(slot), value))
+                    .collect::<HashMap<_, _>>();
+                // See `from_revert_range` for why reverts are never wiped.
+                (keccak256(address), HashedStorage { wiped: false, storage: hashed_storage })
+            })
+            .collect::<HashMap<_, _>>();
+
+        Ok(Self { accounts: hashed_accounts, storages: hashed_storages })
+    }
+
     /// Set account entries on hashed state.
     pub fn with_accounts(
         mut self,
@@ -258,6 +338,102 @@ impl HashedPostState {
             .with_prefix_sets(prefix_sets)
             .root_with_updates()
     }
+
+    /// Calculates the state root for this [`HashedPostState`] using the pre-computed storage roots
+    /// of the changed accounts. See [`StateRoot::with_storage_roots`] for more info.
+    pub fn state_root_with_storage_roots<TX: DbTx>(
+        &self,
+        tx: &TX,
+        storage_roots: HashMap<B256, B256>,
+    ) -> Result<B256, StateRootError> {
+        let sorted = self.clone().into_sorted();
+        let prefix_sets = self.construct_prefix_sets();
+        StateRoot::from_tx(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, &sorted))
+            .with_prefix_sets(prefix_sets)
+            .with_storage_roots(storage_roots)
+            .root()
+    }
+
+    /// Calculates the storage root of the account on top of this [`HashedPostState`].
+    pub fn storage_root<TX: DbTx>(
+        &self,
+        tx: &TX,
+        hashed_address: B256,
+    ) -> Result<B256, StorageRootError> {
+        let sorted = self.clone().into_sorted();
+        let mut prefix_sets = self.construct_prefix_sets();
+        StorageRoot::from_tx_hashed(tx, hashed_address)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, &sorted))
+            .with_prefix_set(
+                prefix_sets.storage_prefix_sets.remove(&hashed_address).unwrap_or_default(),
+            )
+            .root()
+    }
+
+    /// Generates the account proof for the target address and its storage slots on top of this
+    /// [`HashedPostState`]. See [`Proof::account_proof`] for more info.
+    pub fn account_proof<TX: DbTx>(
+        &self,
+        tx: &TX,
+        address: Address,
+        slots: &[B256],
+    ) -> Result<AccountProof, StateRootError> {
+        let sorted = self.clone().into_sorted();
+        let prefix_sets = self.construct_prefix_sets();
+        Proof::new(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, &sorted))
+            .with_prefix_sets(prefix_sets)
+            .account_proof(address, slots)
+    }
+
+    /// Generates the state multiproof for the target accounts and their storage slots on top of
+    /// this [`HashedPostState`]. The targets are hashed addresses mapped to the sets of hashed
+    /// storage slots. See [`Proof::multiproof`] for more info.
+    pub fn multiproof<TX: DbTx>(
+        &self,
+        tx: &TX,
+        targets: &HashMap<B256, HashSet<B256>>,
+    ) -> Result<MultiProof, StateRootError> {
+        let sorted = self.clone().into_sorted();
+        let prefix_sets = self.construct_prefix_sets();
+        Proof::new(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, &sorted))
+            .with_prefix_sets(prefix_sets)
+            .multiproof(targets)
+    }
+
+    /// Generates the storage multiproof for the target hashed slots of the account on top of this
+    /// [`HashedPostState`]. See [`Proof::storage_multiproof`] for more info.
+    pub fn storage_multiproof<TX: DbTx>(
+        &self,
+        tx: &TX,
+        hashed_address: B256,
+        hashed_slots: &HashSet<B256>,
+    ) -> Result<StorageMultiProof, StorageRootError> {
+        let sorted = self.clone().into_sorted();
+        let prefix_sets = self.construct_prefix_sets();
+        Proof::new(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, &sorted))
+            .with_prefix_sets(prefix_sets)
+            .storage_multiproof(hashed_address, hashed_slots)
+    }
+
+    /// Collects up to `limit` storage slots of the account on top of this [`HashedPostState`],
+    /// starting at the first hashed slot greater or equal to `start`. See
+    /// [`StorageRange::from_cursor`] for more info.
+    pub fn storage_range<TX: DbTx>(
+        &self,
+        tx: &TX,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> Result<StorageRange, DatabaseError> {
+        let sorted = self.clone().into_sorted();
+        let mut cursor =
+            HashedPostStateCursorFactory::new(tx, &sorted).hashed_storage_cursor(hashed_address)?;
+        StorageRange::from_cursor(&mut cursor, start, limit)
+    }
 }
 
 /// Representation of in-memory hashed storage.
@@ -334,14 +510,67 @@ pub struct HashedStorageSorted {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::{prelude::ProptestConfig, proptest};
     use reth_db::test_utils::create_test_rw_db;
-    use reth_db_api::database::Database;
-    use reth_primitives::hex;
+    use reth_db_api::{database::Database, transaction::DbTxMut};
+    use reth_primitives::{hex, StorageEntry};
     use revm::{
         db::states::BundleState,
         primitives::{AccountInfo, HashMap},
     };
 
+    #[test]
+    fn state_root_with_precomputed_storage_roots() {
+        let db = create_test_rw_db();
+        let tx = db.tx().expect("failed to create transaction");
+
+        let state = HashedPostState::default()
+            .with_accounts((0..16).map(|address| {
+                (B256::with_last_byte(address), Some(Account { nonce: 1, ..Default::default() }))
+            }))
+            .with_storages((0..16).map(|address| {
+                let storage =
+                    (1..=address).map(|slot| (B256::with_last_byte(slot), U256::from(slot)));
+                (B256::with_last_byte(address), HashedStorage::from_iter(false, storage))
+            }));
+
+        let storage_roots = state
+            .storages
+            .keys()
+            .map(|hashed_address| {
+                (*hashed_address, state.storage_root(&tx, *hashed_address).unwrap())
+            })
+            .collect();
+        assert_eq!(
+            state.state_root_with_storage_roots(&tx, storage_roots).unwrap(),
+            state.state_root(&tx).unwrap()
+        );
+    }
+
+    #[test]
+    fn account_proof_on_top_of_hashed_state() {
+        let db = create_test_rw_db();
+        let tx = db.tx().expect("failed to create transaction");
+
+        let address = Address::with_last_byte(7);
+        let slots = (0..4).map(B256::with_last_byte).collect::<Vec<_>>();
+        let state = HashedPostState::default()
+            .with_accounts((0..16).map(|address| {
+                let account = Account { nonce: address as u64, ..Default::default() };
+                (keccak256(Address::with_last_byte(address)), Some(account))
+            }))
+            .with_storages([(
+                keccak256(address),
+                HashedStorage::from_iter(
+                    false,
+                    slots.iter().map(|slot| (keccak256(slot), U256::from(1))),
+                ),
+            )]);
+
+        let proof = state.account_proof(&tx, address, &slots).unwrap();
+        assert_eq!(proof.verify(state.state_root(&tx).unwrap()), Ok(()));
+    }
+
     #[test]
     fn hashed_state_wiped_extension() {
         let hashed_address = B256::default();
@@ -445,4 +674,43 @@ mod tests {
             hex!("b464525710cafcf5d4044ac85b72c08b1e76231b8d91f288fe438cc41d8eaafd")
         );
     }
+
+    #[test]
+    fn from_revert_range_parallel() {
+        proptest!(
+            ProptestConfig::with_cases(10),
+            |(
+                account_changes: Vec<(u8, u8, Option<Account>)>,
+                storage_changes: Vec<(u8, u8, u8, U256)>,
+                range_start: u8,
+            )| {
+                let db = create_test_rw_db();
+                let tx = db.tx_mut().expect("failed to create transaction");
+
+                // Small key spaces make sure that accounts and slots change multiple times
+                for (block, address, info) in account_changes {
+                    let address = Address::with_last_byte(address % 16);
+                    tx.put::<tables::AccountChangeSets>(
+                        block as BlockNumber,
+                        AccountBeforeTx { address, info },
+                    )
+                    .unwrap();
+                }
+                for (block, address, slot, value) in storage_changes {
+                    let address = Address::with_last_byte(address % 16);
+                    tx.put::<tables::StorageChangeSets>(
+                        BlockNumberAddress((block as BlockNumber, address)),
+                        StorageEntry { key: B256::with_last_byte(slot % 16), value },
+                    )
+                    .unwrap();
+                }
+
+                let range = range_start as BlockNumber..=u8::MAX as BlockNumber;
+                assert_eq!(
+                    HashedPostState::from_revert_range_parallel(&tx, range.clone()).unwrap(),
+                    HashedPostState::from_revert_range(&tx, range).unwrap()
+                );
+            }
+        );
+    }
 }
@@ -6,6 +6,7 @@ pub struct TrieStats {
     duration: Duration,
     branches_added: u64,
     leaves_added: u64,
+    walk: TrieWalkStats,
 }
 
 impl TrieStats {
@@ -23,6 +24,55 @@ impl TrieStats {
     pub const fn branches_added(&self) -> u64 {
         self.branches_added
     }
+
+    /// Number of intermediate branch nodes consumed from the trie by the walker.
+    pub const fn branch_nodes_consumed(&self) -> u64 {
+        self.walk.branch_nodes_consumed
+    }
+
+    /// Number of subtrees the walker skipped because they were not in the prefix set.
+    pub const fn subtrees_skipped(&self) -> u64 {
+        self.walk.subtrees_skipped
+    }
+
+    /// Number of seeks performed on the trie cursor.
+    pub const fn trie_seeks(&self) -> u64 {
+        self.walk.trie_seeks
+    }
+
+    /// Number of seeks performed on the hashed cursor.
+    pub const fn hashed_seeks(&self) -> u64 {
+        self.walk.hashed_seeks
+    }
+
+    /// Stats of the trie walk.
+    pub const fn walk(&self) -> TrieWalkStats {
+        self.walk
+    }
+}
+
+/// Stats of walking the intermediate trie nodes and the hashed entries, see
+/// [`TrieNodeIter::stats`](crate::node_iter::TrieNodeIter::stats).
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct TrieWalkStats {
+    /// Number of intermediate branch nodes consumed from the trie by the walker.
+    pub branch_nodes_consumed: u64,
+    /// Number of subtrees the walker skipped because they were not in the prefix set.
+    pub subtrees_skipped: u64,
+    /// Number of seeks performed on the trie cursor.
+    pub trie_seeks: u64,
+    /// Number of seeks performed on the hashed cursor.
+    pub hashed_seeks: u64,
+}
+
+impl TrieWalkStats {
+    /// Adds the stats of another walk to this one.
+    pub fn extend(&mut self, other: Self) {
+        self.branch_nodes_consumed += other.branch_nodes_consumed;
+        self.subtrees_skipped += other.subtrees_skipped;
+        self.trie_seeks += other.trie_seeks;
+        self.hashed_seeks += other.hashed_seeks;
+    }
 }
 
 /// Trie metrics tracker.
@@ -31,11 +81,17 @@ pub struct TrieTracker {
     started_at: Instant,
     branches_added: u64,
     leaves_added: u64,
+    walk: TrieWalkStats,
 }
 
 impl Default for TrieTracker {
     fn default() -> Self {
-        Self { started_at: Instant::now(), branches_added: 0, leaves_added: 0 }
+        Self {
+            started_at: Instant::now(),
+            branches_added: 0,
+            leaves_added: 0,
+            walk: TrieWalkStats::default(),
+        }
     }
 }
 
@@ -50,12 +106,18 @@ impl TrieTracker {
         self.leaves_added += 1;
     }
 
+    /// Add the stats of the trie walk performed during the calculation.
+    pub fn add_walk_stats(&mut self, stats: TrieWalkStats) {
+        self.walk.extend(stats);
+    }
+
     /// Called when root calculation is finished to return trie statistics.
     pub fn finish(self) -> TrieStats {
         TrieStats {
             duration: self.started_at.elapsed(),
             branches_added: self.branches_added,
             leaves_added: self.leaves_added,
+            walk: self.walk,
         }
     }
 }
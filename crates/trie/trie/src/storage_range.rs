@@ -0,0 +1,196 @@
+use crate::hashed_cursor::HashedCursor;
+use reth_db::{tables, DatabaseError};
+use reth_db_api::{cursor::DbDupCursorRO, transaction::DbTx};
+use reth_primitives::{keccak256, Address, B256, U256};
+use std::collections::BTreeMap;
+
+/// The maximum number of plain storage slots of an account that are scanned to resolve the
+/// preimages of a [`StorageRange`].
+pub const MAX_PLAIN_STORAGE_PREIMAGE_SCAN: usize = 10_000;
+
+/// A contiguous range of the storage of an account, ordered by hashed storage slot.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageRange {
+    /// The storage slots in the range, keyed by their hashed slot.
+    pub slots: BTreeMap<B256, StorageRangeEntry>,
+    /// The hashed slot of the first storage slot after the range, `None` if the range reaches the
+    /// end of the storage.
+    pub next_key: Option<B256>,
+}
+
+/// A storage slot of a [`StorageRange`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StorageRangeEntry {
+    /// The preimage of the hashed slot, if it's known.
+    pub slot: Option<B256>,
+    /// The value of the slot.
+    pub value: U256,
+}
+
+impl StorageRange {
+    /// Collects up to `limit` storage slots from the given cursor, starting at the first hashed
+    /// slot greater or equal to `start`.
+    pub fn from_cursor<C>(cursor: &mut C, start: B256, limit: usize) -> Result<Self, DatabaseError>
+    where
+        C: HashedCursor<Value = U256>,
+    {
+        let mut range = Self::default();
+        let mut entry = cursor.seek(start)?;
+        while let Some((hashed_slot, value)) = entry {
+            if range.slots.len() == limit {
+                range.next_key = Some(hashed_slot);
+                break
+            }
+            range.slots.insert(hashed_slot, StorageRangeEntry { slot: None, value });
+            entry = cursor.next()?;
+        }
+        Ok(range)
+    }
+
+    /// Sets the preimages of the hashed slots in the range that are among the given slots.
+    pub fn set_preimages(&mut self, slots: impl IntoIterator<Item = B256>) {
+        for slot in slots {
+            if let Some(entry) = self.slots.get_mut(&keccak256(slot)) {
+                entry.slot = Some(slot);
+            }
+        }
+    }
+
+    /// Sets the preimages of the hashed slots in the range from the plain storage of the account.
+    ///
+    /// The plain storage isn't indexed by hashed slot, so at most `max_scanned` slots of the
+    /// account are scanned and the scan stops as soon as every slot in the range is resolved. The
+    /// preimages of slots beyond the scanned ones, or that were cleared since, remain unknown.
+    pub fn set_plain_storage_preimages<TX: DbTx>(
+        &mut self,
+        tx: &TX,
+        address: Address,
+        max_scanned: usize,
+    ) -> Result<(), DatabaseError> {
+        let mut unresolved = self.slots.values().filter(|entry| entry.slot.is_none()).count();
+        if unresolved == 0 {
+            return Ok(())
+        }
+
+        let mut cursor = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+        for entry in cursor.walk_dup(Some(address), None)?.take(max_scanned) {
+            let (_, entry) = entry?;
+            if let Some(range_entry) = self.slots.get_mut(&keccak256(entry.key)) {
+                if range_entry.slot.replace(entry.key).is_none() {
+                    unresolved -= 1;
+                    if unresolved == 0 {
+                        break
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hashed_cursor::HashedCursorFactory, HashedPostState, HashedStorage};
+    use reth_db::test_utils::create_test_rw_db;
+    use reth_db_api::{database::Database, transaction::DbTxMut};
+    use reth_primitives::StorageEntry;
+
+    #[test]
+    fn paginate_storage_with_overlay() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().expect("failed to create transaction");
+
+        let address = Address::with_last_byte(1);
+        let hashed_address = keccak256(address);
+        let slots = (0..200u64).map(|slot| B256::from(U256::from(slot))).collect::<Vec<_>>();
+        for (index, slot) in slots.iter().enumerate() {
+            let value = U256::from(index + 1);
+            tx.put::<tables::HashedStorages>(
+                hashed_address,
+                StorageEntry { key: keccak256(slot), value },
+            )
+            .unwrap();
+            // the first half of the slots is still in the plain state
+            if index < 100 {
+                tx.put::<tables::PlainStorageState>(address, StorageEntry { key: *slot, value })
+                    .unwrap();
+            }
+        }
+
+        // clear a slot and write a new one on top of the database
+        let cleared = keccak256(slots[0]);
+        let written = B256::from(U256::from(1_000));
+        let state = HashedPostState::default().with_storages([(
+            hashed_address,
+            HashedStorage::from_iter(
+                false,
+                [(cleared, U256::ZERO), (keccak256(written), U256::from(1_000))],
+            ),
+        )]);
+
+        let mut expected = slots[1..]
+            .iter()
+            .enumerate()
+            .map(|(index, slot)| (keccak256(slot), U256::from(index + 2)))
+            .collect::<BTreeMap<_, _>>();
+        expected.insert(keccak256(written), U256::from(1_000));
+
+        let mut collected = BTreeMap::new();
+        let mut start = B256::ZERO;
+        loop {
+            let mut range = state.storage_range(&tx, hashed_address, start, 32).unwrap();
+            range
+                .set_plain_storage_preimages(&tx, address, MAX_PLAIN_STORAGE_PREIMAGE_SCAN)
+                .unwrap();
+            range.set_preimages([written]);
+            assert!(range.slots.len() <= 32);
+            for (hashed_slot, entry) in &range.slots {
+                let plain = slots[1..100]
+                    .iter()
+                    .chain([&written])
+                    .find(|slot| keccak256(slot) == *hashed_slot);
+                assert_eq!(entry.slot.as_ref(), plain);
+                collected.insert(*hashed_slot, entry.value);
+            }
+            match range.next_key {
+                Some(next_key) => start = next_key,
+                None => break,
+            }
+        }
+        assert_eq!(collected, expected);
+
+        // the cursor of the database without the overlay still has the cleared slot
+        let mut cursor = (&tx).hashed_storage_cursor(hashed_address).unwrap();
+        let range = StorageRange::from_cursor(&mut cursor, cleared, 1).unwrap();
+        assert_eq!(range.slots.keys().collect::<Vec<_>>(), [&cleared]);
+    }
+
+    #[test]
+    fn plain_storage_preimage_scan_is_bounded() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().expect("failed to create transaction");
+
+        let address = Address::with_last_byte(1);
+        let hashed_address = keccak256(address);
+        for slot in 0..100u64 {
+            let slot = B256::from(U256::from(slot));
+            let entry = StorageEntry { key: slot, value: U256::from(1) };
+            tx.put::<tables::PlainStorageState>(address, entry).unwrap();
+            tx.put::<tables::HashedStorages>(
+                hashed_address,
+                StorageEntry { key: keccak256(slot), ..entry },
+            )
+            .unwrap();
+        }
+
+        let mut cursor = (&tx).hashed_storage_cursor(hashed_address).unwrap();
+        let mut range = StorageRange::from_cursor(&mut cursor, B256::ZERO, 100).unwrap();
+        range.set_plain_storage_preimages(&tx, address, 10).unwrap();
+
+        // only the first scanned slots of the plain storage are resolved
+        let resolved = range.slots.values().filter_map(|entry| entry.slot).collect::<Vec<_>>();
+        assert_eq!(resolved.len(), 10);
+        assert!(resolved.iter().all(|slot| U256::from_be_bytes(slot.0) < U256::from(10)));
+    }
+}
@@ -2,8 +2,8 @@ use crate::{
     hashed_cursor::{HashedCursorFactory, HashedStorageCursor},
     node_iter::{TrieElement, TrieNodeIter},
     prefix_set::{PrefixSet, PrefixSetLoader, TriePrefixSets},
-    progress::{IntermediateStateRootState, StateRootProgress},
-    stats::TrieTracker,
+    progress::{CancellationToken, IntermediateStateRootState, StateRootProgress, TrieProgress},
+    stats::{TrieStats, TrieTracker},
     trie_cursor::TrieCursorFactory,
     updates::{TrieKey, TrieOp, TrieUpdates},
     walker::TrieWalker,
@@ -17,12 +17,19 @@ use reth_primitives::{
     trie::{HashBuilder, Nibbles, TrieAccount},
     Address, BlockNumber, B256,
 };
-use std::ops::RangeInclusive;
+use std::{collections::HashMap, fmt, ops::RangeInclusive};
 use tracing::{debug, trace};
 
 #[cfg(feature = "metrics")]
 use crate::metrics::{StateRootMetrics, TrieRootMetrics, TrieType};
 
+/// The default number of walked accounts between progress reports and cancellation checks.
+pub const DEFAULT_PROGRESS_INTERVAL: u64 = 10_000;
+
+/// The number of blocks above which the prefix sets are loaded in parallel, see
+/// [`PrefixSetLoader::load_parallel`].
+const PARALLEL_PREFIX_SETS_THRESHOLD: u64 = 64;
+
 /// `StateRoot` is used to compute the root node of a state trie.
 #[derive(Debug)]
 pub struct StateRoot<T, H> {
@@ -36,11 +43,28 @@ pub struct StateRoot<T, H> {
     previous_state: Option<IntermediateStateRootState>,
     /// The number of updates after which the intermediate progress should be returned.
     threshold: u64,
+    /// The callback to report the progress to.
+    progress: Option<ProgressCallback>,
+    /// The number of walked accounts between progress reports and cancellation checks.
+    progress_interval: u64,
+    /// The token to check for cancellation.
+    cancellation: Option<CancellationToken>,
+    /// Pre-computed storage roots keyed by hashed address.
+    storage_roots: HashMap<B256, B256>,
     #[cfg(feature = "metrics")]
     /// State root metrics.
     metrics: StateRootMetrics,
 }
 
+/// The callback that receives the progress of the state root computation.
+struct ProgressCallback(Box<dyn FnMut(TrieProgress) + Send>);
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressCallback").finish_non_exhaustive()
+    }
+}
+
 impl<T, H> StateRoot<T, H> {
     /// Set the prefix sets.
     pub fn with_prefix_sets(mut self, prefix_sets: TriePrefixSets) -> Self {
@@ -66,6 +90,40 @@ impl<T, H> StateRoot<T, H> {
         self
     }
 
+    /// Set the callback that is invoked with the [`TrieProgress`] every
+    /// [progress interval](Self::with_progress_interval) walked accounts.
+    pub fn with_progress(mut self, callback: impl FnMut(TrieProgress) + Send + 'static) -> Self {
+        self.progress = Some(ProgressCallback(Box::new(callback)));
+        self
+    }
+
+    /// Set the number of walked accounts between progress reports and cancellation checks.
+    pub fn with_progress_interval(mut self, interval: u64) -> Self {
+        self.progress_interval = interval.max(1);
+        self
+    }
+
+    /// Set the cancellation token that is checked every
+    /// [progress interval](Self::with_progress_interval) walked accounts.
+    ///
+    /// Once the token is cancelled, [`Self::root_with_progress`] returns the resumable
+    /// [`StateRootProgress::Progress`], while the other methods fail with
+    /// [`StateRootError::Cancelled`].
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Set the pre-computed storage roots of the accounts keyed by hashed address. The storage
+    /// roots must reflect the storage changes of the hashed cursor factory.
+    ///
+    /// The storage roots are only used if the trie updates are not retained, since otherwise the
+    /// storage tries have to be walked to collect their updates.
+    pub fn with_storage_roots(mut self, storage_roots: HashMap<B256, B256>) -> Self {
+        self.storage_roots = storage_roots;
+        self
+    }
+
     /// Set the hashed cursor factory.
     pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> StateRoot<T, HF> {
         StateRoot {
@@ -74,6 +132,10 @@ impl<T, H> StateRoot<T, H> {
             prefix_sets: self.prefix_sets,
             threshold: self.threshold,
             previous_state: self.previous_state,
+            progress: self.progress,
+            progress_interval: self.progress_interval,
+            cancellation: self.cancellation,
+            storage_roots: self.storage_roots,
             #[cfg(feature = "metrics")]
             metrics: self.metrics,
         }
@@ -87,6 +149,10 @@ impl<T, H> StateRoot<T, H> {
             prefix_sets: self.prefix_sets,
             threshold: self.threshold,
             previous_state: self.previous_state,
+            progress: self.progress,
+            progress_interval: self.progress_interval,
+            cancellation: self.cancellation,
+            storage_roots: self.storage_roots,
             #[cfg(feature = "metrics")]
             metrics: self.metrics,
         }
@@ -102,6 +168,10 @@ impl<'a, TX: DbTx> StateRoot<&'a TX, &'a TX> {
             prefix_sets: TriePrefixSets::default(),
             previous_state: None,
             threshold: 100_000,
+            progress: None,
+            progress_interval: DEFAULT_PROGRESS_INTERVAL,
+            cancellation: None,
+            storage_roots: HashMap::default(),
             #[cfg(feature = "metrics")]
             metrics: StateRootMetrics::default(),
         }
@@ -117,7 +187,13 @@ impl<'a, TX: DbTx> StateRoot<&'a TX, &'a TX> {
         tx: &'a TX,
         range: RangeInclusive<BlockNumber>,
     ) -> Result<Self, StateRootError> {
-        let loaded_prefix_sets = PrefixSetLoader::new(tx).load(range)?;
+        let loader = PrefixSetLoader::new(tx);
+        let loaded_prefix_sets =
+            if range.end().saturating_sub(*range.start()) > PARALLEL_PREFIX_SETS_THRESHOLD {
+                loader.load_parallel(range)?
+            } else {
+                loader.load(range)?
+            };
         Ok(Self::from_tx(tx).with_prefix_sets(loaded_prefix_sets))
     }
 
@@ -180,9 +256,10 @@ where
     ///
     /// The intermediate progress of state root computation and the trie updates.
     pub fn root_with_updates(self) -> Result<(B256, TrieUpdates), StateRootError> {
-        match self.with_no_threshold().calculate(true)? {
+        match self.with_no_threshold().calculate(true)?.0 {
             StateRootProgress::Complete(root, _, updates) => Ok((root, updates)),
-            StateRootProgress::Progress(..) => unreachable!(), // unreachable threshold
+            // unreachable threshold, so the progress is returned only on cancellation
+            StateRootProgress::Progress(..) => Err(StateRootError::Cancelled),
         }
     }
 
@@ -193,9 +270,20 @@ where
     ///
     /// The state root hash.
     pub fn root(self) -> Result<B256, StateRootError> {
+        self.root_with_stats().map(|(root, _)| root)
+    }
+
+    /// Walks the intermediate nodes of existing state trie (if any) and hashed entries. Feeds the
+    /// nodes into the hash builder.
+    ///
+    /// # Returns
+    ///
+    /// The state root hash and the stats of the computation. The stats do not include the storage
+    /// root computations, those are recorded separately.
+    pub fn root_with_stats(self) -> Result<(B256, TrieStats), StateRootError> {
         match self.calculate(false)? {
-            StateRootProgress::Complete(root, _, _) => Ok(root),
-            StateRootProgress::Progress(..) => unreachable!(), // update retenion is disabled
+            (StateRootProgress::Complete(root, _, _), stats) => Ok((root, stats)),
+            (StateRootProgress::Progress(..), _) => unreachable!(), // update retenion is disabled
         }
     }
 
@@ -206,10 +294,13 @@ where
     ///
     /// The intermediate progress of state root computation.
     pub fn root_with_progress(self) -> Result<StateRootProgress, StateRootError> {
-        self.calculate(true)
+        self.calculate(true).map(|(progress, _)| progress)
     }
 
-    fn calculate(self, retain_updates: bool) -> Result<StateRootProgress, StateRootError> {
+    fn calculate(
+        mut self,
+        retain_updates: bool,
+    ) -> Result<(StateRootProgress, TrieStats), StateRootError> {
         trace!(target: "trie::state_root", "calculating state root");
         let mut tracker = TrieTracker::default();
         let mut trie_updates = TrieUpdates::default();
@@ -241,15 +332,18 @@ where
 
         let mut account_rlp = Vec::with_capacity(128);
         let mut hashed_entries_walked = 0;
+        let mut progress = TrieProgress::default();
         while let Some(node) = account_node_iter.try_next()? {
             match node {
                 TrieElement::Branch(node) => {
                     tracker.inc_branch();
+                    progress.branches_walked += 1;
                     hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
                 }
                 TrieElement::Leaf(hashed_address, account) => {
                     tracker.inc_leaf();
                     hashed_entries_walked += 1;
+                    progress.accounts_walked += 1;
 
                     // We assume we can always calculate a storage root without
                     // OOMing. This opens us up to a potential DOS vector if
@@ -258,29 +352,34 @@ where
                     // progress.
                     // TODO: We can consider introducing the TrieProgress::Progress/Complete
                     // abstraction inside StorageRoot, but let's give it a try as-is for now.
-                    let storage_root_calculator = StorageRoot::new_hashed(
-                        self.trie_cursor_factory.clone(),
-                        self.hashed_cursor_factory.clone(),
-                        hashed_address,
-                        #[cfg(feature = "metrics")]
-                        self.metrics.storage_trie.clone(),
-                    )
-                    .with_prefix_set(
-                        self.prefix_sets
-                            .storage_prefix_sets
-                            .get(&hashed_address)
-                            .cloned()
-                            .unwrap_or_default(),
-                    );
-
-                    let storage_root = if retain_updates {
-                        let (root, storage_slots_walked, updates) =
-                            storage_root_calculator.root_with_updates()?;
-                        hashed_entries_walked += storage_slots_walked;
-                        trie_updates.extend(updates);
-                        root
-                    } else {
-                        storage_root_calculator.root()?
+                    let storage_root = match self.storage_roots.get(&hashed_address) {
+                        Some(storage_root) if !retain_updates => *storage_root,
+                        _ => {
+                            let storage_root_calculator = StorageRoot::new_hashed(
+                                self.trie_cursor_factory.clone(),
+                                self.hashed_cursor_factory.clone(),
+                                hashed_address,
+                                #[cfg(feature = "metrics")]
+                                self.metrics.storage_trie.clone(),
+                            )
+                            .with_prefix_set(
+                                self.prefix_sets
+                                    .storage_prefix_sets
+                                    .get(&hashed_address)
+                                    .cloned()
+                                    .unwrap_or_default(),
+                            );
+
+                            if retain_updates {
+                                let (root, storage_slots_walked, updates) =
+                                    storage_root_calculator.root_with_updates()?;
+                                hashed_entries_walked += storage_slots_walked;
+                                trie_updates.extend(updates);
+                                root
+                            } else {
+                                storage_root_calculator.root()?
+                            }
+                        }
                     };
 
                     account_rlp.clear();
@@ -288,11 +387,37 @@ where
                     account.encode(&mut account_rlp as &mut dyn BufMut);
                     hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
 
+                    // Report the progress and check for cancellation every progress interval.
+                    let mut cancelled = false;
+                    if progress.accounts_walked % self.progress_interval == 0 {
+                        progress.hashed_entries_walked = hashed_entries_walked as u64;
+                        progress.last_account_key = hashed_address;
+                        if let Some(ProgressCallback(callback)) = self.progress.as_mut() {
+                            callback(progress);
+                        }
+
+                        cancelled = self
+                            .cancellation
+                            .as_ref()
+                            .is_some_and(|cancellation| cancellation.is_cancelled());
+                        if cancelled {
+                            debug!(
+                                target: "trie::state_root",
+                                ?progress,
+                                "state root computation cancelled"
+                            );
+                            if !retain_updates {
+                                return Err(StateRootError::Cancelled)
+                            }
+                        }
+                    }
+
                     // Decide if we need to return intermediate progress.
                     let total_updates_len = trie_updates.len() +
                         account_node_iter.walker.updates_len() +
                         hash_builder.updates_len();
-                    if retain_updates && total_updates_len as u64 >= self.threshold {
+                    if retain_updates && (cancelled || total_updates_len as u64 >= self.threshold) {
+                        tracker.add_walk_stats(account_node_iter.stats());
                         let (walker_stack, walker_updates) = account_node_iter.walker.split();
                         let (hash_builder, hash_builder_updates) = hash_builder.split();
 
@@ -305,10 +430,13 @@ where
                         trie_updates.extend(walker_updates);
                         trie_updates.extend_with_account_updates(hash_builder_updates);
 
-                        return Ok(StateRootProgress::Progress(
-                            Box::new(state),
-                            hashed_entries_walked,
-                            trie_updates,
+                        return Ok((
+                            StateRootProgress::Progress(
+                                Box::new(state),
+                                hashed_entries_walked,
+                                trie_updates,
+                            ),
+                            tracker.finish(),
                         ))
                     }
                 }
@@ -316,6 +444,7 @@ where
         }
 
         let root = hash_builder.root();
+        tracker.add_walk_stats(account_node_iter.stats());
 
         trie_updates.finalize_state_updates(
             account_node_iter.walker,
@@ -334,10 +463,14 @@ where
             duration = ?stats.duration(),
             branches_added = stats.branches_added(),
             leaves_added = stats.leaves_added(),
+            branch_nodes_consumed = stats.branch_nodes_consumed(),
+            subtrees_skipped = stats.subtrees_skipped(),
+            trie_seeks = stats.trie_seeks(),
+            hashed_seeks = stats.hashed_seeks(),
             "calculated state root"
         );
 
-        Ok(StateRootProgress::Complete(root, hashed_entries_walked, trie_updates))
+        Ok((StateRootProgress::Complete(root, hashed_entries_walked, trie_updates), stats))
     }
 }
 
@@ -470,6 +603,16 @@ where
         Ok(root)
     }
 
+    /// Walks the hashed storage table entries for a given address and calculates the storage root.
+    ///
+    /// # Returns
+    ///
+    /// The storage root and the stats of the computation.
+    pub fn root_with_stats(self) -> Result<(B256, TrieStats), StorageRootError> {
+        let (root, _, _, stats) = self.calculate_with_stats(false)?;
+        Ok((root, stats))
+    }
+
     /// Walks the hashed storage table entries for a given address and calculates the storage root.
     ///
     /// # Returns
@@ -480,6 +623,16 @@ where
         self,
         retain_updates: bool,
     ) -> Result<(B256, usize, TrieUpdates), StorageRootError> {
+        let (root, storage_slots_walked, trie_updates, _) =
+            self.calculate_with_stats(retain_updates)?;
+        Ok((root, storage_slots_walked, trie_updates))
+    }
+
+    fn calculate_with_stats(
+        self,
+        retain_updates: bool,
+    ) -> Result<(B256, usize, TrieUpdates, TrieStats), StorageRootError> {
+        let mut tracker = TrieTracker::default();
         trace!(target: "trie::storage_root", hashed_address = ?self.hashed_address, "calculating storage root");
 
         let mut hashed_storage_cursor =
@@ -491,10 +644,10 @@ where
                 EMPTY_ROOT_HASH,
                 0,
                 TrieUpdates::from([(TrieKey::StorageTrie(self.hashed_address), TrieOp::Delete)]),
+                tracker.finish(),
             ))
         }
 
-        let mut tracker = TrieTracker::default();
         let trie_cursor = self.trie_cursor_factory.storage_tries_cursor(self.hashed_address)?;
         let walker = TrieWalker::new(trie_cursor, self.prefix_set).with_updates(retain_updates);
 
@@ -518,6 +671,7 @@ where
         }
 
         let root = hash_builder.root();
+        tracker.add_walk_stats(storage_node_iter.stats());
 
         let mut trie_updates = TrieUpdates::default();
         trie_updates.finalize_storage_updates(
@@ -538,11 +692,15 @@ where
             duration = ?stats.duration(),
             branches_added = stats.branches_added(),
             leaves_added = stats.leaves_added(),
+            branch_nodes_consumed = stats.branch_nodes_consumed(),
+            subtrees_skipped = stats.subtrees_skipped(),
+            trie_seeks = stats.trie_seeks(),
+            hashed_seeks = stats.hashed_seeks(),
             "calculated storage root"
         );
 
         let storage_slots_walked = stats.leaves_added() as usize;
-        Ok((root, storage_slots_walked, trie_updates))
+        Ok((root, storage_slots_walked, trie_updates, stats))
     }
 }
 
@@ -816,6 +974,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn cancelled_state_root_resumes() {
+        let state = (0..100u64)
+            .map(|i| {
+                let account = Account { nonce: i, balance: U256::from(i), bytecode_hash: None };
+                (Address::with_last_byte(i as u8), (account, BTreeMap::default()))
+            })
+            .collect::<State>();
+
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap();
+        for (address, (account, storage)) in &state {
+            insert_account(tx.tx_ref(), *address, *account, storage)
+        }
+        tx.commit().unwrap();
+        let tx = factory.provider_rw().unwrap();
+        let expected = state_root(state);
+
+        // Cancelled computation without updates fails
+        let cancellation = CancellationToken::default();
+        cancellation.cancel();
+        assert!(matches!(
+            StateRoot::from_tx(tx.tx_ref())
+                .with_progress_interval(10)
+                .with_cancellation(cancellation.clone())
+                .root(),
+            Err(StateRootError::Cancelled)
+        ));
+
+        // Cancelled computation with updates returns the intermediate state at the first check
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let progress = StateRoot::from_tx(tx.tx_ref())
+            .with_no_threshold()
+            .with_progress_interval(10)
+            .with_progress(move |progress| sender.send(progress).unwrap())
+            .with_cancellation(cancellation)
+            .root_with_progress()
+            .unwrap();
+        let StateRootProgress::Progress(intermediate_state, walked, _) = progress else {
+            panic!("expected progress")
+        };
+        assert_eq!(walked, 10);
+        let reported = receiver.try_iter().collect::<Vec<_>>();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].accounts_walked, 10);
+        assert_eq!(reported[0].last_account_key, intermediate_state.last_account_key);
+
+        // Resumed computation yields the same root
+        let progress = StateRoot::from_tx(tx.tx_ref())
+            .with_no_threshold()
+            .with_intermediate_state(Some(*intermediate_state))
+            .root_with_progress()
+            .unwrap();
+        assert!(matches!(progress, StateRootProgress::Complete(root, _, _) if root == expected));
+    }
+
     fn test_state_root_with_state(state: State) {
         let factory = create_test_provider_factory();
         let tx = factory.provider_rw().unwrap();
@@ -1220,6 +1434,56 @@ mod tests {
         assert_trie_updates(&account_updates);
     }
 
+    #[test]
+    fn state_root_stats() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap();
+        let mut hashed_account_cursor =
+            tx.tx_ref().cursor_write::<tables::HashedAccounts>().unwrap();
+
+        let mut state = (0..256u64)
+            .map(|i| (keccak256(B256::from(U256::from(i))), U256::from(i)))
+            .collect::<BTreeMap<_, _>>();
+        for (hashed_address, balance) in &state {
+            hashed_account_cursor
+                .upsert(*hashed_address, Account { balance: *balance, ..Default::default() })
+                .unwrap();
+        }
+        let (_, updates) = StateRoot::from_tx(tx.tx_ref()).root_with_updates().unwrap();
+        updates.flush(tx.tx_ref()).unwrap();
+
+        // Modify a single account, so that the rest of the trie can be skipped.
+        let (modified, _) = state.pop_first().unwrap();
+        state.insert(modified, U256::MAX);
+        hashed_account_cursor
+            .upsert(modified, Account { balance: U256::MAX, ..Default::default() })
+            .unwrap();
+        let mut changes = PrefixSetMut::default();
+        changes.insert(Nibbles::unpack(modified));
+
+        let (root, stats) = StateRoot::from_tx(tx.tx_ref())
+            .with_prefix_sets(TriePrefixSets {
+                account_prefix_set: changes.freeze(),
+                ..Default::default()
+            })
+            .root_with_stats()
+            .unwrap();
+        let expected_root = state_root_prehashed(state.iter().map(|(&key, &balance)| {
+            (key, (Account { balance, ..Default::default() }, std::iter::empty()))
+        }));
+        assert_eq!(root, expected_root);
+
+        assert!(stats.leaves_added() > 0);
+        assert!(stats.leaves_added() < state.len() as u64);
+        assert!(stats.branch_nodes_consumed() > 0);
+        assert!(stats.hashed_seeks() > 0);
+        // Every skipped subtree is added to the hash builder as a branch.
+        assert!(stats.subtrees_skipped() > 0);
+        assert_eq!(stats.subtrees_skipped(), stats.branches_added());
+        // Every consumed branch node is the result of a seek.
+        assert!(stats.trie_seeks() >= stats.branch_nodes_consumed());
+    }
+
     #[test]
     fn account_trie_around_extension_node_with_dbtrie() {
         let factory = create_test_provider_factory();
@@ -0,0 +1,357 @@
+use super::{TrieCursor, TrieCursorFactory};
+use crate::updates::{TrieKey, TrieOp, TrieUpdates};
+use parking_lot::Mutex;
+use reth_db::DatabaseError;
+use reth_primitives::{
+    trie::{BranchNodeCompact, Nibbles, StoredNibbles, StoredNibblesSubKey},
+    B256,
+};
+use schnellru::{ByLength, LruMap};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+#[cfg(feature = "metrics")]
+use crate::metrics::TrieNodeCacheMetrics;
+
+/// The default maximum number of trie nodes held by the [`TrieNodeCache`].
+pub const DEFAULT_TRIE_NODE_CACHE_SIZE: u32 = 100_000;
+
+/// The key of a cached trie node: the hashed address of the storage trie, or `None` for the
+/// account trie, and the path of the node.
+type CacheKey = (Option<B256>, Nibbles);
+
+/// A shared, size-bounded cache of trie nodes that sits in front of the database trie cursors.
+///
+/// The cache only ever holds nodes that exist in the database, so it must be kept in sync with
+/// every write to the trie tables, either by applying the written [`TrieUpdates`] with
+/// [`Self::apply_updates`] or by clearing it with [`Self::clear`].
+#[derive(Clone)]
+pub struct TrieNodeCache {
+    inner: Arc<TrieNodeCacheInner>,
+}
+
+struct TrieNodeCacheInner {
+    /// The cached nodes.
+    nodes: Mutex<LruMap<CacheKey, BranchNodeCompact, ByLength>>,
+    /// The number of lookups served from the cache.
+    hits: AtomicU64,
+    /// The number of lookups that went to the database.
+    misses: AtomicU64,
+    /// Trie node cache metrics.
+    #[cfg(feature = "metrics")]
+    metrics: TrieNodeCacheMetrics,
+}
+
+impl TrieNodeCache {
+    /// Creates a new cache holding at most `max_nodes` trie nodes.
+    pub fn new(max_nodes: u32) -> Self {
+        Self {
+            inner: Arc::new(TrieNodeCacheInner {
+                nodes: Mutex::new(LruMap::new(ByLength::new(max_nodes))),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+                #[cfg(feature = "metrics")]
+                metrics: TrieNodeCacheMetrics::default(),
+            }),
+        }
+    }
+
+    /// Returns the number of lookups served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.inner.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of lookups that went to the database.
+    pub fn misses(&self) -> u64 {
+        self.inner.misses.load(Ordering::Relaxed)
+    }
+
+    /// Returns the ratio of lookups served from the cache, or `None` if there were no lookups.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let hits = self.hits();
+        let total = hits + self.misses();
+        (total > 0).then(|| hits as f64 / total as f64)
+    }
+
+    /// Returns the number of cached trie nodes.
+    pub fn len(&self) -> usize {
+        self.inner.nodes.lock().len()
+    }
+
+    /// Returns `true` if there are no cached trie nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Applies the trie updates that were committed to the database: removed nodes are evicted
+    /// and updated nodes are replaced.
+    pub fn apply_updates(&self, updates: &TrieUpdates) {
+        let mut nodes = self.inner.nodes.lock();
+        for (key, op) in updates.iter() {
+            let key = match key {
+                TrieKey::AccountNode(path) => (None, path.0.clone()),
+                TrieKey::StorageNode(hashed_address, path) => {
+                    (Some(*hashed_address), path.0.clone())
+                }
+                TrieKey::StorageTrie(hashed_address) => {
+                    // The whole storage trie is removed, evict all of its nodes.
+                    let wiped = nodes
+                        .iter()
+                        .filter(|((address, _), _)| address.as_ref() == Some(hashed_address))
+                        .map(|(key, _)| key.clone())
+                        .collect::<Vec<_>>();
+                    for key in wiped {
+                        nodes.remove(&key);
+                    }
+                    continue
+                }
+            };
+
+            match op {
+                TrieOp::Update(node) => {
+                    nodes.insert(key, node.clone());
+                }
+                TrieOp::Delete => {
+                    nodes.remove(&key);
+                }
+            }
+        }
+        self.record_len(nodes.len());
+    }
+
+    /// Removes all cached trie nodes.
+    pub fn clear(&self) {
+        let mut nodes = self.inner.nodes.lock();
+        nodes.clear();
+        self.record_len(nodes.len());
+    }
+
+    /// Returns the cached node at the given path.
+    fn get(&self, key: &CacheKey) -> Option<BranchNodeCompact> {
+        let node = self.inner.nodes.lock().get(key).cloned();
+        if node.is_some() {
+            self.inner.hits.fetch_add(1, Ordering::Relaxed);
+            #[cfg(feature = "metrics")]
+            self.inner.metrics.hits.increment(1);
+        } else {
+            self.inner.misses.fetch_add(1, Ordering::Relaxed);
+            #[cfg(feature = "metrics")]
+            self.inner.metrics.misses.increment(1);
+        }
+        node
+    }
+
+    /// Caches the node read from the database.
+    fn insert(&self, key: CacheKey, node: BranchNodeCompact) {
+        let mut nodes = self.inner.nodes.lock();
+        nodes.insert(key, node);
+        self.record_len(nodes.len());
+    }
+
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn record_len(&self, len: usize) {
+        #[cfg(feature = "metrics")]
+        self.inner.metrics.entries.set(len as f64);
+    }
+}
+
+impl fmt::Debug for TrieNodeCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrieNodeCache")
+            .field("len", &self.len())
+            .field("hits", &self.hits())
+            .field("misses", &self.misses())
+            .finish()
+    }
+}
+
+/// The trie cursor factory that consults the [`TrieNodeCache`] before seeking the cursors of the
+/// wrapped factory.
+#[derive(Clone, Debug)]
+pub struct CachedTrieCursorFactory<F> {
+    /// The underlying trie cursor factory.
+    factory: F,
+    /// The shared trie node cache.
+    cache: Option<TrieNodeCache>,
+}
+
+impl<F> CachedTrieCursorFactory<F> {
+    /// Create a new cached trie cursor factory.
+    ///
+    /// If no cache is given, the cursors of the wrapped factory are returned as is.
+    pub const fn new(factory: F, cache: Option<TrieNodeCache>) -> Self {
+        Self { factory, cache }
+    }
+}
+
+impl<F: TrieCursorFactory> TrieCursorFactory for CachedTrieCursorFactory<F> {
+    fn account_trie_cursor(&self) -> Result<Box<dyn TrieCursor + '_>, DatabaseError> {
+        let cursor = self.factory.account_trie_cursor()?;
+        Ok(match &self.cache {
+            Some(cache) => Box::new(CachedTrieCursor::new(cursor, cache.clone(), None)),
+            None => cursor,
+        })
+    }
+
+    fn storage_tries_cursor(
+        &self,
+        hashed_address: B256,
+    ) -> Result<Box<dyn TrieCursor + '_>, DatabaseError> {
+        let cursor = self.factory.storage_tries_cursor(hashed_address)?;
+        Ok(match &self.cache {
+            Some(cache) => {
+                Box::new(CachedTrieCursor::new(cursor, cache.clone(), Some(hashed_address)))
+            }
+            None => cursor,
+        })
+    }
+}
+
+/// The trie cursor that serves the exact matches from the [`TrieNodeCache`] and caches the nodes
+/// read from the underlying cursor.
+#[derive(Debug)]
+pub struct CachedTrieCursor<C> {
+    /// The underlying cursor.
+    cursor: C,
+    /// The shared trie node cache.
+    cache: TrieNodeCache,
+    /// The hashed address of the storage trie, `None` for the account trie.
+    hashed_address: Option<B256>,
+    /// The key of the last node served from the cache. The underlying cursor is not positioned
+    /// on it.
+    cached_key: Option<Nibbles>,
+}
+
+impl<C> CachedTrieCursor<C> {
+    /// Create a new cached trie cursor.
+    pub const fn new(cursor: C, cache: TrieNodeCache, hashed_address: Option<B256>) -> Self {
+        Self { cursor, cache, hashed_address, cached_key: None }
+    }
+
+    fn lookup(&mut self, key: &Nibbles) -> Option<(Nibbles, BranchNodeCompact)> {
+        let node = self.cache.get(&(self.hashed_address, key.clone()))?;
+        self.cached_key = Some(key.clone());
+        Some((key.clone(), node))
+    }
+
+    fn record(
+        &mut self,
+        entry: Option<(Nibbles, BranchNodeCompact)>,
+    ) -> Option<(Nibbles, BranchNodeCompact)> {
+        self.cached_key = None;
+        if let Some((key, node)) = &entry {
+            self.cache.insert((self.hashed_address, key.clone()), node.clone());
+        }
+        entry
+    }
+}
+
+impl<C: TrieCursor> TrieCursor for CachedTrieCursor<C> {
+    fn seek_exact(
+        &mut self,
+        key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        if let Some(entry) = self.lookup(&key) {
+            return Ok(Some(entry))
+        }
+        let entry = self.cursor.seek_exact(key)?;
+        Ok(self.record(entry))
+    }
+
+    /// The node at the exact key is the first one matching or greater than the key, so only the
+    /// exact matches can be served from the cache.
+    fn seek(
+        &mut self,
+        key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        if let Some(entry) = self.lookup(&key) {
+            return Ok(Some(entry))
+        }
+        let entry = self.cursor.seek(key)?;
+        Ok(self.record(entry))
+    }
+
+    fn current(&mut self) -> Result<Option<TrieKey>, DatabaseError> {
+        let Some(key) = self.cached_key.clone() else { return self.cursor.current() };
+        Ok(Some(match self.hashed_address {
+            Some(hashed_address) => TrieKey::StorageNode(hashed_address, StoredNibblesSubKey(key)),
+            None => TrieKey::AccountNode(StoredNibbles(key)),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::tables;
+    use reth_db_api::{cursor::DbCursorRW, transaction::DbTxMut};
+    use reth_primitives::trie::StoredBranchNode;
+    use reth_provider::test_utils::create_test_provider_factory;
+
+    fn branch_node(tree_mask: u16) -> BranchNodeCompact {
+        BranchNodeCompact::new(0b11, tree_mask, 0, Vec::default(), None)
+    }
+
+    #[test]
+    fn cached_account_trie_cursor() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let mut cursor = provider.tx_ref().cursor_write::<tables::AccountsTrie>().unwrap();
+        for key in [vec![0x1], vec![0x1, 0x2], vec![0x3]] {
+            cursor.upsert(key.into(), StoredBranchNode(branch_node(0))).unwrap();
+        }
+
+        let cache = TrieNodeCache::new(10);
+        let cursor_factory = CachedTrieCursorFactory::new(provider.tx_ref(), Some(cache.clone()));
+        let mut cursor = cursor_factory.account_trie_cursor().unwrap();
+
+        // The first lookup goes to the database and caches the found node
+        let first = cursor.seek(Nibbles::from_nibbles([0x2])).unwrap();
+        assert_eq!(first, Some((Nibbles::from_nibbles([0x3]), branch_node(0))));
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (0, 1, 1));
+
+        // The exact lookup is served from the cache
+        let second = cursor.seek(Nibbles::from_nibbles([0x3])).unwrap();
+        assert_eq!(second, first);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+        assert_eq!(
+            cursor.current().unwrap(),
+            Some(TrieKey::AccountNode(StoredNibbles(Nibbles::from_nibbles([0x3]))))
+        );
+
+        // Committed updates replace and evict the cached nodes
+        cache.apply_updates(&TrieUpdates::from([(
+            TrieKey::AccountNode(StoredNibbles(Nibbles::from_nibbles([0x3]))),
+            TrieOp::Update(branch_node(1)),
+        )]));
+        assert_eq!(
+            cursor.seek_exact(Nibbles::from_nibbles([0x3])).unwrap(),
+            Some((Nibbles::from_nibbles([0x3]), branch_node(1)))
+        );
+        cache.apply_updates(&TrieUpdates::from([(
+            TrieKey::AccountNode(StoredNibbles(Nibbles::from_nibbles([0x3]))),
+            TrieOp::Delete,
+        )]));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn wiped_storage_trie_eviction() {
+        let cache = TrieNodeCache::new(10);
+        let hashed_address = B256::with_last_byte(1);
+        cache.insert((Some(hashed_address), Nibbles::from_nibbles([0x1])), branch_node(0));
+        cache.insert((Some(hashed_address), Nibbles::from_nibbles([0x2])), branch_node(0));
+        cache.insert((None, Nibbles::from_nibbles([0x1])), branch_node(0));
+
+        cache.apply_updates(&TrieUpdates::from([(
+            TrieKey::StorageTrie(hashed_address),
+            TrieOp::Delete,
+        )]));
+        assert_eq!(cache.len(), 1);
+    }
+}
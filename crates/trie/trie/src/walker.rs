@@ -1,5 +1,6 @@
 use crate::{
     prefix_set::PrefixSet,
+    stats::TrieWalkStats,
     trie_cursor::{CursorSubNode, TrieCursor},
     updates::TrieUpdates,
 };
@@ -26,13 +27,21 @@ pub struct TrieWalker<C> {
     pub changes: PrefixSet,
     /// The trie updates to be applied to the trie.
     trie_updates: Option<TrieUpdates>,
+    /// The stats of the walk.
+    stats: TrieWalkStats,
 }
 
 impl<C> TrieWalker<C> {
     /// Constructs a new `TrieWalker` from existing stack and a cursor.
     pub fn from_stack(cursor: C, stack: Vec<CursorSubNode>, changes: PrefixSet) -> Self {
-        let mut this =
-            Self { cursor, changes, stack, can_skip_current_node: false, trie_updates: None };
+        let mut this = Self {
+            cursor,
+            changes,
+            stack,
+            can_skip_current_node: false,
+            trie_updates: None,
+            stats: TrieWalkStats::default(),
+        };
         this.update_skip_node();
         this
     }
@@ -70,6 +79,11 @@ impl<C> TrieWalker<C> {
         self.trie_updates.as_ref().map(|u| u.len()).unwrap_or(0)
     }
 
+    /// Returns the stats of the walk so far. The hashed cursor seeks are not tracked by the walker.
+    pub const fn stats(&self) -> TrieWalkStats {
+        self.stats
+    }
+
     /// Returns the current key in the trie.
     pub fn key(&self) -> Option<&Nibbles> {
         self.stack.last().map(|n| n.full_key())
@@ -120,6 +134,7 @@ impl<C: TrieCursor> TrieWalker<C> {
             stack: vec![CursorSubNode::default()],
             can_skip_current_node: false,
             trie_updates: None,
+            stats: TrieWalkStats::default(),
         };
 
         // Set up the root node of the trie in the stack, if it exists.
@@ -148,6 +163,9 @@ impl<C: TrieCursor> TrieWalker<C> {
                 }
             } else {
                 // If we can skip the current node, move to the next sibling.
+                if self.can_skip_current_node {
+                    self.stats.subtrees_skipped += 1;
+                }
                 self.move_to_next_sibling(false)?;
             }
 
@@ -162,6 +180,7 @@ impl<C: TrieCursor> TrieWalker<C> {
     /// Retrieves the current root node from the DB, seeking either the exact node or the next one.
     fn node(&mut self, exact: bool) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
         let key = self.key().expect("key must exist").clone();
+        self.stats.trie_seeks += 1;
         let entry = if exact { self.cursor.seek_exact(key)? } else { self.cursor.seek(key)? };
 
         if let Some((_, node)) = &entry {
@@ -178,6 +197,7 @@ impl<C: TrieCursor> TrieWalker<C> {
             self.stack.clear();
             return Ok(())
         };
+        self.stats.branch_nodes_consumed += 1;
 
         // Overwrite the root node's first nibble
         // We need to sync the stack with the trie structure when consuming a new node. This is
@@ -147,6 +147,10 @@ impl PayloadBuilderAttributes for CustomPayloadBuilderAttributes {
         &self.0.withdrawals
     }
 
+    fn gas_limit(&self) -> Option<u64> {
+        self.0.gas_limit
+    }
+
     fn cfg_and_block_env(
         &self,
         chain_spec: &ChainSpec,
@@ -238,6 +242,7 @@ where
             .interval(conf.interval())
             .deadline(conf.deadline())
             .max_payload_tasks(conf.max_payload_tasks())
+            .min_fee_improvement(conf.min_fee_improvement())
             .extradata(conf.extradata_bytes());
 
         let payload_generator = BasicPayloadJobGenerator::with_builder(
@@ -274,7 +279,16 @@ where
         &self,
         args: BuildArguments<Pool, Client, Self::Attributes, Self::BuiltPayload>,
     ) -> Result<BuildOutcome<Self::BuiltPayload>, PayloadBuilderError> {
-        let BuildArguments { client, pool, cached_reads, config, cancel, best_payload } = args;
+        let BuildArguments {
+            client,
+            pool,
+            cached_reads,
+            precompile_cache,
+            config,
+            cancel,
+            best_payload,
+            executor,
+        } = args;
         let PayloadConfig {
             initialized_block_env,
             initialized_cfg,
@@ -290,6 +304,7 @@ where
             client,
             pool,
             cached_reads,
+            precompile_cache,
             config: PayloadConfig {
                 initialized_block_env,
                 initialized_cfg,
@@ -300,6 +315,7 @@ where
             },
             cancel,
             best_payload,
+            executor,
         })
     }
 
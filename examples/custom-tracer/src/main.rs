@@ -0,0 +1,208 @@
+//! This example shows how to register a custom tracer of the `debug` namespace with the node
+//! builder and trace a transaction with it over rpc.
+//!
+//! The `touchedAccountsTracer` returns the accounts a transaction called or created, it is
+//! requested like any built-in tracer:
+//!
+//! ```sh
+//! cast rpc debug_traceTransaction <hash> '{"tracer": "touchedAccountsTracer"}'
+//! ```
+
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+
+use futures_util::StreamExt;
+use jsonrpsee::{core::client::ClientT, rpc_params};
+use reth::{
+    builder::{NodeBuilder, NodeHandle},
+    providers::CanonStateSubscriptions,
+    revm::{
+        interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome},
+        Database, EvmContext, Inspector,
+    },
+    rpc::{builder::RpcModuleSelection, eth::EthTransactions},
+    tasks::TaskManager,
+};
+use reth_node_core::{args::RpcServerArgs, node_config::NodeConfig};
+use reth_node_ethereum::EthereumNode;
+use reth_primitives::{hex, Address, ChainSpec, Genesis, U256};
+use reth_rpc::{CustomTracer, CustomTracerError, TracerFactory};
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+};
+
+/// The name the tracer is registered under.
+const TRACER_NAME: &str = "touchedAccountsTracer";
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let touched = run().await?;
+    println!("touched accounts: {touched:?}");
+    Ok(())
+}
+
+/// Launches a dev node with the custom tracer, submits a transaction and returns the accounts it
+/// touched according to the tracer.
+async fn run() -> eyre::Result<Vec<Address>> {
+    let tasks = TaskManager::current();
+
+    // expose the `debug` namespace over http
+    let mut rpc = RpcServerArgs::default().with_http();
+    rpc.http_api = Some(RpcModuleSelection::All);
+    let node_config =
+        NodeConfig::test().dev().with_rpc(rpc).with_chain(custom_chain()).with_unused_ports();
+
+    let NodeHandle { node, node_exit_future: _ } = NodeBuilder::new(node_config)
+        .testing_node(tasks.executor())
+        .node(EthereumNode::default())
+        .extend_rpc_modules(|ctx| {
+            // the tracer is available to all tracing methods of the `debug` namespace
+            ctx.registry.register_tracer(TRACER_NAME, Box::new(TouchedAccountsTracer));
+            Ok(())
+        })
+        .launch()
+        .await?;
+
+    let mut notifications = node.provider.canonical_state_stream();
+
+    // submit a transfer and wait until it's mined
+    let raw_tx = hex!("02f876820a28808477359400847735940082520894ab0840c0e43688012c1adb0f5e3fc665188f83d28a029d394a5d630544000080c080a0a044076b7e67b5deecc63f61a8d7913fab86ca365b344b5759d1fe3563b4c39ea019eab979dd000da04dfc72bb0377c092d30fd9e1cab5ae487de49586cc8b0090");
+    let hash = node.rpc_registry.eth_api().send_raw_transaction(raw_tx.into()).await?;
+    println!("submitted transaction: {hash}");
+
+    let head = notifications.next().await.unwrap();
+    let tx = head.tip().transactions().next().unwrap();
+    assert_eq!(tx.hash(), hash);
+    println!("mined transaction: {hash}");
+
+    // trace the transaction with the custom tracer over http
+    let client = node.rpc_server_handle().http_client().expect("http server is enabled");
+    let trace: serde_json::Value = client
+        .request(
+            "debug_traceTransaction",
+            rpc_params![hash, serde_json::json!({ "tracer": TRACER_NAME })],
+        )
+        .await?;
+
+    Ok(serde_json::from_value(trace)?)
+}
+
+/// Creates the tracers of `touchedAccountsTracer`.
+#[derive(Debug, Clone, Copy, Default)]
+struct TouchedAccountsTracer;
+
+impl TracerFactory for TouchedAccountsTracer {
+    fn create(&self, config: serde_json::Value) -> Result<CustomTracer, CustomTracerError> {
+        if !config.is_null() {
+            return Err(CustomTracerError::InvalidConfig(format!(
+                "{TRACER_NAME} has no config, got {config}"
+            )))
+        }
+
+        // the inspector records the accounts, the result function returns them once the
+        // transaction was executed
+        let touched = Arc::new(Mutex::new(BTreeSet::new()));
+        let inspector = TouchedAccountsInspector { touched: touched.clone() };
+        Ok(CustomTracer {
+            inspector: Box::new(inspector),
+            into_result: Box::new(move |_, _| {
+                let touched = std::mem::take(&mut *touched.lock().unwrap());
+                serde_json::to_value(touched)
+                    .map_err(|err| CustomTracerError::Tracer(err.to_string()))
+            }),
+        })
+    }
+}
+
+/// Records the accounts that are called or created.
+#[derive(Debug)]
+struct TouchedAccountsInspector {
+    touched: Arc<Mutex<BTreeSet<Address>>>,
+}
+
+impl<DB: Database> Inspector<DB> for TouchedAccountsInspector {
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.touched.lock().unwrap().extend([inputs.caller, inputs.target_address]);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        let mut touched = self.touched.lock().unwrap();
+        touched.insert(inputs.caller);
+        touched.extend(outcome.address);
+        drop(touched);
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, _value: U256) {
+        self.touched.lock().unwrap().extend([contract, target]);
+    }
+}
+
+fn custom_chain() -> Arc<ChainSpec> {
+    let custom_genesis = r#"
+{
+    "nonce": "0x42",
+    "timestamp": "0x0",
+    "extraData": "0x5343",
+    "gasLimit": "0x1388",
+    "difficulty": "0x400000000",
+    "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+    "coinbase": "0x0000000000000000000000000000000000000000",
+    "alloc": {
+        "0x6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b": {
+            "balance": "0x4a47e3c12448f4ad000000"
+        }
+    },
+    "number": "0x0",
+    "gasUsed": "0x0",
+    "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+    "config": {
+        "ethash": {},
+        "chainId": 2600,
+        "homesteadBlock": 0,
+        "eip150Block": 0,
+        "eip155Block": 0,
+        "eip158Block": 0,
+        "byzantiumBlock": 0,
+        "constantinopleBlock": 0,
+        "petersburgBlock": 0,
+        "istanbulBlock": 0,
+        "berlinBlock": 0,
+        "londonBlock": 0,
+        "terminalTotalDifficulty": 0,
+        "terminalTotalDifficultyPassed": true,
+        "shanghaiTime": 0
+    }
+}
+"#;
+    let genesis: Genesis = serde_json::from_str(custom_genesis).unwrap();
+    Arc::new(genesis.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::address;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_transaction_with_custom_tracer() {
+        let touched = run().await.unwrap();
+        assert_eq!(
+            touched,
+            vec![
+                address!("6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b"),
+                address!("ab0840c0e43688012c1adb0f5e3fc665188f83d2"),
+            ]
+        );
+    }
+}
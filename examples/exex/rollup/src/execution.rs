@@ -3,7 +3,7 @@ use alloy_consensus::{Blob, SidecarCoder, SimpleCoder};
 use alloy_rlp::Decodable as _;
 use eyre::OptionExt;
 use reth::transaction_pool::TransactionPool;
-use reth_execution_errors::BlockValidationError;
+use reth_execution_errors::{BlockValidationError, FailedTransaction};
 use reth_node_api::{ConfigureEvm, ConfigureEvmEnv};
 use reth_node_ethereum::EthEvmConfig;
 use reth_primitives::{
@@ -12,8 +12,8 @@ use reth_primitives::{
     keccak256,
     revm::env::fill_tx_env,
     revm_primitives::{CfgEnvWithHandlerCfg, EVMError, ExecutionResult, ResultAndState},
-    Address, Block, BlockWithSenders, Bytes, Hardfork, Header, Receipt, TransactionSigned, TxType,
-    B256, U256,
+    Address, Block, BlockNumHash, BlockWithSenders, Bytes, Hardfork, Header, Receipt,
+    TransactionSigned, TxType, B256, U256,
 };
 use reth_revm::{
     db::{states::bundle_state::BundleRetention, BundleState},
@@ -209,6 +209,12 @@ fn execute_transactions(
             let block_available_gas = header.gas_limit - cumulative_gas_used;
             if transaction.gas_limit() > block_available_gas {
                 return Err(BlockValidationError::TransactionGasLimitMoreThanAvailableBlockGas {
+                    transaction: Box::new(FailedTransaction {
+                        block: BlockNumHash::new(header.number, header.hash_slow()),
+                        index: executed_txs.len(),
+                        hash: transaction.hash(),
+                        cumulative_gas_used,
+                    }),
                     transaction_gas_limit: transaction.gas_limit(),
                     block_available_gas,
                 }
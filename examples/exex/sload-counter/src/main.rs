@@ -0,0 +1,146 @@
+//! This example shows how to attach a custom inspector to the block executor, so the blocks
+//! executed by the pipeline during the historical sync are traced, and how to consume the
+//! collected data from an ExEx.
+//!
+//! The inspector counts the `SLOAD` instructions executed in every block.
+
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+
+use futures::Future;
+use parking_lot::Mutex;
+use reth::{
+    builder::{components::ExecutorBuilder, BuilderContext},
+    revm::{
+        interpreter::{opcode, Interpreter},
+        primitives::BlockEnv,
+        Database, EvmContext, Inspector,
+    },
+};
+use reth_evm::execute::BlockInspectorFactory;
+use reth_exex::{ExExContext, ExExEvent};
+use reth_node_api::{FullNodeComponents, FullNodeTypes};
+use reth_node_ethereum::{EthEvmConfig, EthExecutorProvider, EthereumNode};
+use reth_primitives::BlockNumber;
+use reth_tracing::tracing::info;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Number of `SLOAD` instructions executed in every block that wasn't consumed by the ExEx yet.
+type SloadCounts = Arc<Mutex<BTreeMap<BlockNumber, u64>>>;
+
+/// Creates a new [`SloadCounter`] for every executed block.
+#[derive(Debug, Clone, Default)]
+struct SloadCounterFactory {
+    counts: SloadCounts,
+}
+
+impl BlockInspectorFactory for SloadCounterFactory {
+    type Inspector<DB: Database> = SloadCounter;
+
+    fn inspector<DB: Database>(&self, block_env: &BlockEnv) -> Option<Self::Inspector<DB>> {
+        Some(SloadCounter {
+            block_number: block_env.number.to(),
+            sloads: 0,
+            counts: self.counts.clone(),
+        })
+    }
+}
+
+/// Counts the `SLOAD` instructions executed in a single block.
+///
+/// The count is recorded once the block is executed and the inspector is dropped.
+#[derive(Debug)]
+struct SloadCounter {
+    block_number: BlockNumber,
+    sloads: u64,
+    counts: SloadCounts,
+}
+
+impl<DB: Database> Inspector<DB> for SloadCounter {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if interp.current_opcode() == opcode::SLOAD {
+            self.sloads += 1;
+        }
+    }
+}
+
+impl Drop for SloadCounter {
+    fn drop(&mut self) {
+        self.counts.lock().insert(self.block_number, self.sloads);
+    }
+}
+
+/// Builds a regular ethereum block executor with the [`SloadCounter`] attached.
+#[derive(Debug, Clone)]
+struct SloadCounterExecutorBuilder {
+    counts: SloadCounts,
+}
+
+impl<Node> ExecutorBuilder<Node> for SloadCounterExecutorBuilder
+where
+    Node: FullNodeTypes,
+{
+    type EVM = EthEvmConfig;
+    type Executor = EthExecutorProvider<Self::EVM, SloadCounterFactory>;
+
+    async fn build_evm(
+        self,
+        ctx: &BuilderContext<Node>,
+    ) -> eyre::Result<(Self::EVM, Self::Executor)> {
+        let evm_config = EthEvmConfig::default();
+        let executor = EthExecutorProvider::new(ctx.chain_spec(), evm_config)
+            .with_inspector_factory(SloadCounterFactory { counts: self.counts });
+
+        Ok((evm_config, executor))
+    }
+}
+
+async fn exex_init<Node: FullNodeComponents>(
+    ctx: ExExContext<Node>,
+    counts: SloadCounts,
+) -> eyre::Result<impl Future<Output = eyre::Result<()>>> {
+    Ok(exex(ctx, counts))
+}
+
+/// Logs the number of `SLOAD` instructions executed in every committed block.
+async fn exex<Node: FullNodeComponents>(
+    mut ctx: ExExContext<Node>,
+    counts: SloadCounts,
+) -> eyre::Result<()> {
+    while let Some(notification) = ctx.notifications.recv().await {
+        if let Some(committed_chain) = notification.committed_chain() {
+            let range = committed_chain.range();
+            let committed = {
+                let mut counts = counts.lock();
+                // Keep the counts of the blocks that aren't committed yet
+                let pending = counts.split_off(&(range.end() + 1));
+                std::mem::replace(&mut *counts, pending)
+            };
+
+            for (block_number, sloads) in committed.range(range) {
+                info!(block_number, sloads, "Block executed");
+            }
+
+            ctx.events.send(ExExEvent::FinishedHeight(committed_chain.tip().number))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> eyre::Result<()> {
+    reth::cli::Cli::parse_args().run(|builder, _| async move {
+        let counts = SloadCounts::default();
+
+        let handle = builder
+            .with_types::<EthereumNode>()
+            .with_components(
+                EthereumNode::components()
+                    .executor(SloadCounterExecutorBuilder { counts: counts.clone() }),
+            )
+            .install_exex("SloadCounter", move |ctx| exex_init(ctx, counts))
+            .launch()
+            .await?;
+
+        handle.wait_for_node_exit().await
+    })
+}